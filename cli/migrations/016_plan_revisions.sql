@@ -0,0 +1,21 @@
+-- Migration 016: Plan Revisions
+--
+-- Plans get rewritten as specs evolve. Every change to a plan's title,
+-- content, or success criteria is snapshotted here so older versions can
+-- be listed (`sc plan history`), diffed (`sc plan diff`), and restored
+-- (`sc plan revert`). Revision numbers are per plan and start at 1.
+
+CREATE TABLE IF NOT EXISTS plan_revisions (
+    id TEXT PRIMARY KEY,
+    plan_id TEXT NOT NULL,
+    revision INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT,
+    success_criteria TEXT,
+    actor TEXT,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (plan_id) REFERENCES plans(id) ON DELETE CASCADE,
+    UNIQUE(plan_id, revision)
+);
+
+CREATE INDEX IF NOT EXISTS idx_plan_revisions_plan ON plan_revisions(plan_id, revision DESC);
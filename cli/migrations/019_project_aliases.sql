@@ -0,0 +1,14 @@
+-- Migration 019: Project Path Aliases
+--
+-- Maps alternate spellings of a project directory (symlinks, trailing
+-- slashes, different case on case-insensitive filesystems) to the canonical
+-- project_path. Written by `sc project merge-paths`; lookups by path follow
+-- an alias to its target.
+
+CREATE TABLE IF NOT EXISTS project_aliases (
+    alias_path TEXT PRIMARY KEY,
+    project_path TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_aliases_project ON project_aliases(project_path);
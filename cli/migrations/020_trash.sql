@@ -0,0 +1,20 @@
+-- Migration 020: Trash
+--
+-- Deleted context items, issues and sessions are kept here for a retention
+-- period (30 days by default) so `sc trash restore` can bring them back.
+-- `rows` holds the deleted rows and their dependents as JSON; the live rows
+-- are removed, so nothing in the trash shows up in queries or exports.
+
+CREATE TABLE IF NOT EXISTS trash (
+    id TEXT PRIMARY KEY,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    label TEXT NOT NULL,
+    project_path TEXT,
+    rows TEXT NOT NULL,
+    deleted_at INTEGER NOT NULL,
+    deleted_by TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_trash_deleted ON trash(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_trash_entity ON trash(entity_type, entity_id);
@@ -0,0 +1,61 @@
+-- Migration 022: Session last-activity tracking
+--
+-- One row per session holding the time of its last write: an item saved
+-- or changed, a checkpoint taken, or the session started or resumed.
+-- Kept out of `sessions` itself so activity doesn't rewrite the session
+-- row (and mark it dirty for sync) on every save. Used to auto-pause
+-- idle sessions.
+
+CREATE TABLE IF NOT EXISTS session_activity (
+    session_id TEXT PRIMARY KEY,
+    last_activity_at INTEGER NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_activity_at ON session_activity(last_activity_at);
+
+CREATE TRIGGER IF NOT EXISTS session_activity_item_insert
+AFTER INSERT ON context_items
+BEGIN
+    INSERT INTO session_activity (session_id, last_activity_at)
+    VALUES (NEW.session_id, strftime('%s', 'now') * 1000)
+    ON CONFLICT(session_id) DO UPDATE SET last_activity_at = excluded.last_activity_at;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_activity_item_update
+AFTER UPDATE ON context_items
+BEGIN
+    INSERT INTO session_activity (session_id, last_activity_at)
+    VALUES (NEW.session_id, strftime('%s', 'now') * 1000)
+    ON CONFLICT(session_id) DO UPDATE SET last_activity_at = excluded.last_activity_at;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_activity_checkpoint_insert
+AFTER INSERT ON checkpoints
+BEGIN
+    INSERT INTO session_activity (session_id, last_activity_at)
+    VALUES (NEW.session_id, strftime('%s', 'now') * 1000)
+    ON CONFLICT(session_id) DO UPDATE SET last_activity_at = excluded.last_activity_at;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_activity_session_insert
+AFTER INSERT ON sessions
+BEGIN
+    INSERT INTO session_activity (session_id, last_activity_at)
+    VALUES (NEW.id, strftime('%s', 'now') * 1000)
+    ON CONFLICT(session_id) DO UPDATE SET last_activity_at = excluded.last_activity_at;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_activity_session_resume
+AFTER UPDATE OF status ON sessions
+WHEN NEW.status = 'active' AND OLD.status != 'active'
+BEGIN
+    INSERT INTO session_activity (session_id, last_activity_at)
+    VALUES (NEW.id, strftime('%s', 'now') * 1000)
+    ON CONFLICT(session_id) DO UPDATE SET last_activity_at = excluded.last_activity_at;
+END;
+
+-- Existing sessions start from their newest known write
+INSERT OR IGNORE INTO session_activity (session_id, last_activity_at)
+SELECT s.id, MAX(s.updated_at, COALESCE((SELECT MAX(ci.updated_at) FROM context_items ci WHERE ci.session_id = s.id), 0))
+FROM sessions s;
@@ -0,0 +1,72 @@
+-- Migration 025: Embeddings for issues, plans and memory
+--
+-- embedding_chunks only held context items (item_id was a foreign key to
+-- context_items). It gains an entity_type column so issue, plan and
+-- memory text can be embedded and searched alongside them. SQLite can't
+-- drop a foreign key in place, so the table is rebuilt, and the cascade
+-- on delete moves into one trigger per embedded table.
+--
+-- Databases the MCP server created from schema.sql never ran 011, so the
+-- old table is created first if it's missing.
+
+CREATE TABLE IF NOT EXISTS embedding_chunks (
+    id TEXT PRIMARY KEY,
+    item_id TEXT NOT NULL,
+    chunk_index INTEGER NOT NULL DEFAULT 0,
+    chunk_text TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    dimensions INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (item_id) REFERENCES context_items(id) ON DELETE CASCADE,
+    UNIQUE(item_id, chunk_index)
+);
+
+CREATE TABLE embedding_chunks_new (
+    id TEXT PRIMARY KEY,
+    entity_type TEXT NOT NULL DEFAULT 'context_item',
+    item_id TEXT NOT NULL,
+    chunk_index INTEGER NOT NULL DEFAULT 0,
+    chunk_text TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    dimensions INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE(entity_type, item_id, chunk_index)
+);
+
+INSERT INTO embedding_chunks_new (id, entity_type, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
+SELECT id, 'context_item', item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at
+FROM embedding_chunks;
+
+DROP TABLE embedding_chunks;
+ALTER TABLE embedding_chunks_new RENAME TO embedding_chunks;
+
+CREATE INDEX IF NOT EXISTS idx_embedding_chunks_item ON embedding_chunks(entity_type, item_id);
+CREATE INDEX IF NOT EXISTS idx_embedding_chunks_provider ON embedding_chunks(provider, model);
+
+CREATE TRIGGER IF NOT EXISTS embedding_chunks_context_item_delete
+AFTER DELETE ON context_items
+BEGIN
+    DELETE FROM embedding_chunks WHERE entity_type = 'context_item' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS embedding_chunks_issue_delete
+AFTER DELETE ON issues
+BEGIN
+    DELETE FROM embedding_chunks WHERE entity_type = 'issue' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS embedding_chunks_plan_delete
+AFTER DELETE ON plans
+BEGIN
+    DELETE FROM embedding_chunks WHERE entity_type = 'plan' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS embedding_chunks_memory_delete
+AFTER DELETE ON project_memory
+BEGIN
+    DELETE FROM embedding_chunks WHERE entity_type = 'memory' AND item_id = OLD.id;
+END;
@@ -0,0 +1,24 @@
+-- Migration 026: Archive for embeddings of other models
+--
+-- Vectors from different models can't be compared, so after a provider or
+-- model change the old chunks have to leave embedding_chunks. With
+-- `embeddings.keep_other_models` (or `sc embeddings migrate --keep`) they
+-- move here instead of being deleted, keyed by model, and come back
+-- without re-embedding when that model is active again.
+
+CREATE TABLE IF NOT EXISTS embedding_chunks_archive (
+    id TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    chunk_index INTEGER NOT NULL,
+    chunk_text TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    dimensions INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    archived_at INTEGER NOT NULL,
+    PRIMARY KEY (provider, model, id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_embedding_chunks_archive_item ON embedding_chunks_archive(entity_type, item_id);
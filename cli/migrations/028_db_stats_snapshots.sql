@@ -0,0 +1,12 @@
+-- Migration 028: Last `sc db stats` snapshot
+--
+-- `sc db stats` reports how much each table grew since it last ran. Every
+-- run replaces these rows with the current row count and on-disk size of
+-- each table (including its indexes). Local bookkeeping; never exported.
+
+CREATE TABLE IF NOT EXISTS db_stats_snapshots (
+    table_name TEXT PRIMARY KEY,
+    row_count INTEGER NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    taken_at INTEGER NOT NULL
+);
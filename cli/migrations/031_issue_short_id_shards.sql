@@ -0,0 +1,15 @@
+-- Migration 031: Machine-scoped issue short IDs
+--
+-- Short IDs used to come from the clock, so two machines syncing through
+-- JSONL could hand out the same one. Each database now picks a random
+-- shard of three letters from g-z (a range no legacy hex short ID starts
+-- with) and numbers its issues after it: `kqx1`, `kqx2`, ... If an import
+-- brings in another machine's issues under the same shard, this database
+-- picks a new one.
+--
+-- The shard and the counter are local state and are never exported.
+
+CREATE TABLE IF NOT EXISTS sync_meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
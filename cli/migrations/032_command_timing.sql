@@ -0,0 +1,9 @@
+-- Migration 032: Storage Timing in Usage Statistics
+--
+-- Each recorded command now also notes how long its SQL statements took
+-- in total and how many of them ran past the slow-query threshold
+-- ("slow_query_ms" in ~/.savecontext/config.json), so `sc stats` can show
+-- when a growing database starts to need maintenance.
+
+ALTER TABLE command_usage ADD COLUMN sql_ms INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE command_usage ADD COLUMN slow_queries INTEGER NOT NULL DEFAULT 0;
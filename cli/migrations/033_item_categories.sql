@@ -0,0 +1,23 @@
+-- Migration 033: Per-Project Item Categories
+--
+-- Context item categories used to be the four built-in names. A project
+-- can now register its own (`sc category add security`), and override a
+-- built-in one's settings by registering it under the same name. Saving an
+-- item checks its category is built in or registered for the session's
+-- project.
+--
+-- in_prime = 0 keeps a category's items out of `sc prime`; prime_weight is
+-- its factor in `sc prime --smart` scoring.
+
+CREATE TABLE IF NOT EXISTS item_categories (
+    project_path TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    color TEXT,
+    default_priority TEXT NOT NULL DEFAULT 'normal',
+    in_prime INTEGER NOT NULL DEFAULT 1,
+    prime_weight REAL NOT NULL DEFAULT 1.0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (project_path, name)
+);
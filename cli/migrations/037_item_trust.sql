@@ -0,0 +1,24 @@
+-- Migration 037: Item Source and Trust
+--
+-- Records where each context item came from (`human`, `agent` or
+-- `imported`) and how far it can be relied on, so `sc get` and `sc prime`
+-- can prefer human-vetted decisions over speculative agent notes with
+-- `--min-trust`. Trust starts out as the source and can be raised once
+-- someone has checked the item.
+--
+-- Existing items count as human unless the actor who created them is
+-- registered as an agent or model.
+
+ALTER TABLE context_items ADD COLUMN source TEXT NOT NULL DEFAULT 'human';
+ALTER TABLE context_items ADD COLUMN trust TEXT NOT NULL DEFAULT 'human';
+
+UPDATE context_items SET source = 'agent', trust = 'agent'
+WHERE id IN (
+    SELECT e.entity_id FROM events e
+    JOIN actor_aliases al ON al.alias = e.actor
+    JOIN actors a ON a.id = al.actor_id
+    WHERE e.entity_type = 'context_item' AND e.event_type = 'item_created'
+      AND a.actor_type IN ('agent', 'model')
+);
+
+CREATE INDEX IF NOT EXISTS idx_context_items_trust ON context_items(trust);
@@ -0,0 +1,9 @@
+-- Migration 038: Decision Review
+--
+-- Decisions saved by agents wait in `sc review queue` until a person
+-- approves or rejects them. Prime flags or leaves out the ones still
+-- unreviewed (`prime.unreviewed` in the config) and never includes
+-- rejected ones. The status only matters for agent-written decisions;
+-- every other item keeps the default.
+
+ALTER TABLE context_items ADD COLUMN review_status TEXT NOT NULL DEFAULT 'unreviewed';
@@ -0,0 +1,12 @@
+-- Migration 041: Item Provenance
+--
+-- Where in a conversation a context item was saved: the transcript file
+-- and the index of its last message at the time. Hooks pass the
+-- transcript with `sc save --transcript`, and `sc get --key <key>
+-- --provenance` shows the messages that led to the item.
+--
+-- Both stay NULL for items saved outside a conversation. The path points
+-- at a file on this machine, so neither column is exported.
+
+ALTER TABLE context_items ADD COLUMN transcript_path TEXT;
+ALTER TABLE context_items ADD COLUMN transcript_message INTEGER;
@@ -0,0 +1,286 @@
+//! The command log (`~/.savecontext/logs/commands.log`).
+//!
+//! Tracing goes to stderr and is gone once the terminal closes, which says
+//! nothing about what a fleet of agents ran overnight. With the command
+//! log on, every `sc` command (each `sc shell` line included) appends one
+//! JSON line:
+//!
+//! ```json
+//! {"ts":"2026-10-16T02:14:07.512Z","command":"issue update","args":{"id":"SC-a1b2","status":"closed"},
+//!  "actor":"claude-exec","session":"sess_1","cwd":"/home/me/site","pid":4121,
+//!  "duration_ms":38,"ok":false,"exit_code":4,"error":"INVALID_TRANSITION"}
+//! ```
+//!
+//! Arguments are redacted: flags, numbers, record IDs and the values of
+//! options that pick from a fixed set (`--status`, `--category`, ...) are
+//! kept, and every other value (keys, titles, text, paths) is written as
+//! `***`. Error messages are left out for the same reason; `error` is the
+//! code.
+//!
+//! It is off by default. Turn it on with `SC_COMMAND_LOG=1` or the
+//! `command_log` section of `~/.savecontext/config.json`:
+//!
+//! ```json
+//! { "command_log": { "enabled": true, "max_size_mb": 10, "keep": 5 } }
+//! ```
+//!
+//! When the file would grow past `max_size_mb` it becomes `commands.log.1`
+//! (the previous `.1` becomes `.2`, and so on), and files past `keep` are
+//! deleted. Writing the log is best effort: a failure never fails the
+//! command.
+
+use crate::error::{Error, Result};
+use clap::parser::ValueSource;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Default `command_log.max_size_mb`.
+pub const DEFAULT_MAX_SIZE_MB: u64 = 10;
+
+/// Default `command_log.keep`: rotated files kept besides the live one.
+pub const DEFAULT_KEEP: u32 = 5;
+
+/// What a redacted value is written as.
+const REDACTED: &str = "***";
+
+/// Options whose values name a status, type or ordering rather than
+/// carrying the user's text, so are logged as given.
+const KEPT_ARGS: &[&str] = &[
+    "status", "priority", "issue_type", "category", "actor_type", "trust", "min_trust", "sort", "order",
+    "period", "format", "strategy", "mode", "kind",
+];
+
+/// The `command_log` section of `~/.savecontext/config.json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandLogConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Size at which the log is rotated (default: 10).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+    /// Rotated files to keep (default: 5, 0 = none).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep: Option<u32>,
+}
+
+impl CommandLogConfig {
+    /// Size in bytes at which the log is rotated.
+    #[must_use]
+    pub fn max_bytes(&self) -> u64 {
+        self.max_size_mb.unwrap_or(DEFAULT_MAX_SIZE_MB).max(1) * 1024 * 1024
+    }
+
+    /// Rotated files to keep.
+    #[must_use]
+    pub fn keep(&self) -> u32 {
+        self.keep.unwrap_or(DEFAULT_KEEP)
+    }
+}
+
+/// One logged command.
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    /// When it finished, RFC 3339 in UTC.
+    pub ts: String,
+    /// Subcommand path, e.g. `issue dep add`.
+    pub command: String,
+    /// Arguments given on the command line, by name, redacted.
+    pub args: Map<String, Value>,
+    pub actor: String,
+    pub session: Option<String>,
+    pub cwd: Option<String>,
+    pub pid: u32,
+    pub duration_ms: u64,
+    pub ok: bool,
+    pub exit_code: u8,
+    /// Error code of a failed command.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'static str>,
+}
+
+/// Where the log is written.
+#[must_use]
+pub fn log_path() -> Option<PathBuf> {
+    crate::config::global_savecontext_dir().map(|dir| dir.join("logs").join("commands.log"))
+}
+
+/// The arguments of a parsed command line that were given on it, by name,
+/// redacted as described in the module docs.
+#[must_use]
+pub fn redacted_args(command: &clap::Command, matches: &clap::ArgMatches) -> Map<String, Value> {
+    let mut command = command.clone();
+    command.build();
+    let mut args = Map::new();
+    let mut level = (&command, matches);
+    loop {
+        let (command, matches) = level;
+        for arg in command.get_arguments() {
+            let id = arg.get_id().as_str();
+            if matches.value_source(id) != Some(ValueSource::CommandLine) {
+                continue;
+            }
+            let Ok(Some(raw)) = matches.try_get_raw(id) else {
+                continue;
+            };
+            let choices = arg.get_possible_values();
+            let mut values: Vec<Value> = raw
+                .map(|value| {
+                    let value = value.to_string_lossy();
+                    let kept = !arg.get_action().takes_values()
+                        || KEPT_ARGS.contains(&id)
+                        || choices.iter().any(|c| c.matches(&value, arg.is_ignore_case_set()))
+                        || value.parse::<f64>().is_ok()
+                        || is_record_id(&value);
+                    Value::String(if kept { value.into_owned() } else { REDACTED.to_string() })
+                })
+                .collect();
+            let value = if values.len() == 1 { values.remove(0) } else { Value::Array(values) };
+            args.insert(id.to_string(), value);
+        }
+        let Some((name, sub_matches)) = matches.subcommand() else {
+            break;
+        };
+        let Some(sub) = command.find_subcommand(name) else {
+            break;
+        };
+        level = (sub, sub_matches);
+    }
+    args
+}
+
+/// Whether `value` is an ID `sc` generated: `sess_1a2b3c4d-5e6` or a
+/// short ID like `SC-a1b2`.
+fn is_record_id(value: &str) -> bool {
+    let hex = |s: &str| s.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c));
+    if let Some((prefix, rest)) = value.split_once('_') {
+        return !prefix.is_empty()
+            && prefix.chars().all(|c| c.is_ascii_lowercase())
+            && rest.len() == 12
+            && hex(&rest.replace('-', ""));
+    }
+    value.split_once('-').is_some_and(|(prefix, rest)| {
+        !prefix.is_empty()
+            && prefix.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            && rest.len() >= 4
+            && hex(rest)
+    })
+}
+
+/// Append `entry` to the log at `path`, rotating it first if it would grow
+/// past the limit.
+///
+/// # Errors
+///
+/// Returns an error if the log directory or file can't be written.
+pub fn append(path: &Path, config: &CommandLogConfig, entry: &Entry) -> Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let size = fs::metadata(path).map_or(0, |m| m.len());
+    if size > 0 && size + line.len() as u64 > config.max_bytes() {
+        rotate(path, config.keep())?;
+    }
+    // One write per line, so lines from concurrent processes don't interleave
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Shift `commands.log.N` to `.N+1` (dropping what falls past `keep`) and
+/// the live file to `.1`.
+fn rotate(path: &Path, keep: u32) -> Result<()> {
+    let numbered = |n: u32| {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    };
+    if keep == 0 {
+        return remove_if_present(path);
+    }
+    remove_if_present(&numbered(keep))?;
+    for n in (1..keep).rev() {
+        let from = numbered(n);
+        if from.exists() {
+            fs::rename(&from, numbered(n + 1))?;
+        }
+    }
+    // Another process may have rotated it already
+    match fs::rename(path, numbered(1)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Io(e)),
+        _ => Ok(()),
+    }
+}
+
+fn remove_if_present(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Io(e)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+
+    fn entry(command: &str) -> Entry {
+        Entry {
+            ts: "2026-10-16T02:14:07.512Z".to_string(),
+            command: command.to_string(),
+            args: Map::new(),
+            actor: "bot".to_string(),
+            session: None,
+            cwd: None,
+            pid: 1,
+            duration_ms: 5,
+            ok: true,
+            exit_code: 0,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_redacted_args() {
+        let matches = Cli::command()
+            .try_get_matches_from(["sc", "--actor", "bot", "issue", "list", "--status", "closed", "--limit", "5", "--search", "db password"])
+            .unwrap();
+        let args = redacted_args(&Cli::command(), &matches);
+        assert_eq!(args["status"], "closed");
+        assert_eq!(args["limit"], "5");
+        assert_eq!(args["search"], REDACTED);
+        assert_eq!(args["actor"], REDACTED);
+        assert!(!args.contains_key("json"));
+        let matches = Cli::command().try_get_matches_from(["sc", "--quiet", "status"]).unwrap();
+        assert_eq!(redacted_args(&Cli::command(), &matches)["quiet"], "true");
+
+        assert!(is_record_id("sess_f30abdd6-ed8") && is_record_id("SC-a1b2"));
+        assert!(!is_record_id("hunter2") && !is_record_id("db_password") && !is_record_id("feature-login"));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("commands.log");
+        let line_len = serde_json::to_string(&entry("status")).unwrap().len() as u64 + 1;
+        let config = CommandLogConfig { enabled: true, max_size_mb: Some(1), keep: Some(2) };
+        let per_file = config.max_bytes() / line_len;
+
+        for _ in 0..per_file * 4 {
+            append(&path, &config, &entry("status")).unwrap();
+        }
+        let mut files: Vec<String> =
+            fs::read_dir(path.parent().unwrap()).unwrap().map(|e| e.unwrap().file_name().into_string().unwrap()).collect();
+        files.sort();
+        assert_eq!(files, ["commands.log", "commands.log.1", "commands.log.2"]);
+        assert!(fs::metadata(&path).unwrap().len() <= config.max_bytes());
+        let last = fs::read_to_string(&path).unwrap();
+        let parsed: Value = serde_json::from_str(last.lines().next().unwrap()).unwrap();
+        assert_eq!(parsed["command"], "status");
+    }
+}
@@ -0,0 +1,332 @@
+//! Actor command implementations (`sc actor`).
+//!
+//! `SC_ACTOR` and `--actor` take any string, so one person's work gets
+//! split across `alice`, `Alice L` and `alice@laptop`. Registering an actor
+//! gives it a canonical ID, a display name and a type (human, agent or
+//! model); aliases map the other spellings to it. From then on the database
+//! records the canonical ID for events, time entries, @mentions and issue
+//! assignments written under any alias, and `sc inbox` and `sc report`
+//! look up the canonical ID of the name they are given.
+//!
+//! `sc purge-actor` is the way out: when someone leaves or a test agent
+//! wrote into the wrong database, it deletes what the actor created and
+//! strips its name from everything else (or, with `--anonymize`, keeps
+//! the records under a pseudonym).
+
+use crate::cli::{ActorCommands, Database};
+use crate::config::default_actor;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::ActorType;
+use crate::storage::{Actor, ActorPurgeStats, SqliteStorage};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Output for actor list.
+#[derive(Serialize)]
+struct ActorListOutput {
+    actors: Vec<Actor>,
+    count: usize,
+}
+
+/// Output for actor show.
+#[derive(Serialize)]
+struct ActorShowOutput {
+    /// The name looked up.
+    name: String,
+    /// Canonical ID the name resolves to (the name itself if unregistered).
+    id: String,
+    registered: bool,
+    actor: Option<Actor>,
+    /// Role from the `permissions` config section, if any.
+    role: Option<String>,
+    /// Operations the role permits (omitted when unrestricted).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_operations: Option<Vec<String>>,
+}
+
+/// Output for purge-actor.
+#[derive(Serialize)]
+struct PurgeOutput {
+    /// Canonical ID of the purged actor.
+    actor: String,
+    anonymized: bool,
+    /// Name written in place of the actor's on rows that were kept.
+    pseudonym: String,
+    /// True when nothing was changed (no `--force`, or `--dry-run`).
+    dry_run: bool,
+    purged: ActorPurgeStats,
+}
+
+/// Output for actor alias.
+#[derive(Serialize)]
+struct AliasOutput<'a> {
+    actor: &'a str,
+    aliases: &'a [String],
+    removed: bool,
+    /// Aliases that were added or removed; the rest were already so.
+    changed: usize,
+}
+
+json_schema!(AliasOutput<'a> { actor: &'a str, aliases: &'a [String], removed: bool, changed: usize });
+json_schema!(ActorListOutput { actors: Vec<Actor>, count: usize });
+json_schema!(ActorShowOutput {
+    name: String,
+    id: String,
+    registered: bool,
+    actor: Option<Actor>,
+    role: Option<String>,
+    allowed_operations: Option<Vec<String>>,
+});
+json_schema!(PurgeOutput { actor: String, anonymized: bool, pseudonym: String, dry_run: bool, purged: ActorPurgeStats });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("actor register", schema_for::<Actor>()),
+        ("actor list", schema_for::<ActorListOutput>()),
+        ("actor show", schema_for::<ActorShowOutput>()),
+        ("actor alias", schema_for::<AliasOutput<'_>>()),
+        ("purge-actor", schema_for::<PurgeOutput>()),
+    ]
+}
+
+/// Execute actor commands.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened, a type is invalid,
+/// or an alias belongs to another actor.
+pub fn execute(command: &ActorCommands, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let me = actor.map_or_else(default_actor, ToString::to_string);
+
+    match command {
+        ActorCommands::Register { id, display_name, actor_type, aliases } => {
+            let actor_type = actor_type.as_deref().map(str::parse::<ActorType>).transpose()?;
+            register(storage, id, display_name.as_deref(), actor_type, aliases, &me, json)
+        }
+        ActorCommands::Alias { actor, aliases, remove } => alias(storage, actor, aliases, *remove, &me, json),
+        ActorCommands::List => list(storage, json),
+        ActorCommands::Show { name } => show(storage, name.as_deref().unwrap_or(&me), json),
+    }
+}
+
+fn register(
+    storage: &mut SqliteStorage,
+    id: &str,
+    name: Option<&str>,
+    actor_type: Option<ActorType>,
+    aliases: &[String],
+    me: &str,
+    json: bool,
+) -> Result<()> {
+    if id.trim().is_empty() {
+        return Err(Error::InvalidArgument("Actor ID cannot be empty".to_string()));
+    }
+    if crate::is_dry_run() {
+        println!("Would register actor: {id}");
+        return Ok(());
+    }
+
+    let registered = storage.with_transaction(|storage| {
+        let result = storage.register_actor(id, name, actor_type, me).and_then(|_| {
+            for alias in aliases {
+                storage.add_actor_alias(id, alias, me)?;
+            }
+            storage.get_actor(id)
+        });
+        let ok = result.is_ok();
+        (result, ok)
+    })??;
+    let Some(actor) = registered else {
+        return Err(Error::Other(format!("Actor '{id}' was not registered")));
+    };
+
+    if crate::is_silent() {
+        println!("{}", actor.id);
+    } else if json {
+        println!("{}", serde_json::to_string(&actor)?);
+    } else {
+        println!("Registered actor: {}", describe(&actor));
+    }
+    Ok(())
+}
+
+fn alias(storage: &mut SqliteStorage, actor: &str, aliases: &[String], remove: bool, me: &str, json: bool) -> Result<()> {
+    if crate::is_dry_run() {
+        let verb = if remove { "remove" } else { "add" };
+        println!("Would {verb} aliases of {actor}: {}", aliases.join(", "));
+        return Ok(());
+    }
+
+    let changed = storage.with_transaction(|storage| {
+        let result: Result<Vec<bool>> = aliases
+            .iter()
+            .map(|alias| {
+                if remove {
+                    storage.remove_actor_alias(alias, me)
+                } else {
+                    storage.add_actor_alias(actor, alias, me)
+                }
+            })
+            .collect();
+        let ok = result.is_ok();
+        (result, ok)
+    })??;
+
+    if crate::is_silent() {
+        return Ok(());
+    }
+    if json {
+        let output = AliasOutput { actor, aliases, removed: remove, changed: changed.iter().filter(|c| **c).count() };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        for (alias, changed) in aliases.iter().zip(changed) {
+            match (remove, changed) {
+                (false, true) => println!("{alias} -> {actor}"),
+                (false, false) => println!("{alias} already resolves to {actor}"),
+                (true, true) => println!("Removed alias: {alias}"),
+                (true, false) => println!("No alias: {alias}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn list(storage: &SqliteStorage, json: bool) -> Result<()> {
+    let actors = storage.list_actors()?;
+
+    if json {
+        let output = ActorListOutput { count: actors.len(), actors };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    if actors.is_empty() {
+        println!("No actors registered. Register one with: sc actor register <id> --display-name \"Display Name\"");
+        return Ok(());
+    }
+    for actor in &actors {
+        println!("{}", describe(actor));
+        if !actor.aliases.is_empty() {
+            println!("    aliases: {}", actor.aliases.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn show(storage: &SqliteStorage, name: &str, json: bool) -> Result<()> {
+    let actor = storage.get_actor(name)?;
+    let id = actor.as_ref().map_or_else(|| name.to_string(), |a| a.id.clone());
+    let permissions = storage.permissions();
+    let role = permissions.and_then(|p| p.role_of(&id)).map(ToString::to_string);
+    let allowed_operations = permissions.zip(role.as_deref()).map(|(p, role)| p.allowed_operations(role).into_iter().map(ToString::to_string).collect::<Vec<_>>());
+
+    if json {
+        let output = ActorShowOutput { name: name.to_string(), id, registered: actor.is_some(), actor, role, allowed_operations };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    match actor {
+        Some(actor) => {
+            println!("{}", describe(&actor));
+            if !actor.aliases.is_empty() {
+                println!("    aliases: {}", actor.aliases.join(", "));
+            }
+        }
+        None => println!("{name} is not a registered actor or alias"),
+    }
+    match (role, allowed_operations) {
+        (Some(role), Some(ops)) if ops.is_empty() => println!("    role: {role} (permits nothing; is it defined?)"),
+        (Some(role), Some(ops)) => println!("    role: {role} ({})", ops.join(", ")),
+        _ => println!("    role: none (unrestricted)"),
+    }
+    Ok(())
+}
+
+/// Execute `sc purge-actor`. Without `force` the purge runs and is rolled
+/// back, so the counts show what it would do.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened, `name` is the actor
+/// running the purge, or a statement fails.
+pub fn purge(
+    name: &str,
+    anonymize: bool,
+    force: bool,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+    let me = actor.map_or_else(default_actor, ToString::to_string);
+    let id = storage.resolve_actor(name)?;
+    let pseudonym = format!("anon-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+    let dry_run = !force || crate::is_dry_run();
+
+    let purged = storage.with_transaction(|storage| {
+        let result = storage.purge_actor(name, anonymize, &pseudonym, &me);
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+
+    if json {
+        let output = PurgeOutput { actor: id, anonymized: anonymize, pseudonym, dry_run, purged };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    if crate::is_silent() {
+        return Ok(());
+    }
+    if purged.rows() == 0 {
+        println!("Nothing in the database refers to {id}");
+        return Ok(());
+    }
+
+    let verb = match (dry_run, anonymize) {
+        (true, false) => "Would purge",
+        (true, true) => "Would anonymize",
+        (false, false) => "Purged",
+        (false, true) => "Anonymized",
+    };
+    println!("{verb} {id}:");
+    for (label, count) in [
+        ("sessions deleted", purged.sessions),
+        ("context items deleted", purged.context_items),
+        ("issues deleted", purged.issues),
+        ("memory items deleted", purged.memory),
+        ("checkpoints deleted", purged.checkpoints),
+        ("time entries deleted", purged.time_entries),
+        ("issues referring to the actor", purged.issue_refs),
+        ("time entries logged", purged.time_entry_refs),
+        ("audit events", purged.events),
+        ("plan revisions", purged.plan_revisions),
+        ("token usage records", purged.token_usage),
+        ("watches and inbox markers", purged.watches),
+        ("trash entries", purged.trash),
+        ("recorded sync deletions", purged.sync_deletions),
+        ("actor and alias rows", purged.actor_rows),
+    ] {
+        if count > 0 {
+            println!("  {count} {label}");
+        }
+    }
+    if dry_run {
+        println!("Run again with --force to apply.");
+    } else {
+        if anonymize {
+            println!("Records now name {pseudonym}.");
+        }
+        println!("Run `sc sync export` to publish the changes; events already in events.jsonl files are not rewritten.");
+    }
+    Ok(())
+}
+
+/// `id (Display Name) [type]`.
+fn describe(actor: &Actor) -> String {
+    match &actor.display_name {
+        Some(name) => format!("{} ({name}) [{}]", actor.id, actor.actor_type),
+        None => format!("{} [{}]", actor.id, actor.actor_type),
+    }
+}
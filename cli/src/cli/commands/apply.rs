@@ -0,0 +1,683 @@
+//! Batch apply (`sc apply ops.jsonl`).
+//!
+//! Reads one JSON operation per line and runs them all in a single
+//! transaction, so an agent can submit a burst of mixed writes in one call:
+//!
+//! ```text
+//! {"op": "save_item", "key": "auth-choice", "value": "JWT", "category": "decision"}
+//! {"op": "create_issue", "title": "Add login", "issue_type": "feature"}
+//! {"op": "create_issue", "title": "Login form", "parent": "$1"}
+//! {"op": "add_dep", "issue": "$2", "depends_on": "a1b2"}
+//! {"op": "checkpoint", "name": "after-planning"}
+//! ```
+//!
+//! `$N` stands for the ID created by operation `N` (0-based, blank lines
+//! not counted), as in `sc issue batch`. By default the batch is
+//! all-or-nothing: the first failure rolls everything back. With
+//! `--best-effort`, each operation commits or rolls back on its own.
+
+use crate::cli::Database;
+use crate::core::issues::workflow_for_issue;
+use crate::config::{default_actor, resolve_project_path, resolve_session_or_suggest};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for, string_enum, JsonSchema};
+use crate::model::{IssueStatus, IssueType, ItemCategory, ItemPriority, MemoryCategory};
+use crate::storage::SqliteStorage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::path::Path;
+
+/// One operation line.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Op {
+    SaveItem {
+        key: String,
+        value: String,
+        #[serde(default)]
+        category: Option<ItemCategory>,
+        #[serde(default)]
+        priority: Option<ItemPriority>,
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    UpdateItem {
+        key: String,
+        #[serde(default)]
+        value: Option<String>,
+        #[serde(default)]
+        category: Option<ItemCategory>,
+        #[serde(default)]
+        priority: Option<ItemPriority>,
+        #[serde(default)]
+        channel: Option<String>,
+    },
+    DeleteItem {
+        key: String,
+    },
+    TagItem {
+        key: String,
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    SaveMemory {
+        key: String,
+        value: String,
+        #[serde(default)]
+        category: Option<MemoryCategory>,
+    },
+    CreateIssue {
+        title: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        details: Option<String>,
+        #[serde(default)]
+        issue_type: Option<String>,
+        #[serde(default)]
+        priority: Option<i32>,
+        #[serde(default)]
+        parent: Option<String>,
+        #[serde(default)]
+        plan_id: Option<String>,
+        #[serde(default)]
+        labels: Vec<String>,
+    },
+    UpdateIssue {
+        id: String,
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        details: Option<String>,
+        #[serde(default)]
+        issue_type: Option<String>,
+        #[serde(default)]
+        priority: Option<i32>,
+        #[serde(default)]
+        parent: Option<String>,
+        #[serde(default)]
+        status: Option<String>,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    CloseIssue {
+        id: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    AddDep {
+        issue: String,
+        depends_on: String,
+        #[serde(default)]
+        dep_type: Option<String>,
+    },
+    AddLabels {
+        id: String,
+        labels: Vec<String>,
+    },
+    Checkpoint {
+        name: String,
+        #[serde(default)]
+        description: Option<String>,
+    },
+}
+
+impl Op {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::SaveItem { .. } => "save_item",
+            Self::UpdateItem { .. } => "update_item",
+            Self::DeleteItem { .. } => "delete_item",
+            Self::TagItem { .. } => "tag_item",
+            Self::SaveMemory { .. } => "save_memory",
+            Self::CreateIssue { .. } => "create_issue",
+            Self::UpdateIssue { .. } => "update_issue",
+            Self::CloseIssue { .. } => "close_issue",
+            Self::AddDep { .. } => "add_dep",
+            Self::AddLabels { .. } => "add_labels",
+            Self::Checkpoint { .. } => "checkpoint",
+        }
+    }
+}
+
+/// Result of one operation.
+#[derive(Debug, Serialize)]
+struct OpResult {
+    index: usize,
+    line: usize,
+    op: &'static str,
+    /// `ok`, `failed`, `rolled_back` (succeeded, then undone), or `skipped`
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    short_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Output for apply.
+#[derive(Serialize)]
+struct ApplyOutput<'a> {
+    mode: Mode,
+    dry_run: bool,
+    committed: bool,
+    /// Operations whose changes were kept.
+    applied: usize,
+    failed: usize,
+    results: &'a [OpResult],
+}
+
+/// Whether a failed operation rolls back the whole batch.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    Atomic,
+    BestEffort,
+}
+
+impl JsonSchema for Mode {
+    fn json_schema() -> Value {
+        string_enum(&["atomic", "best_effort"])
+    }
+}
+
+json_schema!(OpResult {
+    index: usize,
+    line: usize,
+    op: &'static str,
+    status: &'static str,
+    id: Option<String>,
+    short_id: Option<String>,
+    error: Option<String>,
+});
+json_schema!(ApplyOutput<'a> { mode: Mode, dry_run: bool, committed: bool, applied: usize, failed: usize, results: &'a [OpResult] });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("apply", schema_for::<ApplyOutput<'_>>())]
+}
+
+/// What an operation created or touched.
+#[derive(Default)]
+struct Applied {
+    id: Option<String>,
+    short_id: Option<String>,
+}
+
+/// State shared by the operations of one batch.
+struct Batch<'a> {
+    actor: String,
+    session_arg: Option<&'a str>,
+    session: Option<String>,
+    project_path: Option<String>,
+    /// ID created by each operation so far, for `$N` references
+    ids: Vec<Option<String>>,
+}
+
+impl Batch<'_> {
+    fn session(&mut self, storage: &SqliteStorage) -> Result<String> {
+        if self.session.is_none() {
+            self.session = Some(resolve_session_or_suggest(self.session_arg, storage)?);
+        }
+        Ok(self.session.clone().unwrap_or_default())
+    }
+
+    fn project_path(&mut self, storage: &SqliteStorage) -> Result<String> {
+        if self.project_path.is_none() {
+            self.project_path = Some(resolve_project_path(storage, None)?);
+        }
+        Ok(self.project_path.clone().unwrap_or_default())
+    }
+
+    /// Resolve a `$N` reference to the ID created by operation `N`.
+    fn resolve(&self, id: &str) -> Result<String> {
+        let Some(index) = id.strip_prefix('$') else {
+            return Ok(id.to_string());
+        };
+        index
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| self.ids.get(n).cloned().flatten())
+            .ok_or_else(|| {
+                Error::InvalidArgument(format!("{id} does not name an earlier operation that created something"))
+            })
+    }
+
+    fn resolve_opt(&self, id: Option<&String>) -> Result<Option<String>> {
+        id.map(|id| self.resolve(id)).transpose()
+    }
+}
+
+/// Execute the apply command.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed, or if any
+/// operation fails (after printing the per-operation results).
+pub fn execute(
+    file: &Path,
+    best_effort: bool,
+    db: &mut Database,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let content = if file.as_os_str() == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        std::fs::read_to_string(file)?
+    };
+    let ops = parse_ops(&content)?;
+
+    let storage = db.storage()?;
+    let dry_run = crate::is_dry_run();
+
+    let mut batch = Batch {
+        actor: actor.map_or_else(default_actor, ToString::to_string),
+        session_arg: session_id,
+        session: None,
+        project_path: None,
+        ids: Vec::with_capacity(ops.len()),
+    };
+
+    let results = storage.with_transaction(|storage| {
+        let mut results = Vec::with_capacity(ops.len());
+        let mut failed = false;
+        for (index, (line, op)) in ops.iter().enumerate() {
+            if failed && !best_effort {
+                results.push(result(index, *line, op, "skipped", Applied::default(), None));
+                batch.ids.push(None);
+                continue;
+            }
+            // Each operation is a savepoint, so a failure undoes all of its writes
+            let outcome = storage
+                .with_transaction(|storage| {
+                    let outcome = run_op(storage, &mut batch, op);
+                    let ok = outcome.is_ok();
+                    (outcome, ok)
+                })
+                .and_then(|outcome| outcome);
+            match outcome {
+                Ok(applied) => {
+                    batch.ids.push(applied.id.clone());
+                    results.push(result(index, *line, op, "ok", applied, None));
+                }
+                Err(e) => {
+                    failed = true;
+                    batch.ids.push(None);
+                    results.push(result(index, *line, op, "failed", Applied::default(), Some(e.to_string())));
+                }
+            }
+        }
+        if failed && !best_effort {
+            for r in results.iter_mut().filter(|r| r.status == "ok") {
+                r.status = "rolled_back";
+            }
+        }
+        let keep = !dry_run && (best_effort || !failed);
+        (results, keep)
+    })?;
+
+    print_results(&results, best_effort, dry_run, json)?;
+
+    let failures = results.iter().filter(|r| r.status == "failed").count();
+    if failures == 0 {
+        return Ok(());
+    }
+    let first = results.iter().find(|r| r.status == "failed");
+    let detail = first.map_or_else(String::new, |r| {
+        format!(": line {} ({}): {}", r.line, r.op, r.error.as_deref().unwrap_or_default())
+    });
+    let outcome = if best_effort { "the rest were applied" } else { "nothing was applied" };
+    Err(Error::Other(format!(
+        "{failures} of {} operation(s) failed, {outcome}{detail}",
+        results.len()
+    )))
+}
+
+fn result(
+    index: usize,
+    line: usize,
+    op: &Op,
+    status: &'static str,
+    applied: Applied,
+    error: Option<String>,
+) -> OpResult {
+    OpResult {
+        index,
+        line,
+        op: op.name(),
+        status,
+        id: applied.id,
+        short_id: applied.short_id,
+        error,
+    }
+}
+
+/// Parse the JSONL input into `(line number, op)` pairs. Blank lines are skipped.
+fn parse_ops(content: &str) -> Result<Vec<(usize, Op)>> {
+    let mut ops = Vec::new();
+    let mut errors = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Op>(line) {
+            Ok(op) => ops.push((index + 1, op)),
+            Err(e) => errors.push(format!("line {}: {e}", index + 1)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(Error::InvalidArgument(format!(
+            "Invalid operation(s), nothing was applied:\n  {}",
+            errors.join("\n  ")
+        )));
+    }
+    if ops.is_empty() {
+        return Err(Error::InvalidArgument("No operations to apply".to_string()));
+    }
+    Ok(ops)
+}
+
+fn run_op(storage: &mut SqliteStorage, batch: &mut Batch<'_>, op: &Op) -> Result<Applied> {
+    match op {
+        Op::SaveMemory { key, value, category } => {
+            let project_path = batch.project_path(storage)?;
+            let id = format!("mem_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+            storage.save_memory(&id, &project_path, key, value, category.unwrap_or_default(), None, &batch.actor)?;
+            Ok(Applied {
+                id: storage.get_memory(&project_path, key)?.map(|m| m.id),
+                short_id: None,
+            })
+        }
+        Op::CreateIssue { .. }
+        | Op::UpdateIssue { .. }
+        | Op::CloseIssue { .. }
+        | Op::AddDep { .. }
+        | Op::AddLabels { .. } => run_issue_op(storage, batch, op),
+        _ => run_session_op(storage, batch, op),
+    }
+}
+
+/// Context item and checkpoint operations, which act on the current session.
+fn run_session_op(storage: &mut SqliteStorage, batch: &mut Batch<'_>, op: &Op) -> Result<Applied> {
+    let session = batch.session(storage)?;
+    let actor = batch.actor.as_str();
+    let mut applied = Applied::default();
+
+    match op {
+        Op::SaveItem { key, value, category, priority, tags } => {
+            let id = format!("item_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+            storage.save_context_item(&id, &session, key, value, category.clone(), *priority, actor)?;
+            if !tags.is_empty() {
+                storage.add_tags_to_item(&session, key, tags, actor)?;
+            }
+            applied.id = storage.get_item_id_by_key(&session, key)?;
+        }
+        Op::UpdateItem { key, value, category, priority, channel } => {
+            storage.update_context_item(
+                &session,
+                key,
+                value.as_deref(),
+                category.clone(),
+                *priority,
+                channel.as_deref(),
+                actor,
+            )?;
+            applied.id = storage.get_item_id_by_key(&session, key)?;
+        }
+        Op::DeleteItem { key } => {
+            storage.delete_context_item(&session, key, actor)?;
+        }
+        Op::TagItem { key, add, remove } => {
+            if !add.is_empty() {
+                storage.add_tags_to_item(&session, key, add, actor)?;
+            }
+            if !remove.is_empty() {
+                storage.remove_tags_from_item(&session, key, remove, actor)?;
+            }
+            applied.id = storage.get_item_id_by_key(&session, key)?;
+        }
+        Op::Checkpoint { name, description } => {
+            let id = format!("ckpt_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+            let items = storage.get_context_items(&session, None, None, Some(u32::MAX))?;
+            storage.create_checkpoint(&id, &session, name, description.as_deref(), None, None, actor)?;
+            for item in &items {
+                storage.add_checkpoint_item(&id, &item.id, actor)?;
+            }
+            applied.id = Some(id);
+        }
+        _ => unreachable!("run_op routes other operations elsewhere"),
+    }
+    Ok(applied)
+}
+
+/// Issue operations. IDs may be `$N` references.
+fn run_issue_op(storage: &mut SqliteStorage, batch: &mut Batch<'_>, op: &Op) -> Result<Applied> {
+    let actor = batch.actor.clone();
+    let mut applied = Applied::default();
+
+    match op {
+        Op::CreateIssue { title, description, details, issue_type, priority, parent, plan_id, labels } => {
+            let project_path = batch.project_path(storage)?;
+            let issue_type = issue_type.as_deref().map(normalize_type).transpose()?;
+            let parent = batch.resolve_opt(parent.as_ref())?;
+            let id = format!("issue_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+            let short_id = storage.next_issue_short_id()?;
+            storage.create_issue(
+                &id,
+                Some(&short_id),
+                &project_path,
+                title,
+                description.as_deref(),
+                details.as_deref(),
+                issue_type,
+                *priority,
+                plan_id.as_deref(),
+                None,
+                &actor,
+            )?;
+            if let Some(ref parent) = parent {
+                storage.add_issue_dependency(&id, parent, "parent-child", &actor)?;
+            }
+            if !labels.is_empty() {
+                storage.add_issue_labels(&id, labels, &actor)?;
+            }
+            applied.id = Some(id);
+            applied.short_id = Some(short_id);
+        }
+        Op::UpdateIssue { id, title, description, details, issue_type, priority, parent, status, reason } => {
+            let id = batch.resolve(id)?;
+            let issue_type = issue_type.as_deref().map(normalize_type).transpose()?;
+            let parent = batch.resolve_opt(parent.as_ref())?;
+            if title.is_some()
+                || description.is_some()
+                || details.is_some()
+                || issue_type.is_some()
+                || priority.is_some()
+                || parent.is_some()
+            {
+                storage.update_issue(
+                    &id,
+                    title.as_deref(),
+                    description.as_deref(),
+                    details.as_deref(),
+                    *priority,
+                    issue_type,
+                    None,
+                    parent.as_deref(),
+                    &actor,
+                )?;
+            }
+            if let Some(status) = status {
+                let status: IssueStatus = status.parse()?;
+                let workflow = workflow_for_issue(storage, &id);
+                storage.update_issue_status(&id, &status, reason.as_deref(), &workflow, &actor)?;
+            }
+            applied.id = Some(id);
+        }
+        Op::CloseIssue { id, reason } => {
+            let id = batch.resolve(id)?;
+            let workflow = workflow_for_issue(storage, &id);
+            storage.update_issue_status(&id, &IssueStatus::Closed, reason.as_deref(), &workflow, &actor)?;
+            applied.id = Some(id);
+        }
+        Op::AddDep { issue, depends_on, dep_type } => {
+            let issue = batch.resolve(issue)?;
+            let depends_on = batch.resolve(depends_on)?;
+            storage.add_issue_dependency(&issue, &depends_on, dep_type.as_deref().unwrap_or("blocks"), &actor)?;
+        }
+        Op::AddLabels { id, labels } => {
+            let id = batch.resolve(id)?;
+            storage.add_issue_labels(&id, labels, &actor)?;
+            applied.id = Some(id);
+        }
+        _ => unreachable!("run_op routes other operations elsewhere"),
+    }
+    Ok(applied)
+}
+
+fn normalize_type(issue_type: &str) -> Result<IssueType> {
+    crate::validate::normalize_type(issue_type)
+        .map_err(|(val, suggestion)| {
+            Error::InvalidArgument(suggestion.map_or_else(
+                || format!("Invalid issue type '{val}'. Valid: task, bug, feature, epic, chore"),
+                |s| format!("Invalid issue type '{val}'. Did you mean '{s}'?"),
+            ))
+        })?
+        .parse()
+}
+
+fn print_results(results: &[OpResult], best_effort: bool, dry_run: bool, json: bool) -> Result<()> {
+    let count = |status: &str| results.iter().filter(|r| r.status == status).count();
+    let failed = count("failed");
+    let committed = !dry_run && (best_effort || failed == 0);
+
+    if json {
+        let output = ApplyOutput {
+            mode: if best_effort { Mode::BestEffort } else { Mode::Atomic },
+            dry_run,
+            committed,
+            applied: if committed { count("ok") } else { 0 },
+            failed,
+            results,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if crate::is_silent() {
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("Dry run: {} of {} operation(s) would succeed (rolled back)", count("ok"), results.len());
+    } else if committed {
+        println!("Applied {} of {} operation(s)", count("ok"), results.len());
+    } else {
+        println!("Rolled back: {failed} operation(s) failed");
+    }
+    for r in results.iter().filter(|r| r.status == "failed") {
+        println!(
+            "  line {} ({}): {}",
+            r.line,
+            r.op,
+            r.error.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ops() {
+        let input = "{\"op\": \"save_item\", \"key\": \"k\", \"value\": \"v\"}\n\n\
+                     {\"op\": \"create_issue\", \"title\": \"T\", \"labels\": [\"a\"]}\n";
+        let ops = parse_ops(input).unwrap();
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[1].0, 3, "line numbers count blank lines");
+        assert_eq!(ops[1].1.name(), "create_issue");
+
+        let err = parse_ops("{\"op\": \"launch_rocket\"}\n{\"op\": \"delete_item\"}\n").unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("line 1") && msg.contains("line 2"), "{msg}");
+        assert!(parse_ops("\n").is_err());
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let batch = Batch {
+            actor: "a".to_string(),
+            session_arg: None,
+            session: None,
+            project_path: None,
+            ids: vec![Some("issue_1".to_string()), None],
+        };
+        assert_eq!(batch.resolve("$0").unwrap(), "issue_1");
+        assert_eq!(batch.resolve("abcd").unwrap(), "abcd");
+        assert!(batch.resolve("$1").is_err(), "op 1 created nothing");
+        assert!(batch.resolve("$9").is_err());
+    }
+
+    #[test]
+    fn test_atomic_apply_rolls_back_on_failure() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_session("sess_1", "Test", None, Some("/p"), None, None, "a").unwrap();
+        let ops = parse_ops(
+            "{\"op\": \"save_item\", \"key\": \"k\", \"value\": \"v\"}\n\
+             {\"op\": \"tag_item\", \"key\": \"missing\", \"add\": [\"x\"]}\n",
+        )
+        .unwrap();
+        let mut batch = Batch {
+            actor: "a".to_string(),
+            session_arg: None,
+            session: Some("sess_1".to_string()),
+            project_path: Some("/p".to_string()),
+            ids: Vec::new(),
+        };
+
+        let ok = storage
+            .with_transaction(|s| {
+                let ok = ops.iter().all(|(_, op)| run_op(s, &mut batch, op).is_ok());
+                (ok, ok)
+            })
+            .unwrap();
+        assert!(!ok);
+        assert!(storage.get_context_items("sess_1", None, None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_includes_every_item() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_session("sess_1", "Test", None, Some("/p"), None, None, "a").unwrap();
+        storage
+            .atomically(|s| {
+                (0..1001).try_for_each(|i| {
+                    s.save_context_item(&format!("item_{i}"), "sess_1", &format!("k{i}"), "v", None, None, "a")
+                })
+            })
+            .unwrap();
+        let mut batch = Batch {
+            actor: "a".to_string(),
+            session_arg: None,
+            session: Some("sess_1".to_string()),
+            project_path: Some("/p".to_string()),
+            ids: Vec::new(),
+        };
+
+        let op = Op::Checkpoint { name: "all".to_string(), description: None };
+        let id = run_op(&mut storage, &mut batch, &op).unwrap().id.unwrap();
+        assert_eq!(storage.get_checkpoint(&id).unwrap().unwrap().item_count, 1001);
+    }
+}
@@ -0,0 +1,457 @@
+//! Full backup archives (`sc export archive`, `sc import archive`).
+//!
+//! For people who don't sync through git, one `.tar.zst` file moves
+//! everything to another machine:
+//!
+//! ```text
+//! manifest.json                  sc and schema versions, projects, files
+//! savecontext.db                 snapshot of the database
+//! config.json                    ~/.savecontext/config.json, if present
+//! projects/<nn>-<name>/*.jsonl   `sc sync export` of each project
+//! ```
+//!
+//! Restoring uses the snapshot, which keeps what JSONL doesn't carry
+//! (embeddings, audit history, local settings). The JSONL files are there
+//! to read, and to `sc sync import` into a database that already has
+//! other data.
+//!
+//! `sc import archive` only writes over a database that has data with
+//! `--force`, and keeps the old one next to it. An existing config file is
+//! kept unless `--force` too.
+
+use crate::cli::Database;
+use crate::config::global_savecontext_dir;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::SqliteStorage;
+use crate::sync::{format_size, Exporter, SyncError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the archive layout. Bumped when an older `sc` could no
+/// longer restore a new archive.
+const ARCHIVE_FORMAT: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const DATABASE: &str = "savecontext.db";
+const CONFIG: &str = "config.json";
+
+/// `manifest.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: u32,
+    /// Version of `sc` that wrote the archive.
+    sc_version: String,
+    /// Last migration applied to the snapshot.
+    schema_version: String,
+    /// When the archive was written (Unix ms).
+    created_at: i64,
+    projects: Vec<ArchiveProject>,
+    /// Every file in the archive besides the manifest.
+    files: Vec<String>,
+}
+
+/// A project in the archive.
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveProject {
+    path: String,
+    name: String,
+    /// Directory of its JSONL files (absent if it has no records).
+    dir: Option<String>,
+    /// Records in those files.
+    records: usize,
+}
+
+/// Output for export archive.
+#[derive(Serialize)]
+struct ExportArchiveOutput {
+    archive: String,
+    size_bytes: u64,
+    manifest: Manifest,
+}
+
+/// Output for import archive.
+#[derive(Serialize)]
+struct ImportArchiveOutput {
+    archive: String,
+    db_path: String,
+    /// Where the database that was replaced was kept.
+    previous_db: Option<String>,
+    /// `restored`, `replaced`, `kept` (an existing config won), or `none`
+    /// (the archive has no config).
+    config: String,
+    sc_version: String,
+    schema_version: String,
+    projects: Vec<ArchiveProject>,
+    /// Project paths that don't exist on this machine.
+    missing_paths: Vec<String>,
+}
+
+json_schema!(ArchiveProject { path: String, name: String, dir: Option<String>, records: usize });
+json_schema!(Manifest {
+    format: u32,
+    sc_version: String,
+    schema_version: String,
+    created_at: i64,
+    projects: Vec<ArchiveProject>,
+    files: Vec<String>,
+});
+json_schema!(ExportArchiveOutput { archive: String, size_bytes: u64, manifest: Manifest });
+json_schema!(ImportArchiveOutput {
+    archive: String,
+    db_path: String,
+    previous_db: Option<String>,
+    config: String,
+    sc_version: String,
+    schema_version: String,
+    projects: Vec<ArchiveProject>,
+    missing_paths: Vec<String>,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("export archive", schema_for::<ExportArchiveOutput>()),
+        ("import archive", schema_for::<ImportArchiveOutput>()),
+    ]
+}
+
+/// Execute `sc export archive`: write the database, config and JSONL
+/// exports to `out` (`savecontext-<time>.tar.zst` by default).
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read or the archive written.
+pub fn export(out: Option<&Path>, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let out = out.map_or_else(
+        || PathBuf::from(format!("savecontext-{}.tar.zst", chrono::Local::now().format("%Y%m%d-%H%M%S"))),
+        Path::to_path_buf,
+    );
+    if crate::is_dry_run() {
+        println!("Would write archive: {}", out.display());
+        return Ok(());
+    }
+    export_to(&out, storage, global_savecontext_dir().as_deref(), json)
+}
+
+/// Write the archive, taking the config from `config_dir`.
+fn export_to(out: &Path, storage: &SqliteStorage, config_dir: Option<&Path>, json: bool) -> Result<()> {
+    let staging = Staging::new()?;
+    storage.backup_to(&staging.0.join(DATABASE))?;
+
+    // Exporting clears dirty flags, so it runs on a copy of the snapshot:
+    // the archived database keeps its unexported changes
+    let work_db = staging.0.join("work.db");
+    fs::copy(staging.0.join(DATABASE), &work_db)?;
+    let mut work = SqliteStorage::open(&work_db)?;
+    let mut files = vec![DATABASE.to_string()];
+    let mut projects = Vec::new();
+    for (index, path) in work.data_project_paths()?.into_iter().enumerate() {
+        let name = match work.get_project_by_path(&path)? {
+            Some(project) => project.name,
+            None => dir_name(&path),
+        };
+        let dir = format!("projects/{:02}-{}", index + 1, slug(&name));
+        let result = Exporter::with_output_dir(&mut work, path.clone(), staging.0.join(&dir)).with_events(true).export(true);
+        let (dir, records) = match result {
+            Ok(stats) => (Some(dir), stats.total()),
+            Err(SyncError::NothingToExport) => (None, 0),
+            Err(e) => return Err(Error::Other(e.to_string())),
+        };
+        if let Some(dir) = &dir {
+            files.extend(jsonl_files(&staging.0.join(dir))?.into_iter().map(|f| format!("{dir}/{f}")));
+        }
+        projects.push(ArchiveProject { path, name, dir, records });
+    }
+    drop(work);
+
+    let config = config_dir.map(|dir| dir.join(CONFIG)).filter(|p| p.exists());
+    if config.is_some() {
+        files.push(CONFIG.to_string());
+    }
+    let manifest = Manifest {
+        format: ARCHIVE_FORMAT,
+        sc_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::storage::migrations::versions().last().unwrap_or_default().to_string(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+        projects,
+        files,
+    };
+    write_archive(out, &manifest, &staging.0, config.as_deref())?;
+    let size_bytes = fs::metadata(out).map_or(0, |m| m.len());
+
+    if json {
+        let output = ExportArchiveOutput { archive: out.display().to_string(), size_bytes, manifest };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Archive written: {} ({})", out.display(), format_size(size_bytes));
+        println!("  Database snapshot (schema {})", manifest.schema_version);
+        for project in &manifest.projects {
+            println!("  {}: {} records", project.path, project.records);
+        }
+        if config.is_some() {
+            println!("  {CONFIG}");
+        }
+        println!("Restore with: sc import archive {}", out.display());
+    }
+    Ok(())
+}
+
+/// Write the manifest, then every file it lists, to a zstd-compressed tar.
+fn write_archive(out: &Path, manifest: &Manifest, staging: &Path, config: Option<&Path>) -> Result<()> {
+    let tmp = out.with_extension("zst.tmp");
+    let encoder = zstd::Encoder::new(fs::File::create(&tmp)?, 3)?;
+    let mut tar = tar::Builder::new(encoder);
+
+    let bytes = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(u64::try_from(manifest.created_at / 1000).unwrap_or(0));
+    header.set_cksum();
+    tar.append_data(&mut header, MANIFEST, bytes.as_slice())?;
+    for file in &manifest.files {
+        let source = match config {
+            Some(config) if file == CONFIG => config.to_path_buf(),
+            _ => staging.join(file),
+        };
+        tar.append_path_with_name(source, file)?;
+    }
+
+    tar.into_inner()?.finish()?;
+    fs::rename(&tmp, out)?;
+    Ok(())
+}
+
+/// Execute `sc import archive`: restore an archive onto this machine.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be read, was written by a newer
+/// `sc`, or the database has data and `force` is not set.
+pub fn import(file: &Path, force: bool, db: &mut Database, json: bool) -> Result<()> {
+    let db_path = db.require_path()?.to_path_buf();
+    // The restore replaces the file, so it must not be open
+    db.close();
+    import_into(file, force, &db_path, global_savecontext_dir().as_deref(), json)
+}
+
+/// Restore the archive onto `db_path`, and its config into `config_dir`.
+fn import_into(file: &Path, force: bool, db_path: &Path, config_dir: Option<&Path>, json: bool) -> Result<()> {
+    let staging = Staging::new()?;
+    let decoder = zstd::Decoder::new(fs::File::open(file)?)?;
+    tar::Archive::new(decoder)
+        .unpack(&staging.0)
+        .map_err(|e| Error::InvalidArgument(format!("{} is not a readable .tar.zst archive: {e}", file.display())))?;
+    let manifest: Manifest = fs::read(staging.0.join(MANIFEST))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| Error::InvalidArgument(format!("{} is not a SaveContext archive (no manifest)", file.display())))?;
+    check_compatible(&manifest)?;
+
+    let has_data = db_path.exists() && !SqliteStorage::open(db_path)?.data_project_paths()?.is_empty();
+    if has_data && !force {
+        return Err(Error::InvalidArgument(format!(
+            "{} already has data. Use --force to replace it (it is kept as {})",
+            db_path.display(),
+            kept_path(db_path).display()
+        )));
+    }
+    if crate::is_dry_run() {
+        println!("Would restore {} onto {}", file.display(), db_path.display());
+        return Ok(());
+    }
+
+    let previous_db = if has_data {
+        let kept = kept_path(db_path);
+        SqliteStorage::open(db_path)?.backup_to(&kept)?;
+        Some(kept)
+    } else {
+        None
+    };
+    for path in [db_path.to_path_buf(), sidecar(db_path, "-wal"), sidecar(db_path, "-shm")] {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    if let Some(parent) = db_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(staging.0.join(DATABASE), db_path)?;
+    // Opening applies any migrations newer than the snapshot
+    drop(SqliteStorage::open(db_path)?);
+
+    let config = restore_config(&staging.0.join(CONFIG), config_dir, force)?;
+    let missing_paths: Vec<String> =
+        manifest.projects.iter().map(|p| p.path.clone()).filter(|p| !Path::new(p).exists()).collect();
+
+    if json {
+        let output = ImportArchiveOutput {
+            archive: file.display().to_string(),
+            db_path: db_path.display().to_string(),
+            previous_db: previous_db.map(|p| p.display().to_string()),
+            config: config.to_string(),
+            sc_version: manifest.sc_version,
+            schema_version: manifest.schema_version,
+            projects: manifest.projects,
+            missing_paths,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Restored {} projects onto {}", manifest.projects.len(), db_path.display());
+        if let Some(previous) = &previous_db {
+            println!("  Previous database kept as {}", previous.display());
+        }
+        match config {
+            "restored" => println!("  Config restored"),
+            "replaced" => println!("  Config replaced (previous kept as {CONFIG}.before-restore)"),
+            "kept" => println!("  Existing config kept; the archive's is not applied without --force"),
+            _ => {}
+        }
+        for path in &missing_paths {
+            println!("  Not on this machine: {path}");
+        }
+        if !missing_paths.is_empty() {
+            println!("Point a project at its new location with: sc project move <old-path> <new-path>");
+        }
+    }
+    Ok(())
+}
+
+/// Refuse archives from a newer layout or schema than this `sc` knows.
+fn check_compatible(manifest: &Manifest) -> Result<()> {
+    if manifest.format > ARCHIVE_FORMAT {
+        return Err(Error::InvalidArgument(format!(
+            "Archive format {} is newer than this sc supports ({ARCHIVE_FORMAT}); upgrade sc to {} or later",
+            manifest.format, manifest.sc_version
+        )));
+    }
+    if !crate::storage::migrations::versions().any(|v| v == manifest.schema_version) {
+        return Err(Error::InvalidArgument(format!(
+            "Archive schema {} is unknown to this sc; upgrade sc to {} or later",
+            manifest.schema_version, manifest.sc_version
+        )));
+    }
+    Ok(())
+}
+
+/// Put the archive's config in place; returns what happened to it.
+fn restore_config(source: &Path, config_dir: Option<&Path>, force: bool) -> Result<&'static str> {
+    let Some(dest) = config_dir.map(|dir| dir.join(CONFIG)) else {
+        return Ok("none");
+    };
+    if !source.exists() {
+        return Ok("none");
+    }
+    let status = if !dest.exists() {
+        "restored"
+    } else if force {
+        fs::copy(&dest, sidecar(&dest, ".before-restore"))?;
+        "replaced"
+    } else {
+        return Ok("kept");
+    };
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(source, &dest)?;
+    Ok(status)
+}
+
+/// `<path><suffix>`, e.g. the `-wal` file of a database.
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Where a database replaced by a restore is kept.
+fn kept_path(db_path: &Path) -> PathBuf {
+    sidecar(db_path, ".before-restore")
+}
+
+/// Names of the JSONL files in `dir`, sorted.
+fn jsonl_files(dir: &Path) -> Result<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| Path::new(name).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl")))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Last component of a project path.
+fn dir_name(path: &str) -> String {
+    Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("project").to_string()
+}
+
+/// Lowercase letters, digits and dashes, for directory names.
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() { "project".to_string() } else { slug.to_string() }
+}
+
+/// A scratch directory, removed when dropped.
+struct Staging(PathBuf);
+
+impl Staging {
+    fn new() -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("sc-archive-{}", &uuid::Uuid::new_v4().to_string()[..8]));
+        fs::create_dir_all(&dir)?;
+        Ok(Self(dir))
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source.db");
+        let mut storage = SqliteStorage::open(&source).unwrap();
+        storage.create_session("sess_1", "Work", None, Some("/work/app"), None, None, "test").unwrap();
+
+        let config_dir = temp_dir.path().join("config");
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::write(config_dir.join(CONFIG), r#"{"sync_events": true}"#).unwrap();
+        let out = temp_dir.path().join("backup.tar.zst");
+        export_to(&out, &storage, Some(&config_dir), true).unwrap();
+
+        let restored = temp_dir.path().join("restored.db");
+        let new_home = temp_dir.path().join("new-home");
+        import_into(&out, false, &restored, Some(&new_home), true).unwrap();
+        assert!(new_home.join(CONFIG).exists());
+        let storage = SqliteStorage::open(&restored).unwrap();
+        assert_eq!(storage.get_session("sess_1").unwrap().unwrap().name, "Work");
+        // Exporting the JSONL didn't clear the snapshot's dirty flags
+        assert_eq!(storage.get_dirty_sessions_by_project("/work/app").unwrap().len(), 1);
+        drop(storage);
+
+        // Restoring over data needs --force, and keeps the old database
+        assert!(import_into(&out, false, &restored, Some(&new_home), true).is_err());
+        import_into(&out, true, &restored, Some(&new_home), true).unwrap();
+        assert!(kept_path(&restored).exists());
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("My App (v2)"), "my-app--v2");
+        assert_eq!(slug("***"), "project");
+    }
+}
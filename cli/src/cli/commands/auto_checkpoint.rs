@@ -0,0 +1,327 @@
+//! Scheduled checkpoints (`sc checkpoint auto`, `sc checkpoint prune`).
+//!
+//! With `auto_checkpoint.interval` set to `hourly` or `daily`, each active
+//! session that changed since its last automatic checkpoint gets one per
+//! period, named `auto-YYYYMMDD-HH` in local time (`HH` is `00` for daily).
+//! There is no background service to install: every `sc` command catches
+//! up lazily after it runs, and a marker file in `~/.savecontext/cache/`
+//! keeps that to one database check per period. `sc checkpoint auto
+//! --daemon` does the same on a timer for machines where `sc` runs rarely.
+//!
+//! Only the newest `auto_checkpoint.keep` (default 24) automatic
+//! checkpoints of each session are kept; checkpoints created by hand are
+//! never pruned.
+
+use crate::cli::Database;
+use crate::cli::commands::config::load_config;
+use crate::config::{default_actor, global_savecontext_dir};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::SqliteStorage;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Automatic checkpoints kept per session when not configured.
+const DEFAULT_KEEP: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interval {
+    Hourly,
+    Daily,
+}
+
+impl Interval {
+    fn parse(value: &str) -> Result<Option<Self>> {
+        match value {
+            "hourly" => Ok(Some(Self::Hourly)),
+            "daily" => Ok(Some(Self::Daily)),
+            "off" | "" => Ok(None),
+            other => Err(Error::InvalidArgument(format!(
+                "Unknown checkpoint interval '{other}'. Use hourly, daily or off"
+            ))),
+        }
+    }
+
+    /// Checkpoint name for the period containing `now`.
+    fn slot_name(self, now: DateTime<Local>) -> String {
+        match self {
+            Self::Hourly => now.format("auto-%Y%m%d-%H").to_string(),
+            Self::Daily => now.format("auto-%Y%m%d-00").to_string(),
+        }
+    }
+}
+
+/// The configured interval and retention.
+fn configured() -> (Option<Interval>, usize) {
+    let config = load_config().auto_checkpoint.unwrap_or_default();
+    let interval = config
+        .interval
+        .as_deref()
+        .and_then(|v| Interval::parse(v).ok().flatten());
+    (interval, config.keep.unwrap_or(DEFAULT_KEEP).max(1))
+}
+
+/// Result of one catch-up pass.
+#[derive(Debug, Default)]
+struct Run {
+    name: String,
+    created: Vec<Created>,
+    pruned: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Created {
+    id: String,
+    session_id: String,
+    item_count: usize,
+}
+
+/// Output for checkpoint auto; one per pass with `--daemon`.
+#[derive(Serialize)]
+struct AutoOutput<'a> {
+    /// Name of the period's checkpoints.
+    name: &'a str,
+    created: &'a [Created],
+    pruned: usize,
+    dry_run: bool,
+}
+
+/// Output for checkpoint prune.
+#[derive(Serialize)]
+struct PruneOutput<'a> {
+    keep: usize,
+    /// Names of the checkpoints deleted.
+    pruned: &'a [String],
+    count: usize,
+    dry_run: bool,
+}
+
+json_schema!(AutoOutput<'a> { name: &'a str, created: &'a [Created], pruned: usize, dry_run: bool });
+json_schema!(Created { id: String, session_id: String, item_count: usize });
+json_schema!(PruneOutput<'a> { keep: usize, pruned: &'a [String], count: usize, dry_run: bool });
+
+/// Schema of `sc checkpoint auto --json`.
+pub(crate) fn auto_schema() -> Value {
+    schema_for::<AutoOutput<'_>>()
+}
+
+/// Schema of `sc checkpoint prune --json`.
+pub(crate) fn prune_schema() -> Value {
+    schema_for::<PruneOutput<'_>>()
+}
+
+/// Create the period's checkpoint for each due session, then prune.
+fn run_once(storage: &mut SqliteStorage, interval: Interval, keep: usize, actor: &str) -> Result<Run> {
+    let name = interval.slot_name(Local::now());
+    let description = match interval {
+        Interval::Hourly => "Automatic hourly checkpoint",
+        Interval::Daily => "Automatic daily checkpoint",
+    };
+
+    let mut run = Run { name: name.clone(), ..Run::default() };
+    for session_id in storage.get_sessions_due_auto_checkpoint(&name)? {
+        let id = format!("ckpt_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+        let item_count = storage.create_snapshot_checkpoint(&id, &session_id, &name, Some(description), None, actor)?;
+        run.created.push(Created { id, session_id, item_count });
+    }
+    run.pruned = prune(storage, None, keep, actor)?.len();
+    Ok(run)
+}
+
+fn prune(storage: &mut SqliteStorage, session_id: Option<&str>, keep: usize, actor: &str) -> Result<Vec<String>> {
+    let prunable = storage.get_prunable_auto_checkpoints(session_id, keep)?;
+    let mut deleted = Vec::with_capacity(prunable.len());
+    for checkpoint in prunable {
+        storage.delete_checkpoint(&checkpoint.id, actor)?;
+        deleted.push(checkpoint.name);
+    }
+    Ok(deleted)
+}
+
+/// Lazy catch-up, run after every command.
+///
+/// Does nothing unless an interval is configured, and touches the database
+/// at most once per period per database. Failures are only logged: this
+/// must never break the command that triggered it.
+pub fn catch_up(db: &mut Database, actor: Option<&str>) {
+    if crate::config::is_test_mode() || crate::is_dry_run() {
+        return;
+    }
+    let (Some(interval), keep) = configured() else { return };
+    let Some(db_path) = db.path().filter(|p| p.exists()) else { return };
+
+    let name = interval.slot_name(Local::now());
+    let marker = global_savecontext_dir().map(|dir| dir.join("cache").join("auto-checkpoint"));
+    let stamp = format!("{name}\t{}", db_path.display());
+    if marker.as_deref().is_some_and(|m| marker_contains(m, &stamp)) {
+        return;
+    }
+
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    let result = db.storage().and_then(|storage| run_once(storage, interval, keep, &actor));
+    match result {
+        Ok(run) => {
+            tracing::debug!(name = %run.name, created = run.created.len(), pruned = run.pruned, "Automatic checkpoints");
+            if let Some(marker) = marker {
+                write_marker(&marker, &stamp);
+            }
+        }
+        Err(e) => tracing::debug!(error = %e, "Automatic checkpoints failed"),
+    }
+}
+
+/// Whether `stamp` (period and database) is already recorded as done.
+pub(crate) fn marker_contains(marker: &Path, stamp: &str) -> bool {
+    std::fs::read_to_string(marker).is_ok_and(|content| content.lines().any(|line| line == stamp))
+}
+
+/// Record that `stamp` (period and database) is done, keeping the stamps
+/// of other databases for the same period.
+pub(crate) fn write_marker(marker: &Path, stamp: &str) {
+    let period = stamp.split('\t').next().unwrap_or_default();
+    let mut lines: Vec<String> = std::fs::read_to_string(marker)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| line.split('\t').next() == Some(period))
+        .map(ToString::to_string)
+        .collect();
+    lines.push(stamp.to_string());
+    if let Some(parent) = marker.parent() {
+        let _ = std::fs::create_dir_all(parent).and_then(|()| std::fs::write(marker, lines.join("\n")));
+    }
+}
+
+/// Execute `sc checkpoint auto`.
+///
+/// # Errors
+///
+/// Returns an error if no interval is configured or given, the metrics
+/// address can't be listened on, or a checkpoint cannot be written.
+pub fn execute_auto(
+    interval: Option<&str>,
+    daemon: bool,
+    metrics: Option<&str>,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    let (configured_interval, keep) = configured();
+    let interval = match interval {
+        Some(value) => Interval::parse(value)?,
+        None => configured_interval,
+    }
+    .ok_or_else(|| {
+        Error::InvalidArgument(
+            "No checkpoint interval. Pass --interval hourly|daily or set auto_checkpoint.interval in config"
+                .to_string(),
+        )
+    })?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    if let Some(addr) = metrics {
+        crate::cli::metrics::serve(addr, db.require_path()?.to_path_buf())?;
+    }
+
+    loop {
+        let dry_run = crate::is_dry_run();
+        let run = crate::cli::metrics::timed("checkpoint auto", || {
+            let storage = db.storage()?;
+            storage.with_transaction(|s| {
+                let result = run_once(s, interval, keep, &actor);
+                let commit = result.is_ok() && !dry_run;
+                (result, commit)
+            })?
+        })?;
+        print_run(&run, dry_run, json)?;
+
+        if !daemon {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(60));
+    }
+}
+
+fn print_run(run: &Run, dry_run: bool, json: bool) -> Result<()> {
+    if json {
+        let output = AutoOutput { name: &run.name, created: &run.created, pruned: run.pruned, dry_run };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    if run.created.is_empty() && run.pruned == 0 {
+        if !crate::is_silent() {
+            println!("{}: nothing to do", run.name);
+        }
+        return Ok(());
+    }
+    let verb = if dry_run { "Would create" } else { "Created" };
+    for created in &run.created {
+        println!("{verb} {} for {} ({} items)", run.name, created.session_id, created.item_count);
+    }
+    if run.pruned > 0 {
+        let verb = if dry_run { "Would prune" } else { "Pruned" };
+        println!("{verb} {} old automatic checkpoints", run.pruned);
+    }
+    Ok(())
+}
+
+/// Execute `sc checkpoint prune`.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or a checkpoint
+/// cannot be deleted.
+pub fn execute_prune(
+    keep: Option<usize>,
+    session_id: Option<&str>,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+    let keep = keep.unwrap_or_else(|| configured().1).max(1);
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+
+    let storage = db.storage()?;
+    let dry_run = crate::is_dry_run();
+    let deleted = storage.with_transaction(|s| {
+        let result = prune(s, session_id, keep, &actor);
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+
+    if json {
+        let output = PruneOutput { keep, pruned: &deleted, count: deleted.len(), dry_run };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if deleted.is_empty() {
+        println!("No automatic checkpoints beyond the newest {keep} per session.");
+    } else {
+        let verb = if dry_run { "Would prune" } else { "Pruned" };
+        println!("{verb} {} automatic checkpoints (keeping {keep} per session):", deleted.len());
+        for name in &deleted {
+            println!("  {name}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_slot_names() {
+        let at = Local.with_ymd_and_hms(2026, 3, 7, 14, 35, 0).unwrap();
+        assert_eq!(Interval::Hourly.slot_name(at), "auto-20260307-14");
+        assert_eq!(Interval::Daily.slot_name(at), "auto-20260307-00");
+        assert_eq!(Interval::parse("off").unwrap(), None);
+        assert!(Interval::parse("weekly").is_err());
+    }
+}
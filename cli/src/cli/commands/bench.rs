@@ -0,0 +1,205 @@
+//! Performance benchmarks (`sc bench ...`).
+//!
+//! `sc bench startup` times the fixed cost every command pays before doing
+//! its own work: resolving the database, opening it, resolving the session
+//! and the project, plus whole `sc` processes for a trivial and a typical
+//! command. Output is stable JSON with `--json`, and `--max-ms` turns it
+//! into a pass/fail check for CI.
+
+use crate::cli::BenchCommands;
+use crate::config::{
+    discover_project_savecontext_dir, resolve_db_path, resolve_project_path, resolve_session_id,
+};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::schema::apply_schema_full;
+use crate::storage::SqliteStorage;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+/// Timings for one startup phase, in milliseconds.
+#[derive(Debug, Serialize)]
+struct Phase {
+    name: &'static str,
+    description: &'static str,
+    min_ms: f64,
+    median_ms: f64,
+    p95_ms: f64,
+    max_ms: f64,
+}
+
+#[derive(Serialize)]
+struct StartupOutput {
+    iterations: usize,
+    database: String,
+    phases: Vec<Phase>,
+    max_ms: Option<f64>,
+    passed: bool,
+}
+
+json_schema!(Phase {
+    name: &'static str,
+    description: &'static str,
+    min_ms: f64,
+    median_ms: f64,
+    p95_ms: f64,
+    max_ms: f64,
+});
+json_schema!(StartupOutput { iterations: usize, database: String, phases: Vec<Phase>, max_ms: Option<f64>, passed: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("bench startup", schema_for::<StartupOutput>())]
+}
+
+/// Execute a bench subcommand.
+///
+/// # Errors
+///
+/// Returns an error if the database is missing, a phase fails, or the
+/// `sc status` median exceeds `--max-ms`.
+pub fn execute(command: &BenchCommands, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
+    match command {
+        BenchCommands::Startup { iterations, max_ms } => {
+            startup(db_path, (*iterations).max(1), *max_ms, json)
+        }
+    }
+}
+
+fn startup(db_path: Option<&PathBuf>, iterations: usize, max_ms: Option<f64>, json: bool) -> Result<()> {
+    let db_path = resolve_db_path(db_path.map(PathBuf::as_path)).ok_or(Error::NotInitialized)?;
+    if !db_path.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    let phases = vec![
+        measure("db_path", "Resolve the database path (env, config file)", iterations, || {
+            let _ = resolve_db_path(None);
+            Ok(())
+        })?,
+        measure("open", "Open the database, schema fast path", iterations, || {
+            SqliteStorage::open(&db_path).map(drop)
+        })?,
+        measure("schema_full", "Re-run the full schema and migration check", iterations, || {
+            let conn = rusqlite::Connection::open(&db_path)?;
+            apply_schema_full(&conn)?;
+            Ok(())
+        })?,
+        measure("session", "Resolve the session from the terminal cache", iterations, || {
+            let _ = resolve_session_id(None);
+            Ok(())
+        })?,
+        measure("project", "Discover the project directory and match it", iterations, || {
+            let _ = discover_project_savecontext_dir();
+            let storage = SqliteStorage::open(&db_path)?;
+            let _ = resolve_project_path(&storage, None);
+            Ok(())
+        })?,
+        measure("process_version", "Whole `sc version` process", iterations, || {
+            run_sc(&db_path, &["version"])
+        })?,
+        measure("process_status", "Whole `sc status` process", iterations, || {
+            run_sc(&db_path, &["status"])
+        })?,
+    ];
+
+    let status_median = phases.last().map_or(0.0, |p| p.median_ms);
+    let passed = max_ms.is_none_or(|limit| status_median <= limit);
+
+    if json {
+        let output = StartupOutput {
+            iterations,
+            database: db_path.display().to_string(),
+            phases,
+            max_ms,
+            passed,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Startup benchmark ({iterations} iterations, {})", db_path.display());
+        println!();
+        println!("  {:<16} {:>8} {:>8} {:>8} {:>8}", "phase", "min", "median", "p95", "max");
+        for phase in &phases {
+            println!(
+                "  {:<16} {:>8.2} {:>8.2} {:>8.2} {:>8.2}  {}",
+                phase.name, phase.min_ms, phase.median_ms, phase.p95_ms, phase.max_ms, phase.description
+            );
+        }
+        println!();
+        println!("All times in ms.");
+    }
+
+    if passed {
+        Ok(())
+    } else {
+        Err(Error::Other(format!(
+            "Startup too slow: sc status median {status_median:.2}ms exceeds --max-ms {}",
+            max_ms.unwrap_or_default()
+        )))
+    }
+}
+
+/// Run `f` once to warm caches, then `iterations` times under the clock.
+fn measure(
+    name: &'static str,
+    description: &'static str,
+    iterations: usize,
+    mut f: impl FnMut() -> Result<()>,
+) -> Result<Phase> {
+    f()?;
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let started = Instant::now();
+        f()?;
+        // Microsecond resolution is plenty and keeps the JSON readable
+        samples.push((started.elapsed().as_secs_f64() * 1_000_000.0).round() / 1000.0);
+    }
+    samples.sort_by(f64::total_cmp);
+    Ok(Phase {
+        name,
+        description,
+        min_ms: samples[0],
+        median_ms: percentile(&samples, 50),
+        p95_ms: percentile(&samples, 95),
+        max_ms: samples[samples.len() - 1],
+    })
+}
+
+/// Nearest-rank percentile of sorted, non-empty samples.
+fn percentile(sorted: &[f64], pct: usize) -> f64 {
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Run this binary as a subprocess, with usage stats off so the benchmark
+/// doesn't fill `sc stats`. A failing command (e.g. no session) still counts.
+fn run_sc(db_path: &Path, args: &[&str]) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    Command::new(exe)
+        .arg("--db")
+        .arg(db_path)
+        .arg("--json")
+        .args(args)
+        .env("SC_USAGE_STATS", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert!((percentile(&samples, 50) - 10.0).abs() < f64::EPSILON);
+        assert!((percentile(&samples, 95) - 19.0).abs() < f64::EPSILON);
+        assert!((percentile(&[4.0], 95) - 4.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,291 @@
+//! Capabilities command implementation.
+//!
+//! Orchestrators that drive `sc` need to know what the installed version
+//! supports: which subcommands and flags exist, which values an enum flag
+//! takes, and which schema the database will be migrated to. Parsing
+//! `--help` for that breaks whenever the wording changes, so this command
+//! reports it as data. Commands and flags are read from the clap
+//! definitions, enum values from the model types.
+
+use crate::cli::Cli;
+use crate::embeddings::SearchMode;
+use crate::error::{ErrorCode, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{
+    ActorType, IssueStatus, IssueType, ItemCategory, ItemPriority, MemoryCategory, PlanStatus, SessionStatus,
+    TimeEntryStatus,
+};
+use clap::{Arg, ArgAction, Command, CommandFactory};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Optional components built into this binary. Local embeddings are
+/// always compiled in, and there is no storage backend besides the
+/// embedded database; `otel` is listed when the binary was built with
+/// `--features otel`.
+const FEATURES: &[&str] = &[
+    "local-embeddings",
+    #[cfg(feature = "otel")]
+    "otel",
+];
+
+/// Output for capabilities.
+#[derive(Serialize)]
+struct CapabilitiesOutput {
+    version: String,
+    schema: SchemaInfo,
+    features: Vec<String>,
+    /// Valid values by kind, e.g. `item_category`.
+    enums: BTreeMap<String, Vec<String>>,
+    global_flags: Vec<FlagInfo>,
+    commands: Vec<CommandInfo>,
+}
+
+/// Database schema this binary migrates to.
+#[derive(Serialize)]
+struct SchemaInfo {
+    /// Name of the newest migration.
+    migration: String,
+    migrations: usize,
+    /// Changes whenever the schema or migration list changes.
+    fingerprint: i32,
+}
+
+#[derive(Serialize)]
+struct CommandInfo {
+    /// Full path without `sc`, e.g. `issue create`.
+    name: String,
+    about: Option<String>,
+    aliases: Vec<String>,
+    /// Whether the command only groups subcommands.
+    has_subcommands: bool,
+    args: Vec<ArgInfo>,
+    flags: Vec<FlagInfo>,
+}
+
+/// A positional argument.
+#[derive(Serialize)]
+struct ArgInfo {
+    name: String,
+    help: Option<String>,
+    required: bool,
+    multiple: bool,
+}
+
+/// An option or switch.
+#[derive(Serialize)]
+struct FlagInfo {
+    long: Option<String>,
+    short: Option<String>,
+    help: Option<String>,
+    takes_value: bool,
+    required: bool,
+    multiple: bool,
+    /// Accepted values, when clap restricts them.
+    values: Vec<String>,
+    default: Option<String>,
+    env: Option<String>,
+}
+
+json_schema!(CapabilitiesOutput {
+    version: String,
+    schema: SchemaInfo,
+    features: Vec<String>,
+    enums: BTreeMap<String, Vec<String>>,
+    global_flags: Vec<FlagInfo>,
+    commands: Vec<CommandInfo>,
+});
+json_schema!(SchemaInfo { migration: String, migrations: usize, fingerprint: i32 });
+json_schema!(CommandInfo {
+    name: String,
+    about: Option<String>,
+    aliases: Vec<String>,
+    has_subcommands: bool,
+    args: Vec<ArgInfo>,
+    flags: Vec<FlagInfo>,
+});
+json_schema!(ArgInfo { name: String, help: Option<String>, required: bool, multiple: bool });
+json_schema!(FlagInfo {
+    long: Option<String>,
+    short: Option<String>,
+    help: Option<String>,
+    takes_value: bool,
+    required: bool,
+    multiple: bool,
+    values: Vec<String>,
+    default: Option<String>,
+    env: Option<String>,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("capabilities", schema_for::<CapabilitiesOutput>())]
+}
+
+/// Execute the capabilities command.
+///
+/// # Errors
+///
+/// Returns an error if JSON serialization fails.
+pub fn execute(json: bool) -> Result<()> {
+    let output = capabilities();
+
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    println!("sc {}", output.version);
+    println!(
+        "Schema: {} ({} migrations)",
+        output.schema.migration, output.schema.migrations
+    );
+    println!("Features: {}", output.features.join(", "));
+    println!("Commands: {}", output.commands.len());
+    for (kind, values) in &output.enums {
+        println!("  {kind}: {}", values.join(", "));
+    }
+    println!("\nFull manifest: sc capabilities --json");
+    Ok(())
+}
+
+fn capabilities() -> CapabilitiesOutput {
+    let root = Cli::command();
+    let migrations: Vec<&str> = crate::storage::migrations::versions().collect();
+
+    let mut commands = Vec::new();
+    for sub in visible_subcommands(&root) {
+        collect_commands(sub, "", &mut commands);
+    }
+
+    CapabilitiesOutput {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        schema: SchemaInfo {
+            migration: migrations.last().copied().unwrap_or_default().to_string(),
+            migrations: migrations.len(),
+            fingerprint: crate::storage::schema::schema_fingerprint(),
+        },
+        features: FEATURES.iter().map(ToString::to_string).collect(),
+        enums: enums(),
+        global_flags: root.get_arguments().filter(|a| !a.is_positional() && !a.is_hide_set()).map(flag_info).collect(),
+        commands,
+    }
+}
+
+fn enums() -> BTreeMap<String, Vec<String>> {
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    BTreeMap::from([
+        ("session_status".to_string(), names(SessionStatus::NAMES)),
+        ("item_category".to_string(), names(ItemCategory::NAMES)),
+        ("item_priority".to_string(), names(ItemPriority::NAMES)),
+        ("memory_category".to_string(), names(MemoryCategory::NAMES)),
+        ("issue_type".to_string(), names(IssueType::NAMES)),
+        ("time_entry_status".to_string(), names(TimeEntryStatus::NAMES)),
+        ("actor_type".to_string(), names(ActorType::NAMES)),
+        // Built-in statuses; a workflow can add its own.
+        ("issue_status".to_string(), IssueStatus::BUILT_IN.iter().map(ToString::to_string).collect()),
+        (
+            "plan_status".to_string(),
+            [PlanStatus::Draft, PlanStatus::Active, PlanStatus::Completed]
+                .iter()
+                .map(|s| s.as_str().to_string())
+                .collect(),
+        ),
+        ("search_mode".to_string(), SearchMode::ALL.iter().map(ToString::to_string).collect()),
+        ("error_code".to_string(), ErrorCode::ALL.iter().map(|c| c.as_str().to_string()).collect()),
+    ])
+}
+
+fn visible_subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands().filter(|c| !c.is_hide_set())
+}
+
+/// Add `cmd` and everything under it, depth first.
+fn collect_commands(cmd: &Command, parent: &str, out: &mut Vec<CommandInfo>) {
+    let name = if parent.is_empty() {
+        cmd.get_name().to_string()
+    } else {
+        format!("{parent} {}", cmd.get_name())
+    };
+    let visible = cmd.get_arguments().filter(|a| !a.is_hide_set());
+    let (args, flags): (Vec<&Arg>, Vec<&Arg>) = visible.partition(|a| a.is_positional());
+
+    out.push(CommandInfo {
+        name: name.clone(),
+        about: cmd.get_about().map(ToString::to_string),
+        aliases: cmd.get_visible_aliases().map(ToString::to_string).collect(),
+        has_subcommands: cmd.has_subcommands(),
+        args: args
+            .into_iter()
+            .map(|a| ArgInfo {
+                name: a.get_id().to_string(),
+                help: a.get_help().map(ToString::to_string),
+                required: a.is_required_set(),
+                multiple: is_multiple(a),
+            })
+            .collect(),
+        flags: flags.into_iter().map(flag_info).collect(),
+    });
+
+    for sub in visible_subcommands(cmd) {
+        collect_commands(sub, &name, out);
+    }
+}
+
+fn flag_info(arg: &Arg) -> FlagInfo {
+    let takes_value = arg.get_action().takes_values();
+    FlagInfo {
+        long: arg.get_long().map(|l| format!("--{l}")),
+        short: arg.get_short().map(|s| format!("-{s}")),
+        help: arg.get_help().map(ToString::to_string),
+        takes_value,
+        required: arg.is_required_set(),
+        multiple: is_multiple(arg),
+        values: if takes_value {
+            arg.get_possible_values().iter().filter(|v| !v.is_hide_set()).map(|v| v.get_name().to_string()).collect()
+        } else {
+            Vec::new()
+        },
+        default: arg
+            .get_default_values()
+            .first()
+            .map(|v| v.to_string_lossy().into_owned())
+            .filter(|_| takes_value),
+        env: arg.get_env().map(|e| e.to_string_lossy().into_owned()),
+    }
+}
+
+/// Whether the argument can be given several times or as a list.
+fn is_multiple(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::Append | ArgAction::Count)
+        || arg.get_value_delimiter().is_some()
+        || arg.get_num_args().is_some_and(|n| n.max_values() > 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_describes_commands_and_enums() {
+        let output = capabilities();
+        let create = output.commands.iter().find(|c| c.name == "issue create").unwrap();
+        assert_eq!(create.args[0].name, "title");
+        let priority = create.flags.iter().find(|f| f.long.as_deref() == Some("--priority")).unwrap();
+        assert!(priority.takes_value);
+        assert!(output.commands.iter().any(|c| c.name == "issue" && c.has_subcommands));
+        assert!(output.global_flags.iter().any(|f| f.long.as_deref() == Some("--json")));
+        assert_eq!(output.enums["item_category"], ["reminder", "decision", "progress", "note"]);
+        assert_eq!(output.schema.migrations, crate::storage::migrations::versions().count());
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_manifest_lists_otel_feature() {
+        assert!(capabilities().features.iter().any(|f| f == "otel"));
+    }
+}
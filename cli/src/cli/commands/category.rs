@@ -0,0 +1,254 @@
+//! Category command implementations (`sc category`).
+//!
+//! Context items have one of four built-in categories unless the project
+//! registers more. `sc category add` registers a category, or changes the
+//! settings of an existing one, built-ins included: its description,
+//! terminal color, the priority items get when saved without one, how
+//! `sc prime` treats it, and whether its values are stored encrypted.
+//! Saving an item in a category the project doesn't know fails with the
+//! list of ones it does.
+
+use crate::cli::{CategoryCommands, Database};
+use crate::core::SaveContext;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{Categories, Category, ItemCategory};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Output for category add.
+#[derive(Serialize)]
+struct CategoryAddOutput {
+    category: Category,
+    /// False when an existing category's settings were changed.
+    created: bool,
+}
+
+/// Output for category list.
+#[derive(Serialize)]
+struct CategoryListOutput {
+    categories: Vec<Category>,
+    count: usize,
+}
+
+/// Output for category rename.
+#[derive(Serialize)]
+struct CategoryRenameOutput {
+    old: ItemCategory,
+    new: ItemCategory,
+    /// Context items moved to the new name.
+    items_moved: usize,
+}
+
+/// Output for category remove.
+#[derive(Serialize)]
+struct CategoryRemoveOutput {
+    name: ItemCategory,
+    removed: bool,
+}
+
+json_schema!(CategoryAddOutput { category: Category, created: bool });
+json_schema!(CategoryListOutput { categories: Vec<Category>, count: usize });
+json_schema!(CategoryRenameOutput { old: ItemCategory, new: ItemCategory, items_moved: usize });
+json_schema!(CategoryRemoveOutput { name: ItemCategory, removed: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("category add", schema_for::<CategoryAddOutput>()),
+        ("category list", schema_for::<CategoryListOutput>()),
+        ("category rename", schema_for::<CategoryRenameOutput>()),
+        ("category remove", schema_for::<CategoryRemoveOutput>()),
+    ]
+}
+
+/// Execute category commands.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened, a name or setting is
+/// invalid, or a rename or removal isn't possible.
+pub fn execute(command: &CategoryCommands, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let mut sc = SaveContext::discover_in(db.storage()?, actor)?;
+
+    match command {
+        CategoryCommands::Add { name, description, color, priority, prime, weight, encrypt } => {
+            let name: ItemCategory = name.parse()?;
+            let existing = sc.categories()?.get(&name).cloned();
+            let created = existing.is_none();
+            let mut category = existing.unwrap_or_else(|| Category::custom(name));
+            if let Some(description) = description {
+                category.description = Some(description.clone()).filter(|d| !d.trim().is_empty());
+            }
+            if let Some(color) = color {
+                category.color = Some(color.trim().to_lowercase()).filter(|c| !c.is_empty() && c != "none");
+            }
+            if let Some(priority) = priority {
+                category.default_priority = priority.parse()?;
+            }
+            if let Some(prime) = prime {
+                category.in_prime = parse_prime_rule(prime)?;
+            }
+            if let Some(weight) = weight {
+                category.prime_weight = *weight;
+            }
+            if let Some(encrypt) = encrypt {
+                category.encrypted = parse_switch("encryption", encrypt)?;
+            }
+            add(&mut sc, category, created, json)
+        }
+        CategoryCommands::List => list(&sc, json),
+        CategoryCommands::Rename { old, new } => rename(&mut sc, &old.parse()?, &new.parse()?, json),
+        CategoryCommands::Remove { name } => remove(&mut sc, &name.parse()?, json),
+    }
+}
+
+fn parse_prime_rule(rule: &str) -> Result<bool> {
+    match rule.trim().to_lowercase().as_str() {
+        "include" => Ok(true),
+        "exclude" => Ok(false),
+        _ => Err(Error::InvalidArgument(format!("Invalid prime rule '{rule}'. Valid values: include, exclude"))),
+    }
+}
+
+fn parse_switch(setting: &str, value: &str) -> Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(Error::InvalidArgument(format!("Invalid {setting} '{value}'. Valid values: on, off"))),
+    }
+}
+
+fn add(sc: &mut SaveContext, category: Category, created: bool, json: bool) -> Result<()> {
+    category.validate()?;
+    if crate::is_dry_run() {
+        if json {
+            let output = serde_json::json!({ "dry_run": true, "category": category, "created": created });
+            println!("{output}");
+        } else {
+            let verb = if created { "add" } else { "update" };
+            println!("Would {verb} category: {}", describe(&category));
+        }
+        return Ok(());
+    }
+
+    sc.save_category(&category)?;
+
+    if crate::is_silent() {
+        println!("{}", category.name);
+    } else if json {
+        println!("{}", serde_json::to_string(&CategoryAddOutput { category, created })?);
+    } else {
+        let verb = if created { "Added" } else { "Updated" };
+        println!("{verb} category: {}", describe(&category));
+    }
+    Ok(())
+}
+
+fn list(sc: &SaveContext, json: bool) -> Result<()> {
+    let categories = sc.categories()?.0;
+
+    if json {
+        let output = CategoryListOutput { count: categories.len(), categories };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    println!("Categories ({}):", categories.len());
+    for category in &categories {
+        println!("  {}", describe(category));
+        if let Some(description) = &category.description {
+            println!("      {}", description.dimmed());
+        }
+    }
+    Ok(())
+}
+
+fn rename(sc: &mut SaveContext, old: &ItemCategory, new: &ItemCategory, json: bool) -> Result<()> {
+    let dry_run = crate::is_dry_run();
+    let (project_path, actor) = (sc.project_path().to_string(), sc.actor().to_string());
+    let items_moved = sc.storage_mut().with_transaction(|storage| {
+        let result = storage.rename_category(&project_path, old, new, &actor);
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+
+    if crate::is_silent() {
+        println!("{new}");
+    } else if json {
+        let output = CategoryRenameOutput { old: old.clone(), new: new.clone(), items_moved };
+        if dry_run {
+            let mut preview = serde_json::to_value(&output)?;
+            preview["dry_run"] = true.into();
+            println!("{preview}");
+        } else {
+            println!("{}", serde_json::to_string(&output)?);
+        }
+    } else if dry_run {
+        println!("Would rename category {old} -> {new} ({items_moved} item(s))");
+    } else {
+        println!("Renamed category {old} -> {new} ({items_moved} item(s) moved)");
+    }
+    Ok(())
+}
+
+fn remove(sc: &mut SaveContext, name: &ItemCategory, json: bool) -> Result<()> {
+    let dry_run = crate::is_dry_run();
+    let (project_path, actor) = (sc.project_path().to_string(), sc.actor().to_string());
+    let removed = sc.storage_mut().with_transaction(|storage| {
+        let result = storage.delete_category(&project_path, name, &actor);
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+
+    if crate::is_silent() {
+        return Ok(());
+    }
+    if json {
+        let output = CategoryRemoveOutput { name: name.clone(), removed };
+        if dry_run {
+            let mut preview = serde_json::to_value(&output)?;
+            preview["dry_run"] = true.into();
+            println!("{preview}");
+        } else {
+            println!("{}", serde_json::to_string(&output)?);
+        }
+        return Ok(());
+    }
+
+    if !removed {
+        println!("No settings registered for category {name}");
+    } else if name.is_built_in() {
+        println!("{} category {name} to its defaults", if dry_run { "Would reset" } else { "Reset" });
+    } else {
+        println!("{} category {name}", if dry_run { "Would remove" } else { "Removed" });
+    }
+    Ok(())
+}
+
+/// One line: name (in its color), default priority, prime settings.
+fn describe(category: &Category) -> String {
+    let name = format!("{:<12}", category.name.as_str());
+    let name = match &category.color {
+        Some(color) => name.color(color.as_str()).to_string(),
+        None => name,
+    };
+    let prime = if category.in_prime {
+        format!("prime x{}", category.prime_weight)
+    } else {
+        "not in prime".to_string()
+    };
+    let encrypted = if category.encrypted { "  encrypted" } else { "" };
+    let kind = if category.built_in { "  (built-in)" } else { "" };
+    format!("{name} {:<6}  {prime}{encrypted}{kind}", category.default_priority.as_str())
+}
+
+/// A category label for terminal output, in the category's color if it
+/// has one.
+pub(crate) fn label(categories: &Categories, category: &ItemCategory) -> String {
+    match categories.color(category) {
+        Some(color) => category.as_str().color(color).to_string(),
+        None => category.to_string(),
+    }
+}
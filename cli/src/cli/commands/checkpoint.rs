@@ -1,14 +1,15 @@
 //! Checkpoint command implementations.
 
-use crate::cli::CheckpointCommands;
+use crate::cli::{CheckpointCommands, Database};
 use crate::config::{
-    current_git_branch, default_actor, resolve_db_path, resolve_session_id,
+    current_git_branch, default_actor, resolve_session_id,
     resolve_session_or_suggest,
 };
 use crate::error::{Error, Result};
-use crate::storage::SqliteStorage;
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::{Checkpoint, ContextItem};
 use serde::Serialize;
-use std::path::PathBuf;
+use serde_json::Value;
 
 /// Output for checkpoint create.
 #[derive(Serialize)]
@@ -35,10 +36,85 @@ struct CheckpointInfo {
     created_at: i64,
 }
 
+/// Output for checkpoint restore.
+#[derive(Serialize)]
+struct CheckpointRestoreOutput {
+    id: String,
+    name: String,
+    restored: bool,
+    item_count: usize,
+    target_session_id: String,
+}
+
+/// Output for checkpoint delete.
+#[derive(Serialize)]
+struct CheckpointDeleteOutput<'a> {
+    id: &'a str,
+    deleted: bool,
+}
+
+/// Output for checkpoint add-items.
+#[derive(Serialize)]
+struct ItemsAddedOutput<'a> {
+    checkpoint_id: &'a str,
+    checkpoint_name: String,
+    keys_requested: usize,
+    items_added: usize,
+}
+
+/// Output for checkpoint remove-items.
+#[derive(Serialize)]
+struct ItemsRemovedOutput<'a> {
+    checkpoint_id: &'a str,
+    checkpoint_name: String,
+    keys_requested: usize,
+    items_removed: usize,
+}
+
+/// Output for checkpoint items.
+#[derive(Serialize)]
+struct CheckpointItemsOutput<'a> {
+    checkpoint_id: &'a str,
+    checkpoint_name: String,
+    count: usize,
+    items: Vec<ContextItem>,
+}
+
+json_schema!(CheckpointRestoreOutput { id: String, name: String, restored: bool, item_count: usize, target_session_id: String });
+json_schema!(CheckpointDeleteOutput<'a> { id: &'a str, deleted: bool });
+json_schema!(ItemsAddedOutput<'a> { checkpoint_id: &'a str, checkpoint_name: String, keys_requested: usize, items_added: usize });
+json_schema!(ItemsRemovedOutput<'a> { checkpoint_id: &'a str, checkpoint_name: String, keys_requested: usize, items_removed: usize });
+json_schema!(CheckpointItemsOutput<'a> { checkpoint_id: &'a str, checkpoint_name: String, count: usize, items: Vec<ContextItem> });
+json_schema!(CheckpointCreateOutput { id: String, name: String, session_id: String, item_count: usize });
+json_schema!(CheckpointListOutput { checkpoints: Vec<CheckpointInfo>, count: usize });
+json_schema!(CheckpointInfo {
+    id: String,
+    name: String,
+    description: Option<String>,
+    item_count: i64,
+    created_at: i64,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("checkpoint create", schema_for::<CheckpointCreateOutput>()),
+        ("checkpoint list", schema_for::<CheckpointListOutput>()),
+        ("checkpoint show", schema_for::<Checkpoint>()),
+        ("checkpoint restore", schema_for::<CheckpointRestoreOutput>()),
+        ("checkpoint delete", schema_for::<CheckpointDeleteOutput<'_>>()),
+        ("checkpoint add-items", schema_for::<ItemsAddedOutput<'_>>()),
+        ("checkpoint remove-items", schema_for::<ItemsRemovedOutput<'_>>()),
+        ("checkpoint items", schema_for::<CheckpointItemsOutput<'_>>()),
+        ("checkpoint auto", super::auto_checkpoint::auto_schema()),
+        ("checkpoint prune", super::auto_checkpoint::prune_schema()),
+    ]
+}
+
 /// Execute checkpoint commands.
 pub fn execute(
     command: &CheckpointCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
@@ -48,7 +124,7 @@ pub fn execute(
             name,
             description,
             include_git,
-        } => create(name, description.as_deref(), *include_git, db_path, actor, session_id, json),
+        } => create(name, description.as_deref(), *include_git, db, actor, session_id, json),
         CheckpointCommands::List {
             search,
             session,
@@ -63,23 +139,34 @@ pub fn execute(
             *all_projects,
             *limit,
             *offset,
-            db_path,
+            db,
             json,
         ),
-        CheckpointCommands::Show { id } => show(id, db_path, json),
+        CheckpointCommands::Show { id } => show(id, db, json),
         CheckpointCommands::Restore { id, categories, tags } => restore(
             id,
             categories.as_ref().map(|v| v.as_slice()),
             tags.as_ref().map(|v| v.as_slice()),
-            db_path,
+            db,
             actor,
             session_id,
             json,
         ),
-        CheckpointCommands::Delete { id } => delete(id, db_path, actor, json),
-        CheckpointCommands::AddItems { id, keys } => add_items(id, keys, db_path, actor, session_id, json),
-        CheckpointCommands::RemoveItems { id, keys } => remove_items(id, keys, db_path, actor, json),
-        CheckpointCommands::Items { id } => items(id, db_path, json),
+        CheckpointCommands::Delete { id } => delete(id, db, actor, json),
+        CheckpointCommands::AddItems { id, keys } => add_items(id, keys, db, actor, session_id, json),
+        CheckpointCommands::RemoveItems { id, keys } => remove_items(id, keys, db, actor, json),
+        CheckpointCommands::Items { id } => items(id, db, json),
+        CheckpointCommands::Auto { interval, daemon, metrics } => super::auto_checkpoint::execute_auto(
+            interval.as_deref(),
+            *daemon,
+            metrics.as_deref(),
+            db,
+            actor,
+            json,
+        ),
+        CheckpointCommands::Prune { keep, session } => {
+            super::auto_checkpoint::execute_prune(*keep, session.as_deref(), db, actor, json)
+        }
     }
 }
 
@@ -87,22 +174,15 @@ fn create(
     name: &str,
     description: Option<&str>,
     include_git: bool,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
-    let sid = resolve_session_or_suggest(session_id, &storage)?;
+    let sid = resolve_session_or_suggest(session_id, storage)?;
     let session = storage
         .get_session(&sid)?
         .ok_or_else(|| Error::SessionNotFound { id: sid })?;
@@ -124,22 +204,24 @@ fn create(
     let id = format!("ckpt_{}", &uuid::Uuid::new_v4().to_string()[..12]);
 
     // Get current context items to include
-    let items = storage.get_context_items(&session.id, None, None, Some(1000))?;
-
-    storage.create_checkpoint(
-        &id,
-        &session.id,
-        name,
-        description,
-        git_status.as_deref(),
-        git_branch.as_deref(),
-        &actor,
-    )?;
+    let items = storage.get_context_items(&session.id, None, None, Some(u32::MAX))?;
 
-    // Add items to checkpoint
-    for item in &items {
-        storage.add_checkpoint_item(&id, &item.id, &actor)?;
-    }
+    // A checkpoint missing some of its items would restore the wrong state
+    storage.atomically(|storage| {
+        storage.create_checkpoint(
+            &id,
+            &session.id,
+            name,
+            description,
+            git_status.as_deref(),
+            git_branch.as_deref(),
+            &actor,
+        )?;
+        for item in &items {
+            storage.add_checkpoint_item(&id, &item.id, &actor)?;
+        }
+        Ok(())
+    })?;
 
     if crate::is_silent() {
         println!("{id}");
@@ -172,17 +254,10 @@ fn list(
     all_projects: bool,
     limit: usize,
     offset: Option<usize>,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
 
     // Determine session filter
     let resolved_session_id = if let Some(sid) = session_id {
@@ -270,15 +345,8 @@ fn list(
     Ok(())
 }
 
-fn show(id: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
+fn show(id: &str, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
 
     let checkpoint = storage
         .get_checkpoint(id)?
@@ -322,19 +390,12 @@ fn restore(
     id: &str,
     categories: Option<&[String]>,
     tags: Option<&[String]>,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Get checkpoint to verify it exists
@@ -354,7 +415,7 @@ fn restore(
         })?;
 
     // Determine target session via TTY-keyed status cache
-    let target_session_id = resolve_session_or_suggest(session_id, &storage)?;
+    let target_session_id = resolve_session_or_suggest(session_id, storage)?;
 
     // Restore items from checkpoint to target session
     let restored_count = storage.restore_checkpoint(
@@ -366,14 +427,14 @@ fn restore(
     )?;
 
     if json {
-        let output = serde_json::json!({
-            "id": checkpoint.id,
-            "name": checkpoint.name,
-            "restored": true,
-            "item_count": restored_count,
-            "target_session_id": target_session_id
-        });
-        println!("{output}");
+        let output = CheckpointRestoreOutput {
+            id: checkpoint.id,
+            name: checkpoint.name,
+            restored: true,
+            item_count: restored_count,
+            target_session_id,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Restored checkpoint: {}", checkpoint.name);
         println!("  Items restored: {restored_count}");
@@ -383,25 +444,15 @@ fn restore(
     Ok(())
 }
 
-fn delete(id: &str, db_path: Option<&PathBuf>, actor: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+fn delete(id: &str, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     storage.delete_checkpoint(id, &actor)?;
 
     if json {
-        let output = serde_json::json!({
-            "id": id,
-            "deleted": true
-        });
-        println!("{output}");
+        let output = CheckpointDeleteOutput { id, deleted: true };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Deleted checkpoint: {id}");
     }
@@ -422,22 +473,15 @@ fn get_git_status() -> Option<String> {
 fn add_items(
     id: &str,
     keys: &[String],
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
-    let sid = resolve_session_or_suggest(session_id, &storage)?;
+    let sid = resolve_session_or_suggest(session_id, storage)?;
     let session = storage
         .get_session(&sid)?
         .ok_or_else(|| Error::SessionNotFound { id: sid })?;
@@ -461,13 +505,13 @@ fn add_items(
     let added = storage.add_checkpoint_items_by_keys(id, &session.id, keys, &actor)?;
 
     if json {
-        let output = serde_json::json!({
-            "checkpoint_id": id,
-            "checkpoint_name": checkpoint.name,
-            "keys_requested": keys.len(),
-            "items_added": added
-        });
-        println!("{output}");
+        let output = ItemsAddedOutput {
+            checkpoint_id: id,
+            checkpoint_name: checkpoint.name,
+            keys_requested: keys.len(),
+            items_added: added,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Added {} items to checkpoint: {}", added, checkpoint.name);
         if added < keys.len() {
@@ -481,18 +525,11 @@ fn add_items(
 fn remove_items(
     id: &str,
     keys: &[String],
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Verify checkpoint exists
@@ -514,13 +551,13 @@ fn remove_items(
     let removed = storage.remove_checkpoint_items_by_keys(id, keys, &actor)?;
 
     if json {
-        let output = serde_json::json!({
-            "checkpoint_id": id,
-            "checkpoint_name": checkpoint.name,
-            "keys_requested": keys.len(),
-            "items_removed": removed
-        });
-        println!("{output}");
+        let output = ItemsRemovedOutput {
+            checkpoint_id: id,
+            checkpoint_name: checkpoint.name,
+            keys_requested: keys.len(),
+            items_removed: removed,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Removed {} items from checkpoint: {}", removed, checkpoint.name);
         if removed < keys.len() {
@@ -531,15 +568,8 @@ fn remove_items(
     Ok(())
 }
 
-fn items(id: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
+fn items(id: &str, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
 
     // Verify checkpoint exists
     let checkpoint = storage
@@ -560,12 +590,7 @@ fn items(id: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     let items = storage.get_checkpoint_items(id)?;
 
     if json {
-        let output = serde_json::json!({
-            "checkpoint_id": id,
-            "checkpoint_name": checkpoint.name,
-            "count": items.len(),
-            "items": items
-        });
+        let output = CheckpointItemsOutput { checkpoint_id: id, checkpoint_name: checkpoint.name, count: items.len(), items };
         println!("{}", serde_json::to_string(&output)?);
     } else if items.is_empty() {
         println!("Checkpoint '{}' has no items.", checkpoint.name);
@@ -2,14 +2,32 @@
 //!
 //! Prepares context for compaction by creating an auto-checkpoint
 //! and returning a summary of critical context items.
+//!
+//! `sc compaction check` estimates how full the model's context is from a
+//! Claude Code transcript and compares it with the compaction threshold
+//! (the same `SAVECONTEXT_COMPACTION_THRESHOLD` / `compaction.threshold`
+//! the MCP server uses), reporting through its exit code.
 
+use crate::cli::{CompactionCommands, Database};
+use crate::cli::commands::config::load_config;
 use crate::config::{
-    current_git_branch, default_actor, resolve_db_path, resolve_session_or_suggest,
+    current_git_branch, current_project_path, default_actor, resolve_session_or_suggest,
 };
 use crate::error::{Error, Result};
-use crate::storage::SqliteStorage;
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemCategory, ItemPriority};
 use serde::Serialize;
-use std::path::PathBuf;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Threshold bounds and default (matching MCP server)
+const THRESHOLD_DEFAULT: u8 = 70;
+const THRESHOLD_RANGE: std::ops::RangeInclusive<u8> = 50..=90;
+
+/// Warn this many percentage points below the threshold.
+const WARN_MARGIN: u8 = 10;
+
+const DEFAULT_CONTEXT_WINDOW: u64 = 200_000;
 
 /// Limits for compaction context items (matching MCP server)
 const HIGH_PRIORITY_LIMIT: u32 = 50;
@@ -61,8 +79,8 @@ struct CriticalContext {
 struct ContextSummary {
     key: String,
     value: String,
-    category: String,
-    priority: String,
+    category: ItemCategory,
+    priority: ItemPriority,
 }
 
 #[derive(Serialize)]
@@ -73,18 +91,54 @@ struct RestoreInstructions {
     summary: String,
 }
 
-/// Execute compaction command.
-pub fn execute(db_path: Option<&PathBuf>, actor: Option<&str>, session_id: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
+json_schema!(CompactionOutput {
+    checkpoint: CheckpointInfo,
+    stats: CompactionStats,
+    git_context: Option<GitContext>,
+    critical_context: CriticalContext,
+    restore_instructions: RestoreInstructions,
+});
+json_schema!(CheckpointInfo { id: String, name: String, session_id: String, created_at: i64 });
+json_schema!(CompactionStats {
+    total_items_saved: i64,
+    critical_items: usize,
+    pending_tasks: usize,
+    decisions_made: usize,
+});
+json_schema!(GitContext { branch: String, files: Vec<String> });
+json_schema!(CriticalContext {
+    high_priority_items: Vec<ContextSummary>,
+    next_steps: Vec<ContextSummary>,
+    key_decisions: Vec<ContextSummary>,
+    recent_progress: Vec<ContextSummary>,
+});
+json_schema!(ContextSummary { key: String, value: String, category: ItemCategory, priority: ItemPriority });
+json_schema!(RestoreInstructions { tool: String, checkpoint_id: String, message: String, summary: String });
+json_schema!(CheckOutput {
+    transcript: String,
+    tokens: u64,
+    method: &'static str,
+    context_window: u64,
+    percent: u8,
+    threshold: u8,
+    warn_at: u8,
+    status: &'static str,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("compaction", schema_for::<CompactionOutput>()),
+        ("compaction check", schema_for::<CheckOutput>()),
+    ]
+}
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+/// Execute compaction command.
+pub fn execute(db: &mut Database, actor: Option<&str>, session_id: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
-    let sid = resolve_session_or_suggest(session_id, &storage)?;
+    let sid = resolve_session_or_suggest(session_id, storage)?;
     let session = storage
         .get_session(&sid)?
         .ok_or_else(|| Error::SessionNotFound { id: sid })?;
@@ -112,23 +166,23 @@ pub fn execute(db_path: Option<&PathBuf>, actor: Option<&str>, session_id: Optio
     )?;
 
     // Get current context items to include in checkpoint
-    let all_items = storage.get_context_items(&session.id, None, None, Some(1000))?;
+    let all_items = storage.get_context_items(&session.id, None, None, Some(u32::MAX))?;
     for item in &all_items {
         storage.add_checkpoint_item(&checkpoint_id, &item.id, &actor)?;
     }
 
     // Analyze critical context
     let high_priority_items =
-        storage.get_context_items(&session.id, None, Some("high"), Some(HIGH_PRIORITY_LIMIT))?;
+        storage.get_context_items(&session.id, None, Some(ItemPriority::High), Some(HIGH_PRIORITY_LIMIT))?;
 
     let reminders =
-        storage.get_context_items(&session.id, Some("reminder"), None, Some(REMINDER_LIMIT))?;
+        storage.get_context_items(&session.id, Some(ItemCategory::Reminder), None, Some(REMINDER_LIMIT))?;
 
     let decisions =
-        storage.get_context_items(&session.id, Some("decision"), None, Some(DECISION_LIMIT))?;
+        storage.get_context_items(&session.id, Some(ItemCategory::Decision), None, Some(DECISION_LIMIT))?;
 
     let progress =
-        storage.get_context_items(&session.id, Some("progress"), None, Some(PROGRESS_LIMIT))?;
+        storage.get_context_items(&session.id, Some(ItemCategory::Progress), None, Some(PROGRESS_LIMIT))?;
 
     // Identify unfinished reminders (next steps)
     let next_steps: Vec<_> = reminders
@@ -186,7 +240,7 @@ pub fn execute(db_path: Option<&PathBuf>, actor: Option<&str>, session_id: Optio
                         key: i.key.clone(),
                         value: i.value.clone(),
                         category: i.category.clone(),
-                        priority: i.priority.clone(),
+                        priority: i.priority,
                     })
                     .collect(),
                 next_steps: next_steps
@@ -195,7 +249,7 @@ pub fn execute(db_path: Option<&PathBuf>, actor: Option<&str>, session_id: Optio
                         key: t.key.clone(),
                         value: t.value.clone(),
                         category: t.category.clone(),
-                        priority: t.priority.clone(),
+                        priority: t.priority,
                     })
                     .collect(),
                 key_decisions: decisions
@@ -205,7 +259,7 @@ pub fn execute(db_path: Option<&PathBuf>, actor: Option<&str>, session_id: Optio
                         key: d.key.clone(),
                         value: d.value.clone(),
                         category: d.category.clone(),
-                        priority: d.priority.clone(),
+                        priority: d.priority,
                     })
                     .collect(),
                 recent_progress: progress
@@ -215,7 +269,7 @@ pub fn execute(db_path: Option<&PathBuf>, actor: Option<&str>, session_id: Optio
                         key: p.key.clone(),
                         value: p.value.clone(),
                         category: p.category.clone(),
-                        priority: p.priority.clone(),
+                        priority: p.priority,
                     })
                     .collect(),
             },
@@ -288,6 +342,159 @@ pub fn execute(db_path: Option<&PathBuf>, actor: Option<&str>, session_id: Optio
     Ok(())
 }
 
+/// Execute a compaction subcommand.
+///
+/// # Errors
+///
+/// `check` returns `CompactionSuggested` or `CompactionRequired` (exit 12
+/// or 13) after printing its report when context is near or past the
+/// threshold, or an error if the transcript cannot be read.
+pub fn execute_command(command: &CompactionCommands, json: bool) -> Result<()> {
+    match command {
+        CompactionCommands::Check {
+            transcript,
+            threshold,
+            context_window,
+        } => check(transcript.as_deref(), *threshold, *context_window, json),
+    }
+}
+
+#[derive(Serialize)]
+struct CheckOutput {
+    transcript: String,
+    /// Estimated tokens in context
+    tokens: u64,
+    /// `usage` (from the last model turn) or `size` (bytes / 4)
+    method: &'static str,
+    context_window: u64,
+    percent: u8,
+    threshold: u8,
+    warn_at: u8,
+    /// `ok`, `warn` or `compact`
+    status: &'static str,
+}
+
+fn check(transcript: Option<&Path>, threshold: Option<u8>, context_window: Option<u64>, json: bool) -> Result<()> {
+    let config = load_config().compaction.unwrap_or_default();
+    let threshold = threshold
+        .or_else(|| {
+            std::env::var("SAVECONTEXT_COMPACTION_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+        })
+        .or(config.threshold)
+        .filter(|t| THRESHOLD_RANGE.contains(t))
+        .unwrap_or(THRESHOLD_DEFAULT);
+    let context_window = context_window
+        .or(config.context_window)
+        .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+        .max(1);
+
+    let transcript = match transcript {
+        Some(path) => path.to_path_buf(),
+        None => latest_transcript().ok_or_else(|| {
+            Error::InvalidArgument(
+                "No Claude Code transcript found for this project; pass --transcript <path>".to_string(),
+            )
+        })?,
+    };
+    let content = std::fs::read_to_string(&transcript).map_err(|e| {
+        Error::InvalidArgument(format!("Cannot read transcript {}: {e}", transcript.display()))
+    })?;
+
+    let (tokens, method) = estimate_tokens(&content);
+    let percent = u8::try_from((tokens.saturating_mul(100) / context_window).min(100)).unwrap_or(100);
+    let warn_at = threshold.saturating_sub(WARN_MARGIN);
+    let status = if percent >= threshold {
+        "compact"
+    } else if percent >= warn_at {
+        "warn"
+    } else {
+        "ok"
+    };
+
+    let output = CheckOutput {
+        transcript: transcript.display().to_string(),
+        tokens,
+        method,
+        context_window,
+        percent,
+        threshold,
+        warn_at,
+        status,
+    };
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else if crate::is_silent() {
+        println!("{percent}");
+    } else {
+        println!(
+            "Context: ~{tokens} / {context_window} tokens ({percent}%), threshold {threshold}%: {status}"
+        );
+    }
+
+    match status {
+        "compact" => Err(Error::CompactionRequired { percent, threshold }),
+        "warn" => Err(Error::CompactionSuggested { percent, threshold }),
+        _ => Ok(()),
+    }
+}
+
+/// Newest transcript in Claude Code's directory for this project.
+fn latest_transcript() -> Option<PathBuf> {
+    let project = current_project_path().or_else(|| std::env::current_dir().ok())?;
+    let dir = super::prime::claude_transcript_dir(&project.to_string_lossy())?;
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let modified = path.metadata().ok()?.modified().ok()?;
+            (path.extension().and_then(|e| e.to_str()) == Some("jsonl")).then_some((path, modified))
+        })
+        .max_by_key(|(_, modified)| *modified)
+        .map(|(path, _)| path)
+}
+
+/// Estimate tokens in context from a transcript.
+///
+/// The last main-thread model turn's `usage` is the context size as the
+/// API counted it (input, cache reads and writes, plus output). Without
+/// one since the last compaction, fall back to the transcript bytes
+/// after that compaction divided by four.
+fn estimate_tokens(content: &str) -> (u64, &'static str) {
+    let mut bytes_since_compaction: u64 = 0;
+    for line in content.lines().rev() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let compact_boundary = entry["type"] == "summary"
+            || entry["subtype"] == "compact_boundary"
+            || entry["isCompactSummary"] == true;
+        if compact_boundary {
+            break;
+        }
+        bytes_since_compaction += line.len() as u64;
+        if entry["isSidechain"] == true {
+            continue;
+        }
+
+        let usage = &entry["message"]["usage"];
+        if usage.is_object() {
+            let tokens = [
+                "input_tokens",
+                "cache_creation_input_tokens",
+                "cache_read_input_tokens",
+                "output_tokens",
+            ]
+            .iter()
+            .filter_map(|field| usage[*field].as_u64())
+            .sum();
+            return (tokens, "usage");
+        }
+    }
+    (bytes_since_compaction / 4, "size")
+}
+
 /// Get current git status output.
 fn get_git_status() -> Option<String> {
     std::process::Command::new("git")
@@ -306,3 +513,32 @@ fn truncate(s: &str, max_len: usize) -> String {
         format!("{}...", &s[..max_len.saturating_sub(3)])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens_from_usage() {
+        let transcript = [
+            r#"{"type":"user","message":{"content":"hi"}}"#,
+            r#"{"type":"assistant","message":{"usage":{"input_tokens":10,"cache_read_input_tokens":90000,"cache_creation_input_tokens":500,"output_tokens":200}}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"usage":{"input_tokens":5}}}"#,
+            r#"{"type":"user","message":{"content":"next"}}"#,
+        ]
+        .join("\n");
+        assert_eq!(estimate_tokens(&transcript), (90_710, "usage"));
+    }
+
+    #[test]
+    fn test_estimate_tokens_after_compaction() {
+        let after = r#"{"type":"user","message":{"content":"continue please"}}"#;
+        let transcript = [
+            r#"{"type":"assistant","message":{"usage":{"input_tokens":150000}}}"#,
+            r#"{"type":"system","subtype":"compact_boundary"}"#,
+            after,
+        ]
+        .join("\n");
+        assert_eq!(estimate_tokens(&transcript), (after.len() as u64 / 4, "size"));
+    }
+}
@@ -1,22 +1,286 @@
 //! Shell completions command implementation.
+//!
+//! Completion scripts come from clap. The zsh and fish scripts are extended
+//! to complete session IDs, issue short IDs and memory keys, which they get
+//! by running the hidden `sc complete-values <kind>` at completion time.
+//!
+//! `sc completions man` renders man pages from the same command definitions
+//! with `clap_mangen`: `sc.1` for the top level and `sc-<command>.1` for
+//! every subcommand.
 
-use crate::cli::{Cli, Shell};
-use crate::error::Result;
-use clap::CommandFactory;
+use crate::cli::{Cli, CompletionKind, Database, Shell};
+use crate::core::{IssueFilter, SaveContext};
+use crate::error::{Error, Result};
+use clap::{Command, CommandFactory};
 use clap_complete::{generate, shells};
-use std::io;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::Path;
 
-/// Generate shell completions for the specified shell.
-pub fn execute(shell: &Shell) -> Result<()> {
+/// Commands whose positional arguments take values from the database.
+const DYNAMIC: &[(&str, CompletionKind)] = &[
+    ("session resume", CompletionKind::Sessions),
+    ("session switch", CompletionKind::Sessions),
+    ("session delete", CompletionKind::Sessions),
+    ("issue show", CompletionKind::Issues),
+    ("issue update", CompletionKind::Issues),
+    ("issue complete", CompletionKind::Issues),
+    ("issue claim", CompletionKind::Issues),
+    ("issue release", CompletionKind::Issues),
+    ("issue watch", CompletionKind::Issues),
+    ("issue comment", CompletionKind::Issues),
+    ("issue delete", CompletionKind::Issues),
+    ("issue clone", CompletionKind::Issues),
+    ("issue duplicate", CompletionKind::Issues),
+    ("issue label add", CompletionKind::Issues),
+    ("issue label remove", CompletionKind::Issues),
+    ("issue dep add", CompletionKind::Issues),
+    ("issue dep remove", CompletionKind::Issues),
+    ("issue dep tree", CompletionKind::Issues),
+    ("memory get", CompletionKind::MemoryKeys),
+    ("memory save", CompletionKind::MemoryKeys),
+    ("memory delete", CompletionKind::MemoryKeys),
+];
+
+/// Generate shell completions for the specified shell, or man pages.
+///
+/// # Errors
+///
+/// Returns an error if `out` is given for a shell, or if the output cannot
+/// be written.
+pub fn execute(shell: &Shell, out: Option<&Path>) -> Result<()> {
     let mut cmd = Cli::command();
+    if out.is_some() && !matches!(shell, Shell::Man) {
+        return Err(Error::InvalidArgument("--out only applies to `sc completions man`".to_string()));
+    }
 
+    let mut buf = Vec::new();
     match shell {
-        Shell::Bash => generate(shells::Bash, &mut cmd, "sc", &mut io::stdout()),
-        Shell::Zsh => generate(shells::Zsh, &mut cmd, "sc", &mut io::stdout()),
-        Shell::Fish => generate(shells::Fish, &mut cmd, "sc", &mut io::stdout()),
-        Shell::PowerShell => generate(shells::PowerShell, &mut cmd, "sc", &mut io::stdout()),
-        Shell::Elvish => generate(shells::Elvish, &mut cmd, "sc", &mut io::stdout()),
+        Shell::Bash => generate(shells::Bash, &mut cmd, "sc", &mut buf),
+        Shell::Zsh => {
+            generate(shells::Zsh, &mut cmd, "sc", &mut buf);
+            buf = zsh_dynamic(&String::from_utf8_lossy(&buf)).into_bytes();
+        }
+        Shell::Fish => {
+            generate(shells::Fish, &mut cmd, "sc", &mut buf);
+            buf.extend(fish_dynamic().into_bytes());
+        }
+        Shell::PowerShell => generate(shells::PowerShell, &mut cmd, "sc", &mut buf),
+        Shell::Elvish => generate(shells::Elvish, &mut cmd, "sc", &mut buf),
+        Shell::Man => {
+            let pages = man_pages(cmd)?;
+            let Some(dir) = out else {
+                buf = pages.into_iter().next().map(|(_, page)| page).unwrap_or_default();
+                return write_stdout(&buf);
+            };
+            std::fs::create_dir_all(dir)?;
+            for (name, page) in &pages {
+                std::fs::write(dir.join(name), page)?;
+            }
+            println!("Wrote {} man pages to {}", pages.len(), dir.display());
+            return Ok(());
+        }
+    }
+
+    write_stdout(&buf)
+}
+
+/// Write to stdout, treating a closed pipe (`sc completions zsh | head`) as
+/// success.
+fn write_stdout(buf: &[u8]) -> Result<()> {
+    match io::stdout().lock().write_all(buf) {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Print completion values for `kind`, one `value<TAB>description` per line.
+///
+/// Shells call this on every completion, so when there is no database or
+/// project it prints nothing instead of failing.
+///
+/// # Errors
+///
+/// Never fails; the signature matches the other commands.
+pub fn complete(kind: CompletionKind, db: &mut Database) -> Result<()> {
+    let Some(sc) = db.existing().and_then(|storage| SaveContext::discover_in(storage, None).ok()) else {
+        return Ok(());
+    };
+    let values: Vec<(String, String)> = match kind {
+        CompletionKind::Sessions => sc
+            .list_sessions(None, Some(50))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| (s.id, format!("{} ({})", s.name, s.status)))
+            .collect(),
+        CompletionKind::Issues => sc
+            .list_issues(&IssueFilter { limit: Some(200), ..IssueFilter::default() })
+            .unwrap_or_default()
+            .into_iter()
+            .map(|i| (i.short_id.unwrap_or(i.id), i.title))
+            .collect(),
+        CompletionKind::MemoryKeys => sc
+            .list_memory(None)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| (m.key, m.value))
+            .collect(),
+    };
+
+    let mut out = io::stdout().lock();
+    for (value, description) in values {
+        if writeln!(out, "{value}\t{}", one_line(&description, 60)).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// `text` on one line, cut to `max` characters.
+fn one_line(text: &str, max: usize) -> String {
+    let flat: String = text.chars().map(|c| if c.is_whitespace() { ' ' } else { c }).collect();
+    if flat.chars().count() > max {
+        format!("{}...", flat.chars().take(max - 3).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+fn kind_name(kind: CompletionKind) -> &'static str {
+    match kind {
+        CompletionKind::Sessions => "sessions",
+        CompletionKind::Issues => "issues",
+        CompletionKind::MemoryKeys => "memory-keys",
+    }
+}
+
+/// Wrap clap's `_sc` in `_sc_dynamic`, which completes database values
+/// after the commands in [`DYNAMIC`] and defers to `_sc` otherwise.
+fn zsh_dynamic(script: &str) -> String {
+    let mut cases = String::new();
+    for (path, kind) in DYNAMIC {
+        let depth = path.split(' ').count();
+        let _ = writeln!(cases, "        \"{path} \"*) kind={} depth={depth} ;;", kind_name(*kind));
     }
+    let wrapper = format!(
+        r#"
+# Values from the database for IDs and keys, via `sc complete-values`.
+_sc_dynamic() {{
+    local kind depth line
+    local -a values
+    case "${{words[2,4]}} " in
+{cases}    esac
+    if [[ -n $kind ]] && (( CURRENT > depth + 1 )) && [[ $PREFIX != -* && ${{words[CURRENT-1]}} != -* ]]; then
+        for line in "${{(@f)$(sc complete-values $kind 2>/dev/null)}}"; do
+            [[ -n $line ]] || continue
+            values+=("${{${{line%%$'\t'*}}//:/\\:}}:${{line#*$'\t'}}")
+        done
+        (( $#values )) && _describe -t values "$kind" values && return
+    fi
+    _sc "$@"
+}}
 
+# Autoloading this file as `_sc` replaces that function with clap's, so
+# register the wrapper in both cases.
+compdef _sc_dynamic sc
+if [ "$funcstack[1]" = "_sc" ]; then
+    _sc_dynamic "$@"
+fi
+"#
+    );
+
+    let dispatch = script.rfind("if [ \"$funcstack[1]\" = \"_sc\" ]").unwrap_or(script.len());
+    format!("{}{}", script[..dispatch].trim_end(), wrapper)
+}
+
+/// `complete` lines for the commands in [`DYNAMIC`], grouped by parent.
+fn fish_dynamic() -> String {
+    let mut groups: Vec<(&str, CompletionKind, Vec<&str>)> = Vec::new();
+    for (path, kind) in DYNAMIC {
+        let (parent, name) = path.rsplit_once(' ').unwrap_or(("", path));
+        match groups.iter_mut().find(|(p, k, _)| *p == parent && kind_name(*k) == kind_name(*kind)) {
+            Some((_, _, names)) => names.push(name),
+            None => groups.push((parent, *kind, vec![name])),
+        }
+    }
+
+    let mut script = String::from("\n# Values from the database for IDs and keys, via `sc complete-values`.\n");
+    for (parent, kind, names) in groups {
+        let mut words = parent.split(' ');
+        let mut condition = format!("__fish_sc_using_subcommand {}", words.next().unwrap_or_default());
+        for word in words {
+            let _ = write!(condition, "; and __fish_seen_subcommand_from {word}");
+        }
+        let _ = writeln!(
+            script,
+            "complete -c sc -n \"{condition}; and __fish_seen_subcommand_from {}\" -f -a \"(sc complete-values {})\"",
+            names.join(" "),
+            kind_name(kind)
+        );
+    }
+    script
+}
+
+/// Man pages for `cmd` and its visible subcommands as `(file name, troff)`,
+/// starting with `sc.1`.
+fn man_pages(cmd: Command) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut cmd = cmd.disable_help_subcommand(true);
+    // Names subcommands `sc-issue-create` and gives them `sc issue create`
+    // synopses
+    cmd.build();
+    let mut pages = Vec::new();
+    add_man_pages(&cmd, &mut pages)?;
+    Ok(pages)
+}
+
+fn add_man_pages(cmd: &Command, pages: &mut Vec<(String, Vec<u8>)>) -> Result<()> {
+    let man = clap_mangen::Man::new(cmd.clone())
+        .source(format!("sc {}", env!("CARGO_PKG_VERSION")))
+        .manual("SaveContext Manual");
+    let mut page = Vec::new();
+    man.render(&mut page)?;
+    pages.push((man.get_filename(), page));
+
+    for sub in cmd.get_subcommands().filter(|c| !c.is_hide_set()) {
+        add_man_pages(sub, pages)?;
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    fn find<'a>(cmd: &'a Command, path: &str) -> Option<&'a Command> {
+        path.split(' ').try_fold(cmd, |cmd, name| cmd.find_subcommand(name))
+    }
+
+    #[test]
+    fn test_dynamic_commands_take_positional_values() {
+        let cmd = Cli::command();
+        for (path, _) in DYNAMIC {
+            let sub = find(&cmd, path).unwrap_or_else(|| panic!("no command `{path}`"));
+            assert!(sub.get_arguments().any(Arg::is_positional), "`{path}` has no positional argument");
+        }
+        generate(shells::Bash, &mut Cli::command(), "sc", &mut Vec::new());
+        let zsh = zsh_dynamic("_sc() {}\nif [ \"$funcstack[1]\" = \"_sc\" ]; then\n    _sc \"$@\"\nfi\n");
+        assert!(zsh.contains("\"issue label add \"*) kind=issues depth=3 ;;"));
+        assert!(zsh.ends_with("    _sc_dynamic \"$@\"\nfi\n") && !zsh.contains("compdef _sc sc"));
+        assert!(fish_dynamic().contains(
+            "__fish_sc_using_subcommand issue; and __fish_seen_subcommand_from dep; and __fish_seen_subcommand_from add remove tree\""
+        ));
+    }
+
+    #[test]
+    fn test_man_pages_cover_subcommands() {
+        let pages = man_pages(Cli::command()).unwrap();
+        assert_eq!(pages[0].0, "sc.1");
+        assert!(!pages.iter().any(|(name, _)| name == "sc-help.1" || name == "sc-complete-values.1"));
+        let (_, create) = pages.iter().find(|(name, _)| name == "sc-issue-create.1").unwrap();
+        let create = String::from_utf8_lossy(create);
+        assert!(create.contains(".TH sc-issue-create 1"));
+        assert!(create.contains("\\fBsc issue create\\fR"));
+        assert!(create.contains("\\fB\\-\\-priority\\fR \\fI<PRIORITY>\\fR"));
+    }
+}
@@ -1,13 +1,25 @@
 //! Configuration management commands.
 //!
 //! Manages SaveContext settings including remote host configuration
-//! stored at `~/.savecontext/config.json`.
+//! stored at `~/.savecontext/config.json`, and validates the file against
+//! the schema in [`crate::config::schema`].
 
+use crate::cli::command_log::CommandLogConfig;
 use crate::cli::{ConfigCommands, ConfigRemoteCommands};
+use crate::config::schema::{self, ConfigIssue, Severity};
+use crate::embeddings::EmbeddingSettings;
 use crate::error::{Error, Result};
+use crate::import::ImportConfig;
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{
+    IssueWorkflow, PermissionsConfig, Policy, PolicyConfig, RelevanceConfig, Routing, RoutingConfig, WorkflowConfig,
+};
+use crate::telemetry::OtelConfig;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // ── Types ────────────────────────────────────────────────────
 
@@ -16,8 +28,95 @@ use std::path::PathBuf;
 pub struct SaveContextConfig {
     #[serde(default)]
     pub version: u32,
+    /// Default actor name (overridden by `--actor` and `SC_ACTOR`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// Database location (overridden by `--db` and `SAVECONTEXT_DB`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_path: Option<String>,
+    /// Record local command statistics for `sc stats` (default: true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_stats: Option<bool>,
+    /// Warn about SQL statements slower than this (default: 500, 0 = off).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_query_ms: Option<u64>,
+    /// Embedding provider settings (shared with the MCP server).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<EmbeddingSettings>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub remote: Option<RemoteConfig>,
+    /// Issue status workflow overrides (global and per project).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<WorkflowConfig>,
+    /// How ready issues are dealt out to agents (global and per project).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<RoutingConfig>,
+    /// Limits on the issues one actor may hold (global and per project).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PolicyConfig>,
+    /// Context compaction threshold and mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction: Option<CompactionConfig>,
+    /// Outbound HTTP hooks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Named setting bundles, keyed by profile name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    /// Mapping rules for `sc issue import`, per format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import: Option<ImportConfig>,
+    /// Days deleted items stay in `sc trash` (default: 30, 0 = forever).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_retention_days: Option<u64>,
+    /// Scheduled checkpoints of active sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_checkpoint: Option<AutoCheckpointConfig>,
+    /// Resume or start a session when a command needs one and none is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_session: Option<bool>,
+    /// Pause active sessions idle this many hours (default: 0, off).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_pause_hours: Option<u64>,
+    /// Store context item values over this many bytes compressed
+    /// (default: 8192, 0 = never).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_compression_threshold: Option<u64>,
+    /// Audit event retention for `sc events prune`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_retention: Option<EventRetentionConfig>,
+    /// Export audit events to `events.jsonl` (default: false).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_events: Option<bool>,
+    /// Chat completions endpoint for `sc report standup --polish` and
+    /// `session_summary: "llm"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summarize: Option<SummarizeConfig>,
+    /// How `sc session end` summarizes and names a session: `heuristic`
+    /// (default), `llm` or `off`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_summary: Option<SessionSummaryMode>,
+    /// Roles limiting which operations each actor may perform.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PermissionsConfig>,
+    /// How item reads weigh in smart prime, search and `sc get --sort relevance`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<RelevanceConfig>,
+    /// Handling of stored text in `sc prime` output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prime: Option<PrimeConfig>,
+    /// Merge strategies for `sc sync import`, by entity type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_strategies: Option<BTreeMap<String, String>>,
+    /// Rotating log of the commands run, for debugging.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_log: Option<CommandLogConfig>,
+    /// OpenTelemetry collector for command spans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otel: Option<OtelConfig>,
+    /// Keys not modelled above, kept so rewriting the file doesn't drop them.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Remote host configuration for SSH proxy and sync.
@@ -37,6 +136,219 @@ pub struct RemoteConfig {
     pub remote_db_path: Option<String>,
 }
 
+/// Compaction settings, mirroring the MCP server's
+/// `SAVECONTEXT_COMPACTION_THRESHOLD` / `SAVECONTEXT_COMPACTION_MODE`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CompactionConfig {
+    /// Context usage percentage (50-90) at which to compact.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<u8>,
+    /// `auto`, `remind`, or `manual`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Model context window in tokens, for `sc compaction check`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+}
+
+/// `sc prime` settings.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PrimeConfig {
+    /// `off`, `standard` (the default), or `strict`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sanitize: Option<String>,
+    /// What prime does with agent decisions awaiting review: `flag` (the
+    /// default) or `exclude`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unreviewed: Option<String>,
+}
+
+/// Audit event retention. Unset limits keep events forever.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EventRetentionConfig {
+    /// Prune events older than this many days.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<u64>,
+    /// Prune all but the newest this many events of each entity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_per_entity: Option<usize>,
+    /// Write pruned events to `~/.savecontext/archive/` first (default: false).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<bool>,
+}
+
+/// Automatic checkpoint schedule and retention.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AutoCheckpointConfig {
+    /// `hourly`, `daily`, or `off` (the default).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+    /// Automatic checkpoints kept per session (default: 24).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep: Option<usize>,
+}
+
+/// How `sc session end` summarizes the session it ends.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionSummaryMode {
+    /// Built from the session's issues and items.
+    #[default]
+    Heuristic,
+    /// Written by the `summarize` endpoint, falling back to the heuristic.
+    Llm,
+    Off,
+}
+
+/// An OpenAI-compatible chat completions endpoint that rewrites
+/// generated reports into prose.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SummarizeConfig {
+    /// Full URL, e.g. `http://localhost:11434/v1/chat/completions`.
+    pub endpoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Give up after this many milliseconds (default: 30000).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl SummarizeConfig {
+    /// Timeout when `timeout_ms` is unset.
+    const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+    /// Send `content` with the `system` prompt and return the reply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or times out, or the reply
+    /// has no message content.
+    pub fn complete(&self, system: &str, content: &str) -> Result<String> {
+        let mut body = serde_json::json!({
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": content },
+            ],
+            "temperature": 0.2,
+        });
+        if let Some(model) = &self.model {
+            body["model"] = serde_json::Value::from(model.as_str());
+        }
+        let timeout = std::time::Duration::from_millis(self.timeout_ms.unwrap_or(Self::DEFAULT_TIMEOUT_MS));
+
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| Error::Other(format!("Failed to start async runtime: {e}")))?;
+        let response: serde_json::Value = runtime.block_on(async {
+            let mut request = reqwest::Client::new().post(&self.endpoint).timeout(timeout).json(&body);
+            if let Some(key) = &self.api_key {
+                request = request.bearer_auth(key);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| Error::Other(format!("Summarize request failed: {e}")))?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(Error::Other(format!("Summarize endpoint error {status}: {text}")));
+            }
+            response
+                .json()
+                .await
+                .map_err(|e| Error::Other(format!("Failed to parse summarize response: {e}")))
+        })?;
+
+        response["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(ToString::to_string)
+            .ok_or_else(|| Error::Other("Summarize response has no message content".to_string()))
+    }
+}
+
+/// An HTTP endpoint notified of changes.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Entity types to send; empty means all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// A named bundle of settings.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProfileConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<EmbeddingSettings>,
+}
+
+/// Output for config remote set.
+#[derive(Serialize)]
+struct RemoteSetOutput<'a> {
+    success: bool,
+    remote: Option<&'a RemoteConfig>,
+}
+
+/// Output for config remote show.
+#[derive(Serialize)]
+struct RemoteShowOutput<'a> {
+    configured: bool,
+    remote: Option<&'a RemoteConfig>,
+}
+
+/// Output for config remote remove.
+#[derive(Serialize)]
+struct RemoteRemoveOutput {
+    success: bool,
+    /// Whether a remote was configured before.
+    removed: bool,
+}
+
+/// Output for config validate.
+#[derive(Serialize)]
+struct ValidateOutput<'a> {
+    path: &'a Path,
+    exists: bool,
+    valid: bool,
+    errors: usize,
+    warnings: usize,
+    issues: &'a [ConfigIssue],
+}
+
+json_schema!(RemoteConfig {
+    host: String,
+    user: String,
+    port: u16,
+    identity_file: Option<String>,
+    remote_sc_path: Option<String>,
+    remote_project_path: Option<String>,
+    remote_db_path: Option<String>,
+});
+json_schema!(RemoteSetOutput<'a> { success: bool, remote: Option<&'a RemoteConfig> });
+json_schema!(RemoteShowOutput<'a> { configured: bool, remote: Option<&'a RemoteConfig> });
+json_schema!(RemoteRemoveOutput { success: bool, removed: bool });
+json_schema!(ValidateOutput<'a> {
+    path: &'a Path,
+    exists: bool,
+    valid: bool,
+    errors: usize,
+    warnings: usize,
+    issues: &'a [ConfigIssue],
+});
+
 fn default_port() -> u16 {
     22
 }
@@ -47,6 +359,16 @@ fn default_sc_path() -> Option<String> {
 
 // ── Public API ───────────────────────────────────────────────
 
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("config remote set", schema_for::<RemoteSetOutput<'_>>()),
+        ("config remote show", schema_for::<RemoteShowOutput<'_>>()),
+        ("config remote remove", schema_for::<RemoteRemoveOutput>()),
+        ("config validate", schema_for::<ValidateOutput<'_>>()),
+    ]
+}
+
 /// Execute config commands.
 pub fn execute(command: &ConfigCommands, json: bool) -> Result<()> {
     match command {
@@ -55,19 +377,23 @@ pub fn execute(command: &ConfigCommands, json: bool) -> Result<()> {
             ConfigRemoteCommands::Show => remote_show(json),
             ConfigRemoteCommands::Remove => remote_remove(json),
         },
+        ConfigCommands::Validate { file } => validate(file.as_deref(), json),
     }
 }
 
 /// Load the SaveContext configuration file.
 ///
-/// Returns default config if file doesn't exist or is invalid.
+/// Returns default config if file doesn't exist or is invalid. Schema
+/// problems are reported on stderr (once per process) either way.
 pub fn load_config() -> SaveContextConfig {
-    let path = config_path();
+    read_config(&config_path())
+}
+
+fn read_config(path: &Path) -> SaveContextConfig {
     if path.exists() {
-        fs::read_to_string(&path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_default()
+        let content = fs::read_to_string(path).unwrap_or_default();
+        schema::warn_once(path, &schema::validate_str(&content));
+        serde_json::from_str(&content).unwrap_or_default()
     } else {
         SaveContextConfig::default()
     }
@@ -84,6 +410,33 @@ pub fn load_remote_config() -> Result<RemoteConfig> {
     })
 }
 
+/// Resolve the issue workflow for a project.
+///
+/// Layers the `workflow` section of the config file (global overrides,
+/// then the entry for `project_path`) over the built-in state machine.
+#[must_use]
+pub fn load_issue_workflow(project_path: Option<&str>) -> IssueWorkflow {
+    IssueWorkflow::resolve(load_config().workflow.as_ref(), project_path)
+}
+
+/// Resolve the routing rules for a project.
+///
+/// Layers the `routing` section of the config file (global settings, then
+/// the entry for `project_path`) over round-robin without WIP limits.
+#[must_use]
+pub fn load_routing(project_path: Option<&str>) -> Routing {
+    Routing::resolve(load_config().routing.as_ref(), project_path)
+}
+
+/// Resolve the claim policy for a project.
+///
+/// Layers the `policy` section of the config file (global rules, then the
+/// entry for `project_path`); rules left unset don't apply.
+#[must_use]
+pub fn load_policy(project_path: Option<&str>) -> Policy {
+    Policy::resolve(load_config().policy.as_ref(), project_path)
+}
+
 /// Path to the global config file.
 pub fn config_path() -> PathBuf {
     directories::BaseDirs::new()
@@ -109,10 +462,7 @@ fn remote_set(args: &crate::cli::RemoteSetArgs, json: bool) -> Result<()> {
     save_config(&config)?;
 
     if json {
-        let output = serde_json::json!({
-            "success": true,
-            "remote": config.remote,
-        });
+        let output = RemoteSetOutput { success: true, remote: config.remote.as_ref() };
         println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Remote configuration saved.");
@@ -135,10 +485,7 @@ fn remote_show(json: bool) -> Result<()> {
     let config = load_config();
 
     if json {
-        let output = serde_json::json!({
-            "configured": config.remote.is_some(),
-            "remote": config.remote,
-        });
+        let output = RemoteShowOutput { configured: config.remote.is_some(), remote: config.remote.as_ref() };
         println!("{}", serde_json::to_string(&output)?);
     } else if let Some(ref remote) = config.remote {
         println!("Remote configuration:");
@@ -173,10 +520,7 @@ fn remote_remove(json: bool) -> Result<()> {
     save_config(&config)?;
 
     if json {
-        let output = serde_json::json!({
-            "success": true,
-            "removed": was_configured,
-        });
+        let output = RemoteRemoveOutput { success: true, removed: was_configured };
         println!("{}", serde_json::to_string(&output)?);
     } else if was_configured {
         println!("Remote configuration removed.");
@@ -187,6 +531,57 @@ fn remote_remove(json: bool) -> Result<()> {
     Ok(())
 }
 
+fn validate(file: Option<&Path>, json: bool) -> Result<()> {
+    let path = file.map_or_else(config_path, Path::to_path_buf);
+    let exists = path.exists();
+    if file.is_some() && !exists {
+        return Err(Error::Config(format!("{} does not exist", path.display())));
+    }
+
+    let issues = schema::validate_file(&path)?;
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let warnings = issues.len() - errors;
+
+    if json {
+        let output = ValidateOutput {
+            path: &path,
+            exists,
+            valid: errors == 0,
+            errors,
+            warnings,
+            issues: &issues,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if !exists {
+        println!("No config file at {} (defaults apply).", path.display());
+    } else if issues.is_empty() {
+        println!("✓ {} is valid", path.display());
+    } else {
+        println!("{}:", path.display());
+        print_issues(&issues);
+        println!();
+        println!("{errors} error(s), {warnings} warning(s)");
+    }
+
+    if errors > 0 {
+        return Err(Error::Config(format!(
+            "{} has {errors} error(s)",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+fn print_issues(issues: &[ConfigIssue]) {
+    for issue in issues {
+        let tag = match issue.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        println!("  {tag:<7} {issue}");
+    }
+}
+
 // ── SSH Helpers (shared by remote.rs and sync.rs) ───────────
 
 /// Shell-quote a string for safe interpolation into a remote shell command.
@@ -252,15 +647,19 @@ pub fn build_scp_base_args(config: &RemoteConfig) -> Vec<String> {
 
 // ── Helpers ──────────────────────────────────────────────────
 
-fn save_config(config: &SaveContextConfig) -> Result<()> {
-    let path = config_path();
+/// Write the configuration file, creating `~/.savecontext/` if needed.
+pub(crate) fn save_config(config: &SaveContextConfig) -> Result<()> {
+    write_config(&config_path(), config)
+}
+
+fn write_config(path: &Path, config: &SaveContextConfig) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| Error::Config(format!("Failed to create config directory: {e}")))?;
     }
 
     let json_str = serde_json::to_string_pretty(config)?;
-    fs::write(&path, format!("{json_str}\n"))
+    fs::write(path, format!("{json_str}\n"))
         .map_err(|e| Error::Config(format!("Failed to write config: {e}")))?;
 
     Ok(())
@@ -290,6 +689,7 @@ mod tests {
                 remote_project_path: None,
                 remote_db_path: None,
             }),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -302,6 +702,33 @@ mod tests {
         assert_eq!(remote.port, 22);
     }
 
+    #[test]
+    fn test_rewrite_keeps_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(
+            &path,
+            r#"{
+                "version": 1,
+                "sync_strategies": { "issues": "prefer-local" },
+                "command_log": { "enabled": true, "keep": 3 },
+                "otel": { "endpoint": "http://localhost:4318" },
+                "some_future_key": { "nested": [1, 2] }
+            }"#,
+        )
+        .unwrap();
+
+        let config = read_config(&path);
+        assert_eq!(config.sync_strategies.as_ref().unwrap()["issues"], "prefer-local");
+        write_config(&path, &config).unwrap();
+
+        let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["sync_strategies"]["issues"], "prefer-local");
+        assert_eq!(written["command_log"]["keep"], 3);
+        assert_eq!(written["otel"]["endpoint"], "http://localhost:4318");
+        assert_eq!(written["some_future_key"]["nested"][1], 2);
+    }
+
     #[test]
     fn test_config_path_is_under_savecontext() {
         let path = config_path();
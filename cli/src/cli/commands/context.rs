@@ -1,16 +1,19 @@
 //! Context item command implementations (save, get, delete, update, tag).
 
-use crate::cli::{GetArgs, SaveArgs, TagCommands, UpdateArgs};
-use crate::config::{default_actor, resolve_db_path, resolve_session_or_suggest};
+use crate::cli::{Database, GetArgs, ItemSort, SaveArgs, TagCommands, UpdateArgs};
+use crate::config::{default_actor, resolve_session_or_suggest};
 use crate::embeddings::{
     create_embedding_provider, is_embeddings_enabled, prepare_item_text, BoxedProvider,
-    EmbeddingProvider, Model2VecProvider, SearchMode,
+    EmbeddingEntity, EmbeddingProvider, Model2VecProvider, Reranker, SearchMode,
 };
 use crate::error::{Error, Result};
-use crate::storage::{SemanticSearchResult, SqliteStorage};
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::model::{ItemCategory, ItemPriority, TrustLevel};
+use crate::storage::{ContextItem, EntitySearchResult, ItemProvenance, SemanticSearchResult, SqliteStorage};
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::Path;
 use std::sync::OnceLock;
 use tracing::{debug, info, trace, warn};
 
@@ -93,8 +96,8 @@ fn store_fast_embedding(
 #[derive(Serialize)]
 struct SaveOutput {
     key: String,
-    category: String,
-    priority: String,
+    category: ItemCategory,
+    priority: ItemPriority,
     session_id: String,
 }
 
@@ -103,6 +106,35 @@ struct SaveOutput {
 struct GetOutput {
     items: Vec<crate::storage::ContextItem>,
     count: usize,
+    /// Records closest in meaning to the item; only with `--related`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    related: Option<Vec<EntitySearchResult>>,
+}
+
+/// Output for get --provenance.
+#[derive(Serialize)]
+struct ProvenanceOutput {
+    key: String,
+    item_id: String,
+    /// None when the item was saved outside a conversation.
+    provenance: Option<ItemProvenance>,
+    /// Whether the transcript can still be read.
+    transcript_found: bool,
+    /// The message the item was saved at and the few before it.
+    messages: Vec<TranscriptMessage>,
+}
+
+/// A user or assistant message of a Claude Code transcript.
+#[derive(Serialize)]
+struct TranscriptMessage {
+    /// 0-based among the transcript's messages.
+    index: usize,
+    /// 1-based line in the transcript file.
+    line: usize,
+    role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
+    text: String,
 }
 
 /// Output for semantic search.
@@ -135,56 +167,132 @@ struct DeleteOutput {
     deleted: bool,
 }
 
+json_schema!(SaveOutput { key: String, category: ItemCategory, priority: ItemPriority, session_id: String });
+json_schema!(GetOutput {
+    items: Vec<crate::storage::ContextItem>,
+    count: usize,
+    related: Option<Vec<EntitySearchResult>>,
+});
+json_schema!(ProvenanceOutput {
+    key: String,
+    item_id: String,
+    provenance: Option<ItemProvenance>,
+    transcript_found: bool,
+    messages: Vec<TranscriptMessage>,
+});
+json_schema!(ItemProvenance { transcript_path: String, message_index: usize });
+json_schema!(TranscriptMessage { index: usize, line: usize, role: String, timestamp: Option<String>, text: String });
+json_schema!(SemanticSearchOutput {
+    items: Vec<SemanticSearchItem>,
+    count: usize,
+    query: String,
+    threshold: f32,
+    semantic: bool,
+    strategy: Option<String>,
+});
+json_schema!(SemanticSearchItem {
+    key: String,
+    value: String,
+    category: String,
+    priority: String,
+    similarity: f32,
+    chunk_text: String,
+});
+json_schema!(DeleteOutput { key: String, deleted: bool });
+json_schema!(UpdateOutput { key: String, updated: bool });
+json_schema!(TagOutput { key: String, action: String, tags: Vec<String> });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("save", schema_for::<SaveOutput>()),
+        // `--search` returns ranked matches instead of the items, and
+        // `--provenance` where the item was saved.
+        (
+            "get",
+            one_of(&[schema_for::<GetOutput>(), schema_for::<SemanticSearchOutput>(), schema_for::<ProvenanceOutput>()]),
+        ),
+        ("delete", schema_for::<DeleteOutput>()),
+        ("update", schema_for::<UpdateOutput>()),
+        ("tag add", schema_for::<TagOutput>()),
+        ("tag remove", schema_for::<TagOutput>()),
+    ]
+}
+
 /// Execute save command.
 pub fn execute_save(
     args: &SaveArgs,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let category: ItemCategory = args.category.parse()?;
+    let priority: Option<ItemPriority> = args.priority.as_deref().map(str::parse).transpose()?;
+
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Resolve session: explicit flag > status cache > error
-    let resolved_session_id = resolve_session_or_suggest(session_id, &storage)?;
-    debug!(session = %resolved_session_id, key = %args.key, category = %args.category, "Saving context item");
+    let resolved_session_id = resolve_session_or_suggest(session_id, storage)?;
+    debug!(session = %resolved_session_id, key = %args.key, %category, "Saving context item");
+
+    // Filled from stdin/clipboard by `cli::input::resolve` when not given
+    let edited;
+    let value = if args.input.edit {
+        let current = match args.value {
+            Some(ref value) => Some(value.clone()),
+            None => current_value(storage, &resolved_session_id, &args.key)?,
+        };
+        edited = crate::cli::input::edit(current.as_deref().unwrap_or_default(), &format!("value for {}", args.key))?;
+        edited.as_str()
+    } else {
+        args.value.as_deref().unwrap_or_default()
+    };
 
     // Generate item ID
     let id = format!("item_{}", &uuid::Uuid::new_v4().to_string()[..12]);
 
-    storage.save_context_item(
-        &id,
-        &resolved_session_id,
-        &args.key,
-        &args.value,
-        Some(&args.category),
-        Some(&args.priority),
-        &actor,
-    )?;
+    // The value and its provenance are saved together
+    let actual_id = storage.atomically(|storage| {
+        storage.save_context_item(
+            &id,
+            &resolved_session_id,
+            &args.key,
+            value,
+            Some(category.clone()),
+            priority,
+            &actor,
+        )?;
 
-    // Resolve actual item ID — on upsert (key already exists), the DB keeps the
-    // original id, not the newly generated one. We need the real id for the FK
-    // reference in embedding_chunks_fast.
-    let actual_id = storage
-        .get_item_id_by_key(&resolved_session_id, &args.key)?
-        .unwrap_or(id);
+        // Resolve actual item ID — on upsert (key already exists), the DB keeps the
+        // original id, not the newly generated one. We need the real id for the FK
+        // reference in embedding_chunks_fast.
+        let actual_id = storage
+            .get_item_id_by_key(&resolved_session_id, &args.key)?
+            .unwrap_or(id);
+
+        // Where in the conversation it was saved; saving without a transcript
+        // clears what an earlier value recorded
+        let provenance = args.transcript.as_deref().map(|path| provenance_at(path, args.message_index)).transpose()?;
+        if !crate::is_dry_run() {
+            storage.set_item_provenance(&actual_id, provenance.as_ref())?;
+        }
+        Ok(actual_id)
+    })?;
 
     // Generate and store fast embedding inline (< 1ms with Model2Vec)
     // This enables immediate semantic search while quality embeddings are generated in background
     store_fast_embedding(
-        &mut storage,
+        storage,
         &actual_id,
         &args.key,
-        &args.value,
-        Some(&args.category),
+        value,
+        Some(category.as_str()),
     );
 
     // Spawn background process to generate embedding (fire-and-forget)
@@ -196,15 +304,20 @@ pub fn execute_save(
     }
 
     if json {
+        // Without --priority the item gets its category's default
+        let priority = match priority {
+            Some(priority) => priority,
+            None => storage.get_context_item(&actual_id)?.map(|item| item.priority).unwrap_or_default(),
+        };
         let output = SaveOutput {
             key: args.key.clone(),
-            category: args.category.clone(),
-            priority: args.priority.clone(),
+            category,
+            priority,
             session_id: resolved_session_id.clone(),
         };
         println!("{}", serde_json::to_string(&output)?);
     } else {
-        println!("Saved: {} [{}]", args.key, args.category);
+        println!("Saved: {} [{category}]", args.key);
     }
 
     Ok(())
@@ -222,14 +335,11 @@ pub fn execute_save(
 /// - Items to have been backfilled with embeddings
 pub fn execute_get(
     args: &GetArgs,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
@@ -247,36 +357,47 @@ pub fn execute_get(
         let rt = tokio::runtime::Runtime::new()
             .map_err(|e| Error::Other(format!("Failed to create async runtime: {e}")))?;
 
-        return rt.block_on(execute_semantic_search(args, &db_path, session_id, json));
+        return rt.block_on(execute_semantic_search(args, db.storage()?, session_id, json));
     }
 
     // Standard keyword search path
-    let storage = SqliteStorage::open(&db_path)?;
+    let category: Option<ItemCategory> = args.category.as_deref().map(str::parse).transpose()?;
+    let priority: Option<ItemPriority> = args.priority.as_deref().map(str::parse).transpose()?;
+    let storage = db.storage()?;
 
-    // Fetch extra for post-filtering and pagination
+    // Fetch extra for post-filtering and pagination; ranking by relevance
+    // needs every candidate
     #[allow(clippy::cast_possible_truncation)]
-    let fetch_limit = ((args.limit + args.offset.unwrap_or(0)) * 2).min(1000) as u32;
+    let fetch_limit = match args.sort {
+        ItemSort::Recent => ((args.limit + args.offset.unwrap_or(0)) * 2).min(1000) as u32,
+        ItemSort::Relevance => 1000,
+    };
 
     // Get items - either from all sessions or current session
     let items = if args.search_all_sessions {
         // Search across all sessions
         storage.get_all_context_items(
-            args.category.as_deref(),
-            args.priority.as_deref(),
+            category.as_ref().map(ItemCategory::as_str),
+            priority.map(ItemPriority::as_str),
             Some(fetch_limit),
         )?
     } else {
         // Resolve session: explicit flag > status cache > error
-        let resolved_session_id = resolve_session_or_suggest(session_id, &storage)?;
+        let resolved_session_id = resolve_session_or_suggest(session_id, storage)?;
 
         storage.get_context_items(
             &resolved_session_id,
-            args.category.as_deref(),
-            args.priority.as_deref(),
+            category,
+            priority,
             Some(fetch_limit),
         )?
     };
 
+    let items: Vec<_> = match args.min_trust {
+        Some(min) => items.into_iter().filter(|i| i.trust.at_least(min)).collect(),
+        None => items,
+    };
+
     // Filter by key if specified
     let items: Vec<_> = if let Some(ref key) = args.key {
         items.into_iter().filter(|i| i.key == *key).collect()
@@ -293,12 +414,33 @@ pub fn execute_get(
         items
     };
 
+    let items = match args.sort {
+        ItemSort::Recent => items,
+        ItemSort::Relevance => sort_by_relevance(storage, items)?,
+    };
+
     // Apply offset and limit
     let items: Vec<_> = items
         .into_iter()
         .skip(args.offset.unwrap_or(0))
         .take(args.limit)
         .collect();
+    record_reads(storage, &items.iter().map(|i| i.id.clone()).collect::<Vec<_>>());
+
+    if args.provenance {
+        let key = args.key.as_deref().unwrap_or_default();
+        let item = items.first().ok_or_else(|| Error::Other(format!("Context item not found: {key}")))?;
+        return print_provenance(storage, item, json);
+    }
+
+    // Related to the item with the key (the current session's, if any)
+    let related = match items.first().filter(|_| args.related) {
+        Some(item) => {
+            let project_path = crate::config::resolve_project_path(storage, None).ok();
+            Some(super::search::related(storage, EmbeddingEntity::ContextItem, &item.id, project_path.as_deref())?)
+        }
+        None => None,
+    };
 
     if crate::is_csv() {
         println!("key,category,priority,value");
@@ -310,20 +452,28 @@ pub fn execute_get(
         let output = GetOutput {
             count: items.len(),
             items,
+            related: related.map(Option::unwrap_or_default),
         };
         println!("{}", serde_json::to_string(&output)?);
     } else if items.is_empty() {
         println!("No context items found.");
     } else {
+        // Category colors come from the project's registry
+        let categories = crate::config::resolve_project_path(storage, None)
+            .and_then(|path| storage.get_categories(&path))
+            .unwrap_or_default();
         println!("Context items ({} found):", items.len());
         println!();
         for item in &items {
-            let priority_icon = match item.priority.as_str() {
-                "high" => "!",
-                "low" => "-",
-                _ => " ",
+            let priority_icon = match item.priority {
+                ItemPriority::High => "!",
+                ItemPriority::Low => "-",
+                ItemPriority::Normal => " ",
             };
-            println!("[{}] {} ({})", priority_icon, item.key, item.category);
+            let category = super::category::label(&categories, &item.category);
+            // Only items short of human trust are marked
+            let trust = if item.trust == TrustLevel::Human { String::new() } else { format!(", trust {}", item.trust) };
+            println!("[{}] {} ({category}{trust})", priority_icon, item.key);
             // Truncate long values
             let display_value = if item.value.len() > 100 {
                 format!("{}...", &item.value[..100])
@@ -333,11 +483,60 @@ pub fn execute_get(
             println!("    {display_value}");
             println!();
         }
+        if let Some(related) = &related {
+            super::search::print_related(related.as_deref(), "item");
+        }
     }
 
     Ok(())
 }
 
+/// Most relevant first: see [`RelevanceConfig::score`](crate::model::RelevanceConfig::score).
+fn sort_by_relevance(storage: &SqliteStorage, items: Vec<ContextItem>) -> Result<Vec<ContextItem>> {
+    let config = crate::config::relevance();
+    let ids: Vec<String> = items.iter().map(|i| i.id.clone()).collect();
+    let access = storage.get_item_access(&ids)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut scored: Vec<(f64, ContextItem)> = items
+        .into_iter()
+        .map(|item| {
+            let read = access.get(&item.id).copied().unwrap_or_default();
+            (config.score(item.updated_at, read, now), item)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(scored.into_iter().map(|(_, item)| item).collect())
+}
+
+/// Search results for items trusted at least `min_trust`.
+fn keep_trusted(
+    storage: &SqliteStorage,
+    results: Vec<SemanticSearchResult>,
+    min_trust: Option<TrustLevel>,
+) -> Result<Vec<SemanticSearchResult>> {
+    let Some(min) = min_trust else {
+        return Ok(results);
+    };
+    let mut kept = Vec::with_capacity(results.len());
+    for result in results {
+        if storage.get_context_item(&result.item_id)?.is_some_and(|item| item.trust.at_least(min)) {
+            kept.push(result);
+        }
+    }
+    Ok(kept)
+}
+
+/// Count a read of the items a command showed. Only ranking depends on
+/// it, so a failure is logged rather than failing the command.
+pub(crate) fn record_reads(storage: &SqliteStorage, item_ids: &[String]) {
+    if item_ids.is_empty() || crate::is_dry_run() {
+        return;
+    }
+    if let Err(e) = storage.record_item_access(item_ids) {
+        warn!(error = %e, "Failed to record item reads");
+    }
+}
+
 /// Execute smart semantic search with cascading pipeline.
 ///
 /// 4-stage pipeline that progressively broadens search strategy:
@@ -350,9 +549,11 @@ pub fn execute_get(
 /// - `Fast`: Uses Model2Vec for instant results (lower accuracy)
 /// - `Quality`: Uses Ollama/HuggingFace for accurate results (slower)
 /// - `Tiered`: Fast candidates then quality re-ranking (default, falls back to quality)
+/// - `Rerank`: Quality search for `top_k` candidates, then a cross-encoder or
+///   LLM re-scores whichever stage matched (see [`rerank_results`])
 async fn execute_semantic_search(
     args: &GetArgs,
-    db_path: &std::path::Path,
+    storage: &mut SqliteStorage,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
@@ -363,14 +564,18 @@ async fn execute_semantic_search(
     let explicit_threshold = args.threshold.map(|t| t as f32);
     let search_mode = args.search_mode.unwrap_or_default();
 
-    // Open storage
-    let storage = SqliteStorage::open(db_path)?;
+    // Re-rank mode fetches extra candidates for the scorer to reorder
+    let reranker = match search_mode {
+        SearchMode::Rerank => Some(Reranker::from_config()?),
+        _ => None,
+    };
+    let candidate_limit = reranker.as_ref().map_or(args.limit, |r| r.top_k.max(args.limit));
 
     // Resolve session if not searching all
     let session_filter = if args.search_all_sessions {
         None
     } else {
-        Some(resolve_session_or_suggest(session_id, &storage)?)
+        Some(resolve_session_or_suggest(session_id, storage)?)
     };
 
     // Create embedding provider based on search mode
@@ -387,11 +592,12 @@ async fn execute_semantic_search(
             let emb = p.generate_embedding(&query_text).await?;
             (emb, SmartProvider::Fast(p))
         }
-        SearchMode::Quality | SearchMode::Tiered => {
+        SearchMode::Quality | SearchMode::Tiered | SearchMode::Rerank => {
             debug!("Using quality provider (Ollama/HuggingFace)");
             let p = create_embedding_provider()
                 .await
                 .ok_or_else(|| Error::Embedding("No quality embedding provider available".to_string()))?;
+            super::embeddings::ensure_model_compatible(storage, &p)?;
             let emb = p.generate_embedding(&query_text).await?;
             (emb, SmartProvider::Quality(p))
         }
@@ -400,23 +606,27 @@ async fn execute_semantic_search(
     // Choose the right search function based on search mode
     let search_fn = match search_mode {
         SearchMode::Fast => SearchFn::Fast,
-        SearchMode::Quality | SearchMode::Tiered => SearchFn::Quality,
+        SearchMode::Quality | SearchMode::Tiered | SearchMode::Rerank => SearchFn::Quality,
     };
 
     // --- Stage 1: Full query with adaptive threshold ---
     debug!("Stage 1: adaptive threshold search");
     let results = smart_search_adaptive(
-        &storage,
+        storage,
         &search_fn,
         &query_embedding,
         session_filter.as_deref(),
-        args.limit,
+        candidate_limit,
         explicit_threshold,
     )?;
+    let results = keep_trusted(storage, results, args.min_trust)?;
 
     if !results.is_empty() {
         info!(count = results.len(), "Stage 1 matched");
-        return output_semantic_results(&results, query, explicit_threshold.unwrap_or(0.0), json, None);
+        return output_ranked(
+            storage, reranker.as_ref(), results, query, explicit_threshold.unwrap_or(0.0), json, None, args.limit,
+        )
+        .await;
     }
     debug!("Stage 1: no results");
 
@@ -426,17 +636,21 @@ async fn execute_semantic_search(
     if sub_queries.len() > 1 {
         let results = smart_search_rrf(
             &provider,
-            &storage,
+            storage,
             &search_fn,
             &sub_queries,
             session_filter.as_deref(),
-            args.limit,
+            candidate_limit,
         )
         .await?;
+        let results = keep_trusted(storage, results, args.min_trust)?;
 
         if !results.is_empty() {
             info!(count = results.len(), "Stage 2 matched (decomposed query)");
-            return output_semantic_results(&results, query, 0.0, json, Some("decomposed query"));
+            return output_ranked(
+                storage, reranker.as_ref(), results, query, 0.0, json, Some("decomposed query"), args.limit,
+            )
+            .await;
         }
         debug!("Stage 2: no results from RRF");
     }
@@ -445,40 +659,44 @@ async fn execute_semantic_search(
     if session_filter.is_some() {
         debug!("Stage 3: expanding scope to all sessions");
         let results = smart_search_adaptive(
-            &storage,
+            storage,
             &search_fn,
             &query_embedding,
             None,
-            args.limit,
+            candidate_limit,
             explicit_threshold,
         )?;
+        let results = keep_trusted(storage, results, args.min_trust)?;
 
         if !results.is_empty() {
             info!(count = results.len(), "Stage 3 matched (all sessions, adaptive)");
-            return output_semantic_results(
-                &results, query, explicit_threshold.unwrap_or(0.0), json,
-                Some("expanded to all sessions"),
-            );
+            return output_ranked(
+                storage, reranker.as_ref(), results, query, explicit_threshold.unwrap_or(0.0), json,
+                Some("expanded to all sessions"), args.limit,
+            )
+            .await;
         }
 
         if sub_queries.len() > 1 {
             debug!("Stage 3b: all sessions + decomposition");
             let results = smart_search_rrf(
                 &provider,
-                &storage,
+                storage,
                 &search_fn,
                 &sub_queries,
                 None,
-                args.limit,
+                candidate_limit,
             )
             .await?;
+            let results = keep_trusted(storage, results, args.min_trust)?;
 
             if !results.is_empty() {
                 info!(count = results.len(), "Stage 3b matched (all sessions + decomposed)");
-                return output_semantic_results(
-                    &results, query, 0.0, json,
-                    Some("expanded to all sessions + decomposed"),
-                );
+                return output_ranked(
+                    storage, reranker.as_ref(), results, query, 0.0, json,
+                    Some("expanded to all sessions + decomposed"), args.limit,
+                )
+                .await;
             }
         }
     }
@@ -497,6 +715,93 @@ async fn execute_semantic_search(
     }
 }
 
+/// Output a stage's matches, re-ranked first in `--search-mode rerank`.
+/// Items read often and lately move up; see [`boost_by_reads`].
+#[allow(clippy::too_many_arguments)]
+async fn output_ranked(
+    storage: &SqliteStorage,
+    reranker: Option<&Reranker>,
+    results: Vec<SemanticSearchResult>,
+    query: &str,
+    threshold: f32,
+    json: bool,
+    strategy: Option<&str>,
+    limit: usize,
+) -> Result<()> {
+    let (results, strategy) = match reranker {
+        Some(reranker) => {
+            let (results, note) = rerank_results(reranker, query, results, limit).await;
+            let strategy = match strategy {
+                Some(s) => format!("{s}, {note}"),
+                None => note,
+            };
+            (results, Some(strategy))
+        }
+        None => (results, strategy.map(String::from)),
+    };
+    let results = boost_by_reads(storage, results)?;
+    record_reads(storage, &results.iter().map(|r| r.item_id.clone()).collect::<Vec<_>>());
+    output_semantic_results(&results, query, threshold, json, strategy.as_deref())
+}
+
+/// Order matches by similarity times their read boost
+/// ([`RelevanceConfig::boost`](crate::model::RelevanceConfig::boost)).
+/// Similarity itself is left as is, so the percentages shown still say how
+/// close each match is.
+fn boost_by_reads(storage: &SqliteStorage, results: Vec<SemanticSearchResult>) -> Result<Vec<SemanticSearchResult>> {
+    let config = crate::config::relevance();
+    let ids: Vec<String> = results.iter().map(|r| r.item_id.clone()).collect();
+    let access = storage.get_item_access(&ids)?;
+    if access.is_empty() {
+        return Ok(results);
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut ranked: Vec<(f64, SemanticSearchResult)> = results
+        .into_iter()
+        .map(|r| {
+            let read = access.get(&r.item_id).copied().unwrap_or_default();
+            (f64::from(r.similarity) * config.boost(read, now), r)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(ranked.into_iter().map(|(_, r)| r).collect())
+}
+
+/// Final stage of `--search-mode rerank`: re-score candidates with the
+/// configured cross-encoder or LLM and keep the best `limit`.
+///
+/// Similarity becomes the scorer's relevance (0-1). If the scorer fails or
+/// overruns its latency budget the quality order is kept, so re-ranking
+/// can only slow a search down by the budget, never break it.
+async fn rerank_results(
+    reranker: &Reranker,
+    query: &str,
+    mut results: Vec<SemanticSearchResult>,
+    limit: usize,
+) -> (Vec<SemanticSearchResult>, String) {
+    let started = std::time::Instant::now();
+    let rescored = {
+        let documents: Vec<&str> = results.iter().map(|r| r.chunk_text.as_str()).collect();
+        reranker.score(query, &documents).await
+    };
+    let note = match rescored {
+        Ok(scores) => {
+            for (result, score) in results.iter_mut().zip(scores) {
+                result.similarity = score;
+            }
+            results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+            debug!(candidates = results.len(), elapsed_ms = started.elapsed().as_millis(), "Re-rank complete");
+            format!("re-ranked in {}ms", started.elapsed().as_millis())
+        }
+        Err(e) => {
+            warn!(error = %e, "Re-rank skipped, keeping quality order");
+            format!("re-rank skipped: {e}")
+        }
+    };
+    results.truncate(limit);
+    (results, note)
+}
+
 /// Which search tier to use.
 enum SearchFn {
     Fast,
@@ -776,25 +1081,19 @@ fn output_suggestions(
 /// Execute delete command.
 pub fn execute_delete(
     key: &str,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Resolve session: explicit flag > status cache > error
-    let resolved_session_id = resolve_session_or_suggest(session_id, &storage)?;
+    let resolved_session_id = resolve_session_or_suggest(session_id, storage)?;
 
     storage.delete_context_item(&resolved_session_id, key, &actor)?;
+    super::trash::purge_expired(storage)?;
 
     if json {
         let output = DeleteOutput {
@@ -816,48 +1115,211 @@ struct UpdateOutput {
     updated: bool,
 }
 
+/// Enforce `--if-updated-at`. A missing item is left for the update to
+/// report.
+fn check_item_unchanged(storage: &SqliteStorage, session_id: &str, key: &str, expected: Option<i64>) -> Result<()> {
+    if expected.is_none() {
+        return Ok(());
+    }
+    let Some(id) = storage.get_item_id_by_key(session_id, key)? else {
+        return Ok(());
+    };
+    match storage.get_context_item(&id)? {
+        Some(item) => Error::check_updated_at("context item", key, expected, item.updated_at, &item),
+        None => Ok(()),
+    }
+}
+
+/// Provenance of an item saved now from `transcript`: at `message_index`,
+/// or at the last message the transcript has so far.
+fn provenance_at(transcript: &Path, message_index: Option<usize>) -> Result<ItemProvenance> {
+    let unreadable = |e: std::io::Error| Error::InvalidArgument(format!("Cannot read transcript {}: {e}", transcript.display()));
+    let path = std::fs::canonicalize(transcript).map_err(unreadable)?;
+    let message_index = match message_index {
+        Some(index) => index,
+        None => transcript_messages(&std::fs::read_to_string(&path).map_err(unreadable)?).len().saturating_sub(1),
+    };
+    Ok(ItemProvenance { transcript_path: path.display().to_string(), message_index })
+}
+
+/// Show where in its conversation an item was saved: the transcript
+/// position and the messages leading up to it.
+fn print_provenance(storage: &SqliteStorage, item: &ContextItem, json: bool) -> Result<()> {
+    /// Messages shown before the one the item was saved at.
+    const EARLIER: usize = 3;
+
+    let provenance = storage.get_item_provenance(&item.id)?;
+    let content = provenance.as_ref().and_then(|p| std::fs::read_to_string(&p.transcript_path).ok());
+    let messages: Vec<TranscriptMessage> = match (&provenance, &content) {
+        (Some(provenance), Some(content)) => transcript_messages(content)
+            .into_iter()
+            .filter(|m| m.index <= provenance.message_index && m.index + EARLIER >= provenance.message_index)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if json {
+        let output = ProvenanceOutput {
+            key: item.key.clone(),
+            item_id: item.id.clone(),
+            transcript_found: content.is_some(),
+            provenance,
+            messages,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    let Some(provenance) = provenance else {
+        println!("{} was not saved from a conversation.", item.key);
+        return Ok(());
+    };
+    match messages.last() {
+        Some(saved_at) => println!(
+            "{} was saved at message {} of {}:{}",
+            item.key, provenance.message_index, provenance.transcript_path, saved_at.line
+        ),
+        None => println!("{} was saved at message {} of {}", item.key, provenance.message_index, provenance.transcript_path),
+    }
+    if content.is_none() {
+        println!("The transcript no longer exists.");
+    } else if messages.last().is_none_or(|m| m.index != provenance.message_index) {
+        println!("The transcript has no message {} any more.", provenance.message_index);
+    }
+    for message in &messages {
+        println!();
+        let at = message.timestamp.as_deref().map(|t| format!(" ({t})")).unwrap_or_default();
+        println!("[{}] {}{at}", message.index, message.role);
+        let text = if message.text.chars().count() > 500 {
+            format!("{}...", message.text.chars().take(500).collect::<String>())
+        } else {
+            message.text.clone()
+        };
+        for line in text.lines() {
+            println!("    {line}");
+        }
+    }
+    Ok(())
+}
+
+/// The user and assistant messages of a Claude Code transcript, in order.
+/// Subagent (sidechain) turns are left out.
+fn transcript_messages(content: &str) -> Vec<TranscriptMessage> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let entry: Value = serde_json::from_str(line).ok()?;
+            let role = entry["type"].as_str().filter(|t| matches!(*t, "user" | "assistant"))?.to_string();
+            (entry["isSidechain"] != true).then_some((i + 1, role, entry))
+        })
+        .enumerate()
+        .map(|(index, (line, role, entry))| TranscriptMessage {
+            index,
+            line,
+            role,
+            timestamp: entry["timestamp"].as_str().map(ToString::to_string),
+            text: message_text(&entry["message"]["content"]),
+        })
+        .collect()
+}
+
+/// Text of a message, with tool calls and results reduced to a marker.
+fn message_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block["type"].as_str()? {
+                "text" => block["text"].as_str().map(ToString::to_string),
+                "tool_use" => Some(format!("[tool: {}]", block["name"].as_str().unwrap_or("?"))),
+                "tool_result" => Some("[tool result]".to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Stored value of a context item, for `--edit`.
+fn current_value(storage: &SqliteStorage, session_id: &str, key: &str) -> Result<Option<String>> {
+    let Some(id) = storage.get_item_id_by_key(session_id, key)? else {
+        return Ok(None);
+    };
+    Ok(storage.get_context_item(&id)?.map(|item| item.value))
+}
+
 /// Execute update command.
 pub fn execute_update(
     args: &UpdateArgs,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
-    // Check if any update field is provided
-    if args.value.is_none()
-        && args.category.is_none()
-        && args.priority.is_none()
-        && args.channel.is_none()
-    {
+    // Check if any update field is provided; trust alone leaves updated_at be
+    let edits_fields = args.value.is_some()
+        || args.input.edit
+        || args.category.is_some()
+        || args.priority.is_some()
+        || args.channel.is_some();
+    if !edits_fields && args.trust.is_none() {
         return Err(Error::Config(
-            "At least one of --value, --category, --priority, or --channel must be provided"
+            "At least one of --value, --category, --priority, --channel, or --trust must be provided"
                 .to_string(),
         ));
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let category: Option<ItemCategory> = args.category.as_deref().map(str::parse).transpose()?;
+    let priority: Option<ItemPriority> = args.priority.as_deref().map(str::parse).transpose()?;
+
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Resolve session: explicit flag > status cache > error
-    let resolved_session_id = resolve_session_or_suggest(session_id, &storage)?;
+    let resolved_session_id = resolve_session_or_suggest(session_id, storage)?;
 
-    storage.update_context_item(
-        &resolved_session_id,
-        &args.key,
-        args.value.as_deref(),
-        args.category.as_deref(),
-        args.priority.as_deref(),
-        args.channel.as_deref(),
-        &actor,
-    )?;
+    let edited = if args.input.edit {
+        let initial = match args.value {
+            Some(ref value) => value.clone(),
+            None => current_value(storage, &resolved_session_id, &args.key)?
+                .ok_or_else(|| Error::Other(format!("Context item not found: {}", args.key)))?,
+        };
+        Some(crate::cli::input::edit(&initial, &format!("value for {}", args.key))?)
+    } else {
+        None
+    };
+
+    // The guard and the write share a transaction, so no one can slip in
+    // between them
+    storage.with_transaction(|storage| {
+        let result = check_item_unchanged(storage, &resolved_session_id, &args.key, args.if_updated_at)
+            .and_then(|()| {
+                if !edits_fields {
+                    return Ok(());
+                }
+                storage.update_context_item(
+                    &resolved_session_id,
+                    &args.key,
+                    edited.as_deref().or(args.value.as_deref()),
+                    category,
+                    priority,
+                    args.channel.as_deref(),
+                    &actor,
+                )
+            })
+            .and_then(|()| match args.trust {
+                Some(trust) => storage.set_item_trust(&resolved_session_id, &args.key, trust, &actor),
+                None => Ok(()),
+            });
+        let ok = result.is_ok();
+        (result, ok)
+    })??;
 
     if json {
         let output = UpdateOutput {
@@ -883,23 +1345,16 @@ struct TagOutput {
 /// Execute tag command.
 pub fn execute_tag(
     command: &TagCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Resolve session: explicit flag > status cache > error
-    let resolved_session_id = resolve_session_or_suggest(session_id, &storage)?;
+    let resolved_session_id = resolve_session_or_suggest(session_id, storage)?;
 
     match command {
         TagCommands::Add { key, tags } => {
@@ -1084,4 +1539,38 @@ mod tests {
         assert_eq!((0.3_f32 * 0.6).max(0.25), 0.25);    // floor kicks in
         assert_eq!((0.1_f32 * 0.6).max(0.25), 0.25);    // well below floor
     }
+
+    #[test]
+    fn test_if_updated_at_guard() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_session("sess_1", "S", None, None, None, None, "actor").unwrap();
+        storage.save_context_item("item_1", "sess_1", "auth", "JWT", None, None, "actor").unwrap();
+        let seen = storage.get_context_item("item_1").unwrap().unwrap().updated_at;
+
+        assert!(check_item_unchanged(&storage, "sess_1", "auth", Some(seen)).is_ok());
+        assert!(check_item_unchanged(&storage, "sess_1", "missing", Some(seen)).is_ok());
+        let err = check_item_unchanged(&storage, "sess_1", "auth", Some(seen - 1)).unwrap_err();
+        let json = err.to_structured_json();
+        assert_eq!(json["error"]["code"], "CONFLICT");
+        assert_eq!(json["error"]["current"]["value"], "JWT");
+        assert_eq!(json["error"]["current"]["updated_at"], seen);
+    }
+
+    // --- transcript_messages tests ---
+
+    #[test]
+    fn test_transcript_messages() {
+        let transcript = [
+            r#"{"type":"summary","summary":"Earlier work"}"#,
+            r#"{"type":"user","timestamp":"2026-10-16T09:00:00Z","message":{"content":"Use Postgres?"}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"content":[{"type":"text","text":"subagent"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Yes."},{"type":"tool_use","name":"Bash"}]}}"#,
+        ]
+        .join("\n");
+        let messages = transcript_messages(&transcript);
+        let found: Vec<(usize, usize, &str, &str)> =
+            messages.iter().map(|m| (m.index, m.line, m.role.as_str(), m.text.as_str())).collect();
+        assert_eq!(found, [(0, 2, "user", "Use Postgres?"), (1, 4, "assistant", "Yes.\n[tool: Bash]")]);
+        assert_eq!(messages[0].timestamp.as_deref(), Some("2026-10-16T09:00:00Z"));
+    }
 }
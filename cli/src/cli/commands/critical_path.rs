@@ -0,0 +1,235 @@
+//! Critical path of a plan or epic (`sc plan critical-path`,
+//! `sc issue critical-path`).
+//!
+//! The remaining issues of a plan, or the descendants of an epic, are
+//! scheduled by their `blocks` dependencies with the critical path method
+//! (see [`crate::model::schedule`]): each takes its estimate in hours, or
+//! `--default-hours` when it has none. The longest chain is what decides
+//! when everything can be done, so its issues gate completion and any
+//! delay on them is a delay to the whole; every other issue has slack.
+//!
+//! Closed issues are done and deferred ones postponed, so neither counts.
+//! Only dependencies between the issues in scope are followed: a blocker
+//! outside the plan or epic is not waited for.
+
+use crate::cli::Database;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::schedule;
+use crate::model::IssueStatus;
+use crate::storage::{Issue, SqliteStorage};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// What to find the critical path of.
+pub enum Scope<'a> {
+    Plan(&'a str),
+    Epic(&'a str),
+}
+
+/// Output for plan critical-path and issue critical-path.
+#[derive(Serialize)]
+struct CriticalPathOutput {
+    /// `plan` or `epic`.
+    scope: String,
+    id: String,
+    title: String,
+    /// Hours until every remaining issue is done.
+    total_hours: f64,
+    /// The longest chain of dependent issues, first to last.
+    path: Vec<ScheduledIssue>,
+    /// Every remaining issue, in dependency order.
+    issues: Vec<ScheduledIssue>,
+    /// Issues without an estimate, counted at `--default-hours`.
+    unestimated: usize,
+    /// Issues in a dependency cycle or waiting on one.
+    cyclic: Vec<ScheduledIssue>,
+}
+
+#[derive(Serialize, Clone)]
+struct ScheduledIssue {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    status: IssueStatus,
+    assigned_to_agent: Option<String>,
+    estimate_hours: Option<f64>,
+    /// Hours counted for it: the estimate, or the default.
+    hours: f64,
+    earliest_start: f64,
+    earliest_finish: f64,
+    /// Hours it can slip without delaying the end.
+    slack: f64,
+    critical: bool,
+}
+
+json_schema!(CriticalPathOutput {
+    scope: String,
+    id: String,
+    title: String,
+    total_hours: f64,
+    path: Vec<ScheduledIssue>,
+    issues: Vec<ScheduledIssue>,
+    unestimated: usize,
+    cyclic: Vec<ScheduledIssue>,
+});
+json_schema!(ScheduledIssue {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    status: IssueStatus,
+    assigned_to_agent: Option<String>,
+    estimate_hours: Option<f64>,
+    hours: f64,
+    earliest_start: f64,
+    earliest_finish: f64,
+    slack: f64,
+    critical: bool,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("plan critical-path", schema_for::<CriticalPathOutput>()),
+        ("issue critical-path", schema_for::<CriticalPathOutput>()),
+    ]
+}
+
+/// Print the critical path of a plan or epic.
+///
+/// # Errors
+///
+/// Returns an error if the plan or issue doesn't exist or the database
+/// cannot be read.
+pub fn execute(scope: &Scope, default_hours: f64, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+
+    let (kind, id, title, project_path, issues) = match scope {
+        Scope::Plan(id) => {
+            let plan = storage.get_plan(id)?.ok_or_else(|| Error::Other(format!("Plan not found: {id}")))?;
+            let issues = storage.list_plan_issues(&plan.id)?;
+            ("plan", plan.id, plan.title, plan.project_path, issues)
+        }
+        Scope::Epic(id) => {
+            let epic = storage.get_issue(id, None)?.ok_or_else(|| Error::IssueNotFound { id: (*id).to_string() })?;
+            let issues = descendants(storage, &epic.id)?;
+            ("epic", epic.id, epic.title, epic.project_path, issues)
+        }
+    };
+    let issues: Vec<Issue> =
+        issues.into_iter().filter(|i| !matches!(i.status, IssueStatus::Closed | IssueStatus::Deferred)).collect();
+
+    let ids: Vec<&str> = issues.iter().map(|i| i.id.as_str()).collect();
+    let hours: Vec<f64> = issues.iter().map(|i| i.estimate_hours.unwrap_or(default_hours)).collect();
+    let edges = storage.get_blocking_edges(&project_path)?;
+    let plan = schedule::critical_path(&ids, &edges, &hours);
+
+    let on_path: HashSet<usize> = plan.path.iter().copied().collect();
+    let scheduled = |i: usize| ScheduledIssue {
+        id: issues[i].id.clone(),
+        short_id: issues[i].short_id.clone(),
+        title: issues[i].title.clone(),
+        status: issues[i].status.clone(),
+        assigned_to_agent: issues[i].assigned_to_agent.clone(),
+        estimate_hours: issues[i].estimate_hours,
+        hours: hours[i],
+        earliest_start: plan.earliest_start[i],
+        earliest_finish: plan.earliest_finish[i],
+        slack: plan.slack[i],
+        critical: on_path.contains(&i),
+    };
+    let mut order: Vec<usize> = (0..issues.len()).filter(|i| !plan.cyclic.contains(i)).collect();
+    order.sort_by(|&a, &b| plan.earliest_start[a].total_cmp(&plan.earliest_start[b]).then(a.cmp(&b)));
+
+    let output = CriticalPathOutput {
+        scope: kind.to_string(),
+        id,
+        title,
+        total_hours: plan.total_hours,
+        path: plan.path.iter().map(|&i| scheduled(i)).collect(),
+        issues: order.into_iter().map(scheduled).collect(),
+        unestimated: issues.iter().filter(|i| i.estimate_hours.is_none()).count(),
+        cyclic: plan.cyclic.iter().map(|&i| scheduled(i)).collect(),
+    };
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        print_path(&output, default_hours);
+    }
+    Ok(())
+}
+
+/// Every issue under `epic_id` through `parent-child` links, at any depth.
+fn descendants(storage: &SqliteStorage, epic_id: &str) -> Result<Vec<Issue>> {
+    let mut seen = HashSet::from([epic_id.to_string()]);
+    let mut queue = vec![epic_id.to_string()];
+    let mut issues = Vec::new();
+    while let Some(parent) = queue.pop() {
+        let mut children: Vec<String> = storage.get_child_issue_ids(&parent)?.into_iter().collect();
+        children.sort();
+        for child in children {
+            if seen.insert(child.clone()) {
+                issues.extend(storage.get_issue(&child, None)?);
+                queue.push(child);
+            }
+        }
+    }
+    issues.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.created_at.cmp(&b.created_at)));
+    Ok(issues)
+}
+
+fn print_path(output: &CriticalPathOutput, default_hours: f64) {
+    if output.issues.is_empty() && output.cyclic.is_empty() {
+        println!("Nothing left to do in {} \"{}\".", output.scope, output.title);
+        return;
+    }
+    println!(
+        "Critical path of {} \"{}\": {} through {} issues",
+        output.scope,
+        output.title,
+        hours(output.total_hours),
+        output.path.len()
+    );
+    if output.unestimated > 0 {
+        println!("(unestimated issues: {}, counted as {} each)", output.unestimated, hours(default_hours));
+    }
+    println!();
+    for issue in &output.path {
+        println!("  {}", line(issue));
+    }
+    let others: Vec<&ScheduledIssue> = output.issues.iter().filter(|i| !i.critical).collect();
+    if !others.is_empty() {
+        println!();
+        println!("Off the critical path:");
+        for issue in others {
+            println!("  {}  slack {}", line(issue), hours(issue.slack));
+        }
+    }
+    if !output.cyclic.is_empty() {
+        println!();
+        println!("In a dependency cycle, or waiting on one (not scheduled):");
+        for issue in &output.cyclic {
+            println!("  {}", line(issue));
+        }
+    }
+}
+
+fn line(issue: &ScheduledIssue) -> String {
+    let marker = if issue.status == IssueStatus::InProgress { "●" } else { "○" };
+    let id = issue.short_id.as_deref().unwrap_or(&issue.id);
+    let estimate = if issue.estimate_hours.is_some() { String::new() } else { ", unestimated".to_string() };
+    let assignee = issue.assigned_to_agent.as_deref().map_or_else(String::new, |agent| format!(" → {agent}"));
+    format!(
+        "{marker} [{id}] {} ({}{estimate}; {} → {}){assignee}",
+        issue.title,
+        hours(issue.hours),
+        hours(issue.earliest_start),
+        hours(issue.earliest_finish)
+    )
+}
+
+/// `4h`, `1.5h`.
+fn hours(value: f64) -> String {
+    format!("{}h", (value * 100.0).round() / 100.0)
+}
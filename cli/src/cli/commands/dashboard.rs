@@ -0,0 +1,441 @@
+//! Static project dashboard (`sc export dashboard`).
+//!
+//! Writes a small site for the current project into a directory:
+//!
+//! | File             | Shows                                                    |
+//! |------------------|----------------------------------------------------------|
+//! | `index.html`     | Issue board, a column per status                         |
+//! | `plans.html`     | Each plan with the share of its issues closed            |
+//! | `decisions.html` | The latest decisions, newest first                       |
+//! | `activity.html`  | Timeline of the last `--days` days, grouped by day       |
+//! | `style.css`      | Shared by the pages                                      |
+//!
+//! The pages link to each other and to nothing else, so the directory can
+//! be published as is. The timeline is built from the records' own
+//! timestamps (issues opened and closed, plans created and completed,
+//! decisions, checkpoints, sessions started and ended) rather than the
+//! audit log, which only travels in the sync files with `sync_events`.
+//!
+//! `--from <dir>` reads a project's sync files (`.savecontext/`) into an
+//! in-memory database instead of opening one, so CI can rebuild the site
+//! from a checkout after each `sc sync export` without any setup.
+
+use super::share::{escape, time};
+use crate::cli::Database;
+use crate::config::resolve_project_path;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{IssueStatus, ItemCategory, Plan, PlanStatus, Project, ReviewStatus};
+use crate::storage::{ContextItem, Issue, SqliteStorage};
+use crate::sync::{Importer, MergeStrategy};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Decisions listed on `decisions.html`.
+const DECISION_LIMIT: usize = 100;
+/// Closed issues shown on the board, most recently closed first.
+const CLOSED_LIMIT: usize = 25;
+
+/// Output for export dashboard.
+#[derive(Serialize)]
+struct DashboardOutput {
+    dir: String,
+    project_path: String,
+    files: Vec<String>,
+    issues: usize,
+    plans: usize,
+    decisions: usize,
+    activity: usize,
+}
+
+json_schema!(DashboardOutput {
+    dir: String,
+    project_path: String,
+    files: Vec<String>,
+    issues: usize,
+    plans: usize,
+    decisions: usize,
+    activity: usize,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("export dashboard", schema_for::<DashboardOutput>())]
+}
+
+/// One entry of the activity timeline; `text` is HTML.
+struct Activity {
+    at: i64,
+    text: String,
+}
+
+/// Write the dashboard of the current project, or of the project whose
+/// sync files are in `from`, to `out`.
+///
+/// # Errors
+///
+/// Returns an error if the records cannot be read or the files written.
+pub fn export(out: &Path, from: Option<&Path>, days: u32, db: &mut Database, json: bool) -> Result<()> {
+    let loaded = from.map(load).transpose()?;
+    let (storage, project_path): (&SqliteStorage, String) = if let Some((storage, project_path)) = &loaded {
+        (storage, project_path.clone())
+    } else {
+        let storage = db.storage()?;
+        let project_path = resolve_project_path(storage, None)?;
+        (storage, project_path)
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let issues = storage.get_issues_by_project(&project_path)?;
+    let mut decisions: Vec<ContextItem> = storage
+        .get_context_items_by_project(&project_path)?
+        .into_iter()
+        .filter(|item| item.category == ItemCategory::Decision && item.review_status != ReviewStatus::Rejected)
+        .collect();
+    decisions.sort_by_key(|item| std::cmp::Reverse(item.updated_at));
+    let plans = storage.get_plans_by_project(&project_path)?;
+    let since = now - i64::from(days) * 86_400_000;
+    let activity = activity(storage, &project_path, &issues, &plans, &decisions, since)?;
+    decisions.truncate(DECISION_LIMIT);
+
+    let name = Path::new(&project_path).file_name().and_then(|n| n.to_str()).unwrap_or(&project_path).to_string();
+    let page = |file: &str, title: &str, body: &str| layout(&name, file, title, body, now);
+    let files = [
+        ("index.html", page("index.html", "Issues", &board(&issues))),
+        ("plans.html", page("plans.html", "Plans", &plans_page(&plans, &issues))),
+        ("decisions.html", page("decisions.html", "Decisions", &decisions_page(&decisions))),
+        ("activity.html", page("activity.html", &format!("Last {days} days"), &activity_page(&activity))),
+        ("style.css", STYLE.to_string()),
+    ];
+
+    if crate::is_dry_run() {
+        println!("Would write {} files to {}", files.len(), out.display());
+        return Ok(());
+    }
+    std::fs::create_dir_all(out)?;
+    for (file, content) in &files {
+        std::fs::write(out.join(file), content)?;
+    }
+
+    if json {
+        let output = DashboardOutput {
+            dir: out.display().to_string(),
+            project_path,
+            files: files.iter().map(|(file, _)| (*file).to_string()).collect(),
+            issues: issues.len(),
+            plans: plans.len(),
+            decisions: decisions.len(),
+            activity: activity.len(),
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if !crate::is_silent() {
+        println!(
+            "Wrote the dashboard of {project_path} to {}: {} issues, {} plans, {} decisions",
+            out.display(),
+            issues.len(),
+            plans.len(),
+            decisions.len()
+        );
+    }
+    Ok(())
+}
+
+/// Import the sync files in `dir` into a new in-memory database.
+fn load(dir: &Path) -> Result<(SqliteStorage, String)> {
+    let exported = crate::sync::exported_project(dir)
+        .map_err(|e| Error::Other(e.to_string()))?
+        .ok_or_else(|| Error::InvalidArgument(format!("No sync files with records in {}", dir.display())))?;
+    let mut storage = SqliteStorage::open_memory()?;
+    let name = Path::new(&exported.project_path).file_name().and_then(|n| n.to_str()).unwrap_or("project").to_string();
+    let mut project = Project::new(exported.project_path.clone(), name);
+    if let Some(id) = exported.project_id {
+        project.id = id;
+    }
+    storage.create_project(&project, &crate::config::default_actor())?;
+    Importer::new(&mut storage, MergeStrategy::PreferNewer)
+        .import_all(dir)
+        .map_err(|e| Error::Other(e.to_string()))?;
+    Ok((storage, exported.project_path))
+}
+
+fn activity(
+    storage: &SqliteStorage,
+    project_path: &str,
+    issues: &[Issue],
+    plans: &[Plan],
+    decisions: &[ContextItem],
+    since: i64,
+) -> Result<Vec<Activity>> {
+    let mut activity = Vec::new();
+    for issue in issues {
+        let label = issue_label(issue);
+        activity.push(Activity { at: issue.created_at, text: format!("Opened {label}") });
+        if let Some(closed) = issue.closed_at {
+            activity.push(Activity { at: closed, text: format!("Closed {label}") });
+        }
+    }
+    for plan in plans {
+        let title = escape(&plan.title);
+        activity.push(Activity { at: plan.created_at, text: format!("Plan created: {title}") });
+        if let Some(completed) = plan.completed_at {
+            activity.push(Activity { at: completed, text: format!("Plan completed: {title}") });
+        }
+    }
+    for item in decisions {
+        activity.push(Activity { at: item.created_at, text: format!("Decision: {}", escape(&item.key)) });
+    }
+    for checkpoint in storage.get_checkpoints_by_project(project_path)? {
+        activity.push(Activity { at: checkpoint.created_at, text: format!("Checkpoint {}", escape(&checkpoint.name)) });
+    }
+    for session in storage.get_sessions_by_project(project_path)? {
+        let name = escape(&session.name);
+        activity.push(Activity { at: session.created_at, text: format!("Session started: {name}") });
+        if let Some(ended) = session.ended_at {
+            activity.push(Activity { at: ended, text: format!("Session ended: {name}") });
+        }
+    }
+    activity.retain(|entry| entry.at >= since);
+    activity.sort_by_key(|entry| std::cmp::Reverse(entry.at));
+    Ok(activity)
+}
+
+fn issue_label(issue: &Issue) -> String {
+    format!("<b>{}</b> {}", escape(issue.short_id.as_deref().unwrap_or(&issue.id)), escape(&issue.title))
+}
+
+/// Board columns: the built-in statuses in workflow order, then any
+/// custom ones in the order they first appear.
+fn board(issues: &[Issue]) -> String {
+    let order = [
+        IssueStatus::Backlog,
+        IssueStatus::Open,
+        IssueStatus::InProgress,
+        IssueStatus::Blocked,
+        IssueStatus::Deferred,
+    ];
+    let mut columns: Vec<(IssueStatus, Vec<&Issue>)> = order.into_iter().map(|status| (status, Vec::new())).collect();
+    let mut closed: Vec<&Issue> = Vec::new();
+    for issue in issues {
+        if issue.status == IssueStatus::Closed {
+            closed.push(issue);
+        } else if let Some((_, column)) = columns.iter_mut().find(|(status, _)| *status == issue.status) {
+            column.push(issue);
+        } else {
+            columns.push((issue.status.clone(), vec![issue]));
+        }
+    }
+    closed.sort_by_key(|issue| std::cmp::Reverse(issue.closed_at.unwrap_or(issue.updated_at)));
+    let closed_total = closed.len();
+    closed.truncate(CLOSED_LIMIT);
+    columns.push((IssueStatus::Closed, closed));
+
+    let mut out = String::from("<div class=\"board\">\n");
+    for (status, mut column) in columns {
+        if column.is_empty() && matches!(status, IssueStatus::Backlog | IssueStatus::Deferred) {
+            continue;
+        }
+        if status != IssueStatus::Closed {
+            column.sort_by_key(|issue| (std::cmp::Reverse(issue.priority), std::cmp::Reverse(issue.updated_at)));
+        }
+        let count = if status == IssueStatus::Closed { closed_total } else { column.len() };
+        let _ = writeln!(out, "<section class=\"column\"><h2>{} <span class=\"count\">{count}</span></h2>", escape(&status.as_str().replace('_', " ")));
+        for issue in &column {
+            let mut meta = vec![issue.issue_type.to_string(), format!("P{}", issue.priority)];
+            meta.extend(issue.assigned_to_agent.as_deref().map(escape));
+            let _ = writeln!(out, "<div class=\"card\">{}<div class=\"meta\">{}</div></div>", issue_label(issue), meta.join(" · "));
+        }
+        if count > column.len() {
+            let _ = writeln!(out, "<p class=\"empty\">and {} more</p>", count - column.len());
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</div>\n");
+    out
+}
+
+fn plans_page(plans: &[Plan], issues: &[Issue]) -> String {
+    if plans.is_empty() {
+        return "<p class=\"empty\">No plans.</p>\n".to_string();
+    }
+    let mut out = String::new();
+    let mut plans: Vec<_> = plans.iter().collect();
+    // Active plans first, then drafts, then completed ones
+    plans.sort_by_key(|plan| {
+        let rank = match plan.status {
+            PlanStatus::Active => 0,
+            PlanStatus::Draft => 1,
+            PlanStatus::Completed => 2,
+        };
+        (rank, std::cmp::Reverse(plan.updated_at))
+    });
+    for plan in plans {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for issue in issues.iter().filter(|issue| issue.plan_id.as_deref() == Some(plan.id.as_str())) {
+            *counts.entry(issue.status.as_str()).or_default() += 1;
+        }
+        let total: usize = counts.values().sum();
+        let closed = counts.get("closed").copied().unwrap_or_default();
+        let in_progress = counts.get("in_progress").copied().unwrap_or_default();
+        let id = plan.short_id.as_deref().unwrap_or(&plan.id);
+        let _ = write!(
+            out,
+            "<div class=\"card\"><b>{}</b> {}<span class=\"badge\">{}</span>",
+            escape(id),
+            escape(&plan.title),
+            plan.status.as_str()
+        );
+        if total == 0 {
+            out.push_str("<div class=\"meta\">No issues</div>");
+        } else {
+            let _ = write!(
+                out,
+                "<div class=\"progress\"><span class=\"done\" style=\"width:{}%\"></span><span class=\"doing\" style=\"width:{}%\"></span></div>\
+                 <div class=\"meta\">{closed} of {total} issues closed · {in_progress} in progress</div>",
+                percent(closed, total),
+                percent(in_progress, total)
+            );
+        }
+        out.push_str("</div>\n");
+    }
+    out
+}
+
+/// `part` as a whole percentage of `total`, rounded down.
+fn percent(part: usize, total: usize) -> usize {
+    (part * 100).checked_div(total).unwrap_or_default()
+}
+
+fn decisions_page(decisions: &[ContextItem]) -> String {
+    if decisions.is_empty() {
+        return "<p class=\"empty\">No decisions.</p>\n".to_string();
+    }
+    let mut out = String::new();
+    for item in decisions {
+        let review = if item.awaits_review() { "<span class=\"badge\">awaiting review</span>" } else { "" };
+        let _ = writeln!(
+            out,
+            "<div class=\"card\"><b>{}</b>{review}<div class=\"meta\">{}</div><p class=\"text\">{}</p></div>",
+            escape(&item.key),
+            time(item.updated_at),
+            escape(&item.value)
+        );
+    }
+    out
+}
+
+fn activity_page(activity: &[Activity]) -> String {
+    if activity.is_empty() {
+        return "<p class=\"empty\">Nothing happened in this period.</p>\n".to_string();
+    }
+    let mut out = String::new();
+    let mut day = String::new();
+    for entry in activity {
+        let Some(at) = chrono::DateTime::from_timestamp_millis(entry.at) else { continue };
+        let date = at.format("%Y-%m-%d").to_string();
+        if date != day {
+            if !day.is_empty() {
+                out.push_str("</ul>\n");
+            }
+            let _ = writeln!(out, "<h2>{date}</h2>\n<ul class=\"timeline\">");
+            day = date;
+        }
+        let _ = writeln!(out, "<li><span class=\"meta\">{}</span> {}</li>", at.format("%H:%M"), entry.text);
+    }
+    out.push_str("</ul>\n");
+    out
+}
+
+const PAGES: [(&str, &str); 4] =
+    [("index.html", "Issues"), ("plans.html", "Plans"), ("decisions.html", "Decisions"), ("activity.html", "Activity")];
+
+/// Wrap a page's body in the shared header, navigation and footer.
+fn layout(project: &str, file: &str, title: &str, body: &str, generated_at: i64) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    let _ = writeln!(out, "<title>{} · {}</title>", escape(title), escape(project));
+    out.push_str("<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n<header>\n");
+    let _ = writeln!(out, "<h1>{}</h1>\n<nav>", escape(project));
+    for (page, label) in PAGES {
+        let current = if page == file { " aria-current=\"page\"" } else { "" };
+        let _ = write!(out, "<a href=\"{page}\"{current}>{label}</a>");
+    }
+    let _ = writeln!(out, "</nav>\n</header>\n<main>\n<h2 class=\"page\">{}</h2>", escape(title));
+    out.push_str(body);
+    let _ = writeln!(out, "</main>\n<footer>Generated by <code>sc export dashboard</code> on {}.</footer>", time(generated_at));
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+const STYLE: &str = "\
+:root{--fg:#1f2328;--muted:#656d76;--line:#d0d7de;--bg:#fff;--card:#f6f8fa;--done:#1a7f37;--doing:#9a6700}
+@media (prefers-color-scheme:dark){:root{--fg:#e6edf3;--muted:#8d96a0;--line:#30363d;--bg:#0d1117;--card:#161b22;--done:#3fb950;--doing:#d29922}}
+*{box-sizing:border-box}
+body{margin:0 auto;max-width:1200px;padding:1.5rem 1rem;font:15px/1.5 system-ui,sans-serif;color:var(--fg);background:var(--bg)}
+header{display:flex;flex-wrap:wrap;align-items:baseline;gap:1rem;border-bottom:1px solid var(--line);padding-bottom:.5rem}
+h1{margin:0;font-size:1.4rem}
+nav a{margin-right:1rem;color:var(--muted);text-decoration:none}
+nav a[aria-current]{color:var(--fg);font-weight:600}
+h2{font-size:1rem;margin:1.5rem 0 .5rem}
+h2.page{font-size:1.2rem}
+.board{display:flex;gap:1rem;overflow-x:auto;align-items:flex-start}
+.column{flex:0 0 260px}
+.column h2{margin-top:0;text-transform:capitalize}
+.count{color:var(--muted);font-weight:normal}
+.card{margin:.5rem 0;padding:.6rem .8rem;background:var(--card);border:1px solid var(--line);border-radius:6px}
+.meta{color:var(--muted);font-size:.85rem}
+.text{margin:.35rem 0 0;white-space:pre-wrap;overflow-wrap:anywhere}
+.badge{display:inline-block;margin-left:.4rem;padding:0 .45rem;border:1px solid var(--line);border-radius:1em;font-size:.75rem;color:var(--muted)}
+.progress{display:flex;height:8px;margin:.5rem 0 .25rem;background:var(--line);border-radius:4px;overflow:hidden}
+.progress .done{background:var(--done)}
+.progress .doing{background:var(--doing)}
+.timeline{list-style:none;padding:0;margin:0}
+.timeline li{padding:.2rem 0}
+.empty{color:var(--muted)}
+footer{margin-top:3rem;color:var(--muted);font-size:.8rem}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, status: IssueStatus) -> Issue {
+        Issue {
+            id: id.to_string(),
+            short_id: None,
+            project_path: "/test".to_string(),
+            title: format!("<{id}>"),
+            description: None,
+            details: None,
+            status,
+            priority: 2,
+            issue_type: crate::model::IssueType::default(),
+            plan_id: None,
+            created_by_agent: None,
+            assigned_to_agent: None,
+            created_at: 0,
+            updated_at: 0,
+            closed_at: None,
+            subproject: None,
+            estimate_hours: None,
+        }
+    }
+
+    #[test]
+    fn test_board_columns() {
+        let issues = [
+            issue("a", IssueStatus::Open),
+            issue("b", IssueStatus::Custom("review".to_string())),
+            issue("c", IssueStatus::Closed),
+        ];
+        let html = board(&issues);
+        let headings: Vec<&str> = html.match_indices("<h2>").map(|(i, _)| &html[i + 4..i + 16]).collect();
+        assert_eq!(headings.len(), 5, "open, in_progress, blocked, review, closed: {headings:?}");
+        assert!(html.find(">review <").unwrap() < html.find(">closed <").unwrap());
+        assert!(html.contains("&lt;a&gt;"));
+        assert_eq!(percent(1, 3), 33);
+        assert_eq!(percent(0, 0), 0);
+    }
+}
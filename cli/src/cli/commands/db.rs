@@ -0,0 +1,284 @@
+//! Database maintenance commands (`sc db ...`).
+//!
+//! `sc db indexes` lists every index with the row counts and selectivity
+//! the query planner sees in `sqlite_stat1`, then suggests indexes for the
+//! predicates of the commands you actually run, based on the local usage
+//! statistics recorded for `sc stats`.
+//!
+//! `sc db stats` reports row counts and on-disk size per table, the
+//! largest context items and sessions, how much space embeddings and
+//! compressed values take, the WAL size, how often writers had to wait
+//! for each other this week, and how much each table grew since the
+//! previous run — the numbers to look at before archiving old sessions or
+//! running `VACUUM`.
+
+use crate::cli::{Database, DbCommands};
+use crate::config::value_compression_threshold;
+use crate::error::Result;
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::indexes::{suggest_indexes, IndexInfo, IndexSuggestion};
+use crate::storage::stats::DbStats;
+use crate::storage::SqliteStorage;
+use crate::sync::format_size;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+struct DbStatsOutput {
+    path: PathBuf,
+    stats: DbStats,
+    data_bytes: i64,
+    data_bytes_change: Option<i64>,
+    wal_bytes: u64,
+    compression_threshold_bytes: usize,
+}
+
+#[derive(Serialize)]
+struct IndexesOutput {
+    analyzed: bool,
+    has_stats: bool,
+    indexes: Vec<IndexInfo>,
+    suggestions: Vec<IndexSuggestion>,
+    usage_invocations: usize,
+}
+
+json_schema!(DbStatsOutput {
+    path: PathBuf,
+    stats: DbStats,
+    data_bytes: i64,
+    data_bytes_change: Option<i64>,
+    wal_bytes: u64,
+    compression_threshold_bytes: usize,
+});
+json_schema!(IndexesOutput {
+    analyzed: bool,
+    has_stats: bool,
+    indexes: Vec<IndexInfo>,
+    suggestions: Vec<IndexSuggestion>,
+    usage_invocations: usize,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("db indexes", schema_for::<IndexesOutput>()),
+        ("db stats", schema_for::<DbStatsOutput>()),
+    ]
+}
+
+/// Execute a db subcommand.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or queried.
+pub fn execute(command: &DbCommands, db: &mut Database, json: bool) -> Result<()> {
+    let db_path = db.require_path()?.to_path_buf();
+    let storage = db.storage()?;
+
+    match command {
+        DbCommands::Indexes { analyze, days } => indexes(storage, *analyze, *days, json),
+        DbCommands::Stats { top } => stats(storage, &db_path, *top, json),
+    }
+}
+
+fn stats(storage: &SqliteStorage, db_path: &Path, top: usize, json: bool) -> Result<()> {
+    let stats = storage.db_stats(top)?;
+    let mut wal_path = db_path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    let wal_bytes = std::fs::metadata(&wal_path).map_or(0, |m| m.len());
+    let threshold = value_compression_threshold();
+    if !crate::is_dry_run() {
+        storage.save_db_stats_snapshot(&stats)?;
+    }
+
+    if json {
+        let output = DbStatsOutput {
+            path: db_path.to_path_buf(),
+            data_bytes: stats.data_bytes(),
+            data_bytes_change: stats.data_bytes_change(),
+            stats,
+            wal_bytes,
+            compression_threshold_bytes: threshold,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    print_stats(&stats, db_path, wal_bytes, threshold);
+    Ok(())
+}
+
+fn print_stats(stats: &DbStats, db_path: &Path, wal_bytes: u64, threshold: usize) {
+    let data_bytes = stats.data_bytes();
+    println!("Database: {}", db_path.display());
+    println!(
+        "  File: {}  WAL: {}  Free: {}",
+        size(stats.file_bytes),
+        format_size(wal_bytes),
+        size(stats.free_bytes)
+    );
+    if let (Some(previous_at), Some(change)) = (stats.previous_at, stats.data_bytes_change()) {
+        let when = chrono::DateTime::from_timestamp_millis(previous_at)
+            .map_or_else(String::new, |dt| dt.format("%Y-%m-%d %H:%M").to_string());
+        println!("  Growth since {when}: {}", signed_size(change));
+    }
+
+    println!();
+    println!("  {:<28} {:>10} {:>10}  Change", "Table", "Rows", "Size");
+    for table in stats.tables.iter().filter(|t| t.rows > 0 || t.bytes_change.unwrap_or(0) != 0) {
+        let mut change = Vec::new();
+        if let Some(rows) = table.rows_change.filter(|r| *r != 0) {
+            change.push(format!("{rows:+} row(s)"));
+        }
+        if let Some(bytes) = table.bytes_change.filter(|b| *b != 0) {
+            change.push(signed_size(bytes));
+        }
+        let line = format!("  {:<28} {:>10} {:>10}  {}", table.name, table.rows, size(table.bytes), change.join(", "));
+        println!("{}", line.trim_end());
+    }
+
+    println!();
+    println!(
+        "Embeddings: {} ({}% of data)",
+        size(stats.embedding_bytes),
+        percent(stats.embedding_bytes, data_bytes)
+    );
+    let compression = &stats.compression;
+    if threshold == 0 {
+        println!("Value compression: off (value_compression_threshold is 0)");
+    } else if compression.compressed_items == 0 {
+        println!("Value compression: values over {}; none stored yet", format_size(threshold as u64));
+    } else {
+        println!(
+            "Value compression: {} item(s), {} stored as {} (saved {}, {}%)",
+            compression.compressed_items,
+            size(compression.original_bytes),
+            size(compression.stored_bytes),
+            size(compression.saved_bytes()),
+            percent(compression.saved_bytes(), compression.original_bytes)
+        );
+    }
+
+    let contention = &stats.write_contention;
+    if contention.waits == 0 && contention.busy_retries == 0 {
+        println!("Write contention: none in the last {} days", contention.days);
+    } else {
+        println!(
+            "Write contention ({} days): {} write(s) waited, {}ms on average, {}ms at most; {} busy retries, {} timeouts",
+            contention.days,
+            contention.waits,
+            contention.wait_ms / contention.waits.max(1),
+            contention.max_wait_ms,
+            contention.busy_retries,
+            contention.timeouts
+        );
+    }
+
+    if !stats.largest_items.is_empty() {
+        println!();
+        println!("Largest items:");
+        for item in &stats.largest_items {
+            let compressed = if item.compressed { ", compressed" } else { "" };
+            println!("  {:>10}  {}  ({}{compressed})", size(item.size), item.key, item.session_name);
+        }
+    }
+    if !stats.largest_sessions.is_empty() {
+        println!();
+        println!("Largest sessions:");
+        for session in &stats.largest_sessions {
+            println!("  {:>10}  {}  ({} item(s))", size(session.size), session.name, session.items);
+        }
+    }
+
+    if stats.free_bytes > stats.file_bytes / 4 {
+        println!();
+        println!("A quarter of the file is free pages; VACUUM would return {}.", size(stats.free_bytes));
+    }
+}
+
+/// `part` as a whole percentage of `total`, rounded down.
+fn percent(part: i64, total: i64) -> i64 {
+    (part * 100).checked_div(total).unwrap_or_default()
+}
+
+fn size(bytes: i64) -> String {
+    format_size(bytes.unsigned_abs())
+}
+
+fn signed_size(bytes: i64) -> String {
+    let sign = if bytes < 0 { '-' } else { '+' };
+    format!("{sign}{}", size(bytes))
+}
+
+fn indexes(storage: &SqliteStorage, analyze: bool, days: u32, json: bool) -> Result<()> {
+    let analyzed = analyze && !crate::is_dry_run();
+    if analyzed {
+        storage.analyze()?;
+    }
+
+    let indexes = storage.list_indexes()?;
+    let since = chrono::Utc::now().timestamp_millis() - i64::from(days) * 86_400_000;
+    let usage = storage.get_usage_stats(since, 1000)?;
+    let suggestions = suggest_indexes(&indexes, &usage.commands);
+    let has_stats = indexes.iter().any(|i| i.rows.is_some());
+
+    if json {
+        let output = IndexesOutput {
+            analyzed,
+            has_stats,
+            indexes,
+            suggestions,
+            usage_invocations: usage.total_invocations,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if analyze && crate::is_dry_run() {
+        println!("Would run ANALYZE.");
+    }
+
+    println!("Indexes ({}):", indexes.len());
+    let mut current_table = "";
+    for index in &indexes {
+        if index.table != current_table {
+            println!("  {}", index.table);
+            current_table = &index.table;
+        }
+        let columns = format!("({})", index.columns.join(", "));
+        let unique = if index.unique { " unique" } else { "" };
+        let stats = match (index.rows, index.rows_per_key) {
+            (Some(rows), Some(per_key)) => format!("  {rows} rows, ~{per_key}/key"),
+            (Some(rows), None) => format!("  {rows} rows"),
+            _ => String::new(),
+        };
+        println!("    {:<40} {columns}{unique}{stats}", index.name);
+    }
+    if !has_stats {
+        println!();
+        println!("No planner statistics yet. Run `sc db indexes --analyze` to collect them.");
+    }
+
+    println!();
+    if usage.total_invocations == 0 {
+        println!("No usage recorded in the last {days} days; nothing to suggest (see `sc stats`).");
+    } else if suggestions.is_empty() {
+        println!(
+            "Hot predicates are all indexed ({} command(s) in the last {days} days).",
+            usage.total_invocations
+        );
+    } else {
+        println!("Suggested indexes (from usage in the last {days} days):");
+        for suggestion in &suggestions {
+            println!(
+                "  {}  -- {} ({} run(s))",
+                suggestion.sql,
+                suggestion.commands.join(", "),
+                suggestion.uses
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! Editing a context item from an editor (`sc edit-context`).
+//!
+//! In a terminal, `sc edit-context <key>` opens the item's value in
+//! `$VISUAL`/`$EDITOR` and saves it only if no one changed the item in the
+//! meantime. Editor plugins keep the text in their own buffer instead:
+//! `--emit-lsp-like-json` prints the item shaped like an LSP
+//! `TextDocumentItem`, with `snake_case` names:
+//!
+//! ```json
+//! {
+//!   "text_document": { "uri": "savecontext://item/<session>/<key>", "language_id": "markdown", "version": 1718000000000, "text": "..." },
+//!   "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 3, "character": 12 } },
+//!   "metadata": { "key": "...", "category": "decision", ... },
+//!   "save_args": ["update", "<key>", "--if-updated-at", "1718000000000", "--value"]
+//! }
+//! ```
+//!
+//! `version` is the item's `updated_at`; `range` spans the whole text, in
+//! UTF-16 code units as LSP counts them. Appending the buffer's text to
+//! `save_args` gives the command that writes it back, failing with
+//! `CONFLICT` if the item changed since it was opened.
+
+use super::vault::item_tags;
+use crate::cli::{Database, InputArgs, UpdateArgs};
+use crate::config::resolve_session_or_suggest;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemCategory, ItemPriority, ReviewStatus, TrustLevel};
+use crate::storage::ContextItem;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write as _;
+
+/// Output for edit-context --emit-lsp-like-json.
+#[derive(Serialize)]
+struct EditContextOutput {
+    text_document: TextDocument,
+    /// The whole text, for replacing a buffer's contents.
+    range: Range,
+    metadata: ItemMetadata,
+    /// Arguments that save the buffer once its text is appended.
+    save_args: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct TextDocument {
+    uri: String,
+    language_id: String,
+    version: i64,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+/// Zero-based line, and offset into it in UTF-16 code units.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+/// What an editor shows beside the text.
+#[derive(Serialize)]
+struct ItemMetadata {
+    key: String,
+    session_id: String,
+    category: ItemCategory,
+    priority: ItemPriority,
+    channel: Option<String>,
+    tags: Vec<String>,
+    trust: TrustLevel,
+    review_status: ReviewStatus,
+    updated_at: i64,
+}
+
+json_schema!(EditContextOutput { text_document: TextDocument, range: Range, metadata: ItemMetadata, save_args: Vec<String> });
+json_schema!(TextDocument { uri: String, language_id: String, version: i64, text: String });
+json_schema!(Range { start: Position, end: Position });
+json_schema!(Position { line: usize, character: usize });
+json_schema!(ItemMetadata {
+    key: String,
+    session_id: String,
+    category: ItemCategory,
+    priority: ItemPriority,
+    channel: Option<String>,
+    tags: Vec<String>,
+    trust: TrustLevel,
+    review_status: ReviewStatus,
+    updated_at: i64,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("edit-context", schema_for::<EditContextOutput>())]
+}
+
+/// Execute the edit-context command.
+///
+/// # Errors
+///
+/// Returns an error if the item doesn't exist, the editor fails, or the
+/// item changed while it was being edited.
+pub fn execute(
+    key: &str,
+    emit_lsp_like_json: bool,
+    db: &mut Database,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+    let session_id = resolve_session_or_suggest(session_id, storage)?;
+    let item = match storage.get_item_id_by_key(&session_id, key)? {
+        Some(id) => storage.get_context_item(&id)?,
+        None => None,
+    }
+    .ok_or_else(|| Error::Other(format!("Context item not found: {key}")))?;
+
+    if emit_lsp_like_json {
+        println!("{}", serde_json::to_string(&document(&item))?);
+        return Ok(());
+    }
+
+    let edited = crate::cli::input::edit(&item.value, &format!("value for {key}"))?;
+    if edited == item.value {
+        if !crate::is_silent() && !json {
+            println!("No changes: {key}");
+        }
+        return Ok(());
+    }
+    let args = UpdateArgs {
+        key: key.to_string(),
+        value: Some(edited),
+        category: None,
+        priority: None,
+        channel: None,
+        trust: None,
+        if_updated_at: Some(item.updated_at),
+        input: InputArgs::default(),
+    };
+    super::context::execute_update(&args, db, actor, Some(&session_id), json)
+}
+
+fn document(item: &ContextItem) -> EditContextOutput {
+    EditContextOutput {
+        text_document: TextDocument {
+            uri: format!("savecontext://item/{}/{}", item.session_id, uri_escape(&item.key)),
+            language_id: "markdown".to_string(),
+            version: item.updated_at,
+            text: item.value.clone(),
+        },
+        range: Range { start: Position { line: 0, character: 0 }, end: end_position(&item.value) },
+        metadata: ItemMetadata {
+            key: item.key.clone(),
+            session_id: item.session_id.clone(),
+            category: item.category.clone(),
+            priority: item.priority,
+            channel: item.channel.clone(),
+            tags: item_tags(item),
+            trust: item.trust,
+            review_status: item.review_status,
+            updated_at: item.updated_at,
+        },
+        save_args: vec![
+            "update".to_string(),
+            item.key.clone(),
+            "--if-updated-at".to_string(),
+            item.updated_at.to_string(),
+            "--value".to_string(),
+        ],
+    }
+}
+
+/// Position just past the last character of `text`.
+fn end_position(text: &str) -> Position {
+    let line = text.matches('\n').count();
+    let last = text.rsplit('\n').next().unwrap_or_default();
+    Position { line, character: last.encode_utf16().count() }
+}
+
+/// Percent-encode everything but unreserved URI characters.
+fn uri_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            escaped.push(char::from(byte));
+        } else {
+            let _ = write!(escaped, "%{byte:02X}");
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_positions_and_uri() {
+        assert_eq!(end_position(""), Position { line: 0, character: 0 });
+        assert_eq!(end_position("Use JWT\nwith 🔑 rotation\n"), Position { line: 2, character: 0 });
+        // The key is two UTF-16 code units
+        assert_eq!(end_position("Use JWT\nwith 🔑"), Position { line: 1, character: 7 });
+        assert_eq!(uri_escape("auth/jwt decision"), "auth%2Fjwt%20decision");
+    }
+}
@@ -3,31 +3,53 @@
 //! Provides CLI commands for managing embedding providers:
 //! - `status` - Show provider availability and configuration
 //! - `configure` - Configure embedding provider settings
-//! - `backfill` - Generate embeddings for existing context items
+//! - `backfill` - Generate embeddings for existing context items, several
+//!   requests at a time, resuming where an interrupted run stopped
 //! - `test` - Test provider connectivity
+//! - `migrate` - Move stored embeddings to the active provider and model
+//! - `benchmark` - Compare providers' speed and retrieval quality
+//!
+//! Quality-tier embeddings are cached by provider, model and a hash of the
+//! chunk text, so re-embedding unchanged text never calls the provider.
+//! `--no-cache` bypasses the cache for one run.
 
-use crate::cli::EmbeddingsCommands;
-use crate::config::resolve_db_path;
+use crate::cli::{Database, EmbeddingsCommands};
+use crate::config::resolve_project_path;
+use crate::embeddings::benchmark::{self, BenchmarkResult, LabeledPair};
 use crate::embeddings::{
-    chunk_text, create_embedding_provider, detect_available_providers, get_embedding_settings,
-    is_embeddings_enabled, prepare_item_text, reset_embedding_settings, save_embedding_settings,
-    ChunkConfig, EmbeddingProviderType, EmbeddingSettings,
+    chunk_text, create_available_providers, create_embedding_provider, detect_available_providers, embed_with_retry,
+    get_embedding_settings, is_embeddings_enabled, prepare_item_text, reset_embedding_settings,
+    save_embedding_settings, BoxedProvider, ChunkConfig, EmbeddingEntity, EmbeddingProviderType, EmbeddingResult,
+    EmbeddingSettings, Limits, RateLimiter,
 };
 use tracing::{debug, info, warn};
 use crate::error::{Error, Result};
-use crate::storage::SqliteStorage;
-use serde::Serialize;
-use std::path::PathBuf;
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::storage::sqlite::EmbeddingCacheStats;
+use crate::storage::{EmbeddingModelUsage, SqliteStorage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
 
 /// Output for embeddings status command.
 #[derive(Serialize)]
 struct StatusOutput {
     enabled: bool,
     configured_provider: Option<String>,
+    /// `embeddings.providers`, when more than one is configured.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failover_chain: Vec<String>,
     available_providers: Vec<ProviderStatus>,
     active_provider: Option<ActiveProviderInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stats: Option<EmbeddingStatsOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache: Option<EmbeddingCacheStats>,
+    /// Models behind the stored embeddings.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    models: Vec<EmbeddingModelUsage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    archived_models: Vec<EmbeddingModelUsage>,
 }
 
 #[derive(Serialize)]
@@ -84,6 +106,19 @@ struct BackfillOutput {
     model: String,
 }
 
+/// Output for migrate command.
+#[derive(Serialize)]
+struct MigrateOutput {
+    provider: String,
+    model: String,
+    /// Other models whose embeddings were taken out of search.
+    retired_models: Vec<EmbeddingModelUsage>,
+    retired: usize,
+    kept: bool,
+    restored: usize,
+    dry_run: bool,
+}
+
 /// Output for upgrade-quality command.
 #[derive(Serialize)]
 struct UpgradeQualityOutput {
@@ -95,18 +130,86 @@ struct UpgradeQualityOutput {
     total_eligible: usize,
 }
 
+/// Output for benchmark command.
+#[derive(Serialize)]
+struct BenchmarkOutput<'a> {
+    project: &'a str,
+    samples: usize,
+    /// Rank a query's document must reach to count towards `recall`.
+    recall_at: usize,
+    results: &'a [BenchmarkResult],
+}
+
+json_schema!(StatusOutput {
+    enabled: bool,
+    configured_provider: Option<String>,
+    failover_chain: Vec<String> = optional,
+    available_providers: Vec<ProviderStatus>,
+    active_provider: Option<ActiveProviderInfo>,
+    stats: Option<EmbeddingStatsOutput>,
+    cache: Option<EmbeddingCacheStats>,
+    models: Vec<EmbeddingModelUsage> = optional,
+    archived_models: Vec<EmbeddingModelUsage> = optional,
+});
+json_schema!(EmbeddingStatsOutput { items_with_embeddings: usize, items_without_embeddings: usize, total_items: usize });
+json_schema!(ProviderStatus { name: String, available: bool, model: Option<String>, dimensions: Option<usize> });
+json_schema!(ActiveProviderInfo { name: String, model: String, dimensions: usize, max_chars: usize });
+json_schema!(TestOutput {
+    success: bool,
+    provider: String,
+    model: String,
+    dimensions: usize,
+    input_text: String,
+    embedding_sample: Vec<f32>,
+    error: Option<String>,
+});
+json_schema!(ConfigureOutput { success: bool, message: String, settings: EmbeddingSettings });
+json_schema!(BackfillOutput { processed: usize, skipped: usize, errors: usize, provider: String, model: String });
+json_schema!(MigrateOutput {
+    provider: String,
+    model: String,
+    retired_models: Vec<EmbeddingModelUsage>,
+    retired: usize,
+    kept: bool,
+    restored: usize,
+    dry_run: bool,
+});
+json_schema!(UpgradeQualityOutput {
+    upgraded: usize,
+    skipped: usize,
+    errors: usize,
+    provider: String,
+    model: String,
+    total_eligible: usize,
+});
+json_schema!(BenchmarkOutput<'a> { project: &'a str, samples: usize, recall_at: usize, results: &'a [BenchmarkResult] });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("embeddings status", schema_for::<StatusOutput>()),
+        // Without changes, configure shows the status instead.
+        ("embeddings configure", one_of(&[schema_for::<ConfigureOutput>(), schema_for::<StatusOutput>()])),
+        ("embeddings backfill", schema_for::<BackfillOutput>()),
+        ("embeddings test", schema_for::<TestOutput>()),
+        ("embeddings migrate", schema_for::<MigrateOutput>()),
+        ("embeddings upgrade-quality", schema_for::<UpgradeQualityOutput>()),
+        ("embeddings benchmark", schema_for::<BenchmarkOutput<'_>>()),
+    ]
+}
+
 /// Execute embeddings command.
-pub fn execute(command: EmbeddingsCommands, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
+pub fn execute(command: EmbeddingsCommands, db: &mut Database, json: bool) -> Result<()> {
     // Create tokio runtime for async operations
     let rt = tokio::runtime::Runtime::new()
         .map_err(|e| Error::Other(format!("Failed to create async runtime: {e}")))?;
 
-    rt.block_on(async { execute_async(command, db_path, json).await })
+    rt.block_on(async { execute_async(command, db, json).await })
 }
 
-async fn execute_async(command: EmbeddingsCommands, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
+async fn execute_async(command: EmbeddingsCommands, db: &mut Database, json: bool) -> Result<()> {
     match command {
-        EmbeddingsCommands::Status => execute_status(db_path, json).await,
+        EmbeddingsCommands::Status => execute_status(db, json).await,
         EmbeddingsCommands::Configure {
             provider,
             enable,
@@ -114,45 +217,57 @@ async fn execute_async(command: EmbeddingsCommands, db_path: Option<&PathBuf>, j
             model,
             endpoint,
             token,
-        } => execute_configure(db_path, provider, enable, disable, model, endpoint, token, json).await,
+            providers,
+        } => execute_configure(db, provider, enable, disable, model, endpoint, token, providers, json).await,
         EmbeddingsCommands::Backfill {
             limit,
             session,
             force,
-        } => execute_backfill(db_path, limit, session, force, json).await,
+            entities,
+            concurrency,
+            no_cache,
+        } => execute_backfill(db, limit, session, force, &entities, concurrency, !no_cache, json).await,
         EmbeddingsCommands::Test { text } => execute_test(&text, json).await,
-        EmbeddingsCommands::ProcessPending { limit, quiet } => {
-            execute_process_pending(db_path, limit, quiet).await
+        EmbeddingsCommands::ProcessPending { limit, quiet, no_cache } => {
+            execute_process_pending(db, limit, quiet, !no_cache).await
         }
-        EmbeddingsCommands::UpgradeQuality { limit, session } => {
-            execute_upgrade_quality(db_path, limit, session, json).await
+        EmbeddingsCommands::UpgradeQuality { limit, session, no_cache } => {
+            execute_upgrade_quality(db, limit, session, !no_cache, json).await
+        }
+        EmbeddingsCommands::Migrate { keep } => execute_migrate(db, keep, json).await,
+        EmbeddingsCommands::Benchmark { sample, providers } => {
+            execute_benchmark(db, sample, &providers, json).await
         }
     }
 }
 
 /// Show embeddings status and provider availability.
-async fn execute_status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
+async fn execute_status(db: &mut Database, json: bool) -> Result<()> {
     let enabled = is_embeddings_enabled();
     let settings = get_embedding_settings().unwrap_or_default();
     let detection = detect_available_providers().await;
 
     // Get embedding stats from database
-    let stats = if let Some(path) = resolve_db_path(db_path.map(|p| p.as_path())) {
-        if path.exists() {
-            SqliteStorage::open(&path)
-                .ok()
-                .and_then(|storage| storage.count_embedding_status(None).ok())
-                .map(|s| EmbeddingStatsOutput {
-                    items_with_embeddings: s.with_embeddings,
-                    items_without_embeddings: s.without_embeddings,
-                    total_items: s.with_embeddings + s.without_embeddings,
-                })
-        } else {
-            None
-        }
-    } else {
-        None
-    };
+    let storage = db.existing();
+    let stats = storage
+        .as_ref()
+        .and_then(|storage| storage.count_embedding_status(None).ok())
+        .map(|s| EmbeddingStatsOutput {
+            items_with_embeddings: s.with_embeddings,
+            items_without_embeddings: s.without_embeddings,
+            total_items: s.with_embeddings + s.without_embeddings,
+        });
+    let cache = storage
+        .as_ref()
+        .and_then(|storage| storage.embedding_cache_stats().ok());
+    let models = storage
+        .as_ref()
+        .and_then(|storage| storage.embedding_models(false).ok())
+        .unwrap_or_default();
+    let archived_models = storage
+        .as_ref()
+        .and_then(|storage| storage.embedding_models(true).ok())
+        .unwrap_or_default();
 
     // Try to create the active provider
     let active_provider = if enabled {
@@ -165,6 +280,12 @@ async fn execute_status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
         .as_ref()
         .and_then(|s| s.provider.as_ref())
         .map(|p| p.to_string());
+    let failover_chain: Vec<String> = settings
+        .as_ref()
+        .and_then(|s| s.providers.as_ref())
+        .filter(|chain| chain.len() > 1)
+        .map(|chain| chain.iter().map(ToString::to_string).collect())
+        .unwrap_or_default();
 
     // Build provider status list
     let mut providers = Vec::new();
@@ -205,6 +326,7 @@ async fn execute_status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
         dimensions: if hf_available { Some(384) } else { None },
     });
 
+    let members = active_provider.as_ref().map(BoxedProvider::members).unwrap_or_default();
     let active_info = active_provider.as_ref().map(|p| {
         let info = p.info();
         ActiveProviderInfo {
@@ -219,9 +341,13 @@ async fn execute_status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
         let output = StatusOutput {
             enabled,
             configured_provider,
+            failover_chain,
             available_providers: providers,
             active_provider: active_info,
             stats,
+            cache,
+            models,
+            archived_models,
         };
         println!("{}", serde_json::to_string(&output)?);
     } else {
@@ -232,6 +358,9 @@ async fn execute_status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
         if let Some(ref p) = configured_provider {
             println!("Configured Provider: {p}");
         }
+        if !failover_chain.is_empty() {
+            println!("Failover Chain: {}", failover_chain.join(" → "));
+        }
         println!();
 
         println!("Available Providers:");
@@ -271,6 +400,37 @@ async fn execute_status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
                 println!("Run 'sc embeddings backfill' to generate missing embeddings.");
             }
         }
+
+        if let Some(ref c) = cache {
+            println!();
+            println!("Cache:");
+            println!("  Entries:  {}", c.entries);
+            println!("  Hits:     {}", c.hits);
+            println!("  Misses:   {}", c.misses);
+            println!("  Hit rate: {:.1}%", c.hit_rate * 100.0);
+        }
+
+        if !models.is_empty() || !archived_models.is_empty() {
+            println!();
+            println!("Stored Models:");
+            for m in &models {
+                println!("  {}/{} ({}d): {} chunks, {} records", m.provider, m.model, m.dimensions, m.chunks, m.entities);
+            }
+            for m in &archived_models {
+                println!(
+                    "  {}/{} ({}d): {} chunks, {} records (archived)",
+                    m.provider, m.model, m.dimensions, m.chunks, m.entities
+                );
+            }
+            let mismatched = !members.is_empty()
+                && models
+                    .iter()
+                    .any(|m| !members.iter().any(|p| p.name == m.provider && p.model == m.model));
+            if mismatched {
+                println!();
+                println!("Stored embeddings don't match the active model. Run 'sc embeddings migrate'.");
+            }
+        }
     }
 
     Ok(())
@@ -279,13 +439,14 @@ async fn execute_status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
 /// Configure embedding settings.
 #[allow(clippy::fn_params_excessive_bools)]
 async fn execute_configure(
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     provider: Option<String>,
     enable: bool,
     disable: bool,
     model: Option<String>,
     endpoint: Option<String>,
     token: Option<String>,
+    providers: Vec<EmbeddingProviderType>,
     json: bool,
 ) -> Result<()> {
     // Get current settings or create defaults
@@ -375,9 +536,17 @@ async fn execute_configure(
         changed = true;
     }
 
+    // Handle failover chain
+    if !providers.is_empty() {
+        settings.provider = Some(providers[0]);
+        settings.providers = Some(providers);
+        messages.push("Failover chain configured");
+        changed = true;
+    }
+
     if !changed {
         // If no changes, just show current config
-        return execute_status(db_path, json).await;
+        return execute_status(db, json).await;
     }
 
     // Save settings
@@ -395,29 +564,203 @@ async fn execute_configure(
     } else {
         println!("Configuration updated: {message}");
         println!();
-        execute_status(db_path, false).await?;
+        execute_status(db, false).await?;
     }
 
     Ok(())
 }
 
-/// Backfill embeddings for existing context items.
+/// `embeddings_meta` key holding the progress of an unfinished backfill.
+const BACKFILL_PROGRESS_KEY: &str = "backfill_progress";
+
+/// Progress of a backfill, saved after every item.
+///
+/// Finished items drop out of the next run's query on their own; this only
+/// carries the totals across, so a run that was interrupted reports and
+/// draws its progress against the original workload.
+#[derive(Serialize, Deserialize)]
+struct BackfillProgress {
+    provider: String,
+    model: String,
+    started_at: i64,
+    total: usize,
+    done: usize,
+}
+
+/// One-line progress bar on stderr, only drawn for terminals.
+struct ProgressBar {
+    total: usize,
+    done: usize,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 30;
+
+    fn start(total: usize, done: usize, json: bool) -> Option<Self> {
+        (!json && !crate::is_silent() && std::io::IsTerminal::is_terminal(&std::io::stderr()))
+            .then_some(Self { total, done })
+    }
+
+    fn draw(&self) {
+        let filled = (self.done * Self::WIDTH).checked_div(self.total).unwrap_or(Self::WIDTH);
+        eprint!(
+            "\r\x1b[K[{}{}] {}/{} items",
+            "#".repeat(filled),
+            ".".repeat(Self::WIDTH - filled.min(Self::WIDTH)),
+            self.done,
+            self.total
+        );
+    }
+
+    /// Print a line above the bar.
+    fn println(&self, line: &str) {
+        eprint!("\r\x1b[K");
+        println!("{line}");
+        self.draw();
+    }
+}
+
+/// An item whose chunks are in flight.
+struct PendingItem {
+    entity: EmbeddingEntity,
+    id: String,
+    key: String,
+    chunks: usize,
+    remaining: usize,
+    errors: usize,
+}
+
+/// State of a running backfill, updated as chunks come back.
+struct BackfillRun<'a> {
+    storage: &'a mut SqliteStorage,
+    use_cache: bool,
+    json: bool,
+    items: Vec<PendingItem>,
+    progress: BackfillProgress,
+    bar: Option<ProgressBar>,
+    processed: usize,
+    errors: usize,
+}
+
+impl BackfillRun<'_> {
+    /// Store one chunk's embedding (or count its failure), and report the
+    /// item once its last chunk is in.
+    fn finish_chunk(
+        &mut self,
+        item_idx: usize,
+        chunk_idx: usize,
+        text: &str,
+        result: Result<EmbeddingResult>,
+        cached: bool,
+    ) {
+        let item = &self.items[item_idx];
+        let stored = result.and_then(|embedded| {
+            if self.use_cache && !cached {
+                if let Err(e) = self.storage.cache_embedding(&embedded.provider, &embedded.model, text, &embedded.embedding) {
+                    debug!(error = %e, "Embedding cache write failed");
+                }
+            }
+            let chunk_index = i32::try_from(chunk_idx).unwrap_or(i32::MAX);
+            if item.entity == EmbeddingEntity::ContextItem {
+                self.storage.store_embedding_chunk(
+                    &format!("emb_{}_{}", item.id, chunk_idx),
+                    &item.id,
+                    chunk_index,
+                    text,
+                    &embedded.embedding,
+                    &embedded.provider,
+                    &embedded.model,
+                )
+            } else {
+                self.storage.store_entity_embedding_chunk(
+                    item.entity.as_str(),
+                    &item.id,
+                    chunk_index,
+                    text,
+                    &embedded.embedding,
+                    &embedded.provider,
+                    &embedded.model,
+                )
+            }
+        });
+        if let Err(e) = stored {
+            if !self.json {
+                self.say_err(&format!("  Error embedding {} chunk {}: {}", item.key, chunk_idx, e));
+            }
+            self.items[item_idx].errors += 1;
+        }
+
+        let item = &mut self.items[item_idx];
+        item.remaining -= 1;
+        if item.remaining > 0 {
+            return;
+        }
+        if item.entity != EmbeddingEntity::ContextItem && item.errors < item.chunks {
+            if let Err(e) = self.storage.trim_entity_embeddings(item.entity.as_str(), &item.id, item.chunks) {
+                debug!(error = %e, "Failed to trim stale embedding chunks");
+            }
+        }
+        let line = if item.errors == 0 {
+            self.processed += 1;
+            format!("  ✓ {} ({} chunks)", item.key, item.chunks)
+        } else if item.errors < item.chunks {
+            // Partial success
+            self.processed += 1;
+            self.errors += item.errors;
+            format!("  ⚠ {} ({}/{} chunks)", item.key, item.chunks - item.errors, item.chunks)
+        } else {
+            // Complete failure
+            self.errors += 1;
+            format!("  ✗ {}", item.key)
+        };
+
+        self.progress.done += 1;
+        if let Ok(state) = serde_json::to_string(&self.progress) {
+            if let Err(e) = self.storage.set_embedding_meta(BACKFILL_PROGRESS_KEY, &state) {
+                debug!(error = %e, "Failed to save backfill progress");
+            }
+        }
+        if let Some(bar) = self.bar.as_mut() {
+            bar.done = self.progress.done;
+        }
+        if !self.json {
+            match &self.bar {
+                Some(bar) => bar.println(&line),
+                None => println!("{line}"),
+            }
+        }
+    }
+
+    fn say_err(&self, line: &str) {
+        if self.bar.is_some() {
+            eprint!("\r\x1b[K");
+        }
+        eprintln!("{line}");
+        if let Some(bar) = &self.bar {
+            bar.draw();
+        }
+    }
+}
+
+/// Backfill embeddings for existing context items, issues, plans and memories.
 ///
 /// This function:
-/// 1. Queries context items without embeddings (or all if --force)
+/// 1. Queries context items without embeddings (or all if --force), then
+///    issues, plans and memories that are new or changed since embedded
 /// 2. Chunks large items for full semantic coverage
-/// 3. Generates embeddings via the configured provider
+/// 3. Generates embeddings via the configured provider, `concurrency`
+///    requests at a time, paced and retried per provider
 /// 4. Stores embeddings as BLOBs in the database
 async fn execute_backfill(
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     limit: Option<usize>,
     session: Option<String>,
     force: bool,
+    entities: &[EmbeddingEntity],
+    concurrency: Option<usize>,
+    use_cache: bool,
     json: bool,
 ) -> Result<()> {
-    // Get database path
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-
     // Create provider first to fail fast if not available
     let provider = create_embedding_provider()
         .await
@@ -427,6 +770,11 @@ async fn execute_backfill(
     let provider_name = info.name.clone();
     let model_name = info.model.clone();
 
+    let mut limits = Limits::configured_for(&provider_name);
+    if let Some(concurrency) = concurrency {
+        limits.concurrency = concurrency.max(1);
+    }
+
     // Get chunk config based on provider
     let chunk_config = if provider_name.to_lowercase().contains("ollama") {
         ChunkConfig::for_ollama()
@@ -435,7 +783,12 @@ async fn execute_backfill(
     };
 
     // Open storage
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
+    ensure_model_compatible(storage, &provider)?;
+    let requeued = reconcile_failover(storage, &provider).await?;
+    if requeued > 0 && !json {
+        println!("Re-embedding {requeued} records a fallback provider embedded while {provider_name} was down");
+    }
 
     // When --force is used, first resync phantom 'complete' items that lack actual
     // embedding data (status says complete but no rows in embedding_chunks)
@@ -450,11 +803,32 @@ async fn execute_backfill(
         }
     }
 
-    // Get items to process (now includes any reset phantom items)
-    let items = storage.get_items_without_embeddings(session.as_deref(), Some(limit.unwrap_or(1000) as u32))?;
-    debug!(items_to_process = items.len(), "Backfill items queried");
+    // Get items to process (now includes any reset phantom items), then
+    // issues, plans and memories that are new or changed, up to the limit
+    let wanted = |entity| entities.is_empty() || entities.contains(&entity);
+    let limit = limit.unwrap_or(1000);
+    let items = if wanted(EmbeddingEntity::ContextItem) {
+        storage.get_items_without_embeddings(session.as_deref(), Some(u32::try_from(limit).unwrap_or(u32::MAX)))?
+    } else {
+        Vec::new()
+    };
+    let mut others = Vec::new();
+    for entity in [EmbeddingEntity::Issue, EmbeddingEntity::Plan, EmbeddingEntity::Memory] {
+        let budget = limit.saturating_sub(items.len() + others.len());
+        if wanted(entity) && budget > 0 {
+            others.extend(
+                storage
+                    .get_entities_needing_embeddings(entity.as_str(), u32::try_from(budget).unwrap_or(u32::MAX))?
+                    .into_iter()
+                    .map(|e| (entity, e)),
+            );
+        }
+    }
+    debug!(items_to_process = items.len(), entities_to_process = others.len(), "Backfill items queried");
+    let workload = items.len() + others.len();
 
-    if items.is_empty() {
+    if workload == 0 {
+        storage.delete_embedding_meta(BACKFILL_PROGRESS_KEY)?;
         if json {
             let output = BackfillOutput {
                 processed: 0,
@@ -466,87 +840,130 @@ async fn execute_backfill(
             println!("{}", serde_json::to_string(&output)?);
         } else {
             println!("No items to process.");
-            println!("All context items already have embeddings.");
+            println!("All context items, issues, plans and memories already have embeddings.");
         }
         return Ok(());
     }
 
-    let total_items = items.len();
-    let mut processed = 0;
-    let mut skipped = 0;
-    let mut errors = 0;
+    // Pick up the totals of an interrupted run with the same model
+    let resumed = storage
+        .get_embedding_meta(BACKFILL_PROGRESS_KEY)?
+        .and_then(|state| serde_json::from_str::<BackfillProgress>(&state).ok())
+        .filter(|p| p.provider == provider_name && p.model == model_name);
+    let progress = match resumed {
+        Some(p) => BackfillProgress { total: p.done + workload, ..p },
+        None => BackfillProgress {
+            provider: provider_name.clone(),
+            model: model_name.clone(),
+            started_at: chrono::Utc::now().timestamp_millis(),
+            total: workload,
+            done: 0,
+        },
+    };
 
     if !json {
-        println!("Backfilling embeddings for {} items...", total_items);
-        println!("Provider: {} ({})", provider_name, model_name);
+        if progress.done > 0 {
+            let started = chrono::DateTime::from_timestamp_millis(progress.started_at)
+                .map_or_else(String::new, |dt| dt.format("%Y-%m-%d %H:%M").to_string());
+            println!("Resuming backfill started {started} ({}/{} items done)", progress.done, progress.total);
+        }
+        println!("Backfilling embeddings for {workload} items...");
+        println!(
+            "Provider: {} ({}), {} concurrent{}",
+            provider_name,
+            model_name,
+            limits.concurrency,
+            limits
+                .requests_per_minute
+                .map_or_else(String::new, |rpm| format!(", {rpm} requests/min"))
+        );
         println!();
     }
 
-    for item in items {
-        // Prepare text for embedding
-        let text = prepare_item_text(&item.key, &item.value, Some(&item.category));
-
-        // Chunk the text
+    // Queue every chunk; items without text are skipped up front
+    let mut skipped = 0;
+    let mut pending = Vec::with_capacity(workload);
+    let mut queue = std::collections::VecDeque::new();
+    let work = items
+        .into_iter()
+        .map(|item| {
+            let text = prepare_item_text(&item.key, &item.value, Some(item.category.as_str()));
+            (EmbeddingEntity::ContextItem, item.id, item.key, text)
+        })
+        .chain(others.into_iter().map(|(entity, e)| {
+            let text = prepare_item_text(&e.title, &e.text, Some(&e.label));
+            (entity, e.id, format!("{entity} {}", e.title), text)
+        }));
+    for (entity, id, key, text) in work {
         let chunks = chunk_text(&text, &chunk_config);
-
         if chunks.is_empty() {
             skipped += 1;
             continue;
         }
-
-        // Generate embeddings for each chunk
-        let mut chunk_errors = 0;
+        let item_idx = pending.len();
         for (chunk_idx, chunk) in chunks.iter().enumerate() {
-            match provider.generate_embedding(&chunk.text).await {
-                Ok(embedding) => {
-                    // Generate chunk ID
-                    let chunk_id = format!("emb_{}_{}", item.id, chunk_idx);
+            queue.push_back((item_idx, chunk_idx, chunk.text.clone()));
+        }
+        pending.push(PendingItem {
+            entity,
+            id,
+            key,
+            chunks: chunks.len(),
+            remaining: chunks.len(),
+            errors: 0,
+        });
+    }
 
-                    // Store the embedding
-                    if let Err(e) = storage.store_embedding_chunk(
-                        &chunk_id,
-                        &item.id,
-                        chunk_idx as i32,
-                        &chunk.text,
-                        &embedding,
-                        &provider_name,
-                        &model_name,
-                    ) {
-                        if !json {
-                            eprintln!("  Error storing chunk {}: {}", chunk_idx, e);
-                        }
-                        chunk_errors += 1;
-                    }
-                }
-                Err(e) => {
-                    if !json {
-                        eprintln!("  Error generating embedding for {}: {}", item.key, e);
+    let bar = ProgressBar::start(progress.total, progress.done, json);
+    if let Some(bar) = &bar {
+        bar.draw();
+    }
+    let mut run = BackfillRun {
+        storage,
+        use_cache,
+        json,
+        items: pending,
+        progress,
+        bar,
+        processed: 0,
+        errors: 0,
+    };
+
+    let provider = Arc::new(provider);
+    let limiter = Arc::new(RateLimiter::new(limits.requests_per_minute));
+    let mut tasks = tokio::task::JoinSet::new();
+    loop {
+        while tasks.len() < limits.concurrency {
+            let Some((item_idx, chunk_idx, text)) = queue.pop_front() else { break };
+            if use_cache {
+                match run.storage.get_cached_embedding(&provider_name, &model_name, &text) {
+                    Ok(Some(embedding)) => {
+                        let embedded = from_cache(embedding, &provider_name, &model_name);
+                        run.finish_chunk(item_idx, chunk_idx, &text, Ok(embedded), true);
+                        continue;
                     }
-                    chunk_errors += 1;
+                    Ok(None) => {}
+                    Err(e) => debug!(error = %e, "Embedding cache lookup failed"),
                 }
             }
+            let provider = Arc::clone(&provider);
+            let limiter = Arc::clone(&limiter);
+            tasks.spawn(async move {
+                let result = embed_with_retry(&provider, &limiter, &text).await;
+                (item_idx, chunk_idx, text, result)
+            });
         }
+        let Some(joined) = tasks.join_next().await else { break };
+        let (item_idx, chunk_idx, text, result) =
+            joined.map_err(|e| Error::Embedding(format!("Embedding task failed: {e}")))?;
+        run.finish_chunk(item_idx, chunk_idx, &text, result, false);
+    }
 
-        if chunk_errors == 0 {
-            processed += 1;
-            if !json {
-                println!("  ✓ {} ({} chunks)", item.key, chunks.len());
-            }
-        } else if chunk_errors < chunks.len() {
-            // Partial success
-            processed += 1;
-            errors += chunk_errors;
-            if !json {
-                println!("  ⚠ {} ({}/{} chunks)", item.key, chunks.len() - chunk_errors, chunks.len());
-            }
-        } else {
-            // Complete failure
-            errors += 1;
-            if !json {
-                println!("  ✗ {}", item.key);
-            }
-        }
+    if run.bar.is_some() {
+        eprint!("\r\x1b[K");
     }
+    let (processed, errors) = (run.processed, run.errors);
+    storage.delete_embedding_meta(BACKFILL_PROGRESS_KEY)?;
 
     if json {
         let output = BackfillOutput {
@@ -634,19 +1051,303 @@ async fn execute_test(text: &str, json: bool) -> Result<()> {
     Ok(())
 }
 
+/// Embed one chunk, serving text embedded before from the cache.
+///
+/// Cache failures are logged and fall through to the provider.
+async fn embed_chunk(
+    storage: &SqliteStorage,
+    provider: &BoxedProvider,
+    provider_name: &str,
+    model_name: &str,
+    text: &str,
+    use_cache: bool,
+) -> Result<EmbeddingResult> {
+    if use_cache {
+        match storage.get_cached_embedding(provider_name, model_name, text) {
+            Ok(Some(embedding)) => return Ok(from_cache(embedding, provider_name, model_name)),
+            Ok(None) => {}
+            Err(e) => debug!(error = %e, "Embedding cache lookup failed"),
+        }
+    }
+    let embedded = provider.generate_attributed(text).await?;
+    if use_cache {
+        if let Err(e) = storage.cache_embedding(&embedded.provider, &embedded.model, text, &embedded.embedding) {
+            debug!(error = %e, "Embedding cache write failed");
+        }
+    }
+    Ok(embedded)
+}
+
+/// Wrap a cached vector as if `provider_name`/`model_name` had just made it.
+fn from_cache(embedding: Vec<f32>, provider_name: &str, model_name: &str) -> EmbeddingResult {
+    EmbeddingResult {
+        dimensions: embedding.len(),
+        embedding,
+        model: model_name.to_string(),
+        provider: provider_name.to_string(),
+    }
+}
+
+/// Check that the stored quality embeddings come from the active model.
+///
+/// Vectors from different models, or of different sizes, can't be
+/// compared: mixing them makes search quietly return nothing or noise. With
+/// `embeddings.keep_other_models` the other models' chunks are archived on
+/// the spot (and any archive of the active model restored); otherwise this
+/// refuses until `sc embeddings migrate` is run.
+///
+/// # Errors
+///
+/// Returns [`Error::EmbeddingModelMismatch`] when other models' embeddings
+/// are stored and may not be archived automatically.
+pub(crate) fn ensure_model_compatible(storage: &mut SqliteStorage, provider: &BoxedProvider) -> Result<()> {
+    // A failover chain may have stored vectors from any of its members
+    let members = provider.members();
+    let (primary, model) = (members[0].name.as_str(), members[0].model.as_str());
+    let others: Vec<_> = storage
+        .embedding_models(false)?
+        .into_iter()
+        .filter(|m| !members.iter().any(|p| p.name == m.provider && p.model == m.model))
+        .collect();
+    let keep_other_models = get_embedding_settings()
+        .ok()
+        .flatten()
+        .and_then(|s| s.keep_other_models)
+        .unwrap_or(false);
+    if others.is_empty() {
+        // Switching back to an archived model brings its vectors back too
+        let archived = keep_other_models
+            && !crate::is_dry_run()
+            && storage.embedding_models(true)?.iter().any(|m| m.provider == primary && m.model == model);
+        if archived {
+            let restored = storage.restore_archived_embeddings(primary, model)?;
+            info!(provider = primary, model, restored, "Restored archived embeddings");
+        }
+        return Ok(());
+    }
+
+    if keep_other_models && !crate::is_dry_run() {
+        let archived = storage.retire_embeddings_except(primary, model, true)?;
+        let restored = storage.restore_archived_embeddings(primary, model)?;
+        info!(provider = primary, model, archived, restored, "Embedding model changed, archived other models");
+        return Ok(());
+    }
+
+    let stored = others
+        .iter()
+        .map(|m| format!("{}/{} ({}d)", m.provider, m.model, m.dimensions))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let active = members
+        .iter()
+        .map(|m| format!("{}/{}", m.name, m.model))
+        .collect::<Vec<_>>()
+        .join(" → ");
+    Err(Error::EmbeddingModelMismatch { stored, active })
+}
+
+/// Re-queue records a fallback provider embedded, once the preferred
+/// provider of the failover chain is reachable again.
+///
+/// Context items go back to `pending` and other records' chunks are marked
+/// as older than the record, so the next backfill re-embeds them with the
+/// preferred model. Their fallback vectors stay searchable until then.
+/// Returns the number of records re-queued.
+async fn reconcile_failover(storage: &mut SqliteStorage, provider: &BoxedProvider) -> Result<usize> {
+    let members = provider.members();
+    let Some((primary, fallbacks)) = members.split_first() else {
+        return Ok(0);
+    };
+    if fallbacks.is_empty() || crate::is_dry_run() {
+        return Ok(0);
+    }
+    let produced: Vec<_> = storage
+        .embedding_models(false)?
+        .into_iter()
+        .filter(|m| !(m.provider == primary.name && m.model == primary.model))
+        .filter(|m| fallbacks.iter().any(|f| f.name == m.provider && f.model == m.model))
+        .collect();
+    if produced.is_empty() || !provider.is_available().await {
+        return Ok(0);
+    }
+    let mut requeued = 0;
+    for m in &produced {
+        requeued += storage.requeue_embeddings_from(&m.provider, &m.model)?;
+    }
+    info!(requeued, provider = %primary.name, "Re-queued embeddings made by fallback providers");
+    Ok(requeued)
+}
+
+/// Move stored embeddings to the active provider and model.
+///
+/// Other models' chunks are deleted (or archived with `keep`), archived
+/// chunks of the active model come back where still current, and every
+/// record left without embeddings is picked up by the next backfill.
+async fn execute_migrate(db: &mut Database, keep: bool, json: bool) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    let provider = create_embedding_provider()
+        .await
+        .ok_or_else(|| Error::Embedding("No embedding provider available".to_string()))?;
+    // With a failover chain, migrate to the preferred provider
+    let info = provider.members().swap_remove(0);
+
+    let storage = db.storage()?;
+    let retired_models: Vec<_> = storage
+        .embedding_models(false)?
+        .into_iter()
+        .filter(|m| m.provider != info.name || m.model != info.model)
+        .collect();
+
+    let dry_run = crate::is_dry_run();
+    let (retired, restored) = if dry_run {
+        (retired_models.iter().map(|m| m.entities).sum(), 0)
+    } else {
+        let retired = storage.retire_embeddings_except(&info.name, &info.model, keep)?;
+        let restored = storage.restore_archived_embeddings(&info.name, &info.model)?;
+        (retired, restored)
+    };
+    info!(provider = %info.name, model = %info.model, retired, restored, keep, "Embeddings migrated");
+
+    if json {
+        let output = MigrateOutput {
+            provider: info.name,
+            model: info.model,
+            retired_models,
+            retired,
+            kept: keep,
+            restored,
+            dry_run,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    let active = format!("{}/{}", info.name, info.model);
+    if retired_models.is_empty() && restored == 0 {
+        println!("All stored embeddings already come from {active}.");
+        return Ok(());
+    }
+    let verb = match (dry_run, keep) {
+        (true, true) => "Would archive",
+        (true, false) => "Would delete",
+        (false, true) => "Archived",
+        (false, false) => "Deleted",
+    };
+    for m in &retired_models {
+        println!("{verb} embeddings of {} records from {}/{} ({}d)", m.entities, m.provider, m.model, m.dimensions);
+    }
+    if restored > 0 {
+        println!("Restored archived {active} embeddings for {restored} records");
+    }
+    if retired > 0 {
+        println!();
+        println!("Run 'sc embeddings backfill' to embed the rest with {active}.");
+    }
+    Ok(())
+}
+
+/// Compare providers on a labeled sample of the project's context items.
+async fn execute_benchmark(
+    db: &mut Database,
+    sample: usize,
+    provider_types: &[EmbeddingProviderType],
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let categories = storage.get_categories(&project_path)?;
+    let pairs: Vec<LabeledPair> = storage
+        .get_context_items_by_project(&project_path)?
+        .iter()
+        .rev()
+        .filter(|item| !categories.encrypted(&item.category))
+        .filter_map(|item| LabeledPair::from_item(&item.key, &item.value))
+        .take(sample)
+        .collect();
+    if pairs.len() < 2 {
+        return Err(Error::InvalidArgument(format!(
+            "Benchmark needs at least 2 context items with descriptive keys in {project_path}, found {}",
+            pairs.len()
+        )));
+    }
+
+    let providers = create_available_providers(provider_types).await;
+    if providers.is_empty() {
+        return Err(Error::Embedding("No embedding provider available to benchmark".to_string()));
+    }
+    if !json {
+        println!("Benchmarking {} providers on {} items...", providers.len(), pairs.len());
+    }
+    let mut results = Vec::with_capacity(providers.len());
+    for provider in &providers {
+        results.push(benchmark::run(provider, &pairs).await);
+    }
+
+    if json {
+        let output = BenchmarkOutput {
+            project: &project_path,
+            samples: pairs.len(),
+            recall_at: benchmark::RECALL_AT,
+            results: &results,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  {:<12} {:<32} {:>5} {:>8} {:>8} {:>9} {:>9} {:>6}",
+        "Provider",
+        "Model",
+        "Dims",
+        "p50 ms",
+        "p95 ms",
+        "items/s",
+        format!("Recall@{}", benchmark::RECALL_AT),
+        "MRR"
+    );
+    for r in &results {
+        let model: String = r.model.chars().take(32).collect();
+        if let Some(ref e) = r.error {
+            println!("  {:<12} {model:<32} failed: {e}", r.provider);
+            continue;
+        }
+        println!(
+            "  {:<12} {model:<32} {:>5} {:>8.2} {:>8.2} {:>9.1} {:>8.0}% {:>6.2}",
+            r.provider,
+            r.dimensions,
+            r.p50_ms,
+            r.p95_ms,
+            r.items_per_sec,
+            r.recall * 100.0,
+            r.mrr
+        );
+    }
+
+    let finished: Vec<_> = results.iter().filter(|r| r.error.is_none()).collect();
+    let fastest = finished.iter().max_by(|a, b| a.items_per_sec.total_cmp(&b.items_per_sec));
+    let best = finished.iter().max_by(|a, b| a.mrr.total_cmp(&b.mrr));
+    if let (Some(fastest), Some(best)) = (fastest, best) {
+        println!();
+        println!("Fastest: {} ({}). Best retrieval: {} ({}).", fastest.provider, fastest.model, best.provider, best.model);
+    }
+    Ok(())
+}
+
 /// Process pending embeddings (for background execution).
 ///
 /// This is called by the spawned background process after a save operation.
 /// It processes a limited number of items to avoid long-running operations.
 async fn execute_process_pending(
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     limit: usize,
     quiet: bool,
+    use_cache: bool,
 ) -> Result<()> {
-    // Get database path
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+    if !db.exists() {
         return Ok(()); // No database yet, nothing to do
     }
 
@@ -673,20 +1374,19 @@ async fn execute_process_pending(
     };
 
     // Open storage
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
+    ensure_model_compatible(storage, &provider)?;
+    reconcile_failover(storage, &provider).await?;
 
     // Get items to process (limited batch)
     let items = storage.get_items_without_embeddings(None, Some(limit as u32))?;
 
-    if items.is_empty() {
-        return Ok(());
-    }
-
     let mut processed = 0;
+    let budget = limit.saturating_sub(items.len());
 
     for item in items {
         // Prepare text for embedding
-        let text = prepare_item_text(&item.key, &item.value, Some(&item.category));
+        let text = prepare_item_text(&item.key, &item.value, Some(item.category.as_str()));
 
         // Chunk the text
         let chunks = chunk_text(&text, &chunk_config);
@@ -698,8 +1398,8 @@ async fn execute_process_pending(
         // Generate embeddings for each chunk
         let mut success = true;
         for (chunk_idx, chunk) in chunks.iter().enumerate() {
-            match provider.generate_embedding(&chunk.text).await {
-                Ok(embedding) => {
+            match embed_chunk(storage, &provider, &provider_name, &model_name, &chunk.text, use_cache).await {
+                Ok(embedded) => {
                     let chunk_id = format!("emb_{}_{}", item.id, chunk_idx);
                     if storage
                         .store_embedding_chunk(
@@ -707,9 +1407,9 @@ async fn execute_process_pending(
                             &item.id,
                             chunk_idx as i32,
                             &chunk.text,
-                            &embedding,
-                            &provider_name,
-                            &model_name,
+                            &embedded.embedding,
+                            &embedded.provider,
+                            &embedded.model,
                         )
                         .is_err()
                     {
@@ -732,6 +1432,44 @@ async fn execute_process_pending(
         }
     }
 
+    // Spend what is left of the batch on new or changed issues, plans and memories
+    let mut others = Vec::new();
+    for entity in [EmbeddingEntity::Issue, EmbeddingEntity::Plan, EmbeddingEntity::Memory] {
+        let remaining = budget.saturating_sub(others.len());
+        if remaining > 0 {
+            others.extend(storage.get_entities_needing_embeddings(entity.as_str(), u32::try_from(remaining).unwrap_or(u32::MAX))?);
+        }
+    }
+    for entity in others {
+        let text = prepare_item_text(&entity.title, &entity.text, Some(&entity.label));
+        let chunks = chunk_text(&text, &chunk_config);
+        let mut success = !chunks.is_empty();
+        for (chunk_idx, chunk) in chunks.iter().enumerate() {
+            let stored = match embed_chunk(storage, &provider, &provider_name, &model_name, &chunk.text, use_cache).await {
+                Ok(embedded) => storage.store_entity_embedding_chunk(
+                    &entity.entity_type,
+                    &entity.id,
+                    i32::try_from(chunk_idx).unwrap_or(i32::MAX),
+                    &chunk.text,
+                    &embedded.embedding,
+                    &embedded.provider,
+                    &embedded.model,
+                ),
+                Err(e) => Err(e),
+            };
+            if stored.is_err() {
+                success = false;
+                break;
+            }
+        }
+        if success && storage.trim_entity_embeddings(&entity.entity_type, &entity.id, chunks.len()).is_ok() {
+            processed += 1;
+            if !quiet {
+                eprintln!("[bg] Embedded: {} {} ({} chunks)", entity.entity_type, entity.title, chunks.len());
+            }
+        }
+    }
+
     if !quiet && processed > 0 {
         eprintln!("[bg] Processed {} pending embeddings", processed);
     }
@@ -741,15 +1479,15 @@ async fn execute_process_pending(
 
 /// Spawn a detached background process to generate embeddings.
 ///
-/// This is called after save operations to process pending embeddings
-/// without blocking the main command. The spawned process runs independently
+/// This is called after saving context items, issues, plans and memories
+/// to process pending embeddings without blocking the main command. The spawned process runs independently
 /// and exits when done.
 pub fn spawn_background_embedder() {
     use std::fs;
     use std::process::{Command, Stdio};
 
-    // Only spawn if embeddings are enabled
-    if !is_embeddings_enabled() {
+    // Only spawn if embeddings are enabled and something was written
+    if !is_embeddings_enabled() || crate::is_dry_run() {
         debug!("Background embedder skipped: embeddings disabled");
         return;
     }
@@ -806,15 +1544,14 @@ pub fn reset_to_defaults() -> Result<()> {
 /// The quality embeddings enable better semantic search accuracy while the
 /// fast embeddings continue to provide instant results.
 async fn execute_upgrade_quality(
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     limit: Option<usize>,
     session: Option<String>,
+    use_cache: bool,
     json: bool,
 ) -> Result<()> {
     // Get database path
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
@@ -835,7 +1572,8 @@ async fn execute_upgrade_quality(
     };
 
     // Open storage
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
+    ensure_model_compatible(storage, &provider)?;
 
     // Get items that need quality upgrade (have fast embeddings but no quality)
     let items = storage.get_items_needing_quality_upgrade(
@@ -875,7 +1613,7 @@ async fn execute_upgrade_quality(
 
     for item in items {
         // Prepare text for embedding
-        let text = prepare_item_text(&item.key, &item.value, Some(&item.category));
+        let text = prepare_item_text(&item.key, &item.value, Some(item.category.as_str()));
 
         // Chunk the text
         let chunks = chunk_text(&text, &chunk_config);
@@ -891,8 +1629,8 @@ async fn execute_upgrade_quality(
         // Generate embeddings for each chunk
         let mut chunk_errors = 0;
         for (chunk_idx, chunk) in chunks.iter().enumerate() {
-            match provider.generate_embedding(&chunk.text).await {
-                Ok(embedding) => {
+            match embed_chunk(storage, &provider, &provider_name, &model_name, &chunk.text, use_cache).await {
+                Ok(embedded) => {
                     // Generate chunk ID (for quality tier)
                     let chunk_id = format!("emb_{}_{}", item.id, chunk_idx);
 
@@ -902,9 +1640,9 @@ async fn execute_upgrade_quality(
                         &item.id,
                         chunk_idx as i32,
                         &chunk.text,
-                        &embedding,
-                        &provider_name,
-                        &model_name,
+                        &embedded.embedding,
+                        &embedded.provider,
+                        &embedded.model,
                     ) {
                         if !json {
                             eprintln!("  Error storing chunk {}: {}", chunk_idx, e);
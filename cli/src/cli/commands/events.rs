@@ -0,0 +1,215 @@
+//! Audit log retention (`sc events prune`).
+//!
+//! `event_retention` in `~/.savecontext/config.json` sets how long events
+//! are kept (`days`) and how many each entity keeps (`max_per_entity`).
+//! Creates, deletes, issue comments and mentions are never pruned. With a
+//! policy configured, pruning also runs lazily after other commands: at
+//! most once a day per database, and at most [`LAZY_BATCH`] events at a
+//! time so a large backlog never stalls the command that triggered it.
+//!
+//! With `archive` on (or `--archive`), pruned events are first written to
+//! a zstd-compressed JSONL file in `~/.savecontext/archive/`, one event per
+//! line, and only deleted once the file is complete.
+
+use crate::cli::Database;
+use crate::cli::commands::auto_checkpoint::{marker_contains, write_marker};
+use crate::cli::commands::config::load_config;
+use crate::cli::EventsCommands;
+use crate::config::global_savecontext_dir;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::events::{RetentionPolicy, StoredEvent};
+use crate::storage::SqliteStorage;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Most events one lazy run prunes.
+const LAZY_BATCH: usize = 5_000;
+
+/// zstd level for archives. They're written once and rarely read.
+const ARCHIVE_LEVEL: i32 = 19;
+
+/// The configured policy and whether to archive.
+fn configured() -> (RetentionPolicy, bool) {
+    let config = load_config().event_retention.unwrap_or_default();
+    let policy = RetentionPolicy {
+        older_than: config.days.filter(|d| *d > 0).map(cutoff),
+        max_per_entity: config.max_per_entity.filter(|n| *n > 0),
+    };
+    (policy, config.archive.unwrap_or(false))
+}
+
+/// Time (Unix ms) before which the configured `days` limit prunes events.
+pub(crate) fn retention_cutoff() -> Option<i64> {
+    configured().0.older_than
+}
+
+fn cutoff(days: u64) -> i64 {
+    let days = i64::try_from(days).unwrap_or(i64::MAX / DAY_MS);
+    chrono::Utc::now().timestamp_millis() - days.saturating_mul(DAY_MS)
+}
+
+/// Result of one prune.
+#[derive(Debug, serde::Serialize)]
+struct Pruned {
+    pruned: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive: Option<PathBuf>,
+}
+
+/// Output for events prune.
+#[derive(Serialize)]
+struct PruneOutput<'a> {
+    pruned: usize,
+    /// Archive the pruned events were written to.
+    archive: Option<&'a Path>,
+    policy: &'a RetentionPolicy,
+    dry_run: bool,
+}
+
+json_schema!(PruneOutput<'a> { pruned: usize, archive: Option<&'a Path>, policy: &'a RetentionPolicy, dry_run: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("events prune", schema_for::<PruneOutput<'_>>())]
+}
+
+/// Archive (when `archive_dir` is set) and delete up to `limit` events.
+fn prune(
+    storage: &mut SqliteStorage,
+    policy: &RetentionPolicy,
+    archive_dir: Option<&Path>,
+    limit: Option<usize>,
+) -> Result<Pruned> {
+    let events = storage.prunable_events(policy, limit)?;
+    if events.is_empty() || crate::is_dry_run() {
+        return Ok(Pruned { pruned: events.len(), archive: None });
+    }
+    let archive = archive_dir.map(|dir| write_archive(dir, &events)).transpose()?;
+    let ids: Vec<i64> = events.iter().map(|e| e.id).collect();
+    let pruned = storage.delete_events(&ids)?;
+    Ok(Pruned { pruned, archive })
+}
+
+/// Write `events` to a new `events-<time>.jsonl.zst` in `dir`.
+fn write_archive(dir: &Path, events: &[StoredEvent]) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let name = format!("events-{}.jsonl.zst", chrono::Utc::now().format("%Y%m%d-%H%M%S%3f"));
+    let path = dir.join(name);
+    let partial = path.with_extension("zst.partial");
+
+    let file = std::fs::File::create(&partial)?;
+    let mut encoder = zstd::Encoder::new(file, ARCHIVE_LEVEL)?;
+    for event in events {
+        serde_json::to_writer(&mut encoder, event)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()?.sync_all()?;
+    std::fs::rename(&partial, &path)?;
+    Ok(path)
+}
+
+fn default_archive_dir() -> Result<PathBuf> {
+    global_savecontext_dir()
+        .map(|dir| dir.join("archive"))
+        .ok_or_else(|| Error::Config("Cannot locate ~/.savecontext for the event archive".to_string()))
+}
+
+/// Lazy prune, run after every command when a policy is configured.
+///
+/// Runs once a day per database unless the last run hit [`LAZY_BATCH`].
+/// Failures are only logged.
+pub fn prune_after_command(db: &mut Database) {
+    if crate::config::is_test_mode() || crate::is_dry_run() {
+        return;
+    }
+    let (policy, archive) = configured();
+    if policy.is_empty() {
+        return;
+    }
+    let Some(db_path) = db.path().filter(|p| p.exists()) else { return };
+
+    let marker = global_savecontext_dir().map(|dir| dir.join("cache").join("event-prune"));
+    let stamp = format!("{}\t{}", chrono::Local::now().format("%Y-%m-%d"), db_path.display());
+    if marker.as_deref().is_some_and(|m| marker_contains(m, &stamp)) {
+        return;
+    }
+
+    let result = (if archive { default_archive_dir().map(Some) } else { Ok(None) }).and_then(|archive_dir| {
+        prune(db.storage()?, &policy, archive_dir.as_deref(), Some(LAZY_BATCH))
+    });
+    match result {
+        Ok(run) => {
+            tracing::debug!(pruned = run.pruned, "Pruned audit events");
+            if run.pruned < LAZY_BATCH {
+                if let Some(marker) = marker {
+                    write_marker(&marker, &stamp);
+                }
+            }
+        }
+        Err(e) => tracing::debug!(error = %e, "Pruning audit events failed"),
+    }
+}
+
+/// Execute an events subcommand.
+///
+/// # Errors
+///
+/// Returns an error if no retention policy is configured or given, or the
+/// archive or database cannot be written.
+pub fn execute(command: &EventsCommands, db: &mut Database, json: bool) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    match command {
+        EventsCommands::Prune { days, max_per_entity, archive, no_archive, archive_dir } => {
+            let (mut policy, configured_archive) = configured();
+            if let Some(days) = days {
+                policy.older_than = Some(cutoff(*days));
+            }
+            if let Some(max) = max_per_entity {
+                policy.max_per_entity = Some((*max).max(1));
+            }
+            if policy.is_empty() {
+                return Err(Error::InvalidArgument(
+                    "No retention policy. Pass --days or --max-per-entity, or set event_retention in config"
+                        .to_string(),
+                ));
+            }
+            let archive_dir = match (archive_dir, *archive || (configured_archive && !*no_archive)) {
+                (Some(dir), _) => Some(dir.clone()),
+                (None, true) => Some(default_archive_dir()?),
+                (None, false) => None,
+            };
+
+            let storage = db.storage()?;
+            let run = prune(storage, &policy, archive_dir.as_deref(), None)?;
+            print_pruned(&run, &policy, json)
+        }
+    }
+}
+
+fn print_pruned(run: &Pruned, policy: &RetentionPolicy, json: bool) -> Result<()> {
+    let dry_run = crate::is_dry_run();
+    if json {
+        let output = PruneOutput { pruned: run.pruned, archive: run.archive.as_deref(), policy, dry_run };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if run.pruned == 0 {
+        println!("No events to prune.");
+        return Ok(());
+    }
+    let verb = if dry_run { "Would prune" } else { "Pruned" };
+    println!("{verb} {} event(s); creates, deletes, comments and mentions are kept.", run.pruned);
+    if let Some(archive) = &run.archive {
+        println!("Archived to {}", archive.display());
+    }
+    Ok(())
+}
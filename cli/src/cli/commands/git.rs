@@ -0,0 +1,307 @@
+//! Git integration (`sc git`).
+//!
+//! `sc git install-hooks` adds a `post-checkout` hook to the repository
+//! that runs `sc git hook checkout`. When a checkout lands on a branch
+//! other than the session's, the hook checkpoints the session's items on
+//! the channel being left (as `switch-<channel>-YYYYMMDD-HHMMSS`), moves
+//! the session to the new branch and its channel, and prints what that
+//! channel already holds. Items saved from then on go on the new channel,
+//! so context follows the branch.
+//!
+//! A hook must never get in git's way: with no database, no session bound
+//! to the terminal, a file checkout or a detached HEAD it does nothing, and
+//! the installed script ignores its exit status.
+
+use crate::cli::{Database, GitCommands, GitHookCommands};
+use crate::config::{branch_channel, current_git_branch, default_actor, resolve_session_id};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::ItemPriority;
+use crate::storage::ContextItem;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Marks the lines `sc` owns in a hook script, so reinstalling replaces
+/// them and leaves the rest of an existing hook alone.
+const BLOCK_START: &str = "# >>> sc git hooks >>>";
+const BLOCK_END: &str = "# <<< sc git hooks <<<";
+
+/// The `post-checkout` lines. Git passes the previous HEAD, the new HEAD
+/// and 1 for a branch checkout; `|| true` keeps a failure from becoming
+/// the exit status of `git checkout`.
+const POST_CHECKOUT: &str = "# >>> sc git hooks >>>
+# Keep the SaveContext session on the checked-out branch's channel
+command -v sc >/dev/null 2>&1 && sc git hook checkout \"$1\" \"$2\" \"$3\" || true
+# <<< sc git hooks <<<";
+
+/// Items of the new channel listed after a switch.
+const ITEMS_SHOWN: usize = 10;
+
+/// Output for `sc git install-hooks`.
+#[derive(Serialize)]
+struct InstallHooksOutput {
+    hook: PathBuf,
+    /// `created`, `appended` (to an existing hook), `updated` or `unchanged`.
+    action: String,
+}
+
+/// Output for `sc git hook checkout` after a switch.
+#[derive(Serialize)]
+struct CheckoutOutput {
+    session_id: String,
+    from_branch: Option<String>,
+    to_branch: String,
+    from_channel: String,
+    to_channel: String,
+    /// Checkpoint of the items on `from_channel`, if it had any.
+    checkpoint: Option<SwitchCheckpoint>,
+    /// Items already on `to_channel`, most important first.
+    items: Vec<ContextItem>,
+    item_count: usize,
+}
+
+#[derive(Serialize)]
+struct SwitchCheckpoint {
+    id: String,
+    name: String,
+    item_count: usize,
+}
+
+json_schema!(InstallHooksOutput { hook: PathBuf, action: String });
+json_schema!(CheckoutOutput {
+    session_id: String,
+    from_branch: Option<String>,
+    to_branch: String,
+    from_channel: String,
+    to_channel: String,
+    checkpoint: Option<SwitchCheckpoint>,
+    items: Vec<ContextItem>,
+    item_count: usize,
+});
+json_schema!(SwitchCheckpoint { id: String, name: String, item_count: usize });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("git install-hooks", schema_for::<InstallHooksOutput>()),
+        ("git hook checkout", schema_for::<CheckoutOutput>()),
+    ]
+}
+
+/// Execute git commands.
+///
+/// # Errors
+///
+/// Returns an error if the hooks directory can't be found or written, or
+/// the database fails during a branch switch.
+pub fn execute(
+    command: &GitCommands,
+    db: &mut Database,
+    session: Option<&str>,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    match command {
+        GitCommands::InstallHooks => install_hooks(json),
+        GitCommands::Hook { command: GitHookCommands::Checkout { kind, .. } } => {
+            // Git passes 0 for a file checkout, which doesn't change branch
+            if kind.as_deref() == Some("0") {
+                return Ok(());
+            }
+            let actor = actor.map_or_else(default_actor, ToString::to_string);
+            checkout(db, session, &actor, json)
+        }
+    }
+}
+
+fn install_hooks(json: bool) -> Result<()> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .ok_or_else(|| Error::InvalidArgument("Not in a git repository".to_string()))?;
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let hook = dir.join("post-checkout");
+
+    let existing = std::fs::read_to_string(&hook).ok();
+    let (script, action) = hook_script(existing.as_deref());
+
+    if crate::is_dry_run() {
+        if json {
+            println!("{}", serde_json::json!({ "dry_run": true, "hook": hook, "action": action }));
+        } else {
+            println!("Would install {} ({action})", hook.display());
+        }
+        return Ok(());
+    }
+
+    if action != "unchanged" {
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(&hook, script)?;
+        make_executable(&hook)?;
+    }
+
+    if json {
+        let output = InstallHooksOutput { hook, action: action.to_string() };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if !crate::is_silent() {
+        println!("{} {}", capitalize(action), hook.display());
+        println!("Branch switches now checkpoint the current channel and move the session to the new branch's.");
+    }
+    Ok(())
+}
+
+/// The hook script with the `sc` lines in place, and what changed.
+fn hook_script(existing: Option<&str>) -> (String, &'static str) {
+    let Some(existing) = existing.filter(|e| !e.trim().is_empty()) else {
+        return (format!("#!/bin/sh\n{POST_CHECKOUT}\n"), "created");
+    };
+    if let (Some(start), Some(end)) = (existing.find(BLOCK_START), existing.find(BLOCK_END)) {
+        let script = format!("{}{POST_CHECKOUT}{}", &existing[..start], &existing[end + BLOCK_END.len()..]);
+        let action = if script == existing { "unchanged" } else { "updated" };
+        return (script, action);
+    }
+    let separator = if existing.ends_with('\n') { "" } else { "\n" };
+    (format!("{existing}{separator}\n{POST_CHECKOUT}\n"), "appended")
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    std::fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// Follow a branch switch, if there was one.
+fn checkout(db: &mut Database, session: Option<&str>, actor: &str, json: bool) -> Result<()> {
+    let Some(branch) = current_git_branch().filter(|b| b != "HEAD") else {
+        return Ok(());
+    };
+    if !db.exists() {
+        return Ok(());
+    }
+    let Ok(session_id) = resolve_session_id(session) else {
+        return Ok(());
+    };
+    let storage = db.storage()?;
+    let session = storage.get_session(&session_id)?.ok_or(Error::SessionNotFound { id: session_id })?;
+    if session.branch.as_deref() == Some(branch.as_str()) {
+        return Ok(());
+    }
+
+    let from_channel = session.channel.clone().unwrap_or_else(|| "general".to_string());
+    let to_channel = branch_channel(&branch);
+    let name = format!("switch-{from_channel}-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let id = format!("ckpt_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+    let dry_run = crate::is_dry_run();
+
+    let items = storage.get_context_items(&session.id, None, None, Some(u32::MAX))?;
+    let on_channel = |item: &ContextItem, channel: &str| item.channel.as_deref().unwrap_or("general") == channel;
+    let leaving = items.iter().filter(|item| on_channel(item, &from_channel)).count();
+
+    storage.with_transaction(|storage| {
+        let result = (|| {
+            // A checkpoint of nothing would only clutter the list
+            if leaving > 0 {
+                let description = format!("Items on {from_channel} when switching to {branch}");
+                storage.create_snapshot_checkpoint(&id, &session.id, &name, Some(&description), Some(&from_channel), actor)?;
+            }
+            storage.switch_session_branch(&session.id, &branch, &to_channel, actor)
+        })();
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+    let checkpoint = (leaving > 0).then_some(SwitchCheckpoint { id, name, item_count: leaving });
+
+    let mut items: Vec<ContextItem> = items.into_iter().filter(|item| on_channel(item, &to_channel)).collect();
+    items.sort_by_key(|item| (priority_rank(item.priority), -item.updated_at));
+    let item_count = items.len();
+    items.truncate(ITEMS_SHOWN);
+
+    let output = CheckoutOutput {
+        session_id: session.id,
+        from_branch: session.branch,
+        to_branch: branch,
+        from_channel,
+        to_channel,
+        checkpoint,
+        items,
+        item_count,
+    };
+    if json {
+        let mut value = serde_json::to_value(&output)?;
+        if dry_run {
+            value["dry_run"] = true.into();
+        }
+        println!("{value}");
+    } else if !crate::is_silent() {
+        print_switch(&output, dry_run);
+    }
+    Ok(())
+}
+
+fn priority_rank(priority: ItemPriority) -> u8 {
+    match priority {
+        ItemPriority::High => 0,
+        ItemPriority::Normal => 1,
+        ItemPriority::Low => 2,
+    }
+}
+
+fn print_switch(output: &CheckoutOutput, dry_run: bool) {
+    let would = if dry_run { "would " } else { "" };
+    if let Some(checkpoint) = &output.checkpoint {
+        println!(
+            "sc: {would}checkpoint {} item(s) on {} as {}",
+            checkpoint.item_count, output.from_channel, checkpoint.name
+        );
+    }
+    if dry_run {
+        println!("sc: session would move to channel {} ({} item(s))", output.to_channel, output.item_count);
+    } else {
+        println!("sc: session now on channel {} ({} item(s))", output.to_channel, output.item_count);
+    }
+    for item in &output.items {
+        let marker = if item.priority == ItemPriority::High { "!" } else { " " };
+        let value = item.value.lines().next().unwrap_or_default();
+        let value: String = value.chars().take(70).collect();
+        println!("  [{marker}] {} ({}): {value}", item.key, item.category);
+    }
+    if output.item_count > output.items.len() {
+        println!("  ... {} more (sc get)", output.item_count - output.items.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_script_keeps_existing_hooks() {
+        let (created, action) = hook_script(None);
+        assert_eq!(action, "created");
+        assert!(created.starts_with("#!/bin/sh\n"));
+
+        let (appended, action) = hook_script(Some("#!/bin/sh\nnpm install"));
+        assert_eq!(action, "appended");
+        assert!(appended.starts_with("#!/bin/sh\nnpm install\n\n# >>> sc git hooks >>>"));
+
+        assert_eq!(hook_script(Some(&appended)), (appended.clone(), "unchanged"));
+        let outdated = appended.replace("|| true", "");
+        assert_eq!(hook_script(Some(&outdated)), (appended, "updated"));
+    }
+}
@@ -0,0 +1,298 @@
+//! Knowledge-graph export (`sc export graph`).
+//!
+//! Writes a project's records as nodes and how they relate as directed
+//! edges, for graph tools such as Gephi, yEd or Cytoscape:
+//!
+//! | Node      | Edges                                                      |
+//! |-----------|------------------------------------------------------------|
+//! | `session` |                                                            |
+//! | `item`    | `in_session` to its session, `tagged` to each tag          |
+//! | `issue`   | `tagged` to each label, `in_plan` to its plan, and one per dependency (`blocks`, `parent-child`, ...) to the issue depended on |
+//! | `plan`    | `created_in_session` to the session that created it       |
+//! | `tag`     |                                                            |
+//!
+//! Item tags and issue labels share `tag` nodes. Node IDs are
+//! `<type>:<id>`, with the tag name as the ID of a tag. Dependencies on
+//! issues of other projects are left out.
+//!
+//! `--format json` writes `{"nodes": [...], "edges": [...]}`; `--format
+//! graphml` writes XML in the graph format of that name, with the node
+//! fields as `<data>` keys.
+
+use super::vault::item_tags;
+use crate::cli::{Database, GraphFormat};
+use crate::config::resolve_project_path;
+use crate::error::Result;
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::SqliteStorage;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// A project's records and their relations.
+#[derive(Serialize)]
+struct Graph {
+    project_path: String,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Serialize)]
+struct Node {
+    id: String,
+    /// `session`, `item`, `issue`, `plan` or `tag`.
+    #[serde(rename = "type")]
+    kind: String,
+    label: String,
+    /// Fields of the record worth showing, e.g. `status` or `category`.
+    attributes: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct Edge {
+    source: String,
+    target: String,
+    relation: String,
+}
+
+/// Output for export graph to a file.
+#[derive(Serialize)]
+struct ExportGraphOutput {
+    file: String,
+    format: String,
+    nodes: usize,
+    edges: usize,
+}
+
+json_schema!(ExportGraphOutput { file: String, format: String, nodes: usize, edges: usize });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("export graph", schema_for::<ExportGraphOutput>())]
+}
+
+/// Write the current project's graph to `output`, or stdout.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read or the file written.
+pub fn export(format: GraphFormat, output: Option<&PathBuf>, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let graph = build(storage, &project_path)?;
+
+    let content = match format {
+        GraphFormat::Json => serde_json::to_string_pretty(&graph)? + "\n",
+        GraphFormat::Graphml => graphml(&graph),
+    };
+    let format = match format {
+        GraphFormat::Json => "json",
+        GraphFormat::Graphml => "graphml",
+    };
+
+    let Some(path) = output else {
+        print!("{content}");
+        return Ok(());
+    };
+    if crate::is_dry_run() {
+        println!("Would write {} nodes and {} edges to {}", graph.nodes.len(), graph.edges.len(), path.display());
+        return Ok(());
+    }
+    std::fs::write(path, &content)?;
+
+    if json {
+        let output = ExportGraphOutput {
+            file: path.display().to_string(),
+            format: format.to_string(),
+            nodes: graph.nodes.len(),
+            edges: graph.edges.len(),
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if !crate::is_silent() {
+        println!("Exported {} nodes and {} edges to {}", graph.nodes.len(), graph.edges.len(), path.display());
+    }
+    Ok(())
+}
+
+fn build(storage: &SqliteStorage, project_path: &str) -> Result<Graph> {
+    let mut graph = Graph { project_path: project_path.to_string(), nodes: Vec::new(), edges: Vec::new() };
+    let mut tags = BTreeSet::new();
+
+    for session in storage.get_sessions_by_project(project_path)? {
+        let mut attributes = attributes(&[("status", session.status.to_string())]);
+        if let Some(branch) = session.branch {
+            attributes.insert("branch".to_string(), branch);
+        }
+        graph.node("session", &session.id, session.name, attributes);
+    }
+
+    for item in storage.get_context_items_by_project(project_path)? {
+        let id = graph.node(
+            "item",
+            &item.id,
+            item.key.clone(),
+            attributes(&[("category", item.category.to_string()), ("priority", item.priority.to_string())]),
+        );
+        graph.edge(&id, &format!("session:{}", item.session_id), "in_session");
+        for tag in item_tags(&item) {
+            graph.edge(&id, &format!("tag:{tag}"), "tagged");
+            tags.insert(tag);
+        }
+    }
+
+    let plans = storage.get_plans_by_project(project_path)?;
+    for plan in &plans {
+        let mut attributes = attributes(&[("status", plan.status.as_str().to_string())]);
+        if let Some(short_id) = &plan.short_id {
+            attributes.insert("short_id".to_string(), short_id.clone());
+        }
+        let id = graph.node("plan", &plan.id, plan.title.clone(), attributes);
+        if let Some(session) = &plan.created_in_session {
+            graph.edge(&id, &format!("session:{session}"), "created_in_session");
+        }
+    }
+
+    let issues = storage.get_issues_by_project(project_path)?;
+    let issue_ids: HashSet<&str> = issues.iter().map(|i| i.id.as_str()).collect();
+    for issue in &issues {
+        let mut attributes = attributes(&[
+            ("status", issue.status.to_string()),
+            ("priority", issue.priority.to_string()),
+            ("issue_type", issue.issue_type.to_string()),
+        ]);
+        if let Some(short_id) = &issue.short_id {
+            attributes.insert("short_id".to_string(), short_id.clone());
+        }
+        let id = graph.node("issue", &issue.id, issue.title.clone(), attributes);
+        if let Some(plan) = &issue.plan_id {
+            graph.edge(&id, &format!("plan:{plan}"), "in_plan");
+        }
+        for label in storage.get_issue_labels(&issue.id)? {
+            graph.edge(&id, &format!("tag:{label}"), "tagged");
+            tags.insert(label);
+        }
+        for link in storage.get_issue_dependencies(&issue.id)? {
+            if issue_ids.contains(link.id.as_str()) {
+                graph.edge(&id, &format!("issue:{}", link.id), &link.dependency_type);
+            }
+        }
+    }
+
+    for tag in tags {
+        graph.node("tag", &tag, tag.clone(), BTreeMap::new());
+    }
+
+    // Edges to records that aren't nodes, e.g. a plan since deleted
+    let node_ids: HashSet<String> = graph.nodes.iter().map(|n| n.id.clone()).collect();
+    graph.edges.retain(|e| node_ids.contains(&e.source) && node_ids.contains(&e.target));
+    Ok(graph)
+}
+
+impl Graph {
+    /// Add a node, returning its ID.
+    fn node(&mut self, kind: &str, id: &str, label: String, attributes: BTreeMap<String, String>) -> String {
+        let id = format!("{kind}:{id}");
+        self.nodes.push(Node { id: id.clone(), kind: kind.to_string(), label, attributes });
+        id
+    }
+
+    fn edge(&mut self, source: &str, target: &str, relation: &str) {
+        self.edges.push(Edge { source: source.to_string(), target: target.to_string(), relation: relation.to_string() });
+    }
+}
+
+fn attributes(pairs: &[(&str, String)]) -> BTreeMap<String, String> {
+    pairs.iter().map(|(name, value)| ((*name).to_string(), value.clone())).collect()
+}
+
+/// The graph as `GraphML` XML. Every node field becomes a `<data>` key.
+fn graphml(graph: &Graph) -> String {
+    let node_keys: BTreeSet<&str> = graph.nodes.iter().flat_map(|n| n.attributes.keys().map(String::as_str)).collect();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for key in ["type", "label"].into_iter().chain(node_keys.iter().copied()) {
+        let _ = writeln!(out, "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>", xml(key), xml(key));
+    }
+    out.push_str("  <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n");
+    let _ = writeln!(out, "  <graph id=\"{}\" edgedefault=\"directed\">", xml(&graph.project_path));
+    for node in &graph.nodes {
+        let _ = writeln!(out, "    <node id=\"{}\">", xml(&node.id));
+        let _ = writeln!(out, "      <data key=\"type\">{}</data>", xml(&node.kind));
+        let _ = writeln!(out, "      <data key=\"label\">{}</data>", xml(&node.label));
+        for (key, value) in &node.attributes {
+            let _ = writeln!(out, "      <data key=\"{}\">{}</data>", xml(key), xml(value));
+        }
+        out.push_str("    </node>\n");
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "    <edge source=\"{}\" target=\"{}\"><data key=\"relation\">{}</data></edge>",
+            xml(&edge.source),
+            xml(&edge.target),
+            xml(&edge.relation)
+        );
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters aren't allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\t' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ItemCategory, ItemPriority};
+
+    #[test]
+    fn test_graph_export() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_session("s1", "Auth work", None, Some("/p"), None, None, "actor").unwrap();
+        storage
+            .save_context_item("c1", "s1", "jwt", "Use JWT", Some(ItemCategory::Decision), Some(ItemPriority::High), "actor")
+            .unwrap();
+        storage.conn().execute("UPDATE context_items SET tags = '[\"auth\"]' WHERE id = 'c1'", []).unwrap();
+        storage
+            .create_issue("i1", Some("P-1"), "/p", "Login <form>", None, None, None, None, None, None, "actor")
+            .unwrap();
+        storage.create_issue("i2", Some("P-2"), "/p", "Tokens", None, None, None, None, None, None, "actor").unwrap();
+        storage.add_issue_dependency("i1", "i2", "blocks", "actor").unwrap();
+        storage.add_issue_labels("i1", &["auth".to_string()], "actor").unwrap();
+
+        let graph = build(&storage, "/p").unwrap();
+        let kinds: Vec<&str> = graph.nodes.iter().map(|n| n.kind.as_str()).collect();
+        assert_eq!(kinds, ["session", "item", "issue", "issue", "tag"]);
+        let edges: Vec<(&str, &str, &str)> =
+            graph.edges.iter().map(|e| (e.source.as_str(), e.target.as_str(), e.relation.as_str())).collect();
+        assert_eq!(
+            edges,
+            [
+                ("item:c1", "session:s1", "in_session"),
+                ("item:c1", "tag:auth", "tagged"),
+                ("issue:i1", "tag:auth", "tagged"),
+                ("issue:i1", "issue:i2", "blocks"),
+            ]
+        );
+
+        let xml = graphml(&graph);
+        assert!(xml.contains("<data key=\"label\">Login &lt;form&gt;</data>"));
+        assert!(xml.contains("<edge source=\"issue:i1\" target=\"issue:i2\"><data key=\"relation\">blocks</data></edge>"));
+    }
+}
@@ -0,0 +1,355 @@
+//! Text search across stored records (`sc grep`).
+//!
+//! `sc search` ranks records by meaning; this finds exact text, the way
+//! grep does in a codebase. Every text field is searched line by line as if
+//! it were a file with a path: `item/<session>/<key>`, `issue/<id>/<field>`,
+//! `plan/<id>/<field>` and `memory/<key>`. Matches print as
+//! `path:line:text`, with `--context` lines around them printed as
+//! `path-line-text` and `--` between separate hunks. `--stream` writes each
+//! match as a JSON line as soon as its record has been searched (see
+//! [`crate::cli::shell`]).
+
+use crate::cli::Database;
+use crate::config::resolve_project_path;
+use crate::embeddings::EmbeddingEntity;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::SqliteStorage;
+use colored::Colorize;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::Value;
+
+/// What `sc grep` looks for.
+pub struct GrepPattern<'a> {
+    pub text: &'a str,
+    /// `text` is a regex rather than a literal string.
+    pub regex: bool,
+    pub ignore_case: bool,
+}
+
+/// Options for `sc grep`.
+pub struct GrepOptions<'a> {
+    pub pattern: GrepPattern<'a>,
+    /// Entity names as given; empty or `all` for every kind.
+    pub entities: &'a [String],
+    pub context: usize,
+    pub all_projects: bool,
+    pub limit: usize,
+    pub stream: bool,
+}
+
+/// Output for grep.
+#[derive(Serialize)]
+struct GrepOutput {
+    pattern: String,
+    entities: Vec<String>,
+    matches: Vec<GrepMatch>,
+    count: usize,
+    /// More lines matched than `--limit`.
+    truncated: bool,
+}
+
+/// One matching line.
+#[derive(Serialize)]
+struct GrepMatch {
+    entity: String,
+    id: String,
+    /// File-style path of the searched text, e.g. `issue/SC-4/description`.
+    path: String,
+    /// 1-based.
+    line: usize,
+    text: String,
+    /// Up to `--context` lines before and after the match.
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+json_schema!(GrepOutput {
+    pattern: String,
+    entities: Vec<String>,
+    matches: Vec<GrepMatch>,
+    count: usize,
+    truncated: bool,
+});
+json_schema!(GrepMatch {
+    entity: String,
+    id: String,
+    path: String,
+    line: usize,
+    text: String,
+    before: Vec<String>,
+    after: Vec<String>,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("grep", schema_for::<GrepOutput>())]
+}
+
+/// A text field of a record, searched as one file.
+struct Document {
+    entity: EmbeddingEntity,
+    id: String,
+    path: String,
+    text: String,
+}
+
+/// Consecutive lines of a document shown together: matches and their
+/// context, 0-based and inclusive.
+#[derive(Debug, PartialEq, Eq)]
+struct Hunk {
+    start: usize,
+    end: usize,
+    matches: Vec<usize>,
+}
+
+/// Execute the grep command.
+///
+/// # Errors
+///
+/// Returns an error if the pattern or an entity name is invalid, or the
+/// database cannot be queried.
+pub fn execute(options: &GrepOptions<'_>, db: &mut Database, json: bool) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+    let entities = parse_entities(options.entities)?;
+    let re = options.pattern.compile()?;
+
+    let storage = db.storage()?;
+    let projects: Vec<(String, String)> = if options.all_projects {
+        storage.list_projects(u32::MAX as usize)?.into_iter().map(|p| (p.project_path, p.name)).collect()
+    } else {
+        vec![(resolve_project_path(storage, None)?, String::new())]
+    };
+
+    let mut matches = Vec::new();
+    let mut count = 0;
+    let mut hunks = Vec::new();
+    let mut truncated = false;
+    // One kind of record at a time, so streamed matches start arriving
+    // before everything is loaded
+    'scan: for (project_path, name) in &projects {
+        let prefix = if options.all_projects { format!("{name}/") } else { String::new() };
+        for entity in &entities {
+            let mut documents = Vec::new();
+            load_documents(storage, project_path, *entity, &prefix, &mut documents)?;
+            for document in documents {
+                let lines: Vec<&str> = document.text.lines().collect();
+                let mut found: Vec<usize> = (0..lines.len()).filter(|&i| re.is_match(lines[i])).collect();
+                if found.is_empty() {
+                    continue;
+                }
+                if count + found.len() > options.limit {
+                    found.truncate(options.limit - count);
+                    truncated = true;
+                }
+                count += found.len();
+                for &i in &found {
+                    let found_match = GrepMatch {
+                        entity: document.entity.as_str().to_string(),
+                        id: document.id.clone(),
+                        path: document.path.clone(),
+                        line: i + 1,
+                        text: lines[i].to_string(),
+                        before: lines[i.saturating_sub(options.context)..i].iter().map(ToString::to_string).collect(),
+                        after: lines[i + 1..(i + 1 + options.context).min(lines.len())]
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect(),
+                    };
+                    if options.stream {
+                        crate::cli::shell::emit(&found_match)?;
+                    } else {
+                        matches.push(found_match);
+                    }
+                }
+                if !options.stream {
+                    let document_hunks = hunks_of(&found, options.context, lines.len());
+                    hunks.push((document, document_hunks));
+                }
+                if truncated {
+                    break 'scan;
+                }
+            }
+        }
+    }
+
+    if options.stream {
+        if truncated {
+            eprintln!("Stopped after {} matching lines (--limit)", options.limit);
+        }
+        return Ok(());
+    }
+    if crate::is_silent() {
+        let mut paths: Vec<&str> = matches.iter().map(|m| m.path.as_str()).collect();
+        paths.dedup();
+        for path in paths {
+            println!("{path}");
+        }
+        return Ok(());
+    }
+    if json {
+        let output = GrepOutput {
+            pattern: options.pattern.text.to_string(),
+            entities: entities.iter().map(|e| e.as_str().to_string()).collect(),
+            count: matches.len(),
+            matches,
+            truncated,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    print_hunks(&hunks, &re, options.context);
+    if truncated {
+        eprintln!("Stopped after {} matching lines (--limit)", options.limit);
+    }
+    Ok(())
+}
+
+fn parse_entities(names: &[String]) -> Result<Vec<EmbeddingEntity>> {
+    if names.is_empty() || names.iter().any(|n| n.trim().eq_ignore_ascii_case("all")) {
+        return Ok(EmbeddingEntity::ALL.to_vec());
+    }
+    let mut entities = Vec::new();
+    for name in names {
+        let entity: EmbeddingEntity = name.parse().map_err(|e: String| Error::InvalidArgument(e.replace(" or memory)", ", memory or all)")))?;
+        if !entities.contains(&entity) {
+            entities.push(entity);
+        }
+    }
+    Ok(entities)
+}
+
+impl GrepPattern<'_> {
+    /// The pattern as a regex: escaped unless `regex`.
+    fn compile(&self) -> Result<Regex> {
+        if self.text.is_empty() {
+            return Err(Error::InvalidArgument("Pattern is empty".to_string()));
+        }
+        let source = if self.regex { self.text.to_string() } else { regex::escape(self.text) };
+        RegexBuilder::new(&source)
+            .case_insensitive(self.ignore_case)
+            .build()
+            .map_err(|e| Error::InvalidArgument(format!("Invalid regex '{}': {e}", self.text)))
+    }
+}
+
+/// Print the hunks grep-style, `--` between them when there is context.
+fn print_hunks(hunks: &[(Document, Vec<Hunk>)], re: &Regex, context: usize) {
+    let mut first = true;
+    for (document, document_hunks) in hunks {
+        let lines: Vec<&str> = document.text.lines().collect();
+        for hunk in document_hunks {
+            if !first && context > 0 {
+                println!("{}", "--".cyan());
+            }
+            first = false;
+            for (i, line) in lines.iter().enumerate().take(hunk.end + 1).skip(hunk.start) {
+                let separator = if hunk.matches.contains(&i) { ":" } else { "-" };
+                let text = if separator == ":" { highlight(re, line) } else { (*line).to_string() };
+                println!("{}{separator}{}{separator}{text}", document.path.magenta(), (i + 1).to_string().green());
+            }
+        }
+    }
+}
+
+/// Add the text fields of one kind of record in a project to `documents`.
+fn load_documents(
+    storage: &SqliteStorage,
+    project_path: &str,
+    entity: EmbeddingEntity,
+    prefix: &str,
+    documents: &mut Vec<Document>,
+) -> Result<()> {
+    let mut add = |id: &str, path: String, text: Option<&str>| {
+        if let Some(text) = text.filter(|t| !t.is_empty()) {
+            documents.push(Document { entity, id: id.to_string(), path: format!("{prefix}{path}"), text: text.to_string() });
+        }
+    };
+    match entity {
+        EmbeddingEntity::ContextItem => {
+            for item in storage.get_context_items_by_project(project_path)? {
+                add(&item.id, format!("item/{}/{}", item.session_id, item.key), Some(&item.value));
+            }
+        }
+        EmbeddingEntity::Issue => {
+            for issue in storage.list_issues(project_path, Some("all"), None, Some(u32::MAX))? {
+                let name = issue.short_id.as_deref().unwrap_or(&issue.id);
+                add(&issue.id, format!("issue/{name}/title"), Some(&issue.title));
+                add(&issue.id, format!("issue/{name}/description"), issue.description.as_deref());
+                add(&issue.id, format!("issue/{name}/details"), issue.details.as_deref());
+            }
+        }
+        EmbeddingEntity::Plan => {
+            for plan in storage.list_plans(project_path, Some("all"), u32::MAX as usize)? {
+                let name = plan.short_id.as_deref().unwrap_or(&plan.id);
+                add(&plan.id, format!("plan/{name}/title"), Some(&plan.title));
+                add(&plan.id, format!("plan/{name}/content"), plan.content.as_deref());
+                add(&plan.id, format!("plan/{name}/success_criteria"), plan.success_criteria.as_deref());
+            }
+        }
+        EmbeddingEntity::Memory => {
+            for memory in storage.list_memory(project_path, None)? {
+                add(&memory.id, format!("memory/{}", memory.key), Some(&memory.value));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Group matching lines, with `context` lines around each, into hunks;
+/// overlapping or adjacent ones merge.
+fn hunks_of(matches: &[usize], context: usize, line_count: usize) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for &i in matches {
+        let start = i.saturating_sub(context);
+        let end = (i + context).min(line_count.saturating_sub(1));
+        match hunks.last_mut() {
+            Some(last) if start <= last.end + 1 => {
+                last.end = end;
+                last.matches.push(i);
+            }
+            _ => hunks.push(Hunk { start, end, matches: vec![i] }),
+        }
+    }
+    hunks
+}
+
+/// `line` with the matches in bold red.
+fn highlight(re: &Regex, line: &str) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for found in re.find_iter(line) {
+        out.push_str(&line[last..found.start()]);
+        out.push_str(&found.as_str().red().bold().to_string());
+        last = found.end();
+    }
+    out.push_str(&line[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hunks_merge_overlapping_context() {
+        assert_eq!(
+            hunks_of(&[1, 3, 9], 1, 10),
+            vec![Hunk { start: 0, end: 4, matches: vec![1, 3] }, Hunk { start: 8, end: 9, matches: vec![9] }]
+        );
+        assert_eq!(hunks_of(&[2, 3], 0, 5), vec![Hunk { start: 2, end: 3, matches: vec![2, 3] }]);
+        assert_eq!(hunks_of(&[2, 4], 0, 5).len(), 2);
+
+        let compile = |text, regex, ignore_case| GrepPattern { text, regex, ignore_case }.compile();
+        assert!(compile("retry_policy(", false, false).unwrap().is_match("see retry_policy(3)"));
+        assert!(compile("retry_\\w+", true, false).unwrap().is_match("retry_policy"));
+        assert!(compile("RETRY", false, true).unwrap().is_match("retry"));
+        assert!(compile("(", true, false).is_err());
+        assert_eq!(parse_entities(&["all".to_string()]).unwrap().len(), 4);
+        assert_eq!(parse_entities(&["issues".to_string(), "issue".to_string()]).unwrap(), vec![EmbeddingEntity::Issue]);
+    }
+}
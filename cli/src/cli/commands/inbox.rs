@@ -0,0 +1,137 @@
+//! Inbox command (`sc inbox`).
+//!
+//! A queue of what other actors did that concerns you: `@mentions` in issue
+//! text and comments, and any activity on issues assigned to you or that
+//! you watch (`sc issue watch`). By default only entries newer than the
+//! last `--mark-read` are shown.
+
+use crate::cli::Database;
+use crate::config::{default_actor, resolve_project_path};
+use crate::error::Result;
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::InboxItem;
+use serde::Serialize;
+use serde_json::Value;
+
+const DAY_MS: i64 = 86_400_000;
+
+#[derive(Serialize)]
+struct InboxOutput {
+    actor: String,
+    since: i64,
+    items: Vec<InboxItem>,
+    count: usize,
+    marked_read: bool,
+}
+
+json_schema!(InboxOutput { actor: String, since: i64, items: Vec<InboxItem>, count: usize, marked_read: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("inbox", schema_for::<InboxOutput>())]
+}
+
+/// Options for `sc inbox`.
+pub struct InboxOptions {
+    pub all: bool,
+    pub days: u64,
+    pub all_projects: bool,
+    pub limit: usize,
+    pub mark_read: bool,
+}
+
+/// Execute the inbox command.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or queried.
+pub fn execute(
+    options: &InboxOptions,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+    let actor = storage.resolve_actor(&actor.map_or_else(default_actor, ToString::to_string))?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let days = i64::try_from(options.days).unwrap_or(i64::MAX / DAY_MS);
+    let window_start = now - days.saturating_mul(DAY_MS);
+    let read_at = storage.get_inbox_read_at(&actor)?;
+    let since = match read_at {
+        Some(read_at) if !options.all => read_at,
+        _ => window_start,
+    };
+    let project_path = if options.all_projects {
+        None
+    } else {
+        resolve_project_path(storage, None).ok()
+    };
+
+    let items = storage.get_inbox(&actor, since, project_path.as_deref(), options.limit)?;
+    let marked = options.mark_read && !crate::is_dry_run();
+    if marked {
+        storage.mark_inbox_read(&actor, now)?;
+    }
+
+    if json {
+        let output = InboxOutput { actor, since, count: items.len(), items, marked_read: marked };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        println!("Inbox empty for {actor}.");
+    } else {
+        println!("Inbox for {actor} ({}):", items.len());
+        for item in &items {
+            print_item(item);
+        }
+    }
+    if marked {
+        println!();
+        println!("Marked read.");
+    } else if !items.is_empty() {
+        println!();
+        println!("Run `sc inbox --mark-read` to clear.");
+    }
+    Ok(())
+}
+
+fn print_item(item: &InboxItem) {
+    let when = chrono::DateTime::from_timestamp_millis(item.created_at)
+        .map_or_else(String::new, |dt| dt.format("%Y-%m-%d %H:%M").to_string());
+    let short_id = item.short_id.as_deref().unwrap_or(&item.issue_id);
+    println!(
+        "  {when}  [{short_id}] {}  ({}, {})",
+        item.title, item.reason, item.status
+    );
+    println!("      {} {}", item.actor, describe(item));
+}
+
+/// One-line summary of what happened.
+fn describe(item: &InboxItem) -> String {
+    let excerpt = |text: &Option<String>| {
+        let text = text.as_deref().unwrap_or_default().replace('\n', " ");
+        if text.chars().count() > 80 {
+            format!("{}...", text.chars().take(77).collect::<String>())
+        } else {
+            text
+        }
+    };
+    match item.event_type.as_str() {
+        "issue_mentioned" => format!("mentioned you: {}", excerpt(&item.comment)),
+        "issue_commented" => format!("commented: {}", excerpt(&item.comment)),
+        "issue_work_logged" => format!("logged work: {}", excerpt(&item.comment)),
+        "issue_created" => "created the issue".to_string(),
+        "issue_closed" => "closed the issue".to_string(),
+        "issue_claimed" => "claimed the issue".to_string(),
+        "issue_released" => "released the issue".to_string(),
+        "issue_claim_expired" => "released your claim: its lease ran out".to_string(),
+        "issue_updated" => match (&item.old_value, &item.new_value) {
+            (Some(old), Some(new)) => format!("changed status {old} -> {new}"),
+            _ => "updated the issue".to_string(),
+        },
+        other => other.replace('_', " "),
+    }
+}
@@ -10,11 +10,19 @@
 //!
 //! The database is shared across all projects, while each project maintains
 //! its own git-friendly JSONL exports.
+//!
+//! In a monorepo, `sc init --subproject packages/api` registers a directory
+//! of the current project as a sub-project instead; see
+//! [`crate::config::resolve_subproject`].
 
-use crate::config::{global_savecontext_dir, is_test_mode};
+use crate::cli::Database;
+use crate::config::{global_savecontext_dir, is_test_mode, subproject_path};
+use crate::core::SaveContext;
 use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, one_of, schema_for};
 use crate::sync::gitignore_content;
 use serde::Serialize;
+use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -27,6 +35,22 @@ struct InitOutput {
     export_dir: Option<PathBuf>,
 }
 
+#[derive(Serialize)]
+struct SubprojectInitOutput {
+    project_path: String,
+    subproject: String,
+    /// False when it was already registered.
+    created: bool,
+}
+
+json_schema!(InitOutput { path: PathBuf, database: Option<PathBuf>, export_dir: Option<PathBuf> });
+json_schema!(SubprojectInitOutput { project_path: String, subproject: String, created: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("init", one_of(&[schema_for::<InitOutput>(), schema_for::<SubprojectInitOutput>()]))]
+}
+
 /// Execute the init command.
 ///
 /// - **Global mode**: Creates the shared database at `~/.savecontext/data/savecontext.db`
@@ -167,6 +191,47 @@ fn execute_project_init(force: bool, json: bool) -> Result<()> {
     Ok(())
 }
 
+/// Register `path`, relative to the current project's root, as one of its
+/// sub-projects.
+///
+/// # Errors
+///
+/// Returns an error if there is no current project, the path isn't a
+/// directory inside it, or the database fails.
+pub fn register_subproject(path: &str, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let mut sc = SaveContext::discover_in(db.storage()?, actor)?;
+    let (project_path, actor) = (sc.project_path().to_string(), sc.actor().to_string());
+    let path = subproject_path(path)?;
+    if !Path::new(&project_path).join(&path).is_dir() {
+        return Err(Error::InvalidArgument(format!("No directory '{path}' in project {project_path}")));
+    }
+
+    let dry_run = crate::is_dry_run();
+    let created = sc.storage_mut().with_transaction(|storage| {
+        let result = storage.add_subproject(&project_path, &path, &actor);
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+
+    if crate::is_silent() {
+        println!("{path}");
+    } else if json {
+        let output = SubprojectInitOutput { project_path, subproject: path, created };
+        let mut value = serde_json::to_value(&output)?;
+        if dry_run {
+            value["dry_run"] = true.into();
+        }
+        println!("{value}");
+    } else if !created {
+        println!("Sub-project {path} is already registered in {project_path}");
+    } else {
+        let verb = if dry_run { "Would register" } else { "Registered" };
+        println!("{verb} sub-project {path} in {project_path}");
+        println!("  Issues and memory created inside it are tagged with it, and `sc issue list` and `sc prime` there show only its own and project-wide ones (--root for all).");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,15 +1,22 @@
 //! Issue command implementations.
 
-use crate::cli::{
-    IssueCommands, IssueCreateArgs, IssueDepCommands, IssueLabelCommands, IssueListArgs,
-    IssueUpdateArgs,
-};
-use crate::config::{default_actor, resolve_db_path, resolve_project_path};
+use crate::cli::{Database, IssueCommands, IssueCreateArgs, IssueDepCommands, IssueLabelCommands, IssueListArgs, IssueUpdateArgs};
+use crate::cli::commands::config::{load_config, load_issue_workflow, load_policy, load_routing};
+use crate::config::{default_actor, in_subproject, resolve_project_path, resolve_subproject};
+use crate::core::issues::workflow_for_issue;
+use crate::core::{NewIssue, SaveContext};
 use crate::error::{Error, Result};
-use crate::storage::SqliteStorage;
+use crate::import::csv_report::{Column, DEFAULT_COLUMNS};
+use crate::import::{ExportFormat, ImportFormat, ImportedIssue};
+use crate::json_schema::{json_schema, merged, object, one_of, schema_for, JsonSchema};
+use crate::model::{ActorType, AgentWorkload, IssueStatus, IssueType, IssueWorkflow, Policy, Routing, RoutingPolicy};
+use crate::embeddings::EmbeddingEntity;
+use crate::storage::{EntitySearchResult, EpicProgress, Issue, IssueLink, IssueWaves, SqliteStorage, WorklogEntry};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::io::BufRead;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Input for batch issue creation (from JSON).
 #[derive(Debug, Deserialize)]
@@ -32,7 +39,7 @@ struct BatchIssue {
     #[serde(default)]
     details: Option<String>,
     #[serde(default)]
-    issue_type: Option<String>,
+    issue_type: Option<IssueType>,
     #[serde(default)]
     priority: Option<i32>,
     #[serde(default)]
@@ -58,6 +65,10 @@ struct BatchDependency {
 struct BatchOutput {
     issues: Vec<BatchIssueResult>,
     dependencies: Vec<BatchDepResult>,
+    /// Short ID created for each input index (`"0"`, `"1"`, ...).
+    short_ids: BTreeMap<String, String>,
+    count: usize,
+    dependency_count: usize,
 }
 
 /// Result for single issue in batch.
@@ -74,18 +85,33 @@ struct BatchIssueResult {
 struct BatchDepResult {
     issue_id: String,
     depends_on_id: String,
+    issue_short_id: String,
+    depends_on_short_id: String,
     dependency_type: String,
 }
 
+/// Dependency types `sc issue batch` accepts.
+const BATCH_DEPENDENCY_TYPES: [&str; 4] = ["blocks", "related", "parent-child", "discovered-from"];
+
+/// Where a batch issue's `parentId` points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BatchParent {
+    /// `$N`: another issue of the batch.
+    Index(usize),
+    /// The ID of an issue that already exists.
+    Existing(String),
+}
+
 /// Output for issue create.
 #[derive(Serialize)]
 struct IssueCreateOutput {
     id: String,
     short_id: Option<String>,
     title: String,
-    status: String,
+    status: IssueStatus,
     priority: i32,
-    issue_type: String,
+    issue_type: IssueType,
+    subproject: Option<String>,
 }
 
 /// Output for issue list.
@@ -95,57 +121,512 @@ struct IssueListOutput {
     count: usize,
 }
 
+/// Output for issue route.
+#[derive(Serialize)]
+struct RouteOutput {
+    policy: String,
+    assignments: Vec<RouteAssignment>,
+    count: usize,
+}
+
+/// One ready issue claimed for one agent.
+#[derive(Serialize)]
+struct RouteAssignment {
+    agent: String,
+    issue_id: String,
+    short_id: Option<String>,
+    title: String,
+    /// The agent's skills the issue is labelled with.
+    matched_skills: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct IssueShowOutput<'a> {
+    #[serde(flatten)]
+    issue: &'a Issue,
+    #[serde(flatten)]
+    relations: IssueRelations,
+}
+
+#[derive(Serialize)]
+struct IssueRelations {
+    labels: Vec<String>,
+    parent: Option<IssueLink>,
+    children: Vec<IssueLink>,
+    /// Issues this one depends on through `blocks`.
+    blocked_by: Vec<IssueLink>,
+    /// Issues that depend on this one through `blocks`.
+    blocks: Vec<IssueLink>,
+    /// Other dependencies of this issue, e.g. `duplicate-of`.
+    depends_on: Vec<IssueLink>,
+    /// Other issues depending on this one, e.g. `discovered-from`.
+    depended_on_by: Vec<IssueLink>,
+    plan: Option<PlanRef>,
+    /// Commits whose message mentions the issue.
+    commits: Vec<LinkedCommit>,
+    comments: Vec<IssueComment>,
+    /// Latest changes first, without comments and mentions.
+    events: Vec<IssueEvent>,
+    watchers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    progress: Option<EpicProgress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    close_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_logged: Option<f64>,
+    /// When the claim's lease runs out (Unix ms), if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lease_expires_at: Option<i64>,
+    /// Worklog entries, oldest first; only with `--logs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worklog: Option<Vec<WorklogEntry>>,
+    /// Records of the project closest in meaning; only with `--related`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    related: Option<Vec<EntitySearchResult>>,
+}
+
+/// Output for issue log.
+#[derive(Serialize)]
+struct IssueLogOutput {
+    issue_id: String,
+    text: String,
+    /// Time entry created for `--hours`.
+    time_entry_id: Option<String>,
+    time_entry_short_id: Option<String>,
+    hours: Option<f64>,
+}
+
+/// Output for issue create `--file`.
+#[derive(Serialize)]
+struct IssueCreateFileOutput {
+    issues: Vec<BatchIssueResult>,
+    count: usize,
+}
+
+/// Output for issue import.
+#[derive(Serialize)]
+struct IssueImportOutput<'a> {
+    format: &'a str,
+    file: &'a Path,
+    count: usize,
+    /// Parent links created between imported issues.
+    parents_linked: usize,
+    issues: Vec<ImportIssueResult>,
+    updates: Vec<BacklogUpdate>,
+    warnings: Vec<String>,
+}
+
+/// Output for issue export to a file.
+#[derive(Serialize)]
+struct IssueExportOutput<'a> {
+    file: &'a Path,
+    count: usize,
+}
+
+/// Output for issue update.
+#[derive(Serialize)]
+struct IssueUpdateOutput<'a> {
+    id: &'a str,
+    updated: bool,
+}
+
+/// Output for issue complete, claim, release and delete.
+#[derive(Serialize)]
+struct IssueIdsOutput<'a> {
+    ids: Vec<&'a str>,
+    /// Status the issues now have (not set by delete).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<IssueStatus>,
+    /// Agent the issues are assigned to (claim).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assigned_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    close_reason: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted: Option<bool>,
+    count: usize,
+}
+
+/// Output for issue watch.
+#[derive(Serialize)]
+struct IssueWatchOutput<'a> {
+    ids: &'a [String],
+    watching: bool,
+    actor: String,
+    /// Issues whose watch state changed.
+    changed: usize,
+}
+
+/// Output for issue comment.
+#[derive(Serialize)]
+struct IssueCommentOutput {
+    id: String,
+    commented: bool,
+    /// Actors `@mentioned` in the comment.
+    mentions: Vec<String>,
+}
+
+/// Output for issue label add and remove.
+#[derive(Serialize)]
+struct IssueLabelOutput<'a> {
+    id: &'a str,
+    /// `add` or `remove`.
+    action: &'static str,
+    labels: &'a [String],
+}
+
+/// Output for issue dep add.
+#[derive(Serialize)]
+struct DepAddOutput<'a> {
+    issue_id: &'a str,
+    depends_on_id: &'a str,
+    dependency_type: &'a str,
+}
+
+/// Output for issue dep remove.
+#[derive(Serialize)]
+struct DepRemoveOutput<'a> {
+    issue_id: &'a str,
+    depends_on_id: &'a str,
+    removed: bool,
+}
+
+/// Output for issue duplicate.
+#[derive(Serialize)]
+struct DuplicateOutput<'a> {
+    id: &'a str,
+    duplicate_of: &'a str,
+    status: IssueStatus,
+}
+
+/// Output for issue count.
+#[derive(Serialize)]
+struct IssueCountOutput<'a> {
+    groups: Vec<IssueCountGroup<'a>>,
+    total: i64,
+    group_by: &'a str,
+}
+
+#[derive(Serialize)]
+struct IssueCountGroup<'a> {
+    key: &'a str,
+    count: i64,
+}
+
+/// Output for issue stale.
+#[derive(Serialize)]
+struct StaleOutput<'a> {
+    issues: Vec<StaleIssue<'a>>,
+    count: usize,
+    threshold_days: u64,
+}
+
+#[derive(Serialize)]
+struct StaleIssue<'a> {
+    issue: &'a Issue,
+    /// Days since the issue was last updated.
+    stale_days: i64,
+}
+
+/// Output for issue blocked.
+#[derive(Serialize)]
+struct BlockedOutput<'a> {
+    blocked_issues: Vec<BlockedIssue<'a>>,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct BlockedIssue<'a> {
+    issue: &'a Issue,
+    blocked_by: &'a [Issue],
+}
+
+/// An issue of `issue dep tree` with the issues depending on it.
+#[derive(Serialize)]
+struct TreeNode<'a> {
+    issue: &'a Issue,
+    children: Vec<TreeNode<'a>>,
+}
+
+/// Output for issue dep tree without an ID: one tree per epic.
+#[derive(Serialize)]
+struct DepTreesOutput<'a> {
+    trees: Vec<TreeNode<'a>>,
+    count: usize,
+}
+
+/// `$anchor` of the tree node schema, which its children refer back to.
+const TREE_NODE_ANCHOR: &str = "issue-tree-node";
+
+/// Nodes nest, so `children` refers back to the node schema by anchor.
+impl JsonSchema for TreeNode<'_> {
+    fn json_schema() -> Value {
+        let children = serde_json::json!({ "type": "array", "items": { "$ref": format!("#{TREE_NODE_ANCHOR}") } });
+        let mut schema = object(&[("issue", schema_for::<Issue>(), true), ("children", children, true)]);
+        schema["$anchor"] = Value::from(TREE_NODE_ANCHOR);
+        schema
+    }
+}
+
+#[derive(Serialize)]
+struct PlanRef {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    status: String,
+}
+
+#[derive(Serialize)]
+struct LinkedCommit {
+    hash: String,
+    subject: String,
+    /// Commit time (Unix ms).
+    committed_at: i64,
+}
+
+#[derive(Serialize)]
+struct IssueComment {
+    actor: String,
+    text: String,
+    created_at: i64,
+}
+
+#[derive(Serialize)]
+struct IssueEvent {
+    event_type: String,
+    actor: String,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    comment: Option<String>,
+    created_at: i64,
+}
+
+json_schema!(IssueRelations {
+    labels: Vec<String>,
+    parent: Option<IssueLink>,
+    children: Vec<IssueLink>,
+    blocked_by: Vec<IssueLink>,
+    blocks: Vec<IssueLink>,
+    depends_on: Vec<IssueLink>,
+    depended_on_by: Vec<IssueLink>,
+    plan: Option<PlanRef>,
+    commits: Vec<LinkedCommit>,
+    comments: Vec<IssueComment>,
+    events: Vec<IssueEvent>,
+    watchers: Vec<String>,
+    progress: Option<EpicProgress>,
+    close_reason: Option<String>,
+    time_logged: Option<f64>,
+    lease_expires_at: Option<i64>,
+    worklog: Option<Vec<WorklogEntry>>,
+    related: Option<Vec<EntitySearchResult>>,
+});
+json_schema!(IssueLogOutput {
+    issue_id: String,
+    text: String,
+    time_entry_id: Option<String>,
+    time_entry_short_id: Option<String>,
+    hours: Option<f64>,
+});
+json_schema!(PlanRef { id: String, short_id: Option<String>, title: String, status: String });
+json_schema!(LinkedCommit { hash: String, subject: String, committed_at: i64 });
+json_schema!(IssueComment { actor: String, text: String, created_at: i64 });
+json_schema!(IssueEvent {
+    event_type: String,
+    actor: String,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    comment: Option<String>,
+    created_at: i64,
+});
+json_schema!(BatchOutput {
+    issues: Vec<BatchIssueResult>,
+    dependencies: Vec<BatchDepResult>,
+    short_ids: BTreeMap<String, String>,
+    count: usize,
+    dependency_count: usize,
+});
+json_schema!(BatchIssueResult { id: String, short_id: Option<String>, title: String, index: usize });
+json_schema!(BatchDepResult {
+    issue_id: String,
+    depends_on_id: String,
+    issue_short_id: String,
+    depends_on_short_id: String,
+    dependency_type: String,
+});
+json_schema!(IssueCreateOutput {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    status: IssueStatus,
+    priority: i32,
+    issue_type: IssueType,
+    subproject: Option<String>,
+});
+json_schema!(IssueListOutput { issues: Vec<crate::storage::Issue>, count: usize });
+json_schema!(IssueCreateFileOutput { issues: Vec<BatchIssueResult>, count: usize });
+json_schema!(IssueImportOutput<'a> {
+    format: &'a str,
+    file: &'a Path,
+    count: usize,
+    parents_linked: usize,
+    issues: Vec<ImportIssueResult>,
+    updates: Vec<BacklogUpdate>,
+    warnings: Vec<String>,
+});
+json_schema!(ImportIssueResult { id: String, short_id: String, external_id: String, title: String, status: String });
+json_schema!(BacklogUpdate {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    old_title: Option<String>,
+    status: Option<(String, String)>,
+});
+json_schema!(IssueExportOutput<'a> { file: &'a Path, count: usize });
+json_schema!(IssueUpdateOutput<'a> { id: &'a str, updated: bool });
+json_schema!(IssueIdsOutput<'a> {
+    ids: Vec<&'a str>,
+    status: Option<IssueStatus>,
+    assigned_to: Option<String>,
+    close_reason: Option<&'a str>,
+    deleted: Option<bool>,
+    count: usize,
+});
+json_schema!(IssueWatchOutput<'a> { ids: &'a [String], watching: bool, actor: String, changed: usize });
+json_schema!(IssueCommentOutput { id: String, commented: bool, mentions: Vec<String> });
+json_schema!(IssueLabelOutput<'a> { id: &'a str, action: &'static str, labels: &'a [String] });
+json_schema!(DepAddOutput<'a> { issue_id: &'a str, depends_on_id: &'a str, dependency_type: &'a str });
+json_schema!(DepRemoveOutput<'a> { issue_id: &'a str, depends_on_id: &'a str, removed: bool });
+json_schema!(DuplicateOutput<'a> { id: &'a str, duplicate_of: &'a str, status: IssueStatus });
+json_schema!(IssueCountOutput<'a> { groups: Vec<IssueCountGroup<'a>>, total: i64, group_by: &'a str });
+json_schema!(IssueCountGroup<'a> { key: &'a str, count: i64 });
+json_schema!(StaleOutput<'a> { issues: Vec<StaleIssue<'a>>, count: usize, threshold_days: u64 });
+json_schema!(StaleIssue<'a> { issue: &'a Issue, stale_days: i64 });
+json_schema!(BlockedOutput<'a> { blocked_issues: Vec<BlockedIssue<'a>>, count: usize });
+json_schema!(BlockedIssue<'a> { issue: &'a Issue, blocked_by: &'a [Issue] });
+json_schema!(RouteOutput { policy: String, assignments: Vec<RouteAssignment>, count: usize });
+json_schema!(RouteAssignment {
+    agent: String,
+    issue_id: String,
+    short_id: Option<String>,
+    title: String,
+    matched_skills: Vec<String>,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("issue create", one_of(&[schema_for::<IssueCreateOutput>(), schema_for::<IssueCreateFileOutput>()])),
+        ("issue list", schema_for::<IssueListOutput>()),
+        ("issue show", merged(&[schema_for::<Issue>(), schema_for::<IssueRelations>()])),
+        ("issue ready", schema_for::<IssueListOutput>()),
+        ("issue waves", schema_for::<IssueWaves>()),
+        ("issue next-block", schema_for::<IssueListOutput>()),
+        ("issue route", schema_for::<RouteOutput>()),
+        ("issue batch", schema_for::<BatchOutput>()),
+        ("issue log", schema_for::<IssueLogOutput>()),
+        ("issue update", schema_for::<IssueUpdateOutput<'_>>()),
+        ("issue complete", schema_for::<IssueIdsOutput<'_>>()),
+        ("issue claim", schema_for::<IssueIdsOutput<'_>>()),
+        ("issue release", schema_for::<IssueIdsOutput<'_>>()),
+        ("issue delete", schema_for::<IssueIdsOutput<'_>>()),
+        ("issue watch", schema_for::<IssueWatchOutput<'_>>()),
+        ("issue comment", schema_for::<IssueCommentOutput>()),
+        ("issue label add", schema_for::<IssueLabelOutput<'_>>()),
+        ("issue label remove", schema_for::<IssueLabelOutput<'_>>()),
+        ("issue dep add", schema_for::<DepAddOutput<'_>>()),
+        ("issue dep remove", schema_for::<DepRemoveOutput<'_>>()),
+        ("issue dep tree", dep_tree_schema()),
+        ("issue clone", schema_for::<Issue>()),
+        ("issue duplicate", schema_for::<DuplicateOutput<'_>>()),
+        ("issue import", schema_for::<IssueImportOutput<'_>>()),
+        ("issue export", schema_for::<IssueExportOutput<'_>>()),
+        ("issue count", schema_for::<IssueCountOutput<'_>>()),
+        ("issue stale", schema_for::<StaleOutput<'_>>()),
+        ("issue blocked", schema_for::<BlockedOutput<'_>>()),
+    ]
+}
+
+/// One tree, or with no ID one per epic. The node schema appears once, so
+/// its anchor is unique within the document.
+fn dep_tree_schema() -> Value {
+    let node = serde_json::json!({ "$ref": format!("#{TREE_NODE_ANCHOR}") });
+    let trees = object(&[
+        ("trees", serde_json::json!({ "type": "array", "items": node }), true),
+        ("count", schema_for::<usize>(), true),
+    ]);
+    one_of(&[schema_for::<TreeNode<'_>>(), trees])
+}
+
 /// Execute issue commands.
 pub fn execute(
     command: &IssueCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
     match command {
-        IssueCommands::Create(args) => create(args, db_path, actor, json),
-        IssueCommands::List(args) => list(args, db_path, json),
-        IssueCommands::Show { id } => show(id, db_path, json),
-        IssueCommands::Update(args) => update(args, db_path, actor, json),
-        IssueCommands::Claim { ids } => claim(ids, db_path, actor, json),
-        IssueCommands::Release { ids } => release(ids, db_path, actor, json),
-        IssueCommands::Delete { ids } => delete(ids, db_path, actor, json),
-        IssueCommands::Label { command } => label(command, db_path, actor, json),
-        IssueCommands::Dep { command } => dep(command, db_path, actor, json),
-        IssueCommands::Clone { id, title } => clone_issue(id, title.as_deref(), db_path, actor, json),
-        IssueCommands::Duplicate { id, of } => duplicate(id, of, db_path, actor, json),
-        IssueCommands::Ready { limit } => ready(*limit, db_path, json),
-        IssueCommands::NextBlock { count } => next_block(*count, db_path, actor, json),
-        IssueCommands::Batch { json_input } => batch(json_input, db_path, actor, json),
-        IssueCommands::Count { group_by } => count(group_by, db_path, json),
-        IssueCommands::Stale { days, limit } => stale(*days, *limit, db_path, json),
-        IssueCommands::Blocked { limit } => blocked(*limit, db_path, json),
-        IssueCommands::Complete { ids, reason } => complete(ids, reason.as_deref(), db_path, actor, json),
+        IssueCommands::Create(args) => create(args, db, actor, json).inspect(|()| super::embeddings::spawn_background_embedder()),
+        IssueCommands::List(args) => list(args, db, json),
+        IssueCommands::Show { id, logs, related } => show(id, *logs, *related, db, json),
+        IssueCommands::Update(args) => update(args, db, actor, json).inspect(|()| super::embeddings::spawn_background_embedder()),
+        IssueCommands::Claim { ids, force } => claim(ids, *force, db, actor, json),
+        IssueCommands::Release { ids } => release(ids, db, actor, json),
+        IssueCommands::Watch { ids, stop } => watch(ids, *stop, db, actor, json),
+        IssueCommands::Comment { id, text } => comment(id, text, db, actor, json),
+        IssueCommands::Log { id, text, hours, date, period } => {
+            log_work(id, text, *hours, date.as_deref(), period.as_deref(), db, actor, json)
+        }
+        IssueCommands::Delete { ids } => delete(ids, db, actor, json),
+        IssueCommands::Label { command } => label(command, db, actor, json),
+        IssueCommands::Dep { command } => dep(command, db, actor, json),
+        IssueCommands::Clone { id, title } => clone_issue(id, title.as_deref(), db, actor, json),
+        IssueCommands::Duplicate { id, of } => duplicate(id, of, db, actor, json),
+        IssueCommands::Ready { limit } => ready(*limit, db, actor, json),
+        IssueCommands::Waves => waves(db, json),
+        IssueCommands::CriticalPath { epic, default_hours } => {
+            super::critical_path::execute(&super::critical_path::Scope::Epic(epic), *default_hours, db, json)
+        }
+        IssueCommands::NextBlock { count, force } => next_block(*count, *force, db, actor, json),
+        IssueCommands::Route { count, policy, agents } => route(*count, *policy, agents, db, actor, json),
+        IssueCommands::Batch { json_input } => batch(json_input, db, actor, json),
+        IssueCommands::Import { file, from } => import(file, *from, db, actor, json),
+        IssueCommands::Export { to, columns, output } => {
+            export(*to, columns.as_deref(), output.as_ref(), db, json)
+        }
+        IssueCommands::Count { group_by } => count(group_by, db, json),
+        IssueCommands::Stale { days, limit } => stale(*days, *limit, db, json),
+        IssueCommands::Blocked { limit } => blocked(*limit, db, json),
+        IssueCommands::Triage { label, status, limit, list } => {
+            let queue = super::issue_triage::Queue { label, status: status.as_deref(), limit: *limit };
+            super::issue_triage::execute(&queue, *list, db, actor, json)
+        }
+        IssueCommands::Complete { ids, reason } => complete(ids, reason.as_deref(), db, actor, json),
     }
 }
 
+/// Starting text for `sc issue create -t bug --edit`.
+const BUG_TEMPLATE: &str = "## Steps to reproduce\n\n## Expected\n\n## Actual\n";
+
 fn create(
     args: &IssueCreateArgs,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
     // Handle file-based bulk import
     if let Some(ref file_path) = args.file {
-        return create_from_file(file_path, db_path, actor, json);
+        return create_from_file(file_path, db, actor, json);
     }
 
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Normalize type via synonym lookup
-    let issue_type = crate::validate::normalize_type(&args.issue_type)
+    let issue_type: IssueType = crate::validate::normalize_type(&args.issue_type)
         .map_err(|(val, suggestion)| {
             let msg = if let Some(s) = suggestion {
                 format!("Invalid issue type '{val}'. Did you mean '{s}'?")
@@ -153,7 +634,8 @@ fn create(
                 format!("Invalid issue type '{val}'. Valid: task, bug, feature, epic, chore")
             };
             Error::InvalidArgument(msg)
-        })?;
+        })?
+        .parse()?;
 
     // Normalize priority via synonym lookup
     let priority = crate::validate::normalize_priority(&args.priority.to_string())
@@ -162,6 +644,9 @@ fn create(
             Error::InvalidArgument(msg)
         })?;
 
+    let mut sc = SaveContext::discover_in(db.storage()?, Some(&actor))?;
+    let subproject = resolve_subproject(sc.storage(), sc.project_path(), args.scope.subproject.as_deref(), args.scope.root)?;
+
     // Dry-run: preview without writing
     if crate::is_dry_run() {
         let labels_str = args.labels.as_ref().map(|l| l.join(",")).unwrap_or_default();
@@ -173,6 +658,7 @@ fn create(
                 "issue_type": issue_type,
                 "priority": priority,
                 "labels": labels_str,
+                "subproject": subproject,
             });
             println!("{output}");
         } else {
@@ -180,41 +666,35 @@ fn create(
             if !labels_str.is_empty() {
                 println!("  Labels: {labels_str}");
             }
+            if let Some(subproject) = &subproject {
+                println!("  Sub-project: {subproject}");
+            }
         }
         return Ok(());
     }
+    sc.set_subproject(subproject);
 
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
-
-    // Generate IDs
-    let id = format!("issue_{}", &uuid::Uuid::new_v4().to_string()[..12]);
-    let short_id = generate_short_id();
-
-    storage.create_issue(
-        &id,
-        Some(&short_id),
-        &project_path,
-        &args.title,
-        args.description.as_deref(),
-        args.details.as_deref(),
-        Some(&issue_type),
-        Some(priority),
-        args.plan_id.as_deref(),
-        &actor,
-    )?;
-
-    // Set parent via parent-child dependency if provided
-    if let Some(ref parent) = args.parent {
-        storage.add_issue_dependency(&id, parent, "parent-child", &actor)?;
-    }
+    let description = if args.input.edit {
+        let template = if issue_type == IssueType::Bug { BUG_TEMPLATE } else { "" };
+        let initial = args.description.as_deref().unwrap_or(template);
+        Some(crate::cli::input::edit(initial, "issue description")?)
+    } else {
+        args.description.clone()
+    };
 
-    // Add labels if provided (already Vec from clap value_delimiter)
-    if let Some(ref labels) = args.labels {
-        if !labels.is_empty() {
-            storage.add_issue_labels(&id, labels, &actor)?;
-        }
-    }
+    let issue = sc.create_issue(&NewIssue {
+        title: args.title.clone(),
+        description,
+        details: args.details.clone(),
+        issue_type,
+        priority: Some(priority),
+        plan_id: args.plan_id.clone(),
+        parent: args.parent.clone(),
+        // Already a Vec from clap value_delimiter
+        labels: args.labels.clone().unwrap_or_default(),
+        estimate_hours: args.estimate.filter(|hours| *hours > 0.0),
+    })?;
+    let short_id = issue.short_id.clone().unwrap_or_default();
 
     if crate::is_silent() {
         println!("{short_id}");
@@ -223,18 +703,22 @@ fn create(
 
     if json {
         let output = IssueCreateOutput {
-            id,
-            short_id: Some(short_id),
-            title: args.title.clone(),
-            status: "open".to_string(),
-            priority,
-            issue_type: issue_type.clone(),
+            id: issue.id,
+            short_id: issue.short_id,
+            title: issue.title,
+            status: issue.status,
+            priority: issue.priority,
+            issue_type: issue.issue_type,
+            subproject: issue.subproject,
         };
         println!("{}", serde_json::to_string(&output)?);
     } else {
-        println!("Created issue: {} [{}]", args.title, short_id);
-        println!("  Type: {issue_type}");
-        println!("  Priority: {priority}");
+        println!("Created issue: {} [{}]", issue.title, short_id);
+        println!("  Type: {}", issue.issue_type);
+        println!("  Priority: {}", issue.priority);
+        if let Some(subproject) = &issue.subproject {
+            println!("  Sub-project: {subproject}");
+        }
     }
 
     Ok(())
@@ -243,14 +727,11 @@ fn create(
 /// Create issues from a JSONL file (one JSON object per line).
 fn create_from_file(
     file_path: &PathBuf,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
@@ -288,86 +769,442 @@ fn create_from_file(
         } else {
             println!("Would create {} issues from {}:", issues.len(), file_path.display());
             for issue in &issues {
-                println!("  - {} [{}]", issue.title, issue.issue_type.as_deref().unwrap_or("task"));
+                println!("  - {} [{}]", issue.title, issue.issue_type.unwrap_or_default());
             }
         }
         return Ok(());
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let project_path = resolve_project_path(&storage, None)?;
+    let project_path = resolve_project_path(storage, None)?;
+
+    // The whole file is imported, or nothing is
+    let results = storage.atomically(|storage| {
+        let mut results: Vec<BatchIssueResult> = Vec::with_capacity(issues.len());
+        for (index, issue) in issues.iter().enumerate() {
+            let id = format!("issue_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+            let short_id = storage.next_issue_short_id()?;
+
+            storage.create_issue(
+                &id,
+                Some(&short_id),
+                &project_path,
+                &issue.title,
+                issue.description.as_deref(),
+                issue.details.as_deref(),
+                issue.issue_type,
+                issue.priority,
+                issue.plan_id.as_deref(),
+                None,
+                &actor,
+            )?;
+
+            if let Some(ref labels) = issue.labels {
+                if !labels.is_empty() {
+                    storage.add_issue_labels(&id, labels, &actor)?;
+                }
+            }
 
-    let mut results: Vec<BatchIssueResult> = Vec::with_capacity(issues.len());
+            results.push(BatchIssueResult {
+                id,
+                short_id: Some(short_id),
+                title: issue.title.clone(),
+                index,
+            });
+        }
+        Ok(results)
+    })?;
 
-    for (index, issue) in issues.iter().enumerate() {
+    if crate::is_silent() {
+        for r in &results {
+            println!("{}", r.short_id.as_deref().unwrap_or(&r.id));
+        }
+        return Ok(());
+    }
+
+    if json {
+        let output = IssueCreateFileOutput { count: results.len(), issues: results };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Created {} issues from {}:", results.len(), file_path.display());
+        for r in &results {
+            let sid = r.short_id.as_deref().unwrap_or(&r.id[..8]);
+            println!("  [{}] {}", sid, r.title);
+        }
+    }
+
+    Ok(())
+}
+
+/// Result for one issue created by `issue import`.
+#[derive(Debug, Serialize)]
+struct ImportIssueResult {
+    id: String,
+    short_id: String,
+    external_id: String,
+    title: String,
+    status: String,
+}
+
+/// A change `issue import --format backlog-md` applies to an existing issue.
+#[derive(Debug, Serialize)]
+struct BacklogUpdate {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<(String, String)>,
+}
+
+fn import(
+    file_path: &PathBuf,
+    format: ImportFormat,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    let content = std::fs::read_to_string(file_path)
+        .map_err(|e| Error::Other(format!("Could not open file {}: {e}", file_path.display())))?;
+    let config = load_config();
+    let rules = config.import.as_ref().and_then(|c| c.for_format(format));
+    let batch = crate::import::parse(format, &content, rules)
+        .map_err(|e| Error::InvalidArgument(format!("{}: {e}", file_path.display())))?;
+
+    if batch.issues.is_empty() {
+        return Err(Error::Other(format!("No issues found in {}", file_path.display())));
+    }
+
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+
+    // A backlog written by `issue export` refers to existing issues
+    let (new_issues, updates, known) = if format == ImportFormat::BacklogMd {
+        plan_backlog_updates(storage, &project_path, batch.issues)?
+    } else {
+        (batch.issues, Vec::new(), HashMap::new())
+    };
+
+    // Imported history shouldn't be held to required fields, but every
+    // status must still exist in this project's workflow
+    let mut workflow = load_issue_workflow(Some(&project_path));
+    workflow.required_fields.clear();
+    for (from, to) in updates.iter().filter_map(|u| u.status.as_ref()) {
+        if !workflow.can_transition(from, to) {
+            return Err(Error::InvalidTransition {
+                from: from.clone(),
+                to: to.clone(),
+                allowed: workflow.allowed_from(from).to_vec(),
+            });
+        }
+    }
+    for issue in &new_issues {
+        if !workflow.can_transition("open", &issue.status) {
+            return Err(Error::InvalidArgument(format!(
+                "Status '{}' (for '{}') is not reachable from 'open' in this project's workflow; \
+                 map it under import.{}.status in config.json",
+                issue.status,
+                issue.title,
+                format.name()
+            )));
+        }
+    }
+
+    if crate::is_dry_run() {
+        if json {
+            let output = serde_json::json!({
+                "dry_run": true,
+                "action": "import_issues",
+                "format": format.name(),
+                "file": file_path.display().to_string(),
+                "count": new_issues.len(),
+                "issues": new_issues,
+                "updates": updates,
+                "warnings": batch.warnings,
+            });
+            println!("{output}");
+        } else {
+            println!(
+                "Would import {} issues from {} ({}):",
+                new_issues.len(),
+                file_path.display(),
+                format.name()
+            );
+            for issue in &new_issues {
+                println!(
+                    "  - {} [{}, {}, priority={}]",
+                    issue.title, issue.issue_type, issue.status, issue.priority
+                );
+            }
+            print_backlog_updates(&updates, "Would update");
+            for warning in &batch.warnings {
+                println!("Warning: {warning}");
+            }
+        }
+        return Ok(());
+    }
+
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    // Updates, new issues and parent links land together, so a failed
+    // import can simply be run again
+    let (results, parents_linked) = storage.atomically(|storage| {
+        for update in &updates {
+            if update.old_title.is_some() {
+                storage.update_issue(&update.id, Some(&update.title), None, None, None, None, None, None, &actor)?;
+            }
+            if let Some((_, ref to)) = update.status {
+                storage.update_issue_status(&update.id, &to.parse()?, None, &workflow, &actor)?;
+            }
+        }
+        create_imported(storage, &new_issues, known, &project_path, &workflow, &actor)
+    })?;
+
+    if crate::is_silent() {
+        for r in &results {
+            println!("{}", r.short_id);
+        }
+        return Ok(());
+    }
+
+    if json {
+        let output = IssueImportOutput {
+            format: format.name(),
+            file: file_path,
+            count: results.len(),
+            parents_linked,
+            issues: results,
+            updates,
+            warnings: batch.warnings,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!(
+            "Imported {} issues from {} ({}):",
+            results.len(),
+            file_path.display(),
+            format.name()
+        );
+        for r in &results {
+            println!("  [{}] {} ({})", r.short_id, r.title, r.status);
+        }
+        if parents_linked > 0 {
+            println!("Linked {parents_linked} subtask(s) to their parents.");
+        }
+        print_backlog_updates(&updates, "Updated");
+        for warning in &batch.warnings {
+            println!("Warning: {warning}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Create imported issues, then link subtasks to their parents.
+///
+/// Returns the created issues and the number of parent links.
+///
+/// `known` maps external IDs of issues that already exist to their IDs,
+/// so new subtasks can be attached to existing parents.
+fn create_imported(
+    storage: &mut SqliteStorage,
+    issues: &[ImportedIssue],
+    mut known: HashMap<String, String>,
+    project_path: &str,
+    workflow: &IssueWorkflow,
+    actor: &str,
+) -> Result<(Vec<ImportIssueResult>, usize)> {
+    let mut results: Vec<ImportIssueResult> = Vec::with_capacity(issues.len());
+
+    for issue in issues {
         let id = format!("issue_{}", &uuid::Uuid::new_v4().to_string()[..12]);
-        let short_id = generate_short_id();
+        let short_id = storage.next_issue_short_id()?;
 
         storage.create_issue(
             &id,
             Some(&short_id),
-            &project_path,
+            project_path,
             &issue.title,
             issue.description.as_deref(),
             issue.details.as_deref(),
-            issue.issue_type.as_deref(),
-            issue.priority,
-            issue.plan_id.as_deref(),
-            &actor,
+            Some(issue.issue_type.parse()?),
+            Some(issue.priority),
+            None,
+            None,
+            actor,
         )?;
-
-        if let Some(ref labels) = issue.labels {
-            if !labels.is_empty() {
-                storage.add_issue_labels(&id, labels, &actor)?;
-            }
+        if !issue.labels.is_empty() {
+            storage.add_issue_labels(&id, &issue.labels, actor)?;
+        }
+        let status: IssueStatus = issue.status.parse()?;
+        if status != IssueStatus::Open {
+            storage.update_issue_status(&id, &status, None, workflow, actor)?;
         }
 
-        results.push(BatchIssueResult {
+        known.insert(issue.external_id.clone(), id.clone());
+        results.push(ImportIssueResult {
             id,
-            short_id: Some(short_id),
+            short_id,
+            external_id: issue.external_id.clone(),
             title: issue.title.clone(),
-            index,
+            status: issue.status.clone(),
         });
     }
 
-    if crate::is_silent() {
-        for r in &results {
-            println!("{}", r.short_id.as_deref().unwrap_or(&r.id));
+    // Link parents once every issue exists, so order in the export doesn't matter
+    let mut parents_linked = 0;
+    for issue in issues {
+        let parent = issue.parent_external_id.as_deref().and_then(|p| known.get(p));
+        if let (Some(parent), Some(child)) = (parent, known.get(&issue.external_id)) {
+            storage.add_issue_dependency(child, parent, "parent-child", actor)?;
+            parents_linked += 1;
         }
+    }
+
+    Ok((results, parents_linked))
+}
+
+fn export(
+    format: ExportFormat,
+    columns: Option<&[Column]>,
+    output: Option<&PathBuf>,
+    db: &mut Database,
+    json: bool,
+) -> Result<()> {
+    if columns.is_some() && format != ExportFormat::Csv {
+        return Err(Error::InvalidArgument("--columns only applies to --format csv".to_string()));
+    }
+
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let issues = storage.get_issues_by_project(&project_path)?;
+
+    let content = match format {
+        ExportFormat::BacklogMd => {
+            let mut children = std::collections::BTreeMap::new();
+            for epic in issues.iter().filter(|i| i.issue_type == IssueType::Epic) {
+                let ids = storage.get_child_issue_ids(&epic.id)?;
+                children.insert(epic.id.clone(), ids.into_iter().collect());
+            }
+            let title = std::path::Path::new(&project_path)
+                .file_name()
+                .map_or_else(|| project_path.clone(), |n| n.to_string_lossy().to_string());
+            crate::import::backlog_md::render(&title, &issues, &children)
+        }
+        ExportFormat::Csv => {
+            let columns = columns.unwrap_or(DEFAULT_COLUMNS);
+            let mut labels = std::collections::BTreeMap::new();
+            let mut parents = std::collections::BTreeMap::new();
+            for issue in &issues {
+                if columns.contains(&Column::Labels) {
+                    labels.insert(issue.id.clone(), storage.get_issue_labels(&issue.id)?);
+                }
+                if columns.contains(&Column::Parent) {
+                    let parent = storage
+                        .get_issue_dependencies(&issue.id)?
+                        .into_iter()
+                        .find(|l| l.dependency_type == "parent-child");
+                    if let Some(parent) = parent {
+                        parents.insert(issue.id.clone(), parent.short_id.unwrap_or(parent.id));
+                    }
+                }
+            }
+            crate::import::csv_report::render(&issues, columns, &labels, &parents)
+        }
+    };
+
+    let Some(path) = output else {
+        print!("{content}");
+        return Ok(());
+    };
+
+    if crate::is_dry_run() {
+        println!("Would write {} issues to {}", issues.len(), path.display());
         return Ok(());
     }
+    std::fs::write(path, &content)?;
 
     if json {
-        let output = serde_json::json!({
-            "issues": results,
-            "count": results.len(),
-        });
+        let output = IssueExportOutput { file: path, count: issues.len() };
         println!("{}", serde_json::to_string(&output)?);
     } else {
-        println!("Created {} issues from {}:", results.len(), file_path.display());
-        for r in &results {
-            let sid = r.short_id.as_deref().unwrap_or(&r.id[..8]);
-            println!("  [{}] {}", sid, r.title);
+        println!("Exported {} issues to {}", issues.len(), path.display());
+    }
+    Ok(())
+}
+
+/// New issues, updates to existing ones, and external ID → issue ID links.
+type BacklogPlan = (Vec<ImportedIssue>, Vec<BacklogUpdate>, HashMap<String, String>);
+
+/// Split backlog items into new issues and changes to existing ones.
+///
+/// Items whose `sc:` ID names an issue in this project become updates
+/// (title and status only). Also returns the external ID → issue ID map
+/// for existing items and epic headings, used to parent new items.
+fn plan_backlog_updates(
+    storage: &SqliteStorage,
+    project_path: &str,
+    issues: Vec<ImportedIssue>,
+) -> Result<BacklogPlan> {
+    let mut known = HashMap::new();
+    let mut new_issues = Vec::new();
+    let mut updates = Vec::new();
+
+    for item in issues {
+        let Some(existing) = storage.get_issue(&item.external_id, Some(project_path))? else {
+            new_issues.push(item);
+            continue;
+        };
+        known.insert(item.external_id.clone(), existing.id.clone());
+        let old_title = (existing.title != item.title).then(|| existing.title.clone());
+        let status = (existing.status.as_str() != item.status)
+            .then(|| (existing.status.to_string(), item.status.clone()));
+        if old_title.is_some() || status.is_some() {
+            updates.push(BacklogUpdate {
+                id: existing.id,
+                short_id: existing.short_id,
+                title: item.title,
+                old_title,
+                status,
+            });
+        }
+    }
+
+    // Epic headings carry IDs but aren't items themselves
+    for parent in new_issues.iter().filter_map(|i| i.parent_external_id.as_deref()) {
+        if !known.contains_key(parent) {
+            if let Some(epic) = storage.get_issue(parent, Some(project_path))? {
+                known.insert(parent.to_string(), epic.id);
+            }
         }
     }
 
-    Ok(())
+    Ok((new_issues, updates, known))
 }
 
-fn list(args: &IssueListArgs, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
+fn print_backlog_updates(updates: &[BacklogUpdate], verb: &str) {
+    for update in updates {
+        let sid = update.short_id.as_deref().unwrap_or(&update.id);
+        if let Some(ref old) = update.old_title {
+            println!("{verb} [{sid}] title: {old} → {}", update.title);
+        }
+        if let Some((ref from, ref to)) = update.status {
+            println!("{verb} [{sid}] {}: {from} → {to}", update.title);
+        }
     }
+}
 
-    let storage = SqliteStorage::open(&db_path)?;
+fn list(args: &IssueListArgs, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
 
     // Handle single issue lookup by ID
     if let Some(ref id) = args.id {
-        let project_path = resolve_project_path(&storage, None).ok();
+        let project_path = resolve_project_path(storage, None).ok();
         let issue = storage
             .get_issue(id, project_path.as_deref())?
             .ok_or_else(|| {
@@ -389,16 +1226,20 @@ fn list(args: &IssueListArgs, db_path: Option<&PathBuf>, json: bool) -> Result<(
             };
             println!("{}", serde_json::to_string(&output)?);
         } else {
-            print_issue_list(&[issue], Some(&storage));
+            print_issue_list(&[issue], Some(storage));
         }
         return Ok(());
     }
 
-    // Determine project filter
+    // Determine project filter, and within the project the sub-project
     let project_path = if args.all_projects {
         None
     } else {
-        Some(resolve_project_path(&storage, None)?)
+        Some(resolve_project_path(storage, None)?)
+    };
+    let subproject = match &project_path {
+        Some(path) => resolve_subproject(storage, path, args.scope.subproject.as_deref(), args.scope.root)?,
+        None => None,
     };
 
     // Normalize status filter via synonym lookup (e.g., "done" → "closed")
@@ -437,6 +1278,7 @@ fn list(args: &IssueListArgs, db_path: Option<&PathBuf>, json: bool) -> Result<(
 
     let issues: Vec<_> = issues
         .into_iter()
+        .filter(|i| in_subproject(i.subproject.as_deref(), subproject.as_deref()))
         // Filter by search
         .filter(|i| {
             if let Some(ref search) = args.search {
@@ -625,7 +1467,7 @@ fn list(args: &IssueListArgs, db_path: Option<&PathBuf>, json: bool) -> Result<(
     } else if issues.is_empty() {
         println!("No issues found.");
     } else {
-        print_issue_list(&issues, Some(&storage));
+        print_issue_list(&issues, Some(storage));
     }
 
     Ok(())
@@ -655,7 +1497,7 @@ fn print_issue_list(issues: &[crate::storage::Issue], storage: Option<&SqliteSto
         let short_id = issue.short_id.as_deref().unwrap_or(&issue.id[..8]);
 
         // Show epic progress inline if available
-        let progress_str = if issue.issue_type == "epic" {
+        let progress_str = if issue.issue_type == IssueType::Epic {
             storage.and_then(|s| s.get_epic_progress(&issue.id).ok())
                 .filter(|p| p.total > 0)
                 .map(|p| {
@@ -667,8 +1509,10 @@ fn print_issue_list(issues: &[crate::storage::Issue], storage: Option<&SqliteSto
             String::new()
         };
 
+        let subproject_str = issue.subproject.as_deref().map(|s| format!(" <{s}>")).unwrap_or_default();
+
         println!(
-            "{} [{}] {} {} ({}){progress_str}",
+            "{} [{}] {} {} ({}){progress_str}{subproject_str}",
             status_icon, short_id, priority_str, issue.title, issue.issue_type
         );
         if let Some(ref desc) = issue.description {
@@ -682,16 +1526,9 @@ fn print_issue_list(issues: &[crate::storage::Issue], storage: Option<&SqliteSto
     }
 }
 
-fn show(id: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None).ok();
+fn show(id: &str, logs: bool, related: bool, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None).ok();
 
     let issue = storage
         .get_issue(id, project_path.as_deref())?
@@ -708,87 +1545,318 @@ fn show(id: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
             }
         })?;
 
-    // Check for epic progress
-    let progress = if issue.issue_type == "epic" {
-        storage.get_epic_progress(&issue.id).ok()
-            .filter(|p| p.total > 0)
-    } else {
-        None
-    };
-
-    // Check for close_reason
-    let close_reason = if issue.status == "closed" {
-        storage.get_close_reason(&issue.id).ok().flatten()
-    } else {
-        None
-    };
-
-    // Check for logged time
-    let time_total = storage.get_issue_time_total(&issue.id).unwrap_or(0.0);
+    let mut relations = issue_relations(storage, &issue)?;
+    let worklog = storage.get_worklog(Some(&issue.id), None)?;
+    let worklog_count = worklog.len();
+    relations.worklog = logs.then_some(worklog);
+    let related = related
+        .then(|| super::search::related(storage, EmbeddingEntity::Issue, &issue.id, Some(&issue.project_path)))
+        .transpose()?;
+    relations.related = related.clone().map(Option::unwrap_or_default);
 
     if json {
-        let mut value = serde_json::to_value(&issue)?;
-        if let Some(ref p) = progress {
-            value["progress"] = serde_json::to_value(p)?;
-        }
-        if let Some(ref reason) = close_reason {
-            value["close_reason"] = serde_json::Value::String(reason.clone());
+        let output = IssueShowOutput { issue: &issue, relations };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    let relations = &relations;
+    let short_id = issue.short_id.as_deref().unwrap_or(&issue.id[..8]);
+    println!("[{}] {}", short_id, issue.title);
+    println!();
+    println!("Status:   {}", issue.status);
+    println!("Type:     {}", issue.issue_type);
+    println!("Priority: {}", issue.priority);
+    if let Some(hours) = issue.estimate_hours {
+        println!("Estimate: {hours}h");
+    }
+    if !relations.labels.is_empty() {
+        println!("Labels:   {}", relations.labels.join(", "));
+    }
+    if let Some(ref plan) = relations.plan {
+        println!("Plan:     {} {} ({})", plan.short_id.as_deref().unwrap_or(&plan.id), plan.title, plan.status);
+    }
+    if let Some(ref parent) = relations.parent {
+        println!("Parent:   {}", link_line(parent));
+    }
+    if let Some(ref desc) = issue.description {
+        println!();
+        println!("Description:");
+        println!("{desc}");
+    }
+    if let Some(ref details) = issue.details {
+        println!();
+        println!("Details:");
+        println!("{details}");
+    }
+    if let Some(ref agent) = issue.assigned_to_agent {
+        println!();
+        println!("Assigned to: {agent}");
+        if let Some(expires_at) = relations.lease_expires_at {
+            println!("Lease runs out: {}", format_time(expires_at));
         }
-        if time_total > 0.0 {
-            value["time_logged"] = serde_json::json!(time_total);
+    }
+    if let Some(ref reason) = relations.close_reason {
+        println!();
+        println!("Close reason: {reason}");
+    }
+    if let Some(hours) = relations.time_logged {
+        println!();
+        println!("Time logged: {hours:.1}hrs");
+    }
+    if !relations.watchers.is_empty() {
+        println!();
+        println!("Watchers: {}", relations.watchers.join(", "));
+    }
+    print_links(relations);
+    if let Some(ref progress) = relations.progress {
+        print_progress(progress);
+    }
+    if !relations.commits.is_empty() {
+        println!();
+        println!("Commits:");
+        for commit in &relations.commits {
+            println!("  {} {} ({})", commit.hash, commit.subject, format_time(commit.committed_at));
         }
-        println!("{}", serde_json::to_string(&value)?);
-    } else {
-        let short_id = issue.short_id.as_deref().unwrap_or(&issue.id[..8]);
-        println!("[{}] {}", short_id, issue.title);
+    }
+    print_comments(&relations.comments);
+    print_worklog(relations.worklog.as_deref(), worklog_count, short_id);
+    print_events(&relations.events);
+    if let Some(related) = &related {
         println!();
-        println!("Status:   {}", issue.status);
-        println!("Type:     {}", issue.issue_type);
-        println!("Priority: {}", issue.priority);
-        if let Some(ref desc) = issue.description {
-            println!();
-            println!("Description:");
-            println!("{desc}");
+        super::search::print_related(related.as_deref(), "issue");
+    }
+
+    Ok(())
+}
+
+fn print_comments(comments: &[IssueComment]) {
+    if comments.is_empty() {
+        return;
+    }
+    println!();
+    println!("Comments:");
+    for comment in comments {
+        println!("  {} ({}): {}", comment.actor, format_time(comment.created_at), comment.text);
+    }
+}
+
+/// Dependencies grouped by direction and kind.
+fn print_links(relations: &IssueRelations) {
+    for (heading, links) in [
+        ("Blocked by", &relations.blocked_by),
+        ("Blocks", &relations.blocks),
+        ("Children", &relations.children),
+        ("Depends on", &relations.depends_on),
+        ("Depended on by", &relations.depended_on_by),
+    ] {
+        if links.is_empty() {
+            continue;
         }
-        if let Some(ref details) = issue.details {
-            println!();
-            println!("Details:");
-            println!("{details}");
+        println!();
+        println!("{heading}:");
+        for link in links {
+            if matches!(heading, "Depends on" | "Depended on by") {
+                println!("  {} [{}]", link_line(link), link.dependency_type);
+            } else {
+                println!("  {}", link_line(link));
+            }
         }
-        if let Some(ref agent) = issue.assigned_to_agent {
+    }
+}
+
+fn print_progress(p: &EpicProgress) {
+    let pct = if p.total > 0 {
+        (p.closed as f64 / p.total as f64 * 100.0) as u32
+    } else {
+        0
+    };
+    println!();
+    println!("Progress: {}/{} tasks ({pct}%)", p.closed, p.total);
+    if p.closed > 0 { println!("  Closed:      {}", p.closed); }
+    if p.in_progress > 0 { println!("  In progress: {}", p.in_progress); }
+    if p.open > 0 { println!("  Open:        {}", p.open); }
+    if p.blocked > 0 { println!("  Blocked:     {}", p.blocked); }
+    if p.deferred > 0 { println!("  Deferred:    {}", p.deferred); }
+}
+
+fn print_events(events: &[IssueEvent]) {
+    if events.is_empty() {
+        return;
+    }
+    println!();
+    println!("Recent activity:");
+    for event in events {
+        let change = match (&event.old_value, &event.new_value) {
+            (Some(old), Some(new)) => format!(": {old} -> {new}"),
+            (None, Some(new)) => format!(": {new}"),
+            _ => event.comment.as_ref().map_or_else(String::new, |c| format!(": {c}")),
+        };
+        println!("  {}  {} by {}{change}", format_time(event.created_at), event.event_type, event.actor);
+    }
+}
+
+/// The worklog when `--logs` was given, otherwise a pointer to it.
+fn print_worklog(worklog: Option<&[WorklogEntry]>, count: usize, short_id: &str) {
+    match worklog {
+        Some(worklog) if !worklog.is_empty() => {
             println!();
-            println!("Assigned to: {agent}");
+            println!("Worklog:");
+            for entry in worklog {
+                let hours = entry.hours.map_or_else(String::new, |h| format!(" [{h:.1}hrs]"));
+                println!("  {} ({}){hours}: {}", entry.actor, format_time(entry.created_at), entry.text);
+            }
         }
-        if let Some(ref reason) = close_reason {
+        None if count > 0 => {
             println!();
-            println!("Close reason: {reason}");
+            println!("Worklog: {count} entries (sc issue show {short_id} --logs)");
         }
-        if time_total > 0.0 {
-            println!();
-            println!("Time logged: {time_total:.1}hrs");
+        _ => {}
+    }
+}
+
+/// Events shown by `issue show`.
+const SHOW_EVENT_LIMIT: u32 = 10;
+
+/// Gather labels, dependencies, plan, commits, comments and recent events
+/// of an issue.
+fn issue_relations(storage: &SqliteStorage, issue: &Issue) -> Result<IssueRelations> {
+    let mut parent = None;
+    let (mut blocked_by, mut depends_on) = (Vec::new(), Vec::new());
+    for link in storage.get_issue_dependencies(&issue.id)? {
+        match link.dependency_type.as_str() {
+            "parent-child" => parent = Some(link),
+            "blocks" => blocked_by.push(link),
+            _ => depends_on.push(link),
         }
-        if let Some(ref p) = progress {
-            let pct = if p.total > 0 {
-                (p.closed as f64 / p.total as f64 * 100.0) as u32
-            } else {
-                0
-            };
-            println!();
-            println!("Progress: {}/{} tasks ({pct}%)", p.closed, p.total);
-            if p.closed > 0 { println!("  Closed:      {}", p.closed); }
-            if p.in_progress > 0 { println!("  In progress: {}", p.in_progress); }
-            if p.open > 0 { println!("  Open:        {}", p.open); }
-            if p.blocked > 0 { println!("  Blocked:     {}", p.blocked); }
-            if p.deferred > 0 { println!("  Deferred:    {}", p.deferred); }
+    }
+    let (mut children, mut blocks, mut depended_on_by) = (Vec::new(), Vec::new(), Vec::new());
+    for link in storage.get_issue_dependents(&issue.id)? {
+        match link.dependency_type.as_str() {
+            "parent-child" => children.push(link),
+            "blocks" => blocks.push(link),
+            _ => depended_on_by.push(link),
         }
     }
 
-    Ok(())
+    let plan = match issue.plan_id.as_deref() {
+        Some(plan_id) => storage.get_plan(plan_id)?.map(|p| PlanRef {
+            id: p.id,
+            short_id: p.short_id,
+            title: p.title,
+            status: p.status.as_str().to_string(),
+        }),
+        None => None,
+    };
+
+    let comments = storage
+        .get_issue_comments(&issue.id)?
+        .into_iter()
+        .map(|(actor, text, created_at)| IssueComment { actor, text, created_at })
+        .collect();
+
+    // Comments and the worklog are listed on their own; a mention repeats
+    // the comment it came from
+    let events = crate::storage::events::get_events(storage.conn(), "issue", &issue.id, None)?
+        .into_iter()
+        .filter(|e| !matches!(e.event_type.as_str(), "issue_commented" | "issue_mentioned" | "issue_work_logged"))
+        .take(SHOW_EVENT_LIMIT as usize)
+        .map(|e| IssueEvent {
+            event_type: e.event_type.as_str().to_string(),
+            actor: e.actor,
+            old_value: e.old_value,
+            new_value: e.new_value,
+            comment: e.comment,
+            created_at: e.created_at,
+        })
+        .collect();
+
+    let progress = if issue.issue_type == IssueType::Epic {
+        storage.get_epic_progress(&issue.id).ok().filter(|p| p.total > 0)
+    } else {
+        None
+    };
+    let close_reason = if issue.status == IssueStatus::Closed {
+        storage.get_close_reason(&issue.id).ok().flatten()
+    } else {
+        None
+    };
+    let time_logged = Some(storage.get_issue_time_total(&issue.id).unwrap_or(0.0)).filter(|h| *h > 0.0);
+
+    Ok(IssueRelations {
+        labels: storage.get_issue_labels(&issue.id)?,
+        parent,
+        children,
+        blocked_by,
+        blocks,
+        depends_on,
+        depended_on_by,
+        plan,
+        commits: linked_commits(issue),
+        comments,
+        events,
+        watchers: storage.get_issue_watchers(&issue.id)?,
+        progress,
+        close_reason,
+        time_logged,
+        lease_expires_at: storage.get_claim_lease(&issue.id)?.map(|lease| lease.expires_at),
+        worklog: None,
+        related: None,
+    })
+}
+
+/// Commits in the project's repository whose message mentions the issue's
+/// short or full ID, newest first. Empty when the project isn't a git
+/// repository.
+fn linked_commits(issue: &Issue) -> Vec<LinkedCommit> {
+    let escape = |id: &str| -> String {
+        id.chars()
+            .flat_map(|c| {
+                let special = "\\.[]()*+?{}|^$".contains(c);
+                special.then_some('\\').into_iter().chain(std::iter::once(c))
+            })
+            .collect()
+    };
+    let ids: Vec<String> = std::iter::once(issue.id.as_str()).chain(issue.short_id.as_deref()).map(escape).collect();
+    let pattern = format!("(^|[^[:alnum:]_-])({})([^[:alnum:]_-]|$)", ids.join("|"));
+
+    let Ok(output) = std::process::Command::new("git")
+        .arg("-C")
+        .arg(&issue.project_path)
+        .args(["log", "--all", "-E", "-i", "-n", "20", "--format=%h%x1f%ct%x1f%s"])
+        .arg(format!("--grep={pattern}"))
+        .stderr(std::process::Stdio::null())
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\u{1f}');
+            let hash = fields.next()?.to_string();
+            let seconds: i64 = fields.next()?.parse().ok()?;
+            Some(LinkedCommit { hash, committed_at: seconds * 1000, subject: fields.next()?.to_string() })
+        })
+        .collect()
+}
+
+/// `[short_id] title (status)`.
+fn link_line(link: &IssueLink) -> String {
+    format!("[{}] {} ({})", link.short_id.as_deref().unwrap_or(&link.id), link.title, link.status)
+}
+
+/// Unix ms as `YYYY-MM-DD HH:MM`.
+fn format_time(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms).map_or_else(String::new, |dt| dt.format("%Y-%m-%d %H:%M").to_string())
 }
 
 fn update(
     args: &IssueUpdateArgs,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
@@ -806,32 +1874,73 @@ fn update(
         return Ok(());
     }
 
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     // Normalize type if provided
-    let normalized_type = args.issue_type.as_ref().map(|t| {
-        crate::validate::normalize_type(t).unwrap_or_else(|_| t.clone())
-    });
+    let normalized_type: Option<IssueType> = args.issue_type.as_ref().map(|t| {
+        crate::validate::normalize_type(t).unwrap_or_else(|_| t.clone()).parse()
+    }).transpose()?;
 
     // Normalize priority if provided
     let normalized_priority = args.priority.map(|p| {
         crate::validate::normalize_priority(&p.to_string()).unwrap_or(p)
     });
 
+    let description = if args.input.edit {
+        let current = match args.description {
+            Some(ref description) => description.clone(),
+            None => storage
+                .get_issue(&args.id, None)?
+                .ok_or_else(|| Error::IssueNotFound { id: args.id.clone() })?
+                .description
+                .unwrap_or_default(),
+        };
+        Some(crate::cli::input::edit(&current, "issue description")?)
+    } else {
+        args.description.clone()
+    };
+
+    // Fields, status and the --if-updated-at guard apply together or not at all
+    storage.with_transaction(|storage| {
+        let result = apply_update(storage, args, description.as_deref(), normalized_priority, normalized_type, &actor);
+        let ok = result.is_ok();
+        (result, ok)
+    })??;
+
+    if json {
+        let output = IssueUpdateOutput { id: &args.id, updated: true };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Updated issue: {}", args.id);
+    }
+
+    Ok(())
+}
+
+/// The writes of `sc issue update`, after input has been normalized.
+fn apply_update(
+    storage: &mut SqliteStorage,
+    args: &IssueUpdateArgs,
+    description: Option<&str>,
+    priority: Option<i32>,
+    issue_type: Option<IssueType>,
+    actor: &str,
+) -> Result<()> {
+    if let Some(hours) = args.estimate {
+        storage.set_issue_estimate(&args.id, Some(hours).filter(|hours| *hours > 0.0), actor)?;
+    }
+    if args.if_updated_at.is_some() {
+        let current = storage.get_issue(&args.id, None)?.ok_or_else(|| Error::IssueNotFound { id: args.id.clone() })?;
+        Error::check_updated_at("issue", &args.id, args.if_updated_at, current.updated_at, &current)?;
+    }
+
     // Check if any non-status fields are being updated
     let has_field_updates = args.title.is_some()
-        || args.description.is_some()
+        || description.is_some()
         || args.details.is_some()
-        || normalized_priority.is_some()
-        || normalized_type.is_some()
+        || priority.is_some()
+        || issue_type.is_some()
         || args.plan.is_some()
         || args.parent.is_some();
 
@@ -840,41 +1949,31 @@ fn update(
         storage.update_issue(
             &args.id,
             args.title.as_deref(),
-            args.description.as_deref(),
+            description,
             args.details.as_deref(),
-            normalized_priority,
-            normalized_type.as_deref(),
+            priority,
+            issue_type,
             args.plan.as_deref(),
             args.parent.as_deref(),
-            &actor,
+            actor,
         )?;
     }
 
-    // Normalize and update status if provided
+    // Normalize and update status if provided. Unrecognized words are
+    // passed through so project workflows can define their own statuses;
+    // the workflow rejects anything it doesn't know.
     if let Some(ref status) = args.status {
-        let normalized = crate::validate::normalize_status(status)
-            .unwrap_or_else(|_| status.clone());
-        storage.update_issue_status(&args.id, &normalized, &actor)?;
-    }
-
-    if json {
-        let output = serde_json::json!({
-            "id": args.id,
-            "updated": true
-        });
-        println!("{output}");
-    } else {
-        println!("Updated issue: {}", args.id);
+        let status: IssueStatus = status.parse()?;
+        let workflow = workflow_for_issue(storage, &args.id);
+        storage.update_issue_status(&args.id, &status, args.reason.as_deref(), &workflow, actor)?;
+    } else if let Some(ref reason) = args.reason {
+        storage.set_close_reason(&args.id, reason, actor)?;
     }
-
     Ok(())
 }
 
-fn complete(ids: &[String], reason: Option<&str>, db_path: Option<&PathBuf>, actor: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+fn complete(ids: &[String], reason: Option<&str>, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
@@ -885,32 +1984,34 @@ fn complete(ids: &[String], reason: Option<&str>, db_path: Option<&PathBuf>, act
         return Ok(());
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
-    let mut results = Vec::new();
-    for id in ids {
-        storage.update_issue_status(id, "closed", &actor)?;
-        if let Some(reason) = reason {
-            storage.set_close_reason(id, reason, &actor)?;
+    // If one of the issues can't be closed, none are
+    let results = storage.atomically(|storage| {
+        let mut results = Vec::new();
+        for id in ids {
+            let workflow = workflow_for_issue(storage, id);
+            storage.update_issue_status(id, &IssueStatus::Closed, reason, &workflow, &actor)?;
+            results.push(id.as_str());
         }
-        results.push(id.as_str());
-    }
+        Ok(results)
+    })?;
 
     if crate::is_silent() {
         for id in &results {
             println!("{id}");
         }
     } else if json {
-        let mut output = serde_json::json!({
-            "ids": results,
-            "status": "closed",
-            "count": results.len()
-        });
-        if let Some(reason) = reason {
-            output["close_reason"] = serde_json::Value::String(reason.to_string());
-        }
-        println!("{output}");
+        let output = IssueIdsOutput {
+            count: results.len(),
+            ids: results,
+            status: Some(IssueStatus::Closed),
+            assigned_to: None,
+            close_reason: reason,
+            deleted: None,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         for id in &results {
             println!("Completed issue: {id}");
@@ -923,11 +2024,8 @@ fn complete(ids: &[String], reason: Option<&str>, db_path: Option<&PathBuf>, act
     Ok(())
 }
 
-fn claim(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+fn claim(ids: &[String], force: bool, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
@@ -938,41 +2036,233 @@ fn claim(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json: b
         return Ok(());
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
+    // Each issue falls under the policy of its own project
+    let canonical = storage.resolve_actor(&actor)?;
+    let mut by_project: std::collections::BTreeMap<String, Vec<&str>> = std::collections::BTreeMap::new();
+    for id in ids {
+        if let Some(issue) = storage.get_issue(id, None)? {
+            by_project.entry(issue.project_path).or_default().push(id);
+        }
+    }
+    let mut policy_of: HashMap<&str, Policy> = HashMap::new();
+    for (project_path, ids) in &by_project {
+        let policy = load_policy(Some(project_path));
+        if !force {
+            super::policy::enforce(storage, &policy, project_path, &canonical, ids)?;
+        }
+        policy_of.extend(ids.iter().map(|id| (*id, policy)));
+    }
+
     let mut results = Vec::new();
     for id in ids {
-        storage.claim_issue(id, &actor)?;
+        let workflow = workflow_for_issue(storage, id);
+        storage.claim_issue(id, &workflow, &actor)?;
+        if let Some(policy) = policy_of.get(id.as_str()) {
+            super::policy::lease(storage, policy, id, &canonical)?;
+        }
         results.push(id.as_str());
     }
 
     if crate::is_silent() {
-        for id in &results {
-            println!("{id}");
-        }
+        for id in &results {
+            println!("{id}");
+        }
+    } else if json {
+        let output = IssueIdsOutput {
+            count: results.len(),
+            ids: results,
+            status: Some(IssueStatus::InProgress),
+            assigned_to: Some(actor),
+            close_reason: None,
+            deleted: None,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        for id in &results {
+            println!("Claimed issue: {id}");
+        }
+    }
+
+    Ok(())
+}
+
+fn watch(ids: &[String], stop: bool, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    let verb = if stop { "unwatch" } else { "watch" };
+    if crate::is_dry_run() {
+        for id in ids {
+            println!("Would {verb} issue: {id}");
+        }
+        return Ok(());
+    }
+
+    let storage = db.storage()?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+
+    let mut changed = Vec::new();
+    for id in ids {
+        let did = if stop {
+            storage.unwatch_issue(id, &actor, &actor)?
+        } else {
+            storage.watch_issue(id, &actor, &actor)?
+        };
+        if did {
+            changed.push(id.as_str());
+        }
+    }
+
+    if crate::is_silent() {
+        for id in &changed {
+            println!("{id}");
+        }
+    } else if json {
+        let output = IssueWatchOutput { ids, watching: !stop, actor, changed: changed.len() };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        for id in ids {
+            match (stop, changed.contains(&id.as_str())) {
+                (false, true) => println!("Watching issue: {id}"),
+                (false, false) => println!("Already watching issue: {id}"),
+                (true, true) => println!("Stopped watching issue: {id}"),
+                (true, false) => println!("Not watching issue: {id}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn comment(id: &str, text: &str, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    if text.trim().is_empty() {
+        return Err(Error::InvalidArgument("Comment text cannot be empty".to_string()));
+    }
+
+    let mentions = crate::model::parse_mentions(text);
+    if crate::is_dry_run() {
+        println!("Would comment on issue: {id}");
+        return Ok(());
+    }
+
+    let storage = db.storage()?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    let full_id = storage.comment_issue(id, text, &actor)?;
+
+    if crate::is_silent() {
+        println!("{full_id}");
+    } else if json {
+        let output = IssueCommentOutput { id: full_id, commented: true, mentions };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Commented on issue: {id}");
+        if !mentions.is_empty() {
+            println!("  Notified: {}", mentions.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn log_work(
+    id: &str,
+    text: &str,
+    hours: Option<f64>,
+    date: Option<&str>,
+    period: Option<&str>,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    if text.trim().is_empty() {
+        return Err(Error::InvalidArgument("Worklog text cannot be empty".to_string()));
+    }
+    if hours.is_some_and(|h| h <= 0.0 || !h.is_finite()) {
+        return Err(Error::InvalidArgument("Hours must be greater than 0".to_string()));
+    }
+    if let Some(date) = date {
+        super::time_entry::validate_date(date)?;
+    }
+
+    if crate::is_dry_run() {
+        let hours = hours.map_or_else(String::new, |h| format!(" ({h:.1}hrs)"));
+        println!("Would log work on issue: {id}{hours}");
+        return Ok(());
+    }
+
+    let storage = db.storage()?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    let project_path = resolve_project_path(storage, None).ok();
+    let issue = storage
+        .get_issue(id, project_path.as_deref())?
+        .ok_or_else(|| Error::IssueNotFound { id: id.to_string() })?;
+
+    // The time entry and the note are written together, or not at all
+    let time_entry = hours.map(|_| {
+        (format!("time_{}", uuid::Uuid::new_v4()), format!("TE-{}", super::time_entry::generate_short_id()))
+    });
+    storage.with_transaction(|storage| {
+        let result = (|| {
+            if let (Some((entry_id, entry_short_id)), Some(hours)) = (&time_entry, hours) {
+                let work_date = date.map_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string(), String::from);
+                storage.create_time_entry(
+                    entry_id,
+                    Some(entry_short_id),
+                    &issue.project_path,
+                    hours,
+                    text,
+                    &work_date,
+                    Some(&issue.id),
+                    period,
+                    &actor,
+                )?;
+            }
+            storage.log_issue_work(&issue.id, text, time_entry.as_ref().map(|(id, _)| id.as_str()), &actor)
+        })();
+        let ok = result.is_ok();
+        (result, ok)
+    })??;
+
+    let short_id = issue.short_id.as_deref().unwrap_or(&issue.id);
+    if crate::is_silent() {
+        println!("{}", issue.id);
     } else if json {
-        let output = serde_json::json!({
-            "ids": results,
-            "status": "in_progress",
-            "assigned_to": actor,
-            "count": results.len()
-        });
-        println!("{output}");
+        let (time_entry_id, time_entry_short_id) = time_entry.unzip();
+        let output = IssueLogOutput {
+            issue_id: issue.id.clone(),
+            text: text.to_string(),
+            time_entry_id,
+            time_entry_short_id,
+            hours,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
-        for id in &results {
-            println!("Claimed issue: {id}");
+        match (&time_entry, hours) {
+            (Some((_, entry_short_id)), Some(hours)) => {
+                println!("Logged work on [{short_id}]: {hours:.1}hrs as {entry_short_id}");
+            }
+            _ => println!("Logged work on [{short_id}]"),
         }
     }
 
     Ok(())
 }
 
-fn release(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+fn release(ids: &[String], db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
@@ -983,12 +2273,13 @@ fn release(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json:
         return Ok(());
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     let mut results = Vec::new();
     for id in ids {
-        storage.release_issue(id, &actor)?;
+        let workflow = workflow_for_issue(storage, id);
+        storage.release_issue(id, &workflow, &actor)?;
         results.push(id.as_str());
     }
 
@@ -997,12 +2288,15 @@ fn release(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json:
             println!("{id}");
         }
     } else if json {
-        let output = serde_json::json!({
-            "ids": results,
-            "status": "open",
-            "count": results.len()
-        });
-        println!("{output}");
+        let output = IssueIdsOutput {
+            count: results.len(),
+            ids: results,
+            status: Some(IssueStatus::Open),
+            assigned_to: None,
+            close_reason: None,
+            deleted: None,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         for id in &results {
             println!("Released issue: {id}");
@@ -1012,11 +2306,8 @@ fn release(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json:
     Ok(())
 }
 
-fn delete(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+fn delete(ids: &[String], db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
@@ -1027,26 +2318,33 @@ fn delete(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json:
         return Ok(());
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
-    let mut results = Vec::new();
-    for id in ids {
-        storage.delete_issue(id, &actor)?;
-        results.push(id.as_str());
-    }
+    let results = storage.atomically(|storage| {
+        let mut results = Vec::new();
+        for id in ids {
+            storage.delete_issue(id, &actor)?;
+            results.push(id.as_str());
+        }
+        Ok(results)
+    })?;
+    super::trash::purge_expired(storage)?;
 
     if crate::is_silent() {
         for id in &results {
             println!("{id}");
         }
     } else if json {
-        let output = serde_json::json!({
-            "ids": results,
-            "deleted": true,
-            "count": results.len()
-        });
-        println!("{output}");
+        let output = IssueIdsOutput {
+            count: results.len(),
+            ids: results,
+            status: None,
+            assigned_to: None,
+            close_reason: None,
+            deleted: Some(true),
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         for id in &results {
             println!("Deleted issue: {id}");
@@ -1056,30 +2354,13 @@ fn delete(ids: &[String], db_path: Option<&PathBuf>, actor: Option<&str>, json:
     Ok(())
 }
 
-/// Generate a short ID (4 hex chars).
-fn generate_short_id() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis();
-    format!("{:04x}", (now & 0xFFFF) as u16)
-}
-
 fn label(
     command: &IssueLabelCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     match command {
@@ -1087,12 +2368,8 @@ fn label(
             storage.add_issue_labels(id, labels, &actor)?;
 
             if json {
-                let output = serde_json::json!({
-                    "id": id,
-                    "action": "add",
-                    "labels": labels
-                });
-                println!("{output}");
+                let output = IssueLabelOutput { id, action: "add", labels };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("Added labels to {}: {}", id, labels.join(", "));
             }
@@ -1101,12 +2378,8 @@ fn label(
             storage.remove_issue_labels(id, labels, &actor)?;
 
             if json {
-                let output = serde_json::json!({
-                    "id": id,
-                    "action": "remove",
-                    "labels": labels
-                });
-                println!("{output}");
+                let output = IssueLabelOutput { id, action: "remove", labels };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("Removed labels from {}: {}", id, labels.join(", "));
             }
@@ -1118,18 +2391,11 @@ fn label(
 
 fn dep(
     command: &IssueDepCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     match command {
@@ -1137,12 +2403,8 @@ fn dep(
             storage.add_issue_dependency(id, depends_on, dep_type, &actor)?;
 
             if json {
-                let output = serde_json::json!({
-                    "issue_id": id,
-                    "depends_on_id": depends_on,
-                    "dependency_type": dep_type
-                });
-                println!("{output}");
+                let output = DepAddOutput { issue_id: id, depends_on_id: depends_on, dependency_type: dep_type };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("Added dependency: {} depends on {} ({})", id, depends_on, dep_type);
             }
@@ -1151,18 +2413,14 @@ fn dep(
             storage.remove_issue_dependency(id, depends_on, &actor)?;
 
             if json {
-                let output = serde_json::json!({
-                    "issue_id": id,
-                    "depends_on_id": depends_on,
-                    "removed": true
-                });
-                println!("{output}");
+                let output = DepRemoveOutput { issue_id: id, depends_on_id: depends_on, removed: true };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("Removed dependency: {} no longer depends on {}", id, depends_on);
             }
         }
         IssueDepCommands::Tree { id } => {
-            return dep_tree(id.as_deref(), Some(&db_path), json);
+            return dep_tree(id.as_deref(), db, json);
         }
     }
 
@@ -1172,18 +2430,11 @@ fn dep(
 fn clone_issue(
     id: &str,
     new_title: Option<&str>,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
     let cloned = storage.clone_issue(id, new_title, &actor)?;
@@ -1201,29 +2452,19 @@ fn clone_issue(
 fn duplicate(
     id: &str,
     duplicate_of: &str,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
 
-    storage.mark_issue_duplicate(id, duplicate_of, &actor)?;
+    let workflow = workflow_for_issue(storage, id);
+    storage.mark_issue_duplicate(id, duplicate_of, &workflow, &actor)?;
 
     if json {
-        let output = serde_json::json!({
-            "id": id,
-            "duplicate_of": duplicate_of,
-            "status": "closed"
-        });
-        println!("{output}");
+        let output = DuplicateOutput { id, duplicate_of, status: IssueStatus::Closed };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Marked {} as duplicate of {} (closed)", id, duplicate_of);
     }
@@ -1231,16 +2472,11 @@ fn duplicate(
     Ok(())
 }
 
-fn ready(limit: usize, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+fn ready(limit: usize, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    super::policy::release_expired(storage, &project_path, &actor);
 
     #[allow(clippy::cast_possible_truncation)]
     let issues = storage.get_ready_issues(&project_path, limit as u32)?;
@@ -1276,25 +2512,84 @@ fn ready(limit: usize, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     Ok(())
 }
 
+fn waves(db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let waves = storage.get_issue_waves(&project_path)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&waves)?);
+        return Ok(());
+    }
+    if waves.waves.is_empty() && waves.unscheduled.is_empty() {
+        println!("No unfinished issues.");
+        return Ok(());
+    }
+    let line = |issue: &Issue| {
+        let marker = if issue.status == IssueStatus::Open && issue.assigned_to_agent.is_none() { "○" } else { "●" };
+        let short_id = issue.short_id.as_deref().unwrap_or(&issue.id);
+        let assignee = issue.assigned_to_agent.as_deref().map_or_else(String::new, |agent| format!(" → {agent}"));
+        println!("  {marker} [{short_id}] P{} {} ({}, {}){assignee}", issue.priority, issue.title, issue.issue_type, issue.status);
+    };
+    for (index, wave) in waves.waves.iter().enumerate() {
+        println!("Wave {}:", index + 1);
+        wave.iter().for_each(line);
+        println!();
+    }
+    if !waves.unscheduled.is_empty() {
+        println!("Unscheduled (in a dependency cycle, or waiting on a deferred issue or another project):");
+        waves.unscheduled.iter().for_each(line);
+    }
+
+    Ok(())
+}
+
 fn next_block(
     count: usize,
-    db_path: Option<&PathBuf>,
+    force: bool,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
+    let storage = db.storage()?;
+    let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
+    let project_path = resolve_project_path(storage, None)?;
+
+    // The caller's share under the routing rules: no more than its WIP
+    // limit allows, issues matching its skills first
+    let mut routing = canonical_routing(storage, load_routing(Some(&project_path)))?;
+    let canonical = storage.resolve_actor(&actor)?;
+    super::policy::release_expired(storage, &project_path, &actor);
+    let workload = workloads(storage, &project_path, std::slice::from_ref(&canonical))?;
+    let (ready, labels) = routable(storage, &project_path)?;
+
+    // The policy is a hard limit: refuse outright when even one more claim
+    // breaks it, else hand out no more than it leaves room for
+    let policy = load_policy(Some(&project_path));
+    if !force {
+        if let (Some(first), true) = (ready.first(), count > 0) {
+            super::policy::enforce(storage, &policy, &project_path, &canonical, &[first.id.as_str()])?;
+        }
+        if let Some(max) = policy.max_in_progress {
+            routing.cap_wip_limits(max);
+        }
     }
+    let picks = routing.assign(&labels, &workload, count);
 
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let project_path = resolve_project_path(&storage, None)?;
+    if crate::is_dry_run() {
+        for pick in &picks {
+            println!("Would claim issue: {}", ready[pick.issue].short_id.as_deref().unwrap_or(&ready[pick.issue].id));
+        }
+        return Ok(());
+    }
 
-    #[allow(clippy::cast_possible_truncation)]
-    let issues = storage.get_next_issue_block(&project_path, count as u32, &actor)?;
+    let workflow = load_issue_workflow(Some(&project_path));
+    let mut issues = Vec::with_capacity(picks.len());
+    for pick in &picks {
+        storage.claim_issue(&ready[pick.issue].id, &workflow, &actor)?;
+        super::policy::lease(storage, &policy, &ready[pick.issue].id, &canonical)?;
+        issues.extend(storage.get_issue(&ready[pick.issue].id, None)?);
+    }
 
     if json {
         let output = IssueListOutput {
@@ -1303,7 +2598,11 @@ fn next_block(
         };
         println!("{}", serde_json::to_string(&output)?);
     } else if issues.is_empty() {
-        println!("No issues available to claim.");
+        if routing.capacity(&workload[0]) == Some(0) {
+            println!("{actor} is at its WIP limit ({} in progress).", workload[0].in_progress);
+        } else {
+            println!("No issues available to claim.");
+        }
     } else {
         println!("Claimed {} issues:", issues.len());
         println!();
@@ -1327,129 +2626,267 @@ fn next_block(
     Ok(())
 }
 
-/// Create multiple issues at once with dependencies.
-fn batch(
-    json_input: &str,
-    db_path: Option<&PathBuf>,
+/// Deal ready issues out to agents under the project's routing rules.
+fn route(
+    count: Option<usize>,
+    policy: Option<RoutingPolicy>,
+    agents: &[String],
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let mut routing = canonical_routing(storage, load_routing(Some(&project_path)))?;
+    if let Some(policy) = policy {
+        routing.policy = policy;
+    }
+    let claim_policy = load_policy(Some(&project_path));
+    if let Some(max) = claim_policy.max_in_progress {
+        routing.cap_wip_limits(max);
     }
 
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let project_path = resolve_project_path(&storage, None)?;
+    let mut names: Vec<String> = if !agents.is_empty() {
+        agents.iter().map(|name| storage.resolve_actor(name.trim())).collect::<Result<_>>()?
+    } else if !routing.agents.is_empty() {
+        routing.agents.keys().cloned().collect()
+    } else {
+        storage
+            .list_actors()?
+            .into_iter()
+            .filter(|a| matches!(a.actor_type, ActorType::Agent | ActorType::Model))
+            .map(|a| a.id)
+            .collect()
+    };
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        return Err(Error::InvalidArgument(
+            "No agents to route to. Pass --agents, list them under routing.agents in the config, or register them with: sc actor register <id> --type agent".to_string(),
+        ));
+    }
 
-    // Parse the JSON input
-    let input: BatchInput = serde_json::from_str(json_input)
-        .map_err(|e| Error::Other(format!("Invalid JSON input: {e}")))?;
+    super::policy::release_expired(storage, &project_path, &actor.map_or_else(default_actor, ToString::to_string));
+    let workload = workloads(storage, &project_path, &names)?;
+    let (ready, labels) = routable(storage, &project_path)?;
+    let picks = routing.assign(&labels, &workload, count.unwrap_or(names.len()));
 
-    // Track created issue IDs by index for resolving $N references
-    let mut created_ids: Vec<String> = Vec::with_capacity(input.issues.len());
-    let mut results: Vec<BatchIssueResult> = Vec::with_capacity(input.issues.len());
+    let assignments: Vec<RouteAssignment> = picks
+        .into_iter()
+        .map(|pick| {
+            let issue = &ready[pick.issue];
+            RouteAssignment {
+                agent: pick.agent,
+                issue_id: issue.id.clone(),
+                short_id: issue.short_id.clone(),
+                title: issue.title.clone(),
+                matched_skills: pick.matched,
+            }
+        })
+        .collect();
 
-    // Create issues in order
-    for (index, issue) in input.issues.iter().enumerate() {
-        let id = format!("issue_{}", &uuid::Uuid::new_v4().to_string()[..12]);
-        let short_id = generate_short_id();
+    if crate::is_dry_run() {
+        for assignment in &assignments {
+            let id = assignment.short_id.as_deref().unwrap_or(&assignment.issue_id);
+            println!("Would assign {id} to {}", assignment.agent);
+        }
+        return Ok(());
+    }
+    let workflow = load_issue_workflow(Some(&project_path));
+    for assignment in &assignments {
+        storage.claim_issue(&assignment.issue_id, &workflow, &assignment.agent)?;
+        super::policy::lease(storage, &claim_policy, &assignment.issue_id, &assignment.agent)?;
+    }
 
-        // Resolve parent_id: if it starts with "$", look up created ID by index
-        let resolved_parent_id = issue.parent_id.as_ref().and_then(|pid| {
-            if let Some(idx_str) = pid.strip_prefix('$') {
-                if let Ok(idx) = idx_str.parse::<usize>() {
-                    created_ids.get(idx).cloned()
-                } else {
-                    Some(pid.clone())
-                }
+    if json {
+        let output = RouteOutput {
+            policy: routing.policy.to_string(),
+            count: assignments.len(),
+            assignments,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if assignments.is_empty() {
+        println!("Nothing to route: no ready issues, or every agent is at its WIP limit.");
+    } else {
+        println!("Routed {} issues ({}):", assignments.len(), routing.policy);
+        println!();
+        for assignment in &assignments {
+            let id = assignment.short_id.as_deref().unwrap_or(&assignment.issue_id);
+            let skills = if assignment.matched_skills.is_empty() {
+                String::new()
             } else {
-                Some(pid.clone())
-            }
-        });
+                format!("  [{}]", assignment.matched_skills.join(", "))
+            };
+            println!("● [{id}] {} → {}{skills}", assignment.title, assignment.agent);
+        }
+    }
 
-        // Use issue-level plan_id, or fall back to batch-level plan_id
-        let plan_id = issue.plan_id.as_ref().or(input.plan_id.as_ref());
+    Ok(())
+}
 
-        storage.create_issue(
-            &id,
-            Some(&short_id),
-            &project_path,
-            &issue.title,
-            issue.description.as_deref(),
-            issue.details.as_deref(),
-            issue.issue_type.as_deref(),
-            issue.priority,
-            plan_id.map(String::as_str),
-            &actor,
-        )?;
+/// Routing rules with the agents they name resolved to canonical actors.
+fn canonical_routing(storage: &SqliteStorage, mut routing: Routing) -> Result<Routing> {
+    routing.agents = std::mem::take(&mut routing.agents)
+        .into_iter()
+        .map(|(agent, rules)| Ok((storage.resolve_actor(&agent)?, rules)))
+        .collect::<Result<_>>()?;
+    Ok(routing)
+}
 
-        // Set parent via parent-child dependency if resolved
-        if let Some(ref parent) = resolved_parent_id {
-            storage.add_issue_dependency(&id, parent, "parent-child", &actor)?;
+/// Current load of each of `agents` (canonical IDs), counting aliases.
+fn workloads(storage: &SqliteStorage, project_path: &str, agents: &[String]) -> Result<Vec<AgentWorkload>> {
+    let mut by_agent: HashMap<&str, AgentWorkload> = agents
+        .iter()
+        .map(|agent| (agent.as_str(), AgentWorkload { agent: agent.clone(), ..AgentWorkload::default() }))
+        .collect();
+    for row in storage.get_agent_workload(project_path)? {
+        let canonical = storage.resolve_actor(&row.agent)?;
+        if let Some(entry) = by_agent.get_mut(canonical.as_str()) {
+            entry.in_progress += row.in_progress;
+            entry.last_assigned_at = entry.last_assigned_at.max(row.last_assigned_at);
         }
+    }
+    Ok(agents.iter().filter_map(|agent| by_agent.remove(agent.as_str())).collect())
+}
+
+/// Every ready issue, in ready order, with its labels.
+fn routable(storage: &SqliteStorage, project_path: &str) -> Result<(Vec<Issue>, Vec<Vec<String>>)> {
+    let ready = storage.get_ready_issues(project_path, u32::MAX)?;
+    let labels = ready.iter().map(|issue| storage.get_issue_labels(&issue.id)).collect::<Result<_>>()?;
+    Ok((ready, labels))
+}
+
+/// Create multiple issues at once with dependencies.
+fn batch(
+    json_input: &str,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+    let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
+    let project_path = resolve_project_path(storage, None)?;
+
+    // Parse the JSON input
+    let input: BatchInput = serde_json::from_str(json_input)
+        .map_err(|e| Error::InvalidArgument(format!("Invalid JSON input: {e}")))?;
+    let dependencies = input.dependencies.as_deref().unwrap_or_default();
+
+    // Check the whole graph first, so a bad batch writes nothing
+    let parents = validate_batch(storage, &input.issues, dependencies)?;
 
-        // Add labels if provided
-        if let Some(ref labels) = issue.labels {
-            if !labels.is_empty() {
-                storage.add_issue_labels(&id, labels, &actor)?;
+    if crate::is_dry_run() {
+        if json {
+            let output = serde_json::json!({
+                "dry_run": true,
+                "action": "batch_create_issues",
+                "count": input.issues.len(),
+                "dependency_count": dependencies.len(),
+            });
+            println!("{output}");
+        } else {
+            println!("Would create {} issues and {} dependencies:", input.issues.len(), dependencies.len());
+            for (index, issue) in input.issues.iter().enumerate() {
+                println!("  ${index} {}", issue.title);
             }
         }
-
-        created_ids.push(id.clone());
-        results.push(BatchIssueResult {
-            id,
-            short_id: Some(short_id),
-            title: issue.title.clone(),
-            index,
-        });
+        return Ok(());
     }
 
-    // Create dependencies
-    let mut dep_results: Vec<BatchDepResult> = Vec::new();
-    if let Some(deps) = input.dependencies {
-        for dep in deps {
-            if dep.issue_index >= created_ids.len() || dep.depends_on_index >= created_ids.len() {
-                return Err(Error::Other(format!(
-                    "Dependency index out of range: {} -> {}",
-                    dep.issue_index, dep.depends_on_index
-                )));
+    // Issues and dependencies are created together, or not at all
+    let (results, dep_results) = storage.atomically(|storage| {
+        let mut results: Vec<BatchIssueResult> = Vec::with_capacity(input.issues.len());
+        for (index, issue) in input.issues.iter().enumerate() {
+            let id = format!("issue_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+            let short_id = storage.next_issue_short_id()?;
+
+            // Use issue-level plan_id, or fall back to batch-level plan_id
+            let plan_id = issue.plan_id.as_ref().or(input.plan_id.as_ref());
+
+            storage.create_issue(
+                &id,
+                Some(&short_id),
+                &project_path,
+                &issue.title,
+                issue.description.as_deref(),
+                issue.details.as_deref(),
+                issue.issue_type,
+                issue.priority,
+                plan_id.map(String::as_str),
+                None,
+                &actor,
+            )?;
+
+            if let Some(ref labels) = issue.labels {
+                if !labels.is_empty() {
+                    storage.add_issue_labels(&id, labels, &actor)?;
+                }
             }
 
-            let issue_id = &created_ids[dep.issue_index];
-            let depends_on_id = &created_ids[dep.depends_on_index];
+            results.push(BatchIssueResult {
+                id,
+                short_id: Some(short_id),
+                title: issue.title.clone(),
+                index,
+            });
+        }
+
+        // Parents are linked once every issue exists, so `$N` may point forward
+        for (result, parent) in results.iter().zip(&parents) {
+            let parent = match parent {
+                Some(BatchParent::Index(index)) => &results[*index].id,
+                Some(BatchParent::Existing(id)) => id,
+                None => continue,
+            };
+            storage.add_issue_dependency(&result.id, parent, "parent-child", &actor)?;
+        }
+
+        let mut dep_results: Vec<BatchDepResult> = Vec::with_capacity(dependencies.len());
+        for dep in dependencies {
+            let issue = &results[dep.issue_index];
+            let depends_on = &results[dep.depends_on_index];
             let dep_type = dep.dependency_type.as_deref().unwrap_or("blocks");
 
-            storage.add_issue_dependency(issue_id, depends_on_id, dep_type, &actor)?;
+            storage.add_issue_dependency(&issue.id, &depends_on.id, dep_type, &actor)?;
 
             dep_results.push(BatchDepResult {
-                issue_id: issue_id.clone(),
-                depends_on_id: depends_on_id.clone(),
+                issue_id: issue.id.clone(),
+                depends_on_id: depends_on.id.clone(),
+                issue_short_id: issue.short_id.clone().unwrap_or_default(),
+                depends_on_short_id: depends_on.short_id.clone().unwrap_or_default(),
                 dependency_type: dep_type.to_string(),
             });
         }
-    }
+        Ok((results, dep_results))
+    })?;
 
     let output = BatchOutput {
+        short_ids: results
+            .iter()
+            .map(|r| (r.index.to_string(), r.short_id.clone().unwrap_or_default()))
+            .collect(),
+        count: results.len(),
+        dependency_count: dep_results.len(),
         issues: results,
         dependencies: dep_results,
     };
 
-    if json {
+    if crate::is_silent() {
+        for result in &output.issues {
+            println!("{}", result.short_id.as_deref().unwrap_or(&result.id));
+        }
+    } else if json {
         println!("{}", serde_json::to_string(&output)?);
     } else {
-        println!("Created {} issues:", output.issues.len());
+        println!("Created {} issues:", output.count);
         for result in &output.issues {
             let short_id = result.short_id.as_deref().unwrap_or(&result.id[..8]);
-            println!("  [{}] {}", short_id, result.title);
+            println!("  ${} [{}] {}", result.index, short_id, result.title);
         }
         if !output.dependencies.is_empty() {
-            println!("\nCreated {} dependencies:", output.dependencies.len());
+            println!("\nCreated {} dependencies:", output.dependency_count);
             for dep in &output.dependencies {
-                println!("  {} -> {} ({})", dep.issue_id, dep.depends_on_id, dep.dependency_type);
+                println!("  {} -> {} ({})", dep.issue_short_id, dep.depends_on_short_id, dep.dependency_type);
             }
         }
     }
@@ -1457,16 +2894,155 @@ fn batch(
     Ok(())
 }
 
-fn count(group_by: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
+/// Check a batch before anything is written: titles present and distinct,
+/// every `$N` and dependency index inside the batch, every other parent an
+/// existing issue, known dependency types, and no cycle through `blocks`
+/// and parent-child links. Returns each issue's parent.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] listing every problem found, or
+/// [`Error::CycleDetected`] for a cycle.
+fn validate_batch(
+    storage: &SqliteStorage,
+    issues: &[BatchIssue],
+    dependencies: &[BatchDependency],
+) -> Result<Vec<Option<BatchParent>>> {
+    let mut problems = Vec::new();
+    if issues.is_empty() {
+        problems.push("no issues given".to_string());
+    }
 
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
+    let mut titles: HashMap<String, usize> = HashMap::new();
+    for (index, issue) in issues.iter().enumerate() {
+        let title = issue.title.trim().to_lowercase();
+        if title.is_empty() {
+            problems.push(format!("issue ${index} has no title"));
+        } else if let Some(first) = titles.get(&title) {
+            problems.push(format!("issue ${index} repeats the title of ${first} ({:?})", issue.title));
+        } else {
+            titles.insert(title, index);
+        }
+    }
+
+    let mut parents = Vec::with_capacity(issues.len());
+    for (index, issue) in issues.iter().enumerate() {
+        let Some(reference) = issue.parent_id.as_deref() else {
+            parents.push(None);
+            continue;
+        };
+        let parent = if let Some(n) = reference.strip_prefix('$') {
+            match n.parse::<usize>() {
+                Ok(parent) if parent == index => {
+                    problems.push(format!("issue ${index} is its own parent"));
+                    None
+                }
+                Ok(parent) if parent < issues.len() => Some(BatchParent::Index(parent)),
+                _ => {
+                    problems.push(format!("issue ${index}: parent {reference} is not in the batch"));
+                    None
+                }
+            }
+        } else {
+            let parent = storage.get_issue(reference, None)?.map(|parent| BatchParent::Existing(parent.id));
+            if parent.is_none() {
+                problems.push(format!("issue ${index}: parent {reference} not found"));
+            }
+            parent
+        };
+        parents.push(parent);
+    }
+
+    for (n, dep) in dependencies.iter().enumerate() {
+        for index in [dep.issue_index, dep.depends_on_index] {
+            if index >= issues.len() {
+                problems.push(format!("dependency {n}: ${index} is not in the batch"));
+            }
+        }
+        if dep.issue_index == dep.depends_on_index {
+            problems.push(format!("dependency {n}: ${} depends on itself", dep.issue_index));
+        }
+        if let Some(dep_type) = dep.dependency_type.as_deref() {
+            if !BATCH_DEPENDENCY_TYPES.contains(&dep_type) {
+                problems.push(format!(
+                    "dependency {n}: unknown type '{dep_type}' (use {})",
+                    BATCH_DEPENDENCY_TYPES.join(", ")
+                ));
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(Error::InvalidArgument(format!("Batch not created: {}", problems.join("; "))));
+    }
+
+    // Edges point from an issue to what it waits on
+    let mut edges: Vec<(usize, usize)> = parents
+        .iter()
+        .enumerate()
+        .filter_map(|(child, parent)| match parent {
+            Some(BatchParent::Index(parent)) => Some((child, *parent)),
+            _ => None,
+        })
+        .collect();
+    edges.extend(
+        dependencies
+            .iter()
+            .filter(|dep| matches!(dep.dependency_type.as_deref(), None | Some("blocks" | "parent-child")))
+            .map(|dep| (dep.issue_index, dep.depends_on_index)),
+    );
+    if let Some(cycle) = find_cycle(issues.len(), &edges) {
+        return Err(Error::CycleDetected { cycle: cycle.iter().map(|index| format!("${index}")).collect() });
+    }
+
+    Ok(parents)
+}
+
+/// A cycle among `nodes` nodes joined by `edges` (from, to): the nodes
+/// along it, with the first repeated at the end.
+fn find_cycle(nodes: usize, edges: &[(usize, usize)]) -> Option<Vec<usize>> {
+    let mut next = vec![Vec::new(); nodes];
+    for &(from, to) in edges {
+        next[from].push(to);
+    }
+
+    // Depth-first, keeping the path on an explicit stack so long chains
+    // can't overflow the call stack
+    let (unvisited, on_path, done) = (0u8, 1u8, 2u8);
+    let mut state = vec![unvisited; nodes];
+    for root in 0..nodes {
+        if state[root] != unvisited {
+            continue;
+        }
+        state[root] = on_path;
+        let mut path = vec![(root, 0usize)];
+        while let Some(&(node, edge)) = path.last() {
+            let Some(&to) = next[node].get(edge) else {
+                state[node] = done;
+                path.pop();
+                continue;
+            };
+            if let Some(top) = path.last_mut() {
+                top.1 += 1;
+            }
+            if state[to] == on_path {
+                let start = path.iter().position(|&(n, _)| n == to).unwrap_or(0);
+                let mut cycle: Vec<usize> = path[start..].iter().map(|&(n, _)| n).collect();
+                cycle.push(to);
+                return Some(cycle);
+            }
+            if state[to] == unvisited {
+                state[to] = on_path;
+                path.push((to, 0));
+            }
+        }
     }
+    None
+}
 
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+fn count(group_by: &str, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     let groups = storage.count_issues_grouped(&project_path, group_by)?;
     let total: i64 = groups.iter().map(|(_, c)| c).sum();
@@ -1477,14 +3053,12 @@ fn count(group_by: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
             println!("{},{count}", crate::csv_escape(key));
         }
     } else if json {
-        let output = serde_json::json!({
-            "groups": groups.iter().map(|(k, c)| {
-                serde_json::json!({"key": k, "count": c})
-            }).collect::<Vec<_>>(),
-            "total": total,
-            "group_by": group_by
-        });
-        println!("{output}");
+        let output = IssueCountOutput {
+            groups: groups.iter().map(|(key, count)| IssueCountGroup { key, count: *count }).collect(),
+            total,
+            group_by,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else if groups.is_empty() {
         println!("No issues found.");
     } else {
@@ -1500,16 +3074,9 @@ fn count(group_by: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     Ok(())
 }
 
-fn stale(days: u64, limit: usize, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+fn stale(days: u64, limit: usize, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     #[allow(clippy::cast_possible_truncation)]
     let issues = storage.get_stale_issues(&project_path, days, limit as u32)?;
@@ -1524,18 +3091,11 @@ fn stale(days: u64, limit: usize, db_path: Option<&PathBuf>, json: bool) -> Resu
             println!("{short_id},{title},{},{},{},{stale_d}", issue.status, issue.priority, issue.issue_type);
         }
     } else if json {
-        let enriched: Vec<_> = issues.iter().map(|i| {
-            let stale_d = (now_ms - i.updated_at) / (24 * 60 * 60 * 1000);
-            serde_json::json!({
-                "issue": i,
-                "stale_days": stale_d
-            })
+        let enriched = issues.iter().map(|issue| {
+            let stale_days = (now_ms - issue.updated_at) / (24 * 60 * 60 * 1000);
+            StaleIssue { issue, stale_days }
         }).collect();
-        let output = serde_json::json!({
-            "issues": enriched,
-            "count": issues.len(),
-            "threshold_days": days
-        });
+        let output = StaleOutput { issues: enriched, count: issues.len(), threshold_days: days };
         println!("{}", serde_json::to_string(&output)?);
     } else if issues.is_empty() {
         println!("No stale issues (threshold: {days} days).");
@@ -1561,16 +3121,9 @@ fn stale(days: u64, limit: usize, db_path: Option<&PathBuf>, json: bool) -> Resu
     Ok(())
 }
 
-fn blocked(limit: usize, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+fn blocked(limit: usize, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     #[allow(clippy::cast_possible_truncation)]
     let blocked_issues = storage.get_blocked_issues(&project_path, limit as u32)?;
@@ -1586,16 +3139,8 @@ fn blocked(limit: usize, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
             println!("{short_id},{title},{},{}", issue.status, blocker_ids.join(";"));
         }
     } else if json {
-        let entries: Vec<_> = blocked_issues.iter().map(|(issue, blockers)| {
-            serde_json::json!({
-                "issue": issue,
-                "blocked_by": blockers
-            })
-        }).collect();
-        let output = serde_json::json!({
-            "blocked_issues": entries,
-            "count": blocked_issues.len()
-        });
+        let entries = blocked_issues.iter().map(|(issue, blockers)| BlockedIssue { issue, blocked_by: blockers }).collect();
+        let output = BlockedOutput { blocked_issues: entries, count: blocked_issues.len() };
         println!("{}", serde_json::to_string(&output)?);
     } else if blocked_issues.is_empty() {
         println!("No blocked issues.");
@@ -1618,16 +3163,9 @@ fn blocked(limit: usize, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     Ok(())
 }
 
-fn dep_tree(id: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+fn dep_tree(id: Option<&str>, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     if let Some(root_id) = id {
         // Show tree for a specific issue
@@ -1638,7 +3176,8 @@ fn dep_tree(id: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result<(
         let epics = storage.get_epics(&project_path)?;
         if epics.is_empty() {
             if json {
-                println!("{{\"trees\":[],\"count\":0}}");
+                let output = DepTreesOutput { trees: Vec::new(), count: 0 };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("No epics found.");
             }
@@ -1646,15 +3185,8 @@ fn dep_tree(id: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result<(
         }
 
         if json {
-            let mut trees = Vec::new();
-            for epic in &epics {
-                let tree = storage.get_dependency_tree(&epic.id)?;
-                trees.push(tree_to_json(&tree));
-            }
-            let output = serde_json::json!({
-                "trees": trees,
-                "count": epics.len()
-            });
+            let flat = epics.iter().map(|epic| storage.get_dependency_tree(&epic.id)).collect::<Result<Vec<_>>>()?;
+            let output = DepTreesOutput { trees: flat.iter().filter_map(|tree| tree_node(tree)).collect(), count: epics.len() };
             println!("{}", serde_json::to_string(&output)?);
         } else {
             for (i, epic) in epics.iter().enumerate() {
@@ -1672,31 +3204,20 @@ fn dep_tree(id: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result<(
 
 fn print_dep_tree(tree: &[(crate::storage::Issue, i32)], json: bool) -> Result<()> {
     if json {
-        let output = tree_to_json(tree);
-        println!("{}", serde_json::to_string(&output)?);
+        println!("{}", serde_json::to_string(&tree_node(tree))?);
     } else {
         print_ascii_tree(tree);
     }
     Ok(())
 }
 
-fn tree_to_json(tree: &[(crate::storage::Issue, i32)]) -> serde_json::Value {
-    if tree.is_empty() {
-        return serde_json::json!(null);
-    }
-
-    // Build nested structure from flat (issue, depth) list
-    #[derive(serde::Serialize)]
-    struct TreeNode {
-        issue: serde_json::Value,
-        children: Vec<TreeNode>,
-    }
-
+/// Nest a flat `(issue, depth)` list, root first, into a tree.
+fn tree_node(tree: &[(crate::storage::Issue, i32)]) -> Option<TreeNode<'_>> {
     fn build_children(
         tree: &[(crate::storage::Issue, i32)],
         parent_idx: usize,
         parent_depth: i32,
-    ) -> Vec<TreeNode> {
+    ) -> Vec<TreeNode<'_>> {
         let mut children = Vec::new();
         let mut i = parent_idx + 1;
         while i < tree.len() {
@@ -1705,24 +3226,15 @@ fn tree_to_json(tree: &[(crate::storage::Issue, i32)]) -> serde_json::Value {
                 break;
             }
             if depth == parent_depth + 1 {
-                let node = TreeNode {
-                    issue: serde_json::to_value(issue).unwrap_or_default(),
-                    children: build_children(tree, i, depth),
-                };
-                children.push(node);
+                children.push(TreeNode { issue, children: build_children(tree, i, depth) });
             }
             i += 1;
         }
         children
     }
 
-    let (ref root, root_depth) = tree[0];
-    let root_node = TreeNode {
-        issue: serde_json::to_value(root).unwrap_or_default(),
-        children: build_children(tree, 0, root_depth),
-    };
-
-    serde_json::to_value(root_node).unwrap_or_default()
+    let (root, root_depth) = tree.first()?;
+    Some(TreeNode { issue: root, children: build_children(tree, 0, *root_depth) })
 }
 
 fn print_ascii_tree(tree: &[(crate::storage::Issue, i32)]) {
@@ -1755,3 +3267,60 @@ fn print_ascii_tree(tree: &[(crate::storage::Issue, i32)]) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch_input(json: &str) -> BatchInput {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_validate_batch() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_issue("issue_1", Some("SC-1"), "/repo", "Epic", None, None, None, None, None, None, "actor").unwrap();
+
+        // Parents may point forward in the batch or at an existing issue
+        let input = batch_input(
+            r#"{"issues": [{"title": "A", "parentId": "$1"}, {"title": "B", "parentId": "SC-1"}],
+                "dependencies": [{"issueIndex": 0, "dependsOnIndex": 1, "dependencyType": "related"}]}"#,
+        );
+        let parents = validate_batch(&storage, &input.issues, input.dependencies.as_deref().unwrap()).unwrap();
+        assert_eq!(parents, [Some(BatchParent::Index(1)), Some(BatchParent::Existing("issue_1".to_string()))]);
+
+        // Every problem is reported at once
+        let input = batch_input(
+            r#"{"issues": [{"title": "A", "parentId": "$5"}, {"title": " a "}, {"title": "C", "parentId": "SC-9"}],
+                "dependencies": [{"issueIndex": 0, "dependsOnIndex": 3}, {"issueIndex": 1, "dependsOnIndex": 2, "dependencyType": "waits"}]}"#,
+        );
+        let message = validate_batch(&storage, &input.issues, input.dependencies.as_deref().unwrap()).unwrap_err().to_string();
+        for problem in [
+            "issue $1 repeats the title of $0",
+            "issue $0: parent $5 is not in the batch",
+            "issue $2: parent SC-9 not found",
+            "dependency 0: $3 is not in the batch",
+            "dependency 1: unknown type 'waits'",
+        ] {
+            assert!(message.contains(problem), "{problem} missing from {message}");
+        }
+
+        // A cycle through a parent link and a blocking dependency
+        let input = batch_input(
+            r#"{"issues": [{"title": "A"}, {"title": "B", "parentId": "$2"}, {"title": "C"}],
+                "dependencies": [{"issueIndex": 2, "dependsOnIndex": 1}]}"#,
+        );
+        let err = validate_batch(&storage, &input.issues, input.dependencies.as_deref().unwrap()).unwrap_err();
+        assert!(matches!(&err, Error::CycleDetected { cycle } if cycle == &["$1", "$2", "$1"]), "{err}");
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(find_cycle(4, &[(0, 1), (1, 2), (0, 2), (3, 2)]), None);
+        assert_eq!(find_cycle(3, &[(0, 1), (1, 2), (2, 1)]), Some(vec![1, 2, 1]));
+        assert_eq!(find_cycle(1, &[(0, 0)]), Some(vec![0, 0]));
+        // A long chain doesn't recurse
+        let chain: Vec<_> = (0..100_000).map(|i| (i, i + 1)).collect();
+        assert_eq!(find_cycle(100_001, &chain), None);
+    }
+}
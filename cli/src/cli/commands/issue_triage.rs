@@ -0,0 +1,443 @@
+//! Issue triage queue (`sc issue triage`).
+//!
+//! Untriaged issues are the open ones carrying the triage label
+//! (`needs-triage` unless `--label` names another), plus any in `--status`.
+//! On a terminal the queue is shown oldest first, one issue at a time,
+//! asking for priority, type, labels and assignee; Enter keeps each as it
+//! is. With stdin piped, each line is instead a JSON decision, so an agent
+//! can read the queue with `--list --json` and triage it in one call:
+//!
+//! ```text
+//! {"id":"SC-a1b2","priority":3,"type":"bug","labels":["frontend","-ui"],"assignee":"alice"}
+//! {"id":"SC-c3d4","skip":true}
+//! ```
+//!
+//! A label starting with `-` is removed, and an assignee of `-` unassigns.
+//! Each decision is applied in its own transaction and takes the issue out
+//! of the queue by removing the triage label.
+
+use crate::cli::Database;
+use crate::cli::commands::setup::Prompter;
+use crate::config::{default_actor, resolve_project_path};
+use crate::core::issues::workflow_for_issue;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::model::{IssueStatus, IssueType};
+use crate::storage::{Issue, SqliteStorage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, IsTerminal, Write};
+
+/// What `sc issue triage` was asked to go through.
+pub struct Queue<'a> {
+    /// Label marking an issue as untriaged.
+    pub label: &'a str,
+    /// Status that also queues an issue.
+    pub status: Option<&'a str>,
+    pub limit: usize,
+}
+
+/// A triage decision for one issue, as piped in or answered at the prompts.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Decision {
+    id: String,
+    /// 0-4, `P3`, or a name like `high`.
+    #[serde(default)]
+    priority: Option<Value>,
+    #[serde(default, rename = "type")]
+    issue_type: Option<String>,
+    /// Labels to add; `-name` removes one.
+    #[serde(default)]
+    labels: Vec<String>,
+    /// New assignee; `-` unassigns.
+    #[serde(default)]
+    assignee: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    /// Leave the issue in the queue untouched.
+    #[serde(default)]
+    skip: bool,
+}
+
+/// An issue as the queue shows it.
+#[derive(Serialize)]
+struct QueuedIssue {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    description: Option<String>,
+    status: IssueStatus,
+    priority: i32,
+    issue_type: IssueType,
+    assignee: Option<String>,
+    labels: Vec<String>,
+    created_at: i64,
+}
+
+/// Output for `sc issue triage --list`.
+#[derive(Serialize)]
+struct TriageQueueOutput {
+    issues: Vec<QueuedIssue>,
+    count: usize,
+}
+
+/// What happened to one issue.
+#[derive(Serialize)]
+struct TriageResult {
+    /// `triaged` or `skipped`.
+    action: String,
+    /// The issue after the decision.
+    issue: QueuedIssue,
+}
+
+/// Output for `sc issue triage`.
+#[derive(Serialize)]
+struct TriageOutput {
+    results: Vec<TriageResult>,
+    triaged: usize,
+    skipped: usize,
+    /// Issues still in the queue afterwards.
+    remaining: usize,
+}
+
+json_schema!(QueuedIssue {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    description: Option<String>,
+    status: IssueStatus,
+    priority: i32,
+    issue_type: IssueType,
+    assignee: Option<String>,
+    labels: Vec<String>,
+    created_at: i64,
+});
+json_schema!(TriageQueueOutput { issues: Vec<QueuedIssue>, count: usize });
+json_schema!(TriageResult { action: String, issue: QueuedIssue });
+json_schema!(TriageOutput { results: Vec<TriageResult>, triaged: usize, skipped: usize, remaining: usize });
+
+/// Schemas of the `--json` output: the queue with `--list`, the results
+/// otherwise.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("issue triage", one_of(&[schema_for::<TriageQueueOutput>(), schema_for::<TriageOutput>()]))]
+}
+
+/// Execute `sc issue triage`.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened, a piped decision is
+/// malformed, or a decision can't be applied (an unknown issue, an invalid
+/// value, or a status change the workflow forbids). Decisions before the
+/// failing one stay applied.
+pub fn execute(queue: &Queue, list: bool, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    let project_path = resolve_project_path(storage, None)?;
+    let issues = untriaged(storage, &project_path, queue, queue.limit)?;
+
+    if list {
+        let issues = issues.iter().map(|issue| queued(storage, issue)).collect::<Result<Vec<_>>>()?;
+        if json {
+            let output = TriageQueueOutput { count: issues.len(), issues };
+            println!("{}", serde_json::to_string(&output)?);
+        } else if issues.is_empty() {
+            println!("Nothing to triage.");
+        } else {
+            println!("Untriaged issues ({}):", issues.len());
+            for issue in &issues {
+                println!("  {}", summary(issue));
+            }
+        }
+        return Ok(());
+    }
+
+    let stdin = std::io::stdin();
+    let results = if stdin.is_terminal() {
+        if issues.is_empty() {
+            eprintln!("Nothing to triage.");
+        }
+        let mut prompter = Prompter::new(stdin.lock(), std::io::stderr(), false);
+        interactive(storage, &mut prompter, &issues, queue, &actor, json)?
+    } else {
+        piped(storage, stdin.lock(), queue, &actor, json)?
+    };
+
+    let triaged = results.iter().filter(|r| r.action == "triaged").count();
+    let skipped = results.len() - triaged;
+    let remaining = untriaged(storage, &project_path, queue, usize::MAX)?.len();
+    if json {
+        let output = TriageOutput { results, triaged, skipped, remaining };
+        if crate::is_dry_run() {
+            let mut preview = serde_json::to_value(&output)?;
+            preview["dry_run"] = true.into();
+            println!("{preview}");
+        } else {
+            println!("{}", serde_json::to_string(&output)?);
+        }
+    } else if !crate::is_silent() {
+        let verb = if crate::is_dry_run() { "Would triage" } else { "Triaged" };
+        println!("{verb} {triaged}, skipped {skipped}; {remaining} left in the queue");
+    }
+    Ok(())
+}
+
+fn untriaged(storage: &SqliteStorage, project_path: &str, queue: &Queue, limit: usize) -> Result<Vec<Issue>> {
+    let status = queue
+        .status
+        .map(|s| crate::validate::normalize_status(s).unwrap_or_else(|_| s.to_string()));
+    let limit = u32::try_from(limit).unwrap_or(u32::MAX);
+    storage.get_untriaged_issues(project_path, queue.label, status.as_deref(), limit)
+}
+
+/// Ask about each issue in turn until the queue ends or the user quits.
+fn interactive<R: BufRead, W: Write>(
+    storage: &mut SqliteStorage,
+    prompter: &mut Prompter<R, W>,
+    issues: &[Issue],
+    queue: &Queue,
+    actor: &str,
+    json: bool,
+) -> Result<Vec<TriageResult>> {
+    let mut results = Vec::new();
+    for (i, issue) in issues.iter().enumerate() {
+        let current = queued(storage, issue)?;
+        prompter.say("")?;
+        prompter.say(&format!("[{}/{}] {}", i + 1, issues.len(), summary(&current)))?;
+        if let Some(description) = current.description.as_deref().and_then(|d| d.lines().next()) {
+            prompter.say(&format!("    {description}"))?;
+        }
+
+        // A rejected answer (a typo in the type, a forbidden status) asks again
+        let result = loop {
+            let Some(decision) = ask(prompter, &current, queue)? else {
+                return Ok(results);
+            };
+            match apply(storage, &decision, queue.label, actor) {
+                Ok(result) => break result,
+                // Exit code 4: the input was invalid
+                Err(err) if err.exit_code() == 4 => prompter.say(&format!("{err}"))?,
+                Err(err) => return Err(err),
+            }
+        };
+        if !json && !crate::is_silent() {
+            prompter.say(&format!("  {}: {}", result.action, summary(&result.issue)))?;
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Prompt for a decision on `issue`; `None` when the user quits.
+fn ask<R: BufRead, W: Write>(prompter: &mut Prompter<R, W>, issue: &QueuedIssue, queue: &Queue) -> Result<Option<Decision>> {
+    let mut decision = Decision { id: issue.id.clone(), ..Decision::default() };
+    let action = prompter.ask("(t)riage, (s)kip or (q)uit", "t")?.to_lowercase();
+    if prompter.assuming_defaults() || action.starts_with('q') {
+        // Input ran out: stop rather than triage the rest with defaults
+        return Ok(None);
+    }
+    if action.starts_with('s') {
+        decision.skip = true;
+        return Ok(Some(decision));
+    }
+
+    let changed = |answer: String, current: &str| (answer != current).then_some(answer);
+    decision.priority = changed(prompter.ask("Priority 0-4", &issue.priority.to_string())?, &issue.priority.to_string())
+        .map(Value::from);
+    decision.issue_type = changed(prompter.ask("Type", issue.issue_type.as_str())?, issue.issue_type.as_str());
+    decision.labels = prompter
+        .ask("Labels (+add -remove)", "")?
+        .split([',', ' '])
+        .filter(|l| !l.is_empty())
+        .map(|l| l.strip_prefix('+').unwrap_or(l).to_string())
+        .collect();
+    let assignee = issue.assignee.as_deref().unwrap_or_default();
+    decision.assignee = changed(prompter.ask("Assignee (- to unassign)", assignee)?, assignee);
+    // Issues queued by status need a new one to leave the queue
+    if queue.status.is_some_and(|s| s == issue.status.as_str()) {
+        decision.status = Some(prompter.ask("Status", IssueStatus::Open.as_str())?);
+    }
+    Ok(Some(decision))
+}
+
+/// Apply one JSON decision per line of `input`.
+fn piped(storage: &mut SqliteStorage, input: impl BufRead, queue: &Queue, actor: &str, json: bool) -> Result<Vec<TriageResult>> {
+    let mut results = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let decision: Decision = serde_json::from_str(&line)
+            .map_err(|e| {
+                Error::InvalidArgument(format!(
+                    "Line {}: {e}. Valid values: a JSON object with `id` and any of priority, type, labels, assignee, status, skip",
+                    n + 1
+                ))
+            })?;
+        let result = apply(storage, &decision, queue.label, actor)?;
+        if !json && !crate::is_silent() {
+            println!("{}: {}", result.action, summary(&result.issue));
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Apply `decision`, returning the issue as it ends up. Under `--dry-run`
+/// the changes are rolled back after reading the result.
+fn apply(storage: &mut SqliteStorage, decision: &Decision, label: &str, actor: &str) -> Result<TriageResult> {
+    let issue = storage
+        .get_issue(&decision.id, None)?
+        .ok_or_else(|| Error::IssueNotFound { id: decision.id.clone() })?;
+    if decision.skip {
+        return Ok(TriageResult { action: "skipped".to_string(), issue: queued(storage, &issue)? });
+    }
+
+    let priority = decision
+        .priority
+        .as_ref()
+        .map(|p| {
+            let p = p.as_str().map_or_else(|| p.to_string(), ToString::to_string);
+            crate::validate::normalize_priority(&p)
+                .map_err(|(val, hint)| Error::InvalidArgument(hint.unwrap_or_else(|| format!("Invalid priority '{val}'"))))
+        })
+        .transpose()?;
+    let issue_type = decision
+        .issue_type
+        .as_deref()
+        .map(|t| {
+            crate::validate::normalize_type(t).map_err(|(val, suggestion)| {
+                Error::InvalidArgument(suggestion.map_or_else(
+                    || format!("Invalid issue type '{val}'. Valid: task, bug, feature, epic, chore"),
+                    |s| format!("Invalid issue type '{val}'. Did you mean '{s}'?"),
+                ))
+            })
+        })
+        .transpose()?
+        .map(|t| t.parse::<IssueType>())
+        .transpose()?;
+    let status = decision
+        .status
+        .as_deref()
+        .map(|s| crate::validate::normalize_status(s).unwrap_or_else(|_| s.to_string()).parse::<IssueStatus>())
+        .transpose()?;
+    let (removed, added): (Vec<String>, Vec<String>) =
+        decision.labels.iter().map(|l| l.trim().to_string()).filter(|l| !l.is_empty() && l != "-").partition(|l| l.starts_with('-'));
+    let mut removed: Vec<String> = removed.into_iter().map(|l| l[1..].to_string()).collect();
+    removed.push(label.to_string());
+
+    let dry_run = crate::is_dry_run();
+    let id = issue.id;
+    storage.with_transaction(|storage| {
+        let result = (|| {
+            if priority.is_some() || issue_type.is_some() {
+                storage.update_issue(&id, None, None, None, priority, issue_type, None, None, actor)?;
+            }
+            storage.remove_issue_labels(&id, &removed, actor)?;
+            if !added.is_empty() {
+                storage.add_issue_labels(&id, &added, actor)?;
+            }
+            if let Some(assignee) = decision.assignee.as_deref().map(str::trim) {
+                let assignee = Some(assignee).filter(|a| !a.is_empty() && *a != "-");
+                storage.assign_issue(&id, assignee, actor)?;
+            }
+            if let Some(status) = &status {
+                let workflow = workflow_for_issue(storage, &id);
+                storage.update_issue_status(&id, status, None, &workflow, actor)?;
+            }
+            let issue = storage.get_issue(&id, None)?.ok_or_else(|| Error::IssueNotFound { id: id.clone() })?;
+            Ok(TriageResult { action: "triaged".to_string(), issue: queued(storage, &issue)? })
+        })();
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })?
+}
+
+fn queued(storage: &SqliteStorage, issue: &Issue) -> Result<QueuedIssue> {
+    Ok(QueuedIssue {
+        id: issue.id.clone(),
+        short_id: issue.short_id.clone(),
+        title: issue.title.clone(),
+        description: issue.description.clone(),
+        status: issue.status.clone(),
+        priority: issue.priority,
+        issue_type: issue.issue_type,
+        assignee: issue.assigned_to_agent.clone(),
+        labels: storage.get_issue_labels(&issue.id)?,
+        created_at: issue.created_at,
+    })
+}
+
+/// One line: ID, title, then type, priority, status, assignee and labels.
+fn summary(issue: &QueuedIssue) -> String {
+    let id = issue.short_id.as_deref().unwrap_or(&issue.id);
+    let assignee = issue.assignee.as_deref().unwrap_or("unassigned");
+    let labels = if issue.labels.is_empty() { String::new() } else { format!(" [{}]", issue.labels.join(", ")) };
+    format!(
+        "{id} {} ({}, P{}, {}, {assignee}){labels}",
+        issue.title,
+        issue.issue_type,
+        issue.priority,
+        issue.status
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn issue() -> QueuedIssue {
+        QueuedIssue {
+            id: "issue_1".to_string(),
+            short_id: Some("SC-1".to_string()),
+            title: "Login fails".to_string(),
+            description: None,
+            status: IssueStatus::Open,
+            priority: 2,
+            issue_type: IssueType::Task,
+            assignee: Some("alice".to_string()),
+            labels: vec!["needs-triage".to_string()],
+            created_at: 0,
+        }
+    }
+
+    fn answers(input: &str, queue: &Queue) -> Option<Decision> {
+        let mut p = Prompter::new(Cursor::new(input.as_bytes().to_vec()), Vec::new(), false);
+        ask(&mut p, &issue(), queue).unwrap()
+    }
+
+    #[test]
+    fn test_ask_records_only_changes() {
+        let queue = Queue { label: "needs-triage", status: None, limit: 10 };
+        let decision = answers("\n3\nbug\n+ui, -old\n\n", &queue).unwrap();
+        assert_eq!(
+            decision,
+            Decision {
+                id: "issue_1".to_string(),
+                priority: Some(Value::from("3")),
+                issue_type: Some("bug".to_string()),
+                labels: vec!["ui".to_string(), "-old".to_string()],
+                ..Decision::default()
+            }
+        );
+
+        assert!(answers("s\n", &queue).unwrap().skip);
+        assert_eq!(answers("q\n", &queue), None);
+        // Running out of input stops instead of triaging with defaults
+        assert_eq!(answers("", &queue), None);
+
+        let by_status = Queue { label: "needs-triage", status: Some("open"), limit: 10 };
+        assert_eq!(answers("t\n\n\n\n-\n\n", &by_status).unwrap().status.as_deref(), Some("open"));
+        assert_eq!(answers("t\n\n\n\n-\n\n", &by_status).unwrap().assignee.as_deref(), Some("-"));
+    }
+
+    #[test]
+    fn test_decision_rejects_unknown_fields() {
+        let decision: Decision = serde_json::from_str(r#"{"id":"SC-1","priority":"P3","skip":false}"#).unwrap();
+        assert_eq!(decision.priority, Some(Value::from("P3")));
+        assert!(serde_json::from_str::<Decision>(r#"{"id":"SC-1","prio":3}"#).is_err());
+    }
+}
@@ -1,18 +1,23 @@
 //! Memory command implementations (project-level persistent storage).
 
-use crate::cli::MemoryCommands;
-use crate::config::{default_actor, resolve_db_path, resolve_project_path};
+use crate::cli::{Database, MemoryCommands, SubprojectArgs};
+use crate::config::resolve_subproject;
+use crate::core::SaveContext;
 use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::MemoryCategory;
 use crate::storage::SqliteStorage;
 use serde::Serialize;
-use std::path::PathBuf;
+use serde_json::Value;
+use tracing::warn;
 
 /// Output for memory save.
 #[derive(Serialize)]
 struct MemorySaveOutput {
     key: String,
-    category: String,
+    category: MemoryCategory,
     project_path: String,
+    subproject: Option<String>,
 }
 
 /// Output for memory get.
@@ -20,7 +25,7 @@ struct MemorySaveOutput {
 struct MemoryGetOutput {
     key: String,
     value: String,
-    category: String,
+    category: MemoryCategory,
 }
 
 /// Output for memory list.
@@ -34,13 +39,42 @@ struct MemoryListOutput {
 struct MemoryItem {
     key: String,
     value: String,
-    category: String,
+    category: MemoryCategory,
+    subproject: Option<String>,
+}
+
+/// Output for memory delete.
+#[derive(Serialize)]
+struct MemoryDeleteOutput {
+    key: String,
+    deleted: bool,
+}
+
+json_schema!(MemorySaveOutput {
+    key: String,
+    category: MemoryCategory,
+    project_path: String,
+    subproject: Option<String>,
+});
+json_schema!(MemoryGetOutput { key: String, value: String, category: MemoryCategory });
+json_schema!(MemoryListOutput { items: Vec<MemoryItem>, count: usize });
+json_schema!(MemoryItem { key: String, value: String, category: MemoryCategory, subproject: Option<String> });
+json_schema!(MemoryDeleteOutput { key: String, deleted: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("memory save", schema_for::<MemorySaveOutput>()),
+        ("memory get", schema_for::<MemoryGetOutput>()),
+        ("memory list", schema_for::<MemoryListOutput>()),
+        ("memory delete", schema_for::<MemoryDeleteOutput>()),
+    ]
 }
 
 /// Execute memory commands.
 pub fn execute(
     command: &MemoryCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
@@ -49,36 +83,57 @@ pub fn execute(
             key,
             value,
             category,
-        } => save(key, value, category, db_path, actor, json),
-        MemoryCommands::Get { key } => get(key, db_path, json),
-        MemoryCommands::List { category } => list(category.as_deref(), db_path, json),
-        MemoryCommands::Delete { key } => delete(key, db_path, actor, json),
+            input,
+            scope,
+        } => save(key, value.as_deref(), category, input.edit, scope, db, actor, json)
+            .inspect(|()| super::embeddings::spawn_background_embedder()),
+        MemoryCommands::Get { key } => get(key, db, json),
+        MemoryCommands::List { category } => list(category.as_deref(), db, json),
+        MemoryCommands::Delete { key } => delete(key, db, actor, json),
+        MemoryCommands::Audit { months, prune } => super::memory_audit::execute(*months, *prune, db, actor, json),
+        MemoryCommands::Harvest { from_history, from_readme, history_file, min_count, limit, yes } => {
+            let sources = super::memory_harvest::Sources {
+                history: *from_history,
+                readme: *from_readme,
+                history_file: history_file.as_deref(),
+                min_count: *min_count,
+                limit: *limit,
+            };
+            super::memory_harvest::execute(&sources, *yes, db, actor, json)
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn save(
     key: &str,
-    value: &str,
+    value: Option<&str>,
     category: &str,
-    db_path: Option<&PathBuf>,
+    edit: bool,
+    scope: &SubprojectArgs,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let project_path = resolve_project_path(&storage, None)?;
-
-    // Generate ID
-    let id = format!("mem_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+    let category: MemoryCategory = category.parse()?;
+    let mut sc = SaveContext::discover_in(db.storage()?, actor)?;
+    let subproject = resolve_subproject(sc.storage(), sc.project_path(), scope.subproject.as_deref(), scope.root)?;
+    sc.set_subproject(subproject);
+
+    // Filled from stdin/clipboard by `cli::input::resolve` unless editing
+    let edited;
+    let value = if edit {
+        let current = match value {
+            Some(value) => Some(value.to_string()),
+            None => sc.memory(key)?.map(|m| m.value),
+        };
+        edited = crate::cli::input::edit(current.as_deref().unwrap_or_default(), &format!("value for {key}"))?;
+        edited.as_str()
+    } else {
+        value.unwrap_or_default()
+    };
 
-    storage.save_memory(&id, &project_path, key, value, category, &actor)?;
+    let memory = sc.save_memory(key, value, category)?;
 
     if crate::is_silent() {
         println!("{key}");
@@ -87,32 +142,26 @@ fn save(
 
     if json {
         let output = MemorySaveOutput {
-            key: key.to_string(),
-            category: category.to_string(),
-            project_path,
+            key: memory.key,
+            category: memory.category,
+            project_path: memory.project_path,
+            subproject: memory.subproject,
         };
         println!("{}", serde_json::to_string(&output)?);
     } else {
-        println!("Saved memory: {key} [{category}]");
+        println!("Saved memory: {key} [{}]", tags(category, memory.subproject.as_deref()));
     }
 
     Ok(())
 }
 
-fn get(key: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+fn get(key: &str, db: &mut Database, json: bool) -> Result<()> {
+    let sc = SaveContext::discover_in(db.storage()?, None)?;
 
-    let memory = storage
-        .get_memory(&project_path, key)?
+    let memory = sc
+        .memory(key)?
         .ok_or_else(|| Error::Other(format!("Memory not found: {key}")))?;
+    record_reads(sc.storage(), std::slice::from_ref(&memory.id));
 
     if json {
         let output = MemoryGetOutput {
@@ -128,18 +177,11 @@ fn get(key: &str, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     Ok(())
 }
 
-fn list(category: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
+fn list(category: Option<&str>, db: &mut Database, json: bool) -> Result<()> {
+    let category: Option<MemoryCategory> = category.map(str::parse).transpose()?;
+    let sc = SaveContext::discover_in(db.storage()?, None)?;
 
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
-
-    let memories = storage.list_memory(&project_path, category)?;
+    let memories = sc.list_memory(category)?;
 
     if crate::is_csv() {
         println!("key,category,value");
@@ -152,7 +194,8 @@ fn list(category: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result
             .map(|m| MemoryItem {
                 key: m.key.clone(),
                 value: m.value.clone(),
-                category: m.category.clone(),
+                category: m.category,
+                subproject: m.subproject.clone(),
             })
             .collect();
         let output = MemoryListOutput {
@@ -166,13 +209,12 @@ fn list(category: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result
         println!("Memory items ({} found):", memories.len());
         println!();
         for mem in &memories {
-            let cat_icon = match mem.category.as_str() {
-                "command" => "$",
-                "config" => "⚙",
-                "note" => "📝",
-                _ => "•",
+            let cat_icon = match mem.category {
+                MemoryCategory::Command => "$",
+                MemoryCategory::Config => "⚙",
+                MemoryCategory::Note => "📝",
             };
-            println!("{} {} [{}]", cat_icon, mem.key, mem.category);
+            println!("{} {} [{}]", cat_icon, mem.key, tags(mem.category, mem.subproject.as_deref()));
             // Truncate long values
             let display_value = if mem.value.len() > 80 {
                 format!("{}...", &mem.value[..80])
@@ -187,26 +229,32 @@ fn list(category: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result
     Ok(())
 }
 
-fn delete(key: &str, db_path: Option<&PathBuf>, actor: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
+/// Count a read of the memory a command showed. Only `sc memory audit`
+/// depends on it, so a failure is logged rather than failing the command.
+pub(crate) fn record_reads(storage: &SqliteStorage, memory_ids: &[String]) {
+    if memory_ids.is_empty() || crate::is_dry_run() {
+        return;
     }
+    if let Err(e) = storage.record_memory_access(memory_ids) {
+        warn!(error = %e, "Failed to record memory reads");
+    }
+}
 
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let project_path = resolve_project_path(&storage, None)?;
+/// The category, and the sub-project if the memory belongs to one.
+fn tags(category: MemoryCategory, subproject: Option<&str>) -> String {
+    match subproject {
+        Some(subproject) => format!("{category}, {subproject}"),
+        None => category.to_string(),
+    }
+}
 
-    storage.delete_memory(&project_path, key, &actor)?;
+fn delete(key: &str, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let mut sc = SaveContext::discover_in(db.storage()?, actor)?;
+    sc.delete_memory(key)?;
 
     if json {
-        let output = serde_json::json!({
-            "key": key,
-            "deleted": true
-        });
-        println!("{output}");
+        let output = MemoryDeleteOutput { key: key.to_string(), deleted: true };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Deleted memory: {key}");
     }
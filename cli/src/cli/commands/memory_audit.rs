@@ -0,0 +1,511 @@
+//! Memory hygiene report (`sc memory audit`).
+//!
+//! Project memory outlives the sessions that wrote it, so it drifts: a
+//! `deploy` command for a script since deleted, the same test command saved
+//! as `test` and `run-tests`, notes nobody has looked at since spring. The
+//! audit flags three kinds of entry:
+//!
+//! - stale: not read (`sc memory get`, or shown by `sc prime`) nor saved in
+//!   the last `--months` months (3 by default)
+//! - duplicate: a value near-identical to another entry's; the entry read
+//!   most (then the oldest) is kept and the others are flagged
+//! - missing command: a `command` entry running an npm/yarn/pnpm/bun script
+//!   that package.json doesn't have, a make target the Makefile doesn't
+//!   have, or a script file that doesn't exist, checked against the
+//!   project's checkout (and the sub-project's directory, for tagged
+//!   entries)
+//!
+//! With `--prune`, each flagged entry is shown with why, and deleted if you
+//! answer yes. Run it now and then, say monthly, to keep prime lean.
+
+use crate::cli::commands::setup::Prompter;
+use crate::cli::Database;
+use crate::core::SaveContext;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemAccess, MemoryCategory};
+use crate::storage::Memory;
+use crate::validate::levenshtein_distance;
+use chrono::{Local, Months};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Longest value compared by edit distance; longer ones must match exactly
+/// once normalized.
+const MAX_FUZZY_CHARS: usize = 1000;
+
+/// Programs that run the script file given as their first argument.
+pub(super) const INTERPRETERS: &[&str] = &["bash", "sh", "zsh", "python", "python3", "node", "ruby", "perl"];
+
+/// Output for memory audit.
+#[derive(Serialize)]
+struct MemoryAuditOutput {
+    project_path: String,
+    /// Entries audited.
+    total: usize,
+    months: u32,
+    stale: Vec<StaleMemory>,
+    duplicates: Vec<DuplicateGroup>,
+    missing_commands: Vec<MissingCommand>,
+    /// Keys deleted by `--prune`.
+    pruned: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct StaleMemory {
+    key: String,
+    category: MemoryCategory,
+    reads: i64,
+    /// Unix ms; `None` if it was never read.
+    last_read_at: Option<i64>,
+    updated_at: i64,
+}
+
+#[derive(Serialize)]
+struct DuplicateGroup {
+    /// The entry kept: read most, then oldest.
+    keep: String,
+    duplicates: Vec<String>,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct MissingCommand {
+    key: String,
+    /// The part of the value that no longer works.
+    command: String,
+    reason: String,
+}
+
+json_schema!(MemoryAuditOutput {
+    project_path: String,
+    total: usize,
+    months: u32,
+    stale: Vec<StaleMemory>,
+    duplicates: Vec<DuplicateGroup>,
+    missing_commands: Vec<MissingCommand>,
+    pruned: Vec<String>,
+});
+json_schema!(StaleMemory {
+    key: String,
+    category: MemoryCategory,
+    reads: i64,
+    last_read_at: Option<i64>,
+    updated_at: i64,
+});
+json_schema!(DuplicateGroup { keep: String, duplicates: Vec<String>, value: String });
+json_schema!(MissingCommand { key: String, command: String, reason: String });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("memory audit", schema_for::<MemoryAuditOutput>())]
+}
+
+/// Execute memory audit.
+///
+/// # Errors
+///
+/// Returns an error if the project can't be resolved, `--prune` is used
+/// without a terminal, or a deletion fails.
+pub fn execute(months: u32, prune: bool, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let stdin = std::io::stdin();
+    if prune && !stdin.is_terminal() {
+        return Err(Error::InvalidArgument(
+            "--prune asks before deleting each entry; run it on a terminal".to_string(),
+        ));
+    }
+
+    let mut sc = SaveContext::discover_in(db.storage()?, actor)?;
+    let memories = sc.list_memory(None)?;
+    let ids: Vec<String> = memories.iter().map(|m| m.id.clone()).collect();
+    let access = sc.storage().get_memory_access(&ids)?;
+    let now = Local::now();
+    let cutoff = now.checked_sub_months(Months::new(months)).unwrap_or(now).timestamp_millis();
+
+    let mut output = MemoryAuditOutput {
+        project_path: sc.project_path().to_string(),
+        total: memories.len(),
+        months,
+        stale: stale(&memories, &access, cutoff),
+        duplicates: duplicates(&memories, &access),
+        missing_commands: missing_commands(&memories, Path::new(sc.project_path())),
+        pruned: Vec::new(),
+    };
+
+    if !json && !crate::is_silent() {
+        print_report(&output, !prune);
+    }
+    if prune {
+        let mut prompter = Prompter::new(stdin.lock(), std::io::stderr(), false);
+        output.pruned = prune_flagged(&mut sc, &mut prompter, &output)?;
+    }
+
+    if crate::is_silent() {
+        for (key, _) in flagged(&output) {
+            println!("{key}");
+        }
+    } else if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else if prune {
+        let verb = if crate::is_dry_run() { "Would delete" } else { "Deleted" };
+        println!("{verb} {} memory entr{}.", output.pruned.len(), if output.pruned.len() == 1 { "y" } else { "ies" });
+    }
+    Ok(())
+}
+
+/// Entries neither read nor saved since `cutoff` (Unix ms), least recently
+/// used first.
+fn stale(memories: &[Memory], access: &HashMap<String, ItemAccess>, cutoff: i64) -> Vec<StaleMemory> {
+    let mut stale: Vec<StaleMemory> = memories
+        .iter()
+        .filter_map(|m| {
+            let read = access.get(&m.id).copied().unwrap_or_default();
+            let last_used = read.last_accessed_at.unwrap_or_default().max(m.updated_at);
+            (last_used < cutoff).then(|| StaleMemory {
+                key: m.key.clone(),
+                category: m.category,
+                reads: read.count,
+                last_read_at: read.last_accessed_at,
+                updated_at: m.updated_at,
+            })
+        })
+        .collect();
+    stale.sort_by_key(|s| s.last_read_at.unwrap_or_default().max(s.updated_at));
+    stale
+}
+
+/// Groups of entries with near-identical values.
+fn duplicates(memories: &[Memory], access: &HashMap<String, ItemAccess>) -> Vec<DuplicateGroup> {
+    let normalized: Vec<String> = memories.iter().map(|m| normalize(&m.value)).collect();
+    let mut grouped = vec![false; memories.len()];
+    let mut groups = Vec::new();
+    for i in 0..memories.len() {
+        if grouped[i] || normalized[i].is_empty() {
+            continue;
+        }
+        let mut members = vec![i];
+        for j in i + 1..memories.len() {
+            if !grouped[j] && near_identical(&normalized[i], &normalized[j]) {
+                grouped[j] = true;
+                members.push(j);
+            }
+        }
+        if members.len() < 2 {
+            continue;
+        }
+        // Keep the one read most, then the oldest
+        members.sort_by_key(|&k| {
+            let reads = access.get(&memories[k].id).map_or(0, |a| a.count);
+            (std::cmp::Reverse(reads), memories[k].created_at)
+        });
+        let keep = &memories[members[0]];
+        groups.push(DuplicateGroup {
+            keep: keep.key.clone(),
+            duplicates: members[1..].iter().map(|&k| memories[k].key.clone()).collect(),
+            value: keep.value.clone(),
+        });
+    }
+    groups
+}
+
+/// Lowercase, with runs of whitespace as one space and trailing `;` and
+/// `.` dropped.
+pub(super) fn normalize(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase().trim_end_matches([';', '.']).to_string()
+}
+
+/// Equal, or apart by at most one edit in ten characters.
+fn near_identical(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let (len_a, len_b) = (a.chars().count(), b.chars().count());
+    let longest = len_a.max(len_b);
+    if !(8..=MAX_FUZZY_CHARS).contains(&longest) {
+        return false;
+    }
+    let allowed = longest / 10;
+    len_a.abs_diff(len_b) <= allowed && levenshtein_distance(a, b) <= allowed
+}
+
+/// `command` entries that run something the project no longer has. Skipped
+/// when the project isn't checked out here.
+fn missing_commands(memories: &[Memory], project_root: &Path) -> Vec<MissingCommand> {
+    if !project_root.is_dir() {
+        return Vec::new();
+    }
+    let mut repo = Repo::default();
+    memories
+        .iter()
+        .filter(|m| m.category == MemoryCategory::Command)
+        .filter_map(|m| {
+            let base = m.subproject.as_deref().map_or_else(|| project_root.to_path_buf(), |s| project_root.join(s));
+            let (command, reason) = check_command(&m.value, &base, &mut repo)?;
+            Some(MissingCommand { key: m.key.clone(), command, reason })
+        })
+        .collect()
+}
+
+/// The first part of a shell command line that no longer works, and why.
+/// Parts it can't judge pass.
+pub(super) fn check_command(value: &str, base: &Path, repo: &mut Repo) -> Option<(String, String)> {
+    let mut dir = base.to_path_buf();
+    for segment in segments(value) {
+        let words = words(segment);
+        let missing = match words.as_slice() {
+            ["cd", target, ..] => {
+                if target.starts_with(['~', '$', '/']) {
+                    // Outside the project: nothing to check from here on
+                    return None;
+                }
+                dir = dir.join(target);
+                (!dir.is_dir()).then(|| format!("directory {target} does not exist"))
+            }
+            ["npm", "run" | "run-script", script, ..]
+            | ["yarn" | "pnpm" | "bun", "run", script, ..] => repo.missing_script(&dir, script),
+            ["npm", "test" | "t"] => repo.missing_script(&dir, "test"),
+            ["yarn", script, ..] if !script.starts_with('-') && !YARN_BUILTINS.contains(script) => {
+                repo.missing_script(&dir, script)
+            }
+            ["make", rest @ ..] => {
+                if rest.iter().any(|w| w.starts_with("-C") || *w == "-f" || *w == "--directory") {
+                    None
+                } else {
+                    repo.missing_target(&dir, rest.iter().find(|w| !w.starts_with('-') && !w.contains('=')).copied())
+                }
+            }
+            [interpreter, script, ..] if INTERPRETERS.contains(interpreter) && is_script_path(script) => {
+                missing_file(&dir, script)
+            }
+            [program, ..] if program.starts_with("./") || program.starts_with("scripts/") => missing_file(&dir, program),
+            _ => None,
+        };
+        if let Some(reason) = missing {
+            return Some((segment.to_string(), reason));
+        }
+    }
+    None
+}
+
+/// The commands of a shell command line, split at `&&`, `||`, `;`, `|`
+/// and newlines.
+pub(super) fn segments(value: &str) -> impl Iterator<Item = &str> {
+    value.split(['\n', ';', '|', '&']).map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// The words of one command, unquoted, without leading `VAR=value`
+/// assignments.
+pub(super) fn words(segment: &str) -> Vec<&str> {
+    segment
+        .split_whitespace()
+        .map(|w| w.trim_matches(['"', '\'']))
+        .skip_while(|w| w.contains('=') && !w.starts_with('-'))
+        .collect()
+}
+
+/// Yarn subcommands that aren't package scripts.
+pub(super) const YARN_BUILTINS: &[&str] = &[
+    "add", "install", "remove", "upgrade", "up", "init", "info", "why", "workspace", "workspaces", "dlx", "exec",
+    "global", "cache", "config", "link", "unlink", "outdated", "audit", "set", "node", "bin", "pack", "publish",
+    "version", "create",
+];
+
+/// Whether `word` names a script file rather than a flag or inline code.
+pub(super) fn is_script_path(word: &str) -> bool {
+    !word.starts_with('-')
+        && (word.contains('/')
+            || [".sh", ".py", ".js", ".mjs", ".ts", ".rb", ".pl"].iter().any(|ext| word.ends_with(ext)))
+}
+
+fn missing_file(dir: &Path, path: &str) -> Option<String> {
+    (!dir.join(path).exists()).then(|| format!("{path} does not exist"))
+}
+
+/// The project's package.json scripts and Makefile targets, by directory,
+/// read once each.
+#[derive(Default)]
+pub(super) struct Repo {
+    scripts: HashMap<PathBuf, Option<HashSet<String>>>,
+    targets: HashMap<PathBuf, Option<HashSet<String>>>,
+}
+
+impl Repo {
+    pub(super) fn missing_script(&mut self, dir: &Path, script: &str) -> Option<String> {
+        let scripts = self.scripts.entry(dir.to_path_buf()).or_insert_with(|| package_scripts(dir));
+        match scripts {
+            None => Some(format!("no package.json for script \"{script}\"")),
+            Some(scripts) if !scripts.contains(script) => Some(format!("no \"{script}\" script in package.json")),
+            Some(_) => None,
+        }
+    }
+
+    pub(super) fn missing_target(&mut self, dir: &Path, target: Option<&str>) -> Option<String> {
+        let targets = self.targets.entry(dir.to_path_buf()).or_insert_with(|| makefile_targets(dir));
+        match (targets, target) {
+            (None, _) => Some("no Makefile".to_string()),
+            (Some(targets), Some(target)) if !targets.contains(target) => {
+                Some(format!("no \"{target}\" target in the Makefile"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Script names in `dir/package.json`, or `None` without a readable one.
+fn package_scripts(dir: &Path) -> Option<HashSet<String>> {
+    let content = fs::read_to_string(dir.join("package.json")).ok()?;
+    let manifest: Value = serde_json::from_str(&content).ok()?;
+    Some(manifest.get("scripts").and_then(Value::as_object).map(|s| s.keys().cloned().collect()).unwrap_or_default())
+}
+
+/// Targets defined in `dir`'s Makefile, or `None` without one.
+fn makefile_targets(dir: &Path) -> Option<HashSet<String>> {
+    let content = ["GNUmakefile", "makefile", "Makefile"].iter().find_map(|f| fs::read_to_string(dir.join(f)).ok())?;
+    Some(parse_targets(&content))
+}
+
+fn parse_targets(makefile: &str) -> HashSet<String> {
+    makefile
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#']))
+        .filter_map(|line| {
+            let (targets, rest) = line.split_once(':')?;
+            // `VAR := value` and `VAR ::= value` are assignments
+            (!rest.starts_with('=') && !rest.starts_with(":=") && !targets.contains('=')).then_some(targets)
+        })
+        .flat_map(str::split_whitespace)
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Every flagged key with why, in report order.
+fn flagged(output: &MemoryAuditOutput) -> Vec<(&str, Vec<String>)> {
+    fn flag<'a>(flagged: &mut Vec<(&'a str, Vec<String>)>, key: &'a str, reason: String) {
+        match flagged.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, reasons)) => reasons.push(reason),
+            None => flagged.push((key, vec![reason])),
+        }
+    }
+
+    let mut flagged = Vec::new();
+    for missing in &output.missing_commands {
+        flag(&mut flagged, &missing.key, format!("{}: {}", missing.command, missing.reason));
+    }
+    for group in &output.duplicates {
+        for key in &group.duplicates {
+            flag(&mut flagged, key, format!("same value as {}", group.keep));
+        }
+    }
+    for stale in &output.stale {
+        flag(&mut flagged, &stale.key, format!("last used {}", last_used(stale)));
+    }
+    flagged
+}
+
+/// Ask about each flagged entry; returns the keys deleted.
+fn prune_flagged<R: BufRead, W: Write>(
+    sc: &mut SaveContext,
+    prompter: &mut Prompter<R, W>,
+    output: &MemoryAuditOutput,
+) -> Result<Vec<String>> {
+    let mut pruned = Vec::new();
+    for (key, reasons) in flagged(output) {
+        prompter.say("")?;
+        prompter.say(&format!("{key} ({})", reasons.join("; ")))?;
+        if !prompter.confirm(&format!("Delete {key}?"), false)? {
+            continue;
+        }
+        if !crate::is_dry_run() {
+            sc.delete_memory(key)?;
+        }
+        pruned.push(key.to_string());
+    }
+    Ok(pruned)
+}
+
+fn last_used(stale: &StaleMemory) -> String {
+    let (at, what) = match stale.last_read_at {
+        Some(read) if read >= stale.updated_at => (read, "read"),
+        _ => (stale.updated_at, "saved"),
+    };
+    let date = chrono::DateTime::from_timestamp_millis(at)
+        .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    format!("{date} ({what}, {} read{})", stale.reads, if stale.reads == 1 { "" } else { "s" })
+}
+
+fn print_report(output: &MemoryAuditOutput, hint: bool) {
+    println!("Memory audit of {} ({} entries)", output.project_path, output.total);
+
+    println!();
+    if output.missing_commands.is_empty() {
+        println!("{} No commands missing from the repo", "✓".green());
+    } else {
+        println!("{}", format!("Missing commands ({})", output.missing_commands.len()).red().bold());
+        for missing in &output.missing_commands {
+            println!("  {}: {}", missing.key.bold(), missing.reason);
+            println!("    {}", missing.command.dimmed());
+        }
+    }
+
+    println!();
+    if output.duplicates.is_empty() {
+        println!("{} No duplicate values", "✓".green());
+    } else {
+        println!("{}", format!("Duplicates ({})", output.duplicates.len()).yellow().bold());
+        for group in &output.duplicates {
+            println!("  {} kept; also saved as {}", group.keep.bold(), group.duplicates.join(", "));
+            let value: String = group.value.lines().next().unwrap_or_default().chars().take(80).collect();
+            println!("    {}", value.dimmed());
+        }
+    }
+
+    println!();
+    if output.stale.is_empty() {
+        println!("{} Nothing unused for {} months", "✓".green(), output.months);
+    } else {
+        println!("{}", format!("Unused for {} months ({})", output.months, output.stale.len()).yellow().bold());
+        for stale in &output.stale {
+            println!("  {} [{}] last used {}", stale.key.bold(), stale.category, last_used(stale));
+        }
+    }
+
+    let flagged = output.missing_commands.len() + output.duplicates.len() + output.stale.len();
+    if flagged > 0 && hint {
+        println!();
+        println!("Clean up with `sc memory audit --prune`, which asks before deleting each entry.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicates_and_commands() {
+        assert!(near_identical(&normalize("cargo test --workspace"), &normalize("Cargo  test --workspace;")));
+        assert!(near_identical(&normalize("cargo test --workspace"), &normalize("cargo test --workspaces")));
+        assert!(!near_identical(&normalize("npm run build"), &normalize("npm run lint")));
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"scripts": {"build": "tsc", "test": "vitest"}}"#).unwrap();
+        fs::write(dir.path().join("Makefile"), "VERSION := 1\n.PHONY: all test\nall test: deps\n\tcargo test\n").unwrap();
+        fs::create_dir(dir.path().join("scripts")).unwrap();
+        fs::write(dir.path().join("scripts/release.sh"), "").unwrap();
+        let mut repo = Repo::default();
+        let mut check = |value: &str| check_command(value, dir.path(), &mut repo).map(|(_, reason)| reason);
+
+        for ok in ["npm run build", "yarn test", "make", "make test", "bash scripts/release.sh", "FOO=1 npm test", "cargo fmt"] {
+            assert_eq!(check(ok), None, "{ok}");
+        }
+        assert_eq!(check("npm run build && npm run deploy").as_deref(), Some("no \"deploy\" script in package.json"));
+        assert_eq!(check("make -j4 release").as_deref(), Some("no \"release\" target in the Makefile"));
+        assert_eq!(check("./scripts/deploy.sh prod").as_deref(), Some("./scripts/deploy.sh does not exist"));
+        assert_eq!(check("cd web && pnpm run dev").as_deref(), Some("directory web does not exist"));
+    }
+}
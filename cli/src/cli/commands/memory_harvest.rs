@@ -0,0 +1,484 @@
+//! Memory from shell history and project docs (`sc memory harvest`).
+//!
+//! Filling project memory by hand means remembering which commands matter.
+//! Harvest reads them from where they already are: the shell history
+//! (`$HISTFILE`, else bash, zsh and fish history) and the shell blocks of
+//! the project's README, CONTRIBUTING, DEVELOPMENT and docs/ markdown.
+//!
+//! A command is proposed when it runs something this project has: an
+//! npm/yarn/pnpm/bun script in package.json, a make or just target, a
+//! cargo, go, maven, gradle or compose verb next to its manifest, pytest in
+//! a Python project, or one of the project's script files. History
+//! commands must have been run `--min-count` times (2). Commands already in
+//! memory are left out, and each proposal is keyed after its script,
+//! target or verb.
+//!
+//! Each proposal is confirmed on the terminal, or all are saved with
+//! `--yes`; `--dry-run` only lists them.
+
+use super::memory_audit::{check_command, is_script_path, normalize, segments, words, Repo, INTERPRETERS, YARN_BUILTINS};
+use crate::cli::commands::setup::Prompter;
+use crate::cli::Database;
+use crate::core::SaveContext;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::MemoryCategory;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Subcommands of build tools worth remembering.
+const CARGO_VERBS: &[&str] = &["build", "test", "check", "clippy", "fmt", "run", "bench", "doc", "nextest", "publish"];
+const GO_VERBS: &[&str] = &["build", "test", "vet", "run", "generate", "install"];
+const JVM_VERBS: &[&str] = &["build", "test", "check", "package", "install", "verify", "clean", "run", "bootRun", "deploy"];
+const COMPOSE_VERBS: &[&str] = &["up", "down", "build", "logs", "run", "exec", "restart", "ps"];
+
+/// Files whose shell blocks are read, at the project root.
+const DOC_PREFIXES: &[&str] = &["readme", "contributing", "development", "hacking"];
+
+/// Output for memory harvest.
+#[derive(Serialize)]
+struct HarvestOutput {
+    proposals: Vec<Proposal>,
+    /// Keys saved.
+    saved: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct Proposal {
+    key: String,
+    command: String,
+    /// `history`, or the doc it was found in.
+    sources: Vec<String>,
+    /// Times it was run, per the history.
+    count: usize,
+}
+
+json_schema!(HarvestOutput { proposals: Vec<Proposal>, saved: Vec<String> });
+json_schema!(Proposal { key: String, command: String, sources: Vec<String>, count: usize });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("memory harvest", schema_for::<HarvestOutput>())]
+}
+
+/// What `sc memory harvest` was asked to read.
+pub struct Sources<'a> {
+    pub history: bool,
+    pub readme: bool,
+    /// History file read instead of the shell's.
+    pub history_file: Option<&'a Path>,
+    pub min_count: usize,
+    pub limit: usize,
+}
+
+/// Execute memory harvest.
+///
+/// # Errors
+///
+/// Returns an error if the project isn't checked out here, confirmation is
+/// needed without a terminal, or saving fails.
+pub fn execute(sources: &Sources, yes: bool, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let stdin = std::io::stdin();
+    let dry_run = crate::is_dry_run();
+    if !yes && !dry_run && !stdin.is_terminal() {
+        return Err(Error::InvalidArgument(
+            "memory harvest asks before saving each command; pass --yes or run it on a terminal".to_string(),
+        ));
+    }
+
+    let mut sc = SaveContext::discover_in(db.storage()?, actor)?;
+    let root = PathBuf::from(sc.project_path());
+    if !root.is_dir() {
+        return Err(Error::InvalidArgument(format!("Project directory {} is not on this machine", root.display())));
+    }
+
+    // Both sources unless one is named
+    let (history, readme) = if sources.history || sources.readme { (sources.history, sources.readme) } else { (true, true) };
+    let mut found: Vec<(String, String)> = Vec::new();
+    if history {
+        let commands = history_commands(sources.history_file)?;
+        found.extend(commands.into_iter().map(|c| (c, "history".to_string())));
+    }
+    if readme {
+        found.extend(doc_commands(&root));
+    }
+
+    let existing = sc.list_memory(None)?;
+    let proposals = propose(&found, &root, &existing, sources.min_count, sources.limit);
+
+    let mut saved = Vec::new();
+    if !dry_run && !proposals.is_empty() {
+        let mut prompter = Prompter::new(stdin.lock(), std::io::stderr(), yes);
+        for proposal in &proposals {
+            if confirm(&mut prompter, proposal, yes)? {
+                sc.save_memory(&proposal.key, &proposal.command, MemoryCategory::Command)?;
+                saved.push(proposal.key.clone());
+            }
+        }
+    }
+
+    if crate::is_silent() {
+        for key in &saved {
+            println!("{key}");
+        }
+    } else if json {
+        println!("{}", serde_json::to_string(&HarvestOutput { proposals, saved })?);
+    } else if proposals.is_empty() {
+        println!("No new commands found.");
+    } else if dry_run {
+        println!("Would propose {} command{}:", proposals.len(), if proposals.len() == 1 { "" } else { "s" });
+        for proposal in &proposals {
+            println!("  {}: {}  ({})", proposal.key, proposal.command, describe(proposal));
+        }
+    } else {
+        println!("Saved {} of {} proposed commands to memory.", saved.len(), proposals.len());
+    }
+    Ok(())
+}
+
+fn confirm<R: BufRead, W: Write>(prompter: &mut Prompter<R, W>, proposal: &Proposal, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    prompter.say("")?;
+    prompter.say(&format!("{}  ({})", proposal.command, describe(proposal)))?;
+    prompter.confirm(&format!("Save as memory \"{}\"?", proposal.key), true)
+}
+
+fn describe(proposal: &Proposal) -> String {
+    proposal
+        .sources
+        .iter()
+        .map(|source| {
+            if source == "history" {
+                format!("run {}× per history", proposal.count)
+            } else {
+                format!("in {source}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The commands worth proposing, most run first, with what found them.
+fn propose(
+    found: &[(String, String)],
+    root: &Path,
+    existing: &[crate::storage::Memory],
+    min_count: usize,
+    limit: usize,
+) -> Vec<Proposal> {
+    let known: HashSet<String> = existing.iter().map(|m| normalize(&m.value)).collect();
+    let mut taken: HashSet<String> = existing.iter().map(|m| m.key.clone()).collect();
+    let mut repo = Repo::default();
+
+    // Group by normalized command, keeping the first spelling seen
+    let mut order: Vec<String> = Vec::new();
+    let mut grouped: HashMap<String, (String, Vec<String>, usize, String)> = HashMap::new();
+    for (command, source) in found {
+        let normalized = normalize(command);
+        if known.contains(&normalized) {
+            continue;
+        }
+        if !grouped.contains_key(&normalized) {
+            let Some(key) = project_command(command, root, &mut repo) else {
+                continue;
+            };
+            order.push(normalized.clone());
+            grouped.insert(normalized.clone(), (command.clone(), Vec::new(), 0, key));
+        }
+        let Some((_, sources, count, _)) = grouped.get_mut(&normalized) else {
+            continue;
+        };
+        if source == "history" {
+            *count += 1;
+        }
+        if !sources.contains(source) {
+            sources.push(source.clone());
+        }
+    }
+
+    let mut candidates: Vec<(String, Vec<String>, usize, String)> = order
+        .into_iter()
+        .filter_map(|n| grouped.remove(&n))
+        .filter(|(_, sources, count, _)| sources.iter().any(|s| s != "history") || *count >= min_count)
+        .collect();
+    // Stable: ties keep history order, then docs order
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.2));
+    candidates.truncate(limit);
+
+    candidates
+        .into_iter()
+        .map(|(command, sources, count, key)| {
+            let key = unique_key(&key, &command, &mut taken);
+            Proposal { key, command, sources, count }
+        })
+        .collect()
+}
+
+/// `key`, else the program and `key`, else that with a number, whichever
+/// is free first.
+fn unique_key(key: &str, command: &str, taken: &mut HashSet<String>) -> String {
+    let program = segments(command)
+        .filter_map(|segment| words(segment).first().copied())
+        .filter(|w| *w != "cd")
+        .last()
+        .map(|w| sanitize_key(w.trim_start_matches("./")))
+        .unwrap_or_default();
+    let prefixed = format!("{program}-{key}");
+    let mut candidates = vec![key.to_string()];
+    if !program.is_empty() && program != key {
+        candidates.push(prefixed.clone());
+    }
+    let chosen = candidates
+        .into_iter()
+        .find(|k| !taken.contains(k))
+        .unwrap_or_else(|| {
+            let mut n = 2;
+            while taken.contains(&format!("{prefixed}-{n}")) {
+                n += 1;
+            }
+            format!("{prefixed}-{n}")
+        });
+    taken.insert(chosen.clone());
+    chosen
+}
+
+/// The key for a command line that runs something this project has, or
+/// `None` if it doesn't (or is broken here).
+fn project_command(line: &str, root: &Path, repo: &mut Repo) -> Option<String> {
+    if check_command(line, root, repo).is_some() {
+        return None;
+    }
+    let mut dir = root.to_path_buf();
+    let mut key = None;
+    for segment in segments(line) {
+        match words(segment).as_slice() {
+            ["cd", target, ..] => {
+                if target.starts_with(['~', '$', '/']) {
+                    return None;
+                }
+                dir = dir.join(target);
+            }
+            words => key = key_for(words, &dir, repo).or(key),
+        }
+    }
+    key.map(|k| sanitize_key(&k))
+}
+
+/// What a single command runs, if it is something of the project in `dir`.
+fn key_for(words: &[&str], dir: &Path, repo: &mut Repo) -> Option<String> {
+    let has = |file: &str| dir.join(file).exists();
+    let verb = |verbs: &[&str], verb: &str| verbs.contains(&verb).then(|| verb.to_string());
+    match words {
+        ["npm", "run" | "run-script", script, ..] | ["yarn" | "pnpm" | "bun", "run", script, ..] => {
+            repo.missing_script(dir, script).is_none().then(|| (*script).to_string())
+        }
+        ["npm" | "pnpm", "test" | "t", ..] => repo.missing_script(dir, "test").is_none().then(|| "test".to_string()),
+        ["yarn", script, ..] if !script.starts_with('-') && !YARN_BUILTINS.contains(script) => {
+            repo.missing_script(dir, script).is_none().then(|| (*script).to_string())
+        }
+        ["make", rest @ ..] if !rest.iter().any(|w| w.starts_with("-C") || *w == "-f" || *w == "--directory") => {
+            let target = rest.iter().find(|w| !w.starts_with('-') && !w.contains('=')).copied();
+            repo.missing_target(dir, target).is_none().then(|| target.unwrap_or("make").to_string())
+        }
+        ["just", recipe, ..] if (has("justfile") || has("Justfile")) && !recipe.starts_with('-') => {
+            Some((*recipe).to_string())
+        }
+        ["cargo", v, ..] if has("Cargo.toml") => verb(CARGO_VERBS, v),
+        ["go", v, ..] if has("go.mod") => verb(GO_VERBS, v),
+        ["mvn" | "./mvnw", v, ..] if has("pom.xml") => verb(JVM_VERBS, v),
+        ["gradle" | "./gradlew", v, ..] if has("build.gradle") || has("build.gradle.kts") => verb(JVM_VERBS, v),
+        ["docker", "compose", v, ..] | ["docker-compose", v, ..]
+            if ["compose.yaml", "compose.yml", "docker-compose.yml", "docker-compose.yaml"].iter().any(|f| has(f)) =>
+        {
+            verb(COMPOSE_VERBS, v).map(|v| format!("compose-{v}"))
+        }
+        ["pytest", ..] | ["python" | "python3", "-m", "pytest", ..]
+            if ["pyproject.toml", "setup.py", "setup.cfg", "pytest.ini", "tox.ini"].iter().any(|f| has(f)) =>
+        {
+            Some("test".to_string())
+        }
+        [interpreter, script, ..] if INTERPRETERS.contains(interpreter) && is_script_path(script) && has(script) => {
+            script_name(script)
+        }
+        [program, ..] if (program.starts_with("./") || program.starts_with("scripts/")) && has(program) => {
+            script_name(program)
+        }
+        _ => None,
+    }
+}
+
+fn script_name(path: &str) -> Option<String> {
+    Path::new(path).file_stem().map(|s| s.to_string_lossy().to_string())
+}
+
+/// Lowercase letters, digits, `-` and `_`; anything else becomes `-`.
+fn sanitize_key(raw: &str) -> String {
+    let key: String = raw
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '-' })
+        .collect();
+    key.trim_matches('-').to_string()
+}
+
+/// Commands from `file`, or from `$HISTFILE`, else every bash, zsh and
+/// fish history there is.
+fn history_commands(file: Option<&Path>) -> Result<Vec<String>> {
+    if let Some(file) = file {
+        let content = fs::read(file)?;
+        return Ok(parse_history(&String::from_utf8_lossy(&content)));
+    }
+    let files: Vec<PathBuf> = match std::env::var_os("HISTFILE") {
+        Some(histfile) => vec![PathBuf::from(histfile)],
+        None => directories::BaseDirs::new()
+            .map(|b| {
+                let home = b.home_dir();
+                vec![home.join(".bash_history"), home.join(".zsh_history"), home.join(".local/share/fish/fish_history")]
+            })
+            .unwrap_or_default(),
+    };
+    // Unreadable history is skipped: it's one source among several
+    Ok(files
+        .iter()
+        .filter_map(|f| fs::read(f).ok())
+        .flat_map(|content| parse_history(&String::from_utf8_lossy(&content)))
+        .collect())
+}
+
+/// One command per entry of a bash, zsh (plain or extended) or fish
+/// history.
+fn parse_history(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end();
+            // zsh extended history: `: 1700000000:0;command`
+            if let Some(rest) = line.strip_prefix(": ") {
+                return rest.split_once(';').map(|(_, command)| command.to_string());
+            }
+            // fish: `- cmd: command`, followed by `  when:` lines
+            if let Some(command) = line.strip_prefix("- cmd: ") {
+                return Some(command.replace("\\n", "\n").replace("\\\\", "\\"));
+            }
+            // bash timestamps (`#1700000000`) and fish metadata
+            if line.starts_with('#') || line.starts_with("  ") {
+                return None;
+            }
+            Some(line.to_string())
+        })
+        .filter(|command| !command.trim().is_empty())
+        .collect()
+}
+
+/// Commands in the shell blocks of the project's docs, with the doc each
+/// came from.
+fn doc_commands(root: &Path) -> Vec<(String, String)> {
+    let mut docs: Vec<PathBuf> = Vec::new();
+    for dir in [root.to_path_buf(), root.join("docs")] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                let name = p.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+                let markdown = p.extension().is_some_and(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"));
+                p.is_file() && markdown && (dir != root || DOC_PREFIXES.iter().any(|prefix| name.starts_with(prefix)))
+            })
+            .collect();
+        files.sort();
+        docs.extend(files);
+    }
+
+    docs.iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(path).ok()?;
+            let name = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+            Some(shell_blocks(&content).into_iter().map(move |command| (command, name.clone())))
+        })
+        .flatten()
+        .collect()
+}
+
+/// The command lines of fenced shell blocks, without prompts and comments.
+/// In `console` blocks, lines without a prompt are output and skipped.
+fn shell_blocks(markdown: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    // Inside a block: whether it is a shell one, and whether it has prompts
+    let mut block: Option<(bool, bool)> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            block = if block.is_some() {
+                None
+            } else {
+                let lang = info.trim().to_lowercase();
+                let shell = matches!(lang.as_str(), "" | "bash" | "sh" | "shell" | "zsh" | "console" | "shell-session");
+                Some((shell, matches!(lang.as_str(), "console" | "shell-session")))
+            };
+            continue;
+        }
+        let Some((true, prompted)) = block else {
+            continue;
+        };
+        let command = match trimmed.strip_prefix("$ ").or_else(|| trimmed.strip_prefix("% ")) {
+            Some(command) => command,
+            None if prompted => continue,
+            None => trimmed,
+        };
+        // Drop trailing comments (`make test   # runs everything`)
+        let command = command.split(" #").next().unwrap_or_default().trim();
+        if !command.is_empty() && !command.starts_with('#') && !command.ends_with('\\') {
+            commands.push(command.to_string());
+        }
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harvest_proposals() {
+        let history = "#1700000000\nnpm run build\n: 1700000001:0;npm run build\n- cmd: cargo test\n  when: 1700000002\nls -la\nnpm run lint\nnpm run lint\n";
+        assert_eq!(
+            parse_history(history),
+            ["npm run build", "npm run build", "cargo test", "ls -la", "npm run lint", "npm run lint"]
+        );
+        let readme = "Run:\n\n```bash\n# build it\nmake test  # everything\n```\n\n```console\n$ npm run build\nbuilt in 2s\n```\n\n```rust\nfn main() {}\n```\n";
+        assert_eq!(shell_blocks(readme), ["make test", "npm run build"]);
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"scripts": {"build": "tsc", "lint": "eslint ."}}"#).unwrap();
+        fs::write(dir.path().join("Makefile"), "test:\n\tnpm test\n").unwrap();
+        let found: Vec<(String, String)> = parse_history(history)
+            .into_iter()
+            .map(|c| (c, "history".to_string()))
+            .chain(shell_blocks(readme).into_iter().map(|c| (c, "README.md".to_string())))
+            .collect();
+        let existing = vec![crate::storage::Memory {
+            id: "mem_1".to_string(),
+            project_path: "/repo".to_string(),
+            key: "lint".to_string(),
+            value: "eslint src".to_string(),
+            category: MemoryCategory::Command,
+            created_at: 0,
+            updated_at: 0,
+            subproject: None,
+        }];
+
+        let proposals = propose(&found, dir.path(), &existing, 2, 10);
+        let summary: Vec<(&str, &str, usize)> =
+            proposals.iter().map(|p| (p.key.as_str(), p.command.as_str(), p.count)).collect();
+        // cargo test: no Cargo.toml; ls: not a project command; lint: key taken
+        assert_eq!(summary, [("build", "npm run build", 2), ("npm-lint", "npm run lint", 2), ("test", "make test", 0)]);
+        assert_eq!(proposals[0].sources, ["history", "README.md"]);
+    }
+}
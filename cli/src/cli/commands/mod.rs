@@ -1,21 +1,58 @@
 //! Command implementations.
 
+pub mod actor;
+pub mod apply;
+pub mod archive;
+pub mod auto_checkpoint;
+pub mod bench;
+pub mod capabilities;
+pub mod category;
 pub mod checkpoint;
 pub mod compaction;
 pub mod completions;
 pub mod config;
 pub mod context;
+pub mod critical_path;
+pub mod dashboard;
+pub mod db;
+pub mod edit_context;
 pub mod embeddings;
+pub mod events;
+pub mod git;
+pub mod graph;
+pub mod grep;
+pub mod inbox;
 pub mod init;
 pub mod issue;
+pub mod issue_triage;
 pub mod memory;
+pub mod memory_audit;
+pub mod memory_harvest;
 pub mod plan;
+pub mod policy;
 pub mod prime;
 pub mod project;
 pub mod remote;
+pub mod remind;
+pub mod report;
+pub mod review;
+pub mod sanitize;
+pub mod schema;
+pub mod search;
+pub mod serve;
+pub mod seed;
 pub mod session;
+pub mod session_summary;
+pub mod session_usage;
+pub mod setup;
+pub mod share;
 pub mod skills;
+pub mod stats;
 pub mod status;
 pub mod sync;
+pub mod team;
 pub mod time_entry;
+pub mod trash;
+pub mod vault;
 pub mod version;
+pub mod watch;
@@ -5,14 +5,22 @@
 //! - `sc plan list` - List plans
 //! - `sc plan show <id>` - Show plan details
 //! - `sc plan update <id>` - Update plan settings
+//! - `sc plan history <id>` - List content revisions
+//! - `sc plan diff <id> --rev 3..5` - Unified diff between revisions
+//! - `sc plan revert <id> --rev 3` - Restore an earlier revision
+//! - `sc plan capture [path]` - Import a markdown file or agent plan file
+//! - `sc plan refresh [id]` - Re-sync captured plans from their source files
 
-use crate::cli::{PlanCommands, PlanCreateArgs, PlanUpdateArgs};
+use crate::cli::{Database, PlanCommands, PlanCreateArgs, PlanUpdateArgs};
 use crate::config::plan_discovery::{self, AgentKind};
-use crate::config::{default_actor, resolve_db_path, resolve_project_path, resolve_session_id};
+use crate::config::{default_actor, resolve_project_path, resolve_session_id};
 use crate::error::{Error, Result};
-use crate::model::{Plan, PlanStatus};
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::model::{Plan, PlanRevision, PlanStatus};
 use crate::storage::SqliteStorage;
 use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -106,6 +114,154 @@ struct PlanListOutput {
     count: usize,
 }
 
+json_schema!(PlanOutput {
+    id: String,
+    short_id: Option<String>,
+    project_path: String,
+    title: String,
+    status: String,
+    session_id: Option<String>,
+    content_preview: Option<String>,
+    success_criteria: Option<String>,
+    created_at: String,
+    updated_at: String,
+    completed_at: Option<String>,
+});
+json_schema!(PlanDetailOutput {
+    id: String,
+    short_id: Option<String>,
+    project_path: String,
+    title: String,
+    status: String,
+    session_id: Option<String>,
+    content: Option<String>,
+    success_criteria: Option<String>,
+    created_in_session: Option<String>,
+    completed_in_session: Option<String>,
+    source_path: Option<String>,
+    created_at: String,
+    updated_at: String,
+    completed_at: Option<String>,
+});
+/// Output for plan history.
+#[derive(Serialize)]
+struct PlanHistoryOutput<'a> {
+    plan_id: &'a str,
+    title: &'a str,
+    count: usize,
+    revisions: Vec<RevisionSummary<'a>>,
+}
+
+#[derive(Serialize)]
+struct RevisionSummary<'a> {
+    revision: i64,
+    title: &'a str,
+    actor: Option<&'a str>,
+    created_at: String,
+    /// Lines of content at this revision.
+    lines: usize,
+}
+
+/// Output for plan diff.
+#[derive(Serialize)]
+struct PlanDiffOutput<'a> {
+    plan_id: &'a str,
+    from: i64,
+    to: i64,
+    title_changed: bool,
+    criteria_changed: bool,
+    /// Unified diff of the content; empty when it did not change.
+    diff: String,
+}
+
+/// Output for plan revert.
+#[derive(Serialize)]
+struct PlanRevertOutput<'a> {
+    plan_id: &'a str,
+    /// Revision whose content was restored.
+    restored: i64,
+    /// Revision the plan is now at.
+    revision: i64,
+}
+
+/// Output for plan capture when a plan was captured.
+#[derive(Serialize)]
+struct CaptureOutput {
+    captured: bool,
+    /// `created`, `updated` (same content captured before) or `synced`
+    /// (same file, new content).
+    action: &'static str,
+    agent: &'static str,
+    /// Only when synced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed_sections: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flagged_issues: Option<Vec<String>>,
+    plan: PlanOutput,
+}
+
+/// Output for plan capture when no plan file was found.
+#[derive(Serialize)]
+struct NothingCapturedOutput {
+    captured: bool,
+    reason: &'static str,
+}
+
+/// Output for plan refresh.
+#[derive(Serialize)]
+struct RefreshOutput<'a> {
+    dry_run: bool,
+    updated: usize,
+    count: usize,
+    plans: &'a [RefreshResult],
+}
+
+json_schema!(PlanListOutput { plans: Vec<PlanOutput>, count: usize });
+json_schema!(PlanHistoryOutput<'a> { plan_id: &'a str, title: &'a str, count: usize, revisions: Vec<RevisionSummary<'a>> });
+json_schema!(RevisionSummary<'a> { revision: i64, title: &'a str, actor: Option<&'a str>, created_at: String, lines: usize });
+json_schema!(PlanDiffOutput<'a> {
+    plan_id: &'a str,
+    from: i64,
+    to: i64,
+    title_changed: bool,
+    criteria_changed: bool,
+    diff: String,
+});
+json_schema!(PlanRevertOutput<'a> { plan_id: &'a str, restored: i64, revision: i64 });
+json_schema!(CaptureOutput {
+    captured: bool,
+    action: &'static str,
+    agent: &'static str,
+    changed_sections: Option<Vec<String>>,
+    flagged_issues: Option<Vec<String>>,
+    plan: PlanOutput,
+});
+json_schema!(NothingCapturedOutput { captured: bool, reason: &'static str });
+json_schema!(RefreshOutput<'a> { dry_run: bool, updated: usize, count: usize, plans: &'a [RefreshResult] });
+json_schema!(RefreshResult {
+    plan_id: String,
+    title: String,
+    source_path: String,
+    status: &'static str,
+    changed_sections: Vec<String>,
+    flagged_issues: Vec<String>,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("plan create", schema_for::<PlanOutput>()),
+        ("plan list", schema_for::<PlanListOutput>()),
+        ("plan show", schema_for::<PlanDetailOutput>()),
+        ("plan update", schema_for::<PlanOutput>()),
+        ("plan history", schema_for::<PlanHistoryOutput<'_>>()),
+        ("plan diff", schema_for::<PlanDiffOutput<'_>>()),
+        ("plan revert", schema_for::<PlanRevertOutput<'_>>()),
+        ("plan capture", one_of(&[schema_for::<CaptureOutput>(), schema_for::<NothingCapturedOutput>()])),
+        ("plan refresh", schema_for::<RefreshOutput<'_>>()),
+    ]
+}
+
 fn format_timestamp(ts: i64) -> String {
     chrono::DateTime::from_timestamp_millis(ts)
         .map(|dt| dt.to_rfc3339())
@@ -115,31 +271,39 @@ fn format_timestamp(ts: i64) -> String {
 /// Execute a plan command.
 pub fn execute(
     command: &PlanCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json_output: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(String::from).unwrap_or_else(default_actor);
 
     match command {
-        PlanCommands::Create(args) => execute_create(&mut storage, args, json_output, &actor),
-        PlanCommands::List { status, limit, session } => execute_list(&storage, status, *limit, session.as_deref(), json_output),
-        PlanCommands::Show { id } => execute_show(&storage, id, json_output),
-        PlanCommands::Update(args) => execute_update(&mut storage, args, json_output, &actor),
-        PlanCommands::Capture { agent, max_age, file } => {
-            execute_capture(&mut storage, agent.as_deref(), *max_age, file.as_deref(), json_output, &actor)
+        PlanCommands::Create(args) => {
+            execute_create(storage, args, json_output, &actor).inspect(|()| super::embeddings::spawn_background_embedder())
+        }
+        PlanCommands::List { status, limit, session } => execute_list(storage, status, *limit, session.as_deref(), json_output),
+        PlanCommands::Show { id } => execute_show(storage, id, json_output),
+        PlanCommands::Update(args) => {
+            execute_update(storage, args, json_output, &actor).inspect(|()| super::embeddings::spawn_background_embedder())
+        }
+        PlanCommands::History { id } => execute_history(storage, id, json_output),
+        PlanCommands::Diff { id, rev } => execute_diff(storage, id, rev.as_deref(), json_output),
+        PlanCommands::Revert { id, rev } => execute_revert(storage, id, *rev, json_output, &actor),
+        PlanCommands::Capture { path, agent, max_age, file } => {
+            let file = path.as_deref().or(file.as_deref());
+            execute_capture(storage, agent.as_deref(), *max_age, file, json_output, &actor)
+        }
+        PlanCommands::Refresh { id } => execute_refresh(storage, id.as_deref(), json_output, &actor),
+        PlanCommands::CriticalPath { id, default_hours } => {
+            super::critical_path::execute(&super::critical_path::Scope::Plan(id), *default_hours, db, json_output)
         }
     }
 }
 
+/// Starting text for `sc plan create --edit`.
+const PLAN_TEMPLATE: &str = "## Goals\n\n## Approach\n\n## Out of scope\n";
+
 fn execute_create(
     storage: &mut SqliteStorage,
     args: &PlanCreateArgs,
@@ -157,7 +321,10 @@ fn execute_create(
     let mut plan = Plan::new(project.id.clone(), project_path, args.title.clone())
         .with_status(status);
 
-    if let Some(ref content) = args.content {
+    if args.input.edit {
+        let initial = args.content.as_deref().unwrap_or(PLAN_TEMPLATE);
+        plan = plan.with_content(&crate::cli::input::edit(initial, "plan content")?);
+    } else if let Some(ref content) = args.content {
         plan = plan.with_content(content);
     }
 
@@ -315,18 +482,39 @@ fn execute_update(
     let plan = storage.get_plan(&args.id)?
         .ok_or_else(|| Error::Other(format!("Plan not found: {}", args.id)))?;
 
-    // Update
-    storage.update_plan(
-        &plan.id,
-        args.title.as_deref(),
-        args.content.as_deref(),
-        args.status.as_deref(),
-        args.success_criteria.as_deref(),
-        actor,
-    )?;
+    let content = if args.input.edit {
+        let initial = args.content.as_deref().or(plan.content.as_deref()).unwrap_or(PLAN_TEMPLATE);
+        Some(crate::cli::input::edit(initial, "plan content")?)
+    } else {
+        args.content.clone()
+    };
+
+    // Re-read under the write lock so the guard sees the latest revision
+    storage.with_transaction(|storage| {
+        let result = storage
+            .get_plan(&plan.id)
+            .and_then(|current| match current {
+                Some(current) => Error::check_updated_at("plan", &plan.id, args.if_updated_at, current.updated_at, &current),
+                None => Err(Error::Other(format!("Plan not found: {}", args.id))),
+            })
+            .and_then(|()| {
+                storage.update_plan(
+                    &plan.id,
+                    args.title.as_deref(),
+                    content.as_deref(),
+                    args.status.as_deref(),
+                    args.success_criteria.as_deref(),
+                    actor,
+                )
+            });
+        let ok = result.is_ok();
+        (result, ok)
+    })??;
 
     // Fetch updated plan
-    let updated = storage.get_plan(&plan.id)?.unwrap();
+    let updated = storage
+        .get_plan(&plan.id)?
+        .ok_or_else(|| Error::Other(format!("Plan not found: {}", plan.id)))?;
 
     if json_output {
         let output = PlanOutput::from(updated);
@@ -342,7 +530,7 @@ fn execute_update(
         if args.success_criteria.is_some() {
             println!("  Success criteria updated");
         }
-        if args.content.is_some() {
+        if content.is_some() {
             println!("  Content updated");
         }
     }
@@ -350,6 +538,279 @@ fn execute_update(
     Ok(())
 }
 
+fn execute_history(
+    storage: &SqliteStorage,
+    id: &str,
+    json_output: bool,
+) -> Result<()> {
+    let plan = storage.get_plan(id)?
+        .ok_or_else(|| Error::Other(format!("Plan not found: {id}")))?;
+    let revisions = storage.list_plan_revisions(&plan.id)?;
+
+    if crate::is_csv() {
+        println!("revision,title,actor,created_at,lines");
+        for rev in &revisions {
+            println!(
+                "{},{},{},{},{}",
+                rev.revision,
+                crate::csv_escape(&rev.title),
+                crate::csv_escape(rev.actor.as_deref().unwrap_or("")),
+                format_timestamp(rev.created_at),
+                rev.content.as_deref().map_or(0, |c| c.lines().count()),
+            );
+        }
+    } else if json_output {
+        let entries: Vec<_> = revisions
+            .iter()
+            .map(|rev| RevisionSummary {
+                revision: rev.revision,
+                title: &rev.title,
+                actor: rev.actor.as_deref(),
+                created_at: format_timestamp(rev.created_at),
+                lines: rev.content.as_deref().map_or(0, |c| c.lines().count()),
+            })
+            .collect();
+        let output = PlanHistoryOutput { plan_id: &plan.id, title: &plan.title, count: entries.len(), revisions: entries };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if revisions.is_empty() {
+        println!("No revisions recorded for plan: {}", plan.title);
+    } else {
+        println!("History for plan: {} ({} revisions)\n", plan.title, revisions.len());
+        let latest = revisions.last().map(|r| r.revision);
+        for rev in revisions.iter().rev() {
+            let marker = if Some(rev.revision) == latest { " (current)" } else { "" };
+            println!(
+                "  r{}{}  {}  {}",
+                rev.revision,
+                marker,
+                format_timestamp(rev.created_at),
+                rev.actor.as_deref().unwrap_or("unknown"),
+            );
+            println!(
+                "      {} ({} lines)",
+                rev.title,
+                rev.content.as_deref().map_or(0, |c| c.lines().count())
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_diff(
+    storage: &SqliteStorage,
+    id: &str,
+    rev: Option<&str>,
+    json_output: bool,
+) -> Result<()> {
+    let plan = storage.get_plan(id)?
+        .ok_or_else(|| Error::Other(format!("Plan not found: {id}")))?;
+    let revisions = storage.list_plan_revisions(&plan.id)?;
+    let latest = revisions.last().map_or(0, |r| r.revision);
+
+    let (from, to) = parse_rev_range(rev, latest)?;
+    let find = |n: i64| -> Result<&PlanRevision> {
+        revisions
+            .iter()
+            .find(|r| r.revision == n)
+            .ok_or_else(|| Error::InvalidArgument(format!(
+                "Revision {n} not found (plan has revisions 1..{latest})"
+            )))
+    };
+    let old = find(from)?;
+    let new = find(to)?;
+
+    let diff = unified_diff(
+        old.content.as_deref().unwrap_or(""),
+        new.content.as_deref().unwrap_or(""),
+        &format!("{} (r{from})", plan.id),
+        &format!("{} (r{to})", plan.id),
+    );
+
+    if json_output {
+        let output = PlanDiffOutput {
+            plan_id: &plan.id,
+            from,
+            to,
+            title_changed: old.title != new.title,
+            criteria_changed: old.success_criteria != new.success_criteria,
+            diff,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        if old.title != new.title {
+            println!("Title: {} → {}", old.title, new.title);
+        }
+        if old.success_criteria != new.success_criteria {
+            println!(
+                "Success criteria: {} → {}",
+                old.success_criteria.as_deref().unwrap_or("(none)"),
+                new.success_criteria.as_deref().unwrap_or("(none)"),
+            );
+        }
+        if diff.is_empty() {
+            println!("No content changes between r{from} and r{to}.");
+        } else {
+            print!("{diff}");
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_revert(
+    storage: &mut SqliteStorage,
+    id: &str,
+    rev: i64,
+    json_output: bool,
+    actor: &str,
+) -> Result<()> {
+    let plan = storage.get_plan(id)?
+        .ok_or_else(|| Error::Other(format!("Plan not found: {id}")))?;
+
+    let current = storage.revert_plan(&plan.id, rev, actor)?;
+
+    if crate::is_silent() {
+        println!("{}", plan.id);
+    } else if json_output {
+        let output = PlanRevertOutput { plan_id: &plan.id, restored: rev, revision: current };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Reverted plan: {}", plan.title);
+        println!("  Restored: r{rev}");
+        println!("  Now at:   r{current}");
+    }
+
+    Ok(())
+}
+
+/// Parse a `--rev` spec into a `(from, to)` revision pair.
+///
+/// Accepts `A..B`, `A..` (through `latest`), or a single `N` (compared with
+/// `N - 1`). With no spec, compares the last two revisions.
+fn parse_rev_range(spec: Option<&str>, latest: i64) -> Result<(i64, i64)> {
+    let parse = |s: &str| -> Result<i64> {
+        s.trim()
+            .trim_start_matches('r')
+            .parse()
+            .map_err(|_| Error::InvalidArgument(format!("Invalid revision: {s}")))
+    };
+
+    if latest == 0 {
+        return Err(Error::InvalidArgument("Plan has no revisions".to_string()));
+    }
+
+    let (from, to) = match spec {
+        None => ((latest - 1).max(1), latest),
+        Some(spec) => match spec.split_once("..") {
+            Some((a, "")) => (parse(a)?, latest),
+            Some((a, b)) => (parse(a)?, parse(b)?),
+            None => {
+                let n = parse(spec)?;
+                ((n - 1).max(1), n)
+            }
+        },
+    };
+    Ok((from, to))
+}
+
+/// Produce a unified diff (3 lines of context) between two texts.
+///
+/// Returns an empty string when the texts have identical lines.
+fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    const CONTEXT: usize = 3;
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Op {
+        Keep,
+        Remove,
+        Add,
+    }
+
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (rows, cols) = (old_lines.len(), new_lines.len());
+
+    // Longest-common-subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; cols + 1]; rows + 1];
+    for i in (0..rows).rev() {
+        for j in (0..cols).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Walk the table into (op, old index, new index) steps
+    let mut ops = Vec::with_capacity(rows + cols);
+    let (mut i, mut j) = (0, 0);
+    while i < rows || j < cols {
+        if i < rows && j < cols && old_lines[i] == new_lines[j] {
+            ops.push((Op::Keep, i, j));
+            i += 1;
+            j += 1;
+        } else if i < rows && (j == cols || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Remove, i, j));
+            i += 1;
+        } else {
+            ops.push((Op::Add, i, j));
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _, _))| *op != Op::Keep)
+        .map(|(idx, _)| idx)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let range = |start: usize, len: usize| match len {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{len}", start + 1),
+    };
+
+    let mut out = format!("--- {old_label}\n+++ {new_label}\n");
+    let mut next = 0;
+    while next < changes.len() {
+        let start = changes[next].saturating_sub(CONTEXT);
+        let mut last = changes[next];
+        while next + 1 < changes.len() && changes[next + 1] - last <= 2 * CONTEXT + 1 {
+            next += 1;
+            last = changes[next];
+        }
+        next += 1;
+        let end = (last + CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..end];
+
+        let old_len = hunk.iter().filter(|(op, _, _)| *op != Op::Add).count();
+        let new_len = hunk.iter().filter(|(op, _, _)| *op != Op::Remove).count();
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            range(hunk[0].1, old_len),
+            range(hunk[0].2, new_len)
+        );
+        for &(op, oi, ni) in hunk {
+            let (prefix, line) = match op {
+                Op::Keep => (' ', old_lines[oi]),
+                Op::Remove => ('-', old_lines[oi]),
+                Op::Add => ('+', new_lines[ni]),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
 fn execute_capture(
     storage: &mut SqliteStorage,
     agent: Option<&str>,
@@ -401,7 +862,8 @@ fn execute_capture(
     if discovered.is_empty() {
         if !crate::is_silent() {
             if json_output {
-                println!(r#"{{"captured":false,"reason":"no_plans_found"}}"#);
+                let output = NothingCapturedOutput { captured: false, reason: "no_plans_found" };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("No recent plan files found.");
                 if let Some(agent_name) = agent {
@@ -418,7 +880,7 @@ fn execute_capture(
     // Take the most recent plan
     let plan_file = &discovered[0];
     let source_hash = plan_discovery::compute_content_hash(&plan_file.content);
-    let source_path = plan_file.path.to_string_lossy().to_string();
+    let source_path = source_path_for(&project_path, &plan_file.path);
 
     // Check for existing plan with same content hash (dedup)
     if let Some(existing) = storage.find_plan_by_source_hash(&source_hash)? {
@@ -435,14 +897,18 @@ fn execute_capture(
         if crate::is_silent() {
             println!("{}", existing.id);
         } else if json_output {
-            let updated = storage.get_plan(&existing.id)?.unwrap();
-            let output = PlanOutput::from(updated);
-            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
-                "captured": true,
-                "action": "updated",
-                "agent": plan_file.agent.display_name(),
-                "plan": output,
-            }))?);
+            let updated = storage
+                .get_plan(&existing.id)?
+                .ok_or_else(|| Error::Other(format!("Plan not found: {}", existing.id)))?;
+            let output = CaptureOutput {
+                captured: true,
+                action: "updated",
+                agent: plan_file.agent.display_name(),
+                changed_sections: None,
+                flagged_issues: None,
+                plan: PlanOutput::from(updated),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
         } else {
             println!("Updated existing plan: {}", existing.title);
             println!("  ID:     {}", existing.id);
@@ -453,6 +919,49 @@ fn execute_capture(
         return Ok(());
     }
 
+    // Same file captured before with different content: sync it in place
+    if let Some(existing) = storage.find_plan_by_source_path(&project_path, &source_path)? {
+        let changed = plan_discovery::changed_sections(
+            existing.content.as_deref().unwrap_or(""),
+            &plan_file.content,
+        );
+        let flagged = storage.atomically(|storage| {
+            storage.update_plan_source(
+                &existing.id,
+                &plan_file.title,
+                &plan_file.content,
+                &source_path,
+                &source_hash,
+                actor,
+            )?;
+            flag_linked_issues(storage, &existing.id, &changed, actor)
+        })?;
+
+        if crate::is_silent() {
+            println!("{}", existing.id);
+        } else if json_output {
+            let updated = storage
+                .get_plan(&existing.id)?
+                .ok_or_else(|| Error::Other(format!("Plan not found: {}", existing.id)))?;
+            let output = CaptureOutput {
+                captured: true,
+                action: "synced",
+                agent: plan_file.agent.display_name(),
+                changed_sections: Some(changed),
+                flagged_issues: Some(flagged),
+                plan: PlanOutput::from(updated),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!("Synced plan from source: {}", plan_file.title);
+            println!("  ID:     {}", existing.id);
+            println!("  Source: {source_path}");
+            print_refresh_details(&changed, &flagged);
+        }
+
+        return Ok(());
+    }
+
     // Create new plan
     let mut plan = Plan::new(project.id.clone(), project_path, plan_file.title.clone())
         .with_content(&plan_file.content)
@@ -469,13 +978,15 @@ fn execute_capture(
     if crate::is_silent() {
         println!("{}", plan.id);
     } else if json_output {
-        let output = PlanOutput::from(plan.clone());
-        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
-            "captured": true,
-            "action": "created",
-            "agent": plan_file.agent.display_name(),
-            "plan": output,
-        }))?);
+        let output = CaptureOutput {
+            captured: true,
+            action: "created",
+            agent: plan_file.agent.display_name(),
+            changed_sections: None,
+            flagged_issues: None,
+            plan: PlanOutput::from(plan.clone()),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
         println!("Captured plan: {}", plan.title);
         println!("  ID:     {}", plan.id);
@@ -488,3 +999,276 @@ fn execute_capture(
 
     Ok(())
 }
+
+
+/// Label added to open issues whose plan sections changed on refresh.
+const PLAN_CHANGED_LABEL: &str = "plan-changed";
+
+#[derive(Serialize)]
+struct RefreshResult {
+    plan_id: String,
+    title: String,
+    source_path: String,
+    /// "updated", "unchanged", or "missing"
+    status: &'static str,
+    changed_sections: Vec<String>,
+    flagged_issues: Vec<String>,
+}
+
+fn execute_refresh(
+    storage: &mut SqliteStorage,
+    id: Option<&str>,
+    json_output: bool,
+    actor: &str,
+) -> Result<()> {
+    let plans = if let Some(id) = id {
+        let plan = storage.get_plan(id)?
+            .ok_or_else(|| Error::Other(format!("Plan not found: {id}")))?;
+        if plan.source_path.is_none() {
+            return Err(Error::InvalidArgument(format!(
+                "Plan {id} was not captured from a file (use `sc plan capture <path>`)"
+            )));
+        }
+        vec![plan]
+    } else {
+        let project_path = resolve_project_path(storage, None)?;
+        storage
+            .get_plans_by_project(&project_path)?
+            .into_iter()
+            .filter(|p| p.source_path.is_some())
+            .collect()
+    };
+
+    let mut results = Vec::with_capacity(plans.len());
+    for plan in plans {
+        let Some(source_path) = plan.source_path.clone() else { continue };
+        let file = resolve_source_path(&plan.project_path, &source_path);
+
+        let mut result = RefreshResult {
+            plan_id: plan.id.clone(),
+            title: plan.title.clone(),
+            source_path,
+            status: "unchanged",
+            changed_sections: Vec::new(),
+            flagged_issues: Vec::new(),
+        };
+
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            result.status = "missing";
+            results.push(result);
+            continue;
+        };
+        let hash = plan_discovery::compute_content_hash(&content);
+        if plan.source_hash.as_deref() == Some(hash.as_str()) {
+            results.push(result);
+            continue;
+        }
+
+        let filename = file
+            .file_stem()
+            .map_or_else(|| "unnamed".to_string(), |s| s.to_string_lossy().to_string());
+        let title = plan_discovery::extract_title(&content, &filename);
+        result.changed_sections =
+            plan_discovery::changed_sections(plan.content.as_deref().unwrap_or(""), &content);
+        result.status = "updated";
+
+        if crate::is_dry_run() {
+            result.flagged_issues = issues_for_sections(storage, &plan.id, &result.changed_sections)?;
+        } else {
+            // A plan counts as refreshed only once its issues are flagged
+            result.flagged_issues = storage.atomically(|storage| {
+                storage.update_plan_source(&plan.id, &title, &content, &result.source_path, &hash, actor)?;
+                flag_linked_issues(storage, &plan.id, &result.changed_sections, actor)
+            })?;
+            result.title = title;
+        }
+        results.push(result);
+    }
+
+    if crate::is_silent() {
+        for r in results.iter().filter(|r| r.status == "updated") {
+            println!("{}", r.plan_id);
+        }
+        return Ok(());
+    }
+
+    if json_output {
+        let updated = results.iter().filter(|r| r.status == "updated").count();
+        let output = RefreshOutput { dry_run: crate::is_dry_run(), updated, count: results.len(), plans: &results };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if results.is_empty() {
+        println!("No captured plans to refresh.");
+        println!("\nCapture one with: sc plan capture docs/prd.md");
+    } else {
+        let prefix = if crate::is_dry_run() { "[dry-run] " } else { "" };
+        for r in &results {
+            let icon = match r.status {
+                "updated" => "↻",
+                "missing" => "✗",
+                _ => "✓",
+            };
+            println!("{prefix}{icon} {} [{}]", r.title, r.status);
+            println!("    ID:     {}", r.plan_id);
+            println!("    Source: {}", r.source_path);
+            if r.status == "updated" {
+                print_refresh_details(&r.changed_sections, &r.flagged_issues);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_refresh_details(changed: &[String], flagged: &[String]) {
+    if !changed.is_empty() {
+        let names: Vec<&str> = changed
+            .iter()
+            .map(|h| if h.is_empty() { "(preamble)" } else { h.as_str() })
+            .collect();
+        println!("    Changed sections: {}", names.join(", "));
+    }
+    if !flagged.is_empty() {
+        println!("    Flagged issues ({PLAN_CHANGED_LABEL}): {}", flagged.join(", "));
+    }
+}
+
+/// Open issues linked to a plan that mention one of the changed section
+/// headings in their title, description, or details.
+fn issues_for_sections(
+    storage: &SqliteStorage,
+    plan_id: &str,
+    changed: &[String],
+) -> Result<Vec<String>> {
+    let headings: Vec<String> = changed
+        .iter()
+        .filter(|h| h.chars().count() >= 3)
+        .map(|h| h.to_lowercase())
+        .collect();
+    if headings.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let issues = storage.list_plan_issues(plan_id)?;
+    Ok(issues
+        .into_iter()
+        .filter(|issue| {
+            let text = [
+                Some(issue.title.as_str()),
+                issue.description.as_deref(),
+                issue.details.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n")
+            .to_lowercase();
+            headings.iter().any(|h| text.contains(h.as_str()))
+        })
+        .map(|issue| issue.short_id.unwrap_or(issue.id))
+        .collect())
+}
+
+/// Label linked issues affected by changed sections. Returns their IDs.
+fn flag_linked_issues(
+    storage: &mut SqliteStorage,
+    plan_id: &str,
+    changed: &[String],
+    actor: &str,
+) -> Result<Vec<String>> {
+    let flagged = issues_for_sections(storage, plan_id, changed)?;
+    let label = [PLAN_CHANGED_LABEL.to_string()];
+    for id in &flagged {
+        storage.add_issue_labels(id, &label, actor)?;
+    }
+    Ok(flagged)
+}
+
+/// Source path to store for a captured file: relative to the project root
+/// when the file lives inside it (so it survives sync to another machine),
+/// absolute otherwise.
+fn source_path_for(project_path: &str, file: &Path) -> String {
+    let file = std::fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+    let root = std::fs::canonicalize(project_path).unwrap_or_else(|_| PathBuf::from(project_path));
+    file.strip_prefix(&root)
+        .map_or_else(|_| file.to_string_lossy().to_string(), |rel| rel.to_string_lossy().to_string())
+}
+
+/// Resolve a stored source path against the plan's project root.
+fn resolve_source_path(project_path: &str, source_path: &str) -> PathBuf {
+    let path = Path::new(source_path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        Path::new(project_path).join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rev_range() {
+        assert_eq!(parse_rev_range(None, 5).unwrap(), (4, 5));
+        assert_eq!(parse_rev_range(None, 1).unwrap(), (1, 1));
+        assert_eq!(parse_rev_range(Some("3..5"), 7).unwrap(), (3, 5));
+        assert_eq!(parse_rev_range(Some("2.."), 7).unwrap(), (2, 7));
+        assert_eq!(parse_rev_range(Some("r4"), 7).unwrap(), (3, 4));
+        assert!(parse_rev_range(Some("x..2"), 7).is_err());
+        assert!(parse_rev_range(None, 0).is_err());
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "# Auth\n\nUse sessions.\nStore in redis.\n";
+        let new = "# Auth\n\nUse JWT.\nStore in redis.\n";
+        let diff = unified_diff(old, new, "a", "b");
+        assert_eq!(
+            diff,
+            "--- a\n+++ b\n@@ -1,4 +1,4 @@\n # Auth\n \n-Use sessions.\n+Use JWT.\n Store in redis.\n"
+        );
+        assert!(unified_diff(old, old, "a", "b").is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("line {i}")).collect::<Vec<_>>().join("\n");
+        let new = old.replace("line 2\n", "line two\n").replace("line 19\n", "");
+        let diff = unified_diff(&old, &new, "a", "b");
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@"));
+        assert!(diff.contains("@@ -16,5 +16,4 @@"));
+    }
+
+    #[test]
+    fn test_source_path_relative_to_project() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        let file = dir.path().join("docs").join("prd.md");
+        std::fs::write(&file, "# PRD\n").unwrap();
+
+        let root = dir.path().to_string_lossy().to_string();
+        let stored = source_path_for(&root, &file);
+        assert_eq!(Path::new(&stored), Path::new("docs").join("prd.md"));
+        assert!(resolve_source_path(&root, &stored).exists());
+    }
+
+    #[test]
+    fn test_flag_linked_issues_by_section() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        let project = storage.get_or_create_project("/p", "actor").unwrap();
+        let plan = Plan::new(project.id, "/p".to_string(), "PRD".to_string());
+        storage.create_plan(&plan, "actor").unwrap();
+        storage
+            .create_issue("i1", Some("P-1"), "/p", "Implement auth flow", None, None, None, None, Some(&plan.id), None, "actor")
+            .unwrap();
+        storage
+            .create_issue("i2", Some("P-2"), "/p", "Storage layer", None, None, None, None, Some(&plan.id), None, "actor")
+            .unwrap();
+
+        let flagged = flag_linked_issues(&mut storage, &plan.id, &["Auth".to_string()], "actor").unwrap();
+        assert_eq!(flagged, ["P-1"]);
+        assert_eq!(storage.get_issue_labels("i1").unwrap(), [PLAN_CHANGED_LABEL]);
+        assert!(storage.get_issue_labels("i2").unwrap().is_empty());
+    }
+}
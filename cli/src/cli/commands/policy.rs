@@ -0,0 +1,275 @@
+//! Claim policy checks and expired claims (`sc policy check`, `sc policy
+//! reap`).
+//!
+//! The rules come from the `policy` config section (see
+//! [`crate::model::policy`]). Claims are counted per canonical actor, so an
+//! agent can't get around a limit by claiming under an alias. The same
+//! helpers back the checks `sc issue claim` and `sc issue next-block` make
+//! before claiming, the leases they take out, and the release of expired
+//! claims that `sc issue ready`, `next-block` and `route` do first.
+
+use crate::cli::commands::config::{load_issue_workflow, load_policy};
+use crate::cli::{Database, PolicyCommands};
+use crate::config::{default_actor, resolve_project_path};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{Claim, Policy, Violation};
+use crate::storage::{ExpiredClaim, SqliteStorage};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Output for policy check.
+#[derive(Serialize)]
+struct PolicyCheckOutput {
+    project_path: String,
+    max_in_progress: Option<u32>,
+    max_claim_idle_hours: Option<u32>,
+    violations: Vec<ViolationOutput>,
+}
+
+#[derive(Serialize)]
+struct ViolationOutput {
+    /// `max_in_progress` or `max_claim_idle_hours`.
+    rule: String,
+    actor: String,
+    /// Issues or hours, by rule.
+    limit: u32,
+    /// Claims breaking the rule: every one the actor holds, or the idle ones.
+    issues: Vec<ClaimOutput>,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ClaimOutput {
+    id: String,
+    short_id: Option<String>,
+    updated_at: i64,
+}
+
+/// Output for policy reap.
+#[derive(Serialize)]
+struct ReapOutput {
+    project_path: String,
+    released: Vec<ExpiredClaim>,
+    count: usize,
+}
+
+json_schema!(PolicyCheckOutput {
+    project_path: String,
+    max_in_progress: Option<u32>,
+    max_claim_idle_hours: Option<u32>,
+    violations: Vec<ViolationOutput>,
+});
+json_schema!(ViolationOutput { rule: String, actor: String, limit: u32, issues: Vec<ClaimOutput>, message: String });
+json_schema!(ClaimOutput { id: String, short_id: Option<String>, updated_at: i64 });
+json_schema!(ReapOutput { project_path: String, released: Vec<ExpiredClaim>, count: usize });
+json_schema!(ExpiredClaim {
+    issue_id: String,
+    short_id: Option<String>,
+    title: String,
+    assignee: String,
+    holder: String,
+    expires_at: i64,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("policy check", schema_for::<PolicyCheckOutput>()), ("policy reap", schema_for::<ReapOutput>())]
+}
+
+/// Execute a policy command.
+///
+/// # Errors
+///
+/// `check` returns `PolicyViolation` (exit 16) after printing its report
+/// when any rule is broken, or an error if the database cannot be read.
+pub fn execute(command: &PolicyCommands, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    match command {
+        PolicyCommands::Check => check(db.storage()?, json),
+        PolicyCommands::Reap { daemon, metrics } => reap(db, *daemon, metrics.as_deref(), &actor, json),
+    }
+}
+
+/// Release the project's expired claims, once or every minute with
+/// `--daemon` (serving `--metrics` meanwhile).
+fn reap(db: &mut Database, daemon: bool, metrics: Option<&str>, actor: &str, json: bool) -> Result<()> {
+    if let Some(addr) = metrics {
+        crate::cli::metrics::serve(addr, db.require_path()?.to_path_buf())?;
+    }
+    let storage = db.storage()?;
+    loop {
+        let (project_path, released) = crate::cli::metrics::timed("policy reap", || {
+            let project_path = resolve_project_path(storage, None)?;
+            let notify = load_policy(Some(&project_path)).notifies_on_expiry();
+            let released = if crate::is_dry_run() {
+                storage.get_expired_claims(&project_path)?
+            } else {
+                let workflow = load_issue_workflow(Some(&project_path));
+                storage.release_expired_claims(&project_path, notify, &workflow, actor)?
+            };
+            Ok((project_path, released))
+        })?;
+
+        if json {
+            let output = ReapOutput { project_path, count: released.len(), released };
+            println!("{}", serde_json::to_string(&output)?);
+        } else if released.is_empty() {
+            if !daemon {
+                println!("No expired claims.");
+            }
+        } else {
+            let verb = if crate::is_dry_run() { "Would release" } else { "Released" };
+            println!("{verb} {} expired claims:", released.len());
+            for claim in &released {
+                let id = claim.short_id.as_deref().unwrap_or(&claim.issue_id);
+                println!("  [{id}] {} (claimed by {})", claim.title, claim.assignee);
+            }
+        }
+
+        if !daemon {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(60));
+    }
+}
+
+/// Lazy counterpart of `sc policy reap`, run before ready issues are
+/// listed or claimed so the ones freed up are among them. Errors (say, a
+/// role that may not release claims) are only logged.
+pub(crate) fn release_expired(storage: &mut SqliteStorage, project_path: &str, actor: &str) {
+    if crate::is_dry_run() {
+        return;
+    }
+    let notify = load_policy(Some(project_path)).notifies_on_expiry();
+    let workflow = load_issue_workflow(Some(project_path));
+    match storage.release_expired_claims(project_path, notify, &workflow, actor) {
+        Ok(released) if !released.is_empty() => {
+            tracing::debug!(count = released.len(), "Released expired claims");
+        }
+        Ok(_) => {}
+        Err(e) => tracing::debug!(error = %e, "Release of expired claims failed"),
+    }
+}
+
+/// Take out a lease on a claim just made, if the policy leases claims.
+///
+/// # Errors
+///
+/// Returns an error if the lease cannot be written.
+pub(crate) fn lease(storage: &SqliteStorage, policy: &Policy, issue_id: &str, holder: &str) -> Result<()> {
+    match policy.lease_ms() {
+        Some(lease_ms) => storage.grant_claim_lease(issue_id, holder, lease_ms),
+        None => Ok(()),
+    }
+}
+
+fn check(storage: &SqliteStorage, json: bool) -> Result<()> {
+    let project_path = resolve_project_path(storage, None)?;
+    let policy = load_policy(Some(&project_path));
+    let violations = policy.violations(&claims(storage, &project_path)?, chrono::Utc::now().timestamp_millis());
+
+    let output = PolicyCheckOutput {
+        project_path,
+        max_in_progress: policy.max_in_progress,
+        max_claim_idle_hours: policy.max_claim_idle_hours,
+        violations: violations
+            .iter()
+            .map(|v| ViolationOutput {
+                rule: v.rule.as_str().to_string(),
+                actor: v.actor.clone(),
+                limit: v.limit,
+                issues: v
+                    .claims
+                    .iter()
+                    .map(|c| ClaimOutput { id: c.issue_id.clone(), short_id: c.short_id.clone(), updated_at: c.updated_at })
+                    .collect(),
+                message: v.describe(),
+            })
+            .collect(),
+    };
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        print_check(&output);
+    }
+
+    match violations.first() {
+        Some(first) if violations.len() == 1 => Err(violation_error(first)),
+        Some(first) => Err(Error::PolicyViolation {
+            rule: first.rule.as_str().to_string(),
+            message: format!("{} rules broken in {}", violations.len(), output.project_path),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn print_check(output: &PolicyCheckOutput) {
+    let limit = |value: Option<u32>, unit: &str| value.map_or_else(|| "none".to_string(), |v| format!("{v}{unit}"));
+    println!(
+        "Policy for {}: max in progress {}, max claim idle {}",
+        output.project_path,
+        limit(output.max_in_progress, ""),
+        limit(output.max_claim_idle_hours, "h")
+    );
+    if output.max_in_progress.is_none() && output.max_claim_idle_hours.is_none() {
+        println!("No rules set. Add a `policy` section to ~/.savecontext/config.json to enforce some.");
+    } else if output.violations.is_empty() {
+        println!("✓ No violations");
+    }
+    for violation in &output.violations {
+        println!("✗ {}", violation.message);
+    }
+}
+
+/// Claims in a project, by canonical actor.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read.
+pub(crate) fn claims(storage: &SqliteStorage, project_path: &str) -> Result<Vec<Claim>> {
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut claims = storage.get_claims(project_path)?;
+    for claim in &mut claims {
+        if let Some(actor) = names.get(&claim.actor) {
+            claim.actor.clone_from(actor);
+        } else {
+            let actor = storage.resolve_actor(&claim.actor)?;
+            names.insert(std::mem::replace(&mut claim.actor, actor.clone()), actor);
+        }
+    }
+    Ok(claims)
+}
+
+/// Fail if `actor` (canonical) claiming `adding` more issues breaks the
+/// project's policy. Issues it already holds don't count as new.
+///
+/// # Errors
+///
+/// Returns `PolicyViolation` if a rule would be broken, or an error if the
+/// database cannot be read.
+pub(crate) fn enforce(
+    storage: &SqliteStorage,
+    policy: &Policy,
+    project_path: &str,
+    actor: &str,
+    adding: &[&str],
+) -> Result<()> {
+    if policy.max_in_progress.is_none() && policy.max_claim_idle_hours.is_none() {
+        return Ok(());
+    }
+    let holds: Vec<Claim> = claims(storage, project_path)?.into_iter().filter(|c| c.actor == actor).collect();
+    let new = adding
+        .iter()
+        .filter(|id| !holds.iter().any(|c| c.issue_id == **id || c.short_id.as_deref() == Some(**id)))
+        .count();
+    match policy.refuse(actor, &holds, new, chrono::Utc::now().timestamp_millis()) {
+        Some(violation) if new > 0 => Err(violation_error(&violation)),
+        _ => Ok(()),
+    }
+}
+
+fn violation_error(violation: &Violation) -> Error {
+    Error::PolicyViolation { rule: violation.rule.as_str().to_string(), message: violation.describe() }
+}
@@ -4,15 +4,33 @@
 //! session state, issues, memory, and optionally Claude Code transcripts
 //! into a single injectable context block.
 //!
-//! This is a **read-only** command — it never mutates the database.
-
-use crate::config::{current_git_branch, resolve_db_path, resolve_project_path, resolve_session_or_suggest};
+//! Sessions that span several project paths (monorepos) get issues and
+//! memory from every path, grouped into one section per project.
+//!
+//! Stored text is cleaned for prompt injection before it is printed (see
+//! [`super::sanitize`]); `--raw` prints it as stored.
+//!
+//! This is a **read-only** command — it never changes records. It does
+//! count a read of each item it includes, which later primes use to rank
+//! items (see [`crate::model::relevance`]).
+
+use super::context::record_reads;
+use super::sanitize::Sanitizer;
+use crate::cli::Database;
+use crate::config::{
+    current_git_branch, in_subproject, resolve_project_path, resolve_session_or_suggest, resolve_subproject,
+};
 use crate::embeddings::{is_embeddings_enabled, EmbeddingProvider, Model2VecProvider};
 use crate::error::{Error, Result};
-use crate::storage::{ContextItem, SqliteStorage};
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::model::{
+    Categories, IssueStatus, IssueType, ItemCategory, ItemPriority, MemoryCategory, ReviewStatus, SessionStatus,
+    TrustLevel,
+};
+use crate::storage::{ContextItem, Issue, Memory, SqliteStorage};
 use serde::Serialize;
+use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
 use std::sync::OnceLock;
 use tracing::{debug, warn};
 
@@ -35,11 +53,16 @@ const HEADER_TOKEN_RESERVE: usize = 200;
 #[derive(Serialize)]
 struct PrimeOutput {
     session: SessionInfo,
+    /// Reminders of the project that have come due, due first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    due_reminders: Vec<DueReminderEntry>,
     #[serde(skip_serializing_if = "Option::is_none")]
     git: Option<GitInfo>,
     context: ContextBlock,
     issues: IssueBlock,
     memory: Vec<MemoryEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    projects: Vec<ProjectSection>,
     #[serde(skip_serializing_if = "Option::is_none")]
     transcript: Option<TranscriptBlock>,
     command_reference: Vec<CmdRef>,
@@ -51,7 +74,7 @@ struct SessionInfo {
     name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
-    status: String,
+    status: SessionStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     branch: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -73,12 +96,22 @@ struct ContextBlock {
     total_items: usize,
 }
 
+#[derive(Serialize)]
+struct DueReminderEntry {
+    key: String,
+    value: String,
+    due_at: i64,
+}
+
 #[derive(Serialize)]
 struct ContextEntry {
     key: String,
     value: String,
-    category: String,
-    priority: String,
+    category: ItemCategory,
+    priority: ItemPriority,
+    trust: TrustLevel,
+    /// An agent's decision no one has approved or rejected yet.
+    awaiting_review: bool,
 }
 
 #[derive(Serialize)]
@@ -93,16 +126,35 @@ struct IssueSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     short_id: Option<String>,
     title: String,
-    status: String,
+    status: IssueStatus,
     priority: i32,
-    issue_type: String,
+    issue_type: IssueType,
 }
 
 #[derive(Serialize)]
 struct MemoryEntry {
     key: String,
     value: String,
-    category: String,
+    category: MemoryCategory,
+}
+
+/// Per-project issues and memory (only emitted for multi-path sessions).
+#[derive(Serialize)]
+struct ProjectSection {
+    project_path: String,
+    name: String,
+    issues: IssueBlock,
+    memory: Vec<MemoryEntry>,
+}
+
+/// Issues and memory loaded for one project path of the session.
+struct ProjectScope {
+    project_path: String,
+    name: String,
+    active_issues: Vec<Issue>,
+    ready_issues: Vec<Issue>,
+    all_open: Vec<Issue>,
+    memory: Vec<Memory>,
 }
 
 #[derive(Serialize, Clone)]
@@ -145,9 +197,13 @@ struct SmartConfig {
 #[derive(Serialize)]
 struct SmartPrimeOutput {
     stats: SmartPrimeStats,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    due_reminders: Vec<DueReminderEntry>,
     scored_context: Vec<ScoredContextEntry>,
     issues: IssueBlock,
     memory: Vec<MemoryEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    projects: Vec<ProjectSection>,
     #[serde(skip_serializing_if = "Option::is_none")]
     transcript: Option<TranscriptBlock>,
     command_reference: Vec<CmdRef>,
@@ -168,10 +224,97 @@ struct SmartPrimeStats {
 struct ScoredContextEntry {
     key: String,
     value: String,
-    category: String,
-    priority: String,
+    category: ItemCategory,
+    priority: ItemPriority,
+    trust: TrustLevel,
+    awaiting_review: bool,
+    score: f64,
+    token_estimate: usize,
+}
+
+json_schema!(PrimeOutput {
+    session: SessionInfo,
+    due_reminders: Vec<DueReminderEntry> = optional,
+    git: Option<GitInfo>,
+    context: ContextBlock,
+    issues: IssueBlock,
+    memory: Vec<MemoryEntry>,
+    projects: Vec<ProjectSection> = optional,
+    transcript: Option<TranscriptBlock>,
+    command_reference: Vec<CmdRef>,
+});
+json_schema!(SessionInfo {
+    id: String,
+    name: String,
+    description: Option<String>,
+    status: SessionStatus,
+    branch: Option<String>,
+    project_path: Option<String>,
+});
+json_schema!(GitInfo { branch: String, changed_files: Vec<String> });
+json_schema!(ContextBlock {
+    high_priority: Vec<ContextEntry>,
+    decisions: Vec<ContextEntry>,
+    reminders: Vec<ContextEntry>,
+    recent_progress: Vec<ContextEntry>,
+    total_items: usize,
+});
+json_schema!(DueReminderEntry { key: String, value: String, due_at: i64 });
+json_schema!(ContextEntry {
+    key: String,
+    value: String,
+    category: ItemCategory,
+    priority: ItemPriority,
+    trust: TrustLevel,
+    awaiting_review: bool,
+});
+json_schema!(IssueBlock { active: Vec<IssueSummary>, ready: Vec<IssueSummary>, total_open: usize });
+json_schema!(IssueSummary {
+    short_id: Option<String>,
+    title: String,
+    status: IssueStatus,
+    priority: i32,
+    issue_type: IssueType,
+});
+json_schema!(MemoryEntry { key: String, value: String, category: MemoryCategory });
+json_schema!(ProjectSection { project_path: String, name: String, issues: IssueBlock, memory: Vec<MemoryEntry> });
+json_schema!(TranscriptBlock { source: String, entries: Vec<TranscriptEntry> });
+json_schema!(TranscriptEntry { summary: String, timestamp: Option<String> });
+json_schema!(CmdRef { cmd: String, desc: String });
+json_schema!(SmartPrimeOutput {
+    stats: SmartPrimeStats,
+    due_reminders: Vec<DueReminderEntry> = optional,
+    scored_context: Vec<ScoredContextEntry>,
+    issues: IssueBlock,
+    memory: Vec<MemoryEntry>,
+    projects: Vec<ProjectSection> = optional,
+    transcript: Option<TranscriptBlock>,
+    command_reference: Vec<CmdRef>,
+});
+json_schema!(SmartPrimeStats {
+    total_items: usize,
+    selected_items: usize,
+    tokens_used: usize,
+    tokens_budget: usize,
+    embeddings_available: bool,
+    mmr_applied: bool,
+    query_boosted: bool,
+});
+json_schema!(ScoredContextEntry {
+    key: String,
+    value: String,
+    category: ItemCategory,
+    priority: ItemPriority,
+    trust: TrustLevel,
+    awaiting_review: bool,
     score: f64,
     token_estimate: usize,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    // `--smart` ranks items against a token budget instead of grouping them.
+    vec![("prime", one_of(&[schema_for::<PrimeOutput>(), schema_for::<SmartPrimeOutput>()]))]
 }
 
 /// Lazy-init Model2Vec provider for query embedding generation.
@@ -195,7 +338,7 @@ fn get_fast_provider() -> Option<&'static Model2VecProvider> {
 /// Execute the prime command.
 #[allow(clippy::too_many_arguments)]
 pub fn execute(
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     session_id: Option<&str>,
     json: bool,
     include_transcript: bool,
@@ -205,151 +348,62 @@ pub fn execute(
     budget: usize,
     query: Option<&str>,
     decay_days: u32,
+    paths: &[String],
+    subproject: Option<&str>,
+    root: bool,
+    min_trust: Option<TrustLevel>,
+    raw: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-
-    // Resolve session via TTY-keyed status cache
-    let sid = resolve_session_or_suggest(session_id, &storage)?;
-    let session = storage
-        .get_session(&sid)?
-        .ok_or_else(|| Error::SessionNotFound { id: sid })?;
-
-    let project_path = session
-        .project_path
-        .clone()
-        .or_else(|| resolve_project_path(&storage, None).ok())
-        .unwrap_or_else(|| ".".to_string());
-
-    // Git info
-    let git_branch = current_git_branch();
-    let git_status = get_git_status();
+    let storage = db.storage()?;
+    let prepared = prepare(storage, session_id, paths, subproject, root, raw)?;
 
     // Smart mode: scoring pipeline with embedding-powered ranking
     if smart {
+        let Prepared { session, project_path, scopes, categories, git_branch, git_status, sanitizer, due_reminders } =
+            &prepared;
         return execute_smart(
-            &storage, &session, &project_path, &git_branch, &git_status,
+            storage, session, project_path, scopes, categories, git_branch, git_status,
             json, compact, include_transcript, transcript_limit,
-            budget, query, decay_days,
+            budget, query, decay_days, min_trust, *sanitizer, due_reminders,
         );
     }
 
-    // Context items (read-only queries)
-    let all_items = storage.get_context_items(&session.id, None, None, Some(1000))?;
-    let high_priority =
-        storage.get_context_items(&session.id, None, Some("high"), Some(HIGH_PRIORITY_LIMIT))?;
-    let decisions =
-        storage.get_context_items(&session.id, Some("decision"), None, Some(DECISION_LIMIT))?;
-    let reminders =
-        storage.get_context_items(&session.id, Some("reminder"), None, Some(REMINDER_LIMIT))?;
-    let progress =
-        storage.get_context_items(&session.id, Some("progress"), None, Some(PROGRESS_LIMIT))?;
-
-    // Issues
-    let active_issues =
-        storage.list_issues(&project_path, Some("in_progress"), None, Some(READY_ISSUES_LIMIT))?;
-    let ready_issues = storage.get_ready_issues(&project_path, READY_ISSUES_LIMIT)?;
-    let all_open_issues = storage.list_issues(&project_path, None, None, Some(1000))?;
-
-    // Memory
-    let memory_items = storage.list_memory(&project_path, None)?;
-
-    // Transcript (optional, never fails the command)
-    let transcript = if include_transcript {
-        parse_claude_transcripts(&project_path, transcript_limit)
-    } else {
-        None
-    };
-
+    let sections = sections(storage, &prepared, min_trust)?;
+    let transcript = transcript(&prepared, include_transcript, transcript_limit);
     let cmd_ref = build_command_reference();
 
     if json {
-        let output = PrimeOutput {
-            session: SessionInfo {
-                id: session.id.clone(),
-                name: session.name.clone(),
-                description: session.description.clone(),
-                status: session.status.clone(),
-                branch: session.branch.clone(),
-                project_path: session.project_path.clone(),
-            },
-            git: git_branch.as_ref().map(|branch| {
-                let files: Vec<String> = git_status
-                    .as_ref()
-                    .map(|s| {
-                        s.lines()
-                            .take(20)
-                            .map(|l| l.trim().to_string())
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                GitInfo {
-                    branch: branch.clone(),
-                    changed_files: files,
-                }
-            }),
-            context: ContextBlock {
-                high_priority: high_priority.iter().map(to_context_entry).collect(),
-                decisions: decisions.iter().map(to_context_entry).collect(),
-                reminders: reminders.iter().map(to_context_entry).collect(),
-                recent_progress: progress.iter().map(to_context_entry).collect(),
-                total_items: all_items.len(),
-            },
-            issues: IssueBlock {
-                active: active_issues.iter().map(to_issue_summary).collect(),
-                ready: ready_issues.iter().map(to_issue_summary).collect(),
-                total_open: all_open_issues.len(),
-            },
-            memory: memory_items
-                .iter()
-                .take(MEMORY_DISPLAY_LIMIT)
-                .map(|m| MemoryEntry {
-                    key: m.key.clone(),
-                    value: m.value.clone(),
-                    category: m.category.clone(),
-                })
-                .collect(),
-            transcript,
-            command_reference: cmd_ref,
-        };
+        let output = json_output(&prepared, &sections, transcript, cmd_ref);
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else if compact {
         print_compact(
-            &session,
-            &git_branch,
-            &git_status,
-            &high_priority,
-            &decisions,
-            &reminders,
-            &progress,
-            &active_issues,
-            &ready_issues,
-            &all_open_issues,
-            &memory_items,
+            prepared.sanitizer,
+            &prepared.session,
+            &prepared.git_branch,
+            &prepared.git_status,
+            &prepared.due_reminders,
+            &sections.high_priority,
+            &sections.decisions,
+            &sections.reminders,
+            &sections.progress,
+            &prepared.scopes,
             &transcript,
-            all_items.len(),
+            sections.total_items,
             &cmd_ref,
         );
     } else {
         print_full(
-            &session,
-            &git_branch,
-            &git_status,
-            &high_priority,
-            &decisions,
-            &reminders,
-            &progress,
-            &active_issues,
-            &ready_issues,
-            &all_open_issues,
-            &memory_items,
+            &prepared.session,
+            &prepared.git_branch,
+            &prepared.git_status,
+            &prepared.due_reminders,
+            &sections.high_priority,
+            &sections.decisions,
+            &sections.reminders,
+            &sections.progress,
+            &prepared.scopes,
             &transcript,
-            all_items.len(),
+            sections.total_items,
             &cmd_ref,
         );
     }
@@ -357,6 +411,206 @@ pub fn execute(
     Ok(())
 }
 
+/// `sc prime --json` output for a session (the current one when `None`),
+/// for callers that want the primer as a value rather than printed.
+///
+/// # Errors
+///
+/// Returns an error if the session cannot be resolved or a query fails.
+pub(crate) fn json_value(
+    storage: &SqliteStorage,
+    session_id: Option<&str>,
+    include_transcript: bool,
+    transcript_limit: usize,
+    min_trust: Option<TrustLevel>,
+) -> Result<Value> {
+    let prepared = prepare(storage, session_id, &[], None, false, false)?;
+    let sections = sections(storage, &prepared, min_trust)?;
+    let transcript = transcript(&prepared, include_transcript, transcript_limit);
+    Ok(serde_json::to_value(json_output(&prepared, &sections, transcript, build_command_reference()))?)
+}
+
+/// What every prime mode starts from, with stored text already sanitized.
+struct Prepared {
+    session: crate::storage::Session,
+    project_path: String,
+    /// Issues and memory of every path the session spans.
+    scopes: Vec<ProjectScope>,
+    /// Which categories prime includes, and their weights in smart mode.
+    categories: Categories,
+    git_branch: Option<String>,
+    git_status: Option<String>,
+    sanitizer: Sanitizer,
+    /// Reminders of the project that have come due, in every session.
+    due_reminders: Vec<ContextItem>,
+}
+
+fn prepare(
+    storage: &SqliteStorage,
+    session_id: Option<&str>,
+    paths: &[String],
+    subproject: Option<&str>,
+    root: bool,
+    raw: bool,
+) -> Result<Prepared> {
+    // Resolve session via TTY-keyed status cache
+    let sid = resolve_session_or_suggest(session_id, storage)?;
+    let mut session = storage
+        .get_session(&sid)?
+        .ok_or_else(|| Error::SessionNotFound { id: sid })?;
+
+    let project_path = session
+        .project_path
+        .clone()
+        .or_else(|| resolve_project_path(storage, None).ok())
+        .unwrap_or_else(|| ".".to_string());
+
+    // Issues and memory come from every path the session spans
+    let scope_paths = resolve_scope_paths(storage, &session.id, &project_path, paths)?;
+    let mut scopes = load_project_scopes(storage, &scope_paths)?;
+    let subproject = resolve_subproject(storage, &project_path, subproject, root)?;
+    narrow_to_subproject(&mut scopes, &project_path, subproject.as_deref());
+    // Shown memory counts as read (up to the most any output shows), so
+    // `sc memory audit` knows what is still in use
+    let shown: Vec<String> =
+        scopes.iter().flat_map(|s| s.memory.iter().take(MEMORY_DISPLAY_LIMIT)).map(|m| m.id.clone()).collect();
+    super::memory::record_reads(storage, &shown);
+
+    // Everything below that came from the database is untrusted text
+    let sanitizer = Sanitizer::from_config(raw);
+    sanitizer.clean_in_place(&mut session.name);
+    if let Some(description) = &mut session.description {
+        sanitizer.clean_in_place(description);
+    }
+    sanitize_scopes(sanitizer, &mut scopes);
+
+    super::remind::refresh_statusline(storage, &project_path)?;
+    let mut due_reminders =
+        super::remind::due_reminders(storage, &project_path, chrono::Utc::now().timestamp_millis())?;
+    for item in &mut due_reminders {
+        sanitize_item(sanitizer, item);
+    }
+
+    let categories = storage.get_session_categories(&session.id)?;
+    Ok(Prepared {
+        session,
+        project_path,
+        scopes,
+        categories,
+        git_branch: current_git_branch(),
+        git_status: get_git_status(),
+        sanitizer,
+        due_reminders,
+    })
+}
+
+/// Context items normal (not smart) prime shows, by section.
+struct Sections {
+    high_priority: Vec<ContextItem>,
+    decisions: Vec<ContextItem>,
+    reminders: Vec<ContextItem>,
+    progress: Vec<ContextItem>,
+    total_items: usize,
+}
+
+/// Read each section's items, count the reads and sanitize them.
+fn sections(storage: &SqliteStorage, prepared: &Prepared, min_trust: Option<TrustLevel>) -> Result<Sections> {
+    let session_id = &prepared.session.id;
+    let categories = &prepared.categories;
+
+    // Context items (read-only queries)
+    let total_items = storage.get_context_items(session_id, None, None, Some(1000))?.len();
+    // Fetch every candidate and keep the newest that pass review and trust
+    let admission = Admission::new(min_trust);
+    // Scheduled reminders show up on their own once due
+    let admitted = |mut items: Vec<ContextItem>, limit: u32| {
+        items.retain(|item| item.due_at.is_none() && admission.admits(item));
+        items.truncate(limit as usize);
+        items
+    };
+    let mut high_priority =
+        admitted(storage.get_context_items(session_id, None, Some(ItemPriority::High), Some(1000))?, HIGH_PRIORITY_LIMIT);
+    high_priority.retain(|item| categories.in_prime(&item.category));
+    let section = |category: ItemCategory, limit| -> Result<Vec<ContextItem>> {
+        if !categories.in_prime(&category) {
+            return Ok(Vec::new());
+        }
+        let items = storage.get_context_items(session_id, Some(category), None, Some(1000))?;
+        Ok(admitted(items, limit))
+    };
+    let mut sections = Sections {
+        decisions: section(ItemCategory::Decision, DECISION_LIMIT)?,
+        reminders: section(ItemCategory::Reminder, REMINDER_LIMIT)?,
+        progress: section(ItemCategory::Progress, PROGRESS_LIMIT)?,
+        high_priority,
+        total_items,
+    };
+    let all = [&mut sections.high_priority, &mut sections.decisions, &mut sections.reminders, &mut sections.progress];
+    let mut shown: Vec<String> = all.iter().flat_map(|items| items.iter()).map(|i| i.id.clone()).collect();
+    shown.sort();
+    shown.dedup();
+    record_reads(storage, &shown);
+    for item in all.into_iter().flatten() {
+        sanitize_item(prepared.sanitizer, item);
+    }
+    Ok(sections)
+}
+
+/// Recent transcript entries, when asked for (never fails the command).
+fn transcript(prepared: &Prepared, include: bool, limit: usize) -> Option<TranscriptBlock> {
+    let mut transcript = if include { parse_claude_transcripts(&prepared.project_path, limit) } else { None };
+    sanitize_transcript(prepared.sanitizer, transcript.as_mut());
+    transcript
+}
+
+fn json_output(
+    prepared: &Prepared,
+    sections: &Sections,
+    transcript: Option<TranscriptBlock>,
+    command_reference: Vec<CmdRef>,
+) -> PrimeOutput {
+    let session = &prepared.session;
+    PrimeOutput {
+        due_reminders: prepared.due_reminders.iter().map(to_due_reminder_entry).collect(),
+        session: SessionInfo {
+            id: session.id.clone(),
+            name: session.name.clone(),
+            description: session.description.clone(),
+            status: session.status,
+            branch: session.branch.clone(),
+            project_path: session.project_path.clone(),
+        },
+        git: prepared.git_branch.as_ref().map(|branch| {
+            let files: Vec<String> = prepared
+                .git_status
+                .as_ref()
+                .map(|s| {
+                    s.lines()
+                        .take(20)
+                        .map(|l| l.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            GitInfo {
+                branch: branch.clone(),
+                changed_files: files,
+            }
+        }),
+        context: ContextBlock {
+            high_priority: sections.high_priority.iter().map(to_context_entry).collect(),
+            decisions: sections.decisions.iter().map(to_context_entry).collect(),
+            reminders: sections.reminders.iter().map(to_context_entry).collect(),
+            recent_progress: sections.progress.iter().map(to_context_entry).collect(),
+            total_items: sections.total_items,
+        },
+        issues: combined_issue_block(&prepared.scopes),
+        memory: combined_memory(&prepared.scopes),
+        projects: project_sections(&prepared.scopes),
+        transcript,
+        command_reference,
+    }
+}
+
 // ============================================================================
 // Smart Prime Pipeline
 // ============================================================================
@@ -366,6 +620,8 @@ fn execute_smart(
     storage: &SqliteStorage,
     session: &crate::storage::Session,
     project_path: &str,
+    scopes: &[ProjectScope],
+    categories: &Categories,
     git_branch: &Option<String>,
     git_status: &Option<String>,
     json: bool,
@@ -375,6 +631,9 @@ fn execute_smart(
     budget: usize,
     query: Option<&str>,
     decay_days: u32,
+    min_trust: Option<TrustLevel>,
+    sanitizer: Sanitizer,
+    due_reminders: &[ContextItem],
 ) -> Result<()> {
     let now_ms = chrono::Utc::now().timestamp_millis();
     let half_life = decay_days as f64;
@@ -395,18 +654,27 @@ fn execute_smart(
         mmr_lambda: MMR_LAMBDA,
     };
 
-    // Step 2: Score each item
+    // Items read often and lately count for more
+    let relevance = crate::config::relevance();
+    let ids: Vec<String> = items_with_embeddings.iter().map(|(item, _)| item.id.clone()).collect();
+    let access = storage.get_item_access(&ids)?;
+
+    // Step 2: Score each item (categories left out of prime, and items
+    // turned away by review or --min-trust, are skipped)
+    let admission = Admission::new(min_trust);
     let mut scored: Vec<ScoredItem> = items_with_embeddings
         .into_iter()
+        .filter(|(item, _)| item.due_at.is_none() && categories.in_prime(&item.category) && admission.admits(item))
         .map(|(item, embedding)| {
             let td = temporal_decay(item.updated_at, now_ms, config.decay_half_life_days);
-            let pw = priority_weight(&item.priority);
-            let cw = category_weight(&item.category);
+            let pw = priority_weight(item.priority.as_str());
+            let cw = categories.prime_weight(&item.category);
             let sb = semantic_boost(
                 embedding.as_deref(),
                 config.query_embedding.as_deref(),
             );
-            let score = td * pw * cw * sb;
+            let rb = relevance.boost(access.get(&item.id).copied().unwrap_or_default(), now_ms);
+            let score = td * pw * cw * sb * rb;
             let token_estimate = estimate_tokens(&item.key, &item.value);
 
             ScoredItem { item, score, token_estimate, embedding }
@@ -423,7 +691,11 @@ fn execute_smart(
     }
 
     // Step 5: Greedy token-budget packing
-    let packed = pack_to_budget(scored, config.budget);
+    let mut packed = pack_to_budget(scored, config.budget);
+    record_reads(storage, &packed.iter().map(|s| s.item.id.clone()).collect::<Vec<_>>());
+    for s in &mut packed {
+        sanitize_item(sanitizer, &mut s.item);
+    }
     let selected_items = packed.len();
     let tokens_used: usize = packed.iter().map(|s| s.token_estimate).sum::<usize>() + HEADER_TOKEN_RESERVE;
 
@@ -437,25 +709,21 @@ fn execute_smart(
         query_boosted,
     };
 
-    // Fetch shared data (issues, memory, transcript)
-    let active_issues =
-        storage.list_issues(project_path, Some("in_progress"), None, Some(READY_ISSUES_LIMIT))?;
-    let ready_issues = storage.get_ready_issues(project_path, READY_ISSUES_LIMIT)?;
-    let all_open_issues = storage.list_issues(project_path, None, None, Some(1000))?;
-    let memory_items = storage.list_memory(project_path, None)?;
-    let transcript = if include_transcript {
+    // Fetch shared data (transcript; issues and memory are in `scopes`)
+    let mut transcript = if include_transcript {
         parse_claude_transcripts(project_path, transcript_limit)
     } else {
         None
     };
+    sanitize_transcript(sanitizer, transcript.as_mut());
     let cmd_ref = build_command_reference();
 
     if json {
-        output_smart_json(&stats, &packed, &active_issues, &ready_issues, &all_open_issues, &memory_items, &transcript, &cmd_ref)?;
+        output_smart_json(&stats, &packed, due_reminders, scopes, &transcript, &cmd_ref)?;
     } else if compact {
-        output_smart_compact(session, git_branch, &stats, &packed, &active_issues, &ready_issues, &all_open_issues, &memory_items, &transcript, &cmd_ref);
+        output_smart_compact(sanitizer, session, git_branch, &stats, &packed, due_reminders, scopes, &transcript, &cmd_ref);
     } else {
-        output_smart_terminal(session, git_branch, git_status, &stats, &packed, &active_issues, &ready_issues, &all_open_issues, &memory_items, &transcript, &cmd_ref);
+        output_smart_terminal(session, git_branch, git_status, &stats, &packed, due_reminders, scopes, &transcript, &cmd_ref);
     }
 
     Ok(())
@@ -487,17 +755,6 @@ fn priority_weight(priority: &str) -> f64 {
     }
 }
 
-/// Weight by category importance.
-fn category_weight(category: &str) -> f64 {
-    match category {
-        "decision" => 2.0,
-        "reminder" => 1.5,
-        "progress" => 1.0,
-        "note" => 0.5,
-        _ => 1.0,
-    }
-}
-
 /// Semantic boost when a query embedding is provided.
 ///
 /// sim=1.0 -> 2.5x boost, sim=0.0 -> 1.0x (neutral), sim=-1.0 -> penalty (0.5x minimum via clamp)
@@ -653,10 +910,8 @@ fn pack_to_budget(items: Vec<ScoredItem>, budget: usize) -> Vec<ScoredItem> {
 fn output_smart_json(
     stats: &SmartPrimeStats,
     items: &[ScoredItem],
-    active_issues: &[crate::storage::Issue],
-    ready_issues: &[crate::storage::Issue],
-    all_open: &[crate::storage::Issue],
-    memory: &[crate::storage::Memory],
+    due_reminders: &[ContextItem],
+    scopes: &[ProjectScope],
     transcript: &Option<TranscriptBlock>,
     cmd_ref: &[CmdRef],
 ) -> Result<()> {
@@ -670,31 +925,23 @@ fn output_smart_json(
             mmr_applied: stats.mmr_applied,
             query_boosted: stats.query_boosted,
         },
+        due_reminders: due_reminders.iter().map(to_due_reminder_entry).collect(),
         scored_context: items
             .iter()
             .map(|s| ScoredContextEntry {
                 key: s.item.key.clone(),
                 value: s.item.value.clone(),
                 category: s.item.category.clone(),
-                priority: s.item.priority.clone(),
+                priority: s.item.priority,
+                trust: s.item.trust,
+                awaiting_review: s.item.awaits_review(),
                 score: (s.score * 100.0).round() / 100.0, // 2 decimal places
                 token_estimate: s.token_estimate,
             })
             .collect(),
-        issues: IssueBlock {
-            active: active_issues.iter().map(to_issue_summary).collect(),
-            ready: ready_issues.iter().map(to_issue_summary).collect(),
-            total_open: all_open.len(),
-        },
-        memory: memory
-            .iter()
-            .take(MEMORY_DISPLAY_LIMIT)
-            .map(|m| MemoryEntry {
-                key: m.key.clone(),
-                value: m.value.clone(),
-                category: m.category.clone(),
-            })
-            .collect(),
+        issues: combined_issue_block(scopes),
+        memory: combined_memory(scopes),
+        projects: project_sections(scopes),
         transcript: transcript.clone(),
         command_reference: cmd_ref.to_vec(),
     };
@@ -704,14 +951,13 @@ fn output_smart_json(
 
 #[allow(clippy::too_many_arguments)]
 fn output_smart_compact(
+    sanitizer: Sanitizer,
     session: &crate::storage::Session,
     git_branch: &Option<String>,
     stats: &SmartPrimeStats,
     items: &[ScoredItem],
-    active_issues: &[crate::storage::Issue],
-    ready_issues: &[crate::storage::Issue],
-    all_open: &[crate::storage::Issue],
-    memory: &[crate::storage::Memory],
+    due_reminders: &[ContextItem],
+    scopes: &[ProjectScope],
     transcript: &Option<TranscriptBlock>,
     cmd_ref: &[CmdRef],
 ) {
@@ -728,54 +974,29 @@ fn output_smart_compact(
         stats.selected_items,
         if stats.mmr_applied { "yes" } else { "no" }
     );
+    if let Some(preamble) = sanitizer.preamble() {
+        println!("{preamble}");
+    }
     println!();
 
-    if !items.is_empty() {
-        println!("## Context (ranked by relevance)");
-        for s in items {
-            println!(
+    print_compact_due_reminders(sanitizer, session, due_reminders);
+    print_fenced(
+        sanitizer,
+        "## Context (ranked by relevance)",
+        &format!("context-items session={}", session.id),
+        items.iter().map(|s| {
+            format!(
                 "- [{:.2}] {}: {} [{}/{}]",
                 s.score,
-                s.item.key,
+                item_label(&s.item),
                 truncate(&s.item.value, 100),
                 s.item.category,
                 s.item.priority
-            );
-        }
-        println!();
-    }
-
-    if !active_issues.is_empty() || !ready_issues.is_empty() {
-        println!("## Issues ({} open)", all_open.len());
-        for issue in active_issues {
-            let id = issue.short_id.as_deref().unwrap_or("??");
-            println!(
-                "- [{}] {} ({}/P{})",
-                id, issue.title, issue.status, issue.priority
-            );
-        }
-        for issue in ready_issues.iter().take(5) {
-            let id = issue.short_id.as_deref().unwrap_or("??");
-            println!("- [{}] {} (ready/P{})", id, issue.title, issue.priority);
-        }
-        println!();
-    }
-
-    if !memory.is_empty() {
-        println!("## Memory");
-        for item in memory.iter().take(10) {
-            println!("- {} [{}]: {}", item.key, item.category, truncate(&item.value, 80));
-        }
-        println!();
-    }
+            )
+        }),
+    );
 
-    if let Some(t) = transcript {
-        println!("## Recent Transcripts");
-        for entry in &t.entries {
-            println!("- {}", truncate(&entry.summary, 120));
-        }
-        println!();
-    }
+    print_compact_scopes(sanitizer, scopes, transcript.as_ref());
 
     println!("## Quick Reference");
     for c in cmd_ref {
@@ -790,10 +1011,8 @@ fn output_smart_terminal(
     git_status: &Option<String>,
     stats: &SmartPrimeStats,
     items: &[ScoredItem],
-    active_issues: &[crate::storage::Issue],
-    ready_issues: &[crate::storage::Issue],
-    all_open: &[crate::storage::Issue],
-    memory: &[crate::storage::Memory],
+    due_reminders: &[ContextItem],
+    scopes: &[ProjectScope],
     transcript: &Option<TranscriptBlock>,
     cmd_ref: &[CmdRef],
 ) {
@@ -805,6 +1024,7 @@ fn output_smart_terminal(
         "━━━ SaveContext Smart Prime ━━━━━━━━━━━━━━━━━━━━━━━━━━━━".magenta().bold()
     );
     println!();
+    print_due_reminders(due_reminders);
 
     // Session
     println!("{}", "Session".cyan().bold());
@@ -857,7 +1077,7 @@ fn output_smart_terminal(
             println!(
                 "  {} {} {} {}",
                 score_str.yellow(),
-                s.item.key.bold(),
+                item_label(&s.item).bold(),
                 meta.dimmed(),
                 truncate(&s.item.value, 60)
             );
@@ -865,48 +1085,51 @@ fn output_smart_terminal(
         println!();
     }
 
-    // Issues
-    if !active_issues.is_empty() || !ready_issues.is_empty() {
-        println!(
-            "{} ({} open)",
-            "Issues".cyan().bold(),
-            all_open.len()
-        );
-        for issue in active_issues {
-            let id = issue.short_id.as_deref().unwrap_or("??");
-            println!(
-                "    {} {} {} {}",
-                id.cyan(),
-                issue.title,
-                format!("[{}]", issue.issue_type).dimmed(),
-                format!("P{}", issue.priority).dimmed()
-            );
-        }
-        for issue in ready_issues.iter().take(5) {
-            let id = issue.short_id.as_deref().unwrap_or("??");
+    // Issues and memory, one section per project path
+    for scope in scopes {
+        let label = scope_label(scope, scopes.len());
+        if !scope.active_issues.is_empty() || !scope.ready_issues.is_empty() {
             println!(
-                "    {} {} {} {}",
-                id.dimmed(),
-                issue.title,
-                format!("[{}]", issue.issue_type).dimmed(),
-                format!("P{}", issue.priority).dimmed()
+                "{}{label} ({} open)",
+                "Issues".cyan().bold(),
+                scope.all_open.len()
             );
+            for issue in &scope.active_issues {
+                let id = issue.short_id.as_deref().unwrap_or("??");
+                println!(
+                    "    {} {} {} {}",
+                    id.cyan(),
+                    issue.title,
+                    format!("[{}]", issue.issue_type).dimmed(),
+                    format!("P{}", issue.priority).dimmed()
+                );
+            }
+            for issue in scope.ready_issues.iter().take(5) {
+                let id = issue.short_id.as_deref().unwrap_or("??");
+                println!(
+                    "    {} {} {} {}",
+                    id.dimmed(),
+                    issue.title,
+                    format!("[{}]", issue.issue_type).dimmed(),
+                    format!("P{}", issue.priority).dimmed()
+                );
+            }
+            println!();
         }
-        println!();
-    }
 
-    // Memory
-    if !memory.is_empty() {
-        println!("{}", "Project Memory".cyan().bold());
-        for item in memory.iter().take(10) {
-            println!(
-                "  {} {} {}",
-                item.key.bold(),
-                format!("[{}]", item.category).dimmed(),
-                truncate(&item.value, 60)
-            );
+        // Memory
+        if !scope.memory.is_empty() {
+            println!("{}{label}", "Project Memory".cyan().bold());
+            for item in scope.memory.iter().take(10) {
+                println!(
+                    "  {} {} {}",
+                    item.key.bold(),
+                    format!("[{}]", item.category).dimmed(),
+                    truncate(&item.value, 60)
+                );
+            }
+            println!();
         }
-        println!();
     }
 
     // Transcript
@@ -948,13 +1171,7 @@ fn output_smart_terminal(
 /// Each line is a JSON object; lines with `"type": "summary"` contain
 /// conversation summaries from previous sessions.
 fn parse_claude_transcripts(project_path: &str, limit: usize) -> Option<TranscriptBlock> {
-    let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
-    let encoded_path = encode_project_path(project_path);
-    let transcript_dir = home.join(".claude").join("projects").join(&encoded_path);
-
-    if !transcript_dir.exists() {
-        return None;
-    }
+    let transcript_dir = claude_transcript_dir(project_path)?;
 
     // Find .jsonl files, sorted by modification time (most recent first)
     let mut jsonl_files: Vec<_> = fs::read_dir(&transcript_dir)
@@ -1021,6 +1238,16 @@ fn parse_claude_transcripts(project_path: &str, limit: usize) -> Option<Transcri
     })
 }
 
+/// Claude Code's transcript directory for a project, if it exists.
+pub(crate) fn claude_transcript_dir(project_path: &str) -> Option<std::path::PathBuf> {
+    let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
+    let transcript_dir = home
+        .join(".claude")
+        .join("projects")
+        .join(encode_project_path(project_path));
+    transcript_dir.exists().then_some(transcript_dir)
+}
+
 /// Encode a project path for Claude Code's directory naming.
 ///
 /// Replaces `/` with `-` to match Claude Code's convention:
@@ -1078,16 +1305,190 @@ fn build_command_reference() -> Vec<CmdRef> {
     ]
 }
 
+// ============================================================================
+// Project Scopes (multi-path sessions)
+// ============================================================================
+
+/// Project paths to prime from: every path the session spans (primary
+/// first), optionally restricted to `filter`.
+fn resolve_scope_paths(
+    storage: &SqliteStorage,
+    session_id: &str,
+    primary: &str,
+    filter: &[String],
+) -> Result<Vec<String>> {
+    let mut paths = vec![primary.to_string()];
+    for path in storage.get_session_paths(session_id).unwrap_or_default() {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    if filter.is_empty() {
+        return Ok(paths);
+    }
+
+    let wanted: Vec<String> = filter.iter().map(|p| normalize_scope_path(p)).collect();
+    let selected: Vec<String> = paths
+        .iter()
+        .filter(|p| wanted.contains(&normalize_scope_path(p)))
+        .cloned()
+        .collect();
+
+    if selected.is_empty() {
+        return Err(Error::InvalidArgument(format!(
+            "None of --paths belong to this session. Session paths: {}",
+            paths.join(", ")
+        )));
+    }
+    Ok(selected)
+}
+
+/// Canonicalize a path for comparison, falling back to trimming a
+/// trailing separator when the path doesn't exist locally.
+fn normalize_scope_path(path: &str) -> String {
+    fs::canonicalize(path).map_or_else(
+        |_| path.trim_end_matches('/').to_string(),
+        |p| p.to_string_lossy().to_string(),
+    )
+}
+
+fn load_project_scopes(storage: &SqliteStorage, paths: &[String]) -> Result<Vec<ProjectScope>> {
+    paths
+        .iter()
+        .map(|path| {
+            let name = storage.get_project_by_path(path)?.map_or_else(
+                || {
+                    std::path::Path::new(path)
+                        .file_name()
+                        .map_or_else(|| path.clone(), |n| n.to_string_lossy().to_string())
+                },
+                |p| p.name,
+            );
+            Ok(ProjectScope {
+                project_path: path.clone(),
+                name,
+                active_issues: storage.list_issues(path, Some("in_progress"), None, Some(READY_ISSUES_LIMIT))?,
+                ready_issues: storage.get_ready_issues(path, READY_ISSUES_LIMIT)?,
+                all_open: storage.list_issues(path, None, None, Some(1000))?,
+                memory: storage.list_memory(path, None)?,
+            })
+        })
+        .collect()
+}
+
+/// Leave out of the session's own project the issues and memory of other
+/// sub-projects than the one worked in.
+fn narrow_to_subproject(scopes: &mut [ProjectScope], project_path: &str, subproject: Option<&str>) {
+    if subproject.is_none() {
+        return;
+    }
+    for scope in scopes.iter_mut().filter(|s| s.project_path == project_path) {
+        let keep = |issue: &Issue| in_subproject(issue.subproject.as_deref(), subproject);
+        scope.active_issues.retain(keep);
+        scope.ready_issues.retain(keep);
+        scope.all_open.retain(keep);
+        scope.memory.retain(|m| in_subproject(m.subproject.as_deref(), subproject));
+    }
+}
+
+/// Heading suffix naming the project, empty for single-project sessions.
+fn scope_label(scope: &ProjectScope, scope_count: usize) -> String {
+    if scope_count > 1 {
+        format!(" — {}", scope.name)
+    } else {
+        String::new()
+    }
+}
+
+fn combined_issue_block(scopes: &[ProjectScope]) -> IssueBlock {
+    IssueBlock {
+        active: scopes.iter().flat_map(|s| &s.active_issues).map(to_issue_summary).collect(),
+        ready: scopes.iter().flat_map(|s| &s.ready_issues).map(to_issue_summary).collect(),
+        total_open: scopes.iter().map(|s| s.all_open.len()).sum(),
+    }
+}
+
+fn combined_memory(scopes: &[ProjectScope]) -> Vec<MemoryEntry> {
+    scopes
+        .iter()
+        .flat_map(|s| &s.memory)
+        .take(MEMORY_DISPLAY_LIMIT)
+        .map(to_memory_entry)
+        .collect()
+}
+
+/// Per-project sections for JSON output; empty unless the session spans
+/// more than one project.
+fn project_sections(scopes: &[ProjectScope]) -> Vec<ProjectSection> {
+    if scopes.len() < 2 {
+        return Vec::new();
+    }
+    scopes
+        .iter()
+        .map(|scope| ProjectSection {
+            project_path: scope.project_path.clone(),
+            name: scope.name.clone(),
+            issues: combined_issue_block(std::slice::from_ref(scope)),
+            memory: scope.memory.iter().take(MEMORY_DISPLAY_LIMIT).map(to_memory_entry).collect(),
+        })
+        .collect()
+}
+
 // ============================================================================
 // Converters
 // ============================================================================
 
+fn to_memory_entry(m: &Memory) -> MemoryEntry {
+    MemoryEntry {
+        key: m.key.clone(),
+        value: m.value.clone(),
+        category: m.category,
+    }
+}
+
+/// Which items prime may include: never rejected decisions, unreviewed
+/// agent decisions unless `prime.unreviewed` is `exclude`, and only items
+/// trusted at least `--min-trust`.
+#[derive(Clone, Copy)]
+struct Admission {
+    min_trust: Option<TrustLevel>,
+    exclude_unreviewed: bool,
+}
+
+impl Admission {
+    fn new(min_trust: Option<TrustLevel>) -> Self {
+        Self { min_trust, exclude_unreviewed: crate::config::prime_excludes_unreviewed() }
+    }
+
+    fn admits(self, item: &ContextItem) -> bool {
+        item.review_status != ReviewStatus::Rejected
+            && !(self.exclude_unreviewed && item.awaits_review())
+            && self.min_trust.is_none_or(|min| item.trust.at_least(min))
+    }
+}
+
+/// An item's key, flagged when it is an agent decision awaiting review.
+fn item_label(item: &ContextItem) -> String {
+    if item.awaits_review() {
+        format!("{} (unreviewed)", item.key)
+    } else {
+        item.key.clone()
+    }
+}
+
+fn to_due_reminder_entry(item: &ContextItem) -> DueReminderEntry {
+    DueReminderEntry { key: item.key.clone(), value: item.value.clone(), due_at: item.due_at.unwrap_or_default() }
+}
+
 fn to_context_entry(item: &crate::storage::ContextItem) -> ContextEntry {
     ContextEntry {
         key: item.key.clone(),
         value: item.value.clone(),
         category: item.category.clone(),
-        priority: item.priority.clone(),
+        priority: item.priority,
+        trust: item.trust,
+        awaiting_review: item.awaits_review(),
     }
 }
 
@@ -1097,7 +1498,7 @@ fn to_issue_summary(issue: &crate::storage::Issue) -> IssueSummary {
         title: issue.title.clone(),
         status: issue.status.clone(),
         priority: issue.priority,
-        issue_type: issue.issue_type.clone(),
+        issue_type: issue.issue_type,
     }
 }
 
@@ -1110,14 +1511,12 @@ fn print_full(
     session: &crate::storage::Session,
     git_branch: &Option<String>,
     git_status: &Option<String>,
+    due_reminders: &[ContextItem],
     high_priority: &[crate::storage::ContextItem],
     decisions: &[crate::storage::ContextItem],
     reminders: &[crate::storage::ContextItem],
     progress: &[crate::storage::ContextItem],
-    active_issues: &[crate::storage::Issue],
-    ready_issues: &[crate::storage::Issue],
-    all_open: &[crate::storage::Issue],
-    memory: &[crate::storage::Memory],
+    scopes: &[ProjectScope],
     transcript: &Option<TranscriptBlock>,
     total_items: usize,
     cmd_ref: &[CmdRef],
@@ -1130,6 +1529,7 @@ fn print_full(
         "━━━ SaveContext Prime ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".magenta().bold()
     );
     println!();
+    print_due_reminders(due_reminders);
 
     // Session
     println!("{}", "Session".cyan().bold());
@@ -1163,7 +1563,7 @@ fn print_full(
             println!(
                 "  {} {} {}",
                 "•".red(),
-                item.key,
+                item_label(item),
                 format!("[{}]", item.category).dimmed()
             );
             println!("    {}", truncate(&item.value, 80));
@@ -1175,7 +1575,7 @@ fn print_full(
     if !decisions.is_empty() {
         println!("{}", "Key Decisions".yellow().bold());
         for item in decisions.iter().take(5) {
-            println!("  {} {}", "•".yellow(), item.key);
+            println!("  {} {}", "•".yellow(), item_label(item));
             println!("    {}", truncate(&item.value, 80));
         }
         println!();
@@ -1201,56 +1601,59 @@ fn print_full(
         println!();
     }
 
-    // Issues
-    if !active_issues.is_empty() || !ready_issues.is_empty() {
-        println!(
-            "{} ({} open)",
-            "Issues".cyan().bold(),
-            all_open.len()
-        );
+    // Issues and memory, one section per project path
+    for scope in scopes {
+        let label = scope_label(scope, scopes.len());
+        if !scope.active_issues.is_empty() || !scope.ready_issues.is_empty() {
+            println!(
+                "{}{label} ({} open)",
+                "Issues".cyan().bold(),
+                scope.all_open.len()
+            );
 
-        if !active_issues.is_empty() {
-            println!("  {}", "In Progress:".bold());
-            for issue in active_issues {
-                let id = issue.short_id.as_deref().unwrap_or("??");
-                println!(
-                    "    {} {} {} {}",
-                    id.cyan(),
-                    issue.title,
-                    format!("[{}]", issue.issue_type).dimmed(),
-                    format!("P{}", issue.priority).dimmed()
-                );
+            if !scope.active_issues.is_empty() {
+                println!("  {}", "In Progress:".bold());
+                for issue in &scope.active_issues {
+                    let id = issue.short_id.as_deref().unwrap_or("??");
+                    println!(
+                        "    {} {} {} {}",
+                        id.cyan(),
+                        issue.title,
+                        format!("[{}]", issue.issue_type).dimmed(),
+                        format!("P{}", issue.priority).dimmed()
+                    );
+                }
+            }
+
+            if !scope.ready_issues.is_empty() {
+                println!("  {}", "Ready:".bold());
+                for issue in scope.ready_issues.iter().take(5) {
+                    let id = issue.short_id.as_deref().unwrap_or("??");
+                    println!(
+                        "    {} {} {} {}",
+                        id.dimmed(),
+                        issue.title,
+                        format!("[{}]", issue.issue_type).dimmed(),
+                        format!("P{}", issue.priority).dimmed()
+                    );
+                }
             }
+            println!();
         }
 
-        if !ready_issues.is_empty() {
-            println!("  {}", "Ready:".bold());
-            for issue in ready_issues.iter().take(5) {
-                let id = issue.short_id.as_deref().unwrap_or("??");
+        // Memory
+        if !scope.memory.is_empty() {
+            println!("{}{label}", "Project Memory".cyan().bold());
+            for item in scope.memory.iter().take(10) {
                 println!(
-                    "    {} {} {} {}",
-                    id.dimmed(),
-                    issue.title,
-                    format!("[{}]", issue.issue_type).dimmed(),
-                    format!("P{}", issue.priority).dimmed()
+                    "  {} {} {}",
+                    item.key.bold(),
+                    format!("[{}]", item.category).dimmed(),
+                    truncate(&item.value, 60)
                 );
             }
+            println!();
         }
-        println!();
-    }
-
-    // Memory
-    if !memory.is_empty() {
-        println!("{}", "Project Memory".cyan().bold());
-        for item in memory.iter().take(10) {
-            println!(
-                "  {} {} {}",
-                item.key.bold(),
-                format!("[{}]", item.category).dimmed(),
-                truncate(&item.value, 60)
-            );
-        }
-        println!();
     }
 
     // Transcript
@@ -1285,17 +1688,16 @@ fn print_full(
 
 #[allow(clippy::too_many_arguments)]
 fn print_compact(
+    sanitizer: Sanitizer,
     session: &crate::storage::Session,
     git_branch: &Option<String>,
     _git_status: &Option<String>,
+    due_reminders: &[ContextItem],
     high_priority: &[crate::storage::ContextItem],
     decisions: &[crate::storage::ContextItem],
     reminders: &[crate::storage::ContextItem],
     _progress: &[crate::storage::ContextItem],
-    active_issues: &[crate::storage::Issue],
-    ready_issues: &[crate::storage::Issue],
-    all_open: &[crate::storage::Issue],
-    memory: &[crate::storage::Memory],
+    scopes: &[ProjectScope],
     transcript: &Option<TranscriptBlock>,
     total_items: usize,
     cmd_ref: &[CmdRef],
@@ -1307,79 +1709,164 @@ fn print_compact(
         print!(" | Branch: {branch}");
     }
     println!(" | {total_items} context items");
+    if let Some(preamble) = sanitizer.preamble() {
+        println!("{preamble}");
+    }
     println!();
 
-    if !high_priority.is_empty() {
-        println!("## High Priority");
-        for item in high_priority.iter().take(5) {
-            println!(
-                "- {}: {} [{}]",
-                item.key,
-                truncate(&item.value, 100),
-                item.category
-            );
-        }
-        println!();
-    }
+    print_compact_due_reminders(sanitizer, session, due_reminders);
+    let items = format!("context-items session={}", session.id);
+    print_fenced(
+        sanitizer,
+        "## High Priority",
+        &items,
+        high_priority
+            .iter()
+            .take(5)
+            .map(|item| format!("- {}: {} [{}]", item_label(item), truncate(&item.value, 100), item.category)),
+    );
+    print_fenced(
+        sanitizer,
+        "## Decisions",
+        &items,
+        decisions.iter().take(5).map(|item| format!("- {}: {}", item_label(item), truncate(&item.value, 100))),
+    );
+    print_fenced(
+        sanitizer,
+        "## Reminders",
+        &items,
+        reminders.iter().take(5).map(|item| format!("- {}: {}", item.key, truncate(&item.value, 100))),
+    );
 
-    if !decisions.is_empty() {
-        println!("## Decisions");
-        for item in decisions.iter().take(5) {
-            println!("- {}: {}", item.key, truncate(&item.value, 100));
-        }
-        println!();
-    }
+    print_compact_scopes(sanitizer, scopes, transcript.as_ref());
 
-    if !reminders.is_empty() {
-        println!("## Reminders");
-        for item in reminders.iter().take(5) {
-            println!("- {}: {}", item.key, truncate(&item.value, 100));
-        }
-        println!();
+    println!("## Quick Reference");
+    for c in cmd_ref {
+        println!("- `{}` — {}", c.cmd, c.desc);
     }
+}
 
-    if !active_issues.is_empty() || !ready_issues.is_empty() {
-        println!("## Issues ({} open)", all_open.len());
-        for issue in active_issues {
+/// Issues, memory and transcripts in compact output, shared by plain and
+/// smart prime.
+fn print_compact_scopes(sanitizer: Sanitizer, scopes: &[ProjectScope], transcript: Option<&TranscriptBlock>) {
+    // Issues and memory, one section per project path
+    for scope in scopes {
+        let label = scope_label(scope, scopes.len());
+        let active = scope.active_issues.iter().map(|issue| {
             let id = issue.short_id.as_deref().unwrap_or("??");
-            println!(
-                "- [{}] {} ({}/P{})",
-                id, issue.title, issue.status, issue.priority
-            );
-        }
-        for issue in ready_issues.iter().take(5) {
+            format!("- [{}] {} ({}/P{})", id, issue.title, issue.status, issue.priority)
+        });
+        let ready = scope.ready_issues.iter().take(5).map(|issue| {
             let id = issue.short_id.as_deref().unwrap_or("??");
-            println!("- [{}] {} (ready/P{})", id, issue.title, issue.priority);
-        }
-        println!();
+            format!("- [{}] {} (ready/P{})", id, issue.title, issue.priority)
+        });
+        print_fenced(
+            sanitizer,
+            &format!("## Issues{label} ({} open)", scope.all_open.len()),
+            &format!("issues project={}", scope.project_path),
+            active.chain(ready),
+        );
+        print_fenced(
+            sanitizer,
+            &format!("## Memory{label}"),
+            &format!("memory project={}", scope.project_path),
+            scope
+                .memory
+                .iter()
+                .take(10)
+                .map(|item| format!("- {} [{}]: {}", item.key, item.category, truncate(&item.value, 80))),
+        );
     }
 
-    if !memory.is_empty() {
-        println!("## Memory");
-        for item in memory.iter().take(10) {
-            println!("- {} [{}]: {}", item.key, item.category, truncate(&item.value, 80));
-        }
-        println!();
+    if let Some(t) = transcript {
+        print_fenced(
+            sanitizer,
+            "## Recent Transcripts",
+            &format!("transcripts dir={}", t.source),
+            t.entries.iter().map(|entry| format!("- {}", truncate(&entry.summary, 120))),
+        );
     }
+}
 
-    if let Some(t) = transcript {
-        println!("## Recent Transcripts");
-        for entry in &t.entries {
-            println!("- {}", truncate(&entry.summary, 120));
-        }
-        println!();
+/// A compact section of stored text: `heading`, then `lines` in a block
+/// fenced as coming from `source` when sanitizing. Nothing without lines.
+/// Due reminders, ahead of everything else in terminal prime.
+fn print_due_reminders(due_reminders: &[ContextItem]) {
+    use colored::Colorize;
+
+    if due_reminders.is_empty() {
+        return;
+    }
+    println!("{}", format!("Due Reminders ({})", due_reminders.len()).red().bold());
+    for item in due_reminders {
+        let due = super::remind::format_due(item.due_at.unwrap_or_default());
+        println!("  ! {} {}: {}", item.key.bold(), format!("(due {due})").dimmed(), truncate(&item.value, 100));
     }
+    println!("  {}", "Clear one with `sc remind done <key>`".dimmed());
+    println!();
+}
 
-    println!("## Quick Reference");
-    for c in cmd_ref {
-        println!("- `{}` — {}", c.cmd, c.desc);
+/// Due reminders, ahead of everything else in compact prime.
+fn print_compact_due_reminders(sanitizer: Sanitizer, session: &crate::storage::Session, due_reminders: &[ContextItem]) {
+    print_fenced(
+        sanitizer,
+        "## Due Reminders (clear with `sc remind done <key>`)",
+        &format!("reminders project={}", session.project_path.as_deref().unwrap_or(".")),
+        due_reminders.iter().map(|item| {
+            let due = super::remind::format_due(item.due_at.unwrap_or_default());
+            format!("- {} (due {due}): {}", item.key, truncate(&item.value, 100))
+        }),
+    );
+}
+
+fn print_fenced(sanitizer: Sanitizer, heading: &str, source: &str, lines: impl Iterator<Item = String>) {
+    let lines: Vec<String> = lines.collect();
+    if lines.is_empty() {
+        return;
+    }
+    println!("{heading}");
+    if let Some(open) = sanitizer.open_fence(source) {
+        println!("{open}");
     }
+    for line in &lines {
+        println!("{line}");
+    }
+    if let Some(close) = sanitizer.close_fence() {
+        println!("{close}");
+    }
+    println!();
 }
 
 // ============================================================================
 // Helpers
 // ============================================================================
 
+/// Clean the text of an item prime shows.
+fn sanitize_item(sanitizer: Sanitizer, item: &mut ContextItem) {
+    sanitizer.clean_in_place(&mut item.key);
+    sanitizer.clean_in_place(&mut item.value);
+}
+
+/// Clean issue titles and memory prime shows.
+fn sanitize_scopes(sanitizer: Sanitizer, scopes: &mut [ProjectScope]) {
+    for scope in scopes {
+        let issues = scope.active_issues.iter_mut().chain(&mut scope.ready_issues).chain(&mut scope.all_open);
+        for issue in issues {
+            sanitizer.clean_in_place(&mut issue.title);
+        }
+        for memory in &mut scope.memory {
+            sanitizer.clean_in_place(&mut memory.key);
+            sanitizer.clean_in_place(&mut memory.value);
+        }
+    }
+}
+
+fn sanitize_transcript(sanitizer: Sanitizer, transcript: Option<&mut TranscriptBlock>) {
+    for entry in transcript.into_iter().flat_map(|t| &mut t.entries) {
+        sanitizer.clean_in_place(&mut entry.summary);
+    }
+}
+
 /// Get current git status output.
 fn get_git_status() -> Option<String> {
     std::process::Command::new("git")
@@ -1448,11 +1935,13 @@ mod tests {
 
     #[test]
     fn test_category_weight_values() {
-        assert!((category_weight("decision") - 2.0).abs() < 1e-10);
-        assert!((category_weight("reminder") - 1.5).abs() < 1e-10);
-        assert!((category_weight("progress") - 1.0).abs() < 1e-10);
-        assert!((category_weight("note") - 0.5).abs() < 1e-10);
-        assert!((category_weight("other") - 1.0).abs() < 1e-10);
+        let categories = Categories::default();
+        let weight = |name: &str| categories.prime_weight(&name.parse().unwrap());
+        assert!((weight("decision") - 2.0).abs() < 1e-10);
+        assert!((weight("reminder") - 1.5).abs() < 1e-10);
+        assert!((weight("progress") - 1.0).abs() < 1e-10);
+        assert!((weight("note") - 0.5).abs() < 1e-10);
+        assert!((weight("other") - 1.0).abs() < 1e-10);
     }
 
     #[test]
@@ -1530,13 +2019,18 @@ mod tests {
                 session_id: "sess_test".to_string(),
                 key: key.to_string(),
                 value: value.to_string(),
-                category: "note".to_string(),
-                priority: "normal".to_string(),
+                category: ItemCategory::Note,
+                priority: ItemPriority::Normal,
                 channel: None,
                 tags: None,
                 size: value.len() as i64,
                 created_at: 0,
                 updated_at: 0,
+                source: crate::model::ItemSource::Human,
+                trust: crate::model::TrustLevel::Human,
+                review_status: crate::model::ReviewStatus::Unreviewed,
+                due_at: None,
+                done_at: None,
             },
             score,
             token_estimate: estimate_tokens(key, value),
@@ -1639,4 +2133,32 @@ mod tests {
         // Items without embeddings go at the end
         assert_eq!(result[2].item.key, "b");
     }
+
+    #[test]
+    fn test_multi_path_scopes() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Mono", None, Some("/repo/api"), None, None, "actor")
+            .unwrap();
+        storage.add_session_path("sess_1", "/repo/web", "actor").unwrap();
+        storage
+            .create_issue("i1", Some("API-1"), "/repo/api", "API bug", None, None, None, None, None, None, "actor")
+            .unwrap();
+        storage
+            .save_memory("m1", "/repo/web", "build", "pnpm build", MemoryCategory::Command, None, "actor")
+            .unwrap();
+
+        let paths = resolve_scope_paths(&storage, "sess_1", "/repo/api", &[]).unwrap();
+        assert_eq!(paths, ["/repo/api", "/repo/web"]);
+
+        let scopes = load_project_scopes(&storage, &paths).unwrap();
+        assert_eq!(combined_issue_block(&scopes).total_open, 1);
+        assert_eq!(combined_memory(&scopes).len(), 1);
+        assert_eq!(project_sections(&scopes).len(), 2);
+        assert_eq!(scope_label(&scopes[1], 2), " — web");
+
+        let only_web = resolve_scope_paths(&storage, "sess_1", "/repo/api", &["/repo/web/".to_string()]).unwrap();
+        assert_eq!(only_web, ["/repo/web"]);
+        assert!(resolve_scope_paths(&storage, "sess_1", "/repo/api", &["/elsewhere".to_string()]).is_err());
+    }
 }
@@ -4,16 +4,22 @@
 //! - `sc project create <path>` - Create a new project
 //! - `sc project list` - List all projects
 //! - `sc project show <id>` - Show project details
+//! - `sc project status [id]` - Health overview
 //! - `sc project update <id>` - Update project settings
 //! - `sc project delete <id>` - Delete a project
+//! - `sc project merge-paths <a> <b>` - Fold data stored under path `a` into `b`
+//! - `sc project move <old> <new>` - Re-key a project after moving it on disk
 
-use crate::cli::{ProjectCommands, ProjectCreateArgs, ProjectUpdateArgs};
-use crate::config::{current_project_path, default_actor, resolve_db_path};
+use crate::cli::{Database, ProjectCommands, ProjectCreateArgs, ProjectUpdateArgs};
+use crate::config::{
+    current_project_path, default_actor, normalize_path, resolve_project_path,
+};
 use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, merged, object, schema_for, JsonSchema};
 use crate::model::Project;
-use crate::storage::SqliteStorage;
+use crate::storage::{PathMoveStats, ProjectCounts, SqliteStorage, StaleIssue};
 use serde::Serialize;
-use std::path::PathBuf;
+use serde_json::Value;
 
 #[derive(Serialize)]
 struct ProjectOutput {
@@ -44,19 +50,122 @@ impl From<Project> for ProjectOutput {
 
 #[derive(Serialize)]
 struct ProjectListOutput {
-    projects: Vec<ProjectOutput>,
+    projects: Vec<ProjectListEntry>,
     count: usize,
 }
 
+#[derive(Serialize)]
+struct ProjectListEntry {
+    #[serde(flatten)]
+    project: ProjectOutput,
+    /// Only with `--session-count`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_count: Option<usize>,
+}
+
+impl JsonSchema for ProjectListEntry {
+    fn json_schema() -> Value {
+        merged(&[schema_for::<ProjectOutput>(), object(&[("session_count", schema_for::<usize>(), false)])])
+    }
+}
+
 #[derive(Serialize)]
 struct ProjectWithCounts {
     #[serde(flatten)]
     project: ProjectOutput,
+    #[serde(flatten)]
+    counts: ProjectCountFields,
+}
+
+/// Output for project status.
+#[derive(Serialize)]
+struct ProjectStatusOutput {
+    project: ProjectOutput,
+    open_issues: usize,
+    /// Open issues by priority, 0 (backlog) to 4 (critical).
+    open_by_priority: [usize; 5],
+    stale_days: u32,
+    stale_count: usize,
+    stale_issues: Vec<StaleIssue>,
+    active_sessions: usize,
+    embedding_backlog: usize,
+    pending_sync: usize,
+    last_checkpoint_at: Option<String>,
+    last_checkpoint_age_hours: Option<i64>,
+    counts: ProjectCounts,
+}
+
+/// Output for project delete.
+#[derive(Serialize)]
+struct ProjectDeleteOutput {
+    deleted: bool,
+    id: String,
+    name: String,
+    items_deleted: usize,
+}
+
+/// Output for project merge-paths and move.
+#[derive(Serialize)]
+struct PathMoveOutput<'a> {
+    from: &'a str,
+    into: &'a str,
+    dry_run: bool,
+    moved: &'a PathMoveStats,
+}
+
+/// Fields `project show` adds to the project.
+#[derive(Serialize)]
+#[allow(clippy::struct_field_names)]
+struct ProjectCountFields {
     session_count: usize,
     issue_count: usize,
     memory_count: usize,
 }
 
+json_schema!(ProjectListOutput { projects: Vec<ProjectListEntry>, count: usize });
+json_schema!(ProjectCountFields { session_count: usize, issue_count: usize, memory_count: usize });
+json_schema!(ProjectStatusOutput {
+    project: ProjectOutput,
+    open_issues: usize,
+    open_by_priority: [usize; 5],
+    stale_days: u32,
+    stale_count: usize,
+    stale_issues: Vec<StaleIssue>,
+    active_sessions: usize,
+    embedding_backlog: usize,
+    pending_sync: usize,
+    last_checkpoint_at: Option<String>,
+    last_checkpoint_age_hours: Option<i64>,
+    counts: ProjectCounts,
+});
+json_schema!(ProjectDeleteOutput { deleted: bool, id: String, name: String, items_deleted: usize });
+json_schema!(PathMoveOutput<'a> { from: &'a str, into: &'a str, dry_run: bool, moved: &'a PathMoveStats });
+
+json_schema!(ProjectOutput {
+    id: String,
+    project_path: String,
+    name: String,
+    description: Option<String>,
+    issue_prefix: Option<String>,
+    next_issue_number: i32,
+    created_at: String,
+    updated_at: String,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("project create", schema_for::<ProjectOutput>()),
+        ("project update", schema_for::<ProjectOutput>()),
+        ("project list", schema_for::<ProjectListOutput>()),
+        ("project show", merged(&[schema_for::<ProjectOutput>(), schema_for::<ProjectCountFields>()])),
+        ("project status", schema_for::<ProjectStatusOutput>()),
+        ("project delete", schema_for::<ProjectDeleteOutput>()),
+        ("project merge-paths", schema_for::<PathMoveOutput<'_>>()),
+        ("project move", schema_for::<PathMoveOutput<'_>>()),
+    ]
+}
+
 fn format_timestamp(ts: i64) -> String {
     chrono::DateTime::from_timestamp_millis(ts)
         .map(|dt| dt.to_rfc3339())
@@ -66,26 +175,28 @@ fn format_timestamp(ts: i64) -> String {
 /// Execute a project command.
 pub fn execute(
     command: &ProjectCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json_output: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let actor = actor.map(String::from).unwrap_or_else(default_actor);
 
     match command {
-        ProjectCommands::Create(args) => execute_create(&mut storage, args, json_output, &actor),
-        ProjectCommands::List { limit, session_count } => execute_list(&storage, *limit, *session_count, json_output),
-        ProjectCommands::Show { id } => execute_show(&storage, id, json_output),
-        ProjectCommands::Update(args) => execute_update(&mut storage, args, json_output, &actor),
-        ProjectCommands::Delete { id, force } => execute_delete(&mut storage, id, *force, json_output, &actor),
+        ProjectCommands::Create(args) => execute_create(storage, args, json_output, &actor),
+        ProjectCommands::List { limit, session_count } => execute_list(storage, *limit, *session_count, json_output),
+        ProjectCommands::Show { id } => execute_show(storage, id, json_output),
+        ProjectCommands::Status { id, stale_days } => {
+            execute_status(storage, id.as_deref(), *stale_days, json_output)
+        }
+        ProjectCommands::Update(args) => execute_update(storage, args, json_output, &actor),
+        ProjectCommands::Delete { id, force } => execute_delete(storage, id, *force, json_output, &actor),
+        ProjectCommands::MergePaths { from, into } => {
+            execute_merge_paths(storage, from, into, json_output, &actor)
+        }
+        ProjectCommands::Move { old_path, new_path } => {
+            execute_move(storage, old_path, new_path, json_output, &actor)
+        }
     }
 }
 
@@ -99,19 +210,12 @@ fn execute_create(
     let project_path = args.path.clone().unwrap_or_else(|| {
         std::env::current_dir()
             .ok()
-            .and_then(|p| p.canonicalize().ok())
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| {
-                current_project_path()
-                    .map(|p| p.to_string_lossy().to_string())
-                    .unwrap_or_else(|| ".".to_string())
-            })
+            .or_else(current_project_path)
+            .map_or_else(|| ".".to_string(), |p| p.to_string_lossy().to_string())
     });
 
     // Canonicalize the path
-    let project_path = std::fs::canonicalize(&project_path)
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or(project_path);
+    let project_path = normalize_path(std::path::Path::new(&project_path));
 
     // Check if project already exists
     if let Some(existing) = storage.get_project_by_path(&project_path)? {
@@ -170,31 +274,17 @@ fn execute_list(
     let projects = storage.list_projects(limit)?;
 
     if json_output {
-        if include_session_count {
-            // Include session counts for each project
-            let projects_with_counts: Vec<serde_json::Value> = projects
-                .iter()
-                .map(|p| {
-                    let counts = storage.get_project_counts(&p.project_path).ok();
-                    let mut obj = serde_json::to_value(ProjectOutput::from(p.clone())).unwrap();
-                    if let (Some(counts), Some(obj_map)) = (counts, obj.as_object_mut()) {
-                        obj_map.insert("session_count".to_string(), serde_json::json!(counts.sessions));
-                    }
-                    obj
-                })
-                .collect();
-            let output = serde_json::json!({
-                "count": projects_with_counts.len(),
-                "projects": projects_with_counts
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-        } else {
-            let output = ProjectListOutput {
-                count: projects.len(),
-                projects: projects.into_iter().map(ProjectOutput::from).collect(),
-            };
-            println!("{}", serde_json::to_string_pretty(&output)?);
-        }
+        let entries: Vec<ProjectListEntry> = projects
+            .into_iter()
+            .map(|p| {
+                let session_count = include_session_count
+                    .then(|| storage.get_project_counts(&p.project_path).ok().map(|c| c.sessions))
+                    .flatten();
+                ProjectListEntry { project: ProjectOutput::from(p), session_count }
+            })
+            .collect();
+        let output = ProjectListOutput { count: entries.len(), projects: entries };
+        println!("{}", serde_json::to_string_pretty(&output)?);
     } else if projects.is_empty() {
         println!("No projects found.");
         println!("\nCreate one with: sc project create [--name <name>]");
@@ -242,9 +332,11 @@ fn execute_show(
     if json_output {
         let output = ProjectWithCounts {
             project: ProjectOutput::from(project),
-            session_count: counts.sessions,
-            issue_count: counts.issues,
-            memory_count: counts.memories,
+            counts: ProjectCountFields {
+                session_count: counts.sessions,
+                issue_count: counts.issues,
+                memory_count: counts.memories,
+            },
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
@@ -267,6 +359,86 @@ fn execute_show(
     Ok(())
 }
 
+/// Human labels for priorities 0-4.
+const PRIORITY_LABELS: [&str; 5] = ["backlog", "low", "medium", "high", "critical"];
+
+fn execute_status(
+    storage: &SqliteStorage,
+    id: Option<&str>,
+    stale_days: u32,
+    json_output: bool,
+) -> Result<()> {
+    let project = if let Some(id) = id {
+        storage.get_project(id)?
+            .or_else(|| storage.get_project_by_path(id).ok().flatten())
+    } else {
+        let path = resolve_project_path(storage, None)?;
+        storage.get_project_by_path(&path)?
+    }
+    .ok_or_else(|| Error::ProjectNotFound { id: id.unwrap_or(".").to_string() })?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let stale_before = now - i64::from(stale_days) * 24 * 60 * 60 * 1000;
+    let health = storage.get_project_health(&project.project_path, stale_before)?;
+    let counts = storage.get_project_counts(&project.project_path)?;
+    let open_total: usize = health.open_by_priority.iter().sum();
+    let checkpoint_age_hours = health.last_checkpoint_at.map(|ts| (now - ts) / 3_600_000);
+
+    if json_output {
+        let output = ProjectStatusOutput {
+            project: ProjectOutput::from(project),
+            open_issues: open_total,
+            open_by_priority: health.open_by_priority,
+            stale_days,
+            stale_count: health.stale_count,
+            stale_issues: health.stale_issues,
+            active_sessions: health.active_sessions,
+            embedding_backlog: health.embedding_backlog,
+            pending_sync: health.pending_sync,
+            last_checkpoint_at: health.last_checkpoint_at.map(format_timestamp),
+            last_checkpoint_age_hours: checkpoint_age_hours,
+            counts,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Project: {} ({})", project.name, project.project_path);
+    println!();
+    println!("Issues: {open_total} open");
+    for priority in (0..PRIORITY_LABELS.len()).rev() {
+        let count = health.open_by_priority[priority];
+        if count > 0 {
+            println!("  P{priority} {:<9} {count}", PRIORITY_LABELS[priority]);
+        }
+    }
+    if health.stale_count > 0 {
+        println!();
+        println!("Stale (no update in {stale_days}d): {}", health.stale_count);
+        for issue in &health.stale_issues {
+            let days = (now - issue.updated_at) / 86_400_000;
+            println!(
+                "  {} {} [{}] — {days}d",
+                issue.short_id.as_deref().unwrap_or(&issue.id),
+                issue.title,
+                issue.status,
+            );
+        }
+    }
+    println!();
+    println!("Sessions:  {} active / {} total", health.active_sessions, counts.sessions);
+    println!("Memory:    {} items", counts.memories);
+    println!("Embedding backlog: {}", health.embedding_backlog);
+    println!("Pending sync export: {}", health.pending_sync);
+    match checkpoint_age_hours {
+        Some(hours) if hours >= 48 => println!("Last checkpoint: {}d ago", hours / 24),
+        Some(hours) => println!("Last checkpoint: {hours}h ago"),
+        None => println!("Last checkpoint: never"),
+    }
+
+    Ok(())
+}
+
 fn execute_update(
     storage: &mut SqliteStorage,
     args: &ProjectUpdateArgs,
@@ -311,6 +483,94 @@ fn execute_update(
     Ok(())
 }
 
+fn execute_merge_paths(
+    storage: &mut SqliteStorage,
+    from: &str,
+    into: &str,
+    json_output: bool,
+    actor: &str,
+) -> Result<()> {
+    // `from` is used as stored: normalizing it could turn it into `into`
+    let into = match storage.get_project_by_path(into)? {
+        Some(project) => project.project_path,
+        None => normalize_path(std::path::Path::new(into)),
+    };
+    let dry_run = crate::is_dry_run();
+    let stats = storage.with_transaction(|s| {
+        let stats = s.merge_project_paths(from, &into, actor);
+        let commit = stats.is_ok() && !dry_run;
+        (stats, commit)
+    })??;
+
+    let verb = if dry_run { "Would merge" } else { "Merged" };
+    print_path_move(&stats, from, &into, verb, json_output)?;
+    if !dry_run && !json_output {
+        println!("{from} is now an alias of {into}.");
+    }
+    Ok(())
+}
+
+fn execute_move(
+    storage: &mut SqliteStorage,
+    old_path: &str,
+    new_path: &str,
+    json_output: bool,
+    actor: &str,
+) -> Result<()> {
+    // The old directory is usually gone, so only a registered spelling helps
+    let from = storage
+        .get_project_by_path(old_path)?
+        .map_or_else(|| old_path.to_string(), |p| p.project_path);
+    let new = std::path::Path::new(new_path);
+    let into = normalize_path(new);
+    let dry_run = crate::is_dry_run();
+    let stats = storage.with_transaction(|s| {
+        let stats = s.move_project_path(&from, &into, actor);
+        let commit = stats.is_ok() && !dry_run;
+        (stats, commit)
+    })??;
+
+    let verb = if dry_run { "Would move" } else { "Moved" };
+    print_path_move(&stats, &from, &into, verb, json_output)?;
+    if !json_output && !new.is_dir() {
+        println!("Note: {into} does not exist on disk yet.");
+    }
+    Ok(())
+}
+
+fn print_path_move(stats: &PathMoveStats, from: &str, into: &str, verb: &str, json_output: bool) -> Result<()> {
+    if json_output {
+        let output = PathMoveOutput { from, into, dry_run: crate::is_dry_run(), moved: stats };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("{verb} {from} -> {into}");
+    for (label, count) in [
+        ("sessions", stats.sessions),
+        ("session paths", stats.session_paths),
+        ("issues", stats.issues),
+        ("issue paths", stats.issue_paths),
+        ("plans", stats.plans),
+        ("memory items", stats.memory),
+        ("time entries", stats.time_entries),
+        ("agent sessions", stats.agent_sessions),
+        ("sync deletions", stats.sync_deletions),
+        ("export hashes reset", stats.export_hashes),
+    ] {
+        if count > 0 {
+            println!("  {count} {label}");
+        }
+    }
+    if stats.memory_conflicts > 0 {
+        println!("  {} duplicate memory key(s): kept the newer value", stats.memory_conflicts);
+    }
+    if stats.project_updated {
+        println!("  Project entry updated");
+    }
+    Ok(())
+}
+
 fn execute_delete(
     storage: &mut SqliteStorage,
     id: &str,
@@ -344,12 +604,7 @@ fn execute_delete(
     storage.delete_project(&project.id, actor)?;
 
     if json_output {
-        let output = serde_json::json!({
-            "deleted": true,
-            "id": project.id,
-            "name": project.name,
-            "items_deleted": total_items
-        });
+        let output = ProjectDeleteOutput { deleted: true, id: project.id, name: project.name, items_deleted: total_items };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
         println!("Deleted project: {} ({})", project.name, project.id);
@@ -0,0 +1,307 @@
+//! Scheduled reminders (`sc remind`).
+//!
+//! `sc remind "rotate the staging cert" --at 2025-07-01` (or `--in 3d`)
+//! saves a `reminder` item in the current session with the time it comes
+//! due. Until then it stays out of the way; once due it heads `sc status`
+//! and `sc prime` in every session of the project, and the statusline
+//! counts it, until `sc remind done <id>` clears it. Reminding again with
+//! the same key reschedules the reminder and reopens it.
+
+use super::report::start_of_day;
+use crate::cli::{Database, RemindArgs, RemindCommands};
+use crate::config::{
+    cache_reminders, default_actor, resolve_project_path, resolve_session_or_suggest,
+    CachedReminder,
+};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::ItemPriority;
+use crate::storage::{ContextItem, SqliteStorage};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Words of the text kept in a default key.
+const KEY_WORDS: usize = 5;
+
+/// Output for remind.
+#[derive(Serialize)]
+struct RemindOutput {
+    id: String,
+    key: String,
+    value: String,
+    due_at: i64,
+}
+
+/// Output for remind done.
+#[derive(Serialize)]
+struct RemindDoneOutput {
+    id: String,
+    key: String,
+    done_at: Option<i64>,
+}
+
+/// Output for remind list.
+#[derive(Serialize)]
+struct RemindListOutput {
+    reminders: Vec<ContextItem>,
+    count: usize,
+    /// Open reminders already due.
+    due: usize,
+}
+
+json_schema!(RemindOutput { id: String, key: String, value: String, due_at: i64 });
+json_schema!(RemindDoneOutput { id: String, key: String, done_at: Option<i64> });
+json_schema!(RemindListOutput { reminders: Vec<ContextItem>, count: usize, due: usize });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("remind", schema_for::<RemindOutput>()),
+        ("remind done", schema_for::<RemindDoneOutput>()),
+        ("remind list", schema_for::<RemindListOutput>()),
+    ]
+}
+
+/// Execute remind commands.
+///
+/// # Errors
+///
+/// Returns an error if the due time can't be parsed, no session or project
+/// can be resolved, or the reminder doesn't exist.
+pub fn execute(
+    command: Option<&RemindCommands>,
+    args: &RemindArgs,
+    db: &mut Database,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+
+    match command {
+        None => remind(storage, args, session_id, &actor, json),
+        Some(RemindCommands::Done { id }) => done(storage, id, &actor, json),
+        Some(RemindCommands::List { all }) => list(storage, *all, json),
+    }
+}
+
+fn remind(
+    storage: &mut SqliteStorage,
+    args: &RemindArgs,
+    session_id: Option<&str>,
+    actor: &str,
+    json: bool,
+) -> Result<()> {
+    let text = args.text.as_deref().unwrap_or_default().trim();
+    if text.is_empty() {
+        return Err(Error::InvalidArgument("Reminder text cannot be empty".to_string()));
+    }
+    let due_at = due_at(args.at.as_deref(), args.within.as_deref(), Local::now())?;
+    let priority: Option<ItemPriority> = args.priority.as_deref().map(str::parse).transpose()?;
+    let key = args.key.clone().unwrap_or_else(|| default_key(text));
+    let session_id = resolve_session_or_suggest(session_id, storage)?;
+
+    if crate::is_dry_run() {
+        println!("Would remind: {key} (due {})", format_due(due_at));
+        return Ok(());
+    }
+
+    let id = format!("item_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+    storage.save_reminder(&id, &session_id, &key, text, due_at, priority, actor)?;
+    // On reschedule the item keeps its original ID
+    let id = storage.get_item_id_by_key(&session_id, &key)?.unwrap_or(id);
+    if let Some(project_path) = storage.get_session(&session_id)?.and_then(|s| s.project_path) {
+        refresh_statusline(storage, &project_path)?;
+    }
+
+    if crate::is_silent() {
+        println!("{key}");
+    } else if json {
+        let output = RemindOutput { id, key, value: text.to_string(), due_at };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("{} Reminder {} due {}", "✓".green(), key.bold(), format_due(due_at));
+    }
+    Ok(())
+}
+
+fn done(storage: &mut SqliteStorage, id: &str, actor: &str, json: bool) -> Result<()> {
+    let project_path = resolve_project_path(storage, None)?;
+    if crate::is_dry_run() {
+        println!("Would mark done: {id}");
+        return Ok(());
+    }
+
+    let item = storage.complete_reminder(&project_path, id, actor)?;
+    refresh_statusline(storage, &project_path)?;
+
+    if crate::is_silent() {
+        println!("{}", item.key);
+    } else if json {
+        let output = RemindDoneOutput { id: item.id, key: item.key, done_at: item.done_at };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("{} Done: {}", "✓".green(), item.key.bold());
+    }
+    Ok(())
+}
+
+fn list(storage: &SqliteStorage, all: bool, json: bool) -> Result<()> {
+    let project_path = resolve_project_path(storage, None)?;
+    let reminders = storage.get_reminders(&project_path, all)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let due = reminders.iter().filter(|r| is_due(r, now)).count();
+
+    if crate::is_silent() {
+        for reminder in &reminders {
+            println!("{}", reminder.key);
+        }
+        return Ok(());
+    }
+    if json {
+        let output = RemindListOutput { count: reminders.len(), due, reminders };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    if reminders.is_empty() {
+        println!("No reminders.");
+        return Ok(());
+    }
+
+    println!("Reminders ({}, {due} due):", reminders.len());
+    println!();
+    for reminder in &reminders {
+        let when = format_due(reminder.due_at.unwrap_or_default());
+        let state = if reminder.done_at.is_some() {
+            format!("done, was due {when}").dimmed()
+        } else if is_due(reminder, now) {
+            format!("due {when}").red().bold()
+        } else {
+            format!("due {when}").normal()
+        };
+        println!("  {} {} {}", reminder.key.bold(), format!("[{}]", reminder.id).dimmed(), state);
+        println!("    {}", reminder.value.lines().next().unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// Open reminders of `project_path` that are due at `now` (Unix ms), due
+/// first.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub(crate) fn due_reminders(storage: &SqliteStorage, project_path: &str, now: i64) -> Result<Vec<ContextItem>> {
+    let mut reminders = storage.get_reminders(project_path, false)?;
+    reminders.retain(|r| is_due(r, now));
+    Ok(reminders)
+}
+
+/// Put the project's open reminders in this terminal's status cache entry,
+/// where the statusline counts the due ones.
+///
+/// # Errors
+///
+/// Returns an error if the query fails. A cache that can't be written is
+/// left as it is.
+pub(crate) fn refresh_statusline(storage: &SqliteStorage, project_path: &str) -> Result<()> {
+    let reminders = storage
+        .get_reminders(project_path, false)?
+        .into_iter()
+        .filter_map(|r| r.due_at.map(|due_at| CachedReminder { key: r.key, due_at }))
+        .collect();
+    cache_reminders(project_path, reminders);
+    Ok(())
+}
+
+/// Whether `item` is an open reminder due at `now` (Unix ms).
+pub(crate) fn is_due(item: &ContextItem, now: i64) -> bool {
+    item.done_at.is_none() && item.due_at.is_some_and(|due_at| due_at <= now)
+}
+
+/// A due time (Unix ms) in local time, to the minute.
+pub(crate) fn format_due(due_at: i64) -> String {
+    DateTime::from_timestamp_millis(due_at)
+        .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// When a reminder comes due (Unix ms), from `--at` or `--in`.
+fn due_at(at: Option<&str>, within: Option<&str>, now: DateTime<Local>) -> Result<i64> {
+    if let Some(at) = at {
+        let spec = at.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+            return Ok(start_of_day(date));
+        }
+        if let Ok(dt) = DateTime::parse_from_rfc3339(spec) {
+            return Ok(dt.timestamp_millis());
+        }
+        let local = ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(spec, format).ok())
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest());
+        return local.map(|dt| dt.timestamp_millis()).ok_or_else(|| {
+            Error::InvalidArgument(format!("Invalid --at '{at}': use YYYY-MM-DD, YYYY-MM-DD HH:MM, or RFC 3339"))
+        });
+    }
+
+    let within = within.unwrap_or_default();
+    let spec = within.trim().to_ascii_lowercase();
+    let (count, unit) = spec.split_at(spec.len().saturating_sub(1));
+    let minutes = match (count.parse::<i64>(), unit) {
+        (Ok(n), "m") if n > 0 => n,
+        (Ok(n), "h") if n > 0 => n.saturating_mul(60),
+        (Ok(n), "d") if n > 0 => n.saturating_mul(24 * 60),
+        (Ok(n), "w") if n > 0 => n.saturating_mul(7 * 24 * 60),
+        _ => {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid --in '{within}': use a span like 30m, 4h, 3d or 2w"
+            )));
+        }
+    };
+    Ok(now.timestamp_millis().saturating_add(minutes.saturating_mul(60_000)))
+}
+
+/// `remind-` and the first words of `text`, lowercased and hyphenated.
+fn default_key(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(KEY_WORDS)
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        "remind".to_string()
+    } else {
+        format!("remind-{}", words.join("-"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_at_and_default_key() {
+        let now = Local.with_ymd_and_hms(2026, 6, 1, 9, 30, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        assert_eq!(due_at(Some("2026-07-01"), None, now).unwrap(), start_of_day(date));
+        assert_eq!(
+            due_at(Some("2026-07-01 14:05"), None, now).unwrap(),
+            Local.with_ymd_and_hms(2026, 7, 1, 14, 5, 0).unwrap().timestamp_millis()
+        );
+        assert_eq!(due_at(Some("2026-07-01T12:00:00Z"), None, now).unwrap(), 1_782_907_200_000);
+        assert_eq!(due_at(None, Some("3d"), now).unwrap(), now.timestamp_millis() + 3 * 86_400_000);
+        assert_eq!(due_at(None, Some("90m"), now).unwrap(), now.timestamp_millis() + 90 * 60_000);
+        assert!(due_at(Some("next week"), None, now).is_err());
+        assert!(due_at(None, Some("0d"), now).is_err());
+        assert!(due_at(None, Some("3y"), now).is_err());
+
+        assert_eq!(default_key("Rotate the staging cert!"), "remind-rotate-the-staging-cert");
+        assert_eq!(default_key("a b c d e f g"), "remind-a-b-c-d-e");
+        assert_eq!(default_key("!!"), "remind");
+    }
+}
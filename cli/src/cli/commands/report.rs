@@ -0,0 +1,693 @@
+//! Reports built from the project's history (`sc report`).
+//!
+//! `sc report flow` rebuilds how many issues were in each status at the
+//! end of every day, for burndown and cumulative-flow charts. The status
+//! of an issue on a given day is replayed from the status changes in the
+//! event log, so events removed by `sc events prune` make earlier days less
+//! exact, and deleted issues are not counted.
+//!
+//! `sc report standup` lists what an actor closed, decided, checkpointed
+//! and wrote in issue worklogs since a point in time, and what they have in
+//! progress, as markdown ready
+//! to paste. Work is attributed through the actor recorded on each event.
+//! With `--polish` the update is rewritten by the chat completions
+//! endpoint under `summarize` in config.json.
+
+use super::config::{load_config, SummarizeConfig};
+use crate::cli::{Database, ReportCommands};
+use crate::config::{default_actor, resolve_project_path};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemCategory, IssueStatus};
+use crate::storage::events::StoredEvent;
+use crate::storage::{Checkpoint, ContextItem, Issue, IssueStatusChange, SqliteStorage, WorklogEntry};
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+
+/// Longest decision value quoted in a standup.
+const DECISION_CHARS: usize = 160;
+
+const POLISH_PROMPT: &str = "You edit standup updates. Rewrite the notes you are given as a short \
+first-person update in markdown with the same sections. Keep every issue ID, do not add work that \
+is not listed, and reply with only the update.";
+
+/// Output for report flow.
+#[derive(Serialize)]
+struct FlowOutput {
+    from: String,
+    to: String,
+    /// Statuses counted, in workflow order; every day has a count for each.
+    statuses: Vec<String>,
+    days: Vec<FlowDay>,
+}
+
+#[derive(Serialize)]
+struct FlowDay {
+    date: String,
+    /// Issues in each status at the end of the day.
+    counts: BTreeMap<String, usize>,
+    total: usize,
+    /// Issues not closed at the end of the day (the burndown line).
+    remaining: usize,
+    created: usize,
+    closed: usize,
+}
+
+/// Output for report standup.
+#[derive(Serialize)]
+struct StandupOutput {
+    /// Whose work is covered; null for everyone.
+    actor: Option<String>,
+    /// Start of the period (RFC 3339).
+    since: String,
+    closed: Vec<StandupIssue>,
+    decisions: Vec<StandupDecision>,
+    checkpoints: Vec<StandupCheckpoint>,
+    /// Issue worklog entries, oldest first.
+    worklog: Vec<WorklogEntry>,
+    in_progress: Vec<StandupIssue>,
+    markdown: String,
+    /// Whether `markdown` was rewritten by the summarize endpoint.
+    polished: bool,
+}
+
+#[derive(Serialize)]
+struct StandupIssue {
+    /// Short ID, or the full ID if there is none.
+    id: String,
+    title: String,
+    priority: i32,
+}
+
+#[derive(Serialize)]
+struct StandupDecision {
+    key: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct StandupCheckpoint {
+    id: String,
+    name: String,
+    description: Option<String>,
+}
+
+json_schema!(FlowOutput { from: String, to: String, statuses: Vec<String>, days: Vec<FlowDay> });
+json_schema!(FlowDay {
+    date: String,
+    counts: BTreeMap<String, usize>,
+    total: usize,
+    remaining: usize,
+    created: usize,
+    closed: usize,
+});
+json_schema!(StandupOutput {
+    actor: Option<String>,
+    since: String,
+    closed: Vec<StandupIssue>,
+    decisions: Vec<StandupDecision>,
+    checkpoints: Vec<StandupCheckpoint>,
+    worklog: Vec<WorklogEntry>,
+    in_progress: Vec<StandupIssue>,
+    markdown: String,
+    polished: bool,
+});
+json_schema!(StandupIssue { id: String, title: String, priority: i32 });
+json_schema!(StandupDecision { key: String, value: String });
+json_schema!(StandupCheckpoint { id: String, name: String, description: Option<String> });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("report flow", schema_for::<FlowOutput>()),
+        ("report standup", schema_for::<StandupOutput>()),
+    ]
+}
+
+/// Execute report commands.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or queried, or
+/// `--since` is not understood.
+pub fn execute(command: &ReportCommands, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+
+    match command {
+        ReportCommands::Flow { days } => flow(storage, &project_path, *days, json),
+        ReportCommands::Standup { since, all, polish } => {
+            // `--actor me` is a readable way to ask for the default actor
+            let actor = match actor {
+                _ if *all => None,
+                Some(name) if name != "me" => Some(storage.resolve_actor(name)?),
+                _ => Some(storage.resolve_actor(&default_actor())?),
+            };
+            let since = since_millis(since, Local::now())?;
+            standup(storage, &project_path, actor, since, *polish, json)
+        }
+    }
+}
+
+fn flow(storage: &SqliteStorage, project_path: &str, days: u32, json: bool) -> Result<()> {
+    if days == 0 {
+        return Err(Error::InvalidArgument("--days must be at least 1".to_string()));
+    }
+    let issues = storage.get_issues_by_project(project_path)?;
+    let changes = storage.get_issue_status_changes(project_path)?;
+
+    let today = Local::now().date_naive();
+    let first = today.checked_sub_days(Days::new(u64::from(days - 1))).unwrap_or(today);
+    let dates: Vec<NaiveDate> = first.iter_days().take_while(|d| *d <= today).collect();
+    let output = flow_series(&issues, &changes, &dates, end_of_day);
+
+    if crate::is_csv() {
+        let mut header = vec!["date".to_string()];
+        header.extend(output.statuses.iter().cloned());
+        header.extend(["total", "remaining", "issues_created", "issues_closed"].map(String::from));
+        println!("{}", header.join(","));
+        for day in &output.days {
+            let mut row = vec![day.date.clone()];
+            row.extend(output.statuses.iter().map(|s| day.counts[s].to_string()));
+            row.extend([day.total, day.remaining, day.created, day.closed].map(|n| n.to_string()));
+            println!("{}", row.join(","));
+        }
+    } else if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        print_flow(&output);
+    }
+    Ok(())
+}
+
+/// Midnight at the start of `date` in local time (Unix ms).
+pub(crate) fn start_of_day(date: NaiveDate) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map_or_else(|| midnight.and_utc().timestamp_millis(), |dt| dt.timestamp_millis())
+}
+
+/// The first moment after `date` in local time (Unix ms).
+fn end_of_day(date: NaiveDate) -> i64 {
+    start_of_day(date.succ_opt().unwrap_or(date))
+}
+
+/// Count issues by status at the end of each of `dates`.
+///
+/// An issue's status before its first recorded change is that change's
+/// old status; an issue with no changes has always had its current one.
+fn flow_series(
+    issues: &[Issue],
+    changes: &[IssueStatusChange],
+    dates: &[NaiveDate],
+    end_of_day: impl Fn(NaiveDate) -> i64,
+) -> FlowOutput {
+    let mut by_issue: HashMap<&str, Vec<&IssueStatusChange>> = HashMap::new();
+    for change in changes {
+        by_issue.entry(change.issue_id.as_str()).or_default().push(change);
+    }
+
+    let mut statuses: Vec<String> = IssueStatus::BUILT_IN.iter().map(ToString::to_string).collect();
+    let mut custom: Vec<&str> = issues
+        .iter()
+        .map(|i| i.status.as_str())
+        .chain(changes.iter().map(|c| c.to.as_str()))
+        .filter(|s| !statuses.iter().any(|b| b == s))
+        .collect();
+    custom.sort_unstable();
+    custom.dedup();
+    statuses.extend(custom.into_iter().map(String::from));
+
+    let mut series = Vec::with_capacity(dates.len());
+    let mut previous_end = dates.first().map_or(0, |d| end_of_day(d.pred_opt().unwrap_or(*d)));
+    for date in dates {
+        let end = end_of_day(*date);
+        let mut counts: BTreeMap<String, usize> = statuses.iter().map(|s| (s.clone(), 0)).collect();
+        let mut created = 0;
+
+        for issue in issues.iter().filter(|i| i.created_at < end) {
+            if issue.created_at >= previous_end {
+                created += 1;
+            }
+            let history = by_issue.get(issue.id.as_str()).map_or(&[][..], Vec::as_slice);
+            let status = match history.iter().rev().find(|c| c.at < end) {
+                Some(change) => change.to.as_str(),
+                None => match history.first() {
+                    Some(first) => first.from.as_deref().unwrap_or("open"),
+                    None => issue.status.as_str(),
+                },
+            };
+            *counts.entry(status.to_string()).or_insert(0) += 1;
+        }
+
+        let closed = changes.iter().filter(|c| c.to == "closed" && (previous_end..end).contains(&c.at)).count();
+        let total = counts.values().sum();
+        series.push(FlowDay {
+            date: date.to_string(),
+            remaining: total - counts.get("closed").copied().unwrap_or(0),
+            counts,
+            total,
+            created,
+            closed,
+        });
+        previous_end = end;
+    }
+
+    FlowOutput {
+        from: dates.first().map(ToString::to_string).unwrap_or_default(),
+        to: dates.last().map(ToString::to_string).unwrap_or_default(),
+        statuses,
+        days: series,
+    }
+}
+
+fn print_flow(output: &FlowOutput) {
+    println!("Issue flow {} to {} ({} days)", output.from, output.to, output.days.len());
+    println!();
+
+    let column = |f: &dyn Fn(&FlowDay) -> usize| output.days.iter().map(f).collect::<Vec<usize>>();
+    let mut rows: Vec<(&str, Vec<usize>)> = output
+        .statuses
+        .iter()
+        .map(|s| (s.as_str(), column(&|d| d.counts[s])))
+        .filter(|(_, values)| values.iter().any(|n| *n > 0))
+        .collect();
+    rows.push(("remaining", column(&|d| d.remaining)));
+
+    for (name, values) in &rows {
+        println!("  {name:<12} {}  {}", sparkline(values), values.last().copied().unwrap_or(0));
+    }
+    let created: usize = output.days.iter().map(|d| d.created).sum();
+    let closed: usize = output.days.iter().map(|d| d.closed).sum();
+    println!();
+    println!("  created {created}, closed {closed}");
+}
+
+/// One character per value, scaled from the lowest to the highest level.
+fn sparkline(values: &[usize]) -> String {
+    const LEVELS: &[u8] = b" .:-=+*#%@";
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|&n| {
+            // Round up, so any non-zero value is visible
+            let level = (n * (LEVELS.len() - 1)).div_ceil(max);
+            char::from(LEVELS[level])
+        })
+        .collect()
+}
+
+/// Start of the `--since` period (Unix ms): `today`, `yesterday`, a
+/// `YYYY-MM-DD` date, or a number of hours or days before `now`.
+fn since_millis(since: &str, now: DateTime<Local>) -> Result<i64> {
+    let today = now.date_naive();
+    let spec = since.trim().to_ascii_lowercase();
+    let millis = match spec.as_str() {
+        "today" => start_of_day(today),
+        "yesterday" => start_of_day(today.pred_opt().unwrap_or(today)),
+        _ => {
+            if let Ok(date) = NaiveDate::parse_from_str(&spec, "%Y-%m-%d") {
+                start_of_day(date)
+            } else {
+                let (count, unit) = spec.split_at(spec.len().saturating_sub(1));
+                let hours = match (count.parse::<i64>(), unit) {
+                    (Ok(n), "h") if n >= 0 => n,
+                    (Ok(n), "d") if n >= 0 => n.saturating_mul(24),
+                    _ => {
+                        return Err(Error::InvalidArgument(format!(
+                            "Invalid --since '{since}': use today, yesterday, YYYY-MM-DD, or a span like 12h or 3d"
+                        )));
+                    }
+                };
+                now.timestamp_millis().saturating_sub(hours.saturating_mul(3_600_000))
+            }
+        }
+    };
+    Ok(millis)
+}
+
+/// A project's records and events, as read for a standup.
+struct History {
+    issues: Vec<Issue>,
+    changes: Vec<IssueStatusChange>,
+    items: Vec<ContextItem>,
+    checkpoints: Vec<Checkpoint>,
+    events: Vec<StoredEvent>,
+    worklog: Vec<WorklogEntry>,
+}
+
+fn standup(
+    storage: &SqliteStorage,
+    project_path: &str,
+    actor: Option<String>,
+    since: i64,
+    polish: bool,
+    json: bool,
+) -> Result<()> {
+    let settings = if polish {
+        Some(load_config().summarize.ok_or_else(|| {
+            Error::Config("--polish needs summarize.endpoint in ~/.savecontext/config.json".to_string())
+        })?)
+    } else {
+        None
+    };
+
+    let history = History {
+        issues: storage.get_issues_by_project(project_path)?,
+        changes: storage.get_issue_status_changes(project_path)?,
+        items: storage.get_context_items_by_project(project_path)?,
+        checkpoints: storage.get_checkpoints_by_project(project_path)?,
+        events: storage.get_project_events(project_path)?,
+        worklog: storage.get_worklog(None, Some(project_path))?,
+    };
+    let mut output = standup_report(&history, actor, since);
+
+    if let Some(settings) = settings {
+        match polish_markdown(&output.markdown, &settings) {
+            Ok(markdown) => {
+                output.markdown = markdown;
+                output.polished = true;
+            }
+            // The plain update is still worth printing
+            Err(e) => eprintln!("Warning: could not polish the standup: {e}"),
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        print!("{}", output.markdown);
+    }
+    Ok(())
+}
+
+/// Collect the actor's work since `since` (everyone's when `actor` is
+/// `None`) and render it as markdown.
+fn standup_report(history: &History, actor: Option<String>, since: i64) -> StandupOutput {
+    let by_actor = |name: &str| actor.as_deref().is_none_or(|a| a == name);
+    let created_by_actor = |entity_type: &str, event_type: &str| -> HashSet<&str> {
+        history
+            .events
+            .iter()
+            .filter(|e| e.entity_type == entity_type && e.event_type == event_type)
+            .filter(|e| e.created_at >= since && by_actor(&e.actor))
+            .map(|e| e.entity_id.as_str())
+            .collect()
+    };
+    let summary = |issue: &Issue| StandupIssue {
+        id: issue.short_id.clone().unwrap_or_else(|| issue.id.clone()),
+        title: issue.title.clone(),
+        priority: issue.priority,
+    };
+
+    // Issues reopened since are no longer done
+    let mut closed_ids: Vec<&str> = history
+        .changes
+        .iter()
+        .filter(|c| c.to == IssueStatus::Closed.as_str() && c.at >= since && by_actor(&c.actor))
+        .map(|c| c.issue_id.as_str())
+        .collect();
+    let mut seen = HashSet::new();
+    closed_ids.retain(|id| seen.insert(*id));
+    let closed = closed_ids
+        .iter()
+        .filter_map(|id| history.issues.iter().find(|i| i.id == *id))
+        .filter(|i| i.status == IssueStatus::Closed)
+        .map(summary)
+        .collect();
+
+    let new_items = created_by_actor("context_item", "item_created");
+    let decisions = history
+        .items
+        .iter()
+        .filter(|i| i.category == ItemCategory::Decision && i.created_at >= since)
+        .filter(|i| actor.is_none() || new_items.contains(i.id.as_str()))
+        .map(|i| StandupDecision { key: i.key.clone(), value: i.value.clone() })
+        .collect();
+
+    let new_checkpoints = created_by_actor("checkpoint", "checkpoint_created");
+    let checkpoints = history
+        .checkpoints
+        .iter()
+        .filter(|c| c.created_at >= since)
+        .filter(|c| actor.is_none() || new_checkpoints.contains(c.id.as_str()))
+        .map(|c| StandupCheckpoint { id: c.id.clone(), name: c.name.clone(), description: c.description.clone() })
+        .collect();
+
+    let worklog =
+        history.worklog.iter().filter(|e| e.created_at >= since && by_actor(&e.actor)).cloned().collect();
+
+    let mut in_progress: Vec<&Issue> = history
+        .issues
+        .iter()
+        .filter(|i| i.status == IssueStatus::InProgress)
+        .filter(|i| actor.is_none() || i.assigned_to_agent.as_deref().is_some_and(by_actor))
+        .collect();
+    in_progress.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.created_at.cmp(&b.created_at)));
+
+    let since = Local.timestamp_millis_opt(since).single().unwrap_or_else(Local::now);
+    let mut output = StandupOutput {
+        actor,
+        since: since.to_rfc3339(),
+        closed,
+        decisions,
+        checkpoints,
+        worklog,
+        in_progress: in_progress.into_iter().map(summary).collect(),
+        markdown: String::new(),
+        polished: false,
+    };
+    output.markdown = standup_markdown(&output, since);
+    output
+}
+
+fn standup_markdown(output: &StandupOutput, since: DateTime<Local>) -> String {
+    let mut md = format!(
+        "## Standup: {} (since {})\n",
+        output.actor.as_deref().unwrap_or("everyone"),
+        since.format("%a %b %-d %H:%M")
+    );
+    let issue_line = |i: &StandupIssue| format!("- {} ({})", i.title, i.id);
+    let sections = [
+        ("Done", output.closed.iter().map(issue_line).collect::<Vec<_>>()),
+        (
+            "Decisions",
+            output.decisions.iter().map(|d| format!("- **{}**: {}", d.key, excerpt(&d.value))).collect(),
+        ),
+        (
+            "Checkpoints",
+            output
+                .checkpoints
+                .iter()
+                .map(|c| match &c.description {
+                    Some(description) => format!("- {}: {}", c.name, excerpt(description)),
+                    None => format!("- {}", c.name),
+                })
+                .collect(),
+        ),
+        (
+            "Worklog",
+            output
+                .worklog
+                .iter()
+                .map(|e| {
+                    let id = e.short_id.as_deref().unwrap_or(&e.issue_id);
+                    let hours = e.hours.map_or_else(String::new, |h| format!(", {h:.1}h"));
+                    format!("- {} ({id}{hours})", excerpt(&e.text))
+                })
+                .collect(),
+        ),
+        ("In progress", output.in_progress.iter().map(issue_line).collect()),
+    ];
+
+    let mut empty = true;
+    for (heading, lines) in sections.iter().filter(|(_, lines)| !lines.is_empty()) {
+        empty = false;
+        let _ = write!(md, "\n### {heading}\n");
+        for line in lines {
+            let _ = writeln!(md, "{line}");
+        }
+    }
+    if empty {
+        md.push_str("\nNothing recorded.\n");
+    }
+    md
+}
+
+/// The first line of `text`, cut to [`DECISION_CHARS`].
+fn excerpt(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > DECISION_CHARS {
+        let cut: String = line.chars().take(DECISION_CHARS - 3).collect();
+        format!("{cut}...")
+    } else {
+        line.to_string()
+    }
+}
+
+/// Rewrite `markdown` with the summarize endpoint.
+fn polish_markdown(markdown: &str, settings: &SummarizeConfig) -> Result<String> {
+    Ok(format!("{}\n", settings.complete(POLISH_PROMPT, markdown)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::IssueType;
+    use chrono::Datelike;
+
+    fn issue(id: &str, status: IssueStatus, created_at: i64) -> Issue {
+        Issue {
+            id: id.to_string(),
+            short_id: None,
+            project_path: "/p".to_string(),
+            title: id.to_string(),
+            description: None,
+            details: None,
+            status,
+            priority: 2,
+            issue_type: IssueType::Task,
+            plan_id: None,
+            created_by_agent: None,
+            assigned_to_agent: None,
+            created_at,
+            updated_at: created_at,
+            closed_at: None,
+            subproject: None,
+            estimate_hours: None,
+        }
+    }
+
+    fn change(issue_id: &str, from: Option<&str>, to: &str, at: i64) -> IssueStatusChange {
+        IssueStatusChange {
+            issue_id: issue_id.to_string(),
+            from: from.map(String::from),
+            to: to.to_string(),
+            actor: "alice".to_string(),
+            at,
+        }
+    }
+
+    #[test]
+    fn test_flow_replays_status_changes() {
+        // Day n ends at (n + 1) * 100
+        let date = |n: u32| NaiveDate::from_ymd_opt(2026, 1, n + 10).unwrap();
+        let end = |d: NaiveDate| (i64::from(d.day()) - 9) * 100;
+        let issues = [
+            issue("a", IssueStatus::Closed, 50),
+            issue("b", IssueStatus::Open, 150),
+            issue("c", IssueStatus::InProgress, 250),
+        ];
+        let changes = [
+            change("a", Some("open"), "in_progress", 120),
+            change("a", Some("in_progress"), "closed", 210),
+            change("c", None, "in_progress", 260),
+        ];
+
+        let output = flow_series(&issues, &changes, &[date(0), date(1), date(2)], end);
+        let counts = |day: usize, status: &str| output.days[day].counts[status];
+        assert_eq!((counts(0, "open"), output.days[0].total), (1, 1));
+        assert_eq!((counts(1, "in_progress"), counts(1, "open")), (1, 1));
+        assert_eq!((counts(2, "closed"), counts(2, "in_progress"), output.days[2].remaining), (1, 1, 2));
+        assert_eq!((output.days[2].created, output.days[2].closed), (1, 1));
+        assert_eq!(sparkline(&[0, 1, 5, 9]), " .+@");
+    }
+
+    #[test]
+    fn test_standup_covers_only_the_actor_since_the_cutoff() {
+        let event = |entity_type: &str, entity_id: &str, event_type: &str, actor: &str, at: i64| StoredEvent {
+            id: 0,
+            entity_type: entity_type.to_string(),
+            entity_id: entity_id.to_string(),
+            event_type: event_type.to_string(),
+            actor: actor.to_string(),
+            old_value: None,
+            new_value: None,
+            comment: None,
+            created_at: at,
+        };
+        let decision = |id: &str, at: i64| ContextItem {
+            id: id.to_string(),
+            session_id: "s".to_string(),
+            key: id.to_string(),
+            value: "Use JWT\nbecause sessions don't scale".to_string(),
+            category: ItemCategory::Decision,
+            priority: crate::model::ItemPriority::Normal,
+            channel: None,
+            tags: None,
+            size: 0,
+            created_at: at,
+            updated_at: at,
+            source: crate::model::ItemSource::Human,
+            trust: crate::model::TrustLevel::Human,
+            review_status: crate::model::ReviewStatus::Unreviewed,
+            due_at: None,
+            done_at: None,
+        };
+        let mut mine = issue("a", IssueStatus::InProgress, 0);
+        mine.assigned_to_agent = Some("alice".to_string());
+        let mut changes = vec![
+            change("b", Some("open"), "closed", 150),
+            change("c", Some("open"), "closed", 50),
+            change("d", Some("open"), "closed", 150),
+        ];
+        changes[2].actor = "bob".to_string();
+        let history = History {
+            issues: vec![
+                mine,
+                issue("b", IssueStatus::Closed, 0),
+                issue("c", IssueStatus::Closed, 0),
+                issue("d", IssueStatus::Closed, 0),
+                issue("e", IssueStatus::InProgress, 0),
+            ],
+            changes,
+            items: vec![decision("auth", 120), decision("db", 130), decision("old", 10)],
+            checkpoints: Vec::new(),
+            events: vec![
+                event("context_item", "auth", "item_created", "alice", 120),
+                event("context_item", "db", "item_created", "bob", 130),
+            ],
+            worklog: vec![WorklogEntry {
+                issue_id: "a".to_string(),
+                short_id: Some("SC-a".to_string()),
+                actor: "alice".to_string(),
+                text: "Suspect a race in the pool".to_string(),
+                time_entry_id: None,
+                hours: Some(1.5),
+                created_at: 140,
+            }],
+        };
+
+        let output = standup_report(&history, Some("alice".to_string()), 100);
+        let ids = |issues: &[StandupIssue]| issues.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&output.closed), ["b"]);
+        assert_eq!(ids(&output.in_progress), ["a"]);
+        assert_eq!(output.decisions.len(), 1);
+        assert!(output.markdown.contains("### Done\n- b (b)\n"));
+        assert!(output.markdown.contains("- **auth**: Use JWT\n"));
+        assert!(output.markdown.contains("### Worklog\n- Suspect a race in the pool (SC-a, 1.5h)\n"));
+        assert!(!output.markdown.contains("### Checkpoints"));
+
+        let everyone = standup_report(&history, None, 100);
+        assert_eq!((everyone.closed.len(), everyone.decisions.len(), everyone.in_progress.len()), (2, 2, 2));
+        assert!(standup_report(&history, Some("bob".to_string()), 100).worklog.is_empty());
+    }
+
+    #[test]
+    fn test_since_millis() {
+        let now = Local.with_ymd_and_hms(2026, 3, 10, 15, 30, 0).unwrap();
+        let day = |d: u32| start_of_day(NaiveDate::from_ymd_opt(2026, 3, d).unwrap());
+        assert_eq!(since_millis("yesterday", now).unwrap(), day(9));
+        assert_eq!(since_millis("Today", now).unwrap(), day(10));
+        assert_eq!(since_millis("2026-03-01", now).unwrap(), day(1));
+        assert_eq!(since_millis("2h", now).unwrap(), now.timestamp_millis() - 7_200_000);
+        assert_eq!(since_millis("3d", now).unwrap(), now.timestamp_millis() - 3 * 86_400_000);
+        assert!(since_millis("last week", now).is_err());
+        assert!(since_millis("h", now).is_err());
+    }
+}
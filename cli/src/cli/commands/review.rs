@@ -0,0 +1,149 @@
+//! Review of agent-written decisions (`sc review`).
+//!
+//! A decision an agent saves is its best guess until a person has looked
+//! at it. `sc review queue` lists the agent decisions nobody has reviewed
+//! yet; `sc review approve` and `sc review reject` settle one, keeping the
+//! optional `--comment` on the audit event. Approving also raises the
+//! item's trust to `human`.
+//!
+//! Prime flags decisions still in the queue, or leaves them out with
+//! `"prime": { "unreviewed": "exclude" }`, and never includes rejected
+//! ones. An agent rewriting a reviewed decision puts it back in the queue.
+
+use crate::cli::{Database, ReviewCommands};
+use crate::config::{default_actor, resolve_project_path, resolve_session_or_suggest};
+use crate::error::Result;
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemCategory, ReviewStatus};
+use crate::storage::{ContextItem, SqliteStorage};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Output for review queue.
+#[derive(Serialize)]
+struct ReviewQueueOutput {
+    items: Vec<ContextItem>,
+    count: usize,
+}
+
+/// Output for review approve and reject.
+#[derive(Serialize)]
+struct ReviewOutput {
+    key: String,
+    review_status: ReviewStatus,
+    comment: Option<String>,
+}
+
+json_schema!(ReviewQueueOutput { items: Vec<ContextItem>, count: usize });
+json_schema!(ReviewOutput { key: String, review_status: ReviewStatus, comment: Option<String> });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("review queue", schema_for::<ReviewQueueOutput>()),
+        ("review approve", schema_for::<ReviewOutput>()),
+        ("review reject", schema_for::<ReviewOutput>()),
+    ]
+}
+
+/// Execute review commands.
+///
+/// # Errors
+///
+/// Returns an error if no session can be resolved, the item doesn't exist,
+/// or the reviewer is registered as an agent.
+pub fn execute(
+    command: &ReviewCommands,
+    db: &mut Database,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+
+    match command {
+        ReviewCommands::Queue { all_sessions } => queue(storage, session_id, *all_sessions, json),
+        ReviewCommands::Approve { key, comment } => {
+            review(storage, session_id, key, ReviewStatus::Approved, comment.as_deref(), actor, json)
+        }
+        ReviewCommands::Reject { key, comment } => {
+            review(storage, session_id, key, ReviewStatus::Rejected, comment.as_deref(), actor, json)
+        }
+    }
+}
+
+fn queue(storage: &SqliteStorage, session_id: Option<&str>, all_sessions: bool, json: bool) -> Result<()> {
+    let mut items = if all_sessions {
+        let project_path = resolve_project_path(storage, None)?;
+        storage.get_context_items_by_project(&project_path)?
+    } else {
+        let session_id = resolve_session_or_suggest(session_id, storage)?;
+        storage.get_context_items(&session_id, Some(ItemCategory::Decision), None, Some(u32::MAX))?
+    };
+    items.retain(ContextItem::awaits_review);
+    // Oldest first: the longest waiting get looked at first
+    items.sort_by_key(|item| item.created_at);
+
+    if crate::is_silent() {
+        for item in &items {
+            println!("{}", item.key);
+        }
+        return Ok(());
+    }
+    if json {
+        let output = ReviewQueueOutput { count: items.len(), items };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    if items.is_empty() {
+        println!("No decisions awaiting review.");
+        return Ok(());
+    }
+
+    println!("Decisions awaiting review ({}):", items.len());
+    println!();
+    for item in &items {
+        let saved = chrono::DateTime::from_timestamp_millis(item.created_at)
+            .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let session = if all_sessions { format!(" session {}", item.session_id) } else { String::new() };
+        println!("  {} {}", item.key.bold(), format!("({saved}{session})").dimmed());
+        let value: String = item.value.chars().take(100).collect();
+        let ellipsis = if item.value.chars().count() > 100 { "..." } else { "" };
+        println!("    {value}{ellipsis}");
+    }
+    println!();
+    println!("Settle one with `sc review approve <key>` or `sc review reject <key> --comment \"...\"`.");
+    Ok(())
+}
+
+fn review(
+    storage: &mut SqliteStorage,
+    session_id: Option<&str>,
+    key: &str,
+    status: ReviewStatus,
+    comment: Option<&str>,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let session_id = resolve_session_or_suggest(session_id, storage)?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    let verb = if status == ReviewStatus::Approved { "approve" } else { "reject" };
+    if crate::is_dry_run() {
+        println!("Would {verb}: {key}");
+        return Ok(());
+    }
+
+    storage.review_context_item(&session_id, key, status, comment, &actor)?;
+
+    if crate::is_silent() {
+        println!("{key}");
+    } else if json {
+        let output = ReviewOutput { key: key.to_string(), review_status: status, comment: comment.map(ToString::to_string) };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("{}: {key}", if status == ReviewStatus::Approved { "Approved" } else { "Rejected" });
+    }
+    Ok(())
+}
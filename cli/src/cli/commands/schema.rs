@@ -0,0 +1,196 @@
+//! Schema command implementation.
+//!
+//! `sc schema` lists the commands whose `--json` output has a published
+//! schema; `sc schema <command>` prints that schema, `sc schema error` the
+//! error envelope, and `sc schema timing` the `--timing` line. Both of those
+//! go to stderr, so stdout only ever holds the command's own output. With
+//! `--json` and no command, every schema is printed as one document under
+//! `$defs`, keyed by command.
+//!
+//! `--dry-run` previews print their own `{"dry_run": true, ...}` objects and
+//! are not covered.
+
+use crate::error::{Error, Result};
+use crate::json_schema::{document, error_schema, timing_schema, DIALECT};
+use serde_json::{Map, Value};
+
+/// Every published schema, by command, with the stderr envelopes last.
+fn all() -> Vec<(&'static str, Value)> {
+    use super::{
+        actor, apply, archive, bench, capabilities, category, checkpoint, compaction, config, context, critical_path,
+        dashboard, db, edit_context, embeddings, events, git, graph, grep, inbox, init, issue, issue_triage, memory,
+        memory_audit, memory_harvest, plan, policy, prime, project, remind, report, review, search, seed, session,
+        session_usage, setup, share, skills, stats, status, sync, team, time_entry, trash, vault, version, watch,
+    };
+
+    let mut schemas = Vec::new();
+    for module in [
+        version::output_schemas,
+        capabilities::output_schemas,
+        status::output_schemas,
+        session::output_schemas,
+        context::output_schemas,
+        edit_context::output_schemas,
+        memory::output_schemas,
+        memory_audit::output_schemas,
+        memory_harvest::output_schemas,
+        category::output_schemas,
+        git::output_schemas,
+        graph::output_schemas,
+        dashboard::output_schemas,
+        grep::output_schemas,
+        issue::output_schemas,
+        issue_triage::output_schemas,
+        critical_path::output_schemas,
+        checkpoint::output_schemas,
+        plan::output_schemas,
+        project::output_schemas,
+        time_entry::output_schemas,
+        prime::output_schemas,
+        compaction::output_schemas,
+        report::output_schemas,
+        remind::output_schemas,
+        review::output_schemas,
+        share::output_schemas,
+        actor::output_schemas,
+        team::output_schemas,
+        policy::output_schemas,
+        watch::output_schemas,
+        sync::output_schemas,
+        archive::output_schemas,
+        seed::output_schemas,
+        init::output_schemas,
+        setup::output_schemas,
+        search::output_schemas,
+        inbox::output_schemas,
+        stats::output_schemas,
+        db::output_schemas,
+        bench::output_schemas,
+        trash::output_schemas,
+        events::output_schemas,
+        vault::output_schemas,
+        apply::output_schemas,
+        session_usage::output_schemas,
+        embeddings::output_schemas,
+        skills::output_schemas,
+        config::output_schemas,
+    ] {
+        schemas.extend(module());
+    }
+    schemas.push(("error", error_schema()));
+    schemas.push(("timing", timing_schema()));
+    schemas
+}
+
+/// Execute the schema command.
+///
+/// # Errors
+///
+/// Returns an error if no schema is published for `command`.
+pub fn execute(command: &[String], json: bool) -> Result<()> {
+    let schemas = all();
+
+    if command.is_empty() {
+        if json {
+            let defs: Map<String, Value> =
+                schemas.into_iter().map(|(name, schema)| (name.to_string(), schema)).collect();
+            let bundle = serde_json::json!({
+                "$schema": DIALECT,
+                "title": "sc --json output",
+                "$defs": defs,
+            });
+            println!("{}", serde_json::to_string(&bundle)?);
+        } else {
+            println!("JSON Schemas ({}):", schemas.len());
+            for (name, _) in &schemas {
+                println!("  {name}");
+            }
+            println!("\nPrint one with: sc schema <command>");
+        }
+        return Ok(());
+    }
+
+    let name = command.join(" ");
+    let names: Vec<String> = schemas.iter().map(|(n, _)| (*n).to_string()).collect();
+    let Some((_, schema)) = schemas.into_iter().find(|(n, _)| *n == name) else {
+        let similar = crate::validate::find_similar_ids(&name, &names, 3);
+        let hint = if similar.is_empty() {
+            "Run `sc schema` to list commands with one".to_string()
+        } else {
+            format!("Did you mean: {}?", similar.join(", "))
+        };
+        return Err(Error::InvalidArgument(format!("No JSON Schema for '{name}'. {hint}")));
+    };
+
+    let title = match name.as_str() {
+        "error" => "sc error output".to_string(),
+        "timing" => "sc --timing output".to_string(),
+        _ => format!("sc {name} --json"),
+    };
+    let doc = document(&title, schema);
+    if json {
+        println!("{}", serde_json::to_string(&doc)?);
+    } else {
+        println!("{}", serde_json::to_string_pretty(&doc)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use std::collections::HashSet;
+
+    /// Commands without `--json` output of their own, and why.
+    const NO_JSON_OUTPUT: &[(&str, &str)] = &[
+        ("schema", "prints JSON Schemas"),
+        ("shell", "interactive"),
+        ("serve", "speaks its own protocol"),
+        ("completions", "prints a shell script"),
+        ("remote", "relays the remote command's output"),
+    ];
+
+    /// Schemas of what `sc` writes to stderr rather than a command's output.
+    const STDERR: &[&str] = &["error", "timing"];
+
+    /// Space-separated paths of the visible commands that run on their own,
+    /// including those whose subcommand is optional.
+    fn runnable_commands(command: &clap::Command, prefix: &str, out: &mut Vec<String>) {
+        for sub in command.get_subcommands().filter(|c| !c.is_hide_set() && c.get_name() != "help") {
+            let path = if prefix.is_empty() { sub.get_name().to_string() } else { format!("{prefix} {}", sub.get_name()) };
+            if !sub.is_subcommand_required_set() {
+                out.push(path.clone());
+            }
+            runnable_commands(sub, &path, out);
+        }
+    }
+
+    #[test]
+    fn test_every_command_has_a_schema() {
+        let schemas = all();
+        let names: HashSet<&str> =
+            schemas.iter().map(|(name, _)| *name).chain(NO_JSON_OUTPUT.iter().map(|(name, _)| *name)).collect();
+        let mut commands = Vec::new();
+        runnable_commands(&crate::cli::Cli::command(), "", &mut commands);
+        let missing: Vec<&String> = commands.iter().filter(|command| !names.contains(command.as_str())).collect();
+        assert!(missing.is_empty(), "no JSON Schema for: {missing:?}");
+        let unknown: Vec<&str> =
+            names.iter().copied().filter(|name| !STDERR.contains(name) && !commands.iter().any(|c| c == name)).collect();
+        assert!(unknown.is_empty(), "JSON Schema for no command: {unknown:?}");
+    }
+
+    #[test]
+    fn test_every_schema_is_a_closed_object_or_choice() {
+        let schemas = all();
+        let names: HashSet<&str> = schemas.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names.len(), schemas.len(), "duplicate command names");
+        for (name, schema) in &schemas {
+            let branches = schema["oneOf"].as_array().cloned().unwrap_or_else(|| vec![schema.clone()]);
+            for branch in branches {
+                assert_eq!(branch["type"], "object", "{name}");
+                assert_eq!(branch["additionalProperties"], false, "{name}");
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Semantic search across entities (`sc search`).
+//!
+//! `sc get -s` searches a session's context items; this searches
+//! everything that has quality embeddings — context items, issues, plans
+//! and memories — in one ranked list, scoped to the current project
+//! unless `--all-projects` is given. Issues, plans and memories get their
+//! embeddings from `sc embeddings backfill` and the background embedder.
+//!
+//! `--related` on `sc issue show` and `sc get --key` runs the same search
+//! with the shown record's own embedding as the query.
+
+use crate::cli::Database;
+use crate::config::resolve_project_path;
+use crate::embeddings::{
+    create_embedding_provider, embed_with_retry, is_embeddings_enabled, prepare_item_text, EmbeddingEntity,
+    RateLimiter,
+};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::storage::{EntitySearchResult, SqliteStorage};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Records listed by `--related` on show commands.
+const RELATED_LIMIT: usize = 5;
+
+/// Least similarity for a record to count as related.
+const RELATED_THRESHOLD: f32 = 0.3;
+
+/// Options for `sc search`.
+#[derive(Serialize)]
+struct SearchOutput<'a> {
+    query: &'a str,
+    entities: Vec<&'a str>,
+    results: Vec<EntitySearchResult>,
+    count: usize,
+}
+
+json_schema!(SearchOutput<'a> { query: &'a str, entities: Vec<&'a str>, results: Vec<EntitySearchResult>, count: usize });
+
+/// Schemas of the `--json` output, by command. With `--stream` each line
+/// is one result.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("search", one_of(&[schema_for::<SearchOutput<'_>>(), schema_for::<EntitySearchResult>()]))]
+}
+
+pub struct SearchOptions<'a> {
+    pub query: &'a str,
+    pub entities: &'a [EmbeddingEntity],
+    pub all_projects: bool,
+    pub threshold: f32,
+    pub limit: usize,
+    /// One JSON line per result, in rank order
+    pub stream: bool,
+}
+
+/// Execute the search command.
+///
+/// # Errors
+///
+/// Returns an error if embeddings are disabled, no quality provider is
+/// available, or the database cannot be queried.
+pub fn execute(options: &SearchOptions<'_>, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    if !is_embeddings_enabled() {
+        return Err(Error::Embedding(
+            "Semantic search needs embeddings. Enable them with `sc embeddings configure --enable`".to_string(),
+        ));
+    }
+
+    let project_path = if options.all_projects {
+        None
+    } else {
+        resolve_project_path(storage, None).ok()
+    };
+
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| Error::Other(format!("Failed to create async runtime: {e}")))?;
+    let query_embedding = rt.block_on(async {
+        let provider = create_embedding_provider()
+            .await
+            .ok_or_else(|| Error::Embedding("No quality embedding provider available".to_string()))?;
+        super::embeddings::ensure_model_compatible(storage, &provider)?;
+        let query_text = prepare_item_text("query", options.query, None);
+        embed_with_retry(&provider, &RateLimiter::new(None), &query_text)
+            .await
+            .map(|result| result.embedding)
+    })?;
+
+    let entities = if options.entities.is_empty() { &EmbeddingEntity::ALL[..] } else { options.entities };
+    let types: Vec<&str> = entities.iter().map(|e| e.as_str()).collect();
+    let results = storage.semantic_search_entities(
+        &query_embedding,
+        &types,
+        project_path.as_deref(),
+        options.limit,
+        options.threshold,
+    )?;
+
+    if options.stream {
+        for result in &results {
+            crate::cli::shell::emit(result)?;
+        }
+        return Ok(());
+    }
+    if json {
+        let output = SearchOutput { query: options.query, entities: types, count: results.len(), results };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        println!("No matches for \"{}\" in {}.", options.query, types.join(", "));
+        println!("Run `sc embeddings backfill` if issues, plans or memories were never embedded.");
+        return Ok(());
+    }
+    println!("Matches for \"{}\" ({}):", options.query, results.len());
+    println!();
+    for result in &results {
+        print_result(result);
+    }
+    Ok(())
+}
+
+fn print_result(result: &EntitySearchResult) {
+    println!("[{}] {}  ({:.0}%)", label(result), result.title, result.similarity * 100.0);
+    let excerpt: String = result.chunk_text.replace('\n', " ").chars().take(100).collect();
+    println!("    {excerpt}");
+    println!();
+}
+
+/// The records of a project closest in meaning to an embedded one, found
+/// with its stored embedding, so no provider is needed. `None` if the
+/// record hasn't been embedded yet.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be queried.
+pub(crate) fn related(
+    storage: &SqliteStorage,
+    entity: EmbeddingEntity,
+    id: &str,
+    project_path: Option<&str>,
+) -> Result<Option<Vec<EntitySearchResult>>> {
+    let Some(embedding) = storage.get_entity_embedding(entity.as_str(), id)? else {
+        return Ok(None);
+    };
+    let types: Vec<&str> = EmbeddingEntity::ALL.iter().map(|e| e.as_str()).collect();
+    let mut results =
+        storage.semantic_search_entities(&embedding, &types, project_path, RELATED_LIMIT + 1, RELATED_THRESHOLD)?;
+    results.retain(|r| !(r.entity_type == entity.as_str() && r.entity_id == id));
+    results.truncate(RELATED_LIMIT);
+    Ok(Some(results))
+}
+
+/// The `--related` section of a show command; `what` names the record.
+pub(crate) fn print_related(related: Option<&[EntitySearchResult]>, what: &str) {
+    let Some(related) = related else {
+        println!("Related: this {what} has no embedding yet (sc embeddings backfill)");
+        return;
+    };
+    if related.is_empty() {
+        println!("Related: nothing similar in the project");
+        return;
+    }
+    println!("Related:");
+    for result in related {
+        println!("  [{}] {}  ({:.0}%)", label(result), result.title, result.similarity * 100.0);
+    }
+}
+
+fn label(result: &EntitySearchResult) -> String {
+    match (&result.short_id, result.entity_type.as_str()) {
+        (Some(short_id), kind) => format!("{kind} {short_id}"),
+        (None, "context_item") => "item".to_string(),
+        (None, kind) => kind.to_string(),
+    }
+}
@@ -0,0 +1,333 @@
+//! Demo data (`sc seed --demo`).
+//!
+//! Trying out `sc issue ready`, `sc prime` or the TUI on an empty database
+//! shows very little. `sc seed --demo` registers a small made-up project
+//! (an API service midway through an auth rewrite and a search overhaul)
+//! with sessions, decisions, a checkpoint, a plan, an epic with blocked
+//! and claimed work, project memory and logged time, all under a
+//! directory of its own so real projects are untouched. Items are left
+//! unembedded; `sc embeddings backfill` embeds them with the configured
+//! provider.
+
+use crate::cli::Database;
+use crate::cli::commands::config::load_issue_workflow;
+use crate::config::{default_actor, global_savecontext_dir, normalize_path};
+use crate::core::{NewIssue, NewItem, NewSession, SaveContext};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{IssueStatus, IssueType, ItemCategory, ItemPriority, MemoryCategory, Plan, PlanStatus, Project, SessionStatus};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Name of the demo project.
+const DEMO_NAME: &str = "Acme API (demo)";
+
+/// Records created by [`populate_demo`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DemoCounts {
+    pub sessions: usize,
+    pub items: usize,
+    pub checkpoints: usize,
+    pub plans: usize,
+    pub issues: usize,
+    pub memory: usize,
+    pub time_entries: usize,
+}
+
+json_schema!(DemoCounts {
+    sessions: usize,
+    items: usize,
+    checkpoints: usize,
+    plans: usize,
+    issues: usize,
+    memory: usize,
+    time_entries: usize,
+});
+
+/// Output for seed.
+#[derive(Serialize)]
+struct SeedOutput {
+    project_path: String,
+    name: String,
+    /// Whether an earlier demo project at the path was deleted first.
+    replaced: bool,
+    created: DemoCounts,
+}
+
+json_schema!(SeedOutput { project_path: String, name: String, replaced: bool, created: DemoCounts });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("seed", schema_for::<SeedOutput>())]
+}
+
+/// Execute `sc seed --demo`: register the demo project at `path`
+/// (default `~/.savecontext/demo`) and fill it.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened, a project already
+/// exists at the path and `force` isn't set, or a write fails.
+pub fn execute(path: Option<&Path>, force: bool, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let dir = match path {
+        Some(path) => path.to_path_buf(),
+        None => global_savecontext_dir().ok_or_else(|| Error::Config("Cannot find the home directory".to_string()))?.join("demo"),
+    };
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+
+    let existing = storage.get_project_by_path(&normalize_path(&dir))?;
+    if existing.is_some() && !force {
+        return Err(Error::InvalidArgument(format!(
+            "A project is already registered at {}; pass --force to replace it with a fresh demo",
+            dir.display()
+        )));
+    }
+    if crate::is_dry_run() {
+        let verb = if existing.is_some() { "replace" } else { "create" };
+        println!("Would {verb} demo project at {}", dir.display());
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    let project_path = normalize_path(&dir);
+    let replaced = existing.is_some();
+    storage.with_transaction(|s| {
+        let result = (|| {
+            if let Some(project) = &existing {
+                s.delete_project(&project.id, &actor)?;
+            }
+            s.create_project(&Project::new(project_path.clone(), DEMO_NAME.to_string()), &actor)
+        })();
+        let commit = result.is_ok();
+        (result, commit)
+    })??;
+    let mut sc = SaveContext::borrowing(storage, project_path.clone(), actor);
+    let created = populate_demo(&mut sc)?;
+
+    if json {
+        let output = SeedOutput { project_path, name: DEMO_NAME.to_string(), replaced, created };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if crate::is_silent() {
+        println!("{project_path}");
+    } else {
+        let verb = if replaced { "Replaced" } else { "Created" };
+        println!("{verb} demo project {DEMO_NAME} at {project_path}");
+        println!(
+            "  {} sessions, {} context items, {} checkpoint, {} plan, {} issues, {} memory items, {} time entries",
+            created.sessions, created.items, created.checkpoints, created.plans, created.issues, created.memory, created.time_entries
+        );
+        println!("\nTry it:");
+        println!("  cd {project_path}");
+        println!("  sc issue ready");
+        println!("  sc prime --compact");
+        println!("  sc embeddings backfill   # then: sc search \"token refresh\"");
+    }
+    Ok(())
+}
+
+/// Fill the project `sc` acts on with the demo data. The project must be
+/// registered.
+///
+/// # Errors
+///
+/// Returns [`Error::ProjectNotFound`] if it isn't, or an error if a write
+/// fails.
+pub fn populate_demo(sc: &mut SaveContext) -> Result<DemoCounts> {
+    let project = sc
+        .storage()
+        .get_project_by_path(sc.project_path())?
+        .ok_or_else(|| Error::ProjectNotFound { id: sc.project_path().to_string() })?;
+    let mut counts = DemoCounts::default();
+    for (key, value, category) in [
+        ("test", "cargo test --workspace", MemoryCategory::Command),
+        ("lint", "cargo clippy --all-targets -- -D warnings", MemoryCategory::Command),
+        ("dev-server", "docker compose up -d db && cargo run -- serve --port 8080", MemoryCategory::Command),
+        ("staging-url", "https://staging.acme.example", MemoryCategory::Config),
+        ("release-process", "Tag vX.Y.Z on main; CI publishes the image and deploys to staging", MemoryCategory::Note),
+    ] {
+        sc.save_memory(key, value, category)?;
+        counts.memory += 1;
+    }
+    demo_auth_session(sc, &mut counts)?;
+    let issues = demo_search_plan(sc, &project.id, &mut counts)?;
+    demo_search_session(sc, &mut counts)?;
+    demo_time(sc, &issues, &mut counts)?;
+    Ok(counts)
+}
+
+/// A finished session with a checkpoint.
+fn demo_auth_session(sc: &mut SaveContext, counts: &mut DemoCounts) -> Result<()> {
+    let actor = sc.actor().to_string();
+    let auth = sc.start_session(&session("Auth rewrite", "Replace session cookies with JWTs", "feature/jwt-auth"))?.session;
+    for (key, value, category, priority) in [
+        (
+            "auth-token-format",
+            "Access tokens are JWTs signed with EdDSA, 15 minute lifetime; refresh tokens are opaque and rotated on use",
+            ItemCategory::Decision,
+            ItemPriority::High,
+        ),
+        (
+            "auth-refresh-storage",
+            "Refresh tokens are stored hashed (SHA-256) in the refresh_tokens table, one row per device",
+            ItemCategory::Decision,
+            ItemPriority::Normal,
+        ),
+        (
+            "auth-middleware",
+            "Middleware validates the JWT and puts the user ID in request extensions; all /v1 routes migrated",
+            ItemCategory::Progress,
+            ItemPriority::Normal,
+        ),
+        (
+            "auth-clock-skew",
+            "Mobile clients drift by up to 90s; validation allows 2 minutes of leeway",
+            ItemCategory::Note,
+            ItemPriority::Low,
+        ),
+    ] {
+        sc.save_item(&auth.id, &item(key, value, category, priority))?;
+        counts.items += 1;
+    }
+    let checkpoint = format!("ckpt_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+    sc.storage_mut().create_checkpoint(&checkpoint, &auth.id, "jwt-auth-done", Some("All routes on JWT auth"), None, Some("feature/jwt-auth"), &actor)?;
+    let keys = ["auth-token-format", "auth-refresh-storage", "auth-middleware"].map(String::from);
+    sc.storage_mut().add_checkpoint_items_by_keys(&checkpoint, &auth.id, &keys, &actor)?;
+    counts.checkpoints += 1;
+    sc.set_session_status(&auth.id, SessionStatus::Completed)?;
+    counts.sessions += 1;
+    Ok(())
+}
+
+/// The plan the open work hangs off, its epic and issues. Returns the IDs
+/// of the issues time is logged against.
+fn demo_search_plan(sc: &mut SaveContext, project_id: &str, counts: &mut DemoCounts) -> Result<[String; 3]> {
+    let actor = sc.actor().to_string();
+    let mut plan = Plan::new(project_id.to_string(), sc.project_path().to_string(), "Search overhaul".to_string());
+    plan.status = PlanStatus::Active;
+    plan.content = Some(
+        "# Search overhaul\n\nMove note search from LIKE queries to a full-text index, then add typo tolerance.\n\n\
+         ## Steps\n1. FTS5 index over title and body\n2. Ranking with BM25 and recency\n3. Typo tolerance via trigram fallback\n"
+            .to_string(),
+    );
+    plan.success_criteria = Some("p95 search latency under 50ms on 1M notes; no regressions in the search test suite".to_string());
+    sc.storage_mut().create_plan(&plan, &actor)?;
+    counts.plans += 1;
+
+    let epic = sc.create_issue(&NewIssue {
+        title: "Search overhaul".to_string(),
+        description: Some("Full-text search with ranking and typo tolerance".to_string()),
+        issue_type: IssueType::Epic,
+        priority: Some(3),
+        plan_id: Some(plan.id.clone()),
+        labels: vec!["search".to_string()],
+        ..NewIssue::default()
+    })?;
+    let child = |title: &str, description: &str, priority: i32| NewIssue {
+        title: title.to_string(),
+        description: Some(description.to_string()),
+        priority: Some(priority),
+        plan_id: Some(plan.id.clone()),
+        parent: Some(epic.id.clone()),
+        labels: vec!["search".to_string()],
+        ..NewIssue::default()
+    };
+    let index = sc.create_issue(&child("Add FTS5 index over notes", "Virtual table kept in sync by triggers; backfill in batches of 10k", 3))?;
+    let ranking = sc.create_issue(&child("Rank results with BM25 and recency", "Blend bm25() with a 30-day recency decay", 2))?;
+    let typos = sc.create_issue(&child("Typo-tolerant search", "Fall back to trigram matching when FTS finds nothing", 1))?;
+    sc.storage_mut().add_issue_dependency(&ranking.id, &index.id, "blocks", &actor)?;
+    sc.storage_mut().add_issue_dependency(&typos.id, &index.id, "blocks", &actor)?;
+    let workflow = load_issue_workflow(Some(sc.project_path()));
+    sc.storage_mut().claim_issue(&index.id, &workflow, &actor)?;
+    sc.storage_mut().comment_issue(&index.id, "Backfill takes ~4 minutes on the staging dataset", &actor)?;
+
+    let bug = sc.create_issue(&NewIssue {
+        title: "Refresh token reuse not detected across devices".to_string(),
+        description: Some("Reusing a rotated refresh token should revoke the whole family".to_string()),
+        issue_type: IssueType::Bug,
+        priority: Some(4),
+        labels: vec!["auth".to_string(), "security".to_string()],
+        ..NewIssue::default()
+    })?;
+    let done = sc.create_issue(&NewIssue {
+        title: "Migrate /v1 routes to JWT middleware".to_string(),
+        issue_type: IssueType::Feature,
+        labels: vec!["auth".to_string()],
+        ..NewIssue::default()
+    })?;
+    sc.set_issue_status(&done.id, &IssueStatus::Closed, Some("Shipped in v2.3.0"))?;
+    sc.create_issue(&NewIssue {
+        title: "Drop the legacy sessions table".to_string(),
+        issue_type: IssueType::Chore,
+        priority: Some(1),
+        ..NewIssue::default()
+    })?;
+    counts.issues += 7;
+    Ok([done.id, bug.id, index.id])
+}
+
+/// The session in progress.
+fn demo_search_session(sc: &mut SaveContext, counts: &mut DemoCounts) -> Result<()> {
+    let search = sc.start_session(&session("Search overhaul", "FTS5 index and ranking", "feature/fts"))?.session;
+    for (key, value, category, priority) in [
+        (
+            "fts-tokenizer",
+            "Use the unicode61 tokenizer with remove_diacritics 2; porter stemming hurt code-heavy notes",
+            ItemCategory::Decision,
+            ItemPriority::High,
+        ),
+        (
+            "fts-triggers",
+            "Insert/update/delete triggers on notes keep notes_fts in sync; verified with 50k random edits",
+            ItemCategory::Progress,
+            ItemPriority::Normal,
+        ),
+        (
+            "fts-backfill",
+            "Backfill script written; run it on staging before enabling the new search endpoint",
+            ItemCategory::Reminder,
+            ItemPriority::High,
+        ),
+    ] {
+        sc.save_item(&search.id, &item(key, value, category, priority))?;
+        counts.items += 1;
+    }
+    counts.sessions += 1;
+    Ok(())
+}
+
+/// Time logged over the last three days against `issues`.
+fn demo_time(sc: &mut SaveContext, issues: &[String; 3], counts: &mut DemoCounts) -> Result<()> {
+    let actor = sc.actor().to_string();
+    let project_path = sc.project_path().to_string();
+    let today = chrono::Local::now().date_naive();
+    let [done, bug, index] = issues;
+    for (days_ago, hours, description, issue) in [
+        (2, 3.5, "JWT middleware and route migration", done),
+        (1, 2.0, "Refresh token reuse investigation", bug),
+        (0, 1.5, "FTS5 index and triggers", index),
+    ] {
+        let work_date = (today - chrono::Days::new(days_ago)).format("%Y-%m-%d").to_string();
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let (id, short_id) = (format!("time_{uuid}"), format!("TE-{}", &uuid[..4]));
+        sc.storage_mut()
+            .create_time_entry(&id, Some(&short_id), &project_path, hours, description, &work_date, Some(issue), None, &actor)?;
+        counts.time_entries += 1;
+    }
+    Ok(())
+}
+
+fn session(name: &str, description: &str, branch: &str) -> NewSession {
+    NewSession {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        channel: Some(branch.to_string()),
+        worktree: None,
+        force_new: true,
+    }
+}
+
+fn item(key: &str, value: &str, category: ItemCategory, priority: ItemPriority) -> NewItem {
+    NewItem { key: key.to_string(), value: value.to_string(), category, priority }
+}
@@ -0,0 +1,558 @@
+//! JSON-RPC server over stdio (`sc serve --jsonrpc`).
+//!
+//! Editor extensions (VS Code in particular) talk to `sc` through one
+//! long-running process instead of spawning a command per call. Requests
+//! and responses follow [JSON-RPC 2.0](https://www.jsonrpc.org/specification),
+//! including notifications and batches, framed either one message per line
+//! or with LSP-style `Content-Length` headers; each response is framed the
+//! way its request was, so `vscode-jsonrpc`'s stream reader and writer work
+//! unchanged.
+//!
+//! Methods take named params and call the same operations as the commands
+//! ([`crate::core`]); records come back in the shape `--json` prints them:
+//!
+//! | Method           | Params                                                       | Result |
+//! |------------------|--------------------------------------------------------------|--------|
+//! | `listIssues`     | `status`, `type`, `includeClosed`, `limit`                   | `{issues, count}` |
+//! | `showIssue`      | `id`                                                         | issue |
+//! | `createIssue`    | `title`, `description`, `details`, `type`, `priority`, `labels`, `parent`, `planId` | issue |
+//! | `setIssueStatus` | `id`, `status`, `reason`                                     | issue |
+//! | `saveItem`       | `key`, `value`, `category`, `priority`, `sessionId`          | item |
+//! | `getItem`        | `key`, `sessionId`                                           | item or `null` |
+//! | `listItems`      | `category`, `priority`, `limit`, `sessionId`                 | `{items, count}` |
+//! | `deleteItem`     | `key`, `sessionId`                                           | `null` |
+//! | `saveMemory`     | `key`, `value`, `category`                                   | memory |
+//! | `listMemory`     | `category`                                                   | `{items, count}` |
+//! | `listSessions`   | `status`, `limit`                                            | `{sessions, count}` |
+//! | `startSession`   | `name`, `description`, `channel`                             | `{session, resumed}` |
+//! | `prime`          | `sessionId`, `transcript`, `minTrust`                        | as `sc prime --json` |
+//!
+//! Item methods and `prime` work in `sessionId`, or else the session
+//! `sc serve` was started in (`--session` or the current one), which
+//! `startSession` replaces. Failures of an operation are error `-32000`
+//! with the structured error of `--json` (`code`, `message`, `hint`, ...)
+//! as `data`.
+//!
+//! Each method counts as the command it stands in for (`saveItem` as
+//! `save`, `setIssueStatus` as `issue update`, ...), so `permissions.commands`
+//! rules apply to it as they would on the command line.
+
+use super::prime;
+use crate::cli::metrics;
+use crate::core::{IssueFilter, ItemFilter, NewIssue, NewItem, NewSession, SaveContext};
+use crate::error::{Error, Result};
+use crate::model::{IssueStatus, IssueType, ItemCategory, ItemPriority, MemoryCategory, SessionStatus, TrustLevel};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Standard JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// An `sc` operation failed; `data` carries the structured error.
+const OPERATION_FAILED: i64 = -32000;
+
+/// Transcript entries `prime` includes when `transcript` is set.
+const PRIME_TRANSCRIPT_LIMIT: usize = 5;
+
+/// Run the JSON-RPC server until stdin closes, with `/healthz` and
+/// `/metrics` on `metrics` if given (see [`crate::cli::metrics`]).
+///
+/// # Errors
+///
+/// Returns an error if the database or project cannot be opened, the
+/// metrics address can't be listened on, or stdin or stdout fail.
+pub fn execute(
+    db_path: Option<&PathBuf>,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    metrics: Option<&str>,
+) -> Result<()> {
+    crate::storage::schema::keep_schema_warm();
+    let sc = SaveContext::discover(db_path.map(PathBuf::as_path), actor)?;
+    if let Some(addr) = metrics {
+        let db_path = crate::config::resolve_db_path(db_path.map(PathBuf::as_path)).ok_or(Error::NotInitialized)?;
+        crate::cli::metrics::serve(addr, db_path)?;
+    }
+    let session = session_id
+        .map(ToString::to_string)
+        .or_else(|| crate::config::resolve_session_id(None).ok());
+    let mut server = Server { sc, session };
+
+    let mut stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    while let Some((body, framing)) = read_message(&mut stdin)? {
+        if let Some(response) = server.handle(&body) {
+            write_message(&mut stdout, &response, framing)?;
+        }
+    }
+    Ok(())
+}
+
+/// How a message is delimited on the stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    /// One message per line.
+    Line,
+    /// `Content-Length` headers, a blank line, then the body.
+    Headers,
+}
+
+/// The next message body and its framing; `None` at end of input.
+fn read_message(reader: &mut impl BufRead) -> Result<Option<(String, Framing)>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(length) = header_value(trimmed, "content-length") else {
+            return Ok(Some((trimmed.to_string(), Framing::Line)));
+        };
+        let length: usize = length
+            .parse()
+            .map_err(|_| Error::InvalidArgument(format!("Invalid Content-Length header: {trimmed}")))?;
+        // Other headers (Content-Type) end at a blank line
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        let body = String::from_utf8(body).map_err(|e| Error::InvalidArgument(format!("Message is not UTF-8: {e}")))?;
+        return Ok(Some((body, Framing::Headers)));
+    }
+}
+
+/// The value of header `name`, in any case, if `line` is that header.
+fn header_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let (key, value) = line.split_once(':')?;
+    key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+}
+
+fn write_message(writer: &mut impl Write, message: &Value, framing: Framing) -> Result<()> {
+    let body = message.to_string();
+    match framing {
+        Framing::Line => writeln!(writer, "{body}")?,
+        Framing::Headers => write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A JSON-RPC error object.
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({ "code": self.code, "message": self.message });
+        if let Some(data) = &self.data {
+            error["data"] = data.clone();
+        }
+        error
+    }
+}
+
+impl From<Error> for RpcError {
+    fn from(e: Error) -> Self {
+        let data = e.to_structured_json()["error"].take();
+        Self { code: OPERATION_FAILED, message: e.to_string(), data: Some(data) }
+    }
+}
+
+/// Server state: the open database and the session calls default to.
+struct Server {
+    sc: SaveContext<'static>,
+    session: Option<String>,
+}
+
+impl Server {
+    /// Answer one message: a request, a notification or a batch. `None`
+    /// when nothing is owed a reply.
+    fn handle(&mut self, body: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(body) {
+            Ok(message) => message,
+            Err(e) => return Some(error_response(&Value::Null, &RpcError::new(PARSE_ERROR, format!("Parse error: {e}")))),
+        };
+        match message {
+            Value::Array(batch) if batch.is_empty() => {
+                Some(error_response(&Value::Null, &RpcError::new(INVALID_REQUEST, "Empty batch")))
+            }
+            Value::Array(batch) => {
+                let responses: Vec<Value> = batch.into_iter().filter_map(|m| self.handle_one(m)).collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            message => self.handle_one(message),
+        }
+    }
+
+    fn handle_one(&mut self, message: Value) -> Option<Value> {
+        let Value::Object(mut request) = message else {
+            return Some(error_response(&Value::Null, &RpcError::new(INVALID_REQUEST, "Request must be an object")));
+        };
+        let id = request.remove("id");
+        if id.as_ref().is_some_and(|id| !matches!(id, Value::String(_) | Value::Number(_) | Value::Null)) {
+            return Some(error_response(&Value::Null, &RpcError::new(INVALID_REQUEST, "id must be a string, number or null")));
+        }
+        let version = request.get("jsonrpc").and_then(Value::as_str);
+        let Some(method) = request.get("method").and_then(Value::as_str).filter(|_| version == Some("2.0")) else {
+            let error = RpcError::new(INVALID_REQUEST, "Expected \"jsonrpc\": \"2.0\" and a method name");
+            return Some(error_response(&id.unwrap_or(Value::Null), &error));
+        };
+        let method = method.to_string();
+        let started = Instant::now();
+        let result = match request.remove("params") {
+            None | Some(Value::Null) => self.call(&method, Value::Object(serde_json::Map::new())),
+            Some(params @ Value::Object(_)) => self.call(&method, params),
+            Some(_) => Err(RpcError::new(INVALID_PARAMS, "params must be an object of named params")),
+        };
+        // Unknown methods would each add a metric
+        if !matches!(&result, Err(e) if e.code == METHOD_NOT_FOUND) {
+            metrics::observe(&method, started.elapsed(), result.is_ok());
+        }
+
+        // No id: a notification, answered with nothing even if it failed
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(&id, &error),
+        })
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> std::result::Result<Value, RpcError> {
+        if let Some(command) = command_of(method) {
+            self.sc.storage().check_command_policy(self.sc.actor(), command)?;
+        }
+        match method {
+            "listIssues" => {
+                let p: ListIssuesParams = params_of(params)?;
+                let filter =
+                    IssueFilter { status: p.status, include_closed: p.include_closed, issue_type: p.issue_type, limit: p.limit };
+                let issues = self.sc.list_issues(&filter)?;
+                Ok(json!({ "count": issues.len(), "issues": issues }))
+            }
+            "showIssue" => {
+                let p: IdParams = params_of(params)?;
+                let issue = self.sc.issue(&p.id)?.ok_or(Error::IssueNotFound { id: p.id })?;
+                to_value(&issue)
+            }
+            "createIssue" => {
+                let p: CreateIssueParams = params_of(params)?;
+                let new = NewIssue {
+                    title: p.title,
+                    description: p.description,
+                    details: p.details,
+                    issue_type: p.issue_type.unwrap_or_default(),
+                    priority: p.priority,
+                    plan_id: p.plan_id,
+                    parent: p.parent,
+                    labels: p.labels,
+                    estimate_hours: p.estimate_hours,
+                };
+                to_value(&self.sc.create_issue(&new)?)
+            }
+            "setIssueStatus" => {
+                let p: SetIssueStatusParams = params_of(params)?;
+                to_value(&self.sc.set_issue_status(&p.id, &p.status, p.reason.as_deref())?)
+            }
+            "saveItem" => {
+                let p: SaveItemParams = params_of(params)?;
+                let session = self.session(p.session_id)?;
+                let new = NewItem {
+                    key: p.key,
+                    value: p.value,
+                    category: p.category.unwrap_or_default(),
+                    priority: p.priority.unwrap_or_default(),
+                };
+                to_value(&self.sc.save_item(&session, &new)?)
+            }
+            "getItem" => {
+                let p: ItemKeyParams = params_of(params)?;
+                let session = self.session(p.session_id)?;
+                to_value(&self.sc.item(&session, &p.key)?)
+            }
+            "listItems" => {
+                let p: ListItemsParams = params_of(params)?;
+                let session = self.session(p.session_id)?;
+                let items = self.sc.items(&session, &ItemFilter { category: p.category, priority: p.priority, limit: p.limit })?;
+                Ok(json!({ "count": items.len(), "items": items }))
+            }
+            "deleteItem" => {
+                let p: ItemKeyParams = params_of(params)?;
+                let session = self.session(p.session_id)?;
+                self.sc.delete_item(&session, &p.key)?;
+                Ok(Value::Null)
+            }
+            "saveMemory" => {
+                let p: SaveMemoryParams = params_of(params)?;
+                to_value(&self.sc.save_memory(&p.key, &p.value, p.category.unwrap_or_default())?)
+            }
+            "listMemory" => {
+                let p: ListMemoryParams = params_of(params)?;
+                let items = self.sc.list_memory(p.category)?;
+                Ok(json!({ "count": items.len(), "items": items }))
+            }
+            "listSessions" => {
+                let p: ListSessionsParams = params_of(params)?;
+                let sessions = self.sc.list_sessions(p.status, p.limit)?;
+                Ok(json!({ "count": sessions.len(), "sessions": sessions }))
+            }
+            "startSession" => {
+                let p: StartSessionParams = params_of(params)?;
+                let new = NewSession {
+                    name: p.name,
+                    description: p.description,
+                    channel: p.channel,
+                    ..NewSession::default()
+                };
+                let started = self.sc.start_session(&new)?;
+                self.session = Some(started.session.id.clone());
+                Ok(json!({ "session": started.session, "resumed": started.resumed }))
+            }
+            "prime" => {
+                let p: PrimeParams = params_of(params)?;
+                let session = self.session(p.session_id)?;
+                let limit = if p.transcript { PRIME_TRANSCRIPT_LIMIT } else { 0 };
+                Ok(prime::json_value(self.sc.storage(), Some(&session), p.transcript, limit, p.min_trust)?)
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+        }
+    }
+
+    /// The session a call works in: its own, or the server's.
+    fn session(&self, session_id: Option<String>) -> Result<String> {
+        session_id.or_else(|| self.session.clone()).ok_or(Error::NoActiveSession)
+    }
+}
+
+/// The command a method stands in for, as `permissions.commands` rules
+/// name it.
+fn command_of(method: &str) -> Option<&'static str> {
+    Some(match method {
+        "listIssues" => "issue list",
+        "showIssue" => "issue show",
+        "createIssue" => "issue create",
+        "setIssueStatus" => "issue update",
+        "saveItem" => "save",
+        "getItem" | "listItems" => "get",
+        "deleteItem" => "delete",
+        "saveMemory" => "memory save",
+        "listMemory" => "memory list",
+        "listSessions" => "session list",
+        "startSession" => "session start",
+        "prime" => "prime",
+        _ => return None,
+    })
+}
+
+fn error_response(id: &Value, error: &RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() })
+}
+
+fn params_of<T: DeserializeOwned>(params: Value) -> std::result::Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {e}")))
+}
+
+fn to_value(value: &impl Serialize) -> std::result::Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| Error::from(e).into())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct IdParams {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ListIssuesParams {
+    status: Option<IssueStatus>,
+    #[serde(rename = "type")]
+    issue_type: Option<IssueType>,
+    #[serde(default)]
+    include_closed: bool,
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct CreateIssueParams {
+    title: String,
+    description: Option<String>,
+    details: Option<String>,
+    #[serde(rename = "type")]
+    issue_type: Option<IssueType>,
+    priority: Option<i32>,
+    plan_id: Option<String>,
+    parent: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    estimate_hours: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SetIssueStatusParams {
+    id: String,
+    status: IssueStatus,
+    reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SaveItemParams {
+    key: String,
+    value: String,
+    category: Option<ItemCategory>,
+    priority: Option<ItemPriority>,
+    session_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ItemKeyParams {
+    key: String,
+    session_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ListItemsParams {
+    category: Option<ItemCategory>,
+    priority: Option<ItemPriority>,
+    limit: Option<u32>,
+    session_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SaveMemoryParams {
+    key: String,
+    value: String,
+    category: Option<MemoryCategory>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ListMemoryParams {
+    category: Option<MemoryCategory>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ListSessionsParams {
+    status: Option<SessionStatus>,
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct StartSessionParams {
+    name: String,
+    description: Option<String>,
+    channel: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct PrimeParams {
+    session_id: Option<String>,
+    #[serde(default)]
+    transcript: bool,
+    min_trust: Option<TrustLevel>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SqliteStorage;
+
+    #[test]
+    fn test_jsonrpc_requests() {
+        let sc = SaveContext::with_storage(SqliteStorage::open_memory().unwrap(), "/repo", "vscode");
+        let mut server = Server { sc, session: None };
+        let mut call = |body: &str| server.handle(body);
+
+        let no_session = call(r#"{"jsonrpc":"2.0","id":1,"method":"saveItem","params":{"key":"k","value":"v"}}"#).unwrap();
+        assert_eq!(no_session["error"]["code"], OPERATION_FAILED);
+        assert_eq!(no_session["error"]["data"]["code"], "NO_ACTIVE_SESSION");
+
+        let started = call(r#"{"jsonrpc":"2.0","id":"s","method":"startSession","params":{"name":"Work"}}"#).unwrap();
+        assert_eq!(started["result"]["resumed"], false);
+
+        // A notification gets no reply; a batch gets one per request
+        assert!(call(r#"{"jsonrpc":"2.0","method":"saveItem","params":{"key":"jwt","value":"Use JWT","category":"decision"}}"#).is_none());
+        let batch = call(
+            r#"[{"jsonrpc":"2.0","id":2,"method":"listItems"},{"jsonrpc":"2.0","id":3,"method":"getItem","params":{"key":"jwt"}}]"#,
+        )
+        .unwrap();
+        assert_eq!(batch[0]["result"]["count"], 1);
+        assert_eq!(batch[1]["result"]["category"], "decision");
+
+        let issue = call(r#"{"jsonrpc":"2.0","id":4,"method":"createIssue","params":{"title":"Crash","type":"bug"}}"#).unwrap();
+        assert_eq!(issue["result"]["issue_type"], "bug");
+        let listed = call(r#"{"jsonrpc":"2.0","id":5,"method":"listIssues","params":{"type":"bug"}}"#).unwrap();
+        assert_eq!(listed["result"]["count"], 1);
+
+        let codes = |body: &str, server: &mut Server| server.handle(body).unwrap()["error"]["code"].as_i64();
+        assert_eq!(codes("{", &mut server), Some(PARSE_ERROR));
+        assert_eq!(codes("[]", &mut server), Some(INVALID_REQUEST));
+        assert_eq!(codes(r#"{"id":6,"method":"listIssues"}"#, &mut server), Some(INVALID_REQUEST));
+        assert_eq!(codes(r#"{"jsonrpc":"2.0","id":7,"method":"nope"}"#, &mut server), Some(METHOD_NOT_FOUND));
+        assert_eq!(codes(r#"{"jsonrpc":"2.0","id":8,"method":"listIssues","params":[1]}"#, &mut server), Some(INVALID_PARAMS));
+        assert_eq!(codes(r#"{"jsonrpc":"2.0","id":9,"method":"listIssues","params":{"statuz":"open"}}"#, &mut server), Some(INVALID_PARAMS));
+    }
+
+    #[test]
+    fn test_jsonrpc_command_policy() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.register_actor("vscode", None, Some(crate::model::ActorType::Agent), "admin").unwrap();
+        storage.set_permissions(Some(
+            serde_json::from_value(json!({ "commands": { "agent": { "deny": ["save", "delete"] } } })).unwrap(),
+        ));
+        let sc = SaveContext::with_storage(storage, "/repo", "vscode");
+        let mut server = Server { sc, session: None };
+
+        server.handle(r#"{"jsonrpc":"2.0","id":1,"method":"startSession","params":{"name":"Work"}}"#).unwrap();
+        for method in ["saveItem", "deleteItem"] {
+            let body = format!(r#"{{"jsonrpc":"2.0","id":2,"method":"{method}","params":{{"key":"k","value":"v"}}}}"#);
+            let denied = server.handle(&body).unwrap();
+            assert_eq!(denied["error"]["data"]["code"], "POLICY_DENIED", "{method}");
+        }
+        let listed = server.handle(r#"{"jsonrpc":"2.0","id":3,"method":"listItems"}"#).unwrap();
+        assert_eq!(listed["result"]["count"], 0);
+    }
+
+    #[test]
+    fn test_message_framing() {
+        let input = "Content-Length: 2\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n[]\n{\"a\":1}\n";
+        let mut reader = std::io::Cursor::new(input);
+        assert_eq!(read_message(&mut reader).unwrap(), Some(("[]".to_string(), Framing::Headers)));
+        assert_eq!(read_message(&mut reader).unwrap(), Some(("{\"a\":1}".to_string(), Framing::Line)));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+
+        let mut out = Vec::new();
+        write_message(&mut out, &json!({"ok": true}), Framing::Headers).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "Content-Length: 11\r\n\r\n{\"ok\":true}");
+    }
+}
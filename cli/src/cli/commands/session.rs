@@ -1,14 +1,21 @@
 //! Session command implementations.
 
-use crate::cli::SessionCommands;
+use crate::cli::{Database, SessionCommands};
 use crate::config::{
-    bind_session_to_terminal, clear_status_cache, current_git_branch,
-    default_actor, resolve_db_path, resolve_project, resolve_project_path, resolve_session_or_suggest,
+    auto_pause_hours, bind_session_to_terminal, clear_status_cache, current_git_branch,
+    current_worktree, default_actor, linked_worktree, normalize_path, resolve_project,
+    resolve_project_path, resolve_session_or_suggest,
 };
+use crate::core::{NewSession, SaveContext};
 use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use super::config::{load_config, SessionSummaryMode};
+use super::session_summary;
+use crate::model::{ItemCategory, SessionStatus};
 use crate::storage::SqliteStorage;
 use serde::Serialize;
-use std::path::PathBuf;
+use serde_json::Value;
+use std::path::Path;
 
 /// Output for session list command.
 #[derive(Serialize)]
@@ -17,6 +24,122 @@ struct SessionListOutput {
     count: usize,
 }
 
+/// Output for session start.
+#[derive(Serialize)]
+struct SessionStartOutput {
+    id: String,
+    name: String,
+    status: SessionStatus,
+    project_path: Option<String>,
+    branch: Option<String>,
+    worktree: Option<String>,
+    resumed: bool,
+}
+
+/// Output for session end, pause and resume.
+#[derive(Serialize)]
+struct SessionStatusOutput {
+    id: String,
+    name: String,
+    status: SessionStatus,
+    /// Placeholder name the session had before `end` named it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    renamed_from: Option<String>,
+    /// Key of the summary item `end` saved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary_key: Option<String>,
+}
+
+/// Output for session rename.
+#[derive(Serialize)]
+struct SessionRenameOutput {
+    id: String,
+    name: String,
+    old_name: String,
+}
+
+/// Output for session delete.
+#[derive(Serialize)]
+struct SessionDeleteOutput {
+    id: String,
+    name: String,
+    deleted: bool,
+}
+
+/// Output for session add-path.
+#[derive(Serialize)]
+struct SessionPathAddedOutput {
+    session_id: String,
+    session_name: String,
+    path_added: String,
+}
+
+/// Output for session remove-path.
+#[derive(Serialize)]
+struct SessionPathRemovedOutput {
+    session_id: String,
+    session_name: String,
+    path_removed: String,
+}
+
+/// A session paused by pause-idle.
+#[derive(Serialize)]
+struct PausedSession<'a> {
+    id: &'a str,
+    name: &'a str,
+    last_activity_at: i64,
+}
+
+/// Output for session pause-idle; one per pass with `--daemon`.
+#[derive(Serialize)]
+struct PauseIdleOutput<'a> {
+    hours: u64,
+    paused: Vec<PausedSession<'a>>,
+    count: usize,
+    dry_run: bool,
+}
+
+json_schema!(SessionListOutput { sessions: Vec<crate::storage::Session>, count: usize });
+json_schema!(SessionStartOutput {
+    id: String,
+    name: String,
+    status: SessionStatus,
+    project_path: Option<String>,
+    branch: Option<String>,
+    worktree: Option<String>,
+    resumed: bool,
+});
+json_schema!(SessionStatusOutput {
+    id: String,
+    name: String,
+    status: SessionStatus,
+    renamed_from: Option<String>,
+    summary_key: Option<String>,
+});
+json_schema!(SessionRenameOutput { id: String, name: String, old_name: String });
+json_schema!(SessionDeleteOutput { id: String, name: String, deleted: bool });
+json_schema!(SessionPathAddedOutput { session_id: String, session_name: String, path_added: String });
+json_schema!(SessionPathRemovedOutput { session_id: String, session_name: String, path_removed: String });
+json_schema!(PausedSession<'a> { id: &'a str, name: &'a str, last_activity_at: i64 });
+json_schema!(PauseIdleOutput<'a> { hours: u64, paused: Vec<PausedSession<'a>>, count: usize, dry_run: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("session start", schema_for::<SessionStartOutput>()),
+        ("session end", schema_for::<SessionStatusOutput>()),
+        ("session pause", schema_for::<SessionStatusOutput>()),
+        ("session resume", schema_for::<SessionStatusOutput>()),
+        ("session list", schema_for::<SessionListOutput>()),
+        ("session switch", schema_for::<SessionStatusOutput>()),
+        ("session rename", schema_for::<SessionRenameOutput>()),
+        ("session delete", schema_for::<SessionDeleteOutput>()),
+        ("session add-path", schema_for::<SessionPathAddedOutput>()),
+        ("session remove-path", schema_for::<SessionPathRemovedOutput>()),
+        ("session pause-idle", schema_for::<PauseIdleOutput<'_>>()),
+    ]
+}
+
 /// Execute session commands.
 ///
 /// # Errors
@@ -24,17 +147,12 @@ struct SessionListOutput {
 /// Returns an error if the database operation fails.
 pub fn execute(
     command: &SessionCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     session_id: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or_else(|| Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
+    let storage = db.storage()?;
 
     let actor = actor
         .map(ToString::to_string)
@@ -48,7 +166,7 @@ pub fn execute(
             channel,
             force_new,
         } => start(
-            &db_path,
+            storage,
             name,
             description.as_deref(),
             project.as_deref(),
@@ -57,9 +175,9 @@ pub fn execute(
             &actor,
             json,
         ),
-        SessionCommands::End => end(&db_path, session_id, &actor, json),
-        SessionCommands::Pause => pause(&db_path, session_id, &actor, json),
-        SessionCommands::Resume { id } => resume(&db_path, id, &actor, json),
+        SessionCommands::End { no_summary } => end(storage, session_id, *no_summary, &actor, json),
+        SessionCommands::Pause => pause(storage, session_id, &actor, json),
+        SessionCommands::Resume { id } => resume(storage, id, &actor, json),
         SessionCommands::List {
             status,
             limit,
@@ -67,31 +185,39 @@ pub fn execute(
             project,
             all_projects,
             include_completed,
+            worktree,
         } => list(
-            &db_path,
+            storage,
             status,
             *limit,
             search.as_deref(),
             project.as_deref(),
             *all_projects,
             *include_completed,
+            worktree.as_deref(),
             json,
         ),
-        SessionCommands::Switch { id } => switch(&db_path, id, &actor, json),
-        SessionCommands::Rename { name } => rename(&db_path, session_id, name, &actor, json),
-        SessionCommands::Delete { id, force } => delete(&db_path, id, *force, &actor, json),
+        SessionCommands::Switch { id } => switch(storage, id, &actor, json),
+        SessionCommands::Rename { name } => rename(storage, session_id, name, &actor, json),
+        SessionCommands::Delete { id, force } => delete(storage, id, *force, &actor, json),
         SessionCommands::AddPath { id, path } => {
-            add_path(&db_path, id.as_deref(), path.as_deref(), &actor, json)
+            add_path(storage, id.as_deref(), path.as_deref(), &actor, json)
         }
         SessionCommands::RemovePath { id, path } => {
-            remove_path(&db_path, id.as_deref(), path, &actor, json)
+            remove_path(storage, id.as_deref(), path, &actor, json)
+        }
+        SessionCommands::Usage { command } => {
+            super::session_usage::execute(command, storage, session_id, &actor, json)
+        }
+        SessionCommands::PauseIdle { hours, daemon, metrics } => {
+            pause_idle(db, *hours, *daemon, metrics.as_deref(), &actor, json)
         }
     }
 }
 
 /// Start a new session.
 fn start(
-    db_path: &PathBuf,
+    storage: &mut SqliteStorage,
     name: &str,
     description: Option<&str>,
     project: Option<&str>,
@@ -100,147 +226,151 @@ fn start(
     actor: &str,
     json: bool,
 ) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
 
     // Resolve project: validates against DB, accepts ID or path, auto-detects from CWD
-    let resolved = resolve_project(&storage, project)?;
+    let resolved = resolve_project(storage, project)?;
     let project_path = resolved.project_path;
     let branch = current_git_branch();
 
-    // Use provided channel or derive from git branch
-    let resolved_channel = channel
-        .map(ToString::to_string)
-        .or_else(|| branch.clone());
-
-    // Check for existing session to resume (unless force_new)
-    if !force_new {
-        // Look for a session with matching name + project that can be resumed
-        let existing = storage.list_sessions(Some(&project_path), Some("paused"), Some(10))?;
-        if let Some(session) = existing.iter().find(|s| s.name == name) {
-            // Resume the existing session
-            storage.update_session_status(&session.id, "active", actor)?;
-
-            // Bind terminal to this session
-            bind_session_to_terminal(&session.id, &session.name, &project_path, "active");
-
-            if crate::is_silent() {
-                println!("{}", session.id);
-                return Ok(());
-            }
-
-            if json {
-                let output = serde_json::json!({
-                    "id": session.id,
-                    "name": session.name,
-                    "status": "active",
-                    "project_path": session.project_path,
-                    "branch": branch,
-                    "resumed": true
-                });
-                println!("{output}");
-            } else {
-                println!("Resumed session: {name}");
-                println!("  ID: {}", session.id);
-                println!("  Project: {project_path}");
-                if let Some(ref branch) = branch {
-                    println!("  Branch: {branch}");
-                }
-            }
-            return Ok(());
-        }
-    }
-
-    // Generate session ID
-    let id = format!("sess_{}", &uuid::Uuid::new_v4().to_string()[..12]);
-
-    storage.create_session(
-        &id,
-        name,
-        description,
-        Some(&project_path),
-        resolved_channel.as_deref(),
-        actor,
-    )?;
-
-    // Bind terminal to new session
-    bind_session_to_terminal(&id, name, &project_path, "active");
+    // Resumes a paused session with the same name unless force_new
+    let mut sc = SaveContext::borrowing(storage, project_path.as_str(), actor);
+    let started = sc.start_session(&NewSession {
+        name: name.to_string(),
+        description: description.map(ToString::to_string),
+        // Use provided channel or derive from git branch
+        channel: channel.map(ToString::to_string).or_else(|| branch.clone()),
+        worktree: current_worktree().map(|w| w.key()),
+        force_new,
+    })?;
+    let session = started.session;
+
+    // Bind terminal to the session
+    bind_session_to_terminal(&session.id, &session.name, &project_path, "active");
 
     if crate::is_silent() {
-        println!("{id}");
+        println!("{}", session.id);
         return Ok(());
     }
 
     if json {
-        let output = serde_json::json!({
-            "id": id,
-            "name": name,
-            "status": "active",
-            "project_path": project_path,
-            "branch": branch,
-            "resumed": false
-        });
-        println!("{output}");
+        let output = SessionStartOutput {
+            id: session.id,
+            name: session.name,
+            status: session.status,
+            project_path: session.project_path,
+            branch,
+            worktree: session.worktree,
+            resumed: started.resumed,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
-        println!("Started session: {name}");
-        println!("  ID: {id}");
+        let verb = if started.resumed { "Resumed" } else { "Started" };
+        println!("{verb} session: {name}");
+        println!("  ID: {}", session.id);
         println!("  Project: {project_path}");
         if let Some(ref branch) = branch {
             println!("  Branch: {branch}");
         }
+        if let Some(ref worktree) = session.worktree {
+            println!("  Worktree: {worktree}");
+        }
     }
 
     Ok(())
 }
 
-/// End (complete) the current session.
-fn end(db_path: &PathBuf, session_id: Option<&str>, actor: &str, json: bool) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
+/// End (complete) the current session, summarizing it first.
+fn end(storage: &mut SqliteStorage, session_id: Option<&str>, no_summary: bool, actor: &str, json: bool) -> Result<()> {
 
-    let sid = resolve_session_or_suggest(session_id, &storage)?;
+    let sid = resolve_session_or_suggest(session_id, storage)?;
     let session = storage
         .get_session(&sid)?
         .ok_or_else(|| Error::SessionNotFound { id: sid })?;
 
-    storage.update_session_status(&session.id, "completed", actor)?;
+    let config = load_config();
+    let mode = if no_summary { SessionSummaryMode::Off } else { config.session_summary.unwrap_or_default() };
+    let summary = match mode {
+        SessionSummaryMode::Off => None,
+        mode => session_summary::summarize(
+            storage,
+            &session,
+            mode,
+            config.summarize.as_ref(),
+            chrono::Utc::now().timestamp_millis(),
+        )?,
+    };
+
+    let mut name = session.name.clone();
+    let mut renamed_from = None;
+    // The summary is only saved if the session is completed with it
+    storage.atomically(|storage| {
+        if let Some(summary) = &summary {
+            let id = format!("item_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+            storage.save_context_item(
+                &id,
+                &session.id,
+                session_summary::SUMMARY_KEY,
+                &summary.text,
+                Some(ItemCategory::Progress),
+                None,
+                actor,
+            )?;
+            if let Some(new_name) = summary.name.clone().filter(|_| session_summary::is_placeholder_name(&session.name)) {
+                storage.rename_session(&session.id, &new_name, actor)?;
+                renamed_from = Some(std::mem::replace(&mut name, new_name));
+            }
+        }
+        storage.update_session_status(&session.id, SessionStatus::Completed, actor)
+    })?;
 
     // Unbind terminal from this session
     clear_status_cache();
 
     if json {
-        let output = serde_json::json!({
-            "id": session.id,
-            "name": session.name,
-            "status": "completed"
-        });
-        println!("{output}");
+        let output = SessionStatusOutput {
+            id: session.id,
+            name,
+            status: SessionStatus::Completed,
+            renamed_from,
+            summary_key: summary.map(|_| session_summary::SUMMARY_KEY.to_string()),
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
-        println!("Completed session: {}", session.name);
+        println!("Completed session: {name}");
+        if let Some(old) = renamed_from {
+            println!("Renamed from: {old}");
+        }
+        if let Some(summary) = summary {
+            let by = if summary.llm { " (written by the summarize endpoint)" } else { "" };
+            println!("Saved summary as {}{by}", session_summary::SUMMARY_KEY);
+        }
     }
 
     Ok(())
 }
 
 /// Pause the current session.
-fn pause(db_path: &PathBuf, session_id: Option<&str>, actor: &str, json: bool) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
+fn pause(storage: &mut SqliteStorage, session_id: Option<&str>, actor: &str, json: bool) -> Result<()> {
 
-    let sid = resolve_session_or_suggest(session_id, &storage)?;
+    let sid = resolve_session_or_suggest(session_id, storage)?;
     let session = storage
         .get_session(&sid)?
         .ok_or_else(|| Error::SessionNotFound { id: sid })?;
 
-    storage.update_session_status(&session.id, "paused", actor)?;
+    storage.update_session_status(&session.id, SessionStatus::Paused, actor)?;
 
     // Unbind terminal from this session
     clear_status_cache();
 
     if json {
-        let output = serde_json::json!({
-            "id": session.id,
-            "name": session.name,
-            "status": "paused"
-        });
-        println!("{output}");
+        let output = SessionStatusOutput {
+            id: session.id,
+            name: session.name,
+            status: SessionStatus::Paused,
+            renamed_from: None,
+            summary_key: None,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Paused session: {}", session.name);
     }
@@ -250,8 +380,7 @@ fn pause(db_path: &PathBuf, session_id: Option<&str>, actor: &str, json: bool) -
 
 /// Resume a paused, completed, or even active session.
 /// Active sessions are allowed because the user may be resuming in a new terminal instance.
-fn resume(db_path: &PathBuf, id: &str, actor: &str, json: bool) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
+fn resume(storage: &mut SqliteStorage, id: &str, actor: &str, json: bool) -> Result<()> {
 
     // Get the session to verify it exists
     let session = storage
@@ -273,7 +402,7 @@ fn resume(db_path: &PathBuf, id: &str, actor: &str, json: bool) -> Result<()> {
     // This matches the MCP server behavior where resumeSession() doesn't check status
 
     // Set to active and clear ended_at (matching MCP server behavior)
-    storage.update_session_status(id, "active", actor)?;
+    storage.update_session_status(id, SessionStatus::Active, actor)?;
 
     // Bind terminal to this session
     let project_path = session
@@ -283,12 +412,14 @@ fn resume(db_path: &PathBuf, id: &str, actor: &str, json: bool) -> Result<()> {
     bind_session_to_terminal(&session.id, &session.name, project_path, "active");
 
     if json {
-        let output = serde_json::json!({
-            "id": session.id,
-            "name": session.name,
-            "status": "active"
-        });
-        println!("{output}");
+        let output = SessionStatusOutput {
+            id: session.id,
+            name: session.name,
+            status: SessionStatus::Active,
+            renamed_from: None,
+            summary_key: None,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Resumed session: {}", session.name);
     }
@@ -299,16 +430,17 @@ fn resume(db_path: &PathBuf, id: &str, actor: &str, json: bool) -> Result<()> {
 /// List sessions.
 #[allow(clippy::too_many_arguments)]
 fn list(
-    db_path: &PathBuf,
+    storage: &mut SqliteStorage,
     status: &str,
     limit: usize,
     search: Option<&str>,
     project: Option<&str>,
     all_projects: bool,
     include_completed: bool,
+    worktree: Option<&str>,
     json: bool,
 ) -> Result<()> {
-    let storage = SqliteStorage::open(db_path)?;
+    let worktree = worktree.map(worktree_filter);
 
     // When --search is active, widen scope to "find it anywhere" unless
     // the user explicitly narrowed with -s or -p/--project.
@@ -331,7 +463,7 @@ fn list(
         None
     } else {
         project.map(ToString::to_string).or_else(|| {
-            resolve_project_path(&storage, None).ok()
+            resolve_project_path(storage, None).ok()
         })
     };
 
@@ -353,12 +485,13 @@ fn list(
         status_filter,
         Some(limit as u32 * 2), // Fetch extra to allow filtering
         search,
+        worktree.as_deref(),
     )?;
 
     // If we're not fetching "all" status and include_completed is set,
     // filter to only include the requested status OR completed
     if effective_status != "all" && include_completed {
-        sessions.retain(|s| s.status == effective_status || s.status == "completed");
+        sessions.retain(|s| s.status.as_str() == effective_status || s.status == SessionStatus::Completed);
     }
 
     // Apply limit after filtering
@@ -396,6 +529,9 @@ fn list(
             if let Some(ref branch) = session.branch {
                 println!("  Branch: {branch}");
             }
+            if let Some(ref worktree) = session.worktree {
+                println!("  Worktree: {worktree}");
+            }
             println!();
         }
     }
@@ -403,40 +539,55 @@ fn list(
     Ok(())
 }
 
+/// The `--worktree` filter for the worktree `dir` is in: the path of a
+/// linked worktree, empty in a main one (sessions started outside linked
+/// worktrees), or `dir` itself once its worktree has been removed.
+fn worktree_filter(dir: &str) -> String {
+    let path = Path::new(dir);
+    match linked_worktree(path) {
+        Some(worktree) => worktree.key(),
+        None if path.exists() => String::new(),
+        None => normalize_path(path),
+    }
+}
+
 /// Switch to a different session.
-fn switch(db_path: &PathBuf, id: &str, actor: &str, json: bool) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
-
-    // Pause the currently bound session (if any) via status cache
-    if let Some(current_sid) = crate::config::current_session_id() {
-        if current_sid != id {
-            // Only pause if it's a different session
-            if let Ok(Some(_)) = storage.get_session(&current_sid) {
-                storage.update_session_status(&current_sid, "paused", actor)?;
+fn switch(storage: &mut SqliteStorage, id: &str, actor: &str, json: bool) -> Result<()> {
+
+    // The current session stays active unless the switch goes through
+    let target = storage.atomically(|storage| {
+        // Pause the currently bound session (if any) via status cache
+        if let Some(current_sid) = crate::config::current_session_id() {
+            if current_sid != id {
+                // Only pause if it's a different session
+                if let Ok(Some(_)) = storage.get_session(&current_sid) {
+                    storage.update_session_status(&current_sid, SessionStatus::Paused, actor)?;
+                }
             }
         }
-    }
 
-    // Get the target session
-    let target = storage
-        .get_session(id)?
-        .ok_or_else(|| {
-            let all_ids = storage.get_all_session_ids().unwrap_or_default();
-            let similar = crate::validate::find_similar_ids(id, &all_ids, 3);
-            if similar.is_empty() {
-                Error::SessionNotFound { id: id.to_string() }
-            } else {
-                Error::SessionNotFoundSimilar {
-                    id: id.to_string(),
-                    similar,
+        // Get the target session
+        let target = storage
+            .get_session(id)?
+            .ok_or_else(|| {
+                let all_ids = storage.get_all_session_ids().unwrap_or_default();
+                let similar = crate::validate::find_similar_ids(id, &all_ids, 3);
+                if similar.is_empty() {
+                    Error::SessionNotFound { id: id.to_string() }
+                } else {
+                    Error::SessionNotFoundSimilar {
+                        id: id.to_string(),
+                        similar,
+                    }
                 }
-            }
-        })?;
+            })?;
 
-    // Activate the target session if not already active
-    if target.status != "active" {
-        storage.update_session_status(id, "active", actor)?;
-    }
+        // Activate the target session if not already active
+        if target.status != SessionStatus::Active {
+            storage.update_session_status(id, SessionStatus::Active, actor)?;
+        }
+        Ok(target)
+    })?;
 
     // Bind terminal to the new session
     let project_path = target
@@ -446,12 +597,14 @@ fn switch(db_path: &PathBuf, id: &str, actor: &str, json: bool) -> Result<()> {
     bind_session_to_terminal(&target.id, &target.name, project_path, "active");
 
     if json {
-        let output = serde_json::json!({
-            "id": target.id,
-            "name": target.name,
-            "status": "active"
-        });
-        println!("{output}");
+        let output = SessionStatusOutput {
+            id: target.id,
+            name: target.name,
+            status: SessionStatus::Active,
+            renamed_from: None,
+            summary_key: None,
+        };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Switched to session: {}", target.name);
     }
@@ -460,10 +613,9 @@ fn switch(db_path: &PathBuf, id: &str, actor: &str, json: bool) -> Result<()> {
 }
 
 /// Rename the current session.
-fn rename(db_path: &PathBuf, session_id: Option<&str>, new_name: &str, actor: &str, json: bool) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
+fn rename(storage: &mut SqliteStorage, session_id: Option<&str>, new_name: &str, actor: &str, json: bool) -> Result<()> {
 
-    let sid = resolve_session_or_suggest(session_id, &storage)?;
+    let sid = resolve_session_or_suggest(session_id, storage)?;
     let session = storage
         .get_session(&sid)?
         .ok_or_else(|| Error::SessionNotFound { id: sid })?;
@@ -472,16 +624,12 @@ fn rename(db_path: &PathBuf, session_id: Option<&str>, new_name: &str, actor: &s
 
     // Update the status cache with the new name
     if let Some(ref path) = session.project_path {
-        bind_session_to_terminal(&session.id, new_name, path, &session.status);
+        bind_session_to_terminal(&session.id, new_name, path, session.status.as_str());
     }
 
     if json {
-        let output = serde_json::json!({
-            "id": session.id,
-            "name": new_name,
-            "old_name": session.name
-        });
-        println!("{output}");
+        let output = SessionRenameOutput { id: session.id, name: new_name.to_string(), old_name: session.name };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Renamed session to: {new_name}");
     }
@@ -490,8 +638,7 @@ fn rename(db_path: &PathBuf, session_id: Option<&str>, new_name: &str, actor: &s
 }
 
 /// Delete a session permanently.
-fn delete(db_path: &PathBuf, id: &str, force: bool, actor: &str, json: bool) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
+fn delete(storage: &mut SqliteStorage, id: &str, force: bool, actor: &str, json: bool) -> Result<()> {
 
     // Get the session to verify it exists and show info
     let session = storage
@@ -510,23 +657,20 @@ fn delete(db_path: &PathBuf, id: &str, force: bool, actor: &str, json: bool) ->
         })?;
 
     // Cannot delete active session without force
-    if session.status == "active" && !force {
+    if session.status == SessionStatus::Active && !force {
         return Err(Error::InvalidSessionStatus {
             expected: "paused or completed (use --force to delete active session)".to_string(),
-            actual: session.status.clone(),
+            actual: session.status.to_string(),
         });
     }
 
     // Perform deletion
     storage.delete_session(id, actor)?;
+    super::trash::purge_expired(storage)?;
 
     if json {
-        let output = serde_json::json!({
-            "id": session.id,
-            "name": session.name,
-            "deleted": true
-        });
-        println!("{output}");
+        let output = SessionDeleteOutput { id: session.id, name: session.name, deleted: true };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Deleted session: {}", session.name);
     }
@@ -536,25 +680,21 @@ fn delete(db_path: &PathBuf, id: &str, force: bool, actor: &str, json: bool) ->
 
 /// Add a project path to a session.
 fn add_path(
-    db_path: &PathBuf,
+    storage: &mut SqliteStorage,
     id: Option<&str>,
     path: Option<&str>,
     actor: &str,
     json: bool,
 ) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
 
     // Resolve session ID: explicit -i flag first, then standard resolution
-    let session_id = resolve_session_or_suggest(id, &storage)?;
+    let session_id = resolve_session_or_suggest(id, storage)?;
 
     // Resolve path (use provided or current directory)
     let project_path = match path {
-        Some(p) => std::path::PathBuf::from(p)
-            .canonicalize()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| p.to_string()),
+        Some(p) => crate::config::normalize_path(std::path::Path::new(p)),
         None => std::env::current_dir()
-            .map(|p| p.to_string_lossy().to_string())
+            .map(|p| crate::config::normalize_path(&p))
             .map_err(|e| Error::Io(e))?,
     };
 
@@ -569,12 +709,8 @@ fn add_path(
     storage.add_session_path(&session_id, &project_path, actor)?;
 
     if json {
-        let output = serde_json::json!({
-            "session_id": session.id,
-            "session_name": session.name,
-            "path_added": project_path
-        });
-        println!("{output}");
+        let output = SessionPathAddedOutput { session_id: session.id, session_name: session.name, path_added: project_path };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Added path to session: {}", session.name);
         println!("  Path: {project_path}");
@@ -585,16 +721,15 @@ fn add_path(
 
 /// Remove a project path from a session.
 fn remove_path(
-    db_path: &PathBuf,
+    storage: &mut SqliteStorage,
     id: Option<&str>,
     path: &str,
     actor: &str,
     json: bool,
 ) -> Result<()> {
-    let mut storage = SqliteStorage::open(db_path)?;
 
     // Resolve session ID: explicit -i flag first, then standard resolution
-    let session_id = resolve_session_or_suggest(id, &storage)?;
+    let session_id = resolve_session_or_suggest(id, storage)?;
 
     // Get session info for output
     let session = storage
@@ -604,21 +739,14 @@ fn remove_path(
         })?;
 
     // Canonicalize path if possible (to match stored paths)
-    let project_path = std::path::PathBuf::from(path)
-        .canonicalize()
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| path.to_string());
+    let project_path = crate::config::normalize_path(std::path::Path::new(path));
 
     // Remove the path
     storage.remove_session_path(&session_id, &project_path, actor)?;
 
     if json {
-        let output = serde_json::json!({
-            "session_id": session.id,
-            "session_name": session.name,
-            "path_removed": project_path
-        });
-        println!("{output}");
+        let output = SessionPathRemovedOutput { session_id: session.id, session_name: session.name, path_removed: project_path };
+        println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Removed path from session: {}", session.name);
         println!("  Path: {project_path}");
@@ -626,3 +754,135 @@ fn remove_path(
 
     Ok(())
 }
+
+/// Pause sessions idle for `hours` (or `auto_pause_hours`), once or every
+/// ten minutes with `--daemon`, serving `--metrics` meanwhile.
+fn pause_idle(
+    db: &mut Database,
+    hours: Option<u64>,
+    daemon: bool,
+    metrics: Option<&str>,
+    actor: &str,
+    json: bool,
+) -> Result<()> {
+    let hours = match hours.unwrap_or_else(auto_pause_hours) {
+        0 => {
+            return Err(Error::InvalidArgument(
+                "No idle limit. Pass --hours <n> or set auto_pause_hours in config".to_string(),
+            ));
+        }
+        hours => hours,
+    };
+    if let Some(addr) = metrics {
+        crate::cli::metrics::serve(addr, db.require_path()?.to_path_buf())?;
+    }
+
+    let storage = db.storage()?;
+    loop {
+        let dry_run = crate::is_dry_run();
+        let paused = crate::cli::metrics::timed("session pause-idle", || {
+            storage.with_transaction(|s| {
+                let result = s.pause_idle_sessions(idle_cutoff(hours), actor);
+                let commit = result.is_ok() && !dry_run;
+                (result, commit)
+            })?
+        })?;
+
+        if json {
+            let sessions = paused
+                .iter()
+                .map(|(id, name, last_activity_at)| PausedSession { id, name, last_activity_at: *last_activity_at })
+                .collect();
+            let output = PauseIdleOutput { hours, paused: sessions, count: paused.len(), dry_run };
+            println!("{}", serde_json::to_string(&output)?);
+        } else if paused.is_empty() {
+            if !daemon {
+                println!("No sessions idle for {hours}h or more.");
+            }
+        } else {
+            let verb = if dry_run { "Would pause" } else { "Paused" };
+            println!("{verb} {} sessions idle for {hours}h or more:", paused.len());
+            for (id, name, last_activity_at) in &paused {
+                let last = chrono::DateTime::from_timestamp_millis(*last_activity_at)
+                    .map_or_else(String::new, |dt| dt.format("%Y-%m-%d %H:%M").to_string());
+                println!("  {name} ({id}), last active {last}");
+            }
+        }
+
+        if !daemon {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(600));
+    }
+}
+
+fn idle_cutoff(hours: u64) -> i64 {
+    let hours = i64::try_from(hours).unwrap_or(i64::MAX / 3_600_000);
+    chrono::Utc::now().timestamp_millis() - hours.saturating_mul(3_600_000)
+}
+
+/// Lazy counterpart of `sc session pause-idle`, run after every command
+/// when `auto_pause_hours` is set. Errors are only logged.
+pub fn pause_idle_after_command(db: &mut Database, actor: Option<&str>) {
+    let hours = auto_pause_hours();
+    if hours == 0 || crate::config::is_test_mode() || crate::is_dry_run() {
+        return;
+    }
+    let Some(storage) = db.existing() else { return };
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    let result = storage.pause_idle_sessions(idle_cutoff(hours), &actor);
+    match result {
+        Ok(paused) if !paused.is_empty() => {
+            tracing::debug!(count = paused.len(), hours, "Auto-paused idle sessions");
+        }
+        Ok(_) => {}
+        Err(e) => tracing::debug!(error = %e, "Auto-pause of idle sessions failed"),
+    }
+}
+
+/// Pick a session for a command that needs one when none is set and
+/// `auto_session` is on: the most recently active session of the current
+/// project and git worktree, or a new one named `auto YYYY-MM-DD`. The
+/// terminal is bound to it, so later commands resolve it the usual way,
+/// and a `session_auto_resolved` event records which command triggered it.
+///
+/// Returns `None` under `--dry-run` when a session would have to be
+/// created.
+///
+/// # Errors
+///
+/// Returns an error if the project is not registered or the session
+/// cannot be written.
+pub fn auto_resolve(storage: &mut SqliteStorage, command: &str, actor: &str) -> Result<Option<String>> {
+    let project_path = resolve_project(storage, None)?.project_path;
+
+    // Sessions of other worktrees belong to the agents working there
+    let worktree = current_worktree().map(|w| w.key());
+    let active = storage.list_sessions_with_search(
+        Some(&project_path),
+        Some("active"),
+        Some(1),
+        None,
+        Some(worktree.as_deref().unwrap_or_default()),
+    )?;
+    let (id, name, how) = if let Some(session) = active.into_iter().next() {
+        (session.id, session.name, "resumed")
+    } else if crate::is_dry_run() {
+        return Ok(None);
+    } else {
+        let id = format!("sess_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+        let name = format!("auto {}", chrono::Local::now().format("%Y-%m-%d"));
+        let channel = current_git_branch();
+        storage.create_session(&id, &name, None, Some(&project_path), channel.as_deref(), worktree.as_deref(), actor)?;
+        (id, name, "created")
+    };
+
+    if !crate::is_dry_run() {
+        storage.record_session_auto_resolved(&id, how, command, actor)?;
+    }
+    bind_session_to_terminal(&id, &name, &project_path, "active");
+    if !crate::is_silent() {
+        eprintln!("auto_session: {how} session '{name}' ({id})");
+    }
+    Ok(Some(id))
+}
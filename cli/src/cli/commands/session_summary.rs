@@ -0,0 +1,188 @@
+//! Summary and name of a session as it ends (`sc session end`).
+//!
+//! Sessions started by `auto_session` or named in a hurry pile up in `sc
+//! session list` as "auto 2026-10-16" and "wip". When one ends, what it
+//! worked on is saved as a `session-summary` progress item, and a session
+//! with a placeholder name is renamed after its dominant work: the issue
+//! with the most activity while it ran, else its first decision.
+//!
+//! `session_summary` in config.json picks how: `heuristic` (the default)
+//! builds both from the records, `llm` has the `summarize` endpoint write
+//! them from the same notes and falls back to the heuristic when the call
+//! fails, and `off` leaves sessions as they are.
+
+use super::config::{SessionSummaryMode, SummarizeConfig};
+use crate::error::Result;
+use crate::model::ItemCategory;
+use crate::storage::{ContextItem, Issue, Session, SqliteStorage};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// Key of the summary item saved in the session.
+pub(crate) const SUMMARY_KEY: &str = "session-summary";
+
+/// Longest name given to a session.
+const MAX_NAME_CHARS: usize = 60;
+
+/// Names that say nothing about a session, once dates and counters are
+/// left out.
+const PLACEHOLDER_NAMES: &[&str] =
+    &["auto", "wip", "session", "new session", "untitled", "default", "tmp", "temp", "test", "work"];
+
+const SUMMARY_PROMPT: &str = "You name and summarize coding sessions. From the notes you are given, \
+reply with a name of at most six words on the first line, then a summary of two to four sentences. \
+Keep issue IDs, do not add work that is not listed, and reply with only the name and summary.";
+
+/// What a session amounted to.
+pub(crate) struct SessionSummary {
+    /// A name after the session's dominant work, if it had any.
+    pub name: Option<String>,
+    pub text: String,
+    /// Whether the summarize endpoint wrote it.
+    pub llm: bool,
+}
+
+/// Whether `name` is a placeholder worth replacing: "auto 2026-10-16",
+/// "wip", "session 3" and the like.
+pub(crate) fn is_placeholder_name(name: &str) -> bool {
+    let words: Vec<&str> = name
+        .split_whitespace()
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit() || matches!(c, '-' | '/' | '.' | '#')))
+        .collect();
+    let name = words.join(" ").to_lowercase();
+    name.is_empty() || PLACEHOLDER_NAMES.contains(&name.as_str())
+}
+
+/// Summarize `session` up to `now`, or `None` when it recorded nothing to
+/// summarize.
+///
+/// # Errors
+///
+/// Returns an error if the session's records cannot be read. A failed
+/// summarize call only falls back to the heuristic.
+pub(crate) fn summarize(
+    storage: &SqliteStorage,
+    session: &Session,
+    mode: SessionSummaryMode,
+    settings: Option<&SummarizeConfig>,
+    now: i64,
+) -> Result<Option<SessionSummary>> {
+    // Encrypted values stay out of the summary, which is saved in the clear
+    let categories = storage.get_session_categories(&session.id)?;
+    let mut items: Vec<ContextItem> = storage
+        .get_context_items(&session.id, None, None, Some(1000))?
+        .into_iter()
+        .filter(|i| i.key != SUMMARY_KEY && !categories.encrypted(&i.category))
+        .collect();
+    items.sort_by_key(|i| i.created_at);
+
+    let (issues, activity) = match &session.project_path {
+        Some(project_path) => (
+            storage.get_issues_by_project(project_path)?,
+            storage.get_issue_activity(project_path, session.created_at, now)?,
+        ),
+        None => (Vec::new(), HashMap::new()),
+    };
+    let mut worked_on: Vec<(&Issue, usize)> =
+        issues.iter().filter_map(|i| activity.get(&i.id).map(|count| (i, *count))).collect();
+    worked_on.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.created_at.cmp(&b.0.created_at)));
+
+    if worked_on.is_empty() && items.is_empty() {
+        return Ok(None);
+    }
+    let notes = notes(session, &worked_on, &items, now);
+    let name = heuristic_name(&worked_on, &items);
+
+    if let (SessionSummaryMode::Llm, Some(settings)) = (mode, settings) {
+        match settings.complete(SUMMARY_PROMPT, &notes).map(|reply| parse_reply(&reply)) {
+            Ok((llm_name, text)) if !text.is_empty() => {
+                return Ok(Some(SessionSummary { name: llm_name.or(name), text: format!("{text}\n\n{notes}"), llm: true }));
+            }
+            Ok(_) => eprintln!("Warning: the summarize endpoint returned no summary; using the built-in one"),
+            Err(e) => eprintln!("Warning: could not summarize the session: {e}"),
+        }
+    }
+    Ok(Some(SessionSummary { name, text: notes, llm: false }))
+}
+
+/// The session's issues (most active first), decisions and progress notes.
+fn notes(session: &Session, worked_on: &[(&Issue, usize)], items: &[ContextItem], now: i64) -> String {
+    let minutes = (now - session.created_at).max(0) / 60_000;
+    let mut md = format!("Session \"{}\", {}h {:02}m.\n", session.name, minutes / 60, minutes % 60);
+
+    if !worked_on.is_empty() {
+        md.push_str("\nIssues:\n");
+        for (issue, count) in worked_on {
+            let id = issue.short_id.as_deref().unwrap_or(&issue.id);
+            let changes = if *count == 1 { "1 change".to_string() } else { format!("{count} changes") };
+            let _ = writeln!(md, "- [{id}] {} ({}, {changes})", issue.title, issue.status);
+        }
+    }
+    for (category, heading) in [(ItemCategory::Decision, "Decisions"), (ItemCategory::Progress, "Progress")] {
+        let listed: Vec<&ContextItem> = items.iter().filter(|i| i.category == category).collect();
+        if listed.is_empty() {
+            continue;
+        }
+        let _ = write!(md, "\n{heading}:\n");
+        for item in listed {
+            let _ = writeln!(md, "- {}: {}", item.key, first_line(&item.value, 160));
+        }
+    }
+    md
+}
+
+/// The most active issue's title, else the first decision's or progress
+/// note's key.
+fn heuristic_name(worked_on: &[(&Issue, usize)], items: &[ContextItem]) -> Option<String> {
+    if let Some((issue, _)) = worked_on.first() {
+        return Some(first_line(&issue.title, MAX_NAME_CHARS));
+    }
+    [ItemCategory::Decision, ItemCategory::Progress]
+        .iter()
+        .find_map(|category| items.iter().find(|i| i.category == *category))
+        .map(|item| first_line(&item.key.replace(['-', '_'], " "), MAX_NAME_CHARS))
+}
+
+/// Split a reply into the name on its first line and the summary below.
+fn parse_reply(reply: &str) -> (Option<String>, String) {
+    let (first, rest) = reply.trim().split_once('\n').unwrap_or((reply.trim(), ""));
+    let name = first
+        .trim_start_matches('#')
+        .trim()
+        .trim_start_matches("Name:")
+        .trim()
+        .trim_matches(['"', '*', '`'])
+        .trim();
+    let name = (!name.is_empty()).then(|| first_line(name, MAX_NAME_CHARS));
+    (name, rest.trim().to_string())
+}
+
+/// First line of `text`, cut to `max` characters.
+fn first_line(text: &str, max: usize) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > max {
+        let cut: String = line.chars().take(max - 3).collect();
+        format!("{}...", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_names_and_replies() {
+        for name in ["auto 2026-10-16", "WIP", "session 3", "", "new session #2"] {
+            assert!(is_placeholder_name(name), "{name}");
+        }
+        for name in ["auth refactor", "wip auth", "2026 planning"] {
+            assert!(!is_placeholder_name(name), "{name}");
+        }
+
+        let (name, text) = parse_reply("Name: \"Token refresh fix\"\nFixed the refresh race in sc-12.\n");
+        assert_eq!((name.as_deref(), text.as_str()), (Some("Token refresh fix"), "Fixed the refresh race in sc-12."));
+        assert_eq!(parse_reply("   ").0, None);
+    }
+}
@@ -0,0 +1,165 @@
+//! Session token usage ledger (`sc session usage ...`).
+//!
+//! Agent hooks report the tokens each model turn consumed with `record`;
+//! `show` totals them for a session and splits the total at each
+//! compaction, so you can see how much context a session burned before
+//! `sc compaction` was needed.
+
+use crate::cli::SessionUsageCommands;
+use crate::config::{resolve_project_path, resolve_session_or_suggest};
+use crate::error::Result;
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::storage::{SqliteStorage, TokenUsage};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Output for session usage record.
+#[derive(Serialize)]
+struct RecordOutput {
+    session_id: String,
+    tokens_in: u64,
+    tokens_out: u64,
+    dry_run: bool,
+}
+
+/// Output for session usage show `--all`.
+#[derive(Serialize)]
+struct UsageListOutput<'a> {
+    sessions: &'a [TokenUsage],
+    count: usize,
+}
+
+json_schema!(RecordOutput { session_id: String, tokens_in: u64, tokens_out: u64, dry_run: bool });
+json_schema!(UsageListOutput<'a> { sessions: &'a [TokenUsage], count: usize });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("session usage record", schema_for::<RecordOutput>()),
+        ("session usage show", one_of(&[schema_for::<TokenUsage>(), schema_for::<UsageListOutput<'_>>()])),
+    ]
+}
+
+/// Execute a session usage subcommand.
+///
+/// # Errors
+///
+/// Returns an error if no session is active (for `record` and `show`
+/// without `--all`) or the database cannot be written.
+pub fn execute(
+    command: &SessionUsageCommands,
+    storage: &mut SqliteStorage,
+    session_id: Option<&str>,
+    actor: &str,
+    json: bool,
+) -> Result<()> {
+    match command {
+        SessionUsageCommands::Record {
+            tokens_in,
+            tokens_out,
+            model,
+            source,
+        } => {
+            let sid = resolve_session_or_suggest(session_id, storage)?;
+            if !crate::is_dry_run() {
+                storage.record_session_usage(
+                    &sid,
+                    *tokens_in,
+                    *tokens_out,
+                    model.as_deref(),
+                    source.as_deref(),
+                    actor,
+                )?;
+            }
+            if json {
+                let output = RecordOutput {
+                    session_id: sid,
+                    tokens_in: *tokens_in,
+                    tokens_out: *tokens_out,
+                    dry_run: crate::is_dry_run(),
+                };
+                println!("{}", serde_json::to_string(&output)?);
+            } else if !crate::is_silent() {
+                println!("Recorded {tokens_in} in / {tokens_out} out for {sid}");
+            }
+            Ok(())
+        }
+        SessionUsageCommands::Show {
+            all,
+            all_projects,
+            limit,
+        } => {
+            if *all {
+                let project_path = if *all_projects {
+                    None
+                } else {
+                    resolve_project_path(storage, None).ok()
+                };
+                let sessions = storage.list_session_token_usage(project_path.as_deref(), *limit)?;
+                print_list(&sessions, json)
+            } else {
+                let sid = resolve_session_or_suggest(session_id, storage)?;
+                print_session(&storage.get_session_token_usage(&sid)?, json)
+            }
+        }
+    }
+}
+
+fn format_timestamp(ts: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ts)
+        .map_or_else(|| ts.to_string(), |dt| dt.format("%Y-%m-%d %H:%M").to_string())
+}
+
+fn print_session(usage: &TokenUsage, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(usage)?);
+        return Ok(());
+    }
+
+    println!("Token usage: {} ({})", usage.session_name, usage.session_id);
+    if usage.records == 0 {
+        println!("  Nothing recorded. Hooks record usage with `sc session usage record`.");
+        return Ok(());
+    }
+    println!(
+        "  {} in / {} out over {} records, {} compactions",
+        usage.tokens_in, usage.tokens_out, usage.records, usage.compactions
+    );
+    if let (Some(first), Some(last)) = (usage.first_at, usage.last_at) {
+        println!("  {} to {}", format_timestamp(first), format_timestamp(last));
+    }
+    if usage.compactions > 0 {
+        println!();
+        println!("  {:<34} {:>12} {:>12} {:>8}", "Window", "In", "Out", "Records");
+        for window in &usage.windows {
+            let label = window.compacted_by.as_deref().unwrap_or("since last compaction");
+            println!(
+                "  {label:<34} {:>12} {:>12} {:>8}",
+                window.tokens_in, window.tokens_out, window.records
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_list(sessions: &[TokenUsage], json: bool) -> Result<()> {
+    if json {
+        let output = UsageListOutput { sessions, count: sessions.len() };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if sessions.is_empty() {
+        println!("No token usage recorded.");
+        return Ok(());
+    }
+    println!("  {:<30} {:>12} {:>12} {:>8} {:>11}", "Session", "In", "Out", "Records", "Compactions");
+    for usage in sessions {
+        let name: String = usage.session_name.chars().take(30).collect();
+        println!(
+            "  {name:<30} {:>12} {:>12} {:>8} {:>11}",
+            usage.tokens_in, usage.tokens_out, usage.records, usage.compactions
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,479 @@
+//! First-run setup wizard (`sc setup`).
+//!
+//! Walks a new user through the decisions a working install needs:
+//! where the database lives, whether to use embeddings (and which
+//! provider, with a connectivity check), the actor name recorded on
+//! changes, the first project, and editor integrations. Answers are
+//! written to `~/.savecontext/config.json`.
+//!
+//! Every question has a default shown in brackets; pressing Enter accepts
+//! it, and `--yes` accepts all of them without prompting. Prompts go to
+//! stderr so `--json` output on stdout stays machine-readable.
+
+use crate::cli::commands::config::{load_config, save_config};
+use crate::cli::SkillsCommands;
+use crate::config::{default_actor, global_savecontext_dir, resolve_db_path};
+use crate::embeddings::{
+    create_huggingface_provider, create_ollama_provider, resolve_hf_token, resolve_ollama_endpoint,
+    resolve_ollama_model, EmbeddingProviderType, EmbeddingSettings,
+};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::Project;
+use crate::storage::SqliteStorage;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Providers offered by the wizard (the ones the CLI can run).
+const PROVIDERS: &[&str] = &["ollama", "huggingface"];
+
+#[derive(Serialize)]
+struct SetupOutput {
+    config_path: PathBuf,
+    database: PathBuf,
+    database_created: bool,
+    actor: String,
+    embeddings: EmbeddingsOutput,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<ProjectOutput>,
+    integrations_installed: bool,
+}
+
+#[derive(Serialize)]
+struct EmbeddingsOutput {
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reachable: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct ProjectOutput {
+    id: String,
+    name: String,
+    path: String,
+    created: bool,
+}
+
+json_schema!(SetupOutput {
+    config_path: PathBuf,
+    database: PathBuf,
+    database_created: bool,
+    actor: String,
+    embeddings: EmbeddingsOutput,
+    project: Option<ProjectOutput>,
+    integrations_installed: bool,
+});
+json_schema!(EmbeddingsOutput { enabled: bool, provider: Option<String>, reachable: Option<bool> });
+json_schema!(ProjectOutput { id: String, name: String, path: String, created: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("setup", schema_for::<SetupOutput>())]
+}
+
+/// Line-based prompts with defaults.
+///
+/// Generic over the input and output streams so the wizard can be driven
+/// by tests. When `assume_defaults` is set (or input is exhausted), every
+/// question returns its default without reading.
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+    assume_defaults: bool,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    pub fn new(input: R, output: W, assume_defaults: bool) -> Self {
+        Self {
+            input,
+            output,
+            assume_defaults,
+        }
+    }
+
+    /// Print a line of commentary.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output stream cannot be written.
+    pub fn say(&mut self, line: &str) -> Result<()> {
+        writeln!(self.output, "{line}")?;
+        Ok(())
+    }
+
+    /// Ask a free-form question; an empty answer selects `default`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the streams cannot be read or written.
+    pub fn ask(&mut self, question: &str, default: &str) -> Result<String> {
+        if default.is_empty() {
+            write!(self.output, "{question}: ")?;
+        } else {
+            write!(self.output, "{question} [{default}]: ")?;
+        }
+        let answer = self.read_answer()?;
+        Ok(answer.unwrap_or_else(|| default.to_string()))
+    }
+
+    /// Ask a yes/no question.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the streams cannot be read or written, or after
+    /// three answers that are neither yes nor no.
+    pub fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        for _ in 0..3 {
+            write!(self.output, "{question} [{hint}]: ")?;
+            match self.read_answer()?.map(|a| a.to_lowercase()) {
+                None => return Ok(default),
+                Some(a) if a == "y" || a == "yes" => return Ok(true),
+                Some(a) if a == "n" || a == "no" => return Ok(false),
+                Some(_) => writeln!(self.output, "Please answer y or n.")?,
+            }
+        }
+        Err(Error::InvalidArgument(format!("No valid answer to: {question}")))
+    }
+
+    /// Pick one of `options` by number or name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the streams cannot be read or written, or after
+    /// three answers that match no option.
+    pub fn choose(&mut self, question: &str, options: &[&str], default: usize) -> Result<usize> {
+        writeln!(self.output, "{question}")?;
+        for (i, option) in options.iter().enumerate() {
+            writeln!(self.output, "  {}) {option}", i + 1)?;
+        }
+        for _ in 0..3 {
+            write!(self.output, "Choice [{}]: ", options[default])?;
+            let Some(answer) = self.read_answer()? else {
+                return Ok(default);
+            };
+            if let Some(i) = answer
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=options.len()).contains(n))
+            {
+                return Ok(i - 1);
+            }
+            if let Some(i) = options.iter().position(|o| o.eq_ignore_ascii_case(&answer)) {
+                return Ok(i);
+            }
+            writeln!(self.output, "Enter a number from 1 to {}.", options.len())?;
+        }
+        Err(Error::InvalidArgument(format!("No valid answer to: {question}")))
+    }
+
+    /// Whether questions are answered with their defaults, because of
+    /// `assume_defaults` or because input ran out.
+    pub fn assuming_defaults(&self) -> bool {
+        self.assume_defaults
+    }
+
+    /// Read one trimmed answer; `None` means "use the default".
+    fn read_answer(&mut self) -> Result<Option<String>> {
+        if self.assume_defaults {
+            writeln!(self.output)?;
+            return Ok(None);
+        }
+        self.output.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            // EOF: behave like --yes for the remaining questions
+            writeln!(self.output)?;
+            self.assume_defaults = true;
+            return Ok(None);
+        }
+        let answer = line.trim();
+        Ok((!answer.is_empty()).then(|| answer.to_string()))
+    }
+}
+
+/// Execute the setup wizard on the terminal.
+///
+/// # Errors
+///
+/// Returns an error if the database or config file cannot be written.
+pub fn execute(db_path: Option<&PathBuf>, yes: bool, json: bool) -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut prompter = Prompter::new(stdin.lock(), std::io::stderr(), yes);
+    run(&mut prompter, db_path, json)
+}
+
+fn run<R: BufRead, W: Write>(
+    p: &mut Prompter<R, W>,
+    db_path: Option<&PathBuf>,
+    json: bool,
+) -> Result<()> {
+    let mut config = load_config();
+    config.version = config.version.max(1);
+
+    p.say("SaveContext setup — press Enter to accept the default in [brackets].")?;
+
+    // 1. Database location
+    p.say("")?;
+    p.say("1/5 Database")?;
+    let global_default = global_savecontext_dir()
+        .map(|d| d.join("data").join("savecontext.db"))
+        .ok_or_else(|| Error::Config("Could not determine home directory".to_string()))?;
+    let current = resolve_db_path(db_path.map(PathBuf::as_path)).unwrap_or_else(|| global_default.clone());
+    let answer = p.ask("Database file", &current.display().to_string())?;
+    let database = expand_home(&answer);
+    let database_created = !database.exists();
+    if let Some(parent) = database.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut storage = SqliteStorage::open(&database)?;
+    config.db_path = (database != global_default).then(|| database.display().to_string());
+    p.say(&format!(
+        "  {} {}",
+        if database_created { "Created" } else { "Using" },
+        database.display()
+    ))?;
+
+    // 2. Embeddings
+    p.say("")?;
+    p.say("2/5 Semantic search")?;
+    let (settings, embeddings) = setup_embeddings(p, config.embeddings.take().unwrap_or_default())?;
+    config.embeddings = Some(settings);
+
+    // 3. Actor
+    p.say("")?;
+    p.say("3/5 Identity")?;
+    let actor = p.ask("Actor name recorded on your changes", &default_actor())?;
+    config.actor = Some(actor.clone());
+
+    // 4. First project
+    p.say("")?;
+    p.say("4/5 Project")?;
+    let project = if p.confirm("Register a project now?", true)? {
+        Some(setup_project(p, &mut storage, &actor)?)
+    } else {
+        None
+    };
+
+    // Save before integrations so a failed download doesn't lose answers
+    save_config(&config)?;
+
+    // 5. Editor integrations
+    p.say("")?;
+    p.say("5/5 Editor integrations")?;
+    let integrations_installed =
+        if p.confirm("Install skills and hooks for detected AI coding tools?", false)? {
+            let install = SkillsCommands::Install {
+                tool: None,
+                mode: "both".to_string(),
+                path: None,
+            };
+            match super::skills::execute(&install, false) {
+                Ok(()) => true,
+                Err(e) => {
+                    p.say(&format!("  Skipped: {e}. Retry later with `sc skills install`."))?;
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
+    let output = SetupOutput {
+        config_path: super::config::config_path(),
+        database,
+        database_created,
+        actor,
+        embeddings,
+        project,
+        integrations_installed,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!();
+        println!("Setup complete. Settings saved to {}", output.config_path.display());
+        println!("  Database:   {}", output.database.display());
+        println!("  Actor:      {}", output.actor);
+        match (&output.embeddings.provider, output.embeddings.reachable) {
+            (Some(provider), Some(true)) => println!("  Embeddings: {provider}"),
+            (Some(provider), _) => println!("  Embeddings: {provider} (not reachable yet)"),
+            (None, _) => println!("  Embeddings: disabled"),
+        }
+        if let Some(ref project) = output.project {
+            println!("  Project:    {} ({})", project.name, project.path);
+        }
+        println!();
+        println!("Next: sc session start \"<what you're working on>\"");
+    }
+
+    Ok(())
+}
+
+fn setup_embeddings<R: BufRead, W: Write>(
+    p: &mut Prompter<R, W>,
+    mut settings: EmbeddingSettings,
+) -> Result<(EmbeddingSettings, EmbeddingsOutput)> {
+    let enabled = p.confirm("Enable embeddings for semantic search?", settings.enabled.unwrap_or(true))?;
+    settings.enabled = Some(enabled);
+    if !enabled {
+        return Ok((
+            settings,
+            EmbeddingsOutput {
+                enabled: false,
+                provider: None,
+                reachable: None,
+            },
+        ));
+    }
+
+    let default = match settings.provider {
+        Some(EmbeddingProviderType::Huggingface) => 1,
+        _ => 0,
+    };
+    let provider = if p.choose("Embedding provider:", PROVIDERS, default)? == 1 {
+        settings.provider = Some(EmbeddingProviderType::Huggingface);
+        let token = p.ask("HuggingFace token", &resolve_hf_token().unwrap_or_default())?;
+        settings.HF_TOKEN = (!token.is_empty()).then_some(token);
+        create_huggingface_provider(None, settings.HF_MODEL.clone(), settings.HF_TOKEN.clone())
+    } else {
+        settings.provider = Some(EmbeddingProviderType::Ollama);
+        let endpoint = p.ask("Ollama endpoint", &resolve_ollama_endpoint())?;
+        let model = p.ask("Ollama model", &resolve_ollama_model())?;
+        settings.OLLAMA_ENDPOINT = Some(endpoint.clone());
+        settings.OLLAMA_MODEL = Some(model.clone());
+        Some(create_ollama_provider(Some(endpoint), Some(model)))
+    };
+
+    p.say("  Testing connection...")?;
+    let reachable = match provider {
+        Some(provider) => tokio::runtime::Runtime::new()
+            .is_ok_and(|rt| rt.block_on(provider.is_available())),
+        None => false,
+    };
+    if reachable {
+        p.say("  ✓ Provider is reachable")?;
+    } else {
+        p.say("  ✗ Provider is not reachable. Search falls back to keywords until it is;")?;
+        p.say("    check it later with `sc embeddings status`.")?;
+    }
+
+    let name = settings.provider.map(|t| t.to_string());
+    Ok((
+        settings,
+        EmbeddingsOutput {
+            enabled: true,
+            provider: name,
+            reachable: Some(reachable),
+        },
+    ))
+}
+
+fn setup_project<R: BufRead, W: Write>(
+    p: &mut Prompter<R, W>,
+    storage: &mut SqliteStorage,
+    actor: &str,
+) -> Result<ProjectOutput> {
+    let cwd = std::env::current_dir()
+        .ok()
+        .map(|d| crate::config::normalize_path(&d))
+        .unwrap_or_default();
+    let answer = p.ask("Project directory", &cwd)?;
+    let path = expand_home(&answer);
+    if let Err(e) = path.canonicalize() {
+        return Err(Error::InvalidArgument(format!("{}: {e}", path.display())));
+    }
+    let path = crate::config::normalize_path(&path);
+
+    if let Some(existing) = storage.get_project_by_path(&path)? {
+        p.say(&format!("  Already registered as '{}'", existing.name))?;
+        return Ok(ProjectOutput {
+            id: existing.id,
+            name: existing.name,
+            path,
+            created: false,
+        });
+    }
+
+    let dir_name = Path::new(&path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("project")
+        .to_string();
+    let name = p.ask("Project name", &dir_name)?;
+    let project = Project::new(path.clone(), name.clone());
+    storage.create_project(&project, actor)?;
+    p.say(&format!("  Registered '{name}'"))?;
+
+    Ok(ProjectOutput {
+        id: project.id,
+        name,
+        path,
+        created: true,
+    })
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    let rest = path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\"));
+    match (rest, directories::BaseDirs::new()) {
+        (Some(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn prompter(input: &str) -> Prompter<Cursor<Vec<u8>>, Vec<u8>> {
+        Prompter::new(Cursor::new(input.as_bytes().to_vec()), Vec::new(), false)
+    }
+
+    #[test]
+    fn test_ask_uses_default_on_empty_answer() {
+        let mut p = prompter("\n  custom  \n");
+        assert_eq!(p.ask("Name", "alice").unwrap(), "alice");
+        assert_eq!(p.ask("Name", "alice").unwrap(), "custom");
+    }
+
+    #[test]
+    fn test_confirm_reprompts_on_invalid_answer() {
+        let mut p = prompter("maybe\nYES\n");
+        assert!(p.confirm("Continue?", false).unwrap());
+        let prompts = String::from_utf8(p.output).unwrap();
+        assert!(prompts.contains("Please answer y or n."));
+    }
+
+    #[test]
+    fn test_choose_by_number_or_name() {
+        let mut p = prompter("2\nOllama\n7\n\n");
+        assert_eq!(p.choose("Provider", PROVIDERS, 0).unwrap(), 1);
+        assert_eq!(p.choose("Provider", PROVIDERS, 1).unwrap(), 0);
+        assert_eq!(p.choose("Provider", PROVIDERS, 1).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_eof_and_assume_defaults() {
+        let mut p = prompter("");
+        assert!(!p.confirm("Install?", false).unwrap());
+        assert_eq!(p.ask("Name", "bob").unwrap(), "bob");
+
+        let mut yes = Prompter::new(Cursor::new(b"n\n".to_vec()), Vec::new(), true);
+        assert!(yes.confirm("Register?", true).unwrap());
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(expand_home("/tmp/x.db"), PathBuf::from("/tmp/x.db"));
+        let expanded = expand_home("~/sc/x.db");
+        assert!(expanded.ends_with("sc/x.db"));
+        assert!(!expanded.starts_with("~"));
+    }
+}
@@ -0,0 +1,319 @@
+//! Read-only session pages (`sc share session`).
+//!
+//! Writes one HTML file that shows a session to someone who will never run
+//! `sc`: its context items grouped by category, its checkpoints, and its
+//! linked issues. The page is self-contained. CSS and the small script that
+//! filters it are inline, and a Content-Security-Policy stops it loading
+//! anything else, so it can be mailed, attached to a ticket or put on any
+//! static host.
+//!
+//! Issues don't record which session worked on them, so an issue counts as
+//! linked when it belongs to a plan bound to the session, or when its ID or
+//! short ID appears in one of the session's items or checkpoints.
+//!
+//! Rejected items are left out, and agent decisions still awaiting review
+//! are marked as such. Everything else in the session is included, so check
+//! its items before sharing the page.
+
+use super::vault::item_tags;
+use crate::cli::{Database, ShareCommands};
+use crate::config::resolve_session_or_suggest;
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemPriority, ReviewStatus};
+use crate::storage::{Checkpoint, ContextItem, Issue, Session, SqliteStorage};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+
+/// Output for share session to a file.
+#[derive(Serialize)]
+struct ShareOutput {
+    file: String,
+    session_id: String,
+    items: usize,
+    checkpoints: usize,
+    issues: usize,
+}
+
+json_schema!(ShareOutput { file: String, session_id: String, items: usize, checkpoints: usize, issues: usize });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("share session", schema_for::<ShareOutput>())]
+}
+
+/// What a session page shows.
+struct Page {
+    session: Session,
+    project_paths: Vec<String>,
+    items: Vec<ContextItem>,
+    checkpoints: Vec<Checkpoint>,
+    issues: Vec<Issue>,
+}
+
+/// Execute a share command.
+///
+/// # Errors
+///
+/// Returns an error if the session doesn't exist, the database cannot be
+/// read or the file written.
+pub fn execute(command: &ShareCommands, db: &mut Database, session_id: Option<&str>, json: bool) -> Result<()> {
+    let ShareCommands::Session { id, out } = command;
+    let storage = db.storage()?;
+    let session_id = resolve_session_or_suggest(id.as_deref().or(session_id), storage)?;
+    let page = collect(storage, &session_id)?;
+    let content = render(&page, chrono::Utc::now().timestamp_millis());
+
+    let Some(path) = out else {
+        print!("{content}");
+        return Ok(());
+    };
+    if crate::is_dry_run() {
+        println!("Would write session {} to {}", page.session.id, path.display());
+        return Ok(());
+    }
+    std::fs::write(path, &content)?;
+
+    if json {
+        let output = ShareOutput {
+            file: path.display().to_string(),
+            session_id: page.session.id,
+            items: page.items.len(),
+            checkpoints: page.checkpoints.len(),
+            issues: page.issues.len(),
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if !crate::is_silent() {
+        println!(
+            "Shared {} items, {} checkpoints and {} issues of \"{}\" in {}",
+            page.items.len(),
+            page.checkpoints.len(),
+            page.issues.len(),
+            page.session.name,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn collect(storage: &SqliteStorage, session_id: &str) -> Result<Page> {
+    let session =
+        storage.get_session(session_id)?.ok_or_else(|| Error::SessionNotFound { id: session_id.to_string() })?;
+    let mut project_paths = storage.get_session_paths(session_id)?;
+    let mut seen = HashSet::new();
+    project_paths.retain(|path| seen.insert(path.clone()));
+    let mut items = storage.get_context_items(session_id, None, None, Some(u32::MAX))?;
+    items.retain(|item| item.review_status != ReviewStatus::Rejected);
+    let checkpoints = storage.list_checkpoints(session_id, Some(u32::MAX))?;
+
+    let mut texts: Vec<&str> = Vec::new();
+    for item in &items {
+        texts.extend([item.key.as_str(), item.value.as_str()]);
+    }
+    for checkpoint in &checkpoints {
+        texts.push(&checkpoint.name);
+        texts.extend(checkpoint.description.as_deref());
+    }
+
+    let mut issues = Vec::new();
+    for path in &project_paths {
+        let plans: HashSet<String> = storage
+            .get_plans_by_project(path)?
+            .into_iter()
+            .filter(|plan| [&plan.session_id, &plan.created_in_session].iter().any(|s| s.as_deref() == Some(session_id)))
+            .map(|plan| plan.id)
+            .collect();
+        for issue in storage.get_issues_by_project(path)? {
+            let in_plan = issue.plan_id.as_ref().is_some_and(|plan| plans.contains(plan));
+            let ids = [Some(issue.id.as_str()), issue.short_id.as_deref()];
+            let mentioned = ids.into_iter().flatten().any(|id| texts.iter().any(|text| mentions(text, id)));
+            if in_plan || mentioned {
+                issues.push(issue);
+            }
+        }
+    }
+
+    Ok(Page { session, project_paths, items, checkpoints, issues })
+}
+
+/// Whether `text` contains `id` as a whole word.
+fn mentions(text: &str, id: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    text.match_indices(id).any(|(start, _)| {
+        !text[..start].chars().next_back().is_some_and(is_word)
+            && !text[start + id.len()..].chars().next().is_some_and(is_word)
+    })
+}
+
+const STYLE: &str = "\
+:root{--fg:#1f2328;--muted:#656d76;--line:#d0d7de;--bg:#fff;--card:#f6f8fa}
+@media (prefers-color-scheme:dark){:root{--fg:#e6edf3;--muted:#8d96a0;--line:#30363d;--bg:#0d1117;--card:#161b22}}
+*{box-sizing:border-box}
+body{margin:0 auto;max-width:960px;padding:2rem 1rem;font:15px/1.5 system-ui,sans-serif;color:var(--fg);background:var(--bg)}
+h1{margin:0 0 .25rem;font-size:1.6rem}
+h2{margin:2rem 0 .75rem;padding-bottom:.25rem;border-bottom:1px solid var(--line);font-size:1.2rem}
+h3{margin:1.25rem 0 .5rem;font-size:1rem;text-transform:capitalize}
+.meta,.card .meta{color:var(--muted);font-size:.85rem}
+.meta span+span::before{content:' \\00b7 '}
+.card{margin:.5rem 0;padding:.75rem 1rem;background:var(--card);border:1px solid var(--line);border-radius:6px}
+.card .title{font-weight:600}
+.text{margin:.35rem 0 0;white-space:pre-wrap;overflow-wrap:anywhere}
+.badge{display:inline-block;margin-left:.35rem;padding:0 .45rem;border:1px solid var(--line);border-radius:1em;font-size:.75rem;font-weight:normal;color:var(--muted)}
+.badge.high,.badge.review{color:#bf3989;border-color:#bf3989}
+.empty{color:var(--muted)}
+input{width:100%;margin-top:1rem;padding:.5rem .75rem;font:inherit;color:inherit;background:var(--card);border:1px solid var(--line);border-radius:6px}
+footer{margin-top:3rem;color:var(--muted);font-size:.8rem}
+";
+
+/// Filters the cards as one types, and shows times in the reader's zone.
+const SCRIPT: &str = "\
+document.querySelectorAll('time').forEach(function(t){var d=new Date(t.dateTime);if(!isNaN(d))t.textContent=d.toLocaleString()});
+var filter=document.getElementById('filter');
+filter.addEventListener('input',function(){var q=filter.value.toLowerCase();
+document.querySelectorAll('.card').forEach(function(c){c.hidden=q!==''&&c.textContent.toLowerCase().indexOf(q)<0})});
+";
+
+fn render(page: &Page, generated_at: i64) -> String {
+    let session = &page.session;
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    out.push_str(
+        "<meta http-equiv=\"Content-Security-Policy\" content=\"default-src 'none'; style-src 'unsafe-inline'; script-src 'unsafe-inline'\">\n",
+    );
+    let _ = writeln!(out, "<title>{}</title>", escape(&session.name));
+    let _ = writeln!(out, "<style>\n{STYLE}</style>\n</head>\n<body>\n<header>");
+    let _ = writeln!(out, "<h1>{}</h1>", escape(&session.name));
+    let mut meta = vec![format!("Session {}", escape(&session.id)), escape(&session.status.to_string())];
+    meta.extend(session.branch.as_deref().map(|branch| format!("branch {}", escape(branch))));
+    meta.push(format!("started {}", time(session.created_at)));
+    meta.extend(session.ended_at.map(|ended| format!("ended {}", time(ended))));
+    let _ = writeln!(out, "<div class=\"meta\">{}</div>", spans(&meta));
+    let paths: Vec<String> = page.project_paths.iter().map(|path| escape(path)).collect();
+    let _ = writeln!(out, "<div class=\"meta\">{}</div>", spans(&paths));
+    if let Some(description) = &session.description {
+        let _ = writeln!(out, "<p class=\"text\">{}</p>", escape(description));
+    }
+    out.push_str("<input id=\"filter\" type=\"search\" placeholder=\"Filter\" aria-label=\"Filter\">\n</header>\n");
+
+    let _ = writeln!(out, "<h2>Context ({})</h2>", page.items.len());
+    let mut by_category: BTreeMap<String, Vec<&ContextItem>> = BTreeMap::new();
+    for item in &page.items {
+        by_category.entry(item.category.to_string()).or_default().push(item);
+    }
+    if by_category.is_empty() {
+        out.push_str("<p class=\"empty\">No context items.</p>\n");
+    }
+    for (category, items) in &by_category {
+        let _ = writeln!(out, "<h3>{}</h3>", escape(category));
+        for item in items {
+            let mut badges = String::new();
+            if item.priority == ItemPriority::High {
+                badges.push_str("<span class=\"badge high\">high</span>");
+            }
+            if item.awaits_review() {
+                badges.push_str("<span class=\"badge review\">awaiting review</span>");
+            }
+            let mut meta = vec![format!("updated {}", time(item.updated_at))];
+            meta.extend(item.channel.as_deref().map(|channel| format!("channel {}", escape(channel))));
+            let tags = item_tags(item);
+            if !tags.is_empty() {
+                meta.push(tags.iter().map(|tag| format!("#{}", escape(tag))).collect::<Vec<_>>().join(" "));
+            }
+            card(&mut out, &format!("{}{badges}", escape(&item.key)), &meta, Some(&item.value));
+        }
+    }
+
+    let _ = writeln!(out, "<h2>Checkpoints ({})</h2>", page.checkpoints.len());
+    if page.checkpoints.is_empty() {
+        out.push_str("<p class=\"empty\">No checkpoints.</p>\n");
+    }
+    for checkpoint in &page.checkpoints {
+        let mut meta = vec![time(checkpoint.created_at), format!("{} items", checkpoint.item_count)];
+        meta.extend(checkpoint.git_branch.as_deref().map(|branch| format!("branch {}", escape(branch))));
+        card(&mut out, &escape(&checkpoint.name), &meta, checkpoint.description.as_deref());
+    }
+
+    let _ = writeln!(out, "<h2>Linked issues ({})</h2>", page.issues.len());
+    if page.issues.is_empty() {
+        out.push_str("<p class=\"empty\">No linked issues.</p>\n");
+    }
+    for issue in &page.issues {
+        let id = issue.short_id.as_deref().unwrap_or(&issue.id);
+        let title = format!(
+            "{} {}<span class=\"badge\">{}</span>",
+            escape(id),
+            escape(&issue.title),
+            escape(&issue.status.to_string())
+        );
+        let mut meta = vec![issue.issue_type.to_string(), format!("P{}", issue.priority)];
+        meta.extend(issue.assigned_to_agent.as_deref().map(|agent| format!("assigned to {}", escape(agent))));
+        meta.extend(issue.closed_at.map(|closed| format!("closed {}", time(closed))));
+        card(&mut out, &title, &meta, issue.description.as_deref());
+    }
+
+    let _ = writeln!(
+        out,
+        "<footer>Read-only snapshot written by <code>sc share session</code> on {}.</footer>",
+        time(generated_at)
+    );
+    let _ = write!(out, "<script>\n{SCRIPT}</script>\n</body>\n</html>\n");
+    out
+}
+
+/// Append a card; `title` and `meta` are HTML already, `text` is escaped here.
+fn card(out: &mut String, title: &str, meta: &[String], text: Option<&str>) {
+    let _ = write!(out, "<div class=\"card\"><div class=\"title\">{title}</div><div class=\"meta\">{}</div>", spans(meta));
+    if let Some(text) = text.filter(|text| !text.is_empty()) {
+        let _ = write!(out, "<p class=\"text\">{}</p>", escape(text));
+    }
+    out.push_str("</div>\n");
+}
+
+fn spans(parts: &[String]) -> String {
+    let mut out = String::new();
+    for part in parts {
+        let _ = write!(out, "<span>{part}</span>");
+    }
+    out
+}
+
+/// A timestamp in UTC, which the page's script rewrites in local time.
+pub(crate) fn time(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms).map_or_else(
+        || ms.to_string(),
+        |dt| format!("<time datetime=\"{}\">{}</time>", dt.to_rfc3339(), dt.format("%Y-%m-%d %H:%M UTC")),
+    )
+}
+
+/// Escape text for HTML content and attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mentions_and_escape() {
+        assert!(mentions("Fixed SC-a1b2 today", "SC-a1b2"));
+        assert!(mentions("(SC-a1b2)", "SC-a1b2"));
+        assert!(!mentions("SC-a1b2c", "SC-a1b2"));
+        assert!(!mentions("XSC-a1b2", "SC-a1b2"));
+        assert_eq!(escape("<script>alert('x')</script> & \"y\""), "&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; &quot;y&quot;");
+    }
+}
@@ -8,7 +8,9 @@
 
 use crate::cli::SkillsCommands;
 use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, object, schema_for, JsonSchema};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -110,6 +112,38 @@ struct ToolInstallResult {
     modes: Vec<String>,
 }
 
+json_schema!(SkillSyncConfig { installations: Vec<SkillInstallation> });
+json_schema!(InstallResult {
+    success: bool,
+    tools: Vec<ToolInstallResult>,
+    hooks_installed: bool,
+    settings_configured: bool,
+    python_found: Option<String>,
+    error: Option<String>,
+});
+json_schema!(ToolInstallResult { tool: String, path: String, files_installed: usize, modes: Vec<String> });
+
+// `installed_at` is renamed, so the fields are listed by hand.
+impl JsonSchema for SkillInstallation {
+    fn json_schema() -> Value {
+        object(&[
+            ("tool", schema_for::<String>(), true),
+            ("path", schema_for::<String>(), true),
+            ("installedAt", schema_for::<u64>(), true),
+            ("mode", schema_for::<String>(), true),
+        ])
+    }
+}
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("skills install", schema_for::<InstallResult>()),
+        ("skills status", schema_for::<SkillSyncConfig>()),
+        ("skills update", schema_for::<InstallResult>()),
+    ]
+}
+
 // ── Entry Points ─────────────────────────────────────────────
 
 /// Execute skills commands.
@@ -142,11 +176,16 @@ fn install(tool: Option<&str>, mode: &str, path_override: Option<&Path>, json: b
 
     if tools.is_empty() {
         if json {
-            let output = serde_json::json!({
-                "success": false,
-                "error": "No AI coding tools detected. Install Claude Code, Codex, Gemini, or Factory AI first.",
-                "tools": []
-            });
+            let output = InstallResult {
+                success: false,
+                tools: Vec::new(),
+                hooks_installed: false,
+                settings_configured: false,
+                python_found: None,
+                error: Some(
+                    "No AI coding tools detected. Install Claude Code, Codex, Gemini, or Factory AI first.".to_string(),
+                ),
+            };
             println!("{}", serde_json::to_string(&output)?);
             return Ok(());
         }
@@ -265,10 +304,7 @@ fn status(json: bool) -> Result<()> {
     let config = load_sync_config();
 
     if json {
-        let output = serde_json::json!({
-            "installations": config.installations,
-        });
-        println!("{}", serde_json::to_string(&output)?);
+        println!("{}", serde_json::to_string(&config)?);
     } else if config.installations.is_empty() {
         println!("No skills installed.");
         println!("Run: sc skills install");
@@ -0,0 +1,184 @@
+//! Local usage statistics (`sc stats`).
+//!
+//! Every `sc` invocation records its command name (never its arguments),
+//! duration, time spent in SQL, slow statements, and outcome in the local
+//! database, along with one database size sample per day. This command
+//! summarizes that data: most-used commands, the slowest runs, slow
+//! queries, and database growth. Nothing is sent
+//! anywhere; disable recording with `"usage_stats": false` in
+//! `~/.savecontext/config.json` or `SC_USAGE_STATS=0`.
+
+use crate::cli::Database;
+use crate::config::usage_stats_enabled;
+use crate::error::Result;
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::storage::UsageStats;
+use crate::sync::format_size;
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct StatsOutput {
+    days: u32,
+    recording: bool,
+    stats: UsageStats,
+}
+
+#[derive(Serialize)]
+struct StatsClearOutput {
+    cleared: usize,
+    dry_run: bool,
+}
+
+json_schema!(StatsOutput { days: u32, recording: bool, stats: UsageStats });
+json_schema!(StatsClearOutput { cleared: usize, dry_run: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("stats", one_of(&[schema_for::<StatsOutput>(), schema_for::<StatsClearOutput>()]))]
+}
+
+/// Execute the stats command.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or queried.
+pub fn execute(
+    db: &mut Database,
+    days: u32,
+    limit: usize,
+    clear: bool,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+
+    if clear {
+        let removed = if crate::is_dry_run() { 0 } else { storage.clear_usage_stats()? };
+        if json {
+            let output = StatsClearOutput { cleared: removed, dry_run: crate::is_dry_run() };
+            println!("{}", serde_json::to_string(&output)?);
+        } else if crate::is_dry_run() {
+            println!("Would clear all recorded usage statistics.");
+        } else {
+            println!("Cleared {removed} usage record(s).");
+        }
+        return Ok(());
+    }
+
+    let since = chrono::Utc::now().timestamp_millis() - i64::from(days) * 86_400_000;
+    let stats = storage.get_usage_stats(since, limit)?;
+    let recording = usage_stats_enabled();
+
+    if json {
+        let output = StatsOutput { days, recording, stats };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    print_stats(&stats, days, recording);
+    Ok(())
+}
+
+/// Print the usage summary for people.
+fn print_stats(stats: &UsageStats, days: u32, recording: bool) {
+    println!(
+        "Usage in the last {days} days: {} command(s) (local only, never uploaded)",
+        stats.total_invocations
+    );
+    if !recording {
+        println!("Recording is disabled (usage_stats = false or SC_USAGE_STATS=0).");
+    }
+    if stats.total_invocations == 0 {
+        return;
+    }
+
+    println!();
+    println!("Most used:");
+    for usage in &stats.commands {
+        let failed = if usage.failures > 0 {
+            format!("  {} failed", usage.failures)
+        } else {
+            String::new()
+        };
+        let slow = if usage.slow_queries > 0 {
+            format!("  {} slow", usage.slow_queries)
+        } else {
+            String::new()
+        };
+        println!(
+            "  {:<20} {:>5}  avg {:>7} ({:>7} SQL)  max {:>7}{failed}{slow}",
+            usage.command,
+            usage.count,
+            format_ms(usage.avg_ms),
+            format_ms(usage.avg_sql_ms),
+            format_ms(usage.max_ms),
+        );
+    }
+
+    println!();
+    println!("Slowest runs:");
+    for run in &stats.slowest {
+        let when = chrono::DateTime::from_timestamp_millis(run.created_at)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let outcome = if run.success { "" } else { "  (failed)" };
+        println!(
+            "  {:<20} {:>7} ({:>7} SQL)  {when}{outcome}",
+            run.command,
+            format_ms(run.duration_ms),
+            format_ms(run.sql_ms)
+        );
+    }
+
+    if stats.slow_queries > 0 {
+        println!();
+        println!(
+            "{} SQL statement(s) ran past the slow-query threshold ({}ms). If these grow with the",
+            stats.slow_queries,
+            crate::config::slow_query_ms()
+        );
+        println!("database, `sc db stats` shows what takes the space and `sc db indexes --analyze` what's unindexed.");
+    }
+
+    if !stats.db_growth.is_empty() {
+        println!();
+        println!("Database size:");
+        let mut previous: Option<i64> = None;
+        for sample in &stats.db_growth {
+            let delta = previous.map_or_else(String::new, |p| match sample.size_bytes - p {
+                0 => String::new(),
+                d if d > 0 => format!("  +{}", format_size(d.unsigned_abs())),
+                d => format!("  -{}", format_size(d.unsigned_abs())),
+            });
+            println!(
+                "  {}  {:>9}{delta}",
+                sample.sampled_on,
+                format_size(sample.size_bytes.unsigned_abs())
+            );
+            previous = Some(sample.size_bytes);
+        }
+    }
+}
+
+/// Format milliseconds compactly (`850ms`, `1.2s`).
+fn format_ms(ms: i64) -> String {
+    let duration = Duration::from_millis(ms.unsigned_abs());
+    if duration.as_millis() < 1000 {
+        format!("{ms}ms")
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_ms() {
+        assert_eq!(format_ms(0), "0ms");
+        assert_eq!(format_ms(999), "999ms");
+        assert_eq!(format_ms(1300), "1.3s");
+    }
+}
@@ -1,10 +1,12 @@
 //! Status command implementation.
 
-use crate::config::{current_git_branch, resolve_db_path, resolve_session_id};
-use crate::error::{Error, Result};
-use crate::storage::SqliteStorage;
+use crate::cli::Database;
+use crate::config::{current_git_branch, resolve_session_id};
+use crate::error::Result;
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemCategory, ItemPriority, SessionStatus};
 use serde::Serialize;
-use std::path::PathBuf;
+use serde_json::Value;
 
 /// Output for status command.
 #[derive(Serialize)]
@@ -15,17 +17,27 @@ struct StatusOutput {
     item_count: usize,
     high_priority_count: usize,
     categories: CategoryBreakdown,
+    /// Open reminders of the project that are due, due first.
+    due_reminders: Vec<DueReminder>,
 }
 
 #[derive(Serialize)]
 struct SessionInfo {
     id: String,
     name: String,
-    status: String,
+    status: SessionStatus,
     created_at: i64,
     updated_at: i64,
 }
 
+#[derive(Serialize)]
+struct DueReminder {
+    id: String,
+    key: String,
+    value: String,
+    due_at: i64,
+}
+
 #[derive(Serialize)]
 struct CategoryBreakdown {
     reminder: usize,
@@ -34,19 +46,30 @@ struct CategoryBreakdown {
     note: usize,
 }
 
+json_schema!(StatusOutput {
+    session: Option<SessionInfo>,
+    project_path: Option<String>,
+    git_branch: Option<String>,
+    item_count: usize,
+    high_priority_count: usize,
+    categories: CategoryBreakdown,
+    due_reminders: Vec<DueReminder>,
+});
+json_schema!(DueReminder { id: String, key: String, value: String, due_at: i64 });
+json_schema!(SessionInfo { id: String, name: String, status: SessionStatus, created_at: i64, updated_at: i64 });
+json_schema!(CategoryBreakdown { reminder: usize, decision: usize, progress: usize, note: usize });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("status", schema_for::<StatusOutput>())]
+}
+
 /// Execute status command.
 ///
 /// If `session_id` is provided (from MCP bridge), looks up that specific session.
 /// Otherwise falls back to finding an active session for the current project path.
-pub fn execute(db_path: Option<&PathBuf>, session_id: Option<&str>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path()))
-        .ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
+pub fn execute(db: &mut Database, session_id: Option<&str>, json: bool) -> Result<()> {
+    let storage = db.storage()?;
     let git_branch = current_git_branch();
 
     // Resolve session via TTY-keyed status cache (soft — no error if missing)
@@ -63,11 +86,11 @@ pub fn execute(db_path: Option<&PathBuf>, session_id: Option<&str>, json: bool)
         // Get all items for the session
         let items = storage.get_context_items(&s.id, None, None, Some(1000))?;
 
-        let high = items.iter().filter(|i| i.priority == "high").count();
-        let reminder = items.iter().filter(|i| i.category == "reminder").count();
-        let decision = items.iter().filter(|i| i.category == "decision").count();
-        let progress = items.iter().filter(|i| i.category == "progress").count();
-        let note = items.iter().filter(|i| i.category == "note").count();
+        let high = items.iter().filter(|i| i.priority == ItemPriority::High).count();
+        let reminder = items.iter().filter(|i| i.category == ItemCategory::Reminder).count();
+        let decision = items.iter().filter(|i| i.category == ItemCategory::Decision).count();
+        let progress = items.iter().filter(|i| i.category == ItemCategory::Progress).count();
+        let note = items.iter().filter(|i| i.category == ItemCategory::Note).count();
 
         (
             items.len(),
@@ -92,12 +115,23 @@ pub fn execute(db_path: Option<&PathBuf>, session_id: Option<&str>, json: bool)
         )
     };
 
+    let due_reminders: Vec<DueReminder> = match project_path {
+        Some(ref path) => {
+            super::remind::refresh_statusline(storage, path)?;
+            super::remind::due_reminders(storage, path, chrono::Utc::now().timestamp_millis())?
+                .into_iter()
+                .map(|r| DueReminder { id: r.id, key: r.key, value: r.value, due_at: r.due_at.unwrap_or_default() })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
     if json {
         let output = StatusOutput {
             session: session.map(|s| SessionInfo {
                 id: s.id.clone(),
                 name: s.name.clone(),
-                status: s.status.clone(),
+                status: s.status,
                 created_at: s.created_at,
                 updated_at: s.updated_at,
             }),
@@ -106,6 +140,7 @@ pub fn execute(db_path: Option<&PathBuf>, session_id: Option<&str>, json: bool)
             item_count,
             high_priority_count,
             categories,
+            due_reminders,
         };
         println!("{}", serde_json::to_string(&output)?);
     } else {
@@ -121,6 +156,15 @@ pub fn execute(db_path: Option<&PathBuf>, session_id: Option<&str>, json: bool)
         }
         println!();
 
+        if !due_reminders.is_empty() {
+            println!("Due Reminders: {}", due_reminders.len());
+            for r in &due_reminders {
+                println!("  ! {} (due {}): {}", r.key, super::remind::format_due(r.due_at), r.value.lines().next().unwrap_or_default());
+            }
+            println!("  Clear one with: sc remind done <key>");
+            println!();
+        }
+
         if let Some(ref s) = session {
             println!("Active Session: {}", s.name);
             println!("  ID: {}", s.id);
@@ -4,34 +4,246 @@
 //! as the project path. JSONL files are written to `<project>/.savecontext/`
 //! so they can be committed to git alongside the project code.
 
-use crate::cli::SyncCommands;
+use crate::cli::{Database, SyncCommands};
 use crate::cli::commands::config::{
     build_scp_base_args, build_ssh_base_args, load_remote_config, shell_quote, RemoteConfig,
 };
-use crate::config::resolve_db_path;
 use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, one_of, schema_for};
+use crate::model::Project;
 use crate::storage::SqliteStorage;
-use crate::sync::{project_export_dir, Exporter, Importer, MergeStrategy};
+use crate::sync::{
+    project_export_dir, Divergence, ExportStats, Exporter, ImportMode, ImportStats, Importer, LineError, MergeStrategies,
+    MergeStrategy, SyncError, SyncStatus,
+};
+use serde::Serialize;
+use serde_json::Value;
 use std::env;
 use std::path::PathBuf;
 use std::process::Command;
 use tracing::debug;
 
+/// Output for sync status.
+#[derive(Serialize)]
+struct SyncStatusOutput {
+    project: String,
+    export_dir: String,
+    status: SyncStatus,
+}
+
+/// Output for sync verify.
+#[derive(Serialize)]
+struct SyncVerifyOutput {
+    project: String,
+    export_dir: String,
+    /// Whether the export matches the database (after `--fix`).
+    ok: bool,
+    /// Divergences `--fix` re-exported.
+    fixed: usize,
+    files_checked: usize,
+    records_checked: usize,
+    /// What still differs.
+    divergences: Vec<Divergence>,
+}
+
+/// Output for sync export.
+#[derive(Serialize)]
+struct SyncExportOutput {
+    success: bool,
+    project: String,
+    output_dir: String,
+    stats: ExportStats,
+}
+
+/// Output for sync export when nothing is dirty.
+#[derive(Serialize)]
+struct NothingToExportOutput {
+    /// Always `nothing_to_export`.
+    error: &'static str,
+    project: String,
+    message: &'static str,
+}
+
+/// Output for sync import.
+#[derive(Serialize)]
+struct SyncImportOutput {
+    success: bool,
+    project: String,
+    import_dir: String,
+    stats: ImportStats,
+}
+
+/// Output for sync import when an export file is missing.
+#[derive(Serialize)]
+struct FileNotFoundOutput {
+    /// Always `file_not_found`.
+    error: &'static str,
+    project: String,
+    path: String,
+}
+
+/// Output for sync clone.
+#[derive(Serialize)]
+struct SyncCloneOutput {
+    success: bool,
+    project: Project,
+    import_dir: String,
+    /// Path the records were exported under, when it differs from this one.
+    moved_from: Option<String>,
+    stats: ImportStats,
+    embeddings_queued: bool,
+}
+
+/// Output for sync push.
+#[derive(Serialize)]
+struct SyncPushOutput {
+    success: bool,
+    files_pushed: usize,
+    local_project: String,
+    remote_project: String,
+}
+
+/// Output for sync push when there are no JSONL files.
+#[derive(Serialize)]
+struct NothingToPushOutput {
+    success: bool,
+    message: &'static str,
+    project: String,
+}
+
+/// Output for sync pull.
+#[derive(Serialize)]
+struct SyncPullOutput {
+    success: bool,
+    remote_project: String,
+    local_project: String,
+    import_stats: ImportStats,
+}
+
+/// Output for sync push `--full`.
+#[derive(Serialize)]
+struct FullPushOutput {
+    success: bool,
+    /// Always `full`.
+    mode: &'static str,
+    size_bytes: u64,
+    remote_db: String,
+    /// `user@host:port`.
+    remote_host: String,
+    /// Copy of the remote database taken before it was replaced.
+    pre_push_backup: String,
+}
+
+/// Output for sync pull `--full`.
+#[derive(Serialize)]
+struct FullPullOutput {
+    success: bool,
+    /// Always `full`.
+    mode: &'static str,
+    size_bytes: u64,
+    remote_db: String,
+    /// `user@host:port`.
+    remote_host: String,
+    /// Copy of the local database taken before it was replaced.
+    local_backup: String,
+}
+
+/// Output for sync backup.
+#[derive(Serialize)]
+struct SyncBackupOutput {
+    success: bool,
+    source: String,
+    backup: String,
+    size_bytes: u64,
+}
+
+json_schema!(SyncExportOutput { success: bool, project: String, output_dir: String, stats: ExportStats });
+json_schema!(NothingToExportOutput { error: &'static str, project: String, message: &'static str });
+json_schema!(SyncImportOutput { success: bool, project: String, import_dir: String, stats: ImportStats });
+json_schema!(FileNotFoundOutput { error: &'static str, project: String, path: String });
+json_schema!(SyncCloneOutput {
+    success: bool,
+    project: Project,
+    import_dir: String,
+    moved_from: Option<String>,
+    stats: ImportStats,
+    embeddings_queued: bool,
+});
+json_schema!(SyncPushOutput { success: bool, files_pushed: usize, local_project: String, remote_project: String });
+json_schema!(NothingToPushOutput { success: bool, message: &'static str, project: String });
+json_schema!(SyncPullOutput { success: bool, remote_project: String, local_project: String, import_stats: ImportStats });
+json_schema!(FullPushOutput {
+    success: bool,
+    mode: &'static str,
+    size_bytes: u64,
+    remote_db: String,
+    remote_host: String,
+    pre_push_backup: String,
+});
+json_schema!(FullPullOutput {
+    success: bool,
+    mode: &'static str,
+    size_bytes: u64,
+    remote_db: String,
+    remote_host: String,
+    local_backup: String,
+});
+json_schema!(SyncBackupOutput { success: bool, source: String, backup: String, size_bytes: u64 });
+json_schema!(SyncStatusOutput { project: String, export_dir: String, status: SyncStatus });
+json_schema!(SyncVerifyOutput {
+    project: String,
+    export_dir: String,
+    ok: bool,
+    fixed: usize,
+    files_checked: usize,
+    records_checked: usize,
+    divergences: Vec<Divergence>,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("sync status", schema_for::<SyncStatusOutput>()),
+        ("sync verify", schema_for::<SyncVerifyOutput>()),
+        ("sync export", one_of(&[schema_for::<SyncExportOutput>(), schema_for::<NothingToExportOutput>()])),
+        ("sync import", one_of(&[schema_for::<SyncImportOutput>(), schema_for::<FileNotFoundOutput>()])),
+        ("sync clone", schema_for::<SyncCloneOutput>()),
+        (
+            "sync push",
+            one_of(&[
+                schema_for::<SyncPushOutput>(),
+                schema_for::<NothingToPushOutput>(),
+                schema_for::<FullPushOutput>(),
+            ]),
+        ),
+        ("sync pull", one_of(&[schema_for::<SyncPullOutput>(), schema_for::<FullPullOutput>()])),
+        ("sync backup", schema_for::<SyncBackupOutput>()),
+    ]
+}
+
 /// Execute sync commands.
-pub fn execute(command: &SyncCommands, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
+pub fn execute(command: &SyncCommands, db: &mut Database, json: bool) -> Result<()> {
     match command {
-        SyncCommands::Export { force } => export(*force, db_path, json),
-        SyncCommands::Import { force } => import(*force, db_path, json),
-        SyncCommands::Status => status(db_path, json),
+        SyncCommands::Export {
+            force,
+            include_embeddings,
+        } => export(*force, *include_embeddings, db, json),
+        SyncCommands::Import { force, strategy, strict, max_field_len } => {
+            let mode = if *strict { ImportMode::Strict } else { ImportMode::Lenient };
+            import(*force, strategy.as_deref(), mode, *max_field_len, db, json)
+        }
+        SyncCommands::Status => status(db, json),
+        SyncCommands::Clone => clone(db, json),
+        SyncCommands::Verify { fix } => verify(*fix, db, json),
         SyncCommands::Push {
             force,
             remote_path,
             full,
         } => {
             if *full {
-                push_full(db_path, json)
+                push_full(db, json)
             } else {
-                push(*force, remote_path.as_deref(), db_path, json)
+                push(*force, remote_path.as_deref(), db, json)
             }
         }
         SyncCommands::Pull {
@@ -40,12 +252,12 @@ pub fn execute(command: &SyncCommands, db_path: Option<&PathBuf>, json: bool) ->
             full,
         } => {
             if *full {
-                pull_full(db_path, json)
+                pull_full(db, json)
             } else {
-                pull(*force, remote_path.as_deref(), db_path, json)
+                pull(*force, remote_path.as_deref(), db, json)
             }
         }
-        SyncCommands::Backup { output } => backup(output.as_deref(), db_path, json),
+        SyncCommands::Backup { output } => backup(output.as_deref(), db, json),
     }
 }
 
@@ -58,29 +270,28 @@ fn get_project_path() -> Result<String> {
         .ok_or_else(|| Error::Other("Current directory path is not valid UTF-8".to_string()))
 }
 
-fn export(force: bool, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path =
-        resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+fn export(force: bool, include_embeddings: bool, db: &mut Database, json: bool) -> Result<()> {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let project_path = get_project_path()?;
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let output_dir = project_export_dir(&project_path);
 
-    let mut exporter = Exporter::new(&mut storage, project_path.clone());
+    let mut exporter = Exporter::new(storage, project_path.clone())
+        .with_embeddings(include_embeddings)
+        .with_events(crate::config::sync_events_enabled());
 
     match exporter.export(force) {
         Ok(stats) => {
             if json {
-                let output = serde_json::json!({
-                    "success": true,
-                    "project": project_path,
-                    "output_dir": output_dir.display().to_string(),
-                    "stats": stats,
-                });
+                let output = SyncExportOutput {
+                    success: true,
+                    project: project_path,
+                    output_dir: output_dir.display().to_string(),
+                    stats,
+                };
                 println!("{}", serde_json::to_string(&output)?);
             } else if stats.is_empty() {
                 println!("No records exported.");
@@ -104,18 +315,24 @@ fn export(force: bool, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
                 }
                 println!();
                 println!("  Total: {} records", stats.total());
+                if stats.embeddings > 0 {
+                    println!("  Embeddings for {} records in {}", stats.embeddings, crate::sync::EMBEDDINGS_FILE);
+                }
+                if stats.events > 0 {
+                    println!("  {} new events appended to {}", stats.events, crate::sync::EVENTS_FILE);
+                }
                 println!("  Location: {}", output_dir.display());
             }
             Ok(())
         }
         Err(crate::sync::SyncError::NothingToExport) => {
             if json {
-                let output = serde_json::json!({
-                    "error": "nothing_to_export",
-                    "project": project_path,
-                    "message": "No dirty records to export for this project. Use --force to export all records."
-                });
-                println!("{output}");
+                let output = NothingToExportOutput {
+                    error: "nothing_to_export",
+                    project: project_path,
+                    message: "No dirty records to export for this project. Use --force to export all records.",
+                };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("No dirty records to export for: {project_path}");
                 println!("Use --force to export all records regardless of dirty state.");
@@ -126,39 +343,58 @@ fn export(force: bool, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     }
 }
 
-fn import(force: bool, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path =
-        resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
+/// Merge strategies for an import: `--force` picks the default, then
+/// `sync_strategies` from config and `--strategy` set them per entity.
+fn import_strategies(force: bool, spec: Option<&str>) -> Result<MergeStrategies> {
+    let default = if force { MergeStrategy::PreferExternal } else { MergeStrategy::PreferNewer };
+    let mut strategies = MergeStrategies::new(default);
+    for (entity, strategy) in crate::config::sync_strategies() {
+        strategies
+            .apply_spec(&format!("{entity}={strategy}"))
+            .map_err(|e| Error::Config(format!("sync_strategies: {e}")))?;
+    }
+    if let Some(spec) = spec {
+        strategies.apply_spec(spec).map_err(Error::InvalidArgument)?;
+    }
+    Ok(strategies)
+}
 
-    if !db_path.exists() {
+fn import(
+    force: bool,
+    strategy: Option<&str>,
+    mode: ImportMode,
+    max_field_len: usize,
+    db: &mut Database,
+    json: bool,
+) -> Result<()> {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let project_path = get_project_path()?;
-    let mut storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let import_dir = project_export_dir(&project_path);
+    let strategies = import_strategies(force, strategy)?;
 
-    // Choose merge strategy based on --force flag
-    let strategy = if force {
-        MergeStrategy::PreferExternal
-    } else {
-        MergeStrategy::PreferNewer
-    };
-
-    let mut importer = Importer::new(&mut storage, strategy);
+    let mut importer = Importer::new(storage, MergeStrategy::PreferNewer)
+        .with_strategies(strategies)
+        .with_embedding_model(crate::embeddings::configured_embedding_model())
+        .with_events_since(crate::cli::commands::events::retention_cutoff())
+        .with_mode(mode)
+        .with_max_field_len(max_field_len);
 
     match importer.import_all(&import_dir) {
         Ok(stats) => {
             let total = stats.total_processed();
             if json {
-                let output = serde_json::json!({
-                    "success": true,
-                    "project": project_path,
-                    "import_dir": import_dir.display().to_string(),
-                    "stats": stats,
-                });
+                let output = SyncImportOutput {
+                    success: true,
+                    project: project_path,
+                    import_dir: import_dir.display().to_string(),
+                    stats,
+                };
                 println!("{}", serde_json::to_string(&output)?);
-            } else if total == 0 {
+            } else if total == 0 && stats.errors.is_empty() {
                 println!("No records to import for: {project_path}");
                 println!("Export files not found in: {}", import_dir.display());
             } else {
@@ -176,17 +412,26 @@ fn import(force: bool, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
                     stats.total_updated(),
                     total - stats.total_created() - stats.total_updated()
                 );
+                print_embedding_stats(&stats.embeddings);
+                print_event_stats(&stats.events);
+                print_line_errors(&stats.errors);
             }
             Ok(())
         }
+        Err(SyncError::InvalidLines(errors)) => {
+            if !json {
+                print_line_errors(&errors);
+            }
+            Err(Error::InvalidArgument(format!(
+                "{} invalid line(s) in {}; nothing was imported (drop --strict to skip them)",
+                errors.len(),
+                import_dir.display()
+            )))
+        }
         Err(crate::sync::SyncError::FileNotFound(path)) => {
             if json {
-                let output = serde_json::json!({
-                    "error": "file_not_found",
-                    "project": project_path,
-                    "path": path
-                });
-                println!("{output}");
+                let output = FileNotFoundOutput { error: "file_not_found", project: project_path, path };
+                println!("{}", serde_json::to_string(&output)?);
             } else {
                 println!("Import file not found: {path}");
                 println!("Run 'sc sync export' first to create JSONL files.");
@@ -197,6 +442,48 @@ fn import(force: bool, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     }
 }
 
+fn print_embedding_stats(stats: &crate::sync::EmbeddingImportStats) {
+    if stats.imported + stats.stale + stats.other_model + stats.invalid == 0 {
+        return;
+    }
+    println!("Embeddings: {} imported, {} stale", stats.imported, stats.stale);
+    if stats.other_model > 0 {
+        println!("  {} skipped: made with a different model than this machine uses", stats.other_model);
+    }
+    if stats.invalid > 0 {
+        println!("  {} skipped: vectors don't match their dimensions", stats.invalid);
+    }
+}
+
+fn print_event_stats(stats: &crate::sync::EventImportStats) {
+    if stats.imported + stats.existing + stats.expired + stats.invalid == 0 {
+        return;
+    }
+    println!("Events: {} imported, {} already recorded", stats.imported, stats.existing);
+    if stats.expired > 0 {
+        println!("  {} skipped: older than event_retention.days", stats.expired);
+    }
+    if stats.invalid > 0 {
+        println!("  {} skipped: ID doesn't match the event", stats.invalid);
+    }
+}
+
+/// At most this many skipped lines are listed.
+const MAX_LISTED_ERRORS: usize = 10;
+
+fn print_line_errors(errors: &[LineError]) {
+    if errors.is_empty() {
+        return;
+    }
+    println!("Skipped {} bad line(s):", errors.len());
+    for error in errors.iter().take(MAX_LISTED_ERRORS) {
+        println!("  {error}");
+    }
+    if errors.len() > MAX_LISTED_ERRORS {
+        println!("  ... and {} more (use --json for all)", errors.len() - MAX_LISTED_ERRORS);
+    }
+}
+
 fn print_entity_stats(name: &str, stats: &crate::sync::EntityStats) {
     let total = stats.total();
     if total > 0 {
@@ -204,30 +491,30 @@ fn print_entity_stats(name: &str, stats: &crate::sync::EntityStats) {
             "  {}: {} created, {} updated, {} skipped",
             name, stats.created, stats.updated, stats.skipped
         );
+        if stats.renumbered > 0 {
+            println!("    {} given a new short ID after a collision", stats.renumbered);
+        }
     }
 }
 
-fn status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path =
-        resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-
-    if !db_path.exists() {
+fn status(db: &mut Database, json: bool) -> Result<()> {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let project_path = get_project_path()?;
-    let storage = SqliteStorage::open(&db_path)?;
+    let storage = db.storage()?;
     let export_dir = project_export_dir(&project_path);
 
-    let sync_status = crate::sync::get_sync_status(&storage, &export_dir, &project_path)
+    let sync_status = crate::sync::get_sync_status(storage, &export_dir, &project_path)
         .map_err(|e| Error::Other(e.to_string()))?;
 
     if json {
-        let output = serde_json::json!({
-            "project": project_path,
-            "export_dir": export_dir.display().to_string(),
-            "status": sync_status,
-        });
+        let output = SyncStatusOutput {
+            project: project_path,
+            export_dir: export_dir.display().to_string(),
+            status: sync_status,
+        };
         println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Sync status for: {project_path}");
@@ -239,6 +526,177 @@ fn status(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     Ok(())
 }
 
+/// Onboard a checkout whose `.savecontext/` came in through git: import
+/// everything, re-key the records if they were exported under another
+/// path, register the project and queue embeddings, all in one step.
+fn clone(db: &mut Database, json: bool) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    let project_path = get_project_path()?;
+    let storage = db.storage()?;
+    let import_dir = project_export_dir(&project_path);
+    let actor = crate::config::default_actor();
+
+    if !JSONL_FILES.iter().any(|f| import_dir.join(f).exists()) {
+        return Err(Error::InvalidArgument(format!(
+            "No sync files in {}. Run `sc sync export` where the project lives and commit .savecontext/",
+            import_dir.display()
+        )));
+    }
+    if storage.get_project_by_path(&project_path)?.is_some() {
+        return Err(Error::InvalidArgument(format!(
+            "Project at {project_path} is already set up; use `sc sync import` to bring in new changes"
+        )));
+    }
+    let exported = crate::sync::exported_project(&import_dir).map_err(|e| Error::Other(e.to_string()))?;
+    let source = exported.as_ref().map(|e| e.project_path.clone()).filter(|source| *source != project_path);
+    if let Some(source) = &source {
+        if storage.get_project_counts(source)?.total() > 0 || storage.get_project_by_path(source)?.is_some() {
+            return Err(Error::InvalidArgument(format!(
+                "This database already has the project at {source}; run `sc sync import` there, \
+                 or combine the paths with `sc project merge-paths`"
+            )));
+        }
+    }
+    if crate::is_dry_run() {
+        println!("Would import {} into a new project at {project_path}", import_dir.display());
+        return Ok(());
+    }
+
+    let name = std::path::Path::new(&project_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Unknown Project")
+        .to_string();
+    // Plans point at the project row by ID, so it must exist (under the
+    // ID and path they were exported with) before they are imported.
+    let mut project = Project::new(source.clone().unwrap_or_else(|| project_path.clone()), name);
+    if let Some(id) = exported.and_then(|e| e.project_id) {
+        project.id = id;
+    }
+    let stats = storage.with_transaction(|storage| {
+        let result = storage.create_project(&project, &actor).and_then(|()| {
+            let stats = Importer::new(storage, MergeStrategy::PreferNewer)
+                .with_embedding_model(crate::embeddings::configured_embedding_model())
+                .with_events_since(crate::cli::commands::events::retention_cutoff())
+                .import_all(&import_dir)
+                .map_err(|e| Error::Other(e.to_string()))?;
+            if let Some(source) = &source {
+                storage.move_project_path(source, &project_path, &actor)?;
+            }
+            Ok(stats)
+        });
+        let ok = result.is_ok();
+        (result, ok)
+    })??;
+    project.project_path.clone_from(&project_path);
+
+    let embeddings_queued = crate::embeddings::is_embeddings_enabled() && stats.total_created() > 0;
+    if embeddings_queued {
+        super::embeddings::spawn_background_embedder();
+    }
+
+    if json {
+        let output = SyncCloneOutput {
+            success: true,
+            project,
+            import_dir: import_dir.display().to_string(),
+            moved_from: source,
+            stats,
+            embeddings_queued,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Cloned project: {} ({})", project.name, project.id);
+        println!("  Path: {project_path}");
+        if let Some(source) = &source {
+            println!("  Records exported under {source} now belong to this path");
+        }
+        println!();
+        print_entity_stats("Sessions", &stats.sessions);
+        print_entity_stats("Issues", &stats.issues);
+        print_entity_stats("Context Items", &stats.context_items);
+        print_entity_stats("Memories", &stats.memories);
+        print_entity_stats("Checkpoints", &stats.checkpoints);
+        print_entity_stats("Plans", &stats.plans);
+        print_entity_stats("Time Entries", &stats.time_entries);
+        println!();
+        println!("Total: {} records imported", stats.total_created());
+        print_embedding_stats(&stats.embeddings);
+        print_event_stats(&stats.events);
+        print_line_errors(&stats.errors);
+        if embeddings_queued {
+            println!("Embedding the imported records in the background");
+        }
+    }
+
+    Ok(())
+}
+
+fn verify(fix: bool, db: &mut Database, json: bool) -> Result<()> {
+    if !db.exists() {
+        return Err(Error::NotInitialized);
+    }
+
+    let project_path = get_project_path()?;
+    let storage = db.storage()?;
+    let export_dir = project_export_dir(&project_path);
+
+    let mut report = crate::sync::verify(storage, &export_dir, &project_path)
+        .map_err(|e| Error::Other(e.to_string()))?;
+    let mut fixed = 0;
+    if fix && report.fixable() > 0 && !crate::is_dry_run() {
+        fixed = crate::sync::repair(storage, &export_dir, &project_path, &report)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        report = crate::sync::verify(storage, &export_dir, &project_path)
+            .map_err(|e| Error::Other(e.to_string()))?;
+    }
+
+    if json {
+        let output = SyncVerifyOutput {
+            project: project_path,
+            export_dir: export_dir.display().to_string(),
+            ok: report.divergences.is_empty(),
+            fixed,
+            files_checked: report.files_checked,
+            records_checked: report.records_checked,
+            divergences: report.divergences,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!("Verifying export for: {project_path}");
+        println!(
+            "Checked {} records in {} files",
+            report.records_checked, report.files_checked
+        );
+        if fixed > 0 {
+            println!("Fixed {fixed} divergences");
+        }
+        if report.divergences.is_empty() {
+            println!("Export matches the database.");
+        } else {
+            println!();
+            for d in &report.divergences {
+                let at = d.line.map(|l| format!(":{l}")).unwrap_or_default();
+                let id = d.entity_id.as_deref().unwrap_or("-");
+                println!("  {}{at}  {}  {id}", d.file, d.kind.as_str());
+            }
+            println!();
+            let fixable = report.fixable();
+            if fixable > 0 && !fix {
+                println!("{fixable} can be re-exported with: sc sync verify --fix");
+            }
+            if fixable < report.divergences.len() {
+                println!("Orphans come from another machine; `sc sync import` applies them.");
+            }
+        }
+    }
+
+    Ok(())
+}
+
 // ── Push / Pull ─────────────────────────────────────────────
 
 /// JSONL files produced by sync export.
@@ -260,21 +718,18 @@ const JSONL_FILES: &[&str] = &[
 fn push(
     force: bool,
     remote_path: Option<&str>,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     json: bool,
 ) -> Result<()> {
     let config = load_remote_config()?;
 
     // Step 1: Run local export silently (directly via Exporter, no stdout)
-    let db = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db.exists() {
-        return Err(Error::NotInitialized);
-    }
+    let storage = db.storage()?;
     let project_path = get_project_path()?;
     let local_export_dir = project_export_dir(&project_path);
     {
-        let mut storage = SqliteStorage::open(&db)?;
-        let mut exporter = Exporter::new(&mut storage, project_path.clone());
+        let mut exporter =
+            Exporter::new(storage, project_path.clone()).with_events(crate::config::sync_events_enabled());
         // Ignore NothingToExport — we'll push whatever files exist
         match exporter.export(force) {
             Ok(stats) => {
@@ -310,11 +765,7 @@ fn push(
     let files_to_push = collect_jsonl_files(&local_export_dir);
     if files_to_push.is_empty() {
         if json {
-            let output = serde_json::json!({
-                "success": true,
-                "message": "No JSONL files to push",
-                "project": project_path,
-            });
+            let output = NothingToPushOutput { success: true, message: "No JSONL files to push", project: project_path };
             println!("{}", serde_json::to_string(&output)?);
         } else {
             println!("No JSONL files to push.");
@@ -336,12 +787,12 @@ fn push(
     ssh_exec(&config, &import_cmd)?;
 
     if json {
-        let output = serde_json::json!({
-            "success": true,
-            "files_pushed": files_to_push.len(),
-            "local_project": project_path,
-            "remote_project": remote_project,
-        });
+        let output = SyncPushOutput {
+            success: true,
+            files_pushed: files_to_push.len(),
+            local_project: project_path,
+            remote_project,
+        };
         println!("{}", serde_json::to_string(&output)?);
     } else {
         println!(
@@ -363,7 +814,7 @@ fn push(
 fn pull(
     force: bool,
     remote_path: Option<&str>,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     json: bool,
 ) -> Result<()> {
     let config = load_remote_config()?;
@@ -401,30 +852,20 @@ fn pull(
     scp_from_remote_glob(&remote_glob, &local_export_dir, &config)?;
 
     // Step 5: Run local import silently (directly via Importer, no stdout)
-    let db = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db.exists() {
-        return Err(Error::NotInitialized);
-    }
-    let strategy = if force {
-        MergeStrategy::PreferExternal
-    } else {
-        MergeStrategy::PreferNewer
-    };
+    let storage = db.storage()?;
+    let strategies = import_strategies(force, None)?;
     let import_stats = {
-        let mut storage = SqliteStorage::open(&db)?;
-        let mut importer = Importer::new(&mut storage, strategy);
+        let mut importer = Importer::new(storage, MergeStrategy::PreferNewer)
+            .with_strategies(strategies)
+            .with_embedding_model(crate::embeddings::configured_embedding_model())
+            .with_events_since(crate::cli::commands::events::retention_cutoff());
         importer
             .import_all(&local_export_dir)
             .map_err(|e| Error::Other(e.to_string()))?
     };
 
     if json {
-        let output = serde_json::json!({
-            "success": true,
-            "remote_project": remote_project,
-            "local_project": project_path,
-            "import_stats": import_stats,
-        });
+        let output = SyncPullOutput { success: true, remote_project, local_project: project_path, import_stats };
         println!("{}", serde_json::to_string(&output)?);
     } else {
         let total = import_stats.total_processed();
@@ -440,6 +881,7 @@ fn pull(
                 total - import_stats.total_created() - import_stats.total_updated()
             );
         }
+        print_line_errors(&import_stats.errors);
     }
 
     Ok(())
@@ -669,22 +1111,19 @@ fn resolve_remote_db(config: &RemoteConfig) -> String {
 ///
 /// Uses SQLite's Backup API to produce a consistent snapshot.
 /// Output defaults to `{db_path}.backup` if no path specified.
-fn backup(output: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db.exists() {
-        return Err(Error::NotInitialized);
-    }
+fn backup(output: Option<&str>, db: &mut Database, json: bool) -> Result<()> {
+    let source = db.require_path()?.to_path_buf();
+    let storage = db.storage()?;
 
     let dest = match output {
         Some(p) => PathBuf::from(p),
         None => {
-            let mut p = db.clone();
+            let mut p = source.clone();
             p.set_extension("db.backup");
             p
         }
     };
 
-    let storage = SqliteStorage::open(&db)?;
     storage.backup_to(&dest)?;
 
     let file_size = std::fs::metadata(&dest)
@@ -692,16 +1131,16 @@ fn backup(output: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result
         .unwrap_or(0);
 
     if json {
-        let out = serde_json::json!({
-            "success": true,
-            "source": db.display().to_string(),
-            "backup": dest.display().to_string(),
-            "size_bytes": file_size,
-        });
+        let out = SyncBackupOutput {
+            success: true,
+            source: source.display().to_string(),
+            backup: dest.display().to_string(),
+            size_bytes: file_size,
+        };
         println!("{}", serde_json::to_string(&out)?);
     } else {
         println!("Backup created: {}", dest.display());
-        println!("  Source: {}", db.display());
+        println!("  Source: {}", source.display());
         println!("  Size:   {:.2} MB", file_size as f64 / 1_048_576.0);
     }
 
@@ -717,21 +1156,16 @@ fn backup(output: Option<&str>, db_path: Option<&PathBuf>, json: bool) -> Result
 /// 4. SSH: timestamped backup of existing remote DB
 /// 5. SSH: atomic replace (mv + remove WAL/SHM)
 /// 6. Clean up local temp file
-fn push_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
+fn push_full(db: &mut Database, json: bool) -> Result<()> {
     let config = load_remote_config()?;
-    let db = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db.exists() {
-        return Err(Error::NotInitialized);
-    }
+    let source = db.require_path()?.to_path_buf();
+    let storage = db.storage()?;
 
     let remote_db = resolve_remote_db(&config);
 
     // Step 1: Create local backup
-    let temp_backup = db.with_extension("db.push-tmp");
-    let storage = SqliteStorage::open(&db)?;
+    let temp_backup = source.with_extension("db.push-tmp");
     storage.backup_to(&temp_backup)?;
-    // Drop storage to release the connection
-    drop(storage);
 
     let file_size = std::fs::metadata(&temp_backup)
         .map(|m| m.len())
@@ -811,14 +1245,14 @@ fn push_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     let _ = std::fs::remove_file(&temp_backup);
 
     if json {
-        let out = serde_json::json!({
-            "success": true,
-            "mode": "full",
-            "size_bytes": file_size,
-            "remote_db": remote_db,
-            "remote_host": format!("{}@{}:{}", config.user, config.host, config.port),
-            "pre_push_backup": pre_push_backup,
-        });
+        let out = FullPushOutput {
+            success: true,
+            mode: "full",
+            size_bytes: file_size,
+            remote_db,
+            remote_host: format!("{}@{}:{}", config.user, config.host, config.port),
+            pre_push_backup,
+        };
         println!("{}", serde_json::to_string(&out)?);
     } else {
         println!(
@@ -842,9 +1276,9 @@ fn push_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
 /// 3. Validate locally (integrity check)
 /// 4. Timestamped backup of existing local DB
 /// 5. Atomic replace (mv + remove WAL/SHM)
-fn pull_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
+fn pull_full(db: &mut Database, json: bool) -> Result<()> {
     let config = load_remote_config()?;
-    let db = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
+    let target = db.require_path()?.to_path_buf();
 
     let remote_db = resolve_remote_db(&config);
     let sc_path = config.remote_sc_path.as_deref().unwrap_or("sc");
@@ -864,7 +1298,7 @@ fn pull_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     ssh_exec(&config, &backup_cmd)?;
 
     // Step 2: SCP backup to local temp
-    let local_temp = db.with_extension("db.pull-tmp");
+    let local_temp = target.with_extension("db.pull-tmp");
     scp_from_remote(&backup_path, &local_temp, &config)?;
 
     let file_size = std::fs::metadata(&local_temp)
@@ -900,9 +1334,9 @@ fn pull_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
 
     // Step 4: Timestamped backup of existing local DB (if it exists)
     let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
-    let pre_pull_backup = db.with_extension(format!("db.pre-pull-{timestamp}"));
-    if db.exists() {
-        std::fs::copy(&db, &pre_pull_backup).map_err(|e| {
+    let pre_pull_backup = target.with_extension(format!("db.pre-pull-{timestamp}"));
+    if target.exists() {
+        std::fs::copy(&target, &pre_pull_backup).map_err(|e| {
             Error::Other(format!(
                 "Failed to create local backup at {}: {e}",
                 pre_pull_backup.display()
@@ -910,13 +1344,14 @@ fn pull_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
         })?;
     }
 
-    // Step 5: Atomic replace
-    std::fs::rename(&local_temp, &db).map_err(|e| {
+    // Step 5: Atomic replace, with our own connection closed first
+    db.close();
+    std::fs::rename(&local_temp, &target).map_err(|e| {
         Error::Other(format!("Failed to replace local database: {e}"))
     })?;
     // Remove stale WAL/SHM files
-    let wal = db.with_extension("db-wal");
-    let shm = db.with_extension("db-shm");
+    let wal = target.with_extension("db-wal");
+    let shm = target.with_extension("db-shm");
     let _ = std::fs::remove_file(&wal);
     let _ = std::fs::remove_file(&shm);
 
@@ -925,14 +1360,14 @@ fn pull_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
     let _ = ssh_exec(&config, &cleanup_cmd);
 
     if json {
-        let out = serde_json::json!({
-            "success": true,
-            "mode": "full",
-            "size_bytes": file_size,
-            "remote_db": remote_db,
-            "remote_host": format!("{}@{}:{}", config.user, config.host, config.port),
-            "local_backup": pre_pull_backup.display().to_string(),
-        });
+        let out = FullPullOutput {
+            success: true,
+            mode: "full",
+            size_bytes: file_size,
+            remote_db,
+            remote_host: format!("{}@{}:{}", config.user, config.host, config.port),
+            local_backup: pre_pull_backup.display().to_string(),
+        };
         println!("{}", serde_json::to_string(&out)?);
     } else {
         println!(
@@ -940,7 +1375,7 @@ fn pull_full(db_path: Option<&PathBuf>, json: bool) -> Result<()> {
             config.user,
             config.host,
             remote_db,
-            db.display()
+            target.display()
         );
         println!("  Size: {:.2} MB", file_size as f64 / 1_048_576.0);
         if pre_pull_backup.exists() {
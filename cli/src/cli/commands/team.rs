@@ -0,0 +1,294 @@
+//! Who is working on what (`sc team`).
+//!
+//! `sc team status` puts together, per actor in the current project:
+//!
+//! - the session it is working in, from agent presence (see
+//!   [`crate::storage::presence`]), or else the newest active session it
+//!   started;
+//! - the issues it has claimed, i.e. in progress and assigned to it;
+//! - when it was last active: its latest heartbeat or audit event.
+//!
+//! Names are resolved to canonical actors, so aliases count as one. An
+//! actor is listed if it was active within `--hours`, holds a claim, or
+//! started a session that is still active. Active sessions no one can be
+//! tied to (their start event was pruned) are listed apart.
+//!
+//! Every `sc` command run in a session is a heartbeat. `sc team heartbeat`
+//! sends one without doing anything else, for agents that go a while
+//! without running `sc`.
+
+use crate::cli::{Database, TeamCommands};
+use crate::config::{default_actor, resolve_project_path, resolve_session_or_suggest};
+use crate::error::Result;
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ActorType, IssueStatus, SessionStatus};
+use crate::storage::presence::CLI_PROVIDER;
+use crate::storage::{Session, SqliteStorage};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Output for team status.
+#[derive(Serialize)]
+struct TeamStatusOutput {
+    project_path: String,
+    members: Vec<Member>,
+    /// Active sessions not tied to any actor.
+    unattributed_sessions: Vec<SessionRef>,
+}
+
+#[derive(Serialize)]
+struct Member {
+    /// Canonical actor ID.
+    actor: String,
+    display_name: Option<String>,
+    /// `None` for an actor that isn't registered.
+    actor_type: Option<ActorType>,
+    /// `active` when last active within `--idle-minutes`, else `idle`.
+    state: String,
+    session: Option<SessionRef>,
+    /// Client of the latest heartbeat, e.g. `sc` or an MCP client.
+    provider: Option<String>,
+    claimed: Vec<ClaimedIssue>,
+    last_active_at: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct SessionRef {
+    id: String,
+    name: String,
+    status: SessionStatus,
+    branch: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ClaimedIssue {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+}
+
+/// Output for team heartbeat.
+#[derive(Serialize)]
+struct HeartbeatOutput {
+    actor: String,
+    session_id: String,
+    last_active_at: i64,
+}
+
+json_schema!(TeamStatusOutput { project_path: String, members: Vec<Member>, unattributed_sessions: Vec<SessionRef> });
+json_schema!(Member {
+    actor: String,
+    display_name: Option<String>,
+    actor_type: Option<ActorType>,
+    state: String,
+    session: Option<SessionRef>,
+    provider: Option<String>,
+    claimed: Vec<ClaimedIssue>,
+    last_active_at: Option<i64>,
+});
+json_schema!(SessionRef { id: String, name: String, status: SessionStatus, branch: Option<String> });
+json_schema!(ClaimedIssue { id: String, short_id: Option<String>, title: String });
+json_schema!(HeartbeatOutput { actor: String, session_id: String, last_active_at: i64 });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("team status", schema_for::<TeamStatusOutput>()), ("team heartbeat", schema_for::<HeartbeatOutput>())]
+}
+
+/// Execute a team command.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read, or for a heartbeat,
+/// if there is no session.
+pub fn execute(
+    command: &TeamCommands,
+    db: &mut Database,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+    match command {
+        TeamCommands::Status { hours, idle_minutes } => status(storage, *hours, *idle_minutes, json),
+        TeamCommands::Heartbeat => heartbeat(storage, actor, session_id, json),
+    }
+}
+
+fn heartbeat(storage: &SqliteStorage, actor: Option<&str>, session_id: Option<&str>, json: bool) -> Result<()> {
+    let session_id = resolve_session_or_suggest(session_id, storage)?;
+    let actor = storage.resolve_actor(&actor.map_or_else(default_actor, ToString::to_string))?;
+    if crate::is_dry_run() {
+        println!("Would record {actor} as active in {session_id}");
+        return Ok(());
+    }
+    storage.record_agent_presence(&actor, &session_id, CLI_PROVIDER)?;
+    if json {
+        let output = HeartbeatOutput { actor, session_id, last_active_at: chrono::Utc::now().timestamp_millis() };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if !crate::is_silent() {
+        println!("{actor} is active in {session_id}");
+    }
+    Ok(())
+}
+
+/// What is known about one actor while the status is put together.
+#[derive(Default)]
+struct Seen {
+    session: Option<String>,
+    provider: Option<String>,
+    last_active_at: Option<i64>,
+    recent: bool,
+    claimed: Vec<ClaimedIssue>,
+    started: Vec<String>,
+}
+
+fn status(storage: &SqliteStorage, hours: u32, idle_minutes: u32, json: bool) -> Result<()> {
+    let project_path = resolve_project_path(storage, None)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let since = now - i64::from(hours) * 3_600_000;
+
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut canonical = |name: &str| -> Result<String> {
+        if let Some(id) = names.get(name) {
+            return Ok(id.clone());
+        }
+        let id = storage.resolve_actor(name)?;
+        names.insert(name.to_string(), id.clone());
+        Ok(id)
+    };
+    let mut seen: BTreeMap<String, Seen> = BTreeMap::new();
+    let bump = |entry: &mut Seen, at: i64| {
+        entry.last_active_at = Some(entry.last_active_at.map_or(at, |last| last.max(at)));
+        entry.recent |= at >= since;
+    };
+
+    for presence in storage.get_agent_presence(&project_path, since)? {
+        let entry = seen.entry(canonical(&presence.agent_id)?).or_default();
+        // Rows come newest first; the first one per actor wins
+        if entry.session.is_none() {
+            entry.session = Some(presence.session_id);
+            entry.provider = presence.provider;
+        }
+        bump(entry, presence.last_active_at);
+    }
+
+    let sessions: HashMap<String, Session> =
+        storage.get_sessions_by_project(&project_path)?.into_iter().map(|s| (s.id.clone(), s)).collect();
+    let mut attributed = HashSet::new();
+    let mut last_event: HashMap<String, i64> = HashMap::new();
+    for event in storage.get_project_events(&project_path)? {
+        let actor = canonical(&event.actor)?;
+        if event.entity_type == "session" && event.event_type == "session_created" {
+            let active = sessions.get(&event.entity_id).is_some_and(|s| s.status == SessionStatus::Active);
+            if active {
+                attributed.insert(event.entity_id.clone());
+                seen.entry(actor.clone()).or_default().started.push(event.entity_id);
+            }
+        }
+        // Events come oldest first
+        last_event.insert(actor, event.created_at);
+    }
+
+    for issue in storage.get_issues_by_project(&project_path)? {
+        let Some(assignee) = issue.assigned_to_agent.as_deref().filter(|_| issue.status == IssueStatus::InProgress)
+        else {
+            continue;
+        };
+        let claimed = ClaimedIssue { id: issue.id, short_id: issue.short_id, title: issue.title };
+        seen.entry(canonical(assignee)?).or_default().claimed.push(claimed);
+    }
+    for (actor, at) in last_event {
+        if at >= since {
+            bump(seen.entry(actor).or_default(), at);
+        } else if let Some(entry) = seen.get_mut(&actor) {
+            bump(entry, at);
+        }
+    }
+
+    let actors: HashMap<String, crate::storage::Actor> =
+        storage.list_actors()?.into_iter().map(|a| (a.id.clone(), a)).collect();
+    let session_ref = |id: &str| {
+        sessions.get(id).map(|s| SessionRef {
+            id: s.id.clone(),
+            name: s.name.clone(),
+            status: s.status,
+            branch: s.branch.clone(),
+        })
+    };
+    let idle_since = now - i64::from(idle_minutes) * 60_000;
+    let mut members: Vec<Member> = seen
+        .into_iter()
+        .filter(|(_, seen)| seen.recent || !seen.claimed.is_empty() || !seen.started.is_empty())
+        .map(|(actor, seen)| {
+            // Sessions started later are newer
+            let session = seen.session.as_deref().or(seen.started.last().map(String::as_str)).and_then(session_ref);
+            let registered = actors.get(&actor);
+            Member {
+                display_name: registered.and_then(|a| a.display_name.clone()),
+                actor_type: registered.map(|a| a.actor_type),
+                state: if seen.last_active_at.is_some_and(|at| at >= idle_since) { "active" } else { "idle" }.to_string(),
+                session,
+                provider: seen.provider,
+                claimed: seen.claimed,
+                last_active_at: seen.last_active_at,
+                actor,
+            }
+        })
+        .collect();
+    members.sort_by_key(|member| std::cmp::Reverse(member.last_active_at));
+
+    let mut unattributed: Vec<SessionRef> = sessions
+        .values()
+        .filter(|s| s.status == SessionStatus::Active && !attributed.contains(&s.id))
+        .filter(|s| !members.iter().any(|m| m.session.as_ref().is_some_and(|session| session.id == s.id)))
+        .filter_map(|s| session_ref(&s.id))
+        .collect();
+    unattributed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let output = TeamStatusOutput { project_path, members, unattributed_sessions: unattributed };
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        print_status(&output);
+    }
+    Ok(())
+}
+
+fn print_status(output: &TeamStatusOutput) {
+    if output.members.is_empty() {
+        println!("No one has been active in {}", output.project_path);
+    }
+    for member in &output.members {
+        let marker = if member.state == "active" { "●" } else { "○" };
+        let name = member.display_name.as_deref().map_or_else(String::new, |name| format!(" ({name})"));
+        let seen = member.last_active_at.map_or_else(|| "never active".to_string(), |at| format!("last active {}", ago(at)));
+        println!("{marker} {}{name}  {} · {seen}", member.actor, member.state);
+        if let Some(session) = &member.session {
+            let via = member.provider.as_deref().map_or_else(String::new, |provider| format!(" via {provider}"));
+            println!("    session: {} [{}]{via}", session.name, session.status);
+        }
+        for issue in &member.claimed {
+            println!("    claimed: {} {}", issue.short_id.as_deref().unwrap_or(&issue.id), issue.title);
+        }
+    }
+    if !output.unattributed_sessions.is_empty() {
+        println!();
+        println!("Active sessions with no known actor:");
+        for session in &output.unattributed_sessions {
+            println!("    {} ({})", session.name, session.id);
+        }
+    }
+}
+
+/// `just now`, `5m ago`, `3h ago`, `2d ago`.
+fn ago(at: i64) -> String {
+    let minutes = (chrono::Utc::now().timestamp_millis() - at) / 60_000;
+    match minutes {
+        m if m < 1 => "just now".to_string(),
+        m if m >= 2 * 24 * 60 => format!("{}d ago", m / (24 * 60)),
+        m if m >= 60 => format!("{}h ago", m / 60),
+        m => format!("{m}m ago"),
+    }
+}
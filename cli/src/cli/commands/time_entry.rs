@@ -1,12 +1,13 @@
 //! Time entry command implementations.
 
-use crate::cli::{TimeCommands, TimeListArgs, TimeLogArgs, TimeUpdateArgs};
-use crate::config::{default_actor, resolve_db_path, resolve_project_path};
+use crate::cli::{Database, TimeCommands, TimeListArgs, TimeLogArgs, TimeUpdateArgs};
+use crate::config::{default_actor, resolve_project_path};
 use crate::error::{Error, Result};
-use crate::storage::SqliteStorage;
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::TimeEntryStatus;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::BTreeMap;
-use std::path::PathBuf;
 
 /// Output for time entry create.
 #[derive(Serialize)]
@@ -18,7 +19,7 @@ struct TimeLogOutput {
     work_date: String,
     period: Option<String>,
     issue_id: Option<String>,
-    status: String,
+    status: TimeEntryStatus,
 }
 
 /// Output for time entry list.
@@ -43,35 +44,111 @@ struct TimeInvoiceOutput {
     period: String,
     count: usize,
     total_hours: f64,
-    from_status: String,
-    to_status: String,
+    from_status: TimeEntryStatus,
+    to_status: TimeEntryStatus,
+}
+
+/// Output for time summary.
+#[derive(Serialize)]
+struct TimeSummaryOutput<'a> {
+    groups: Vec<TimeSummaryGroup<'a>>,
+    running_total: f64,
+}
+
+#[derive(Serialize)]
+struct TimeSummaryGroup<'a> {
+    key: &'a str,
+    entries: Vec<TimeSummaryEntry<'a>>,
+    subtotal: f64,
+}
+
+#[derive(Serialize)]
+struct TimeSummaryEntry<'a> {
+    /// Short ID, or the full ID when there is none.
+    id: &'a str,
+    hours: f64,
+    description: &'a str,
+    work_date: &'a str,
+    status: TimeEntryStatus,
+}
+
+/// Output for time update.
+#[derive(Serialize)]
+struct TimeUpdateOutput<'a> {
+    id: &'a str,
+    updated: bool,
+}
+
+/// Output for time delete.
+#[derive(Serialize)]
+struct TimeDeleteOutput<'a> {
+    id: &'a str,
+    deleted: bool,
+}
+
+json_schema!(TimeSummaryOutput<'a> { groups: Vec<TimeSummaryGroup<'a>>, running_total: f64 });
+json_schema!(TimeSummaryGroup<'a> { key: &'a str, entries: Vec<TimeSummaryEntry<'a>>, subtotal: f64 });
+json_schema!(TimeSummaryEntry<'a> { id: &'a str, hours: f64, description: &'a str, work_date: &'a str, status: TimeEntryStatus });
+json_schema!(TimeUpdateOutput<'a> { id: &'a str, updated: bool });
+json_schema!(TimeDeleteOutput<'a> { id: &'a str, deleted: bool });
+json_schema!(TimeLogOutput {
+    id: String,
+    short_id: Option<String>,
+    hours: f64,
+    description: String,
+    work_date: String,
+    period: Option<String>,
+    issue_id: Option<String>,
+    status: TimeEntryStatus,
+});
+json_schema!(TimeListOutput { entries: Vec<crate::storage::TimeEntry>, count: usize, total_hours: f64 });
+json_schema!(TimeTotalOutput { total_hours: f64, period: Option<String>, status: Option<String> });
+json_schema!(TimeInvoiceOutput {
+    period: String,
+    count: usize,
+    total_hours: f64,
+    from_status: TimeEntryStatus,
+    to_status: TimeEntryStatus,
+});
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("time log", schema_for::<TimeLogOutput>()),
+        ("time list", schema_for::<TimeListOutput>()),
+        ("time total", schema_for::<TimeTotalOutput>()),
+        ("time invoice", schema_for::<TimeInvoiceOutput>()),
+        ("time summary", schema_for::<TimeSummaryOutput<'_>>()),
+        ("time update", schema_for::<TimeUpdateOutput<'_>>()),
+        ("time delete", schema_for::<TimeDeleteOutput<'_>>()),
+    ]
 }
 
 /// Execute time commands.
 pub fn execute(
     command: &TimeCommands,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
     match command {
-        TimeCommands::Log(args) => log(args, db_path, actor, json),
-        TimeCommands::List(args) => list(args, db_path, json),
+        TimeCommands::Log(args) => log(args, db, actor, json),
+        TimeCommands::List(args) => list(args, db, json),
         TimeCommands::Summary { period, group_by, status } => {
-            summary(period.as_deref(), group_by, status.as_deref(), db_path, json)
+            summary(period.as_deref(), group_by, status.as_deref(), db, json)
         }
         TimeCommands::Total { period, status } => {
-            total(period.as_deref(), status.as_deref(), db_path, json)
+            total(period.as_deref(), status.as_deref(), db, json)
         }
-        TimeCommands::Update(args) => update(args, db_path, actor, json),
-        TimeCommands::Delete { id } => delete(id, db_path, actor, json),
-        TimeCommands::Invoice { period, from } => invoice(period, from, db_path, actor, json),
+        TimeCommands::Update(args) => update(args, db, actor, json),
+        TimeCommands::Delete { id } => delete(id, db, actor, json),
+        TimeCommands::Invoice { period, from } => invoice(period, from, db, actor, json),
     }
 }
 
 fn log(
     args: &TimeLogArgs,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
@@ -121,14 +198,13 @@ fn log(
         return Ok(());
     }
 
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     // Resolve issue short ID to full ID if provided
     let issue_id = if let Some(ref issue_ref) = args.issue {
@@ -172,7 +248,7 @@ fn log(
             work_date,
             period: args.period.clone(),
             issue_id,
-            status: "logged".to_string(),
+            status: TimeEntryStatus::Logged,
         };
         println!("{}", serde_json::to_string(&output)?);
     } else {
@@ -199,14 +275,9 @@ fn log(
     Ok(())
 }
 
-fn list(args: &TimeListArgs, db_path: Option<&PathBuf>, json: bool) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+fn list(args: &TimeListArgs, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     let entries = storage.list_time_entries(
         &project_path,
@@ -252,10 +323,10 @@ fn list(args: &TimeListArgs, db_path: Option<&PathBuf>, json: bool) -> Result<()
         println!();
         for e in &entries {
             let short = e.short_id.as_deref().unwrap_or(&e.id[..8]);
-            let status_char = match e.status.as_str() {
-                "reviewed" => '*',
-                "invoiced" => '$',
-                _ => ' ',
+            let status_char = match e.status {
+                TimeEntryStatus::Logged => ' ',
+                TimeEntryStatus::Reviewed => '*',
+                TimeEntryStatus::Invoiced => '$',
             };
             let period_str = e
                 .period
@@ -278,16 +349,11 @@ fn summary(
     period: Option<&str>,
     group_by: &str,
     status: Option<&str>,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     let entries = storage.list_time_entries(
         &project_path,
@@ -301,7 +367,8 @@ fn summary(
 
     if entries.is_empty() {
         if json {
-            println!("{{\"groups\":[],\"running_total\":0}}");
+            let output = TimeSummaryOutput { groups: Vec::new(), running_total: 0.0 };
+            println!("{}", serde_json::to_string(&output)?);
         } else {
             println!("No time entries found.");
         }
@@ -314,7 +381,7 @@ fn summary(
         let key = match group_by {
             "date" => e.work_date.clone(),
             "issue" => e.issue_id.clone().unwrap_or_else(|| "(no issue)".to_string()),
-            "status" => e.status.clone(),
+            "status" => e.status.to_string(),
             _ => e.period.clone().unwrap_or_else(|| "(no period)".to_string()),
         };
         groups.entry(key).or_default().push(e);
@@ -326,37 +393,28 @@ fn summary(
         let mut json_groups = Vec::new();
         for (key, items) in &groups {
             let subtotal: f64 = items.iter().map(|e| e.hours).sum();
-            let entries_json: Vec<serde_json::Value> = items
+            let entries = items
                 .iter()
-                .map(|e| {
-                    serde_json::json!({
-                        "id": e.short_id.as_deref().unwrap_or(&e.id),
-                        "hours": e.hours,
-                        "description": e.description,
-                        "work_date": e.work_date,
-                        "status": e.status,
-                    })
+                .map(|e| TimeSummaryEntry {
+                    id: e.short_id.as_deref().unwrap_or(&e.id),
+                    hours: e.hours,
+                    description: &e.description,
+                    work_date: &e.work_date,
+                    status: e.status,
                 })
                 .collect();
-            json_groups.push(serde_json::json!({
-                "key": key,
-                "entries": entries_json,
-                "subtotal": subtotal,
-            }));
+            json_groups.push(TimeSummaryGroup { key, entries, subtotal });
         }
-        let output = serde_json::json!({
-            "groups": json_groups,
-            "running_total": running_total,
-        });
+        let output = TimeSummaryOutput { groups: json_groups, running_total };
         println!("{}", serde_json::to_string(&output)?);
     } else {
         for (key, items) in &groups {
             println!("{key}:");
             for e in items {
-                let status_suffix = match e.status.as_str() {
-                    "invoiced" => ", INVOICED",
-                    "reviewed" => ", REVIEWED",
-                    _ => "",
+                let status_suffix = match e.status {
+                    TimeEntryStatus::Logged => "",
+                    TimeEntryStatus::Reviewed => ", REVIEWED",
+                    TimeEntryStatus::Invoiced => ", INVOICED",
                 };
                 println!(
                     "  - {}: {:.1}hrs{}",
@@ -376,16 +434,11 @@ fn summary(
 fn total(
     period: Option<&str>,
     status: Option<&str>,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     json: bool,
 ) -> Result<()> {
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db_path.exists() {
-        return Err(Error::NotInitialized);
-    }
-
-    let storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     let total_hours = storage.get_time_total(&project_path, period, status)?;
 
@@ -411,7 +464,7 @@ fn total(
 
 fn update(
     args: &TimeUpdateArgs,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
@@ -439,9 +492,7 @@ fn update(
         validate_date(d)?;
     }
 
-    if let Some(ref s) = args.status {
-        validate_status(s)?;
-    }
+    let status: Option<TimeEntryStatus> = args.status.as_deref().map(str::parse).transpose()?;
 
     if crate::is_dry_run() {
         if json {
@@ -463,56 +514,56 @@ fn update(
         return Ok(());
     }
 
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
-
-    // Handle status update separately
-    if let Some(ref status) = args.status {
-        storage.update_time_entry_status(&args.id, &project_path, status, &actor)?;
-    }
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+
+    // Status and fields change together, or neither does
+    storage.atomically(|storage| {
+        // Handle status update separately
+        if let Some(status) = status {
+            storage.update_time_entry_status(&args.id, &project_path, status, &actor)?;
+        }
 
-    // Handle field updates
-    if args.hours.is_some()
-        || args.description.is_some()
-        || args.period.is_some()
-        || args.issue.is_some()
-        || args.date.is_some()
-    {
-        // Resolve issue short ID if provided
-        let issue_id = if let Some(ref issue_ref) = args.issue {
-            let issue = storage
-                .get_issue(issue_ref, Some(&project_path))?
-                .ok_or_else(|| Error::IssueNotFound {
-                    id: issue_ref.clone(),
-                })?;
-            Some(issue.id)
-        } else {
-            None
-        };
+        // Handle field updates
+        if args.hours.is_some()
+            || args.description.is_some()
+            || args.period.is_some()
+            || args.issue.is_some()
+            || args.date.is_some()
+        {
+            // Resolve issue short ID if provided
+            let issue_id = if let Some(ref issue_ref) = args.issue {
+                let issue = storage
+                    .get_issue(issue_ref, Some(&project_path))?
+                    .ok_or_else(|| Error::IssueNotFound {
+                        id: issue_ref.clone(),
+                    })?;
+                Some(issue.id)
+            } else {
+                None
+            };
 
-        storage.update_time_entry(
-            &args.id,
-            &project_path,
-            args.hours,
-            args.description.as_deref(),
-            args.period.as_deref(),
-            issue_id.as_deref(),
-            args.date.as_deref(),
-            &actor,
-        )?;
-    }
+            storage.update_time_entry(
+                &args.id,
+                &project_path,
+                args.hours,
+                args.description.as_deref(),
+                args.period.as_deref(),
+                issue_id.as_deref(),
+                args.date.as_deref(),
+                &actor,
+            )?;
+        }
+        Ok(())
+    })?;
 
     if json {
-        let output = serde_json::json!({
-            "id": args.id,
-            "updated": true,
-        });
+        let output = TimeUpdateOutput { id: &args.id, updated: true };
         println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Updated time entry: {}", args.id);
@@ -521,7 +572,7 @@ fn update(
     Ok(())
 }
 
-fn delete(id: &str, db_path: Option<&PathBuf>, actor: Option<&str>, json: bool) -> Result<()> {
+fn delete(id: &str, db: &mut Database, actor: Option<&str>, json: bool) -> Result<()> {
     if crate::is_dry_run() {
         if json {
             let output = serde_json::json!({
@@ -536,22 +587,18 @@ fn delete(id: &str, db_path: Option<&PathBuf>, actor: Option<&str>, json: bool)
         return Ok(());
     }
 
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     storage.delete_time_entry(id, &project_path, &actor)?;
 
     if json {
-        let output = serde_json::json!({
-            "id": id,
-            "deleted": true,
-        });
+        let output = TimeDeleteOutput { id, deleted: true };
         println!("{}", serde_json::to_string(&output)?);
     } else {
         println!("Deleted time entry: {id}");
@@ -563,10 +610,11 @@ fn delete(id: &str, db_path: Option<&PathBuf>, actor: Option<&str>, json: bool)
 fn invoice(
     period: &str,
     from: &str,
-    db_path: Option<&PathBuf>,
+    db: &mut Database,
     actor: Option<&str>,
     json: bool,
 ) -> Result<()> {
+    let from: TimeEntryStatus = from.parse()?;
     if crate::is_dry_run() {
         if json {
             let output = serde_json::json!({
@@ -585,25 +633,24 @@ fn invoice(
         return Ok(());
     }
 
-    let db_path = resolve_db_path(db_path.map(|p| p.as_path())).ok_or(Error::NotInitialized)?;
-    if !db_path.exists() {
+    if !db.exists() {
         return Err(Error::NotInitialized);
     }
 
     let actor = actor.map(ToString::to_string).unwrap_or_else(default_actor);
-    let mut storage = SqliteStorage::open(&db_path)?;
-    let project_path = resolve_project_path(&storage, None)?;
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
 
     let (count, total_hours) =
-        storage.invoice_time_entries(&project_path, period, from, "invoiced", &actor)?;
+        storage.invoice_time_entries(&project_path, period, from, TimeEntryStatus::Invoiced, &actor)?;
 
     if json {
         let output = TimeInvoiceOutput {
             period: period.to_string(),
             count,
             total_hours,
-            from_status: from.to_string(),
-            to_status: "invoiced".to_string(),
+            from_status: from,
+            to_status: TimeEntryStatus::Invoiced,
         };
         println!("{}", serde_json::to_string(&output)?);
     } else if count == 0 {
@@ -621,7 +668,7 @@ fn invoice(
 // Helpers
 // ==================
 
-fn validate_date(date: &str) -> Result<()> {
+pub(crate) fn validate_date(date: &str) -> Result<()> {
     if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
         return Err(Error::InvalidArgument(format!(
             "Invalid date format: '{date}'. Expected YYYY-MM-DD"
@@ -630,16 +677,7 @@ fn validate_date(date: &str) -> Result<()> {
     Ok(())
 }
 
-fn validate_status(status: &str) -> Result<()> {
-    match status {
-        "logged" | "reviewed" | "invoiced" => Ok(()),
-        _ => Err(Error::InvalidArgument(format!(
-            "Invalid status: '{status}'. Expected: logged, reviewed, invoiced"
-        ))),
-    }
-}
-
-fn generate_short_id() -> String {
+pub(crate) fn generate_short_id() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -0,0 +1,201 @@
+//! Trash commands (`sc trash ...`).
+//!
+//! Deleted context items, issues and sessions stay in the trash for
+//! `trash_retention_days` (30 by default) and can be brought back with
+//! `sc trash restore`. Expired entries are purged whenever the trash is
+//! used and after each delete.
+
+use crate::cli::{Database, TrashCommands};
+use crate::config::{default_actor, trash_retention_days};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::storage::{SqliteStorage, TrashEntry, TrashRestore};
+use serde::Serialize;
+use serde_json::Value;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Output for trash list.
+#[derive(Serialize)]
+struct TrashListOutput {
+    entries: Vec<TrashEntry>,
+    count: usize,
+    /// Days entries are kept (0 keeps them until emptied).
+    retention_days: u64,
+}
+
+/// Output for trash restore.
+#[derive(Serialize)]
+struct RestoreOutput {
+    restored: TrashRestore,
+    dry_run: bool,
+}
+
+/// Output for trash empty.
+#[derive(Serialize)]
+struct EmptyOutput {
+    /// Entries permanently deleted.
+    purged: usize,
+    dry_run: bool,
+}
+
+json_schema!(TrashListOutput { entries: Vec<TrashEntry>, count: usize, retention_days: u64 });
+json_schema!(RestoreOutput { restored: TrashRestore, dry_run: bool });
+json_schema!(EmptyOutput { purged: usize, dry_run: bool });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("trash list", schema_for::<TrashListOutput>()),
+        ("trash restore", schema_for::<RestoreOutput>()),
+        ("trash empty", schema_for::<EmptyOutput>()),
+    ]
+}
+
+/// Execute a trash subcommand.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or the entry cannot
+/// be restored.
+pub fn execute(
+    command: &TrashCommands,
+    db: &mut Database,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let storage = db.storage()?;
+
+    match command {
+        TrashCommands::List { entity_type, limit } => {
+            purge_expired(storage)?;
+            list(storage, entity_type.as_deref(), *limit, json)
+        }
+        TrashCommands::Restore { id } => {
+            purge_expired(storage)?;
+            let actor = actor.map_or_else(default_actor, ToString::to_string);
+            restore(storage, id, &actor, json)
+        }
+        TrashCommands::Empty { all, older_than } => empty(storage, *all, *older_than, json),
+    }
+}
+
+/// Purge entries older than the retention period. Does nothing on
+/// `--dry-run` or when retention is `0`.
+///
+/// # Errors
+///
+/// Returns an error if the delete fails.
+pub fn purge_expired(storage: &mut SqliteStorage) -> Result<usize> {
+    match trash_retention_days() {
+        0 => Ok(0),
+        _ if crate::is_dry_run() => Ok(0),
+        days => storage.empty_trash(Some(cutoff(days))),
+    }
+}
+
+fn cutoff(days: u64) -> i64 {
+    let days = i64::try_from(days).unwrap_or(i64::MAX / DAY_MS);
+    chrono::Utc::now().timestamp_millis() - days.saturating_mul(DAY_MS)
+}
+
+fn format_timestamp(ts: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ts)
+        .map_or_else(|| ts.to_string(), |dt| dt.format("%Y-%m-%d %H:%M").to_string())
+}
+
+fn list(storage: &SqliteStorage, entity_type: Option<&str>, limit: usize, json: bool) -> Result<()> {
+    let entries = storage.list_trash(entity_type, limit)?;
+    let retention = trash_retention_days();
+
+    if json {
+        let output = TrashListOutput { count: entries.len(), entries, retention_days: retention };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("Trash is empty.");
+        return Ok(());
+    }
+
+    println!("Trash ({}):", entries.len());
+    for entry in &entries {
+        println!(
+            "  {}  {:<12} {}  ({} rows, deleted {} by {})",
+            entry.id,
+            entry.entity_type,
+            entry.label,
+            entry.rows,
+            format_timestamp(entry.deleted_at),
+            entry.deleted_by
+        );
+    }
+    println!();
+    if retention == 0 {
+        println!("Entries are kept until `sc trash empty`.");
+    } else {
+        println!("Entries are purged after {retention} days. Restore with `sc trash restore <id>`.");
+    }
+    Ok(())
+}
+
+fn restore(storage: &mut SqliteStorage, id: &str, actor: &str, json: bool) -> Result<()> {
+    let dry_run = crate::is_dry_run();
+    let restored = storage.with_transaction(|s| {
+        let result = s.restore_trash(id, actor);
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+
+    if json {
+        let output = RestoreOutput { restored, dry_run };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would restore" } else { "Restored" };
+    println!(
+        "{verb} {} {} ({} rows)",
+        restored.entry.entity_type, restored.entry.label, restored.restored_rows
+    );
+    if restored.skipped_rows > 0 {
+        println!(
+            "  Skipped {} related rows that conflict with current data or point at deleted records.",
+            restored.skipped_rows
+        );
+    }
+    Ok(())
+}
+
+fn empty(storage: &mut SqliteStorage, all: bool, older_than: Option<u64>, json: bool) -> Result<()> {
+    let before = match (all, older_than) {
+        (true, _) => None,
+        (false, Some(days)) => Some(cutoff(days)),
+        (false, None) => match trash_retention_days() {
+            0 => {
+                return Err(Error::InvalidArgument(
+                    "Retention is 0 (keep forever); pass --all or --older-than <days>".to_string(),
+                ));
+            }
+            days => Some(cutoff(days)),
+        },
+    };
+
+    let dry_run = crate::is_dry_run();
+    let purged = storage.with_transaction(|s| {
+        let result = s.empty_trash(before);
+        let commit = result.is_ok() && !dry_run;
+        (result, commit)
+    })??;
+
+    if json {
+        let output = EmptyOutput { purged, dry_run };
+        println!("{}", serde_json::to_string(&output)?);
+    } else if dry_run {
+        println!("Would permanently delete {purged} trash entries.");
+    } else {
+        println!("Permanently deleted {purged} trash entries.");
+    }
+    Ok(())
+}
@@ -0,0 +1,597 @@
+//! Markdown vault sync (`sc export vault`, `sc import vault`).
+//!
+//! Writes project memory and decision items as one markdown note each, so
+//! people can read and curate agent memory in Obsidian or any other note
+//! tool, then pulls their edits back:
+//!
+//! ```text
+//! <dir>/memory/<key>.md
+//! <dir>/decisions/<key>.md
+//! ```
+//!
+//! Each note starts with YAML frontmatter:
+//!
+//! ```markdown
+//! ---
+//! id: mem_1a2b3c4d5e6f
+//! key: api-base-url
+//! category: config
+//! tags: [memory, config]
+//! updated_at: 2026-10-16T09:30:00.000Z
+//! ---
+//! https://api.example.com/v2
+//! ```
+//!
+//! On import, the body replaces the stored value and, for decisions, the
+//! `tags` list replaces the item's tags (the `decision` tag is implied).
+//! Memory takes its category from `category`. A note is skipped as a
+//! conflict when both it and the database changed since the export,
+//! unless `--force`. Notes without an `id` match by key (or file name) and
+//! are otherwise created: memory in the project, decisions in the current
+//! session. Deleting a note does not delete anything.
+
+use crate::cli::{Database, ExportCommands, ImportCommands};
+use crate::config::{default_actor, resolve_project_path, resolve_session_or_suggest};
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, schema_for};
+use crate::model::{ItemCategory, MemoryCategory};
+use crate::storage::{ContextItem, Memory, SqliteStorage};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const MEMORY_DIR: &str = "memory";
+const DECISIONS_DIR: &str = "decisions";
+
+/// Output for export vault.
+#[derive(Serialize)]
+struct ExportOutput<'a> {
+    dir: &'a Path,
+    /// Memory notes written.
+    memory: usize,
+    /// Decision notes written.
+    decisions: usize,
+}
+
+/// Output for import vault.
+#[derive(Serialize)]
+struct ImportOutput<'a> {
+    dry_run: bool,
+    updated: usize,
+    created: usize,
+    unchanged: usize,
+    conflicts: usize,
+    notes: &'a [NoteResult],
+    warnings: &'a [String],
+}
+
+json_schema!(ExportOutput<'a> { dir: &'a Path, memory: usize, decisions: usize });
+json_schema!(ImportOutput<'a> {
+    dry_run: bool,
+    updated: usize,
+    created: usize,
+    unchanged: usize,
+    conflicts: usize,
+    notes: &'a [NoteResult],
+    warnings: &'a [String],
+});
+json_schema!(NoteResult { file: String, key: String, action: &'static str });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("export vault", schema_for::<ExportOutput<'_>>()),
+        ("import vault", schema_for::<ImportOutput<'_>>()),
+    ]
+}
+
+/// Execute an export subcommand.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read or the notes cannot be written.
+pub fn execute_export(command: &ExportCommands, db: &mut Database, json: bool) -> Result<()> {
+    match command {
+        ExportCommands::Vault { dir } => export(dir, db, json),
+        ExportCommands::Archive { out } => super::archive::export(out.as_deref(), db, json),
+        ExportCommands::Graph { to, output } => super::graph::export(*to, output.as_ref(), db, json),
+        ExportCommands::Dashboard { out, from, days } => {
+            super::dashboard::export(out, from.as_deref(), *days, db, json)
+        }
+    }
+}
+
+/// Execute an import subcommand.
+///
+/// # Errors
+///
+/// Returns an error if the notes cannot be read or the database update fails.
+pub fn execute_import(
+    command: &ImportCommands,
+    db: &mut Database,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    match command {
+        ImportCommands::Vault { dir, force } => import(dir, *force, db, actor, session_id, json),
+        ImportCommands::Archive { file, force } => super::archive::import(file, *force, db, json),
+    }
+}
+
+fn export(dir: &Path, db: &mut Database, json: bool) -> Result<()> {
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let memory = storage.get_memory_by_project(&project_path)?;
+    let decisions: Vec<ContextItem> = storage
+        .get_context_items_by_project(&project_path)?
+        .into_iter()
+        .filter(|item| item.category == ItemCategory::Decision)
+        .collect();
+
+    if crate::is_dry_run() {
+        println!(
+            "Would write {} memory and {} decision notes to {}",
+            memory.len(),
+            decisions.len(),
+            dir.display()
+        );
+        return Ok(());
+    }
+
+    let mut used = HashSet::new();
+    std::fs::create_dir_all(dir.join(MEMORY_DIR))?;
+    for item in &memory {
+        let path = dir.join(MEMORY_DIR).join(file_name(&item.key, &item.id, &mut used));
+        std::fs::write(path, render_memory(item))?;
+    }
+    std::fs::create_dir_all(dir.join(DECISIONS_DIR))?;
+    for item in &decisions {
+        let path = dir.join(DECISIONS_DIR).join(file_name(&item.key, &item.id, &mut used));
+        std::fs::write(path, render_decision(item))?;
+    }
+
+    if json {
+        let output = ExportOutput { dir, memory: memory.len(), decisions: decisions.len() };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!(
+            "Exported {} memory and {} decision notes to {}",
+            memory.len(),
+            decisions.len(),
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// What importing one note did (or would do).
+#[derive(Debug, Serialize)]
+struct NoteResult {
+    file: String,
+    key: String,
+    action: &'static str,
+}
+
+fn import(
+    dir: &Path,
+    force: bool,
+    db: &mut Database,
+    actor: Option<&str>,
+    session_id: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(Error::InvalidArgument(format!("Not a directory: {}", dir.display())));
+    }
+    let storage = db.storage()?;
+    let project_path = resolve_project_path(storage, None)?;
+    let actor = actor.map_or_else(default_actor, ToString::to_string);
+    let dry_run = crate::is_dry_run();
+
+    let mut results = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (path, note) in read_notes(&dir.join(MEMORY_DIR), &mut warnings)? {
+        let file = path.display().to_string();
+        let key = note.key.clone().unwrap_or_else(|| file_stem(&path));
+        let existing = match note.id.as_deref() {
+            Some(id) => storage
+                .get_memory_by_project(&project_path)?
+                .into_iter()
+                .find(|m| m.id == id),
+            None => storage.get_memory(&project_path, &key)?,
+        };
+        let category = match note.category.as_deref().map(str::parse::<MemoryCategory>).transpose() {
+            Ok(category) => category,
+            Err(e) => {
+                warnings.push(format!("{file}: {e}; category left unchanged"));
+                None
+            }
+        };
+        let action = if let Some(memory) = existing {
+            let category = category.unwrap_or(memory.category);
+            if same_text(&memory.value, &note.body) && category == memory.category {
+                "unchanged"
+            } else if !force && changed_since(memory.updated_at, note.updated_at) {
+                "conflict"
+            } else {
+                if !dry_run {
+                    storage.save_memory(&memory.id, &project_path, &memory.key, &note.body, category, None, &actor)?;
+                }
+                "updated"
+            }
+        } else {
+            if !dry_run {
+                let id = format!("mem_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+                let category = category.unwrap_or(MemoryCategory::Note);
+                storage.save_memory(&id, &project_path, &key, &note.body, category, None, &actor)?;
+            }
+            "created"
+        };
+        results.push(NoteResult { file, key, action });
+    }
+
+    let mut new_session: Option<Result<String>> = None;
+    for (path, note) in read_notes(&dir.join(DECISIONS_DIR), &mut warnings)? {
+        let file = path.display().to_string();
+        let existing = if let Some(id) = note.id.as_deref() {
+            storage.get_context_item(id)?
+        } else {
+            let key = note.key.clone().unwrap_or_else(|| file_stem(&path));
+            storage
+                .get_context_items_by_project(&project_path)?
+                .into_iter()
+                .find(|item| item.category == ItemCategory::Decision && item.key == key)
+        };
+        let tags: Vec<String> = note.tags.iter().filter(|t| *t != "decision").cloned().collect();
+        let (key, action) = if let Some(item) = existing {
+            let current_tags = item_tags(&item);
+            let action = if same_text(&item.value, &note.body) && current_tags == tags {
+                "unchanged"
+            } else if !force && changed_since(item.updated_at, note.updated_at) {
+                "conflict"
+            } else {
+                if !dry_run {
+                    update_decision(storage, &item, &note.body, &current_tags, &tags, &actor)?;
+                }
+                "updated"
+            };
+            (item.key, action)
+        } else {
+            let key = note.key.clone().unwrap_or_else(|| file_stem(&path));
+            match new_session.get_or_insert_with(|| resolve_session_or_suggest(session_id, storage)) {
+                Ok(session) => {
+                    if !dry_run {
+                        let id = format!("item_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+                        storage.atomically(|storage| {
+                            storage.save_context_item(&id, session, &key, &note.body, Some(ItemCategory::Decision), None, &actor)?;
+                            if !tags.is_empty() {
+                                storage.add_tags_to_item(session, &key, &tags, &actor)?;
+                            }
+                            Ok(())
+                        })?;
+                    }
+                    (key, "created")
+                }
+                Err(e) => {
+                    warnings.push(format!("{file}: new decision skipped ({e})"));
+                    (key, "skipped")
+                }
+            }
+        };
+        results.push(NoteResult { file, key, action });
+    }
+
+    print_import_results(&results, &warnings, dry_run, json)
+}
+
+fn print_import_results(results: &[NoteResult], warnings: &[String], dry_run: bool, json: bool) -> Result<()> {
+    let count = |action: &str| results.iter().filter(|r| r.action == action).count();
+
+    if json {
+        let output = ImportOutput {
+            dry_run,
+            updated: count("updated"),
+            created: count("created"),
+            unchanged: count("unchanged"),
+            conflicts: count("conflict"),
+            notes: results,
+            warnings,
+        };
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    let prefix = if dry_run { "Would import" } else { "Imported" };
+    println!(
+        "{prefix}: {} updated, {} created, {} unchanged, {} conflict(s)",
+        count("updated"),
+        count("created"),
+        count("unchanged"),
+        count("conflict")
+    );
+    for r in results.iter().filter(|r| r.action != "unchanged") {
+        println!("  {:<9} {} ({})", r.action, r.key, r.file);
+    }
+    if count("conflict") > 0 {
+        println!("Conflicting notes changed in both places since the export; re-export, or use --force to keep the note.");
+    }
+    for warning in warnings {
+        println!("Warning: {warning}");
+    }
+    Ok(())
+}
+
+fn update_decision(
+    storage: &mut SqliteStorage,
+    item: &ContextItem,
+    body: &str,
+    current_tags: &[String],
+    tags: &[String],
+    actor: &str,
+) -> Result<()> {
+    let removed: Vec<String> = current_tags.iter().filter(|t| !tags.contains(t)).cloned().collect();
+    let added: Vec<String> = tags.iter().filter(|t| !current_tags.contains(t)).cloned().collect();
+    storage.atomically(|storage| {
+        if !same_text(&item.value, body) {
+            storage.update_context_item(&item.session_id, &item.key, Some(body), None, None, None, actor)?;
+        }
+        if !removed.is_empty() {
+            storage.remove_tags_from_item(&item.session_id, &item.key, &removed, actor)?;
+        }
+        if !added.is_empty() {
+            storage.add_tags_to_item(&item.session_id, &item.key, &added, actor)?;
+        }
+        Ok(())
+    })
+}
+
+/// Markdown notes in `dir`, parsed. Unreadable notes become warnings.
+fn read_notes(dir: &Path, warnings: &mut Vec<String>) -> Result<Vec<(PathBuf, Note)>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+
+    let mut notes = Vec::with_capacity(paths.len());
+    for path in paths {
+        let content = std::fs::read_to_string(&path)?;
+        match parse_note(&content) {
+            Ok(note) => notes.push((path, note)),
+            Err(e) => warnings.push(format!("{}: {e}", path.display())),
+        }
+    }
+    Ok(notes)
+}
+
+/// Tags of a context item, stored as a JSON array.
+pub(crate) fn item_tags(item: &ContextItem) -> Vec<String> {
+    item.tags
+        .as_deref()
+        .and_then(|t| serde_json::from_str(t).ok())
+        .unwrap_or_default()
+}
+
+/// Whether the database copy changed after the note was exported.
+fn changed_since(db_updated_at: i64, note_updated_at: Option<i64>) -> bool {
+    note_updated_at.is_some_and(|exported| db_updated_at > exported)
+}
+
+fn same_text(a: &str, b: &str) -> bool {
+    a.trim_end() == b.trim_end()
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// A note file name for `key`, unique within one export.
+fn file_name(key: &str, id: &str, used: &mut HashSet<String>) -> String {
+    let mut stem: String = key
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ') { c } else { '-' })
+        .collect::<String>()
+        .trim_matches(['.', ' '])
+        .to_string();
+    if stem.is_empty() {
+        stem = id.to_string();
+    }
+    if !used.insert(stem.to_lowercase()) {
+        stem = format!("{stem}-{}", id.rsplit('_').next().unwrap_or(id));
+        used.insert(stem.to_lowercase());
+    }
+    format!("{stem}.md")
+}
+
+// ── Note format ──────────────────────────────────────────────
+
+/// A parsed note.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Note {
+    id: Option<String>,
+    key: Option<String>,
+    category: Option<String>,
+    tags: Vec<String>,
+    updated_at: Option<i64>,
+    body: String,
+}
+
+fn render_memory(memory: &Memory) -> String {
+    let tags = ["memory".to_string(), memory.category.to_string()];
+    render_note(&memory.id, &memory.key, Some(memory.category.as_str()), &tags, memory.updated_at, &memory.value)
+}
+
+fn render_decision(item: &ContextItem) -> String {
+    let mut tags = vec!["decision".to_string()];
+    tags.extend(item_tags(item));
+    render_note(&item.id, &item.key, None, &tags, item.updated_at, &item.value)
+}
+
+fn render_note(
+    id: &str,
+    key: &str,
+    category: Option<&str>,
+    tags: &[String],
+    updated_at: i64,
+    body: &str,
+) -> String {
+    let mut out = String::from("---\n");
+    let _ = writeln!(out, "id: {id}");
+    let _ = writeln!(out, "key: {}", yaml_scalar(key));
+    if let Some(category) = category {
+        let _ = writeln!(out, "category: {}", yaml_scalar(category));
+    }
+    let tags: Vec<String> = tags.iter().map(|t| yaml_scalar(t)).collect();
+    let _ = writeln!(out, "tags: [{}]", tags.join(", "));
+    if let Some(dt) = chrono::DateTime::from_timestamp_millis(updated_at) {
+        let _ = writeln!(out, "updated_at: {}", dt.format("%Y-%m-%dT%H:%M:%S%.3fZ"));
+    }
+    out.push_str("---\n");
+    out.push_str(body.trim_end());
+    out.push('\n');
+    out
+}
+
+/// Quote a YAML scalar when it would otherwise be misread.
+fn yaml_scalar(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ' '))
+        && !value.starts_with(' ')
+        && !value.ends_with(' ');
+    if plain {
+        value.to_string()
+    } else {
+        serde_json::to_string(value).unwrap_or_else(|_| value.to_string())
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        serde_json::from_str(value).unwrap_or_else(|_| value[1..value.len() - 1].to_string())
+    } else if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        value[1..value.len() - 1].replace("''", "'")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse a note. A file without frontmatter is all body.
+///
+/// Accepts `tags` as an inline list (`[a, b]`) or a block list
+/// (`- a` lines), since note tools rewrite one into the other.
+fn parse_note(content: &str) -> std::result::Result<Note, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let Some(rest) = content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n")) else {
+        return Ok(Note {
+            body: content.trim_end().to_string(),
+            ..Note::default()
+        });
+    };
+    let (front, body) = rest
+        .split_once("\n---\n")
+        .or_else(|| rest.split_once("\n---\r\n"))
+        .or_else(|| rest.strip_suffix("\n---").map(|f| (f, "")))
+        .ok_or("frontmatter is not closed with '---'")?;
+
+    let mut note = Note {
+        body: body.trim_end().to_string(),
+        ..Note::default()
+    };
+    let mut in_tags = false;
+    for line in front.lines() {
+        if in_tags {
+            if let Some(tag) = line.trim().strip_prefix("- ") {
+                note.tags.push(unquote(tag));
+                continue;
+            }
+            in_tags = false;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim() {
+            "id" if !value.is_empty() => note.id = Some(unquote(value)),
+            "key" if !value.is_empty() => note.key = Some(unquote(value)),
+            "category" if !value.is_empty() => note.category = Some(unquote(value)),
+            "updated_at" => {
+                note.updated_at = chrono::DateTime::parse_from_rfc3339(&unquote(value))
+                    .ok()
+                    .map(|dt| dt.timestamp_millis());
+            }
+            "tags" => {
+                if let Some(list) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    note.tags = list.split(',').map(unquote).filter(|t| !t.is_empty()).collect();
+                } else if value.is_empty() {
+                    in_tags = true;
+                } else {
+                    note.tags = vec![unquote(value)];
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_round_trip() {
+        let memory = Memory {
+            id: "mem_abc".to_string(),
+            project_path: "/p".to_string(),
+            key: "api: base url".to_string(),
+            value: "https://api.example.com\n\nSecond paragraph\n".to_string(),
+            category: MemoryCategory::Config,
+            created_at: 0,
+            updated_at: 1_760_000_000_123,
+            subproject: None,
+        };
+        let note = parse_note(&render_memory(&memory)).unwrap();
+        assert_eq!(note.id.as_deref(), Some("mem_abc"));
+        assert_eq!(note.key.as_deref(), Some("api: base url"));
+        assert_eq!(note.category.as_deref(), Some("config"));
+        assert_eq!(note.tags, vec!["memory", "config"]);
+        assert_eq!(note.updated_at, Some(1_760_000_000_123));
+        assert!(same_text(&note.body, &memory.value));
+    }
+
+    #[test]
+    fn test_parse_block_tags_and_plain_files() {
+        let note = parse_note("---\nid: item_1\ntags:\n  - decision\n  - 'auth'\nextra: x\n---\nUse JWT\n").unwrap();
+        assert_eq!(note.tags, vec!["decision", "auth"]);
+        assert_eq!(note.body, "Use JWT");
+
+        let plain = parse_note("Just text\n").unwrap();
+        assert!(plain.id.is_none());
+        assert_eq!(plain.body, "Just text");
+
+        assert!(parse_note("---\nid: x\nno closing").is_err());
+    }
+
+    #[test]
+    fn test_file_names_are_safe_and_unique() {
+        let mut used = HashSet::new();
+        assert_eq!(file_name("auth/strategy", "item_1", &mut used), "auth-strategy.md");
+        assert_eq!(file_name("Auth/Strategy", "item_2", &mut used), "Auth-Strategy-2.md");
+        assert_eq!(file_name("..", "item_3", &mut used), "item_3.md");
+    }
+
+    #[test]
+    fn test_conflict_detection() {
+        assert!(changed_since(2_000, Some(1_000)));
+        assert!(!changed_since(1_000, Some(1_000)));
+        assert!(!changed_since(2_000, None));
+    }
+}
@@ -1,7 +1,9 @@
 //! Version command implementation.
 
 use crate::error::Result;
+use crate::json_schema::{json_schema, schema_for};
 use serde::Serialize;
+use serde_json::Value;
 
 #[derive(Serialize)]
 struct VersionOutput<'a> {
@@ -9,6 +11,13 @@ struct VersionOutput<'a> {
     build: &'a str,
 }
 
+json_schema!(VersionOutput<'a> { version: &'a str, build: &'a str });
+
+/// Schemas of the `--json` output, by command.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("version", schema_for::<VersionOutput>())]
+}
+
 /// Execute the version command.
 ///
 /// # Errors
@@ -0,0 +1,123 @@
+//! Change notifications (`sc watch`).
+//!
+//! The TUI, statusline and editor plugins want to react when an agent in
+//! another terminal saves an item or closes an issue, without re-running
+//! their full queries every second. Every mutation writes an audit event,
+//! so the events table already is a change feed; `sc watch` follows it.
+//!
+//! Between changes the only query is `PRAGMA data_version`, which moves
+//! whenever another connection commits. When it moves, the events
+//! with a higher ID than the last one printed are read and printed, one
+//! per line (NDJSON with `--json`, in the shape of `sc schema watch`).
+//! Writes that record no event (sync imports, pruning) wake the loop but
+//! print nothing.
+
+use crate::cli::{Database, WatchEntity};
+use crate::error::Result;
+use crate::json_schema::schema_for;
+use crate::storage::events::StoredEvent;
+use crate::storage::SqliteStorage;
+use serde_json::Value;
+use std::io::Write;
+use std::time::Duration;
+
+/// Schemas of the `--json` output, by command. `watch` prints one of
+/// these per line.
+pub(crate) fn output_schemas() -> Vec<(&'static str, Value)> {
+    vec![("watch", schema_for::<StoredEvent>())]
+}
+
+/// `entity_type` of the events about `entity`.
+const fn entity_type(entity: WatchEntity) -> &'static str {
+    match entity {
+        WatchEntity::Items => "context_item",
+        WatchEntity::Issues => "issue",
+        WatchEntity::Sessions => "session",
+        WatchEntity::Plans => "plan",
+        WatchEntity::Checkpoints => "checkpoint",
+        WatchEntity::Memory => "memory",
+        WatchEntity::Projects => "project",
+        WatchEntity::Time => "time_entry",
+    }
+}
+
+/// Execute the watch command. Runs until interrupted, the reader goes
+/// away, or `count` events have been printed.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or read.
+pub fn execute(
+    entities: &[WatchEntity],
+    interval_ms: u64,
+    since: Option<i64>,
+    count: Option<usize>,
+    db: &mut Database,
+    json: bool,
+) -> Result<()> {
+    let path = db.require_path()?.display().to_string();
+    let storage = db.storage()?;
+
+    let types: Vec<&str> = entities.iter().map(|e| entity_type(*e)).collect();
+    let mut feed = Feed { last_id: since.map_or_else(|| storage.latest_event_id(), Ok)?, remaining: count, json };
+    if !json {
+        eprintln!("Watching {path} for changes (Ctrl-C to stop)");
+    }
+
+    let mut version = storage.data_version()?;
+    if since.is_some() && !feed.emit(storage, &types)? {
+        return Ok(());
+    }
+    loop {
+        std::thread::sleep(Duration::from_millis(interval_ms));
+        let current = storage.data_version()?;
+        if current == version {
+            continue;
+        }
+        version = current;
+        if !feed.emit(storage, &types)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Where the output stands.
+struct Feed {
+    /// ID of the last event printed (or skipped as old).
+    last_id: i64,
+    /// Events left to print before exiting, if limited.
+    remaining: Option<usize>,
+    json: bool,
+}
+
+impl Feed {
+    /// Print the events after `last_id`. Returns `false` once the count is
+    /// reached or stdout is closed.
+    fn emit(&mut self, storage: &SqliteStorage, types: &[&str]) -> Result<bool> {
+        let events = storage.events_after(self.last_id, types)?;
+        let mut out = std::io::stdout().lock();
+        for event in events {
+            if self.remaining == Some(0) {
+                break;
+            }
+            self.last_id = event.id;
+            let line = if self.json { serde_json::to_string(&event)? } else { describe(&event) };
+            if let Err(e) = writeln!(out, "{line}") {
+                return if e.kind() == std::io::ErrorKind::BrokenPipe { Ok(false) } else { Err(e.into()) };
+            }
+            self.remaining = self.remaining.map(|n| n - 1);
+        }
+        if let Err(e) = out.flush() {
+            return if e.kind() == std::io::ErrorKind::BrokenPipe { Ok(false) } else { Err(e.into()) };
+        }
+        Ok(self.remaining != Some(0))
+    }
+}
+
+/// `HH:MM:SS entity_type entity_id event_type by actor`.
+fn describe(event: &StoredEvent) -> String {
+    let time = chrono::DateTime::from_timestamp_millis(event.created_at)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+        .unwrap_or_default();
+    format!("{time} {} {} {} by {}", event.entity_type, event.entity_id, event.event_type, event.actor)
+}
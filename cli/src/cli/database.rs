@@ -0,0 +1,117 @@
+//! The database connection one command shares with the work around it.
+//!
+//! Before a command, `sc` may check `permissions.commands` and pick a
+//! session for it; afterwards it records usage and presence and runs
+//! scheduled checkpoints and pruning. Each of those used to open the
+//! database on its own, paying for the connection and schema check every
+//! time. `main` now makes one [`Database`] per command and passes it to
+//! all of them, so the connection is opened once, on first use, and
+//! commands that never touch the database don't open it at all.
+
+use crate::config::resolve_db_path;
+use crate::error::{Error, Result};
+use crate::storage::SqliteStorage;
+use std::path::{Path, PathBuf};
+
+/// The database a command runs against, opened on first use.
+pub struct Database {
+    path: Option<PathBuf>,
+    storage: Option<SqliteStorage>,
+}
+
+impl Database {
+    /// The database at `db_path`, or the configured one. Nothing is opened
+    /// until [`storage`](Self::storage) is called.
+    #[must_use]
+    pub fn new(db_path: Option<&Path>) -> Self {
+        Self { path: resolve_db_path(db_path), storage: None }
+    }
+
+    /// Where the database is, whether or not it exists yet.
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Where the database is.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotInitialized`] if no location can be resolved.
+    pub fn require_path(&self) -> Result<&Path> {
+        self.path().ok_or(Error::NotInitialized)
+    }
+
+    /// Whether the database file exists.
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        self.path.as_deref().is_some_and(Path::exists)
+    }
+
+    /// The open database.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotInitialized`] if the database doesn't exist, or
+    /// an error if it cannot be opened or migrated.
+    pub fn storage(&mut self) -> Result<&mut SqliteStorage> {
+        if self.storage.is_none() {
+            let path = self.path.as_deref().filter(|p| p.exists()).ok_or(Error::NotInitialized)?;
+            self.storage = Some(SqliteStorage::open(path)?);
+        }
+        self.storage.as_mut().ok_or(Error::NotInitialized)
+    }
+
+    /// The open database if it exists, for best-effort work around a
+    /// command; a database that fails to open counts as missing.
+    pub fn existing(&mut self) -> Option<&mut SqliteStorage> {
+        if !self.exists() {
+            return None;
+        }
+        match self.storage() {
+            Ok(storage) => Some(storage),
+            Err(e) => {
+                tracing::debug!(error = %e, "Failed to open the database");
+                None
+            }
+        }
+    }
+
+    /// Close the connection before the file is replaced or removed. The
+    /// next [`storage`](Self::storage) opens it again.
+    pub fn close(&mut self) {
+        self.storage = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_missing_database_is_not_created() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("missing.db");
+        let mut db = Database::new(Some(&path));
+
+        assert!(matches!(db.storage(), Err(Error::NotInitialized)));
+        assert!(db.existing().is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_storage_reopens_after_close() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.db");
+        SqliteStorage::open(&path).unwrap();
+        let mut db = Database::new(Some(&path));
+
+        db.storage().unwrap().create_session("sess_1", "Work", None, Some("/work/app"), None, None, "test").unwrap();
+        let storage = db.existing().unwrap();
+        assert_eq!(storage.get_session("sess_1").unwrap().unwrap().name, "Work");
+
+        db.close();
+        assert!(db.storage().unwrap().get_session("sess_1").unwrap().is_some());
+    }
+}
@@ -0,0 +1,330 @@
+//! Value input from stdin, files and the clipboard.
+//!
+//! Multi-line values are awkward to pass as shell arguments, so the
+//! content-bearing arguments (`save` value, `update --value`, `memory save`
+//! value, `plan --content`/`--success-criteria`, `issue --description`/
+//! `--details`) also accept:
+//!
+//! - `-` — read stdin
+//! - `@path` — read a file, like curl's `-d @file` (`@@text` is a literal `@text`).
+//!   Text after the `@` that is neither an existing file nor a path with a
+//!   directory in it, such as `@bob please review`, stays literal
+//! - `--stdin` / `--from-clipboard` — fill the command's main value
+//!
+//! `issue batch --json-input` takes `-` and `@path` too, and a bare path
+//! to a JSON file as well, since JSON itself never looks like one.
+//!
+//! [`resolve`] rewrites these in place before dispatch, so command handlers
+//! only ever see the final text. `--edit` is the exception: the starting
+//! text is often the stored value, so handlers call [`edit`] once they have
+//! loaded it.
+
+use super::{Commands, InputArgs, IssueCommands, MemoryCommands, PlanCommands};
+use crate::error::{Error, Result};
+use std::io::{IsTerminal, Read, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Replace `-`, `@path`, `--stdin` and `--from-clipboard` inputs with their contents.
+///
+/// # Errors
+///
+/// Returns an error if a file or stdin cannot be read, stdin is requested
+/// twice, or a flag is combined with an explicit value.
+pub fn resolve(command: &mut Commands) -> Result<()> {
+    let mut reader = Reader::default();
+    match command {
+        Commands::Save(args) => reader.fill(&args.input, &mut args.value, "value"),
+        Commands::Update(args) => reader.fill(&args.input, &mut args.value, "--value"),
+        Commands::Memory {
+            command: MemoryCommands::Save { value, input, .. },
+        } => reader.fill(input, value, "value"),
+        Commands::Plan { command } => match command {
+            PlanCommands::Create(args) => {
+                reader.fill(&args.input, &mut args.content, "--content")?;
+                reader.expand(&mut args.success_criteria)
+            }
+            PlanCommands::Update(args) => {
+                reader.fill(&args.input, &mut args.content, "--content")?;
+                reader.expand(&mut args.success_criteria)
+            }
+            _ => Ok(()),
+        },
+        Commands::Issue { command } => match command {
+            IssueCommands::Create(args) => {
+                reader.fill(&args.input, &mut args.description, "--description")?;
+                reader.expand(&mut args.details)
+            }
+            IssueCommands::Update(args) => {
+                reader.fill(&args.input, &mut args.description, "--description")?;
+                reader.expand(&mut args.details)
+            }
+            IssueCommands::Batch { json_input } => reader.expand_json(json_input),
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Tracks stdin so it is only consumed once per command.
+#[derive(Default)]
+struct Reader {
+    stdin_used: bool,
+}
+
+impl Reader {
+    /// Fill the command's main value from `--stdin`/`--from-clipboard`, or expand it.
+    fn fill(&mut self, input: &InputArgs, value: &mut Option<String>, name: &str) -> Result<()> {
+        if input.edit || (!input.stdin && !input.from_clipboard) {
+            return self.expand(value);
+        }
+        if value.is_some() {
+            let flag = if input.stdin { "--stdin" } else { "--from-clipboard" };
+            return Err(Error::InvalidArgument(format!("{flag} cannot be combined with {name}")));
+        }
+        *value = Some(if input.stdin { self.stdin()? } else { clipboard()? });
+        Ok(())
+    }
+
+    /// Expand a `-` or `@path` value.
+    fn expand(&mut self, value: &mut Option<String>) -> Result<()> {
+        let Some(raw) = value.as_deref() else {
+            return Ok(());
+        };
+        let expanded = match parse_source(raw) {
+            Source::Literal(text) => text.to_string(),
+            Source::Stdin => self.stdin()?,
+            Source::File(path) => std::fs::read_to_string(path)
+                .map(|s| trim_newlines(&s))
+                .map_err(|e| {
+                    Error::InvalidArgument(format!(
+                        "Cannot read @{path}: {e} (use @@ for a value starting with @)"
+                    ))
+                })?,
+        };
+        *value = Some(expanded);
+        Ok(())
+    }
+
+    /// Expand a JSON argument: `-`, `@path`, or a path to an existing
+    /// file when the value isn't JSON.
+    fn expand_json(&mut self, value: &mut String) -> Result<()> {
+        if !value.trim_start().starts_with(['{', '[']) && Path::new(value.as_str()).is_file() {
+            *value = std::fs::read_to_string(value.as_str())
+                .map_err(|e| Error::InvalidArgument(format!("Cannot read {value}: {e}")))?;
+            return Ok(());
+        }
+        let mut expanded = Some(std::mem::take(value));
+        self.expand(&mut expanded)?;
+        *value = expanded.unwrap_or_default();
+        Ok(())
+    }
+
+    fn stdin(&mut self) -> Result<String> {
+        if self.stdin_used {
+            return Err(Error::InvalidArgument("stdin can only be read once per command".to_string()));
+        }
+        self.stdin_used = true;
+        let mut stdin = std::io::stdin();
+        if stdin.is_terminal() {
+            return Err(Error::InvalidArgument(
+                "Expected input on stdin, but stdin is a terminal (pipe or redirect content in)".to_string(),
+            ));
+        }
+        let mut buf = String::new();
+        stdin.read_to_string(&mut buf)?;
+        Ok(trim_newlines(&buf))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Source<'a> {
+    Literal(&'a str),
+    Stdin,
+    File(&'a str),
+}
+
+fn parse_source(raw: &str) -> Source<'_> {
+    match raw {
+        "-" => Source::Stdin,
+        _ if raw.starts_with("@@") => Source::Literal(&raw[1..]),
+        _ => match raw.strip_prefix('@') {
+            Some(path) if Path::new(path).is_file() || looks_like_path(path) => Source::File(path),
+            _ => Source::Literal(raw),
+        },
+    }
+}
+
+/// Whether the text after an `@` names a file even though none exists
+/// there, so a mistyped path fails instead of being saved as the value.
+/// Mentions (`@alice.smith`) and prose (`@here deploy is frozen`) never
+/// contain a directory separator.
+fn looks_like_path(text: &str) -> bool {
+    !text.contains(char::is_whitespace) && text.contains(['/', '\\'])
+}
+
+/// Drop the trailing newline(s) that editors and `echo` add.
+fn trim_newlines(s: &str) -> String {
+    s.trim_end_matches(['\n', '\r']).to_string()
+}
+
+/// First line of the editor buffer; removed again when reading it back.
+const EDIT_HINT: &str = "<!-- sc: save and close to continue; empty to abort. This line is removed. -->";
+
+/// Open `$VISUAL`/`$EDITOR` on `initial` and return the saved text.
+///
+/// `what` names the value in the abort message ("issue description").
+///
+/// # Errors
+///
+/// Returns an error if the editor cannot be started, exits with a failure
+/// status, or the saved text is empty.
+pub fn edit(initial: &str, what: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+
+    let file = EditFile::create(&format!("{EDIT_HINT}\n{initial}\n"))?;
+
+    // $EDITOR may carry arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(&file.0).status();
+    let text = std::fs::read_to_string(&file.0);
+    drop(file);
+
+    let status = status.map_err(|e| Error::Other(format!("Could not start editor '{editor}': {e}")))?;
+    if !status.success() {
+        return Err(Error::Other(format!("Editor '{editor}' exited with {status}")));
+    }
+    let text = strip_hint(&text?);
+    if text.trim().is_empty() {
+        return Err(Error::InvalidArgument(format!("Aborted: {what} is empty")));
+    }
+    Ok(text)
+}
+
+/// The editor buffer: readable by the owner only, since it may hold the
+/// decrypted value of an encrypted category, and removed when dropped.
+struct EditFile(PathBuf);
+
+impl EditFile {
+    fn create(contents: &str) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("sc-edit-{}.md", &uuid::Uuid::new_v4().to_string()[..8]));
+        let mut opts = std::fs::OpenOptions::new();
+        opts.write(true).create_new(true);
+        #[cfg(unix)]
+        opts.mode(0o600);
+        let mut file = opts.open(&path)?;
+        let created = Self(path);
+        file.write_all(contents.as_bytes())?;
+        Ok(created)
+    }
+}
+
+impl Drop for EditFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn strip_hint(text: &str) -> String {
+    let text = text.strip_prefix(EDIT_HINT).map_or(text, |rest| rest.strip_prefix('\n').unwrap_or(rest));
+    trim_newlines(text)
+}
+
+/// Read the system clipboard with whichever paste tool is available.
+fn clipboard() -> Result<String> {
+    let candidates: &[&[&str]] = if cfg!(target_os = "macos") {
+        &[&["pbpaste"]]
+    } else if cfg!(windows) {
+        &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]]
+    } else {
+        &[
+            &["wl-paste", "--no-newline"],
+            &["xclip", "-selection", "clipboard", "-o"],
+            &["xsel", "--clipboard", "--output"],
+        ]
+    };
+
+    for argv in candidates {
+        let Ok(output) = Command::new(argv[0]).args(&argv[1..]).output() else {
+            continue;
+        };
+        if output.status.success() {
+            return Ok(trim_newlines(&String::from_utf8_lossy(&output.stdout)));
+        }
+    }
+    Err(Error::Other(
+        "Could not read the clipboard (needs pbpaste, wl-paste, xclip or xsel)".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(parse_source("-"), Source::Stdin);
+        assert_eq!(parse_source("@docs/notes.md"), Source::File("docs/notes.md"));
+        assert_eq!(parse_source("@@alice"), Source::Literal("@alice"));
+        assert_eq!(parse_source("@"), Source::Literal("@"));
+        assert_eq!(parse_source("@bob please review"), Source::Literal("@bob please review"));
+        assert_eq!(parse_source("@alice.smith"), Source::Literal("@alice.smith"));
+        assert_eq!(parse_source("plain - text"), Source::Literal("plain - text"));
+    }
+
+    #[test]
+    fn test_expand_file_and_flag_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("value.md");
+        std::fs::write(&path, "line one\nline two\n\n").unwrap();
+
+        let mut reader = Reader::default();
+        let mut value = Some(format!("@{}", path.display()));
+        reader.expand(&mut value).unwrap();
+        assert_eq!(value.as_deref(), Some("line one\nline two"));
+
+        let mut missing = Some("@missing/does-not-exist.md".to_string());
+        assert!(reader.expand(&mut missing).is_err());
+
+        // Spaces don't stop an existing file from being read
+        let spaced = dir.path().join("review notes.md");
+        std::fs::write(&spaced, "from file").unwrap();
+        let mut value = Some(format!("@{}", spaced.display()));
+        reader.expand(&mut value).unwrap();
+        assert_eq!(value.as_deref(), Some("from file"));
+
+        let mut prose = Some("@bob please review".to_string());
+        reader.expand(&mut prose).unwrap();
+        assert_eq!(prose.as_deref(), Some("@bob please review"));
+
+        let input = InputArgs { stdin: true, ..InputArgs::default() };
+        let mut explicit = Some("text".to_string());
+        assert!(reader.fill(&input, &mut explicit, "value").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_edit_file_is_private_and_removed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = EditFile::create("secret").unwrap();
+        let path = file.0.clone();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "secret");
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_strip_hint() {
+        assert_eq!(strip_hint(&format!("{EDIT_HINT}\nbody\n\n")), "body");
+        assert_eq!(strip_hint("hint deleted by the user\n"), "hint deleted by the user");
+    }
+}
@@ -0,0 +1,262 @@
+//! Health check and Prometheus metrics for long-running modes.
+//!
+//! `sc serve` and the `--daemon` commands (`sc checkpoint auto`,
+//! `sc session pause-idle`, `sc policy reap`) live as long as whatever
+//! supervises them. Given `--metrics <ADDR>` they also answer plain HTTP
+//! on that address, so systemd, Kubernetes or a Prometheus scrape can
+//! watch them like any other service:
+//!
+//! - `GET /healthz`: `200 {"status":"ok"}` while the database answers,
+//!   `503` with the error when it doesn't
+//! - `GET /metrics`: the Prometheus text format
+//!
+//! | Metric | Type | Meaning |
+//! |--------|------|---------|
+//! | `sc_up` | gauge | 1 while the database answers |
+//! | `sc_uptime_seconds` | gauge | Time since the process started serving |
+//! | `sc_db_size_bytes` | gauge | Database file plus its WAL |
+//! | `sc_embeddings_pending` | gauge | Context items without an embedding yet |
+//! | `sc_sync_dirty_records{kind}` | gauge | Records waiting for `sc sync export` |
+//! | `sc_request_duration_seconds{request}` | histogram | JSON-RPC calls by method, daemon passes by command |
+//! | `sc_request_errors_total{request}` | counter | Of those, the ones that failed |
+//!
+//! Database figures are read when scraped, on a connection of their own.
+
+use crate::error::{Error, Result};
+use crate::storage::SqliteStorage;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const BUCKETS: [f64; 12] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// When serving started; unset until [`serve`] runs, and requests aren't
+/// recorded before then.
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// Latencies by request name.
+static REQUESTS: Mutex<BTreeMap<String, Histogram>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations at or under each of [`BUCKETS`].
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+    errors: u64,
+}
+
+/// Answer `/healthz` and `/metrics` on `addr` from a background thread,
+/// reading database figures from `db_path`. Returns the bound address
+/// (which differs from `addr` for port 0).
+///
+/// # Errors
+///
+/// Returns an error if `addr` can't be listened on.
+pub fn serve(addr: &str, db_path: PathBuf) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)
+        .map_err(|e| Error::InvalidArgument(format!("Cannot serve metrics on {addr}: {e}")))?;
+    let local = listener.local_addr()?;
+    STARTED.get_or_init(Instant::now);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = respond(&stream, &db_path) {
+                tracing::debug!(error = %e, "Metrics request failed");
+            }
+        }
+    });
+    tracing::info!(%local, "Serving /healthz and /metrics");
+    Ok(local)
+}
+
+/// Record one request (a JSON-RPC call, a daemon pass) that took
+/// `elapsed`. A no-op unless [`serve`] is running.
+pub fn observe(request: &str, elapsed: Duration, ok: bool) {
+    if STARTED.get().is_none() {
+        return;
+    }
+    let seconds = elapsed.as_secs_f64();
+    let mut requests = REQUESTS.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let histogram = requests.entry(request.to_string()).or_default();
+    for (count, bound) in histogram.buckets.iter_mut().zip(BUCKETS) {
+        if seconds <= bound {
+            *count += 1;
+        }
+    }
+    histogram.count += 1;
+    histogram.sum += seconds;
+    if !ok {
+        histogram.errors += 1;
+    }
+}
+
+/// Run one daemon pass, recording it as `request`.
+///
+/// # Errors
+///
+/// Returns the pass's error.
+pub fn timed<T>(request: &str, pass: impl FnOnce() -> Result<T>) -> Result<T> {
+    let started = Instant::now();
+    let result = pass();
+    observe(request, started.elapsed(), result.is_ok());
+    result
+}
+
+fn respond(stream: &TcpStream, db_path: &Path) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers end at a blank line; none of them matter here
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default().split('?').next().unwrap_or_default();
+    let (status, content_type, body) = match (method, path) {
+        ("GET" | "HEAD", "/healthz") => match health(db_path) {
+            Ok(()) => ("200 OK", "application/json", r#"{"status":"ok"}"#.to_string()),
+            Err(e) => {
+                let body = serde_json::json!({ "status": "error", "error": e.to_string() }).to_string();
+                ("503 Service Unavailable", "application/json", body)
+            }
+        },
+        ("GET" | "HEAD", "/metrics") => ("200 OK", "text/plain; version=0.0.4", render(db_path)),
+        ("GET" | "HEAD", _) => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain", "Method not allowed\n".to_string()),
+    };
+
+    let mut writer = stream;
+    write!(
+        writer,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    if method != "HEAD" {
+        writer.write_all(body.as_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn health(db_path: &Path) -> Result<()> {
+    let storage = SqliteStorage::open(db_path)?;
+    storage.conn().query_row("SELECT 1", [], |_| Ok(()))?;
+    Ok(())
+}
+
+/// The `/metrics` page.
+fn render(db_path: &Path) -> String {
+    let mut out = String::new();
+    let mut gauge = |name: &str, help: &str, samples: &[(String, f64)]| {
+        let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} gauge");
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{name}{labels} {value}");
+        }
+    };
+
+    let database = SqliteStorage::open(db_path).and_then(|storage| {
+        Ok((storage.count_embedding_status(None)?.without_embeddings, storage.count_dirty_records()?))
+    });
+    gauge("sc_up", "Whether the database answers.", &[(String::new(), if database.is_ok() { 1.0 } else { 0.0 })]);
+    let uptime = STARTED.get().map_or(0.0, |started| started.elapsed().as_secs_f64());
+    gauge("sc_uptime_seconds", "Seconds since the process started serving.", &[(String::new(), uptime)]);
+    #[allow(clippy::cast_precision_loss)]
+    let db_bytes = [db_path.to_path_buf(), wal_path(db_path)]
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|m| m.len())
+        .sum::<u64>() as f64;
+    gauge("sc_db_size_bytes", "Size of the database file and its WAL.", &[(String::new(), db_bytes)]);
+    if let Ok((pending, dirty)) = database {
+        #[allow(clippy::cast_precision_loss)]
+        let pending = pending as f64;
+        gauge("sc_embeddings_pending", "Context items without an embedding yet.", &[(String::new(), pending)]);
+        #[allow(clippy::cast_precision_loss)]
+        let dirty: Vec<_> = dirty.into_iter().map(|(kind, count)| (format!("{{kind=\"{kind}\"}}"), count as f64)).collect();
+        gauge("sc_sync_dirty_records", "Records waiting for sync export.", &dirty);
+    }
+
+    let requests = REQUESTS.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let name = "sc_request_duration_seconds";
+    let _ = writeln!(out, "# HELP {name} Latency of JSON-RPC calls and daemon passes.\n# TYPE {name} histogram");
+    for (request, histogram) in requests.iter() {
+        let request = escape_label(request);
+        for (count, bound) in histogram.buckets.iter().zip(BUCKETS) {
+            let _ = writeln!(out, "{name}_bucket{{request=\"{request}\",le=\"{bound}\"}} {count}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{request=\"{request}\",le=\"+Inf\"}} {}", histogram.count);
+        let _ = writeln!(out, "{name}_sum{{request=\"{request}\"}} {}", histogram.sum);
+        let _ = writeln!(out, "{name}_count{{request=\"{request}\"}} {}", histogram.count);
+    }
+    let name = "sc_request_errors_total";
+    let _ = writeln!(out, "# HELP {name} JSON-RPC calls and daemon passes that failed.\n# TYPE {name} counter");
+    for (request, histogram) in requests.iter() {
+        let _ = writeln!(out, "{name}{{request=\"{}\"}} {}", escape_label(request), histogram.errors);
+    }
+    out
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.as_os_str().to_os_string();
+    name.push("-wal");
+    PathBuf::from(name)
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_health_and_metrics() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let mut storage = SqliteStorage::open(&db_path).unwrap();
+        storage.create_session("sess_1", "Work", None, Some("/repo"), None, None, "bot").unwrap();
+        drop(storage);
+
+        let addr = serve("127.0.0.1:0", db_path.clone()).unwrap();
+        observe("listIssues", Duration::from_millis(3), true);
+        observe("listIssues", Duration::from_millis(300), false);
+
+        let health = get(addr, "/healthz");
+        assert!(health.starts_with("HTTP/1.1 200 OK"), "{health}");
+        assert!(health.ends_with(r#"{"status":"ok"}"#));
+
+        let metrics = get(addr, "/metrics?x=1");
+        assert!(metrics.contains("\nsc_up 1\n"), "{metrics}");
+        assert!(metrics.contains("sc_sync_dirty_records{kind=\"sessions\"} 1\n"));
+        assert!(metrics.contains("sc_request_duration_seconds_bucket{request=\"listIssues\",le=\"0.005\"} 1\n"));
+        assert!(metrics.contains("sc_request_duration_seconds_bucket{request=\"listIssues\",le=\"+Inf\"} 2\n"));
+        assert!(metrics.contains("sc_request_errors_total{request=\"listIssues\"} 1\n"));
+
+        assert!(get(addr, "/nope").starts_with("HTTP/1.1 404"));
+        std::fs::remove_file(&db_path).unwrap();
+        std::fs::create_dir(&db_path).unwrap();
+        assert!(get(addr, "/healthz").starts_with("HTTP/1.1 503"));
+    }
+}
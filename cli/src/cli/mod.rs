@@ -15,7 +15,14 @@ pub enum OutputFormat {
     Csv,
 }
 
+pub mod command_log;
 pub mod commands;
+pub mod database;
+pub mod input;
+pub mod metrics;
+pub mod shell;
+
+pub use database::Database;
 
 /// SaveContext CLI - The OS for AI coding agents
 #[derive(Parser, Debug)]
@@ -52,6 +59,14 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub dry_run: bool,
 
+    /// Print the SQL run, its query plan, and timing (to stderr)
+    #[arg(long, global = true)]
+    pub explain: bool,
+
+    /// Print how long the command and its SQL took (to stderr)
+    #[arg(long, global = true)]
+    pub timing: bool,
+
     /// Increase logging verbosity (-v, -vv)
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
@@ -76,11 +91,284 @@ pub enum Commands {
         /// Overwrite existing database
         #[arg(long)]
         force: bool,
+
+        /// Register a directory of the current project as a monorepo
+        /// sub-project (path relative to the project root)
+        #[arg(long, conflicts_with_all = ["global", "force"])]
+        subproject: Option<String>,
+    },
+
+    /// Interactive first-run setup (database, embeddings, actor, project, integrations)
+    Setup {
+        /// Accept every default without prompting
+        #[arg(short, long)]
+        yes: bool,
     },
 
     /// Print version information
     Version,
 
+    /// Describe subcommands, flags, enum values and schema version for orchestrators
+    Capabilities,
+
+    /// Print the JSON Schema of a command's --json output (or list them)
+    Schema {
+        /// Command to describe, e.g. `issue create`, or `error` for the error format
+        command: Vec<String>,
+    },
+
+    /// Local usage statistics: most-used commands, slowest runs, DB growth
+    Stats {
+        /// Reporting window in days
+        #[arg(long, default_value = "30")]
+        days: u32,
+
+        /// Rows to show per table
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+
+        /// Delete all recorded statistics
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Database maintenance: index audit
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+
+    /// Performance benchmarks
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommands,
+    },
+
+    /// Issue activity by others that concerns you: mentions, assigned and watched issues
+    Inbox {
+        /// Show everything in the window, not just unread
+        #[arg(long)]
+        all: bool,
+
+        /// Window in days when nothing has been marked read (or with --all)
+        #[arg(long, default_value = "7")]
+        days: u64,
+
+        /// Include all projects
+        #[arg(long)]
+        all_projects: bool,
+
+        /// Maximum entries to show
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+
+        /// Mark the inbox read after listing
+        #[arg(long)]
+        mark_read: bool,
+    },
+
+    /// Semantic search across context items, issues, plans and memory
+    Search {
+        /// What to search for
+        query: String,
+
+        /// Entities to search: items, issues, plans, memory (comma-separated; default all)
+        #[arg(short, long, value_delimiter = ',', value_parser = parse_embedding_entity)]
+        entities: Vec<crate::embeddings::EmbeddingEntity>,
+
+        /// Include all projects
+        #[arg(long)]
+        all_projects: bool,
+
+        /// Minimum similarity (0.0-1.0)
+        #[arg(long, default_value = "0.3")]
+        threshold: f32,
+
+        /// Maximum results
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+
+        /// Write each result as its own JSON line, for editor plugins
+        #[arg(long)]
+        stream: bool,
+    },
+
+    /// Find text in context items, issues, plans and memory, grep-style
+    Grep {
+        /// Text to find (a regex with --regex)
+        pattern: String,
+
+        /// Entities to search: items, issues, plans, memory, all (comma-separated; default all)
+        #[arg(short, long, value_delimiter = ',')]
+        entities: Vec<String>,
+
+        /// Treat the pattern as a regular expression
+        #[arg(short = 'E', long)]
+        regex: bool,
+
+        /// Match case-insensitively
+        #[arg(short, long)]
+        ignore_case: bool,
+
+        /// Lines of context to show around each match
+        #[arg(short = 'C', long, default_value = "0")]
+        context: usize,
+
+        /// Include all projects
+        #[arg(long)]
+        all_projects: bool,
+
+        /// Maximum matching lines
+        #[arg(short, long, default_value = "200")]
+        limit: usize,
+
+        /// Write each match as its own JSON line as soon as it is found,
+        /// for editor plugins
+        #[arg(long)]
+        stream: bool,
+    },
+
+    /// Deleted items, issues and sessions: list, restore, empty
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+
+    /// Reports from the project's history (cumulative flow, burndown)
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+
+    /// Audit log retention: prune old events, optionally archiving them
+    Events {
+        #[command(subcommand)]
+        command: EventsCommands,
+    },
+
+    /// Actor identities: canonical IDs, display names and aliases
+    Actor {
+        #[command(subcommand)]
+        command: ActorCommands,
+    },
+
+    /// Who is working on what: each actor's session, claimed issues and
+    /// last activity
+    Team {
+        #[command(subcommand)]
+        command: TeamCommands,
+    },
+
+    /// Claim policy: limits on the issues one actor may hold
+    Policy {
+        #[command(subcommand)]
+        command: PolicyCommands,
+    },
+
+    /// Erase an actor: delete what it created and its name everywhere else
+    ///
+    /// Removes the records the actor created, its audit events and
+    /// @mentions, and its name on issues, time entries, watches, trash and
+    /// recorded sync deletions. With --anonymize the records stay and the
+    /// name is replaced by a pseudonym. Without --force, only reports what
+    /// would change.
+    PurgeActor {
+        /// Actor ID or alias
+        name: String,
+
+        /// Keep the actor's records and replace its name with a pseudonym
+        #[arg(long)]
+        anonymize: bool,
+
+        /// Purge (default: report what would change)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Stream changes other processes make to the database as they happen
+    ///
+    /// Prints one line per audit event (NDJSON with --json) until
+    /// interrupted. Cheap to leave running: between changes it only polls
+    /// the database's data version.
+    Watch {
+        /// Entities to follow (comma-separated; default: all)
+        #[arg(long, value_delimiter = ',')]
+        entities: Vec<WatchEntity>,
+
+        /// Milliseconds between polls
+        #[arg(long, default_value = "250", value_parser = clap::value_parser!(u64).range(10..=60_000))]
+        interval: u64,
+
+        /// First replay events with a higher ID than this (0 = all kept)
+        #[arg(long)]
+        since: Option<i64>,
+
+        /// Exit after printing this many events
+        #[arg(long)]
+        count: Option<usize>,
+    },
+
+    /// Create sample data to try features on
+    ///
+    /// --demo registers a made-up project with sessions, decisions, a
+    /// checkpoint, a plan, issues with dependencies, memory and time
+    /// entries, in a directory of its own (default ~/.savecontext/demo).
+    Seed {
+        /// Create the demo project
+        #[arg(long, required = true)]
+        demo: bool,
+
+        /// Directory for the demo project
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Replace a project already registered at the path
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Export memory and decisions to external tools
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+
+    /// Import edits made in external tools
+    Import {
+        #[command(subcommand)]
+        command: ImportCommands,
+    },
+
+    /// Write read-only HTML pages for people who don't use sc
+    Share {
+        #[command(subcommand)]
+        command: ShareCommands,
+    },
+
+    /// Read commands from stdin, one per line, answering each in JSON
+    Shell,
+
+    /// Serve requests from an editor extension over stdio
+    Serve {
+        /// Speak JSON-RPC 2.0, one message per line or with Content-Length headers
+        #[arg(long, required = true)]
+        jsonrpc: bool,
+
+        /// Serve /healthz and Prometheus /metrics on this address (e.g. 127.0.0.1:9464)
+        #[arg(long, value_name = "ADDR")]
+        metrics: Option<String>,
+    },
+
+    /// Apply a JSONL file of operations in one transaction
+    Apply {
+        /// Operations file, one JSON object per line (`-` reads stdin)
+        file: PathBuf,
+
+        /// Commit the operations that succeed instead of rolling back on the first failure
+        #[arg(long)]
+        best_effort: bool,
+    },
+
     /// Session management
     Session {
         #[command(subcommand)]
@@ -105,12 +393,40 @@ pub enum Commands {
     /// Update a context item
     Update(UpdateArgs),
 
+    /// Edit a context item's value in $EDITOR, or print it for an editor
+    /// plugin
+    EditContext {
+        /// Key of the item to edit
+        key: String,
+
+        /// Print the item as an LSP-style text document (JSON) instead of
+        /// opening $EDITOR
+        #[arg(long)]
+        emit_lsp_like_json: bool,
+    },
+
     /// Tag context items
     Tag {
         #[command(subcommand)]
         command: TagCommands,
     },
 
+    /// Reminders that come due at a set time
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Remind {
+        #[command(subcommand)]
+        command: Option<RemindCommands>,
+
+        #[command(flatten)]
+        args: RemindArgs,
+    },
+
+    /// Review decisions saved by agents
+    Review {
+        #[command(subcommand)]
+        command: ReviewCommands,
+    },
+
     /// Issue management
     Issue {
         #[command(subcommand)]
@@ -129,6 +445,18 @@ pub enum Commands {
         command: MemoryCommands,
     },
 
+    /// Context item categories of the project
+    Category {
+        #[command(subcommand)]
+        command: CategoryCommands,
+    },
+
+    /// Git integration: hooks that keep sessions on the current branch
+    Git {
+        #[command(subcommand)]
+        command: GitCommands,
+    },
+
     /// Sync with JSONL files
     Sync {
         #[command(subcommand)]
@@ -148,7 +476,10 @@ pub enum Commands {
     },
 
     /// Prepare context for compaction (auto-checkpoint + summary)
-    Compaction,
+    Compaction {
+        #[command(subcommand)]
+        command: Option<CompactionCommands>,
+    },
 
     /// Generate context primer for agent injection
     Prime {
@@ -179,13 +510,42 @@ pub enum Commands {
         /// Temporal decay half-life in days for smart mode (default: 14)
         #[arg(long, default_value = "14")]
         decay_days: u32,
+
+        /// Only include these session project paths (comma-separated; default: all)
+        #[arg(long, value_delimiter = ',')]
+        paths: Vec<String>,
+
+        #[command(flatten)]
+        scope: SubprojectArgs,
+
+        /// Only include items trusted at least this much (agent < imported
+        /// < human), e.g. `human` for decisions a person has vetted
+        #[arg(long, value_parser = parse_trust_level)]
+        min_trust: Option<crate::model::TrustLevel>,
+
+        /// Print stored text as is, without the prompt-injection cleanup
+        /// set by prime.sanitize in the config
+        #[arg(long)]
+        raw: bool,
     },
 
-    /// Generate shell completions
+    /// Generate shell completions or man pages
     Completions {
-        /// Shell to generate completions for
+        /// Shell to generate completions for, or `man` for man pages
         #[arg(value_enum)]
         shell: Shell,
+
+        /// Directory to write man pages to, one per command (default: print sc.1)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Values for dynamic shell completion (internal use)
+    // Not `__complete`: clap's bash script joins command paths with `__`.
+    #[command(name = "complete-values", hide = true)]
+    Complete {
+        #[arg(value_enum)]
+        kind: CompletionKind,
     },
 
     /// Embedding configuration and management
@@ -229,6 +589,34 @@ pub enum Shell {
     Fish,
     PowerShell,
     Elvish,
+    /// Man pages (troff) rather than a completion script
+    Man,
+}
+
+/// Values `sc complete-values` can list for shell completion.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum CompletionKind {
+    /// Recent session IDs
+    Sessions,
+    /// Short IDs of open issues
+    Issues,
+    /// Project memory keys
+    MemoryKeys,
+}
+
+/// Entities `sc watch` can follow.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchEntity {
+    /// Context items
+    Items,
+    Issues,
+    Sessions,
+    Plans,
+    Checkpoints,
+    Memory,
+    Projects,
+    /// Time entries
+    Time,
 }
 
 // ============================================================================
@@ -259,8 +647,12 @@ pub enum SessionCommands {
         force_new: bool,
     },
 
-    /// End current session
-    End,
+    /// End current session, saving a summary and naming it after its work if it has a placeholder name
+    End {
+        /// Skip the summary and keep the name (also `"session_summary": "off"`)
+        #[arg(long)]
+        no_summary: bool,
+    },
 
     /// Pause current session
     Pause,
@@ -296,6 +688,11 @@ pub enum SessionCommands {
         /// Include completed sessions (when status is not 'all' or 'completed')
         #[arg(long)]
         include_completed: bool,
+
+        /// Only sessions started in the git worktree at this path (default:
+        /// the current one)
+        #[arg(long, num_args = 0..=1, default_missing_value = ".")]
+        worktree: Option<String>,
     },
 
     /// Switch to a different session
@@ -310,7 +707,7 @@ pub enum SessionCommands {
         name: String,
     },
 
-    /// Delete a session permanently
+    /// Delete a session (kept in `sc trash` for the retention period)
     Delete {
         /// Session ID
         id: String,
@@ -339,6 +736,64 @@ pub enum SessionCommands {
         /// Project path to remove
         path: String,
     },
+
+    /// Model token usage of sessions (recorded by hooks)
+    Usage {
+        #[command(subcommand)]
+        command: SessionUsageCommands,
+    },
+
+    /// Pause active sessions with no activity for a while
+    PauseIdle {
+        /// Idle hours before pausing (default: `auto_pause_hours` from config)
+        #[arg(long)]
+        hours: Option<u64>,
+
+        /// Keep running, checking every 10 minutes
+        #[arg(long)]
+        daemon: bool,
+
+        /// Serve /healthz and Prometheus /metrics on this address (e.g. 127.0.0.1:9464)
+        #[arg(long, value_name = "ADDR", requires = "daemon")]
+        metrics: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SessionUsageCommands {
+    /// Record tokens consumed by the current session
+    Record {
+        /// Input (prompt) tokens
+        #[arg(long, default_value = "0")]
+        tokens_in: u64,
+
+        /// Output (completion) tokens
+        #[arg(long, default_value = "0")]
+        tokens_out: u64,
+
+        /// Model that consumed them
+        #[arg(long)]
+        model: Option<String>,
+
+        /// What recorded it (e.g. the hook name)
+        #[arg(long)]
+        source: Option<String>,
+    },
+
+    /// Show token usage of the current session, split at compactions
+    Show {
+        /// List sessions by usage instead
+        #[arg(long)]
+        all: bool,
+
+        /// Include all projects (with --all)
+        #[arg(long)]
+        all_projects: bool,
+
+        /// Maximum sessions to list (with --all)
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
 }
 
 // ============================================================================
@@ -350,16 +805,58 @@ pub struct SaveArgs {
     /// Unique key for this context item
     pub key: String,
 
-    /// Value to save
-    pub value: String,
+    /// Value to save (`-` reads stdin, `@file` reads a file)
+    #[arg(required_unless_present_any = ["stdin", "from_clipboard", "edit"])]
+    pub value: Option<String>,
 
-    /// Category (reminder, decision, progress, note)
+    /// Category (reminder, decision, progress, note, or one from `sc category list`)
     #[arg(short, long, default_value = "note")]
     pub category: String,
 
-    /// Priority (high, normal, low)
-    #[arg(short, long, default_value = "normal")]
-    pub priority: String,
+    /// Priority (high, normal, low; default: the category's, usually normal)
+    #[arg(short, long)]
+    pub priority: Option<String>,
+
+    /// Claude Code transcript the item comes from (hooks pass their `transcript_path`)
+    #[arg(long, env = "SC_TRANSCRIPT")]
+    pub transcript: Option<PathBuf>,
+
+    /// Message in the transcript the item was decided at, 0-based (default: the last one)
+    #[arg(long, requires = "transcript")]
+    pub message_index: Option<usize>,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+}
+
+/// Alternative sources for a command's main value.
+#[derive(Args, Debug, Default, Clone)]
+pub struct InputArgs {
+    /// Read the value from stdin
+    #[arg(long, conflicts_with = "from_clipboard")]
+    pub stdin: bool,
+
+    /// Read the value from the system clipboard
+    #[arg(long)]
+    pub from_clipboard: bool,
+
+    /// Write the value in $EDITOR, starting from the current value
+    #[arg(long, conflicts_with_all = ["stdin", "from_clipboard"])]
+    pub edit: bool,
+}
+
+/// Which sub-project of a monorepo a command works in. By default, the one
+/// registered with `sc init --subproject` that contains the current
+/// directory.
+#[derive(Args, Debug, Default, Clone)]
+pub struct SubprojectArgs {
+    /// Sub-project path, relative to the project root
+    #[arg(long, conflicts_with = "root")]
+    pub subproject: Option<String>,
+
+    /// Work on the whole project, not the current sub-project
+    #[arg(long)]
+    pub root: bool,
 }
 
 #[derive(Args, Debug, Default)]
@@ -372,6 +869,15 @@ pub struct GetArgs {
     #[arg(short, long)]
     pub key: Option<String>,
 
+    /// With --key, also list the items, issues, plans and memories most
+    /// similar in meaning (needs embeddings)
+    #[arg(long, requires = "key")]
+    pub related: bool,
+
+    /// With --key, show where in the conversation the item was saved
+    #[arg(long, requires = "key", conflicts_with = "related")]
+    pub provenance: bool,
+
     /// Filter by category
     #[arg(short, long)]
     pub category: Option<String>,
@@ -380,6 +886,10 @@ pub struct GetArgs {
     #[arg(short = 'P', long)]
     pub priority: Option<String>,
 
+    /// Only items trusted at least this much (agent < imported < human)
+    #[arg(long, value_parser = parse_trust_level)]
+    pub min_trust: Option<crate::model::TrustLevel>,
+
     /// Search across all sessions (not just current)
     #[arg(long)]
     pub search_all_sessions: bool,
@@ -388,11 +898,13 @@ pub struct GetArgs {
     #[arg(long)]
     pub threshold: Option<f64>,
 
-    /// Semantic search mode (fast, quality, tiered)
+    /// Semantic search mode (fast, quality, tiered, rerank)
     ///
     /// - fast: Instant results using Model2Vec (lower accuracy)
     /// - quality: Slower but more accurate results using Ollama/HuggingFace
     /// - tiered: Fast candidates, quality re-ranking (default)
+    /// - rerank: Quality candidates re-scored by the cross-encoder or LLM
+    ///   configured under embeddings.rerank, within its latency budget
     #[arg(long, value_parser = parse_search_mode)]
     pub search_mode: Option<crate::embeddings::SearchMode>,
 
@@ -403,6 +915,36 @@ pub struct GetArgs {
     /// Maximum items to return
     #[arg(short, long, default_value = "50")]
     pub limit: usize,
+
+    /// Order of the items returned
+    #[arg(long, value_enum, default_value_t = ItemSort::Recent)]
+    pub sort: ItemSort,
+}
+
+/// Orders `sc get` can return items in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ItemSort {
+    /// Most recently created first
+    #[default]
+    Recent,
+    /// Most relevant first: recently updated, and often and recently read
+    Relevance,
+}
+
+/// Parse an issue estimate: hours, 0 or more
+fn parse_estimate(s: &str) -> std::result::Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(hours) if hours.is_finite() && hours >= 0.0 => Ok(hours),
+        _ => Err(format!("'{s}' is not a number of hours")),
+    }
+}
+
+/// Parse a routing policy (round-robin, skills)
+fn parse_routing_policy(s: &str) -> std::result::Result<crate::model::RoutingPolicy, String> {
+    s.parse().map_err(|e| match e {
+        crate::error::Error::InvalidArgument(msg) => msg,
+        e => e.to_string(),
+    })
 }
 
 /// Parse search mode from string
@@ -410,12 +952,38 @@ fn parse_search_mode(s: &str) -> std::result::Result<crate::embeddings::SearchMo
     s.parse()
 }
 
+/// Parse an embedded entity kind (items, issues, plans, memory)
+fn parse_embedding_entity(s: &str) -> std::result::Result<crate::embeddings::EmbeddingEntity, String> {
+    s.parse()
+}
+
+/// Parse a trust level (agent, imported, human)
+fn parse_trust_level(s: &str) -> std::result::Result<crate::model::TrustLevel, String> {
+    s.parse().map_err(|e| match e {
+        crate::error::Error::InvalidArgument(msg) => msg,
+        e => e.to_string(),
+    })
+}
+
+/// Parse an `updated_at` value: Unix milliseconds or an RFC 3339 timestamp.
+fn parse_updated_at(s: &str) -> std::result::Result<i64, String> {
+    s.parse::<i64>().or_else(|_| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.timestamp_millis())
+            .map_err(|_| format!("'{s}' is neither Unix milliseconds nor an RFC 3339 timestamp"))
+    })
+}
+
+fn parse_embedding_provider(s: &str) -> std::result::Result<crate::embeddings::EmbeddingProviderType, String> {
+    s.parse()
+}
+
 #[derive(Args, Debug)]
 pub struct UpdateArgs {
     /// Key of the item to update
     pub key: String,
 
-    /// New value
+    /// New value (`-` reads stdin, `@file` reads a file)
     #[arg(long)]
     pub value: Option<String>,
 
@@ -430,6 +998,19 @@ pub struct UpdateArgs {
     /// New channel
     #[arg(long)]
     pub channel: Option<String>,
+
+    /// New trust level (agent, imported, human), e.g. `human` once you
+    /// have checked an agent's note
+    #[arg(long, value_parser = parse_trust_level)]
+    pub trust: Option<crate::model::TrustLevel>,
+
+    /// Fail with CONFLICT unless `updated_at` still equals this (Unix ms or
+    /// RFC 3339, as printed by --json), so concurrent edits aren't lost
+    #[arg(long, value_parser = parse_updated_at)]
+    pub if_updated_at: Option<i64>,
+
+    #[command(flatten)]
+    pub input: InputArgs,
 }
 
 #[derive(Subcommand, Debug)]
@@ -455,6 +1036,76 @@ pub enum TagCommands {
     },
 }
 
+/// Arguments for scheduling a reminder (`sc remind <text>`).
+#[derive(Args, Debug)]
+pub struct RemindArgs {
+    /// What to be reminded of
+    #[arg(required = true)]
+    pub text: Option<String>,
+
+    /// When it comes due: YYYY-MM-DD (start of the day), YYYY-MM-DD HH:MM, or RFC 3339
+    #[arg(long, required_unless_present = "within", conflicts_with = "within")]
+    pub at: Option<String>,
+
+    /// When it comes due, from now: a span like 30m, 4h, 3d or 2w
+    #[arg(long = "in", value_name = "SPAN")]
+    pub within: Option<String>,
+
+    /// Key of the reminder item (default: remind- and the text's first words)
+    #[arg(short, long)]
+    pub key: Option<String>,
+
+    /// Priority (high, normal, low; default: the reminder category's)
+    #[arg(short, long)]
+    pub priority: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RemindCommands {
+    /// Mark a reminder done so it stops showing up
+    Done {
+        /// ID or key of the reminder
+        id: String,
+    },
+
+    /// List the project's open reminders, due first
+    List {
+        /// Include reminders marked done
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReviewCommands {
+    /// List decisions saved by agents that no one has reviewed yet
+    Queue {
+        /// Include every session of the project, not just the current one
+        #[arg(long)]
+        all_sessions: bool,
+    },
+
+    /// Approve a decision: prime stops flagging it and its trust becomes human
+    Approve {
+        /// Key of the decision
+        key: String,
+
+        /// Note kept on the audit event
+        #[arg(long)]
+        comment: Option<String>,
+    },
+
+    /// Reject a decision: prime leaves it out
+    Reject {
+        /// Key of the decision
+        key: String,
+
+        /// Why, kept on the audit event
+        #[arg(long)]
+        comment: Option<String>,
+    },
+}
+
 // ============================================================================
 // Issue Commands
 // ============================================================================
@@ -471,6 +1122,15 @@ pub enum IssueCommands {
     Show {
         /// Issue ID (short or full)
         id: String,
+
+        /// List every worklog entry
+        #[arg(long)]
+        logs: bool,
+
+        /// List the items, issues, plans and memories most similar in
+        /// meaning (needs embeddings)
+        #[arg(long)]
+        related: bool,
     },
 
     /// Update an issue
@@ -490,6 +1150,10 @@ pub enum IssueCommands {
     Claim {
         /// Issue IDs (one or more)
         ids: Vec<String>,
+
+        /// Claim even if it breaks the claim policy
+        #[arg(long)]
+        force: bool,
     },
 
     /// Release issue(s)
@@ -498,6 +1162,46 @@ pub enum IssueCommands {
         ids: Vec<String>,
     },
 
+    /// Watch issue(s) so their activity shows up in `sc inbox`
+    Watch {
+        /// Issue IDs (one or more)
+        ids: Vec<String>,
+
+        /// Stop watching instead
+        #[arg(long)]
+        stop: bool,
+    },
+
+    /// Comment on an issue (`@actor` mentions notify that actor)
+    Comment {
+        /// Issue ID
+        id: String,
+
+        /// Comment text
+        text: String,
+    },
+
+    /// Add a worklog entry (what was done or found), optionally logging hours
+    Log {
+        /// Issue ID
+        id: String,
+
+        /// What was done
+        text: String,
+
+        /// Hours spent, recorded as a time entry linked to the issue
+        #[arg(long)]
+        hours: Option<f64>,
+
+        /// Work date for the time entry (YYYY-MM-DD, defaults to today)
+        #[arg(long, requires = "hours")]
+        date: Option<String>,
+
+        /// Billing period for the time entry
+        #[arg(long, requires = "hours")]
+        period: Option<String>,
+    },
+
     /// Delete issue(s)
     Delete {
         /// Issue IDs (one or more)
@@ -543,20 +1247,84 @@ pub enum IssueCommands {
         limit: usize,
     },
 
-    /// Get next block of issues and claim them
+    /// Unfinished issues in dependency order, grouped into waves that can be
+    /// worked on in parallel
+    Waves,
+
+    /// Longest chain of dependent open issues under an epic, by their estimates
+    CriticalPath {
+        /// Epic ID
+        epic: String,
+
+        /// Hours to count for an issue without an estimate
+        #[arg(long, default_value = "1", value_parser = parse_estimate)]
+        default_hours: f64,
+    },
+
+    /// Get next block of issues and claim them, within the routing rules
     NextBlock {
         /// Number of issues to claim
         #[arg(short, long, default_value = "3")]
         count: usize,
+
+        /// Claim even if it breaks the claim policy
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Deal ready issues out to agents and claim them on their behalf
+    Route {
+        /// Issues to hand out in total (default: one per agent)
+        #[arg(short, long)]
+        count: Option<usize>,
+
+        /// round-robin or skills (default: from the routing config)
+        #[arg(long, value_parser = parse_routing_policy)]
+        policy: Option<crate::model::RoutingPolicy>,
+
+        /// Agents to route to (default: those in the routing config, else
+        /// every registered agent and model)
+        #[arg(long, value_delimiter = ',')]
+        agents: Vec<String>,
     },
 
     /// Create multiple issues at once with dependencies
+    ///
+    /// The whole batch is checked first (unknown `$N` references, missing
+    /// parents, duplicate titles, dependency cycles) and then created in
+    /// one transaction, so it lands completely or not at all.
     Batch {
         /// JSON input containing issues array, dependencies, and optional planId
+        /// (`-` reads stdin; `@path` or a plain path reads a file)
         #[arg(long)]
         json_input: String,
     },
 
+    /// Import issues from a Jira CSV, Trello JSON, or markdown backlog export
+    Import {
+        /// Export file
+        file: PathBuf,
+
+        /// Export format (`--format` also works here)
+        #[arg(short = 'f', long, value_enum)]
+        from: crate::import::ImportFormat,
+    },
+
+    /// Export this project's issues (e.g. as a markdown backlog)
+    Export {
+        /// Output format (`--format` also works here)
+        #[arg(short = 'f', long, value_enum, default_value = "backlog-md")]
+        to: crate::import::ExportFormat,
+
+        /// Columns of a CSV report, in order (default: `id,title,status,priority,type,assignee,labels,created_at`)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        columns: Option<Vec<crate::import::csv_report::Column>>,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
     /// Count issues grouped by a field
     Count {
         /// Group by: status, type, priority, assignee
@@ -581,6 +1349,27 @@ pub enum IssueCommands {
         #[arg(short, long, default_value = "50")]
         limit: usize,
     },
+
+    /// Go through untriaged issues one at a time, setting priority, type,
+    /// labels and assignee (prompts on a terminal; reads JSONL decisions
+    /// from piped stdin)
+    Triage {
+        /// Label marking an issue as untriaged; removed once it's triaged
+        #[arg(long, default_value = "needs-triage")]
+        label: String,
+
+        /// Also queue issues in this status (e.g. a workflow's `new`)
+        #[arg(short, long)]
+        status: Option<String>,
+
+        /// Maximum issues to go through
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+
+        /// Print the queue instead of triaging it
+        #[arg(long)]
+        list: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -644,11 +1433,11 @@ pub struct IssueCreateArgs {
     /// Issue title
     pub title: String,
 
-    /// Issue description
+    /// Issue description (`-` reads stdin, `@file` reads a file)
     #[arg(short, long)]
     pub description: Option<String>,
 
-    /// Implementation details or notes
+    /// Implementation details or notes (`-` or `@file` also accepted)
     #[arg(long)]
     pub details: Option<String>,
 
@@ -672,9 +1461,20 @@ pub struct IssueCreateArgs {
     #[arg(short, long, value_delimiter = ',')]
     pub labels: Option<Vec<String>>,
 
+    /// Estimated hours of work (e.g. 4, 1.5), for critical path analysis
+    #[arg(long, value_parser = parse_estimate)]
+    pub estimate: Option<f64>,
+
     /// Import issues from a JSONL file (one JSON object per line)
     #[arg(short, long)]
     pub file: Option<PathBuf>,
+
+    /// Read the description from stdin, the clipboard or $EDITOR
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub scope: SubprojectArgs,
 }
 
 #[derive(Args, Debug, Default)]
@@ -774,6 +1574,9 @@ pub struct IssueListArgs {
     /// Maximum issues to return
     #[arg(short, long, default_value = "50")]
     pub limit: usize,
+
+    #[command(flatten)]
+    pub scope: SubprojectArgs,
 }
 
 #[derive(Args, Debug)]
@@ -785,11 +1588,11 @@ pub struct IssueUpdateArgs {
     #[arg(long)]
     pub title: Option<String>,
 
-    /// New description
+    /// New description (`-` reads stdin, `@file` reads a file)
     #[arg(short, long)]
     pub description: Option<String>,
 
-    /// New details
+    /// New details (`-` or `@file` also accepted)
     #[arg(long)]
     pub details: Option<String>,
 
@@ -797,6 +1600,10 @@ pub struct IssueUpdateArgs {
     #[arg(short, long)]
     pub status: Option<String>,
 
+    /// Reason for the status change (stored as close reason)
+    #[arg(short = 'r', long)]
+    pub reason: Option<String>,
+
     /// New priority
     #[arg(short, long)]
     pub priority: Option<i32>,
@@ -812,12 +1619,46 @@ pub struct IssueUpdateArgs {
     /// New plan ID
     #[arg(long)]
     pub plan: Option<String>,
+
+    /// New estimate in hours (0 clears it)
+    #[arg(long, value_parser = parse_estimate)]
+    pub estimate: Option<f64>,
+
+    /// Fail with CONFLICT unless `updated_at` still equals this (Unix ms or
+    /// RFC 3339, as printed by --json), so concurrent edits aren't lost
+    #[arg(long, value_parser = parse_updated_at)]
+    pub if_updated_at: Option<i64>,
+
+    /// Read the description from stdin, the clipboard or $EDITOR
+    #[command(flatten)]
+    pub input: InputArgs,
 }
 
 // ============================================================================
 // Checkpoint Commands
 // ============================================================================
 
+#[derive(Subcommand, Debug)]
+pub enum CompactionCommands {
+    /// Estimate context usage from a transcript and compare it to the threshold
+    ///
+    /// Exits 0 when under the warning level, 12 when within 10 points of the
+    /// threshold, and 13 at or past it, for hooks and statuslines.
+    Check {
+        /// Claude Code transcript (.jsonl); defaults to the project's newest
+        #[arg(long)]
+        transcript: Option<PathBuf>,
+
+        /// Compaction threshold percentage (default: `compaction.threshold`, or 70)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(50..=90))]
+        threshold: Option<u8>,
+
+        /// Context window in tokens (default: `compaction.context_window`, or 200000)
+        #[arg(long)]
+        context_window: Option<u64>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum CheckpointCommands {
     /// Create a checkpoint
@@ -887,69 +1728,228 @@ pub enum CheckpointCommands {
         id: String,
     },
 
-    /// Add items to an existing checkpoint
-    AddItems {
-        /// Checkpoint ID
-        id: String,
+    /// Add items to an existing checkpoint
+    AddItems {
+        /// Checkpoint ID
+        id: String,
+
+        /// Context item keys to add (comma-separated)
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        keys: Vec<String>,
+    },
+
+    /// Remove items from a checkpoint
+    RemoveItems {
+        /// Checkpoint ID
+        id: String,
+
+        /// Context item keys to remove (comma-separated)
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        keys: Vec<String>,
+    },
+
+    /// List items in a checkpoint
+    Items {
+        /// Checkpoint ID
+        id: String,
+    },
+
+    /// Create due automatic checkpoints of active sessions now
+    Auto {
+        /// `hourly` or `daily` (default: `auto_checkpoint.interval` from config)
+        #[arg(long)]
+        interval: Option<String>,
+
+        /// Keep running, checking once a minute (for a service or tmux pane)
+        #[arg(long)]
+        daemon: bool,
+
+        /// Serve /healthz and Prometheus /metrics on this address (e.g. 127.0.0.1:9464)
+        #[arg(long, value_name = "ADDR", requires = "daemon")]
+        metrics: Option<String>,
+    },
+
+    /// Delete old automatic checkpoints, keeping the newest per session
+    Prune {
+        /// Automatic checkpoints to keep per session (default: `auto_checkpoint.keep`, or 24)
+        #[arg(long)]
+        keep: Option<usize>,
+
+        /// Only prune this session
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+// ============================================================================
+// Memory Commands (Project-level persistent storage)
+// ============================================================================
+
+#[derive(Subcommand, Debug)]
+pub enum MemoryCommands {
+    /// Save a memory item
+    Save {
+        /// Key
+        key: String,
+
+        /// Value (`-` reads stdin, `@file` reads a file)
+        #[arg(required_unless_present_any = ["stdin", "from_clipboard", "edit"])]
+        value: Option<String>,
+
+        /// Category (command, config, note)
+        #[arg(short, long, default_value = "command")]
+        category: String,
+
+        #[command(flatten)]
+        input: InputArgs,
+
+        #[command(flatten)]
+        scope: SubprojectArgs,
+    },
+
+    /// Get a memory item
+    Get {
+        /// Key
+        key: String,
+    },
+
+    /// List memory items
+    List {
+        /// Filter by category
+        #[arg(short, long)]
+        category: Option<String>,
+    },
+
+    /// Delete a memory item
+    Delete {
+        /// Key
+        key: String,
+    },
+
+    /// Flag memory unused for months, near-identical values under different
+    /// keys, and commands the repo no longer has
+    Audit {
+        /// Months without a read or save before an entry counts as unused
+        #[arg(long, default_value_t = 3)]
+        months: u32,
+
+        /// Go through the flagged entries, asking before deleting each
+        #[arg(long)]
+        prune: bool,
+    },
+
+    /// Propose `command` entries from shell history and project docs
+    Harvest {
+        /// Read shell history ($HISTFILE, else bash, zsh and fish history)
+        #[arg(long)]
+        from_history: bool,
+
+        /// Read shell blocks in README, CONTRIBUTING, DEVELOPMENT and docs/
+        #[arg(long)]
+        from_readme: bool,
+
+        /// History file to read instead of the shell's
+        #[arg(long, value_name = "PATH")]
+        history_file: Option<PathBuf>,
+
+        /// Times a history command must have been run to be proposed
+        #[arg(long, default_value_t = 2)]
+        min_count: usize,
+
+        /// Most commands to propose
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Save every proposal without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+// ============================================================================
+// Category Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug)]
+pub enum CategoryCommands {
+    /// Register a category, or change the settings of one (built-ins included)
+    Add {
+        /// Category name (lowercase letters, digits, `-` and `_`)
+        name: String,
+
+        /// What the category is for
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Color of its label in terminal output (red, green, yellow, blue, magenta, cyan, white, none)
+        #[arg(long)]
+        color: Option<String>,
+
+        /// Priority of items saved without --priority (high, normal, low)
+        #[arg(short, long)]
+        priority: Option<String>,
+
+        /// Whether `sc prime` includes its items (include, exclude)
+        #[arg(long)]
+        prime: Option<String>,
 
-        /// Context item keys to add (comma-separated)
-        #[arg(short, long, value_delimiter = ',', required = true)]
-        keys: Vec<String>,
+        /// Weight in `sc prime --smart` scoring, 0-10 (decision 2, reminder 1.5, progress 1, note 0.5)
+        #[arg(long)]
+        weight: Option<f64>,
+
+        /// Store values encrypted with a key from the OS keychain (on, off)
+        #[arg(long)]
+        encrypt: Option<String>,
     },
 
-    /// Remove items from a checkpoint
-    RemoveItems {
-        /// Checkpoint ID
-        id: String,
+    /// List built-in and registered categories
+    List,
 
-        /// Context item keys to remove (comma-separated)
-        #[arg(short, long, value_delimiter = ',', required = true)]
-        keys: Vec<String>,
+    /// Rename a registered category, moving the project's items to the new name
+    Rename {
+        /// Current name
+        old: String,
+
+        /// New name
+        new: String,
     },
 
-    /// List items in a checkpoint
-    Items {
-        /// Checkpoint ID
-        id: String,
+    /// Unregister a category, or reset a built-in one to its defaults
+    Remove {
+        /// Category name
+        name: String,
     },
 }
 
 // ============================================================================
-// Memory Commands (Project-level persistent storage)
+// Git Commands
 // ============================================================================
 
 #[derive(Subcommand, Debug)]
-pub enum MemoryCommands {
-    /// Save a memory item
-    Save {
-        /// Key
-        key: String,
+pub enum GitCommands {
+    /// Install the repository's `post-checkout` hook, which runs `sc git hook checkout`
+    InstallHooks,
 
-        /// Value
-        value: String,
-
-        /// Category (command, config, note)
-        #[arg(short, long, default_value = "command")]
-        category: String,
+    /// Entry points run by the installed hooks
+    Hook {
+        #[command(subcommand)]
+        command: GitHookCommands,
     },
+}
 
-    /// Get a memory item
-    Get {
-        /// Key
-        key: String,
-    },
+#[derive(Subcommand, Debug)]
+pub enum GitHookCommands {
+    /// After a branch switch: checkpoint the channel being left and move the
+    /// session to the new branch's channel
+    Checkout {
+        /// Previous HEAD (passed by git)
+        previous: Option<String>,
 
-    /// List memory items
-    List {
-        /// Filter by category
-        #[arg(short, long)]
-        category: Option<String>,
-    },
+        /// New HEAD (passed by git)
+        new: Option<String>,
 
-    /// Delete a memory item
-    Delete {
-        /// Key
-        key: String,
+        /// 1 for a branch checkout, 0 for a file checkout (passed by git)
+        kind: Option<String>,
     },
 }
 
@@ -964,6 +1964,11 @@ pub enum SyncCommands {
         /// Force export even if JSONL is newer
         #[arg(long)]
         force: bool,
+
+        /// Also write embedding vectors to embeddings.jsonl, so an import
+        /// elsewhere has semantic search without a backfill
+        #[arg(long)]
+        include_embeddings: bool,
     },
 
     /// Import from JSONL
@@ -971,11 +1976,37 @@ pub enum SyncCommands {
         /// Force import even with conflicts
         #[arg(long)]
         force: bool,
+
+        /// Merge strategy per entity type, e.g.
+        /// `issues=prefer-local,items=prefer-newer` (overrides
+        /// `sync_strategies` in config)
+        #[arg(long, value_name = "SPEC")]
+        strategy: Option<String>,
+
+        /// Import nothing if any line is invalid (default: skip bad lines
+        /// and report them)
+        #[arg(long)]
+        strict: bool,
+
+        /// Reject lines with a string field longer than this many bytes
+        #[arg(long, value_name = "BYTES", default_value_t = crate::sync::DEFAULT_MAX_FIELD_LEN)]
+        max_field_len: usize,
     },
 
     /// Show sync status
     Status,
 
+    /// Set up a project from the JSONL files in a fresh checkout
+    Clone,
+
+    /// Check that the JSONL export matches the database
+    Verify {
+        /// Re-export the records that differ (lines the database has never
+        /// seen are kept for import)
+        #[arg(long)]
+        fix: bool,
+    },
+
     /// Push local JSONL exports to remote host via SCP
     Push {
         /// Force export before push
@@ -1040,6 +2071,16 @@ pub enum ProjectCommands {
         id: String,
     },
 
+    /// Show a health overview (issues, sessions, embeddings, sync, checkpoints)
+    Status {
+        /// Project ID or path (default: current project)
+        id: Option<String>,
+
+        /// Days without updates before an open issue counts as stale
+        #[arg(long, default_value = "14")]
+        stale_days: u32,
+    },
+
     /// Update a project
     Update(ProjectUpdateArgs),
 
@@ -1052,6 +2093,24 @@ pub enum ProjectCommands {
         #[arg(short, long)]
         force: bool,
     },
+
+    /// Move data stored under one path spelling to another and alias the old path
+    MergePaths {
+        /// Path to merge away, exactly as stored (e.g. with a trailing slash or via a symlink)
+        from: String,
+
+        /// Path to keep
+        into: String,
+    },
+
+    /// Re-key all data after moving a project directory on disk
+    Move {
+        /// Path the project was registered at
+        old_path: String,
+
+        /// Where the project lives now
+        new_path: String,
+    },
 }
 
 #[derive(Args, Debug)]
@@ -1123,8 +2182,39 @@ pub enum PlanCommands {
     /// Update a plan
     Update(PlanUpdateArgs),
 
-    /// Capture a plan from an AI coding agent's plan file
+    /// Show the revision history of a plan
+    History {
+        /// Plan ID
+        id: String,
+    },
+
+    /// Show a unified diff between plan revisions
+    Diff {
+        /// Plan ID
+        id: String,
+
+        /// Revisions to compare: "3..5", "3.." (to latest), or "5" (against 4).
+        /// Defaults to the latest change.
+        #[arg(long)]
+        rev: Option<String>,
+    },
+
+    /// Restore a plan's title, content, and criteria from an earlier revision
+    Revert {
+        /// Plan ID
+        id: String,
+
+        /// Revision to restore
+        #[arg(long)]
+        rev: i64,
+    },
+
+    /// Capture a plan from a markdown file or an AI coding agent's plan file
     Capture {
+        /// Markdown file to capture (e.g. docs/prd.md); skips agent discovery
+        #[arg(conflicts_with = "file")]
+        path: Option<PathBuf>,
+
         /// Only look in a specific agent's directory (claude, gemini, opencode, cursor)
         #[arg(long)]
         agent: Option<String>,
@@ -1137,6 +2227,22 @@ pub enum PlanCommands {
         #[arg(long)]
         file: Option<PathBuf>,
     },
+
+    /// Re-read captured source files and update plans whose files changed
+    Refresh {
+        /// Only refresh this plan (default: all captured plans in the project)
+        id: Option<String>,
+    },
+
+    /// Longest chain of dependent open issues in a plan, by their estimates
+    CriticalPath {
+        /// Plan ID
+        id: String,
+
+        /// Hours to count for an issue without an estimate
+        #[arg(long, default_value = "1", value_parser = parse_estimate)]
+        default_hours: f64,
+    },
 }
 
 #[derive(Args, Debug)]
@@ -1144,7 +2250,7 @@ pub struct PlanCreateArgs {
     /// Plan title
     pub title: String,
 
-    /// Plan content (markdown PRD/spec)
+    /// Plan content (markdown PRD/spec; `-` reads stdin, `@file` reads a file)
     #[arg(short, long)]
     pub content: Option<String>,
 
@@ -1159,6 +2265,10 @@ pub struct PlanCreateArgs {
     /// Bind to a specific session (default: auto-resolve from TTY)
     #[arg(long)]
     pub session: Option<String>,
+
+    /// Read the content from stdin, the clipboard or $EDITOR
+    #[command(flatten)]
+    pub input: InputArgs,
 }
 
 #[derive(Args, Debug)]
@@ -1170,7 +2280,7 @@ pub struct PlanUpdateArgs {
     #[arg(long)]
     pub title: Option<String>,
 
-    /// New content
+    /// New content (`-` reads stdin, `@file` reads a file)
     #[arg(short, long)]
     pub content: Option<String>,
 
@@ -1181,6 +2291,15 @@ pub struct PlanUpdateArgs {
     /// New success criteria
     #[arg(long)]
     pub success_criteria: Option<String>,
+
+    /// Fail with CONFLICT unless `updated_at` still equals this (Unix ms or
+    /// RFC 3339, as printed by --json), so concurrent edits aren't lost
+    #[arg(long, value_parser = parse_updated_at)]
+    pub if_updated_at: Option<i64>,
+
+    /// Read the content from stdin, the clipboard or $EDITOR
+    #[command(flatten)]
+    pub input: InputArgs,
 }
 
 // ============================================================================
@@ -1217,6 +2336,10 @@ pub enum EmbeddingsCommands {
         /// API token (for HuggingFace)
         #[arg(long)]
         token: Option<String>,
+
+        /// Failover chain, most preferred first (e.g. ollama,huggingface,model2vec)
+        #[arg(long, value_delimiter = ',', value_parser = parse_embedding_provider)]
+        providers: Vec<crate::embeddings::EmbeddingProviderType>,
     },
 
     /// Backfill embeddings for existing context items
@@ -1232,6 +2355,19 @@ pub enum EmbeddingsCommands {
         /// Force regeneration of existing embeddings
         #[arg(long)]
         force: bool,
+
+        /// What to embed: items, issues, plans, memory (comma-separated; default all)
+        #[arg(long, value_delimiter = ',', value_parser = parse_embedding_entity)]
+        entities: Vec<crate::embeddings::EmbeddingEntity>,
+
+        /// Parallel provider requests (default: `embeddings.concurrency` in
+        /// config, else 4 for Ollama and Hugging Face)
+        #[arg(short = 'j', long)]
+        concurrency: Option<usize>,
+
+        /// Call the provider even for text already in the embedding cache
+        #[arg(long)]
+        no_cache: bool,
     },
 
     /// Test embedding provider connectivity
@@ -1251,6 +2387,10 @@ pub enum EmbeddingsCommands {
         /// Run silently (no output)
         #[arg(long)]
         quiet: bool,
+
+        /// Call the provider even for text already in the embedding cache
+        #[arg(long)]
+        no_cache: bool,
     },
 
     /// Upgrade items with fast embeddings to quality embeddings
@@ -1266,6 +2406,38 @@ pub enum EmbeddingsCommands {
         /// Session ID to upgrade (defaults to all sessions)
         #[arg(short, long)]
         session: Option<String>,
+
+        /// Call the provider even for text already in the embedding cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Move stored embeddings to the active provider and model
+    ///
+    /// Embeddings from different models can't be searched together, so
+    /// after a provider or model change search refuses to run until this
+    /// is done. Other models' embeddings are deleted (or archived with
+    /// --keep), archived embeddings of the active model are restored, and
+    /// the rest is left for `sc embeddings backfill`.
+    Migrate {
+        /// Archive the old embeddings instead of deleting them
+        #[arg(long)]
+        keep: bool,
+    },
+
+    /// Compare providers' speed and retrieval quality on this project
+    ///
+    /// Each sampled context item's key is used as a query for its value.
+    /// Every available provider embeds the sample, and the table shows
+    /// per-item latency, throughput, recall@5 and MRR side by side.
+    Benchmark {
+        /// Context items to sample (most recent first)
+        #[arg(short, long, default_value = "50")]
+        sample: usize,
+
+        /// Providers to compare (default: every available one)
+        #[arg(long, value_delimiter = ',', value_parser = parse_embedding_provider)]
+        providers: Vec<crate::embeddings::EmbeddingProviderType>,
     },
 }
 
@@ -1312,6 +2484,13 @@ pub enum ConfigCommands {
         #[command(subcommand)]
         command: ConfigRemoteCommands,
     },
+
+    /// Check config.json for unknown keys, type errors, and deprecated fields
+    Validate {
+        /// Config file to check (default: ~/.savecontext/config.json)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -1493,3 +2672,297 @@ pub struct TimeUpdateArgs {
     #[arg(long)]
     pub status: Option<String>,
 }
+
+// ============================================================================
+// Database Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DbCommands {
+    /// List indexes with planner statistics and suggest missing ones from usage stats
+    Indexes {
+        /// Run ANALYZE first to refresh planner statistics
+        #[arg(long)]
+        analyze: bool,
+
+        /// Usage window for suggestions, in days
+        #[arg(long, default_value = "30")]
+        days: u32,
+    },
+
+    /// Storage analytics: table sizes, largest items and sessions, embeddings, WAL, growth since last run
+    Stats {
+        /// Largest items and sessions to list
+        #[arg(long, default_value = "5")]
+        top: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BenchCommands {
+    /// Time per-command startup costs: DB open, schema check, session and project resolution
+    Startup {
+        /// Timed runs per phase
+        #[arg(short = 'n', long, default_value = "20")]
+        iterations: usize,
+
+        /// Fail if the `sc status` process median exceeds this many milliseconds
+        #[arg(long)]
+        max_ms: Option<f64>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TrashCommands {
+    /// List trashed items, issues and sessions, newest first
+    List {
+        /// Only this type: `context_item`, `issue` or `session`
+        #[arg(short = 't', long = "type")]
+        entity_type: Option<String>,
+
+        /// Maximum entries to show
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Restore a trash entry by trash ID, the deleted item, issue or session ID, or an item key
+    Restore {
+        /// Trash ID, original ID, issue short ID, or item key
+        id: String,
+    },
+
+    /// Permanently delete trash entries past the retention period
+    Empty {
+        /// Delete every entry, not just expired ones
+        #[arg(long, conflicts_with = "older_than")]
+        all: bool,
+
+        /// Delete entries trashed more than this many days ago
+        #[arg(long)]
+        older_than: Option<u64>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EventsCommands {
+    /// Delete events past the retention policy; creates, deletes and comments are kept
+    Prune {
+        /// Prune events older than this many days
+        #[arg(long)]
+        days: Option<u64>,
+
+        /// Keep only the newest N events of each entity
+        #[arg(long)]
+        max_per_entity: Option<usize>,
+
+        /// Write pruned events to ~/.savecontext/archive/ as compressed JSONL first
+        #[arg(long, conflicts_with = "no_archive")]
+        archive: bool,
+
+        /// Don't archive, even if `event_retention.archive` is set
+        #[arg(long)]
+        no_archive: bool,
+
+        /// Archive into this directory instead
+        #[arg(long, conflicts_with = "no_archive")]
+        archive_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ActorCommands {
+    /// Register an actor, or update its display name and type
+    Register {
+        /// Canonical actor ID, recorded on events and assignments
+        id: String,
+
+        /// Display name
+        #[arg(long)]
+        display_name: Option<String>,
+
+        /// human, agent, or model (default: human)
+        #[arg(long = "type")]
+        actor_type: Option<String>,
+
+        /// Other names that should resolve to this actor
+        #[arg(long = "alias")]
+        aliases: Vec<String>,
+    },
+
+    /// Map other names (e.g. `alice@laptop`) to a registered actor
+    Alias {
+        /// Actor ID or an existing alias
+        actor: String,
+
+        /// Names to map
+        #[arg(required = true)]
+        aliases: Vec<String>,
+
+        /// Remove the aliases instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// List registered actors with their aliases
+    List,
+
+    /// Show the actor a name resolves to (default: you)
+    Show {
+        /// Actor ID or alias
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum TeamCommands {
+    /// Each actor's current session, claimed issues and last activity in this project
+    Status {
+        /// Also list actors with no claims or active sessions who were active this many hours back
+        #[arg(long, default_value = "24")]
+        hours: u32,
+
+        /// Minutes without activity after which an actor shows as idle
+        #[arg(long, default_value = "15")]
+        idle_minutes: u32,
+    },
+
+    /// Record that you are working in the current session (every command does this too)
+    Heartbeat,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PolicyCommands {
+    /// List actors holding more issues than allowed, or claims idle too long (exits 16 if any)
+    Check,
+
+    /// Release claims whose lease ran out (`claim_lease_minutes`)
+    Reap {
+        /// Keep running, checking every minute
+        #[arg(long)]
+        daemon: bool,
+
+        /// Serve /healthz and Prometheus /metrics on this address (e.g. 127.0.0.1:9464)
+        #[arg(long, value_name = "ADDR", requires = "daemon")]
+        metrics: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReportCommands {
+    /// Issues per status at the end of each day, rebuilt from the event log
+    Flow {
+        /// Days to cover, ending today
+        #[arg(long, default_value = "30")]
+        days: u32,
+    },
+
+    /// Markdown standup update: issues closed, decisions and checkpoints, and work in progress
+    Standup {
+        /// Start of the period: `yesterday`, `today`, a date (YYYY-MM-DD), or hours/days back (`12h`, `3d`)
+        #[arg(long, default_value = "yesterday")]
+        since: String,
+
+        /// Include everyone's work, not only the actor's (`--actor`, `me` for yourself)
+        #[arg(long)]
+        all: bool,
+
+        /// Rewrite the update with the `summarize` endpoint from config.json
+        #[arg(long)]
+        polish: bool,
+    },
+}
+
+// ============================================================================
+// Vault Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ExportCommands {
+    /// Write memory and decisions as markdown notes (Obsidian-compatible)
+    Vault {
+        /// Vault directory; notes go in `memory/` and `decisions/` below it
+        dir: PathBuf,
+    },
+
+    /// Write a full backup (database, config, JSONL of every project) as .tar.zst
+    Archive {
+        /// Archive file (default: savecontext-<time>.tar.zst)
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+
+    /// Write this project's sessions, items, issues, plans and tags as a
+    /// node/edge graph
+    Graph {
+        /// Graph format (`--format` also works here)
+        #[arg(short = 'f', long, value_enum, default_value = "json")]
+        to: GraphFormat,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Write a static site for this project: issue board, plan progress,
+    /// recent decisions and an activity timeline
+    Dashboard {
+        /// Directory for the site (created if missing)
+        #[arg(long, short)]
+        out: PathBuf,
+
+        /// Build from a project's sync files (e.g. `.savecontext/`) instead
+        /// of the database
+        #[arg(long)]
+        from: Option<PathBuf>,
+
+        /// Days of activity to show
+        #[arg(long, default_value = "30")]
+        days: u32,
+    },
+}
+
+/// Formats `sc export graph` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// `{"nodes": [...], "edges": [...]}`
+    Json,
+    /// `GraphML`, for Gephi, yEd, Cytoscape and the like
+    Graphml,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportCommands {
+    /// Apply edits from markdown notes written by `sc export vault`
+    Vault {
+        /// Vault directory passed to `sc export vault`
+        dir: PathBuf,
+
+        /// Apply notes even when the database changed since the export
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Restore a backup written by `sc export archive`
+    Archive {
+        /// Archive file
+        file: PathBuf,
+
+        /// Replace a database that already has data (and the config); the
+        /// old ones are kept with a `.before-restore` suffix
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ShareCommands {
+    /// A self-contained page of a session's context, checkpoints and linked issues
+    Session {
+        /// Session ID (default: the current session)
+        id: Option<String>,
+
+        /// HTML file to write (default: stdout)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+}
@@ -0,0 +1,225 @@
+//! The `sc shell` line protocol.
+//!
+//! Each input line is one command, written either as it would be typed
+//! after `sc` (`save auth "Use JWT" -c decision`), as a JSON array of
+//! arguments (`["save", "auth", "Use JWT", "-c", "decision"]`), which
+//! avoids quoting rules entirely, or as a JSON object carrying a request
+//! ID of the client's choosing (`{"id": 7, "args": ["get", "-s", "auth"]}`).
+//! Blank lines and `#` comments are skipped; `exit` or `quit` ends the
+//! session.
+//!
+//! Every reply ends with a `{"done": {...}}` line that repeats the request
+//! ID (`null` if none was given), so an editor plugin can match replies to
+//! requests without counting lines. `sc grep --stream` and `sc search
+//! --stream` write each result as its own `{"id": .., "result": {...}}`
+//! line as soon as it is found, for pickers that fill in while the search
+//! runs.
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// ID of the request being answered, set by the shell for [`emit`].
+static REQUEST_ID: Mutex<Option<Value>> = Mutex::new(None);
+
+/// One parsed input line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Request {
+    /// Blank line or comment
+    Skip,
+    /// `exit` / `quit`
+    Quit,
+    /// Arguments to run, without the leading `sc`, and the client's ID
+    /// for the request
+    Args { id: Option<Value>, args: Vec<String> },
+    /// The line could not be parsed
+    Invalid(String),
+}
+
+/// Parse one input line.
+#[must_use]
+pub fn parse_request(line: &str) -> Request {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Request::Skip;
+    }
+    if matches!(line, "exit" | "quit") {
+        return Request::Quit;
+    }
+
+    let parsed = if line.starts_with('{') {
+        parse_object(line)
+    } else if line.starts_with('[') {
+        serde_json::from_str::<Vec<String>>(line)
+            .map(|args| (None, args))
+            .map_err(|e| format!("Invalid JSON argument array: {e}"))
+    } else {
+        split_words(line).map(|args| (None, args))
+    };
+    match parsed {
+        Ok((id, mut args)) => {
+            if args.first().is_some_and(|a| a == "sc") {
+                args.remove(0);
+            }
+            if args.is_empty() {
+                Request::Skip
+            } else {
+                Request::Args { id, args }
+            }
+        }
+        Err(e) => Request::Invalid(e),
+    }
+}
+
+/// A request in object form.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectRequest {
+    id: Option<Value>,
+    args: Vec<String>,
+}
+
+fn parse_object(line: &str) -> Result<(Option<Value>, Vec<String>), String> {
+    let request: ObjectRequest =
+        serde_json::from_str(line).map_err(|e| format!("Invalid JSON request: {e}"))?;
+    match request.id {
+        Some(ref id) if !(id.is_string() || id.is_i64() || id.is_u64()) => {
+            Err(format!("Request id must be a string or an integer, got {id}"))
+        }
+        id => Ok((id, request.args)),
+    }
+}
+
+/// Set the ID that [`emit`] tags results with; `None` outside a request.
+pub fn set_request_id(id: Option<Value>) {
+    *REQUEST_ID.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = id;
+}
+
+/// The ID of the request being answered.
+#[must_use]
+pub fn request_id() -> Option<Value> {
+    REQUEST_ID.lock().unwrap_or_else(std::sync::PoisonError::into_inner).clone()
+}
+
+/// Write one streamed result line, `{"id": .., "result": ..}`, and flush
+/// it so the reader sees it straight away. The ID is left out outside
+/// `sc shell`.
+///
+/// # Errors
+///
+/// Returns an error if the result cannot be serialized or stdout written.
+pub fn emit(result: &impl serde::Serialize) -> crate::Result<()> {
+    let mut line = serde_json::json!({ "result": result });
+    if let Some(id) = request_id() {
+        line["id"] = id;
+    }
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{line}")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Split a command line into words with POSIX shell quoting: single quotes
+/// are literal, double quotes allow `\"`, `\\`, `\$` and `` \` `` escapes,
+/// and a backslash outside quotes escapes the next character. No expansion
+/// of variables, globs or `~` takes place.
+///
+/// # Errors
+///
+/// Returns a message for an unterminated quote or trailing backslash.
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.push(chars.next().ok_or("Trailing backslash")?);
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words(r#"save key "multi word \"value\"" -c 'it''s' a\ b """#).unwrap(),
+            vec!["save", "key", "multi word \"value\"", "-c", "its", "a b", ""]
+        );
+        assert_eq!(split_words(r#""C:\path""#).unwrap(), vec![r"C:\path"]);
+        assert!(split_words("save 'open").is_err());
+        assert!(split_words("save x\\").is_err());
+    }
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(parse_request("   "), Request::Skip);
+        assert_eq!(parse_request("# note"), Request::Skip);
+        assert_eq!(parse_request("quit"), Request::Quit);
+        assert_eq!(
+            parse_request("sc issue list"),
+            Request::Args { id: None, args: vec!["issue".to_string(), "list".to_string()] }
+        );
+        assert_eq!(
+            parse_request(r#"["save", "k", "a \"quoted\" value"]"#),
+            Request::Args { id: None, args: vec!["save".to_string(), "k".to_string(), "a \"quoted\" value".to_string()] }
+        );
+        assert!(matches!(parse_request("[1, 2]"), Request::Invalid(_)));
+        assert_eq!(
+            parse_request(r#"{"id": "r7", "args": ["sc", "status"]}"#),
+            Request::Args { id: Some(Value::from("r7")), args: vec!["status".to_string()] }
+        );
+        assert!(matches!(parse_request(r#"{"id": 1.5, "args": ["status"]}"#), Request::Invalid(_)));
+        assert!(matches!(parse_request(r#"{"id": 1, "argv": ["status"]}"#), Request::Invalid(_)));
+    }
+}
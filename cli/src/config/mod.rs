@@ -12,16 +12,24 @@
 //! This allows the CLI and MCP server to share the same data, while each project
 //! maintains its own git-friendly JSONL exports.
 
+mod paths;
 pub mod plan_discovery;
+mod project_cache;
+pub mod schema;
 mod status_cache;
+mod subproject;
+mod worktree;
 
+pub use paths::{normalize_path, path_within, paths_equal};
 pub use status_cache::{
-    bind_session_to_terminal, clear_status_cache, current_session_id, read_status_cache,
-    write_status_cache, StatusCacheEntry,
+    bind_session_to_terminal, cache_reminders, clear_status_cache, current_session_id,
+    read_status_cache, write_status_cache, CachedReminder, StatusCacheEntry,
 };
+pub use subproject::{in_subproject, resolve_subproject, subproject_path};
+pub use worktree::{current_worktree, linked_worktree, Worktree};
 
 use crate::error::{Error, Result};
-use crate::model::Project;
+use crate::model::{Project, SessionStatus};
 use crate::storage::SqliteStorage;
 use tracing::{debug, trace};
 
@@ -39,9 +47,25 @@ use std::path::{Path, PathBuf};
 /// Resolution strategy:
 /// 1. Check the **git root** first — if the git root has `.savecontext/`, use it.
 ///    This prevents subdirectory export dirs from shadowing the real project root.
+///    A linked git worktree without one uses its main worktree's.
 /// 2. Fall back to walking up from CWD (for non-git projects).
+///
+/// Results are memoized per working directory (see `project_cache`).
 #[must_use]
 pub fn discover_project_savecontext_dir() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok();
+    if let Some(dir) = cwd.as_deref().and_then(project_cache::lookup) {
+        trace!(dir = %dir.display(), "Project dir from cache");
+        return Some(dir);
+    }
+    let dir = find_project_savecontext_dir();
+    if let (Some(cwd), Some(dir)) = (&cwd, &dir) {
+        project_cache::store(cwd, dir);
+    }
+    dir
+}
+
+fn find_project_savecontext_dir() -> Option<PathBuf> {
     // Strategy 1: Use git root as the anchor (handles monorepos/subdirectories)
     if let Some(git_root) = git_toplevel() {
         let candidate = git_root.join(".savecontext");
@@ -50,6 +74,13 @@ pub fn discover_project_savecontext_dir() -> Option<PathBuf> {
         }
     }
 
+    // A linked worktree without its own export dir uses the main worktree's
+    if let Some(candidate) = current_worktree().map(|w| w.main.join(".savecontext")) {
+        if candidate.is_dir() {
+            return Some(candidate);
+        }
+    }
+
     // Strategy 2: Walk up from CWD (non-git projects)
     if let Ok(cwd) = std::env::current_dir() {
         let mut dir = cwd.as_path();
@@ -133,7 +164,8 @@ pub fn test_db_path() -> Option<PathBuf> {
 /// 1. If `explicit_path` is provided, use it directly
 /// 2. `SC_TEST_DB` environment variable → uses test database
 /// 3. `SAVECONTEXT_DB` environment variable
-/// 4. Global location: `~/.savecontext/data/savecontext.db`
+/// 4. `db_path` in `~/.savecontext/config.json` (written by `sc setup`)
+/// 5. Global location: `~/.savecontext/data/savecontext.db`
 ///
 /// # Test Mode
 ///
@@ -166,7 +198,13 @@ pub fn resolve_db_path(explicit_path: Option<&Path>) -> Option<PathBuf> {
         }
     }
 
-    // Priority 4: Global database location (matches MCP server)
+    // Priority 4: db_path from the config file
+    if let Some(db_path) = config_file_setting("db_path") {
+        trace!(path = %db_path, source = "config.json", "DB path resolved");
+        return Some(PathBuf::from(db_path));
+    }
+
+    // Priority 5: Global database location (matches MCP server)
     let path = global_savecontext_dir().map(|dir| dir.join("data").join("savecontext.db"));
     trace!(path = ?path, source = "global default", "DB path resolved");
     path
@@ -233,10 +271,10 @@ pub fn resolve_session_or_suggest(
             .list_sessions(pp_str.as_deref(), None, Some(5))
             .unwrap_or_default()
             .into_iter()
-            .filter(|s| s.status == "active" || s.status == "paused")
+            .filter(|s| matches!(s.status, SessionStatus::Active | SessionStatus::Paused))
             .take(3)
             .map(|s| {
-                (s.id.clone(), s.name.clone(), s.status.clone())
+                (s.id.clone(), s.name.clone(), s.status.to_string())
             })
             .collect::<Vec<_>>();
 
@@ -274,8 +312,10 @@ pub fn current_project_path() -> Option<PathBuf> {
 ///
 /// **Auto-detect (`None`):**
 /// 1. Canonicalize CWD
-/// 2. Check CWD and parent directories against known project paths (longest match)
-/// 3. Fall back to `.savecontext/` directory discovery (legacy)
+/// 2. Check CWD and parent directories against known project paths and
+///    their aliases (longest match)
+/// 3. In a linked git worktree, do the same for the matching directory of
+///    the main worktree, so every worktree shares the repository's project
 /// 4. Error with `NoProjectForDirectory` listing available projects
 ///
 /// # Errors
@@ -292,14 +332,19 @@ pub fn resolve_project(
         }
 
         // Try as filesystem path (canonicalize for consistent matching)
-        let canon = std::path::PathBuf::from(value)
-            .canonicalize()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| value.to_string());
+        let canon = normalize_path(Path::new(value));
 
         if let Some(project) = storage.get_project_by_path(&canon)? {
             return Ok(project);
         }
+        // Case-insensitive filesystems (Windows) may store another spelling
+        if let Some(project) = storage
+            .list_projects(200)?
+            .into_iter()
+            .find(|p| paths_equal(&p.project_path, &canon))
+        {
+            return Ok(project);
+        }
 
         return Err(Error::ProjectNotFound {
             id: value.to_string(),
@@ -309,34 +354,28 @@ pub fn resolve_project(
     // Auto-detect from CWD
     let cwd = std::env::current_dir()
         .ok()
-        .and_then(|p| p.canonicalize().ok())
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|| ".".to_string());
+        .map_or_else(|| ".".to_string(), |p| normalize_path(&p));
     trace!(cwd = %cwd, "Resolving project from CWD");
 
     // Load all projects and find the best match (longest path that is a prefix of CWD)
     let projects = storage.list_projects(200)?;
     trace!(registered_projects = projects.len(), "Loaded projects for matching");
 
-    let mut best_match: Option<&Project> = None;
-    let mut best_len: usize = 0;
-
-    for project in &projects {
-        let pp = &project.project_path;
-        // CWD must equal or be a subdirectory of the project path
-        if cwd == *pp || cwd.starts_with(&format!("{pp}/")) {
-            if pp.len() > best_len {
-                best_len = pp.len();
-                best_match = Some(project);
-            }
-        }
-    }
-
-    if let Some(project) = best_match {
+    let aliases = storage.list_project_aliases()?;
+    if let Some(project) = match_project(&projects, &aliases, &cwd) {
         debug!(project = %project.project_path, name = %project.name, "Project resolved via CWD");
         return Ok(project.clone());
     }
 
+    // A linked git worktree counts as the main worktree's project
+    if let Some(worktree) = linked_worktree(Path::new(&cwd)) {
+        let main_dir = worktree.in_main(Path::new(&cwd));
+        if let Some(project) = match_project(&projects, &aliases, &normalize_path(&main_dir)) {
+            debug!(project = %project.project_path, worktree = %worktree.path.display(), "Project resolved via git worktree");
+            return Ok(project.clone());
+        }
+    }
+
     // No match — error with suggestions
     debug!(cwd = %cwd, "No project matched CWD");
     let available: Vec<(String, String)> = projects
@@ -347,6 +386,33 @@ pub fn resolve_project(
     Err(Error::NoProjectForDirectory { cwd, available })
 }
 
+/// The project `dir` belongs to: the registered path or alias that is the
+/// longest prefix of it.
+fn match_project<'a>(projects: &'a [Project], aliases: &[(String, String)], dir: &str) -> Option<&'a Project> {
+    let mut best_match: Option<&Project> = None;
+    let mut best_len: usize = 0;
+
+    for project in projects {
+        let pp = &project.project_path;
+        // The directory must equal or be a subdirectory of the project path
+        if path_within(dir, pp) && pp.len() > best_len {
+            best_len = pp.len();
+            best_match = Some(project);
+        }
+    }
+
+    // Aliases left behind by `sc project merge-paths` count as their target
+    for (alias, target) in aliases {
+        if path_within(dir, alias) && alias.len() > best_len {
+            if let Some(project) = projects.iter().find(|p| &p.project_path == target) {
+                best_len = alias.len();
+                best_match = Some(project);
+            }
+        }
+    }
+    best_match
+}
+
 /// Resolve the project path from explicit input or CWD.
 ///
 /// Convenience wrapper around [`resolve_project`] that returns just the path string.
@@ -375,13 +441,35 @@ pub fn current_git_branch() -> Option<String> {
         .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// The channel of items saved on `branch`, as the MCP server derives it:
+/// lowercase, other characters turned into `-`, at most 20 characters.
+/// `main`, `master` and branches with no usable characters use `general`.
+#[must_use]
+pub fn branch_channel(branch: &str) -> String {
+    let branch = branch.trim();
+    if branch.is_empty() || branch == "main" || branch == "master" {
+        return "general".to_string();
+    }
+    let mut channel = String::new();
+    for c in branch.to_lowercase().chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '_' { c } else { '-' };
+        if !(c == '-' && (channel.is_empty() || channel.ends_with('-'))) {
+            channel.push(c);
+        }
+    }
+    channel.truncate(20);
+    let channel = channel.trim_end_matches('-');
+    if channel.is_empty() { "general".to_string() } else { channel.to_string() }
+}
+
 /// Get the default actor name.
 ///
 /// Priority:
 /// 1. `SC_ACTOR` environment variable
-/// 2. Git user name
-/// 3. System username
-/// 4. "unknown"
+/// 2. `actor` in `~/.savecontext/config.json`
+/// 3. Git user name
+/// 4. System username
+/// 5. "unknown"
 #[must_use]
 pub fn default_actor() -> String {
     // Check environment variable
@@ -391,6 +479,10 @@ pub fn default_actor() -> String {
         }
     }
 
+    if let Some(actor) = config_file_setting("actor") {
+        return actor;
+    }
+
     // Try git user name
     if let Ok(output) = std::process::Command::new("git")
         .args(["config", "user.name"])
@@ -412,6 +504,179 @@ pub fn default_actor() -> String {
     "unknown".to_string()
 }
 
+/// Read a top-level, non-empty string setting from the global config file.
+///
+/// Deliberately untyped: this runs on every command, and a broken file is
+/// reported by the typed loader rather than here.
+fn config_file_setting(key: &str) -> Option<String> {
+    config_file_value(key)?
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+}
+
+fn config_file_value(key: &str) -> Option<serde_json::Value> {
+    let path = global_savecontext_dir()?.join("config.json");
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    value.get_mut(key).map(serde_json::Value::take)
+}
+
+/// Whether local usage statistics should be recorded.
+///
+/// On by default. Disabled by `SC_USAGE_STATS=0` (or `false`) or by
+/// `"usage_stats": false` in `~/.savecontext/config.json`.
+#[must_use]
+pub fn usage_stats_enabled() -> bool {
+    if let Ok(value) = std::env::var("SC_USAGE_STATS") {
+        return value != "0" && !value.eq_ignore_ascii_case("false");
+    }
+    config_file_value("usage_stats")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+/// Milliseconds a single SQL statement may take before `sc` warns about it.
+///
+/// Defaults to 500. `SC_SLOW_QUERY_MS` or `"slow_query_ms"` in
+/// `~/.savecontext/config.json` changes it; `0` turns the warning off.
+#[must_use]
+pub fn slow_query_ms() -> u64 {
+    if let Some(ms) = std::env::var("SC_SLOW_QUERY_MS").ok().and_then(|v| v.trim().parse().ok()) {
+        return ms;
+    }
+    config_file_value("slow_query_ms")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(500)
+}
+
+/// Whether session-scoped commands may pick a session on their own.
+///
+/// Off by default, so a missing session is an error. Enabled by
+/// `SC_AUTO_SESSION=1` or `"auto_session": true` in
+/// `~/.savecontext/config.json`; the environment variable wins either way.
+#[must_use]
+pub fn auto_session_enabled() -> bool {
+    if let Ok(value) = std::env::var("SC_AUTO_SESSION") {
+        return value != "0" && !value.eq_ignore_ascii_case("false");
+    }
+    config_file_value("auto_session")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Hours without activity after which active sessions are paused.
+///
+/// `0` (the default) leaves sessions alone. Set `"auto_pause_hours"` in
+/// `~/.savecontext/config.json` to turn it on.
+#[must_use]
+pub fn auto_pause_hours() -> u64 {
+    config_file_value("auto_pause_hours")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+}
+
+/// Days deleted items, issues and sessions stay in the trash.
+///
+/// Defaults to 30; set `"trash_retention_days"` in
+/// `~/.savecontext/config.json` to change it. `0` keeps entries until
+/// `sc trash empty`.
+#[must_use]
+pub fn trash_retention_days() -> u64 {
+    config_file_value("trash_retention_days")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(30)
+}
+
+/// Whether `sc sync export` writes the project's audit events.
+///
+/// Off by default. Set `"sync_events": true` in
+/// `~/.savecontext/config.json` to keep `events.jsonl` alongside the other
+/// sync files.
+#[must_use]
+pub fn sync_events_enabled() -> bool {
+    config_file_value("sync_events")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Merge strategies for `sc sync import`, by entity type.
+///
+/// Set `"sync_strategies": { "issues": "prefer-local" }` in
+/// `~/.savecontext/config.json`; `--strategy` on the command line overrides
+/// it. Entries are returned as written, for the caller to parse.
+#[must_use]
+pub fn sync_strategies() -> Vec<(String, String)> {
+    let Some(serde_json::Value::Object(map)) = config_file_value("sync_strategies") else {
+        return Vec::new();
+    };
+    map.into_iter().filter_map(|(entity, strategy)| Some((entity, strategy.as_str()?.to_string()))).collect()
+}
+
+/// Size in bytes above which context item values are stored compressed.
+///
+/// Defaults to 8 KiB; set `"value_compression_threshold"` in
+/// `~/.savecontext/config.json` to change it. `0` stores every value
+/// uncompressed.
+#[must_use]
+pub fn value_compression_threshold() -> usize {
+    config_file_value("value_compression_threshold")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| usize::try_from(v).ok())
+        .unwrap_or(crate::storage::compression::DEFAULT_THRESHOLD)
+}
+
+/// Actor roles from the `"permissions"` section of
+/// `~/.savecontext/config.json`, if there is one.
+///
+/// A section that doesn't parse is treated as absent; `sc config validate`
+/// reports what is wrong with it.
+#[must_use]
+pub fn permissions() -> Option<crate::model::PermissionsConfig> {
+    config_file_value("permissions").and_then(|v| serde_json::from_value(v).ok())
+}
+
+/// The `"command_log"` section of `~/.savecontext/config.json`, with
+/// `SC_COMMAND_LOG` (`1` or `0`) overriding whether it is on.
+#[must_use]
+pub fn command_log() -> crate::cli::command_log::CommandLogConfig {
+    let mut config: crate::cli::command_log::CommandLogConfig =
+        config_file_value("command_log").and_then(|v| serde_json::from_value(v).ok()).unwrap_or_default();
+    if let Ok(value) = std::env::var("SC_COMMAND_LOG") {
+        config.enabled = value != "0" && !value.eq_ignore_ascii_case("false");
+    }
+    config
+}
+
+/// The `"otel"` section of `~/.savecontext/config.json`: where to send
+/// traces when built with the `otel` feature. Defaults when absent.
+#[must_use]
+pub fn otel() -> crate::telemetry::OtelConfig {
+    config_file_value("otel").and_then(|v| serde_json::from_value(v).ok()).unwrap_or_default()
+}
+
+/// The `"relevance"` section of `~/.savecontext/config.json`: how much
+/// item reads count and how fast they fade. Defaults when absent.
+#[must_use]
+pub fn relevance() -> crate::model::RelevanceConfig {
+    config_file_value("relevance").and_then(|v| serde_json::from_value(v).ok()).unwrap_or_default()
+}
+
+/// `"prime": { "sanitize": ... }` from `~/.savecontext/config.json`, as
+/// written: how strictly `sc prime` cleans stored text.
+#[must_use]
+pub fn prime_sanitize() -> Option<String> {
+    config_file_value("prime")?.get("sanitize")?.as_str().map(ToString::to_string)
+}
+
+/// Whether `sc prime` leaves out agent decisions awaiting review
+/// (`"prime": { "unreviewed": "exclude" }`) rather than flagging them.
+#[must_use]
+pub fn prime_excludes_unreviewed() -> bool {
+    config_file_value("prime").and_then(|p| p.get("unreviewed")?.as_str().map(|v| v == "exclude")).unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -422,6 +687,14 @@ mod tests {
         assert!(!actor.is_empty());
     }
 
+    #[test]
+    fn test_branch_channel() {
+        assert_eq!(branch_channel("main"), "general");
+        assert_eq!(branch_channel("bugfix/Login--Error"), "bugfix-login-error");
+        assert_eq!(branch_channel("feature/a-very-long-branch-name"), "feature-a-very-long");
+        assert_eq!(branch_channel("//"), "general");
+    }
+
     #[test]
     fn test_resolve_db_path_with_explicit() {
         let explicit = PathBuf::from("/custom/path/db.sqlite");
@@ -0,0 +1,114 @@
+//! Project path normalization across platforms.
+//!
+//! Project paths are stored as strings and compared as strings, so the same
+//! directory has to produce the same text everywhere. On Windows that needs
+//! extra care: `canonicalize` returns verbatim paths (`\\?\C:\repo`,
+//! `\\?\UNC\server\share`) that the MCP server never writes, drive letters
+//! come in either case, and the filesystem ignores case altogether.
+
+use std::path::Path;
+
+/// Canonical string form of a path for storing as a `project_path`.
+///
+/// Resolves symlinks and `..` when the path exists, strips Windows verbatim
+/// prefixes, upper-cases the drive letter and drops trailing separators.
+#[must_use]
+pub fn normalize_path(path: &Path) -> String {
+    let resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    normalize_str(&resolved.to_string_lossy(), cfg!(windows))
+}
+
+/// Whether two stored project paths name the same directory.
+#[must_use]
+pub fn paths_equal(a: &str, b: &str) -> bool {
+    same_path(a, b, cfg!(windows))
+}
+
+/// Whether `child` is `parent` or lies inside it.
+#[must_use]
+pub fn path_within(child: &str, parent: &str) -> bool {
+    within(child, parent, cfg!(windows))
+}
+
+fn normalize_str(path: &str, windows: bool) -> String {
+    if !windows {
+        return trim_trailing(path, &['/']).to_string();
+    }
+
+    let path = if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else {
+        path.strip_prefix(r"\\?\").unwrap_or(path).to_string()
+    };
+
+    let mut chars: Vec<char> = path.chars().collect();
+    if chars.len() >= 2 && chars[1] == ':' && chars[0].is_ascii_alphabetic() {
+        chars[0] = chars[0].to_ascii_uppercase();
+    }
+    let path: String = chars.into_iter().collect();
+    trim_trailing(&path, &['\\', '/']).to_string()
+}
+
+/// Drop trailing separators, keeping roots like `/` and `C:\` intact.
+fn trim_trailing<'a>(path: &'a str, separators: &[char]) -> &'a str {
+    let trimmed = path.trim_end_matches(separators);
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        &path[..(trimmed.len() + 1).min(path.len())]
+    } else {
+        trimmed
+    }
+}
+
+fn same_path(a: &str, b: &str, windows: bool) -> bool {
+    let (a, b) = (normalize_str(a, windows), normalize_str(b, windows));
+    if windows {
+        a.replace('/', "\\").eq_ignore_ascii_case(&b.replace('/', "\\"))
+    } else {
+        a == b
+    }
+}
+
+fn within(child: &str, parent: &str, windows: bool) -> bool {
+    let (child, parent) = (normalize_str(child, windows), normalize_str(parent, windows));
+    let (child, parent, sep) = if windows {
+        (
+            child.replace('/', "\\").to_lowercase(),
+            parent.replace('/', "\\").to_lowercase(),
+            '\\',
+        )
+    } else {
+        (child, parent, '/')
+    };
+    child == parent
+        || child
+            .strip_prefix(parent.as_str())
+            .is_some_and(|rest| rest.starts_with(sep) || parent.ends_with(sep))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_windows_paths() {
+        assert_eq!(normalize_str(r"\\?\c:\Users\dev\repo\", true), r"C:\Users\dev\repo");
+        assert_eq!(normalize_str(r"\\?\UNC\server\share\repo", true), r"\\server\share\repo");
+        assert_eq!(normalize_str(r"C:\", true), r"C:\");
+        assert_eq!(normalize_str("/home/dev/repo/", false), "/home/dev/repo");
+        assert_eq!(normalize_str("/", false), "/");
+    }
+
+    #[test]
+    fn test_windows_comparison_ignores_case_and_separators() {
+        assert!(same_path(r"C:\Users\Dev\Repo", r"c:/users/dev/repo/", true));
+        assert!(!same_path("/home/Dev", "/home/dev", false));
+
+        assert!(within(r"C:\Users\dev\repo\src", r"c:\users\DEV\repo", true));
+        assert!(within(r"\\?\C:\repo", r"C:\repo", true));
+        assert!(!within(r"C:\repo-two", r"C:\repo", true));
+        assert!(within(r"C:\repo", r"C:\", true));
+        assert!(within("/home/dev/repo/src", "/home/dev/repo", false));
+        assert!(!within("/home/dev/repo2", "/home/dev/repo", false));
+        assert!(within("/home/dev", "/", false));
+    }
+}
@@ -7,7 +7,8 @@
 //! - Cursor: `<project>/.cursor/plans/*.md`
 //! - Factory AI: `~/.factory/specs/*.md`
 //!
-//! Used by `sc plan capture` to import plans into SaveContext.
+//! Used by `sc plan capture` to import plans, and by
+//! `sc plan refresh` to detect which sections of a captured file changed.
 
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
@@ -250,6 +251,62 @@ pub fn compute_content_hash(content: &str) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Split markdown into `(heading, body)` sections.
+///
+/// Every ATX heading (`#` through `######`) starts a new section. Text
+/// before the first heading is returned under an empty heading.
+#[must_use]
+pub fn markdown_sections(content: &str) -> Vec<(String, String)> {
+    let mut sections = vec![(String::new(), String::new())];
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if !in_fence && (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            sections.push((trimmed[level..].trim().to_string(), String::new()));
+            continue;
+        }
+        if let Some((_, body)) = sections.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+
+    if sections[0].1.trim().is_empty() {
+        sections.remove(0);
+    }
+    sections
+}
+
+/// Headings of sections that were added, removed, or edited between two
+/// versions of a markdown document.
+#[must_use]
+pub fn changed_sections(old: &str, new: &str) -> Vec<String> {
+    let old_sections = markdown_sections(old);
+    let new_sections = markdown_sections(new);
+    let body_of = |sections: &[(String, String)], heading: &str| {
+        sections
+            .iter()
+            .find(|(h, _)| h == heading)
+            .map(|(_, b)| b.trim().to_string())
+    };
+
+    let mut changed: Vec<String> = Vec::new();
+    for (heading, _) in old_sections.iter().chain(new_sections.iter()) {
+        if changed.contains(heading) {
+            continue;
+        }
+        if body_of(&old_sections, heading) != body_of(&new_sections, heading) {
+            changed.push(heading.clone());
+        }
+    }
+    changed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,6 +339,23 @@ mod tests {
         assert_eq!(AgentKind::from_arg("unknown"), None);
     }
 
+    #[test]
+    fn test_markdown_sections() {
+        let content = "intro\n# Plan\n## Auth\nuse jwt\n```\n# not a heading\n```\n## Storage\nsqlite\n";
+        let sections = markdown_sections(content);
+        let headings: Vec<&str> = sections.iter().map(|(h, _)| h.as_str()).collect();
+        assert_eq!(headings, ["", "Plan", "Auth", "Storage"]);
+        assert!(sections[2].1.contains("# not a heading"));
+    }
+
+    #[test]
+    fn test_changed_sections() {
+        let old = "# Plan\n## Auth\nuse sessions\n## Storage\nsqlite\n";
+        let new = "# Plan\n## Auth\nuse jwt\n## Storage\nsqlite\n## Rollout\nflag\n";
+        assert_eq!(changed_sections(old, new), ["Auth", "Rollout"]);
+        assert!(changed_sections(old, old).is_empty());
+    }
+
     #[test]
     fn test_compute_content_hash() {
         let hash = compute_content_hash("test content");
@@ -0,0 +1,52 @@
+//! Memoized project directory discovery.
+//!
+//! Finding a project's `.savecontext/` directory asks git for the repository
+//! root, which costs a process spawn on every command. The result for each
+//! working directory is kept in `~/.savecontext/cache/project-dirs.json`
+//! and reused while the directory it points to still exists.
+//!
+//! Only hits are cached. If a `.savecontext/` directory is later created
+//! closer to the git root than the cached one, delete the cache file (or
+//! the stale directory) to pick it up.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Entries kept before the cache is reset.
+const MAX_ENTRIES: usize = 256;
+
+fn cache_file() -> Option<PathBuf> {
+    super::global_savecontext_dir().map(|dir| dir.join("cache").join("project-dirs.json"))
+}
+
+fn load(path: &Path) -> BTreeMap<String, PathBuf> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The cached `.savecontext/` directory for `cwd`, if it still exists.
+pub fn lookup(cwd: &Path) -> Option<PathBuf> {
+    let entries = load(&cache_file()?);
+    entries.get(cwd.to_string_lossy().as_ref()).filter(|dir| dir.is_dir()).cloned()
+}
+
+/// Remember the `.savecontext/` directory found for `cwd`.
+///
+/// Failures are ignored; the cache is only an optimization.
+pub fn store(cwd: &Path, dir: &Path) {
+    let Some(path) = cache_file() else { return };
+    let mut entries = load(&path);
+    if entries.len() >= MAX_ENTRIES {
+        entries.clear();
+    }
+    entries.insert(cwd.to_string_lossy().into_owned(), dir.to_path_buf());
+
+    let Some(parent) = path.parent() else { return };
+    let temp = path.with_extension("json.tmp");
+    let _ = fs::create_dir_all(parent)
+        .and_then(|()| fs::write(&temp, serde_json::to_string(&entries).unwrap_or_default()))
+        .and_then(|()| fs::rename(&temp, &path));
+}
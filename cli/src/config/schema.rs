@@ -0,0 +1,647 @@
+//! Schema validation for `~/.savecontext/config.json`.
+//!
+//! The typed config structs skip anything they don't recognise, so a typo
+//! like `"embedings"` or `"port": "22"` used to surface much later as a
+//! confusing runtime failure (or not at all). This module describes every
+//! section of the file as a static schema and walks the raw JSON against
+//! it, collecting one [`ConfigIssue`] per problem with a dotted path to the
+//! offending key.
+//!
+//! Sections covered: `version`, `actor`, `db_path`, `usage_stats`,
+//! `slow_query_ms`, `embeddings`, `remote`, `workflow`, `routing`, `policy`, `compaction`, `webhooks`,
+//! `profiles`, `import`, `trash_retention_days`, `auto_checkpoint`,
+//! `auto_session`, `auto_pause_hours`, `value_compression_threshold`,
+//! `event_retention`, `sync_events`, `summarize`, `session_summary`, `permissions`, `relevance`,
+//! `prime`, `command_log`, and `otel`.
+
+use crate::error::{Error, Result};
+use crate::json_schema::{json_schema, string_enum, JsonSchema};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Once;
+
+/// How serious a config problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The file cannot be loaded as written.
+    Error,
+    /// The file still loads, but something is ignored or deprecated.
+    Warning,
+}
+
+/// A single problem found in the config file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// Dotted path to the key, e.g. `remote.port` or `webhooks[0].url`.
+    pub path: String,
+    pub message: String,
+}
+
+impl JsonSchema for Severity {
+    fn json_schema() -> Value {
+        string_enum(&["error", "warning"])
+    }
+}
+
+json_schema!(ConfigIssue { severity: Severity, path: String, message: String });
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Expected shape of a config value.
+enum Kind {
+    Bool,
+    Integer { min: i64, max: i64 },
+    String,
+    Enum(&'static [&'static str]),
+    Array(&'static Kind),
+    /// Object with arbitrary keys (project paths, status names, ...).
+    Map(&'static Kind),
+    Object(&'static [Field]),
+}
+
+/// A key in an object section.
+struct Field {
+    name: &'static str,
+    kind: Kind,
+    required: bool,
+    /// Replacement advice when the key is deprecated.
+    deprecated: Option<&'static str>,
+}
+
+const fn field(name: &'static str, kind: Kind) -> Field {
+    Field { name, kind, required: false, deprecated: None }
+}
+
+const fn required(name: &'static str, kind: Kind) -> Field {
+    Field { name, kind, required: true, deprecated: None }
+}
+
+const fn deprecated(name: &'static str, kind: Kind, advice: &'static str) -> Field {
+    Field { name, kind, required: false, deprecated: Some(advice) }
+}
+
+/// Valid `embeddings.provider` values.
+pub const EMBEDDING_PROVIDERS: &[&str] = &["ollama", "huggingface", "transformers", "model2vec"];
+
+/// Valid `compaction.mode` values (mirrors `SAVECONTEXT_COMPACTION_MODE`).
+pub const COMPACTION_MODES: &[&str] = &["auto", "remind", "manual"];
+
+/// Valid `session_summary` values.
+pub const SESSION_SUMMARY_MODES: &[&str] = &["heuristic", "llm", "off"];
+
+const STRING_LIST: Kind = Kind::Array(&Kind::String);
+
+/// Valid `embeddings.rerank.kind` values.
+pub const RERANK_KINDS: &[&str] = &["cross-encoder", "llm"];
+
+const RERANK: &[Field] = &[
+    field("kind", Kind::Enum(RERANK_KINDS)),
+    field("endpoint", Kind::String),
+    field("model", Kind::String),
+    field("api_key", Kind::String),
+    field("top_k", Kind::Integer { min: 1, max: 200 }),
+    field("budget_ms", Kind::Integer { min: 50, max: 60_000 }),
+];
+
+const EMBEDDINGS: &[Field] = &[
+    field("enabled", Kind::Bool),
+    field("provider", Kind::Enum(EMBEDDING_PROVIDERS)),
+    field("HF_TOKEN", Kind::String),
+    field("HF_MODEL", Kind::String),
+    field("HF_ENDPOINT", Kind::String),
+    field("OLLAMA_ENDPOINT", Kind::String),
+    field("OLLAMA_MODEL", Kind::String),
+    field("providers", Kind::Array(&Kind::Enum(EMBEDDING_PROVIDERS))),
+    field("concurrency", Kind::Integer { min: 1, max: 64 }),
+    field("requests_per_minute", Kind::Integer { min: 0, max: 100_000 }),
+    field("keep_other_models", Kind::Bool),
+    field("rerank", Kind::Object(RERANK)),
+    deprecated(
+        "TRANSFORMERS_MODEL",
+        Kind::String,
+        "Transformers.js is not available in the CLI; use \"provider\": \"ollama\" or \"huggingface\"",
+    ),
+];
+
+const REMOTE: &[Field] = &[
+    required("host", Kind::String),
+    required("user", Kind::String),
+    field("port", Kind::Integer { min: 1, max: 65535 }),
+    field("identity_file", Kind::String),
+    field("remote_sc_path", Kind::String),
+    field("remote_project_path", Kind::String),
+    field("remote_db_path", Kind::String),
+];
+
+const WORKFLOW_OVERRIDES: &[Field] = &[
+    field("transitions", Kind::Map(&STRING_LIST)),
+    field("required_fields", Kind::Map(&STRING_LIST)),
+];
+
+const WORKFLOW: &[Field] = &[
+    field("transitions", Kind::Map(&STRING_LIST)),
+    field("required_fields", Kind::Map(&STRING_LIST)),
+    field("projects", Kind::Map(&Kind::Object(WORKFLOW_OVERRIDES))),
+];
+
+const ROUTING_AGENT: &[Field] = &[
+    field("skills", STRING_LIST),
+    field("wip_limit", Kind::Integer { min: 0, max: 1_000 }),
+];
+
+const ROUTING_OVERRIDES: &[Field] = &[
+    field("policy", Kind::Enum(crate::model::RoutingPolicy::NAMES)),
+    field("wip_limit", Kind::Integer { min: 0, max: 1_000 }),
+    field("agents", Kind::Map(&Kind::Object(ROUTING_AGENT))),
+];
+
+const ROUTING: &[Field] = &[
+    field("policy", Kind::Enum(crate::model::RoutingPolicy::NAMES)),
+    field("wip_limit", Kind::Integer { min: 0, max: 1_000 }),
+    field("agents", Kind::Map(&Kind::Object(ROUTING_AGENT))),
+    field("projects", Kind::Map(&Kind::Object(ROUTING_OVERRIDES))),
+];
+
+const POLICY_OVERRIDES: &[Field] = &[
+    field("max_in_progress", Kind::Integer { min: 0, max: 1_000 }),
+    field("max_claim_idle_hours", Kind::Integer { min: 1, max: 8_760 }),
+    field("claim_lease_minutes", Kind::Integer { min: 0, max: 525_600 }),
+    field("notify_on_expiry", Kind::Bool),
+];
+
+const POLICY: &[Field] = &[
+    field("max_in_progress", Kind::Integer { min: 0, max: 1_000 }),
+    field("max_claim_idle_hours", Kind::Integer { min: 1, max: 8_760 }),
+    field("claim_lease_minutes", Kind::Integer { min: 0, max: 525_600 }),
+    field("notify_on_expiry", Kind::Bool),
+    field("projects", Kind::Map(&Kind::Object(POLICY_OVERRIDES))),
+];
+
+const COMPACTION: &[Field] = &[
+    field("threshold", Kind::Integer { min: 50, max: 90 }),
+    field("mode", Kind::Enum(COMPACTION_MODES)),
+    field("context_window", Kind::Integer { min: 1_000, max: 100_000_000 }),
+];
+
+/// Valid `auto_checkpoint.interval` values.
+pub const AUTO_CHECKPOINT_INTERVALS: &[&str] = &["off", "hourly", "daily"];
+
+const AUTO_CHECKPOINT: &[Field] = &[
+    field("interval", Kind::Enum(AUTO_CHECKPOINT_INTERVALS)),
+    field("keep", Kind::Integer { min: 1, max: 10_000 }),
+];
+
+const EVENT_RETENTION: &[Field] = &[
+    field("days", Kind::Integer { min: 0, max: 36_500 }),
+    field("max_per_entity", Kind::Integer { min: 1, max: 1_000_000 }),
+    field("archive", Kind::Bool),
+];
+
+const WEBHOOK: &[Field] = &[
+    required("url", Kind::String),
+    field("events", STRING_LIST),
+    field("secret", Kind::String),
+    field("enabled", Kind::Bool),
+];
+
+const SUMMARIZE: &[Field] = &[
+    required("endpoint", Kind::String),
+    field("model", Kind::String),
+    field("api_key", Kind::String),
+    field("timeout_ms", Kind::Integer { min: 100, max: 600_000 }),
+];
+
+const ROLE: &[Field] = &[field("allow", STRING_LIST), field("deny", STRING_LIST)];
+
+/// `permissions.commands` rules, by actor type.
+const COMMAND_RULES: &[Field] = &[
+    field("human", Kind::Object(ROLE)),
+    field("agent", Kind::Object(ROLE)),
+    field("model", Kind::Object(ROLE)),
+];
+
+const PERMISSIONS: &[Field] = &[
+    field("roles", Kind::Map(&Kind::Object(ROLE))),
+    field("actors", Kind::Map(&Kind::String)),
+    field("default_role", Kind::String),
+    field("commands", Kind::Object(COMMAND_RULES)),
+];
+
+const RELEVANCE: &[Field] = &[
+    field("half_life_days", Kind::Integer { min: 1, max: 3_650 }),
+    field("access_weight", Kind::Integer { min: 0, max: 1_000 }),
+];
+
+/// Valid `prime.sanitize` values.
+pub const PRIME_SANITIZE_LEVELS: &[&str] = &["off", "standard", "strict"];
+
+const PRIME_UNREVIEWED: &[&str] = &["flag", "exclude"];
+
+const PRIME: &[Field] = &[
+    field("sanitize", Kind::Enum(PRIME_SANITIZE_LEVELS)),
+    field("unreviewed", Kind::Enum(PRIME_UNREVIEWED)),
+];
+
+const COMMAND_LOG: &[Field] = &[
+    field("enabled", Kind::Bool),
+    field("max_size_mb", Kind::Integer { min: 1, max: 10_000 }),
+    field("keep", Kind::Integer { min: 0, max: 100 }),
+];
+
+const OTEL: &[Field] = &[field("endpoint", Kind::String), field("service_name", Kind::String)];
+
+const PROFILE: &[Field] = &[
+    field("actor", Kind::String),
+    field("db_path", Kind::String),
+    field("project", Kind::String),
+    field("embeddings", Kind::Object(EMBEDDINGS)),
+];
+
+/// Issue types accepted by the import mapping.
+const ISSUE_TYPES: &[&str] = &["task", "bug", "feature", "epic", "chore"];
+
+const IMPORT_MAPPING: &[Field] = &[
+    field("status", Kind::Map(&Kind::String)),
+    field("priority", Kind::Map(&Kind::Integer { min: 0, max: 4 })),
+    field("issue_type", Kind::Map(&Kind::Enum(ISSUE_TYPES))),
+    field("labels", Kind::Map(&Kind::String)),
+];
+
+const IMPORT: &[Field] = &[
+    field("jira", Kind::Object(IMPORT_MAPPING)),
+    field("trello", Kind::Object(IMPORT_MAPPING)),
+    field("backlog_md", Kind::Object(IMPORT_MAPPING)),
+];
+
+/// `sync_strategies` values, as `sc sync import --strategy` takes them.
+const MERGE_STRATEGIES: &[&str] = &["prefer-newer", "prefer-local", "prefer-external"];
+
+/// Entity types, with the short forms `items` and `time` that
+/// `--strategy` takes too.
+const SYNC_STRATEGIES: &[Field] = &[
+    field("sessions", Kind::Enum(MERGE_STRATEGIES)),
+    field("issues", Kind::Enum(MERGE_STRATEGIES)),
+    field("context_items", Kind::Enum(MERGE_STRATEGIES)),
+    field("items", Kind::Enum(MERGE_STRATEGIES)),
+    field("memories", Kind::Enum(MERGE_STRATEGIES)),
+    field("checkpoints", Kind::Enum(MERGE_STRATEGIES)),
+    field("plans", Kind::Enum(MERGE_STRATEGIES)),
+    field("time_entries", Kind::Enum(MERGE_STRATEGIES)),
+    field("time", Kind::Enum(MERGE_STRATEGIES)),
+];
+
+const ROOT: &[Field] = &[
+    field("version", Kind::Integer { min: 0, max: 1 }),
+    field("actor", Kind::String),
+    field("db_path", Kind::String),
+    field("usage_stats", Kind::Bool),
+    field("slow_query_ms", Kind::Integer { min: 0, max: 3_600_000 }),
+    field("embeddings", Kind::Object(EMBEDDINGS)),
+    field("remote", Kind::Object(REMOTE)),
+    field("workflow", Kind::Object(WORKFLOW)),
+    field("routing", Kind::Object(ROUTING)),
+    field("policy", Kind::Object(POLICY)),
+    field("compaction", Kind::Object(COMPACTION)),
+    field("webhooks", Kind::Array(&Kind::Object(WEBHOOK))),
+    field("profiles", Kind::Map(&Kind::Object(PROFILE))),
+    field("import", Kind::Object(IMPORT)),
+    field("trash_retention_days", Kind::Integer { min: 0, max: 36_500 }),
+    field("auto_checkpoint", Kind::Object(AUTO_CHECKPOINT)),
+    field("auto_session", Kind::Bool),
+    field("auto_pause_hours", Kind::Integer { min: 0, max: 8_760 }),
+    field("value_compression_threshold", Kind::Integer { min: 0, max: 1_073_741_824 }),
+    field("event_retention", Kind::Object(EVENT_RETENTION)),
+    field("sync_events", Kind::Bool),
+    field("sync_strategies", Kind::Object(SYNC_STRATEGIES)),
+    field("summarize", Kind::Object(SUMMARIZE)),
+    field("session_summary", Kind::Enum(SESSION_SUMMARY_MODES)),
+    field("permissions", Kind::Object(PERMISSIONS)),
+    field("relevance", Kind::Object(RELEVANCE)),
+    field("prime", Kind::Object(PRIME)),
+    field("command_log", Kind::Object(COMMAND_LOG)),
+    field("otel", Kind::Object(OTEL)),
+];
+
+/// Validate config file contents.
+///
+/// JSON syntax errors are reported as a single error with line and column.
+#[must_use]
+pub fn validate_str(content: &str) -> Vec<ConfigIssue> {
+    match serde_json::from_str::<Value>(content) {
+        Ok(value) => validate_value(&value),
+        Err(e) => vec![ConfigIssue {
+            severity: Severity::Error,
+            path: String::new(),
+            message: format!(
+                "invalid JSON at line {}, column {}: {e}",
+                e.line(),
+                e.column()
+            ),
+        }],
+    }
+}
+
+/// Validate an already-parsed config document against the schema.
+#[must_use]
+pub fn validate_value(value: &Value) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    check_object(value, ROOT, "", &mut issues);
+    issues
+}
+
+/// Read and validate a config file.
+///
+/// A missing file is valid (every section has defaults).
+///
+/// # Errors
+///
+/// Returns `Error::Config` if the file exists but cannot be read.
+pub fn validate_file(path: &Path) -> Result<Vec<ConfigIssue>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("Failed to read {}: {e}", path.display())))?;
+    Ok(validate_str(&content))
+}
+
+/// Print config problems to stderr, at most once per process.
+///
+/// Called by the config loaders so a broken file is reported where it is
+/// read, rather than as an unrelated failure later on.
+pub fn warn_once(path: &Path, issues: &[ConfigIssue]) {
+    static WARNED: Once = Once::new();
+    if issues.is_empty() {
+        return;
+    }
+    WARNED.call_once(|| {
+        eprintln!("Warning: problems in {}:", path.display());
+        for issue in issues.iter().take(5) {
+            eprintln!("  - {issue}");
+        }
+        if issues.len() > 5 {
+            eprintln!("  ... and {} more", issues.len() - 5);
+        }
+        eprintln!("  Run `sc config validate` for the full report.");
+    });
+}
+
+fn check_object(value: &Value, fields: &[Field], path: &str, issues: &mut Vec<ConfigIssue>) {
+    let Some(map) = value.as_object() else {
+        push_type_error(value, "object", path, issues);
+        return;
+    };
+
+    for (key, child) in map {
+        let child_path = join(path, key);
+        match fields.iter().find(|f| f.name == key) {
+            // `null` means "unset" for optional keys (serde writes it for `None`).
+            Some(field) if child.is_null() && !field.required => {}
+            Some(field) => {
+                if let Some(advice) = field.deprecated {
+                    issues.push(ConfigIssue {
+                        severity: Severity::Warning,
+                        path: child_path.clone(),
+                        message: format!("deprecated: {advice}"),
+                    });
+                }
+                check(child, &field.kind, &child_path, issues);
+            }
+            None => {
+                let message = match closest(key, fields) {
+                    Some(name) => format!("unknown key (did you mean '{name}'?)"),
+                    None => format!(
+                        "unknown key (expected one of: {})",
+                        fields.iter().map(|f| f.name).collect::<Vec<_>>().join(", ")
+                    ),
+                };
+                issues.push(ConfigIssue {
+                    severity: Severity::Warning,
+                    path: child_path,
+                    message,
+                });
+            }
+        }
+    }
+
+    for field in fields.iter().filter(|f| f.required) {
+        if !map.contains_key(field.name) {
+            issues.push(ConfigIssue {
+                severity: Severity::Error,
+                path: join(path, field.name),
+                message: "missing required key".to_string(),
+            });
+        }
+    }
+}
+
+fn check(value: &Value, kind: &Kind, path: &str, issues: &mut Vec<ConfigIssue>) {
+    match kind {
+        Kind::Bool => {
+            if !value.is_boolean() {
+                push_type_error(value, "true or false", path, issues);
+            }
+        }
+        Kind::Integer { min, max } => match value.as_i64() {
+            Some(n) if (*min..=*max).contains(&n) => {}
+            Some(n) => issues.push(ConfigIssue {
+                severity: Severity::Error,
+                path: path.to_string(),
+                message: format!("{n} is out of range ({min}-{max})"),
+            }),
+            None => push_type_error(value, "integer", path, issues),
+        },
+        Kind::String => {
+            if !value.is_string() {
+                push_type_error(value, "string", path, issues);
+            }
+        }
+        Kind::Enum(allowed) => match value.as_str() {
+            Some(s) if allowed.contains(&s) => {}
+            Some(s) => issues.push(ConfigIssue {
+                severity: Severity::Error,
+                path: path.to_string(),
+                message: format!("'{s}' is not one of: {}", allowed.join(", ")),
+            }),
+            None => push_type_error(value, "string", path, issues),
+        },
+        Kind::Array(item) => match value.as_array() {
+            Some(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    check(child, item, &format!("{path}[{i}]"), issues);
+                }
+            }
+            None => push_type_error(value, "array", path, issues),
+        },
+        Kind::Map(item) => match value.as_object() {
+            Some(map) => {
+                for (key, child) in map {
+                    check(child, item, &join(path, key), issues);
+                }
+            }
+            None => push_type_error(value, "object", path, issues),
+        },
+        Kind::Object(fields) => check_object(value, fields, path, issues),
+    }
+}
+
+fn push_type_error(value: &Value, expected: &str, path: &str, issues: &mut Vec<ConfigIssue>) {
+    let found = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    issues.push(ConfigIssue {
+        severity: Severity::Error,
+        path: path.to_string(),
+        message: format!("expected {expected}, found {found} ({value})"),
+    });
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+/// Suggest the known key closest to a misspelled one.
+fn closest<'a>(key: &str, fields: &'a [Field]) -> Option<&'a str> {
+    let lower = key.to_lowercase();
+    fields
+        .iter()
+        .map(|f| (f.name, edit_distance(&lower, &f.name.to_lowercase())))
+        .filter(|(_, d)| *d <= 2)
+        .min_by_key(|(_, d)| *d)
+        .map(|(name, _)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_full_config() {
+        let config = json!({
+            "version": 1,
+            "actor": "me",
+            "slow_query_ms": 250,
+            "embeddings": { "enabled": true, "provider": "ollama", "HF_TOKEN": null, "TRANSFORMERS_MODEL": null, "providers": ["ollama", "model2vec"], "concurrency": 8, "requests_per_minute": 0, "keep_other_models": true,
+                "rerank": { "kind": "llm", "endpoint": "http://localhost:8080/v1/chat/completions", "top_k": 10, "budget_ms": 1500 } },
+            "remote": { "host": "example.com", "user": "me", "port": 2222 },
+            "workflow": {
+                "required_fields": { "closed": ["close_reason"] },
+                "projects": { "/p": { "transitions": { "open": ["closed"] } } }
+            },
+            "routing": {
+                "policy": "skills",
+                "wip_limit": 3,
+                "agents": { "claude-api": { "skills": ["backend"], "wip_limit": 2 } },
+                "projects": { "/p": { "policy": "round-robin" } }
+            },
+            "policy": { "max_in_progress": 3, "max_claim_idle_hours": 24, "claim_lease_minutes": 120, "notify_on_expiry": true, "projects": { "/p": { "max_in_progress": 1 } } },
+            "compaction": { "threshold": 70, "mode": "remind", "context_window": 1_000_000 },
+            "webhooks": [{ "url": "https://example.com/hook", "events": ["issue"] }],
+            "profiles": { "work": { "actor": "me", "embeddings": { "enabled": false } } },
+            "import": { "jira": { "status": { "QA": "in_progress" }, "priority": { "P1": 4 } } },
+            "trash_retention_days": 14,
+            "auto_checkpoint": { "interval": "hourly", "keep": 48 },
+            "auto_session": true,
+            "auto_pause_hours": 8,
+            "value_compression_threshold": 4096,
+            "event_retention": { "days": 90, "max_per_entity": 200, "archive": true },
+            "sync_events": true,
+            "sync_strategies": { "issues": "prefer-local", "context_items": "prefer-newer" },
+            "summarize": { "endpoint": "http://localhost:11434/v1/chat/completions", "model": "llama3.2", "timeout_ms": 20000 },
+            "session_summary": "llm",
+            "permissions": {
+                "roles": { "executor": { "allow": ["claim_issue", "update_issue_status"] }, "planner": { "deny": ["delete_*"] } },
+                "actors": { "claude-exec": "executor" },
+                "default_role": "planner",
+                "commands": { "agent": { "deny": ["project delete", "session delete"] } }
+            },
+            "relevance": { "half_life_days": 7, "access_weight": 25 },
+            "prime": { "sanitize": "strict", "unreviewed": "exclude" },
+            "command_log": { "enabled": true, "max_size_mb": 20, "keep": 3 },
+            "otel": { "endpoint": "http://localhost:4318", "service_name": "sc-ci" }
+        });
+        assert!(validate_value(&config).is_empty());
+    }
+
+    #[test]
+    fn test_sync_strategy_short_forms() {
+        let config = json!({ "sync_strategies": { "items": "prefer-local", "time": "prefer-newer" } });
+        assert!(validate_value(&config).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_key_suggests_closest() {
+        let issues = validate_value(&json!({ "embedings": {} }));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert!(issues[0].message.contains("did you mean 'embeddings'"));
+    }
+
+    #[test]
+    fn test_type_and_range_errors() {
+        let issues = validate_value(&json!({
+            "remote": { "host": "h", "port": "22" },
+            "compaction": { "threshold": 99, "mode": "sometimes" }
+        }));
+        let paths: Vec<&str> = issues.iter().map(|i| i.path.as_str()).collect();
+        assert!(paths.contains(&"remote.port"));
+        assert!(paths.contains(&"remote.user"));
+        assert!(paths.contains(&"compaction.threshold"));
+        assert!(paths.contains(&"compaction.mode"));
+        assert!(issues.iter().all(|i| i.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_deprecated_and_nested_paths() {
+        let issues = validate_value(&json!({
+            "embeddings": { "TRANSFORMERS_MODEL": "x" },
+            "webhooks": [{ "url": "u" }, { "events": [1] }]
+        }));
+        let paths: Vec<&str> = issues.iter().map(|i| i.path.as_str()).collect();
+        assert!(paths.contains(&"embeddings.TRANSFORMERS_MODEL"));
+        assert!(paths.contains(&"webhooks[1].url"));
+        assert!(paths.contains(&"webhooks[1].events[0]"));
+    }
+
+    #[test]
+    fn test_syntax_error_has_location() {
+        let issues = validate_str("{\n  \"version\": 1,\n}");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("line 3"));
+    }
+}
@@ -7,10 +7,16 @@
 //! # TTY Resolution Strategy (matches MCP server)
 //!
 //! 1. `SAVECONTEXT_STATUS_KEY` env var (explicit override)
-//! 2. Parent process TTY via `ps -o tty= -p $PPID`
+//! 2. Parent process TTY via `ps -o tty= -p $PPID` (Unix only)
 //! 3. `TERM_SESSION_ID` env var (macOS Terminal.app)
 //! 4. `ITERM_SESSION_ID` env var (iTerm2)
-//! 5. None if no key available
+//! 5. `WT_SESSION` env var (Windows Terminal, one GUID per tab)
+//! 6. `ConEmuHWND` env var (`ConEmu` / Cmder console window)
+//! 7. None if no key available
+//!
+//! Windows has no controlling-terminal concept to walk to, so a plain
+//! `conhost` PowerShell window without any of these variables needs
+//! `SAVECONTEXT_STATUS_KEY` set (e.g. in `$PROFILE`) to bind sessions.
 
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -39,6 +45,19 @@ pub struct StatusCacheEntry {
     pub active_plan_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub plan_title: Option<String>,
+    /// Open reminders of the session's project, for the statusline to
+    /// count the due ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<Vec<CachedReminder>>,
+}
+
+/// An open reminder in a status cache entry.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedReminder {
+    pub key: String,
+    /// Unix ms.
+    pub due_at: i64,
 }
 
 /// Get the status cache directory path.
@@ -62,19 +81,33 @@ fn sanitize_key(key: &str) -> Option<String> {
         .take(100)
         .collect();
 
+    // Windows silently drops trailing dots and reserves device names
+    let sanitized = sanitized.trim_end_matches('.').to_string();
     if sanitized.is_empty() {
         None
+    } else if is_reserved_name(&sanitized) {
+        Some(format!("_{sanitized}"))
     } else {
         Some(sanitized)
     }
 }
 
+/// Whether `name` is a Windows device name (`CON`, `NUL`, `COM1`, ...).
+fn is_reserved_name(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    matches!(upper.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((upper.starts_with("COM") || upper.starts_with("LPT"))
+            && upper.len() == 4
+            && upper.as_bytes()[3].is_ascii_digit())
+}
+
 /// Walk the process tree to find the controlling terminal.
 ///
 /// Agent-spawned processes (e.g. Claude Code → shell → sc) often have
 /// no TTY ("??") on themselves and their immediate parent. The real
 /// terminal is held by the agent process further up the tree.
 /// Walk up to 5 ancestors to find it.
+#[cfg(unix)]
 fn find_tty_from_ancestors() -> Option<String> {
     let mut current_pid = std::process::id().to_string();
 
@@ -114,6 +147,13 @@ fn find_tty_from_ancestors() -> Option<String> {
     None
 }
 
+/// Windows processes have no TTY; see the Windows variables in
+/// [`get_status_key`] instead.
+#[cfg(not(unix))]
+fn find_tty_from_ancestors() -> Option<String> {
+    None
+}
+
 /// Get the status key for this terminal.
 ///
 /// Uses the same resolution strategy as the MCP server to ensure
@@ -145,7 +185,21 @@ pub fn get_status_key() -> Option<String> {
         }
     }
 
-    // 5. No key available
+    // 5. Windows Terminal tab
+    if let Ok(wt_id) = std::env::var("WT_SESSION") {
+        if !wt_id.is_empty() {
+            return sanitize_key(&format!("wt-{wt_id}"));
+        }
+    }
+
+    // 6. ConEmu / Cmder console window
+    if let Ok(hwnd) = std::env::var("ConEmuHWND") {
+        if !hwnd.is_empty() {
+            return sanitize_key(&format!("conemu-{hwnd}"));
+        }
+    }
+
+    // 7. No key available
     None
 }
 
@@ -284,11 +338,21 @@ pub fn bind_session_to_terminal(
         session_status: Some(status.to_string()),
         active_plan_id: None,
         plan_title: None,
+        reminders: None,
     };
 
     write_status_cache(&entry)
 }
 
+/// Replace the open reminders in this terminal's entry, if its session is
+/// in `project_path`. Best effort, like the rest of the cache.
+pub fn cache_reminders(project_path: &str, reminders: Vec<CachedReminder>) {
+    if let Some(mut entry) = read_status_cache().filter(|e| e.project_path == project_path) {
+        entry.reminders = (!reminders.is_empty()).then_some(reminders);
+        write_status_cache(&entry);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,6 +364,19 @@ mod tests {
         assert_eq!(sanitize_key("with spaces"), Some("with_spaces".to_string()));
         assert_eq!(sanitize_key(""), None);
         assert_eq!(sanitize_key("   "), None);
+        assert_eq!(sanitize_key("C:\\dev"), Some("C__dev".to_string()));
+    }
+
+    #[test]
+    fn test_sanitize_key_windows_names() {
+        assert_eq!(sanitize_key("nul"), Some("_nul".to_string()));
+        assert_eq!(sanitize_key("COM3"), Some("_COM3".to_string()));
+        assert_eq!(sanitize_key("COMPUTE"), Some("COMPUTE".to_string()));
+        assert_eq!(sanitize_key("key..."), Some("key".to_string()));
+        assert_eq!(
+            sanitize_key("wt-0f3a9c52-1b7e-4d2a-9a41-6c0e5b2f7d18"),
+            Some("wt-0f3a9c52-1b7e-4d2a-9a41-6c0e5b2f7d18".to_string())
+        );
     }
 
     #[test]
@@ -0,0 +1,135 @@
+//! Monorepo sub-projects.
+//!
+//! A project can register directories below its root as sub-projects with
+//! `sc init --subproject packages/api`. They are stored by path relative to
+//! the project root, with `/` separators, and the one the current directory
+//! is in becomes the default scope of `sc issue list` and `sc prime`.
+
+use super::{linked_worktree, normalize_path, path_within};
+use crate::error::{Error, Result};
+use crate::storage::SqliteStorage;
+use std::path::{Component, Path};
+
+/// The sub-project a command works in: the registered one named by
+/// `explicit`, none with `root`, otherwise the registered one containing
+/// the current directory, if any.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if `explicit` isn't a registered
+/// sub-project of `project_path`.
+pub fn resolve_subproject(
+    storage: &SqliteStorage,
+    project_path: &str,
+    explicit: Option<&str>,
+    root: bool,
+) -> Result<Option<String>> {
+    if root {
+        return Ok(None);
+    }
+    let registered = storage.list_subprojects(project_path)?;
+    if let Some(explicit) = explicit {
+        let path = subproject_path(explicit)?;
+        if registered.contains(&path) {
+            return Ok(Some(path));
+        }
+        let known = if registered.is_empty() { "none".to_string() } else { registered.join(", ") };
+        return Err(Error::InvalidArgument(format!(
+            "'{path}' is not a sub-project of {project_path} (registered: {known}). Register it with `sc init --subproject {path}`"
+        )));
+    }
+    if registered.is_empty() {
+        return Ok(None);
+    }
+    let Some(dir) = std::env::current_dir().ok().and_then(|cwd| relative_dir(&cwd, project_path)) else {
+        return Ok(None);
+    };
+    Ok(containing(&registered, &dir).map(ToString::to_string))
+}
+
+/// Whether a record tagged with sub-project `tag` belongs in a view of
+/// `scope`. Without a scope everything does; within one, its own records,
+/// those of sub-projects it is part of and the untagged ones, which concern
+/// the whole project.
+#[must_use]
+pub fn in_subproject(tag: Option<&str>, scope: Option<&str>) -> bool {
+    match (tag, scope) {
+        (Some(tag), Some(scope)) => path_within(scope, tag),
+        _ => true,
+    }
+}
+
+/// `raw` as a sub-project path: relative, `/`-separated, without `.`
+/// segments or a trailing separator.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] for an empty or absolute path, or one
+/// that leaves the project with `..`.
+pub fn subproject_path(raw: &str) -> Result<String> {
+    let raw = raw.trim().replace('\\', "/");
+    let path = Path::new(&raw);
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::CurDir => {}
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "Invalid sub-project '{raw}': give a path inside the project, relative to its root"
+                )));
+            }
+        }
+    }
+    if parts.is_empty() {
+        return Err(Error::InvalidArgument("Sub-project path is empty".to_string()));
+    }
+    Ok(parts.join("/"))
+}
+
+/// Where `dir` is below the project root, `/`-separated (empty at the
+/// root). A linked git worktree counts as the main worktree.
+fn relative_dir(dir: &Path, project_path: &str) -> Option<String> {
+    let dir = normalize_path(dir);
+    let dir = if path_within(&dir, project_path) {
+        dir
+    } else {
+        normalize_path(&linked_worktree(Path::new(&dir))?.in_main(Path::new(&dir)))
+    };
+    let rest = dir.get(project_path.len()..).filter(|_| path_within(&dir, project_path))?;
+    Some(rest.replace('\\', "/").trim_matches('/').to_string())
+}
+
+/// The deepest of `registered` that `dir` is in.
+fn containing<'a>(registered: &'a [String], dir: &str) -> Option<&'a str> {
+    registered
+        .iter()
+        .filter(|sub| path_within(dir, sub))
+        .max_by_key(|sub| sub.len())
+        .map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subproject_paths() {
+        assert_eq!(subproject_path("./packages/api/").unwrap(), "packages/api");
+        assert_eq!(subproject_path(r"packages\web").unwrap(), "packages/web");
+        assert!(subproject_path("../other").is_err());
+        assert!(subproject_path("/abs/path").is_err());
+        assert!(subproject_path(".").is_err());
+
+        let registered = vec!["packages".to_string(), "packages/api".to_string()];
+        assert_eq!(containing(&registered, "packages/api/src"), Some("packages/api"));
+        assert_eq!(containing(&registered, "packages/apiv2"), Some("packages"));
+        assert_eq!(containing(&registered, "docs"), None);
+        assert_eq!(containing(&registered, ""), None);
+
+        assert!(in_subproject(None, Some("packages/api")));
+        assert!(in_subproject(Some("packages"), Some("packages/api")));
+        assert!(!in_subproject(Some("packages/web"), Some("packages/api")));
+        assert!(in_subproject(Some("packages/web"), None));
+    }
+}
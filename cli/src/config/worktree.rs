@@ -0,0 +1,90 @@
+//! Git worktree detection.
+//!
+//! A repository checked out in several places with `git worktree add` has
+//! one main worktree and any number of linked ones, all sharing the main
+//! worktree's `.git` directory. Projects are registered by path, so a
+//! linked worktree matches no project on its own; project resolution maps
+//! it to the main worktree instead.
+
+use super::normalize_path;
+use std::path::{Path, PathBuf};
+
+/// A linked worktree of a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worktree {
+    /// Top directory of the linked worktree.
+    pub path: PathBuf,
+    /// Top directory of the repository's main worktree.
+    pub main: PathBuf,
+}
+
+impl Worktree {
+    /// How sessions record the worktree: its normalized path.
+    #[must_use]
+    pub fn key(&self) -> String {
+        normalize_path(&self.path)
+    }
+
+    /// Where `dir`, inside this worktree, is in the main worktree.
+    #[must_use]
+    pub fn in_main(&self, dir: &Path) -> PathBuf {
+        match dir.strip_prefix(self.key()) {
+            Ok(rest) => self.main.join(rest),
+            Err(_) => self.main.clone(),
+        }
+    }
+}
+
+/// The linked worktree `dir` is in, if any.
+///
+/// Returns `None` in a main worktree, outside a repository, and in
+/// worktrees of a bare repository, which have no main worktree.
+#[must_use]
+pub fn linked_worktree(dir: &Path) -> Option<Worktree> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--show-toplevel", "--git-common-dir"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let path = PathBuf::from(lines.next()?.trim());
+    // Relative to `dir` unless git gives it absolute
+    let common_dir = dir.join(lines.next()?.trim());
+    from_git_dirs(path, &common_dir)
+}
+
+/// The linked worktree the current directory is in, if any.
+#[must_use]
+pub fn current_worktree() -> Option<Worktree> {
+    linked_worktree(&std::env::current_dir().ok()?)
+}
+
+/// The worktree at `path` given the common `.git` directory, unless it is
+/// the main one.
+fn from_git_dirs(path: PathBuf, common_dir: &Path) -> Option<Worktree> {
+    if common_dir.file_name()? != ".git" {
+        return None;
+    }
+    let main = PathBuf::from(normalize_path(common_dir.parent()?));
+    (main.as_os_str() != normalize_path(&path).as_str()).then_some(Worktree { path, main })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linked_worktree_maps_to_main() {
+        let main = Path::new("/nonexistent/repo");
+        assert_eq!(from_git_dirs(main.to_path_buf(), &main.join(".git")), None);
+        assert_eq!(from_git_dirs(main.to_path_buf(), Path::new("/nonexistent/repo.git")), None);
+
+        let worktree = from_git_dirs(PathBuf::from("/nonexistent/repo-feature"), &main.join(".git")).unwrap();
+        assert_eq!(worktree.main, main);
+        assert_eq!(worktree.in_main(Path::new("/nonexistent/repo-feature/src/api")), main.join("src/api"));
+        assert_eq!(worktree.in_main(Path::new("/elsewhere")), main);
+    }
+}
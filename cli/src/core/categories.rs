@@ -0,0 +1,47 @@
+//! Context item categories registered for the project.
+
+use super::SaveContext;
+use crate::error::Result;
+use crate::model::{Categories, Category, ItemCategory};
+
+impl SaveContext<'_> {
+    /// The built-in categories, with the project's overrides, and the ones
+    /// it registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn categories(&self) -> Result<Categories> {
+        self.storage.get_categories(&self.project_path)
+    }
+
+    /// Register `category`, or replace the settings of one that exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an invalid color or weight, or if the write
+    /// fails.
+    pub fn save_category(&mut self, category: &Category) -> Result<()> {
+        self.storage.save_category(&self.project_path, category, &self.actor)
+    }
+
+    /// Rename a registered category, returning how many items moved to the
+    /// new name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either name is built in, `old` isn't registered
+    /// or `new` already is.
+    pub fn rename_category(&mut self, old: &ItemCategory, new: &ItemCategory) -> Result<usize> {
+        self.storage.rename_category(&self.project_path, old, new, &self.actor)
+    }
+
+    /// Unregister a category, or reset a built-in one to its defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if items are still in the category.
+    pub fn delete_category(&mut self, name: &ItemCategory) -> Result<bool> {
+        self.storage.delete_category(&self.project_path, name, &self.actor)
+    }
+}
@@ -0,0 +1,78 @@
+//! Context items: the key/value notes saved in a session.
+
+use super::{new_id, ContextItem, SaveContext};
+use crate::error::{Error, Result};
+use crate::model::{ItemCategory, ItemPriority};
+
+/// Input for [`SaveContext::save_item`].
+#[derive(Debug, Clone, Default)]
+pub struct NewItem {
+    pub key: String,
+    pub value: String,
+    pub category: ItemCategory,
+    pub priority: ItemPriority,
+}
+
+/// Filters for [`SaveContext::items`].
+#[derive(Debug, Clone, Default)]
+pub struct ItemFilter {
+    pub category: Option<ItemCategory>,
+    pub priority: Option<ItemPriority>,
+    /// At most this many items (100 when unset).
+    pub limit: Option<u32>,
+}
+
+impl SaveContext<'_> {
+    /// Save an item in `session_id`, replacing the value of an existing
+    /// item with the same key. Returns the stored item, which keeps its
+    /// original ID when it already existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn save_item(&mut self, session_id: &str, item: &NewItem) -> Result<ContextItem> {
+        let id = new_id("item");
+        self.storage.save_context_item(
+            &id,
+            session_id,
+            &item.key,
+            &item.value,
+            Some(item.category.clone()),
+            Some(item.priority),
+            &self.actor,
+        )?;
+        self.item(session_id, &item.key)?
+            .ok_or_else(|| Error::Other(format!("Context item not found after saving: {}", item.key)))
+    }
+
+    /// The item saved under `key` in `session_id`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn item(&self, session_id: &str, key: &str) -> Result<Option<ContextItem>> {
+        match self.storage.get_item_id_by_key(session_id, key)? {
+            Some(id) => self.storage.get_context_item(&id),
+            None => Ok(None),
+        }
+    }
+
+    /// Items in `session_id` matching `filter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn items(&self, session_id: &str, filter: &ItemFilter) -> Result<Vec<ContextItem>> {
+        self.storage
+            .get_context_items(session_id, filter.category.clone(), filter.priority, filter.limit)
+    }
+
+    /// Delete the item saved under `key` in `session_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn delete_item(&mut self, session_id: &str, key: &str) -> Result<()> {
+        self.storage.delete_context_item(session_id, key, &self.actor)
+    }
+}
@@ -0,0 +1,134 @@
+//! Issues in the project.
+
+use super::{new_id, Issue, SaveContext};
+use crate::cli::commands::config::load_issue_workflow;
+use crate::error::{Error, Result};
+use crate::model::{IssueStatus, IssueType, IssueWorkflow};
+use crate::storage::SqliteStorage;
+
+/// Input for [`SaveContext::create_issue`].
+#[derive(Debug, Clone, Default)]
+pub struct NewIssue {
+    pub title: String,
+    pub description: Option<String>,
+    pub details: Option<String>,
+    pub issue_type: IssueType,
+    /// 0 (lowest) to 4 (critical); 2 when unset.
+    pub priority: Option<i32>,
+    pub plan_id: Option<String>,
+    /// ID or short ID of the parent issue.
+    pub parent: Option<String>,
+    pub labels: Vec<String>,
+    /// Estimated hours of work.
+    pub estimate_hours: Option<f64>,
+}
+
+/// Filters for [`SaveContext::list_issues`].
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilter {
+    /// Only issues with this status. When unset, closed issues are left
+    /// out unless `include_closed` is set.
+    pub status: Option<IssueStatus>,
+    pub include_closed: bool,
+    pub issue_type: Option<IssueType>,
+    /// At most this many issues (50 when unset).
+    pub limit: Option<u32>,
+}
+
+impl SaveContext<'_> {
+    /// Create an open issue with a fresh short ID, attach it to its parent
+    /// and label it. Either all of that happens or none of it does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a write fails.
+    pub fn create_issue(&mut self, new: &NewIssue) -> Result<Issue> {
+        let id = new_id("issue");
+        let (project_path, subproject, actor) = (&self.project_path, self.subproject.as_deref(), &self.actor);
+        self.storage.atomically(|storage| {
+            let short_id = storage.next_issue_short_id()?;
+            storage.create_issue(
+                &id,
+                Some(&short_id),
+                project_path,
+                &new.title,
+                new.description.as_deref(),
+                new.details.as_deref(),
+                Some(new.issue_type),
+                new.priority,
+                new.plan_id.as_deref(),
+                subproject,
+                actor,
+            )?;
+            if let Some(parent) = &new.parent {
+                storage.add_issue_dependency(&id, parent, "parent-child", actor)?;
+            }
+            if !new.labels.is_empty() {
+                storage.add_issue_labels(&id, &new.labels, actor)?;
+            }
+            if let Some(hours) = new.estimate_hours {
+                storage.set_issue_estimate(&id, Some(hours), actor)?;
+            }
+            Ok(())
+        })?;
+        self.require_issue(&id)
+    }
+
+    /// The issue with this ID or short ID in the project, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn issue(&self, id: &str) -> Result<Option<Issue>> {
+        self.storage.get_issue(id, Some(&self.project_path))
+    }
+
+    /// The project's issues matching `filter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_issues(&self, filter: &IssueFilter) -> Result<Vec<Issue>> {
+        let status = match &filter.status {
+            Some(status) => Some(status.as_str()),
+            None if filter.include_closed => Some("all"),
+            None => None,
+        };
+        self.storage.list_issues(
+            &self.project_path,
+            status,
+            filter.issue_type.map(IssueType::as_str),
+            filter.limit,
+        )
+    }
+
+    /// Move an issue to `status`, following the project's workflow, and
+    /// return it. `reason` is recorded as the close reason.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IssueNotFound`] if there is no such issue, or an
+    /// error if the workflow doesn't allow the change or the write fails.
+    pub fn set_issue_status(&mut self, id: &str, status: &IssueStatus, reason: Option<&str>) -> Result<Issue> {
+        let workflow = workflow_for_issue(&self.storage, id);
+        self.storage.update_issue_status(id, status, reason, &workflow, &self.actor)?;
+        self.require_issue(id)
+    }
+
+    /// Like the storage calls it follows, this finds issues in any project.
+    fn require_issue(&self, id: &str) -> Result<Issue> {
+        self.storage
+            .get_issue(id, None)?
+            .ok_or_else(|| Error::IssueNotFound { id: id.to_string() })
+    }
+}
+
+/// Resolve the workflow for the project that owns an issue.
+///
+/// Falls back to the global workflow if the issue can't be found; the
+/// storage call that follows reports the missing issue.
+pub(crate) fn workflow_for_issue(storage: &SqliteStorage, id: &str) -> IssueWorkflow {
+    let project_path = storage.get_issue(id, None).ok().flatten().map(|i| i.project_path);
+    load_issue_workflow(project_path.as_deref())
+}
+
@@ -0,0 +1,47 @@
+//! Project memory: commands, config and notes kept per project.
+
+use super::{new_id, Memory, SaveContext};
+use crate::error::{Error, Result};
+use crate::model::MemoryCategory;
+
+impl SaveContext<'_> {
+    /// Save `value` under `key`, replacing any existing value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn save_memory(&mut self, key: &str, value: &str, category: MemoryCategory) -> Result<Memory> {
+        let id = new_id("mem");
+        let subproject = self.subproject.as_deref();
+        self.storage.save_memory(&id, &self.project_path, key, value, category, subproject, &self.actor)?;
+        self.memory(key)?
+            .ok_or_else(|| Error::Other(format!("Memory not found after saving: {key}")))
+    }
+
+    /// The memory saved under `key`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn memory(&self, key: &str) -> Result<Option<Memory>> {
+        self.storage.get_memory(&self.project_path, key)
+    }
+
+    /// All memory of the project, optionally in one category, by key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_memory(&self, category: Option<MemoryCategory>) -> Result<Vec<Memory>> {
+        self.storage.list_memory(&self.project_path, category.map(MemoryCategory::as_str))
+    }
+
+    /// Delete the memory saved under `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn delete_memory(&mut self, key: &str) -> Result<()> {
+        self.storage.delete_memory(&self.project_path, key, &self.actor)
+    }
+}
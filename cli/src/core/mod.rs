@@ -0,0 +1,240 @@
+//! Library API: the operations behind the commands, for use from other tools.
+//!
+//! Command handlers in [`crate::cli`] resolve paths from flags and the
+//! environment and print their results, which makes them awkward to call
+//! from a GUI or a server. This module holds the operations themselves: a
+//! [`SaveContext`] is an open database together with the project and
+//! actor it acts for, and its methods take typed inputs, return typed
+//! records and never print. The CLI builds one with
+//! [`SaveContext::discover`] and only formats what comes back.
+//!
+//! ```no_run
+//! use sc::core::SaveContext;
+//! use sc::model::MemoryCategory;
+//!
+//! let mut sc = SaveContext::open("/home/me/.savecontext/data/savecontext.db".as_ref(), "/home/me/app", "my-tool")?;
+//! sc.save_memory("test", "cargo test --workspace", MemoryCategory::Command)?;
+//! for memory in sc.list_memory(None)? {
+//!     println!("{} = {}", memory.key, memory.value);
+//! }
+//! # Ok::<(), sc::Error>(())
+//! ```
+//!
+//! Operations are grouped by record type:
+//! - [`categories`] - context item categories of the project
+//! - [`context`] - context items in a session
+//! - [`issues`] - issues in the project
+//! - [`memory`] - project memory
+//! - [`sessions`] - sessions of the project
+
+pub mod categories;
+pub mod context;
+pub mod issues;
+pub mod memory;
+pub mod sessions;
+
+pub use context::{ItemFilter, NewItem};
+pub use issues::{IssueFilter, NewIssue};
+pub use sessions::{NewSession, StartedSession};
+
+pub use crate::storage::{ContextItem, Issue, Memory, Session};
+
+use crate::config::{default_actor, resolve_db_path, resolve_project_path};
+use crate::error::{Error, Result};
+use crate::storage::SqliteStorage;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+
+/// An open database, scoped to one project and actor.
+///
+/// It owns its connection, or borrows one the caller already has open for
+/// `'a` (see [`SaveContext::borrowing`]).
+pub struct SaveContext<'a> {
+    storage: Storage<'a>,
+    project_path: String,
+    subproject: Option<String>,
+    actor: String,
+}
+
+/// The connection a [`SaveContext`] works through.
+enum Storage<'a> {
+    Owned(SqliteStorage),
+    Borrowed(&'a mut SqliteStorage),
+}
+
+impl Deref for Storage<'_> {
+    type Target = SqliteStorage;
+
+    fn deref(&self) -> &SqliteStorage {
+        match self {
+            Self::Owned(storage) => storage,
+            Self::Borrowed(storage) => storage,
+        }
+    }
+}
+
+impl DerefMut for Storage<'_> {
+    fn deref_mut(&mut self) -> &mut SqliteStorage {
+        match self {
+            Self::Owned(storage) => storage,
+            Self::Borrowed(storage) => storage,
+        }
+    }
+}
+
+impl SaveContext<'static> {
+    /// Open an existing database for `project_path`, recording changes as
+    /// made by `actor`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotInitialized`] if the database doesn't exist, or
+    /// an error if it cannot be opened or migrated.
+    pub fn open(db_path: &Path, project_path: impl Into<String>, actor: impl Into<String>) -> Result<Self> {
+        if !db_path.exists() {
+            return Err(Error::NotInitialized);
+        }
+        Ok(Self::with_storage(SqliteStorage::open(db_path)?, project_path, actor))
+    }
+
+    /// Open the database the way `sc` does: the given path or the
+    /// configured one, the project containing the working directory, and
+    /// the given actor or the default one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotInitialized`] if there is no database, or an
+    /// error if it cannot be opened or the working directory isn't in a
+    /// known project.
+    pub fn discover(db_path: Option<&Path>, actor: Option<&str>) -> Result<Self> {
+        let db_path = resolve_db_path(db_path).ok_or(Error::NotInitialized)?;
+        if !db_path.exists() {
+            return Err(Error::NotInitialized);
+        }
+        let storage = SqliteStorage::open(&db_path)?;
+        let project_path = resolve_project_path(&storage, None)?;
+        let actor = actor.map_or_else(default_actor, ToString::to_string);
+        Ok(Self::with_storage(storage, project_path, actor))
+    }
+
+    /// Wrap storage that is already open.
+    #[must_use]
+    pub fn with_storage(storage: SqliteStorage, project_path: impl Into<String>, actor: impl Into<String>) -> Self {
+        Self { storage: Storage::Owned(storage), project_path: project_path.into(), subproject: None, actor: actor.into() }
+    }
+}
+
+impl<'a> SaveContext<'a> {
+    /// Work through storage the caller keeps open, without taking it over.
+    #[must_use]
+    pub fn borrowing(storage: &'a mut SqliteStorage, project_path: impl Into<String>, actor: impl Into<String>) -> Self {
+        Self {
+            storage: Storage::Borrowed(storage),
+            project_path: project_path.into(),
+            subproject: None,
+            actor: actor.into(),
+        }
+    }
+
+    /// Like [`SaveContext::discover`], in storage the caller keeps open.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the working directory isn't in a known project.
+    pub fn discover_in(storage: &'a mut SqliteStorage, actor: Option<&str>) -> Result<Self> {
+        let project_path = resolve_project_path(storage, None)?;
+        let actor = actor.map_or_else(default_actor, ToString::to_string);
+        Ok(Self::borrowing(storage, project_path, actor))
+    }
+
+    /// The project operations act on.
+    #[must_use]
+    pub fn project_path(&self) -> &str {
+        &self.project_path
+    }
+
+    /// Tag the issues and memory created from now on with a sub-project of
+    /// the project, by path relative to its root; `None` (the default)
+    /// leaves them to the whole project.
+    pub fn set_subproject(&mut self, subproject: Option<String>) {
+        self.subproject = subproject;
+    }
+
+    /// The sub-project new issues and memory are tagged with.
+    #[must_use]
+    pub fn subproject(&self) -> Option<&str> {
+        self.subproject.as_deref()
+    }
+
+    /// The actor changes are recorded as.
+    #[must_use]
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    /// The underlying storage, for operations this module doesn't cover.
+    #[must_use]
+    pub fn storage(&self) -> &SqliteStorage {
+        &self.storage
+    }
+
+    /// Mutable access to the underlying storage.
+    pub fn storage_mut(&mut self) -> &mut SqliteStorage {
+        &mut self.storage
+    }
+}
+
+/// A new record ID: `prefix`, an underscore and 12 characters of a UUID.
+fn new_id(prefix: &str) -> String {
+    format!("{prefix}_{}", &uuid::Uuid::new_v4().to_string()[..12])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{IssueStatus, IssueType, ItemCategory, MemoryCategory, SessionStatus};
+
+    #[test]
+    fn test_operations_return_records() {
+        let mut sc = SaveContext::with_storage(SqliteStorage::open_memory().unwrap(), "/repo", "tool");
+
+        let memory = sc.save_memory("test", "cargo test", MemoryCategory::Command).unwrap();
+        assert_eq!((memory.project_path.as_str(), memory.category), ("/repo", MemoryCategory::Command));
+        assert_eq!(sc.list_memory(Some(MemoryCategory::Note)).unwrap().len(), 0);
+
+        let new = NewSession { name: "Work".to_string(), ..NewSession::default() };
+        let started = sc.start_session(&new).unwrap();
+        assert!(!started.resumed);
+        let session_id = started.session.id;
+        sc.set_session_status(&session_id, SessionStatus::Paused).unwrap();
+        let resumed = sc.start_session(&new).unwrap();
+        assert!(resumed.resumed);
+        assert_eq!((resumed.session.id.as_str(), resumed.session.status), (session_id.as_str(), SessionStatus::Active));
+
+        let item = NewItem {
+            key: "k".to_string(),
+            value: "v1".to_string(),
+            category: ItemCategory::Decision,
+            ..NewItem::default()
+        };
+        let first = sc.save_item(&session_id, &item).unwrap();
+        let second = sc.save_item(&session_id, &NewItem { value: "v2".to_string(), ..item }).unwrap();
+        assert_eq!((second.id, second.value.as_str()), (first.id, "v2"));
+
+        let issue = sc
+            .create_issue(&NewIssue { title: "Crash".to_string(), issue_type: IssueType::Bug, ..NewIssue::default() })
+            .unwrap();
+        assert_eq!((&issue.status, issue.issue_type), (&IssueStatus::Open, IssueType::Bug));
+        assert_eq!(issue.created_by_agent.as_deref(), Some("tool"));
+        let closed = sc.set_issue_status(&issue.id, &IssueStatus::Closed, None).unwrap();
+        assert_eq!(closed.status, IssueStatus::Closed);
+        assert!(sc.list_issues(&IssueFilter::default()).unwrap().is_empty());
+        let all = IssueFilter { include_closed: true, ..IssueFilter::default() };
+        assert_eq!(sc.list_issues(&all).unwrap().len(), 1);
+
+        // An unknown parent fails the whole creation
+        let orphan = NewIssue { title: "Orphan".to_string(), parent: Some("SC-none".to_string()), ..NewIssue::default() };
+        assert!(sc.create_issue(&orphan).is_err());
+        assert_eq!(sc.list_issues(&all).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,90 @@
+//! Sessions of the project.
+
+use super::{new_id, SaveContext, Session};
+use crate::error::{Error, Result};
+use crate::model::SessionStatus;
+
+/// Input for [`SaveContext::start_session`].
+#[derive(Debug, Clone, Default)]
+pub struct NewSession {
+    pub name: String,
+    pub description: Option<String>,
+    /// Channel for the session's items, usually the git branch.
+    pub channel: Option<String>,
+    /// Linked git worktree the session is started in.
+    pub worktree: Option<String>,
+    /// Always create a new session, even if a paused one has this name.
+    pub force_new: bool,
+}
+
+/// Result of [`SaveContext::start_session`].
+#[derive(Debug, Clone)]
+pub struct StartedSession {
+    pub session: Session,
+    /// Whether a paused session with the same name was resumed.
+    pub resumed: bool,
+}
+
+impl SaveContext<'_> {
+    /// Start a session, resuming the project's paused session of the same
+    /// name unless `force_new` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn start_session(&mut self, new: &NewSession) -> Result<StartedSession> {
+        if !new.force_new {
+            let paused = self.list_sessions(Some(SessionStatus::Paused), Some(10))?;
+            if let Some(session) = paused.into_iter().find(|s| s.name == new.name) {
+                let session = self.set_session_status(&session.id, SessionStatus::Active)?;
+                return Ok(StartedSession { session, resumed: true });
+            }
+        }
+
+        let id = new_id("sess");
+        self.storage.create_session(
+            &id,
+            &new.name,
+            new.description.as_deref(),
+            Some(&self.project_path),
+            new.channel.as_deref(),
+            new.worktree.as_deref(),
+            &self.actor,
+        )?;
+        Ok(StartedSession { session: self.require_session(&id)?, resumed: false })
+    }
+
+    /// The session with `id`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn session(&self, id: &str) -> Result<Option<Session>> {
+        self.storage.get_session(id)
+    }
+
+    /// The project's sessions, optionally with one status, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_sessions(&self, status: Option<SessionStatus>, limit: Option<u32>) -> Result<Vec<Session>> {
+        self.storage
+            .list_sessions(Some(&self.project_path), status.map(SessionStatus::as_str), limit)
+    }
+
+    /// Move a session to `status` and return it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SessionNotFound`] if there is no such session, or
+    /// an error if the write fails.
+    pub fn set_session_status(&mut self, id: &str, status: SessionStatus) -> Result<Session> {
+        self.storage.update_session_status(id, status, &self.actor)?;
+        self.require_session(id)
+    }
+
+    fn require_session(&self, id: &str) -> Result<Session> {
+        self.session(id)?.ok_or_else(|| Error::SessionNotFound { id: id.to_string() })
+    }
+}
@@ -0,0 +1,205 @@
+//! Provider comparison for `sc embeddings benchmark`.
+//!
+//! Speed and quality pull in opposite directions: `Model2Vec` embeds in
+//! microseconds but retrieves less precisely than a transformer served by
+//! Ollama or Hugging Face. The benchmark measures both on the user's own
+//! data. Each sampled context item is a labeled pair: its key, split into
+//! words, is the query and its value is the one document that should come
+//! back first. Every provider embeds the same documents and queries, so
+//! latency, throughput, recall and MRR are directly comparable.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::json_schema::json_schema;
+use crate::storage::sqlite::cosine_similarity;
+
+use super::provider::BoxedProvider;
+use super::rate_limit::{embed_with_retry, RateLimiter};
+
+/// Rank cutoff for the recall figure.
+pub const RECALL_AT: usize = 5;
+
+/// A query and the document it should retrieve.
+#[derive(Debug, Clone)]
+pub struct LabeledPair {
+    pub query: String,
+    pub document: String,
+}
+
+impl LabeledPair {
+    /// Build a pair from a context item, or `None` if its key has no words
+    /// to search with.
+    #[must_use]
+    pub fn from_item(key: &str, value: &str) -> Option<Self> {
+        let query = key
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        (query.chars().any(char::is_alphabetic) && !value.trim().is_empty())
+            .then(|| Self { query, document: value.to_string() })
+    }
+}
+
+/// One provider's results.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkResult {
+    pub provider: String,
+    pub model: String,
+    pub dimensions: usize,
+    pub samples: usize,
+    /// Median time to embed one document.
+    pub p50_ms: f64,
+    /// 95th percentile time to embed one document.
+    pub p95_ms: f64,
+    /// Documents embedded per second.
+    pub items_per_sec: f64,
+    /// Share of queries whose document ranked in the top [`RECALL_AT`].
+    pub recall: f64,
+    /// Mean reciprocal rank of each query's document.
+    pub mrr: f64,
+    /// Why the run stopped, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+json_schema!(BenchmarkResult {
+    provider: String,
+    model: String,
+    dimensions: usize,
+    samples: usize,
+    p50_ms: f64,
+    p95_ms: f64,
+    items_per_sec: f64,
+    recall: f64,
+    mrr: f64,
+    error: Option<String>,
+});
+
+/// Embed `pairs` with `provider` and score retrieval.
+///
+/// A provider error ends that provider's run; it is reported in
+/// [`BenchmarkResult::error`] rather than returned, so one failing provider
+/// doesn't hide the others' numbers.
+#[allow(clippy::cast_precision_loss)]
+pub async fn run(provider: &BoxedProvider, pairs: &[LabeledPair]) -> BenchmarkResult {
+    let info = provider.info();
+    let mut result = BenchmarkResult {
+        provider: info.name,
+        model: info.model,
+        dimensions: info.dimensions,
+        samples: pairs.len(),
+        p50_ms: 0.0,
+        p95_ms: 0.0,
+        items_per_sec: 0.0,
+        recall: 0.0,
+        mrr: 0.0,
+        error: None,
+    };
+    match embed_pairs(provider, pairs, info.max_chars).await {
+        Ok((documents, queries, mut latencies, elapsed)) => {
+            latencies.sort();
+            result.p50_ms = percentile_ms(&latencies, 50);
+            result.p95_ms = percentile_ms(&latencies, 95);
+            result.items_per_sec = documents.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+            (result.recall, result.mrr) = retrieval_scores(&queries, &documents);
+            if let Some(first) = documents.first() {
+                result.dimensions = first.len();
+            }
+        }
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    result
+}
+
+/// Document vectors, query vectors, per-document latencies and the time
+/// spent embedding documents.
+type Embedded = (Vec<Vec<f32>>, Vec<Vec<f32>>, Vec<Duration>, Duration);
+
+async fn embed_pairs(provider: &BoxedProvider, pairs: &[LabeledPair], max_chars: usize) -> Result<Embedded> {
+    let limiter = RateLimiter::new(None);
+    let mut documents = Vec::with_capacity(pairs.len());
+    let mut latencies = Vec::with_capacity(pairs.len());
+    let started = Instant::now();
+    for pair in pairs {
+        let text: String = pair.document.chars().take(max_chars).collect();
+        let request = Instant::now();
+        documents.push(embed_with_retry(provider, &limiter, &text).await?.embedding);
+        latencies.push(request.elapsed());
+    }
+    let elapsed = started.elapsed();
+
+    let mut queries = Vec::with_capacity(pairs.len());
+    for pair in pairs {
+        queries.push(embed_with_retry(provider, &limiter, &pair.query).await?.embedding);
+    }
+    Ok((documents, queries, latencies, elapsed))
+}
+
+/// `pct`th percentile of sorted durations, in milliseconds.
+fn percentile_ms(sorted: &[Duration], pct: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let index = (sorted.len() * pct).div_ceil(100).saturating_sub(1);
+    sorted[index.min(sorted.len() - 1)].as_secs_f64() * 1000.0
+}
+
+/// Recall at [`RECALL_AT`] and mean reciprocal rank, where query `i`
+/// should retrieve document `i`.
+#[allow(clippy::cast_precision_loss)]
+fn retrieval_scores(queries: &[Vec<f32>], documents: &[Vec<f32>]) -> (f64, f64) {
+    if queries.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mut hits = 0;
+    let mut reciprocal_ranks = 0.0;
+    for (i, query) in queries.iter().enumerate() {
+        let target = cosine_similarity(query, &documents[i]);
+        let rank = documents
+            .iter()
+            .enumerate()
+            .filter(|(j, doc)| *j != i && cosine_similarity(query, doc) > target)
+            .count();
+        if rank < RECALL_AT {
+            hits += 1;
+        }
+        reciprocal_ranks += 1.0 / (rank + 1) as f64;
+    }
+    let count = queries.len() as f64;
+    (f64::from(hits) / count, reciprocal_ranks / count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labeled_pair_from_item() {
+        let pair = LabeledPair::from_item("auth-token_refresh", "Refresh tokens rotate hourly").unwrap();
+        assert_eq!(pair.query, "auth token refresh");
+        assert!(LabeledPair::from_item("123", "value").is_none());
+        assert!(LabeledPair::from_item("key", "  ").is_none());
+    }
+
+    #[test]
+    fn test_retrieval_scores() {
+        let documents = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.7, 0.7]];
+        // Query 0 finds its document first; query 1 is closest to document 2
+        let queries = vec![vec![1.0, 0.1], vec![0.6, 0.8], vec![0.7, 0.7]];
+        let (recall, mrr) = retrieval_scores(&queries, &documents);
+        assert!((recall - 1.0).abs() < f64::EPSILON);
+        assert!((mrr - (1.0 + 0.5 + 1.0) / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_percentile_ms() {
+        let sorted: Vec<_> = (1..=20).map(Duration::from_millis).collect();
+        assert!((percentile_ms(&sorted, 50) - 10.0).abs() < 1e-9);
+        assert!((percentile_ms(&sorted, 95) - 19.0).abs() < 1e-9);
+        assert!(percentile_ms(&[], 50).abs() < f64::EPSILON);
+    }
+}
@@ -7,7 +7,7 @@ use crate::error::{Error, Result};
 use std::fs;
 use std::path::PathBuf;
 
-use super::types::{EmbeddingSettings, SaveContextConfig};
+use super::types::{EmbeddingProviderType, EmbeddingSettings, SaveContextConfig};
 
 /// Get the config file path.
 fn config_path() -> Result<PathBuf> {
@@ -28,8 +28,14 @@ pub fn load_config() -> Result<SaveContextConfig> {
         Error::Config(format!("Failed to read config file: {e}"))
     })?;
 
+    let issues = crate::config::schema::validate_str(&content);
+    crate::config::schema::warn_once(&path, &issues);
+
     serde_json::from_str(&content).map_err(|e| {
-        Error::Config(format!("Failed to parse config file: {e}"))
+        Error::Config(format!(
+            "Failed to parse {}: {e}. Run `sc config validate` to see every problem.",
+            path.display()
+        ))
     })
 }
 
@@ -76,6 +82,11 @@ pub fn save_embedding_settings(settings: &EmbeddingSettings) -> Result<()> {
         OLLAMA_ENDPOINT: settings.OLLAMA_ENDPOINT.clone().or(existing.OLLAMA_ENDPOINT),
         OLLAMA_MODEL: settings.OLLAMA_MODEL.clone().or(existing.OLLAMA_MODEL),
         TRANSFORMERS_MODEL: settings.TRANSFORMERS_MODEL.clone().or(existing.TRANSFORMERS_MODEL),
+        providers: settings.providers.clone().or(existing.providers),
+        concurrency: settings.concurrency.or(existing.concurrency),
+        requests_per_minute: settings.requests_per_minute.or(existing.requests_per_minute),
+        keep_other_models: settings.keep_other_models.or(existing.keep_other_models),
+        rerank: settings.rerank.clone().or(existing.rerank),
     });
 
     save_config(&config)
@@ -176,6 +187,28 @@ pub fn resolve_hf_endpoint() -> String {
     "https://router.huggingface.co/hf-inference".to_string()
 }
 
+/// Provider and model new quality embeddings would be made with, read from
+/// config alone without contacting the provider.
+///
+/// Uses the first entry of `providers`, else `provider`. Returns `None`
+/// when neither is configured.
+#[must_use]
+pub fn configured_embedding_model() -> Option<(String, String)> {
+    let settings = get_embedding_settings().ok().flatten()?;
+    let provider = settings
+        .providers
+        .as_ref()
+        .and_then(|chain| chain.first().copied())
+        .or(settings.provider)?;
+    let model = match provider {
+        EmbeddingProviderType::Ollama => resolve_ollama_model(),
+        EmbeddingProviderType::Huggingface => resolve_hf_model(),
+        EmbeddingProviderType::Model2vec => "minishlab/potion-base-8M".to_string(),
+        EmbeddingProviderType::Transformers => return None,
+    };
+    Some((provider.to_string(), model))
+}
+
 /// Check if embeddings are enabled.
 pub fn is_embeddings_enabled() -> bool {
     // Check env var first
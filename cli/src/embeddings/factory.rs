@@ -3,6 +3,7 @@
 //! Handles provider detection and creation.
 
 use super::config::{get_embedding_settings, is_embeddings_enabled};
+use super::failover::FailoverProvider;
 use super::huggingface::HuggingFaceProvider;
 use super::ollama::OllamaProvider;
 use super::provider::{BoxedProvider, EmbeddingProvider};
@@ -48,8 +49,9 @@ pub async fn detect_available_providers() -> ProviderDetection {
 /// Create an embedding provider based on configuration.
 ///
 /// Priority:
-/// 1. Explicit provider in config
-/// 2. Auto-detect available provider (Ollama preferred)
+/// 1. Failover chain in config (`providers`)
+/// 2. Explicit provider in config
+/// 3. Auto-detect available provider (Ollama preferred)
 ///
 /// Returns `None` if no provider is available or embeddings are disabled.
 pub async fn create_embedding_provider() -> Option<BoxedProvider> {
@@ -58,8 +60,12 @@ pub async fn create_embedding_provider() -> Option<BoxedProvider> {
         return None;
     }
 
-    // Check for explicit provider in config
     if let Ok(Some(settings)) = get_embedding_settings() {
+        match settings.providers.as_deref() {
+            Some([provider_type]) => return create_provider_by_type(*provider_type).await,
+            Some(chain) if chain.len() > 1 => return create_failover_chain(chain),
+            _ => {}
+        }
         if let Some(provider_type) = settings.provider {
             return create_provider_by_type(provider_type).await;
         }
@@ -80,6 +86,55 @@ pub async fn create_embedding_provider() -> Option<BoxedProvider> {
     None
 }
 
+/// Create every listed provider that is reachable right now, ignoring the
+/// configured provider and whether embeddings are enabled.
+///
+/// An empty list means Ollama, Hugging Face and `Model2Vec`. Used to compare
+/// providers side by side.
+pub async fn create_available_providers(types: &[EmbeddingProviderType]) -> Vec<BoxedProvider> {
+    let types = if types.is_empty() {
+        &[
+            EmbeddingProviderType::Ollama,
+            EmbeddingProviderType::Huggingface,
+            EmbeddingProviderType::Model2vec,
+        ][..]
+    } else {
+        types
+    };
+    let mut providers = Vec::new();
+    for provider_type in types {
+        let provider = match provider_type {
+            EmbeddingProviderType::Huggingface => match HuggingFaceProvider::new() {
+                Some(hf) if hf.is_available().await => Some(BoxedProvider::new(hf)),
+                _ => None,
+            },
+            other => create_provider_by_type(*other).await,
+        };
+        providers.extend(provider);
+    }
+    providers
+}
+
+/// Create a failover chain over the configured providers.
+///
+/// Members are not probed here: an unreachable one is skipped on its first
+/// failed request. Providers that can't be built at all (Hugging Face
+/// without a token, Transformers) are left out.
+fn create_failover_chain(chain: &[EmbeddingProviderType]) -> Option<BoxedProvider> {
+    let members = chain
+        .iter()
+        .filter_map(|provider_type| match provider_type {
+            EmbeddingProviderType::Ollama => Some(BoxedProvider::new(OllamaProvider::new())),
+            EmbeddingProviderType::Huggingface => HuggingFaceProvider::new().map(BoxedProvider::new),
+            EmbeddingProviderType::Transformers => None,
+            EmbeddingProviderType::Model2vec => {
+                super::model2vec::Model2VecProvider::try_new().map(BoxedProvider::new)
+            }
+        })
+        .collect();
+    FailoverProvider::new(members).map(BoxedProvider::new)
+}
+
 /// Create a specific provider by type.
 async fn create_provider_by_type(provider_type: EmbeddingProviderType) -> Option<BoxedProvider> {
     match provider_type {
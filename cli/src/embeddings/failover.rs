@@ -0,0 +1,136 @@
+//! Provider failover chain.
+//!
+//! `embeddings.providers` lists providers in order of preference, e.g.
+//! `["ollama", "huggingface", "model2vec"]`. [`FailoverProvider`] sends each
+//! request to the first of them that can be reached: when a provider's
+//! server is down it moves on to the next one and stays there for the rest
+//! of the process, so one dead endpoint costs one failed connection rather
+//! than one per chunk.
+//!
+//! Fallbacks usually run a different model, so every embedding is tagged
+//! with the provider that really produced it ([`EmbeddingProvider::generate_attributed`]).
+//! Once the preferred provider is back, `sc embeddings backfill` re-embeds
+//! what the fallbacks produced.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::{Error, Result};
+
+use super::provider::{BoxedProvider, EmbeddingProvider};
+use super::types::{EmbeddingResult, ProviderInfo};
+
+/// Providers tried in order, falling back on connection errors.
+pub struct FailoverProvider {
+    chain: Vec<BoxedProvider>,
+    /// Index of the provider currently answering.
+    active: AtomicUsize,
+}
+
+impl FailoverProvider {
+    /// A chain over `providers`, most preferred first.
+    ///
+    /// Returns `None` for an empty chain.
+    #[must_use]
+    pub fn new(providers: Vec<BoxedProvider>) -> Option<Self> {
+        (!providers.is_empty()).then(|| Self { chain: providers, active: AtomicUsize::new(0) })
+    }
+
+    fn active(&self) -> &BoxedProvider {
+        &self.chain[self.active.load(Ordering::Relaxed)]
+    }
+}
+
+impl EmbeddingProvider for FailoverProvider {
+    fn info(&self) -> ProviderInfo {
+        self.active().info()
+    }
+
+    /// Whether the preferred provider is up; fallbacks are tried per request.
+    async fn is_available(&self) -> bool {
+        self.chain[0].is_available().await
+    }
+
+    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        self.generate_attributed(text).await.map(|result| result.embedding)
+    }
+
+    async fn generate_attributed(&self, text: &str) -> Result<EmbeddingResult> {
+        let last = self.chain.len() - 1;
+        let mut index = self.active.load(Ordering::Relaxed);
+        loop {
+            match self.chain[index].generate_attributed(text).await {
+                Err(Error::EmbeddingUnreachable { provider, message }) if index < last => {
+                    let next = self.chain[index + 1].info().name;
+                    tracing::warn!(%provider, %message, fallback = %next, "Embedding provider unreachable, failing over");
+                    index += 1;
+                    self.active.fetch_max(index, Ordering::Relaxed);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn members(&self) -> Vec<ProviderInfo> {
+        self.chain.iter().map(BoxedProvider::info).collect()
+    }
+}
+
+/// Build the error for a request that never reached the provider.
+pub(crate) fn unreachable(provider: &str, error: &reqwest::Error) -> Error {
+    Error::EmbeddingUnreachable { provider: provider.to_string(), message: error.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider that is either down or returns a fixed vector.
+    struct Fake {
+        name: &'static str,
+        up: bool,
+    }
+
+    impl EmbeddingProvider for Fake {
+        fn info(&self) -> ProviderInfo {
+            ProviderInfo {
+                name: self.name.to_string(),
+                model: format!("{}-model", self.name),
+                dimensions: 2,
+                max_chars: 100,
+                available: self.up,
+            }
+        }
+
+        async fn is_available(&self) -> bool {
+            self.up
+        }
+
+        async fn generate_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+            if self.up {
+                Ok(vec![1.0, 0.0])
+            } else {
+                Err(Error::EmbeddingUnreachable { provider: self.name.to_string(), message: "refused".to_string() })
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_chain() {
+        let chain = FailoverProvider::new(vec![
+            BoxedProvider::new(Fake { name: "primary", up: false }),
+            BoxedProvider::new(Fake { name: "backup", up: true }),
+        ])
+        .unwrap();
+        assert_eq!(chain.info().name, "primary");
+        assert!(!chain.is_available().await);
+
+        let result = chain.generate_attributed("text").await.unwrap();
+        assert_eq!((result.provider.as_str(), result.model.as_str()), ("backup", "backup-model"));
+        assert_eq!(chain.info().name, "backup", "stays on the fallback");
+        assert_eq!(chain.members().len(), 2);
+
+        let dead = FailoverProvider::new(vec![BoxedProvider::new(Fake { name: "only", up: false })]).unwrap();
+        assert!(matches!(dead.generate_embedding("text").await, Err(Error::EmbeddingUnreachable { .. })));
+        assert!(FailoverProvider::new(Vec::new()).is_none());
+    }
+}
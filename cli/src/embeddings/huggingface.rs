@@ -100,6 +100,7 @@ impl EmbeddingProvider for HuggingFaceProvider {
         !self.token.is_empty()
     }
 
+    #[tracing::instrument(name = "embedding.generate", skip_all, fields(provider = "huggingface", model = %self.model, texts = 1))]
     async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
         let url = format!("{}/models/{}/pipeline/feature-extraction", self.endpoint, self.model);
 
@@ -114,7 +115,11 @@ impl EmbeddingProvider for HuggingFaceProvider {
             .json(&request)
             .send()
             .await
-            .map_err(|e| Error::Embedding(format!("HuggingFace request failed: {e}")))?;
+            .map_err(|e| super::failover::unreachable("huggingface", &e))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(super::rate_limit::rate_limited("huggingface", &response));
+        }
 
         if !response.status().is_success() {
             let status = response.status();
@@ -144,6 +149,7 @@ impl EmbeddingProvider for HuggingFaceProvider {
         }
     }
 
+    #[tracing::instrument(name = "embedding.generate", skip_all, fields(provider = "huggingface", model = %self.model, texts = texts.len()))]
     async fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         let url = format!("{}/models/{}/pipeline/feature-extraction", self.endpoint, self.model);
 
@@ -158,7 +164,11 @@ impl EmbeddingProvider for HuggingFaceProvider {
             .json(&request)
             .send()
             .await
-            .map_err(|e| Error::Embedding(format!("HuggingFace batch request failed: {e}")))?;
+            .map_err(|e| super::failover::unreachable("huggingface", &e))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(super::rate_limit::rate_limited("huggingface", &response));
+        }
 
         if !response.status().is_success() {
             let status = response.status();
@@ -59,30 +59,37 @@
 //! }
 //! ```
 
+pub mod benchmark;
 pub mod chunking;
 pub mod config;
 pub mod factory;
+pub mod failover;
 pub mod huggingface;
 pub mod model2vec;
 pub mod ollama;
 pub mod provider;
+pub mod rate_limit;
+pub mod rerank;
 pub mod types;
 
 // Re-exports for convenience
 pub use config::{
-    get_embedding_settings, is_embeddings_enabled, resolve_hf_model, resolve_hf_token,
+    configured_embedding_model, get_embedding_settings, is_embeddings_enabled, resolve_hf_model, resolve_hf_token,
     resolve_ollama_endpoint, resolve_ollama_model, reset_embedding_settings, save_embedding_settings,
 };
 pub use factory::{
-    create_embedding_provider, create_huggingface_provider, create_ollama_provider,
-    detect_available_providers, ProviderDetection,
+    create_available_providers, create_embedding_provider, create_huggingface_provider,
+    create_ollama_provider, detect_available_providers, ProviderDetection,
 };
+pub use failover::FailoverProvider;
 pub use huggingface::HuggingFaceProvider;
 pub use model2vec::Model2VecProvider;
 pub use ollama::OllamaProvider;
 pub use provider::{BoxedProvider, EmbeddingProvider};
+pub use rate_limit::{embed_with_retry, Limits, RateLimiter};
+pub use rerank::Reranker;
 pub use types::{
-    EmbeddingProviderType, EmbeddingResult, EmbeddingSettings, ModelConfig, ProviderInfo,
-    SaveContextConfig, SearchMode, TieredEmbeddingSettings, model2vec_models,
+    EmbeddingEntity, EmbeddingProviderType, EmbeddingResult, EmbeddingSettings, ModelConfig, ProviderInfo,
+    RerankKind, RerankSettings, SaveContextConfig, SearchMode, TieredEmbeddingSettings, model2vec_models,
 };
 pub use chunking::{chunk_text, prepare_item_text, ChunkConfig, TextChunk};
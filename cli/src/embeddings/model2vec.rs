@@ -92,6 +92,7 @@ impl EmbeddingProvider for Model2VecProvider {
         true
     }
 
+    #[tracing::instrument(name = "embedding.generate", skip_all, fields(provider = "model2vec", model = %self.model_name, texts = 1))]
     async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
         // Model2Vec encode expects Vec<String>
         let sentences = vec![text.to_string()];
@@ -103,6 +104,7 @@ impl EmbeddingProvider for Model2VecProvider {
             .ok_or_else(|| Error::Embedding("Model2Vec returned no embeddings".into()))
     }
 
+    #[tracing::instrument(name = "embedding.generate", skip_all, fields(provider = "model2vec", model = %self.model_name, texts = texts.len()))]
     async fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         // Convert to owned strings for Model2Vec
         let sentences: Vec<String> = texts.iter().map(|&s| s.to_string()).collect();
@@ -119,6 +119,7 @@ impl EmbeddingProvider for OllamaProvider {
         })
     }
 
+    #[tracing::instrument(name = "embedding.generate", skip_all, fields(provider = "ollama", model = %self.model, texts = 1))]
     async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
         let url = format!("{}/api/embed", self.endpoint);
 
@@ -132,7 +133,11 @@ impl EmbeddingProvider for OllamaProvider {
             .json(&request)
             .send()
             .await
-            .map_err(|e| Error::Embedding(format!("Ollama request failed: {e}")))?;
+            .map_err(|e| super::failover::unreachable("ollama", &e))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(super::rate_limit::rate_limited("ollama", &response));
+        }
 
         if !response.status().is_success() {
             let error = response.text().await.unwrap_or_default();
@@ -146,6 +151,7 @@ impl EmbeddingProvider for OllamaProvider {
             .ok_or_else(|| Error::Embedding("No embeddings returned from Ollama".into()))
     }
 
+    #[tracing::instrument(name = "embedding.generate", skip_all, fields(provider = "ollama", model = %self.model, texts = texts.len()))]
     async fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         let url = format!("{}/api/embed", self.endpoint);
 
@@ -159,7 +165,11 @@ impl EmbeddingProvider for OllamaProvider {
             .json(&request)
             .send()
             .await
-            .map_err(|e| Error::Embedding(format!("Ollama batch request failed: {e}")))?;
+            .map_err(|e| super::failover::unreachable("ollama", &e))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(super::rate_limit::rate_limited("ollama", &response));
+        }
 
         if !response.status().is_success() {
             let error = response.text().await.unwrap_or_default();
@@ -4,7 +4,7 @@
 //! Uses async methods for HTTP-based providers.
 
 use crate::error::Result;
-use super::types::ProviderInfo;
+use super::types::{EmbeddingResult, ProviderInfo};
 
 /// Trait for embedding providers.
 ///
@@ -35,6 +35,28 @@ pub trait EmbeddingProvider: Send + Sync {
             Ok(results)
         }
     }
+
+    /// Generate an embedding tagged with the provider and model that made it.
+    ///
+    /// Only the failover chain overrides this: a fallback may have answered
+    /// instead of the provider `info()` described when the caller started.
+    fn generate_attributed(&self, text: &str) -> impl std::future::Future<Output = Result<EmbeddingResult>> + Send {
+        async move {
+            let embedding = self.generate_embedding(text).await?;
+            let info = self.info();
+            Ok(EmbeddingResult {
+                dimensions: embedding.len(),
+                embedding,
+                model: info.model,
+                provider: info.name,
+            })
+        }
+    }
+
+    /// Providers this one may embed with, most preferred first.
+    fn members(&self) -> Vec<ProviderInfo> {
+        vec![self.info()]
+    }
 }
 
 /// Boxed provider for dynamic dispatch.
@@ -51,6 +73,8 @@ pub trait EmbeddingProviderBoxed: Send + Sync {
     fn is_available_boxed(&self) -> std::pin::Pin<Box<dyn std::future::Future<Output = bool> + Send + '_>>;
     fn generate_embedding_boxed(&self, text: &str) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<f32>>> + Send + '_>>;
     fn generate_embeddings_boxed(&self, texts: &[&str]) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<Vec<f32>>>> + Send + '_>>;
+    fn generate_attributed_boxed(&self, text: &str) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<EmbeddingResult>> + Send + '_>>;
+    fn members(&self) -> Vec<ProviderInfo>;
 }
 
 impl BoxedProvider {
@@ -80,6 +104,21 @@ impl BoxedProvider {
     pub async fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         self.inner.generate_embeddings_boxed(texts).await
     }
+
+    /// Generate an embedding tagged with the provider and model that made it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the provider that was asked last.
+    pub async fn generate_attributed(&self, text: &str) -> Result<EmbeddingResult> {
+        self.inner.generate_attributed_boxed(text).await
+    }
+
+    /// Providers this one may embed with, most preferred first.
+    #[must_use]
+    pub fn members(&self) -> Vec<ProviderInfo> {
+        self.inner.members()
+    }
 }
 
 /// Wrapper to implement EmbeddingProviderBoxed for any EmbeddingProvider.
@@ -114,4 +153,13 @@ impl<P: EmbeddingProvider + 'static> EmbeddingProviderBoxed for BoxedProviderWra
             self.0.generate_embeddings(&refs).await
         })
     }
+
+    fn generate_attributed_boxed(&self, text: &str) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<EmbeddingResult>> + Send + '_>> {
+        let text_owned = text.to_string();
+        Box::pin(async move { self.0.generate_attributed(&text_owned).await })
+    }
+
+    fn members(&self) -> Vec<ProviderInfo> {
+        self.0.members()
+    }
 }
@@ -0,0 +1,170 @@
+//! Request pacing and retry for bulk embedding.
+//!
+//! Hosted providers throttle bulk work: the Hugging Face inference API starts
+//! answering 429 long before a backfill of a large database is done.
+//! [`RateLimiter`] spaces requests to a per-minute budget shared by every
+//! concurrent worker, and [`embed_with_retry`] backs off on 429 (honouring
+//! `Retry-After`) by pausing the whole limiter, so other workers don't keep
+//! hammering the provider while one of them waits.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+
+use super::config::get_embedding_settings;
+use super::provider::BoxedProvider;
+use super::types::EmbeddingResult;
+
+/// Retries of a single rate-limited request before giving up.
+const MAX_RETRIES: u32 = 5;
+
+/// Longest single backoff, whatever `Retry-After` asks for.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Concurrency and pacing for one provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Requests in flight at once.
+    pub concurrency: usize,
+    /// Request budget per minute; `None` is unlimited.
+    pub requests_per_minute: Option<u32>,
+}
+
+impl Limits {
+    /// Built-in limits for a provider.
+    ///
+    /// Ollama runs locally and only needs a little parallelism to keep the
+    /// model busy; Hugging Face is paced to stay under the free tier.
+    #[must_use]
+    pub fn defaults_for(provider: &str) -> Self {
+        match provider {
+            "ollama" => Self { concurrency: 4, requests_per_minute: None },
+            "huggingface" => Self { concurrency: 4, requests_per_minute: Some(240) },
+            _ => Self { concurrency: 1, requests_per_minute: None },
+        }
+    }
+
+    /// Limits for a provider with `embeddings.concurrency` and
+    /// `embeddings.requests_per_minute` applied (0 means unlimited).
+    #[must_use]
+    pub fn configured_for(provider: &str) -> Self {
+        let mut limits = Self::defaults_for(provider);
+        if let Ok(Some(settings)) = get_embedding_settings() {
+            if let Some(concurrency) = settings.concurrency {
+                limits.concurrency = concurrency.max(1);
+            }
+            if let Some(rpm) = settings.requests_per_minute {
+                limits.requests_per_minute = (rpm > 0).then_some(rpm);
+            }
+        }
+        limits
+    }
+}
+
+/// Shared pacing for concurrent requests to one provider.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// A limiter allowing `requests_per_minute` (unlimited when `None`).
+    #[must_use]
+    pub fn new(requests_per_minute: Option<u32>) -> Self {
+        let interval = requests_per_minute
+            .filter(|rpm| *rpm > 0)
+            .map_or(Duration::ZERO, |rpm| Duration::from_secs(60) / rpm);
+        Self { interval, next: Mutex::new(Instant::now()) }
+    }
+
+    /// Wait for the next request slot.
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut next = self.next.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+            let now = Instant::now();
+            let slot = (*next).max(now);
+            *next = slot + self.interval;
+            slot - now
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Hold every request back for at least `delay`.
+    pub fn pause(&self, delay: Duration) {
+        let mut next = self.next.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let until = Instant::now() + delay;
+        if *next < until {
+            *next = until;
+        }
+    }
+}
+
+/// Delay before retry number `attempt` (0-based).
+fn backoff(attempt: u32, retry_after: Option<u64>) -> Duration {
+    retry_after
+        .map_or_else(|| Duration::from_secs(1 << attempt.min(6)), Duration::from_secs)
+        .min(MAX_BACKOFF)
+}
+
+/// Generate an embedding, pacing through `limiter` and retrying on 429.
+///
+/// The result names the provider that produced it, which differs from
+/// `provider.info()` when a failover chain fell back.
+///
+/// # Errors
+///
+/// Returns the provider's error, or [`Error::EmbeddingRateLimited`] once
+/// the retries run out.
+pub async fn embed_with_retry(provider: &BoxedProvider, limiter: &RateLimiter, text: &str) -> Result<EmbeddingResult> {
+    let mut attempt = 0;
+    loop {
+        limiter.acquire().await;
+        match provider.generate_attributed(text).await {
+            Err(Error::EmbeddingRateLimited { provider, retry_after }) if attempt < MAX_RETRIES => {
+                let delay = backoff(attempt, retry_after);
+                tracing::debug!(%provider, attempt, delay_ms = delay.as_millis(), "Rate limited, backing off");
+                limiter.pause(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Build the error for a 429 response.
+pub(crate) fn rate_limited(provider: &str, response: &reqwest::Response) -> Error {
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok());
+    Error::EmbeddingRateLimited { provider: provider.to_string(), retry_after }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0, None), Duration::from_secs(1));
+        assert_eq!(backoff(3, None), Duration::from_secs(8));
+        assert_eq!(backoff(10, None), MAX_BACKOFF);
+        assert_eq!(backoff(0, Some(7)), Duration::from_secs(7));
+        assert_eq!(backoff(0, Some(3600)), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_limiter_spacing() {
+        let limiter = RateLimiter::new(Some(120));
+        assert_eq!(limiter.interval, Duration::from_millis(500));
+        assert_eq!(RateLimiter::new(Some(0)).interval, Duration::ZERO);
+
+        limiter.pause(Duration::from_secs(30));
+        let next = *limiter.next.lock().unwrap();
+        assert!(next >= Instant::now() + Duration::from_secs(29));
+    }
+}
@@ -0,0 +1,215 @@
+//! Final re-ranking stage for `--search-mode rerank`.
+//!
+//! Bi-encoder similarity ranks candidates by how close two vectors are,
+//! which misses negation, exact identifiers and other nuance. A
+//! cross-encoder (or an LLM asked to grade relevance) reads the query and
+//! each candidate together and scores them far more precisely, at the cost
+//! of a network round trip per search. [`Reranker`] sends the top-K quality
+//! candidates to the scorer configured under `embeddings.rerank` and gives
+//! up once the latency budget is spent, so a slow scorer degrades search
+//! to the quality ranking instead of stalling it.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+use super::config::get_embedding_settings;
+use super::types::RerankKind;
+
+/// Candidates sent to the scorer when `top_k` is unset.
+const DEFAULT_TOP_K: usize = 20;
+
+/// Latency budget when `budget_ms` is unset.
+const DEFAULT_BUDGET_MS: u64 = 2000;
+
+/// Longest candidate excerpt put into an LLM scoring prompt.
+const LLM_PASSAGE_CHARS: usize = 600;
+
+const LLM_SYSTEM_PROMPT: &str = "You grade search results. For each numbered passage, rate how well it \
+answers the query from 0 (unrelated) to 10 (exactly what was asked for). Reply with only a JSON array \
+of numbers, one per passage, in passage order.";
+
+/// A configured cross-encoder or LLM scorer.
+pub struct Reranker {
+    client: reqwest::Client,
+    kind: RerankKind,
+    endpoint: String,
+    model: Option<String>,
+    api_key: Option<String>,
+    /// Candidates to fetch and score.
+    pub top_k: usize,
+    /// Time allowed for the scoring call.
+    pub budget: Duration,
+}
+
+impl Reranker {
+    /// Build the scorer from `embeddings.rerank` in config.json.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if no rerank endpoint is configured.
+    pub fn from_config() -> Result<Self> {
+        let settings = get_embedding_settings()?
+            .and_then(|s| s.rerank)
+            .unwrap_or_default();
+        let endpoint = settings.endpoint.filter(|e| !e.trim().is_empty()).ok_or_else(|| {
+            Error::Config(
+                "--search-mode rerank needs embeddings.rerank.endpoint in ~/.savecontext/config.json".to_string(),
+            )
+        })?;
+        Ok(Self {
+            client: reqwest::Client::new(),
+            kind: settings.kind.unwrap_or_default(),
+            endpoint,
+            model: settings.model,
+            api_key: settings.api_key,
+            top_k: settings.top_k.unwrap_or(DEFAULT_TOP_K).max(1),
+            budget: Duration::from_millis(settings.budget_ms.unwrap_or(DEFAULT_BUDGET_MS)),
+        })
+    }
+
+    /// Score each document's relevance to `query`, in document order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Embedding`] if the scorer fails, answers with
+    /// something unparseable, or takes longer than the budget.
+    pub async fn score(&self, query: &str, documents: &[&str]) -> Result<Vec<f32>> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+        let request = async {
+            match self.kind {
+                RerankKind::CrossEncoder => self.score_cross_encoder(query, documents).await,
+                RerankKind::Llm => self.score_llm(query, documents).await,
+            }
+        };
+        tokio::time::timeout(self.budget, request).await.map_err(|_| {
+            Error::Embedding(format!("re-rank exceeded {}ms budget", self.budget.as_millis()))
+        })?
+    }
+
+    async fn post(&self, body: &Value) -> Result<Value> {
+        let mut request = self.client.post(&self.endpoint).json(body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::Embedding(format!("Re-rank request failed: {e}")))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(Error::Embedding(format!("Re-rank endpoint error {status}: {text}")));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Embedding(format!("Failed to parse re-rank response: {e}")))
+    }
+
+    async fn score_cross_encoder(&self, query: &str, documents: &[&str]) -> Result<Vec<f32>> {
+        // text-embeddings-inference reads `texts`; Cohere and Jina read `documents`.
+        let mut body = serde_json::json!({
+            "query": query,
+            "texts": documents,
+            "documents": documents,
+        });
+        if let Some(model) = &self.model {
+            body["model"] = Value::from(model.as_str());
+        }
+        parse_cross_encoder_scores(&self.post(&body).await?, documents.len())
+    }
+
+    async fn score_llm(&self, query: &str, documents: &[&str]) -> Result<Vec<f32>> {
+        let mut prompt = format!("Query: {query}\n\nPassages:\n");
+        for (i, doc) in documents.iter().enumerate() {
+            let excerpt: String = doc.replace('\n', " ").chars().take(LLM_PASSAGE_CHARS).collect();
+            let _ = writeln!(prompt, "[{}] {excerpt}", i + 1);
+        }
+        let mut body = serde_json::json!({
+            "messages": [
+                { "role": "system", "content": LLM_SYSTEM_PROMPT },
+                { "role": "user", "content": prompt },
+            ],
+            "temperature": 0,
+        });
+        if let Some(model) = &self.model {
+            body["model"] = Value::from(model.as_str());
+        }
+        let response = self.post(&body).await?;
+        let content = response["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| Error::Embedding("Re-rank LLM response has no message content".to_string()))?;
+        parse_llm_scores(content, documents.len())
+    }
+}
+
+/// Read `[{index, score}]` (text-embeddings-inference) or
+/// `{results: [{index, relevance_score}]}` (Cohere, Jina). Documents the
+/// scorer left out score 0.
+#[allow(clippy::cast_possible_truncation)]
+fn parse_cross_encoder_scores(response: &Value, count: usize) -> Result<Vec<f32>> {
+    let entries = response
+        .as_array()
+        .or_else(|| response["results"].as_array())
+        .ok_or_else(|| Error::Embedding("Unrecognised re-rank response".to_string()))?;
+    let mut scores = vec![0.0; count];
+    for entry in entries {
+        let index = entry["index"].as_u64().and_then(|i| usize::try_from(i).ok());
+        let score = entry["relevance_score"].as_f64().or_else(|| entry["score"].as_f64());
+        match (index, score) {
+            (Some(i), Some(score)) if i < count => scores[i] = score as f32,
+            _ => return Err(Error::Embedding(format!("Unrecognised re-rank entry: {entry}"))),
+        }
+    }
+    Ok(scores)
+}
+
+/// Pull the JSON array of 0-10 grades out of an LLM reply and scale it to 0-1.
+fn parse_llm_scores(content: &str, count: usize) -> Result<Vec<f32>> {
+    let array = content
+        .find('[')
+        .zip(content.rfind(']'))
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| &content[start..=end])
+        .ok_or_else(|| Error::Embedding("Re-rank LLM reply has no score array".to_string()))?;
+    let grades: Vec<f32> = serde_json::from_str(array)
+        .map_err(|e| Error::Embedding(format!("Re-rank LLM reply is not a score array: {e}")))?;
+    if grades.len() != count {
+        return Err(Error::Embedding(format!(
+            "Re-rank LLM scored {} passages, expected {count}",
+            grades.len()
+        )));
+    }
+    Ok(grades.into_iter().map(|g| (g / 10.0).clamp(0.0, 1.0)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cross_encoder_scores() {
+        let tei = serde_json::json!([{ "index": 1, "score": 0.9 }, { "index": 0, "score": 0.1 }]);
+        assert_eq!(parse_cross_encoder_scores(&tei, 2).unwrap(), vec![0.1, 0.9]);
+
+        let cohere = serde_json::json!({ "results": [{ "index": 2, "relevance_score": 0.5 }] });
+        assert_eq!(parse_cross_encoder_scores(&cohere, 3).unwrap(), vec![0.0, 0.0, 0.5]);
+
+        assert!(parse_cross_encoder_scores(&serde_json::json!({ "error": "x" }), 1).is_err());
+        assert!(parse_cross_encoder_scores(&serde_json::json!([{ "index": 5, "score": 1.0 }]), 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_llm_scores() {
+        assert_eq!(parse_llm_scores("[10, 5, 0]", 3).unwrap(), vec![1.0, 0.5, 0.0]);
+        assert_eq!(parse_llm_scores("Scores:\n```json\n[2, 12]\n```", 2).unwrap(), vec![0.2, 1.0]);
+        assert!(parse_llm_scores("[1, 2]", 3).is_err());
+        assert!(parse_llm_scores("no idea", 1).is_err());
+    }
+}
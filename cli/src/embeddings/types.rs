@@ -3,7 +3,9 @@
 //! Mirrors the TypeScript `EmbeddingSettings` and `EmbeddingProvider` interfaces
 //! to maintain config compatibility with `~/.savecontext/config.json`.
 
+use crate::json_schema::{json_schema, object, schema_for, string_enum, JsonSchema};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Embedding provider types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -16,6 +18,19 @@ pub enum EmbeddingProviderType {
     Model2vec,
 }
 
+impl std::str::FromStr for EmbeddingProviderType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ollama" => Ok(Self::Ollama),
+            "huggingface" | "hf" => Ok(Self::Huggingface),
+            "model2vec" | "local" => Ok(Self::Model2vec),
+            _ => Err(format!("Unknown provider: {s}. Valid options: ollama, huggingface, model2vec")),
+        }
+    }
+}
+
 impl std::fmt::Display for EmbeddingProviderType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -41,14 +56,111 @@ pub struct EmbeddingSettings {
     pub OLLAMA_ENDPOINT: Option<String>,
     pub OLLAMA_MODEL: Option<String>,
     pub TRANSFORMERS_MODEL: Option<String>,
+    /// Failover chain, most preferred first; overrides `provider` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub providers: Option<Vec<EmbeddingProviderType>>,
+    /// Parallel requests during backfill (provider default when unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+    /// Request budget per minute during backfill; 0 is unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+    /// On a provider or model change, archive the old embeddings and carry
+    /// on instead of refusing until `sc embeddings migrate` is run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_other_models: Option<bool>,
+    /// Scorer used by `--search-mode rerank`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankSettings>,
+}
+
+/// How `--search-mode rerank` scores candidates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RerankKind {
+    /// A cross-encoder `/rerank` endpoint (text-embeddings-inference,
+    /// Cohere or Jina style).
+    #[default]
+    CrossEncoder,
+    /// An OpenAI-compatible chat completions endpoint asked to score each
+    /// candidate from 0 to 10.
+    Llm,
+}
+
+/// Re-ranking settings (`embeddings.rerank` in config.json).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RerankSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<RerankKind>,
+    /// Full URL of the rerank or chat completions endpoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Sent as a bearer token when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Candidates passed to the scorer (default 20).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<usize>,
+    /// Latency budget for the scoring call in milliseconds (default 2000).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_ms: Option<u64>,
+}
+
+impl JsonSchema for EmbeddingProviderType {
+    fn json_schema() -> Value {
+        string_enum(&["ollama", "huggingface", "transformers", "model2vec"])
+    }
+}
+
+impl JsonSchema for RerankKind {
+    fn json_schema() -> Value {
+        string_enum(&["cross-encoder", "llm"])
+    }
+}
+
+// The upper-case fields can't be bound by name in `json_schema!`.
+impl JsonSchema for EmbeddingSettings {
+    fn json_schema() -> Value {
+        let string = schema_for::<Option<String>>;
+        object(&[
+            ("enabled", schema_for::<Option<bool>>(), false),
+            ("provider", schema_for::<Option<EmbeddingProviderType>>(), false),
+            ("HF_TOKEN", string(), false),
+            ("HF_MODEL", string(), false),
+            ("HF_ENDPOINT", string(), false),
+            ("OLLAMA_ENDPOINT", string(), false),
+            ("OLLAMA_MODEL", string(), false),
+            ("TRANSFORMERS_MODEL", string(), false),
+            ("providers", schema_for::<Option<Vec<EmbeddingProviderType>>>(), false),
+            ("concurrency", schema_for::<Option<usize>>(), false),
+            ("requests_per_minute", schema_for::<Option<u32>>(), false),
+            ("keep_other_models", schema_for::<Option<bool>>(), false),
+            ("rerank", schema_for::<Option<RerankSettings>>(), false),
+        ])
+    }
 }
 
+json_schema!(RerankSettings {
+    kind: Option<RerankKind>,
+    endpoint: Option<String>,
+    model: Option<String>,
+    api_key: Option<String>,
+    top_k: Option<usize>,
+    budget_ms: Option<u64>,
+});
+
 /// SaveContext local configuration file structure.
 ///
 /// Stored at `~/.savecontext/config.json`.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SaveContextConfig {
     pub embeddings: Option<EmbeddingSettings>,
+    /// Other sections (remote, workflow, ...), kept so saving embedding
+    /// settings doesn't drop them.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Result from embedding generation.
@@ -221,6 +333,13 @@ pub enum SearchMode {
     /// Tiered (default) - fast candidates, quality re-ranking
     #[default]
     Tiered,
+    /// Quality candidates re-scored by a cross-encoder or LLM
+    Rerank,
+}
+
+impl SearchMode {
+    /// Every mode, fastest first.
+    pub const ALL: &'static [Self] = &[Self::Fast, Self::Quality, Self::Tiered, Self::Rerank];
 }
 
 impl std::fmt::Display for SearchMode {
@@ -229,6 +348,7 @@ impl std::fmt::Display for SearchMode {
             Self::Fast => write!(f, "fast"),
             Self::Quality => write!(f, "quality"),
             Self::Tiered => write!(f, "tiered"),
+            Self::Rerank => write!(f, "rerank"),
         }
     }
 }
@@ -241,11 +361,57 @@ impl std::str::FromStr for SearchMode {
             "fast" => Ok(Self::Fast),
             "quality" => Ok(Self::Quality),
             "tiered" => Ok(Self::Tiered),
+            "rerank" => Ok(Self::Rerank),
             _ => Err(format!("Unknown search mode: {s}")),
         }
     }
 }
 
+/// Kinds of records that get embeddings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingEntity {
+    ContextItem,
+    Issue,
+    Plan,
+    Memory,
+}
+
+impl EmbeddingEntity {
+    pub const ALL: [Self; 4] = [Self::ContextItem, Self::Issue, Self::Plan, Self::Memory];
+
+    /// Value of `embedding_chunks.entity_type`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ContextItem => "context_item",
+            Self::Issue => "issue",
+            Self::Plan => "plan",
+            Self::Memory => "memory",
+        }
+    }
+}
+
+impl std::fmt::Display for EmbeddingEntity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for EmbeddingEntity {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "items" | "item" | "context" | "context_item" => Ok(Self::ContextItem),
+            "issues" | "issue" => Ok(Self::Issue),
+            "plans" | "plan" => Ok(Self::Plan),
+            "memory" | "memories" => Ok(Self::Memory),
+            _ => Err(format!("Unknown entity: {s} (use items, issues, plans or memory)")),
+        }
+    }
+}
+
 /// Tiered embedding settings for 2-tier architecture.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TieredEmbeddingSettings {
@@ -40,6 +40,7 @@ pub enum ErrorCode {
     InvalidPriority,
     InvalidArgument,
     InvalidSessionStatus,
+    InvalidTransition,
     RequiredField,
 
     // Dependency (exit 5)
@@ -66,14 +67,49 @@ pub enum ErrorCode {
     // Remote (exit 11)
     RemoteError,
 
+    // Context window (exit 12-13), from `sc compaction check`
+    CompactionSuggested,
+    CompactionRequired,
+
+    // Permissions (exit 14)
+    PermissionDenied,
+    PolicyDenied,
+
+    // Concurrency (exit 15)
+    Conflict,
+
+    // Claim policy (exit 16)
+    PolicyViolation,
+
     // Internal (exit 1)
     InternalError,
 }
 
 impl ErrorCode {
+    /// Every code, in the order declared.
+    pub const ALL: &'static [Self] = &[
+        Self::NotInitialized, Self::AlreadyInitialized, Self::DatabaseError,
+        Self::SessionNotFound, Self::IssueNotFound, Self::CheckpointNotFound, Self::ProjectNotFound,
+        Self::NoActiveSession, Self::AmbiguousId,
+        Self::InvalidStatus, Self::InvalidType, Self::InvalidPriority, Self::InvalidArgument,
+        Self::InvalidSessionStatus, Self::InvalidTransition, Self::RequiredField,
+        Self::CycleDetected, Self::HasDependents,
+        Self::SyncError,
+        Self::ConfigError,
+        Self::IoError, Self::JsonError,
+        Self::EmbeddingError,
+        Self::SkillInstallError, Self::DownloadError,
+        Self::RemoteError,
+        Self::CompactionSuggested, Self::CompactionRequired,
+        Self::PermissionDenied, Self::PolicyDenied,
+        Self::Conflict,
+        Self::PolicyViolation,
+        Self::InternalError,
+    ];
+
     /// Machine-readable SCREAMING_SNAKE code string.
     #[must_use]
-    pub const fn as_str(&self) -> &str {
+    pub const fn as_str(&self) -> &'static str {
         match self {
             Self::NotInitialized => "NOT_INITIALIZED",
             Self::AlreadyInitialized => "ALREADY_INITIALIZED",
@@ -89,6 +125,7 @@ impl ErrorCode {
             Self::InvalidPriority => "INVALID_PRIORITY",
             Self::InvalidArgument => "INVALID_ARGUMENT",
             Self::InvalidSessionStatus => "INVALID_SESSION_STATUS",
+            Self::InvalidTransition => "INVALID_TRANSITION",
             Self::RequiredField => "REQUIRED_FIELD",
             Self::CycleDetected => "CYCLE_DETECTED",
             Self::HasDependents => "HAS_DEPENDENTS",
@@ -100,6 +137,12 @@ impl ErrorCode {
             Self::SkillInstallError => "SKILL_INSTALL_ERROR",
             Self::DownloadError => "DOWNLOAD_ERROR",
             Self::RemoteError => "REMOTE_ERROR",
+            Self::CompactionSuggested => "COMPACTION_SUGGESTED",
+            Self::CompactionRequired => "COMPACTION_REQUIRED",
+            Self::PermissionDenied => "PERMISSION_DENIED",
+            Self::PolicyDenied => "POLICY_DENIED",
+            Self::Conflict => "CONFLICT",
+            Self::PolicyViolation => "POLICY_VIOLATION",
             Self::InternalError => "INTERNAL_ERROR",
         }
     }
@@ -121,6 +164,7 @@ impl ErrorCode {
             | Self::InvalidPriority
             | Self::InvalidArgument
             | Self::InvalidSessionStatus
+            | Self::InvalidTransition
             | Self::RequiredField => 4,
             Self::CycleDetected | Self::HasDependents => 5,
             Self::SyncError => 6,
@@ -129,13 +173,19 @@ impl ErrorCode {
             Self::EmbeddingError => 9,
             Self::SkillInstallError | Self::DownloadError => 10,
             Self::RemoteError => 11,
+            Self::CompactionSuggested => 12,
+            Self::CompactionRequired => 13,
+            Self::PermissionDenied | Self::PolicyDenied => 14,
+            Self::Conflict => 15,
+            Self::PolicyViolation => 16,
         }
     }
 
     /// Whether an agent should retry with corrected input.
     ///
-    /// True for validation errors (wrong status, type, priority) and
-    /// ambiguous IDs. False for not-found, I/O, or internal errors.
+    /// True for validation errors (wrong status, type, priority), ambiguous
+    /// IDs and lost update races. False for not-found, I/O, or internal
+    /// errors.
     #[must_use]
     pub const fn is_retryable(&self) -> bool {
         matches!(
@@ -145,9 +195,11 @@ impl ErrorCode {
                 | Self::InvalidPriority
                 | Self::InvalidArgument
                 | Self::InvalidSessionStatus
+                | Self::InvalidTransition
                 | Self::RequiredField
                 | Self::AmbiguousId
                 | Self::DatabaseError
+                | Self::Conflict
         )
     }
 }
@@ -181,6 +233,17 @@ pub enum Error {
     #[error("Invalid session status: expected {expected}, got {actual}")]
     InvalidSessionStatus { expected: String, actual: String },
 
+    #[error("Invalid status transition: {from} → {to}")]
+    InvalidTransition {
+        from: String,
+        to: String,
+        /// Statuses reachable from `from` under the active workflow.
+        allowed: Vec<String>,
+    },
+
+    #[error("Missing required field '{field}' for status '{status}'")]
+    RequiredField { field: String, status: String },
+
     #[error("Issue not found: {id}")]
     IssueNotFound { id: String },
 
@@ -221,6 +284,23 @@ pub enum Error {
     #[error("Embedding error: {0}")]
     Embedding(String),
 
+    #[error("Embedding error: {provider} is rate limiting requests")]
+    EmbeddingRateLimited {
+        provider: String,
+        /// Seconds from the `Retry-After` header, when sent.
+        retry_after: Option<u64>,
+    },
+
+    #[error("Embedding error: {provider} is unreachable: {message}")]
+    EmbeddingUnreachable { provider: String, message: String },
+
+    #[error("Embedding error: stored embeddings come from {stored}, but the active model is {active}")]
+    EmbeddingModelMismatch {
+        /// Models of the stored chunks, e.g. `ollama/nomic-embed-text (768d)`.
+        stored: String,
+        active: String,
+    },
+
     #[error("Skill install error: {0}")]
     SkillInstall(String),
 
@@ -230,6 +310,39 @@ pub enum Error {
     #[error("Remote error: {0}")]
     Remote(String),
 
+    #[error("Context at {percent}%, nearing the {threshold}% compaction threshold")]
+    CompactionSuggested { percent: u8, threshold: u8 },
+
+    #[error("Context at {percent}%, at or past the {threshold}% compaction threshold")]
+    CompactionRequired { percent: u8, threshold: u8 },
+
+    #[error("Permission denied: {actor} (role '{role}') may not {operation}")]
+    PermissionDenied { actor: String, role: String, operation: String },
+
+    #[error("Policy denied: {actor} ({actor_type}) may not run `sc {command}`")]
+    PolicyDenied { actor: String, actor_type: String, command: String },
+
+    #[error("Conflict: {entity} {id} was updated at {actual}, not {expected}")]
+    Conflict {
+        entity: String,
+        id: String,
+        /// `updated_at` the caller last saw (Unix ms).
+        expected: i64,
+        /// `updated_at` now.
+        actual: i64,
+        /// The record as it is now, for the caller to merge with.
+        current: Box<serde_json::Value>,
+    },
+
+    #[error("Policy violation: {message}")]
+    PolicyViolation { rule: String, message: String },
+
+    #[error("Dependency cycle: {}", cycle.join(" → "))]
+    CycleDetected {
+        /// The issues along the cycle, the first repeated at the end.
+        cycle: Vec<String>,
+    },
+
     #[error("{0}")]
     Other(String),
 }
@@ -258,12 +371,24 @@ impl Error {
                 ErrorCode::NoActiveSession
             }
             Self::InvalidSessionStatus { .. } => ErrorCode::InvalidSessionStatus,
+            Self::InvalidTransition { .. } => ErrorCode::InvalidTransition,
+            Self::RequiredField { .. } => ErrorCode::RequiredField,
             Self::InvalidArgument(_) => ErrorCode::InvalidArgument,
             Self::Config(_) => ErrorCode::ConfigError,
-            Self::Embedding(_) => ErrorCode::EmbeddingError,
+            Self::Embedding(_)
+            | Self::EmbeddingRateLimited { .. }
+            | Self::EmbeddingUnreachable { .. }
+            | Self::EmbeddingModelMismatch { .. } => ErrorCode::EmbeddingError,
             Self::SkillInstall(_) => ErrorCode::SkillInstallError,
             Self::Download(_) => ErrorCode::DownloadError,
             Self::Remote(_) => ErrorCode::RemoteError,
+            Self::CompactionSuggested { .. } => ErrorCode::CompactionSuggested,
+            Self::CompactionRequired { .. } => ErrorCode::CompactionRequired,
+            Self::PermissionDenied { .. } => ErrorCode::PermissionDenied,
+            Self::PolicyDenied { .. } => ErrorCode::PolicyDenied,
+            Self::Conflict { .. } => ErrorCode::Conflict,
+            Self::PolicyViolation { .. } => ErrorCode::PolicyViolation,
+            Self::CycleDetected { .. } => ErrorCode::CycleDetected,
             Self::Io(_) => ErrorCode::IoError,
             Self::Json(_) => ErrorCode::JsonError,
             Self::Other(_) => ErrorCode::InternalError,
@@ -354,9 +479,26 @@ impl Error {
                  Use `sc session list` to check session states."
             )),
 
+            Self::InvalidTransition { from, allowed, .. } => Some(if allowed.is_empty() {
+                format!("Issues in '{from}' cannot change status under the current workflow.")
+            } else {
+                format!("From '{from}' you can move to: {}", allowed.join(", "))
+            }),
+
+            Self::RequiredField { field, .. } => Some(if field == "close_reason" {
+                "Pass a reason: sc issue complete <id> --reason \"...\" \
+                 (or sc issue update <id> --status closed --reason \"...\")"
+                    .to_string()
+            } else {
+                format!("Set '{field}' on the issue first, then retry the status change.")
+            }),
+
             Self::InvalidArgument(msg) => {
-                // Check for validation-style messages and add synonym hints
-                if msg.contains("status") {
+                // Check for validation-style messages and add synonym hints.
+                // Enum parse errors already list their valid values.
+                if msg.contains("Valid values:") {
+                    None
+                } else if msg.contains("status") {
                     Some(
                         "Valid statuses: backlog, open, in_progress, blocked, closed, deferred. \
                          Synonyms: done→closed, wip→in_progress, todo→open"
@@ -395,6 +537,63 @@ impl Error {
                     .to_string(),
             ),
 
+            Self::EmbeddingRateLimited { .. } => Some(
+                "Wait and retry, or lower `sc embeddings backfill --concurrency` \
+                 or `embeddings.requests_per_minute` in config."
+                    .to_string(),
+            ),
+
+            Self::EmbeddingUnreachable { .. } => Some(
+                "Check that the provider is running, or list fallbacks in `embeddings.providers` \
+                 (e.g. `sc embeddings configure --providers ollama,huggingface,model2vec`)."
+                    .to_string(),
+            ),
+
+            Self::EmbeddingModelMismatch { .. } => Some(
+                "Run `sc embeddings migrate` to re-embed with the active model (`--keep` archives the old \
+                 vectors instead of deleting them), set `embeddings.keep_other_models` to do that \
+                 automatically, or switch back with `sc embeddings configure`."
+                    .to_string(),
+            ),
+
+            Self::CompactionSuggested { .. } => Some(
+                "Save anything important now; run `sc compaction` before the context fills up."
+                    .to_string(),
+            ),
+
+            Self::CompactionRequired { .. } => Some(
+                "Run `sc compaction` to checkpoint critical context, then compact.".to_string(),
+            ),
+
+            Self::PermissionDenied { role, .. } => Some(format!(
+                "Role '{role}' is set in the `permissions` section of the config. \
+                 Hand the task to an actor whose role allows it; `sc actor show` lists what yours allows."
+            )),
+
+            Self::PolicyDenied { actor_type, .. } => Some(format!(
+                "`permissions.commands.{actor_type}` in the config turns this command off for {actor_type} actors. \
+                 Ask a person to run it."
+            )),
+
+            Self::Conflict { actual, .. } => Some(format!(
+                "Someone else changed it first. Re-read it (with --json the error carries it as `current`), \
+                 reapply your change, and retry with --if-updated-at {actual}."
+            )),
+
+            Self::PolicyViolation { rule, .. } => Some(if rule == "max_claim_idle_hours" {
+                "Update or release the idle issues first (sc issue release <id>), or pass --force to claim anyway."
+                    .to_string()
+            } else {
+                "Finish or release an issue first (sc issue release <id>), or pass --force to claim anyway. \
+                 `sc policy check` lists every claim over the limits."
+                    .to_string()
+            }),
+
+            Self::CycleDetected { .. } => Some(
+                "Drop one of the dependencies or parent links along the cycle; an issue can't wait on itself."
+                    .to_string(),
+            ),
+
             Self::Database(_) | Self::Io(_) | Self::Json(_) | Self::Config(_)
             | Self::Embedding(_) | Self::Other(_) => None,
         }
@@ -419,7 +618,35 @@ impl Error {
         if let Some(hint) = self.hint() {
             obj["error"]["hint"] = serde_json::Value::String(hint);
         }
+        if let Self::Conflict { current, .. } = self {
+            obj["error"]["current"] = (**current).clone();
+        }
 
         obj
     }
+
+    /// Check an `--if-updated-at` guard: `Ok` if no timestamp was expected
+    /// or it still matches, otherwise [`Self::Conflict`] carrying `current`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Self::Conflict`] if the record changed since `expected`.
+    pub fn check_updated_at<T: serde::Serialize>(
+        entity: &str,
+        id: &str,
+        expected: Option<i64>,
+        actual: i64,
+        current: &T,
+    ) -> Result<()> {
+        match expected {
+            Some(expected) if expected != actual => Err(Self::Conflict {
+                entity: entity.to_string(),
+                id: id.to_string(),
+                expected,
+                actual,
+                current: Box::new(serde_json::to_value(current)?),
+            }),
+            _ => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,280 @@
+//! Markdown backlog files.
+//!
+//! The format is a checklist grouped by epic, readable by people and by
+//! agent workflows that keep their backlog in a markdown file:
+//!
+//! ```markdown
+//! # Backlog: my-app
+//!
+//! ## Login page <!-- sc:730b -->
+//! - [ ] Password reset _(in_progress)_ <!-- sc:730d -->
+//! - [x] Session cookie <!-- sc:1f38 -->
+//!
+//! ## No epic
+//! - [ ] Upgrade dependencies <!-- sc:1f39 -->
+//! ```
+//!
+//! The `sc:` comments carry issue IDs so a file can be imported back:
+//! checked items become `closed`, unchecked items take the status in the
+//! `_(status)_` suffix (or `open`), and edited titles are applied. Lines
+//! without an ID are new issues; headings without an ID are new epics.
+//! New items are attached to the epic heading they sit under. Epic status
+//! is not represented, and moving an existing item to another heading does
+//! not re-parent it, so importing changes neither.
+
+use super::{ImportedIssue, Mapper};
+use crate::model::{IssueStatus, IssueType};
+use crate::storage::Issue;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write as _;
+
+/// Heading for issues that don't belong to an epic.
+const NO_EPIC: &str = "No epic";
+
+/// Render a project's issues as a markdown backlog.
+///
+/// `children` maps an epic's full ID to the full IDs of its children.
+/// Issues appear under every epic that contains them; issues in no epic
+/// are listed last. Items are ordered by priority, then creation time.
+#[must_use]
+pub fn render(title: &str, issues: &[Issue], children: &BTreeMap<String, BTreeSet<String>>) -> String {
+    let mut sorted: Vec<&Issue> = issues.iter().collect();
+    sorted.sort_by_key(|i| (std::cmp::Reverse(i.priority), i.created_at));
+
+    let mut out = format!("# Backlog: {title}\n");
+    let mut grouped: HashSet<&str> = HashSet::new();
+
+    for epic in sorted.iter().filter(|i| i.issue_type == IssueType::Epic) {
+        grouped.insert(epic.id.as_str());
+        let _ = write!(out, "\n## {} <!-- sc:{} -->\n", epic.title, display_id(epic));
+        if let Some(ids) = children.get(&epic.id) {
+            for child in sorted.iter().filter(|i| ids.contains(&i.id) && i.issue_type != IssueType::Epic) {
+                grouped.insert(child.id.as_str());
+                push_item(&mut out, child);
+            }
+        }
+    }
+
+    let ungrouped: Vec<&&Issue> = sorted.iter().filter(|i| !grouped.contains(i.id.as_str())).collect();
+    if !ungrouped.is_empty() {
+        let _ = write!(out, "\n## {NO_EPIC}\n");
+        for issue in ungrouped {
+            push_item(&mut out, issue);
+        }
+    }
+    out
+}
+
+fn push_item(out: &mut String, issue: &Issue) {
+    let mark = if issue.status == IssueStatus::Closed { "x" } else { " " };
+    let status = match issue.status {
+        IssueStatus::Open | IssueStatus::Closed => String::new(),
+        ref other => format!(" _({other})_"),
+    };
+    let _ = writeln!(out, "- [{mark}] {}{status} <!-- sc:{} -->", issue.title, display_id(issue));
+}
+
+fn display_id(issue: &Issue) -> &str {
+    issue.short_id.as_deref().unwrap_or(&issue.id)
+}
+
+/// Parse a markdown backlog.
+///
+/// Items keep their `sc:` ID as `external_id`; new lines get a
+/// `line-N` placeholder. Headings with an ID only set the parent of the
+/// items below them.
+///
+/// # Errors
+///
+/// Returns a message if the file contains no checklist items or headings.
+pub fn parse(content: &str, mapper: &mut Mapper<'_>) -> Result<Vec<ImportedIssue>, String> {
+    let mut issues = Vec::new();
+    let mut parent: Option<String> = None;
+    let mut found = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            found = true;
+            let (title, id) = split_marker(heading);
+            parent = match id {
+                Some(id) => Some(id),
+                None if title.eq_ignore_ascii_case(NO_EPIC) => None,
+                None => {
+                    let external_id = format!("line-{}", index + 1);
+                    issues.push(new_issue(external_id.clone(), None, title, "epic", "open"));
+                    Some(external_id)
+                }
+            };
+            continue;
+        }
+
+        let Some((checked, rest)) = checkbox(trimmed) else {
+            continue;
+        };
+        found = true;
+        let (text, id) = split_marker(rest);
+        let (title, suffix) = split_status(&text);
+        if title.is_empty() {
+            continue;
+        }
+        let status = if checked {
+            "closed".to_string()
+        } else {
+            suffix.map_or_else(|| "open".to_string(), |s| mapper.status(&s))
+        };
+        let external_id = id.unwrap_or_else(|| format!("line-{}", index + 1));
+        issues.push(new_issue(external_id, parent.clone(), title, "task", &status));
+    }
+
+    if found {
+        Ok(issues)
+    } else {
+        Err("No checklist items or '## ' headings found".to_string())
+    }
+}
+
+fn new_issue(
+    external_id: String,
+    parent_external_id: Option<String>,
+    title: String,
+    issue_type: &str,
+    status: &str,
+) -> ImportedIssue {
+    ImportedIssue {
+        external_id,
+        parent_external_id,
+        title,
+        description: None,
+        details: None,
+        issue_type: issue_type.to_string(),
+        priority: 2,
+        status: status.to_string(),
+        labels: Vec::new(),
+    }
+}
+
+/// `- [x] rest` / `* [ ] rest` → (checked, rest).
+fn checkbox(line: &str) -> Option<(bool, &str)> {
+    let rest = line.strip_prefix("- [").or_else(|| line.strip_prefix("* ["))?;
+    let mut chars = rest.chars();
+    let checked = match chars.next()? {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    let rest = chars.as_str().strip_prefix(']')?;
+    Some((checked, rest.trim()))
+}
+
+/// Split a trailing `<!-- sc:ID -->` marker from the text.
+fn split_marker(text: &str) -> (String, Option<String>) {
+    let trimmed = text.trim();
+    if let Some(start) = trimmed.rfind("<!-- sc:") {
+        if let Some(id) = trimmed[start + 8..].strip_suffix("-->") {
+            let id = id.trim();
+            if !id.is_empty() {
+                return (trimmed[..start].trim().to_string(), Some(id.to_string()));
+            }
+        }
+    }
+    (trimmed.to_string(), None)
+}
+
+/// Split a trailing `_(status)_` suffix from the title.
+fn split_status(text: &str) -> (String, Option<String>) {
+    if let Some(body) = text.strip_suffix(")_") {
+        if let Some(start) = body.rfind(" _(") {
+            return (body[..start].trim().to_string(), Some(body[start + 3..].to_string()));
+        }
+    }
+    (text.to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{ImportFormat, ImportMapping};
+
+    fn issue(id: &str, title: &str, issue_type: &str, status: &str, priority: i32) -> Issue {
+        Issue {
+            id: format!("issue_{id}"),
+            short_id: Some(id.to_string()),
+            project_path: "/p".to_string(),
+            title: title.to_string(),
+            description: None,
+            details: None,
+            status: status.parse().unwrap(),
+            priority,
+            issue_type: issue_type.parse().unwrap(),
+            plan_id: None,
+            created_by_agent: None,
+            assigned_to_agent: None,
+            created_at: 0,
+            updated_at: 0,
+            closed_at: None,
+            subproject: None,
+            estimate_hours: None,
+        }
+    }
+
+    #[test]
+    fn test_render_groups_by_epic() {
+        let issues = vec![
+            issue("a1", "Login page", "epic", "open", 3),
+            issue("b2", "Password reset", "task", "in_progress", 2),
+            issue("c3", "Session cookie", "task", "closed", 4),
+            issue("d4", "Upgrade deps", "chore", "open", 1),
+        ];
+        let children = BTreeMap::from([(
+            "issue_a1".to_string(),
+            BTreeSet::from(["issue_b2".to_string(), "issue_c3".to_string()]),
+        )]);
+        let md = render("demo", &issues, &children);
+
+        assert!(md.starts_with("# Backlog: demo\n"));
+        let epic = md.find("## Login page <!-- sc:a1 -->").unwrap();
+        let closed = md.find("- [x] Session cookie <!-- sc:c3 -->").unwrap();
+        let progress = md.find("- [ ] Password reset _(in_progress)_ <!-- sc:b2 -->").unwrap();
+        let no_epic = md.find("## No epic\n- [ ] Upgrade deps <!-- sc:d4 -->").unwrap();
+        assert!(epic < closed && closed < progress && progress < no_epic);
+    }
+
+    #[test]
+    fn test_parse_round_trip_and_new_items() {
+        let md = "# Backlog: demo\n\n\
+                  ## Login page <!-- sc:a1 -->\n\
+                  - [x] Password reset _(in_progress)_ <!-- sc:b2 -->\n\
+                  - [ ] Remember me\n\n\
+                  ## Billing\n\
+                  * [ ] Invoices _(blocked)_\n\n\
+                  ## No epic\n\
+                  - [ ] Upgrade deps <!-- sc:d4 -->\n\
+                  - [?] not a checkbox\n";
+        let rules = ImportMapping::default();
+        let mut mapper = Mapper::new(ImportFormat::BacklogMd, &rules);
+        let issues = parse(md, &mut mapper).unwrap();
+
+        let by_title = |t: &str| issues.iter().find(|i| i.title == t).unwrap();
+        assert_eq!(issues.len(), 5);
+        assert_eq!(by_title("Password reset").status, "closed", "checkbox wins over suffix");
+        assert_eq!(by_title("Password reset").external_id, "b2");
+        assert_eq!(by_title("Remember me").parent_external_id.as_deref(), Some("a1"));
+        assert!(by_title("Remember me").external_id.starts_with("line-"));
+
+        let billing = by_title("Billing");
+        assert_eq!(billing.issue_type, "epic");
+        let invoices = by_title("Invoices");
+        assert_eq!(invoices.status, "blocked");
+        assert_eq!(invoices.parent_external_id.as_ref(), Some(&billing.external_id));
+
+        assert!(by_title("Upgrade deps").parent_external_id.is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_plain_text() {
+        let rules = ImportMapping::default();
+        let mut mapper = Mapper::new(ImportFormat::BacklogMd, &rules);
+        assert!(parse("just some notes\n", &mut mapper).is_err());
+    }
+}
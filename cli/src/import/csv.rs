@@ -0,0 +1,87 @@
+//! Minimal RFC 4180 CSV reader.
+//!
+//! Handles quoted fields with embedded commas, newlines, and doubled
+//! quotes, CRLF line endings, and a leading byte-order mark, which covers
+//! what Jira and spreadsheet tools export.
+
+/// Split CSV text into records of fields. Blank lines are skipped.
+///
+/// # Errors
+///
+/// Returns a message if a quoted field is never closed.
+pub fn parse(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut quote_line = 0;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => {
+                in_quotes = true;
+                quote_line = line;
+            }
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                record.push(std::mem::take(&mut field));
+                if !(record.len() == 1 && record[0].is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("Unterminated quoted field starting on line {quote_line}"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quoting_and_line_endings() {
+        let input = "\u{feff}a,b,c\r\n\"x, y\",\"say \"\"hi\"\"\",\"multi\nline\"\r\n\n1,,3";
+        let records = parse(input).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], vec!["a", "b", "c"]);
+        assert_eq!(records[1], vec!["x, y", "say \"hi\"", "multi\nline"]);
+        assert_eq!(records[2], vec!["1", "", "3"]);
+    }
+
+    #[test]
+    fn test_parse_unterminated_quote() {
+        let err = parse("a,b\n\"open,2\n").unwrap_err();
+        assert!(err.contains("line 2"));
+    }
+}
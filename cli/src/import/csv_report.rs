@@ -0,0 +1,173 @@
+//! Issue reports as CSV, for spreadsheets.
+//!
+//! `sc issue export --format csv` writes one row per issue with the
+//! columns chosen by `--columns`, in that order, under a header row of
+//! column names. Labels are joined with `;` into one cell, timestamps are
+//! written as `YYYY-MM-DD HH:MM:SS` (UTC), which Excel, Numbers and Sheets
+//! read as dates, and cells are quoted with [`crate::csv_escape`].
+
+use crate::storage::Issue;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// A column of the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum Column {
+    /// Short ID, or the full ID if there is none
+    Id,
+    FullId,
+    Title,
+    Description,
+    Status,
+    Priority,
+    Type,
+    /// Agent the issue is assigned to
+    Assignee,
+    CreatedBy,
+    /// Labels, separated by `;`
+    Labels,
+    /// Short ID of the parent issue
+    Parent,
+    /// ID of the linked plan
+    Plan,
+    CreatedAt,
+    UpdatedAt,
+    ClosedAt,
+}
+
+/// Columns used when `--columns` is not given.
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Id,
+    Column::Title,
+    Column::Status,
+    Column::Priority,
+    Column::Type,
+    Column::Assignee,
+    Column::Labels,
+    Column::CreatedAt,
+];
+
+impl Column {
+    /// Name in the header row, as given to `--columns`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::FullId => "full_id",
+            Self::Title => "title",
+            Self::Description => "description",
+            Self::Status => "status",
+            Self::Priority => "priority",
+            Self::Type => "type",
+            Self::Assignee => "assignee",
+            Self::CreatedBy => "created_by",
+            Self::Labels => "labels",
+            Self::Parent => "parent",
+            Self::Plan => "plan",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::ClosedAt => "closed_at",
+        }
+    }
+}
+
+/// Render `issues` as CSV with a header row.
+///
+/// `labels` and `parents` map an issue's full ID to its labels and to its
+/// parent's short ID; they only need to be filled when those columns are
+/// included.
+#[must_use]
+pub fn render(
+    issues: &[Issue],
+    columns: &[Column],
+    labels: &BTreeMap<String, Vec<String>>,
+    parents: &BTreeMap<String, String>,
+) -> String {
+    let mut out = String::new();
+    let header: Vec<&str> = columns.iter().map(|c| c.name()).collect();
+    let _ = writeln!(out, "{}", header.join(","));
+
+    for issue in issues {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| crate::csv_escape(&cell(issue, *column, labels, parents)))
+            .collect();
+        let _ = writeln!(out, "{}", cells.join(","));
+    }
+    out
+}
+
+fn cell(
+    issue: &Issue,
+    column: Column,
+    labels: &BTreeMap<String, Vec<String>>,
+    parents: &BTreeMap<String, String>,
+) -> String {
+    match column {
+        Column::Id => issue.short_id.clone().unwrap_or_else(|| issue.id.clone()),
+        Column::FullId => issue.id.clone(),
+        Column::Title => issue.title.clone(),
+        Column::Description => issue.description.clone().unwrap_or_default(),
+        Column::Status => issue.status.to_string(),
+        Column::Priority => issue.priority.to_string(),
+        Column::Type => issue.issue_type.to_string(),
+        Column::Assignee => issue.assigned_to_agent.clone().unwrap_or_default(),
+        Column::CreatedBy => issue.created_by_agent.clone().unwrap_or_default(),
+        Column::Labels => labels.get(&issue.id).map(|l| l.join(";")).unwrap_or_default(),
+        Column::Parent => parents.get(&issue.id).cloned().unwrap_or_default(),
+        Column::Plan => issue.plan_id.clone().unwrap_or_default(),
+        Column::CreatedAt => timestamp(issue.created_at),
+        Column::UpdatedAt => timestamp(issue.updated_at),
+        Column::ClosedAt => issue.closed_at.map(timestamp).unwrap_or_default(),
+    }
+}
+
+fn timestamp(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_names_match_flag_values() {
+        use clap::ValueEnum;
+        for column in Column::value_variants() {
+            assert_eq!(column.to_possible_value().unwrap().get_name(), column.name());
+        }
+    }
+
+    #[test]
+    fn test_render_selected_columns() {
+        let issue = Issue {
+            id: "issue_1".to_string(),
+            short_id: Some("a1b2".to_string()),
+            project_path: "/p".to_string(),
+            title: "Fix \"login\", again".to_string(),
+            description: None,
+            details: None,
+            status: crate::model::IssueStatus::InProgress,
+            priority: 3,
+            issue_type: crate::model::IssueType::Bug,
+            plan_id: None,
+            created_by_agent: None,
+            assigned_to_agent: Some("alice".to_string()),
+            created_at: 1_700_000_000_000,
+            updated_at: 0,
+            closed_at: None,
+            subproject: None,
+            estimate_hours: None,
+        };
+        let labels = BTreeMap::from([("issue_1".to_string(), vec!["ui".to_string(), "auth".to_string()])]);
+        let columns = [Column::Id, Column::Title, Column::Labels, Column::Status, Column::CreatedAt, Column::ClosedAt];
+
+        let csv = render(&[issue], &columns, &labels, &BTreeMap::new());
+        let rows = crate::import::csv::parse(&csv).unwrap();
+        assert_eq!(rows[0], ["id", "title", "labels", "status", "created_at", "closed_at"]);
+        assert_eq!(rows[1], ["a1b2", "Fix \"login\", again", "ui;auth", "in_progress", "2023-11-14 22:13:20", ""]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Jira CSV export.
+//!
+//! Reads the "Export CSV (all fields)" output of a Jira issue search.
+//! Only `Summary` is required; `Issue key`, `Issue id`, `Issue Type`,
+//! `Status`, `Priority`, `Description`, `Labels`, and `Parent id` /
+//! `Parent` are used when present. Jira repeats the `Labels` header once
+//! per label, so every column with that name is collected.
+
+use super::{ImportedIssue, Mapper};
+
+/// Convert a Jira CSV export into issues.
+///
+/// # Errors
+///
+/// Returns a message if the CSV is malformed or has no `Summary` column.
+pub fn parse(content: &str, mapper: &mut Mapper<'_>) -> Result<Vec<ImportedIssue>, String> {
+    let mut records = super::csv::parse(content)?.into_iter();
+    let header = records.next().ok_or("Jira CSV is empty")?;
+
+    let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let summary = column("Summary").ok_or("Jira CSV has no 'Summary' column")?;
+    let key = column("Issue key");
+    let id = column("Issue id");
+    let issue_type = column("Issue Type");
+    let status = column("Status");
+    let priority = column("Priority");
+    let description = column("Description");
+    let parent = column("Parent id").or_else(|| column("Parent"));
+    let labels: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.trim().eq_ignore_ascii_case("Labels"))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut issues = Vec::new();
+    for (row, record) in records.enumerate() {
+        let get = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        let Some(title) = get(Some(summary)) else {
+            continue;
+        };
+
+        let issue_key = get(key);
+        let external_id = get(id)
+            .or(issue_key)
+            // Header is row 1, so data starts on row 2
+            .map_or_else(|| format!("row-{}", row + 2), ToString::to_string);
+
+        issues.push(ImportedIssue {
+            external_id,
+            parent_external_id: get(parent).map(ToString::to_string),
+            title: title.to_string(),
+            description: get(description).map(ToString::to_string),
+            details: issue_key.map(|k| format!("Imported from Jira {k}")),
+            issue_type: mapper.issue_type(get(issue_type).unwrap_or("")),
+            priority: mapper.priority(get(priority).unwrap_or("")),
+            status: get(status).map_or_else(|| "open".to_string(), |s| mapper.status(s)),
+            labels: labels
+                .iter()
+                .filter_map(|&c| get(Some(c)))
+                .flat_map(|cell| cell.split_whitespace())
+                .filter_map(|l| mapper.label(l))
+                .collect(),
+        });
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{ImportFormat, ImportMapping};
+
+    #[test]
+    fn test_parse_jira_export() {
+        let csv = "Summary,Issue key,Issue id,Parent id,Issue Type,Status,Priority,Labels,Labels,Description\n\
+                   Login page,WEB-1,10001,,Epic,In Progress,High,frontend,auth,\"Build the\nlogin page\"\n\
+                   Password reset,WEB-2,10002,10001,Sub-task,To Do,Blocker,auth,,\n\
+                   ,WEB-3,10003,,Task,Done,Low,,,\n";
+        let rules = ImportMapping::default();
+        let mut mapper = Mapper::new(ImportFormat::JiraCsv, &rules);
+        let issues = parse(csv, &mut mapper).unwrap();
+
+        assert_eq!(issues.len(), 2, "rows without a summary are skipped");
+        assert_eq!(issues[0].external_id, "10001");
+        assert_eq!(issues[0].issue_type, "epic");
+        assert_eq!(issues[0].status, "in_progress");
+        assert_eq!(issues[0].priority, 3);
+        assert_eq!(issues[0].labels, vec!["frontend", "auth"]);
+        assert_eq!(issues[0].description.as_deref(), Some("Build the\nlogin page"));
+        assert_eq!(issues[0].details.as_deref(), Some("Imported from Jira WEB-1"));
+
+        assert_eq!(issues[1].parent_external_id.as_deref(), Some("10001"));
+        assert_eq!(issues[1].issue_type, "task");
+        assert_eq!(issues[1].status, "open");
+        assert_eq!(issues[1].priority, 4);
+        assert!(mapper.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_requires_summary() {
+        let rules = ImportMapping::default();
+        let mut mapper = Mapper::new(ImportFormat::JiraCsv, &rules);
+        assert!(parse("Title,Status\nx,Done\n", &mut mapper).unwrap_err().contains("Summary"));
+    }
+}
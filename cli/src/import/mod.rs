@@ -0,0 +1,351 @@
+//! Issue import from other trackers, markdown backlog interchange, and
+//! CSV reports.
+//!
+//! Converts exports from Jira (CSV), Trello (board JSON), and markdown
+//! backlogs into [`ImportedIssue`]s that `sc issue import` then creates
+//! (or, for backlogs written by `sc issue export`, applies). Each tracker's
+//! statuses, priorities, types, and labels go through a [`Mapper`], which
+//! resolves a value in this order:
+//!
+//! 1. the rules for that format in `~/.savecontext/config.json`
+//! 2. built-in defaults for common tracker vocabulary (`In Review`, `Blocker`, ...)
+//! 3. the synonym tables used for CLI input (`Done`, `Story`, `High`, ...)
+//!
+//! Values nothing matches fall back to `open` / `task` / priority 2 and are
+//! reported as warnings so the mapping can be added to config:
+//!
+//! ```json
+//! {
+//!   "import": {
+//!     "jira": {
+//!       "status": { "QA": "in_progress", "Ready for Release": "closed" },
+//!       "priority": { "P1": 4 },
+//!       "issue_type": { "Spike": "task" },
+//!       "labels": { "fe": "frontend", "needs-triage": "" }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! A label mapped to `""` is dropped.
+
+pub mod backlog_md;
+pub mod csv;
+pub mod csv_report;
+pub mod jira;
+pub mod trello;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Supported export formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    /// Jira issue search export ("Export CSV (all fields)")
+    JiraCsv,
+    /// Trello board export ("Print, export, and share" → JSON)
+    Trello,
+    /// Markdown checklist backlog (see [`backlog_md`])
+    BacklogMd,
+}
+
+/// Formats `sc issue export` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Markdown checklist grouped by epic (see [`backlog_md`])
+    BacklogMd,
+    /// Spreadsheet report with selectable columns (see [`csv_report`])
+    Csv,
+}
+
+impl ImportFormat {
+    /// Name used in output and as the config section key.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::JiraCsv => "jira",
+            Self::Trello => "trello",
+            Self::BacklogMd => "backlog_md",
+        }
+    }
+}
+
+/// Mapping rules for one format, as stored in the config file.
+///
+/// Keys are the tracker's values and match case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportMapping {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub status: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub priority: BTreeMap<String, i32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub issue_type: BTreeMap<String, String>,
+    /// Label renames; an empty value drops the label.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// The `import` section of `~/.savecontext/config.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jira: Option<ImportMapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trello: Option<ImportMapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backlog_md: Option<ImportMapping>,
+}
+
+impl ImportConfig {
+    /// Rules configured for `format`, if any.
+    #[must_use]
+    pub fn for_format(&self, format: ImportFormat) -> Option<&ImportMapping> {
+        match format {
+            ImportFormat::JiraCsv => self.jira.as_ref(),
+            ImportFormat::Trello => self.trello.as_ref(),
+            ImportFormat::BacklogMd => self.backlog_md.as_ref(),
+        }
+    }
+}
+
+/// An issue converted from an external tracker, ready to create.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedIssue {
+    /// The tracker's identifier (Jira issue id or key, Trello card id).
+    pub external_id: String,
+    /// The tracker's identifier of the parent issue, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_external_id: Option<String>,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    pub issue_type: String,
+    pub priority: i32,
+    pub status: String,
+    pub labels: Vec<String>,
+}
+
+/// Parsed issues plus any mapping warnings.
+#[derive(Debug, Default)]
+pub struct ImportBatch {
+    pub issues: Vec<ImportedIssue>,
+    pub warnings: Vec<String>,
+}
+
+/// Parse an export in the given format.
+///
+/// # Errors
+///
+/// Returns a message describing the first structural problem (missing
+/// columns, malformed CSV or JSON).
+pub fn parse(
+    format: ImportFormat,
+    content: &str,
+    rules: Option<&ImportMapping>,
+) -> Result<ImportBatch, String> {
+    let default_rules = ImportMapping::default();
+    let mut mapper = Mapper::new(format, rules.unwrap_or(&default_rules));
+    let issues = match format {
+        ImportFormat::JiraCsv => jira::parse(content, &mut mapper)?,
+        ImportFormat::Trello => trello::parse(content, &mut mapper)?,
+        ImportFormat::BacklogMd => backlog_md::parse(content, &mut mapper)?,
+    };
+    Ok(ImportBatch {
+        issues,
+        warnings: mapper.warnings,
+    })
+}
+
+/// Tracker statuses missing from the status synonyms.
+const STATUS_DEFAULTS: &[(&str, &str)] = &[
+    ("to do", "open"),
+    ("selected for development", "open"),
+    ("ready", "open"),
+    ("doing", "in_progress"),
+    ("in review", "in_progress"),
+    ("review", "in_progress"),
+    ("code review", "in_progress"),
+    ("won't do", "closed"),
+    ("won't fix", "closed"),
+    ("cancelled", "closed"),
+];
+
+/// Tracker priorities missing from the priority synonyms.
+const PRIORITY_DEFAULTS: &[(&str, i32)] = &[("blocker", 4), ("major", 3)];
+
+/// Tracker issue types missing from the type synonyms.
+const TYPE_DEFAULTS: &[(&str, &str)] = &[
+    ("sub-task", "task"),
+    ("subtask", "task"),
+    ("new feature", "feature"),
+    ("user story", "feature"),
+];
+
+/// Resolves tracker values to issue fields and collects warnings.
+pub struct Mapper<'a> {
+    format: ImportFormat,
+    rules: &'a ImportMapping,
+    warnings: Vec<String>,
+}
+
+impl<'a> Mapper<'a> {
+    #[must_use]
+    pub fn new(format: ImportFormat, rules: &'a ImportMapping) -> Self {
+        Self {
+            format,
+            rules,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Map a tracker status, defaulting to `open`.
+    pub fn status(&mut self, raw: &str) -> String {
+        let key = raw.trim().to_lowercase();
+        if let Some(mapped) = lookup(&self.rules.status, &key) {
+            return mapped.clone();
+        }
+        if let Some((_, mapped)) = STATUS_DEFAULTS.iter().find(|(k, _)| *k == key) {
+            return (*mapped).to_string();
+        }
+        if let Some(status) = variants(&key).find_map(|k| crate::validate::normalize_status(&k).ok()) {
+            return status;
+        }
+        self.warn("status", raw, "open");
+        "open".to_string()
+    }
+
+    /// Map a tracker priority, defaulting to 2 (medium).
+    pub fn priority(&mut self, raw: &str) -> i32 {
+        if raw.trim().is_empty() {
+            return 2;
+        }
+        self.priority_opt(raw).unwrap_or_else(|| {
+            self.warn("priority", raw, "2");
+            2
+        })
+    }
+
+    /// Map a tracker priority, returning `None` when nothing matches.
+    #[must_use]
+    pub fn priority_opt(&self, raw: &str) -> Option<i32> {
+        let key = raw.trim().to_lowercase();
+        if let Some(&mapped) = lookup(&self.rules.priority, &key) {
+            return Some(mapped.clamp(0, 4));
+        }
+        if let Some((_, mapped)) = PRIORITY_DEFAULTS.iter().find(|(k, _)| *k == key) {
+            return Some(*mapped);
+        }
+        crate::validate::normalize_priority(&key).ok()
+    }
+
+    /// Map a tracker issue type, defaulting to `task`.
+    pub fn issue_type(&mut self, raw: &str) -> String {
+        let key = raw.trim().to_lowercase();
+        if key.is_empty() {
+            return "task".to_string();
+        }
+        if let Some(mapped) = lookup(&self.rules.issue_type, &key) {
+            return mapped.clone();
+        }
+        if let Some((_, mapped)) = TYPE_DEFAULTS.iter().find(|(k, _)| *k == key) {
+            return (*mapped).to_string();
+        }
+        if let Some(issue_type) = variants(&key).find_map(|k| crate::validate::normalize_type(&k).ok()) {
+            return issue_type;
+        }
+        self.warn("issue_type", raw, "task");
+        "task".to_string()
+    }
+
+    /// Apply label renames; `None` means the label is dropped.
+    #[must_use]
+    pub fn label(&self, raw: &str) -> Option<String> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        match lookup(&self.rules.labels, &trimmed.to_lowercase()) {
+            Some(mapped) if mapped.is_empty() => None,
+            Some(mapped) => Some(mapped.clone()),
+            None => Some(trimmed.to_string()),
+        }
+    }
+
+    fn warn(&mut self, kind: &str, raw: &str, fallback: &str) {
+        let message = format!(
+            "{kind} '{raw}' is not mapped; imported as {fallback} (add it under import.{}.{kind})",
+            self.format.name()
+        );
+        if !self.warnings.contains(&message) {
+            self.warnings.push(message);
+        }
+    }
+}
+
+/// Case-insensitive lookup in a config map.
+fn lookup<'m, V>(map: &'m BTreeMap<String, V>, lower_key: &str) -> Option<&'m V> {
+    map.iter()
+        .find(|(k, _)| k.trim().to_lowercase() == lower_key)
+        .map(|(_, v)| v)
+}
+
+/// Spellings to try against the synonym tables: `in progress` is tried as
+/// `in progress`, `in_progress`, and `inprogress`.
+fn variants(key: &str) -> impl Iterator<Item = String> {
+    let underscored: String = key
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    let joined = underscored.replace('_', "");
+    [key.to_string(), underscored, joined].into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapper_resolution_order() {
+        let rules = ImportMapping {
+            status: [("QA".to_string(), "blocked".to_string())].into(),
+            priority: [("P1".to_string(), 4)].into(),
+            issue_type: BTreeMap::new(),
+            labels: [("fe".to_string(), "frontend".to_string()), ("noise".to_string(), String::new())].into(),
+        };
+        let mut mapper = Mapper::new(ImportFormat::JiraCsv, &rules);
+
+        assert_eq!(mapper.status("qa"), "blocked");
+        assert_eq!(mapper.status("In Review"), "in_progress");
+        assert_eq!(mapper.status("In Progress"), "in_progress");
+        assert_eq!(mapper.status("To Do"), "open");
+        assert_eq!(mapper.status("Done"), "closed");
+        assert_eq!(mapper.priority("P1"), 4);
+        assert_eq!(mapper.priority("Blocker"), 4);
+        assert_eq!(mapper.priority("Lowest"), 0);
+        assert_eq!(mapper.issue_type("Story"), "feature");
+        assert_eq!(mapper.issue_type("Sub-task"), "task");
+        assert_eq!(mapper.label("FE").as_deref(), Some("frontend"));
+        assert_eq!(mapper.label("noise"), None);
+        assert_eq!(mapper.label("backend").as_deref(), Some("backend"));
+        assert!(mapper.warnings.is_empty());
+
+        assert_eq!(mapper.status("Awaiting Vendor"), "open");
+        assert_eq!(mapper.status("Awaiting Vendor"), "open");
+        assert_eq!(mapper.warnings.len(), 1);
+        assert!(mapper.warnings[0].contains("import.jira.status"));
+    }
+
+    #[test]
+    fn test_import_config_roundtrip() {
+        let json = r#"{"jira":{"status":{"QA":"in_progress"}},"trello":{"labels":{"x":""}}}"#;
+        let config: ImportConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.for_format(ImportFormat::JiraCsv).unwrap().status["QA"], "in_progress");
+        assert!(config.for_format(ImportFormat::Trello).unwrap().labels.contains_key("x"));
+        let back: ImportConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(back, config);
+    }
+}
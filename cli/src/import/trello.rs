@@ -0,0 +1,213 @@
+//! Trello board JSON export.
+//!
+//! Each card becomes an issue. The card's list decides its status (list
+//! names go through the status mapping, so `Doing` becomes `in_progress`),
+//! and archived cards are imported as `closed`. Trello has no priority
+//! field, so a label that matches the priority mapping, or whose name ends
+//! in "priority" (`High Priority`), sets the priority instead of becoming a
+//! label. Checklists are rendered into the issue details.
+
+use super::{ImportedIssue, Mapper};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+#[derive(Deserialize)]
+struct Board {
+    #[serde(default)]
+    lists: Vec<List>,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    cards: Vec<Card>,
+    #[serde(default)]
+    checklists: Vec<Checklist>,
+}
+
+#[derive(Deserialize)]
+struct List {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    id: String,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    #[serde(default)]
+    id_list: String,
+    #[serde(default)]
+    id_labels: Vec<String>,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    short_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checklist {
+    id_card: String,
+    name: String,
+    #[serde(default)]
+    check_items: Vec<CheckItem>,
+}
+
+#[derive(Deserialize)]
+struct CheckItem {
+    name: String,
+    #[serde(default)]
+    state: String,
+}
+
+/// Convert a Trello board export into issues.
+///
+/// # Errors
+///
+/// Returns a message if the JSON is not a Trello board export.
+pub fn parse(content: &str, mapper: &mut Mapper<'_>) -> Result<Vec<ImportedIssue>, String> {
+    let board: Board =
+        serde_json::from_str(content).map_err(|e| format!("Not a Trello board export: {e}"))?;
+
+    let lists: HashMap<&str, &str> = board.lists.iter().map(|l| (l.id.as_str(), l.name.as_str())).collect();
+    let labels: HashMap<&str, &str> = board
+        .labels
+        .iter()
+        .map(|l| {
+            let name = if l.name.trim().is_empty() {
+                l.color.as_deref().unwrap_or_default()
+            } else {
+                l.name.as_str()
+            };
+            (l.id.as_str(), name)
+        })
+        .collect();
+
+    let mut issues = Vec::with_capacity(board.cards.len());
+    for card in &board.cards {
+        let status = if card.closed {
+            "closed".to_string()
+        } else {
+            lists
+                .get(card.id_list.as_str())
+                .map_or_else(|| "open".to_string(), |list| mapper.status(list))
+        };
+
+        let mut priority = None;
+        let mut card_labels = Vec::new();
+        for name in card.id_labels.iter().filter_map(|id| labels.get(id.as_str())) {
+            if priority.is_none() {
+                if let Some(p) = label_priority(mapper, name) {
+                    priority = Some(p);
+                    continue;
+                }
+            }
+            card_labels.extend(mapper.label(name));
+        }
+
+        issues.push(ImportedIssue {
+            external_id: card.id.clone(),
+            parent_external_id: None,
+            title: card.name.trim().to_string(),
+            description: Some(card.desc.trim().to_string()).filter(|d| !d.is_empty()),
+            details: card_details(card, &board.checklists),
+            issue_type: "task".to_string(),
+            priority: priority.unwrap_or(2),
+            status,
+            labels: card_labels,
+        });
+    }
+    Ok(issues)
+}
+
+/// Priority named by a label, if it is one.
+fn label_priority(mapper: &Mapper<'_>, name: &str) -> Option<i32> {
+    if mapper.rules.priority.keys().any(|k| k.trim().eq_ignore_ascii_case(name.trim())) {
+        return mapper.priority_opt(name);
+    }
+    let lower = name.trim().to_lowercase();
+    let level = lower.strip_suffix("priority")?.trim_end_matches([' ', '-', '_']);
+    mapper.priority_opt(level)
+}
+
+/// Checklists and the card link, rendered as Markdown.
+fn card_details(card: &Card, checklists: &[Checklist]) -> Option<String> {
+    let mut details = String::new();
+    for checklist in checklists.iter().filter(|c| c.id_card == card.id) {
+        let _ = writeln!(details, "### {}", checklist.name);
+        for item in &checklist.check_items {
+            let mark = if item.state == "complete" { "x" } else { " " };
+            let _ = writeln!(details, "- [{mark}] {}", item.name);
+        }
+        details.push('\n');
+    }
+    if let Some(ref url) = card.short_url {
+        let _ = writeln!(details, "Imported from Trello {url}");
+    }
+    let details = details.trim_end().to_string();
+    (!details.is_empty()).then_some(details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{ImportFormat, ImportMapping};
+
+    #[test]
+    fn test_parse_trello_board() {
+        let board = r#"{
+            "lists": [{"id": "l1", "name": "Doing"}, {"id": "l2", "name": "Someday"}],
+            "labels": [
+                {"id": "b1", "name": "High Priority", "color": "red"},
+                {"id": "b2", "name": "", "color": "green"},
+                {"id": "b3", "name": "backend"}
+            ],
+            "cards": [
+                {"id": "c1", "name": "Wire up API", "desc": "REST", "idList": "l1",
+                 "idLabels": ["b1", "b3"], "closed": false, "shortUrl": "https://trello.com/c/abc"},
+                {"id": "c2", "name": "Old idea", "desc": "", "idList": "l2",
+                 "idLabels": ["b2"], "closed": true}
+            ],
+            "checklists": [
+                {"idCard": "c1", "name": "Steps", "checkItems": [
+                    {"name": "Schema", "state": "complete"}, {"name": "Handlers", "state": "incomplete"}
+                ]}
+            ]
+        }"#;
+        let rules = ImportMapping::default();
+        let mut mapper = Mapper::new(ImportFormat::Trello, &rules);
+        let issues = parse(board, &mut mapper).unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].status, "in_progress");
+        assert_eq!(issues[0].priority, 3);
+        assert_eq!(issues[0].labels, vec!["backend"]);
+        let details = issues[0].details.as_deref().unwrap();
+        assert!(details.contains("- [x] Schema"));
+        assert!(details.contains("- [ ] Handlers"));
+        assert!(details.contains("https://trello.com/c/abc"));
+
+        assert_eq!(issues[1].status, "closed", "archived cards are closed");
+        assert_eq!(issues[1].labels, vec!["green"]);
+        assert!(mapper.warnings.is_empty(), "archived card's list is not mapped");
+    }
+
+    #[test]
+    fn test_parse_rejects_other_json() {
+        let rules = ImportMapping::default();
+        let mut mapper = Mapper::new(ImportFormat::Trello, &rules);
+        assert!(parse("[1, 2]", &mut mapper).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! JSON Schema for the `--json` output of commands.
+//!
+//! Agent frameworks validate and generate clients from a schema, so `sc
+//! schema` publishes one for every command's output. Schemas come from the
+//! same structs that are serialized: each output type implements
+//! [`JsonSchema`] through [`json_schema!`], which lists the struct's fields
+//! and fails to compile if that list and the struct drift apart. Enums use
+//! their list of valid values.
+//!
+//! Schemas follow draft 2020-12. Fields whose type is an `Option` are left
+//! out of `required` because they may be skipped or null; other fields are
+//! required unless marked `= optional` (for `Vec`s skipped when empty).
+
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// The `$schema` URI of the draft schemas are written against.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A type with a JSON Schema for its serialized form.
+pub trait JsonSchema {
+    /// Whether the value can be null, so a field of this type is not
+    /// listed as required.
+    const OPTIONAL: bool = false;
+
+    /// The schema of the serialized value.
+    fn json_schema() -> Value;
+}
+
+/// Implement [`JsonSchema`] for a struct from a list of its fields.
+///
+/// ```ignore
+/// json_schema!(MemoryItem { key: String, value: String, category: MemoryCategory });
+/// json_schema!(StatusOutput { enabled: bool, models: Vec<String> = optional });
+/// ```
+///
+/// The list must name every field with its type, in any order; `= optional`
+/// marks a field that is skipped when empty.
+macro_rules! json_schema {
+    ($name:ident $(<$lt:lifetime>)? { $($field:ident: $ty:ty $(= $opt:ident)?),* $(,)? }) => {
+        impl$(<$lt>)? $crate::json_schema::JsonSchema for $name$(<$lt>)? {
+            fn json_schema() -> serde_json::Value {
+                // Destructuring without `..` and annotating each binding keeps
+                // the field list in step with the struct.
+                #[allow(clippy::ref_option_ref)]
+                let _ = |value: &$name$(<$lt>)?| {
+                    let $name { $($field),* } = value;
+                    $(let _: &$ty = $field;)*
+                };
+                $crate::json_schema::object(&[$((
+                    stringify!($field),
+                    <$ty as $crate::json_schema::JsonSchema>::json_schema(),
+                    !<$ty as $crate::json_schema::JsonSchema>::OPTIONAL && stringify!($($opt)?).is_empty(),
+                )),*])
+            }
+        }
+    };
+}
+
+pub(crate) use json_schema;
+
+/// Schema of an object with exactly these `(name, schema, required)` fields.
+#[must_use]
+pub fn object(fields: &[(&str, Value, bool)]) -> Value {
+    let properties: Map<String, Value> =
+        fields.iter().map(|(name, schema, _)| ((*name).to_string(), schema.clone())).collect();
+    let required: Vec<&str> = fields.iter().filter(|(_, _, required)| *required).map(|(name, ..)| *name).collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Schema of a string with one of `values`.
+#[must_use]
+pub fn string_enum(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+/// Schema of a value matching exactly one of `schemas`, for commands whose
+/// output depends on their flags.
+#[must_use]
+pub fn one_of(schemas: &[Value]) -> Value {
+    json!({ "oneOf": schemas })
+}
+
+/// Schema of an object with the fields of all of `objects`, for a struct
+/// that `#[serde(flatten)]`s others.
+#[must_use]
+pub fn merged(objects: &[Value]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for object in objects {
+        if let Some(fields) = object["properties"].as_object() {
+            properties.extend(fields.clone());
+        }
+        if let Some(names) = object["required"].as_array() {
+            required.extend(names.iter().cloned());
+        }
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// The schema for `T`.
+#[must_use]
+pub fn schema_for<T: JsonSchema>() -> Value {
+    T::json_schema()
+}
+
+/// `schema` as a standalone document titled `title`.
+#[must_use]
+pub fn document(title: &str, schema: Value) -> Value {
+    let mut doc = Map::new();
+    doc.insert("$schema".to_string(), Value::from(DIALECT));
+    doc.insert("title".to_string(), Value::from(title));
+    if let Value::Object(fields) = schema {
+        doc.extend(fields);
+    }
+    Value::Object(doc)
+}
+
+macro_rules! primitive {
+    ($schema:tt; $($ty:ty),+) => {
+        $(impl JsonSchema for $ty {
+            fn json_schema() -> Value {
+                json!($schema)
+            }
+        })+
+    };
+}
+
+primitive!({ "type": "string" }; String, str, Path, PathBuf);
+primitive!({ "type": "boolean" }; bool);
+primitive!({ "type": "integer" }; i32, i64);
+primitive!({ "type": "integer", "minimum": 0 }; u8, u16, u32, u64, usize);
+primitive!({ "type": "number" }; f32, f64);
+
+impl<T: JsonSchema + ?Sized> JsonSchema for &T {
+    const OPTIONAL: bool = T::OPTIONAL;
+
+    fn json_schema() -> Value {
+        T::json_schema()
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Option<T> {
+    const OPTIONAL: bool = true;
+
+    fn json_schema() -> Value {
+        let mut schema = T::json_schema();
+        match schema.get("type") {
+            Some(Value::String(kind)) => {
+                schema["type"] = json!([kind, "null"]);
+                if let Some(Value::Array(values)) = schema.get_mut("enum") {
+                    values.push(Value::Null);
+                }
+                schema
+            }
+            _ => json!({ "anyOf": [schema, { "type": "null" }] }),
+        }
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Vec<T> {
+    fn json_schema() -> Value {
+        json!({ "type": "array", "items": T::json_schema() })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for [T] {
+    fn json_schema() -> Value {
+        Vec::<T>::json_schema()
+    }
+}
+
+impl<T: JsonSchema, const N: usize> JsonSchema for [T; N] {
+    fn json_schema() -> Value {
+        json!({ "type": "array", "items": T::json_schema(), "minItems": N, "maxItems": N })
+    }
+}
+
+/// A pair, serialized as a two-element array.
+impl<A: JsonSchema, B: JsonSchema> JsonSchema for (A, B) {
+    fn json_schema() -> Value {
+        json!({ "type": "array", "prefixItems": [A::json_schema(), B::json_schema()], "items": false })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for BTreeMap<String, T> {
+    fn json_schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::json_schema() })
+    }
+}
+
+impl<T: JsonSchema, S> JsonSchema for HashMap<String, T, S> {
+    fn json_schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::json_schema() })
+    }
+}
+
+/// Any JSON value.
+impl JsonSchema for Value {
+    fn json_schema() -> Value {
+        json!({})
+    }
+}
+
+/// Schema of the error envelope printed instead of a command's output when
+/// it fails.
+#[must_use]
+pub fn error_schema() -> Value {
+    let codes: Vec<&str> = crate::error::ErrorCode::ALL.iter().map(crate::error::ErrorCode::as_str).collect();
+    object(&[(
+        "error",
+        object(&[
+            ("code", string_enum(&codes), true),
+            ("message", json!({ "type": "string" }), true),
+            ("retryable", json!({ "type": "boolean" }), true),
+            ("exit_code", json!({ "type": "integer", "minimum": 1 }), true),
+            ("hint", json!({ "type": "string" }), false),
+            ("current", json!({ "type": "object" }), false),
+        ]),
+        true,
+    )])
+}
+
+/// Schema of the line `--timing`, or a slow-query warning, writes to
+/// stderr after the command's output when `--json` is set. It never goes to
+/// stdout, so it can't break a client parsing the command's output.
+#[must_use]
+pub fn timing_schema() -> Value {
+    object(&[
+        ("timing", schema_for::<crate::storage::CommandTiming>(), true),
+        ("warning", json!({ "type": "string" }), false),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::IssueStatus;
+    use crate::storage::Issue;
+
+    /// The properties and required fields of an object schema.
+    fn shape(schema: &Value) -> (Vec<&str>, Vec<&str>) {
+        let properties = schema["properties"].as_object().unwrap().keys().map(String::as_str).collect();
+        let required = schema["required"].as_array().unwrap().iter().map(|v| v.as_str().unwrap()).collect();
+        (properties, required)
+    }
+
+    #[test]
+    fn test_struct_schema_matches_serialized_fields() {
+        let issue = Issue {
+            id: "issue_1".to_string(),
+            short_id: None,
+            project_path: "/repo".to_string(),
+            title: "Crash".to_string(),
+            description: None,
+            details: None,
+            status: IssueStatus::Open,
+            priority: 2,
+            issue_type: crate::model::IssueType::Bug,
+            plan_id: None,
+            created_by_agent: None,
+            assigned_to_agent: None,
+            created_at: 0,
+            updated_at: 0,
+            closed_at: None,
+            subproject: None,
+            estimate_hours: None,
+        };
+        let value = serde_json::to_value(&issue).unwrap();
+        let schema = schema_for::<Issue>();
+        let (mut properties, required) = shape(&schema);
+        let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        properties.sort_unstable();
+        keys.sort_unstable();
+        assert_eq!(properties, keys);
+        assert!(required.contains(&"title") && !required.contains(&"short_id"));
+        assert_eq!(schema["properties"]["short_id"]["type"], json!(["string", "null"]));
+        assert_eq!(schema["properties"]["issue_type"]["enum"][1], "bug");
+    }
+
+    #[test]
+    fn test_optional_enum_allows_null() {
+        let schema = schema_for::<Option<crate::model::ItemPriority>>();
+        assert_eq!(schema["enum"], json!(["high", "normal", "low", null]));
+        let nested = schema_for::<Option<Issue>>();
+        assert_eq!(nested["type"], json!(["object", "null"]));
+    }
+
+    #[test]
+    fn test_error_schema_lists_codes() {
+        let schema = error_schema();
+        let codes = &schema["properties"]["error"]["properties"]["code"]["enum"];
+        assert!(codes.as_array().unwrap().contains(&json!("ISSUE_NOT_FOUND")));
+        assert_eq!(shape(&schema["properties"]["error"]).1, ["code", "message", "retryable", "exit_code"]);
+    }
+}
@@ -5,12 +5,17 @@
 //! # Architecture
 //!
 //! - [`cli`] - Command-line interface using clap
+//! - [`core`] - Typed operations for using `sc` as a library
 //! - [`model`] - Data types (Session, Issue, ContextItem, Checkpoint, Plan)
 //! - [`storage`] - SQLite database layer
 //! - [`sync`] - JSONL import/export operations
+//! - [`import`] - Issue import from Jira and Trello exports
+//! - [`json_schema`] - JSON Schema for `--json` output
 //! - [`config`] - Configuration management
 //! - [`embeddings`] - Embedding providers (Ollama, HuggingFace)
 //! - [`error`] - Error types and handling
+//! - [`telemetry`] - OpenTelemetry trace export (`otel` feature)
+//! - `testing` - In-memory fixtures for tests (`testing` feature)
 
 #![forbid(unsafe_code)]
 #![warn(clippy::pedantic)]
@@ -18,11 +23,17 @@
 
 pub mod cli;
 pub mod config;
+pub mod core;
 pub mod embeddings;
 pub mod error;
+pub mod import;
+pub mod json_schema;
 pub mod model;
 pub mod storage;
 pub mod sync;
+pub mod telemetry;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod validate;
 
 pub use error::{Error, Result};
@@ -42,6 +53,12 @@ pub static DRY_RUN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBoo
 /// Global CSV output flag (set when `--format csv`).
 pub static CSV_OUTPUT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
+/// Global query tracing flag (`--explain`).
+///
+/// When set, storage connections record every statement so the SQL, its
+/// timing, and its query plan can be printed after the command runs.
+pub static EXPLAIN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
 /// Check if silent mode is active.
 #[inline]
 pub fn is_silent() -> bool {
@@ -54,6 +71,12 @@ pub fn is_dry_run() -> bool {
     DRY_RUN.load(std::sync::atomic::Ordering::Relaxed)
 }
 
+/// Check if query tracing is active.
+#[inline]
+pub fn is_explain() -> bool {
+    EXPLAIN.load(std::sync::atomic::Ordering::Relaxed)
+}
+
 /// Check if CSV output is requested.
 #[inline]
 pub fn is_csv() -> bool {
@@ -1,9 +1,10 @@
 //! SaveContext CLI entry point.
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use sc::cli::commands;
-use sc::cli::{Cli, Commands, OutputFormat};
+use sc::cli::{Cli, Commands, Database, OutputFormat};
 use sc::error::Error;
+use sc::storage::CommandTiming;
 use std::process::ExitCode;
 
 /// Rewrite named flags to positional args for agent ergonomics.
@@ -41,8 +42,8 @@ fn preprocess_args(args: impl Iterator<Item = String>) -> Vec<String> {
     }
 
     // --key is positional in: save, update, delete, tag, memory save/delete/get
-    // --key is a NAMED flag in: get (GetArgs.key)
-    if subcommand.as_deref() != Some("get") {
+    // --key is a NAMED flag in: get (GetArgs.key), remind
+    if !matches!(subcommand.as_deref(), Some("get" | "remind")) {
         aliases.push("--key");
     }
 
@@ -52,11 +53,22 @@ fn preprocess_args(args: impl Iterator<Item = String>) -> Vec<String> {
         aliases.push("--value");
     }
 
+    // --format is the global output format, except in issue import/export
+    // and export graph where it names the file format (the flags are
+    // --from / --to)
+    let rename_format = match (subcommand.as_deref(), subsubcommand.as_deref()) {
+        (Some("issue"), Some("import")) => Some("--from"),
+        (Some("issue"), Some("export")) | (Some("export"), Some("graph")) => Some("--to"),
+        _ => None,
+    };
+
     let mut result = Vec::new();
     let mut iter = raw.into_iter().peekable();
 
     while let Some(arg) = iter.next() {
-        if aliases.contains(&arg.as_str()) {
+        if let Some(flag) = rename_format.filter(|_| arg == "--format" || arg.starts_with("--format=")) {
+            result.push(arg.replacen("--format", flag, 1));
+        } else if aliases.contains(&arg.as_str()) {
             // Strip the flag, keep the value
             if let Some(value) = iter.next() {
                 result.push(value);
@@ -86,15 +98,22 @@ fn detect_subcommand(args: &[String]) -> (Option<String>, Option<String>) {
         "save", "get", "update", "delete", "tag",
         "session", "status", "issue", "checkpoint", "memory",
         "sync", "project", "plan", "compaction", "prime",
-        "init", "version", "completions", "embeddings",
-        "skills", "config", "remote", "time",
+        "init", "setup", "version", "capabilities", "schema", "stats", "completions", "embeddings",
+        "skills", "config", "remote", "time", "db", "export", "import", "apply", "shell", "serve", "bench",
+        "trash", "inbox", "search", "grep", "events", "report", "actor", "watch",
+        "purge-actor", "seed", "category", "git", "review", "edit-context", "share", "team",
+        "policy", "remind",
     ];
 
     // Known sub-subcommands to recognize
     const SUBSUBCOMMANDS: &[&str] = &[
         "create", "update", "delete", "list", "show", "resume", "pause", "end",
         "install", "status", "update", "tree", "add", "remove", "set",
-        "log", "list", "summary", "total", "invoice",
+        "log", "list", "summary", "total", "invoice", "ready", "indexes", "import", "export",
+        "vault", "startup", "merge-paths", "move", "restore", "empty", "watch", "comment",
+        "auto", "prune", "pause-idle", "usage", "check", "migrate", "benchmark", "stats",
+        "flow", "standup", "register", "alias", "archive", "rename", "triage", "install-hooks", "hook",
+        "graph", "session", "dashboard", "heartbeat", "reap", "audit", "harvest",
     ];
 
     let subcommand = args.iter()
@@ -127,20 +146,17 @@ fn detect_subcommand(args: &[String]) -> (Option<String>, Option<String>) {
 
 fn main() -> ExitCode {
     let args = preprocess_args(std::env::args());
-    let cli = Cli::parse_from(args);
-
-    if cli.silent {
-        sc::SILENT.store(true, std::sync::atomic::Ordering::Relaxed);
-    }
-    if cli.dry_run {
-        sc::DRY_RUN.store(true, std::sync::atomic::Ordering::Relaxed);
-    }
-    if cli.format == OutputFormat::Csv {
-        sc::CSV_OUTPUT.store(true, std::sync::atomic::Ordering::Relaxed);
-    }
+    let command_name = usage_command_name(&args);
+    let mut matches = Cli::command().get_matches_from(args);
+    let command = command_path(&matches);
+    let logged_args = logged_args(&matches);
+    let mut cli = Cli::from_arg_matches_mut(&mut matches).unwrap_or_else(|e| e.exit());
+    set_global_flags(&cli);
 
     // Set up tracing based on verbosity
     init_tracing(cli.verbose, cli.quiet);
+    let span = command_span(&command);
+    let entered = span.enter();
 
     // Resolve effective JSON mode: --json OR --format json OR non-TTY stdout
     // When --format csv is explicit, don't override with auto-JSON
@@ -149,8 +165,24 @@ fn main() -> ExitCode {
         || (cli.format != OutputFormat::Csv
             && !std::io::IsTerminal::is_terminal(&std::io::stdout()));
 
-    // Run the command and handle errors
-    match run(&cli, json) {
+    // Run the command and handle errors. The checks and hooks around it
+    // share its connection.
+    let started = std::time::Instant::now();
+    let mut db = Database::new(cli.db.as_deref());
+    let result = check_command_policy(&cli, &mut db, &command)
+        .and_then(|()| sc::cli::input::resolve(&mut cli.command))
+        .and_then(|()| {
+            auto_session(&mut cli, &mut db, command_name.as_deref());
+            run(&cli, &mut db, json)
+        });
+    finish_command(&cli, &mut db, command_name.as_deref(), started.elapsed(), result.is_ok(), json);
+    drop(db);
+    log_command(&cli, command, logged_args, started.elapsed(), &result);
+    record_outcome(&span, &result);
+    drop(entered);
+    sc::telemetry::shutdown();
+
+    match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
             if json {
@@ -167,10 +199,466 @@ fn main() -> ExitCode {
     }
 }
 
+/// Set the process-wide output flags from the global options.
+fn set_global_flags(cli: &Cli) {
+    use std::sync::atomic::Ordering::Relaxed;
+    sc::SILENT.store(cli.silent, Relaxed);
+    sc::DRY_RUN.store(cli.dry_run, Relaxed);
+    sc::CSV_OUTPUT.store(cli.format == OutputFormat::Csv, Relaxed);
+    sc::EXPLAIN.store(cli.explain, Relaxed);
+}
+
+/// Run `sc shell`: one command per stdin line, answered on stdout.
+///
+/// A response is the command's usual JSON output (or its structured error)
+/// followed by one status line, `{"done":{"id":..,"seq":N,"ok":..,"exit_code":..,"ms":..,"timing":..}}`,
+/// so clients read until that line. `id` is the request's own ID, or null;
+/// `timing` is null if the line didn't parse into a command. The database schema check and session
+/// resolution happen once, not per command, and commands share `db`'s
+/// connection unless they name another database; options given to `sc shell`
+/// (`--db`, `--actor`, `--session`, ...) apply to every command.
+fn shell(base: &Cli, db: &mut Database) -> Result<(), Error> {
+    use sc::cli::shell::{parse_request, Request};
+    use std::io::{BufRead, Write};
+
+    sc::storage::schema::keep_schema_warm();
+    let mut session = base
+        .session
+        .clone()
+        .or_else(|| sc::config::resolve_session_id(None).ok());
+    let mut seq = 0u64;
+
+    for line in std::io::stdin().lock().lines() {
+        let (id, args) = match parse_request(&line?) {
+            Request::Skip => continue,
+            Request::Quit => break,
+            Request::Invalid(msg) => (None, Err(Error::InvalidArgument(msg))),
+            Request::Args { id, args } => (id, Ok(args)),
+        };
+        seq += 1;
+        sc::cli::shell::set_request_id(id.clone());
+        let started = std::time::Instant::now();
+        let (result, timing) = match args {
+            Ok(args) => shell_command(base, db, session.as_deref(), args, started),
+            Err(e) => (Err(e), None),
+        };
+
+        // Commands like `session start` change which session is current
+        if base.session.is_none() {
+            session = sc::config::resolve_session_id(None).ok();
+        }
+
+        if let Err(ref e) = result {
+            let mut error = e.to_structured_json();
+            if let Some(ref id) = id {
+                error["id"] = id.clone();
+            }
+            println!("{error}");
+        }
+        let done = serde_json::json!({
+            "done": {
+                "id": id,
+                "seq": seq,
+                "ok": result.is_ok(),
+                "exit_code": result.as_ref().map_or_else(Error::exit_code, |()| 0),
+                "ms": started.elapsed().as_millis(),
+                "timing": timing,
+            }
+        });
+        println!("{done}");
+        std::io::stdout().flush()?;
+    }
+
+    sc::cli::shell::set_request_id(None);
+    set_global_flags(base);
+    Ok(())
+}
+
+/// Parse and run one `sc shell` command line, returning its timing if it
+/// got as far as running.
+fn shell_command(
+    base: &Cli,
+    db: &mut Database,
+    session: Option<&str>,
+    args: Vec<String>,
+    started: std::time::Instant,
+) -> (Result<(), Error>, Option<CommandTiming>) {
+    // stdin carries the command stream, so commands can't read values from it
+    if args.iter().any(|a| a == "-" || a == "--stdin") {
+        let msg = "stdin is the shell's command stream; pass the value inline or as @file";
+        return (Err(Error::InvalidArgument(msg.to_string())), None);
+    }
+
+    let full_args = preprocess_args(std::iter::once("sc".to_string()).chain(args));
+    let command_name = usage_command_name(&full_args);
+    let parsed = Cli::command()
+        .try_get_matches_from(&full_args)
+        .and_then(|mut matches| {
+            Ok((command_path(&matches), logged_args(&matches), Cli::from_arg_matches_mut(&mut matches)?))
+        });
+    let (command, logged_args, mut cli) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) if !e.use_stderr() => {
+            // --help / --version: print and treat as success
+            print!("{e}");
+            return (Ok(()), None);
+        }
+        Err(e) => return (Err(Error::InvalidArgument(e.to_string().trim().to_string())), None),
+    };
+    if matches!(cli.command, Commands::Shell | Commands::Serve { .. }) {
+        return (Err(Error::InvalidArgument("Already in sc shell".to_string())), None);
+    }
+    if matches!(cli.command, Commands::EditContext { emit_lsp_like_json: false, .. }) {
+        let msg = "sc shell can't open an editor; use edit-context --emit-lsp-like-json";
+        return (Err(Error::InvalidArgument(msg.to_string())), None);
+    }
+
+    let span = command_span(&command);
+    let _entered = span.enter();
+    cli.db = cli.db.or_else(|| base.db.clone());
+    cli.actor = cli.actor.or_else(|| base.actor.clone());
+    cli.session = cli.session.or_else(|| session.map(ToString::to_string));
+    cli.silent |= base.silent;
+    cli.dry_run |= base.dry_run;
+    set_global_flags(&cli);
+
+    let mut own_db;
+    let db = if cli.db == base.db {
+        db
+    } else {
+        own_db = Database::new(cli.db.as_deref());
+        &mut own_db
+    };
+    let json = cli.format != OutputFormat::Csv;
+    let result = check_command_policy(&cli, db, &command)
+        .and_then(|()| sc::cli::input::resolve(&mut cli.command))
+        .and_then(|()| {
+            auto_session(&mut cli, db, command_name.as_deref());
+            run(&cli, db, json)
+        });
+    let timing = finish_command(&cli, db, command_name.as_deref(), started.elapsed(), result.is_ok(), json);
+    log_command(&cli, command, logged_args, started.elapsed(), &result);
+    record_outcome(&span, &result);
+    (result, Some(timing))
+}
+
+/// After a command: report its timing and slow queries, print `--explain`,
+/// record usage, and run scheduled work.
+fn finish_command(
+    cli: &Cli,
+    db: &mut Database,
+    command: Option<&str>,
+    elapsed: std::time::Duration,
+    success: bool,
+    json: bool,
+) -> CommandTiming {
+    // Taken first, so the statements below aren't counted
+    let timing = sc::storage::timing::take(elapsed);
+    print_timing(cli, &timing, json);
+    if cli.explain {
+        print_explain(db, elapsed, json);
+    }
+    record_usage(db, command, &timing, success);
+    record_presence(cli, db);
+    run_scheduled(cli, db);
+    let _ = sc::storage::timing::take(std::time::Duration::ZERO);
+    timing
+}
+
+/// Warn on stderr about statements slower than the slow-query threshold,
+/// and print the whole timing for `--timing`. With `--json` both are one
+/// object on stderr (see `sc schema timing`), never part of stdout.
+fn print_timing(cli: &Cli, timing: &CommandTiming, json: bool) {
+    if cli.quiet || (timing.slow_queries == 0 && !cli.timing) {
+        return;
+    }
+    let warning = (timing.slow_queries > 0).then(|| {
+        format!(
+            "{} SQL statement(s) took over {}ms; the database may need maintenance (see `sc stats` and `sc db stats`)",
+            timing.slow_queries, timing.threshold_ms
+        )
+    });
+
+    if json {
+        let mut output = serde_json::json!({ "timing": timing });
+        if let Some(warning) = warning {
+            output["warning"] = warning.into();
+        }
+        eprintln!("{output}");
+        return;
+    }
+
+    if let Some(warning) = warning {
+        eprintln!("warning: {warning}");
+        for query in &timing.slowest {
+            eprintln!("  {}ms  {}", query.ms, query.sql);
+        }
+    }
+    if cli.timing {
+        eprintln!(
+            "timing: {}ms total, {}ms in SQL over {} statement(s)",
+            timing.total_ms, timing.sql_ms, timing.statements
+        );
+    }
+}
+
+/// Print the `--explain` report to stderr.
+///
+/// Reads are grouped by SQL text, slowest first, each with its query plan.
+/// Steps that `SCAN` a table (no usable index) are called out.
+fn print_explain(db: &mut Database, elapsed: std::time::Duration, json: bool) {
+    let report = db.storage().and_then(|storage| storage.explain_report());
+    let traces = match report {
+        Ok(traces) => traces,
+        Err(e) => {
+            eprintln!("explain: {e}");
+            return;
+        }
+    };
+
+    if json {
+        let output = serde_json::json!({
+            "explain": {
+                "command_ms": elapsed.as_millis(),
+                "queries": traces,
+            }
+        });
+        eprintln!("{output}");
+        return;
+    }
+
+    let query_us: u64 = traces.iter().map(|t| t.total_us).sum();
+    eprintln!();
+    eprintln!(
+        "── explain: {} distinct quer{}, {}ms in SQL, {}ms total ──",
+        traces.len(),
+        if traces.len() == 1 { "y" } else { "ies" },
+        query_us / 1000,
+        elapsed.as_millis()
+    );
+    for (i, trace) in traces.iter().enumerate() {
+        eprintln!();
+        let time = if trace.total_us < 1000 {
+            "<1ms".to_string()
+        } else {
+            format!("{}ms", trace.total_us / 1000)
+        };
+        let calls = if trace.calls > 1 { format!(" ({} calls)", trace.calls) } else { String::new() };
+        eprintln!("[{}] {time}{calls}", i + 1);
+        eprintln!("  {}", trace.sql);
+        for step in &trace.plan {
+            eprintln!("    {step}");
+        }
+        if !trace.full_scans.is_empty() {
+            eprintln!("    ! full scan of {} (no index used)", trace.full_scans.join(", "));
+        }
+    }
+}
+
+/// Command name recorded for `sc stats`, e.g. `"issue list"`.
+///
+/// Built only from known subcommand tokens so arguments (titles, values,
+/// paths) are never stored.
+fn usage_command_name(args: &[String]) -> Option<String> {
+    match detect_subcommand(args) {
+        (Some(cmd), Some(sub)) => Some(format!("{cmd} {sub}")),
+        (cmd, _) => cmd,
+    }
+}
+
+/// The subcommand path of a parsed command line, e.g. `"issue dep add"`,
+/// as `permissions.commands` rules name it.
+fn command_path(matches: &clap::ArgMatches) -> String {
+    let mut path = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        path.push(name);
+        current = sub;
+    }
+    path.join(" ")
+}
+
+/// The span a command runs in (exported with the `otel` feature).
+fn command_span(command: &str) -> tracing::Span {
+    tracing::info_span!("sc.command", command, exit_code = tracing::field::Empty, ok = tracing::field::Empty)
+}
+
+/// Record how the command in `span` ended.
+fn record_outcome(span: &tracing::Span, result: &Result<(), Error>) {
+    span.record("ok", result.is_ok());
+    span.record("exit_code", result.as_ref().map_or_else(Error::exit_code, |()| 0));
+}
+
+/// The redacted arguments to log for a command, if the command log is on.
+fn logged_args(matches: &clap::ArgMatches) -> Option<serde_json::Map<String, serde_json::Value>> {
+    sc::config::command_log()
+        .enabled
+        .then(|| sc::cli::command_log::redacted_args(&Cli::command(), matches))
+}
+
+/// Append the command to the command log (best effort), if it is on.
+fn log_command(
+    cli: &Cli,
+    command: String,
+    args: Option<serde_json::Map<String, serde_json::Value>>,
+    elapsed: std::time::Duration,
+    result: &Result<(), Error>,
+) {
+    use sc::cli::command_log::{append, log_path, Entry};
+
+    let (Some(args), Some(path)) = (args, log_path()) else { return };
+    let entry = Entry {
+        ts: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        command,
+        args,
+        actor: cli.actor.clone().unwrap_or_else(sc::config::default_actor),
+        session: cli.session.clone().or_else(|| sc::config::resolve_session_id(None).ok()),
+        cwd: std::env::current_dir().ok().map(|dir| dir.display().to_string()),
+        pid: std::process::id(),
+        duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        ok: result.is_ok(),
+        exit_code: result.as_ref().map_or_else(Error::exit_code, |()| 0),
+        error: result.as_ref().err().map(|e| e.error_code().as_str()),
+    };
+    if let Err(e) = append(&path, &sc::config::command_log(), &entry) {
+        tracing::debug!(error = %e, "Failed to write the command log");
+    }
+}
+
+/// Refuse a command that `permissions.commands` turns off for the actor's
+/// type. Actors that aren't registered (or have no database yet) count as
+/// humans.
+fn check_command_policy(cli: &Cli, db: &mut Database, command: &str) -> Result<(), Error> {
+    let Some(permissions) = sc::config::permissions().filter(|p| !p.commands.is_empty()) else {
+        return Ok(());
+    };
+    let actor = cli.actor.clone().unwrap_or_else(sc::config::default_actor);
+    if db.exists() {
+        return db.storage()?.check_command_policy(&actor, command);
+    }
+    let actor_type = sc::model::ActorType::default();
+    if permissions.allows_command(actor_type, command) {
+        return Ok(());
+    }
+    Err(Error::PolicyDenied { actor, actor_type: actor_type.to_string(), command: command.to_string() })
+}
+
+/// Record the invocation in the local usage tables (best effort).
+///
+/// Skipped when stats are disabled, in test mode or dry-run, or when the
+/// database doesn't exist yet, so `sc version` on a fresh machine creates
+/// nothing.
+fn record_usage(db: &mut Database, command: Option<&str>, timing: &CommandTiming, success: bool) {
+    let Some(command) = command else { return };
+    if sc::config::is_test_mode() || sc::is_dry_run() || !sc::config::usage_stats_enabled() {
+        return;
+    }
+    let Some(storage) = db.existing() else { return };
+    if let Err(e) = storage.record_command_usage(command, timing, success) {
+        tracing::debug!(error = %e, "Failed to record usage stats");
+    }
+}
+
+/// Record the actor as working in the command's session, for `sc team
+/// status` (best effort). Commands run without a session, or under
+/// dry-run, record nothing.
+fn record_presence(cli: &Cli, db: &mut Database) {
+    if sc::config::is_test_mode() || sc::is_dry_run() || !db.exists() {
+        return;
+    }
+    let Ok(session_id) = sc::config::resolve_session_id(cli.session.as_deref()) else { return };
+    let Some(storage) = db.existing() else { return };
+    let actor = cli.actor.clone().unwrap_or_else(sc::config::default_actor);
+    let result = storage
+        .resolve_actor(&actor)
+        .and_then(|id| storage.record_agent_presence(&id, &session_id, sc::storage::presence::CLI_PROVIDER));
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "Failed to record presence");
+    }
+}
+
+/// With `auto_session` on, give a session-scoped command that has no
+/// session one to work in (see `session::auto_resolve`). Any failure leaves
+/// `cli.session` unset, so the command reports the usual missing-session
+/// error.
+fn auto_session(cli: &mut Cli, db: &mut Database, command: Option<&str>) {
+    let session_scoped = match &cli.command {
+        Commands::Save(_)
+        | Commands::Update(_)
+        | Commands::Delete { .. }
+        | Commands::Tag { .. }
+        | Commands::Review { .. }
+        | Commands::Remind { command: None, .. }
+        | Commands::EditContext { .. }
+        | Commands::Compaction { command: None }
+        | Commands::Prime { .. }
+        | Commands::Apply { .. } => true,
+        Commands::Checkpoint { command } => matches!(
+            command,
+            sc::cli::CheckpointCommands::Create { .. }
+                | sc::cli::CheckpointCommands::Restore { .. }
+                | sc::cli::CheckpointCommands::AddItems { .. }
+        ),
+        _ => false,
+    };
+    if !session_scoped || cli.session.is_some() || !sc::config::auto_session_enabled() {
+        return;
+    }
+    if sc::config::resolve_session_id(None).is_ok() {
+        return;
+    }
+    let Some(storage) = db.existing() else { return };
+    let actor = cli.actor.clone().unwrap_or_else(sc::config::default_actor);
+    match commands::session::auto_resolve(storage, command.unwrap_or_default(), &actor) {
+        Ok(session) => cli.session = session,
+        Err(e) => tracing::debug!(error = %e, "auto_session could not pick a session"),
+    }
+}
+
+/// Lazily run scheduled work after a command: due automatic checkpoints
+/// (see `auto_checkpoint`), pausing idle sessions (`auto_pause_hours`) and
+/// pruning audit events (`event_retention`).
+fn run_scheduled(cli: &Cli, db: &mut Database) {
+    if matches!(
+        cli.command,
+        Commands::Shell
+            | Commands::Serve { .. }
+            | Commands::Bench { .. }
+            | Commands::Version
+            | Commands::Capabilities
+            | Commands::Schema { .. }
+            | Commands::Completions { .. }
+            | Commands::Complete { .. }
+            | Commands::Init { .. }
+            | Commands::Checkpoint {
+                command: sc::cli::CheckpointCommands::Auto { .. },
+            }
+            | Commands::Session {
+                command: sc::cli::SessionCommands::PauseIdle { .. },
+            }
+            | Commands::Events { .. }
+            | Commands::Watch { .. }
+            | Commands::PurgeActor { .. }
+            | Commands::Seed { .. }
+            | Commands::Import {
+                command: sc::cli::ImportCommands::Archive { .. },
+            }
+    ) {
+        return;
+    }
+    commands::auto_checkpoint::catch_up(db, cli.actor.as_deref());
+    commands::session::pause_idle_after_command(db, cli.actor.as_deref());
+    commands::events::prune_after_command(db);
+}
+
 fn init_tracing(verbose: u8, quiet: bool) {
-    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::{EnvFilter, Layer};
 
-    if quiet {
+    // Spans still go to the collector with --quiet
+    let export = sc::telemetry::layer();
+    if quiet && export.is_none() {
         return;
     }
 
@@ -186,82 +674,155 @@ fn init_tracing(verbose: u8, quiet: bool) {
         }
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_writer(std::io::stderr)
-        .without_time()
-        .init();
+    let stderr = (!quiet).then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .without_time()
+            .with_filter(filter)
+    });
+    tracing_subscriber::registry().with(export).with(stderr).init();
 }
 
-fn run(cli: &Cli, json: bool) -> Result<(), Error> {
+fn run(cli: &Cli, db: &mut Database, json: bool) -> Result<(), Error> {
     match &cli.command {
-        Commands::Init { global, force } => {
+        Commands::Shell => shell(cli, db),
+        Commands::Serve { jsonrpc: _, metrics } => commands::serve::execute(
+            cli.db.as_ref(),
+            cli.actor.as_deref(),
+            cli.session.as_deref(),
+            metrics.as_deref(),
+        ),
+        Commands::Init { global, force, subproject: None } => {
             commands::init::execute(*global, *force, json)
         }
+        Commands::Setup { yes } => commands::setup::execute(cli.db.as_ref(), *yes, json),
         Commands::Version => commands::version::execute(json),
+        Commands::Capabilities => commands::capabilities::execute(json),
+        Commands::Schema { command } => commands::schema::execute(command, json),
+        Commands::Bench { command } => commands::bench::execute(command, cli.db.as_ref(), json),
+        // Shell completions
+        Commands::Completions { shell, out } => commands::completions::execute(shell, out.as_deref()),
+        Commands::Compaction { command: Some(command) } => commands::compaction::execute_command(command, json),
+        // Skills
+        Commands::Skills { command } => commands::skills::execute(command, json),
+        // Config
+        Commands::Config { command } => commands::config::execute(command, json),
+        // Remote (SSH proxy)
+        Commands::Remote { args } => commands::remote::execute(args, cli.db.as_ref(), json),
 
+        Commands::Session { .. }
+        | Commands::Status
+        | Commands::Save(_)
+        | Commands::Get(_)
+        | Commands::Delete { .. }
+        | Commands::Update(_)
+        | Commands::Tag { .. }
+        | Commands::Remind { .. }
+        | Commands::Review { .. }
+        | Commands::EditContext { .. }
+        | Commands::Checkpoint { .. }
+        | Commands::Compaction { command: None }
+        | Commands::Prime { .. } => run_session_command(cli, db, json),
+        Commands::Init { subproject: Some(_), .. }
+        | Commands::Stats { .. }
+        | Commands::Db { .. }
+        | Commands::Trash { .. }
+        | Commands::Events { .. }
+        | Commands::Watch { .. }
+        | Commands::Actor { .. }
+        | Commands::Team { .. }
+        | Commands::Policy { .. }
+        | Commands::PurgeActor { .. }
+        | Commands::Seed { .. }
+        | Commands::Report { .. }
+        | Commands::Complete { .. } => run_database_command(cli, db, json),
+        Commands::Issue { .. }
+        | Commands::Memory { .. }
+        | Commands::Category { .. }
+        | Commands::Plan { .. }
+        | Commands::Project { .. }
+        | Commands::Time { .. }
+        | Commands::Search { .. }
+        | Commands::Grep { .. }
+        | Commands::Inbox { .. }
+        | Commands::Export { .. }
+        | Commands::Share { .. }
+        | Commands::Import { .. }
+        | Commands::Apply { .. }
+        | Commands::Git { .. }
+        | Commands::Sync { .. }
+        | Commands::Embeddings { .. } => run_project_command(cli, db, json),
+    }
+}
+
+/// Session, context item and checkpoint commands.
+fn run_session_command(cli: &Cli, db: &mut Database, json: bool) -> Result<(), Error> {
+    match &cli.command {
         // Session commands
         Commands::Session { command } => {
-            commands::session::execute(command, cli.db.as_ref(), cli.actor.as_deref(), cli.session.as_deref(), json)
+            commands::session::execute(command, db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
-
         // Status
-        Commands::Status => commands::status::execute(cli.db.as_ref(), cli.session.as_deref(), json),
-
+        Commands::Status => commands::status::execute(db, cli.session.as_deref(), json),
         // Context items
         Commands::Save(args) => {
-            commands::context::execute_save(args, cli.db.as_ref(), cli.actor.as_deref(), cli.session.as_deref(), json)
+            commands::context::execute_save(args, db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
         Commands::Get(args) => {
-            commands::context::execute_get(args, cli.db.as_ref(), cli.session.as_deref(), json)
+            commands::context::execute_get(args, db, cli.session.as_deref(), json)
         }
         Commands::Delete { key } => {
-            commands::context::execute_delete(key, cli.db.as_ref(), cli.actor.as_deref(), cli.session.as_deref(), json)
+            commands::context::execute_delete(key, db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
         Commands::Update(args) => {
-            commands::context::execute_update(args, cli.db.as_ref(), cli.actor.as_deref(), cli.session.as_deref(), json)
+            commands::context::execute_update(args, db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
         Commands::Tag { command } => {
-            commands::context::execute_tag(command, cli.db.as_ref(), cli.actor.as_deref(), cli.session.as_deref(), json)
+            commands::context::execute_tag(command, db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
-
-        // Issues
-        Commands::Issue { command } => {
-            commands::issue::execute(command, cli.db.as_ref(), cli.actor.as_deref(), json)
+        Commands::Remind { command, args } => commands::remind::execute(
+            command.as_ref(),
+            args,
+            db,
+            cli.actor.as_deref(),
+            cli.session.as_deref(),
+            json,
+        ),
+        Commands::Review { command } => {
+            commands::review::execute(command, db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
-
+        Commands::EditContext { key, emit_lsp_like_json } => commands::edit_context::execute(
+            key,
+            *emit_lsp_like_json,
+            db,
+            cli.actor.as_deref(),
+            cli.session.as_deref(),
+            json,
+        ),
         // Checkpoints
         Commands::Checkpoint { command } => {
-            commands::checkpoint::execute(command, cli.db.as_ref(), cli.actor.as_deref(), cli.session.as_deref(), json)
+            commands::checkpoint::execute(command, db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
-
-        // Memory
-        Commands::Memory { command } => {
-            commands::memory::execute(command, cli.db.as_ref(), cli.actor.as_deref(), json)
-        }
-
-        // Sync
-        Commands::Sync { command } => commands::sync::execute(command, cli.db.as_ref(), json),
-
-        // Project
-        Commands::Project { command } => {
-            commands::project::execute(command, cli.db.as_ref(), cli.actor.as_deref(), json)
-        }
-
-        // Plan
-        Commands::Plan { command } => {
-            commands::plan::execute(command, cli.db.as_ref(), cli.actor.as_deref(), json)
-        }
-
         // Compaction
-        Commands::Compaction => {
-            commands::compaction::execute(cli.db.as_ref(), cli.actor.as_deref(), cli.session.as_deref(), json)
+        Commands::Compaction { command: None } => {
+            commands::compaction::execute(db, cli.actor.as_deref(), cli.session.as_deref(), json)
         }
-
         // Prime (read-only context aggregation for agent injection)
-        Commands::Prime { transcript, transcript_limit, compact, smart, budget, query, decay_days } => {
+        Commands::Prime {
+            transcript,
+            transcript_limit,
+            compact,
+            smart,
+            budget,
+            query,
+            decay_days,
+            paths,
+            scope,
+            min_trust,
+            raw,
+        } => {
             commands::prime::execute(
-                cli.db.as_ref(),
+                db,
                 cli.session.as_deref(),
                 json,
                 *transcript,
@@ -271,30 +832,140 @@ fn run(cli: &Cli, json: bool) -> Result<(), Error> {
                 *budget,
                 query.as_deref(),
                 *decay_days,
+                paths,
+                scope.subproject.as_deref(),
+                scope.root,
+                *min_trust,
+                *raw,
             )
         }
+        _ => unreachable!("run routes other commands elsewhere"),
+    }
+}
 
-        // Shell completions
-        Commands::Completions { shell } => commands::completions::execute(shell),
-
-        // Embeddings
-        Commands::Embeddings { command } => {
-            commands::embeddings::execute(command.clone(), cli.db.as_ref(), json)
+/// Commands on a project's issues, memory, plans and other data.
+fn run_project_command(cli: &Cli, db: &mut Database, json: bool) -> Result<(), Error> {
+    match &cli.command {
+        // Issues
+        Commands::Issue { command } => {
+            commands::issue::execute(command, db, cli.actor.as_deref(), json)
+        }
+        // Memory
+        Commands::Memory { command } => {
+            commands::memory::execute(command, db, cli.actor.as_deref(), json)
+        }
+        Commands::Category { command } => {
+            commands::category::execute(command, db, cli.actor.as_deref(), json)
+        }
+        // Plan
+        Commands::Plan { command } => {
+            commands::plan::execute(command, db, cli.actor.as_deref(), json)
+        }
+        // Project
+        Commands::Project { command } => {
+            commands::project::execute(command, db, cli.actor.as_deref(), json)
         }
-
-        // Skills
-        Commands::Skills { command } => commands::skills::execute(command, json),
-
-        // Config
-        Commands::Config { command } => commands::config::execute(command, json),
-
         // Time tracking
         Commands::Time { command } => {
-            commands::time_entry::execute(command, cli.db.as_ref(), cli.actor.as_deref(), json)
+            commands::time_entry::execute(command, db, cli.actor.as_deref(), json)
+        }
+        Commands::Search { query, entities, all_projects, threshold, limit, stream } => {
+            let options = commands::search::SearchOptions {
+                query,
+                entities,
+                all_projects: *all_projects,
+                threshold: *threshold,
+                limit: *limit,
+                stream: *stream,
+            };
+            commands::search::execute(&options, db, json)
+        }
+        Commands::Grep { pattern, entities, regex, ignore_case, context, all_projects, limit, stream } => {
+            let options = commands::grep::GrepOptions {
+                pattern: commands::grep::GrepPattern { text: pattern, regex: *regex, ignore_case: *ignore_case },
+                entities,
+                context: *context,
+                all_projects: *all_projects,
+                limit: *limit,
+                stream: *stream,
+            };
+            commands::grep::execute(&options, db, json)
+        }
+        Commands::Inbox { all, days, all_projects, limit, mark_read } => {
+            let options = commands::inbox::InboxOptions {
+                all: *all,
+                days: *days,
+                all_projects: *all_projects,
+                limit: *limit,
+                mark_read: *mark_read,
+            };
+            commands::inbox::execute(&options, db, cli.actor.as_deref(), json)
+        }
+        Commands::Export { command } => commands::vault::execute_export(command, db, json),
+        Commands::Share { command } => {
+            commands::share::execute(command, db, cli.session.as_deref(), json)
+        }
+        Commands::Import { command } => commands::vault::execute_import(
+            command,
+            db,
+            cli.actor.as_deref(),
+            cli.session.as_deref(),
+            json,
+        ),
+        Commands::Apply { file, best_effort } => commands::apply::execute(
+            file,
+            *best_effort,
+            db,
+            cli.actor.as_deref(),
+            cli.session.as_deref(),
+            json,
+        ),
+        Commands::Git { command } => {
+            commands::git::execute(command, db, cli.session.as_deref(), cli.actor.as_deref(), json)
         }
+        // Sync
+        Commands::Sync { command } => commands::sync::execute(command, db, json),
+        // Embeddings
+        Commands::Embeddings { command } => {
+            commands::embeddings::execute(command.clone(), db, json)
+        }
+        _ => unreachable!("run routes other commands elsewhere"),
+    }
+}
 
-        // Remote (SSH proxy)
-        Commands::Remote { args } => commands::remote::execute(args, cli.db.as_ref(), json),
+/// Commands that administer the database: stats, trash, actors, policy.
+fn run_database_command(cli: &Cli, db: &mut Database, json: bool) -> Result<(), Error> {
+    match &cli.command {
+        Commands::Init { subproject: Some(path), .. } => {
+            commands::init::register_subproject(path, db, cli.actor.as_deref(), json)
+        }
+        Commands::Stats { days, limit, clear } => {
+            commands::stats::execute(db, *days, *limit, *clear, json)
+        }
+        Commands::Db { command } => commands::db::execute(command, db, json),
+        Commands::Trash { command } => {
+            commands::trash::execute(command, db, cli.actor.as_deref(), json)
+        }
+        Commands::Events { command } => commands::events::execute(command, db, json),
+        Commands::Watch { entities, interval, since, count } => {
+            commands::watch::execute(entities, *interval, *since, *count, db, json)
+        }
+        Commands::Actor { command } => commands::actor::execute(command, db, cli.actor.as_deref(), json),
+        Commands::Team { command } => {
+            commands::team::execute(command, db, cli.actor.as_deref(), cli.session.as_deref(), json)
+        }
+        Commands::Policy { command } => {
+            commands::policy::execute(command, db, cli.actor.as_deref(), json)
+        }
+        Commands::PurgeActor { name, anonymize, force } => {
+            commands::actor::purge(name, *anonymize, *force, db, cli.actor.as_deref(), json)
+        }
+        Commands::Seed { demo: _, path, force } => {
+            commands::seed::execute(path.as_deref(), *force, db, cli.actor.as_deref(), json)
+        }
+        Commands::Report { command } => commands::report::execute(command, db, cli.actor.as_deref(), json),
+        Commands::Complete { kind } => commands::completions::complete(*kind, db),
+        _ => unreachable!("run routes other commands elsewhere"),
     }
 }
 
@@ -366,6 +1037,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_issue_import_renames_format() {
+        // --format names the export format in issue import, not the output format
+        assert_eq!(
+            pp(&["sc", "issue", "import", "--format", "jira-csv", "export.csv"]),
+            vec!["sc", "issue", "import", "--from", "jira-csv", "export.csv"]
+        );
+        assert_eq!(
+            pp(&["sc", "issue", "import", "board.json", "--format=trello"]),
+            vec!["sc", "issue", "import", "board.json", "--from=trello"]
+        );
+        assert_eq!(
+            pp(&["sc", "issue", "export", "--format", "backlog-md"]),
+            vec!["sc", "issue", "export", "--to", "backlog-md"]
+        );
+        assert_eq!(
+            pp(&["sc", "issue", "export", "--format", "csv", "--columns", "id,title"]),
+            vec!["sc", "issue", "export", "--to", "csv", "--columns", "id,title"]
+        );
+        assert_eq!(
+            pp(&["sc", "export", "graph", "--format", "graphml"]),
+            vec!["sc", "export", "graph", "--to", "graphml"]
+        );
+        assert_eq!(
+            pp(&["sc", "issue", "list", "--format", "csv"]),
+            vec!["sc", "issue", "list", "--format", "csv"]
+        );
+    }
+
     #[test]
     fn test_global_flags_preserved() {
         assert_eq!(
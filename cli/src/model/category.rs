@@ -0,0 +1,177 @@
+//! Context item categories a project knows about.
+//!
+//! The four built-in categories always exist. A project can register more
+//! with `sc category add`, and can override a built-in one's description,
+//! color, default priority and prime settings the same way. A category
+//! carries:
+//!
+//! - `default_priority`: the priority of items saved without `--priority`
+//! - `color`: how `sc get` labels its items in a terminal
+//! - `in_prime`: whether `sc prime` includes its items at all
+//! - `prime_weight`: its factor in `sc prime --smart` scoring
+//! - `encrypted`: whether its items' values are stored encrypted
+//!
+//! [`Categories`] is the merged view: built-ins with any overrides, then
+//! the registered ones by name.
+
+use crate::error::{Error, Result};
+use crate::json_schema::json_schema;
+use crate::model::{ItemCategory, ItemPriority};
+use serde::{Deserialize, Serialize};
+
+/// Terminal colors a category can have.
+pub const COLORS: &[&str] = &["red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// A category and how items in it are treated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Category {
+    pub name: ItemCategory,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// One of [`COLORS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    pub default_priority: ItemPriority,
+    pub in_prime: bool,
+    pub prime_weight: f64,
+    /// Values are stored encrypted with a key from the OS keychain.
+    #[serde(default)]
+    pub encrypted: bool,
+    pub built_in: bool,
+}
+
+json_schema!(Category {
+    name: ItemCategory,
+    description: Option<String>,
+    color: Option<String>,
+    default_priority: ItemPriority,
+    in_prime: bool,
+    prime_weight: f64,
+    encrypted: bool,
+    built_in: bool,
+});
+
+impl Category {
+    /// A built-in category with its default settings.
+    #[must_use]
+    pub fn built_in(name: ItemCategory) -> Self {
+        let (description, prime_weight) = match name {
+            ItemCategory::Reminder => ("Things to do or keep in mind", 1.5),
+            ItemCategory::Decision => ("Choices made and why", 2.0),
+            ItemCategory::Progress => ("What got done", 1.0),
+            ItemCategory::Note => ("Everything else", 0.5),
+            ItemCategory::Custom(_) => return Self::custom(name),
+        };
+        Self { description: Some(description.to_string()), prime_weight, built_in: true, ..Self::custom(name) }
+    }
+
+    /// A newly registered category with default settings.
+    #[must_use]
+    pub fn custom(name: ItemCategory) -> Self {
+        Self {
+            name,
+            description: None,
+            color: None,
+            default_priority: ItemPriority::Normal,
+            in_prime: true,
+            prime_weight: 1.0,
+            encrypted: false,
+            built_in: false,
+        }
+    }
+
+    /// Check the settings a user can change.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] for an unknown color or a weight
+    /// outside 0-10.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(color) = &self.color {
+            if !COLORS.contains(&color.as_str()) {
+                return Err(Error::InvalidArgument(format!(
+                    "Invalid color '{color}'. Valid values: {}",
+                    COLORS.join(", ")
+                )));
+            }
+        }
+        if !(0.0..=10.0).contains(&self.prime_weight) {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid prime weight {}: must be between 0 and 10",
+                self.prime_weight
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Every category of a project: the built-ins first, then registered ones
+/// by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Categories(pub Vec<Category>);
+
+impl Default for Categories {
+    fn default() -> Self {
+        Self(ItemCategory::BUILT_IN.iter().cloned().map(Category::built_in).collect())
+    }
+}
+
+impl Categories {
+    /// The settings of `name`, if the project knows it.
+    #[must_use]
+    pub fn get(&self, name: &ItemCategory) -> Option<&Category> {
+        self.0.iter().find(|c| &c.name == name)
+    }
+
+    /// Whether `sc prime` includes items in `name`. Unknown categories
+    /// (items imported from another machine) are included.
+    #[must_use]
+    pub fn in_prime(&self, name: &ItemCategory) -> bool {
+        self.get(name).is_none_or(|c| c.in_prime)
+    }
+
+    /// The `sc prime --smart` weight of `name`; 1.0 if unknown.
+    #[must_use]
+    pub fn prime_weight(&self, name: &ItemCategory) -> f64 {
+        self.get(name).map_or(1.0, |c| c.prime_weight)
+    }
+
+    /// Whether values in `name` are stored encrypted.
+    #[must_use]
+    pub fn encrypted(&self, name: &ItemCategory) -> bool {
+        self.get(name).is_some_and(|c| c.encrypted)
+    }
+
+    /// The terminal color of `name`, if it has one.
+    #[must_use]
+    pub fn color(&self, name: &ItemCategory) -> Option<&str> {
+        self.get(name).and_then(|c| c.color.as_deref())
+    }
+
+    /// Every category name, for error messages.
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.0.iter().map(|c| c.name.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_defaults_and_lookup() {
+        let mut categories = Categories::default();
+        assert_eq!(categories.names(), ItemCategory::NAMES);
+        assert!((categories.prime_weight(&ItemCategory::Decision) - 2.0).abs() < f64::EPSILON);
+
+        let security = ItemCategory::Custom("security".to_string());
+        assert!(categories.in_prime(&security));
+        categories.0.push(Category { in_prime: false, color: Some("red".to_string()), ..Category::custom(security.clone()) });
+        assert!(!categories.in_prime(&security));
+        assert_eq!(categories.color(&security), Some("red"));
+
+        assert!(Category { color: Some("pink".to_string()), ..Category::custom(security.clone()) }.validate().is_err());
+        assert!(Category { prime_weight: 11.0, ..Category::custom(security) }.validate().is_err());
+    }
+}
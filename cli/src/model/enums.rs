@@ -0,0 +1,491 @@
+//! Typed values for status, category, priority and type columns.
+//!
+//! These columns used to be free strings, so a typo like `"Decision"` or
+//! `"bugfix"` went straight into the database and then silently missed
+//! every filter. Each enum here has the canonical lowercase spelling used
+//! in storage and JSON, parses case-insensitively (rejecting anything else
+//! with the list of valid values), and converts to and from column values
+//! directly. Migration `029_normalize_enum_columns` rewrote older rows into
+//! these spellings.
+//!
+//! Issue status and item category are the exceptions: the workflow can
+//! define statuses of its own (see [`crate::model::workflow`]) and projects
+//! can register categories (see [`crate::model::category`]), so
+//! [`IssueStatus`] and [`ItemCategory`] keep those in a `Custom` variant and
+//! leave validating them to the workflow or the project's registry.
+
+use crate::error::{Error, Result};
+use crate::json_schema::JsonSchema;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// The error for a value outside `valid`.
+fn invalid(what: &str, value: &str, valid: &[&str]) -> Error {
+    Error::InvalidArgument(format!("Invalid {what} '{value}'. Valid values: {}", valid.join(", ")))
+}
+
+/// Define a closed enum stored as one of a fixed set of strings.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $what:literal, default $default:ident {
+            $($(#[$vmeta:meta])* $variant:ident => $text:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)+
+        }
+
+        impl $name {
+            /// Every value, in display order.
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            /// Canonical spellings of [`Self::ALL`].
+            pub const NAMES: &'static [&'static str] = &[$($text),+];
+
+            /// The string stored in the database and JSON.
+            #[must_use]
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $text),+
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::$default
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self> {
+                match s.trim().to_lowercase().as_str() {
+                    $($text => Ok(Self::$variant),)+
+                    _ => Err(invalid($what, s, Self::NAMES)),
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn json_schema() -> serde_json::Value {
+                crate::json_schema::string_enum(Self::NAMES)
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.as_str()))
+            }
+        }
+
+        impl FromSql for $name {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                value.as_str()?.parse().map_err(|e: Error| FromSqlError::Other(Box::new(e)))
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// Lifecycle state of a session.
+    SessionStatus, "session status", default Active {
+        Active => "active",
+        Paused => "paused",
+        Completed => "completed",
+    }
+}
+
+string_enum! {
+    /// Importance of a context item.
+    ItemPriority, "priority", default Normal {
+        High => "high",
+        Normal => "normal",
+        Low => "low",
+    }
+}
+
+string_enum! {
+    /// Kind of a project memory entry.
+    MemoryCategory, "memory category", default Command {
+        Command => "command",
+        Config => "config",
+        Note => "note",
+    }
+}
+
+string_enum! {
+    /// Kind of an issue.
+    IssueType, "issue type", default Task {
+        Task => "task",
+        Bug => "bug",
+        Feature => "feature",
+        Epic => "epic",
+        Chore => "chore",
+    }
+}
+
+string_enum! {
+    /// What kind of participant an actor is.
+    ActorType, "actor type", default Human {
+        Human => "human",
+        Agent => "agent",
+        Model => "model",
+    }
+}
+
+string_enum! {
+    /// Where a context item came from, set when it is first saved.
+    ItemSource, "item source", default Human {
+        Human => "human",
+        Agent => "agent",
+        Imported => "imported",
+    }
+}
+
+string_enum! {
+    /// How far a context item can be relied on, lowest first.
+    ///
+    /// Starts out as the item's [`ItemSource`]; a person who has checked an
+    /// agent's note can raise it to `human`.
+    TrustLevel, "trust level", default Human {
+        Agent => "agent",
+        Imported => "imported",
+        Human => "human",
+    }
+}
+
+string_enum! {
+    /// Where an agent-written decision stands with the people reviewing it
+    /// (`sc review`).
+    ReviewStatus, "review status", default Unreviewed {
+        Unreviewed => "unreviewed",
+        Approved => "approved",
+        Rejected => "rejected",
+    }
+}
+
+impl TrustLevel {
+    /// Whether this is `min` or more trusted.
+    #[must_use]
+    pub fn at_least(self, min: Self) -> bool {
+        self as u8 >= min as u8
+    }
+}
+
+impl From<ItemSource> for TrustLevel {
+    fn from(source: ItemSource) -> Self {
+        match source {
+            ItemSource::Human => Self::Human,
+            ItemSource::Agent => Self::Agent,
+            ItemSource::Imported => Self::Imported,
+        }
+    }
+}
+
+impl From<ActorType> for ItemSource {
+    fn from(actor_type: ActorType) -> Self {
+        match actor_type {
+            ActorType::Human => Self::Human,
+            ActorType::Agent | ActorType::Model => Self::Agent,
+        }
+    }
+}
+
+string_enum! {
+    /// Billing state of a time entry.
+    TimeEntryStatus, "time entry status", default Logged {
+        Logged => "logged",
+        Reviewed => "reviewed",
+        Invoiced => "invoiced",
+    }
+}
+
+/// Status of an issue.
+///
+/// The built-in statuses have their own variants. Statuses added by a
+/// workflow override are kept as [`Self::Custom`]; whether one may be
+/// entered is up to the workflow, not to parsing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum IssueStatus {
+    Backlog,
+    #[default]
+    Open,
+    InProgress,
+    Blocked,
+    Closed,
+    Deferred,
+    /// A status defined by a workflow override, in lowercase.
+    Custom(String),
+}
+
+impl IssueStatus {
+    /// The built-in statuses.
+    pub const BUILT_IN: &'static [Self] =
+        &[Self::Backlog, Self::Open, Self::InProgress, Self::Blocked, Self::Closed, Self::Deferred];
+
+    /// The string stored in the database and JSON.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Backlog => "backlog",
+            Self::Open => "open",
+            Self::InProgress => "in_progress",
+            Self::Blocked => "blocked",
+            Self::Closed => "closed",
+            Self::Deferred => "deferred",
+            Self::Custom(status) => status,
+        }
+    }
+
+    /// Whether `name` can be a custom status: lowercase letters, digits,
+    /// `-` and `_`, starting with a letter (`in-review`, `qa2`).
+    #[must_use]
+    pub fn is_valid_name(name: &str) -> bool {
+        name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    }
+
+    /// Read a stored status, which is never rejected.
+    fn from_stored(s: &str) -> Self {
+        Self::BUILT_IN
+            .iter()
+            .find(|status| status.as_str() == s)
+            .cloned()
+            .unwrap_or_else(|| Self::Custom(s.to_string()))
+    }
+}
+
+impl fmt::Display for IssueStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses built-in statuses and their synonyms (`done`, `wip`, ...), and
+/// takes any other name [`IssueStatus::is_valid_name`] accepts, once
+/// lowercased, as a custom status.
+impl FromStr for IssueStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(status) = crate::validate::normalize_status(s.trim()) {
+            return Ok(Self::from_stored(&status));
+        }
+        let lower = s.trim().to_lowercase();
+        if Self::is_valid_name(&lower) {
+            return Ok(Self::Custom(lower));
+        }
+        let names: Vec<&str> = Self::BUILT_IN.iter().map(Self::as_str).collect();
+        Err(invalid("status", s, &names))
+    }
+}
+
+impl Serialize for IssueStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for IssueStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A string, usually one of the built-in statuses; workflows can add
+/// their own.
+impl JsonSchema for IssueStatus {
+    fn json_schema() -> serde_json::Value {
+        let names: Vec<&str> = Self::BUILT_IN.iter().map(Self::as_str).collect();
+        serde_json::json!({ "type": "string", "pattern": "^[a-z][a-z0-9_-]*$", "examples": names })
+    }
+}
+
+impl ToSql for IssueStatus {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for IssueStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str().map(Self::from_stored)
+    }
+}
+
+/// Kind of a context item.
+///
+/// The built-in categories have their own variants. Categories a project
+/// registers with `sc category add` are kept as [`Self::Custom`]; saving an
+/// item checks that its project has registered the category.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum ItemCategory {
+    Reminder,
+    Decision,
+    Progress,
+    #[default]
+    Note,
+    /// A category registered by the project, in lowercase.
+    Custom(String),
+}
+
+impl ItemCategory {
+    /// The built-in categories, in display order.
+    pub const BUILT_IN: &'static [Self] = &[Self::Reminder, Self::Decision, Self::Progress, Self::Note];
+
+    /// Canonical spellings of [`Self::BUILT_IN`].
+    pub const NAMES: &'static [&'static str] = &["reminder", "decision", "progress", "note"];
+
+    /// The string stored in the database and JSON.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Reminder => "reminder",
+            Self::Decision => "decision",
+            Self::Progress => "progress",
+            Self::Note => "note",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Whether this is one of [`Self::BUILT_IN`].
+    #[must_use]
+    pub const fn is_built_in(&self) -> bool {
+        !matches!(self, Self::Custom(_))
+    }
+
+    /// Read a stored category, which is never rejected.
+    fn from_stored(s: &str) -> Self {
+        Self::BUILT_IN
+            .iter()
+            .find(|category| category.as_str() == s)
+            .cloned()
+            .unwrap_or_else(|| Self::Custom(s.to_string()))
+    }
+}
+
+impl fmt::Display for ItemCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the built-in categories case-insensitively, and takes any other
+/// name of lowercase letters, digits, `-` and `_` (starting with a letter,
+/// at most 32 characters) as a custom category.
+impl FromStr for ItemCategory {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.trim().to_lowercase();
+        let valid = lower.len() <= 32
+            && lower.starts_with(|c: char| c.is_ascii_lowercase())
+            && lower.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if valid {
+            Ok(Self::from_stored(&lower))
+        } else {
+            Err(invalid("category", s, Self::NAMES))
+        }
+    }
+}
+
+impl Serialize for ItemCategory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemCategory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A string, usually one of the built-in categories; projects can
+/// register their own.
+impl JsonSchema for ItemCategory {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({ "type": "string", "pattern": "^[a-z][a-z0-9_-]*$", "examples": Self::NAMES })
+    }
+}
+
+impl ToSql for ItemCategory {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for ItemCategory {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str().map(Self::from_stored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_store() {
+        assert_eq!("Decision".parse::<ItemCategory>().unwrap(), ItemCategory::Decision);
+        assert_eq!(ItemPriority::High.to_string(), "high");
+        assert_eq!(serde_json::to_string(&SessionStatus::Paused).unwrap(), "\"paused\"");
+        assert_eq!(serde_json::from_str::<IssueType>("\"bug\"").unwrap(), IssueType::Bug);
+
+        let err = "bugfix".parse::<IssueType>().unwrap_err().to_string();
+        assert!(err.contains("task, bug, feature, epic, chore"), "{err}");
+        assert_eq!(serde_json::from_str::<ItemCategory>("\"idea\"").unwrap(), ItemCategory::Custom("idea".to_string()));
+        assert!("security review".parse::<ItemCategory>().is_err());
+
+        assert_eq!("done".parse::<IssueStatus>().unwrap(), IssueStatus::Closed);
+        assert_eq!("Review".parse::<IssueStatus>().unwrap(), IssueStatus::Custom("review".to_string()));
+        assert_eq!("in-review".parse::<IssueStatus>().unwrap(), IssueStatus::Custom("in-review".to_string()));
+        assert_eq!("QA2".parse::<IssueStatus>().unwrap(), IssueStatus::Custom("qa2".to_string()));
+        assert!("2nd-pass".parse::<IssueStatus>().is_err());
+        assert!("not a status".parse::<IssueStatus>().is_err());
+
+        assert!(TrustLevel::Human.at_least(TrustLevel::Imported));
+        assert!(!TrustLevel::Agent.at_least(TrustLevel::Imported));
+        assert_eq!(TrustLevel::from(ItemSource::from(ActorType::Model)), TrustLevel::Agent);
+    }
+
+    #[test]
+    fn test_sql_round_trip() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let (category, status): (ItemCategory, IssueStatus) = conn
+            .query_row("SELECT ?1, ?2", rusqlite::params![ItemCategory::Progress, IssueStatus::InProgress], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((category, status), (ItemCategory::Progress, IssueStatus::InProgress));
+        assert!(conn.query_row("SELECT 'urgent'", [], |row| row.get::<_, ItemPriority>(0)).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! `@actor` mentions in issue text.
+//!
+//! A mention is `@` at the start of the text or after a character that
+//! can't be part of a name, followed by letters, digits, `_`, `-` or `.`.
+//! That keeps email addresses (`dev@example.com`) from counting. Trailing
+//! `.` and `-` are dropped so `@alice.` at the end of a sentence names
+//! `alice`.
+
+/// Actors mentioned in `text`, in order of first appearance.
+#[must_use]
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let boundary = previous.is_none_or(|p| !is_name_char(p) && p != '@');
+        previous = Some(c);
+        if c != '@' || !boundary {
+            continue;
+        }
+
+        let mut end = start + 1;
+        while let Some(&(i, next)) = chars.peek() {
+            if !is_name_char(next) {
+                break;
+            }
+            end = i + next.len_utf8();
+            previous = Some(next);
+            chars.next();
+        }
+
+        let name = text[start + 1..end].trim_end_matches(['.', '-']);
+        if !name.is_empty() && !mentions.iter().any(|m| m == name) {
+            mentions.push(name.to_string());
+        }
+    }
+    mentions
+}
+
+/// Mentions in `new` that `old` did not already contain, so editing text
+/// doesn't notify the same actors again.
+#[must_use]
+pub fn new_mentions(old: Option<&str>, new: &str) -> Vec<String> {
+    let existing = old.map(parse_mentions).unwrap_or_default();
+    parse_mentions(new)
+        .into_iter()
+        .filter(|m| !existing.contains(m))
+        .collect()
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mentions() {
+        assert_eq!(
+            parse_mentions("@alice please review, cc @bob-smith and (@carol). Thanks @alice."),
+            vec!["alice", "bob-smith", "carol"]
+        );
+        assert!(parse_mentions("mail dev@example.com or @ alone or @@x").is_empty());
+        assert_eq!(parse_mentions("ping @claude_code."), vec!["claude_code"]);
+    }
+
+    #[test]
+    fn test_new_mentions() {
+        assert_eq!(new_mentions(Some("@alice"), "@alice and @bob"), vec!["bob"]);
+        assert_eq!(new_mentions(None, "@alice"), vec!["alice"]);
+    }
+}
@@ -8,9 +8,38 @@
 //! - Plan
 //! - Memory
 //! - Project
+//! - Categories (built-in and project-registered context item categories)
+//! - Workflow (issue status transitions)
+//! - Roles (which operations each actor may perform)
+//! - Policy (how many issues an actor may hold, and for how long)
+//! - Relevance (read history of context items)
+//! - Routing (which agent gets which ready issue)
+//! - Schedule (dependency waves and critical paths)
+//! - Mentions (`@actor` in issue text)
+//! - Enums for status, category, priority and type columns
 
+pub mod category;
+pub mod enums;
+pub mod mention;
 pub mod plan;
+pub mod policy;
 pub mod project;
+pub mod relevance;
+pub mod roles;
+pub mod routing;
+pub mod schedule;
+pub mod workflow;
 
-pub use plan::{Plan, PlanStatus};
+pub use category::{Categories, Category};
+pub use enums::{
+    ActorType, IssueStatus, IssueType, ItemCategory, ItemPriority, ItemSource, MemoryCategory, ReviewStatus,
+    SessionStatus, TimeEntryStatus, TrustLevel,
+};
+pub use mention::{new_mentions, parse_mentions};
+pub use plan::{Plan, PlanRevision, PlanStatus};
+pub use policy::{Claim, Policy, PolicyConfig, PolicyRule, Violation};
 pub use project::Project;
+pub use relevance::{ItemAccess, RelevanceConfig};
+pub use roles::{PermissionsConfig, Role};
+pub use routing::{AgentWorkload, Routing, RoutingConfig, RoutingPolicy};
+pub use workflow::{IssueWorkflow, WorkflowConfig, WorkflowOverrides};
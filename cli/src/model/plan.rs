@@ -163,6 +163,35 @@ impl Plan {
     }
 }
 
+/// A snapshot of a plan's title, content, and success criteria.
+///
+/// A revision is recorded whenever any of those fields change, so the
+/// history of a spec survives rewrites. Revision numbers start at 1 and
+/// increase per plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanRevision {
+    /// Plan this revision belongs to
+    pub plan_id: String,
+
+    /// Revision number (1-based, per plan)
+    pub revision: i64,
+
+    /// Plan title at this revision
+    pub title: String,
+
+    /// Plan content at this revision
+    pub content: Option<String>,
+
+    /// Success criteria at this revision
+    pub success_criteria: Option<String>,
+
+    /// Actor that made the change
+    pub actor: Option<String>,
+
+    /// When the revision was recorded (Unix milliseconds)
+    pub created_at: i64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
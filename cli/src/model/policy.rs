@@ -0,0 +1,276 @@
+//! Claim policies (`sc policy check`, `sc policy reap`).
+//!
+//! Nothing stopped an agent from claiming every ready issue and sitting on
+//! them. A policy caps what one actor may hold:
+//!
+//! ```json
+//! {
+//!   "policy": {
+//!     "max_in_progress": 3,
+//!     "max_claim_idle_hours": 24,
+//!     "claim_lease_minutes": 120,
+//!     "projects": { "/home/me/site": { "max_in_progress": 1 } }
+//!   }
+//! }
+//! ```
+//!
+//! - `max_in_progress`: issues an actor may have claimed (in progress and
+//!   assigned to it) at once.
+//! - `max_claim_idle_hours`: how long a claimed issue may go without an
+//!   update before it counts as hoarded.
+//! - `claim_lease_minutes`: how long a claim lasts without any activity
+//!   from its holder. Agents crash and leave issues claimed forever; with a
+//!   lease, a claim whose holder ran no `sc` command for this long is
+//!   released (see [`crate::storage::leases`]) and, unless
+//!   `notify_on_expiry` is `false`, the holder finds it in `sc inbox`.
+//!
+//! `sc issue claim` and `sc issue next-block` refuse a claim that would
+//! break either rule (an actor holding an idle claim gets nothing new
+//! until it updates or releases it); `--force` claims anyway. `sc issue
+//! route` hands no agent more than `max_in_progress`. `sc policy check`
+//! lists who breaks the rules now. Unset rules don't apply.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Policy rules as stored in the config file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_in_progress: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_claim_idle_hours: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claim_lease_minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_on_expiry: Option<bool>,
+}
+
+/// The `policy` section of `~/.savecontext/config.json`.
+///
+/// Top-level rules apply to every project; entries under `projects`
+/// (keyed by project path) replace the rules they set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    #[serde(flatten)]
+    pub global: PolicyOverrides,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, PolicyOverrides>,
+}
+
+/// A rule of the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyRule {
+    MaxInProgress,
+    MaxClaimIdleHours,
+}
+
+impl PolicyRule {
+    /// The rule's key in the config file.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::MaxInProgress => "max_in_progress",
+            Self::MaxClaimIdleHours => "max_claim_idle_hours",
+        }
+    }
+}
+
+/// An issue an actor holds: in progress and assigned to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    /// Canonical actor.
+    pub actor: String,
+    pub issue_id: String,
+    pub short_id: Option<String>,
+    pub updated_at: i64,
+}
+
+impl Claim {
+    /// The short ID if there is one.
+    #[must_use]
+    pub fn display_id(&self) -> &str {
+        self.short_id.as_deref().unwrap_or(&self.issue_id)
+    }
+}
+
+/// An actor breaking a rule, and the claims that break it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub rule: PolicyRule,
+    pub actor: String,
+    /// The rule's limit: issues, or hours.
+    pub limit: u32,
+    pub claims: Vec<Claim>,
+}
+
+impl Violation {
+    /// One line for humans and for the `POLICY_VIOLATION` error.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let ids: Vec<&str> = self.claims.iter().map(Claim::display_id).collect();
+        match self.rule {
+            PolicyRule::MaxInProgress => format!(
+                "{} holds {} issues in progress, the limit is {} ({})",
+                self.actor,
+                self.claims.len(),
+                self.limit,
+                ids.join(", ")
+            ),
+            PolicyRule::MaxClaimIdleHours => format!(
+                "{} has claimed issues without an update for over {}h ({})",
+                self.actor,
+                self.limit,
+                ids.join(", ")
+            ),
+        }
+    }
+}
+
+/// Resolved policy for a project.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Policy {
+    pub max_in_progress: Option<u32>,
+    pub max_claim_idle_hours: Option<u32>,
+    pub claim_lease_minutes: Option<u32>,
+    /// Whether a holder hears about its expired claims (default: yes).
+    pub notify_on_expiry: Option<bool>,
+}
+
+impl Policy {
+    /// Resolve the rules for a project from the global settings and the
+    /// project's entry (in that order).
+    #[must_use]
+    pub fn resolve(config: Option<&PolicyConfig>, project_path: Option<&str>) -> Self {
+        let mut policy = Self::default();
+        if let Some(config) = config {
+            policy.apply(config.global);
+            if let Some(overrides) = project_path.and_then(|p| config.projects.get(p)) {
+                policy.apply(*overrides);
+            }
+        }
+        policy
+    }
+
+    /// Apply overrides: each rule that is set.
+    pub fn apply(&mut self, overrides: PolicyOverrides) {
+        if let Some(max) = overrides.max_in_progress {
+            self.max_in_progress = Some(max);
+        }
+        if let Some(hours) = overrides.max_claim_idle_hours {
+            self.max_claim_idle_hours = Some(hours);
+        }
+        if let Some(minutes) = overrides.claim_lease_minutes {
+            self.claim_lease_minutes = Some(minutes);
+        }
+        if let Some(notify) = overrides.notify_on_expiry {
+            self.notify_on_expiry = Some(notify);
+        }
+    }
+
+    /// Length of a claim lease in ms, if claims are leased.
+    #[must_use]
+    pub fn lease_ms(&self) -> Option<i64> {
+        self.claim_lease_minutes.filter(|&minutes| minutes > 0).map(|minutes| i64::from(minutes) * 60_000)
+    }
+
+    /// Whether releasing an expired claim notifies its holder.
+    #[must_use]
+    pub fn notifies_on_expiry(&self) -> bool {
+        self.notify_on_expiry.unwrap_or(true)
+    }
+
+    /// Every rule broken by `claims` at `now` (Unix ms), by actor.
+    #[must_use]
+    pub fn violations(&self, claims: &[Claim], now: i64) -> Vec<Violation> {
+        let mut by_actor: BTreeMap<&str, Vec<&Claim>> = BTreeMap::new();
+        for claim in claims {
+            by_actor.entry(&claim.actor).or_default().push(claim);
+        }
+        let mut violations = Vec::new();
+        for (actor, held) in by_actor {
+            let violation = |rule, limit, claims: Vec<&Claim>| Violation {
+                rule,
+                actor: actor.to_string(),
+                limit,
+                claims: claims.into_iter().cloned().collect(),
+            };
+            if let Some(max) = self.max_in_progress.filter(|&max| held.len() > max as usize) {
+                violations.push(violation(PolicyRule::MaxInProgress, max, held.clone()));
+            }
+            if let Some(hours) = self.max_claim_idle_hours {
+                let idle: Vec<&Claim> = held.iter().copied().filter(|c| self.is_idle(c, now)).collect();
+                if !idle.is_empty() {
+                    violations.push(violation(PolicyRule::MaxClaimIdleHours, hours, idle));
+                }
+            }
+        }
+        violations
+    }
+
+    /// The rule `actor` would break by claiming `adding` more issues, given
+    /// the claims it `holds` (only its own).
+    #[must_use]
+    pub fn refuse(&self, actor: &str, holds: &[Claim], adding: usize, now: i64) -> Option<Violation> {
+        let idle: Vec<Claim> = holds.iter().filter(|c| self.is_idle(c, now)).cloned().collect();
+        if let Some(hours) = self.max_claim_idle_hours.filter(|_| !idle.is_empty()) {
+            return Some(Violation { rule: PolicyRule::MaxClaimIdleHours, actor: actor.to_string(), limit: hours, claims: idle });
+        }
+        self.max_in_progress.filter(|&max| holds.len() + adding > max as usize).map(|max| Violation {
+            rule: PolicyRule::MaxInProgress,
+            actor: actor.to_string(),
+            limit: max,
+            claims: holds.to_vec(),
+        })
+    }
+
+    fn is_idle(&self, claim: &Claim, now: i64) -> bool {
+        self.max_claim_idle_hours.is_some_and(|hours| now - claim.updated_at > i64::from(hours) * 3_600_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn claim(actor: &str, id: &str, updated_at: i64) -> Claim {
+        Claim { actor: actor.to_string(), issue_id: id.to_string(), short_id: None, updated_at }
+    }
+
+    #[test]
+    fn test_violations_and_refusals() {
+        let config: PolicyConfig = serde_json::from_value(serde_json::json!({
+            "max_in_progress": 2,
+            "max_claim_idle_hours": 24,
+            "claim_lease_minutes": 90,
+            "projects": { "/p": { "max_in_progress": 1, "notify_on_expiry": false } }
+        }))
+        .unwrap();
+        let policy = Policy::resolve(Some(&config), Some("/q"));
+        assert_eq!((policy.max_in_progress, policy.max_claim_idle_hours), (Some(2), Some(24)));
+        let project = Policy::resolve(Some(&config), Some("/p"));
+        assert_eq!((project.max_in_progress, project.lease_ms()), (Some(1), Some(90 * 60_000)));
+        assert!(policy.notifies_on_expiry() && !project.notifies_on_expiry());
+
+        let now = 100 * HOUR;
+        let claims = [
+            claim("a", "a1", now - HOUR),
+            claim("a", "a2", now - HOUR),
+            claim("a", "a3", now - 30 * HOUR),
+            claim("b", "b1", now),
+        ];
+        let violations = policy.violations(&claims, now);
+        let found: Vec<(PolicyRule, &str, usize)> =
+            violations.iter().map(|v| (v.rule, v.actor.as_str(), v.claims.len())).collect();
+        assert_eq!(found, [(PolicyRule::MaxInProgress, "a", 3), (PolicyRule::MaxClaimIdleHours, "a", 1)]);
+        assert!(violations[1].describe().contains("a3"));
+
+        // b may take one more; a is holding an idle claim
+        assert_eq!(policy.refuse("b", &claims[3..], 1, now), None);
+        assert_eq!(policy.refuse("b", &claims[3..], 2, now).map(|v| v.rule), Some(PolicyRule::MaxInProgress));
+        assert_eq!(policy.refuse("a", &claims[..3], 0, now).map(|v| v.rule), Some(PolicyRule::MaxClaimIdleHours));
+        assert_eq!(Policy::default().refuse("a", &claims[..3], 10, now), None);
+    }
+}
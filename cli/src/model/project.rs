@@ -3,6 +3,7 @@
 //! Projects represent distinct codebases/directories that can have their own
 //! issue prefixes, plans, and memory.
 
+use crate::json_schema::json_schema;
 use serde::{Deserialize, Serialize};
 
 /// A project in SaveContext.
@@ -47,6 +48,19 @@ pub struct Project {
     pub updated_at: i64,
 }
 
+json_schema!(Project {
+    id: String,
+    project_path: String,
+    name: String,
+    description: Option<String>,
+    issue_prefix: Option<String>,
+    next_issue_number: i32,
+    plan_prefix: Option<String>,
+    next_plan_number: i32,
+    created_at: i64,
+    updated_at: i64,
+});
+
 fn default_one() -> i32 {
     1
 }
@@ -0,0 +1,109 @@
+//! Read-based relevance of context items.
+//!
+//! Every time `sc get` returns an item or `sc prime` includes it, its read
+//! count goes up and its last-read time moves to now. Items an agent keeps
+//! coming back to are worth more than their age suggests, so smart prime
+//! and semantic search multiply their scores by a boost from that history:
+//!
+//! ```text
+//! boost = 1 + weight × ln(1 + reads) × 0.5 ^ (days since last read / half-life)
+//! ```
+//!
+//! An item never read gets no boost, and one not read for a while loses
+//! its boost the way an unchanged item loses recency. Both knobs live in
+//! the `relevance` section of `~/.savecontext/config.json`:
+//!
+//! ```json
+//! { "relevance": { "half_life_days": 14, "access_weight": 50 } }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Default `relevance.half_life_days`.
+pub const DEFAULT_HALF_LIFE_DAYS: u64 = 14;
+
+/// Default `relevance.access_weight`, in percent.
+pub const DEFAULT_ACCESS_WEIGHT: u64 = 50;
+
+const DAY_MS: f64 = 86_400_000.0;
+
+/// How often and how recently an item has been read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ItemAccess {
+    pub count: i64,
+    /// Unix milliseconds; `None` for an item never read.
+    pub last_accessed_at: Option<i64>,
+}
+
+/// The `relevance` section of `~/.savecontext/config.json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelevanceConfig {
+    /// Days after which a read counts half (default: 14).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub half_life_days: Option<u64>,
+    /// How much read history counts, in percent (default: 50, 0 = not at all).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_weight: Option<u64>,
+}
+
+impl RelevanceConfig {
+    #[allow(clippy::cast_precision_loss)]
+    fn half_life_days(self) -> f64 {
+        self.half_life_days.unwrap_or(DEFAULT_HALF_LIFE_DAYS).max(1) as f64
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn weight(self) -> f64 {
+        self.access_weight.unwrap_or(DEFAULT_ACCESS_WEIGHT) as f64 / 100.0
+    }
+
+    /// Score multiplier from an item's read history: 1.0 for an item never
+    /// read, more the more and the more recently it was.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn boost(self, access: ItemAccess, now_ms: i64) -> f64 {
+        let Some(last) = access.last_accessed_at.filter(|_| access.count > 0) else {
+            return 1.0;
+        };
+        let reads = (access.count as f64).ln_1p();
+        1.0 + self.weight() * reads * self.decay(last, now_ms)
+    }
+
+    /// Relevance for `sc get --sort relevance`: recency of the last update,
+    /// decayed over the same half-life, times the read boost.
+    #[must_use]
+    pub fn score(self, updated_at: i64, access: ItemAccess, now_ms: i64) -> f64 {
+        self.decay(updated_at, now_ms) * self.boost(access, now_ms)
+    }
+
+    /// 1.0 at `at_ms`, halving every half-life since.
+    #[allow(clippy::cast_precision_loss)]
+    fn decay(self, at_ms: i64, now_ms: i64) -> f64 {
+        let age_days = (now_ms - at_ms).max(0) as f64 / DAY_MS;
+        0.5_f64.powf(age_days / self.half_life_days())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boost_grows_with_reads_and_fades() {
+        let config = RelevanceConfig::default();
+        let now = 100 * 86_400_000;
+        let read = |count, days_ago: i64| ItemAccess { count, last_accessed_at: Some(now - days_ago * 86_400_000) };
+
+        assert!((config.boost(ItemAccess::default(), now) - 1.0).abs() < 1e-10);
+        assert!((config.boost(read(1, 0), now) - (1.0 + 0.5 * 2.0_f64.ln())).abs() < 1e-10);
+        assert!(config.boost(read(10, 0), now) > config.boost(read(1, 0), now));
+        assert!(config.boost(read(10, 28), now) < config.boost(read(10, 0), now));
+
+        let off = RelevanceConfig { access_weight: Some(0), ..RelevanceConfig::default() };
+        assert!((off.boost(read(10, 0), now) - 1.0).abs() < 1e-10);
+
+        // Read yesterday beats untouched and equally old
+        assert!(config.score(now - 30 * 86_400_000, read(3, 1), now) > config.score(now - 30 * 86_400_000, ItemAccess::default(), now));
+        assert!((config.score(now - 14 * 86_400_000, ItemAccess::default(), now) - 0.5).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,268 @@
+//! Actor roles (permission gates).
+//!
+//! In an orchestrated setup several agents share one database, and a
+//! planner that can also delete sessions, or an executor that can rewrite
+//! plans, is one bad tool call away from damaging shared state. Roles
+//! limit which storage operations an actor may perform:
+//!
+//! ```json
+//! {
+//!   "permissions": {
+//!     "roles": {
+//!       "planner": { "allow": ["create_*", "update_*", "add_*", "comment_issue"], "deny": ["update_issue_status"] },
+//!       "executor": { "allow": ["claim_issue", "release_issue", "update_issue_status", "comment_issue", "log_issue_work", "save_context_item"] }
+//!     },
+//!     "actors": { "claude-planner": "planner", "claude-exec": "executor" }
+//!   }
+//! }
+//! ```
+//!
+//! Rules name the operations in [`OPERATIONS`]; a trailing `*` matches a
+//! prefix and `*` alone matches everything. An operation is permitted when
+//! the role's `allow` list is empty or matches it, and no `deny` entry
+//! does. Actors without a role (and no `default_role`) are unrestricted.
+//!
+//! Whole commands can be switched off by actor type, for environments
+//! where agents shouldn't run destructive commands at all:
+//!
+//! ```json
+//! { "permissions": { "commands": { "agent": { "deny": ["project delete", "session delete", "purge-actor"] } } } }
+//! ```
+//!
+//! Command rules name subcommand paths as typed (`issue dep add`); a rule
+//! also covers the subcommands below it, so `"issue"` matches every issue
+//! command. The keys are actor types (`human`, `agent`, `model`), and an
+//! actor that isn't registered counts as `human`. `sc` checks them before
+//! running anything and refuses with `POLICY_DENIED`.
+//!
+//! Roles are a guardrail against mistakes, not access control: anyone who
+//! can set `SC_ACTOR` can pick a different actor.
+
+use crate::model::ActorType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Every operation that goes through the storage mutation path, by the
+/// name role rules refer to.
+pub const OPERATIONS: &[&str] = &[
+    "add_actor_alias",
+    "add_checkpoint_item",
+    "add_issue_dependency",
+    "add_issue_labels",
+    "add_session_path",
+    "add_subproject",
+    "add_tags_to_item",
+    "assign_issue",
+    "claim_issue",
+    "clone_issue",
+    "comment_issue",
+    "complete_reminder",
+    "create_checkpoint",
+    "create_issue",
+    "create_plan",
+    "create_project",
+    "create_session",
+    "create_time_entry",
+    "delete_category",
+    "delete_checkpoint",
+    "delete_context_item",
+    "delete_issue",
+    "delete_memory",
+    "delete_project",
+    "delete_session",
+    "delete_time_entry",
+    "invoice_time_entries",
+    "log_issue_work",
+    "merge_project_paths",
+    "move_project_path",
+    "purge_actor",
+    "register_actor",
+    "release_expired_claims",
+    "release_issue",
+    "remove_actor_alias",
+    "remove_checkpoint_item",
+    "remove_issue_dependency",
+    "remove_issue_labels",
+    "remove_session_path",
+    "remove_tags_from_item",
+    "rename_category",
+    "rename_session",
+    "restore_checkpoint",
+    "restore_trash",
+    "revert_plan",
+    "review_context_item",
+    "save_category",
+    "save_context_item",
+    "save_memory",
+    "save_reminder",
+    "set_close_reason",
+    "set_issue_estimate",
+    "set_item_trust",
+    "switch_session_branch",
+    "unwatch_issue",
+    "update_context_item",
+    "update_issue",
+    "update_issue_status",
+    "update_plan",
+    "update_plan_source",
+    "update_project",
+    "update_session_status",
+    "update_time_entry",
+    "update_time_entry_status",
+    "watch_issue",
+];
+
+/// Housekeeping the CLI does on its own after a command (idle pauses,
+/// scheduled checkpoints, usage counters, session auto-resolution). These
+/// run under the caller's actor but are never gated.
+pub const HOUSEKEEPING_OPERATIONS: &[&str] =
+    &["auto_resolve_session", "create_snapshot_checkpoint", "pause_idle_sessions", "record_session_usage"];
+
+/// What a role may do.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Role {
+    /// Operations permitted; empty permits all but the denied ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Operations refused even if allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+/// The `permissions` section of `~/.savecontext/config.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionsConfig {
+    /// Role name → rules.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub roles: BTreeMap<String, Role>,
+    /// Canonical actor ID → role name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub actors: BTreeMap<String, String>,
+    /// Role of actors not listed under `actors`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_role: Option<String>,
+    /// Actor type → commands it may run.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, Role>,
+}
+
+impl PermissionsConfig {
+    /// The role `actor` (a canonical ID) has, if any.
+    #[must_use]
+    pub fn role_of(&self, actor: &str) -> Option<&str> {
+        self.actors.get(actor).or(self.default_role.as_ref()).map(String::as_str)
+    }
+
+    /// Whether `role` permits `op`. Roles that aren't defined permit
+    /// nothing, so a typo in `actors` fails closed.
+    #[must_use]
+    pub fn allows(&self, role: &str, op: &str) -> bool {
+        if HOUSEKEEPING_OPERATIONS.contains(&op) {
+            return true;
+        }
+        let Some(rules) = self.roles.get(role) else {
+            return false;
+        };
+        let allowed = rules.allow.is_empty() || rules.allow.iter().any(|p| matches(p, op));
+        allowed && !rules.deny.iter().any(|p| matches(p, op))
+    }
+
+    /// Whether actors of `actor_type` may run `command`, a subcommand path
+    /// like `"project delete"`. Types without rules may run anything.
+    #[must_use]
+    pub fn allows_command(&self, actor_type: ActorType, command: &str) -> bool {
+        let Some(rules) = self.commands.get(actor_type.as_str()) else {
+            return true;
+        };
+        let allowed = rules.allow.is_empty() || rules.allow.iter().any(|p| matches_command(p, command));
+        allowed && !rules.deny.iter().any(|p| matches_command(p, command))
+    }
+
+    /// The operations in [`OPERATIONS`] that `role` permits.
+    #[must_use]
+    pub fn allowed_operations(&self, role: &str) -> Vec<&'static str> {
+        OPERATIONS.iter().copied().filter(|op| self.allows(role, op)).collect()
+    }
+}
+
+/// Whether a rule pattern matches an operation name.
+fn matches(pattern: &str, op: &str) -> bool {
+    pattern.strip_suffix('*').map_or(pattern == op, |prefix| op.starts_with(prefix))
+}
+
+/// Whether a command rule matches a command or one of its parents.
+fn matches_command(pattern: &str, command: &str) -> bool {
+    let pattern = pattern.trim();
+    pattern == "*"
+        || command
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PermissionsConfig {
+        serde_json::from_value(serde_json::json!({
+            "roles": {
+                "planner": { "allow": ["create_*", "update_*"], "deny": ["update_issue_status"] },
+                "reviewer": { "deny": ["delete_*"] }
+            },
+            "actors": { "plan-bot": "planner", "typo-bot": "plannr" },
+            "default_role": "reviewer"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_allow_and_deny_patterns() {
+        let config = config();
+        assert!(config.allows("planner", "create_issue"));
+        assert!(config.allows("planner", "update_plan"));
+        assert!(!config.allows("planner", "update_issue_status"));
+        assert!(!config.allows("planner", "delete_session"));
+        assert!(config.allows("reviewer", "claim_issue"));
+        assert!(!config.allows("reviewer", "delete_issue"));
+        assert!(config.allows("planner", "pause_idle_sessions"));
+    }
+
+    #[test]
+    fn test_role_lookup_and_undefined_roles() {
+        let config = config();
+        assert_eq!(config.role_of("plan-bot"), Some("planner"));
+        assert_eq!(config.role_of("someone"), Some("reviewer"));
+        assert!(!config.allows(config.role_of("typo-bot").unwrap(), "create_issue"));
+        assert_eq!(PermissionsConfig::default().role_of("someone"), None);
+    }
+
+    #[test]
+    fn test_command_rules_by_actor_type() {
+        let config: PermissionsConfig = serde_json::from_value(serde_json::json!({
+            "commands": {
+                "agent": { "deny": ["project delete", "session delete", "issue dep"] },
+                "model": { "allow": ["get", "prime"] }
+            }
+        }))
+        .unwrap();
+        assert!(!config.allows_command(ActorType::Agent, "project delete"));
+        assert!(config.allows_command(ActorType::Agent, "project list"));
+        assert!(!config.allows_command(ActorType::Agent, "issue dep remove"));
+        assert!(config.allows_command(ActorType::Agent, "issue deps"));
+        assert!(config.allows_command(ActorType::Human, "project delete"));
+        assert!(config.allows_command(ActorType::Model, "prime"));
+        assert!(!config.allows_command(ActorType::Model, "save"));
+    }
+
+    #[test]
+    fn test_operations_cover_the_mutation_path() {
+        let source = include_str!("../storage/sqlite.rs");
+        for chunk in source.split("self.mutate(\"").skip(1) {
+            let op = &chunk[..chunk.find('"').unwrap()];
+            assert!(
+                OPERATIONS.contains(&op) || HOUSEKEEPING_OPERATIONS.contains(&op),
+                "{op} is missing from OPERATIONS"
+            );
+        }
+    }
+}
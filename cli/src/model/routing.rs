@@ -0,0 +1,330 @@
+//! Work routing (who gets which ready issue).
+//!
+//! `sc issue next-block` used to hand the caller the top N ready issues,
+//! however many it already had in progress, and an orchestrator had to
+//! split the ready list between its agents itself. Routing rules decide
+//! both, for `sc issue route` (one call that deals ready issues out to a
+//! set of agents) and for `sc issue next-block` (the caller's own share):
+//!
+//! ```json
+//! {
+//!   "routing": {
+//!     "policy": "skills",
+//!     "wip_limit": 3,
+//!     "agents": {
+//!       "claude-backend": { "skills": ["api", "database"] },
+//!       "claude-frontend": { "skills": ["ui"], "wip_limit": 2 }
+//!     },
+//!     "projects": { "/home/me/site": { "policy": "round-robin" } }
+//!   }
+//! }
+//! ```
+//!
+//! Issues are taken in ready order (priority, then age). Under
+//! `round-robin` each goes to the agent with the fewest issues in
+//! progress, ties going to the one assigned least recently. Under `skills`
+//! an issue goes to the agent whose skills share the most labels with it;
+//! issues no agent with room has a skill for are dealt out round-robin
+//! after every matching issue. An agent at its WIP limit (in-progress
+//! issues) gets nothing; without a limit there is none.
+//!
+//! Project entries replace the policy and limit they set, and the entries
+//! of the agents they list.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// How ready issues are dealt out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoutingPolicy {
+    /// Least loaded agent first.
+    #[default]
+    RoundRobin,
+    /// Agent whose skills match the issue's labels first.
+    Skills,
+}
+
+impl RoutingPolicy {
+    /// Spellings accepted in config and on the command line.
+    pub const NAMES: &'static [&'static str] = &["round-robin", "skills"];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::RoundRobin => "round-robin",
+            Self::Skills => "skills",
+        }
+    }
+}
+
+impl fmt::Display for RoutingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RoutingPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "round-robin" => Ok(Self::RoundRobin),
+            "skills" => Ok(Self::Skills),
+            _ => Err(Error::InvalidArgument(format!(
+                "Invalid routing policy '{s}'. Valid values: {}",
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+/// What routing knows about one agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentRouting {
+    /// Issue labels this agent should get first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<String>,
+    /// In-progress issues above which it gets no more (overrides the
+    /// top-level limit).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wip_limit: Option<u32>,
+}
+
+/// Routing settings as stored in the config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<RoutingPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wip_limit: Option<u32>,
+    /// Keyed by actor.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, AgentRouting>,
+}
+
+/// The `routing` section of `~/.savecontext/config.json`.
+///
+/// Top-level settings apply to every project; entries under `projects`
+/// (keyed by project path) are applied on top of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    #[serde(flatten)]
+    pub global: RoutingOverrides,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, RoutingOverrides>,
+}
+
+/// An agent's current load, as counted from the issues assigned to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgentWorkload {
+    pub agent: String,
+    /// Issues in progress and assigned to it.
+    pub in_progress: u32,
+    /// When it was last assigned an issue.
+    pub last_assigned_at: Option<i64>,
+}
+
+/// One issue given to one agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    /// Index into the ready issues passed to [`Routing::assign`].
+    pub issue: usize,
+    pub agent: String,
+    /// The agent's skills the issue is labelled with.
+    pub matched: Vec<String>,
+}
+
+/// Resolved routing rules for a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Routing {
+    pub policy: RoutingPolicy,
+    pub wip_limit: Option<u32>,
+    pub agents: BTreeMap<String, AgentRouting>,
+}
+
+impl Routing {
+    /// Resolve the rules for a project from the global settings and the
+    /// project's entry (in that order).
+    #[must_use]
+    pub fn resolve(config: Option<&RoutingConfig>, project_path: Option<&str>) -> Self {
+        let mut routing = Self::default();
+        if let Some(config) = config {
+            routing.apply(&config.global);
+            if let Some(overrides) = project_path.and_then(|p| config.projects.get(p)) {
+                routing.apply(overrides);
+            }
+        }
+        routing
+    }
+
+    /// Apply overrides: the policy and limit if set, and each agent listed.
+    pub fn apply(&mut self, overrides: &RoutingOverrides) {
+        if let Some(policy) = overrides.policy {
+            self.policy = policy;
+        }
+        if let Some(limit) = overrides.wip_limit {
+            self.wip_limit = Some(limit);
+        }
+        for (agent, rules) in &overrides.agents {
+            self.agents.insert(agent.clone(), rules.clone());
+        }
+    }
+
+    /// The most issues `agent` may have in progress, if limited.
+    #[must_use]
+    pub fn wip_limit(&self, agent: &str) -> Option<u32> {
+        self.agents.get(agent).and_then(|a| a.wip_limit).or(self.wip_limit)
+    }
+
+    /// Lower every WIP limit to at most `max`, and set it where unset.
+    pub fn cap_wip_limits(&mut self, max: u32) {
+        self.wip_limit = Some(self.wip_limit.map_or(max, |limit| limit.min(max)));
+        for agent in self.agents.values_mut() {
+            agent.wip_limit = agent.wip_limit.map(|limit| limit.min(max));
+        }
+    }
+
+    /// How many more issues `agent` may take; `None` when unlimited.
+    #[must_use]
+    pub fn capacity(&self, workload: &AgentWorkload) -> Option<u32> {
+        self.wip_limit(&workload.agent).map(|limit| limit.saturating_sub(workload.in_progress))
+    }
+
+    /// Deal up to `count` ready issues out to `agents`.
+    ///
+    /// `labels` holds the labels of each ready issue, in ready order.
+    /// Assignments come back in that order too.
+    #[must_use]
+    pub fn assign(&self, labels: &[Vec<String>], agents: &[AgentWorkload], count: usize) -> Vec<Assignment> {
+        struct Slot<'a> {
+            agent: &'a str,
+            skills: Vec<String>,
+            load: u32,
+            room: Option<u32>,
+            turn: usize,
+        }
+
+        let mut order: Vec<&AgentWorkload> = agents.iter().collect();
+        order.sort_by(|a, b| {
+            (a.in_progress, a.last_assigned_at, &a.agent).cmp(&(b.in_progress, b.last_assigned_at, &b.agent))
+        });
+        let mut slots: Vec<Slot> = order
+            .iter()
+            .enumerate()
+            .map(|(turn, workload)| Slot {
+                agent: &workload.agent,
+                skills: self
+                    .agents
+                    .get(&workload.agent)
+                    .map(|a| a.skills.iter().map(|s| s.to_lowercase()).collect())
+                    .unwrap_or_default(),
+                load: workload.in_progress,
+                room: self.capacity(workload),
+                turn,
+            })
+            .collect();
+        let mut next_turn = slots.len();
+
+        let labels: Vec<Vec<String>> =
+            labels.iter().map(|l| l.iter().map(|label| label.to_lowercase()).collect()).collect();
+        let matching = |slot: &Slot, issue: &[String]| -> Vec<String> {
+            slot.skills.iter().filter(|skill| issue.contains(skill)).cloned().collect()
+        };
+
+        let skills = self.policy == RoutingPolicy::Skills;
+        let passes: &[bool] = if skills { &[true, false] } else { &[false] };
+
+        let mut taken = vec![false; labels.len()];
+        let mut assignments = Vec::new();
+        'passes: for &skilled_only in passes {
+            for (index, issue) in labels.iter().enumerate() {
+                if assignments.len() >= count || slots.iter().all(|slot| slot.room == Some(0)) {
+                    break 'passes;
+                }
+                if taken[index] {
+                    continue;
+                }
+                let best = slots
+                    .iter_mut()
+                    .filter(|slot| slot.room != Some(0))
+                    .map(|slot| (matching(slot, issue), slot))
+                    .filter(|(matched, _)| !skilled_only || !matched.is_empty())
+                    .min_by_key(|(matched, slot)| {
+                        let skill = if skills { matched.len() } else { 0 };
+                        (std::cmp::Reverse(skill), slot.load, slot.turn)
+                    });
+                let Some((matched, slot)) = best else {
+                    continue;
+                };
+                slot.load += 1;
+                slot.room = slot.room.map(|room| room - 1);
+                slot.turn = next_turn;
+                next_turn += 1;
+                taken[index] = true;
+                assignments.push(Assignment { issue: index, agent: slot.agent.to_string(), matched });
+            }
+        }
+        assignments.sort_by_key(|assignment| assignment.issue);
+        assignments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workload(agent: &str, in_progress: u32, last_assigned_at: Option<i64>) -> AgentWorkload {
+        AgentWorkload { agent: agent.to_string(), in_progress, last_assigned_at }
+    }
+
+    fn labels(issues: &[&[&str]]) -> Vec<Vec<String>> {
+        issues.iter().map(|l| l.iter().map(ToString::to_string).collect()).collect()
+    }
+
+    fn agents_of(assignments: &[Assignment]) -> Vec<(usize, &str)> {
+        assignments.iter().map(|a| (a.issue, a.agent.as_str())).collect()
+    }
+
+    #[test]
+    fn test_round_robin_least_loaded_first() {
+        let routing = Routing::default();
+        let agents = [workload("a", 1, Some(10)), workload("b", 0, Some(20)), workload("c", 0, Some(5))];
+        let assigned = routing.assign(&labels(&[&[], &[], &[], &[]]), &agents, 4);
+        assert_eq!(agents_of(&assigned), [(0, "c"), (1, "b"), (2, "a"), (3, "c")]);
+
+        let assigned = routing.assign(&labels(&[&[], &[], &[]]), &agents, 2);
+        assert_eq!(assigned.len(), 2);
+    }
+
+    #[test]
+    fn test_skills_and_wip_limits() {
+        let config: RoutingConfig = serde_json::from_value(serde_json::json!({
+            "policy": "round-robin",
+            "wip_limit": 2,
+            "agents": { "api": { "skills": ["Backend"] }, "web": { "skills": ["ui"], "wip_limit": 1 } },
+            "projects": { "/p": { "policy": "skills" } }
+        }))
+        .unwrap();
+        let routing = Routing::resolve(Some(&config), Some("/p"));
+        assert_eq!(routing.policy, RoutingPolicy::Skills);
+        assert_eq!(routing.wip_limit("web"), Some(1));
+        assert_eq!(routing.wip_limit("other"), Some(2));
+        assert_eq!(Routing::resolve(Some(&config), Some("/q")).policy, RoutingPolicy::RoundRobin);
+
+        let agents = [workload("api", 0, None), workload("web", 0, None)];
+        let issues = labels(&[&["docs"], &["ui"], &["backend"], &["ui"], &["backend"]]);
+        let assigned = routing.assign(&issues, &agents, 10);
+        // web is full after one ui issue; api takes both backend issues and
+        // is then full too, so the unmatched and second ui issue wait
+        assert_eq!(agents_of(&assigned), [(1, "web"), (2, "api"), (4, "api")]);
+        assert_eq!(assigned[1].matched, ["backend"]);
+
+        let busy = [workload("api", 2, None), workload("web", 0, None)];
+        assert_eq!(agents_of(&routing.assign(&issues, &busy, 10)), [(1, "web")]);
+        assert!("random".parse::<RoutingPolicy>().is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Scheduling issues by their `blocks` dependencies.
+//!
+//! Both functions take issues as a list of IDs and dependencies as
+//! (blocked, blocker) pairs, and answer in indexes into that list:
+//!
+//! - [`waves`] layers the issues so each wave only waits on earlier ones,
+//!   for `sc issue waves`;
+//! - [`critical_path`] adds up hours along the dependency chains (the
+//!   critical path method), for `sc plan critical-path` and
+//!   `sc issue critical-path`.
+
+use std::collections::{HashMap, HashSet};
+
+/// Layer `ids` (in order) by `edges` of (blocked, blocker).
+///
+/// Returns the index of each ID per wave, keeping the input order within a
+/// wave, and the indexes that can't be placed: those in a cycle, or
+/// blocked (directly or not) by an ID not in `ids`.
+#[must_use]
+pub fn waves(ids: &[&str], edges: &[(String, String)]) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let graph = Graph::new(ids, edges);
+    let mut waiting_on: Vec<usize> = graph.blockers.iter().map(Vec::len).collect();
+
+    let mut waves = Vec::new();
+    let mut current: Vec<usize> = (0..ids.len()).filter(|&i| waiting_on[i] == 0 && !graph.outside[i]).collect();
+    let mut placed = vec![false; ids.len()];
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &i in &current {
+            placed[i] = true;
+            for &dependent in &graph.dependents[i] {
+                waiting_on[dependent] -= 1;
+                if waiting_on[dependent] == 0 && !graph.outside[dependent] {
+                    next.push(dependent);
+                }
+            }
+        }
+        next.sort_unstable();
+        waves.push(std::mem::replace(&mut current, next));
+    }
+    let stuck = (0..ids.len()).filter(|&i| !placed[i]).collect();
+    (waves, stuck)
+}
+
+/// Timing of every issue under the critical path method.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schedule {
+    /// Hours until the issue can start, its blockers done.
+    pub earliest_start: Vec<f64>,
+    pub earliest_finish: Vec<f64>,
+    /// Hours the issue can slip without delaying the end; 0 on the
+    /// critical path.
+    pub slack: Vec<f64>,
+    /// The longest chain, first issue first.
+    pub path: Vec<usize>,
+    /// Hours until everything is done: the length of `path`.
+    pub total_hours: f64,
+    /// Issues in a dependency cycle, or waiting on one, which can't be
+    /// scheduled.
+    pub cyclic: Vec<usize>,
+}
+
+/// Schedule `ids`, each taking `hours[i]`, by `edges` of (blocked,
+/// blocker). Edges to IDs not in `ids` are ignored.
+#[must_use]
+pub fn critical_path(ids: &[&str], edges: &[(String, String)], hours: &[f64]) -> Schedule {
+    let in_scope: HashSet<&str> = ids.iter().copied().collect();
+    let edges: Vec<(String, String)> =
+        edges.iter().filter(|(_, blocker)| in_scope.contains(blocker.as_str())).cloned().collect();
+    let graph = Graph::new(ids, &edges);
+    let (layers, cyclic) = waves(ids, &edges);
+    let order: Vec<usize> = layers.into_iter().flatten().collect();
+
+    let mut start = vec![0.0_f64; ids.len()];
+    let mut finish = vec![0.0_f64; ids.len()];
+    for &i in &order {
+        start[i] = graph.blockers[i].iter().map(|&b| finish[b]).fold(0.0, f64::max);
+        finish[i] = start[i] + hours[i];
+    }
+    let total_hours = order.iter().map(|&i| finish[i]).fold(0.0, f64::max);
+
+    let mut latest_finish = vec![total_hours; ids.len()];
+    for &i in order.iter().rev() {
+        let latest_start = latest_finish[i] - hours[i];
+        for &b in &graph.blockers[i] {
+            latest_finish[b] = latest_finish[b].min(latest_start);
+        }
+    }
+    let slack: Vec<f64> = (0..ids.len())
+        .map(|i| if cyclic.contains(&i) { 0.0 } else { (latest_finish[i] - finish[i]).max(0.0) })
+        .collect();
+
+    // Walk back from the issue that finishes last, through the blocker
+    // that finished right when each issue could start
+    let mut path = Vec::new();
+    let mut at = order.iter().copied().find(|&i| close(finish[i], total_hours));
+    while let Some(i) = at {
+        path.push(i);
+        at = order.iter().copied().find(|&b| graph.blockers[i].contains(&b) && close(finish[b], start[i]));
+    }
+    path.reverse();
+
+    Schedule { earliest_start: start, earliest_finish: finish, slack, path, total_hours, cyclic }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+/// `blocks` edges by index.
+struct Graph {
+    blockers: Vec<Vec<usize>>,
+    dependents: Vec<Vec<usize>>,
+    /// Blocked by something not in the list.
+    outside: Vec<bool>,
+}
+
+impl Graph {
+    fn new(ids: &[&str], edges: &[(String, String)]) -> Self {
+        let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut graph =
+            Self { blockers: vec![Vec::new(); ids.len()], dependents: vec![Vec::new(); ids.len()], outside: vec![false; ids.len()] };
+        for (issue, blocker) in edges {
+            let Some(&issue) = index.get(issue.as_str()) else {
+                continue;
+            };
+            match index.get(blocker.as_str()) {
+                Some(&blocker) if !graph.blockers[issue].contains(&blocker) => {
+                    graph.blockers[issue].push(blocker);
+                    graph.dependents[blocker].push(issue);
+                }
+                Some(_) => {}
+                None => graph.outside[issue] = true,
+            }
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(a, b)| ((*a).to_string(), (*b).to_string())).collect()
+    }
+
+    #[test]
+    fn test_critical_path() {
+        // a(4) → c(2) → d(1); b(1) → d; e(3) on its own
+        let ids = ["a", "b", "c", "d", "e"];
+        let deps = edges(&[("c", "a"), ("d", "c"), ("d", "b"), ("a", "elsewhere")]);
+        let schedule = critical_path(&ids, &deps, &[4.0, 1.0, 2.0, 1.0, 3.0]);
+        assert_eq!(schedule.path, [0, 2, 3]);
+        assert!(close(schedule.total_hours, 7.0));
+        assert_eq!(schedule.earliest_start, [0.0, 0.0, 4.0, 6.0, 0.0]);
+        assert_eq!(schedule.slack, [0.0, 5.0, 0.0, 0.0, 4.0]);
+        assert!(schedule.cyclic.is_empty());
+
+        let cycle = critical_path(&["x", "y", "z"], &edges(&[("x", "y"), ("y", "x")]), &[1.0, 1.0, 2.0]);
+        assert_eq!((cycle.path, cycle.cyclic), (vec![2], vec![0, 1]));
+    }
+}
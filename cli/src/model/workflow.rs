@@ -0,0 +1,297 @@
+//! Issue workflow (status state machine).
+//!
+//! Issue status used to be a free string, so agents could invent values
+//! like `"review"` or move a closed issue straight to `"blocked"`. The
+//! workflow defines which statuses exist, which transitions between them
+//! are legal, and which fields must be present when entering a status.
+//!
+//! The built-in graph follows the lifecycle
+//! `backlog → open → in_progress → blocked → closed/deferred` and can be
+//! overridden globally or per project in `~/.savecontext/config.json`:
+//!
+//! ```json
+//! {
+//!   "workflow": {
+//!     "required_fields": { "closed": ["close_reason"] },
+//!     "projects": {
+//!       "/home/me/api": {
+//!         "transitions": { "in_progress": ["review", "blocked"], "review": ["closed", "in_progress"] }
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Overrides replace the entry for each status they mention; statuses they
+//! don't mention keep the built-in rules.
+
+use crate::model::IssueStatus;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Fields that may be listed under `required_fields`.
+///
+/// Each name maps to a column on the `issues` table (or, for
+/// `close_reason`, to the value supplied with the transition).
+pub const REQUIRED_FIELD_NAMES: &[&str] =
+    &["close_reason", "description", "details", "assignee", "plan_id"];
+
+/// Workflow overrides as stored in the config file.
+///
+/// Both maps are keyed by status. A `transitions` entry lists the statuses
+/// reachable from the key; a `required_fields` entry lists the fields that
+/// must be set when an issue enters the key status.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transitions: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_fields: Option<BTreeMap<String, Vec<String>>>,
+}
+
+/// The `workflow` section of `~/.savecontext/config.json`.
+///
+/// Top-level overrides apply to every project; entries under `projects`
+/// (keyed by project path) are applied on top of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowConfig {
+    #[serde(flatten)]
+    pub global: WorkflowOverrides,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, WorkflowOverrides>,
+}
+
+/// A resolved issue workflow: the set of legal transitions and the
+/// fields required when entering each status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IssueWorkflow {
+    /// Status → statuses reachable from it.
+    pub transitions: BTreeMap<String, Vec<String>>,
+    /// Status → fields required when entering it.
+    pub required_fields: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for IssueWorkflow {
+    fn default() -> Self {
+        let transitions = [
+            ("backlog", &["open", "deferred", "closed"][..]),
+            ("open", &["backlog", "in_progress", "blocked", "deferred", "closed"][..]),
+            ("in_progress", &["open", "blocked", "deferred", "closed"][..]),
+            ("blocked", &["open", "in_progress", "deferred", "closed"][..]),
+            ("deferred", &["backlog", "open", "closed"][..]),
+            ("closed", &["open"][..]),
+        ]
+        .into_iter()
+        .map(|(from, to)| {
+            (
+                from.to_string(),
+                to.iter().map(ToString::to_string).collect(),
+            )
+        })
+        .collect();
+
+        Self {
+            transitions,
+            required_fields: BTreeMap::new(),
+        }
+    }
+}
+
+impl IssueWorkflow {
+    /// Resolve the workflow for a project from the built-in defaults,
+    /// the global overrides, and the project's overrides (in that order).
+    #[must_use]
+    pub fn resolve(config: Option<&WorkflowConfig>, project_path: Option<&str>) -> Self {
+        let mut workflow = Self::default();
+        if let Some(config) = config {
+            workflow.apply(&config.global);
+            if let Some(overrides) = project_path.and_then(|p| config.projects.get(p)) {
+                workflow.apply(overrides);
+            }
+        }
+        workflow
+    }
+
+    /// Apply overrides, replacing the rules for every status they mention.
+    pub fn apply(&mut self, overrides: &WorkflowOverrides) {
+        if let Some(ref transitions) = overrides.transitions {
+            for (from, to) in transitions {
+                self.transitions.insert(from.clone(), to.clone());
+            }
+        }
+        if let Some(ref required) = overrides.required_fields {
+            for (status, fields) in required {
+                self.required_fields.insert(status.clone(), fields.clone());
+            }
+        }
+    }
+
+    /// All statuses known to this workflow (sources and targets).
+    #[must_use]
+    pub fn statuses(&self) -> Vec<&str> {
+        let mut all: Vec<&str> = self
+            .transitions
+            .iter()
+            .flat_map(|(from, to)| std::iter::once(from.as_str()).chain(to.iter().map(String::as_str)))
+            .collect();
+        all.sort_unstable();
+        all.dedup();
+        all
+    }
+
+    /// Whether `status` is a state in this workflow.
+    #[must_use]
+    pub fn is_known(&self, status: &str) -> bool {
+        self.transitions.contains_key(status)
+            || self.transitions.values().any(|to| to.iter().any(|s| s == status))
+    }
+
+    /// Statuses reachable from `from`.
+    #[must_use]
+    pub fn allowed_from(&self, from: &str) -> &[String] {
+        self.transitions.get(from).map_or(&[], Vec::as_slice)
+    }
+
+    /// Check whether moving from `from` to `to` is legal.
+    ///
+    /// Staying in the same status is always allowed. An issue whose current
+    /// status is not part of the workflow (legacy data, or a status removed
+    /// by an override) may move to any known status so it can be repaired.
+    #[must_use]
+    pub fn can_transition(&self, from: &str, to: &str) -> bool {
+        if !self.is_known(to) {
+            return false;
+        }
+        if from == to || !self.is_known(from) {
+            return true;
+        }
+        self.allowed_from(from).iter().any(|s| s == to)
+    }
+
+    /// Fields required when entering `status`.
+    #[must_use]
+    pub fn required_for(&self, status: &str) -> &[String] {
+        self.required_fields.get(status).map_or(&[], Vec::as_slice)
+    }
+
+    /// Problems with this workflow, one message per problem.
+    ///
+    /// Flags unknown required-field names, status names issues can't be set
+    /// to (see [`IssueStatus::is_valid_name`]), and reachable statuses that
+    /// have no outgoing transitions (an issue entering them could never
+    /// leave).
+    #[must_use]
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (status, fields) in &self.required_fields {
+            for field in fields {
+                if !REQUIRED_FIELD_NAMES.contains(&field.as_str()) {
+                    problems.push(format!(
+                        "required_fields.{status}: unknown field '{field}' (valid: {})",
+                        REQUIRED_FIELD_NAMES.join(", ")
+                    ));
+                }
+            }
+        }
+        for status in self.statuses() {
+            if !IssueStatus::is_valid_name(status) {
+                problems.push(format!(
+                    "transitions: '{status}' is not a valid status name (lowercase letters, digits, - and _)"
+                ));
+            }
+            if !self.transitions.contains_key(status) {
+                problems.push(format!(
+                    "transitions: status '{status}' is reachable but has no outgoing transitions"
+                ));
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_transitions() {
+        let wf = IssueWorkflow::default();
+        assert!(wf.can_transition("backlog", "open"));
+        assert!(wf.can_transition("open", "in_progress"));
+        assert!(wf.can_transition("in_progress", "blocked"));
+        assert!(wf.can_transition("blocked", "closed"));
+        assert!(wf.can_transition("closed", "open"));
+        assert!(!wf.can_transition("closed", "blocked"));
+        assert!(!wf.can_transition("open", "review"));
+        assert!(wf.problems().is_empty());
+    }
+
+    #[test]
+    fn test_same_and_unknown_source_status() {
+        let wf = IssueWorkflow::default();
+        assert!(wf.can_transition("closed", "closed"));
+        assert!(wf.can_transition("legacy-status", "open"));
+        assert!(!wf.can_transition("legacy-status", "nonsense"));
+    }
+
+    #[test]
+    fn test_project_overrides_layer_on_global() {
+        let mut config = WorkflowConfig::default();
+        config.global.required_fields =
+            Some([("closed".to_string(), vec!["close_reason".to_string()])].into());
+        config.projects.insert(
+            "/proj".to_string(),
+            WorkflowOverrides {
+                transitions: Some(
+                    [
+                        ("in_progress".to_string(), vec!["review".to_string()]),
+                        ("review".to_string(), vec!["closed".to_string(), "in_progress".to_string()]),
+                    ]
+                    .into(),
+                ),
+                required_fields: None,
+            },
+        );
+
+        let wf = IssueWorkflow::resolve(Some(&config), Some("/proj"));
+        assert!(wf.can_transition("in_progress", "review"));
+        assert!(!wf.can_transition("in_progress", "closed"));
+        assert!(wf.can_transition("open", "closed"));
+        assert_eq!(wf.required_for("closed"), ["close_reason".to_string()]);
+
+        let other = IssueWorkflow::resolve(Some(&config), Some("/other"));
+        assert!(!other.is_known("review"));
+        assert_eq!(other.required_for("closed"), ["close_reason".to_string()]);
+    }
+
+    #[test]
+    fn test_config_roundtrip() {
+        let json = r#"{"required_fields":{"closed":["close_reason"]},"projects":{"/p":{"transitions":{"open":["closed"]}}}}"#;
+        let config: WorkflowConfig = serde_json::from_str(json).unwrap();
+        assert!(config.global.transitions.is_none());
+        assert!(config.projects.contains_key("/p"));
+        let back: WorkflowConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(back, config);
+    }
+
+    #[test]
+    fn test_problems_reports_unknown_fields() {
+        let mut wf = IssueWorkflow::default();
+        wf.required_fields.insert("closed".to_string(), vec!["reviewer".to_string()]);
+        assert_eq!(wf.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_hyphenated_custom_status() {
+        let mut wf = IssueWorkflow::default();
+        wf.transitions.insert("in_progress".to_string(), vec!["in-review".to_string()]);
+        wf.transitions.insert("in-review".to_string(), vec!["closed".to_string()]);
+        assert!(wf.problems().is_empty());
+
+        let status: IssueStatus = "In-Review".parse().unwrap();
+        assert!(wf.can_transition("in_progress", status.as_str()));
+
+        wf.transitions.insert("In Review".to_string(), vec!["closed".to_string()]);
+        assert_eq!(wf.problems().len(), 1);
+    }
+}
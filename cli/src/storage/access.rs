@@ -0,0 +1,70 @@
+//! Read history of context items, behind `sc get --sort relevance` and the
+//! read boost in smart prime and semantic search, and of project memory,
+//! behind `sc memory audit`.
+//!
+//! Reads are bookkeeping, not changes: they go straight to `item_access`
+//! and `memory_access` without audit events, `updated_at` or sync dirty
+//! flags.
+
+use crate::error::Result;
+use crate::model::ItemAccess;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Count one read of each of `item_ids` at `now`.
+pub(crate) fn record(conn: &Connection, item_ids: &[String], now: i64) -> Result<()> {
+    record_in(
+        conn,
+        "INSERT INTO item_access (item_id, access_count, last_accessed_at)
+         SELECT id, 1, ?2 FROM context_items WHERE id = ?1
+         ON CONFLICT(item_id) DO UPDATE SET
+             access_count = access_count + 1,
+             last_accessed_at = excluded.last_accessed_at",
+        item_ids,
+        now,
+    )
+}
+
+/// The read history of those of `item_ids` that have been read.
+pub(crate) fn load(conn: &Connection, item_ids: &[String]) -> Result<HashMap<String, ItemAccess>> {
+    load_from(conn, "SELECT access_count, last_accessed_at FROM item_access WHERE item_id = ?1", item_ids)
+}
+
+/// Count one read of each of `memory_ids` at `now`.
+pub(crate) fn record_memory(conn: &Connection, memory_ids: &[String], now: i64) -> Result<()> {
+    record_in(
+        conn,
+        "INSERT INTO memory_access (memory_id, access_count, last_accessed_at)
+         SELECT id, 1, ?2 FROM project_memory WHERE id = ?1
+         ON CONFLICT(memory_id) DO UPDATE SET
+             access_count = access_count + 1,
+             last_accessed_at = excluded.last_accessed_at",
+        memory_ids,
+        now,
+    )
+}
+
+/// The read history of those of `memory_ids` that have been read.
+pub(crate) fn load_memory(conn: &Connection, memory_ids: &[String]) -> Result<HashMap<String, ItemAccess>> {
+    load_from(conn, "SELECT access_count, last_accessed_at FROM memory_access WHERE memory_id = ?1", memory_ids)
+}
+
+fn record_in(conn: &Connection, sql: &str, ids: &[String], now: i64) -> Result<()> {
+    let mut stmt = conn.prepare(sql)?;
+    for id in ids {
+        stmt.execute(rusqlite::params![id, now])?;
+    }
+    Ok(())
+}
+
+fn load_from(conn: &Connection, sql: &str, ids: &[String]) -> Result<HashMap<String, ItemAccess>> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut access = HashMap::new();
+    for id in ids {
+        let mut rows = stmt.query([id])?;
+        if let Some(row) = rows.next()? {
+            access.insert(id.clone(), ItemAccess { count: row.get(0)?, last_accessed_at: row.get(1)? });
+        }
+    }
+    Ok(access)
+}
@@ -0,0 +1,233 @@
+//! The per-project category registry behind `sc category`.
+//!
+//! Rows of `item_categories` either register a new category for a project
+//! or, under a built-in name, override that category's settings. Readers
+//! get the merged [`Categories`]. A session spanning several projects
+//! knows the categories of all of them; where two define the same name,
+//! the session's primary project wins.
+
+use crate::error::{Error, Result};
+use crate::model::{Categories, Category, ItemCategory};
+use crate::storage::compression::read_value;
+use crate::storage::encryption::{skip_embeddings, store_value};
+use rusqlite::{Connection, OptionalExtension};
+
+/// Sessions of a project, primary or added with `sc session add-path`.
+const PROJECT_SESSIONS: &str = "SELECT id FROM sessions WHERE project_path = ?1
+     UNION SELECT session_id FROM session_projects WHERE project_path = ?1";
+
+/// The categories of `project_path`.
+pub(crate) fn list(conn: &Connection, project_path: &str) -> Result<Categories> {
+    let mut categories = Categories::default();
+    merge(conn, project_path, &mut categories)?;
+    Ok(categories)
+}
+
+/// The categories of every project `session_id` belongs to.
+pub(crate) fn for_session(conn: &Connection, session_id: &str) -> Result<Categories> {
+    let primary: Option<Option<String>> = conn
+        .query_row("SELECT project_path FROM sessions WHERE id = ?1", [session_id], |row| row.get(0))
+        .optional()?;
+    let mut paths: Vec<String> = primary.flatten().into_iter().collect();
+    let mut stmt = conn.prepare("SELECT project_path FROM session_projects WHERE session_id = ?1 ORDER BY added_at")?;
+    for path in stmt.query_map([session_id], |row| row.get::<_, String>(0))? {
+        let path = path?;
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    let mut categories = Categories::default();
+    for path in paths.iter().rev() {
+        merge(conn, path, &mut categories)?;
+    }
+    Ok(categories)
+}
+
+/// Apply the rows of `project_path` over `categories`.
+fn merge(conn: &Connection, project_path: &str, categories: &mut Categories) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT name, description, color, default_priority, in_prime, prime_weight, encrypted
+         FROM item_categories WHERE project_path = ?1 ORDER BY name",
+    )?;
+    let rows = stmt.query_map([project_path], |row| {
+        let name: ItemCategory = row.get(0)?;
+        Ok(Category {
+            built_in: name.is_built_in(),
+            name,
+            description: row.get(1)?,
+            color: row.get(2)?,
+            default_priority: row.get(3)?,
+            in_prime: row.get(4)?,
+            prime_weight: row.get(5)?,
+            encrypted: row.get(6)?,
+        })
+    })?;
+    for category in rows {
+        let category = category?;
+        match categories.0.iter_mut().find(|c| c.name == category.name) {
+            Some(existing) => *existing = category,
+            None => categories.0.push(category),
+        }
+    }
+    Ok(())
+}
+
+/// The settings an item saved in `category` gets: the priority it has
+/// when saved without one, and whether its value is encrypted.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if none of the session's projects
+/// knows `category`.
+pub(crate) fn check_item(conn: &Connection, session_id: &str, category: &ItemCategory) -> Result<Category> {
+    let categories = for_session(conn, session_id)?;
+    categories.get(category).cloned().ok_or_else(|| {
+        Error::InvalidArgument(format!(
+            "Unknown category '{category}'. Valid values: {}. Register it with `sc category add {category}`",
+            categories.names().join(", ")
+        ))
+    })
+}
+
+/// Register or update a category.
+pub(crate) fn save(conn: &Connection, project_path: &str, category: &Category, now: i64) -> Result<()> {
+    category.validate()?;
+    conn.execute(
+        "INSERT INTO item_categories
+           (project_path, name, description, color, default_priority, in_prime, prime_weight, encrypted, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+         ON CONFLICT(project_path, name) DO UPDATE SET
+           description = excluded.description,
+           color = excluded.color,
+           default_priority = excluded.default_priority,
+           in_prime = excluded.in_prime,
+           prime_weight = excluded.prime_weight,
+           encrypted = excluded.encrypted,
+           updated_at = excluded.updated_at",
+        rusqlite::params![
+            project_path,
+            category.name,
+            category.description,
+            category.color,
+            category.default_priority,
+            category.in_prime,
+            category.prime_weight,
+            category.encrypted,
+            now
+        ],
+    )?;
+    Ok(())
+}
+
+/// IDs of the project's items in `category`.
+pub(crate) fn item_ids(conn: &Connection, project_path: &str, category: &ItemCategory) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id FROM context_items WHERE category = ?2 AND session_id IN ({PROJECT_SESSIONS})"
+    ))?;
+    let ids = stmt
+        .query_map(rusqlite::params![project_path, category], |row| row.get(0))?
+        .collect::<std::result::Result<_, _>>()?;
+    Ok(ids)
+}
+
+/// Store the values of the project's items in `category` encrypted, or
+/// plain again, returning the IDs of the items changed.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if an encrypted value can't be read
+/// with this user's key, or an error if there is no key to encrypt with.
+pub(crate) fn set_encrypted(
+    conn: &Connection,
+    project_path: &str,
+    category: &ItemCategory,
+    encrypt: bool,
+    compression_threshold: usize,
+) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, value FROM context_items
+         WHERE category = ?2 AND value_encrypted != ?3 AND session_id IN ({PROJECT_SESSIONS})"
+    ))?;
+    let items: Vec<(String, String)> = stmt
+        .query_map(rusqlite::params![project_path, category, encrypt], |row| {
+            Ok((row.get(0)?, read_value(row, 1)?))
+        })?
+        .collect::<std::result::Result<_, _>>()?;
+
+    for (id, value) in &items {
+        let (stored, compressed, encrypted) = store_value(conn, value, encrypt, compression_threshold)?;
+        conn.execute(
+            "UPDATE context_items SET value = ?2, value_compressed = ?3, value_encrypted = ?4 WHERE id = ?1",
+            rusqlite::params![id, stored, compressed, encrypted],
+        )?;
+        skip_embeddings(conn, id, encrypted)?;
+    }
+    Ok(items.into_iter().map(|(id, _)| id).collect())
+}
+
+/// Rename a registered category and move the project's items to the new
+/// name, returning the IDs of the items moved.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if `old` is built in or not
+/// registered, or `new` is built in or taken.
+pub(crate) fn rename(
+    conn: &Connection,
+    project_path: &str,
+    old: &ItemCategory,
+    new: &ItemCategory,
+    now: i64,
+) -> Result<Vec<String>> {
+    if old.is_built_in() || new.is_built_in() {
+        return Err(Error::InvalidArgument(format!(
+            "Built-in categories can't be renamed ({})",
+            ItemCategory::NAMES.join(", ")
+        )));
+    }
+    let categories = list(conn, project_path)?;
+    if categories.get(old).is_none() {
+        return Err(Error::InvalidArgument(format!("No category '{old}' in this project")));
+    }
+    if categories.get(new).is_some() {
+        return Err(Error::InvalidArgument(format!("Category '{new}' already exists")));
+    }
+
+    conn.execute(
+        "UPDATE item_categories SET name = ?3, updated_at = ?4 WHERE project_path = ?1 AND name = ?2",
+        rusqlite::params![project_path, old, new, now],
+    )?;
+    let ids = item_ids(conn, project_path, old)?;
+    conn.execute(
+        &format!(
+            "UPDATE context_items SET category = ?3, updated_at = ?4
+             WHERE category = ?2 AND session_id IN ({PROJECT_SESSIONS})"
+        ),
+        rusqlite::params![project_path, old, new, now],
+    )?;
+    Ok(ids)
+}
+
+/// Remove a category's registration. A built-in category goes back to its
+/// default settings.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if the project still has items in a
+/// registered category.
+pub(crate) fn delete(conn: &Connection, project_path: &str, name: &ItemCategory) -> Result<bool> {
+    if !name.is_built_in() {
+        let used = item_ids(conn, project_path, name)?.len();
+        if used > 0 {
+            return Err(Error::InvalidArgument(format!(
+                "{used} item(s) are in category '{name}'; rename it or recategorize them first"
+            )));
+        }
+    }
+    let removed = conn.execute(
+        "DELETE FROM item_categories WHERE project_path = ?1 AND name = ?2",
+        rusqlite::params![project_path, name],
+    )?;
+    Ok(removed > 0)
+}
@@ -0,0 +1,116 @@
+//! Compressed storage for large context item values.
+//!
+//! Agents sometimes save whole diffs or build logs as context items, and a
+//! few of those can dwarf everything else in the database. Values longer
+//! than the threshold (`value_compression_threshold` in the config,
+//! [`DEFAULT_THRESHOLD`] bytes by default) are zstd-compressed and stored
+//! as a BLOB in `context_items.value`, with `value_compressed` set. Plain
+//! values stay TEXT, so a BLOB is itself the sign that a value needs
+//! decompressing; every read goes through [`read_value`] and callers only
+//! ever see the original string. `size` always holds the uncompressed
+//! length. Values of encrypted categories are BLOBs too, told apart by
+//! their header and never compressed (see [`crate::storage::encryption`]).
+
+use crate::error::Result;
+use crate::json_schema::json_schema;
+use rusqlite::types::{FromSqlError, Value, ValueRef};
+use rusqlite::{Connection, Row};
+use serde::Serialize;
+
+/// Values longer than this many bytes are compressed unless configured
+/// otherwise.
+pub const DEFAULT_THRESHOLD: usize = 8192;
+
+/// zstd level for values. Saves are interactive, so favour speed.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Compression totals for `sc db stats`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompressionStats {
+    pub items: i64,
+    pub compressed_items: i64,
+    /// Bytes of the compressed values before compression.
+    pub original_bytes: i64,
+    /// Bytes the compressed values take in the database.
+    pub stored_bytes: i64,
+}
+
+json_schema!(CompressionStats { items: i64, compressed_items: i64, original_bytes: i64, stored_bytes: i64 });
+
+impl CompressionStats {
+    /// Bytes saved by compression.
+    #[must_use]
+    pub fn saved_bytes(&self) -> i64 {
+        self.original_bytes - self.stored_bytes
+    }
+}
+
+/// The column value to store for `value`, and whether it was compressed.
+///
+/// Values are only compressed when longer than `threshold` (0 disables
+/// compression) and when compressing actually makes them smaller.
+pub(crate) fn encode_value(value: &str, threshold: usize) -> (Value, bool) {
+    if threshold > 0 && value.len() > threshold {
+        if let Ok(compressed) = zstd::encode_all(value.as_bytes(), COMPRESSION_LEVEL) {
+            if compressed.len() < value.len() {
+                return (Value::Blob(compressed), true);
+            }
+        }
+    }
+    (Value::Text(value.to_string()), false)
+}
+
+/// Read a context item value, decompressing or decrypting it if it was
+/// stored that way.
+pub(crate) fn read_value(row: &Row<'_>, idx: usize) -> rusqlite::Result<String> {
+    match row.get_ref(idx)? {
+        ValueRef::Blob(encrypted) if crate::storage::encryption::is_encrypted(encrypted) => {
+            Ok(crate::storage::encryption::decrypt(encrypted))
+        }
+        ValueRef::Blob(compressed) => zstd::decode_all(compressed)
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+            .and_then(|bytes| String::from_utf8(bytes).map_err(|e| FromSqlError::Other(Box::new(e))))
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Blob, Box::new(e))),
+        _ => row.get(idx),
+    }
+}
+
+/// Totals over all context items.
+pub(crate) fn stats(conn: &Connection) -> Result<CompressionStats> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*),
+                COALESCE(SUM(value_compressed), 0),
+                COALESCE(SUM(CASE WHEN value_compressed = 1 THEN size END), 0),
+                COALESCE(SUM(CASE WHEN value_compressed = 1 THEN length(value) END), 0)
+         FROM context_items",
+        [],
+        |row| {
+            Ok(CompressionStats {
+                items: row.get(0)?,
+                compressed_items: row.get(1)?,
+                original_bytes: row.get(2)?,
+                stored_bytes: row.get(3)?,
+            })
+        },
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_value() {
+        let log = "error: build failed\n".repeat(1000);
+        let (stored, compressed) = encode_value(&log, 100);
+        assert!(compressed);
+        let Value::Blob(bytes) = stored else { panic!("expected a blob") };
+        assert!(bytes.len() < log.len() / 10);
+
+        // Short values, disabled compression and incompressible data stay text
+        assert!(!encode_value("short", 100).1);
+        assert!(!encode_value(&log, 0).1);
+        let distinct: String = (b'!'..=b'z').map(char::from).collect();
+        assert!(!encode_value(&distinct, 10).1);
+    }
+}
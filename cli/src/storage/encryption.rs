@@ -0,0 +1,533 @@
+//! Encrypted storage for the values of chosen categories.
+//!
+//! A category registered with `sc category add <name> --encrypt on` has
+//! its items' values stored as AES-256-GCM BLOBs in `context_items.value`,
+//! with `value_encrypted` set. Keys, categories and tags stay plain, so
+//! items can still be listed and found by key.
+//!
+//! The key never enters the database. It lives in a [`KeyStore`], by
+//! default the OS keychain of the user who first encrypted something, and
+//! is created there on first use. It is never created while the database
+//! already holds encrypted values, and a keychain that is locked or denies
+//! access is an error, not an empty one. For that user every read goes
+//! through [`crate::storage::compression::read_value`] and returns the plaintext as
+//! before. Anyone else (another account, another machine after a sync) gets
+//! [`REDACTED`] in its place, and saving that back is refused so the value
+//! isn't lost.
+//!
+//! Keychains, in order:
+//!
+//! - `SAVECONTEXT_ENCRYPTION_KEY`: a base64 32-byte key, for CI and headless
+//!   machines, or to share a key between users
+//! - macOS: the login keychain, through `security`
+//! - Linux: the Secret Service (GNOME Keyring and the like), through
+//!   `secret-tool`
+//! - Windows: a file under `~/.savecontext` protected with the user's DPAPI
+//!   key, through PowerShell
+//!
+//! Code built on the library can plug in its own with [`use_key_store`].
+
+use crate::error::{Error, Result};
+use crate::storage::compression::encode_value;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use rusqlite::{Connection, OptionalExtension};
+use rusqlite::types::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// What a reader without the key gets instead of the value.
+pub const REDACTED: &str = "[encrypted: no key for this user]";
+
+/// Start of every encrypted value: a format tag that can't begin a zstd
+/// frame, so [`crate::storage::compression::read_value`] can tell the two
+/// BLOBs apart.
+const MAGIC: &[u8] = b"SCENC\x01";
+
+/// Encrypted values as text, in sync exports: this prefix, then the BLOB in
+/// base64.
+pub(crate) const SEALED_PREFIX: &str = "sc-encrypted:v1:";
+
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Environment variable holding a key, ahead of the OS keychain.
+pub const KEY_ENV: &str = "SAVECONTEXT_ENCRYPTION_KEY";
+
+const KEYCHAIN_SERVICE: &str = "savecontext";
+const KEYCHAIN_ACCOUNT: &str = "category-encryption";
+
+/// Where the encryption key is kept.
+pub trait KeyStore: Send {
+    /// Where that is, for messages.
+    fn name(&self) -> String;
+
+    /// The key, base64-encoded, or `None` if there isn't one yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store can't be reached or won't answer
+    /// (locked, or access denied). That must not read as "no key": a
+    /// fresh one would replace the key existing values were encrypted
+    /// with.
+    fn load(&self) -> Result<Option<String>>;
+
+    /// Keep `key` (base64) for later runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store can't be reached or written.
+    fn store(&self, key: &str) -> Result<()>;
+}
+
+/// The key store in use; `None` until first needed.
+static STORE: Mutex<Option<Box<dyn KeyStore>>> = Mutex::new(None);
+
+/// The key, once looked up.
+static KEY: Mutex<CachedKey> = Mutex::new(CachedKey::Unknown);
+
+enum CachedKey {
+    Unknown,
+    Missing,
+    Found(Vec<u8>),
+}
+
+/// Keep the key in `store` from now on, instead of the environment or OS
+/// keychain.
+pub fn use_key_store(store: Box<dyn KeyStore>) {
+    *STORE.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = Some(store);
+    *KEY.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = CachedKey::Unknown;
+}
+
+/// The column value to store for an item's `value`, whether it was
+/// compressed, and whether it was encrypted: encrypted if `encrypt`, else
+/// as [`encode_value`] stores it.
+///
+/// # Errors
+///
+/// Returns an error if `value` is [`REDACTED`] or can't be encrypted.
+pub(crate) fn store_value(
+    conn: &Connection,
+    value: &str,
+    encrypt: bool,
+    compression_threshold: usize,
+) -> Result<(Value, bool, bool)> {
+    check_not_redacted(value)?;
+    if encrypt {
+        return Ok((Value::Blob(encrypt_value(conn, value)?), false, true));
+    }
+    let (stored, compressed) = encode_value(value, compression_threshold);
+    Ok((stored, compressed, false))
+}
+
+/// Keep an encrypted item out of semantic search: its embeddings would hold
+/// its text in the clear. An item stored plain again goes back in the
+/// embedding queue.
+pub(crate) fn skip_embeddings(conn: &Connection, item_id: &str, encrypted: bool) -> Result<()> {
+    if encrypted {
+        conn.execute("DELETE FROM embedding_chunks WHERE entity_type = 'context_item' AND item_id = ?1", [item_id])?;
+        conn.execute("DELETE FROM embedding_chunks_fast WHERE item_id = ?1", [item_id])?;
+        conn.execute(
+            "UPDATE context_items SET embedding_status = 'skipped', fast_embedding_status = 'none',
+               chunk_count = 0, embedded_at = NULL, fast_embedded_at = NULL
+             WHERE id = ?1",
+            [item_id],
+        )?;
+    } else {
+        conn.execute(
+            "UPDATE context_items SET embedding_status = 'pending' WHERE id = ?1 AND embedding_status = 'skipped'",
+            [item_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Whether a stored BLOB is an encrypted value.
+pub(crate) fn is_encrypted(stored: &[u8]) -> bool {
+    stored.starts_with(MAGIC)
+}
+
+/// Encrypt `value`, creating the key if this user has none yet.
+fn encrypt_value(conn: &Connection, value: &str) -> Result<Vec<u8>> {
+    let key = writing_key(conn)?;
+    seal(&key, value)
+}
+
+/// The plaintext of an encrypted value, or [`REDACTED`] if this user's key
+/// doesn't open it.
+pub(crate) fn decrypt(stored: &[u8]) -> String {
+    reading_key()
+        .and_then(|key| open(&key, stored))
+        .unwrap_or_else(|| REDACTED.to_string())
+}
+
+/// Refuse to save [`REDACTED`] over the value it stands in for.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if `value` is [`REDACTED`].
+pub(crate) fn check_not_redacted(value: &str) -> Result<()> {
+    if value == REDACTED {
+        return Err(Error::InvalidArgument(format!(
+            "This value is encrypted with a key this user doesn't have; it can't be saved back. \
+             Set {KEY_ENV} to the owner's key to read it"
+        )));
+    }
+    Ok(())
+}
+
+/// An encrypted value as text, for sync exports.
+pub(crate) fn sealed_text(stored: &[u8]) -> String {
+    format!("{SEALED_PREFIX}{}", STANDARD.encode(stored))
+}
+
+/// The encrypted value a sync export's text stands for, if it is one.
+pub(crate) fn unseal_text(text: &str) -> Option<Vec<u8>> {
+    let stored = STANDARD.decode(text.strip_prefix(SEALED_PREFIX)?).ok()?;
+    is_encrypted(&stored).then_some(stored)
+}
+
+fn seal(key: &[u8], value: &str) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, value.as_bytes())
+        .map_err(|_| Error::Other("Failed to encrypt the value".to_string()))?;
+    Ok([MAGIC, nonce.as_slice(), &ciphertext].concat())
+}
+
+fn open(key: &[u8], stored: &[u8]) -> Option<String> {
+    let body = stored.strip_prefix(MAGIC)?;
+    if body.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+    String::from_utf8(plaintext).ok()
+}
+
+/// The key for reading, looked up once per process. A store that can't be
+/// reached counts as having none.
+fn reading_key() -> Option<Vec<u8>> {
+    let mut cached = KEY.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    if matches!(*cached, CachedKey::Unknown) {
+        let key = with_store(|store| store.load())
+            .inspect_err(|e| tracing::warn!(error = %e, "Could not read the encryption key"))
+            .ok()
+            .flatten()
+            .and_then(|key| decode_key(&key).ok());
+        *cached = key.map_or(CachedKey::Missing, CachedKey::Found);
+    }
+    match &*cached {
+        CachedKey::Found(key) => Some(key.clone()),
+        CachedKey::Unknown | CachedKey::Missing => None,
+    }
+}
+
+/// The key for writing, created and stored if there is none yet.
+fn writing_key(conn: &Connection) -> Result<Vec<u8>> {
+    let mut cached = KEY.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let CachedKey::Found(key) = &*cached {
+        return Ok(key.clone());
+    }
+    let key = with_store(|store| load_or_create_key(store, conn))?;
+    *cached = CachedKey::Found(key.clone());
+    Ok(key)
+}
+
+/// The key in `store`, or a new one stored there. No key is created while
+/// the database holds encrypted values: they were encrypted with a key
+/// this store doesn't have, and replacing it would lose them for good.
+fn load_or_create_key(store: &dyn KeyStore, conn: &Connection) -> Result<Vec<u8>> {
+    if let Some(stored) = store.load()? {
+        return decode_key(&stored);
+    }
+    let encrypted: Option<i64> = conn
+        .query_row("SELECT 1 FROM context_items WHERE value_encrypted = 1 LIMIT 1", [], |row| row.get(0))
+        .optional()?;
+    if encrypted.is_some() {
+        return Err(Error::Config(format!(
+            "{} has no encryption key, but this database holds values encrypted with one. \
+             Restore that key there, or set {KEY_ENV} to it; a new key would leave them unreadable",
+            store.name()
+        )));
+    }
+    let key = Aes256Gcm::generate_key(OsRng).to_vec();
+    store.store(&STANDARD.encode(&key))?;
+    Ok(key)
+}
+
+fn decode_key(encoded: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .filter(|key| key.len() == KEY_LEN)
+        .ok_or_else(|| Error::Config(format!("The encryption key must be {KEY_LEN} bytes, base64-encoded")))
+}
+
+fn with_store<T>(f: impl FnOnce(&dyn KeyStore) -> Result<T>) -> Result<T> {
+    let mut store = STORE.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    f(store.get_or_insert_with(default_store).as_ref())
+}
+
+#[cfg(not(test))]
+fn default_store() -> Box<dyn KeyStore> {
+    if std::env::var_os(KEY_ENV).is_some() {
+        Box::new(EnvKey)
+    } else {
+        Box::new(Keychain)
+    }
+}
+
+/// Unit tests never touch the real keychain.
+#[cfg(test)]
+fn default_store() -> Box<dyn KeyStore> {
+    struct Fixed;
+    impl KeyStore for Fixed {
+        fn name(&self) -> String {
+            "the test key".to_string()
+        }
+        fn load(&self) -> Result<Option<String>> {
+            Ok(Some(STANDARD.encode([7u8; KEY_LEN])))
+        }
+        fn store(&self, _key: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+    Box::new(Fixed)
+}
+
+/// The key in [`KEY_ENV`].
+pub struct EnvKey;
+
+impl KeyStore for EnvKey {
+    fn name(&self) -> String {
+        KEY_ENV.to_string()
+    }
+
+    fn load(&self) -> Result<Option<String>> {
+        Ok(std::env::var(KEY_ENV).ok().filter(|key| !key.trim().is_empty()))
+    }
+
+    fn store(&self, _key: &str) -> Result<()> {
+        Err(Error::Config(format!("{KEY_ENV} is set but empty; set it to a base64 32-byte key")))
+    }
+}
+
+/// The OS keychain, through the platform's command-line tool.
+pub struct Keychain;
+
+impl KeyStore for Keychain {
+    fn name(&self) -> String {
+        if cfg!(target_os = "macos") {
+            "the macOS keychain".to_string()
+        } else if cfg!(windows) {
+            "Windows data protection".to_string()
+        } else {
+            "the Secret Service keyring".to_string()
+        }
+    }
+
+    fn load(&self) -> Result<Option<String>> {
+        let output = if cfg!(target_os = "macos") {
+            run(&["security", "find-generic-password", "-s", KEYCHAIN_SERVICE, "-a", KEYCHAIN_ACCOUNT, "-w"], None)?
+        } else if cfg!(windows) {
+            let Some(path) = dpapi_path() else {
+                return Ok(None);
+            };
+            if !path.exists() {
+                return Ok(None);
+            }
+            let script = format!(
+                "Add-Type -AssemblyName System.Security; \
+                 $b = [IO.File]::ReadAllBytes('{}'); \
+                 [Convert]::ToBase64String([Security.Cryptography.ProtectedData]::Unprotect($b, $null, 'CurrentUser'))",
+                path.display()
+            );
+            run(&["powershell", "-NoProfile", "-Command", &script], None)?
+        } else {
+            run(&["secret-tool", "lookup", "service", KEYCHAIN_SERVICE, "account", KEYCHAIN_ACCOUNT], None)?
+        };
+        match output {
+            Ok(key) => Ok(Some(key)),
+            Err(failure) if failure.is_not_found() => Ok(None),
+            Err(failure) => Err(Error::Config(format!(
+                "Could not read the encryption key from {} ({failure}); unlock it, or set {KEY_ENV}",
+                self.name()
+            ))),
+        }
+    }
+
+    fn store(&self, key: &str) -> Result<()> {
+        let stored = if cfg!(target_os = "macos") {
+            // `security` only takes the secret as an argument
+            run(
+                &["security", "add-generic-password", "-U", "-s", KEYCHAIN_SERVICE, "-a", KEYCHAIN_ACCOUNT, "-w", key],
+                None,
+            )
+        } else if cfg!(windows) {
+            let path = dpapi_path().ok_or_else(|| Error::Config("No home directory for the key file".to_string()))?;
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let script = format!(
+                "Add-Type -AssemblyName System.Security; \
+                 $k = [Convert]::FromBase64String([Console]::In.ReadLine()); \
+                 [IO.File]::WriteAllBytes('{}', [Security.Cryptography.ProtectedData]::Protect($k, $null, 'CurrentUser'))",
+                path.display()
+            );
+            run(&["powershell", "-NoProfile", "-Command", &script], Some(key))
+        } else {
+            let label = "SaveContext category encryption key";
+            run(
+                &["secret-tool", "store", "--label", label, "service", KEYCHAIN_SERVICE, "account", KEYCHAIN_ACCOUNT],
+                Some(key),
+            )
+        }?;
+        stored
+            .map(|_| ())
+            .map_err(|failure| Error::Config(format!("Could not store the encryption key in {} ({failure})", self.name())))
+    }
+}
+
+fn dpapi_path() -> Option<std::path::PathBuf> {
+    crate::config::global_savecontext_dir().map(|dir| dir.join("category-key.dpapi"))
+}
+
+/// A keychain tool that exited with an error.
+#[derive(Debug)]
+struct ToolFailure {
+    code: Option<i32>,
+    stderr: String,
+}
+
+impl ToolFailure {
+    /// Whether the tool only said the entry doesn't exist: `security`
+    /// exits with 44 (`errSecItemNotFound`), `secret-tool lookup` with 1
+    /// and nothing on stderr. A locked keyring, a missing D-Bus session or
+    /// denied access all fail some other way. On Windows a missing key is
+    /// a missing file, found before PowerShell runs.
+    fn is_not_found(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.code == Some(44)
+        } else if cfg!(windows) {
+            false
+        } else {
+            self.code == Some(1) && self.stderr.is_empty()
+        }
+    }
+}
+
+impl std::fmt::Display for ToolFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.code, self.stderr.is_empty()) {
+            (Some(code), true) => write!(f, "exit code {code}"),
+            (None, true) => write!(f, "killed by a signal"),
+            (_, false) => write!(f, "{}", self.stderr),
+        }
+    }
+}
+
+/// Run a keychain tool, feeding it `input`: its output, or how it failed.
+///
+/// # Errors
+///
+/// Returns an error if the tool can't be started.
+fn run(argv: &[&str], input: Option<&str>) -> Result<std::result::Result<String, ToolFailure>> {
+    let mut child = Command::new(argv[0])
+        .args(&argv[1..])
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            Error::Config(format!(
+                "Could not run {} to reach the keychain ({e}); install it or set {KEY_ENV}",
+                argv[0]
+            ))
+        })?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    Ok(if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(ToolFailure { code: output.status.code(), stderr: String::from_utf8_lossy(&output.stderr).trim().to_string() })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = [1u8; KEY_LEN];
+        let stored = seal(&key, "db password is in 1Password").unwrap();
+        assert!(is_encrypted(&stored));
+        assert!(!String::from_utf8_lossy(&stored).contains("password"));
+        assert_eq!(open(&key, &stored).as_deref(), Some("db password is in 1Password"));
+        // Another user's key, or a damaged value, opens nothing
+        assert_eq!(open(&[2u8; KEY_LEN], &stored), None);
+        assert_eq!(open(&key, &stored[..stored.len() - 1]), None);
+
+        assert_eq!(unseal_text(&sealed_text(&stored)), Some(stored));
+        assert_eq!(unseal_text("sc-encrypted:v1:bm90IGVuY3J5cHRlZA=="), None);
+        assert!(check_not_redacted(REDACTED).is_err());
+    }
+
+    /// A store that fails to answer, or has no key, and counts writes.
+    struct Unreachable {
+        answers: bool,
+        stored: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl KeyStore for Unreachable {
+        fn name(&self) -> String {
+            "the locked keyring".to_string()
+        }
+        fn load(&self) -> Result<Option<String>> {
+            if self.answers { Ok(None) } else { Err(Error::Config("keyring is locked".to_string())) }
+        }
+        fn store(&self, _key: &str) -> Result<()> {
+            self.stored.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_no_new_key_over_existing_values() {
+        use crate::storage::SqliteStorage;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let storage = SqliteStorage::open_memory().unwrap();
+        let conn = storage.conn();
+        let stored = std::sync::Arc::new(AtomicUsize::new(0));
+
+        // A keychain that won't answer is not one without a key
+        let locked = Unreachable { answers: false, stored: stored.clone() };
+        assert!(load_or_create_key(&locked, conn).is_err());
+        assert_eq!(stored.load(Ordering::SeqCst), 0);
+
+        conn.execute_batch(
+            "INSERT INTO sessions (id, name, created_at, updated_at) VALUES ('sess_1', 'S', 0, 0);
+             INSERT INTO context_items (id, session_id, key, value, value_encrypted, created_at, updated_at)
+             VALUES ('item_1', 'sess_1', 'k', X'00', 1, 0, 0);",
+        )
+        .unwrap();
+        let empty = Unreachable { answers: true, stored: stored.clone() };
+        let message = load_or_create_key(&empty, conn).unwrap_err().to_string();
+        assert!(message.contains(KEY_ENV), "{message}");
+        assert_eq!(stored.load(Ordering::SeqCst), 0);
+
+        // With nothing encrypted yet, the first key is created
+        conn.execute("DELETE FROM context_items", []).unwrap();
+        assert_eq!(load_or_create_key(&empty, conn).unwrap().len(), KEY_LEN);
+        assert_eq!(stored.load(Ordering::SeqCst), 1);
+    }
+}
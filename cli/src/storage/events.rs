@@ -1,8 +1,24 @@
 //! Audit event storage and retrieval.
 //!
 //! Events track all mutations in the database for debugging and history.
+//! Left alone the table grows without bound, so a retention policy (see
+//! `sc events prune`) can drop events older than a cutoff or beyond the
+//! newest N per entity. Creates and deletes are always kept, and so are
+//! issue comments and mentions, which are stored as events.
+//!
+//! With `sync_events` on, a project's events also travel through the JSONL
+//! sync (`events.jsonl`), and [`import_events`] adds those another machine
+//! recorded.
 
+use crate::json_schema::json_schema;
 use rusqlite::{Connection, Result};
+use serde::Serialize;
+
+/// SQL condition matching events no retention policy removes; the SQL form
+/// of [`is_protected`].
+const PROTECTED_EVENTS: &str = "event_type LIKE '%\\_created' ESCAPE '\\'
+     OR event_type LIKE '%\\_deleted' ESCAPE '\\'
+     OR event_type IN ('issue_commented', 'issue_mentioned', 'issue_work_logged')";
 
 /// Event types for audit logging.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,11 +31,15 @@ pub enum EventType {
     SessionDeleted,
     SessionPathAdded,
     SessionPathRemoved,
+    SessionRestored,
+    SessionAutoResolved,
 
     // Context item events
     ItemCreated,
     ItemUpdated,
     ItemDeleted,
+    ItemRestored,
+    ItemReviewed,
 
     // Issue events
     IssueCreated,
@@ -27,7 +47,12 @@ pub enum EventType {
     IssueClosed,
     IssueClaimed,
     IssueReleased,
+    IssueClaimExpired,
     IssueDeleted,
+    IssueRestored,
+    IssueCommented,
+    IssueMentioned,
+    IssueWorkLogged,
 
     // Checkpoint events
     CheckpointCreated,
@@ -67,15 +92,24 @@ impl EventType {
             Self::SessionDeleted => "session_deleted",
             Self::SessionPathAdded => "session_path_added",
             Self::SessionPathRemoved => "session_path_removed",
+            Self::SessionRestored => "session_restored",
+            Self::SessionAutoResolved => "session_auto_resolved",
             Self::ItemCreated => "item_created",
             Self::ItemUpdated => "item_updated",
             Self::ItemDeleted => "item_deleted",
+            Self::ItemRestored => "item_restored",
+            Self::ItemReviewed => "item_reviewed",
             Self::IssueCreated => "issue_created",
             Self::IssueUpdated => "issue_updated",
             Self::IssueClosed => "issue_closed",
             Self::IssueClaimed => "issue_claimed",
             Self::IssueReleased => "issue_released",
+            Self::IssueClaimExpired => "issue_claim_expired",
             Self::IssueDeleted => "issue_deleted",
+            Self::IssueRestored => "issue_restored",
+            Self::IssueCommented => "issue_commented",
+            Self::IssueMentioned => "issue_mentioned",
+            Self::IssueWorkLogged => "issue_work_logged",
             Self::CheckpointCreated => "checkpoint_created",
             Self::CheckpointRestored => "checkpoint_restored",
             Self::CheckpointDeleted => "checkpoint_deleted",
@@ -186,7 +220,7 @@ pub fn get_events(
         "SELECT id, entity_type, entity_id, event_type, actor, old_value, new_value, comment, created_at
          FROM events
          WHERE entity_type = ?1 AND entity_id = ?2
-         ORDER BY created_at DESC
+         ORDER BY created_at DESC, id DESC
          LIMIT ?3",
     )?;
 
@@ -207,6 +241,223 @@ pub fn get_events(
     rows.collect()
 }
 
+/// Which events a retention policy removes.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct RetentionPolicy {
+    /// Remove events created before this time (Unix ms).
+    pub older_than: Option<i64>,
+    /// Remove events beyond the newest this many of each entity.
+    pub max_per_entity: Option<usize>,
+}
+
+json_schema!(RetentionPolicy { older_than: Option<i64>, max_per_entity: Option<usize> });
+
+impl RetentionPolicy {
+    /// Whether the policy removes anything at all.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.older_than.is_none() && self.max_per_entity.is_none()
+    }
+}
+
+/// An event row as stored, for archiving before it is pruned.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredEvent {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub event_type: String,
+    pub actor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub created_at: i64,
+}
+
+json_schema!(StoredEvent {
+    id: i64,
+    entity_type: String,
+    entity_id: String,
+    event_type: String,
+    actor: String,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    comment: Option<String>,
+    created_at: i64,
+});
+
+/// Events `policy` would remove, oldest first, at most `limit` of them.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub fn prunable_events(conn: &Connection, policy: &RetentionPolicy, limit: Option<usize>) -> Result<Vec<StoredEvent>> {
+    let max_per_entity = policy.max_per_entity.map(|n| i64::try_from(n).unwrap_or(i64::MAX));
+    let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, entity_type, entity_id, event_type, actor, old_value, new_value, comment, created_at
+         FROM (
+             SELECT e.*, ROW_NUMBER() OVER (
+                 PARTITION BY entity_type, entity_id ORDER BY created_at DESC, id DESC
+             ) AS newest
+             FROM events e
+             WHERE NOT ({PROTECTED_EVENTS})
+         )
+         WHERE created_at < ?1 OR newest > ?2
+         ORDER BY id
+         LIMIT ?3"
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![policy.older_than, max_per_entity, limit], |row| {
+        Ok(StoredEvent {
+            id: row.get(0)?,
+            entity_type: row.get(1)?,
+            entity_id: row.get(2)?,
+            event_type: row.get(3)?,
+            actor: row.get(4)?,
+            old_value: row.get(5)?,
+            new_value: row.get(6)?,
+            comment: row.get(7)?,
+            created_at: row.get(8)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Delete events by ID, returning how many were removed.
+///
+/// # Errors
+///
+/// Returns an error if the delete fails.
+pub fn delete_events(conn: &Connection, ids: &[i64]) -> Result<usize> {
+    let ids = serde_json::to_string(ids).unwrap_or_else(|_| "[]".to_string());
+    conn.execute("DELETE FROM events WHERE id IN (SELECT value FROM json_each(?1))", [ids])
+}
+
+/// Whether no retention policy ever removes events of this type.
+#[must_use]
+pub fn is_protected(event_type: &str) -> bool {
+    event_type.ends_with("_created")
+        || event_type.ends_with("_deleted")
+        || matches!(event_type, "issue_commented" | "issue_mentioned" | "issue_work_logged")
+}
+
+/// Events about the records of a project, oldest first.
+///
+/// Covers records that still exist and those whose deletion is waiting in
+/// `sync_deletions`; events of records deleted before sync tracking began
+/// can't be placed and are left out.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub fn project_events(conn: &Connection, project_path: &str) -> Result<Vec<StoredEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entity_type, entity_id, event_type, actor, old_value, new_value, comment, created_at
+         FROM events e
+         WHERE (entity_type = 'session' AND entity_id IN (SELECT id FROM sessions WHERE project_path = ?1))
+            OR (entity_type = 'issue' AND entity_id IN (SELECT id FROM issues WHERE project_path = ?1))
+            OR (entity_type = 'context_item' AND entity_id IN (
+                    SELECT ci.id FROM context_items ci JOIN sessions s ON s.id = ci.session_id
+                    WHERE s.project_path = ?1))
+            OR (entity_type = 'checkpoint' AND entity_id IN (
+                    SELECT c.id FROM checkpoints c JOIN sessions s ON s.id = c.session_id
+                    WHERE s.project_path = ?1))
+            OR (entity_type = 'plan' AND entity_id IN (SELECT id FROM plans WHERE project_path = ?1))
+            OR (entity_type = 'memory' AND entity_id IN (SELECT id FROM project_memory WHERE project_path = ?1))
+            OR (entity_type = 'time_entry' AND entity_id IN (SELECT id FROM time_entries WHERE project_path = ?1))
+            OR (entity_type = 'project' AND entity_id IN (SELECT id FROM projects WHERE project_path = ?1))
+            OR EXISTS (
+                SELECT 1 FROM sync_deletions d
+                WHERE d.entity_type = e.entity_type AND d.entity_id = e.entity_id AND d.project_path = ?1)
+         ORDER BY created_at, id",
+    )?;
+    let rows = stmt.query_map([project_path], |row| {
+        Ok(StoredEvent {
+            id: row.get(0)?,
+            entity_type: row.get(1)?,
+            entity_id: row.get(2)?,
+            event_type: row.get(3)?,
+            actor: row.get(4)?,
+            old_value: row.get(5)?,
+            new_value: row.get(6)?,
+            comment: row.get(7)?,
+            created_at: row.get(8)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Events with an ID above `after_id`, oldest first, limited to
+/// `entity_types` unless it is empty.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub fn events_after(conn: &Connection, after_id: i64, entity_types: &[&str]) -> Result<Vec<StoredEvent>> {
+    let types = serde_json::to_string(entity_types).unwrap_or_else(|_| "[]".to_string());
+    let mut stmt = conn.prepare(
+        "SELECT id, entity_type, entity_id, event_type, actor, old_value, new_value, comment, created_at
+         FROM events
+         WHERE id > ?1 AND (json_array_length(?2) = 0 OR entity_type IN (SELECT value FROM json_each(?2)))
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![after_id, types], |row| {
+        Ok(StoredEvent {
+            id: row.get(0)?,
+            entity_type: row.get(1)?,
+            entity_id: row.get(2)?,
+            event_type: row.get(3)?,
+            actor: row.get(4)?,
+            old_value: row.get(5)?,
+            new_value: row.get(6)?,
+            comment: row.get(7)?,
+            created_at: row.get(8)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Insert events recorded elsewhere, skipping any already stored.
+///
+/// An event counts as stored when every field but the local `id` matches,
+/// so importing the same events twice adds nothing. Returns how many were
+/// inserted.
+///
+/// # Errors
+///
+/// Returns an error if an insert fails.
+pub fn import_events(conn: &Connection, events: &[StoredEvent]) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut inserted = 0;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO events (entity_type, entity_id, event_type, actor, old_value, new_value, comment, created_at)
+             SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM events
+                 WHERE entity_type = ?1 AND entity_id = ?2 AND event_type = ?3 AND actor = ?4
+                   AND old_value IS ?5 AND new_value IS ?6 AND comment IS ?7 AND created_at = ?8)",
+        )?;
+        for event in events {
+            inserted += stmt.execute(rusqlite::params![
+                event.entity_type,
+                event.entity_id,
+                event.event_type,
+                event.actor,
+                event.old_value,
+                event.new_value,
+                event.comment,
+                event.created_at,
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(inserted)
+}
+
 fn parse_event_type(s: &str) -> EventType {
     match s {
         "session_created" => EventType::SessionCreated,
@@ -216,15 +467,24 @@ fn parse_event_type(s: &str) -> EventType {
         "session_deleted" => EventType::SessionDeleted,
         "session_path_added" => EventType::SessionPathAdded,
         "session_path_removed" => EventType::SessionPathRemoved,
+        "session_restored" => EventType::SessionRestored,
+        "session_auto_resolved" => EventType::SessionAutoResolved,
         "item_created" => EventType::ItemCreated,
         "item_updated" => EventType::ItemUpdated,
         "item_deleted" => EventType::ItemDeleted,
+        "item_restored" => EventType::ItemRestored,
+        "item_reviewed" => EventType::ItemReviewed,
         "issue_created" => EventType::IssueCreated,
         "issue_updated" => EventType::IssueUpdated,
         "issue_closed" => EventType::IssueClosed,
         "issue_claimed" => EventType::IssueClaimed,
         "issue_released" => EventType::IssueReleased,
+        "issue_claim_expired" => EventType::IssueClaimExpired,
         "issue_deleted" => EventType::IssueDeleted,
+        "issue_restored" => EventType::IssueRestored,
+        "issue_commented" => EventType::IssueCommented,
+        "issue_mentioned" => EventType::IssueMentioned,
+        "issue_work_logged" => EventType::IssueWorkLogged,
         "checkpoint_created" => EventType::CheckpointCreated,
         "checkpoint_restored" => EventType::CheckpointRestored,
         "checkpoint_deleted" => EventType::CheckpointDeleted,
@@ -265,4 +525,36 @@ mod tests {
         assert_eq!(events[0].actor, "test-actor");
         assert_eq!(events[0].comment, Some("Test session created".to_string()));
     }
+
+    #[test]
+    fn test_retention_policy() {
+        let conn = Connection::open_in_memory().unwrap();
+        apply_schema(&conn).unwrap();
+        let at = |event_type, created_at| {
+            let mut event = Event::new("issue", "SC-1", event_type, "actor");
+            event.created_at = created_at;
+            insert_event(&conn, &event).unwrap()
+        };
+        at(EventType::IssueCreated, 100);
+        let old_update = at(EventType::IssueUpdated, 200);
+        at(EventType::IssueCommented, 300);
+        let claim = at(EventType::IssueClaimed, 400);
+        at(EventType::IssueUpdated, 500);
+
+        let by_age = RetentionPolicy { older_than: Some(450), max_per_entity: None };
+        let ids: Vec<i64> = prunable_events(&conn, &by_age, None).unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![old_update, claim], "creates and comments are kept");
+
+        let by_count = RetentionPolicy { older_than: None, max_per_entity: Some(2) };
+        let prunable = prunable_events(&conn, &by_count, None).unwrap();
+        assert_eq!(prunable.len(), 1);
+        assert_eq!(prunable[0].id, old_update);
+        assert_eq!(prunable[0].event_type, "issue_updated");
+
+        assert!(prunable_events(&conn, &RetentionPolicy::default(), None).unwrap().is_empty());
+        assert_eq!(prunable_events(&conn, &by_age, Some(1)).unwrap().len(), 1);
+
+        assert_eq!(delete_events(&conn, &ids).unwrap(), 2);
+        assert_eq!(get_events(&conn, "issue", "SC-1", None).unwrap().len(), 3);
+    }
 }
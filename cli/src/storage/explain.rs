@@ -0,0 +1,156 @@
+//! Query tracing for `--explain`.
+//!
+//! When `--explain` is set, the profile hook every connection opened by
+//! [`SqliteStorage`] gets (see [`timing`]) also records each statement's
+//! SQL and execution time. After the command finishes, the recorded reads are
+//! grouped by SQL text and run through `EXPLAIN QUERY PLAN`, so a slow
+//! `issue list` or semantic search shows which tables are scanned and
+//! which indexes are used.
+//!
+//! [`SqliteStorage`]: super::SqliteStorage
+//! [`timing`]: super::timing
+
+use crate::error::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Statements recorded by the profile hook, in execution order.
+static RECORDED: Mutex<Vec<(String, Duration)>> = Mutex::new(Vec::new());
+
+/// One distinct read statement and how it executed.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryTrace {
+    /// SQL text with whitespace collapsed (parameters stay as `?N`).
+    pub sql: String,
+    /// Number of times the statement ran.
+    pub calls: usize,
+    /// Total execution time across all calls, in microseconds. The profile
+    /// hook only measures to the millisecond on most platforms, so fast
+    /// statements report 0.
+    pub total_us: u64,
+    /// `EXPLAIN QUERY PLAN` output, indented by nesting level.
+    pub plan: Vec<String>,
+    /// Tables read without an index (`SCAN` steps).
+    pub full_scans: Vec<String>,
+}
+
+/// Called by the profile hook when `--explain` is active.
+pub(crate) fn record(sql: &str, elapsed: Duration) {
+    if let Ok(mut recorded) = RECORDED.lock() {
+        recorded.push((sql.to_string(), elapsed));
+    }
+}
+
+/// Take everything recorded so far.
+fn take_recorded() -> Vec<(String, Duration)> {
+    RECORDED
+        .lock()
+        .map(|mut recorded| std::mem::take(&mut *recorded))
+        .unwrap_or_default()
+}
+
+/// Group recorded reads by SQL and attach their query plans.
+///
+/// Writes and schema bookkeeping are skipped. Results are ordered by
+/// total time, slowest first.
+pub(crate) fn build_report(conn: &Connection) -> Result<Vec<QueryTrace>> {
+    let mut traces: Vec<QueryTrace> = Vec::new();
+    for (sql, elapsed) in take_recorded() {
+        let sql = collapse_whitespace(&sql);
+        if !is_read(&sql) {
+            continue;
+        }
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        if let Some(trace) = traces.iter_mut().find(|t| t.sql == sql) {
+            trace.calls += 1;
+            trace.total_us = trace.total_us.saturating_add(micros);
+        } else {
+            traces.push(QueryTrace {
+                sql,
+                calls: 1,
+                total_us: micros,
+                plan: Vec::new(),
+                full_scans: Vec::new(),
+            });
+        }
+    }
+
+    for trace in &mut traces {
+        trace.plan = query_plan(conn, &trace.sql)?;
+        trace.full_scans = trace
+            .plan
+            .iter()
+            .filter_map(|step| step.trim_start().strip_prefix("SCAN "))
+            .map(|rest| rest.split_whitespace().next().unwrap_or(rest).to_string())
+            .collect();
+    }
+
+    // The EXPLAIN statements themselves were profiled too
+    take_recorded();
+
+    traces.sort_by_key(|t| std::cmp::Reverse(t.total_us));
+    Ok(traces)
+}
+
+/// Run `EXPLAIN QUERY PLAN` and return the detail column, indented by depth.
+fn query_plan(conn: &Connection, sql: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+    // Parameters are left unbound (NULL); the plan doesn't depend on them
+    let mut rows = stmt.raw_query();
+
+    let mut depths: Vec<(i64, usize)> = Vec::new();
+    let mut plan = Vec::new();
+    while let Some(row) = rows.next()? {
+        let (id, parent, detail): (i64, i64, String) = (row.get(0)?, row.get(1)?, row.get(3)?);
+        let depth = depths
+            .iter()
+            .find(|(node, _)| *node == parent)
+            .map_or(0, |(_, d)| d + 1);
+        depths.push((id, depth));
+        plan.push(format!("{}{detail}", "  ".repeat(depth)));
+    }
+    Ok(plan)
+}
+
+fn is_read(sql: &str) -> bool {
+    let upper = sql.get(..6).unwrap_or(sql).to_ascii_uppercase();
+    (upper.starts_with("SELECT") || upper.starts_with("WITH"))
+        && !sql.contains("sqlite_master")
+        && !sql.contains("schema_migrations")
+}
+
+pub(crate) fn collapse_whitespace(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_plan_reports_scans_and_indexes() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a TEXT, b TEXT);
+             CREATE INDEX idx_t_a ON t(a);",
+        )
+        .unwrap();
+
+        let indexed = query_plan(&conn, "SELECT * FROM t WHERE a = ?1").unwrap();
+        assert!(indexed.iter().any(|s| s.contains("idx_t_a")));
+
+        let scan = query_plan(&conn, "SELECT * FROM t WHERE b = ?1").unwrap();
+        assert!(scan.iter().any(|s| s.starts_with("SCAN t")));
+    }
+
+    #[test]
+    fn test_is_read_and_collapse() {
+        assert!(is_read("SELECT 1"));
+        assert!(is_read("with x as (select 1) select * from x"));
+        assert!(!is_read("INSERT INTO t VALUES (1)"));
+        assert!(!is_read("SELECT name FROM sqlite_master"));
+        assert_eq!(collapse_whitespace("SELECT *\n   FROM t"), "SELECT * FROM t");
+    }
+}
@@ -0,0 +1,255 @@
+//! Index audit for `sc db indexes`.
+//!
+//! Lists the indexes on every table together with their `sqlite_stat1`
+//! statistics (collected by `ANALYZE`), and suggests new indexes by
+//! matching the commands recorded in the usage-stats tables against the
+//! predicates those commands filter on. A predicate is covered when some
+//! index on its table starts with the same columns.
+
+use crate::error::Result;
+use crate::json_schema::json_schema;
+use crate::storage::CommandUsage;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+/// Columns each frequently used command filters on, as
+/// `(command, table, leading columns)`.
+///
+/// Command names match those recorded by usage stats (`"issue list"`,
+/// `"get"`, ...). Keep the columns in the order an index would need them:
+/// equality predicates first, then the sort column.
+const HOT_PREDICATES: &[(&str, &str, &[&str])] = &[
+    ("get", "context_items", &["session_id", "category"]),
+    ("prime", "context_items", &["session_id", "category"]),
+    ("prime", "issues", &["project_path", "status"]),
+    ("issue list", "issues", &["project_path", "status"]),
+    ("issue ready", "issues", &["project_path", "status"]),
+    ("status", "sessions", &["project_path", "status"]),
+    ("checkpoint list", "checkpoints", &["session_id", "created_at"]),
+    ("memory list", "project_memory", &["project_path", "category"]),
+    ("plan list", "plans", &["project_path", "status"]),
+    ("time list", "time_entries", &["project_path", "period"]),
+];
+
+/// An index and its planner statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexInfo {
+    pub name: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    /// Rows in the index, from `sqlite_stat1` (`None` until `ANALYZE` runs).
+    pub rows: Option<i64>,
+    /// Average rows sharing one full key; lower is more selective.
+    pub rows_per_key: Option<i64>,
+}
+
+json_schema!(IndexInfo {
+    name: String,
+    table: String,
+    columns: Vec<String>,
+    unique: bool,
+    rows: Option<i64>,
+    rows_per_key: Option<i64>,
+});
+
+/// A missing index that recorded usage says would help.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexSuggestion {
+    pub table: String,
+    pub columns: Vec<String>,
+    /// Commands that filter on these columns.
+    pub commands: Vec<String>,
+    /// Recorded invocations of those commands.
+    pub uses: usize,
+    /// Ready-to-run `CREATE INDEX` statement.
+    pub sql: String,
+}
+
+json_schema!(IndexSuggestion { table: String, columns: Vec<String>, commands: Vec<String>, uses: usize, sql: String });
+
+/// List every index on a user table, with statistics when available.
+///
+/// Automatic indexes created for `UNIQUE`/`PRIMARY KEY` constraints are
+/// included since the planner uses them like any other index.
+pub(crate) fn list_indexes(conn: &Connection) -> Result<Vec<IndexInfo>> {
+    let has_stats: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_stat1'",
+            [],
+            |_| Ok(true),
+        )
+        .optional()?
+        .unwrap_or(false);
+
+    let mut stmt = conn.prepare(
+        "SELECT m.name, il.name, il.\"unique\"
+         FROM sqlite_master m, pragma_index_list(m.name) il
+         WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'
+         ORDER BY m.name, il.name",
+    )?;
+    let listed = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, bool>(2)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut indexes = Vec::with_capacity(listed.len());
+    for (table, name, unique) in listed {
+        let mut info = conn.prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")?;
+        let columns = info
+            .query_map([&name], |row| row.get::<_, Option<String>>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .into_iter()
+            // Expression columns have no name
+            .map(|c| c.unwrap_or_else(|| "<expr>".to_string()))
+            .collect();
+
+        let sampled: Option<String> = if has_stats {
+            conn.query_row("SELECT stat FROM sqlite_stat1 WHERE idx = ?1", [&name], |row| {
+                row.get(0)
+            })
+            .optional()?
+        } else {
+            None
+        };
+        let (rows, rows_per_key) = sampled.as_deref().map_or((None, None), parse_stat);
+
+        indexes.push(IndexInfo {
+            name,
+            table,
+            columns,
+            unique,
+            rows,
+            rows_per_key,
+        });
+    }
+    Ok(indexes)
+}
+
+/// Parse a `sqlite_stat1.stat` value (`"rows avg1 avg2 ..."`) into the row
+/// count and the average rows per full key.
+fn parse_stat(stat: &str) -> (Option<i64>, Option<i64>) {
+    let numbers: Vec<i64> = stat
+        .split_whitespace()
+        .map_while(|n| n.parse().ok())
+        .collect();
+    let rows = numbers.first().copied();
+    let per_key = if numbers.len() > 1 { numbers.last().copied() } else { None };
+    (rows, per_key)
+}
+
+/// Suggest indexes for the hot predicates of commands that were actually used.
+///
+/// Predicates already covered by an index prefix are skipped. Suggestions
+/// are ordered by how often the commands behind them ran.
+#[must_use]
+pub fn suggest_indexes(indexes: &[IndexInfo], usage: &[CommandUsage]) -> Vec<IndexSuggestion> {
+    let mut suggestions: Vec<IndexSuggestion> = Vec::new();
+    for used in usage {
+        for &(command, table, columns) in HOT_PREDICATES {
+            if command != used.command || is_covered(indexes, table, columns) {
+                continue;
+            }
+            if let Some(existing) = suggestions
+                .iter_mut()
+                .find(|s| s.table == table && s.columns.iter().eq(columns.iter()))
+            {
+                existing.commands.push(used.command.clone());
+                existing.uses += used.count;
+                continue;
+            }
+            suggestions.push(IndexSuggestion {
+                table: table.to_string(),
+                columns: columns.iter().map(ToString::to_string).collect(),
+                commands: vec![used.command.clone()],
+                uses: used.count,
+                sql: format!(
+                    "CREATE INDEX IF NOT EXISTS idx_{table}_{} ON {table}({});",
+                    columns.join("_"),
+                    columns.join(", ")
+                ),
+            });
+        }
+    }
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.uses));
+    suggestions
+}
+
+/// Whether some index on `table` starts with exactly `columns`.
+fn is_covered(indexes: &[IndexInfo], table: &str, columns: &[&str]) -> bool {
+    indexes.iter().any(|index| {
+        index.table == table
+            && index.columns.len() >= columns.len()
+            && index.columns.iter().zip(columns).all(|(have, want)| have == want)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(command: &str, count: usize) -> CommandUsage {
+        CommandUsage {
+            command: command.to_string(),
+            count,
+            failures: 0,
+            avg_ms: 1,
+            max_ms: 1,
+            avg_sql_ms: 0,
+            slow_queries: 0,
+        }
+    }
+
+    #[test]
+    fn test_list_indexes_with_stats() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE plans (id TEXT PRIMARY KEY, project_path TEXT, status TEXT);
+             CREATE INDEX idx_plans_project_path ON plans(project_path);
+             INSERT INTO plans VALUES ('a', '/p', 'active'), ('b', '/p', 'done');",
+        )
+        .unwrap();
+
+        let before = list_indexes(&conn).unwrap();
+        let plain = before.iter().find(|i| i.name == "idx_plans_project_path").unwrap();
+        assert_eq!(plain.columns, vec!["project_path"]);
+        assert!(plain.rows.is_none());
+        assert!(before.iter().any(|i| i.unique), "primary key autoindex listed");
+
+        conn.execute_batch("ANALYZE").unwrap();
+        let after = list_indexes(&conn).unwrap();
+        let plain = after.iter().find(|i| i.name == "idx_plans_project_path").unwrap();
+        assert_eq!(plain.rows, Some(2));
+        assert_eq!(plain.rows_per_key, Some(2));
+    }
+
+    #[test]
+    fn test_suggestions_skip_covered_and_unused() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE plans (id TEXT, project_path TEXT, status TEXT);
+             CREATE INDEX idx_plans_project_path ON plans(project_path);
+             CREATE TABLE issues (id TEXT, project_path TEXT, status TEXT, priority INTEGER);
+             CREATE INDEX idx_issues_psp ON issues(project_path, status, priority DESC);",
+        )
+        .unwrap();
+        let indexes = list_indexes(&conn).unwrap();
+
+        let suggestions =
+            suggest_indexes(&indexes, &[usage("issue list", 40), usage("plan list", 3)]);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].table, "plans");
+        assert_eq!(suggestions[0].uses, 3);
+        assert!(suggestions[0].sql.contains("ON plans(project_path, status)"));
+
+        assert!(suggest_indexes(&indexes, &[usage("version", 99)]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_stat() {
+        assert_eq!(parse_stat("120 12 1"), (Some(120), Some(1)));
+        assert_eq!(parse_stat("5"), (Some(5), None));
+        assert_eq!(parse_stat(""), (None, None));
+    }
+}
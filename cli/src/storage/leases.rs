@@ -0,0 +1,126 @@
+//! Claim leases: how long a claimed issue stays claimed without word from
+//! the actor holding it.
+//!
+//! With `policy.claim_lease_minutes` set, `sc issue claim`, `next-block`
+//! and `route` take out a lease along with each claim. The holder renews
+//! all of its leases whenever its presence is recorded, i.e. on every `sc`
+//! command it runs in a session and on `sc team heartbeat`. A claim whose
+//! lease ran out is released by [`crate::storage::SqliteStorage::release_expired_claims`].
+//!
+//! Like presence, leases are bookkeeping: writing one records no event and
+//! marks nothing for sync. Only the release is audited.
+
+use crate::error::Result;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+/// The lease on one claimed issue.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaimLease {
+    pub issue_id: String,
+    /// Canonical actor whose activity renews it.
+    pub holder: String,
+    pub renewed_at: i64,
+    pub expires_at: i64,
+}
+
+/// A claim whose lease ran out.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpiredClaim {
+    pub issue_id: String,
+    pub short_id: Option<String>,
+    pub title: String,
+    /// The name on the issue.
+    pub assignee: String,
+    /// Canonical actor that held the lease.
+    pub holder: String,
+    pub expires_at: i64,
+}
+
+/// Lease `issue_id` (full or short ID) to `holder` for `lease_ms` from
+/// `now`, replacing any lease it had.
+pub(crate) fn grant(
+    conn: &Connection,
+    issue_id: &str,
+    holder: &str,
+    lease_ms: i64,
+    now: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO claim_leases (issue_id, assignee, holder, lease_ms, renewed_at, expires_at)
+         SELECT id, assigned_to_agent, ?2, ?3, ?4, ?4 + ?3 FROM issues
+         WHERE (id = ?1 OR short_id = ?1) AND assigned_to_agent IS NOT NULL
+         ON CONFLICT(issue_id) DO UPDATE SET
+             assignee = excluded.assignee,
+             holder = excluded.holder,
+             lease_ms = excluded.lease_ms,
+             renewed_at = excluded.renewed_at,
+             expires_at = excluded.expires_at",
+        rusqlite::params![issue_id, holder, lease_ms, now],
+    )?;
+    Ok(())
+}
+
+/// Extend every lease `holder` has by its full length from `now`.
+pub(crate) fn renew(conn: &Connection, holder: &str, now: i64) -> Result<usize> {
+    Ok(conn.execute(
+        "UPDATE claim_leases SET renewed_at = ?2, expires_at = ?2 + lease_ms WHERE holder = ?1",
+        rusqlite::params![holder, now],
+    )?)
+}
+
+/// Drop the lease on `issue_id` (full or short ID), if any.
+pub(crate) fn drop_lease(conn: &Connection, issue_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM claim_leases WHERE issue_id IN (SELECT id FROM issues WHERE id = ?1 OR short_id = ?1)",
+        [issue_id],
+    )?;
+    Ok(())
+}
+
+/// The lease on a claimed issue, if it still holds the claim it was taken
+/// out for.
+pub(crate) fn load(conn: &Connection, issue_id: &str) -> Result<Option<ClaimLease>> {
+    Ok(conn
+        .query_row(
+            "SELECT l.issue_id, l.holder, l.renewed_at, l.expires_at
+             FROM claim_leases l JOIN issues i ON i.id = l.issue_id
+             WHERE (i.id = ?1 OR i.short_id = ?1) AND i.status = 'in_progress' AND i.assigned_to_agent = l.assignee",
+            [issue_id],
+            |row| {
+                Ok(ClaimLease {
+                    issue_id: row.get(0)?,
+                    holder: row.get(1)?,
+                    renewed_at: row.get(2)?,
+                    expires_at: row.get(3)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+/// Claims in a project whose lease ran out by `now`, oldest first.
+///
+/// A lease only counts while the issue is still in progress and assigned
+/// to the name it was taken out for; a claim handed to someone else some
+/// other way doesn't expire with it.
+pub(crate) fn expired(conn: &Connection, project_path: &str, now: i64) -> Result<Vec<ExpiredClaim>> {
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.short_id, i.title, l.assignee, l.holder, l.expires_at
+         FROM claim_leases l JOIN issues i ON i.id = l.issue_id
+         WHERE i.project_path = ?1 AND i.status = 'in_progress' AND i.assigned_to_agent = l.assignee
+           AND l.expires_at <= ?2
+         ORDER BY l.expires_at",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![project_path, now], |row| {
+        Ok(ExpiredClaim {
+            issue_id: row.get(0)?,
+            short_id: row.get(1)?,
+            title: row.get(2)?,
+            assignee: row.get(3)?,
+            holder: row.get(4)?,
+            expires_at: row.get(5)?,
+        })
+    })?;
+    Ok(rows.collect::<std::result::Result<_, _>>()?)
+}
@@ -80,8 +80,133 @@ const MIGRATIONS: &[Migration] = &[
         version: "015_add_time_entries",
         sql: include_str!("../../migrations/015_add_time_entries.sql"),
     },
+    Migration {
+        version: "016_plan_revisions",
+        sql: include_str!("../../migrations/016_plan_revisions.sql"),
+    },
+    Migration {
+        version: "017_usage_stats",
+        sql: include_str!("../../migrations/017_usage_stats.sql"),
+    },
+    Migration {
+        version: "018_hot_path_indexes",
+        sql: include_str!("../../migrations/018_hot_path_indexes.sql"),
+    },
+    Migration {
+        version: "019_project_aliases",
+        sql: include_str!("../../migrations/019_project_aliases.sql"),
+    },
+    Migration {
+        version: "020_trash",
+        sql: include_str!("../../migrations/020_trash.sql"),
+    },
+    Migration {
+        version: "021_issue_watchers",
+        sql: include_str!("../../migrations/021_issue_watchers.sql"),
+    },
+    Migration {
+        version: "022_session_activity",
+        sql: include_str!("../../migrations/022_session_activity.sql"),
+    },
+    Migration {
+        version: "023_session_usage",
+        sql: include_str!("../../migrations/023_session_usage.sql"),
+    },
+    Migration {
+        version: "024_embedding_cache",
+        sql: include_str!("../../migrations/024_embedding_cache.sql"),
+    },
+    Migration {
+        version: "025_entity_embeddings",
+        sql: include_str!("../../migrations/025_entity_embeddings.sql"),
+    },
+    Migration {
+        version: "026_embedding_archive",
+        sql: include_str!("../../migrations/026_embedding_archive.sql"),
+    },
+    Migration {
+        version: "027_value_compression",
+        sql: include_str!("../../migrations/027_value_compression.sql"),
+    },
+    Migration {
+        version: "028_db_stats_snapshots",
+        sql: include_str!("../../migrations/028_db_stats_snapshots.sql"),
+    },
+    Migration {
+        version: "029_normalize_enum_columns",
+        sql: include_str!("../../migrations/029_normalize_enum_columns.sql"),
+    },
+    Migration {
+        version: "030_actors",
+        sql: include_str!("../../migrations/030_actors.sql"),
+    },
+    Migration {
+        version: "031_issue_short_id_shards",
+        sql: include_str!("../../migrations/031_issue_short_id_shards.sql"),
+    },
+    Migration {
+        version: "032_command_timing",
+        sql: include_str!("../../migrations/032_command_timing.sql"),
+    },
+    Migration {
+        version: "033_item_categories",
+        sql: include_str!("../../migrations/033_item_categories.sql"),
+    },
+    Migration {
+        version: "034_session_worktree",
+        sql: include_str!("../../migrations/034_session_worktree.sql"),
+    },
+    Migration {
+        version: "035_subprojects",
+        sql: include_str!("../../migrations/035_subprojects.sql"),
+    },
+    Migration {
+        version: "036_item_access",
+        sql: include_str!("../../migrations/036_item_access.sql"),
+    },
+    Migration {
+        version: "037_item_trust",
+        sql: include_str!("../../migrations/037_item_trust.sql"),
+    },
+    Migration {
+        version: "038_item_review",
+        sql: include_str!("../../migrations/038_item_review.sql"),
+    },
+    Migration {
+        version: "039_issue_estimates",
+        sql: include_str!("../../migrations/039_issue_estimates.sql"),
+    },
+    Migration {
+        version: "040_claim_leases",
+        sql: include_str!("../../migrations/040_claim_leases.sql"),
+    },
+    Migration {
+        version: "041_item_provenance",
+        sql: include_str!("../../migrations/041_item_provenance.sql"),
+    },
+    Migration {
+        version: "042_reminder_due",
+        sql: include_str!("../../migrations/042_reminder_due.sql"),
+    },
+    Migration {
+        version: "043_memory_access",
+        sql: include_str!("../../migrations/043_memory_access.sql"),
+    },
+    Migration {
+        version: "044_category_encryption",
+        sql: include_str!("../../migrations/044_category_encryption.sql"),
+    },
+    Migration {
+        version: "045_write_contention",
+        sql: include_str!("../../migrations/045_write_contention.sql"),
+    },
 ];
 
+/// Version names of all embedded migrations, in order.
+pub(crate) fn versions() -> impl Iterator<Item = &'static str> {
+    MIGRATIONS.iter().map(|m| m.version)
+}
+
 /// Run all pending migrations on the database.
 ///
 /// Migrations are applied in order. Already-applied migrations (tracked in
@@ -165,7 +290,7 @@ mod tests {
         // This test verifies that all include_str! paths are valid
         // If any path is wrong, compilation will fail
         assert!(!MIGRATIONS.is_empty());
-        assert_eq!(MIGRATIONS.len(), 15);
+        assert_eq!(MIGRATIONS.len(), 45);
     }
 
     #[test]
@@ -180,7 +305,7 @@ mod tests {
                 row.get(0)
             })
             .unwrap();
-        assert_eq!(count, 15);
+        assert_eq!(count, 45);
     }
 
     #[test]
@@ -198,6 +323,71 @@ mod tests {
                 row.get(0)
             })
             .unwrap();
-        assert_eq!(count, 15);
+        assert_eq!(count, 45);
+    }
+
+    /// Table and column names of a database.
+    fn columns(conn: &Connection) -> std::collections::BTreeSet<String> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.name || '.' || p.name FROM sqlite_master m, pragma_table_info(m.name) p
+                 WHERE m.type = 'table'",
+            )
+            .unwrap();
+        stmt.query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn test_server_initialised_database() {
+        // Build the database the way the MCP server does on first start:
+        // schema.sql, its baseline migrations recorded, the later ones run
+        let schema = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../server/src/database/schema.sql"
+        ))
+        .expect("server schema.sql");
+        let baseline = schema
+            .lines()
+            .find_map(|line| line.strip_prefix("-- Baseline: "))
+            .expect("Baseline line")
+            .trim()
+            .to_string();
+        // sqlite-vec is only loaded by the server
+        let vec_start = schema.find("CREATE VIRTUAL TABLE").unwrap();
+        let vec_end = vec_start + schema[vec_start..].find(");").unwrap() + 2;
+        let schema = format!("{}{}", &schema[..vec_start], &schema[vec_end..]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(&schema).unwrap();
+            for migration in MIGRATIONS {
+                if migration.version > baseline.as_str() {
+                    conn.execute_batch(migration.sql)
+                        .unwrap_or_else(|e| panic!("{} failed on the server schema: {e}", migration.version));
+                }
+                conn.execute(
+                    "INSERT INTO schema_migrations (version, applied_at) VALUES (?1, 0)",
+                    [migration.version],
+                )
+                .unwrap();
+            }
+        }
+
+        // The CLI then opens it and works as on a database it created
+        let mut storage = crate::storage::SqliteStorage::open(&path).unwrap();
+        let project = crate::model::Project::new("/srv/app".to_string(), "app".to_string());
+        storage.create_project(&project, "test").unwrap();
+        storage
+            .create_session("sess_1", "Server DB", None, Some("/srv/app"), None, None, "test")
+            .unwrap();
+        assert!(storage.get_project_by_path("/srv/app").unwrap().is_some());
+        assert!(storage.list_project_aliases().unwrap().is_empty());
+
+        let cli = Connection::open_in_memory().unwrap();
+        crate::storage::schema::apply_schema(&cli).unwrap();
+        let missing: Vec<_> = columns(&cli).difference(&columns(storage.conn())).cloned().collect();
+        assert!(missing.is_empty(), "missing from a server-initialised database: {missing:?}");
     }
 }
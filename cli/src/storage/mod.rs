@@ -8,16 +8,51 @@
 //!
 //! # Submodules
 //!
+//! - [`access`] - Read history of context items
+//! - [`categories`] - Per-project context item categories
+//! - [`compression`] - Compressed storage for large context item values
+//! - [`encryption`] - Encrypted storage for the values of chosen categories
 //! - [`events`] - Audit event storage
+//! - [`explain`] - Query tracing for `--explain`
+//! - [`indexes`] - Index audit for `sc db indexes`
+//! - [`presence`] - Which session each agent is in, for `sc team status`
 //! - [`schema`] - Database schema definitions
 //! - [`sqlite`] - Main SQLite storage implementation
+//! - [`stats`] - Storage analytics for `sc db stats`
+//! - [`timing`] - Statement timing and slow-query warnings
+//! - [`trash`] - Deleted items, issues and sessions kept for restore
+//! - [`write_queue`] - Turn-taking between processes writing one database
 
+pub mod access;
+pub mod categories;
+pub mod compression;
+pub mod encryption;
 pub mod events;
+pub mod explain;
+pub mod indexes;
+pub mod leases;
 pub mod migrations;
+pub mod presence;
+pub mod provenance;
 pub mod schema;
 pub mod sqlite;
+pub mod stats;
+pub mod timing;
+pub mod trash;
+pub mod write_queue;
 
+pub use compression::CompressionStats;
+pub use indexes::{IndexInfo, IndexSuggestion};
+pub use leases::{ClaimLease, ExpiredClaim};
+pub use presence::AgentPresence;
+pub use provenance::ItemProvenance;
+pub use stats::{DbStats, LargeItem, LargeSession, TableStats};
+pub use timing::{CommandTiming, SlowQuery};
+pub use trash::{TrashEntry, TrashRestore};
+pub use write_queue::WriteContention;
 pub use sqlite::{
-    BackfillStats, Checkpoint, ContextItem, Issue, Memory, MutationContext, ProjectCounts,
-    SemanticSearchResult, Session, SqliteStorage, TimeEntry,
+    Actor, ActorPurgeStats, BackfillStats, Checkpoint, CommandInvocation, CommandUsage, ContextItem, DbSizeSample, EmbeddingModelUsage,
+    EntityEmbeddings, EntitySearchResult, EntityText, EpicProgress, InboxItem, Issue, IssueLink, IssueStatusChange, IssueWaves,
+    Memory, MutationContext, PathMoveStats, ProjectCounts, ProjectHealth, SemanticSearchResult, Session,
+    SqliteStorage, StaleIssue, TimeEntry, TokenUsage, UsageStats, UsageWindow, WorklogEntry,
 };
@@ -0,0 +1,64 @@
+//! Agent presence: which session each agent is working in, and when it was
+//! last active, behind `sc team status`.
+//!
+//! The MCP server keeps one row per agent in `agent_sessions`, keyed by an
+//! agent ID it derives from the project, branch and client. `sc` writes to
+//! the same table, keyed by the canonical actor, after every command run in
+//! a session and on `sc team heartbeat`. Like reads, presence is
+//! bookkeeping: no audit events, no sync, and it stays in this database.
+
+use crate::error::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Provider recorded for presence written by the CLI.
+pub const CLI_PROVIDER: &str = "sc";
+
+/// An agent's current session and its last heartbeat.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentPresence {
+    pub agent_id: String,
+    pub session_id: String,
+    pub project_path: String,
+    pub git_branch: Option<String>,
+    /// The client that reported it, e.g. an MCP client name or `sc`.
+    pub provider: Option<String>,
+    pub last_active_at: i64,
+}
+
+/// Make `session_id` the agent's current session, active at `now`.
+pub(crate) fn record(conn: &Connection, agent_id: &str, session_id: &str, provider: &str, now: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO agent_sessions (agent_id, session_id, project_path, git_branch, provider, last_active_at)
+         SELECT ?1, id, COALESCE(project_path, ''), branch, ?3, ?4 FROM sessions WHERE id = ?2
+         ON CONFLICT(agent_id) DO UPDATE SET
+             session_id = excluded.session_id,
+             project_path = excluded.project_path,
+             git_branch = excluded.git_branch,
+             provider = excluded.provider,
+             last_active_at = excluded.last_active_at",
+        rusqlite::params![agent_id, session_id, provider, now],
+    )?;
+    Ok(())
+}
+
+/// Agents active in a project since `since`, most recent first.
+pub(crate) fn load(conn: &Connection, project_path: &str, since: i64) -> Result<Vec<AgentPresence>> {
+    let mut stmt = conn.prepare(
+        "SELECT agent_id, session_id, project_path, git_branch, provider, last_active_at
+         FROM agent_sessions
+         WHERE project_path = ?1 AND last_active_at >= ?2
+         ORDER BY last_active_at DESC",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![project_path, since], |row| {
+        Ok(AgentPresence {
+            agent_id: row.get(0)?,
+            session_id: row.get(1)?,
+            project_path: row.get(2)?,
+            git_branch: row.get(3)?,
+            provider: row.get(4)?,
+            last_active_at: row.get(5)?,
+        })
+    })?;
+    Ok(rows.collect::<std::result::Result<_, _>>()?)
+}
@@ -0,0 +1,51 @@
+//! Where in a conversation a context item was saved, behind `sc save
+//! --transcript` and `sc get --provenance`.
+//!
+//! Provenance is bookkeeping like item reads: it is written next to the
+//! item without an audit event or sync dirty flag, since the transcript it
+//! points at only exists on this machine.
+
+use crate::error::Result;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+/// The transcript position an item was saved at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemProvenance {
+    pub transcript_path: String,
+    /// 0-based index among the transcript's user and assistant messages.
+    pub message_index: usize,
+}
+
+/// Record `provenance` for an item, or clear it when the item was saved
+/// outside a conversation.
+pub(crate) fn set(conn: &Connection, item_id: &str, provenance: Option<&ItemProvenance>) -> Result<()> {
+    #[allow(clippy::cast_possible_wrap)]
+    conn.execute(
+        "UPDATE context_items SET transcript_path = ?2, transcript_message = ?3 WHERE id = ?1",
+        rusqlite::params![
+            item_id,
+            provenance.map(|p| p.transcript_path.as_str()),
+            provenance.map(|p| p.message_index as i64)
+        ],
+    )?;
+    Ok(())
+}
+
+/// The provenance of an item, if it has any.
+pub(crate) fn load(conn: &Connection, item_id: &str) -> Result<Option<ItemProvenance>> {
+    let row: Option<(Option<String>, Option<i64>)> = conn
+        .query_row(
+            "SELECT transcript_path, transcript_message FROM context_items WHERE id = ?1",
+            [item_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(match row {
+        Some((Some(transcript_path), Some(index))) => Some(ItemProvenance {
+            transcript_path,
+            message_index: usize::try_from(index).unwrap_or_default(),
+        }),
+        _ => None,
+    })
+}
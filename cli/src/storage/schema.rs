@@ -4,6 +4,8 @@
 //! ported from the TypeScript MCP server's schema.sql.
 
 use rusqlite::{Connection, Result};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Current schema version for migration tracking.
 pub const CURRENT_SCHEMA_VERSION: i32 = 1;
@@ -512,10 +514,27 @@ GROUP BY i.id
 ORDER BY i.priority DESC, i.created_at ASC;
 "#;
 
+/// Whether to skip the schema for databases already opened by this process.
+static KEEP_WARM: AtomicBool = AtomicBool::new(false);
+
+/// Database files whose schema was applied by this process.
+static WARM_PATHS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Apply the schema only on the first open of each database file.
+///
+/// For long-running processes (`sc shell`) that open the same database for
+/// every command. Migrations made by another process while this one runs
+/// are not picked up until it restarts.
+pub fn keep_schema_warm() {
+    KEEP_WARM.store(true, Ordering::Relaxed);
+}
+
 /// Apply the schema to the database.
 ///
-/// This uses `execute_batch` to run the entire DDL script.
-/// It is idempotent because all statements use `IF NOT EXISTS`.
+/// Pragmas are set on every connection. The DDL and migrations only run
+/// when `PRAGMA user_version` doesn't hold this binary's
+/// [`schema_fingerprint`], so opening an up-to-date database costs one
+/// pragma read instead of re-running the whole script.
 ///
 /// # Errors
 ///
@@ -528,7 +547,37 @@ pub fn apply_schema(conn: &Connection) -> Result<()> {
     conn.pragma_update(None, "cache_size", "-64000")?; // 64MB cache
     conn.pragma_update(None, "temp_store", "MEMORY")?;
 
-    // Apply schema
+    let warm_path = conn
+        .path()
+        .filter(|p| !p.is_empty() && KEEP_WARM.load(Ordering::Relaxed))
+        .map(ToString::to_string);
+    if let Some(ref path) = warm_path {
+        if WARM_PATHS.lock().is_ok_and(|paths| paths.contains(path)) {
+            return Ok(());
+        }
+    }
+
+    let fingerprint = schema_fingerprint();
+    let stored: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if stored != fingerprint {
+        apply_schema_full(conn)?;
+    }
+
+    if let (Some(path), Ok(mut paths)) = (warm_path, WARM_PATHS.lock()) {
+        paths.push(path);
+    }
+    Ok(())
+}
+
+/// Run the full DDL script and pending migrations, skipping the fast path.
+///
+/// Idempotent because all statements use `IF NOT EXISTS`. Records the
+/// fingerprint in `user_version` afterwards.
+///
+/// # Errors
+///
+/// Returns an error if the SQL execution fails.
+pub fn apply_schema_full(conn: &Connection) -> Result<()> {
     conn.execute_batch(SCHEMA_SQL)?;
 
     // Run migrations for existing databases
@@ -543,7 +592,24 @@ pub fn apply_schema(conn: &Connection) -> Result<()> {
         ],
     )?;
 
-    Ok(())
+    conn.pragma_update(None, "user_version", schema_fingerprint())
+}
+
+/// Hash of the DDL script and migration list (FNV-1a).
+///
+/// Any change to either gives a new value, so databases last opened by a
+/// different build get the full schema pass once. Always positive, since
+/// `user_version` 0 means "never set".
+#[must_use]
+pub fn schema_fingerprint() -> i32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for part in std::iter::once(SCHEMA_SQL).chain(super::migrations::versions()) {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= u32::from(byte);
+            hash = hash.wrapping_mul(0x0100_0193);
+        }
+    }
+    i32::try_from(hash >> 1).unwrap_or(1).max(1)
 }
 
 /// Check if a column exists in a table.
@@ -596,6 +662,28 @@ mod tests {
         apply_schema(&conn).expect("Second apply failed");
     }
 
+    #[test]
+    fn test_fingerprint_fast_path() {
+        let conn = Connection::open_in_memory().unwrap();
+        apply_schema(&conn).unwrap();
+        let stored: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(stored, schema_fingerprint());
+
+        // An up-to-date database is not touched again
+        conn.execute_batch("DROP VIEW open_issues").unwrap();
+        apply_schema(&conn).unwrap();
+        let views = |conn: &Connection| -> i32 {
+            conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'open_issues'", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(views(&conn), 0);
+
+        // A stale fingerprint triggers the full pass
+        conn.pragma_update(None, "user_version", 7).unwrap();
+        apply_schema(&conn).unwrap();
+        assert_eq!(views(&conn), 1);
+    }
+
     #[test]
     fn test_foreign_keys_enabled() {
         let conn = Connection::open_in_memory().unwrap();
@@ -4,11 +4,19 @@
 //! It follows the MutationContext pattern for transaction discipline and audit logging.
 
 use crate::error::{Error, Result};
-use crate::model::{Plan, PlanStatus, Project};
+use crate::json_schema::json_schema;
+use crate::model::{
+    ActorType, IssueStatus, IssueType, IssueWorkflow, ItemCategory, ItemPriority, ItemSource, MemoryCategory, PermissionsConfig, Plan,
+    PlanRevision, PlanStatus, Project, ReviewStatus, SessionStatus, TimeEntryStatus, TrustLevel,
+};
+use crate::storage::compression::read_value;
+use crate::storage::encryption::store_value;
 use crate::storage::events::{insert_event, Event, EventType};
 use crate::storage::schema::apply_schema;
-use rusqlite::{Connection, OptionalExtension, Transaction};
-use std::collections::HashSet;
+use crate::storage::trash::{self, TrashEntry, TrashRestore};
+use crate::storage::write_queue::WriteTurn;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::time::Duration;
 
@@ -16,6 +24,11 @@ use std::time::Duration;
 #[derive(Debug)]
 pub struct SqliteStorage {
     conn: Connection,
+    /// Context item values longer than this many bytes are stored
+    /// compressed; 0 disables compression.
+    compression_threshold: usize,
+    /// Actor roles checked by [`Self::mutate`]; `None` permits everything.
+    permissions: Option<PermissionsConfig>,
 }
 
 /// Context for a mutation operation, tracking side effects.
@@ -129,6 +142,133 @@ pub struct BackfillStats {
     pub time_entries: usize,
 }
 
+/// Rows moved by `merge_project_paths` / `move_project_path`, per table.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct PathMoveStats {
+    pub sessions: usize,
+    pub session_paths: usize,
+    pub issues: usize,
+    pub issue_paths: usize,
+    pub plans: usize,
+    pub memory: usize,
+    /// Memory keys present under both paths; the older value was dropped.
+    pub memory_conflicts: usize,
+    pub time_entries: usize,
+    pub agent_sessions: usize,
+    pub sync_deletions: usize,
+    /// Export hashes cleared so the next export rewrites the moved records.
+    pub export_hashes: usize,
+    /// Whether the `projects` row for the old path was moved or folded into the target.
+    pub project_updated: bool,
+}
+
+json_schema!(PathMoveStats {
+    sessions: usize,
+    session_paths: usize,
+    issues: usize,
+    issue_paths: usize,
+    plans: usize,
+    memory: usize,
+    memory_conflicts: usize,
+    time_entries: usize,
+    agent_sessions: usize,
+    sync_deletions: usize,
+    export_hashes: usize,
+    project_updated: bool,
+});
+
+impl PathMoveStats {
+    /// Total rows touched, excluding the `projects` row.
+    #[must_use]
+    pub const fn rows(&self) -> usize {
+        self.sessions
+            + self.session_paths
+            + self.issues
+            + self.issue_paths
+            + self.plans
+            + self.memory
+            + self.memory_conflicts
+            + self.time_entries
+            + self.agent_sessions
+            + self.sync_deletions
+            + self.export_hashes
+    }
+}
+
+/// Rows removed or rewritten by `purge_actor`.
+///
+/// The record counts (`sessions` through `time_entries`) are records the
+/// actor created that were deleted; they stay zero when anonymizing. The
+/// rest count rows whose reference to the actor was removed or replaced.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ActorPurgeStats {
+    /// Sessions the actor started that held nothing else once their
+    /// items and checkpoints were gone.
+    pub sessions: usize,
+    pub context_items: usize,
+    pub issues: usize,
+    pub memory: usize,
+    pub checkpoints: usize,
+    pub time_entries: usize,
+    /// Issues other actors created that named the actor as assignee,
+    /// closer or (when anonymizing) creator.
+    pub issue_refs: usize,
+    /// Time entries logged by the actor (when anonymizing).
+    pub time_entry_refs: usize,
+    /// Audit events by or mentioning the actor, or about deleted records.
+    pub events: usize,
+    pub plan_revisions: usize,
+    pub token_usage: usize,
+    /// Issue watches and inbox read markers.
+    pub watches: usize,
+    pub trash: usize,
+    /// Recorded sync deletions made by the actor.
+    pub sync_deletions: usize,
+    /// The `actors` row and its aliases.
+    pub actor_rows: usize,
+}
+
+json_schema!(ActorPurgeStats {
+    sessions: usize,
+    context_items: usize,
+    issues: usize,
+    memory: usize,
+    checkpoints: usize,
+    time_entries: usize,
+    issue_refs: usize,
+    time_entry_refs: usize,
+    events: usize,
+    plan_revisions: usize,
+    token_usage: usize,
+    watches: usize,
+    trash: usize,
+    sync_deletions: usize,
+    actor_rows: usize,
+});
+
+impl ActorPurgeStats {
+    /// Records deleted.
+    #[must_use]
+    pub const fn records(&self) -> usize {
+        self.sessions + self.context_items + self.issues + self.memory + self.checkpoints + self.time_entries
+    }
+
+    /// Total rows touched.
+    #[must_use]
+    pub const fn rows(&self) -> usize {
+        self.records()
+            + self.issue_refs
+            + self.time_entry_refs
+            + self.events
+            + self.plan_revisions
+            + self.token_usage
+            + self.watches
+            + self.trash
+            + self.sync_deletions
+            + self.actor_rows
+    }
+}
+
 impl BackfillStats {
     /// Returns true if any records were marked dirty.
     #[must_use]
@@ -143,6 +283,202 @@ impl BackfillStats {
     }
 }
 
+/// Health indicators for a project.
+///
+/// Returned by `get_project_health` for the `sc project status` overview.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ProjectHealth {
+    /// Open (not closed or deferred) issues, indexed by priority 0-4.
+    pub open_by_priority: [usize; 5],
+    /// Number of open issues not updated since the stale cutoff.
+    pub stale_count: usize,
+    /// Oldest stale issues (at most 10).
+    pub stale_issues: Vec<StaleIssue>,
+    /// Number of active sessions touching the project.
+    pub active_sessions: usize,
+    /// Context items still waiting for embeddings.
+    pub embedding_backlog: usize,
+    /// Records marked dirty but not yet exported.
+    pub pending_sync: usize,
+    /// Timestamp of the most recent checkpoint (Unix milliseconds).
+    pub last_checkpoint_at: Option<i64>,
+}
+
+/// An open issue that has not been updated recently.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StaleIssue {
+    pub id: String,
+    pub short_id: Option<String>,
+    pub title: String,
+    pub status: String,
+    pub updated_at: i64,
+}
+
+json_schema!(StaleIssue { id: String, short_id: Option<String>, title: String, status: String, updated_at: i64 });
+
+/// Activity on an issue that concerns an actor, for `sc inbox`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InboxItem {
+    pub event_id: i64,
+    /// `issue_created`, `issue_closed`, `issue_commented`, `issue_mentioned`, ...
+    pub event_type: String,
+    /// Who did it
+    pub actor: String,
+    /// Why it is in the inbox: `mentioned`, `assigned` or `watching`
+    pub reason: String,
+    pub issue_id: String,
+    pub short_id: Option<String>,
+    pub title: String,
+    pub status: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// Comment text, or the text containing the mention
+    pub comment: Option<String>,
+    pub created_at: i64,
+}
+
+json_schema!(InboxItem {
+    event_id: i64,
+    event_type: String,
+    actor: String,
+    reason: String,
+    issue_id: String,
+    short_id: Option<String>,
+    title: String,
+    status: String,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    comment: Option<String>,
+    created_at: i64,
+});
+
+/// Local command usage, returned by `get_usage_stats` for `sc stats`.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct UsageStats {
+    /// Start of the reporting window (Unix milliseconds).
+    pub since: i64,
+    /// Invocations recorded in the window.
+    pub total_invocations: usize,
+    /// SQL statements that ran past the slow-query threshold in the window.
+    pub slow_queries: usize,
+    /// Per-command totals, most used first.
+    pub commands: Vec<CommandUsage>,
+    /// Slowest individual invocations, slowest first.
+    pub slowest: Vec<CommandInvocation>,
+    /// Daily database size samples, oldest first.
+    pub db_growth: Vec<DbSizeSample>,
+}
+
+json_schema!(UsageStats {
+    since: i64,
+    total_invocations: usize,
+    slow_queries: usize,
+    commands: Vec<CommandUsage>,
+    slowest: Vec<CommandInvocation>,
+    db_growth: Vec<DbSizeSample>,
+});
+
+/// Aggregated usage of one command.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommandUsage {
+    pub command: String,
+    pub count: usize,
+    pub failures: usize,
+    pub avg_ms: i64,
+    pub max_ms: i64,
+    /// Average time spent in SQL.
+    pub avg_sql_ms: i64,
+    pub slow_queries: usize,
+}
+
+json_schema!(CommandUsage {
+    command: String,
+    count: usize,
+    failures: usize,
+    avg_ms: i64,
+    max_ms: i64,
+    avg_sql_ms: i64,
+    slow_queries: usize,
+});
+
+/// A single recorded command run.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommandInvocation {
+    pub command: String,
+    pub duration_ms: i64,
+    pub sql_ms: i64,
+    pub slow_queries: usize,
+    pub success: bool,
+    pub created_at: i64,
+}
+
+json_schema!(CommandInvocation {
+    command: String,
+    duration_ms: i64,
+    sql_ms: i64,
+    slow_queries: usize,
+    success: bool,
+    created_at: i64,
+});
+
+/// Database size on a given day (`YYYY-MM-DD`).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DbSizeSample {
+    pub sampled_on: String,
+    pub size_bytes: i64,
+}
+
+json_schema!(DbSizeSample { sampled_on: String, size_bytes: i64 });
+
+/// Model tokens consumed by a session, for `sc session usage show`.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct TokenUsage {
+    pub session_id: String,
+    pub session_name: String,
+    pub tokens_in: i64,
+    pub tokens_out: i64,
+    /// Usage records (typically one per model turn)
+    pub records: usize,
+    pub first_at: Option<i64>,
+    pub last_at: Option<i64>,
+    /// Compactions (`pre-compact-*` checkpoints) in the session
+    pub compactions: usize,
+    /// Usage split at each compaction, oldest first; empty in listings
+    pub windows: Vec<UsageWindow>,
+}
+
+json_schema!(TokenUsage {
+    session_id: String,
+    session_name: String,
+    tokens_in: i64,
+    tokens_out: i64,
+    records: usize,
+    first_at: Option<i64>,
+    last_at: Option<i64>,
+    compactions: usize,
+    windows: Vec<UsageWindow>,
+});
+
+/// Tokens used between two compactions of a session.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct UsageWindow {
+    /// Compaction checkpoint that closed the window; `None` for the
+    /// current one
+    pub compacted_by: Option<String>,
+    pub ended_at: Option<i64>,
+    pub tokens_in: i64,
+    pub tokens_out: i64,
+    pub records: usize,
+}
+
+json_schema!(UsageWindow {
+    compacted_by: Option<String>,
+    ended_at: Option<i64>,
+    tokens_in: i64,
+    tokens_out: i64,
+    records: usize,
+});
+
 /// Counts of records for a project.
 ///
 /// Used by `get_project_counts` to return summary statistics about
@@ -161,6 +497,8 @@ pub struct ProjectCounts {
     pub checkpoints: usize,
 }
 
+json_schema!(ProjectCounts { sessions: usize, issues: usize, context_items: usize, memories: usize, checkpoints: usize });
+
 impl ProjectCounts {
     /// Returns total number of records.
     #[must_use]
@@ -187,7 +525,7 @@ impl SqliteStorage {
     ///
     /// Returns an error if the connection cannot be established or schema fails.
     pub fn open_with_timeout(path: &Path, timeout_ms: Option<u64>) -> Result<Self> {
-        let conn = Connection::open(path)?;
+        let mut conn = Connection::open(path)?;
 
         if let Some(timeout) = timeout_ms {
             conn.busy_timeout(Duration::from_millis(timeout))?;
@@ -197,7 +535,15 @@ impl SqliteStorage {
         }
 
         apply_schema(&conn)?;
-        Ok(Self { conn })
+
+        // Installed after migrations so only the command's own queries are timed
+        conn.profile(Some(crate::storage::timing::record));
+
+        Ok(Self {
+            conn,
+            compression_threshold: crate::config::value_compression_threshold(),
+            permissions: crate::config::permissions(),
+        })
     }
 
     /// Open an in-memory database (for testing).
@@ -208,7 +554,64 @@ impl SqliteStorage {
     pub fn open_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
         apply_schema(&conn)?;
-        Ok(Self { conn })
+        Ok(Self { conn, compression_threshold: crate::storage::compression::DEFAULT_THRESHOLD, permissions: None })
+    }
+
+    /// Query plans for the reads traced since `--explain` was enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a recorded statement cannot be explained.
+    pub fn explain_report(&self) -> Result<Vec<crate::storage::explain::QueryTrace>> {
+        crate::storage::explain::build_report(&self.conn)
+    }
+
+    /// List all indexes with their `sqlite_stat1` statistics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema cannot be read.
+    pub fn list_indexes(&self) -> Result<Vec<crate::storage::IndexInfo>> {
+        crate::storage::indexes::list_indexes(&self.conn)
+    }
+
+    /// Compression totals over all context items.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn compression_stats(&self) -> Result<crate::storage::CompressionStats> {
+        crate::storage::compression::stats(&self.conn)
+    }
+
+    /// Table sizes, largest items and sessions, and growth since the last
+    /// snapshot, listing `top` items and sessions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a query fails.
+    pub fn db_stats(&self, top: usize) -> Result<crate::storage::DbStats> {
+        crate::storage::stats::collect(&self.conn, top)
+    }
+
+    /// Store `stats` as the snapshot the next [`Self::db_stats`] compares
+    /// against.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot cannot be written.
+    pub fn save_db_stats_snapshot(&self, stats: &crate::storage::DbStats) -> Result<()> {
+        crate::storage::stats::save_snapshot(&self.conn, stats)
+    }
+
+    /// Run `ANALYZE` to refresh the statistics the query planner uses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the analysis fails.
+    pub fn analyze(&self) -> Result<()> {
+        self.conn.execute_batch("ANALYZE")?;
+        Ok(())
     }
 
     /// Get a reference to the underlying connection (for read operations).
@@ -245,25 +648,45 @@ impl SqliteStorage {
     /// Execute a mutation with the transaction protocol.
     ///
     /// This method:
-    /// 1. Begins an IMMEDIATE transaction (for write locking)
-    /// 2. Executes the mutation closure
-    /// 3. Writes audit events
-    /// 4. Updates dirty tracking tables
-    /// 5. Commits (or rolls back on error)
+    /// 1. Checks that the actor's role permits `op`
+    /// 2. Waits its turn among writing processes and begins an IMMEDIATE
+    ///    transaction, retrying while the database is busy (see
+    ///    [`write_queue`](crate::storage::write_queue))
+    /// 3. Executes the mutation closure
+    /// 4. Writes audit events
+    /// 5. Updates dirty tracking tables
+    /// 6. Commits (or rolls back on error)
+    ///
+    /// Inside [`Self::with_transaction`] the mutation runs as a savepoint
+    /// instead, so it commits or rolls back with the enclosing transaction.
     ///
     /// # Errors
     ///
-    /// Returns an error if any step fails. The transaction is rolled back on error.
+    /// Returns [`Error::PermissionDenied`] if the actor's role forbids `op`,
+    /// or an error if any step fails. The transaction is rolled back on error.
     pub fn mutate<F, R>(&mut self, op: &str, actor: &str, f: F) -> Result<R>
     where
-        F: FnOnce(&Transaction, &mut MutationContext) -> Result<R>,
+        F: FnOnce(&Connection, &mut MutationContext) -> Result<R>,
     {
-        let tx = self
-            .conn
-            .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
-
+        self.check_permission(op, actor)?;
         let mut ctx = MutationContext::new(op, actor);
 
+        if !self.conn.is_autocommit() {
+            let sp = self.conn.savepoint()?;
+            let result = f(&sp, &mut ctx)?;
+            for event in &ctx.events {
+                insert_event(&sp, event)?;
+            }
+            sp.commit()?;
+            return Ok(result);
+        }
+
+        // Declared before the transaction, so the turn ends after it does
+        let mut turn = WriteTurn::wait(&self.conn);
+        let tx = turn.begin(&self.conn, || {
+            rusqlite::Transaction::new_unchecked(&self.conn, rusqlite::TransactionBehavior::Immediate)
+        })?;
+
         // Execute the mutation
         let result = f(&tx, &mut ctx)?;
 
@@ -281,15 +704,126 @@ impl SqliteStorage {
         Ok(result)
     }
 
+    /// Fail with [`Error::PermissionDenied`] if `actor` has a role that
+    /// forbids `op`. Aliases are resolved first, so roles are assigned to
+    /// canonical actor IDs.
+    fn check_permission(&self, op: &str, actor: &str) -> Result<()> {
+        let Some(permissions) = &self.permissions else {
+            return Ok(());
+        };
+        let id = alias_owner(&self.conn, actor)?.unwrap_or_else(|| actor.to_string());
+        match permissions.role_of(&id) {
+            Some(role) if !permissions.allows(role, op) => Err(Error::PermissionDenied {
+                actor: id,
+                role: role.to_string(),
+                operation: op.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail with [`Error::PolicyDenied`] if `permissions.commands` turns
+    /// `command` (a subcommand path like `"issue dep add"`) off for the type
+    /// of `actor`. Actors that aren't registered count as humans.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PolicyDenied`, or an error if the actor lookup fails.
+    pub fn check_command_policy(&self, actor: &str, command: &str) -> Result<()> {
+        let Some(permissions) = self.permissions.as_ref().filter(|p| !p.commands.is_empty()) else {
+            return Ok(());
+        };
+        let registered = self.get_actor(actor)?;
+        let actor_type = registered.as_ref().map(|a| a.actor_type).unwrap_or_default();
+        if permissions.allows_command(actor_type, command) {
+            return Ok(());
+        }
+        Err(Error::PolicyDenied {
+            actor: registered.map_or_else(|| actor.to_string(), |a| a.id),
+            actor_type: actor_type.to_string(),
+            command: command.to_string(),
+        })
+    }
+
+    /// Replace the actor roles loaded from the config file.
+    pub fn set_permissions(&mut self, permissions: Option<PermissionsConfig>) {
+        self.permissions = permissions;
+    }
+
+    /// The actor roles in effect, if any.
+    #[must_use]
+    pub const fn permissions(&self) -> Option<&PermissionsConfig> {
+        self.permissions.as_ref()
+    }
+
+    /// Run several mutations as one unit.
+    ///
+    /// `f` returns its result and whether to keep its writes: `true`
+    /// commits, `false` rolls everything back. Mutations made by `f` become
+    /// savepoints of this transaction. Calls nest: an inner call is a
+    /// savepoint, so rolling it back leaves the outer transaction intact.
+    /// The outermost call takes a write turn first, as [`Self::mutate`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started or finished.
+    pub fn with_transaction<R>(&mut self, f: impl FnOnce(&mut Self) -> (R, bool)) -> Result<R> {
+        let nested = !self.conn.is_autocommit();
+        let mut turn = None;
+        if nested {
+            self.conn.execute_batch("SAVEPOINT sc_scope")?;
+        } else {
+            let turn = turn.insert(WriteTurn::wait(&self.conn));
+            turn.begin(&self.conn, || self.conn.execute_batch("BEGIN IMMEDIATE"))?;
+        }
+
+        let (result, keep) = f(self);
+
+        let finish = match (nested, keep) {
+            (false, true) => "COMMIT",
+            (false, false) => "ROLLBACK",
+            (true, true) => "RELEASE sc_scope",
+            (true, false) => "ROLLBACK TO sc_scope; RELEASE sc_scope",
+        };
+        if let Err(e) = self.conn.execute_batch(finish) {
+            if !nested && !self.conn.is_autocommit() {
+                let _ = self.conn.execute_batch("ROLLBACK");
+            }
+            return Err(e.into());
+        }
+        Ok(result)
+    }
+
+    /// Run the steps of a composite command as one unit: keep every write
+    /// of `f` if it returns `Ok`, none of them if it returns `Err`.
+    ///
+    /// Shorthand for [`Self::with_transaction`] keeping the writes on
+    /// success, so it nests the same way.
+    ///
+    /// # Errors
+    ///
+    /// Returns `f`'s error, or an error if the transaction cannot be
+    /// started or finished.
+    pub fn atomically<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        self.with_transaction(|s| {
+            let result = f(s);
+            let ok = result.is_ok();
+            (result, ok)
+        })?
+    }
+
     // ==================
     // Session Operations
     // ==================
 
-    /// Create a new session.
+    /// Create a new session. Its channel is derived from `branch` with
+    /// [`branch_channel`](crate::config::branch_channel); `worktree` is the
+    /// linked git worktree it was started in, if any.
     ///
     /// # Errors
     ///
     /// Returns an error if the insert fails.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_session(
         &mut self,
         id: &str,
@@ -297,15 +831,17 @@ impl SqliteStorage {
         description: Option<&str>,
         project_path: Option<&str>,
         branch: Option<&str>,
+        worktree: Option<&str>,
         actor: &str,
     ) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
+        let channel = crate::config::branch_channel(branch.unwrap_or_default());
 
         self.mutate("create_session", actor, |tx, ctx| {
             tx.execute(
-                "INSERT INTO sessions (id, name, description, project_path, branch, status, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, 'active', ?6, ?6)",
-                rusqlite::params![id, name, description, project_path, branch, now],
+                "INSERT INTO sessions (id, name, description, project_path, branch, channel, worktree, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'active', ?8, ?8)",
+                rusqlite::params![id, name, description, project_path, branch, channel, worktree, now],
             )?;
 
             // Also insert into session_projects junction table for project-based filtering
@@ -330,7 +866,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_session(&self, id: &str) -> Result<Option<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at
+            "SELECT id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at, worktree
              FROM sessions WHERE id = ?1",
         )?;
 
@@ -347,6 +883,7 @@ impl SqliteStorage {
                     ended_at: row.get(7)?,
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
+                    worktree: row.get(10)?,
                 })
             })
             .optional()?;
@@ -365,13 +902,15 @@ impl SqliteStorage {
         status: Option<&str>,
         limit: Option<u32>,
     ) -> Result<Vec<Session>> {
-        self.list_sessions_with_search(project_path, status, limit, None)
+        self.list_sessions_with_search(project_path, status, limit, None, None)
     }
 
     /// List sessions with optional filters and search.
     ///
     /// Uses the `session_projects` junction table for project path filtering,
-    /// matching the MCP server's `listSessionsByPaths` behavior.
+    /// matching the MCP server's `listSessionsByPaths` behavior. `worktree`
+    /// keeps sessions started in that linked worktree; an empty string keeps
+    /// the ones started outside any.
     ///
     /// # Errors
     ///
@@ -382,6 +921,7 @@ impl SqliteStorage {
         status: Option<&str>,
         limit: Option<u32>,
         search: Option<&str>,
+        worktree: Option<&str>,
     ) -> Result<Vec<Session>> {
         let limit = limit.unwrap_or(50);
 
@@ -420,6 +960,12 @@ impl SqliteStorage {
             param_idx += 1;
         }
 
+        if let Some(worktree) = worktree {
+            conditions.push(format!("COALESCE(s.worktree, '') = ?{param_idx}"));
+            params.push(worktree.to_string());
+            param_idx += 1;
+        }
+
         let where_clause = if conditions.is_empty() {
             " WHERE 1=1".to_string()
         } else {
@@ -427,7 +973,7 @@ impl SqliteStorage {
         };
 
         let sql = format!(
-            "SELECT {select_distinct}s.id, s.name, s.description, s.branch, s.channel, s.project_path, s.status, s.ended_at, s.created_at, s.updated_at
+            "SELECT {select_distinct}s.id, s.name, s.description, s.branch, s.channel, s.project_path, s.status, s.ended_at, s.created_at, s.updated_at, s.worktree
              FROM {from_clause}{where_clause}
              ORDER BY s.updated_at DESC LIMIT ?{param_idx}"
         );
@@ -451,6 +997,7 @@ impl SqliteStorage {
                 ended_at: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                worktree: row.get(10)?,
             })
         })?;
 
@@ -458,6 +1005,30 @@ impl SqliteStorage {
             .map_err(Error::from)
     }
 
+    /// Record that `auto_session` picked a session for a command that named
+    /// none. `how` is `resumed` or `created`, and `command` the command
+    /// that triggered it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be written.
+    pub fn record_session_auto_resolved(
+        &mut self,
+        session_id: &str,
+        how: &str,
+        command: &str,
+        actor: &str,
+    ) -> Result<()> {
+        self.mutate("auto_resolve_session", actor, |_, ctx| {
+            ctx.events.push(
+                Event::new("session", session_id, EventType::SessionAutoResolved, actor)
+                    .with_values(None, Some(how.to_string()))
+                    .with_comment(command),
+            );
+            Ok(())
+        })
+    }
+
     /// Update session status.
     ///
     /// # Errors
@@ -466,11 +1037,11 @@ impl SqliteStorage {
     pub fn update_session_status(
         &mut self,
         id: &str,
-        status: &str,
+        status: SessionStatus,
         actor: &str,
     ) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
-        let ended_at = if status == "completed" || status == "paused" {
+        let ended_at = if matches!(status, SessionStatus::Completed | SessionStatus::Paused) {
             Some(now)
         } else {
             None
@@ -487,9 +1058,9 @@ impl SqliteStorage {
             }
 
             let event_type = match status {
-                "paused" => EventType::SessionPaused,
-                "completed" => EventType::SessionCompleted,
-                _ => EventType::SessionUpdated,
+                SessionStatus::Paused => EventType::SessionPaused,
+                SessionStatus::Completed => EventType::SessionCompleted,
+                SessionStatus::Active => EventType::SessionUpdated,
             };
             ctx.record_event("session", id, event_type);
             ctx.mark_session_dirty(id);
@@ -498,6 +1069,65 @@ impl SqliteStorage {
         })
     }
 
+    /// Time of the last write to a session (item, checkpoint, start or
+    /// resume), if known.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_session_last_activity(&self, id: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT last_activity_at FROM session_activity WHERE session_id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Pause every active session with no activity since `idle_since`
+    /// (Unix ms). Returns `(id, name, last_activity_at)` of each, oldest
+    /// activity first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the update fails.
+    pub fn pause_idle_sessions(
+        &mut self,
+        idle_since: i64,
+        actor: &str,
+    ) -> Result<Vec<(String, String, i64)>> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        self.mutate("pause_idle_sessions", actor, |tx, ctx| {
+            let idle = {
+                let mut stmt = tx.prepare(
+                    "SELECT s.id, s.name, COALESCE(a.last_activity_at, s.updated_at) AS last_activity
+                     FROM sessions s LEFT JOIN session_activity a ON a.session_id = s.id
+                     WHERE s.status = 'active' AND COALESCE(a.last_activity_at, s.updated_at) < ?1
+                     ORDER BY last_activity",
+                )?;
+                stmt.query_map([idle_since], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect::<std::result::Result<Vec<(String, String, i64)>, _>>()?
+            };
+
+            for (id, _, last_activity) in &idle {
+                tx.execute(
+                    "UPDATE sessions SET status = 'paused', ended_at = ?1, updated_at = ?1 WHERE id = ?2",
+                    rusqlite::params![now, id],
+                )?;
+                let idle_hours = (now - last_activity) / 3_600_000;
+                ctx.events.push(
+                    Event::new("session", id, EventType::SessionPaused, actor)
+                        .with_comment(&format!("Auto-paused after {idle_hours}h idle")),
+                );
+                ctx.mark_session_dirty(id);
+            }
+            Ok(idle)
+        })
+    }
+
     /// Rename a session.
     ///
     /// # Errors
@@ -528,7 +1158,32 @@ impl SqliteStorage {
         })
     }
 
-    /// Delete a session and all related data.
+    /// Move a session to another git branch and the channel items saved on
+    /// it go to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session doesn't exist or the update fails.
+    pub fn switch_session_branch(&mut self, id: &str, branch: &str, channel: &str, actor: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        self.mutate("switch_session_branch", actor, |tx, ctx| {
+            let rows = tx.execute(
+                "UPDATE sessions SET branch = ?1, channel = ?2, updated_at = ?3 WHERE id = ?4",
+                rusqlite::params![branch, channel, now, id],
+            )?;
+
+            if rows == 0 {
+                return Err(Error::SessionNotFound { id: id.to_string() });
+            }
+
+            ctx.record_event("session", id, EventType::SessionUpdated);
+            ctx.mark_session_dirty(id);
+
+            Ok(())
+        })
+    }
+
     ///
     /// This cascades to delete:
     /// - Context items in the session
@@ -553,6 +1208,13 @@ impl SqliteStorage {
                 return Err(Error::SessionNotFound { id: id.to_string() });
             }
 
+            let (name, project_path): (String, Option<String>) = tx.query_row(
+                "SELECT name, project_path FROM sessions WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            trash::capture(tx, "session", id, &name, project_path.as_deref(), &ctx.actor)?;
+
             // Delete context items for this session
             tx.execute(
                 "DELETE FROM context_items WHERE session_id = ?1",
@@ -724,7 +1386,8 @@ impl SqliteStorage {
     // Context Item Operations
     // =======================
 
-    /// Save a context item (upsert).
+    /// Save a context item (upsert). A new item goes on the session's
+    /// current channel; an existing one keeps its channel.
     ///
     /// # Errors
     ///
@@ -735,56 +1398,110 @@ impl SqliteStorage {
         session_id: &str,
         key: &str,
         value: &str,
-        category: Option<&str>,
-        priority: Option<&str>,
+        category: Option<ItemCategory>,
+        priority: Option<ItemPriority>,
         actor: &str,
     ) -> Result<()> {
-        let now = chrono::Utc::now().timestamp_millis();
-        let category = category.unwrap_or("note");
-        let priority = priority.unwrap_or("normal");
-        let size = value.len() as i64;
-
+        let threshold = self.compression_threshold;
         self.mutate("save_context_item", actor, |tx, ctx| {
-            // Check if exists for event type
-            let exists: bool = tx
-                .prepare("SELECT 1 FROM context_items WHERE session_id = ?1 AND key = ?2")?
-                .exists(rusqlite::params![session_id, key])?;
+            put_context_item(tx, ctx, threshold, id, session_id, key, value, category, priority).map(|_| ())
+        })
+    }
 
+    /// Save a reminder that comes due at `due_at` (Unix ms): a `reminder`
+    /// item, created or rescheduled (and reopened) by key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session doesn't exist or the write fails.
+    #[allow(clippy::too_many_arguments)]
+    pub fn save_reminder(
+        &mut self,
+        id: &str,
+        session_id: &str,
+        key: &str,
+        value: &str,
+        due_at: i64,
+        priority: Option<ItemPriority>,
+        actor: &str,
+    ) -> Result<()> {
+        let threshold = self.compression_threshold;
+        self.mutate("save_reminder", actor, |tx, ctx| {
+            let id = put_context_item(tx, ctx, threshold, id, session_id, key, value, Some(ItemCategory::Reminder), priority)?;
             tx.execute(
-                "INSERT INTO context_items (id, session_id, key, value, category, priority, size, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
-                 ON CONFLICT(session_id, key) DO UPDATE SET
-                   value = excluded.value,
-                   category = excluded.category,
-                   priority = excluded.priority,
-                   size = excluded.size,
-                   updated_at = excluded.updated_at",
-                rusqlite::params![id, session_id, key, value, category, priority, size, now],
+                "UPDATE context_items SET due_at = ?2, done_at = NULL WHERE id = ?1",
+                rusqlite::params![id, due_at],
             )?;
-
-            let event_type = if exists {
-                EventType::ItemUpdated
-            } else {
-                EventType::ItemCreated
-            };
-            ctx.record_event("context_item", id, event_type);
-            ctx.mark_item_dirty(id);
-
             Ok(())
         })
     }
 
-    /// Look up the actual item ID by session + key.
+    /// Mark a project's reminder done, by item ID or key. A key names the
+    /// open reminder due first, if several sessions have one.
     ///
-    /// Needed after upserts where ON CONFLICT keeps the original ID.
-    pub fn get_item_id_by_key(&self, session_id: &str, key: &str) -> Result<Option<String>> {
-        let id = self.conn.query_row(
-            "SELECT id FROM context_items WHERE session_id = ?1 AND key = ?2",
-            rusqlite::params![session_id, key],
-            |row| row.get(0),
-        ).optional()?;
-        Ok(id)
-    }
+    /// # Errors
+    ///
+    /// Returns an error if there is no such reminder or the write fails.
+    pub fn complete_reminder(&mut self, project_path: &str, id_or_key: &str, actor: &str) -> Result<ContextItem> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.mutate("complete_reminder", actor, |tx, ctx| {
+            let id: String = tx
+                .query_row(
+                    "SELECT ci.id FROM context_items ci JOIN sessions s ON s.id = ci.session_id
+                     WHERE (ci.id = ?1 OR ci.key = ?1) AND s.project_path = ?2 AND ci.due_at IS NOT NULL
+                     ORDER BY ci.done_at IS NOT NULL, ci.due_at LIMIT 1",
+                    rusqlite::params![id_or_key, project_path],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| Error::Other(format!("Reminder not found: {id_or_key}")))?;
+            let changed = tx.execute(
+                "UPDATE context_items SET done_at = ?2, updated_at = ?2 WHERE id = ?1 AND done_at IS NULL",
+                rusqlite::params![id, now],
+            )?;
+            if changed > 0 {
+                ctx.events.push(
+                    Event::new("context_item", &id, EventType::ItemUpdated, &ctx.actor).with_comment("Reminder done"),
+                );
+                ctx.mark_item_dirty(&id);
+            }
+            Self::context_item_by_id(tx, &id)?.ok_or_else(|| Error::Other(format!("Reminder not found: {id_or_key}")))
+        })
+    }
+
+    /// A project's scheduled reminders, due first. Done ones only with
+    /// `include_done`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_reminders(&self, project_path: &str, include_done: bool) -> Result<Vec<ContextItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ci.id FROM context_items ci JOIN sessions s ON s.id = ci.session_id
+             WHERE s.project_path = ?1 AND ci.due_at IS NOT NULL AND (?2 OR ci.done_at IS NULL)
+             ORDER BY ci.due_at",
+        )?;
+        let ids = stmt
+            .query_map(rusqlite::params![project_path, include_done], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let mut items = Vec::with_capacity(ids.len());
+        for id in &ids {
+            items.extend(Self::context_item_by_id(&self.conn, id)?);
+        }
+        Ok(items)
+    }
+
+    /// Look up the actual item ID by session + key.
+    ///
+    /// Needed after upserts where ON CONFLICT keeps the original ID.
+    pub fn get_item_id_by_key(&self, session_id: &str, key: &str) -> Result<Option<String>> {
+        let id = self.conn.query_row(
+            "SELECT id FROM context_items WHERE session_id = ?1 AND key = ?2",
+            rusqlite::params![session_id, key],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(id)
+    }
 
     /// Get all context items for a session with their fast-tier embeddings (if any).
     ///
@@ -795,7 +1512,7 @@ impl SqliteStorage {
         session_id: &str,
     ) -> Result<Vec<(ContextItem, Option<Vec<f32>>)>> {
         let sql = "SELECT ci.id, ci.session_id, ci.key, ci.value, ci.category, ci.priority,
-                          ci.channel, ci.tags, ci.size, ci.created_at, ci.updated_at,
+                          ci.channel, ci.tags, ci.size, ci.created_at, ci.updated_at, ci.source, ci.trust, ci.review_status, ci.due_at, ci.done_at,
                           ec.embedding
                    FROM context_items ci
                    LEFT JOIN embedding_chunks_fast ec ON ec.item_id = ci.id AND ec.chunk_index = 0
@@ -808,7 +1525,7 @@ impl SqliteStorage {
                 id: row.get(0)?,
                 session_id: row.get(1)?,
                 key: row.get(2)?,
-                value: row.get(3)?,
+                value: read_value(row, 3)?,
                 category: row.get(4)?,
                 priority: row.get(5)?,
                 channel: row.get(6)?,
@@ -816,9 +1533,14 @@ impl SqliteStorage {
                 size: row.get(8)?,
                 created_at: row.get(9)?,
                 updated_at: row.get(10)?,
+                source: row.get(11)?,
+                trust: row.get(12)?,
+                review_status: row.get(13)?,
+                due_at: row.get(14)?,
+                done_at: row.get(15)?,
             };
 
-            let embedding: Option<Vec<f32>> = row.get::<_, Option<Vec<u8>>>(11)?
+            let embedding: Option<Vec<f32>> = row.get::<_, Option<Vec<u8>>>(16)?
                 .map(|blob| {
                     blob.chunks_exact(4)
                         .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
@@ -843,14 +1565,14 @@ impl SqliteStorage {
     pub fn get_context_items(
         &self,
         session_id: &str,
-        category: Option<&str>,
-        priority: Option<&str>,
+        category: Option<ItemCategory>,
+        priority: Option<ItemPriority>,
         limit: Option<u32>,
     ) -> Result<Vec<ContextItem>> {
         let limit = limit.unwrap_or(100);
 
         let mut sql = String::from(
-            "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at
+            "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at
              FROM context_items WHERE session_id = ?1",
         );
 
@@ -858,12 +1580,12 @@ impl SqliteStorage {
 
         if let Some(cat) = category {
             sql.push_str(" AND category = ?");
-            params.push(Box::new(cat.to_string()));
+            params.push(Box::new(cat));
         }
 
         if let Some(pri) = priority {
             sql.push_str(" AND priority = ?");
-            params.push(Box::new(pri.to_string()));
+            params.push(Box::new(pri));
         }
 
         sql.push_str(" ORDER BY created_at DESC LIMIT ?");
@@ -880,7 +1602,7 @@ impl SqliteStorage {
                 id: row.get(0)?,
                 session_id: row.get(1)?,
                 key: row.get(2)?,
-                value: row.get(3)?,
+                value: read_value(row, 3)?,
                 category: row.get(4)?,
                 priority: row.get(5)?,
                 channel: row.get(6)?,
@@ -888,6 +1610,11 @@ impl SqliteStorage {
                 size: row.get(8)?,
                 created_at: row.get(9)?,
                 updated_at: row.get(10)?,
+                source: row.get(11)?,
+                trust: row.get(12)?,
+                review_status: row.get(13)?,
+                due_at: row.get(14)?,
+                done_at: row.get(15)?,
             })
         })?;
 
@@ -919,6 +1646,10 @@ impl SqliteStorage {
                 )
                 .optional()?;
 
+            if let Some((item_id, project_path)) = &info {
+                trash::capture(tx, "context_item", item_id, key, project_path.as_deref(), &ctx.actor)?;
+            }
+
             let rows = tx.execute(
                 "DELETE FROM context_items WHERE session_id = ?1 AND key = ?2",
                 rusqlite::params![session_id, key],
@@ -958,11 +1689,12 @@ impl SqliteStorage {
         session_id: &str,
         key: &str,
         value: Option<&str>,
-        category: Option<&str>,
-        priority: Option<&str>,
+        category: Option<ItemCategory>,
+        priority: Option<ItemPriority>,
         channel: Option<&str>,
         actor: &str,
     ) -> Result<()> {
+        let threshold = self.compression_threshold;
         self.mutate("update_context_item", actor, |tx, ctx| {
             let now = chrono::Utc::now().timestamp_millis();
 
@@ -970,38 +1702,48 @@ impl SqliteStorage {
             let mut set_parts: Vec<&str> = vec!["updated_at"];
             let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(now)];
 
+            // Get item ID for event tracking, and how its value is stored
+            let current: Option<(String, String, ItemCategory, bool)> = tx
+                .query_row(
+                    "SELECT id, value, category, value_encrypted FROM context_items WHERE session_id = ?1 AND key = ?2",
+                    rusqlite::params![session_id, key],
+                    |row| Ok((row.get(0)?, read_value(row, 1)?, row.get(2)?, row.get(3)?)),
+                )
+                .optional()?;
+            let Some((item_id, current_value, current_category, was_encrypted)) = current else {
+                return Err(Error::Database(rusqlite::Error::QueryReturnedNoRows));
+            };
+
+            let encrypt = match &category {
+                Some(c) => crate::storage::categories::check_item(tx, session_id, c)?.encrypted,
+                None => crate::storage::categories::for_session(tx, session_id)?.encrypted(&current_category),
+            };
+            // A move into or out of an encrypted category re-stores the value
+            let value = value.or_else(|| (encrypt != was_encrypted).then_some(current_value.as_str()));
             if let Some(v) = value {
+                let (stored, compressed, encrypted) = store_value(tx, v, encrypt, threshold)?;
                 set_parts.push("value");
+                set_parts.push("value_compressed");
+                set_parts.push("value_encrypted");
                 set_parts.push("size");
-                params.push(Box::new(v.to_string()));
+                params.push(Box::new(stored));
+                params.push(Box::new(compressed));
+                params.push(Box::new(encrypted));
                 params.push(Box::new(v.len() as i64));
             }
             if let Some(c) = category {
                 set_parts.push("category");
-                params.push(Box::new(c.to_string()));
+                params.push(Box::new(c));
             }
             if let Some(p) = priority {
                 set_parts.push("priority");
-                params.push(Box::new(p.to_string()));
+                params.push(Box::new(p));
             }
             if let Some(ch) = channel {
                 set_parts.push("channel");
                 params.push(Box::new(ch.to_string()));
             }
 
-            // Get item ID for event tracking
-            let item_id: Option<String> = tx
-                .query_row(
-                    "SELECT id FROM context_items WHERE session_id = ?1 AND key = ?2",
-                    rusqlite::params![session_id, key],
-                    |row| row.get(0),
-                )
-                .optional()?;
-
-            if item_id.is_none() {
-                return Err(Error::Database(rusqlite::Error::QueryReturnedNoRows));
-            }
-
             // Build SET clause with numbered placeholders
             let set_clause: String = set_parts
                 .iter()
@@ -1023,11 +1765,85 @@ impl SqliteStorage {
 
             let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
             tx.execute(&query, param_refs.as_slice())?;
+            crate::storage::encryption::skip_embeddings(tx, &item_id, encrypt)?;
 
-            if let Some(id) = item_id {
-                ctx.record_event("context_item", &id, EventType::ItemUpdated);
+            ctx.record_event("context_item", &item_id, EventType::ItemUpdated);
+
+            Ok(())
+        })
+    }
+
+    /// Set how far a context item can be relied on, e.g. raise an agent's
+    /// note to `human` once someone has checked it. The item's source and
+    /// `updated_at` stay as they were.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item doesn't exist or the update fails.
+    pub fn set_item_trust(&mut self, session_id: &str, key: &str, trust: TrustLevel, actor: &str) -> Result<()> {
+        self.mutate("set_item_trust", actor, |tx, ctx| {
+            let (id, old): (String, TrustLevel) = tx
+                .query_row(
+                    "SELECT id, trust FROM context_items WHERE session_id = ?1 AND key = ?2",
+                    rusqlite::params![session_id, key],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?
+                .ok_or_else(|| Error::Other(format!("Context item not found: {key}")))?;
+            if old == trust {
+                return Ok(());
             }
+            tx.execute("UPDATE context_items SET trust = ?1 WHERE id = ?2", rusqlite::params![trust, id])?;
+            ctx.record_change("context_item", &id, EventType::ItemUpdated, Some(old.to_string()), Some(trust.to_string()));
+            ctx.mark_item_dirty(&id);
+            Ok(())
+        })
+    }
 
+    /// Approve or reject a context item, with an optional comment kept on
+    /// the audit event. Approving also raises the item's trust to `human`.
+    /// Reviews must come from a person, so an actor registered as an agent
+    /// or model is refused.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item doesn't exist, the actor is an agent, or
+    /// the update fails.
+    pub fn review_context_item(
+        &mut self,
+        session_id: &str,
+        key: &str,
+        status: ReviewStatus,
+        comment: Option<&str>,
+        actor: &str,
+    ) -> Result<()> {
+        self.mutate("review_context_item", actor, |tx, ctx| {
+            if actor_item_source(tx, actor)? == ItemSource::Agent {
+                return Err(Error::InvalidArgument(format!(
+                    "'{actor}' is registered as an agent; decisions must be reviewed by a person"
+                )));
+            }
+            let (id, old): (String, ReviewStatus) = tx
+                .query_row(
+                    "SELECT id, review_status FROM context_items WHERE session_id = ?1 AND key = ?2",
+                    rusqlite::params![session_id, key],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?
+                .ok_or_else(|| Error::Other(format!("Context item not found: {key}")))?;
+            tx.execute(
+                "UPDATE context_items SET review_status = ?1,
+                   trust = CASE WHEN ?1 = 'approved' THEN 'human' ELSE trust END
+                 WHERE id = ?2",
+                rusqlite::params![status, id],
+            )?;
+            let mut event = Event::new("context_item", &id, EventType::ItemReviewed, actor)
+                .with_values(Some(old.to_string()), Some(status.to_string()));
+            if let Some(comment) = comment {
+                event = event.with_comment(comment);
+            }
+            ctx.events.push(event);
+            ctx.mark_item_dirty(&id);
             Ok(())
         })
     }
@@ -1122,7 +1938,8 @@ impl SqliteStorage {
     // Issue Operations
     // ================
 
-    /// Create a new issue.
+    /// Create a new issue, in a sub-project of a monorepo if `subproject`
+    /// is set.
     ///
     /// # Errors
     ///
@@ -1136,23 +1953,26 @@ impl SqliteStorage {
         title: &str,
         description: Option<&str>,
         details: Option<&str>,
-        issue_type: Option<&str>,
+        issue_type: Option<IssueType>,
         priority: Option<i32>,
         plan_id: Option<&str>,
+        subproject: Option<&str>,
         actor: &str,
     ) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
-        let issue_type = issue_type.unwrap_or("task");
+        let issue_type = issue_type.unwrap_or_default();
         let priority = priority.unwrap_or(2);
 
         self.mutate("create_issue", actor, |tx, ctx| {
             tx.execute(
-                "INSERT INTO issues (id, short_id, project_path, title, description, details, issue_type, priority, plan_id, status, created_by_agent, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'open', ?10, ?11, ?11)",
-                rusqlite::params![id, short_id, project_path, title, description, details, issue_type, priority, plan_id, actor, now],
+                "INSERT INTO issues (id, short_id, project_path, title, description, details, issue_type, priority, plan_id, subproject, status, created_by_agent, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'open', ?11, ?12, ?12)",
+                rusqlite::params![id, short_id, project_path, title, description, details, issue_type, priority, plan_id, subproject, actor, now],
             )?;
 
             ctx.record_event("issue", id, EventType::IssueCreated);
+            let text = [Some(title), description, details].into_iter().flatten().collect::<Vec<_>>().join("\n");
+            record_mentions(ctx, id, None, &text);
             ctx.mark_issue_dirty(id);
 
             Ok(())
@@ -1167,10 +1987,10 @@ impl SqliteStorage {
     pub fn get_issue(&self, id: &str, project_path: Option<&str>) -> Result<Option<Issue>> {
         // Try full ID first, then short ID
         let sql = if project_path.is_some() {
-            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at
+            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues WHERE (id = ?1 OR short_id = ?1) AND project_path = ?2"
         } else {
-            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at
+            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues WHERE id = ?1 OR short_id = ?1"
         };
 
@@ -1201,7 +2021,7 @@ impl SqliteStorage {
         let limit = limit.unwrap_or(50);
 
         let mut sql = String::from(
-            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at
+            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues WHERE project_path = ?1",
         );
 
@@ -1251,7 +2071,7 @@ impl SqliteStorage {
         let limit = limit.unwrap_or(50);
 
         let mut sql = String::from(
-            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at
+            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues WHERE 1=1",
         );
 
@@ -1284,39 +2104,63 @@ impl SqliteStorage {
             .map_err(Error::from)
     }
 
-    /// Update issue status.
+    /// Update issue status, enforcing the issue workflow.
     ///
-    /// Accepts either full ID or short_id.
+    /// Accepts either full ID or `short_id`. The move from the current status
+    /// must be allowed by `workflow`, and every field the workflow requires
+    /// for the target status must be present. `close_reason` is stored with
+    /// the transition (so it can satisfy a `close_reason` requirement).
     ///
     /// # Errors
     ///
-    /// Returns an error if the update fails.
+    /// Returns `InvalidTransition` if the workflow forbids the move,
+    /// `RequiredField` if a required field is missing, or an error if the
+    /// update fails.
     pub fn update_issue_status(
         &mut self,
         id: &str,
-        status: &str,
+        status: &IssueStatus,
+        close_reason: Option<&str>,
+        workflow: &IssueWorkflow,
         actor: &str,
     ) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
-        let closed_at = if status == "closed" { Some(now) } else { None };
+        let closing = *status == IssueStatus::Closed;
+        let closed_at = closing.then_some(now);
+        let status = status.as_str();
 
         self.mutate("update_issue_status", actor, |tx, ctx| {
-            let rows = tx.execute(
-                "UPDATE issues SET status = ?1, closed_at = ?2, closed_by_agent = ?3, updated_at = ?4 WHERE id = ?5 OR short_id = ?5",
-                rusqlite::params![status, closed_at, if status == "closed" { Some(actor) } else { None }, now, id],
-            )?;
-
-            if rows == 0 {
-                return Err(Error::IssueNotFound { id: id.to_string() });
+            let (full_id, old_status, mut fields) = load_issue_state(tx, id)?;
+            let old_reason = fields.get("close_reason").cloned().flatten();
+            if let Some(reason) = close_reason {
+                fields.insert("close_reason", Some(reason.to_string()));
             }
+            check_transition(workflow, &old_status, status, &fields)?;
+
+            tx.execute(
+                "UPDATE issues SET status = ?1, closed_at = ?2, closed_by_agent = ?3,
+                        close_reason = COALESCE(?4, close_reason), updated_at = ?5
+                 WHERE id = ?6",
+                rusqlite::params![
+                    status,
+                    closed_at,
+                    closing.then_some(actor),
+                    close_reason,
+                    now,
+                    full_id
+                ],
+            )?;
 
-            let event_type = if status == "closed" {
+            let event_type = if closing {
                 EventType::IssueClosed
             } else {
                 EventType::IssueUpdated
             };
-            ctx.record_event("issue", id, event_type);
-            ctx.mark_issue_dirty(id);
+            ctx.record_change("issue", &full_id, event_type, Some(old_status), Some(status.to_string()));
+            if let Some(reason) = close_reason {
+                record_mentions(ctx, &full_id, old_reason.as_deref(), reason);
+            }
+            ctx.mark_issue_dirty(&full_id);
 
             Ok(())
         })
@@ -1337,7 +2181,7 @@ impl SqliteStorage {
         description: Option<&str>,
         details: Option<&str>,
         priority: Option<i32>,
-        issue_type: Option<&str>,
+        issue_type: Option<IssueType>,
         plan_id: Option<&str>,
         parent_id: Option<&str>,
         actor: &str,
@@ -1366,7 +2210,7 @@ impl SqliteStorage {
         }
         if let Some(it) = issue_type {
             set_clauses.push("issue_type = ?");
-            params.push(Box::new(it.to_string()));
+            params.push(Box::new(it));
         }
         if let Some(pid) = plan_id {
             set_clauses.push("plan_id = ?");
@@ -1379,6 +2223,19 @@ impl SqliteStorage {
         }
 
         self.mutate("update_issue", actor, |tx, ctx| {
+            // Text before the update, so only newly added mentions notify
+            let old_text: Option<(String, String)> = if title.is_some() || description.is_some() || details.is_some() {
+                tx.query_row(
+                    "SELECT id, title || char(10) || COALESCE(description, '') || char(10) || COALESCE(details, '')
+                     FROM issues WHERE id = ?1 OR short_id = ?1",
+                    [id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?
+            } else {
+                None
+            };
+
             // Update the issue fields
             if set_clauses.len() > 1 {
                 let sql = format!(
@@ -1428,60 +2285,344 @@ impl SqliteStorage {
             }
 
             ctx.record_event("issue", id, EventType::IssueUpdated);
+            if let Some((full_id, old_text)) = old_text {
+                let text = [title, description, details].into_iter().flatten().collect::<Vec<_>>().join("\n");
+                record_mentions(ctx, &full_id, Some(&old_text), &text);
+            }
             ctx.mark_issue_dirty(id);
 
             Ok(())
         })
     }
 
-    /// Claim an issue (assign to agent).
+    /// Add a comment to an issue. `@actor` mentions in it notify those actors.
     ///
-    /// Accepts either full ID or short_id.
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist.
+    pub fn comment_issue(&mut self, id: &str, text: &str, actor: &str) -> Result<String> {
+        self.mutate("comment_issue", actor, |tx, ctx| {
+            let full_id = resolve_issue_id(tx, id)?;
+            ctx.events.push(
+                Event::new("issue", &full_id, EventType::IssueCommented, actor).with_comment(text),
+            );
+            record_mentions(ctx, &full_id, None, text);
+            tx.execute(
+                "UPDATE issues SET updated_at = ?1 WHERE id = ?2",
+                rusqlite::params![chrono::Utc::now().timestamp_millis(), full_id],
+            )?;
+            Ok(full_id)
+        })
+    }
+
+    /// Comments on an issue, oldest first, as `(actor, text, created_at)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_issue_comments(&self, issue_id: &str) -> Result<Vec<(String, String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT actor, comment, created_at FROM events
+             WHERE entity_type = 'issue' AND entity_id = ?1 AND event_type = 'issue_commented'
+             ORDER BY created_at, id",
+        )?;
+        let comments = stmt
+            .query_map([issue_id], |row| Ok((row.get(0)?, row.get::<_, Option<String>>(1)?.unwrap_or_default(), row.get(2)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(comments)
+    }
+
+    /// Append a worklog entry to an issue, linked to the time entry that
+    /// records its hours, if any. Returns the issue's full ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist.
+    pub fn log_issue_work(&mut self, id: &str, text: &str, time_entry_id: Option<&str>, actor: &str) -> Result<String> {
+        self.mutate("log_issue_work", actor, |tx, ctx| {
+            let full_id = resolve_issue_id(tx, id)?;
+            ctx.events.push(
+                Event::new("issue", &full_id, EventType::IssueWorkLogged, actor)
+                    .with_values(None, time_entry_id.map(ToString::to_string))
+                    .with_comment(text),
+            );
+            tx.execute(
+                "UPDATE issues SET updated_at = ?1 WHERE id = ?2",
+                rusqlite::params![chrono::Utc::now().timestamp_millis(), full_id],
+            )?;
+            Ok(full_id)
+        })
+    }
+
+    /// Worklog entries of one issue, or of every issue in `project_path`,
+    /// oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_worklog(&self, issue_id: Option<&str>, project_path: Option<&str>) -> Result<Vec<WorklogEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.id, i.short_id, e.actor, e.comment, e.new_value, t.hours, e.created_at
+             FROM events e
+             JOIN issues i ON i.id = e.entity_id
+             LEFT JOIN time_entries t ON t.id = e.new_value
+             WHERE e.entity_type = 'issue' AND e.event_type = 'issue_work_logged'
+               AND (?1 IS NULL OR i.id = ?1) AND (?2 IS NULL OR i.project_path = ?2)
+             ORDER BY e.created_at, e.id",
+        )?;
+        let entries = stmt
+            .query_map(rusqlite::params![issue_id, project_path], |row| {
+                Ok(WorklogEntry {
+                    issue_id: row.get(0)?,
+                    short_id: row.get(1)?,
+                    actor: row.get(2)?,
+                    text: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                    time_entry_id: row.get(4)?,
+                    hours: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Subscribe `watcher` to an issue's activity. Returns `false` if
+    /// already watching.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist.
+    pub fn watch_issue(&mut self, id: &str, watcher: &str, actor: &str) -> Result<bool> {
+        self.mutate("watch_issue", actor, |tx, _ctx| {
+            let full_id = resolve_issue_id(tx, id)?;
+            let watcher = alias_owner(tx, watcher)?.unwrap_or_else(|| watcher.to_string());
+            let added = tx.execute(
+                "INSERT OR IGNORE INTO issue_watchers (issue_id, actor, created_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![full_id, watcher, chrono::Utc::now().timestamp_millis()],
+            )?;
+            Ok(added > 0)
+        })
+    }
+
+    /// Stop watching an issue. Returns `false` if `watcher` wasn't watching.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist.
+    pub fn unwatch_issue(&mut self, id: &str, watcher: &str, actor: &str) -> Result<bool> {
+        self.mutate("unwatch_issue", actor, |tx, _ctx| {
+            let full_id = resolve_issue_id(tx, id)?;
+            let watcher = alias_owner(tx, watcher)?.unwrap_or_else(|| watcher.to_string());
+            let removed = tx.execute(
+                "DELETE FROM issue_watchers WHERE issue_id = ?1 AND actor = ?2",
+                rusqlite::params![full_id, watcher],
+            )?;
+            Ok(removed > 0)
+        })
+    }
+
+    /// Actors watching an issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_issue_watchers(&self, issue_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT actor FROM issue_watchers WHERE issue_id = ?1 ORDER BY created_at")?;
+        let watchers = stmt
+            .query_map([issue_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(watchers)
+    }
+
+    /// Activity by others that concerns `actor`, newest first: mentions of
+    /// `actor`, and every event on issues `actor` is assigned or watches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_inbox(
+        &self,
+        actor: &str,
+        since: i64,
+        project_path: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<InboxItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT e.id, e.event_type, e.actor,
+                    CASE
+                        WHEN e.event_type = 'issue_mentioned' THEN 'mentioned'
+                        WHEN e.event_type = 'issue_claim_expired' OR i.assigned_to_agent = ?1 THEN 'assigned'
+                        ELSE 'watching'
+                    END,
+                    i.id, i.short_id, i.title, i.status, e.old_value, e.new_value, e.comment, e.created_at
+             FROM events e
+             JOIN issues i ON e.entity_type = 'issue' AND (e.entity_id = i.id OR e.entity_id = i.short_id)
+             WHERE e.created_at > ?2
+               AND e.actor <> ?1
+               AND (?3 IS NULL OR i.project_path = ?3)
+               AND CASE
+                       WHEN e.event_type = 'issue_mentioned' THEN e.new_value = ?1 COLLATE NOCASE
+                       WHEN e.event_type = 'issue_claim_expired' THEN e.new_value = ?1
+                       ELSE i.assigned_to_agent = ?1
+                            OR EXISTS (SELECT 1 FROM issue_watchers w WHERE w.issue_id = i.id AND w.actor = ?1)
+                   END
+             ORDER BY e.created_at DESC, e.id DESC
+             LIMIT ?4",
+        )?;
+        let items = stmt
+            .query_map(
+                rusqlite::params![actor, since, project_path, i64::try_from(limit).unwrap_or(i64::MAX)],
+                |row| {
+                    Ok(InboxItem {
+                        event_id: row.get(0)?,
+                        event_type: row.get(1)?,
+                        actor: row.get(2)?,
+                        reason: row.get(3)?,
+                        issue_id: row.get(4)?,
+                        short_id: row.get(5)?,
+                        title: row.get(6)?,
+                        status: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                        old_value: row.get(8)?,
+                        new_value: row.get(9)?,
+                        comment: row.get(10)?,
+                        created_at: row.get(11)?,
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // A comment that mentions the actor is already listed as the mention
+        let mentions: Vec<(String, String, String)> = items
+            .iter()
+            .filter(|i| i.event_type == "issue_mentioned")
+            .map(|i| (i.issue_id.clone(), i.actor.clone(), i.comment.clone().unwrap_or_default()))
+            .collect();
+        let items = items
+            .into_iter()
+            .filter(|i| {
+                i.event_type != "issue_commented"
+                    || !mentions.iter().any(|(issue, by, text)| {
+                        *issue == i.issue_id
+                            && *by == i.actor
+                            && i.comment.as_deref().is_some_and(|c| c.starts_with(text.as_str()))
+                    })
+            })
+            .collect();
+        Ok(items)
+    }
+
+    /// When `actor` last marked their inbox read (Unix ms).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_inbox_read_at(&self, actor: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row("SELECT read_at FROM inbox_reads WHERE actor = ?1", [actor], |row| row.get(0))
+            .optional()?)
+    }
+
+    /// Mark `actor`'s inbox read up to `read_at` (Unix ms).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn mark_inbox_read(&mut self, actor: &str, read_at: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO inbox_reads (actor, read_at) VALUES (?1, ?2)
+             ON CONFLICT(actor) DO UPDATE SET read_at = MAX(read_at, excluded.read_at)",
+            rusqlite::params![actor, read_at],
+        )?;
+        Ok(())
+    }
+
+    /// Claim an issue (assign to agent and move it to `in_progress`).
+    ///
+    /// Accepts either full ID or short_id. The move to `in_progress` must be
+    /// allowed by `workflow`, as for [`Self::update_issue_status`].
     ///
     /// # Errors
     ///
-    /// Returns an error if the claim fails.
-    pub fn claim_issue(&mut self, id: &str, actor: &str) -> Result<()> {
+    /// Returns `InvalidTransition` or `RequiredField` if the workflow
+    /// forbids the claim, or an error if the claim fails.
+    pub fn claim_issue(&mut self, id: &str, workflow: &IssueWorkflow, actor: &str) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
 
         self.mutate("claim_issue", actor, |tx, ctx| {
-            let rows = tx.execute(
-                "UPDATE issues SET assigned_to_agent = ?1, assigned_at = ?2, status = 'in_progress', updated_at = ?2 WHERE id = ?3 OR short_id = ?3",
-                rusqlite::params![actor, now, id],
-            )?;
+            let (full_id, old_status, mut fields) = load_issue_state(tx, id)?;
+            fields.insert("assignee", Some(actor.to_string()));
+            check_transition(workflow, &old_status, IssueStatus::InProgress.as_str(), &fields)?;
 
-            if rows == 0 {
-                return Err(Error::IssueNotFound { id: id.to_string() });
-            }
+            tx.execute(
+                "UPDATE issues SET assigned_to_agent = ?1, assigned_at = ?2, status = 'in_progress', updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![actor, now, full_id],
+            )?;
+            // A new claim needs a new lease, if leases are on
+            crate::storage::leases::drop_lease(tx, &full_id)?;
 
-            ctx.record_event("issue", id, EventType::IssueClaimed);
-            ctx.mark_issue_dirty(id);
+            ctx.record_event("issue", &full_id, EventType::IssueClaimed);
+            ctx.mark_issue_dirty(&full_id);
 
             Ok(())
         })
     }
 
-    /// Release an issue (unassign).
+    /// Release an issue (unassign and move it back to `open`).
     ///
-    /// Accepts either full ID or short_id.
+    /// Accepts either full ID or short_id. The move to `open` must be
+    /// allowed by `workflow`, as for [`Self::update_issue_status`].
     ///
     /// # Errors
     ///
-    /// Returns an error if the release fails.
-    pub fn release_issue(&mut self, id: &str, actor: &str) -> Result<()> {
+    /// Returns `InvalidTransition` or `RequiredField` if the workflow
+    /// forbids the release, or an error if the release fails.
+    pub fn release_issue(&mut self, id: &str, workflow: &IssueWorkflow, actor: &str) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
 
         self.mutate("release_issue", actor, |tx, ctx| {
+            let (full_id, old_status, mut fields) = load_issue_state(tx, id)?;
+            fields.insert("assignee", None);
+            check_transition(workflow, &old_status, IssueStatus::Open.as_str(), &fields)?;
+
+            tx.execute(
+                "UPDATE issues SET assigned_to_agent = NULL, assigned_at = NULL, status = 'open', updated_at = ?1 WHERE id = ?2",
+                rusqlite::params![now, full_id],
+            )?;
+            crate::storage::leases::drop_lease(tx, &full_id)?;
+
+            ctx.record_event("issue", &full_id, EventType::IssueReleased);
+            ctx.mark_issue_dirty(&full_id);
+
+            Ok(())
+        })
+    }
+
+    /// Assign an issue to `assignee`, or unassign it with `None`, without
+    /// changing its status the way a claim does.
+    ///
+    /// Accepts either full ID or `short_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist or the update fails.
+    pub fn assign_issue(&mut self, id: &str, assignee: Option<&str>, actor: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        self.mutate("assign_issue", actor, |tx, ctx| {
             let rows = tx.execute(
-                "UPDATE issues SET assigned_to_agent = NULL, assigned_at = NULL, status = 'open', updated_at = ?1 WHERE id = ?2 OR short_id = ?2",
-                rusqlite::params![now, id],
+                "UPDATE issues SET assigned_to_agent = ?1, assigned_at = CASE WHEN ?1 IS NULL THEN NULL ELSE ?2 END, updated_at = ?2
+                 WHERE id = ?3 OR short_id = ?3",
+                rusqlite::params![assignee, now, id],
             )?;
 
             if rows == 0 {
                 return Err(Error::IssueNotFound { id: id.to_string() });
             }
 
-            ctx.record_event("issue", id, EventType::IssueReleased);
+            ctx.record_event("issue", id, EventType::IssueUpdated);
             ctx.mark_issue_dirty(id);
 
             Ok(())
@@ -1498,16 +2639,18 @@ impl SqliteStorage {
     pub fn delete_issue(&mut self, id: &str, actor: &str) -> Result<()> {
         self.mutate("delete_issue", actor, |tx, ctx| {
             // First get the full issue ID and project_path
-            let info: Option<(String, String)> = tx
+            let info: Option<(String, String, String)> = tx
                 .query_row(
-                    "SELECT id, project_path FROM issues WHERE id = ?1 OR short_id = ?1",
+                    "SELECT id, project_path, COALESCE(short_id || ' ', '') || title
+                     FROM issues WHERE id = ?1 OR short_id = ?1",
                     [id],
-                    |row| Ok((row.get(0)?, row.get(1)?)),
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
                 )
                 .optional()?;
 
-            let (full_id, project_path) =
+            let (full_id, project_path, label) =
                 info.ok_or_else(|| Error::IssueNotFound { id: id.to_string() })?;
+            trash::capture(tx, "issue", &full_id, &label, Some(&project_path), &ctx.actor)?;
 
             // Delete dependencies using full ID
             tx.execute(
@@ -1540,15 +2683,56 @@ impl SqliteStorage {
         })
     }
 
-    /// Add labels to an issue.
+    /// List trashed items, issues and sessions, newest first.
     ///
     /// # Errors
     ///
-    /// Returns an error if the operation fails.
-    pub fn add_issue_labels(&mut self, id: &str, labels: &[String], actor: &str) -> Result<()> {
-        self.mutate("add_issue_labels", actor, |tx, ctx| {
-            // Get full issue ID
-            let full_id: String = tx
+    /// Returns an error if `entity_type` is unknown or the query fails.
+    pub fn list_trash(&self, entity_type: Option<&str>, limit: usize) -> Result<Vec<TrashEntry>> {
+        trash::list(&self.conn, entity_type, limit)
+    }
+
+    /// Restore a trash entry, by trash ID, the deleted entity's ID, an
+    /// issue's short ID or an item's key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no entry matches, or if the entity can't go back
+    /// (e.g. an item's key was reused, or its session is gone).
+    pub fn restore_trash(&mut self, id: &str, actor: &str) -> Result<TrashRestore> {
+        self.mutate("restore_trash", actor, |tx, ctx| {
+            let (entry, rows) = trash::find(tx, id)?
+                .ok_or_else(|| Error::InvalidArgument(format!("No trash entry matches '{id}'")))?;
+            let event = match entry.entity_type.as_str() {
+                "context_item" => EventType::ItemRestored,
+                "issue" => EventType::IssueRestored,
+                _ => EventType::SessionRestored,
+            };
+            let restored = trash::restore(tx, entry, &rows)?;
+            ctx.record_event(&restored.entry.entity_type, &restored.entry.entity_id, event);
+            Ok(restored)
+        })
+    }
+
+    /// Permanently delete trash entries older than `before` (Unix ms), or
+    /// all entries when `before` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn empty_trash(&mut self, before: Option<i64>) -> Result<usize> {
+        trash::purge(&self.conn, before)
+    }
+
+    /// Add labels to an issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation fails.
+    pub fn add_issue_labels(&mut self, id: &str, labels: &[String], actor: &str) -> Result<()> {
+        self.mutate("add_issue_labels", actor, |tx, ctx| {
+            // Get full issue ID
+            let full_id: String = tx
                 .query_row(
                     "SELECT id FROM issues WHERE id = ?1 OR short_id = ?1",
                     [id],
@@ -1693,6 +2877,86 @@ impl SqliteStorage {
         Ok(ids)
     }
 
+    /// Issues that `id` depends on, with the dependency type, e.g. its
+    /// parent (`parent-child`) and its blockers (`blocks`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist or the query fails.
+    pub fn get_issue_dependencies(&self, id: &str) -> Result<Vec<IssueLink>> {
+        self.issue_links(id, "d.issue_id = ?1", "d.depends_on_id")
+    }
+
+    /// Issues that depend on `id`, with the dependency type, e.g. its
+    /// children (`parent-child`) and the issues it blocks (`blocks`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist or the query fails.
+    pub fn get_issue_dependents(&self, id: &str) -> Result<Vec<IssueLink>> {
+        self.issue_links(id, "d.depends_on_id = ?1", "d.issue_id")
+    }
+
+    fn issue_links(&self, id: &str, condition: &str, other: &str) -> Result<Vec<IssueLink>> {
+        let full_id = resolve_issue_id(&self.conn, id)?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT i.id, i.short_id, i.title, i.status, i.priority, d.dependency_type
+             FROM issue_dependencies d
+             JOIN issues i ON i.id = {other}
+             WHERE {condition}
+             ORDER BY d.dependency_type, i.priority DESC, i.created_at"
+        ))?;
+        let links = stmt
+            .query_map([&full_id], |row| {
+                Ok(IssueLink {
+                    id: row.get(0)?,
+                    short_id: row.get(1)?,
+                    title: row.get(2)?,
+                    status: row.get(3)?,
+                    priority: row.get(4)?,
+                    dependency_type: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(links)
+    }
+
+    /// Status changes of the project's issues from the event log, oldest
+    /// first. Claims count as moves to `in_progress` and releases as moves
+    /// to `open`; pruned events are missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_issue_status_changes(&self, project_path: &str) -> Result<Vec<IssueStatusChange>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.id, CASE e.event_type WHEN 'issue_claim_expired' THEN 'in_progress' ELSE e.old_value END,
+                    CASE e.event_type WHEN 'issue_claimed' THEN 'in_progress'
+                                      WHEN 'issue_released' THEN 'open'
+                                      WHEN 'issue_claim_expired' THEN 'open'
+                                      ELSE e.new_value END,
+                    e.actor, e.created_at
+             FROM events e
+             JOIN issues i ON e.entity_id = i.id OR e.entity_id = i.short_id
+             WHERE e.entity_type = 'issue' AND i.project_path = ?1
+               AND (e.event_type IN ('issue_claimed', 'issue_released', 'issue_claim_expired')
+                    OR (e.event_type IN ('issue_updated', 'issue_closed') AND e.new_value IS NOT NULL))
+             ORDER BY e.created_at, e.id",
+        )?;
+        let changes = stmt
+            .query_map([project_path], |row| {
+                Ok(IssueStatusChange {
+                    issue_id: row.get(0)?,
+                    from: row.get(1)?,
+                    to: row.get(2)?,
+                    actor: row.get(3)?,
+                    at: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(changes)
+    }
+
     /// Add a dependency between issues.
     ///
     /// # Errors
@@ -1805,7 +3069,7 @@ impl SqliteStorage {
             .ok_or_else(|| Error::IssueNotFound { id: id.to_string() })?;
 
         let new_id = format!("issue_{}", &uuid::Uuid::new_v4().to_string()[..12]);
-        let new_short_id = generate_short_id();
+        let new_short_id = self.next_issue_short_id()?;
         let default_title = format!("Copy of {}", source.title);
         let title = new_title.unwrap_or(&default_title);
         let now = chrono::Utc::now().timestamp_millis();
@@ -1861,13 +3125,15 @@ impl SqliteStorage {
         &mut self,
         id: &str,
         duplicate_of_id: &str,
+        workflow: &IssueWorkflow,
         actor: &str,
     ) -> Result<()> {
         // Add duplicate-of dependency
         self.add_issue_dependency(id, duplicate_of_id, "duplicate-of", actor)?;
 
-        // Close the issue
-        self.update_issue_status(id, "closed", actor)?;
+        // Close the issue, recording the duplicate as the close reason
+        let reason = format!("Duplicate of {duplicate_of_id}");
+        self.update_issue_status(id, &IssueStatus::Closed, Some(&reason), workflow, actor)?;
 
         Ok(())
     }
@@ -1881,7 +3147,7 @@ impl SqliteStorage {
         let mut stmt = self.conn.prepare(
             "SELECT i.id, i.short_id, i.project_path, i.title, i.description, i.details,
                     i.status, i.priority, i.issue_type, i.plan_id, i.created_by_agent,
-                    i.assigned_to_agent, i.created_at, i.updated_at, i.closed_at
+                    i.assigned_to_agent, i.created_at, i.updated_at, i.closed_at, i.subproject, i.estimate_hours
              FROM issues i
              WHERE i.project_path = ?1
                AND i.status = 'open'
@@ -1915,6 +3181,8 @@ impl SqliteStorage {
                     created_at: row.get(12)?,
                     updated_at: row.get(13)?,
                     closed_at: row.get(14)?,
+                    subproject: row.get(15)?,
+                    estimate_hours: row.get(16)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -1922,6 +3190,116 @@ impl SqliteStorage {
         Ok(issues)
     }
 
+    /// Unfinished issues of a project grouped into waves by their `blocks`
+    /// dependencies.
+    ///
+    /// Wave 0 holds the issues nothing open blocks; each later wave holds
+    /// the issues blocked only by issues in earlier waves, so the issues of
+    /// one wave can be worked on in parallel once the waves before it are
+    /// done. Flattened, the waves are a topological order of the project's
+    /// work. Issues in a cycle, or waiting on an issue that is deferred or
+    /// belongs to another project, can't be placed and are returned apart.
+    /// Closed and deferred issues are left out; within a wave, issues are in
+    /// ready order (priority, then age).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_issue_waves(&self, project_path: &str) -> Result<IssueWaves> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, short_id, project_path, title, description, details,
+                    status, priority, issue_type, plan_id, created_by_agent,
+                    assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
+             FROM issues
+             WHERE project_path = ?1 AND status NOT IN ('closed', 'deferred')
+             ORDER BY priority DESC, created_at ASC",
+        )?;
+        let issues = stmt
+            .query_map([project_path], map_issue_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let edges = self.get_blocking_edges(project_path)?;
+        let ids: Vec<&str> = issues.iter().map(|issue| issue.id.as_str()).collect();
+        let (layers, stuck) = crate::model::schedule::waves(&ids, &edges);
+        let mut issues: Vec<Option<Issue>> = issues.into_iter().map(Some).collect();
+        let mut take = |indexes: Vec<usize>| -> Vec<Issue> {
+            indexes.into_iter().filter_map(|index| issues[index].take()).collect()
+        };
+        let waves = layers.into_iter().map(&mut take).collect();
+        Ok(IssueWaves { waves, unscheduled: take(stuck) })
+    }
+
+    /// Open `blocks` dependencies of a project's issues, as (blocked,
+    /// blocker) ID pairs. Dependencies on closed issues are done and left
+    /// out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_blocking_edges(&self, project_path: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT d.issue_id, d.depends_on_id
+             FROM issue_dependencies d
+             JOIN issues i ON i.id = d.issue_id
+             JOIN issues dep ON dep.id = d.depends_on_id
+             WHERE i.project_path = ?1
+               AND d.dependency_type = 'blocks'
+               AND dep.status != 'closed'",
+        )?;
+        let edges = stmt
+            .query_map([project_path], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(edges)
+    }
+
+    /// In-progress issues and latest assignment per assignee, for routing.
+    ///
+    /// Assignees are as stored, so aliases of one actor come back apart.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_agent_workload(&self, project_path: &str) -> Result<Vec<crate::model::AgentWorkload>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT assigned_to_agent, SUM(status = 'in_progress'), MAX(assigned_at)
+             FROM issues
+             WHERE project_path = ?1 AND assigned_to_agent IS NOT NULL
+             GROUP BY assigned_to_agent",
+        )?;
+        let rows = stmt.query_map([project_path], |row| {
+            Ok(crate::model::AgentWorkload {
+                agent: row.get(0)?,
+                in_progress: row.get(1)?,
+                last_assigned_at: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    /// Issues in progress and assigned to someone in a project, for
+    /// claim policies. Actors are as recorded, not resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_claims(&self, project_path: &str) -> Result<Vec<crate::model::Claim>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT assigned_to_agent, id, short_id, updated_at
+             FROM issues
+             WHERE project_path = ?1 AND status = 'in_progress' AND assigned_to_agent IS NOT NULL
+             ORDER BY updated_at",
+        )?;
+        let rows = stmt.query_map([project_path], |row| {
+            Ok(crate::model::Claim {
+                actor: row.get(0)?,
+                issue_id: row.get(1)?,
+                short_id: row.get(2)?,
+                updated_at: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
     /// Get and claim next block of ready issues.
     ///
     /// # Errors
@@ -1931,12 +3309,13 @@ impl SqliteStorage {
         &mut self,
         project_path: &str,
         count: u32,
+        workflow: &IssueWorkflow,
         actor: &str,
     ) -> Result<Vec<Issue>> {
         let ready = self.get_ready_issues(project_path, count)?;
 
         for issue in &ready {
-            self.claim_issue(&issue.id, actor)?;
+            self.claim_issue(&issue.id, workflow, actor)?;
         }
 
         // Return claimed issues with updated status
@@ -1994,7 +3373,7 @@ impl SqliteStorage {
         let mut stmt = self.conn.prepare(
             "SELECT id, short_id, project_path, title, description, details,
                     status, priority, issue_type, plan_id, created_by_agent,
-                    assigned_to_agent, created_at, updated_at, closed_at
+                    assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues
              WHERE project_path = ?1
                AND status IN ('open', 'in_progress', 'blocked')
@@ -2010,6 +3389,40 @@ impl SqliteStorage {
         Ok(issues)
     }
 
+    /// Get issues waiting for `sc issue triage`: open ones carrying `label`,
+    /// or in `status` if given. Oldest first, so the queue is worked in the
+    /// order issues arrived.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_untriaged_issues(
+        &self,
+        project_path: &str,
+        label: &str,
+        status: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<Issue>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.id, i.short_id, i.project_path, i.title, i.description, i.details,
+                    i.status, i.priority, i.issue_type, i.plan_id, i.created_by_agent,
+                    i.assigned_to_agent, i.created_at, i.updated_at, i.closed_at, i.subproject, i.estimate_hours
+             FROM issues i
+             WHERE i.project_path = ?1
+               AND i.status != 'closed'
+               AND (i.status = ?3
+                    OR EXISTS (SELECT 1 FROM issue_labels l WHERE l.issue_id = i.id AND l.label = ?2))
+             ORDER BY i.created_at ASC
+             LIMIT ?4",
+        )?;
+
+        let issues = stmt
+            .query_map(rusqlite::params![project_path, label, status, limit], map_issue_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(issues)
+    }
+
     /// Get blocked issues with their blockers.
     pub fn get_blocked_issues(
         &self,
@@ -2019,7 +3432,7 @@ impl SqliteStorage {
         let mut stmt = self.conn.prepare(
             "SELECT i.id, i.short_id, i.project_path, i.title, i.description, i.details,
                     i.status, i.priority, i.issue_type, i.plan_id, i.created_by_agent,
-                    i.assigned_to_agent, i.created_at, i.updated_at, i.closed_at
+                    i.assigned_to_agent, i.created_at, i.updated_at, i.closed_at, i.subproject, i.estimate_hours
              FROM issues i
              WHERE i.project_path = ?1
                AND i.status NOT IN ('closed', 'deferred')
@@ -2041,7 +3454,7 @@ impl SqliteStorage {
         let mut blocker_stmt = self.conn.prepare(
             "SELECT dep.id, dep.short_id, dep.project_path, dep.title, dep.description, dep.details,
                     dep.status, dep.priority, dep.issue_type, dep.plan_id, dep.created_by_agent,
-                    dep.assigned_to_agent, dep.created_at, dep.updated_at, dep.closed_at
+                    dep.assigned_to_agent, dep.created_at, dep.updated_at, dep.closed_at, dep.subproject, dep.estimate_hours
              FROM issue_dependencies d
              JOIN issues dep ON dep.id = d.depends_on_id
              WHERE d.issue_id = ?1
@@ -2111,7 +3524,7 @@ impl SqliteStorage {
                     child.description, child.details, child.status, child.priority,
                     child.issue_type, child.plan_id, child.created_by_agent,
                     child.assigned_to_agent, child.created_at, child.updated_at,
-                    child.closed_at
+                    child.closed_at, child.subproject, child.estimate_hours
              FROM issue_dependencies d
              JOIN issues child ON child.id = d.issue_id
              WHERE d.depends_on_id = ?1
@@ -2141,7 +3554,7 @@ impl SqliteStorage {
         let mut stmt = self.conn.prepare(
             "SELECT id, short_id, project_path, title, description, details,
                     status, priority, issue_type, plan_id, created_by_agent,
-                    assigned_to_agent, created_at, updated_at, closed_at
+                    assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues
              WHERE project_path = ?1
                AND issue_type = 'epic'
@@ -2176,6 +3589,27 @@ impl SqliteStorage {
         })
     }
 
+    /// Set or clear the estimated hours of an issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issue doesn't exist or the update fails.
+    pub fn set_issue_estimate(&mut self, id: &str, hours: Option<f64>, actor: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.mutate("set_issue_estimate", actor, |tx, ctx| {
+            let rows = tx.execute(
+                "UPDATE issues SET estimate_hours = ?1, updated_at = ?2 WHERE id = ?3 OR short_id = ?3",
+                rusqlite::params![hours, now, id],
+            )?;
+            if rows == 0 {
+                return Err(Error::IssueNotFound { id: id.to_string() });
+            }
+            ctx.record_event("issue", id, EventType::IssueUpdated);
+            ctx.mark_issue_dirty(id);
+            Ok(())
+        })
+    }
+
     /// Get close_reason for an issue.
     pub fn get_close_reason(&self, id: &str) -> Result<Option<String>> {
         let result = self.conn.query_row(
@@ -2248,6 +3682,110 @@ impl SqliteStorage {
         })
     }
 
+    /// Snapshot the items of a session into a new checkpoint, in one
+    /// transaction: every item, or those on `channel`. The checkpoint
+    /// records the session's branch. Returns the number of items captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    pub fn create_snapshot_checkpoint(
+        &mut self,
+        id: &str,
+        session_id: &str,
+        name: &str,
+        description: Option<&str>,
+        channel: Option<&str>,
+        actor: &str,
+    ) -> Result<usize> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        self.mutate("create_snapshot_checkpoint", actor, |tx, ctx| {
+            tx.execute(
+                "INSERT INTO checkpoints (id, session_id, name, description, git_branch, item_count, total_size, created_at)
+                 SELECT ?1, ?2, ?3, ?4, (SELECT branch FROM sessions WHERE id = ?2), COUNT(*), COALESCE(SUM(size), 0), ?5
+                 FROM context_items WHERE session_id = ?2 AND (?6 IS NULL OR channel = ?6)",
+                rusqlite::params![id, session_id, name, description, now, channel],
+            )?;
+            let items = tx.execute(
+                "INSERT INTO checkpoint_items (id, checkpoint_id, context_item_id)
+                 SELECT 'cpitem_' || lower(hex(randomblob(6))), ?1, id
+                 FROM context_items WHERE session_id = ?2 AND (?3 IS NULL OR channel = ?3)",
+                rusqlite::params![id, session_id, channel],
+            )?;
+
+            ctx.record_event("checkpoint", id, EventType::CheckpointCreated);
+
+            Ok(items)
+        })
+    }
+
+    /// Active sessions that need an automatic checkpoint named `name`:
+    /// they have items, don't have a checkpoint by that name yet, and
+    /// changed since their last automatic checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_sessions_due_auto_checkpoint(&self, name: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.id FROM sessions s
+             WHERE s.status = 'active'
+               AND NOT EXISTS (SELECT 1 FROM checkpoints c WHERE c.session_id = s.id AND c.name = ?1)
+               AND (SELECT MAX(ci.updated_at) FROM context_items ci WHERE ci.session_id = s.id)
+                   > COALESCE((SELECT MAX(c.created_at) FROM checkpoints c
+                               WHERE c.session_id = s.id AND c.name LIKE 'auto-%'), 0)
+             ORDER BY s.updated_at DESC",
+        )?;
+        let ids = stmt
+            .query_map([name], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Automatic (`auto-*`) checkpoints beyond the newest `keep` of each
+    /// session, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_prunable_auto_checkpoints(
+        &self,
+        session_id: Option<&str>,
+        keep: usize,
+    ) -> Result<Vec<Checkpoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, name, description, git_status, git_branch, created_at, item_count
+             FROM (
+                 SELECT c.id, c.session_id, c.name, c.description, c.git_status, c.git_branch, c.created_at,
+                        (SELECT COUNT(*) FROM checkpoint_items ci WHERE ci.checkpoint_id = c.id) AS item_count,
+                        ROW_NUMBER() OVER (PARTITION BY c.session_id ORDER BY c.created_at DESC, c.id DESC) AS rank
+                 FROM checkpoints c
+                 WHERE c.name LIKE 'auto-%' AND (?1 IS NULL OR c.session_id = ?1)
+             )
+             WHERE rank > ?2
+             ORDER BY created_at",
+        )?;
+        let checkpoints = stmt
+            .query_map(
+                rusqlite::params![session_id, i64::try_from(keep).unwrap_or(i64::MAX)],
+                |row| {
+                    Ok(Checkpoint {
+                        id: row.get(0)?,
+                        session_id: row.get(1)?,
+                        name: row.get(2)?,
+                        description: row.get(3)?,
+                        git_status: row.get(4)?,
+                        git_branch: row.get(5)?,
+                        created_at: row.get(6)?,
+                        item_count: row.get(7)?,
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(checkpoints)
+    }
+
     /// Count context items created since the most recent checkpoint for a session.
     ///
     /// Returns 0 if no items exist. If no checkpoint exists, counts all items.
@@ -2405,7 +3943,7 @@ impl SqliteStorage {
     pub fn get_checkpoint_items(&self, checkpoint_id: &str) -> Result<Vec<ContextItem>> {
         let mut stmt = self.conn.prepare(
             "SELECT ci.id, ci.session_id, ci.key, ci.value, ci.category, ci.priority,
-                    ci.channel, ci.tags, ci.size, ci.created_at, ci.updated_at
+                    ci.channel, ci.tags, ci.size, ci.created_at, ci.updated_at, ci.source, ci.trust, ci.review_status, ci.due_at, ci.done_at
              FROM context_items ci
              JOIN checkpoint_items cpi ON cpi.context_item_id = ci.id
              WHERE cpi.checkpoint_id = ?1
@@ -2417,7 +3955,7 @@ impl SqliteStorage {
                 id: row.get(0)?,
                 session_id: row.get(1)?,
                 key: row.get(2)?,
-                value: row.get(3)?,
+                value: read_value(row, 3)?,
                 category: row.get(4)?,
                 priority: row.get(5)?,
                 channel: row.get(6)?,
@@ -2425,6 +3963,11 @@ impl SqliteStorage {
                 size: row.get(8)?,
                 created_at: row.get(9)?,
                 updated_at: row.get(10)?,
+                source: row.get(11)?,
+                trust: row.get(12)?,
+                review_status: row.get(13)?,
+                due_at: row.get(14)?,
+                done_at: row.get(15)?,
             })
         })?;
 
@@ -2453,7 +3996,7 @@ impl SqliteStorage {
 
         // Apply category filter
         if let Some(categories) = restore_categories {
-            items.retain(|item| categories.contains(&item.category));
+            items.retain(|item| categories.iter().any(|c| c == item.category.as_str()));
         }
 
         // Apply tag filter
@@ -2470,6 +4013,7 @@ impl SqliteStorage {
         }
 
         let now = chrono::Utc::now().timestamp_millis();
+        let threshold = self.compression_threshold;
 
         self.mutate("restore_checkpoint", actor, |tx, ctx| {
             // Clear existing context items in target session
@@ -2479,27 +4023,38 @@ impl SqliteStorage {
             )?;
 
             // Restore items
+            let categories = crate::storage::categories::for_session(tx, target_session_id)?;
             let mut restored = 0;
             for item in &items {
                 let new_id = uuid::Uuid::new_v4().to_string();
                 let size = item.value.len() as i64;
+                let (stored, compressed, encrypted) =
+                    store_value(tx, &item.value, categories.encrypted(&item.category), threshold)?;
 
                 tx.execute(
-                    "INSERT INTO context_items (id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+                    "INSERT INTO context_items (id, session_id, key, value, value_compressed, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at, value_encrypted)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
                     rusqlite::params![
                         new_id,
                         target_session_id,
                         item.key,
-                        item.value,
+                        stored,
+                        compressed,
                         item.category,
                         item.priority,
                         item.channel,
                         item.tags,
                         size,
                         now,
+                        item.source,
+                        item.trust,
+                        item.review_status,
+                        item.due_at,
+                        item.done_at,
+                        encrypted,
                     ],
                 )?;
+                crate::storage::encryption::skip_embeddings(tx, &new_id, encrypted)?;
 
                 ctx.record_event("context_item", &new_id, EventType::ItemCreated);
                 restored += 1;
@@ -2592,82 +4147,384 @@ impl SqliteStorage {
         Ok(removed)
     }
 
-    // =================
-    // Memory Operations
-    // =================
+    // ======================
+    // Item Access Operations
+    // ======================
 
-    /// Save a memory item (project-level persistent storage).
+    /// Count a read of each of `item_ids`: returned by `sc get` or
+    /// included by `sc prime`. IDs of items that don't exist are ignored.
     ///
     /// # Errors
     ///
-    /// Returns an error if the operation fails.
-    #[allow(clippy::too_many_arguments)]
-    pub fn save_memory(
-        &mut self,
-        id: &str,
-        project_path: &str,
-        key: &str,
-        value: &str,
-        category: &str,
-        actor: &str,
-    ) -> Result<()> {
-        let now = chrono::Utc::now().timestamp_millis();
-
-        self.mutate("save_memory", actor, |tx, ctx| {
-            tx.execute(
-                "INSERT INTO project_memory (id, project_path, key, value, category, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
-                 ON CONFLICT(project_path, key) DO UPDATE SET
-                   value = excluded.value,
-                   category = excluded.category,
-                   updated_at = excluded.updated_at",
-                rusqlite::params![id, project_path, key, value, category, now],
-            )?;
-
-            ctx.record_event("memory", id, EventType::MemorySaved);
-
-            Ok(())
-        })
+    /// Returns an error if the write fails.
+    pub fn record_item_access(&self, item_ids: &[String]) -> Result<()> {
+        crate::storage::access::record(&self.conn, item_ids, chrono::Utc::now().timestamp_millis())
     }
 
-    /// Get a memory item by key.
+    /// Read counts and last-read times of those of `item_ids` read before.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn get_memory(&self, project_path: &str, key: &str) -> Result<Option<Memory>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, project_path, key, value, category, created_at, updated_at
-             FROM project_memory WHERE project_path = ?1 AND key = ?2",
-        )?;
-
-        let memory = stmt
-            .query_row(rusqlite::params![project_path, key], |row| {
-                Ok(Memory {
-                    id: row.get(0)?,
-                    project_path: row.get(1)?,
-                    key: row.get(2)?,
-                    value: row.get(3)?,
-                    category: row.get(4)?,
-                    created_at: row.get(5)?,
-                    updated_at: row.get(6)?,
-                })
-            })
-            .optional()?;
+    pub fn get_item_access(&self, item_ids: &[String]) -> Result<HashMap<String, crate::model::ItemAccess>> {
+        crate::storage::access::load(&self.conn, item_ids)
+    }
 
-        Ok(memory)
+    /// Count a read of each of `memory_ids`: fetched by `sc memory get` or
+    /// shown by `sc prime`. IDs of memory that doesn't exist are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn record_memory_access(&self, memory_ids: &[String]) -> Result<()> {
+        crate::storage::access::record_memory(&self.conn, memory_ids, chrono::Utc::now().timestamp_millis())
     }
 
-    /// List memory items for a project.
+    /// Read counts and last-read times of those of `memory_ids` read before.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn list_memory(
-        &self,
-        project_path: &str,
-        category: Option<&str>,
-    ) -> Result<Vec<Memory>> {
+    pub fn get_memory_access(&self, memory_ids: &[String]) -> Result<HashMap<String, crate::model::ItemAccess>> {
+        crate::storage::access::load_memory(&self.conn, memory_ids)
+    }
+
+    /// Record the transcript position an item was saved at, or clear it
+    /// (`None`) when it was saved outside a conversation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn set_item_provenance(&self, item_id: &str, provenance: Option<&crate::storage::ItemProvenance>) -> Result<()> {
+        crate::storage::provenance::set(&self.conn, item_id, provenance)
+    }
+
+    /// The transcript position an item was saved at, if recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_item_provenance(&self, item_id: &str) -> Result<Option<crate::storage::ItemProvenance>> {
+        crate::storage::provenance::load(&self.conn, item_id)
+    }
+
+    // ==========================
+    // Agent Presence Operations
+    // ==========================
+
+    /// Record that `agent_id` is working in `session_id` now, and renew
+    /// the claim leases it holds. Records no presence if the session
+    /// doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn record_agent_presence(&self, agent_id: &str, session_id: &str, provider: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        crate::storage::presence::record(&self.conn, agent_id, session_id, provider, now)?;
+        crate::storage::leases::renew(&self.conn, agent_id, now)?;
+        Ok(())
+    }
+
+    /// Lease a claimed issue to `holder` (a canonical actor) for `lease_ms`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn grant_claim_lease(&self, issue_id: &str, holder: &str, lease_ms: i64) -> Result<()> {
+        crate::storage::leases::grant(&self.conn, issue_id, holder, lease_ms, chrono::Utc::now().timestamp_millis())
+    }
+
+    /// The lease on a claimed issue, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_claim_lease(&self, issue_id: &str) -> Result<Option<crate::storage::ClaimLease>> {
+        crate::storage::leases::load(&self.conn, issue_id)
+    }
+
+    /// Claims in a project whose lease ran out, without releasing them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_expired_claims(&self, project_path: &str) -> Result<Vec<crate::storage::ExpiredClaim>> {
+        crate::storage::leases::expired(&self.conn, project_path, chrono::Utc::now().timestamp_millis())
+    }
+
+    /// Release the claims in a project whose lease ran out, recording an
+    /// `issue_claim_expired` event for each. With `notify` the event names
+    /// the holder, so it shows up in that actor's `sc inbox`.
+    ///
+    /// Claims the project's `workflow` doesn't let go back to `open` are
+    /// left in place and not returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the actor may not release claims or the write
+    /// fails.
+    pub fn release_expired_claims(
+        &mut self,
+        project_path: &str,
+        notify: bool,
+        workflow: &IssueWorkflow,
+        actor: &str,
+    ) -> Result<Vec<crate::storage::ExpiredClaim>> {
+        let now = chrono::Utc::now().timestamp_millis();
+        // Nothing to do is the common case; don't take the write lock for it
+        let expired = crate::storage::leases::expired(&self.conn, project_path, now)?;
+        if expired.is_empty() {
+            return Ok(expired);
+        }
+
+        self.mutate("release_expired_claims", actor, |tx, ctx| {
+            let expired = crate::storage::leases::expired(tx, project_path, now)?;
+            let mut released = Vec::with_capacity(expired.len());
+            for claim in expired {
+                let (_, old_status, mut fields) = load_issue_state(tx, &claim.issue_id)?;
+                fields.insert("assignee", None);
+                if let Err(e) = check_transition(workflow, &old_status, IssueStatus::Open.as_str(), &fields) {
+                    tracing::warn!(issue = %claim.issue_id, error = %e, "Expired claim not released");
+                    continue;
+                }
+                tx.execute(
+                    "UPDATE issues SET assigned_to_agent = NULL, assigned_at = NULL, status = 'open', updated_at = ?1 WHERE id = ?2",
+                    rusqlite::params![now, claim.issue_id],
+                )?;
+                crate::storage::leases::drop_lease(tx, &claim.issue_id)?;
+                let expired_at = chrono::DateTime::from_timestamp_millis(claim.expires_at)
+                    .map_or_else(String::new, |at| at.format("%Y-%m-%d %H:%M UTC").to_string());
+                ctx.events.push(
+                    Event::new("issue", &claim.issue_id, EventType::IssueClaimExpired, &ctx.actor)
+                        .with_values(Some(claim.assignee.clone()), notify.then(|| claim.holder.clone()))
+                        .with_comment(&format!("Lease of {} ran out at {expired_at}", claim.holder)),
+                );
+                ctx.mark_issue_dirty(&claim.issue_id);
+                released.push(claim);
+            }
+            Ok(released)
+        })
+    }
+
+    /// Agents active in a project since `since` (Unix ms), most recent
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_agent_presence(&self, project_path: &str, since: i64) -> Result<Vec<crate::storage::AgentPresence>> {
+        crate::storage::presence::load(&self.conn, project_path, since)
+    }
+
+    // ===================
+    // Category Operations
+    // ===================
+
+    /// The context item categories of a project: built-ins (with any
+    /// overrides) and the ones it registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_categories(&self, project_path: &str) -> Result<crate::model::Categories> {
+        crate::storage::categories::list(&self.conn, project_path)
+    }
+
+    /// The categories of every project a session belongs to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_session_categories(&self, session_id: &str) -> Result<crate::model::Categories> {
+        crate::storage::categories::for_session(&self.conn, session_id)
+    }
+
+    /// Register a category for a project, or change its settings. Turning
+    /// encryption on or off re-stores the values of the project's items in
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] for an invalid color or weight, or
+    /// an encrypted value this user can't read, or an error if the write
+    /// fails or there is no key to encrypt with.
+    pub fn save_category(
+        &mut self,
+        project_path: &str,
+        category: &crate::model::Category,
+        actor: &str,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let threshold = self.compression_threshold;
+        self.mutate("save_category", actor, |tx, ctx| {
+            crate::storage::categories::save(tx, project_path, category, now)?;
+            let ids = crate::storage::categories::set_encrypted(tx, project_path, &category.name, category.encrypted, threshold)?;
+            for id in &ids {
+                ctx.mark_item_dirty(id);
+            }
+            Ok(())
+        })
+    }
+
+    /// Rename a registered category, moving the project's items along, and
+    /// return how many items moved.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if either name is built in, `old`
+    /// isn't registered or `new` is taken.
+    pub fn rename_category(
+        &mut self,
+        project_path: &str,
+        old: &ItemCategory,
+        new: &ItemCategory,
+        actor: &str,
+    ) -> Result<usize> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.mutate("rename_category", actor, |tx, ctx| {
+            let ids = crate::storage::categories::rename(tx, project_path, old, new, now)?;
+            for id in &ids {
+                ctx.record_event("context_item", id, EventType::ItemUpdated);
+                ctx.mark_item_dirty(id);
+            }
+            Ok(ids.len())
+        })
+    }
+
+    /// Remove a category's registration, returning whether there was one.
+    /// A built-in category goes back to its defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if items are still in a registered
+    /// category.
+    pub fn delete_category(&mut self, project_path: &str, name: &ItemCategory, actor: &str) -> Result<bool> {
+        let threshold = self.compression_threshold;
+        self.mutate("delete_category", actor, |tx, ctx| {
+            let removed = crate::storage::categories::delete(tx, project_path, name)?;
+            // A built-in back at its defaults is no longer encrypted
+            for id in crate::storage::categories::set_encrypted(tx, project_path, name, false, threshold)? {
+                ctx.mark_item_dirty(&id);
+            }
+            Ok(removed)
+        })
+    }
+
+    // =======================
+    // Sub-Project Operations
+    // =======================
+
+    /// Register a directory of a monorepo, relative to the project root, as
+    /// a sub-project. Returns false if it already was one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn add_subproject(&mut self, project_path: &str, path: &str, actor: &str) -> Result<bool> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.mutate("add_subproject", actor, |tx, _| {
+            let added = tx.execute(
+                "INSERT OR IGNORE INTO subprojects (project_path, path, created_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![project_path, path, now],
+            )?;
+            Ok(added > 0)
+        })
+    }
+
+    /// The sub-projects registered for a project, by path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_subprojects(&self, project_path: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT path FROM subprojects WHERE project_path = ?1 ORDER BY path")?;
+        let paths = stmt
+            .query_map([project_path], |row| row.get(0))?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(paths)
+    }
+
+    // =================
+    // Memory Operations
+    // =================
+
+    /// Save a memory item (project-level persistent storage), tagged with a
+    /// monorepo sub-project if `subproject` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation fails.
+    #[allow(clippy::too_many_arguments)]
+    pub fn save_memory(
+        &mut self,
+        id: &str,
+        project_path: &str,
+        key: &str,
+        value: &str,
+        category: MemoryCategory,
+        subproject: Option<&str>,
+        actor: &str,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        self.mutate("save_memory", actor, |tx, ctx| {
+            tx.execute(
+                "INSERT INTO project_memory (id, project_path, key, value, category, subproject, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+                 ON CONFLICT(project_path, key) DO UPDATE SET
+                   value = excluded.value,
+                   category = excluded.category,
+                   subproject = excluded.subproject,
+                   updated_at = excluded.updated_at",
+                rusqlite::params![id, project_path, key, value, category, subproject, now],
+            )?;
+
+            ctx.record_event("memory", id, EventType::MemorySaved);
+
+            Ok(())
+        })
+    }
+
+    /// Get a memory item by key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_memory(&self, project_path: &str, key: &str) -> Result<Option<Memory>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, project_path, key, value, category, created_at, updated_at, subproject
+             FROM project_memory WHERE project_path = ?1 AND key = ?2",
+        )?;
+
+        let memory = stmt
+            .query_row(rusqlite::params![project_path, key], |row| {
+                Ok(Memory {
+                    id: row.get(0)?,
+                    project_path: row.get(1)?,
+                    key: row.get(2)?,
+                    value: row.get(3)?,
+                    category: row.get(4)?,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                    subproject: row.get(7)?,
+                })
+            })
+            .optional()?;
+
+        Ok(memory)
+    }
+
+    /// List memory items for a project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_memory(
+        &self,
+        project_path: &str,
+        category: Option<&str>,
+    ) -> Result<Vec<Memory>> {
         let map_row = |row: &rusqlite::Row| -> rusqlite::Result<Memory> {
             Ok(Memory {
                 id: row.get(0)?,
@@ -2677,12 +4534,13 @@ impl SqliteStorage {
                 category: row.get(4)?,
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
+                subproject: row.get(7)?,
             })
         };
 
         let rows = if let Some(cat) = category {
             let mut stmt = self.conn.prepare(
-                "SELECT id, project_path, key, value, category, created_at, updated_at
+                "SELECT id, project_path, key, value, category, created_at, updated_at, subproject
                  FROM project_memory WHERE project_path = ?1 AND category = ?2
                  ORDER BY key ASC",
             )?;
@@ -2690,7 +4548,7 @@ impl SqliteStorage {
                 .collect::<std::result::Result<Vec<_>, _>>()
         } else {
             let mut stmt = self.conn.prepare(
-                "SELECT id, project_path, key, value, category, created_at, updated_at
+                "SELECT id, project_path, key, value, category, created_at, updated_at, subproject
                  FROM project_memory WHERE project_path = ?1
                  ORDER BY key ASC",
             )?;
@@ -2796,6 +4654,22 @@ impl SqliteStorage {
             .map_err(Error::from)
     }
 
+    /// Count records waiting for export, by kind (`sessions`, `issues`,
+    /// `context_items`, `plans`, `time_entries`), across all projects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn count_dirty_records(&self) -> Result<Vec<(&'static str, i64)>> {
+        ["sessions", "issues", "context_items", "plans", "time_entries"]
+            .into_iter()
+            .map(|kind| {
+                let count = self.conn.query_row(&format!("SELECT COUNT(*) FROM dirty_{kind}"), [], |row| row.get(0))?;
+                Ok((kind, count))
+            })
+            .collect()
+    }
+
     /// Clear dirty flags for sessions after successful export.
     ///
     /// # Errors
@@ -2991,6 +4865,94 @@ impl SqliteStorage {
         Ok(count as usize)
     }
 
+    /// When the oldest change not yet exported for a project was made:
+    /// the earliest dirty mark or unexported deletion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn oldest_unexported_change(&self, project_path: &str) -> Result<Option<i64>> {
+        Ok(self.conn.query_row(
+            "SELECT MIN(at) FROM (
+               SELECT ds.marked_at AS at FROM dirty_sessions ds
+                 INNER JOIN sessions s ON ds.session_id = s.id WHERE s.project_path = ?1
+               UNION ALL
+               SELECT di.marked_at FROM dirty_issues di
+                 INNER JOIN issues i ON di.issue_id = i.id WHERE i.project_path = ?1
+               UNION ALL
+               SELECT dci.marked_at FROM dirty_context_items dci
+                 INNER JOIN context_items ci ON dci.item_id = ci.id
+                 INNER JOIN sessions s ON ci.session_id = s.id WHERE s.project_path = ?1
+               UNION ALL
+               SELECT dp.marked_at FROM dirty_plans dp
+                 INNER JOIN plans p ON dp.plan_id = p.id WHERE p.project_path = ?1
+               UNION ALL
+               SELECT dte.marked_at FROM dirty_time_entries dte
+                 INNER JOIN time_entries te ON dte.time_entry_id = te.id WHERE te.project_path = ?1
+               UNION ALL
+               SELECT deleted_at FROM sync_deletions WHERE project_path = ?1 AND exported = 0
+             )",
+            [project_path],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// When the most recently changed record of a project was last
+    /// changed (creation time for checkpoints, which don't change).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn newest_record_at(&self, project_path: &str) -> Result<Option<i64>> {
+        Ok(self.conn.query_row(
+            "SELECT MAX(at) FROM (
+               SELECT MAX(updated_at) AS at FROM sessions WHERE project_path = ?1
+               UNION ALL
+               SELECT MAX(updated_at) FROM issues WHERE project_path = ?1
+               UNION ALL
+               SELECT MAX(ci.updated_at) FROM context_items ci
+                 INNER JOIN sessions s ON ci.session_id = s.id WHERE s.project_path = ?1
+               UNION ALL
+               SELECT MAX(updated_at) FROM project_memory WHERE project_path = ?1
+               UNION ALL
+               SELECT MAX(c.created_at) FROM checkpoints c
+                 INNER JOIN sessions s ON c.session_id = s.id WHERE s.project_path = ?1
+               UNION ALL
+               SELECT MAX(updated_at) FROM plans WHERE project_path = ?1
+               UNION ALL
+               SELECT MAX(updated_at) FROM time_entries WHERE project_path = ?1
+             )",
+            [project_path],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Read a value from the local `sync_meta` table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn sync_meta(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM sync_meta WHERE key = ?1", [key], |row| row.get(0))
+            .optional()?)
+    }
+
+    /// Write a value to the local `sync_meta` table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn set_sync_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO sync_meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [key, value],
+        )?;
+        Ok(())
+    }
+
     /// Delete entity by ID for import (applies deletion from another machine).
     ///
     /// # Errors
@@ -3003,6 +4965,7 @@ impl SqliteStorage {
             "context_item" => "DELETE FROM context_items WHERE id = ?1",
             "memory" => "DELETE FROM project_memory WHERE id = ?1",
             "checkpoint" => "DELETE FROM checkpoints WHERE id = ?1",
+            "time_entry" => "DELETE FROM time_entries WHERE id = ?1",
             _ => return Ok(false),
         };
         let rows = self.conn.execute(sql, [entity_id])?;
@@ -3016,7 +4979,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_all_sessions(&self) -> Result<Vec<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at
+            "SELECT id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at, worktree
              FROM sessions ORDER BY created_at ASC",
         )?;
         let rows = stmt.query_map([], |row| {
@@ -3031,6 +4994,7 @@ impl SqliteStorage {
                 ended_at: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                worktree: row.get(10)?,
             })
         })?;
         rows.collect::<std::result::Result<Vec<_>, _>>()
@@ -3044,7 +5008,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_all_issues(&self) -> Result<Vec<Issue>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at
+            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues ORDER BY created_at ASC",
         )?;
         let rows = stmt.query_map([], map_issue_row)?;
@@ -3064,7 +5028,7 @@ impl SqliteStorage {
         limit: Option<u32>,
     ) -> Result<Vec<ContextItem>> {
         let mut sql = String::from(
-            "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at
+            "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at
              FROM context_items WHERE 1=1",
         );
 
@@ -3094,7 +5058,7 @@ impl SqliteStorage {
                 id: row.get(0)?,
                 session_id: row.get(1)?,
                 key: row.get(2)?,
-                value: row.get(3)?,
+                value: read_value(row, 3)?,
                 category: row.get(4)?,
                 priority: row.get(5)?,
                 channel: row.get(6)?,
@@ -3102,6 +5066,11 @@ impl SqliteStorage {
                 size: row.get(8)?,
                 created_at: row.get(9)?,
                 updated_at: row.get(10)?,
+                source: row.get(11)?,
+                trust: row.get(12)?,
+                review_status: row.get(13)?,
+                due_at: row.get(14)?,
+                done_at: row.get(15)?,
             })
         })?;
         rows.collect::<std::result::Result<Vec<_>, _>>()
@@ -3115,7 +5084,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_all_memory(&self) -> Result<Vec<Memory>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, project_path, key, value, category, created_at, updated_at
+            "SELECT id, project_path, key, value, category, created_at, updated_at, subproject
              FROM project_memory ORDER BY created_at ASC",
         )?;
         let rows = stmt.query_map([], |row| {
@@ -3127,6 +5096,7 @@ impl SqliteStorage {
                 category: row.get(4)?,
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
+                subproject: row.get(7)?,
             })
         })?;
         rows.collect::<std::result::Result<Vec<_>, _>>()
@@ -3146,23 +5116,117 @@ impl SqliteStorage {
             .map_err(Error::from)
     }
 
-    /// Get all session IDs (for Levenshtein suggestions).
-    pub fn get_all_session_ids(&self) -> Result<Vec<String>> {
-        let mut stmt = self.conn.prepare("SELECT id FROM sessions")?;
-        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(Error::from)
-    }
-
-    /// Get all checkpoint IDs (for Levenshtein suggestions).
-    pub fn get_all_checkpoint_ids(&self) -> Result<Vec<String>> {
-        let mut stmt = self.conn.prepare("SELECT id FROM checkpoints")?;
-        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(Error::from)
-    }
-
-    /// Get all checkpoints (for full export).
+    /// Allocate the next issue short ID: this database's shard followed by
+    /// a counter (`kqx17`), so machines that sync never hand out the same
+    /// one. The counter is shared by all projects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the counter cannot be updated.
+    pub fn next_issue_short_id(&self) -> Result<String> {
+        let shard = self.machine_shard()?;
+        let seq: String = self.conn.query_row(
+            "INSERT INTO sync_meta (key, value) VALUES ('issue_seq', '1')
+             ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1
+             RETURNING value",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(format!("{shard}{seq}"))
+    }
+
+    /// This database's short ID shard, chosen on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shard cannot be read or stored.
+    pub fn machine_shard(&self) -> Result<String> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO sync_meta (key, value) VALUES ('machine_shard', ?1)",
+            [random_shard()],
+        )?;
+        Ok(self.conn.query_row("SELECT value FROM sync_meta WHERE key = 'machine_shard'", [], |row| row.get(0))?)
+    }
+
+    /// Whether `short_id` is in this database's shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shard cannot be read.
+    pub fn owns_short_id(&self, short_id: &str) -> Result<bool> {
+        Ok(shard_of(short_id).is_some_and(|shard| self.machine_shard().is_ok_and(|own| own == shard)))
+    }
+
+    /// Switch to a shard no stored issue uses, after an import showed
+    /// another machine shares the current one. Returns the new shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the issues cannot be read or the shard stored.
+    pub fn reroll_machine_shard(&self) -> Result<String> {
+        let taken = |shard: &str| -> Result<bool> {
+            Ok(self.conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM issues WHERE short_id LIKE ?1 || '%')",
+                [shard],
+                |row| row.get(0),
+            )?)
+        };
+        let mut shard = random_shard();
+        while taken(&shard)? {
+            shard = random_shard();
+        }
+        self.conn.execute(
+            "INSERT INTO sync_meta (key, value) VALUES ('machine_shard', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [&shard],
+        )?;
+        Ok(shard)
+    }
+
+    /// ID of the issue with `short_id`, in any project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn issue_id_by_short_id(&self, short_id: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT id FROM issues WHERE short_id = ?1 ORDER BY id LIMIT 1", [short_id], |row| row.get(0))
+            .optional()?)
+    }
+
+    /// Give an issue a new short ID (for sync import, which renumbers the
+    /// loser of a short ID collision). Bumps `updated_at` so the change
+    /// wins on the other machine too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the update fails.
+    pub fn set_issue_short_id(&self, id: &str, short_id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE issues SET short_id = ?2, updated_at = ?3 WHERE id = ?1",
+            rusqlite::params![id, short_id, chrono::Utc::now().timestamp_millis()],
+        )?;
+        Ok(())
+    }
+
+    /// Get all session IDs (for Levenshtein suggestions).
+    pub fn get_all_session_ids(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT id FROM sessions")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Get all checkpoint IDs (for Levenshtein suggestions).
+    pub fn get_all_checkpoint_ids(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT id FROM checkpoints")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Get all checkpoints (for full export).
     ///
     /// # Errors
     ///
@@ -3195,8 +5259,12 @@ impl SqliteStorage {
     ///
     /// Returns an error if the query fails.
     pub fn get_context_item(&self, id: &str) -> Result<Option<ContextItem>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at
+        Self::context_item_by_id(&self.conn, id)
+    }
+
+    fn context_item_by_id(conn: &Connection, id: &str) -> Result<Option<ContextItem>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at
              FROM context_items WHERE id = ?1",
         )?;
         let item = stmt
@@ -3205,7 +5273,7 @@ impl SqliteStorage {
                     id: row.get(0)?,
                     session_id: row.get(1)?,
                     key: row.get(2)?,
-                    value: row.get(3)?,
+                    value: read_value(row, 3)?,
                     category: row.get(4)?,
                     priority: row.get(5)?,
                     channel: row.get(6)?,
@@ -3213,6 +5281,11 @@ impl SqliteStorage {
                     size: row.get(8)?,
                     created_at: row.get(9)?,
                     updated_at: row.get(10)?,
+                    source: row.get(11)?,
+                    trust: row.get(12)?,
+                    review_status: row.get(13)?,
+                    due_at: row.get(14)?,
+                    done_at: row.get(15)?,
                 })
             })
             .optional()?;
@@ -3230,7 +5303,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_sessions_by_project(&self, project_path: &str) -> Result<Vec<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at
+            "SELECT id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at, worktree
              FROM sessions WHERE project_path = ?1 ORDER BY created_at ASC",
         )?;
         let rows = stmt.query_map([project_path], |row| {
@@ -3245,6 +5318,7 @@ impl SqliteStorage {
                 ended_at: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                worktree: row.get(10)?,
             })
         })?;
         rows.collect::<std::result::Result<Vec<_>, _>>()
@@ -3258,7 +5332,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_issues_by_project(&self, project_path: &str) -> Result<Vec<Issue>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at
+            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
              FROM issues WHERE project_path = ?1 ORDER BY created_at ASC",
         )?;
         let rows = stmt.query_map([project_path], map_issue_row)?;
@@ -3266,6 +5340,33 @@ impl SqliteStorage {
             .map_err(Error::from)
     }
 
+    /// A project's context items as sync exports carry them: encrypted
+    /// values stay ciphertext, as sealed text.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_context_items_for_sync(&self, project_path: &str) -> Result<Vec<ContextItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ci.id, ci.value FROM context_items ci
+             INNER JOIN sessions s ON ci.session_id = s.id
+             WHERE s.project_path = ?1 AND ci.value_encrypted = 1",
+        )?;
+        let mut sealed: std::collections::HashMap<String, String> = stmt
+            .query_map([project_path], |row| {
+                Ok((row.get(0)?, crate::storage::encryption::sealed_text(&row.get::<_, Vec<u8>>(1)?)))
+            })?
+            .collect::<std::result::Result<_, _>>()?;
+
+        let mut items = self.get_context_items_by_project(project_path)?;
+        for item in &mut items {
+            if let Some(value) = sealed.remove(&item.id) {
+                item.value = value;
+            }
+        }
+        Ok(items)
+    }
+
     /// Get all context items for sessions in a specific project.
     ///
     /// Context items are linked to sessions, so we join on session_id
@@ -3276,7 +5377,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_context_items_by_project(&self, project_path: &str) -> Result<Vec<ContextItem>> {
         let mut stmt = self.conn.prepare(
-            "SELECT ci.id, ci.session_id, ci.key, ci.value, ci.category, ci.priority, ci.channel, ci.tags, ci.size, ci.created_at, ci.updated_at
+            "SELECT ci.id, ci.session_id, ci.key, ci.value, ci.category, ci.priority, ci.channel, ci.tags, ci.size, ci.created_at, ci.updated_at, ci.source, ci.trust, ci.review_status, ci.due_at, ci.done_at
              FROM context_items ci
              INNER JOIN sessions s ON ci.session_id = s.id
              WHERE s.project_path = ?1
@@ -3287,7 +5388,7 @@ impl SqliteStorage {
                 id: row.get(0)?,
                 session_id: row.get(1)?,
                 key: row.get(2)?,
-                value: row.get(3)?,
+                value: read_value(row, 3)?,
                 category: row.get(4)?,
                 priority: row.get(5)?,
                 channel: row.get(6)?,
@@ -3295,6 +5396,11 @@ impl SqliteStorage {
                 size: row.get(8)?,
                 created_at: row.get(9)?,
                 updated_at: row.get(10)?,
+                source: row.get(11)?,
+                trust: row.get(12)?,
+                review_status: row.get(13)?,
+                due_at: row.get(14)?,
+                done_at: row.get(15)?,
             })
         })?;
         rows.collect::<std::result::Result<Vec<_>, _>>()
@@ -3308,7 +5414,7 @@ impl SqliteStorage {
     /// Returns an error if the query fails.
     pub fn get_memory_by_project(&self, project_path: &str) -> Result<Vec<Memory>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, project_path, key, value, category, created_at, updated_at
+            "SELECT id, project_path, key, value, category, created_at, updated_at, subproject
              FROM project_memory WHERE project_path = ?1 ORDER BY created_at ASC",
         )?;
         let rows = stmt.query_map([project_path], |row| {
@@ -3320,6 +5426,7 @@ impl SqliteStorage {
                 category: row.get(4)?,
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
+                subproject: row.get(7)?,
             })
         })?;
         rows.collect::<std::result::Result<Vec<_>, _>>()
@@ -3518,543 +5625,779 @@ impl SqliteStorage {
         })
     }
 
+    /// Get health indicators for a project.
+    ///
+    /// Issues not updated since `stale_before` (Unix milliseconds) count as
+    /// stale. Sessions that include the project via `session_projects`
+    /// count as active sessions for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a query fails.
+    pub fn get_project_health(&self, project_path: &str, stale_before: i64) -> Result<ProjectHealth> {
+        let mut health = ProjectHealth::default();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT priority, COUNT(*) FROM issues
+             WHERE project_path = ?1 AND status NOT IN ('closed', 'deferred')
+             GROUP BY priority",
+        )?;
+        let rows = stmt.query_map([project_path], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, usize>(1)?)))?;
+        for row in rows {
+            let (priority, count) = row?;
+            if let Some(slot) = usize::try_from(priority).ok().and_then(|p| health.open_by_priority.get_mut(p)) {
+                *slot = count;
+            }
+        }
+
+        health.stale_count = self.conn.query_row(
+            "SELECT COUNT(*) FROM issues
+             WHERE project_path = ?1 AND status NOT IN ('closed', 'deferred') AND updated_at < ?2",
+            rusqlite::params![project_path, stale_before],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, short_id, title, status, updated_at FROM issues
+             WHERE project_path = ?1 AND status NOT IN ('closed', 'deferred') AND updated_at < ?2
+             ORDER BY updated_at ASC LIMIT 10",
+        )?;
+        health.stale_issues = stmt
+            .query_map(rusqlite::params![project_path, stale_before], |row| {
+                Ok(StaleIssue {
+                    id: row.get(0)?,
+                    short_id: row.get(1)?,
+                    title: row.get(2)?,
+                    status: row.get(3)?,
+                    updated_at: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<_, _>>()?;
+
+        health.active_sessions = self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions
+             WHERE status = 'active'
+               AND (project_path = ?1
+                    OR id IN (SELECT session_id FROM session_projects WHERE project_path = ?1))",
+            [project_path],
+            |row| row.get(0),
+        )?;
+
+        health.embedding_backlog = self.conn.query_row(
+            "SELECT COUNT(*) FROM context_items ci
+             INNER JOIN sessions s ON ci.session_id = s.id
+             WHERE s.project_path = ?1
+               AND (ci.embedding_status IS NULL OR ci.embedding_status IN ('none', 'pending', 'error'))",
+            [project_path],
+            |row| row.get(0),
+        )?;
+
+        health.pending_sync = self.get_dirty_sessions_by_project(project_path)?.len()
+            + self.get_dirty_issues_by_project(project_path)?.len()
+            + self.get_dirty_context_items_by_project(project_path)?.len()
+            + self.get_dirty_plans_by_project(project_path)?.len()
+            + self.get_dirty_time_entries_by_project(project_path)?.len();
+
+        health.last_checkpoint_at = self.conn.query_row(
+            "SELECT MAX(c.created_at) FROM checkpoints c
+             INNER JOIN sessions s ON c.session_id = s.id
+             WHERE s.project_path = ?1",
+            [project_path],
+            |row| row.get(0),
+        )?;
+
+        Ok(health)
+    }
+
     // ======================
-    // Upsert Operations (for sync import)
+    // Event Retention
     // ======================
 
-    /// Upsert a session (for sync import).
-    ///
-    /// This performs an INSERT OR REPLACE, preserving all fields from the imported record.
+    /// Events `policy` would prune, oldest first, at most `limit` of them.
     ///
     /// # Errors
     ///
-    /// Returns an error if the upsert fails.
-    pub fn upsert_session(&mut self, session: &Session) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO sessions (id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-             ON CONFLICT(id) DO UPDATE SET
-               name = excluded.name,
-               description = excluded.description,
-               branch = excluded.branch,
-               channel = excluded.channel,
-               project_path = excluded.project_path,
-               status = excluded.status,
-               ended_at = excluded.ended_at,
-               updated_at = excluded.updated_at",
-            rusqlite::params![
-                session.id,
-                session.name,
-                session.description,
-                session.branch,
-                session.channel,
-                session.project_path,
-                session.status,
-                session.ended_at,
-                session.created_at,
-                session.updated_at,
-            ],
-        )?;
-        Ok(())
+    /// Returns an error if the query fails.
+    pub fn prunable_events(
+        &self,
+        policy: &crate::storage::events::RetentionPolicy,
+        limit: Option<usize>,
+    ) -> Result<Vec<crate::storage::events::StoredEvent>> {
+        Ok(crate::storage::events::prunable_events(&self.conn, policy, limit)?)
     }
 
-    /// Upsert an issue (for sync import).
+    /// Delete events by ID. Pruning the audit log is not itself audited.
     ///
     /// # Errors
     ///
-    /// Returns an error if the upsert fails.
-    pub fn upsert_issue(&mut self, issue: &Issue) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO issues (id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
-             ON CONFLICT(id) DO UPDATE SET
-               short_id = excluded.short_id,
-               project_path = excluded.project_path,
-               title = excluded.title,
-               description = excluded.description,
-               details = excluded.details,
-               status = excluded.status,
-               priority = excluded.priority,
-               issue_type = excluded.issue_type,
-               plan_id = excluded.plan_id,
-               assigned_to_agent = excluded.assigned_to_agent,
-               updated_at = excluded.updated_at,
-               closed_at = excluded.closed_at",
-            rusqlite::params![
-                issue.id,
-                issue.short_id,
-                issue.project_path,
-                issue.title,
-                issue.description,
-                issue.details,
-                issue.status,
-                issue.priority,
-                issue.issue_type,
-                issue.plan_id,
-                issue.created_by_agent,
-                issue.assigned_to_agent,
-                issue.created_at,
-                issue.updated_at,
-                issue.closed_at,
-            ],
-        )?;
-        Ok(())
+    /// Returns an error if the delete fails.
+    pub fn delete_events(&mut self, ids: &[i64]) -> Result<usize> {
+        Ok(crate::storage::events::delete_events(&self.conn, ids)?)
     }
 
-    /// Upsert a context item (for sync import).
+    /// Events about the records of a project, oldest first.
     ///
     /// # Errors
     ///
-    /// Returns an error if the upsert fails.
-    pub fn upsert_context_item(&mut self, item: &ContextItem) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO context_items (id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
-             ON CONFLICT(id) DO UPDATE SET
-               key = excluded.key,
-               value = excluded.value,
-               category = excluded.category,
-               priority = excluded.priority,
-               channel = excluded.channel,
-               tags = excluded.tags,
-               size = excluded.size,
-               updated_at = excluded.updated_at",
-            rusqlite::params![
-                item.id,
-                item.session_id,
-                item.key,
-                item.value,
-                item.category,
-                item.priority,
-                item.channel,
-                item.tags,
-                item.size,
-                item.created_at,
-                item.updated_at,
-            ],
-        )?;
-        Ok(())
+    /// Returns an error if the query fails.
+    pub fn get_project_events(&self, project_path: &str) -> Result<Vec<crate::storage::events::StoredEvent>> {
+        Ok(crate::storage::events::project_events(&self.conn, project_path)?)
     }
 
-    /// Upsert a memory item (for sync import).
+    /// Number of events on each of a project's issues between `from` and
+    /// `to` (Unix ms, inclusive), by full issue ID. Events recorded under
+    /// a short ID count too.
     ///
     /// # Errors
     ///
-    /// Returns an error if the upsert fails.
-    pub fn upsert_memory(&mut self, memory: &Memory) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO project_memory (id, project_path, key, value, category, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-             ON CONFLICT(id) DO UPDATE SET
-               key = excluded.key,
-               value = excluded.value,
-               category = excluded.category,
-               updated_at = excluded.updated_at",
-            rusqlite::params![
-                memory.id,
-                memory.project_path,
-                memory.key,
-                memory.value,
-                memory.category,
-                memory.created_at,
-                memory.updated_at,
-            ],
+    /// Returns an error if the query fails.
+    pub fn get_issue_activity(&self, project_path: &str, from: i64, to: i64) -> Result<HashMap<String, usize>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.id, COUNT(*) FROM events e
+             JOIN issues i ON e.entity_id = i.id OR e.entity_id = i.short_id
+             WHERE e.entity_type = 'issue' AND i.project_path = ?1 AND e.created_at BETWEEN ?2 AND ?3
+             GROUP BY i.id",
         )?;
-        Ok(())
+        let rows = stmt.query_map(rusqlite::params![project_path, from, to], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        let mut activity = HashMap::new();
+        for row in rows {
+            let (id, count) = row?;
+            activity.insert(id, usize::try_from(count).unwrap_or_default());
+        }
+        Ok(activity)
     }
 
-    /// Upsert a checkpoint (for sync import).
+    /// Events newer than `after_id`, oldest first, of the given entity
+    /// types (all when empty).
     ///
-    /// Note: This does not import checkpoint items - those would need separate handling.
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn events_after(&self, after_id: i64, entity_types: &[&str]) -> Result<Vec<crate::storage::events::StoredEvent>> {
+        Ok(crate::storage::events::events_after(&self.conn, after_id, entity_types)?)
+    }
+
+    /// ID of the newest event, or 0 if there are none.
     ///
     /// # Errors
     ///
-    /// Returns an error if the upsert fails.
-    pub fn upsert_checkpoint(&mut self, checkpoint: &Checkpoint) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO checkpoints (id, session_id, name, description, git_status, git_branch, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-             ON CONFLICT(id) DO UPDATE SET
-               name = excluded.name,
-               description = excluded.description,
-               git_status = excluded.git_status,
-               git_branch = excluded.git_branch",
-            rusqlite::params![
-                checkpoint.id,
-                checkpoint.session_id,
-                checkpoint.name,
-                checkpoint.description,
-                checkpoint.git_status,
-                checkpoint.git_branch,
-                checkpoint.created_at,
-            ],
-        )?;
-        Ok(())
+    /// Returns an error if the query fails.
+    pub fn latest_event_id(&self) -> Result<i64> {
+        Ok(self.conn.query_row("SELECT COALESCE(MAX(id), 0) FROM events", [], |row| row.get(0))?)
     }
 
-    // ======================
-    // Project Operations
-    // ======================
-
-    /// Create a new project.
+    /// `PRAGMA data_version`: changes whenever another connection commits
+    /// to the database, so polling it is a cheap way to notice writes from
+    /// other processes.
     ///
     /// # Errors
     ///
-    /// Returns an error if the project already exists or the insert fails.
-    pub fn create_project(&mut self, project: &Project, actor: &str) -> Result<()> {
-        self.mutate("create_project", actor, |tx, ctx| {
-            tx.execute(
-                "INSERT INTO projects (id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-                rusqlite::params![
-                    project.id,
-                    project.project_path,
-                    project.name,
-                    project.description,
-                    project.issue_prefix,
-                    project.next_issue_number,
-                    project.plan_prefix,
-                    project.next_plan_number,
-                    project.created_at,
-                    project.updated_at,
-                ],
-            )?;
-
-            ctx.record_event("project", &project.id, EventType::ProjectCreated);
-            Ok(())
-        })
+    /// Returns an error if the pragma fails.
+    pub fn data_version(&self) -> Result<i64> {
+        Ok(self.conn.query_row("PRAGMA data_version", [], |row| row.get(0))?)
     }
 
-    /// Get a project by ID.
+    /// Insert events synced from another machine, skipping known ones.
     ///
     /// # Errors
     ///
-    /// Returns an error if the query fails.
-    pub fn get_project(&self, id: &str) -> Result<Option<Project>> {
-        let project = self
-            .conn
-            .query_row(
-                "SELECT id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at
-                 FROM projects WHERE id = ?1",
-                [id],
-                map_project_row,
-            )
-            .optional()?;
-        Ok(project)
+    /// Returns an error if an insert fails.
+    pub fn import_events(&mut self, events: &[crate::storage::events::StoredEvent]) -> Result<usize> {
+        Ok(crate::storage::events::import_events(&self.conn, events)?)
     }
 
-    /// Get a project by path.
+    // ======================
+    // Usage Statistics
+    // ======================
+
+    /// Record one command invocation, with its timing, and today's
+    /// database size.
+    ///
+    /// Usage rows are local bookkeeping: they bypass `mutate`, emit no
+    /// events, and are never exported.
     ///
     /// # Errors
     ///
-    /// Returns an error if the query fails.
-    pub fn get_project_by_path(&self, project_path: &str) -> Result<Option<Project>> {
-        let project = self
-            .conn
-            .query_row(
-                "SELECT id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at
-                 FROM projects WHERE project_path = ?1",
-                [project_path],
-                map_project_row,
-            )
-            .optional()?;
-        Ok(project)
+    /// Returns an error if the rows cannot be written.
+    pub fn record_command_usage(
+        &self,
+        command: &str,
+        timing: &crate::storage::CommandTiming,
+        success: bool,
+    ) -> Result<()> {
+        let now = chrono::Utc::now();
+        let ms = |value: u64| i64::try_from(value).unwrap_or(i64::MAX);
+        self.conn.execute(
+            "INSERT INTO command_usage (command, duration_ms, sql_ms, slow_queries, success, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                command,
+                ms(timing.total_ms),
+                ms(timing.sql_ms),
+                ms(timing.slow_queries),
+                success,
+                now.timestamp_millis()
+            ],
+        )?;
+        self.conn.execute(
+            "INSERT INTO db_size_samples (sampled_on, size_bytes, created_at)
+             VALUES (?1, (SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()), ?2)
+             ON CONFLICT(sampled_on) DO UPDATE SET size_bytes = excluded.size_bytes, created_at = excluded.created_at",
+            rusqlite::params![now.format("%Y-%m-%d").to_string(), now.timestamp_millis()],
+        )?;
+        Ok(())
     }
 
-    /// List all projects.
+    /// Summarize command usage recorded at or after `since`.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn list_projects(&self, limit: usize) -> Result<Vec<Project>> {
+    pub fn get_usage_stats(&self, since: i64, limit: usize) -> Result<UsageStats> {
+        let mut stats = UsageStats {
+            since,
+            ..UsageStats::default()
+        };
+
+        (stats.total_invocations, stats.slow_queries) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(slow_queries), 0) FROM command_usage WHERE created_at >= ?1",
+            [since],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
         let mut stmt = self.conn.prepare(
-            "SELECT id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at
-             FROM projects
-             ORDER BY updated_at DESC
-             LIMIT ?1",
+            "SELECT command, COUNT(*), SUM(success = 0), CAST(AVG(duration_ms) AS INTEGER), MAX(duration_ms),
+                    CAST(AVG(sql_ms) AS INTEGER), SUM(slow_queries)
+             FROM command_usage WHERE created_at >= ?1
+             GROUP BY command ORDER BY COUNT(*) DESC, command ASC LIMIT ?2",
+        )?;
+        stats.commands = stmt
+            .query_map(rusqlite::params![since, limit], |row| {
+                Ok(CommandUsage {
+                    command: row.get(0)?,
+                    count: row.get(1)?,
+                    failures: row.get(2)?,
+                    avg_ms: row.get(3)?,
+                    max_ms: row.get(4)?,
+                    avg_sql_ms: row.get(5)?,
+                    slow_queries: row.get(6)?,
+                })
+            })?
+            .collect::<std::result::Result<_, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT command, duration_ms, sql_ms, slow_queries, success, created_at FROM command_usage
+             WHERE created_at >= ?1 ORDER BY duration_ms DESC, created_at DESC LIMIT ?2",
         )?;
+        stats.slowest = stmt
+            .query_map(rusqlite::params![since, limit], |row| {
+                Ok(CommandInvocation {
+                    command: row.get(0)?,
+                    duration_ms: row.get(1)?,
+                    sql_ms: row.get(2)?,
+                    slow_queries: row.get(3)?,
+                    success: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<_, _>>()?;
 
-        let projects = stmt
-            .query_map([limit], map_project_row)?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT sampled_on, size_bytes FROM db_size_samples
+             WHERE created_at >= ?1 ORDER BY sampled_on ASC",
+        )?;
+        stats.db_growth = stmt
+            .query_map([since], |row| {
+                Ok(DbSizeSample {
+                    sampled_on: row.get(0)?,
+                    size_bytes: row.get(1)?,
+                })
+            })?
+            .collect::<std::result::Result<_, _>>()?;
 
-        Ok(projects)
+        Ok(stats)
     }
 
-    /// Update a project.
+    /// Delete all recorded usage statistics, returning the number of rows removed.
     ///
     /// # Errors
     ///
-    /// Returns an error if the project doesn't exist or the update fails.
-    pub fn update_project(
+    /// Returns an error if the delete fails.
+    pub fn clear_usage_stats(&self) -> Result<usize> {
+        let invocations = self.conn.execute("DELETE FROM command_usage", [])?;
+        let samples = self.conn.execute("DELETE FROM db_size_samples", [])?;
+        Ok(invocations + samples)
+    }
+
+    // ======================
+    // Token Usage Ledger
+    // ======================
+
+    /// Record model tokens consumed by a session.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionNotFound` if the session doesn't exist, or an error
+    /// if the insert fails.
+    pub fn record_session_usage(
         &mut self,
-        id: &str,
-        name: Option<&str>,
-        description: Option<&str>,
-        issue_prefix: Option<&str>,
+        session_id: &str,
+        tokens_in: u64,
+        tokens_out: u64,
+        model: Option<&str>,
+        source: Option<&str>,
         actor: &str,
     ) -> Result<()> {
-        self.mutate("update_project", actor, |tx, ctx| {
-            let now = chrono::Utc::now().timestamp_millis();
-
-            // Build dynamic update query
-            let mut updates = vec!["updated_at = ?1"];
-            let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(now)];
-            let mut param_idx = 2;
-
-            if let Some(n) = name {
-                updates.push(format!("name = ?{param_idx}").leak());
-                params.push(Box::new(n.to_string()));
-                param_idx += 1;
-            }
-
-            if let Some(d) = description {
-                updates.push(format!("description = ?{param_idx}").leak());
-                params.push(Box::new(d.to_string()));
-                param_idx += 1;
-            }
-
-            if let Some(p) = issue_prefix {
-                updates.push(format!("issue_prefix = ?{param_idx}").leak());
-                params.push(Box::new(p.to_string()));
-                param_idx += 1;
-            }
-
-            // Add the WHERE clause parameter
-            params.push(Box::new(id.to_string()));
-
-            let sql = format!(
-                "UPDATE projects SET {} WHERE id = ?{}",
-                updates.join(", "),
-                param_idx
-            );
-
-            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-            let affected = tx.execute(&sql, param_refs.as_slice())?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let tokens_in = i64::try_from(tokens_in).unwrap_or(i64::MAX);
+        let tokens_out = i64::try_from(tokens_out).unwrap_or(i64::MAX);
 
-            if affected == 0 {
-                return Err(Error::ProjectNotFound { id: id.to_string() });
+        self.mutate("record_session_usage", actor, |tx, _| {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?1)",
+                [session_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(Error::SessionNotFound { id: session_id.to_string() });
             }
-
-            ctx.record_event("project", id, EventType::ProjectUpdated);
+            tx.execute(
+                "INSERT INTO session_usage (session_id, tokens_in, tokens_out, model, source, recorded_at, recorded_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![session_id, tokens_in, tokens_out, model, source, now, actor],
+            )?;
             Ok(())
         })
     }
 
-    /// Delete a project and all associated data.
-    ///
-    /// This cascades to delete:
-    /// - All sessions (and their context items, checkpoints)
-    /// - All issues
-    /// - All plans
-    /// - All project memory
+    /// Token usage of one session, split at each compaction.
     ///
     /// # Errors
     ///
-    /// Returns an error if the project doesn't exist or deletion fails.
-    pub fn delete_project(&mut self, id: &str, actor: &str) -> Result<()> {
-        self.mutate("delete_project", actor, |tx, ctx| {
-            // Get project path for cascading deletes
-            let project_path: Option<String> = tx
-                .query_row(
-                    "SELECT project_path FROM projects WHERE id = ?1",
-                    [id],
-                    |row| row.get(0),
-                )
-                .optional()?;
-
-            let project_path = project_path.ok_or_else(|| Error::ProjectNotFound { id: id.to_string() })?;
-
-            // Delete sessions (cascades to context_items, checkpoints via FK)
-            tx.execute(
-                "DELETE FROM sessions WHERE project_path = ?1",
-                [&project_path],
-            )?;
-
-            // Delete issues
-            tx.execute(
-                "DELETE FROM issues WHERE project_path = ?1",
-                [&project_path],
-            )?;
-
-            // Delete plans
-            tx.execute(
-                "DELETE FROM plans WHERE project_path = ?1",
-                [&project_path],
-            )?;
+    /// Returns `SessionNotFound` if the session doesn't exist, or an error
+    /// if a query fails.
+    pub fn get_session_token_usage(&self, session_id: &str) -> Result<TokenUsage> {
+        let session = self
+            .get_session(session_id)?
+            .ok_or_else(|| Error::SessionNotFound { id: session_id.to_string() })?;
 
-            // Delete project memory
-            tx.execute(
-                "DELETE FROM project_memory WHERE project_path = ?1",
-                [&project_path],
-            )?;
-
-            // Delete the project itself
-            let affected = tx.execute("DELETE FROM projects WHERE id = ?1", [id])?;
+        let mut stmt = self.conn.prepare(
+            "SELECT name, created_at FROM checkpoints
+             WHERE session_id = ?1 AND name LIKE 'pre-compact-%'
+             ORDER BY created_at",
+        )?;
+        let compactions = stmt
+            .query_map([session_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-            if affected == 0 {
-                return Err(Error::ProjectNotFound { id: id.to_string() });
-            }
+        let mut windows: Vec<UsageWindow> = compactions
+            .iter()
+            .map(|(name, at)| UsageWindow {
+                compacted_by: Some(name.clone()),
+                ended_at: Some(*at),
+                ..UsageWindow::default()
+            })
+            .chain(std::iter::once(UsageWindow::default()))
+            .collect();
 
-            ctx.record_event("project", id, EventType::ProjectDeleted);
-            Ok(())
-        })
+        let mut usage = TokenUsage {
+            session_id: session.id,
+            session_name: session.name,
+            compactions: compactions.len(),
+            ..TokenUsage::default()
+        };
+        let mut stmt = self.conn.prepare(
+            "SELECT tokens_in, tokens_out, recorded_at FROM session_usage
+             WHERE session_id = ?1 ORDER BY recorded_at",
+        )?;
+        let mut rows = stmt.query([session_id])?;
+        while let Some(row) = rows.next()? {
+            let (tokens_in, tokens_out, at): (i64, i64, i64) = (row.get(0)?, row.get(1)?, row.get(2)?);
+            let index = compactions.partition_point(|(_, compacted_at)| *compacted_at < at);
+            let window = &mut windows[index];
+            window.tokens_in += tokens_in;
+            window.tokens_out += tokens_out;
+            window.records += 1;
+
+            usage.tokens_in += tokens_in;
+            usage.tokens_out += tokens_out;
+            usage.records += 1;
+            usage.first_at.get_or_insert(at);
+            usage.last_at = Some(at);
+        }
+        usage.windows = windows;
+        Ok(usage)
     }
 
-    /// Get or create a project for the given path.
-    ///
-    /// If a project already exists at the path, returns it.
-    /// Otherwise, creates a new project with a name derived from the path.
+    /// Sessions with recorded token usage, most tokens first.
     ///
     /// # Errors
     ///
-    /// Returns an error if the database operation fails.
-    pub fn get_or_create_project(&mut self, project_path: &str, actor: &str) -> Result<Project> {
-        // Check if project exists
-        if let Some(project) = self.get_project_by_path(project_path)? {
-            return Ok(project);
-        }
-
-        // Create new project with name from path
-        let name = std::path::Path::new(project_path)
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("Unknown Project")
-            .to_string();
+    /// Returns an error if the query fails.
+    pub fn list_session_token_usage(&self, project_path: Option<&str>, limit: usize) -> Result<Vec<TokenUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.id, s.name, SUM(u.tokens_in), SUM(u.tokens_out), COUNT(*),
+                    MIN(u.recorded_at), MAX(u.recorded_at),
+                    (SELECT COUNT(*) FROM checkpoints c
+                     WHERE c.session_id = s.id AND c.name LIKE 'pre-compact-%')
+             FROM session_usage u JOIN sessions s ON s.id = u.session_id
+             WHERE ?1 IS NULL OR EXISTS (SELECT 1 FROM session_projects sp
+                                         WHERE sp.session_id = s.id AND sp.project_path = ?1)
+             GROUP BY s.id
+             ORDER BY SUM(u.tokens_in) + SUM(u.tokens_out) DESC
+             LIMIT ?2",
+        )?;
+        let usage = stmt
+            .query_map(
+                rusqlite::params![project_path, i64::try_from(limit).unwrap_or(i64::MAX)],
+                |row| {
+                    Ok(TokenUsage {
+                        session_id: row.get(0)?,
+                        session_name: row.get(1)?,
+                        tokens_in: row.get(2)?,
+                        tokens_out: row.get(3)?,
+                        records: row.get(4)?,
+                        first_at: row.get(5)?,
+                        last_at: row.get(6)?,
+                        compactions: row.get(7)?,
+                        windows: Vec::new(),
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(usage)
+    }
 
-        let project = Project::new(project_path.to_string(), name);
-        self.create_project(&project, actor)?;
-        Ok(project)
+    // ======================
+    // Upsert Operations (for sync import)
+    // ======================
+
+    /// Upsert a session (for sync import).
+    ///
+    /// This performs an INSERT OR REPLACE, preserving all fields from the imported record.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upsert fails.
+    pub fn upsert_session(&mut self, session: &Session) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO sessions (id, name, description, branch, channel, project_path, status, ended_at, created_at, updated_at, worktree)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT(id) DO UPDATE SET
+               name = excluded.name,
+               description = excluded.description,
+               branch = excluded.branch,
+               channel = excluded.channel,
+               project_path = excluded.project_path,
+               status = excluded.status,
+               ended_at = excluded.ended_at,
+               updated_at = excluded.updated_at,
+               worktree = excluded.worktree",
+            rusqlite::params![
+                session.id,
+                session.name,
+                session.description,
+                session.branch,
+                session.channel,
+                session.project_path,
+                session.status,
+                session.ended_at,
+                session.created_at,
+                session.updated_at,
+                session.worktree,
+            ],
+        )?;
+        Ok(())
     }
 
-    /// Increment and return the next issue number for a project.
+    /// Upsert an issue (for sync import).
     ///
     /// # Errors
     ///
-    /// Returns an error if the project doesn't exist or the update fails.
-    pub fn get_next_issue_number(&mut self, project_path: &str) -> Result<i32> {
-        let project = self
-            .get_project_by_path(project_path)?
-            .ok_or_else(|| Error::ProjectNotFound { id: project_path.to_string() })?;
+    /// Returns an error if the upsert fails.
+    pub fn upsert_issue(&mut self, issue: &Issue) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO issues (id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+             ON CONFLICT(id) DO UPDATE SET
+               short_id = excluded.short_id,
+               project_path = excluded.project_path,
+               title = excluded.title,
+               description = excluded.description,
+               details = excluded.details,
+               status = excluded.status,
+               priority = excluded.priority,
+               issue_type = excluded.issue_type,
+               plan_id = excluded.plan_id,
+               assigned_to_agent = excluded.assigned_to_agent,
+               updated_at = excluded.updated_at,
+               closed_at = excluded.closed_at,
+               subproject = excluded.subproject,
+               estimate_hours = excluded.estimate_hours",
+            rusqlite::params![
+                issue.id,
+                issue.short_id,
+                issue.project_path,
+                issue.title,
+                issue.description,
+                issue.details,
+                issue.status,
+                issue.priority,
+                issue.issue_type,
+                issue.plan_id,
+                issue.created_by_agent,
+                issue.assigned_to_agent,
+                issue.created_at,
+                issue.updated_at,
+                issue.closed_at,
+                issue.subproject,
+                issue.estimate_hours,
+            ],
+        )?;
+        Ok(())
+    }
 
-        let next_num = project.next_issue_number;
+    /// Upsert a context item (for sync import).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upsert fails.
+    pub fn upsert_context_item(&mut self, item: &ContextItem) -> Result<()> {
+        // Encrypted values travel as sealed text and are stored as they came
+        let (stored, compressed, encrypted) = if let Some(sealed) = crate::storage::encryption::unseal_text(&item.value) {
+            (rusqlite::types::Value::Blob(sealed), false, true)
+        } else {
+            let encrypt = crate::storage::categories::for_session(&self.conn, &item.session_id)?.encrypted(&item.category);
+            store_value(&self.conn, &item.value, encrypt, self.compression_threshold)?
+        };
+        self.conn.execute(
+            "INSERT INTO context_items (id, session_id, key, value, value_compressed, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at, value_encrypted)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+             ON CONFLICT(id) DO UPDATE SET
+               key = excluded.key,
+               value = excluded.value,
+               value_compressed = excluded.value_compressed,
+               value_encrypted = excluded.value_encrypted,
+               category = excluded.category,
+               priority = excluded.priority,
+               channel = excluded.channel,
+               tags = excluded.tags,
+               size = excluded.size,
+               updated_at = excluded.updated_at,
+               source = excluded.source,
+               trust = excluded.trust,
+               review_status = excluded.review_status,
+               due_at = excluded.due_at,
+               done_at = excluded.done_at",
+            rusqlite::params![
+                item.id,
+                item.session_id,
+                item.key,
+                stored,
+                compressed,
+                item.category,
+                item.priority,
+                item.channel,
+                item.tags,
+                item.size,
+                item.created_at,
+                item.updated_at,
+                item.source,
+                item.trust,
+                item.review_status,
+                item.due_at,
+                item.done_at,
+                encrypted,
+            ],
+        )?;
+        crate::storage::encryption::skip_embeddings(&self.conn, &item.id, encrypted)?;
+        Ok(())
+    }
 
-        // Increment the counter
+    /// Upsert a memory item (for sync import).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upsert fails.
+    pub fn upsert_memory(&mut self, memory: &Memory) -> Result<()> {
         self.conn.execute(
-            "UPDATE projects SET next_issue_number = next_issue_number + 1, updated_at = ?1 WHERE project_path = ?2",
-            rusqlite::params![chrono::Utc::now().timestamp_millis(), project_path],
+            "INSERT INTO project_memory (id, project_path, key, value, category, created_at, updated_at, subproject)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(id) DO UPDATE SET
+               key = excluded.key,
+               value = excluded.value,
+               category = excluded.category,
+               updated_at = excluded.updated_at,
+               subproject = excluded.subproject",
+            rusqlite::params![
+                memory.id,
+                memory.project_path,
+                memory.key,
+                memory.value,
+                memory.category,
+                memory.created_at,
+                memory.updated_at,
+                memory.subproject,
+            ],
         )?;
+        Ok(())
+    }
 
-        Ok(next_num)
+    /// Upsert a checkpoint (for sync import).
+    ///
+    /// Note: This does not import checkpoint items - those would need separate handling.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upsert fails.
+    pub fn upsert_checkpoint(&mut self, checkpoint: &Checkpoint) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO checkpoints (id, session_id, name, description, git_status, git_branch, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+               name = excluded.name,
+               description = excluded.description,
+               git_status = excluded.git_status,
+               git_branch = excluded.git_branch",
+            rusqlite::params![
+                checkpoint.id,
+                checkpoint.session_id,
+                checkpoint.name,
+                checkpoint.description,
+                checkpoint.git_status,
+                checkpoint.git_branch,
+                checkpoint.created_at,
+            ],
+        )?;
+        Ok(())
     }
 
     // ======================
-    // Plan Operations
+    // Project Operations
     // ======================
 
-    /// Create a new plan.
+    /// Create a new project.
     ///
     /// # Errors
     ///
-    /// Returns an error if the plan already exists or the insert fails.
-    pub fn create_plan(&mut self, plan: &Plan, actor: &str) -> Result<()> {
-        self.mutate("create_plan", actor, |tx, ctx| {
+    /// Returns an error if the project already exists or the insert fails.
+    pub fn create_project(&mut self, project: &Project, actor: &str) -> Result<()> {
+        self.mutate("create_project", actor, |tx, ctx| {
             tx.execute(
-                "INSERT INTO plans (id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, source_path, source_hash, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                "INSERT INTO projects (id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
                 rusqlite::params![
-                    plan.id,
-                    plan.short_id,
-                    plan.project_id,
-                    plan.project_path,
-                    plan.title,
-                    plan.content,
-                    plan.status.as_str(),
-                    plan.success_criteria,
-                    plan.session_id,
-                    plan.created_in_session,
-                    plan.source_path,
-                    plan.source_hash,
-                    plan.created_at,
-                    plan.updated_at,
+                    project.id,
+                    project.project_path,
+                    project.name,
+                    project.description,
+                    project.issue_prefix,
+                    project.next_issue_number,
+                    project.plan_prefix,
+                    project.next_plan_number,
+                    project.created_at,
+                    project.updated_at,
                 ],
             )?;
 
-            ctx.record_event("plan", &plan.id, EventType::PlanCreated);
+            ctx.record_event("project", &project.id, EventType::ProjectCreated);
             Ok(())
         })
     }
 
-    /// Get a plan by ID.
+    /// Get a project by ID.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn get_plan(&self, id: &str) -> Result<Option<Plan>> {
-        let plan = self
+    pub fn get_project(&self, id: &str) -> Result<Option<Project>> {
+        let project = self
             .conn
             .query_row(
-                "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
-                 FROM plans WHERE id = ?1",
+                "SELECT id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at
+                 FROM projects WHERE id = ?1",
                 [id],
-                map_plan_row,
+                map_project_row,
             )
             .optional()?;
-        Ok(plan)
+        Ok(project)
     }
 
-    /// List plans for a project.
+    /// Get a project by path, following a path alias to its target.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn list_plans(&self, project_path: &str, status: Option<&str>, limit: usize) -> Result<Vec<Plan>> {
-        let sql = if let Some(status) = status {
-            if status == "all" {
-                "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
-                 FROM plans WHERE project_path = ?1
-                 ORDER BY updated_at DESC
-                 LIMIT ?2".to_string()
-            } else {
-                format!(
-                    "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
-                     FROM plans WHERE project_path = ?1 AND status = '{}'
-                     ORDER BY updated_at DESC
-                     LIMIT ?2",
-                    status
-                )
-            }
-        } else {
-            // Default: show active plans only
-            "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
-             FROM plans WHERE project_path = ?1 AND status = 'active'
-             ORDER BY updated_at DESC
-             LIMIT ?2".to_string()
-        };
-
-        let mut stmt = self.conn.prepare(&sql)?;
-        let plans = stmt
-            .query_map(rusqlite::params![project_path, limit], map_plan_row)?
+    pub fn get_project_by_path(&self, project_path: &str) -> Result<Option<Project>> {
+        let project = self
+            .conn
+            .query_row(
+                "SELECT id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at
+                 FROM projects
+                 WHERE project_path = COALESCE((SELECT project_path FROM project_aliases WHERE alias_path = ?1), ?1)",
+                [project_path],
+                map_project_row,
+            )
+            .optional()?;
+        Ok(project)
+    }
+
+    /// List all projects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_projects(&self, limit: usize) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, project_path, name, description, issue_prefix, next_issue_number, plan_prefix, next_plan_number, created_at, updated_at
+             FROM projects
+             ORDER BY updated_at DESC
+             LIMIT ?1",
+        )?;
+
+        let projects = stmt
+            .query_map([limit], map_project_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(plans)
+        Ok(projects)
     }
 
-    /// Update a plan.
+    /// Every project path that has a project row or any stored records,
+    /// sorted.
     ///
     /// # Errors
     ///
-    /// Returns an error if the plan doesn't exist or the update fails.
-    pub fn update_plan(
+    /// Returns an error if the query fails.
+    pub fn data_project_paths(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT project_path FROM projects
+             UNION SELECT project_path FROM sessions WHERE project_path IS NOT NULL
+             UNION SELECT project_path FROM issues
+             UNION SELECT project_path FROM project_memory
+             UNION SELECT project_path FROM plans
+             UNION SELECT project_path FROM time_entries
+             ORDER BY 1",
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    /// Update a project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the project doesn't exist or the update fails.
+    pub fn update_project(
         &mut self,
         id: &str,
-        title: Option<&str>,
-        content: Option<&str>,
-        status: Option<&str>,
-        success_criteria: Option<&str>,
+        name: Option<&str>,
+        description: Option<&str>,
+        issue_prefix: Option<&str>,
         actor: &str,
     ) -> Result<()> {
-        self.mutate("update_plan", actor, |tx, ctx| {
+        self.mutate("update_project", actor, |tx, ctx| {
             let now = chrono::Utc::now().timestamp_millis();
 
             // Build dynamic update query
@@ -4062,34 +6405,21 @@ impl SqliteStorage {
             let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(now)];
             let mut param_idx = 2;
 
-            if let Some(t) = title {
-                updates.push(format!("title = ?{param_idx}").leak());
-                params.push(Box::new(t.to_string()));
-                param_idx += 1;
-            }
-
-            if let Some(c) = content {
-                updates.push(format!("content = ?{param_idx}").leak());
-                params.push(Box::new(c.to_string()));
+            if let Some(n) = name {
+                updates.push(format!("name = ?{param_idx}").leak());
+                params.push(Box::new(n.to_string()));
                 param_idx += 1;
             }
 
-            if let Some(s) = status {
-                updates.push(format!("status = ?{param_idx}").leak());
-                params.push(Box::new(s.to_string()));
+            if let Some(d) = description {
+                updates.push(format!("description = ?{param_idx}").leak());
+                params.push(Box::new(d.to_string()));
                 param_idx += 1;
-
-                // If marking completed, set completed_at
-                if s == "completed" {
-                    updates.push(format!("completed_at = ?{param_idx}").leak());
-                    params.push(Box::new(now));
-                    param_idx += 1;
-                }
             }
 
-            if let Some(sc) = success_criteria {
-                updates.push(format!("success_criteria = ?{param_idx}").leak());
-                params.push(Box::new(sc.to_string()));
+            if let Some(p) = issue_prefix {
+                updates.push(format!("issue_prefix = ?{param_idx}").leak());
+                params.push(Box::new(p.to_string()));
                 param_idx += 1;
             }
 
@@ -4097,7 +6427,7 @@ impl SqliteStorage {
             params.push(Box::new(id.to_string()));
 
             let sql = format!(
-                "UPDATE plans SET {} WHERE id = ?{}",
+                "UPDATE projects SET {} WHERE id = ?{}",
                 updates.join(", "),
                 param_idx
             );
@@ -4106,1579 +6436,4649 @@ impl SqliteStorage {
             let affected = tx.execute(&sql, param_refs.as_slice())?;
 
             if affected == 0 {
-                return Err(Error::Other(format!("Plan not found: {id}")));
+                return Err(Error::ProjectNotFound { id: id.to_string() });
             }
 
-            let event_type = if status == Some("completed") {
-                EventType::PlanCompleted
-            } else {
-                EventType::PlanUpdated
-            };
-            ctx.record_event("plan", id, event_type);
+            ctx.record_event("project", id, EventType::ProjectUpdated);
             Ok(())
         })
     }
 
-    /// Get all plans for a specific project (for JSONL sync export).
-    ///
-    /// # Errors
+    /// Delete a project and all associated data.
     ///
-    /// Returns an error if the query fails.
-    pub fn get_plans_by_project(&self, project_path: &str) -> Result<Vec<Plan>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
-             FROM plans WHERE project_path = ?1 ORDER BY created_at ASC",
-        )?;
-        let rows = stmt.query_map([project_path], map_plan_row)?;
-        let plans: Vec<Plan> = rows.collect::<std::result::Result<_, _>>()?;
-        Ok(plans)
-    }
-
-    /// Find a plan by source hash (for capture deduplication).
+    /// This cascades to delete:
+    /// - All sessions (and their context items, checkpoints)
+    /// - All issues
+    /// - All plans
+    /// - All project memory
     ///
     /// # Errors
     ///
-    /// Returns an error if the query fails.
-    pub fn find_plan_by_source_hash(&self, source_hash: &str) -> Result<Option<Plan>> {
-        let plan = self
-            .conn
-            .query_row(
-                "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
-                 FROM plans WHERE source_hash = ?1 LIMIT 1",
-                [source_hash],
-                map_plan_row,
-            )
-            .optional()?;
-        Ok(plan)
+    /// Returns an error if the project doesn't exist or deletion fails.
+    pub fn delete_project(&mut self, id: &str, actor: &str) -> Result<()> {
+        self.mutate("delete_project", actor, |tx, ctx| {
+            // Get project path for cascading deletes
+            let project_path: Option<String> = tx
+                .query_row(
+                    "SELECT project_path FROM projects WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let project_path = project_path.ok_or_else(|| Error::ProjectNotFound { id: id.to_string() })?;
+
+            // Delete sessions (cascades to context_items, checkpoints via FK)
+            tx.execute(
+                "DELETE FROM sessions WHERE project_path = ?1",
+                [&project_path],
+            )?;
+
+            // Delete issues
+            tx.execute(
+                "DELETE FROM issues WHERE project_path = ?1",
+                [&project_path],
+            )?;
+
+            // Delete plans
+            tx.execute(
+                "DELETE FROM plans WHERE project_path = ?1",
+                [&project_path],
+            )?;
+
+            // Delete project memory
+            tx.execute(
+                "DELETE FROM project_memory WHERE project_path = ?1",
+                [&project_path],
+            )?;
+
+            // Delete its registered sub-projects
+            tx.execute(
+                "DELETE FROM subprojects WHERE project_path = ?1",
+                [&project_path],
+            )?;
+
+            // Delete the project itself
+            let affected = tx.execute("DELETE FROM projects WHERE id = ?1", [id])?;
+
+            if affected == 0 {
+                return Err(Error::ProjectNotFound { id: id.to_string() });
+            }
+
+            ctx.record_event("project", id, EventType::ProjectDeleted);
+            Ok(())
+        })
     }
 
-    /// Upsert a plan (for sync import).
+    /// Get or create a project for the given path.
+    ///
+    /// If a project already exists at the path, returns it.
+    /// Otherwise, creates a new project with a name derived from the path.
     ///
     /// # Errors
     ///
-    /// Returns an error if the upsert fails.
-    pub fn upsert_plan(&mut self, plan: &Plan) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO plans (id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
-             ON CONFLICT(id) DO UPDATE SET
-               short_id = excluded.short_id,
-               title = excluded.title,
-               content = excluded.content,
-               status = excluded.status,
-               success_criteria = excluded.success_criteria,
-               session_id = excluded.session_id,
-               source_path = excluded.source_path,
-               source_hash = excluded.source_hash,
-               updated_at = excluded.updated_at,
-               completed_at = excluded.completed_at",
-            rusqlite::params![
-                plan.id,
-                plan.short_id,
-                plan.project_id,
-                plan.project_path,
-                plan.title,
-                plan.content,
-                plan.status.as_str(),
-                plan.success_criteria,
-                plan.session_id,
-                plan.created_in_session,
-                plan.completed_in_session,
-                plan.source_path,
-                plan.source_hash,
-                plan.created_at,
-                plan.updated_at,
-                plan.completed_at,
-            ],
-        )?;
-        Ok(())
+    /// Returns an error if the database operation fails.
+    pub fn get_or_create_project(&mut self, project_path: &str, actor: &str) -> Result<Project> {
+        // Check if project exists
+        if let Some(project) = self.get_project_by_path(project_path)? {
+            return Ok(project);
+        }
+
+        // Create new project with name from path
+        let name = std::path::Path::new(project_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown Project")
+            .to_string();
+
+        let project = Project::new(project_path.to_string(), name);
+        self.create_project(&project, actor)?;
+        Ok(project)
     }
 
-    /// Get dirty plan IDs by project (for JSONL sync export).
+    /// List path aliases as `(alias_path, project_path)` pairs.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn get_dirty_plans_by_project(&self, project_path: &str) -> Result<Vec<String>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT dp.plan_id
-             FROM dirty_plans dp
-             INNER JOIN plans p ON dp.plan_id = p.id
-             WHERE p.project_path = ?1",
-        )?;
-        let rows = stmt.query_map([project_path], |row| row.get(0))?;
-        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    pub fn list_project_aliases(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT alias_path, project_path FROM project_aliases ORDER BY alias_path")?;
+        let aliases = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(aliases)
     }
 
-    /// Clear dirty flags for plans after successful export.
+    /// Move everything stored under project path `from` to `into`, then
+    /// record `from` as an alias of `into`.
+    ///
+    /// Memory keys stored under both paths keep the more recently updated
+    /// value. If both paths have a `projects` row, the one for `from` is
+    /// dropped and the target keeps the higher issue/plan counters.
     ///
     /// # Errors
     ///
-    /// Returns an error if the delete fails.
-    pub fn clear_dirty_plans(&mut self, ids: &[String]) -> Result<()> {
-        if ids.is_empty() {
-            return Ok(());
+    /// Returns an error if the paths are equal, nothing is stored under
+    /// `from`, or a statement fails.
+    pub fn merge_project_paths(&mut self, from: &str, into: &str, actor: &str) -> Result<PathMoveStats> {
+        if from == into {
+            return Err(Error::InvalidArgument("Cannot merge a project path into itself".to_string()));
         }
-        let placeholders = vec!["?"; ids.len()].join(",");
-        let sql = format!("DELETE FROM dirty_plans WHERE plan_id IN ({placeholders})");
-        let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
-        self.conn.execute(&sql, params.as_slice())?;
-        Ok(())
-    }
+        self.mutate("merge_project_paths", actor, |tx, ctx| {
+            let now = chrono::Utc::now().timestamp_millis();
+            let mut stats = move_path_rows(tx, from, into)?;
 
-    // ==========================
-    // Time Entry Operations
-    // ==========================
+            let project_id = |path: &str| {
+                tx.query_row("SELECT id FROM projects WHERE project_path = ?1", [path], |row| {
+                    row.get::<_, String>(0)
+                })
+                .optional()
+            };
+            match (project_id(from)?, project_id(into)?) {
+                (Some(old), Some(target)) => {
+                    tx.execute(
+                        "UPDATE projects SET
+                             next_issue_number = MAX(next_issue_number, (SELECT next_issue_number FROM projects WHERE id = ?1)),
+                             next_plan_number = MAX(next_plan_number, (SELECT next_plan_number FROM projects WHERE id = ?1)),
+                             updated_at = ?3
+                         WHERE id = ?2",
+                        rusqlite::params![old, target, now],
+                    )?;
+                    tx.execute("DELETE FROM projects WHERE id = ?1", [&old])?;
+                    ctx.record_event("project", &old, EventType::ProjectDeleted);
+                    ctx.record_event("project", &target, EventType::ProjectUpdated);
+                    stats.project_updated = true;
+                }
+                (Some(old), None) => {
+                    tx.execute(
+                        "UPDATE projects SET project_path = ?1, updated_at = ?2 WHERE id = ?3",
+                        rusqlite::params![into, now, old],
+                    )?;
+                    ctx.record_event("project", &old, EventType::ProjectUpdated);
+                    stats.project_updated = true;
+                }
+                (None, _) => {}
+            }
 
-    /// Create a new time entry.
-    pub fn create_time_entry(
-        &mut self,
-        id: &str,
-        short_id: Option<&str>,
-        project_path: &str,
-        hours: f64,
-        description: &str,
-        work_date: &str,
-        issue_id: Option<&str>,
-        period: Option<&str>,
-        actor: &str,
-    ) -> Result<()> {
-        let now = chrono::Utc::now().timestamp_millis();
+            if stats.rows() == 0 && !stats.project_updated {
+                return Err(Error::InvalidArgument(format!("Nothing is stored under '{from}'")));
+            }
 
-        self.mutate("create_time_entry", actor, |tx, ctx| {
+            tx.execute("UPDATE project_aliases SET project_path = ?2 WHERE project_path = ?1", [from, into])?;
+            tx.execute("DELETE FROM project_aliases WHERE alias_path = ?1", [into])?;
             tx.execute(
-                "INSERT INTO time_entries (id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'logged', ?9, ?10, ?10)",
-                rusqlite::params![id, short_id, project_path, issue_id, period, hours, description, work_date, actor, now],
+                "INSERT OR REPLACE INTO project_aliases (alias_path, project_path, created_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![from, into, now],
             )?;
-
-            ctx.record_event("time_entry", id, EventType::TimeEntryCreated);
-            ctx.mark_time_entry_dirty(id);
-
-            Ok(())
+            Ok(stats)
         })
     }
 
-    /// Get a time entry by ID (full or short).
-    pub fn get_time_entry(&self, id: &str, project_path: Option<&str>) -> Result<Option<TimeEntry>> {
-        let sql = if project_path.is_some() {
-            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
-             FROM time_entries WHERE (id = ?1 OR short_id = ?1) AND project_path = ?2"
-        } else {
-            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
-             FROM time_entries WHERE id = ?1 OR short_id = ?1"
-        };
-
-        let mut stmt = self.conn.prepare(sql)?;
-
-        let entry = if let Some(path) = project_path {
-            stmt.query_row(rusqlite::params![id, path], map_time_entry_row)
-        } else {
-            stmt.query_row(rusqlite::params![id], map_time_entry_row)
-        }
-        .optional()?;
-
-        Ok(entry)
-    }
-
-    /// List time entries with optional filters.
-    pub fn list_time_entries(
-        &self,
-        project_path: &str,
-        period: Option<&str>,
-        status: Option<&str>,
-        issue_id: Option<&str>,
-        date_from: Option<&str>,
-        date_to: Option<&str>,
-        limit: Option<u32>,
-    ) -> Result<Vec<TimeEntry>> {
-        let mut conditions = vec!["project_path = ?1".to_string()];
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(project_path.to_string())];
-        let mut idx = 2;
-
-        if let Some(p) = period {
-            conditions.push(format!("period = ?{idx}"));
-            params.push(Box::new(p.to_string()));
-            idx += 1;
-        }
-        if let Some(s) = status {
-            conditions.push(format!("status = ?{idx}"));
-            params.push(Box::new(s.to_string()));
-            idx += 1;
-        }
-        if let Some(iid) = issue_id {
-            conditions.push(format!("issue_id = ?{idx}"));
-            params.push(Box::new(iid.to_string()));
-            idx += 1;
-        }
-        if let Some(df) = date_from {
-            conditions.push(format!("work_date >= ?{idx}"));
-            params.push(Box::new(df.to_string()));
-            idx += 1;
+    /// Re-key a project that moved on disk from `from` to `into`.
+    ///
+    /// Unlike [`merge_project_paths`](Self::merge_project_paths) the old
+    /// path is not kept as an alias, since a different repository may
+    /// later live there. Aliases of the old path follow the project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the paths are equal, a project is already
+    /// registered at `into`, nothing is stored under `from`, or a statement
+    /// fails.
+    pub fn move_project_path(&mut self, from: &str, into: &str, actor: &str) -> Result<PathMoveStats> {
+        if from == into {
+            return Err(Error::InvalidArgument("Old and new project paths are the same".to_string()));
         }
-        if let Some(dt) = date_to {
-            conditions.push(format!("work_date <= ?{idx}"));
-            params.push(Box::new(dt.to_string()));
-            idx += 1;
+        if let Some(existing) = self.get_project_by_path(into)? {
+            return Err(Error::InvalidArgument(format!(
+                "Project '{}' is already registered at {}; use `sc project merge-paths` to combine them",
+                existing.name, existing.project_path
+            )));
         }
+        self.mutate("move_project_path", actor, |tx, ctx| {
+            let now = chrono::Utc::now().timestamp_millis();
+            let mut stats = move_path_rows(tx, from, into)?;
 
-        let limit_val = limit.unwrap_or(200);
-        conditions.push(format!("1=1"));
-        let where_clause = conditions.join(" AND ");
-        let sql = format!(
-            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
-             FROM time_entries WHERE {where_clause} ORDER BY work_date DESC, created_at DESC LIMIT ?{idx}"
-        );
-
-        params.push(Box::new(limit_val));
-
-        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map(param_refs.as_slice(), map_time_entry_row)?;
-        let entries: Vec<TimeEntry> = rows.collect::<std::result::Result<_, _>>()?;
-        Ok(entries)
-    }
-
-    /// Update a time entry (partial fields).
-    pub fn update_time_entry(
-        &mut self,
-        id: &str,
-        project_path: &str,
-        hours: Option<f64>,
-        description: Option<&str>,
-        period: Option<&str>,
-        issue_id: Option<&str>,
-        work_date: Option<&str>,
-        actor: &str,
-    ) -> Result<()> {
-        // Resolve full ID from short ID
-        let full_id = self.resolve_time_entry_id(id, Some(project_path))?;
-        let now = chrono::Utc::now().timestamp_millis();
-
-        self.mutate("update_time_entry", actor, |tx, ctx| {
-            let mut sets = vec!["updated_at = ?1".to_string()];
-            let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(now)];
-            let mut idx = 2;
-
-            if let Some(h) = hours {
-                sets.push(format!("hours = ?{idx}"));
-                params.push(Box::new(h));
-                idx += 1;
-            }
-            if let Some(d) = description {
-                sets.push(format!("description = ?{idx}"));
-                params.push(Box::new(d.to_string()));
-                idx += 1;
-            }
-            if let Some(p) = period {
-                sets.push(format!("period = ?{idx}"));
-                params.push(Box::new(p.to_string()));
-                idx += 1;
-            }
-            if let Some(iid) = issue_id {
-                sets.push(format!("issue_id = ?{idx}"));
-                params.push(Box::new(iid.to_string()));
-                idx += 1;
+            let project_id: Option<String> = tx
+                .query_row("SELECT id FROM projects WHERE project_path = ?1", [from], |row| row.get(0))
+                .optional()?;
+            if let Some(id) = project_id {
+                tx.execute(
+                    "UPDATE projects SET project_path = ?1, updated_at = ?2 WHERE id = ?3",
+                    rusqlite::params![into, now, id],
+                )?;
+                ctx.record_event("project", &id, EventType::ProjectUpdated);
+                stats.project_updated = true;
             }
-            if let Some(wd) = work_date {
-                sets.push(format!("work_date = ?{idx}"));
-                params.push(Box::new(wd.to_string()));
-                idx += 1;
+
+            if stats.rows() == 0 && !stats.project_updated {
+                return Err(Error::InvalidArgument(format!("Nothing is stored under '{from}'")));
             }
+            tx.execute("UPDATE project_aliases SET project_path = ?2 WHERE project_path = ?1", [from, into])?;
+            Ok(stats)
+        })
+    }
 
-            let set_clause = sets.join(", ");
-            let sql = format!("UPDATE time_entries SET {set_clause} WHERE id = ?{idx}");
-            params.push(Box::new(full_id.clone()));
+    /// Increment and return the next issue number for a project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the project doesn't exist or the update fails.
+    pub fn get_next_issue_number(&mut self, project_path: &str) -> Result<i32> {
+        let project = self
+            .get_project_by_path(project_path)?
+            .ok_or_else(|| Error::ProjectNotFound { id: project_path.to_string() })?;
 
-            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-            tx.execute(&sql, param_refs.as_slice())?;
+        let next_num = project.next_issue_number;
 
-            ctx.record_event("time_entry", &full_id, EventType::TimeEntryUpdated);
-            ctx.mark_time_entry_dirty(&full_id);
+        // Increment the counter
+        self.conn.execute(
+            "UPDATE projects SET next_issue_number = next_issue_number + 1, updated_at = ?1 WHERE project_path = ?2",
+            rusqlite::params![chrono::Utc::now().timestamp_millis(), project_path],
+        )?;
 
-            Ok(())
-        })
+        Ok(next_num)
     }
 
-    /// Update time entry status.
-    pub fn update_time_entry_status(
-        &mut self,
-        id: &str,
-        project_path: &str,
-        status: &str,
-        actor: &str,
-    ) -> Result<()> {
-        let full_id = self.resolve_time_entry_id(id, Some(project_path))?;
-        let now = chrono::Utc::now().timestamp_millis();
+    // ======================
+    // Plan Operations
+    // ======================
 
-        self.mutate("update_time_entry_status", actor, |tx, ctx| {
+    /// Create a new plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plan already exists or the insert fails.
+    pub fn create_plan(&mut self, plan: &Plan, actor: &str) -> Result<()> {
+        self.mutate("create_plan", actor, |tx, ctx| {
             tx.execute(
-                "UPDATE time_entries SET status = ?1, updated_at = ?2 WHERE id = ?3",
-                rusqlite::params![status, now, full_id],
+                "INSERT INTO plans (id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, source_path, source_hash, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                rusqlite::params![
+                    plan.id,
+                    plan.short_id,
+                    plan.project_id,
+                    plan.project_path,
+                    plan.title,
+                    plan.content,
+                    plan.status.as_str(),
+                    plan.success_criteria,
+                    plan.session_id,
+                    plan.created_in_session,
+                    plan.source_path,
+                    plan.source_hash,
+                    plan.created_at,
+                    plan.updated_at,
+                ],
             )?;
 
-            ctx.record_event("time_entry", &full_id, EventType::TimeEntryStatusChanged);
-            ctx.mark_time_entry_dirty(&full_id);
-
+            snapshot_plan_revision(tx, &plan.id, actor, plan.created_at)?;
+            ctx.record_event("plan", &plan.id, EventType::PlanCreated);
             Ok(())
         })
     }
 
-    /// Batch update time entries from one status to another for a period.
-    /// Returns (count, total_hours).
-    pub fn invoice_time_entries(
-        &mut self,
-        project_path: &str,
-        period: &str,
-        from_status: &str,
-        to_status: &str,
-        actor: &str,
-    ) -> Result<(usize, f64)> {
-        let now = chrono::Utc::now().timestamp_millis();
-
-        // First get the entries to update (for event tracking)
-        let entries = self.list_time_entries(project_path, Some(period), Some(from_status), None, None, None, None)?;
-        let count = entries.len();
-        let total_hours: f64 = entries.iter().map(|e| e.hours).sum();
-
-        if count == 0 {
-            return Ok((0, 0.0));
-        }
-
-        self.mutate("invoice_time_entries", actor, |tx, ctx| {
-            tx.execute(
-                "UPDATE time_entries SET status = ?1, updated_at = ?2 WHERE project_path = ?3 AND period = ?4 AND status = ?5",
-                rusqlite::params![to_status, now, project_path, period, from_status],
-            )?;
+    /// Get a plan by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_plan(&self, id: &str) -> Result<Option<Plan>> {
+        let plan = self
+            .conn
+            .query_row(
+                "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
+                 FROM plans WHERE id = ?1",
+                [id],
+                map_plan_row,
+            )
+            .optional()?;
+        Ok(plan)
+    }
 
-            for entry in &entries {
-                ctx.record_event("time_entry", &entry.id, EventType::TimeEntryStatusChanged);
-                ctx.mark_time_entry_dirty(&entry.id);
+    /// List plans for a project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_plans(&self, project_path: &str, status: Option<&str>, limit: usize) -> Result<Vec<Plan>> {
+        let sql = if let Some(status) = status {
+            if status == "all" {
+                "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
+                 FROM plans WHERE project_path = ?1
+                 ORDER BY updated_at DESC
+                 LIMIT ?2".to_string()
+            } else {
+                format!(
+                    "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
+                     FROM plans WHERE project_path = ?1 AND status = '{}'
+                     ORDER BY updated_at DESC
+                     LIMIT ?2",
+                    status
+                )
             }
+        } else {
+            // Default: show active plans only
+            "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
+             FROM plans WHERE project_path = ?1 AND status = 'active'
+             ORDER BY updated_at DESC
+             LIMIT ?2".to_string()
+        };
 
-            Ok(())
-        })?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let plans = stmt
+            .query_map(rusqlite::params![project_path, limit], map_plan_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok((count, total_hours))
+        Ok(plans)
     }
 
-    /// Delete a time entry.
-    pub fn delete_time_entry(
+    /// Update a plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plan doesn't exist or the update fails.
+    pub fn update_plan(
         &mut self,
         id: &str,
-        project_path: &str,
+        title: Option<&str>,
+        content: Option<&str>,
+        status: Option<&str>,
+        success_criteria: Option<&str>,
         actor: &str,
     ) -> Result<()> {
-        let full_id = self.resolve_time_entry_id(id, Some(project_path))?;
+        self.mutate("update_plan", actor, |tx, ctx| {
+            let now = chrono::Utc::now().timestamp_millis();
 
-        self.mutate("delete_time_entry", actor, |tx, ctx| {
-            tx.execute(
-                "DELETE FROM time_entries WHERE id = ?1",
-                rusqlite::params![full_id],
-            )?;
+            // Plans created before revisions existed have no history yet;
+            // keep their current state as the baseline revision.
+            snapshot_plan_revision(tx, id, actor, now)?;
 
-            // Clean up dirty tracking
-            tx.execute(
-                "DELETE FROM dirty_time_entries WHERE time_entry_id = ?1",
-                rusqlite::params![full_id],
-            )?;
+            // Build dynamic update query
+            let mut updates = vec!["updated_at = ?1"];
+            let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(now)];
+            let mut param_idx = 2;
 
-            ctx.record_event("time_entry", &full_id, EventType::TimeEntryDeleted);
+            if let Some(t) = title {
+                updates.push(format!("title = ?{param_idx}").leak());
+                params.push(Box::new(t.to_string()));
+                param_idx += 1;
+            }
 
-            Ok(())
-        })
-    }
+            if let Some(c) = content {
+                updates.push(format!("content = ?{param_idx}").leak());
+                params.push(Box::new(c.to_string()));
+                param_idx += 1;
+            }
 
-    /// Get total hours for a project, optionally filtered by period and/or status.
-    pub fn get_time_total(
-        &self,
-        project_path: &str,
-        period: Option<&str>,
-        status: Option<&str>,
-    ) -> Result<f64> {
-        let mut conditions = vec!["project_path = ?1".to_string()];
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(project_path.to_string())];
-        let mut idx = 2;
+            if let Some(s) = status {
+                updates.push(format!("status = ?{param_idx}").leak());
+                params.push(Box::new(s.to_string()));
+                param_idx += 1;
 
-        if let Some(p) = period {
-            conditions.push(format!("period = ?{idx}"));
-            params.push(Box::new(p.to_string()));
-            idx += 1;
-        }
-        if let Some(s) = status {
-            conditions.push(format!("status = ?{idx}"));
-            params.push(Box::new(s.to_string()));
-            let _ = idx;
-        }
+                // If marking completed, set completed_at
+                if s == "completed" {
+                    updates.push(format!("completed_at = ?{param_idx}").leak());
+                    params.push(Box::new(now));
+                    param_idx += 1;
+                }
+            }
 
-        let where_clause = conditions.join(" AND ");
-        let sql = format!("SELECT COALESCE(SUM(hours), 0) FROM time_entries WHERE {where_clause}");
+            if let Some(sc) = success_criteria {
+                updates.push(format!("success_criteria = ?{param_idx}").leak());
+                params.push(Box::new(sc.to_string()));
+                param_idx += 1;
+            }
 
-        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        let total: f64 = self.conn.query_row(&sql, param_refs.as_slice(), |row| row.get(0))?;
-        Ok(total)
-    }
+            // Add the WHERE clause parameter
+            params.push(Box::new(id.to_string()));
 
-    /// Get total hours logged against a specific issue.
-    pub fn get_issue_time_total(&self, issue_id: &str) -> Result<f64> {
-        let total: f64 = self.conn.query_row(
-            "SELECT COALESCE(SUM(hours), 0) FROM time_entries WHERE issue_id = ?1",
-            rusqlite::params![issue_id],
-            |row| row.get(0),
-        )?;
-        Ok(total)
+            let sql = format!(
+                "UPDATE plans SET {} WHERE id = ?{}",
+                updates.join(", "),
+                param_idx
+            );
+
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            let affected = tx.execute(&sql, param_refs.as_slice())?;
+
+            if affected == 0 {
+                return Err(Error::Other(format!("Plan not found: {id}")));
+            }
+
+            snapshot_plan_revision(tx, id, actor, now)?;
+
+            let event_type = if status == Some("completed") {
+                EventType::PlanCompleted
+            } else {
+                EventType::PlanUpdated
+            };
+            ctx.record_event("plan", id, event_type);
+            Ok(())
+        })
     }
 
-    /// Get all time entries for a project (for sync export).
-    pub fn get_time_entries_by_project(&self, project_path: &str) -> Result<Vec<TimeEntry>> {
+    /// List the revisions of a plan, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_plan_revisions(&self, plan_id: &str) -> Result<Vec<PlanRevision>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
-             FROM time_entries WHERE project_path = ?1 ORDER BY created_at ASC",
+            "SELECT plan_id, revision, title, content, success_criteria, actor, created_at
+             FROM plan_revisions WHERE plan_id = ?1 ORDER BY revision ASC",
         )?;
-        let rows = stmt.query_map([project_path], map_time_entry_row)?;
-        let entries: Vec<TimeEntry> = rows.collect::<std::result::Result<_, _>>()?;
-        Ok(entries)
+        let rows = stmt.query_map([plan_id], map_plan_revision_row)?;
+        let revisions: Vec<PlanRevision> = rows.collect::<std::result::Result<_, _>>()?;
+        Ok(revisions)
     }
 
-    /// Resolve a time entry ID (full or short) to a full ID.
-    fn resolve_time_entry_id(&self, id: &str, project_path: Option<&str>) -> Result<String> {
-        let entry = self.get_time_entry(id, project_path)?
-            .ok_or_else(|| Error::InvalidArgument(format!("Time entry not found: {id}")))?;
-        Ok(entry.id)
+    /// Get a single plan revision.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_plan_revision(&self, plan_id: &str, revision: i64) -> Result<Option<PlanRevision>> {
+        let rev = self
+            .conn
+            .query_row(
+                "SELECT plan_id, revision, title, content, success_criteria, actor, created_at
+                 FROM plan_revisions WHERE plan_id = ?1 AND revision = ?2",
+                rusqlite::params![plan_id, revision],
+                map_plan_revision_row,
+            )
+            .optional()?;
+        Ok(rev)
     }
 
-    /// Get dirty time entry IDs for a project.
-    pub fn get_dirty_time_entries_by_project(&self, project_path: &str) -> Result<Vec<String>> {
+    /// Restore a plan's title, content, and success criteria from an earlier
+    /// revision.
+    ///
+    /// The restore is recorded as a new revision, so reverting never loses
+    /// history. Returns the number of the revision now current.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plan or revision doesn't exist.
+    pub fn revert_plan(&mut self, plan_id: &str, revision: i64, actor: &str) -> Result<i64> {
+        self.mutate("revert_plan", actor, |tx, ctx| {
+            let now = chrono::Utc::now().timestamp_millis();
+
+            let target = tx
+                .query_row(
+                    "SELECT plan_id, revision, title, content, success_criteria, actor, created_at
+                     FROM plan_revisions WHERE plan_id = ?1 AND revision = ?2",
+                    rusqlite::params![plan_id, revision],
+                    map_plan_revision_row,
+                )
+                .optional()?
+                .ok_or_else(|| Error::Other(format!("Revision {revision} not found for plan {plan_id}")))?;
+
+            snapshot_plan_revision(tx, plan_id, actor, now)?;
+
+            let affected = tx.execute(
+                "UPDATE plans SET title = ?1, content = ?2, success_criteria = ?3, updated_at = ?4 WHERE id = ?5",
+                rusqlite::params![target.title, target.content, target.success_criteria, now, plan_id],
+            )?;
+            if affected == 0 {
+                return Err(Error::Other(format!("Plan not found: {plan_id}")));
+            }
+
+            snapshot_plan_revision(tx, plan_id, actor, now)?;
+
+            let current: i64 = tx.query_row(
+                "SELECT MAX(revision) FROM plan_revisions WHERE plan_id = ?1",
+                [plan_id],
+                |row| row.get(0),
+            )?;
+
+            ctx.record_event("plan", plan_id, EventType::PlanUpdated);
+            Ok(current)
+        })
+    }
+
+    /// Get all plans for a specific project (for JSONL sync export).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_plans_by_project(&self, project_path: &str) -> Result<Vec<Plan>> {
         let mut stmt = self.conn.prepare(
-            "SELECT dte.time_entry_id
-             FROM dirty_time_entries dte
-             INNER JOIN time_entries te ON dte.time_entry_id = te.id
-             WHERE te.project_path = ?1",
+            "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
+             FROM plans WHERE project_path = ?1 ORDER BY created_at ASC",
         )?;
-        let rows = stmt.query_map([project_path], |row| row.get(0))?;
-        Ok(rows.collect::<std::result::Result<_, _>>()?)
+        let rows = stmt.query_map([project_path], map_plan_row)?;
+        let plans: Vec<Plan> = rows.collect::<std::result::Result<_, _>>()?;
+        Ok(plans)
     }
 
-    /// Clear dirty flags for time entries after export.
-    pub fn clear_dirty_time_entries(&mut self, ids: &[String]) -> Result<()> {
-        if ids.is_empty() {
-            return Ok(());
-        }
-        let placeholders = vec!["?"; ids.len()].join(",");
-        let sql = format!("DELETE FROM dirty_time_entries WHERE time_entry_id IN ({placeholders})");
-        let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
-        self.conn.execute(&sql, params.as_slice())?;
-        Ok(())
+    /// Find a plan by source hash (for capture deduplication).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn find_plan_by_source_hash(&self, source_hash: &str) -> Result<Option<Plan>> {
+        let plan = self
+            .conn
+            .query_row(
+                "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
+                 FROM plans WHERE source_hash = ?1 LIMIT 1",
+                [source_hash],
+                map_plan_row,
+            )
+            .optional()?;
+        Ok(plan)
     }
 
-    /// Upsert a time entry (for sync import).
-    pub fn upsert_time_entry(&mut self, entry: &TimeEntry) -> Result<()> {
-        self.conn.execute(
-            "INSERT INTO time_entries (id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
-             ON CONFLICT(id) DO UPDATE SET
-               short_id = excluded.short_id,
-               issue_id = excluded.issue_id,
-               period = excluded.period,
-               hours = excluded.hours,
-               description = excluded.description,
-               work_date = excluded.work_date,
-               status = excluded.status,
-               actor = excluded.actor,
-               updated_at = excluded.updated_at",
-            rusqlite::params![
-                entry.id,
-                entry.short_id,
-                entry.project_path,
-                entry.issue_id,
-                entry.period,
-                entry.hours,
-                entry.description,
-                entry.work_date,
-                entry.status,
-                entry.actor,
-                entry.created_at,
-                entry.updated_at,
-            ],
-        )?;
-        Ok(())
+    /// Find a plan captured from a given source file in a project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn find_plan_by_source_path(&self, project_path: &str, source_path: &str) -> Result<Option<Plan>> {
+        let plan = self
+            .conn
+            .query_row(
+                "SELECT id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at
+                 FROM plans WHERE project_path = ?1 AND source_path = ?2
+                 ORDER BY updated_at DESC LIMIT 1",
+                [project_path, source_path],
+                map_plan_row,
+            )
+            .optional()?;
+        Ok(plan)
     }
 
-    // ======================
-    // Embedding Operations
-    // ======================
-
-    /// Store an embedding chunk for a context item.
-    ///
-    /// Embeddings are stored as BLOBs (binary f32 arrays).
-    /// Large items may have multiple chunks for full semantic coverage.
+    /// Replace a plan's title and content with a fresh read of its source
+    /// file, recording the new source path and hash.
     ///
     /// # Errors
     ///
-    /// Returns an error if the insert fails.
-    pub fn store_embedding_chunk(
+    /// Returns an error if the plan doesn't exist or the update fails.
+    pub fn update_plan_source(
         &mut self,
         id: &str,
-        item_id: &str,
-        chunk_index: i32,
-        chunk_text: &str,
-        embedding: &[f32],
-        provider: &str,
-        model: &str,
+        title: &str,
+        content: &str,
+        source_path: &str,
+        source_hash: &str,
+        actor: &str,
     ) -> Result<()> {
-        let now = chrono::Utc::now().timestamp_millis();
-        let dimensions = embedding.len() as i32;
+        self.mutate("update_plan_source", actor, |tx, ctx| {
+            let now = chrono::Utc::now().timestamp_millis();
+            snapshot_plan_revision(tx, id, actor, now)?;
 
-        // Convert f32 slice to bytes (little-endian)
-        let blob: Vec<u8> = embedding
-            .iter()
-            .flat_map(|f| f.to_le_bytes())
-            .collect();
+            let affected = tx.execute(
+                "UPDATE plans SET title = ?1, content = ?2, source_path = ?3, source_hash = ?4, updated_at = ?5
+                 WHERE id = ?6",
+                rusqlite::params![title, content, source_path, source_hash, now, id],
+            )?;
+            if affected == 0 {
+                return Err(Error::Other(format!("Plan not found: {id}")));
+            }
 
-        self.conn.execute(
-            "INSERT INTO embedding_chunks (id, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-             ON CONFLICT(item_id, chunk_index) DO UPDATE SET
-               chunk_text = excluded.chunk_text,
-               embedding = excluded.embedding,
-               dimensions = excluded.dimensions,
-               provider = excluded.provider,
-               model = excluded.model,
-               created_at = excluded.created_at",
-            rusqlite::params![id, item_id, chunk_index, chunk_text, blob, dimensions, provider, model, now],
+            snapshot_plan_revision(tx, id, actor, now)?;
+            ctx.record_event("plan", id, EventType::PlanUpdated);
+            Ok(())
+        })
+    }
+
+    /// List open (not closed) issues linked to a plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_plan_issues(&self, plan_id: &str) -> Result<Vec<Issue>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, short_id, project_path, title, description, details, status, priority, issue_type, plan_id, created_by_agent, assigned_to_agent, created_at, updated_at, closed_at, subproject, estimate_hours
+             FROM issues WHERE plan_id = ?1 AND status != 'closed'
+             ORDER BY priority DESC, created_at ASC",
         )?;
+        let rows = stmt.query_map([plan_id], map_issue_row)?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
 
-        // Update context_items embedding metadata
+    /// Upsert a plan (for sync import).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upsert fails.
+    pub fn upsert_plan(&mut self, plan: &Plan) -> Result<()> {
         self.conn.execute(
-            "UPDATE context_items SET
-               embedding_status = 'complete',
-               embedding_provider = ?1,
-               embedding_model = ?2,
-               chunk_count = COALESCE(
-                 (SELECT MAX(chunk_index) + 1 FROM embedding_chunks WHERE item_id = ?3),
-                 1
-               ),
-               embedded_at = ?4
-             WHERE id = ?3",
-            rusqlite::params![provider, model, item_id, now],
+            "INSERT INTO plans (id, short_id, project_id, project_path, title, content, status, success_criteria, session_id, created_in_session, completed_in_session, source_path, source_hash, created_at, updated_at, completed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+             ON CONFLICT(id) DO UPDATE SET
+               short_id = excluded.short_id,
+               title = excluded.title,
+               content = excluded.content,
+               status = excluded.status,
+               success_criteria = excluded.success_criteria,
+               session_id = excluded.session_id,
+               source_path = excluded.source_path,
+               source_hash = excluded.source_hash,
+               updated_at = excluded.updated_at,
+               completed_at = excluded.completed_at",
+            rusqlite::params![
+                plan.id,
+                plan.short_id,
+                plan.project_id,
+                plan.project_path,
+                plan.title,
+                plan.content,
+                plan.status.as_str(),
+                plan.success_criteria,
+                plan.session_id,
+                plan.created_in_session,
+                plan.completed_in_session,
+                plan.source_path,
+                plan.source_hash,
+                plan.created_at,
+                plan.updated_at,
+                plan.completed_at,
+            ],
         )?;
-
         Ok(())
     }
 
-    /// Get embedding chunks for a context item.
+    /// Get dirty plan IDs by project (for JSONL sync export).
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn get_embedding_chunks(&self, item_id: &str) -> Result<Vec<EmbeddingChunk>> {
+    pub fn get_dirty_plans_by_project(&self, project_path: &str) -> Result<Vec<String>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at
-             FROM embedding_chunks
-             WHERE item_id = ?1
-             ORDER BY chunk_index ASC",
+            "SELECT dp.plan_id
+             FROM dirty_plans dp
+             INNER JOIN plans p ON dp.plan_id = p.id
+             WHERE p.project_path = ?1",
         )?;
+        let rows = stmt.query_map([project_path], |row| row.get(0))?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
 
-        let rows = stmt.query_map([item_id], |row| {
-            let blob: Vec<u8> = row.get(4)?;
-            let dimensions: i32 = row.get(5)?;
+    /// Clear dirty flags for plans after successful export.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn clear_dirty_plans(&mut self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let placeholders = vec!["?"; ids.len()].join(",");
+        let sql = format!("DELETE FROM dirty_plans WHERE plan_id IN ({placeholders})");
+        let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
+        self.conn.execute(&sql, params.as_slice())?;
+        Ok(())
+    }
 
-            // Convert bytes back to f32 vec
-            let embedding: Vec<f32> = blob
-                .chunks_exact(4)
-                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-                .collect();
+    // ======================
+    // Actor Operations
+    // ======================
 
-            Ok(EmbeddingChunk {
-                id: row.get(0)?,
-                item_id: row.get(1)?,
-                chunk_index: row.get(2)?,
-                chunk_text: row.get(3)?,
-                embedding,
-                dimensions: dimensions as usize,
-                provider: row.get(6)?,
-                model: row.get(7)?,
-                created_at: row.get(8)?,
-            })
+    /// Register an actor, or update the display name and type of one
+    /// already registered. The ID becomes an alias of itself, so other
+    /// capitalizations of it resolve to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `id` is already an alias of
+    /// another actor.
+    pub fn register_actor(
+        &mut self,
+        id: &str,
+        display_name: Option<&str>,
+        actor_type: Option<ActorType>,
+        actor: &str,
+    ) -> Result<Actor> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.mutate("register_actor", actor, |tx, _ctx| {
+            if let Some(owner) = alias_owner(tx, id)?.filter(|owner| owner != id) {
+                return Err(Error::InvalidArgument(format!("'{id}' is already an alias of actor '{owner}'")));
+            }
+            tx.execute(
+                "INSERT INTO actors (id, display_name, actor_type, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT(id) DO UPDATE SET
+                   display_name = COALESCE(excluded.display_name, display_name),
+                   actor_type = COALESCE(?5, actor_type),
+                   updated_at = excluded.updated_at",
+                rusqlite::params![id, display_name, actor_type.unwrap_or_default(), now, actor_type],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO actor_aliases (alias, actor_id, created_at) VALUES (?1, ?1, ?2)",
+                rusqlite::params![id, now],
+            )?;
+            Ok(())
         })?;
+        self.get_actor(id)?.ok_or_else(|| Error::Other(format!("Actor '{id}' vanished after registering")))
+    }
 
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(Error::from)
+    /// Map `alias` to a registered actor (given by ID or by an existing
+    /// alias). Returns `false` if it already was.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if the actor isn't registered or
+    /// the alias belongs to another actor.
+    pub fn add_actor_alias(&mut self, actor_id: &str, alias: &str, actor: &str) -> Result<bool> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.mutate("add_actor_alias", actor, |tx, _ctx| {
+            let owner = registered_actor(tx, actor_id)?;
+            match alias_owner(tx, alias)? {
+                Some(existing) if existing == owner => Ok(false),
+                Some(existing) => {
+                    Err(Error::InvalidArgument(format!("'{alias}' is already an alias of actor '{existing}'")))
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO actor_aliases (alias, actor_id, created_at) VALUES (?1, ?2, ?3)",
+                        rusqlite::params![alias, owner, now],
+                    )?;
+                    Ok(true)
+                }
+            }
+        })
     }
 
-    /// Get context items without embeddings (for backfill).
+    /// Remove an alias. Returns `false` if there was no such alias.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] for an actor's own ID, which
+    /// can't be removed.
+    pub fn remove_actor_alias(&mut self, alias: &str, actor: &str) -> Result<bool> {
+        self.mutate("remove_actor_alias", actor, |tx, _ctx| {
+            if alias_owner(tx, alias)?.is_some_and(|owner| owner.eq_ignore_ascii_case(alias)) {
+                return Err(Error::InvalidArgument(format!("'{alias}' is an actor ID, not an alias")));
+            }
+            Ok(tx.execute("DELETE FROM actor_aliases WHERE alias = ?1", [alias])? > 0)
+        })
+    }
+
+    /// An actor with its aliases, by ID or alias.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn get_items_without_embeddings(
-        &self,
-        session_id: Option<&str>,
-        limit: Option<u32>,
-    ) -> Result<Vec<ContextItem>> {
-        let limit = limit.unwrap_or(100);
-
-        let sql = if let Some(sid) = session_id {
-            format!(
-                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at
-                 FROM context_items
-                 WHERE session_id = '{}' AND (embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error'))
-                 ORDER BY created_at DESC
-                 LIMIT {}",
-                sid, limit
-            )
-        } else {
-            format!(
-                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at
-                 FROM context_items
-                 WHERE embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error')
-                 ORDER BY created_at DESC
-                 LIMIT {}",
-                limit
-            )
+    pub fn get_actor(&self, name: &str) -> Result<Option<Actor>> {
+        let Some(id) = alias_owner(&self.conn, name)? else {
+            return Ok(None);
         };
-
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map([], |row| {
-            Ok(ContextItem {
-                id: row.get(0)?,
-                session_id: row.get(1)?,
-                key: row.get(2)?,
-                value: row.get(3)?,
-                category: row.get(4)?,
-                priority: row.get(5)?,
-                channel: row.get(6)?,
-                tags: row.get(7)?,
-                size: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        })?;
-
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(Error::from)
+        Ok(self.list_actors()?.into_iter().find(|a| a.id == id))
     }
 
-    /// Count items with and without embeddings.
+    /// Every registered actor, by ID.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn count_embedding_status(&self, session_id: Option<&str>) -> Result<EmbeddingStats> {
-        let (with_embeddings, without_embeddings) = if let Some(sid) = session_id {
-            let with: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND embedding_status = 'complete'",
-                [sid],
-                |row| row.get(0),
-            )?;
-            let without: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND (embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error'))",
-                [sid],
-                |row| row.get(0),
-            )?;
-            (with, without)
-        } else {
-            let with: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE embedding_status = 'complete'",
-                [],
-                |row| row.get(0),
-            )?;
-            let without: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error')",
-                [],
-                |row| row.get(0),
-            )?;
-            (with, without)
-        };
-
-        Ok(EmbeddingStats {
-            with_embeddings: with_embeddings as usize,
-            without_embeddings: without_embeddings as usize,
-        })
+    pub fn list_actors(&self) -> Result<Vec<Actor>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id, a.display_name, a.actor_type, a.created_at, a.updated_at,
+                    (SELECT json_group_array(alias) FROM (
+                        SELECT alias FROM actor_aliases WHERE actor_id = a.id AND alias != a.id ORDER BY alias))
+             FROM actors a
+             ORDER BY a.id",
+        )?;
+        let actors = stmt
+            .query_map([], |row| {
+                let aliases: String = row.get(5)?;
+                Ok(Actor {
+                    id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    actor_type: row.get(2)?,
+                    aliases: serde_json::from_str(&aliases).unwrap_or_default(),
+                    created_at: row.get(3)?,
+                    updated_at: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(actors)
     }
 
-    /// Resync embedding status for items claiming 'complete' but lacking actual data.
-    ///
-    /// Migration 011 dropped the old vec_context_chunks table and reset statuses to
-    /// 'pending', but subsequent logic set them back to 'complete' without actual
-    /// embedding data. This method detects and fixes that mismatch.
-    ///
-    /// Returns the number of items reset.
+    /// The canonical ID for an actor name: the actor it is an alias of,
+    /// or the name itself when it isn't one.
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub fn resync_embedding_status(&self) -> Result<usize> {
-        let count = self.conn.execute(
-            "UPDATE context_items SET embedding_status = 'pending'
-             WHERE embedding_status = 'complete'
-             AND id NOT IN (SELECT DISTINCT item_id FROM embedding_chunks)",
-            [],
-        )?;
-        Ok(count)
+    pub fn resolve_actor(&self, name: &str) -> Result<String> {
+        Ok(alias_owner(&self.conn, name)?.unwrap_or_else(|| name.to_string()))
     }
 
-    /// Perform semantic search using cosine similarity.
+    /// Remove every trace of an actor: the records it created, its audit
+    /// events and @mentions, and its name on issues, time entries, plan
+    /// revisions, token usage, watches, trash and recorded sync deletions.
+    /// A registered actor is matched under its ID and every alias.
     ///
-    /// This is a brute-force search that computes cosine similarity
-    /// between the query embedding and all stored embeddings.
-    /// Efficient for <50K items; use Hora for larger datasets.
+    /// With `anonymize`, records are kept and each reference is replaced
+    /// by `pseudonym` instead. Either way the `actors` row goes, and where
+    /// a name is required but the row stays (trash, sync deletions) the
+    /// pseudonym takes its place.
+    ///
+    /// Context items, memory, checkpoints and sessions record no creator,
+    /// so the actor of their earliest audit event counts; records whose
+    /// events were pruned are missed. Sessions are deleted only once
+    /// nothing else is left in them. Deleted records are not trashed, and
+    /// their deletions are recorded for sync export like any other.
     ///
     /// # Errors
     ///
-    /// Returns an error if the query fails.
-    pub fn semantic_search(
-        &self,
-        query_embedding: &[f32],
-        session_id: Option<&str>,
-        limit: usize,
-        threshold: f32,
-    ) -> Result<Vec<SemanticSearchResult>> {
-        // Get all embedding chunks (optionally filtered by session)
-        let sql = if let Some(sid) = session_id {
-            format!(
-                "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
-                        ci.key, ci.value, ci.category, ci.priority
-                 FROM embedding_chunks ec
-                 INNER JOIN context_items ci ON ec.item_id = ci.id
-                 WHERE ci.session_id = '{}'",
-                sid
-            )
-        } else {
-            "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
-                    ci.key, ci.value, ci.category, ci.priority
-             FROM embedding_chunks ec
-             INNER JOIN context_items ci ON ec.item_id = ci.id".to_string()
-        };
-
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map([], |row| {
-            let blob: Vec<u8> = row.get(4)?;
-            let embedding: Vec<f32> = blob
-                .chunks_exact(4)
-                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-                .collect();
+    /// Returns [`Error::InvalidArgument`] if `name` resolves to `actor`
+    /// itself, or an error if a statement fails.
+    pub fn purge_actor(&mut self, name: &str, anonymize: bool, pseudonym: &str, actor: &str) -> Result<ActorPurgeStats> {
+        let id = self.resolve_actor(name)?;
+        if self.resolve_actor(actor)?.eq_ignore_ascii_case(&id) {
+            return Err(Error::InvalidArgument(format!(
+                "Cannot purge '{id}' as '{actor}'; run the purge under another actor (--actor)"
+            )));
+        }
+        self.mutate("purge_actor", actor, |tx, ctx| {
+            let mut names: Vec<String> = tx
+                .prepare("SELECT alias FROM actor_aliases WHERE actor_id = ?1")?
+                .query_map([&id], |row| row.get(0))?
+                .collect::<std::result::Result<_, _>>()?;
+            for spelling in [&id, name] {
+                if !names.iter().any(|n| n.eq_ignore_ascii_case(spelling)) {
+                    names.push(spelling.to_string());
+                }
+            }
+            purge_actor_rows(tx, ctx, &names, anonymize, pseudonym)
+        })
+    }
 
-            Ok((
-                row.get::<_, String>(1)?, // item_id
-                row.get::<_, i32>(2)?,    // chunk_index
-                row.get::<_, String>(3)?, // chunk_text
-                embedding,
-                row.get::<_, String>(6)?, // key
-                row.get::<_, String>(7)?, // value
-                row.get::<_, String>(8)?, // category
-                row.get::<_, String>(9)?, // priority
-            ))
-        })?;
+    // ==========================
+    // Time Entry Operations
+    // ==========================
 
-        // Compute similarities and collect results
-        let mut results: Vec<SemanticSearchResult> = rows
-            .filter_map(|row| row.ok())
-            .map(|(item_id, chunk_index, chunk_text, embedding, key, value, category, priority)| {
-                let similarity = cosine_similarity(query_embedding, &embedding);
-                SemanticSearchResult {
-                    item_id,
-                    chunk_index,
-                    chunk_text,
-                    similarity,
-                    key,
-                    value,
-                    category,
-                    priority,
-                }
-            })
-            .filter(|r| r.similarity >= threshold)
-            .collect();
+    /// Create a new time entry.
+    pub fn create_time_entry(
+        &mut self,
+        id: &str,
+        short_id: Option<&str>,
+        project_path: &str,
+        hours: f64,
+        description: &str,
+        work_date: &str,
+        issue_id: Option<&str>,
+        period: Option<&str>,
+        actor: &str,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
 
-        // Sort by similarity (highest first)
-        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+        self.mutate("create_time_entry", actor, |tx, ctx| {
+            tx.execute(
+                "INSERT INTO time_entries (id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'logged', ?9, ?10, ?10)",
+                rusqlite::params![id, short_id, project_path, issue_id, period, hours, description, work_date, actor, now],
+            )?;
 
-        // Take top N results, deduplicating by item_id (keep highest similarity chunk)
-        let mut seen_items = std::collections::HashSet::new();
-        let deduped: Vec<SemanticSearchResult> = results
-            .into_iter()
-            .filter(|r| seen_items.insert(r.item_id.clone()))
-            .take(limit)
-            .collect();
+            ctx.record_event("time_entry", id, EventType::TimeEntryCreated);
+            ctx.mark_time_entry_dirty(id);
 
-        Ok(deduped)
+            Ok(())
+        })
     }
 
-    /// Delete embeddings for a context item.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the delete fails.
-    pub fn delete_embeddings(&mut self, item_id: &str) -> Result<()> {
-        self.conn.execute(
-            "DELETE FROM embedding_chunks WHERE item_id = ?1",
-            [item_id],
-        )?;
+    /// Get a time entry by ID (full or short).
+    pub fn get_time_entry(&self, id: &str, project_path: Option<&str>) -> Result<Option<TimeEntry>> {
+        let sql = if project_path.is_some() {
+            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
+             FROM time_entries WHERE (id = ?1 OR short_id = ?1) AND project_path = ?2"
+        } else {
+            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
+             FROM time_entries WHERE id = ?1 OR short_id = ?1"
+        };
 
-        self.conn.execute(
-            "UPDATE context_items SET
-               embedding_status = 'none',
-               embedding_provider = NULL,
-               embedding_model = NULL,
-               chunk_count = 0,
-               embedded_at = NULL
-             WHERE id = ?1",
-            [item_id],
-        )?;
+        let mut stmt = self.conn.prepare(sql)?;
 
-        Ok(())
-    }
+        let entry = if let Some(path) = project_path {
+            stmt.query_row(rusqlite::params![id, path], map_time_entry_row)
+        } else {
+            stmt.query_row(rusqlite::params![id], map_time_entry_row)
+        }
+        .optional()?;
 
-    /// Get embedding metadata (provider, model, dimensions).
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the query fails.
-    pub fn get_embedding_meta(&self, key: &str) -> Result<Option<String>> {
-        let value = self.conn.query_row(
-            "SELECT value FROM embeddings_meta WHERE key = ?1",
-            [key],
-            |row| row.get(0),
-        ).optional()?;
-        Ok(value)
+        Ok(entry)
     }
 
-    /// Set embedding metadata.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the upsert fails.
-    pub fn set_embedding_meta(&mut self, key: &str, value: &str) -> Result<()> {
+    /// List time entries with optional filters.
+    pub fn list_time_entries(
+        &self,
+        project_path: &str,
+        period: Option<&str>,
+        status: Option<&str>,
+        issue_id: Option<&str>,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<TimeEntry>> {
+        let mut conditions = vec!["project_path = ?1".to_string()];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(project_path.to_string())];
+        let mut idx = 2;
+
+        if let Some(p) = period {
+            conditions.push(format!("period = ?{idx}"));
+            params.push(Box::new(p.to_string()));
+            idx += 1;
+        }
+        if let Some(s) = status {
+            conditions.push(format!("status = ?{idx}"));
+            params.push(Box::new(s.to_string()));
+            idx += 1;
+        }
+        if let Some(iid) = issue_id {
+            conditions.push(format!("issue_id = ?{idx}"));
+            params.push(Box::new(iid.to_string()));
+            idx += 1;
+        }
+        if let Some(df) = date_from {
+            conditions.push(format!("work_date >= ?{idx}"));
+            params.push(Box::new(df.to_string()));
+            idx += 1;
+        }
+        if let Some(dt) = date_to {
+            conditions.push(format!("work_date <= ?{idx}"));
+            params.push(Box::new(dt.to_string()));
+            idx += 1;
+        }
+
+        let limit_val = limit.unwrap_or(200);
+        conditions.push(format!("1=1"));
+        let where_clause = conditions.join(" AND ");
+        let sql = format!(
+            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
+             FROM time_entries WHERE {where_clause} ORDER BY work_date DESC, created_at DESC LIMIT ?{idx}"
+        );
+
+        params.push(Box::new(limit_val));
+
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(param_refs.as_slice(), map_time_entry_row)?;
+        let entries: Vec<TimeEntry> = rows.collect::<std::result::Result<_, _>>()?;
+        Ok(entries)
+    }
+
+    /// Update a time entry (partial fields).
+    pub fn update_time_entry(
+        &mut self,
+        id: &str,
+        project_path: &str,
+        hours: Option<f64>,
+        description: Option<&str>,
+        period: Option<&str>,
+        issue_id: Option<&str>,
+        work_date: Option<&str>,
+        actor: &str,
+    ) -> Result<()> {
+        // Resolve full ID from short ID
+        let full_id = self.resolve_time_entry_id(id, Some(project_path))?;
         let now = chrono::Utc::now().timestamp_millis();
-        self.conn.execute(
-            "INSERT INTO embeddings_meta (key, value, updated_at)
-             VALUES (?1, ?2, ?3)
-             ON CONFLICT(key) DO UPDATE SET
-               value = excluded.value,
-               updated_at = excluded.updated_at",
-            rusqlite::params![key, value, now],
+
+        self.mutate("update_time_entry", actor, |tx, ctx| {
+            let mut sets = vec!["updated_at = ?1".to_string()];
+            let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(now)];
+            let mut idx = 2;
+
+            if let Some(h) = hours {
+                sets.push(format!("hours = ?{idx}"));
+                params.push(Box::new(h));
+                idx += 1;
+            }
+            if let Some(d) = description {
+                sets.push(format!("description = ?{idx}"));
+                params.push(Box::new(d.to_string()));
+                idx += 1;
+            }
+            if let Some(p) = period {
+                sets.push(format!("period = ?{idx}"));
+                params.push(Box::new(p.to_string()));
+                idx += 1;
+            }
+            if let Some(iid) = issue_id {
+                sets.push(format!("issue_id = ?{idx}"));
+                params.push(Box::new(iid.to_string()));
+                idx += 1;
+            }
+            if let Some(wd) = work_date {
+                sets.push(format!("work_date = ?{idx}"));
+                params.push(Box::new(wd.to_string()));
+                idx += 1;
+            }
+
+            let set_clause = sets.join(", ");
+            let sql = format!("UPDATE time_entries SET {set_clause} WHERE id = ?{idx}");
+            params.push(Box::new(full_id.clone()));
+
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            tx.execute(&sql, param_refs.as_slice())?;
+
+            ctx.record_event("time_entry", &full_id, EventType::TimeEntryUpdated);
+            ctx.mark_time_entry_dirty(&full_id);
+
+            Ok(())
+        })
+    }
+
+    /// Update time entry status.
+    pub fn update_time_entry_status(
+        &mut self,
+        id: &str,
+        project_path: &str,
+        status: TimeEntryStatus,
+        actor: &str,
+    ) -> Result<()> {
+        let full_id = self.resolve_time_entry_id(id, Some(project_path))?;
+        let now = chrono::Utc::now().timestamp_millis();
+
+        self.mutate("update_time_entry_status", actor, |tx, ctx| {
+            tx.execute(
+                "UPDATE time_entries SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![status, now, full_id],
+            )?;
+
+            ctx.record_event("time_entry", &full_id, EventType::TimeEntryStatusChanged);
+            ctx.mark_time_entry_dirty(&full_id);
+
+            Ok(())
+        })
+    }
+
+    /// Batch update time entries from one status to another for a period.
+    /// Returns (count, total_hours).
+    pub fn invoice_time_entries(
+        &mut self,
+        project_path: &str,
+        period: &str,
+        from_status: TimeEntryStatus,
+        to_status: TimeEntryStatus,
+        actor: &str,
+    ) -> Result<(usize, f64)> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        // First get the entries to update (for event tracking)
+        let entries =
+            self.list_time_entries(project_path, Some(period), Some(from_status.as_str()), None, None, None, None)?;
+        let count = entries.len();
+        let total_hours: f64 = entries.iter().map(|e| e.hours).sum();
+
+        if count == 0 {
+            return Ok((0, 0.0));
+        }
+
+        self.mutate("invoice_time_entries", actor, |tx, ctx| {
+            tx.execute(
+                "UPDATE time_entries SET status = ?1, updated_at = ?2 WHERE project_path = ?3 AND period = ?4 AND status = ?5",
+                rusqlite::params![to_status, now, project_path, period, from_status],
+            )?;
+
+            for entry in &entries {
+                ctx.record_event("time_entry", &entry.id, EventType::TimeEntryStatusChanged);
+                ctx.mark_time_entry_dirty(&entry.id);
+            }
+
+            Ok(())
+        })?;
+
+        Ok((count, total_hours))
+    }
+
+    /// Delete a time entry.
+    pub fn delete_time_entry(
+        &mut self,
+        id: &str,
+        project_path: &str,
+        actor: &str,
+    ) -> Result<()> {
+        let full_id = self.resolve_time_entry_id(id, Some(project_path))?;
+
+        self.mutate("delete_time_entry", actor, |tx, ctx| {
+            tx.execute(
+                "DELETE FROM time_entries WHERE id = ?1",
+                rusqlite::params![full_id],
+            )?;
+
+            // Clean up dirty tracking
+            tx.execute(
+                "DELETE FROM dirty_time_entries WHERE time_entry_id = ?1",
+                rusqlite::params![full_id],
+            )?;
+
+            ctx.record_event("time_entry", &full_id, EventType::TimeEntryDeleted);
+
+            Ok(())
+        })
+    }
+
+    /// Get total hours for a project, optionally filtered by period and/or status.
+    pub fn get_time_total(
+        &self,
+        project_path: &str,
+        period: Option<&str>,
+        status: Option<&str>,
+    ) -> Result<f64> {
+        let mut conditions = vec!["project_path = ?1".to_string()];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(project_path.to_string())];
+        let mut idx = 2;
+
+        if let Some(p) = period {
+            conditions.push(format!("period = ?{idx}"));
+            params.push(Box::new(p.to_string()));
+            idx += 1;
+        }
+        if let Some(s) = status {
+            conditions.push(format!("status = ?{idx}"));
+            params.push(Box::new(s.to_string()));
+            let _ = idx;
+        }
+
+        let where_clause = conditions.join(" AND ");
+        let sql = format!("SELECT COALESCE(SUM(hours), 0) FROM time_entries WHERE {where_clause}");
+
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let total: f64 = self.conn.query_row(&sql, param_refs.as_slice(), |row| row.get(0))?;
+        Ok(total)
+    }
+
+    /// Get total hours logged against a specific issue.
+    pub fn get_issue_time_total(&self, issue_id: &str) -> Result<f64> {
+        let total: f64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(hours), 0) FROM time_entries WHERE issue_id = ?1",
+            rusqlite::params![issue_id],
+            |row| row.get(0),
         )?;
-        Ok(())
+        Ok(total)
+    }
+
+    /// Get all time entries for a project (for sync export).
+    pub fn get_time_entries_by_project(&self, project_path: &str) -> Result<Vec<TimeEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at
+             FROM time_entries WHERE project_path = ?1 ORDER BY created_at ASC",
+        )?;
+        let rows = stmt.query_map([project_path], map_time_entry_row)?;
+        let entries: Vec<TimeEntry> = rows.collect::<std::result::Result<_, _>>()?;
+        Ok(entries)
+    }
+
+    /// Resolve a time entry ID (full or short) to a full ID.
+    fn resolve_time_entry_id(&self, id: &str, project_path: Option<&str>) -> Result<String> {
+        let entry = self.get_time_entry(id, project_path)?
+            .ok_or_else(|| Error::InvalidArgument(format!("Time entry not found: {id}")))?;
+        Ok(entry.id)
+    }
+
+    /// Get dirty time entry IDs for a project.
+    pub fn get_dirty_time_entries_by_project(&self, project_path: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT dte.time_entry_id
+             FROM dirty_time_entries dte
+             INNER JOIN time_entries te ON dte.time_entry_id = te.id
+             WHERE te.project_path = ?1",
+        )?;
+        let rows = stmt.query_map([project_path], |row| row.get(0))?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    /// Clear dirty flags for time entries after export.
+    pub fn clear_dirty_time_entries(&mut self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let placeholders = vec!["?"; ids.len()].join(",");
+        let sql = format!("DELETE FROM dirty_time_entries WHERE time_entry_id IN ({placeholders})");
+        let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
+        self.conn.execute(&sql, params.as_slice())?;
+        Ok(())
+    }
+
+    /// Upsert a time entry (for sync import).
+    pub fn upsert_time_entry(&mut self, entry: &TimeEntry) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO time_entries (id, short_id, project_path, issue_id, period, hours, description, work_date, status, actor, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(id) DO UPDATE SET
+               short_id = excluded.short_id,
+               issue_id = excluded.issue_id,
+               period = excluded.period,
+               hours = excluded.hours,
+               description = excluded.description,
+               work_date = excluded.work_date,
+               status = excluded.status,
+               actor = excluded.actor,
+               updated_at = excluded.updated_at",
+            rusqlite::params![
+                entry.id,
+                entry.short_id,
+                entry.project_path,
+                entry.issue_id,
+                entry.period,
+                entry.hours,
+                entry.description,
+                entry.work_date,
+                entry.status,
+                entry.actor,
+                entry.created_at,
+                entry.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ======================
+    // Embedding Operations
+    // ======================
+
+    /// Store an embedding chunk for a context item.
+    ///
+    /// Embeddings are stored as BLOBs (binary f32 arrays).
+    /// Large items may have multiple chunks for full semantic coverage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    pub fn store_embedding_chunk(
+        &mut self,
+        id: &str,
+        item_id: &str,
+        chunk_index: i32,
+        chunk_text: &str,
+        embedding: &[f32],
+        provider: &str,
+        model: &str,
+    ) -> Result<()> {
+        // Its text would be stored in the clear
+        if self.is_item_encrypted(item_id)? {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        let dimensions = embedding.len() as i32;
+
+        // Convert f32 slice to bytes (little-endian)
+        let blob: Vec<u8> = embedding
+            .iter()
+            .flat_map(|f| f.to_le_bytes())
+            .collect();
+
+        self.conn.execute(
+            "INSERT INTO embedding_chunks (id, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(entity_type, item_id, chunk_index) DO UPDATE SET
+               chunk_text = excluded.chunk_text,
+               embedding = excluded.embedding,
+               dimensions = excluded.dimensions,
+               provider = excluded.provider,
+               model = excluded.model,
+               created_at = excluded.created_at",
+            rusqlite::params![id, item_id, chunk_index, chunk_text, blob, dimensions, provider, model, now],
+        )?;
+
+        // Update context_items embedding metadata
+        self.conn.execute(
+            "UPDATE context_items SET
+               embedding_status = 'complete',
+               embedding_provider = ?1,
+               embedding_model = ?2,
+               chunk_count = COALESCE(
+                 (SELECT MAX(chunk_index) + 1 FROM embedding_chunks WHERE entity_type = 'context_item' AND item_id = ?3),
+                 1
+               ),
+               embedded_at = ?4
+             WHERE id = ?3",
+            rusqlite::params![provider, model, item_id, now],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get embedding chunks for a context item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_embedding_chunks(&self, item_id: &str) -> Result<Vec<EmbeddingChunk>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at
+             FROM embedding_chunks
+             WHERE entity_type = 'context_item' AND item_id = ?1
+             ORDER BY chunk_index ASC",
+        )?;
+
+        let rows = stmt.query_map([item_id], |row| {
+            let blob: Vec<u8> = row.get(4)?;
+            let dimensions: i32 = row.get(5)?;
+
+            // Convert bytes back to f32 vec
+            let embedding: Vec<f32> = blob
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+
+            Ok(EmbeddingChunk {
+                id: row.get(0)?,
+                item_id: row.get(1)?,
+                chunk_index: row.get(2)?,
+                chunk_text: row.get(3)?,
+                embedding,
+                dimensions: dimensions as usize,
+                provider: row.get(6)?,
+                model: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })?;
+
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Get context items without embeddings (for backfill).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_items_without_embeddings(
+        &self,
+        session_id: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<ContextItem>> {
+        let limit = limit.unwrap_or(100);
+
+        let sql = if let Some(sid) = session_id {
+            format!(
+                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at
+                 FROM context_items
+                 WHERE session_id = '{}' AND value_encrypted = 0
+                   AND (embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error'))
+                 ORDER BY created_at DESC
+                 LIMIT {}",
+                sid, limit
+            )
+        } else {
+            format!(
+                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at
+                 FROM context_items
+                 WHERE value_encrypted = 0
+                   AND (embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error'))
+                 ORDER BY created_at DESC
+                 LIMIT {}",
+                limit
+            )
+        };
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ContextItem {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                key: row.get(2)?,
+                value: read_value(row, 3)?,
+                category: row.get(4)?,
+                priority: row.get(5)?,
+                channel: row.get(6)?,
+                tags: row.get(7)?,
+                size: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
+                source: row.get(11)?,
+                trust: row.get(12)?,
+                review_status: row.get(13)?,
+                due_at: row.get(14)?,
+                done_at: row.get(15)?,
+            })
+        })?;
+
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Count items with and without embeddings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn count_embedding_status(&self, session_id: Option<&str>) -> Result<EmbeddingStats> {
+        let (with_embeddings, without_embeddings) = if let Some(sid) = session_id {
+            let with: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND embedding_status = 'complete'",
+                [sid],
+                |row| row.get(0),
+            )?;
+            let without: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND (embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error'))",
+                [sid],
+                |row| row.get(0),
+            )?;
+            (with, without)
+        } else {
+            let with: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE embedding_status = 'complete'",
+                [],
+                |row| row.get(0),
+            )?;
+            let without: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE embedding_status IS NULL OR embedding_status IN ('none', 'pending', 'error')",
+                [],
+                |row| row.get(0),
+            )?;
+            (with, without)
+        };
+
+        Ok(EmbeddingStats {
+            with_embeddings: with_embeddings as usize,
+            without_embeddings: without_embeddings as usize,
+        })
+    }
+
+    /// Resync embedding status for items claiming 'complete' but lacking actual data.
+    ///
+    /// Migration 011 dropped the old vec_context_chunks table and reset statuses to
+    /// 'pending', but subsequent logic set them back to 'complete' without actual
+    /// embedding data. This method detects and fixes that mismatch.
+    ///
+    /// Returns the number of items reset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn resync_embedding_status(&self) -> Result<usize> {
+        let count = self.conn.execute(
+            "UPDATE context_items SET embedding_status = 'pending'
+             WHERE embedding_status = 'complete'
+             AND id NOT IN (SELECT DISTINCT item_id FROM embedding_chunks WHERE entity_type = 'context_item')",
+            [],
+        )?;
+        Ok(count)
+    }
+
+    /// Look up a cached embedding for `text` and count the hit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_cached_embedding(&self, provider: &str, model: &str, text: &str) -> Result<Option<Vec<f32>>> {
+        let hash = crate::config::plan_discovery::compute_content_hash(text);
+        let blob: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "UPDATE embedding_cache SET hits = hits + 1, last_used_at = ?4
+                 WHERE provider = ?1 AND model = ?2 AND content_hash = ?3
+                 RETURNING embedding",
+                rusqlite::params![provider, model, hash, chrono::Utc::now().timestamp_millis()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(blob.map(|blob| {
+            blob.chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect()
+        }))
+    }
+
+    /// Cache the embedding generated for `text`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    pub fn cache_embedding(&self, provider: &str, model: &str, text: &str, embedding: &[f32]) -> Result<()> {
+        let hash = crate::config::plan_discovery::compute_content_hash(text);
+        let now = chrono::Utc::now().timestamp_millis();
+        let blob: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+        self.conn.execute(
+            "INSERT INTO embedding_cache (provider, model, content_hash, dimensions, embedding, created_at, last_used_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT(provider, model, content_hash) DO UPDATE SET
+               dimensions = excluded.dimensions,
+               embedding = excluded.embedding,
+               last_used_at = excluded.last_used_at",
+            rusqlite::params![provider, model, hash, embedding.len(), blob, now],
+        )?;
+        Ok(())
+    }
+
+    /// Embedding cache size and hit rate.
+    ///
+    /// Every entry was a miss once, so misses are the entry count.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn embedding_cache_stats(&self) -> Result<EmbeddingCacheStats> {
+        let (entries, hits, hit_rate) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(hits), 0),
+                    COALESCE(CAST(SUM(hits) AS REAL) / (COUNT(*) + SUM(hits)), 0.0)
+             FROM embedding_cache",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        Ok(EmbeddingCacheStats {
+            entries,
+            hits,
+            misses: entries,
+            hit_rate,
+        })
+    }
+
+    /// Perform semantic search using cosine similarity.
+    ///
+    /// This is a brute-force search that computes cosine similarity
+    /// between the query embedding and all stored embeddings.
+    /// Efficient for <50K items; use Hora for larger datasets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn semantic_search(
+        &self,
+        query_embedding: &[f32],
+        session_id: Option<&str>,
+        limit: usize,
+        threshold: f32,
+    ) -> Result<Vec<SemanticSearchResult>> {
+        // Get all embedding chunks (optionally filtered by session)
+        let sql = if let Some(sid) = session_id {
+            format!(
+                "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
+                        ci.key, ci.value, ci.category, ci.priority
+                 FROM embedding_chunks ec
+                 INNER JOIN context_items ci ON ec.entity_type = 'context_item' AND ec.item_id = ci.id
+                 WHERE ci.session_id = '{}'",
+                sid
+            )
+        } else {
+            "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
+                    ci.key, ci.value, ci.category, ci.priority
+             FROM embedding_chunks ec
+             INNER JOIN context_items ci ON ec.entity_type = 'context_item' AND ec.item_id = ci.id".to_string()
+        };
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            let blob: Vec<u8> = row.get(4)?;
+            let embedding: Vec<f32> = blob
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+
+            Ok((
+                row.get::<_, String>(1)?, // item_id
+                row.get::<_, i32>(2)?,    // chunk_index
+                row.get::<_, String>(3)?, // chunk_text
+                embedding,
+                row.get::<_, String>(6)?, // key
+                read_value(row, 7)?,      // value
+                row.get::<_, String>(8)?, // category
+                row.get::<_, String>(9)?, // priority
+            ))
+        })?;
+
+        // Compute similarities and collect results
+        let mut results: Vec<SemanticSearchResult> = rows
+            .filter_map(|row| row.ok())
+            .map(|(item_id, chunk_index, chunk_text, embedding, key, value, category, priority)| {
+                let similarity = cosine_similarity(query_embedding, &embedding);
+                SemanticSearchResult {
+                    item_id,
+                    chunk_index,
+                    chunk_text,
+                    similarity,
+                    key,
+                    value,
+                    category,
+                    priority,
+                }
+            })
+            .filter(|r| r.similarity >= threshold)
+            .collect();
+
+        // Sort by similarity (highest first)
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Take top N results, deduplicating by item_id (keep highest similarity chunk)
+        let mut seen_items = std::collections::HashSet::new();
+        let deduped: Vec<SemanticSearchResult> = results
+            .into_iter()
+            .filter(|r| seen_items.insert(r.item_id.clone()))
+            .take(limit)
+            .collect();
+
+        Ok(deduped)
+    }
+
+    /// Delete embeddings for a context item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn delete_embeddings(&mut self, item_id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM embedding_chunks WHERE entity_type = 'context_item' AND item_id = ?1",
+            [item_id],
+        )?;
+
+        self.conn.execute(
+            "UPDATE context_items SET
+               embedding_status = 'none',
+               embedding_provider = NULL,
+               embedding_model = NULL,
+               chunk_count = 0,
+               embedded_at = NULL
+             WHERE id = ?1",
+            [item_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Issues, plans or memories (`entity_type` `issue`, `plan` or
+    /// `memory`) with no embeddings, or changed since they were embedded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown entity type or if the query fails.
+    pub fn get_entities_needing_embeddings(&self, entity_type: &str, limit: u32) -> Result<Vec<EntityText>> {
+        let select = match entity_type {
+            "issue" => {
+                "SELECT id, title, TRIM(COALESCE(description, '') || char(10) || COALESCE(details, ''), char(10) || ' '),
+                        COALESCE(issue_type, 'task'), updated_at
+                 FROM issues e"
+            }
+            "plan" => "SELECT id, title, COALESCE(content, ''), 'plan', updated_at FROM plans e",
+            "memory" => {
+                "SELECT id, key, value, COALESCE(category, 'memory'), updated_at FROM project_memory e"
+            }
+            other => return Err(Error::InvalidArgument(format!("Cannot embed entity type '{other}'"))),
+        };
+        let sql = format!(
+            "{select}
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM embedding_chunks ec
+                 WHERE ec.entity_type = ?1 AND ec.item_id = e.id AND ec.created_at >= e.updated_at
+             )
+             ORDER BY e.updated_at DESC
+             LIMIT ?2"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params![entity_type, limit], |row| {
+            Ok(EntityText {
+                entity_type: entity_type.to_string(),
+                id: row.get(0)?,
+                title: row.get(1)?,
+                text: row.get(2)?,
+                label: row.get(3)?,
+            })
+        })?;
+        rows.collect::<std::result::Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Store one embedding chunk of an issue, plan or memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    #[allow(clippy::too_many_arguments)]
+    pub fn store_entity_embedding_chunk(
+        &mut self,
+        entity_type: &str,
+        entity_id: &str,
+        chunk_index: i32,
+        chunk_text: &str,
+        embedding: &[f32],
+        provider: &str,
+        model: &str,
+    ) -> Result<()> {
+        let blob: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+        self.conn.execute(
+            "INSERT INTO embedding_chunks (id, entity_type, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(entity_type, item_id, chunk_index) DO UPDATE SET
+               chunk_text = excluded.chunk_text,
+               embedding = excluded.embedding,
+               dimensions = excluded.dimensions,
+               provider = excluded.provider,
+               model = excluded.model,
+               created_at = excluded.created_at",
+            rusqlite::params![
+                format!("emb_{entity_type}_{entity_id}_{chunk_index}"),
+                entity_type,
+                entity_id,
+                chunk_index,
+                chunk_text,
+                blob,
+                embedding.len(),
+                provider,
+                model,
+                chrono::Utc::now().timestamp_millis()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Drop chunks left over from a longer previous version of an entity.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn trim_entity_embeddings(&mut self, entity_type: &str, entity_id: &str, chunk_count: usize) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM embedding_chunks WHERE entity_type = ?1 AND item_id = ?2 AND chunk_index >= ?3",
+            rusqlite::params![entity_type, entity_id, chunk_count],
+        )?;
+        Ok(())
+    }
+
+    /// Semantic search over embedded entities of the given types.
+    ///
+    /// `entity_types` takes `context_item`, `issue`, `plan` and `memory`.
+    /// With `project_path`, only that project's records are searched
+    /// (context items through their session). Each entity appears once, at
+    /// its best-matching chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn semantic_search_entities(
+        &self,
+        query_embedding: &[f32],
+        entity_types: &[&str],
+        project_path: Option<&str>,
+        limit: usize,
+        threshold: f32,
+    ) -> Result<Vec<EntitySearchResult>> {
+        if entity_types.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = (2..entity_types.len() + 2).map(|i| format!("?{i}")).collect::<Vec<_>>().join(", ");
+        let sql = format!(
+            "SELECT * FROM (
+                 SELECT ec.entity_type, ec.item_id, ec.chunk_text, ec.embedding,
+                        COALESCE(ci.key, i.title, p.title, m.key) AS title,
+                        COALESCE(i.short_id, p.short_id) AS short_id,
+                        COALESCE(s.project_path, i.project_path, p.project_path, m.project_path) AS project_path
+                 FROM embedding_chunks ec
+                 LEFT JOIN context_items ci ON ec.entity_type = 'context_item' AND ci.id = ec.item_id
+                 LEFT JOIN sessions s ON s.id = ci.session_id
+                 LEFT JOIN issues i ON ec.entity_type = 'issue' AND i.id = ec.item_id
+                 LEFT JOIN plans p ON ec.entity_type = 'plan' AND p.id = ec.item_id
+                 LEFT JOIN project_memory m ON ec.entity_type = 'memory' AND m.id = ec.item_id
+                 WHERE ec.entity_type IN ({placeholders})
+             )
+             WHERE title IS NOT NULL AND (?1 IS NULL OR project_path = ?1)"
+        );
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&project_path];
+        params.extend(entity_types.iter().map(|t| t as &dyn rusqlite::ToSql));
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params.as_slice(), |row| {
+            let blob: Vec<u8> = row.get(3)?;
+            let embedding: Vec<f32> = blob
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+            Ok(EntitySearchResult {
+                entity_type: row.get(0)?,
+                entity_id: row.get(1)?,
+                chunk_text: row.get(2)?,
+                similarity: cosine_similarity(query_embedding, &embedding),
+                title: row.get(4)?,
+                short_id: row.get(5)?,
+                project_path: row.get(6)?,
+            })
+        })?;
+
+        let mut results: Vec<EntitySearchResult> = rows
+            .filter_map(std::result::Result::ok)
+            .filter(|r| r.similarity >= threshold)
+            .collect();
+        results.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+        let mut seen = std::collections::HashSet::new();
+        Ok(results
+            .into_iter()
+            .filter(|r| seen.insert((r.entity_type.clone(), r.entity_id.clone())))
+            .take(limit)
+            .collect())
+    }
+
+    /// The embedding of a whole record: the mean of its chunks' embeddings,
+    /// or `None` if it has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_entity_embedding(&self, entity_type: &str, id: &str) -> Result<Option<Vec<f32>>> {
+        let mut stmt =
+            self.conn.prepare("SELECT embedding FROM embedding_chunks WHERE entity_type = ?1 AND item_id = ?2")?;
+        let chunks: Vec<Vec<u8>> =
+            stmt.query_map([entity_type, id], |row| row.get(0))?.collect::<std::result::Result<_, _>>()?;
+        let mut mean: Vec<f32> = Vec::new();
+        for blob in &chunks {
+            let embedding = blob.chunks_exact(4).map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+            if mean.is_empty() {
+                mean = embedding.collect();
+            } else {
+                mean.iter_mut().zip(embedding).for_each(|(sum, value)| *sum += value);
+            }
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let count = chunks.len() as f32;
+        Ok((!mean.is_empty()).then(|| mean.into_iter().map(|sum| sum / count).collect()))
+    }
+
+    /// Get embedding metadata (provider, model, dimensions).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_embedding_meta(&self, key: &str) -> Result<Option<String>> {
+        let value = self.conn.query_row(
+            "SELECT value FROM embeddings_meta WHERE key = ?1",
+            [key],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(value)
+    }
+
+    /// Set embedding metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upsert fails.
+    pub fn set_embedding_meta(&mut self, key: &str, value: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.conn.execute(
+            "INSERT INTO embeddings_meta (key, value, updated_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET
+               value = excluded.value,
+               updated_at = excluded.updated_at",
+            rusqlite::params![key, value, now],
+        )?;
+        Ok(())
+    }
+
+    /// Remove embedding metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn delete_embedding_meta(&mut self, key: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM embeddings_meta WHERE key = ?1", [key])?;
+        Ok(())
+    }
+
+    /// Models that produced the stored quality embeddings, most chunks first.
+    ///
+    /// With `archived`, lists `embedding_chunks_archive` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn embedding_models(&self, archived: bool) -> Result<Vec<EmbeddingModelUsage>> {
+        let table = if archived { "embedding_chunks_archive" } else { "embedding_chunks" };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT provider, model, dimensions, COUNT(*), COUNT(DISTINCT entity_type || ':' || item_id)
+             FROM {table}
+             GROUP BY provider, model, dimensions
+             ORDER BY COUNT(*) DESC"
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok(EmbeddingModelUsage {
+                provider: row.get(0)?,
+                model: row.get(1)?,
+                dimensions: row.get(2)?,
+                chunks: row.get(3)?,
+                entities: row.get(4)?,
+            })
+        })?;
+        rows.collect::<std::result::Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Take every embedding not made by `provider`/`model` out of search.
+    ///
+    /// With `keep`, the chunks move to `embedding_chunks_archive` (replacing
+    /// older archived copies); otherwise they are deleted. Context items
+    /// that lose their chunks go back to `pending` so the next backfill
+    /// re-embeds them; issues, plans and memories are picked up anyway once
+    /// they have no chunks. Returns the number of entities affected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails.
+    pub fn retire_embeddings_except(&mut self, provider: &str, model: &str, keep: bool) -> Result<usize> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        let affected: usize = tx.query_row(
+            "SELECT COUNT(DISTINCT entity_type || ':' || item_id) FROM embedding_chunks
+             WHERE provider != ?1 OR model != ?2",
+            rusqlite::params![provider, model],
+            |row| row.get(0),
+        )?;
+        if keep {
+            tx.execute(
+                "INSERT OR REPLACE INTO embedding_chunks_archive
+                   (id, entity_type, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at, archived_at)
+                 SELECT id, entity_type, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at, ?3
+                 FROM embedding_chunks WHERE provider != ?1 OR model != ?2",
+                rusqlite::params![provider, model, now],
+            )?;
+        }
+        tx.execute(
+            "UPDATE context_items SET
+               embedding_status = 'pending',
+               embedding_provider = NULL,
+               embedding_model = NULL,
+               chunk_count = 0,
+               embedded_at = NULL
+             WHERE id IN (SELECT item_id FROM embedding_chunks
+                          WHERE entity_type = 'context_item' AND (provider != ?1 OR model != ?2))",
+            rusqlite::params![provider, model],
+        )?;
+        tx.execute(
+            "DELETE FROM embedding_chunks WHERE provider != ?1 OR model != ?2",
+            rusqlite::params![provider, model],
+        )?;
+        tx.commit()?;
+        Ok(affected)
+    }
+
+    /// Current embeddings of a project's context items, issues, plans and
+    /// memories, grouped by record.
+    ///
+    /// Chunks older than their record are left out, so an export never
+    /// carries vectors for text that has since changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_project_embeddings(&self, project_path: &str) -> Result<Vec<EntityEmbeddings>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ec.entity_type, ec.item_id, e.updated_at, ec.provider, ec.model, ec.dimensions,
+                    ec.chunk_text, ec.embedding
+             FROM embedding_chunks ec
+             JOIN (
+                 SELECT 'context_item' AS entity_type, ci.id, ci.updated_at
+                 FROM context_items ci JOIN sessions s ON s.id = ci.session_id
+                 WHERE s.project_path = ?1
+                 UNION ALL SELECT 'issue', id, updated_at FROM issues WHERE project_path = ?1
+                 UNION ALL SELECT 'plan', id, updated_at FROM plans WHERE project_path = ?1
+                 UNION ALL SELECT 'memory', id, updated_at FROM project_memory WHERE project_path = ?1
+             ) e ON e.entity_type = ec.entity_type AND e.id = ec.item_id
+             WHERE ec.created_at >= e.updated_at
+             ORDER BY ec.entity_type, ec.item_id, ec.chunk_index",
+        )?;
+        let mut rows = stmt.query([project_path])?;
+        let mut records: Vec<EntityEmbeddings> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let entity_type: String = row.get(0)?;
+            let entity_id: String = row.get(1)?;
+            let blob: Vec<u8> = row.get(7)?;
+            let vector = blob
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+            let same_record = records
+                .last()
+                .is_some_and(|r| r.entity_type == entity_type && r.entity_id == entity_id);
+            if !same_record {
+                records.push(EntityEmbeddings {
+                    entity_type,
+                    entity_id,
+                    source_updated_at: row.get(2)?,
+                    provider: row.get(3)?,
+                    model: row.get(4)?,
+                    dimensions: row.get(5)?,
+                    chunk_texts: Vec::new(),
+                    vectors: Vec::new(),
+                });
+            }
+            if let Some(record) = records.last_mut() {
+                record.chunk_texts.push(row.get(6)?);
+                record.vectors.push(vector);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Store embeddings made on another machine.
+    ///
+    /// They are only used if the local record is the exact version they
+    /// were made from (same `updated_at`); otherwise nothing is written and
+    /// `false` is returned, leaving the record to the next backfill.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails.
+    pub fn import_entity_embeddings(&mut self, embeddings: &EntityEmbeddings) -> Result<bool> {
+        let table = match embeddings.entity_type.as_str() {
+            "context_item" => "context_items",
+            "issue" => "issues",
+            "plan" => "plans",
+            "memory" => "project_memory",
+            _ => return Ok(false),
+        };
+        self.with_transaction(|storage| {
+            let result = storage.store_imported_embeddings(table, embeddings);
+            let keep = matches!(result, Ok(true));
+            (result, keep)
+        })?
+    }
+
+    /// The writes of [`import_entity_embeddings`](Self::import_entity_embeddings),
+    /// inside its transaction.
+    fn store_imported_embeddings(&self, table: &str, embeddings: &EntityEmbeddings) -> Result<bool> {
+        let tx = &self.conn;
+        let updated_at: Option<i64> = tx
+            .query_row(
+                &format!("SELECT updated_at FROM {table} WHERE id = ?1"),
+                [&embeddings.entity_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if updated_at != Some(embeddings.source_updated_at) {
+            return Ok(false);
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        tx.execute(
+            "DELETE FROM embedding_chunks WHERE entity_type = ?1 AND item_id = ?2",
+            rusqlite::params![embeddings.entity_type, embeddings.entity_id],
+        )?;
+        for (index, (text, vector)) in embeddings.chunk_texts.iter().zip(&embeddings.vectors).enumerate() {
+            let id = if embeddings.entity_type == "context_item" {
+                format!("emb_{}_{index}", embeddings.entity_id)
+            } else {
+                format!("emb_{}_{}_{index}", embeddings.entity_type, embeddings.entity_id)
+            };
+            let blob: Vec<u8> = vector.iter().flat_map(|f| f.to_le_bytes()).collect();
+            tx.execute(
+                "INSERT INTO embedding_chunks (id, entity_type, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                rusqlite::params![
+                    id,
+                    embeddings.entity_type,
+                    embeddings.entity_id,
+                    index,
+                    text,
+                    blob,
+                    vector.len(),
+                    embeddings.provider,
+                    embeddings.model,
+                    now
+                ],
+            )?;
+        }
+        if embeddings.entity_type == "context_item" {
+            tx.execute(
+                "UPDATE context_items SET embedding_status = 'complete', embedding_provider = ?1,
+                   embedding_model = ?2, chunk_count = ?3, embedded_at = ?4
+                 WHERE id = ?5",
+                rusqlite::params![
+                    embeddings.provider,
+                    embeddings.model,
+                    embeddings.vectors.len(),
+                    now,
+                    embeddings.entity_id
+                ],
+            )?;
+        }
+        Ok(true)
+    }
+
+    /// Queue everything `provider`/`model` embedded for re-embedding while
+    /// keeping its chunks searchable.
+    ///
+    /// Context items go back to `pending`; issue, plan and memory chunks are
+    /// backdated before any record's `updated_at`, which makes them stale. Returns the number
+    /// of records queued.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails.
+    pub fn requeue_embeddings_from(&mut self, provider: &str, model: &str) -> Result<usize> {
+        let tx = self.conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        let queued: usize = tx.query_row(
+            "SELECT COUNT(DISTINCT entity_type || ':' || item_id) FROM embedding_chunks
+             WHERE provider = ?1 AND model = ?2",
+            rusqlite::params![provider, model],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "UPDATE context_items SET embedding_status = 'pending'
+             WHERE id IN (SELECT item_id FROM embedding_chunks
+                          WHERE entity_type = 'context_item' AND provider = ?1 AND model = ?2)",
+            rusqlite::params![provider, model],
+        )?;
+        tx.execute(
+            "UPDATE embedding_chunks SET created_at = -1
+             WHERE entity_type != 'context_item' AND provider = ?1 AND model = ?2",
+            rusqlite::params![provider, model],
+        )?;
+        tx.commit()?;
+        Ok(queued)
+    }
+
+    /// Bring back archived `provider`/`model` embeddings of entities that
+    /// have not changed since they were embedded and have no live chunks.
+    ///
+    /// Archived chunks of deleted or since-edited entities are dropped, as
+    /// they can never be restored. Returns the number of entities restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails.
+    pub fn restore_archived_embeddings(&mut self, provider: &str, model: &str) -> Result<usize> {
+        // Last change of the archived chunk's entity; NULL once it's deleted
+        const ENTITY_UPDATED_AT: &str = "CASE a.entity_type
+               WHEN 'context_item' THEN (SELECT updated_at FROM context_items WHERE id = a.item_id)
+               WHEN 'issue' THEN (SELECT updated_at FROM issues WHERE id = a.item_id)
+               WHEN 'plan' THEN (SELECT updated_at FROM plans WHERE id = a.item_id)
+               WHEN 'memory' THEN (SELECT updated_at FROM project_memory WHERE id = a.item_id)
+             END";
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        tx.execute(
+            &format!(
+                "DELETE FROM embedding_chunks_archive AS a
+                 WHERE a.provider = ?1 AND a.model = ?2
+                   AND ({ENTITY_UPDATED_AT} IS NULL OR a.created_at < {ENTITY_UPDATED_AT})"
+            ),
+            rusqlite::params![provider, model],
+        )?;
+        let restored: usize = tx.query_row(
+            "SELECT COUNT(DISTINCT a.entity_type || ':' || a.item_id) FROM embedding_chunks_archive a
+             WHERE a.provider = ?1 AND a.model = ?2
+               AND NOT EXISTS (SELECT 1 FROM embedding_chunks ec
+                               WHERE ec.entity_type = a.entity_type AND ec.item_id = a.item_id)",
+            rusqlite::params![provider, model],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "INSERT INTO embedding_chunks
+               (id, entity_type, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
+             SELECT a.id, a.entity_type, a.item_id, a.chunk_index, a.chunk_text, a.embedding, a.dimensions,
+                    a.provider, a.model, a.created_at
+             FROM embedding_chunks_archive a
+             WHERE a.provider = ?1 AND a.model = ?2
+               AND NOT EXISTS (SELECT 1 FROM embedding_chunks ec
+                               WHERE ec.entity_type = a.entity_type AND ec.item_id = a.item_id)",
+            rusqlite::params![provider, model],
+        )?;
+        tx.execute(
+            "UPDATE context_items SET
+               embedding_status = 'complete',
+               embedding_provider = ?1,
+               embedding_model = ?2,
+               chunk_count = (SELECT COUNT(*) FROM embedding_chunks
+                              WHERE entity_type = 'context_item' AND item_id = context_items.id),
+               embedded_at = ?3
+             WHERE id IN (SELECT item_id FROM embedding_chunks_archive
+                          WHERE entity_type = 'context_item' AND provider = ?1 AND model = ?2)
+               AND id IN (SELECT item_id FROM embedding_chunks
+                          WHERE entity_type = 'context_item' AND provider = ?1 AND model = ?2)",
+            rusqlite::params![provider, model, now],
+        )?;
+        tx.execute(
+            "DELETE FROM embedding_chunks_archive
+             WHERE provider = ?1 AND model = ?2
+               AND id IN (SELECT id FROM embedding_chunks WHERE provider = ?1 AND model = ?2)",
+            rusqlite::params![provider, model],
+        )?;
+        tx.commit()?;
+        Ok(restored)
+    }
+
+    // ========================================================================
+    // Fast Tier Embeddings (2-tier architecture)
+    // ========================================================================
+
+    /// Store a fast-tier embedding chunk (Model2Vec).
+    ///
+    /// Fast tier embeddings are stored separately for dimension isolation.
+    /// These are generated inline on save for instant semantic search.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails.
+    pub fn store_fast_embedding_chunk(
+        &mut self,
+        id: &str,
+        item_id: &str,
+        chunk_index: i32,
+        chunk_text: &str,
+        embedding: &[f32],
+        model: &str,
+    ) -> Result<()> {
+        // Its text would be stored in the clear
+        if self.is_item_encrypted(item_id)? {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        let dimensions = embedding.len() as i32;
+
+        // Convert f32 slice to bytes (little-endian)
+        let blob: Vec<u8> = embedding
+            .iter()
+            .flat_map(|f| f.to_le_bytes())
+            .collect();
+
+        self.conn.execute(
+            "INSERT INTO embedding_chunks_fast (id, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'model2vec', ?7, ?8)
+             ON CONFLICT(item_id, chunk_index) DO UPDATE SET
+               chunk_text = excluded.chunk_text,
+               embedding = excluded.embedding,
+               dimensions = excluded.dimensions,
+               model = excluded.model,
+               created_at = excluded.created_at",
+            rusqlite::params![id, item_id, chunk_index, chunk_text, blob, dimensions, model, now],
+        )?;
+
+        // Update context_items fast embedding status
+        self.conn.execute(
+            "UPDATE context_items SET
+               fast_embedding_status = 'complete',
+               fast_embedded_at = ?1
+             WHERE id = ?2",
+            rusqlite::params![now, item_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Whether a context item's value is stored encrypted.
+    fn is_item_encrypted(&self, item_id: &str) -> Result<bool> {
+        let encrypted = self
+            .conn
+            .query_row("SELECT value_encrypted FROM context_items WHERE id = ?1", [item_id], |row| row.get(0))
+            .optional()?;
+        Ok(encrypted.unwrap_or(false))
+    }
+
+    /// Search fast-tier embeddings only.
+    ///
+    /// Returns candidates for tiered search or direct fast results.
+    /// Fast tier is optimized for speed over accuracy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn search_fast_tier(
+        &self,
+        query_embedding: &[f32],
+        session_id: Option<&str>,
+        limit: usize,
+        threshold: f32,
+    ) -> Result<Vec<SemanticSearchResult>> {
+        // Get all fast embedding chunks (optionally filtered by session)
+        let sql = if let Some(sid) = session_id {
+            format!(
+                "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
+                        ci.key, ci.value, ci.category, ci.priority
+                 FROM embedding_chunks_fast ec
+                 INNER JOIN context_items ci ON ec.item_id = ci.id
+                 WHERE ci.session_id = '{}'",
+                sid
+            )
+        } else {
+            "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
+                    ci.key, ci.value, ci.category, ci.priority
+             FROM embedding_chunks_fast ec
+             INNER JOIN context_items ci ON ec.item_id = ci.id".to_string()
+        };
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            let blob: Vec<u8> = row.get(4)?;
+            let embedding: Vec<f32> = blob
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+
+            Ok((
+                row.get::<_, String>(1)?, // item_id
+                row.get::<_, i32>(2)?,    // chunk_index
+                row.get::<_, String>(3)?, // chunk_text
+                embedding,
+                row.get::<_, String>(6)?, // key
+                read_value(row, 7)?,      // value
+                row.get::<_, String>(8)?, // category
+                row.get::<_, String>(9)?, // priority
+            ))
+        })?;
+
+        // Compute similarities and collect results
+        let mut results: Vec<SemanticSearchResult> = rows
+            .filter_map(|row| row.ok())
+            .map(|(item_id, chunk_index, chunk_text, embedding, key, value, category, priority)| {
+                let similarity = cosine_similarity(query_embedding, &embedding);
+                SemanticSearchResult {
+                    item_id,
+                    chunk_index,
+                    chunk_text,
+                    similarity,
+                    key,
+                    value,
+                    category,
+                    priority,
+                }
+            })
+            .filter(|r| r.similarity >= threshold)
+            .collect();
+
+        // Sort by similarity (highest first)
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Take top N results, deduplicating by item_id
+        let mut seen_items = std::collections::HashSet::new();
+        let deduped: Vec<SemanticSearchResult> = results
+            .into_iter()
+            .filter(|r| seen_items.insert(r.item_id.clone()))
+            .take(limit)
+            .collect();
+
+        Ok(deduped)
+    }
+
+    /// Get context items with fast embeddings but no quality embeddings.
+    ///
+    /// Used by background quality upgrade process.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_items_needing_quality_upgrade(
+        &self,
+        session_id: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<ContextItem>> {
+        let limit = limit.unwrap_or(100);
+
+        let sql = if let Some(sid) = session_id {
+            format!(
+                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at
+                 FROM context_items
+                 WHERE session_id = '{}'
+                   AND fast_embedding_status = 'complete'
+                   AND (embedding_status IS NULL OR embedding_status = 'none' OR embedding_status = 'pending')
+                 ORDER BY created_at DESC
+                 LIMIT {}",
+                sid, limit
+            )
+        } else {
+            format!(
+                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at, source, trust, review_status, due_at, done_at
+                 FROM context_items
+                 WHERE fast_embedding_status = 'complete'
+                   AND (embedding_status IS NULL OR embedding_status = 'none' OR embedding_status = 'pending')
+                 ORDER BY created_at DESC
+                 LIMIT {}",
+                limit
+            )
+        };
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ContextItem {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                key: row.get(2)?,
+                value: read_value(row, 3)?,
+                category: row.get(4)?,
+                priority: row.get(5)?,
+                channel: row.get(6)?,
+                tags: row.get(7)?,
+                size: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
+                source: row.get(11)?,
+                trust: row.get(12)?,
+                review_status: row.get(13)?,
+                due_at: row.get(14)?,
+                done_at: row.get(15)?,
+            })
+        })?;
+
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Delete fast-tier embeddings for a context item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn delete_fast_embeddings(&mut self, item_id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM embedding_chunks_fast WHERE item_id = ?1",
+            [item_id],
+        )?;
+
+        self.conn.execute(
+            "UPDATE context_items SET
+               fast_embedding_status = 'none',
+               fast_embedded_at = NULL
+             WHERE id = ?1",
+            [item_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Count fast embedding status.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn count_fast_embedding_status(&self, session_id: Option<&str>) -> Result<EmbeddingStats> {
+        let (with_embeddings, without_embeddings) = if let Some(sid) = session_id {
+            let with: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND fast_embedding_status = 'complete'",
+                [sid],
+                |row| row.get(0),
+            )?;
+            let without: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND (fast_embedding_status IS NULL OR fast_embedding_status = 'none')",
+                [sid],
+                |row| row.get(0),
+            )?;
+            (with, without)
+        } else {
+            let with: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE fast_embedding_status = 'complete'",
+                [],
+                |row| row.get(0),
+            )?;
+            let without: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM context_items WHERE fast_embedding_status IS NULL OR fast_embedding_status = 'none'",
+                [],
+                |row| row.get(0),
+            )?;
+            (with, without)
+        };
+
+        Ok(EmbeddingStats {
+            with_embeddings: with_embeddings as usize,
+            without_embeddings: without_embeddings as usize,
+        })
+    }
+}
+
+// Helper to map plan rows
+fn map_plan_row(row: &rusqlite::Row) -> rusqlite::Result<Plan> {
+    let status_str: String = row.get(6)?;
+    Ok(Plan {
+        id: row.get(0)?,
+        short_id: row.get(1)?,
+        project_id: row.get(2)?,
+        project_path: row.get(3)?,
+        title: row.get(4)?,
+        content: row.get(5)?,
+        status: PlanStatus::from_str(&status_str),
+        success_criteria: row.get(7)?,
+        session_id: row.get(8)?,
+        created_in_session: row.get(9)?,
+        completed_in_session: row.get(10)?,
+        source_path: row.get(11)?,
+        source_hash: row.get(12)?,
+        created_at: row.get(13)?,
+        updated_at: row.get(14)?,
+        completed_at: row.get(15)?,
+    })
+}
+
+fn map_plan_revision_row(row: &rusqlite::Row) -> rusqlite::Result<PlanRevision> {
+    Ok(PlanRevision {
+        plan_id: row.get(0)?,
+        revision: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        success_criteria: row.get(4)?,
+        actor: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+/// Record the plan's current title/content/criteria as a new revision,
+/// unless the latest revision already matches it.
+///
+/// Returns `true` if a revision was written.
+/// Re-key every `project_path`-scoped row from `from` to `into`.
+///
+/// Memory keys present under both paths keep the newer value. Export
+/// hashes of the affected records are cleared, since their exported form
+/// contains the path. The `projects` row is left to the caller.
+fn move_path_rows(tx: &Connection, from: &str, into: &str) -> Result<PathMoveStats> {
+    let mut stats = PathMoveStats {
+        memory_conflicts: tx.execute(
+            "DELETE FROM project_memory WHERE id IN (
+                 SELECT CASE WHEN a.updated_at > b.updated_at THEN b.id ELSE a.id END
+                 FROM project_memory a JOIN project_memory b ON a.key = b.key
+                 WHERE a.project_path = ?1 AND b.project_path = ?2
+             )",
+            [from, into],
+        )?,
+        export_hashes: tx.execute(
+            "DELETE FROM export_hashes WHERE entity_id IN (
+                 SELECT id FROM sessions WHERE project_path = ?1
+                 UNION SELECT ci.id FROM context_items ci
+                       JOIN sessions s ON s.id = ci.session_id WHERE s.project_path = ?1
+                 UNION SELECT id FROM issues WHERE project_path = ?1
+                 UNION SELECT id FROM plans WHERE project_path = ?1
+                 UNION SELECT id FROM project_memory WHERE project_path = ?1
+                 UNION SELECT id FROM time_entries WHERE project_path = ?1
+             )",
+            [from],
+        )?,
+        ..PathMoveStats::default()
+    };
+
+    let update = |table: &str| {
+        tx.execute(&format!("UPDATE {table} SET project_path = ?2 WHERE project_path = ?1"), [from, into])
+    };
+    stats.sessions = update("sessions")?;
+    stats.issues = update("issues")?;
+    stats.plans = update("plans")?;
+    stats.memory = update("project_memory")?;
+    stats.time_entries = update("time_entries")?;
+    stats.agent_sessions = update("agent_sessions")?;
+    stats.sync_deletions = update("sync_deletions")?;
+
+    // Link tables keyed on the path: copy, skipping links the target already has
+    let relink = |table: &str, id_column: &str| {
+        tx.execute(
+            &format!(
+                "INSERT OR IGNORE INTO {table} ({id_column}, project_path, added_at)
+                 SELECT {id_column}, ?2, added_at FROM {table} WHERE project_path = ?1"
+            ),
+            [from, into],
+        )?;
+        tx.execute(&format!("DELETE FROM {table} WHERE project_path = ?1"), [from])
+    };
+    stats.session_paths = relink("session_projects", "session_id")?;
+    stats.issue_paths = relink("issue_projects", "issue_id")?;
+    Ok(stats)
+}
+
+fn snapshot_plan_revision(tx: &Connection, plan_id: &str, actor: &str, now: i64) -> Result<bool> {
+    let id = format!("prev_{}", &uuid::Uuid::new_v4().to_string()[..12]);
+    let inserted = tx.execute(
+        "INSERT INTO plan_revisions (id, plan_id, revision, title, content, success_criteria, actor, created_at)
+         SELECT ?1, p.id,
+                COALESCE((SELECT MAX(revision) FROM plan_revisions WHERE plan_id = p.id), 0) + 1,
+                p.title, p.content, p.success_criteria, ?2, ?3
+         FROM plans p
+         WHERE p.id = ?4
+           AND NOT EXISTS (
+               SELECT 1 FROM plan_revisions r
+               WHERE r.plan_id = p.id
+                 AND r.revision = (SELECT MAX(revision) FROM plan_revisions WHERE plan_id = p.id)
+                 AND r.title = p.title
+                 AND r.content IS p.content
+                 AND r.success_criteria IS p.success_criteria
+           )",
+        rusqlite::params![id, actor, now, plan_id],
+    )?;
+    Ok(inserted > 0)
+}
+
+// Helper to map project rows
+/// Full ID of an issue given its ID or short ID.
+/// The source of items saved by `actor`: `agent` when it is registered as
+/// an agent or model, `human` otherwise.
+fn actor_item_source(conn: &Connection, actor: &str) -> Result<ItemSource> {
+    let actor_type: Option<ActorType> = conn
+        .query_row(
+            "SELECT a.actor_type FROM actor_aliases al JOIN actors a ON a.id = al.actor_id WHERE al.alias = ?1",
+            [actor],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(actor_type.unwrap_or_default().into())
+}
+
+/// Insert or update an item by session and key, with its audit event.
+/// Returns the ID it is stored under, which is the existing item's on
+/// update.
+#[allow(clippy::too_many_arguments, clippy::cast_possible_wrap)]
+fn put_context_item(
+    tx: &Connection,
+    ctx: &mut MutationContext,
+    compression_threshold: usize,
+    id: &str,
+    session_id: &str,
+    key: &str,
+    value: &str,
+    category: Option<ItemCategory>,
+    priority: Option<ItemPriority>,
+) -> Result<String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let category = category.unwrap_or_default();
+    let size = value.len() as i64;
+
+    let settings = crate::storage::categories::check_item(tx, session_id, &category)?;
+    let priority = priority.unwrap_or(settings.default_priority);
+    let (stored, compressed, encrypted) = store_value(tx, value, settings.encrypted, compression_threshold)?;
+
+    // Check if exists for event type
+    let existing: Option<String> = tx
+        .query_row(
+            "SELECT id FROM context_items WHERE session_id = ?1 AND key = ?2",
+            rusqlite::params![session_id, key],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    // Source is set once, by whoever saves the item first. An agent
+    // rewriting the value takes trust back to `agent` and sends it
+    // back for review.
+    let source = actor_item_source(tx, &ctx.actor)?;
+    tx.execute(
+        "INSERT INTO context_items (id, session_id, key, value, value_compressed, value_encrypted, category, priority, channel, size, created_at, updated_at, source, trust)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?12, ?6, ?7, COALESCE((SELECT channel FROM sessions WHERE id = ?2), 'general'), ?8, ?9, ?9, ?10, ?11)
+         ON CONFLICT(session_id, key) DO UPDATE SET
+           value = excluded.value,
+           value_compressed = excluded.value_compressed,
+           value_encrypted = excluded.value_encrypted,
+           category = excluded.category,
+           priority = excluded.priority,
+           size = excluded.size,
+           updated_at = excluded.updated_at,
+           trust = CASE WHEN excluded.source = 'agent' AND value IS NOT excluded.value THEN 'agent' ELSE trust END,
+           review_status = CASE WHEN excluded.source = 'agent' AND value IS NOT excluded.value
+             THEN 'unreviewed' ELSE review_status END",
+        rusqlite::params![id, session_id, key, stored, compressed, category, priority, size, now, source, TrustLevel::from(source), encrypted],
+    )?;
+    let stored_id = existing.clone().unwrap_or_else(|| id.to_string());
+    crate::storage::encryption::skip_embeddings(tx, &stored_id, encrypted)?;
+
+    let event_type = if existing.is_some() {
+        EventType::ItemUpdated
+    } else {
+        EventType::ItemCreated
+    };
+    ctx.record_event("context_item", id, event_type);
+    ctx.mark_item_dirty(id);
+
+    Ok(stored_id)
+}
+
+/// The actor `alias` belongs to, matched case-insensitively.
+fn alias_owner(conn: &Connection, alias: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row("SELECT actor_id FROM actor_aliases WHERE alias = ?1", [alias], |row| row.get(0))
+        .optional()?)
+}
+
+/// SQL matching the spellings of an actor, case-insensitively like
+/// aliases. The names bind to `?1..?n`; statements may take one more
+/// parameter, [`Self::arg`].
+struct ActorNames<'a> {
+    names: &'a [String],
+    list: String,
+}
+
+impl<'a> ActorNames<'a> {
+    fn new(names: &'a [String]) -> Self {
+        let list = (1..=names.len()).map(|i| format!("?{i}")).collect::<Vec<_>>().join(", ");
+        Self { names, list }
+    }
+
+    /// `column` holds one of the names.
+    fn by(&self, column: &str) -> String {
+        format!("{column} COLLATE NOCASE IN ({})", self.list)
+    }
+
+    /// The placeholder after the names.
+    fn arg(&self) -> String {
+        format!("?{}", self.names.len() + 1)
+    }
+
+    fn exec(&self, tx: &Connection, sql: &str, extra: Option<&str>) -> Result<usize> {
+        let params = self.names.iter().map(String::as_str).chain(extra);
+        Ok(tx.execute(sql, rusqlite::params_from_iter(params))?)
+    }
+
+    fn ids(&self, tx: &Connection, sql: &str, extra: Option<&str>) -> Result<Vec<String>> {
+        let params = self.names.iter().map(String::as_str).chain(extra);
+        let mut stmt = tx.prepare(sql)?;
+        let ids = stmt.query_map(rusqlite::params_from_iter(params), |row| row.get(0))?;
+        Ok(ids.collect::<std::result::Result<_, _>>()?)
+    }
+}
+
+/// Filter on events `e`: the earliest event about its record. Its actor
+/// counts as the creator of records that don't name one.
+const FIRST_EVENT: &str = "e.id = (SELECT MIN(id) FROM events WHERE entity_type = e.entity_type AND entity_id = e.entity_id)";
+
+/// Delete or rewrite the rows [`SqliteStorage::purge_actor`] covers.
+fn purge_actor_rows(
+    tx: &Connection,
+    ctx: &mut MutationContext,
+    names: &[String],
+    anonymize: bool,
+    pseudonym: &str,
+) -> Result<ActorPurgeStats> {
+    let m = ActorNames::new(names);
+    let (by, arg) = (|column: &str| m.by(column), m.arg());
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut stats = ActorPurgeStats::default();
+
+    if anonymize {
+        stats.issue_refs = m.exec(
+            tx,
+            &format!(
+                "UPDATE issues SET
+                     created_by_agent = CASE WHEN {created} THEN {arg} ELSE created_by_agent END,
+                     assigned_to_agent = CASE WHEN {assigned} THEN {arg} ELSE assigned_to_agent END,
+                     closed_by_agent = CASE WHEN {closed} THEN {arg} ELSE closed_by_agent END,
+                     updated_at = {now}
+                 WHERE {created} OR {assigned} OR {closed}",
+                created = by("created_by_agent"),
+                assigned = by("assigned_to_agent"),
+                closed = by("closed_by_agent"),
+            ),
+            Some(pseudonym),
+        )?;
+        stats.time_entry_refs = m.exec(
+            tx,
+            &format!("UPDATE time_entries SET actor = {arg}, updated_at = {now} WHERE {}", by("actor")),
+            Some(pseudonym),
+        )?;
+        stats.plan_revisions =
+            m.exec(tx, &format!("UPDATE plan_revisions SET actor = {arg} WHERE {}", by("actor")), Some(pseudonym))?;
+        stats.token_usage = m.exec(
+            tx,
+            &format!("UPDATE session_usage SET recorded_by = {arg} WHERE {}", by("recorded_by")),
+            Some(pseudonym),
+        )?;
+        // Two spellings watching one issue become one watch
+        stats.watches = m.exec(
+            tx,
+            &format!("UPDATE OR REPLACE issue_watchers SET actor = {arg} WHERE {}", by("actor")),
+            Some(pseudonym),
+        )? + m.exec(
+            tx,
+            &format!("UPDATE OR REPLACE inbox_reads SET actor = {arg} WHERE {}", by("actor")),
+            Some(pseudonym),
+        )?;
+    } else {
+        delete_actor_records(tx, ctx, &m, now, &mut stats)?;
+        drop_actor_refs(tx, &m, now, &mut stats)?;
+    }
+    // Presence and leases are local bookkeeping, gone either way
+    m.exec(tx, &format!("DELETE FROM agent_sessions WHERE {}", by("agent_id")), None)?;
+    m.exec(tx, &format!("DELETE FROM claim_leases WHERE {} OR {}", by("holder"), by("assignee")), None)?;
+
+    // What's left keeps its row but loses the name
+    stats.events += m.exec(
+        tx,
+        &format!(
+            "UPDATE events SET
+                 actor = CASE WHEN {actor} THEN {arg} ELSE actor END,
+                 old_value = CASE WHEN {old} THEN {arg} ELSE old_value END,
+                 new_value = CASE WHEN {new} THEN {arg} ELSE new_value END
+             WHERE {actor} OR {old} OR {new}",
+            actor = by("actor"),
+            old = by("old_value"),
+            new = by("new_value"),
+        ),
+        Some(pseudonym),
+    )?;
+    // Trashed rows are JSON; swap the name wherever it is a whole string
+    let quoted = |i: usize| format!("json_quote(?{i})");
+    let swapped = (1..=names.len()).fold("rows".to_string(), |rows, i| {
+        format!("replace({rows}, {}, json_quote({arg}))", quoted(i))
+    });
+    let mentions = (1..=names.len()).map(|i| format!("instr(rows, {}) > 0", quoted(i))).collect::<Vec<_>>().join(" OR ");
+    stats.trash += m.exec(
+        tx,
+        &format!(
+            "UPDATE trash SET
+                 deleted_by = CASE WHEN {deleted_by} THEN {arg} ELSE deleted_by END,
+                 rows = {swapped}
+             WHERE {deleted_by} OR {mentions}",
+            deleted_by = by("deleted_by"),
+        ),
+        Some(pseudonym),
+    )?;
+    stats.sync_deletions = m.exec(
+        tx,
+        &format!("UPDATE sync_deletions SET deleted_by = {arg} WHERE {}", by("deleted_by")),
+        Some(pseudonym),
+    )?;
+
+    stats.actor_rows = m.exec(
+        tx,
+        &format!("DELETE FROM actor_aliases WHERE actor_id IN (SELECT actor_id FROM actor_aliases WHERE {})", by("alias")),
+        None,
+    )? + m.exec(tx, &format!("DELETE FROM actors WHERE {}", by("id")), None)?;
+    Ok(stats)
+}
+
+/// Delete the records the actor created, with their events and trashed
+/// copies, recording each deletion for sync export.
+fn delete_actor_records(
+    tx: &Connection,
+    ctx: &mut MutationContext,
+    m: &ActorNames,
+    now: i64,
+    stats: &mut ActorPurgeStats,
+) -> Result<()> {
+    stats.trash = m.exec(
+        tx,
+        &format!(
+            "DELETE FROM trash WHERE (entity_type, entity_id) IN (
+                 SELECT e.entity_type, e.entity_id FROM events e WHERE {} AND {FIRST_EVENT})",
+            m.by("e.actor")
+        ),
+        None,
+    )?;
+
+    let created = |entity_type: &str| {
+        m.ids(
+            tx,
+            &format!(
+                "SELECT e.entity_id FROM events e WHERE e.entity_type = {} AND {} AND {FIRST_EVENT}",
+                m.arg(),
+                m.by("e.actor")
+            ),
+            Some(entity_type),
+        )
+    };
+    let mut issues = m.ids(tx, &format!("SELECT id FROM issues WHERE {}", m.by("created_by_agent")), None)?;
+    issues.extend(created("issue")?);
+    issues.sort();
+    issues.dedup();
+
+    // Sessions go last, once their items and checkpoints are gone
+    let targets = [
+        (
+            "context_item",
+            created("context_item")?,
+            "SELECT s.project_path FROM context_items ci JOIN sessions s ON s.id = ci.session_id WHERE ci.id = ?1",
+            "DELETE FROM context_items WHERE id = ?1",
+            EventType::ItemDeleted,
+        ),
+        (
+            "issue",
+            issues,
+            "SELECT project_path FROM issues WHERE id = ?1",
+            "DELETE FROM issues WHERE id = ?1",
+            EventType::IssueDeleted,
+        ),
+        (
+            "memory",
+            created("memory")?,
+            "SELECT project_path FROM project_memory WHERE id = ?1",
+            "DELETE FROM project_memory WHERE id = ?1",
+            EventType::MemoryDeleted,
+        ),
+        (
+            "checkpoint",
+            created("checkpoint")?,
+            "SELECT s.project_path FROM checkpoints c JOIN sessions s ON s.id = c.session_id WHERE c.id = ?1",
+            "DELETE FROM checkpoints WHERE id = ?1",
+            EventType::CheckpointDeleted,
+        ),
+        (
+            "time_entry",
+            m.ids(tx, &format!("SELECT id FROM time_entries WHERE {}", m.by("actor")), None)?,
+            "SELECT project_path FROM time_entries WHERE id = ?1",
+            "DELETE FROM time_entries WHERE id = ?1",
+            EventType::TimeEntryDeleted,
+        ),
+        (
+            "session",
+            created("session")?,
+            "SELECT project_path FROM sessions WHERE id = ?1",
+            "DELETE FROM sessions WHERE id = ?1
+               AND NOT EXISTS (SELECT 1 FROM context_items WHERE session_id = ?1)
+               AND NOT EXISTS (SELECT 1 FROM checkpoints WHERE session_id = ?1)",
+            EventType::SessionDeleted,
+        ),
+    ];
+    let mut deleted = [0; 6];
+    for (count, (entity_type, entity_ids, path_sql, delete_sql, event)) in deleted.iter_mut().zip(targets) {
+        for entity_id in entity_ids {
+            let project_path: Option<String> =
+                tx.query_row(path_sql, [&entity_id], |row| row.get(0)).optional()?.flatten();
+            if tx.execute(delete_sql, [&entity_id])? == 0 {
+                continue;
+            }
+            *count += 1;
+            stats.events += tx.execute(
+                "DELETE FROM events WHERE entity_type = ?1 AND entity_id = ?2",
+                [entity_type, entity_id.as_str()],
+            )?;
+            if let Some(path) = project_path {
+                tx.execute(
+                    "INSERT INTO sync_deletions (entity_type, entity_id, project_path, deleted_at, deleted_by, exported)
+                     VALUES (?1, ?2, ?3, ?4, ?5, 0)
+                     ON CONFLICT(entity_type, entity_id) DO UPDATE SET
+                       deleted_at = excluded.deleted_at,
+                       deleted_by = excluded.deleted_by,
+                       exported = 0",
+                    rusqlite::params![entity_type, entity_id, path, now, ctx.actor],
+                )?;
+            }
+            ctx.record_event(entity_type, &entity_id, event);
+        }
+    }
+    [stats.context_items, stats.issues, stats.memory, stats.checkpoints, stats.time_entries, stats.sessions] = deleted;
+    Ok(())
+}
+
+/// Delete the actor's events, mentions, watches and token usage, and
+/// clear its name from issues and plan revisions others created.
+fn drop_actor_refs(tx: &Connection, m: &ActorNames, now: i64, stats: &mut ActorPurgeStats) -> Result<()> {
+    let by = |column: &str| m.by(column);
+    stats.events += m.exec(
+        tx,
+        &format!(
+            "DELETE FROM events WHERE {} OR (event_type = 'issue_mentioned' AND {})",
+            by("actor"),
+            by("new_value")
+        ),
+        None,
+    )?;
+    stats.issue_refs = m.exec(
+        tx,
+        &format!(
+            "UPDATE issues SET
+                 assigned_to_agent = CASE WHEN {assigned} THEN NULL ELSE assigned_to_agent END,
+                 assigned_at = CASE WHEN {assigned} THEN NULL ELSE assigned_at END,
+                 assigned_in_session = CASE WHEN {assigned} THEN NULL ELSE assigned_in_session END,
+                 closed_by_agent = CASE WHEN {closed} THEN NULL ELSE closed_by_agent END,
+                 updated_at = {now}
+             WHERE {assigned} OR {closed}",
+            assigned = by("assigned_to_agent"),
+            closed = by("closed_by_agent"),
+        ),
+        None,
+    )?;
+    stats.plan_revisions = m.exec(tx, &format!("UPDATE plan_revisions SET actor = NULL WHERE {}", by("actor")), None)?;
+    stats.token_usage = m.exec(tx, &format!("DELETE FROM session_usage WHERE {}", by("recorded_by")), None)?;
+    stats.watches = m.exec(tx, &format!("DELETE FROM issue_watchers WHERE {}", by("actor")), None)?
+        + m.exec(tx, &format!("DELETE FROM inbox_reads WHERE {}", by("actor")), None)?;
+    Ok(())
+}
+
+/// The ID of the registered actor `name` refers to.
+fn registered_actor(conn: &Connection, name: &str) -> Result<String> {
+    alias_owner(conn, name)?.ok_or_else(|| {
+        Error::InvalidArgument(format!("Unknown actor '{name}'. Register it with: sc actor register {name}"))
+    })
+}
+
+fn resolve_issue_id(tx: &Connection, id: &str) -> Result<String> {
+    tx.query_row("SELECT id FROM issues WHERE id = ?1 OR short_id = ?1", [id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| Error::IssueNotFound { id: id.to_string() })
+}
+
+/// An issue's full ID, status and the fields a workflow can require
+/// (keyed by their [`crate::model::workflow::REQUIRED_FIELD_NAMES`] name).
+type IssueState = (String, String, HashMap<&'static str, Option<String>>);
+
+fn load_issue_state(tx: &Connection, id: &str) -> Result<IssueState> {
+    tx.query_row(
+        "SELECT id, status, description, details, assigned_to_agent, plan_id, close_reason
+         FROM issues WHERE id = ?1 OR short_id = ?1",
+        [id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                HashMap::from([
+                    ("description", row.get::<_, Option<String>>(2)?),
+                    ("details", row.get::<_, Option<String>>(3)?),
+                    ("assignee", row.get::<_, Option<String>>(4)?),
+                    ("plan_id", row.get::<_, Option<String>>(5)?),
+                    ("close_reason", row.get::<_, Option<String>>(6)?),
+                ]),
+            ))
+        },
+    )
+    .optional()?
+    .ok_or_else(|| Error::IssueNotFound { id: id.to_string() })
+}
+
+/// Check a status change against `workflow`: the move must be allowed and
+/// every field required for `to` present in `fields` (as they will be
+/// after the change).
+fn check_transition(
+    workflow: &IssueWorkflow,
+    from: &str,
+    to: &str,
+    fields: &HashMap<&'static str, Option<String>>,
+) -> Result<()> {
+    if !workflow.can_transition(from, to) {
+        return Err(Error::InvalidTransition {
+            allowed: workflow.allowed_from(from).to_vec(),
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+    for field in workflow.required_for(to) {
+        let present = fields
+            .get(field.as_str())
+            .and_then(Option::as_deref)
+            .is_some_and(|v| !v.trim().is_empty());
+        if !present {
+            return Err(Error::RequiredField {
+                field: field.clone(),
+                status: to.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Record an `issue_mentioned` event for each actor newly mentioned in
+/// `new`. Mentioning yourself does nothing.
+fn record_mentions(ctx: &mut MutationContext, issue_id: &str, old: Option<&str>, new: &str) {
+    for mentioned in crate::model::new_mentions(old, new) {
+        if mentioned.eq_ignore_ascii_case(&ctx.actor) {
+            continue;
+        }
+        let text: String = new.chars().take(500).collect();
+        ctx.events.push(
+            Event::new("issue", issue_id, EventType::IssueMentioned, &ctx.actor)
+                .with_values(None, Some(mentioned))
+                .with_comment(&text),
+        );
+    }
+}
+
+fn map_project_row(row: &rusqlite::Row) -> rusqlite::Result<Project> {
+    Ok(Project {
+        id: row.get(0)?,
+        project_path: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+        issue_prefix: row.get(4)?,
+        next_issue_number: row.get(5)?,
+        plan_prefix: row.get(6)?,
+        next_plan_number: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+// Helper to map issue rows
+fn map_issue_row(row: &rusqlite::Row) -> rusqlite::Result<Issue> {
+    Ok(Issue {
+        id: row.get(0)?,
+        short_id: row.get(1)?,
+        project_path: row.get(2)?,
+        title: row.get(3)?,
+        description: row.get(4)?,
+        details: row.get(5)?,
+        status: row.get(6)?,
+        priority: row.get(7)?,
+        issue_type: row.get(8)?,
+        plan_id: row.get(9)?,
+        created_by_agent: row.get(10)?,
+        assigned_to_agent: row.get(11)?,
+        created_at: row.get(12)?,
+        updated_at: row.get(13)?,
+        closed_at: row.get(14)?,
+        subproject: row.get(15)?,
+        estimate_hours: row.get(16)?,
+    })
+}
+
+fn map_time_entry_row(row: &rusqlite::Row) -> rusqlite::Result<TimeEntry> {
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        short_id: row.get(1)?,
+        project_path: row.get(2)?,
+        issue_id: row.get(3)?,
+        period: row.get(4)?,
+        hours: row.get(5)?,
+        description: row.get(6)?,
+        work_date: row.get(7)?,
+        status: row.get(8)?,
+        actor: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+// ==================
+// Data Structures
+// ==================
+
+/// A session record.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub branch: Option<String>,
+    pub channel: Option<String>,
+    pub project_path: Option<String>,
+    pub status: SessionStatus,
+    pub ended_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Linked git worktree the session was started in.
+    pub worktree: Option<String>,
+}
+
+json_schema!(Session {
+    id: String,
+    name: String,
+    description: Option<String>,
+    branch: Option<String>,
+    channel: Option<String>,
+    project_path: Option<String>,
+    status: SessionStatus,
+    ended_at: Option<i64>,
+    created_at: i64,
+    updated_at: i64,
+    worktree: Option<String>,
+});
+
+/// A context item record.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ContextItem {
+    pub id: String,
+    pub session_id: String,
+    pub key: String,
+    pub value: String,
+    pub category: ItemCategory,
+    pub priority: ItemPriority,
+    pub channel: Option<String>,
+    pub tags: Option<String>,
+    pub size: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Records exported before items had a source are taken as imported.
+    #[serde(default = "imported_source")]
+    pub source: ItemSource,
+    #[serde(default = "imported_trust")]
+    pub trust: TrustLevel,
+    #[serde(default)]
+    pub review_status: ReviewStatus,
+    /// When a scheduled reminder comes due (`sc remind`).
+    #[serde(default)]
+    pub due_at: Option<i64>,
+    /// When a scheduled reminder was marked done.
+    #[serde(default)]
+    pub done_at: Option<i64>,
+}
+
+impl ContextItem {
+    /// Whether this is an agent's decision no one has reviewed yet.
+    #[must_use]
+    pub fn awaits_review(&self) -> bool {
+        self.category == ItemCategory::Decision
+            && self.source == ItemSource::Agent
+            && self.review_status == ReviewStatus::Unreviewed
+    }
+}
+
+const fn imported_source() -> ItemSource {
+    ItemSource::Imported
+}
+
+const fn imported_trust() -> TrustLevel {
+    TrustLevel::Imported
+}
+
+json_schema!(ContextItem {
+    id: String,
+    session_id: String,
+    key: String,
+    value: String,
+    category: ItemCategory,
+    priority: ItemPriority,
+    channel: Option<String>,
+    tags: Option<String>,
+    size: i64,
+    created_at: i64,
+    updated_at: i64,
+    source: ItemSource,
+    trust: TrustLevel,
+    review_status: ReviewStatus,
+    due_at: Option<i64>,
+    done_at: Option<i64>,
+});
+
+/// An issue record.
+/// Note: Parent-child relationships are stored in issue_dependencies table.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Issue {
+    pub id: String,
+    pub short_id: Option<String>,
+    pub project_path: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub details: Option<String>,
+    pub status: IssueStatus,
+    pub priority: i32,
+    pub issue_type: IssueType,
+    pub plan_id: Option<String>,
+    pub created_by_agent: Option<String>,
+    pub assigned_to_agent: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub closed_at: Option<i64>,
+    /// Monorepo sub-project the issue belongs to, relative to the project
+    /// root; `None` for the whole project.
+    pub subproject: Option<String>,
+    /// Estimated hours of work, used by critical path analysis.
+    #[serde(default)]
+    pub estimate_hours: Option<f64>,
+}
+
+json_schema!(Issue {
+    id: String,
+    short_id: Option<String>,
+    project_path: String,
+    title: String,
+    description: Option<String>,
+    details: Option<String>,
+    status: IssueStatus,
+    priority: i32,
+    issue_type: IssueType,
+    plan_id: Option<String>,
+    created_by_agent: Option<String>,
+    assigned_to_agent: Option<String>,
+    created_at: i64,
+    updated_at: i64,
+    closed_at: Option<i64>,
+    subproject: Option<String>,
+    estimate_hours: Option<f64>,
+});
+
+/// An issue at the other end of a dependency.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct IssueLink {
+    pub id: String,
+    pub short_id: Option<String>,
+    pub title: String,
+    pub status: IssueStatus,
+    pub priority: i32,
+    pub dependency_type: String,
+}
+
+json_schema!(IssueLink {
+    id: String,
+    short_id: Option<String>,
+    title: String,
+    status: IssueStatus,
+    priority: i32,
+    dependency_type: String,
+});
+
+/// A registered actor.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Actor {
+    /// Canonical ID recorded on events and assignments.
+    pub id: String,
+    pub display_name: Option<String>,
+    pub actor_type: ActorType,
+    /// Other names that resolve to this actor.
+    pub aliases: Vec<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+json_schema!(Actor {
+    id: String,
+    display_name: Option<String>,
+    actor_type: ActorType,
+    aliases: Vec<String>,
+    created_at: i64,
+    updated_at: i64,
+});
+
+/// A worklog note on an issue.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorklogEntry {
+    pub issue_id: String,
+    pub short_id: Option<String>,
+    pub actor: String,
+    pub text: String,
+    /// Time entry holding the hours, when some were logged.
+    pub time_entry_id: Option<String>,
+    /// Null when no hours were logged or the time entry was deleted.
+    pub hours: Option<f64>,
+    pub created_at: i64,
+}
+
+json_schema!(WorklogEntry {
+    issue_id: String,
+    short_id: Option<String>,
+    actor: String,
+    text: String,
+    time_entry_id: Option<String>,
+    hours: Option<f64>,
+    created_at: i64,
+});
+
+/// A change of an issue's status, from the event log.
+#[derive(Debug, Clone)]
+pub struct IssueStatusChange {
+    pub issue_id: String,
+    /// Unknown for claims and releases.
+    pub from: Option<String>,
+    pub to: String,
+    pub actor: String,
+    /// Unix ms.
+    pub at: i64,
+}
+
+/// A project's unfinished issues in dependency order, from
+/// [`SqliteStorage::get_issue_waves`].
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct IssueWaves {
+    /// Issues that can be worked on in parallel, wave by wave.
+    pub waves: Vec<Vec<Issue>>,
+    /// Issues in a dependency cycle or waiting on work outside the project.
+    pub unscheduled: Vec<Issue>,
+}
+
+json_schema!(IssueWaves { waves: Vec<Vec<Issue>>, unscheduled: Vec<Issue> });
+
+/// Progress tracking for an epic (child issue counts by status).
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EpicProgress {
+    pub total: usize,
+    pub closed: usize,
+    pub in_progress: usize,
+    pub open: usize,
+    pub blocked: usize,
+    pub deferred: usize,
+}
+
+json_schema!(EpicProgress {
+    total: usize,
+    closed: usize,
+    in_progress: usize,
+    open: usize,
+    blocked: usize,
+    deferred: usize,
+});
+
+/// A time entry record for billable hour tracking.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub short_id: Option<String>,
+    pub project_path: String,
+    pub issue_id: Option<String>,
+    pub period: Option<String>,
+    pub hours: f64,
+    pub description: String,
+    pub work_date: String,
+    pub status: TimeEntryStatus,
+    pub actor: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+json_schema!(TimeEntry {
+    id: String,
+    short_id: Option<String>,
+    project_path: String,
+    issue_id: Option<String>,
+    period: Option<String>,
+    hours: f64,
+    description: String,
+    work_date: String,
+    status: TimeEntryStatus,
+    actor: Option<String>,
+    created_at: i64,
+    updated_at: i64,
+});
+
+/// A checkpoint record.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub session_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub git_status: Option<String>,
+    pub git_branch: Option<String>,
+    pub created_at: i64,
+    pub item_count: i64,
+}
+
+json_schema!(Checkpoint {
+    id: String,
+    session_id: String,
+    name: String,
+    description: Option<String>,
+    git_status: Option<String>,
+    git_branch: Option<String>,
+    created_at: i64,
+    item_count: i64,
+});
+
+/// A memory record (project-level persistent storage).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Memory {
+    pub id: String,
+    pub project_path: String,
+    pub key: String,
+    pub value: String,
+    pub category: MemoryCategory,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Monorepo sub-project the memory belongs to; `None` for the whole
+    /// project.
+    pub subproject: Option<String>,
+}
+
+json_schema!(Memory {
+    id: String,
+    project_path: String,
+    key: String,
+    value: String,
+    category: MemoryCategory,
+    created_at: i64,
+    updated_at: i64,
+    subproject: Option<String>,
+});
+
+/// A sync deletion record (tracks what was deleted for sync).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SyncDeletion {
+    /// Internal database ID.
+    pub id: i64,
+    /// The type of entity that was deleted (session, issue, etc.).
+    pub entity_type: String,
+    /// The ID of the deleted entity.
+    pub entity_id: String,
+    /// The project path this deletion belongs to.
+    pub project_path: String,
+    /// Unix timestamp (milliseconds) when the deletion occurred.
+    pub deleted_at: i64,
+    /// Actor who performed the deletion.
+    pub deleted_by: String,
+}
+
+/// An embedding chunk record.
+///
+/// Embeddings are stored as BLOB (binary f32 arrays) for efficiency.
+/// Large context items may be split into multiple chunks for better semantic coverage.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EmbeddingChunk {
+    /// Unique ID for this chunk.
+    pub id: String,
+    /// The context item this chunk belongs to.
+    pub item_id: String,
+    /// Chunk index (0 for single-chunk items).
+    pub chunk_index: i32,
+    /// The text that was embedded.
+    pub chunk_text: String,
+    /// The embedding vector (f32 values).
+    pub embedding: Vec<f32>,
+    /// Number of dimensions in the embedding.
+    pub dimensions: usize,
+    /// Provider that generated this embedding (e.g., "ollama").
+    pub provider: String,
+    /// Model used for embedding (e.g., "nomic-embed-text").
+    pub model: String,
+    /// Unix timestamp (milliseconds) when created.
+    pub created_at: i64,
+}
+
+/// Embedding statistics for a session or globally.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EmbeddingStats {
+    /// Number of items with embeddings.
+    pub with_embeddings: usize,
+    /// Number of items without embeddings.
+    pub without_embeddings: usize,
+}
+
+/// One record's embedding chunks, as carried by sync export and import.
+#[derive(Debug, Clone)]
+pub struct EntityEmbeddings {
+    pub entity_type: String,
+    pub entity_id: String,
+    /// `updated_at` of the record version the vectors were made from.
+    pub source_updated_at: i64,
+    pub provider: String,
+    pub model: String,
+    pub dimensions: usize,
+    pub chunk_texts: Vec<String>,
+    pub vectors: Vec<Vec<f32>>,
+}
+
+/// Stored embeddings from one provider, model and dimension count.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EmbeddingModelUsage {
+    pub provider: String,
+    pub model: String,
+    pub dimensions: usize,
+    pub chunks: usize,
+    /// Items, issues, plans and memories with chunks from this model.
+    pub entities: usize,
+}
+
+json_schema!(EmbeddingModelUsage {
+    provider: String,
+    model: String,
+    dimensions: usize,
+    chunks: usize,
+    entities: usize,
+});
+
+/// Embedding cache statistics.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EmbeddingCacheStats {
+    /// Cached embeddings.
+    pub entries: usize,
+    /// Lookups served from the cache.
+    pub hits: usize,
+    /// Lookups that had to call the provider.
+    pub misses: usize,
+    /// `hits / (hits + misses)`, 0 when nothing was looked up.
+    pub hit_rate: f64,
+}
+
+json_schema!(EmbeddingCacheStats { entries: usize, hits: usize, misses: usize, hit_rate: f64 });
+
+/// A semantic search result.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SemanticSearchResult {
+    /// The context item ID.
+    pub item_id: String,
+    /// Which chunk matched (0 for single-chunk items).
+    pub chunk_index: i32,
+    /// The text that was matched.
+    pub chunk_text: String,
+    /// Cosine similarity score (0.0 to 1.0).
+    pub similarity: f32,
+    /// Context item key.
+    pub key: String,
+    /// Context item value.
+    pub value: String,
+    /// Context item category.
+    pub category: String,
+    /// Context item priority.
+    pub priority: String,
+}
+
+/// Text of an issue, plan or memory to embed.
+#[derive(Debug, Clone)]
+pub struct EntityText {
+    /// `issue`, `plan` or `memory`.
+    pub entity_type: String,
+    pub id: String,
+    /// Issue or plan title, memory key.
+    pub title: String,
+    /// Issue description and details, plan content, memory value.
+    pub text: String,
+    /// Issue type, `plan`, or memory category.
+    pub label: String,
+}
+
+/// A semantic search hit on any embedded entity.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EntitySearchResult {
+    /// `context_item`, `issue`, `plan` or `memory`.
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Issue or plan short ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_id: Option<String>,
+    /// Context item or memory key, issue or plan title.
+    pub title: String,
+    /// The chunk that matched best.
+    pub chunk_text: String,
+    /// Cosine similarity score (0.0 to 1.0).
+    pub similarity: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+}
+
+json_schema!(EntitySearchResult {
+    entity_type: String,
+    entity_id: String,
+    short_id: Option<String>,
+    title: String,
+    chunk_text: String,
+    similarity: f32,
+    project_path: Option<String>,
+});
+
+/// Compute cosine similarity between two vectors.
+///
+/// Returns a value between -1.0 and 1.0, where:
+/// - 1.0 means identical direction
+/// - 0.0 means orthogonal (no similarity)
+/// - -1.0 means opposite direction
+///
+/// For normalized embeddings (which most models produce), this is equivalent
+/// to the dot product.
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
     }
 
-    // ========================================================================
-    // Fast Tier Embeddings (2-tier architecture)
-    // ========================================================================
+    let mut dot_product = 0.0;
+    let mut norm_a = 0.0;
+    let mut norm_b = 0.0;
 
-    /// Store a fast-tier embedding chunk (Model2Vec).
-    ///
-    /// Fast tier embeddings are stored separately for dimension isolation.
-    /// These are generated inline on save for instant semantic search.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the insert fails.
-    pub fn store_fast_embedding_chunk(
-        &mut self,
-        id: &str,
-        item_id: &str,
-        chunk_index: i32,
-        chunk_text: &str,
-        embedding: &[f32],
-        model: &str,
-    ) -> Result<()> {
-        let now = chrono::Utc::now().timestamp_millis();
-        let dimensions = embedding.len() as i32;
+    for (x, y) in a.iter().zip(b.iter()) {
+        dot_product += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
 
-        // Convert f32 slice to bytes (little-endian)
-        let blob: Vec<u8> = embedding
-            .iter()
-            .flat_map(|f| f.to_le_bytes())
-            .collect();
+    let magnitude = (norm_a * norm_b).sqrt();
+    if magnitude == 0.0 {
+        0.0
+    } else {
+        dot_product / magnitude
+    }
+}
 
-        self.conn.execute(
-            "INSERT INTO embedding_chunks_fast (id, item_id, chunk_index, chunk_text, embedding, dimensions, provider, model, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'model2vec', ?7, ?8)
-             ON CONFLICT(item_id, chunk_index) DO UPDATE SET
-               chunk_text = excluded.chunk_text,
-               embedding = excluded.embedding,
-               dimensions = excluded.dimensions,
-               model = excluded.model,
-               created_at = excluded.created_at",
-            rusqlite::params![id, item_id, chunk_index, chunk_text, blob, dimensions, model, now],
-        )?;
+/// Three random letters from `g`-`z`, so shard IDs never look like the
+/// hex short IDs issued before shards.
+fn random_shard() -> String {
+    uuid::Uuid::new_v4().as_bytes()[..3].iter().map(|b| char::from(b'g' + b % 20)).collect()
+}
 
-        // Update context_items fast embedding status
-        self.conn.execute(
-            "UPDATE context_items SET
-               fast_embedding_status = 'complete',
-               fast_embedded_at = ?1
-             WHERE id = ?2",
-            rusqlite::params![now, item_id],
-        )?;
+/// The shard of a short ID from [`SqliteStorage::next_issue_short_id`].
+fn shard_of(short_id: &str) -> Option<&str> {
+    let (shard, seq) = short_id.split_at_checked(3)?;
+    let is_shard = shard.bytes().all(|b| (b'g'..=b'z').contains(&b));
+    (is_shard && !seq.is_empty() && seq.bytes().all(|b| b.is_ascii_digit())).then_some(shard)
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_memory() {
+        let storage = SqliteStorage::open_memory();
+        assert!(storage.is_ok());
     }
 
-    /// Search fast-tier embeddings only.
-    ///
-    /// Returns candidates for tiered search or direct fast results.
-    /// Fast tier is optimized for speed over accuracy.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the query fails.
-    pub fn search_fast_tier(
-        &self,
-        query_embedding: &[f32],
-        session_id: Option<&str>,
-        limit: usize,
-        threshold: f32,
-    ) -> Result<Vec<SemanticSearchResult>> {
-        // Get all fast embedding chunks (optionally filtered by session)
-        let sql = if let Some(sid) = session_id {
-            format!(
-                "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
-                        ci.key, ci.value, ci.category, ci.priority
-                 FROM embedding_chunks_fast ec
-                 INNER JOIN context_items ci ON ec.item_id = ci.id
-                 WHERE ci.session_id = '{}'",
-                sid
+    #[test]
+    fn test_session_crud() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+
+        // Create
+        storage
+            .create_session(
+                "sess_1",
+                "Test Session",
+                Some("A test session"),
+                Some("/test/project"),
+                Some("main"),
+                None,
+                "test-actor",
             )
-        } else {
-            "SELECT ec.id, ec.item_id, ec.chunk_index, ec.chunk_text, ec.embedding, ec.dimensions,
-                    ci.key, ci.value, ci.category, ci.priority
-             FROM embedding_chunks_fast ec
-             INNER JOIN context_items ci ON ec.item_id = ci.id".to_string()
+            .unwrap();
+
+        // Read
+        let session = storage.get_session("sess_1").unwrap();
+        assert!(session.is_some());
+        let session = session.unwrap();
+        assert_eq!(session.name, "Test Session");
+        assert_eq!(session.status, SessionStatus::Active);
+
+        // List
+        let sessions = storage
+            .list_sessions(Some("/test/project"), None, None)
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+
+        // Update status
+        storage
+            .update_session_status("sess_1", SessionStatus::Completed, "test-actor")
+            .unwrap();
+        let session = storage.get_session("sess_1").unwrap().unwrap();
+        assert_eq!(session.status, SessionStatus::Completed);
+        assert!(session.ended_at.is_some());
+    }
+
+    #[test]
+    fn test_context_item_crud() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+
+        // Create session first
+        storage
+            .create_session("sess_1", "Test", None, None, None, None, "actor")
+            .unwrap();
+
+        // Save item
+        storage
+            .save_context_item(
+                "item_1",
+                "sess_1",
+                "test-key",
+                "test value",
+                Some(ItemCategory::Note),
+                Some(ItemPriority::High),
+                "actor",
+            )
+            .unwrap();
+
+        // Get items
+        let items = storage.get_context_items("sess_1", None, None, None).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].key, "test-key");
+        assert_eq!(items[0].priority, ItemPriority::High);
+
+        // Update (upsert)
+        storage
+            .save_context_item(
+                "item_1",
+                "sess_1",
+                "test-key",
+                "updated value",
+                Some(ItemCategory::Decision),
+                None,
+                "actor",
+            )
+            .unwrap();
+
+        let items = storage.get_context_items("sess_1", None, None, None).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].value, "updated value");
+
+        // Reads are counted, without touching the item
+        let ids = vec!["item_1".to_string(), "missing".to_string()];
+        storage.record_item_access(&ids).unwrap();
+        storage.record_item_access(&ids[..1]).unwrap();
+        let access = storage.get_item_access(&ids).unwrap();
+        assert_eq!(access.len(), 1);
+        assert_eq!(access["item_1"].count, 2);
+        assert_eq!(storage.get_context_items("sess_1", None, None, None).unwrap()[0].updated_at, items[0].updated_at);
+
+        // Delete
+        storage
+            .delete_context_item("sess_1", "test-key", "actor")
+            .unwrap();
+        let items = storage.get_context_items("sess_1", None, None, None).unwrap();
+        assert_eq!(items.len(), 0);
+        assert!(storage.get_item_access(&ids).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_large_values_stored_compressed() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Test", None, None, None, None, "actor")
+            .unwrap();
+
+        let diff = "+    let value = compute();\n".repeat(2000);
+        let len = i64::try_from(diff.len()).unwrap();
+        storage
+            .save_context_item("item_1", "sess_1", "diff", &diff, None, None, "actor")
+            .unwrap();
+        let item = storage.get_context_item("item_1").unwrap().unwrap();
+        assert_eq!(item.value, diff);
+        assert_eq!(item.size, len);
+
+        let stats = storage.compression_stats().unwrap();
+        assert_eq!((stats.items, stats.compressed_items), (1, 1));
+        assert_eq!(stats.original_bytes, len);
+        assert!(stats.saved_bytes() > stats.original_bytes / 2);
+
+        // Shrinking the value stores it as plain text again
+        storage
+            .update_context_item("sess_1", "diff", Some("short"), None, None, None, "actor")
+            .unwrap();
+        assert_eq!(storage.get_context_item("item_1").unwrap().unwrap().value, "short");
+        assert_eq!(storage.compression_stats().unwrap().compressed_items, 0);
+    }
+
+    #[test]
+    fn test_encrypted_category_values() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Test", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        let stored = |storage: &SqliteStorage| -> (rusqlite::types::Value, bool) {
+            storage
+                .conn()
+                .query_row("SELECT value, value_encrypted FROM context_items WHERE id = 'item_1'", [], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .unwrap()
         };
 
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map([], |row| {
-            let blob: Vec<u8> = row.get(4)?;
-            let embedding: Vec<f32> = blob
-                .chunks_exact(4)
-                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-                .collect();
+        storage
+            .save_context_item("item_1", "sess_1", "staging-db", "password in vault/staging", None, None, "actor")
+            .unwrap();
+        let secrets = ItemCategory::Custom("secrets".to_string());
+        let mut category = crate::model::Category { encrypted: true, ..crate::model::Category::custom(secrets.clone()) };
+        storage.save_category("/repo", &category, "actor").unwrap();
 
-            Ok((
-                row.get::<_, String>(1)?, // item_id
-                row.get::<_, i32>(2)?,    // chunk_index
-                row.get::<_, String>(3)?, // chunk_text
-                embedding,
-                row.get::<_, String>(6)?, // key
-                row.get::<_, String>(7)?, // value
-                row.get::<_, String>(8)?, // category
-                row.get::<_, String>(9)?, // priority
-            ))
-        })?;
+        // Moving the item into the category encrypts it; reads decrypt it
+        storage
+            .update_context_item("sess_1", "staging-db", None, Some(secrets), None, None, "actor")
+            .unwrap();
+        assert!(matches!(stored(&storage), (rusqlite::types::Value::Blob(_), true)));
+        assert_eq!(storage.get_context_item("item_1").unwrap().unwrap().value, "password in vault/staging");
+        let synced = storage.get_context_items_for_sync("/repo").unwrap();
+        assert!(synced[0].value.starts_with(crate::storage::encryption::SEALED_PREFIX));
 
-        // Compute similarities and collect results
-        let mut results: Vec<SemanticSearchResult> = rows
-            .filter_map(|row| row.ok())
-            .map(|(item_id, chunk_index, chunk_text, embedding, key, value, category, priority)| {
-                let similarity = cosine_similarity(query_embedding, &embedding);
-                SemanticSearchResult {
-                    item_id,
-                    chunk_index,
-                    chunk_text,
-                    similarity,
-                    key,
-                    value,
-                    category,
-                    priority,
-                }
-            })
-            .filter(|r| r.similarity >= threshold)
-            .collect();
+        // A redacted value can't be saved back over the real one
+        let redacted = crate::storage::encryption::REDACTED;
+        assert!(storage.update_context_item("sess_1", "staging-db", Some(redacted), None, None, None, "actor").is_err());
 
-        // Sort by similarity (highest first)
-        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+        // Turning encryption off stores the values plain again
+        category.encrypted = false;
+        storage.save_category("/repo", &category, "actor").unwrap();
+        assert_eq!(stored(&storage), (rusqlite::types::Value::Text("password in vault/staging".to_string()), false));
+    }
 
-        // Take top N results, deduplicating by item_id
-        let mut seen_items = std::collections::HashSet::new();
-        let deduped: Vec<SemanticSearchResult> = results
+    #[test]
+    fn test_with_transaction_nests_as_savepoints() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Test", None, None, None, None, "actor")
+            .unwrap();
+
+        storage
+            .with_transaction(|s| {
+                s.save_context_item("item_1", "sess_1", "kept", "v", None, None, "actor")
+                    .unwrap();
+                // A rolled-back inner scope leaves the outer one intact
+                s.with_transaction(|s| {
+                    s.save_context_item("item_2", "sess_1", "undone", "v", None, None, "actor")
+                        .unwrap();
+                    ((), false)
+                })
+                .unwrap();
+                ((), true)
+            })
+            .unwrap();
+        let keys: Vec<_> = storage
+            .get_context_items("sess_1", None, None, None)
+            .unwrap()
             .into_iter()
-            .filter(|r| seen_items.insert(r.item_id.clone()))
-            .take(limit)
+            .map(|i| i.key)
             .collect();
+        assert_eq!(keys, vec!["kept"]);
 
-        Ok(deduped)
+        storage
+            .with_transaction(|s| {
+                s.save_context_item("item_3", "sess_1", "discarded", "v", None, None, "actor")
+                    .unwrap();
+                ((), false)
+            })
+            .unwrap();
+        assert_eq!(storage.get_context_items("sess_1", None, None, None).unwrap().len(), 1);
+        assert!(storage.conn.is_autocommit());
+
+        // A failing step undoes the steps before it
+        let result = storage.atomically(|s| {
+            s.save_context_item("item_4", "sess_1", "first", "v", None, None, "actor")?;
+            s.add_issue_labels("issue_missing", &["x".to_string()], "actor")
+        });
+        assert!(result.is_err());
+        assert_eq!(storage.get_context_items("sess_1", None, None, None).unwrap().len(), 1);
+        assert!(storage.conn.is_autocommit());
     }
 
-    /// Get context items with fast embeddings but no quality embeddings.
-    ///
-    /// Used by background quality upgrade process.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the query fails.
-    pub fn get_items_needing_quality_upgrade(
-        &self,
-        session_id: Option<&str>,
-        limit: Option<u32>,
-    ) -> Result<Vec<ContextItem>> {
-        let limit = limit.unwrap_or(100);
+    #[test]
+    fn test_issue_crud() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
 
-        let sql = if let Some(sid) = session_id {
-            format!(
-                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at
-                 FROM context_items
-                 WHERE session_id = '{}'
-                   AND fast_embedding_status = 'complete'
-                   AND (embedding_status IS NULL OR embedding_status = 'none' OR embedding_status = 'pending')
-                 ORDER BY created_at DESC
-                 LIMIT {}",
-                sid, limit
-            )
-        } else {
-            format!(
-                "SELECT id, session_id, key, value, category, priority, channel, tags, size, created_at, updated_at
-                 FROM context_items
-                 WHERE fast_embedding_status = 'complete'
-                   AND (embedding_status IS NULL OR embedding_status = 'none' OR embedding_status = 'pending')
-                 ORDER BY created_at DESC
-                 LIMIT {}",
-                limit
+        // Create
+        storage
+            .create_issue(
+                "issue_1",
+                Some("TST-1"),
+                "/test/project",
+                "Test Issue",
+                Some("Description"),
+                None,         // details
+                Some(IssueType::Task), // issue_type
+                Some(3),      // priority
+                None, None,         // plan_id
+                "actor",
             )
-        };
+            .unwrap();
 
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map([], |row| {
-            Ok(ContextItem {
-                id: row.get(0)?,
-                session_id: row.get(1)?,
-                key: row.get(2)?,
-                value: row.get(3)?,
-                category: row.get(4)?,
-                priority: row.get(5)?,
-                channel: row.get(6)?,
-                tags: row.get(7)?,
-                size: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        })?;
+        // Get by full ID
+        let issue = storage.get_issue("issue_1", None).unwrap();
+        assert!(issue.is_some());
+        let issue = issue.unwrap();
+        assert_eq!(issue.title, "Test Issue");
+        assert_eq!(issue.priority, 3);
 
-        rows.collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(Error::from)
-    }
+        // Get by short ID
+        let issue = storage
+            .get_issue("TST-1", Some("/test/project"))
+            .unwrap();
+        assert!(issue.is_some());
 
-    /// Delete fast-tier embeddings for a context item.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the delete fails.
-    pub fn delete_fast_embeddings(&mut self, item_id: &str) -> Result<()> {
-        self.conn.execute(
-            "DELETE FROM embedding_chunks_fast WHERE item_id = ?1",
-            [item_id],
-        )?;
+        // List
+        let issues = storage
+            .list_issues("/test/project", None, None, None)
+            .unwrap();
+        assert_eq!(issues.len(), 1);
 
-        self.conn.execute(
-            "UPDATE context_items SET
-               fast_embedding_status = 'none',
-               fast_embedded_at = NULL
-             WHERE id = ?1",
-            [item_id],
-        )?;
+        // Claim
+        storage.claim_issue("issue_1", &IssueWorkflow::default(), "agent-1").unwrap();
+        let issue = storage.get_issue("issue_1", None).unwrap().unwrap();
+        assert_eq!(issue.assigned_to_agent, Some("agent-1".to_string()));
+        assert_eq!(issue.status, IssueStatus::InProgress);
 
-        Ok(())
+        // Release
+        storage.release_issue("issue_1", &IssueWorkflow::default(), "agent-1").unwrap();
+        let issue = storage.get_issue("issue_1", None).unwrap().unwrap();
+        assert!(issue.assigned_to_agent.is_none());
+        assert_eq!(issue.status, IssueStatus::Open);
+
+        // Close
+        storage
+            .update_issue_status("issue_1", &IssueStatus::Closed, None, &IssueWorkflow::default(), "actor")
+            .unwrap();
+        let issue = storage.get_issue("issue_1", None).unwrap().unwrap();
+        assert_eq!(issue.status, IssueStatus::Closed);
+        assert!(issue.closed_at.is_some());
     }
 
-    /// Count fast embedding status.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the query fails.
-    pub fn count_fast_embedding_status(&self, session_id: Option<&str>) -> Result<EmbeddingStats> {
-        let (with_embeddings, without_embeddings) = if let Some(sid) = session_id {
-            let with: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND fast_embedding_status = 'complete'",
-                [sid],
-                |row| row.get(0),
-            )?;
-            let without: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE session_id = ?1 AND (fast_embedding_status IS NULL OR fast_embedding_status = 'none')",
-                [sid],
-                |row| row.get(0),
-            )?;
-            (with, without)
-        } else {
-            let with: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE fast_embedding_status = 'complete'",
-                [],
-                |row| row.get(0),
-            )?;
-            let without: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM context_items WHERE fast_embedding_status IS NULL OR fast_embedding_status = 'none'",
-                [],
-                |row| row.get(0),
-            )?;
-            (with, without)
-        };
+    #[test]
+    fn test_issue_short_ids_are_sharded() {
+        let storage = SqliteStorage::open_memory().unwrap();
+        let shard = storage.machine_shard().unwrap();
+        assert_eq!(shard.len(), 3);
+        assert!(shard.bytes().all(|b| (b'g'..=b'z').contains(&b)));
+        assert_eq!(storage.next_issue_short_id().unwrap(), format!("{shard}1"));
+        assert_eq!(storage.next_issue_short_id().unwrap(), format!("{shard}2"));
+        assert!(storage.owns_short_id(&format!("{shard}2")).unwrap());
+        assert!(!storage.owns_short_id("a1b2").unwrap());
+
+        let rerolled = storage.reroll_machine_shard().unwrap();
+        assert_eq!(storage.next_issue_short_id().unwrap(), format!("{rerolled}3"));
+        assert_eq!(shard_of("kqx17"), Some("kqx"));
+        assert_eq!(shard_of("fab1"), None);
+        assert_eq!(shard_of("kqx"), None);
+    }
 
-        Ok(EmbeddingStats {
-            with_embeddings: with_embeddings as usize,
-            without_embeddings: without_embeddings as usize,
-        })
+    #[test]
+    fn test_issue_status_workflow() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_issue("issue_1", Some("TST-1"), "/p", "Test", None, None, None, None, None, None, "actor")
+            .unwrap();
+
+        let mut workflow = IssueWorkflow::default();
+        workflow
+            .required_fields
+            .insert("closed".to_string(), vec!["close_reason".to_string()]);
+
+        // Unknown status is rejected
+        let err = storage
+            .update_issue_status("TST-1", &IssueStatus::Custom("review".to_string()), None, &workflow, "actor")
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidTransition { .. }));
+
+        // Closing requires a reason under this workflow
+        let err = storage
+            .update_issue_status("TST-1", &IssueStatus::Closed, None, &workflow, "actor")
+            .unwrap_err();
+        assert!(matches!(err, Error::RequiredField { ref field, .. } if field == "close_reason"));
+
+        storage
+            .update_issue_status("TST-1", &IssueStatus::Closed, Some("Fixed"), &workflow, "actor")
+            .unwrap();
+        assert_eq!(storage.get_close_reason("issue_1").unwrap().as_deref(), Some("Fixed"));
+
+        // closed → blocked is not a legal move
+        let err = storage
+            .update_issue_status("TST-1", &IssueStatus::Blocked, None, &workflow, "actor")
+            .unwrap_err();
+        match err {
+            Error::InvalidTransition { from, allowed, .. } => {
+                assert_eq!(from, "closed");
+                assert_eq!(allowed, vec!["open".to_string()]);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
     }
-}
 
-// Helper to map plan rows
-fn map_plan_row(row: &rusqlite::Row) -> rusqlite::Result<Plan> {
-    let status_str: String = row.get(6)?;
-    Ok(Plan {
-        id: row.get(0)?,
-        short_id: row.get(1)?,
-        project_id: row.get(2)?,
-        project_path: row.get(3)?,
-        title: row.get(4)?,
-        content: row.get(5)?,
-        status: PlanStatus::from_str(&status_str),
-        success_criteria: row.get(7)?,
-        session_id: row.get(8)?,
-        created_in_session: row.get(9)?,
-        completed_in_session: row.get(10)?,
-        source_path: row.get(11)?,
-        source_hash: row.get(12)?,
-        created_at: row.get(13)?,
-        updated_at: row.get(14)?,
-        completed_at: row.get(15)?,
-    })
-}
+    #[test]
+    fn test_claim_and_release_follow_workflow() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        let workflow = IssueWorkflow::default();
+        for (id, short_id) in [("i1", "T-1"), ("i2", "T-2"), ("i3", "T-3")] {
+            storage.create_issue(id, Some(short_id), "/p", "Task", None, None, None, None, None, None, "actor").unwrap();
+        }
 
-// Helper to map project rows
-fn map_project_row(row: &rusqlite::Row) -> rusqlite::Result<Project> {
-    Ok(Project {
-        id: row.get(0)?,
-        project_path: row.get(1)?,
-        name: row.get(2)?,
-        description: row.get(3)?,
-        issue_prefix: row.get(4)?,
-        next_issue_number: row.get(5)?,
-        plan_prefix: row.get(6)?,
-        next_plan_number: row.get(7)?,
-        created_at: row.get(8)?,
-        updated_at: row.get(9)?,
-    })
-}
+        // closed → in_progress is not a legal move, so the claim is refused
+        storage.update_issue_status("T-1", &IssueStatus::Closed, None, &workflow, "actor").unwrap();
+        let err = storage.claim_issue("T-1", &workflow, "agent-1").unwrap_err();
+        assert!(matches!(err, Error::InvalidTransition { ref from, ref to, .. } if from == "closed" && to == "in_progress"));
+        let issue = storage.get_issue("i1", None).unwrap().unwrap();
+        assert_eq!(issue.status, IssueStatus::Closed);
+        assert!(issue.assigned_to_agent.is_none());
 
-// Helper to map issue rows
-fn map_issue_row(row: &rusqlite::Row) -> rusqlite::Result<Issue> {
-    Ok(Issue {
-        id: row.get(0)?,
-        short_id: row.get(1)?,
-        project_path: row.get(2)?,
-        title: row.get(3)?,
-        description: row.get(4)?,
-        details: row.get(5)?,
-        status: row.get(6)?,
-        priority: row.get(7)?,
-        issue_type: row.get(8)?,
-        plan_id: row.get(9)?,
-        created_by_agent: row.get(10)?,
-        assigned_to_agent: row.get(11)?,
-        created_at: row.get(12)?,
-        updated_at: row.get(13)?,
-        closed_at: row.get(14)?,
-    })
-}
+        // Neither is deferred → in_progress
+        storage.update_issue_status("T-2", &IssueStatus::Deferred, None, &workflow, "actor").unwrap();
+        assert!(matches!(
+            storage.claim_issue("T-2", &workflow, "agent-1").unwrap_err(),
+            Error::InvalidTransition { .. }
+        ));
+        assert_eq!(storage.get_issue("i2", None).unwrap().unwrap().status, IssueStatus::Deferred);
+
+        // A workflow that keeps claimed work from going back to open holds
+        // on to expired claims too
+        let mut strict = IssueWorkflow::default();
+        strict.transitions.insert("in_progress".to_string(), vec!["closed".to_string()]);
+        storage.claim_issue("T-3", &strict, "agent-1").unwrap();
+        assert!(matches!(
+            storage.release_issue("T-3", &strict, "agent-1").unwrap_err(),
+            Error::InvalidTransition { .. }
+        ));
+        storage.grant_claim_lease("i3", "agent-1", -1000).unwrap();
+        assert!(storage.release_expired_claims("/p", false, &strict, "actor").unwrap().is_empty());
+        assert_eq!(storage.get_issue("i3", None).unwrap().unwrap().status, IssueStatus::InProgress);
+        assert_eq!(storage.release_expired_claims("/p", false, &workflow, "actor").unwrap().len(), 1);
+        assert_eq!(storage.get_issue("i3", None).unwrap().unwrap().status, IssueStatus::Open);
+    }
 
-fn map_time_entry_row(row: &rusqlite::Row) -> rusqlite::Result<TimeEntry> {
-    Ok(TimeEntry {
-        id: row.get(0)?,
-        short_id: row.get(1)?,
-        project_path: row.get(2)?,
-        issue_id: row.get(3)?,
-        period: row.get(4)?,
-        hours: row.get(5)?,
-        description: row.get(6)?,
-        work_date: row.get(7)?,
-        status: row.get(8)?,
-        actor: row.get(9)?,
-        created_at: row.get(10)?,
-        updated_at: row.get(11)?,
-    })
-}
+    #[test]
+    fn test_issue_links_both_directions() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        for (id, short_id, title) in [("i1", "E-1", "Epic"), ("i2", "T-1", "Task"), ("i3", "T-2", "Blocker")] {
+            storage.create_issue(id, Some(short_id), "/p", title, None, None, None, None, None, None, "actor").unwrap();
+        }
+        storage.add_issue_dependency("T-1", "E-1", "parent-child", "actor").unwrap();
+        storage.add_issue_dependency("T-1", "T-2", "blocks", "actor").unwrap();
 
-// ==================
-// Data Structures
-// ==================
+        let deps = storage.get_issue_dependencies("T-1").unwrap();
+        let deps: Vec<(&str, &str)> = deps.iter().map(|l| (l.title.as_str(), l.dependency_type.as_str())).collect();
+        assert_eq!(deps, [("Blocker", "blocks"), ("Epic", "parent-child")]);
+        let dependents = storage.get_issue_dependents("i3").unwrap();
+        assert_eq!((dependents[0].short_id.as_deref(), dependents.len()), (Some("T-1"), 1));
+        assert!(storage.get_issue_dependents("i2").unwrap().is_empty());
+        assert!(matches!(storage.get_issue_dependencies("nope"), Err(Error::IssueNotFound { .. })));
+    }
 
-/// A session record.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Session {
-    pub id: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub branch: Option<String>,
-    pub channel: Option<String>,
-    pub project_path: Option<String>,
-    pub status: String,
-    pub ended_at: Option<i64>,
-    pub created_at: i64,
-    pub updated_at: i64,
-}
+    #[test]
+    fn test_issue_waves() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        for (id, priority) in [("a", 2), ("b", 3), ("c", 2), ("d", 2), ("e", 2), ("f", 2), ("x", 2)] {
+            storage.create_issue(id, None, "/p", id, None, None, None, Some(priority), None, None, "actor").unwrap();
+        }
+        storage.create_issue("other", None, "/q", "other", None, None, None, None, None, None, "actor").unwrap();
+        // a → c → d, b → d; e ⇄ f; x waits on another project
+        for (issue, blocker) in [("c", "a"), ("d", "c"), ("d", "b"), ("e", "f"), ("f", "e"), ("x", "other")] {
+            storage.add_issue_dependency(issue, blocker, "blocks", "actor").unwrap();
+        }
 
-/// A context item record.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ContextItem {
-    pub id: String,
-    pub session_id: String,
-    pub key: String,
-    pub value: String,
-    pub category: String,
-    pub priority: String,
-    pub channel: Option<String>,
-    pub tags: Option<String>,
-    pub size: i64,
-    pub created_at: i64,
-    pub updated_at: i64,
-}
+        let waves = storage.get_issue_waves("/p").unwrap();
+        let ids = |issues: &[Issue]| issues.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        let layers: Vec<Vec<String>> = waves.waves.iter().map(|w| ids(w)).collect();
+        assert_eq!(layers, [vec!["b", "a"], vec!["c"], vec!["d"]]);
+        assert_eq!(ids(&waves.unscheduled), ["e", "f", "x"]);
+    }
 
-/// An issue record.
-/// Note: Parent-child relationships are stored in issue_dependencies table.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Issue {
-    pub id: String,
-    pub short_id: Option<String>,
-    pub project_path: String,
-    pub title: String,
-    pub description: Option<String>,
-    pub details: Option<String>,
-    pub status: String,
-    pub priority: i32,
-    pub issue_type: String,
-    pub plan_id: Option<String>,
-    pub created_by_agent: Option<String>,
-    pub assigned_to_agent: Option<String>,
-    pub created_at: i64,
-    pub updated_at: i64,
-    pub closed_at: Option<i64>,
-}
+    #[test]
+    fn test_worklog_links_time_entries() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_issue("i1", Some("T-1"), "/p", "Flaky test", None, None, None, None, None, None, "actor").unwrap();
+        storage.create_time_entry("time_1", None, "/p", 1.5, "Pool race", "2026-01-05", Some("i1"), None, "actor").unwrap();
+        storage.log_issue_work("T-1", "Suspect a race in the pool", Some("time_1"), "alice").unwrap();
+        storage.log_issue_work("i1", "Reproduced under load", None, "bob").unwrap();
+        storage.comment_issue("i1", "Not a worklog entry", "alice").unwrap();
 
-/// Progress tracking for an epic (child issue counts by status).
-#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
-pub struct EpicProgress {
-    pub total: usize,
-    pub closed: usize,
-    pub in_progress: usize,
-    pub open: usize,
-    pub blocked: usize,
-    pub deferred: usize,
-}
+        let log = storage.get_worklog(Some("i1"), None).unwrap();
+        let entries: Vec<(&str, Option<f64>)> = log.iter().map(|e| (e.actor.as_str(), e.hours)).collect();
+        assert_eq!(entries, [("alice", Some(1.5)), ("bob", None)]);
+        assert_eq!(log[0].short_id.as_deref(), Some("T-1"));
+        assert_eq!(storage.get_worklog(None, Some("/p")).unwrap().len(), 2);
+        assert!(storage.get_worklog(None, Some("/other")).unwrap().is_empty());
+        assert!(storage.log_issue_work("nope", "x", None, "alice").is_err());
+    }
 
-/// A time entry record for billable hour tracking.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct TimeEntry {
-    pub id: String,
-    pub short_id: Option<String>,
-    pub project_path: String,
-    pub issue_id: Option<String>,
-    pub period: Option<String>,
-    pub hours: f64,
-    pub description: String,
-    pub work_date: String,
-    pub status: String,
-    pub actor: Option<String>,
-    pub created_at: i64,
-    pub updated_at: i64,
-}
+    #[test]
+    fn test_item_source_follows_actor_type() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_session("sess_1", "Test", None, Some("/p"), None, None, "actor").unwrap();
+        storage.register_actor("claude", None, Some(ActorType::Agent), "admin").unwrap();
+        storage.add_actor_alias("claude", "claude@ci", "admin").unwrap();
+
+        storage.save_context_item("item_1", "sess_1", "guess", "Maybe a race", None, None, "claude@ci").unwrap();
+        storage.save_context_item("item_2", "sess_1", "plan", "Use JWT", None, None, "alice").unwrap();
+        let item = storage.get_context_item("item_1").unwrap().unwrap();
+        assert_eq!((item.source, item.trust), (ItemSource::Agent, TrustLevel::Agent));
+        let item = storage.get_context_item("item_2").unwrap().unwrap();
+        assert_eq!((item.source, item.trust), (ItemSource::Human, TrustLevel::Human));
+
+        // A person vetting the note raises trust; their later saves keep
+        // source and trust
+        storage.set_item_trust("sess_1", "guess", TrustLevel::Human, "alice").unwrap();
+        storage.save_context_item("item_3", "sess_1", "guess", "A race in the pool", None, None, "alice").unwrap();
+        let item = storage.get_context_item("item_1").unwrap().unwrap();
+        assert_eq!((item.source, item.trust), (ItemSource::Agent, TrustLevel::Human));
+        assert!(storage.set_item_trust("sess_1", "nope", TrustLevel::Human, "alice").is_err());
+
+        // Records exported before items had a source count as imported
+        let mut json = serde_json::to_value(&item).unwrap();
+        json.as_object_mut().unwrap().retain(|k, _| k != "source" && k != "trust");
+        let old: ContextItem = serde_json::from_value(json).unwrap();
+        assert_eq!((old.source, old.trust), (ItemSource::Imported, TrustLevel::Imported));
+    }
 
-/// A checkpoint record.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Checkpoint {
-    pub id: String,
-    pub session_id: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub git_status: Option<String>,
-    pub git_branch: Option<String>,
-    pub created_at: i64,
-    pub item_count: i64,
-}
+    #[test]
+    fn test_review_context_item() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_session("sess_1", "Test", None, Some("/p"), None, None, "actor").unwrap();
+        storage.register_actor("claude", None, Some(ActorType::Agent), "admin").unwrap();
+        storage.save_context_item("item_1", "sess_1", "db", "Use Postgres", Some(ItemCategory::Decision), None, "claude").unwrap();
+        assert!(storage.get_context_item("item_1").unwrap().unwrap().awaits_review());
+
+        // Agents can't approve their own guesses
+        let err = storage.review_context_item("sess_1", "db", ReviewStatus::Approved, None, "claude").unwrap_err();
+        assert!(err.to_string().contains("registered as an agent"), "{err}");
+
+        storage.review_context_item("sess_1", "db", ReviewStatus::Approved, Some("Checked the load test"), "alice").unwrap();
+        let item = storage.get_context_item("item_1").unwrap().unwrap();
+        assert_eq!((item.review_status, item.trust), (ReviewStatus::Approved, TrustLevel::Human));
+        let comment: Option<String> = storage
+            .conn
+            .query_row("SELECT comment FROM events WHERE entity_id = 'item_1' AND event_type = 'item_reviewed'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(comment.as_deref(), Some("Checked the load test"));
 
-/// A memory record (project-level persistent storage).
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Memory {
-    pub id: String,
-    pub project_path: String,
-    pub key: String,
-    pub value: String,
-    pub category: String,
-    pub created_at: i64,
-    pub updated_at: i64,
-}
+        // Saving the same value keeps the review; an agent's rewrite reopens it
+        storage.save_context_item("item_2", "sess_1", "db", "Use Postgres", Some(ItemCategory::Decision), None, "claude").unwrap();
+        assert_eq!(storage.get_context_item("item_1").unwrap().unwrap().review_status, ReviewStatus::Approved);
+        storage.save_context_item("item_2", "sess_1", "db", "Use SQLite", Some(ItemCategory::Decision), None, "claude").unwrap();
+        let item = storage.get_context_item("item_1").unwrap().unwrap();
+        assert_eq!((item.review_status, item.trust), (ReviewStatus::Unreviewed, TrustLevel::Agent));
+    }
 
-/// A sync deletion record (tracks what was deleted for sync).
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct SyncDeletion {
-    /// Internal database ID.
-    pub id: i64,
-    /// The type of entity that was deleted (session, issue, etc.).
-    pub entity_type: String,
-    /// The ID of the deleted entity.
-    pub entity_id: String,
-    /// The project path this deletion belongs to.
-    pub project_path: String,
-    /// Unix timestamp (milliseconds) when the deletion occurred.
-    pub deleted_at: i64,
-    /// Actor who performed the deletion.
-    pub deleted_by: String,
-}
+    #[test]
+    fn test_actor_aliases_normalize_writes() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.register_actor("alice", Some("Alice L"), None, "admin").unwrap();
+        assert!(storage.add_actor_alias("ALICE", "alice@laptop", "admin").unwrap());
+        assert!(!storage.add_actor_alias("alice", "Alice@Laptop", "admin").unwrap());
+        storage.register_actor("bob", None, Some(ActorType::Agent), "admin").unwrap();
+        assert!(storage.add_actor_alias("bob", "alice@laptop", "admin").is_err());
+        assert!(storage.register_actor("alice@laptop", None, None, "admin").is_err());
+        assert!(storage.add_actor_alias("carol", "c", "admin").is_err());
+
+        storage.create_issue("i1", None, "/p", "Task", None, None, None, None, None, None, "alice@laptop").unwrap();
+        storage.claim_issue("i1", &IssueWorkflow::default(), "Alice@Laptop").unwrap();
+        let issue = storage.get_issue("i1", None).unwrap().unwrap();
+        assert_eq!(issue.created_by_agent.as_deref(), Some("alice"));
+        assert_eq!(issue.assigned_to_agent.as_deref(), Some("alice"));
+        let actors: Vec<String> = storage
+            .conn
+            .prepare("SELECT DISTINCT actor FROM events WHERE entity_id = 'i1'")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(actors, ["alice"]);
 
-/// An embedding chunk record.
-///
-/// Embeddings are stored as BLOB (binary f32 arrays) for efficiency.
-/// Large context items may be split into multiple chunks for better semantic coverage.
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct EmbeddingChunk {
-    /// Unique ID for this chunk.
-    pub id: String,
-    /// The context item this chunk belongs to.
-    pub item_id: String,
-    /// Chunk index (0 for single-chunk items).
-    pub chunk_index: i32,
-    /// The text that was embedded.
-    pub chunk_text: String,
-    /// The embedding vector (f32 values).
-    pub embedding: Vec<f32>,
-    /// Number of dimensions in the embedding.
-    pub dimensions: usize,
-    /// Provider that generated this embedding (e.g., "ollama").
-    pub provider: String,
-    /// Model used for embedding (e.g., "nomic-embed-text").
-    pub model: String,
-    /// Unix timestamp (milliseconds) when created.
-    pub created_at: i64,
-}
+        assert_eq!(storage.resolve_actor("ALICE@LAPTOP").unwrap(), "alice");
+        assert_eq!(storage.resolve_actor("dave").unwrap(), "dave");
+        let alice = storage.get_actor("alice@laptop").unwrap().unwrap();
+        assert_eq!(alice.aliases, ["alice@laptop"]);
+        assert_eq!(storage.get_actor("bob").unwrap().unwrap().actor_type, ActorType::Agent);
 
-/// Embedding statistics for a session or globally.
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct EmbeddingStats {
-    /// Number of items with embeddings.
-    pub with_embeddings: usize,
-    /// Number of items without embeddings.
-    pub without_embeddings: usize,
-}
+        assert!(storage.remove_actor_alias("alice", "admin").is_err());
+        assert!(storage.remove_actor_alias("alice@laptop", "admin").unwrap());
+        assert_eq!(storage.resolve_actor("alice@laptop").unwrap(), "alice@laptop");
+    }
 
-/// A semantic search result.
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct SemanticSearchResult {
-    /// The context item ID.
-    pub item_id: String,
-    /// Which chunk matched (0 for single-chunk items).
-    pub chunk_index: i32,
-    /// The text that was matched.
-    pub chunk_text: String,
-    /// Cosine similarity score (0.0 to 1.0).
-    pub similarity: f32,
-    /// Context item key.
-    pub key: String,
-    /// Context item value.
-    pub value: String,
-    /// Context item category.
-    pub category: String,
-    /// Context item priority.
-    pub priority: String,
-}
+    #[test]
+    fn test_purge_actor() {
+        let setup = || {
+            let mut storage = SqliteStorage::open_memory().unwrap();
+            storage.register_actor("alice", None, None, "admin").unwrap();
+            storage.add_actor_alias("alice", "alice@laptop", "admin").unwrap();
+            storage.create_session("sess_bob", "Bob", None, Some("/p"), None, None, "bob").unwrap();
+            storage.create_session("sess_alice", "Alice", None, Some("/p"), None, None, "alice").unwrap();
+            storage.save_context_item("item_a", "sess_bob", "a", "v", None, None, "Alice@Laptop").unwrap();
+            storage.save_context_item("item_b", "sess_bob", "b", "v", None, None, "bob").unwrap();
+            storage.save_context_item("item_c", "sess_alice", "c", "v", None, None, "alice").unwrap();
+            storage.create_issue("i_alice", None, "/p", "Hers", None, None, None, None, None, None, "alice").unwrap();
+            storage.create_issue("i_bob", None, "/p", "His", None, None, None, None, None, None, "bob").unwrap();
+            storage.claim_issue("i_bob", &IssueWorkflow::default(), "alice@laptop").unwrap();
+            storage.watch_issue("i_bob", "alice", "alice").unwrap();
+            storage.delete_context_item("sess_bob", "b", "alice").unwrap();
+            storage
+        };
+        let count = |storage: &SqliteStorage, sql: &str| -> i64 { storage.conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+
+        let mut storage = setup();
+        assert!(storage.purge_actor("alice", false, "anon-1", "ALICE").is_err());
+        let stats = storage.purge_actor("alice@laptop", false, "anon-1", "admin").unwrap();
+        assert_eq!((stats.context_items, stats.issues, stats.sessions), (2, 1, 1));
+        assert_eq!((stats.issue_refs, stats.watches, stats.actor_rows), (1, 1, 3));
+        assert!(storage.get_session("sess_bob").unwrap().is_some());
+        assert!(storage.get_issue("i_alice", None).unwrap().is_none());
+        let issue = storage.get_issue("i_bob", None).unwrap().unwrap();
+        assert_eq!(issue.assigned_to_agent, None);
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM events WHERE actor IN ('alice', 'alice@laptop')"), 0);
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM trash WHERE deleted_by = 'anon-1'"), 1);
+        let mut deleted: Vec<String> =
+            storage.get_pending_deletions("/p").unwrap().into_iter().map(|d| d.entity_id).collect();
+        deleted.sort();
+        assert_eq!(deleted, ["i_alice", "item_a", "item_b", "item_c", "sess_alice"]);
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM sync_deletions WHERE deleted_by = 'alice'"), 0);
+        assert!(storage.get_actor("alice").unwrap().is_none());
+
+        let mut storage = setup();
+        let by_alice = count(&storage, "SELECT COUNT(*) FROM events WHERE actor = 'alice'");
+        let stats = storage.purge_actor("alice", true, "anon-1", "admin").unwrap();
+        assert_eq!(stats.records(), 0);
+        let issue = storage.get_issue("i_alice", None).unwrap().unwrap();
+        assert_eq!(issue.created_by_agent.as_deref(), Some("anon-1"));
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM events WHERE actor = 'anon-1'"), by_alice);
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM issue_watchers WHERE actor = 'anon-1'"), 1);
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM events WHERE actor = 'alice'"), 0);
+    }
 
-/// Compute cosine similarity between two vectors.
-///
-/// Returns a value between -1.0 and 1.0, where:
-/// - 1.0 means identical direction
-/// - 0.0 means orthogonal (no similarity)
-/// - -1.0 means opposite direction
-///
-/// For normalized embeddings (which most models produce), this is equivalent
-/// to the dot product.
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() || a.is_empty() {
-        return 0.0;
+    #[test]
+    fn test_watch_sees_other_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sc.db");
+        let mut writer = SqliteStorage::open(&path).unwrap();
+        let watcher = SqliteStorage::open(&path).unwrap();
+        writer.create_session("sess_1", "S", None, None, None, None, "actor").unwrap();
+
+        let version = watcher.data_version().unwrap();
+        let last = watcher.latest_event_id().unwrap();
+        writer.create_issue("i1", None, "/p", "Task", None, None, None, None, None, None, "alice").unwrap();
+        writer.save_context_item("item_1", "sess_1", "k", "v", None, None, "alice").unwrap();
+        assert_ne!(watcher.data_version().unwrap(), version);
+
+        let types = |events: Vec<crate::storage::events::StoredEvent>| {
+            events.into_iter().map(|e| e.entity_type).collect::<Vec<_>>()
+        };
+        assert_eq!(types(watcher.events_after(last, &[]).unwrap()), ["issue", "context_item"]);
+        assert_eq!(types(watcher.events_after(last, &["context_item"]).unwrap()), ["context_item"]);
+        assert!(watcher.events_after(watcher.latest_event_id().unwrap(), &[]).unwrap().is_empty());
     }
 
-    let mut dot_product = 0.0;
-    let mut norm_a = 0.0;
-    let mut norm_b = 0.0;
+    #[test]
+    fn test_roles_gate_mutations() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.register_actor("exec", None, Some(ActorType::Agent), "admin").unwrap();
+        storage.add_actor_alias("exec", "exec@ci", "admin").unwrap();
+        storage.create_session("sess_1", "S", None, None, None, None, "admin").unwrap();
+        storage.create_issue("i1", None, "/p", "Task", None, None, None, None, None, None, "admin").unwrap();
+        storage.set_permissions(Some(
+            serde_json::from_value(serde_json::json!({
+                "roles": { "executor": { "allow": ["claim_issue", "comment_issue"] } },
+                "actors": { "exec": "executor" }
+            }))
+            .unwrap(),
+        ));
 
-    for (x, y) in a.iter().zip(b.iter()) {
-        dot_product += x * y;
-        norm_a += x * x;
-        norm_b += y * y;
+        storage.claim_issue("i1", &IssueWorkflow::default(), "exec@ci").unwrap();
+        let denied = storage.delete_session("sess_1", "exec@ci").unwrap_err();
+        assert!(matches!(
+            &denied,
+            Error::PermissionDenied { actor, role, operation }
+                if actor == "exec" && role == "executor" && operation == "delete_session"
+        ));
+        assert!(storage.get_session("sess_1").unwrap().is_some());
+        storage.pause_idle_sessions(0, "exec").unwrap();
+        storage.delete_session("sess_1", "admin").unwrap();
     }
 
-    let magnitude = (norm_a * norm_b).sqrt();
-    if magnitude == 0.0 {
-        0.0
-    } else {
-        dot_product / magnitude
+    #[test]
+    fn test_plan_revisions() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        let project = storage.get_or_create_project("/p", "actor").unwrap();
+        let plan = Plan::new(project.id, "/p".to_string(), "Auth".to_string()).with_content("v1");
+        storage.create_plan(&plan, "actor").unwrap();
+
+        // Content change records a revision; a status-only change does not
+        storage.update_plan(&plan.id, None, Some("v2"), None, None, "actor").unwrap();
+        storage.update_plan(&plan.id, None, None, Some("completed"), None, "actor").unwrap();
+        let revisions = storage.list_plan_revisions(&plan.id).unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[1].content.as_deref(), Some("v2"));
+
+        // Revert restores v1 as a new revision
+        let current = storage.revert_plan(&plan.id, 1, "actor").unwrap();
+        assert_eq!(current, 3);
+        let reverted = storage.get_plan(&plan.id).unwrap().unwrap();
+        assert_eq!(reverted.content.as_deref(), Some("v1"));
+        assert_eq!(reverted.status, PlanStatus::Completed);
+
+        assert!(storage.revert_plan(&plan.id, 9, "actor").is_err());
     }
-}
 
-/// Generate a short ID (4 hex chars based on timestamp).
-fn generate_short_id() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis();
-    format!("{:04x}", (now & 0xFFFF) as u16)
-}
+    #[test]
+    fn test_project_health() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Work", None, Some("/p"), None, None, "actor")
+            .unwrap();
+        storage
+            .create_issue("i1", Some("P-1"), "/p", "Old bug", None, None, Some(IssueType::Bug), Some(3), None, None, "actor")
+            .unwrap();
+        storage
+            .create_issue("i2", Some("P-2"), "/p", "New task", None, None, None, Some(1), None, None, "actor")
+            .unwrap();
+        storage
+            .conn
+            .execute("UPDATE issues SET updated_at = 1000 WHERE id = 'i1'", [])
+            .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let health = storage.get_project_health("/p", 5000).unwrap();
+        assert_eq!(health.open_by_priority, [0, 1, 0, 1, 0]);
+        assert_eq!(health.stale_count, 1);
+        assert_eq!(health.stale_issues[0].id, "i1");
+        assert_eq!(health.active_sessions, 1);
+        assert!(health.pending_sync > 0);
+        assert!(health.last_checkpoint_at.is_none());
+    }
+
+    #[test]
+    fn test_merge_project_paths() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_project(&Project::new("/repo/".to_string(), "repo".to_string()), "actor").unwrap();
+        storage.create_project(&Project::new("/repo".to_string(), "repo".to_string()), "actor").unwrap();
+        storage
+            .create_session("sess_1", "Work", None, Some("/repo/"), None, None, "actor")
+            .unwrap();
+        storage
+            .create_issue("i1", Some("R-1"), "/repo/", "Bug", None, None, None, None, None, None, "actor")
+            .unwrap();
+        storage.save_memory("m1", "/repo/", "cmd", "old", MemoryCategory::Command, None, "actor").unwrap();
+        storage.save_memory("m2", "/repo", "cmd", "new", MemoryCategory::Command, None, "actor").unwrap();
+        storage.save_memory("m3", "/repo/", "only-old", "v", MemoryCategory::Command, None, "actor").unwrap();
+        storage
+            .conn
+            .execute("UPDATE project_memory SET updated_at = updated_at + 1 WHERE id = 'm2'", [])
+            .unwrap();
 
-    #[test]
-    fn test_open_memory() {
-        let storage = SqliteStorage::open_memory();
-        assert!(storage.is_ok());
+        let stats = storage.merge_project_paths("/repo/", "/repo", "actor").unwrap();
+        assert_eq!((stats.sessions, stats.issues, stats.memory), (1, 1, 1));
+        assert_eq!(stats.memory_conflicts, 1);
+        assert!(stats.project_updated);
+
+        assert_eq!(storage.list_projects(10).unwrap().len(), 1);
+        assert_eq!(storage.get_memory("/repo", "cmd").unwrap().unwrap().value, "new");
+        assert!(storage.get_memory("/repo", "only-old").unwrap().is_some());
+        assert_eq!(storage.get_issues_by_project("/repo").unwrap().len(), 1);
+
+        // The old spelling now resolves through the alias
+        assert_eq!(storage.get_project_by_path("/repo/").unwrap().unwrap().project_path, "/repo");
+        assert_eq!(
+            storage.list_project_aliases().unwrap(),
+            vec![("/repo/".to_string(), "/repo".to_string())]
+        );
+        assert!(storage.merge_project_paths("/repo/", "/repo", "actor").is_err());
     }
 
     #[test]
-    fn test_session_crud() {
+    fn test_move_project_path() {
         let mut storage = SqliteStorage::open_memory().unwrap();
-
-        // Create
+        storage.create_project(&Project::new("/old".to_string(), "app".to_string()), "actor").unwrap();
+        storage.create_project(&Project::new("/taken".to_string(), "other".to_string()), "actor").unwrap();
         storage
-            .create_session(
-                "sess_1",
-                "Test Session",
-                Some("A test session"),
-                Some("/test/project"),
-                Some("main"),
-                "test-actor",
-            )
+            .create_session("sess_1", "Work", None, Some("/old"), None, None, "actor")
+            .unwrap();
+        storage
+            .create_issue("i1", Some("A-1"), "/old", "Bug", None, None, None, None, None, None, "actor")
             .unwrap();
+        storage.set_export_hash("issue", "i1", "abc").unwrap();
 
-        // Read
-        let session = storage.get_session("sess_1").unwrap();
-        assert!(session.is_some());
-        let session = session.unwrap();
-        assert_eq!(session.name, "Test Session");
-        assert_eq!(session.status, "active");
+        assert!(storage.move_project_path("/old", "/taken", "actor").is_err());
 
-        // List
-        let sessions = storage
-            .list_sessions(Some("/test/project"), None, None)
-            .unwrap();
-        assert_eq!(sessions.len(), 1);
+        let stats = storage.move_project_path("/old", "/new", "actor").unwrap();
+        assert_eq!((stats.sessions, stats.issues, stats.export_hashes), (1, 1, 1));
+        assert!(stats.project_updated);
+        assert_eq!(storage.get_project_by_path("/new").unwrap().unwrap().name, "app");
+        assert!(storage.get_project_by_path("/old").unwrap().is_none(), "no alias left behind");
+        assert!(storage.get_export_hash("issue", "i1").unwrap().is_none());
+        assert!(storage.move_project_path("/old", "/new", "actor").is_err());
+    }
 
-        // Update status
+    #[test]
+    fn test_trash_restore() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
         storage
-            .update_session_status("sess_1", "completed", "test-actor")
+            .create_session("sess_1", "Work", None, Some("/repo"), None, None, "actor")
             .unwrap();
-        let session = storage.get_session("sess_1").unwrap().unwrap();
-        assert_eq!(session.status, "completed");
-        assert!(session.ended_at.is_some());
+        storage
+            .save_context_item("item_1", "sess_1", "auth", "Use JWT", None, None, "actor")
+            .unwrap();
+        storage
+            .create_issue("i1", Some("A-1"), "/repo", "Bug", None, None, None, None, None, None, "actor")
+            .unwrap();
+        storage.add_issue_labels("i1", &["backend".to_string()], "actor").unwrap();
+
+        storage.delete_context_item("sess_1", "auth", "actor").unwrap();
+        storage.delete_issue("A-1", "actor").unwrap();
+        assert!(storage.get_context_items("sess_1", None, None, None).unwrap().is_empty());
+        assert!(storage.get_issue("i1", None).unwrap().is_none());
+
+        let entries = storage.list_trash(None, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].label, "A-1 Bug");
+        assert_eq!(entries[0].rows, 2, "issue and its label");
+        assert_eq!(storage.list_trash(Some("issue"), 10).unwrap().len(), 1);
+        assert!(storage.list_trash(Some("plan"), 10).is_err());
+
+        let restored = storage.restore_trash("A-1", "actor").unwrap();
+        assert_eq!((restored.restored_rows, restored.skipped_rows), (2, 0));
+        assert_eq!(storage.get_issue("i1", None).unwrap().unwrap().title, "Bug");
+        assert!(storage.restore_trash("i1", "actor").is_err(), "entry is consumed");
+
+        // A new item took the key, so the trashed one can't come back
+        storage
+            .save_context_item("item_2", "sess_1", "auth", "Use sessions", None, None, "actor")
+            .unwrap();
+        assert!(storage.restore_trash("item_1", "actor").is_err());
+        storage.delete_context_item("sess_1", "auth", "actor").unwrap();
+        storage.restore_trash("item_1", "actor").unwrap();
+        let items = storage.get_context_items("sess_1", None, None, None).unwrap();
+        assert_eq!(items[0].value, "Use JWT");
+
+        // Deleting the session trashes its items with it
+        storage.delete_session("sess_1", "actor").unwrap();
+        let restored = storage.restore_trash("sess_1", "actor").unwrap();
+        assert_eq!(restored.restored_rows, 4, "session, project link, item and activity");
+        assert_eq!(storage.get_context_items("sess_1", None, None, None).unwrap().len(), 1);
+
+        assert_eq!(storage.empty_trash(Some(0)).unwrap(), 0);
+        assert_eq!(storage.empty_trash(None).unwrap(), 1);
     }
 
     #[test]
-    fn test_context_item_crud() {
+    fn test_trash_restore_by_item_key() {
         let mut storage = SqliteStorage::open_memory().unwrap();
-
-        // Create session first
         storage
-            .create_session("sess_1", "Test", None, None, None, "actor")
+            .create_session("sess_1", "Work", None, Some("/repo"), None, None, "actor")
             .unwrap();
-
-        // Save item
         storage
-            .save_context_item(
-                "item_1",
-                "sess_1",
-                "test-key",
-                "test value",
-                Some("note"),
-                Some("high"),
-                "actor",
-            )
+            .save_context_item("item_1", "sess_1", "auth", "Use JWT", None, None, "actor")
+            .unwrap();
+        storage.delete_context_item("sess_1", "auth", "actor").unwrap();
+        storage
+            .save_context_item("item_2", "sess_1", "auth", "Use sessions", None, None, "actor")
             .unwrap();
+        storage.delete_context_item("sess_1", "auth", "actor").unwrap();
 
-        // Get items
+        // Both entries carry the key; the newest comes back
+        let restored = storage.restore_trash("auth", "actor").unwrap();
+        assert_eq!(restored.entry.entity_id, "item_2");
         let items = storage.get_context_items("sess_1", None, None, None).unwrap();
-        assert_eq!(items.len(), 1);
-        assert_eq!(items[0].key, "test-key");
-        assert_eq!(items[0].priority, "high");
+        assert_eq!(items[0].value, "Use sessions");
+        assert_eq!(storage.list_trash(None, 10).unwrap()[0].entity_id, "item_1");
+    }
 
-        // Update (upsert)
+    #[test]
+    fn test_trash_restore_keeps_session_usage() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
         storage
-            .save_context_item(
-                "item_1",
-                "sess_1",
-                "test-key",
-                "updated value",
-                Some("decision"),
-                None,
-                "actor",
-            )
+            .create_session("sess_1", "Work", None, Some("/repo"), None, None, "actor")
             .unwrap();
+        storage.record_session_usage("sess_1", 1200, 300, Some("m"), None, "actor").unwrap();
+        storage.record_session_usage("sess_1", 800, 200, None, None, "actor").unwrap();
 
-        let items = storage.get_context_items("sess_1", None, None, None).unwrap();
-        assert_eq!(items.len(), 1);
-        assert_eq!(items[0].value, "updated value");
+        storage.delete_session("sess_1", "actor").unwrap();
+        storage.restore_trash("sess_1", "actor").unwrap();
 
-        // Delete
+        let usage = storage.get_session_token_usage("sess_1").unwrap();
+        assert_eq!((usage.tokens_in, usage.tokens_out, usage.records), (2000, 500, 2));
+    }
+
+    #[test]
+    fn test_trash_restore_keeps_session_activity() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
         storage
-            .delete_context_item("sess_1", "test-key", "actor")
+            .create_session("sess_1", "Work", None, Some("/repo"), None, None, "actor")
             .unwrap();
-        let items = storage.get_context_items("sess_1", None, None, None).unwrap();
-        assert_eq!(items.len(), 0);
+        storage
+            .save_context_item("item_1", "sess_1", "auth", "Use JWT", None, None, "actor")
+            .unwrap();
+        storage
+            .conn
+            .execute("UPDATE session_activity SET last_activity_at = 1000 WHERE session_id = 'sess_1'", [])
+            .unwrap();
+
+        storage.delete_session("sess_1", "actor").unwrap();
+        storage.restore_trash("sess_1", "actor").unwrap();
+
+        // Not the time the restore's inserts touched it
+        assert_eq!(storage.get_session_last_activity("sess_1").unwrap(), Some(1000));
     }
 
     #[test]
-    fn test_issue_crud() {
+    fn test_trash_restore_keeps_item_access() {
         let mut storage = SqliteStorage::open_memory().unwrap();
-
-        // Create
         storage
-            .create_issue(
-                "issue_1",
-                Some("TST-1"),
-                "/test/project",
-                "Test Issue",
-                Some("Description"),
-                None,         // details
-                Some("task"), // issue_type
-                Some(3),      // priority
-                None,         // plan_id
-                "actor",
-            )
+            .create_session("sess_1", "Work", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        storage
+            .save_context_item("item_1", "sess_1", "auth", "Use JWT", None, None, "actor")
             .unwrap();
+        let ids = vec!["item_1".to_string()];
+        storage.record_item_access(&ids).unwrap();
+        storage.record_item_access(&ids).unwrap();
 
-        // Get by full ID
-        let issue = storage.get_issue("issue_1", None).unwrap();
-        assert!(issue.is_some());
-        let issue = issue.unwrap();
-        assert_eq!(issue.title, "Test Issue");
-        assert_eq!(issue.priority, 3);
+        storage.delete_context_item("sess_1", "auth", "actor").unwrap();
+        storage.restore_trash("item_1", "actor").unwrap();
+        assert_eq!(storage.get_item_access(&ids).unwrap()["item_1"].count, 2);
 
-        // Get by short ID
-        let issue = storage
-            .get_issue("TST-1", Some("/test/project"))
-            .unwrap();
-        assert!(issue.is_some());
+        storage.delete_session("sess_1", "actor").unwrap();
+        storage.restore_trash("sess_1", "actor").unwrap();
+        assert_eq!(storage.get_item_access(&ids).unwrap()["item_1"].count, 2);
+    }
 
-        // List
-        let issues = storage
-            .list_issues("/test/project", None, None, None)
+    #[test]
+    fn test_trash_restore_keeps_claim_lease() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_issue("i1", Some("A-1"), "/repo", "Bug", None, None, None, None, None, None, "actor")
             .unwrap();
-        assert_eq!(issues.len(), 1);
+        storage.claim_issue("A-1", &IssueWorkflow::default(), "agent-1").unwrap();
+        storage.grant_claim_lease("i1", "agent-1", -1000).unwrap();
+
+        storage.delete_issue("A-1", "actor").unwrap();
+        assert!(storage.get_claim_lease("i1").unwrap().is_none());
+        let restored = storage.restore_trash("A-1", "actor").unwrap();
+        assert_eq!((restored.restored_rows, restored.skipped_rows), (2, 0));
+
+        // The restored claim still runs out and gets released
+        assert_eq!(storage.get_claim_lease("i1").unwrap().unwrap().holder, "agent-1");
+        let released = storage.release_expired_claims("/repo", false, &IssueWorkflow::default(), "actor").unwrap();
+        assert_eq!(released.len(), 1);
+        let issue = storage.get_issue("i1", None).unwrap().unwrap();
+        assert_eq!(issue.status, IssueStatus::Open);
+        assert!(issue.assigned_to_agent.is_none());
+    }
 
-        // Claim
-        storage.claim_issue("issue_1", "agent-1").unwrap();
-        let issue = storage.get_issue("issue_1", None).unwrap().unwrap();
-        assert_eq!(issue.assigned_to_agent, Some("agent-1".to_string()));
-        assert_eq!(issue.status, "in_progress");
+    #[test]
+    fn test_inbox_mentions_and_watchers() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_issue("i1", Some("A-1"), "/repo", "Fix login", Some("@alice can you check?"), None, None, None, None, None, "agent")
+            .unwrap();
+        storage
+            .create_issue("i2", Some("A-2"), "/repo", "Refactor", None, None, None, None, None, None, "agent")
+            .unwrap();
+        storage
+            .create_issue("i3", Some("B-1"), "/other", "Unrelated", None, None, None, None, None, None, "agent")
+            .unwrap();
 
-        // Release
-        storage.release_issue("issue_1", "agent-1").unwrap();
-        let issue = storage.get_issue("issue_1", None).unwrap().unwrap();
-        assert!(issue.assigned_to_agent.is_none());
-        assert_eq!(issue.status, "open");
+        assert!(storage.watch_issue("A-2", "alice", "alice").unwrap());
+        assert!(!storage.watch_issue("A-2", "alice", "alice").unwrap());
+        assert_eq!(storage.get_issue_watchers("i2").unwrap(), vec!["alice"]);
 
-        // Close
+        storage.comment_issue("A-2", "Started on this, @bob FYI", "agent").unwrap();
+        storage.comment_issue("B-1", "nothing for alice here", "agent").unwrap();
+        // Re-saving a description with the same mention doesn't notify again
         storage
-            .update_issue_status("issue_1", "closed", "actor")
+            .update_issue("i1", None, Some("@alice can you check? Updated."), None, None, None, None, None, "agent")
             .unwrap();
-        let issue = storage.get_issue("issue_1", None).unwrap().unwrap();
-        assert_eq!(issue.status, "closed");
-        assert!(issue.closed_at.is_some());
+
+        let inbox = storage.get_inbox("alice", 0, Some("/repo"), 50).unwrap();
+        let kinds: Vec<(&str, &str)> = inbox.iter().map(|i| (i.event_type.as_str(), i.reason.as_str())).collect();
+        assert!(kinds.contains(&("issue_mentioned", "mentioned")));
+        assert!(kinds.contains(&("issue_commented", "watching")));
+        assert_eq!(storage.get_inbox("bob", 0, None, 50).unwrap()[0].event_type, "issue_mentioned");
+        assert_eq!(kinds.iter().filter(|(t, _)| *t == "issue_mentioned").count(), 1);
+        assert!(inbox.iter().all(|i| i.issue_id != "i3"));
+        assert_eq!(storage.get_inbox("bob", 0, None, 50).unwrap().len(), 1);
+        assert!(storage.get_inbox("agent", 0, None, 50).unwrap().is_empty(), "own activity is not news");
+        assert_eq!(storage.get_issue_comments("i2").unwrap()[0].0, "agent");
+
+        storage.mark_inbox_read("alice", i64::MAX - 1).unwrap();
+        let read_at = storage.get_inbox_read_at("alice").unwrap().unwrap();
+        assert!(storage.get_inbox("alice", read_at, None, 50).unwrap().is_empty());
+
+        assert!(storage.unwatch_issue("A-2", "alice", "alice").unwrap());
+        assert!(storage.get_issue_watchers("i2").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_usage_stats() {
+        let storage = SqliteStorage::open_memory().unwrap();
+        let timing = |total_ms, sql_ms, slow_queries| crate::storage::CommandTiming {
+            total_ms,
+            sql_ms,
+            slow_queries,
+            ..Default::default()
+        };
+        storage.record_command_usage("issue list", &timing(12, 2, 0), true).unwrap();
+        storage.record_command_usage("issue list", &timing(30, 20, 0), false).unwrap();
+        storage.record_command_usage("sync export", &timing(900, 850, 2), true).unwrap();
+
+        let stats = storage.get_usage_stats(0, 10).unwrap();
+        assert_eq!(stats.total_invocations, 3);
+        assert_eq!(stats.commands[0].command, "issue list");
+        assert_eq!(stats.commands[0].count, 2);
+        assert_eq!(stats.commands[0].failures, 1);
+        assert_eq!(stats.commands[0].avg_ms, 21);
+        assert_eq!(stats.commands[0].avg_sql_ms, 11);
+        assert_eq!(stats.slow_queries, 2);
+        assert_eq!(stats.slowest[0].command, "sync export");
+        assert_eq!(stats.slowest[0].sql_ms, 850);
+        assert_eq!(stats.db_growth.len(), 1);
+        assert!(stats.db_growth[0].size_bytes > 0);
+
+        assert_eq!(storage.get_usage_stats(i64::MAX, 10).unwrap().total_invocations, 0);
+        assert_eq!(storage.clear_usage_stats().unwrap(), 4);
     }
 
     // --- Embeddings storage tests ---
@@ -5687,7 +11087,7 @@ mod tests {
     fn test_get_items_without_embeddings_includes_pending() {
         let mut storage = SqliteStorage::open_memory().unwrap();
         storage
-            .create_session("sess_1", "Test", None, None, None, "actor")
+            .create_session("sess_1", "Test", None, None, None, None, "actor")
             .unwrap();
 
         // Create items with different embedding statuses
@@ -5698,7 +11098,7 @@ mod tests {
             ("item_4", "complete-status", "complete"),
         ] {
             storage
-                .save_context_item(id, "sess_1", key, "test value", Some("note"), Some("normal"), "actor")
+                .save_context_item(id, "sess_1", key, "test value", Some(ItemCategory::Note), Some(ItemPriority::Normal), "actor")
                 .unwrap();
             storage.conn.execute(
                 "UPDATE context_items SET embedding_status = ?1 WHERE id = ?2",
@@ -5708,7 +11108,7 @@ mod tests {
 
         // Also create one with NULL status (never processed)
         storage
-            .save_context_item("item_5", "sess_1", "null-status", "test", Some("note"), Some("normal"), "actor")
+            .save_context_item("item_5", "sess_1", "null-status", "test", Some(ItemCategory::Note), Some(ItemPriority::Normal), "actor")
             .unwrap();
         storage.conn.execute(
             "UPDATE context_items SET embedding_status = NULL WHERE id = 'item_5'",
@@ -5732,11 +11132,11 @@ mod tests {
     #[test]
     fn test_get_items_without_embeddings_session_filter() {
         let mut storage = SqliteStorage::open_memory().unwrap();
-        storage.create_session("sess_1", "Session 1", None, None, None, "actor").unwrap();
-        storage.create_session("sess_2", "Session 2", None, None, None, "actor").unwrap();
+        storage.create_session("sess_1", "Session 1", None, None, None, None, "actor").unwrap();
+        storage.create_session("sess_2", "Session 2", None, None, None, None, "actor").unwrap();
 
-        storage.save_context_item("item_1", "sess_1", "s1-item", "val", Some("note"), Some("normal"), "actor").unwrap();
-        storage.save_context_item("item_2", "sess_2", "s2-item", "val", Some("note"), Some("normal"), "actor").unwrap();
+        storage.save_context_item("item_1", "sess_1", "s1-item", "val", Some(ItemCategory::Note), Some(ItemPriority::Normal), "actor").unwrap();
+        storage.save_context_item("item_2", "sess_2", "s2-item", "val", Some(ItemCategory::Note), Some(ItemPriority::Normal), "actor").unwrap();
 
         // Reset both to pending
         storage.conn.execute("UPDATE context_items SET embedding_status = 'pending'", []).unwrap();
@@ -5754,12 +11154,12 @@ mod tests {
     #[test]
     fn test_resync_embedding_status() {
         let mut storage = SqliteStorage::open_memory().unwrap();
-        storage.create_session("sess_1", "Test", None, None, None, "actor").unwrap();
+        storage.create_session("sess_1", "Test", None, None, None, None, "actor").unwrap();
 
         // Create items
-        storage.save_context_item("item_1", "sess_1", "phantom", "val", Some("note"), Some("normal"), "actor").unwrap();
-        storage.save_context_item("item_2", "sess_1", "real", "val", Some("note"), Some("normal"), "actor").unwrap();
-        storage.save_context_item("item_3", "sess_1", "pending-already", "val", Some("note"), Some("normal"), "actor").unwrap();
+        storage.save_context_item("item_1", "sess_1", "phantom", "val", Some(ItemCategory::Note), Some(ItemPriority::Normal), "actor").unwrap();
+        storage.save_context_item("item_2", "sess_1", "real", "val", Some(ItemCategory::Note), Some(ItemPriority::Normal), "actor").unwrap();
+        storage.save_context_item("item_3", "sess_1", "pending-already", "val", Some(ItemCategory::Note), Some(ItemPriority::Normal), "actor").unwrap();
 
         // Mark all as complete
         storage.conn.execute("UPDATE context_items SET embedding_status = 'complete'", []).unwrap();
@@ -5793,4 +11193,258 @@ mod tests {
         ).unwrap();
         assert_eq!(status_3, "pending", "already-pending should be untouched");
     }
+
+    #[test]
+    fn test_embedding_cache() {
+        let storage = SqliteStorage::open_memory().unwrap();
+        assert_eq!(storage.get_cached_embedding("ollama", "nomic", "text").unwrap(), None);
+
+        storage.cache_embedding("ollama", "nomic", "text", &[0.5, -1.0]).unwrap();
+        assert_eq!(
+            storage.get_cached_embedding("ollama", "nomic", "text").unwrap(),
+            Some(vec![0.5, -1.0])
+        );
+        assert_eq!(storage.get_cached_embedding("ollama", "other", "text").unwrap(), None, "keyed by model");
+        assert_eq!(storage.get_cached_embedding("ollama", "nomic", "text!").unwrap(), None, "keyed by content");
+        storage.get_cached_embedding("ollama", "nomic", "text").unwrap();
+
+        let stats = storage.embedding_cache_stats().unwrap();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 2, 1));
+        assert!((stats.hit_rate - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_entity_embeddings() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_issue("i1", Some("P-1"), "/p", "Migrate schema", Some("Use a rebuild"), None, None, None, None, None, "actor")
+            .unwrap();
+        storage.save_memory("m1", "/other", "build", "cargo build", MemoryCategory::Command, None, "actor").unwrap();
+
+        let issues = storage.get_entities_needing_embeddings("issue", 10).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].title, "Migrate schema");
+        assert_eq!(issues[0].text, "Use a rebuild");
+        assert!(storage.get_entities_needing_embeddings("session", 10).is_err());
+
+        storage.conn.execute("UPDATE issues SET updated_at = 0", []).unwrap();
+        storage
+            .store_entity_embedding_chunk("issue", "i1", 0, "one", &[1.0, 0.0], "test", "m")
+            .unwrap();
+        storage
+            .store_entity_embedding_chunk("issue", "i1", 1, "two", &[0.0, 1.0], "test", "m")
+            .unwrap();
+        storage
+            .store_entity_embedding_chunk("memory", "m1", 0, "cargo", &[1.0, 0.0], "test", "m")
+            .unwrap();
+        assert_eq!(storage.get_entity_embedding("issue", "i1").unwrap(), Some(vec![0.5, 0.5]));
+        assert_eq!(storage.get_entity_embedding("plan", "i1").unwrap(), None);
+        storage.trim_entity_embeddings("issue", "i1", 1).unwrap();
+        assert!(storage.get_entities_needing_embeddings("issue", 10).unwrap().is_empty());
+
+        let hits = storage
+            .semantic_search_entities(&[1.0, 0.0], &["issue", "memory"], Some("/p"), 10, 0.5)
+            .unwrap();
+        assert_eq!(hits.len(), 1, "memory is in another project, trimmed chunk is gone");
+        assert_eq!((hits[0].entity_id.as_str(), hits[0].short_id.as_deref()), ("i1", Some("P-1")));
+        let hits = storage.semantic_search_entities(&[1.0, 0.0], &["memory"], None, 10, 0.5).unwrap();
+        assert_eq!(hits[0].title, "build");
+
+        // Editing the issue makes it stale; deleting it drops its chunks
+        storage.conn.execute("UPDATE issues SET updated_at = ?1", [i64::MAX]).unwrap();
+        assert_eq!(storage.get_entities_needing_embeddings("issue", 10).unwrap().len(), 1);
+        storage.delete_issue("i1", "actor").unwrap();
+        let left: i64 = storage
+            .conn
+            .query_row("SELECT COUNT(*) FROM embedding_chunks WHERE entity_type = 'issue'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+
+    #[test]
+    fn test_embedding_model_archive() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_issue("i1", None, "/p", "One", Some("first"), None, None, None, None, None, "actor")
+            .unwrap();
+        storage
+            .create_issue("i2", None, "/p", "Two", Some("second"), None, None, None, None, None, "actor")
+            .unwrap();
+        storage.conn.execute("UPDATE issues SET updated_at = 0", []).unwrap();
+        for id in ["i1", "i2"] {
+            storage
+                .store_entity_embedding_chunk("issue", id, 0, "text", &[1.0, 0.0], "old", "m")
+                .unwrap();
+        }
+        let models = storage.embedding_models(false).unwrap();
+        assert_eq!((models[0].model.as_str(), models[0].dimensions, models[0].entities), ("m", 2, 2));
+
+        // Switching to a 3-d model archives the old vectors
+        assert_eq!(storage.retire_embeddings_except("new", "m3", true).unwrap(), 2);
+        assert!(storage.embedding_models(false).unwrap().is_empty());
+        assert_eq!(storage.embedding_models(true).unwrap()[0].chunks, 2);
+        storage
+            .store_entity_embedding_chunk("issue", "i1", 0, "text", &[1.0, 0.0, 0.0], "new", "m3")
+            .unwrap();
+
+        // Switching back restores i2 only: i1 was edited after it was archived
+        storage.conn.execute("UPDATE issues SET updated_at = ?1 WHERE id = 'i1'", [i64::MAX]).unwrap();
+        assert_eq!(storage.retire_embeddings_except("old", "m", false).unwrap(), 1);
+        assert_eq!(storage.restore_archived_embeddings("old", "m").unwrap(), 1);
+        let live = storage.embedding_models(false).unwrap();
+        assert_eq!((live[0].provider.as_str(), live[0].entities), ("old", 1));
+        assert!(storage.embedding_models(true).unwrap().is_empty(), "stale and restored rows leave the archive");
+        assert_eq!(storage.get_entities_needing_embeddings("issue", 10).unwrap()[0].id, "i1");
+
+        // Re-queueing a model's output makes its records stale but keeps the chunks
+        assert_eq!(storage.requeue_embeddings_from("old", "m").unwrap(), 1);
+        assert_eq!(storage.get_entities_needing_embeddings("issue", 10).unwrap().len(), 2);
+        assert_eq!(storage.embedding_models(false).unwrap()[0].chunks, 1);
+    }
+
+    #[test]
+    fn test_auto_checkpoints() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Work", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        storage
+            .create_session("sess_2", "Empty", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        storage
+            .save_context_item("item_1", "sess_1", "a", "one", None, None, "actor")
+            .unwrap();
+        storage
+            .save_context_item("item_2", "sess_1", "b", "two", None, None, "actor")
+            .unwrap();
+
+        let due = storage.get_sessions_due_auto_checkpoint("auto-20260101-09").unwrap();
+        assert_eq!(due, vec!["sess_1"], "sessions without items are skipped");
+        let count = storage
+            .create_snapshot_checkpoint("ckpt_1", "sess_1", "auto-20260101-09", None, None, "actor")
+            .unwrap();
+        assert_eq!(count, 2);
+        let checkpoint = storage.get_checkpoint("ckpt_1").unwrap().unwrap();
+        assert_eq!(checkpoint.item_count, 2);
+
+        // Unchanged since the last automatic checkpoint
+        assert!(storage.get_sessions_due_auto_checkpoint("auto-20260101-10").unwrap().is_empty());
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        storage
+            .save_context_item("item_3", "sess_1", "c", "three", None, None, "actor")
+            .unwrap();
+        assert!(storage.get_sessions_due_auto_checkpoint("auto-20260101-09").unwrap().is_empty());
+        assert_eq!(storage.get_sessions_due_auto_checkpoint("auto-20260101-10").unwrap().len(), 1);
+        storage
+            .create_snapshot_checkpoint("ckpt_2", "sess_1", "auto-20260101-10", None, None, "actor")
+            .unwrap();
+        storage
+            .create_checkpoint("ckpt_manual", "sess_1", "before refactor", None, None, None, "actor")
+            .unwrap();
+
+        let prunable = storage.get_prunable_auto_checkpoints(None, 1).unwrap();
+        assert_eq!(prunable.len(), 1);
+        assert_eq!(prunable[0].id, "ckpt_1");
+        assert!(storage.get_prunable_auto_checkpoints(Some("sess_2"), 1).unwrap().is_empty());
+        assert!(storage.get_prunable_auto_checkpoints(None, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_session_auto_resolved() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "auto 2026-01-01", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        storage
+            .record_session_auto_resolved("sess_1", "created", "save", "actor")
+            .unwrap();
+
+        let events = crate::storage::events::get_events(storage.conn(), "session", "sess_1", None).unwrap();
+        let event = events
+            .iter()
+            .find(|e| e.event_type == EventType::SessionAutoResolved)
+            .unwrap();
+        assert_eq!(event.new_value.as_deref(), Some("created"));
+        assert_eq!(event.comment.as_deref(), Some("save"));
+    }
+
+    #[test]
+    fn test_pause_idle_sessions() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_old", "Old", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        storage
+            .create_session("sess_new", "New", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        let hour_ago = chrono::Utc::now().timestamp_millis() - 3_600_000;
+        storage
+            .conn()
+            .execute(
+                "UPDATE session_activity SET last_activity_at = 1000 WHERE session_id = 'sess_old'",
+                [],
+            )
+            .unwrap();
+
+        let paused = storage.pause_idle_sessions(hour_ago, "actor").unwrap();
+        assert_eq!(paused.len(), 1);
+        assert_eq!(paused[0].0, "sess_old");
+        assert_eq!(storage.get_session("sess_old").unwrap().unwrap().status, SessionStatus::Paused);
+        assert_eq!(storage.get_session("sess_new").unwrap().unwrap().status, SessionStatus::Active);
+
+        // Resuming counts as activity, so it isn't paused again right away
+        storage.update_session_status("sess_old", SessionStatus::Active, "actor").unwrap();
+        assert!(storage.get_session_last_activity("sess_old").unwrap().unwrap() > hour_ago);
+        assert!(storage.pause_idle_sessions(hour_ago, "actor").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_agent_presence() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage.create_session("sess_1", "One", None, Some("/repo"), Some("main"), None, "actor").unwrap();
+        storage.create_session("sess_2", "Two", None, Some("/repo"), None, None, "actor").unwrap();
+        storage.record_agent_presence("claude", "sess_1", "sc").unwrap();
+        storage.record_agent_presence("claude", "sess_2", "sc").unwrap();
+        storage.record_agent_presence("alice", "missing", "sc").unwrap();
+
+        // One row per agent, holding its latest session
+        let presence = storage.get_agent_presence("/repo", 0).unwrap();
+        assert_eq!(presence.len(), 1);
+        assert_eq!((presence[0].agent_id.as_str(), presence[0].session_id.as_str()), ("claude", "sess_2"));
+        assert!(storage.get_agent_presence("/repo", i64::MAX).unwrap().is_empty());
+        assert!(storage.get_agent_presence("/other", 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_session_token_usage() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Work", None, Some("/repo"), None, None, "actor")
+            .unwrap();
+        storage.record_session_usage("sess_1", 1000, 200, Some("m"), None, "actor").unwrap();
+        storage
+            .create_checkpoint("ckpt_1", "sess_1", "pre-compact-2026-01-01", None, None, None, "actor")
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        storage.record_session_usage("sess_1", 300, 50, None, Some("hook"), "actor").unwrap();
+        storage.record_session_usage("sess_1", 100, 10, None, None, "actor").unwrap();
+        assert!(matches!(
+            storage.record_session_usage("missing", 1, 1, None, None, "actor"),
+            Err(Error::SessionNotFound { .. })
+        ));
+
+        let usage = storage.get_session_token_usage("sess_1").unwrap();
+        assert_eq!((usage.tokens_in, usage.tokens_out, usage.records), (1400, 260, 3));
+        assert_eq!(usage.compactions, 1);
+        assert_eq!(usage.windows.len(), 2);
+        assert_eq!(usage.windows[0].compacted_by.as_deref(), Some("pre-compact-2026-01-01"));
+        assert_eq!((usage.windows[0].tokens_in, usage.windows[0].records), (1000, 1));
+        assert_eq!((usage.windows[1].tokens_in, usage.windows[1].records), (400, 2));
+
+        let listed = storage.list_session_token_usage(Some("/repo"), 10).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].tokens_out, 260);
+        assert!(storage.list_session_token_usage(Some("/other"), 10).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,282 @@
+//! Storage analytics for `sc db stats`.
+//!
+//! Row counts come from `COUNT(*)` and sizes from the `dbstat` virtual
+//! table, with each index's pages counted towards the table it indexes.
+//! Every run leaves a snapshot of these figures in `db_stats_snapshots`,
+//! so the next run can show how much each table grew in between. Write
+//! contention comes from the counts kept by the write queue.
+
+use crate::error::Result;
+use crate::json_schema::json_schema;
+use crate::storage::compression::{self, CompressionStats};
+use crate::storage::write_queue::{self, WriteContention};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Days of write contention reported.
+const CONTENTION_DAYS: u32 = 7;
+
+/// Tables that hold embedding vectors.
+const EMBEDDING_TABLES: &[&str] = &[
+    "embedding_chunks",
+    "embedding_chunks_fast",
+    "embedding_chunks_archive",
+    "embedding_cache",
+];
+
+/// Row count and size of one table.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableStats {
+    pub name: String,
+    pub rows: i64,
+    /// Bytes of the table's pages and its indexes' pages.
+    pub bytes: i64,
+    /// Change in rows since the previous snapshot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows_change: Option<i64>,
+    /// Change in bytes since the previous snapshot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_change: Option<i64>,
+}
+
+json_schema!(TableStats { name: String, rows: i64, bytes: i64, rows_change: Option<i64>, bytes_change: Option<i64> });
+
+/// One of the largest context items.
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeItem {
+    pub id: String,
+    pub key: String,
+    pub session_id: String,
+    pub session_name: String,
+    /// Uncompressed value length.
+    pub size: i64,
+    pub compressed: bool,
+}
+
+json_schema!(LargeItem { id: String, key: String, session_id: String, session_name: String, size: i64, compressed: bool });
+
+/// One of the sessions with the most context item data.
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeSession {
+    pub id: String,
+    pub name: String,
+    pub items: i64,
+    /// Uncompressed length of all item values.
+    pub size: i64,
+}
+
+json_schema!(LargeSession { id: String, name: String, items: i64, size: i64 });
+
+/// Everything `sc db stats` reports about the database.
+#[derive(Debug, Clone, Serialize)]
+pub struct DbStats {
+    /// Tables by size, largest first.
+    pub tables: Vec<TableStats>,
+    /// Size of the database file (`page_count * page_size`).
+    pub file_bytes: i64,
+    /// Bytes on the freelist, which `VACUUM` would give back.
+    pub free_bytes: i64,
+    /// Bytes taken by embedding tables.
+    pub embedding_bytes: i64,
+    pub compression: CompressionStats,
+    pub largest_items: Vec<LargeItem>,
+    pub largest_sessions: Vec<LargeSession>,
+    /// Writes that waited for other processes over the last week.
+    pub write_contention: WriteContention,
+    /// When the previous snapshot was taken, if there is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_at: Option<i64>,
+}
+
+json_schema!(DbStats {
+    tables: Vec<TableStats>,
+    file_bytes: i64,
+    free_bytes: i64,
+    embedding_bytes: i64,
+    compression: CompressionStats,
+    largest_items: Vec<LargeItem>,
+    largest_sessions: Vec<LargeSession>,
+    write_contention: WriteContention,
+    previous_at: Option<i64>,
+});
+
+impl DbStats {
+    /// Bytes used by all tables and indexes.
+    #[must_use]
+    pub fn data_bytes(&self) -> i64 {
+        self.tables.iter().map(|t| t.bytes).sum()
+    }
+
+    /// Change in [`Self::data_bytes`] since the previous snapshot.
+    #[must_use]
+    pub fn data_bytes_change(&self) -> Option<i64> {
+        self.previous_at?;
+        Some(self.tables.iter().filter_map(|t| t.bytes_change).sum())
+    }
+}
+
+/// Gather table, size and largest-item figures, listing `top` items and
+/// sessions.
+pub(crate) fn collect(conn: &Connection, top: usize) -> Result<DbStats> {
+    let sizes = table_sizes(conn)?;
+    let previous = previous_snapshot(conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+         ORDER BY name",
+    )?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut tables = Vec::with_capacity(names.len());
+    for name in names {
+        let rows: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM \"{name}\""), [], |row| row.get(0))?;
+        let bytes = sizes.get(&name).copied().unwrap_or(0);
+        let before = previous.as_ref().map(|(_, tables)| tables.get(&name).copied().unwrap_or((0, 0)));
+        tables.push(TableStats {
+            rows_change: before.map(|(r, _)| rows - r),
+            bytes_change: before.map(|(_, b)| bytes - b),
+            name,
+            rows,
+            bytes,
+        });
+    }
+    tables.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+
+    let (file_bytes, free_bytes) = conn.query_row(
+        "SELECT p.page_count * s.page_size, f.freelist_count * s.page_size
+         FROM pragma_page_count() p, pragma_page_size() s, pragma_freelist_count() f",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let embedding_bytes = tables
+        .iter()
+        .filter(|t| EMBEDDING_TABLES.contains(&t.name.as_str()))
+        .map(|t| t.bytes)
+        .sum();
+    let limit = i64::try_from(top).unwrap_or(i64::MAX);
+
+    let mut stmt = conn.prepare(
+        "SELECT ci.id, ci.key, ci.session_id, COALESCE(s.name, ''), ci.size, ci.value_compressed
+         FROM context_items ci LEFT JOIN sessions s ON s.id = ci.session_id
+         ORDER BY ci.size DESC, ci.id LIMIT ?1",
+    )?;
+    let largest_items = stmt
+        .query_map([limit], |row| {
+            Ok(LargeItem {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                session_id: row.get(2)?,
+                session_name: row.get(3)?,
+                size: row.get(4)?,
+                compressed: row.get(5)?,
+            })
+        })?
+        .collect::<std::result::Result<_, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.name, COUNT(*), COALESCE(SUM(ci.size), 0) AS total
+         FROM context_items ci JOIN sessions s ON s.id = ci.session_id
+         GROUP BY s.id ORDER BY total DESC, s.id LIMIT ?1",
+    )?;
+    let largest_sessions = stmt
+        .query_map([limit], |row| {
+            Ok(LargeSession { id: row.get(0)?, name: row.get(1)?, items: row.get(2)?, size: row.get(3)? })
+        })?
+        .collect::<std::result::Result<_, _>>()?;
+
+    Ok(DbStats {
+        tables,
+        file_bytes,
+        free_bytes,
+        embedding_bytes,
+        compression: compression::stats(conn)?,
+        largest_items,
+        largest_sessions,
+        write_contention: write_queue::contention(conn, CONTENTION_DAYS)?,
+        previous_at: previous.map(|(taken_at, _)| taken_at),
+    })
+}
+
+/// Replace the stored snapshot with the figures in `stats`.
+pub(crate) fn save_snapshot(conn: &Connection, stats: &DbStats) -> Result<()> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM db_stats_snapshots", [])?;
+    for table in &stats.tables {
+        tx.execute(
+            "INSERT INTO db_stats_snapshots (table_name, row_count, size_bytes, taken_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![table.name, table.rows, table.bytes, now],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Bytes per table, with index pages added to their table.
+fn table_sizes(conn: &Connection) -> Result<HashMap<String, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT m.tbl_name, SUM(d.pgsize)
+         FROM dbstat d JOIN sqlite_master m ON m.name = d.name
+         WHERE d.aggregate = 1
+         GROUP BY m.tbl_name",
+    )?;
+    let sizes = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    Ok(sizes)
+}
+
+/// Time of the last snapshot and its `(rows, bytes)` per table.
+type Snapshot = (i64, HashMap<String, (i64, i64)>);
+
+fn previous_snapshot(conn: &Connection) -> Result<Option<Snapshot>> {
+    let mut stmt = conn.prepare("SELECT table_name, row_count, size_bytes, taken_at FROM db_stats_snapshots")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let Some(taken_at) = rows.iter().map(|r| r.3).max() else {
+        return Ok(None);
+    };
+    Ok(Some((taken_at, rows.into_iter().map(|(name, count, bytes, _)| (name, (count, bytes))).collect())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::SqliteStorage;
+
+    #[test]
+    fn test_db_stats_growth() {
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        storage
+            .create_session("sess_1", "Big", None, None, None, None, "actor")
+            .unwrap();
+        storage
+            .save_context_item("item_1", "sess_1", "small", "v", None, None, "actor")
+            .unwrap();
+
+        let first = storage.db_stats(5).unwrap();
+        assert!(first.previous_at.is_none());
+        assert!(first.tables.iter().all(|t| t.rows_change.is_none()));
+        let items = first.tables.iter().find(|t| t.name == "context_items").unwrap();
+        assert_eq!(items.rows, 1);
+        assert!(items.bytes > 0);
+        storage.save_db_stats_snapshot(&first).unwrap();
+
+        storage
+            .save_context_item("item_2", "sess_1", "log", &"line\n".repeat(500), None, None, "actor")
+            .unwrap();
+        let second = storage.db_stats(1).unwrap();
+        assert!(second.previous_at.is_some());
+        let items = second.tables.iter().find(|t| t.name == "context_items").unwrap();
+        assert_eq!(items.rows_change, Some(1));
+        assert_eq!(second.largest_items.len(), 1);
+        assert_eq!(second.largest_items[0].key, "log");
+        assert_eq!(second.largest_sessions[0].items, 2);
+    }
+}
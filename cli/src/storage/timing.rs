@@ -0,0 +1,163 @@
+//! Statement timing for every command.
+//!
+//! Every connection opened by [`SqliteStorage`] gets a profile hook that
+//! adds up how long the command's SQL takes and keeps the statements that
+//! ran longer than [`slow_query_ms`]. After the command, `sc` warns about
+//! those on stderr, prints the totals for `--timing` and in `sc shell`
+//! status lines, and records them with the command's usage stats, so a
+//! database that has grown slow shows up before it gets painful.
+//!
+//! [`SqliteStorage`]: super::SqliteStorage
+//! [`slow_query_ms`]: crate::config::slow_query_ms
+
+use crate::json_schema::json_schema;
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Slow statements kept per command; later ones are only counted.
+const MAX_SLOW_LISTED: usize = 5;
+
+/// Longest SQL text kept for a slow statement.
+const MAX_SQL_CHARS: usize = 200;
+
+/// Statements recorded since the last [`take`].
+static RECORDED: Mutex<Recorded> = Mutex::new(Recorded::new());
+
+/// The slow-query threshold, read from the config once per process.
+static THRESHOLD: OnceLock<Duration> = OnceLock::new();
+
+/// Where a command's time went.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CommandTiming {
+    /// Wall-clock time of the whole command.
+    pub total_ms: u64,
+    /// Time spent executing SQL. The profile hook measures to the
+    /// millisecond on most platforms, so fast statements add nothing.
+    pub sql_ms: u64,
+    /// SQL statements run.
+    pub statements: u64,
+    /// Statements that took longer than `threshold_ms`.
+    pub slow_queries: u64,
+    /// The slow-query threshold; `0` when the warning is off.
+    pub threshold_ms: u64,
+    /// The first few slow statements, in execution order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub slowest: Vec<SlowQuery>,
+}
+
+/// A statement that ran past the threshold.
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowQuery {
+    /// SQL text with whitespace collapsed, cut to 200 characters.
+    pub sql: String,
+    pub ms: u64,
+}
+
+json_schema!(CommandTiming {
+    total_ms: u64,
+    sql_ms: u64,
+    statements: u64,
+    slow_queries: u64,
+    threshold_ms: u64,
+    slowest: Vec<SlowQuery> = optional,
+});
+json_schema!(SlowQuery { sql: String, ms: u64 });
+
+struct Recorded {
+    statements: u64,
+    sql: Duration,
+    slow_queries: u64,
+    slowest: Vec<SlowQuery>,
+}
+
+impl Recorded {
+    const fn new() -> Self {
+        Self { statements: 0, sql: Duration::ZERO, slow_queries: 0, slowest: Vec::new() }
+    }
+
+    fn add(&mut self, sql: &str, elapsed: Duration, threshold: Duration) {
+        self.statements += 1;
+        self.sql += elapsed;
+        if threshold.is_zero() || elapsed <= threshold {
+            return;
+        }
+        self.slow_queries += 1;
+        if self.slowest.len() < MAX_SLOW_LISTED {
+            let mut sql = super::explain::collapse_whitespace(sql);
+            if let Some((cut, _)) = sql.char_indices().nth(MAX_SQL_CHARS) {
+                sql.truncate(cut);
+                sql.push('…');
+            }
+            self.slowest.push(SlowQuery { sql, ms: millis(elapsed) });
+        }
+    }
+}
+
+/// Profile hook installed on every connection [`SqliteStorage::open`]s.
+///
+/// [`SqliteStorage::open`]: super::SqliteStorage::open
+pub(crate) fn record(sql: &str, elapsed: Duration) {
+    if crate::is_explain() {
+        super::explain::record(sql, elapsed);
+    }
+    crate::telemetry::record_query(sql, elapsed);
+    if let Ok(mut recorded) = RECORDED.lock() {
+        recorded.add(sql, elapsed, threshold());
+    }
+}
+
+/// Take the statements recorded so far, for a command that ran `elapsed`.
+///
+/// Recording starts over, so statements `sc` runs after a command (usage
+/// stats, `--explain` plans) aren't counted against the next one.
+#[must_use]
+pub fn take(elapsed: Duration) -> CommandTiming {
+    let recorded = RECORDED
+        .lock()
+        .map_or_else(|_| Recorded::new(), |mut recorded| std::mem::replace(&mut *recorded, Recorded::new()));
+    CommandTiming {
+        total_ms: millis(elapsed),
+        sql_ms: millis(recorded.sql),
+        statements: recorded.statements,
+        slow_queries: recorded.slow_queries,
+        threshold_ms: millis(threshold()),
+        slowest: recorded.slowest,
+    }
+}
+
+fn threshold() -> Duration {
+    *THRESHOLD.get_or_init(|| Duration::from_millis(crate::config::slow_query_ms()))
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_counts_and_lists_slow_statements() {
+        let threshold = Duration::from_millis(100);
+        let mut recorded = Recorded::new();
+        recorded.add("SELECT 1", Duration::from_millis(3), threshold);
+        recorded.add("SELECT 2", Duration::from_millis(100), threshold);
+        for _ in 0..=MAX_SLOW_LISTED {
+            recorded.add(&format!("SELECT *\n  FROM issues WHERE title = '{}'", "x".repeat(300)), Duration::from_millis(250), threshold);
+        }
+
+        assert_eq!(recorded.statements, 8);
+        assert_eq!(recorded.sql, Duration::from_millis(103 + 250 * 6));
+        assert_eq!(recorded.slow_queries, 6);
+        assert_eq!(recorded.slowest.len(), MAX_SLOW_LISTED);
+        assert!(recorded.slowest[0].sql.starts_with("SELECT * FROM issues"));
+        assert_eq!(recorded.slowest[0].sql.chars().count(), MAX_SQL_CHARS + 1);
+        assert_eq!(recorded.slowest[0].ms, 250);
+
+        let mut off = Recorded::new();
+        off.add("SELECT 1", Duration::from_secs(5), Duration::ZERO);
+        assert_eq!(off.slow_queries, 0);
+    }
+}
@@ -0,0 +1,347 @@
+//! Trash for deleted context items, issues and sessions.
+//!
+//! Deleting one of these copies its row, and every dependent row the delete
+//! cascades to, into the `trash` table as JSON before the live rows go.
+//! Because the live rows really are removed, trashed data never shows up in
+//! queries, search or exports, and the MCP server needs no changes to skip
+//! it. `sc trash restore` inserts the rows back; entries older than the
+//! retention period are purged.
+//!
+//! Rows are captured with `SELECT *`, so columns added by later migrations
+//! are kept without touching this module. Not restored: time entries keep
+//! their issue link cleared, and plans bound to a deleted session stay
+//! unbound.
+
+use crate::error::{Error, Result};
+use crate::json_schema::json_schema;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::{Map, Value as Json};
+
+/// A trashed entity.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashEntry {
+    pub id: String,
+    /// `context_item`, `issue` or `session`
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Key, short ID and title, or session name
+    pub label: String,
+    pub project_path: Option<String>,
+    /// Rows captured, including dependents
+    pub rows: usize,
+    pub deleted_at: i64,
+    pub deleted_by: String,
+}
+
+json_schema!(TrashEntry {
+    id: String,
+    entity_type: String,
+    entity_id: String,
+    label: String,
+    project_path: Option<String>,
+    rows: usize,
+    deleted_at: i64,
+    deleted_by: String,
+});
+
+/// Result of restoring a trash entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashRestore {
+    pub entry: TrashEntry,
+    pub restored_rows: usize,
+    /// Dependent rows that conflict with current data or point at rows
+    /// that no longer exist
+    pub skipped_rows: usize,
+}
+
+json_schema!(TrashRestore { entry: TrashEntry, restored_rows: usize, skipped_rows: usize });
+
+/// Tables captured for each entity type, as `(table, filter)`, parents
+/// first so a restore inserts them in a valid order. `?1` is the entity ID.
+fn tables(entity_type: &str) -> Result<&'static [(&'static str, &'static str)]> {
+    Ok(match entity_type {
+        "context_item" => &[
+            ("context_items", "id = ?1"),
+            ("checkpoint_items", "context_item_id = ?1"),
+            ("item_access", "item_id = ?1"),
+        ],
+        "issue" => &[
+            ("issues", "id = ?1"),
+            ("issue_projects", "issue_id = ?1"),
+            ("issue_labels", "issue_id = ?1"),
+            ("issue_dependencies", "issue_id = ?1 OR depends_on_id = ?1"),
+            ("issue_watchers", "issue_id = ?1"),
+            ("claim_leases", "issue_id = ?1"),
+        ],
+        "session" => &[
+            ("sessions", "id = ?1"),
+            ("session_projects", "session_id = ?1"),
+            ("context_items", "session_id = ?1"),
+            ("checkpoints", "session_id = ?1"),
+            (
+                "checkpoint_items",
+                "checkpoint_id IN (SELECT id FROM checkpoints WHERE session_id = ?1)",
+            ),
+            ("agent_sessions", "session_id = ?1"),
+            (
+                "item_access",
+                "item_id IN (SELECT id FROM context_items WHERE session_id = ?1)",
+            ),
+            ("session_usage", "session_id = ?1"),
+            ("session_activity", "session_id = ?1"),
+        ],
+        other => {
+            return Err(Error::InvalidArgument(format!(
+                "Unknown kind of trash entry '{other}'. Use context_item, issue or session"
+            )));
+        }
+    })
+}
+
+/// Tables that triggers write to as the rows before them go back in. The
+/// captured rows replace what the triggers wrote.
+const TRIGGER_FILLED: &[&str] = &["session_activity"];
+
+/// Copy an entity and its dependent rows into the trash.
+///
+/// Call inside the deleting transaction, before the delete.
+pub(crate) fn capture(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: &str,
+    label: &str,
+    project_path: Option<&str>,
+    actor: &str,
+) -> Result<()> {
+    let mut captured = Vec::new();
+    for (table, filter) in tables(entity_type)? {
+        let rows = select_rows(conn, table, filter, entity_id)?;
+        if !rows.is_empty() {
+            captured.push(serde_json::json!({ "table": table, "rows": rows }));
+        }
+    }
+
+    let id = format!("trash_{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+    conn.execute(
+        "INSERT INTO trash (id, entity_type, entity_id, label, project_path, rows, deleted_at, deleted_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            id,
+            entity_type,
+            entity_id,
+            label,
+            project_path,
+            Json::Array(captured).to_string(),
+            chrono::Utc::now().timestamp_millis(),
+            actor,
+        ],
+    )?;
+    Ok(())
+}
+
+fn select_rows(conn: &Connection, table: &str, filter: &str, entity_id: &str) -> Result<Vec<Json>> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {table} WHERE {filter}"))?;
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut rows = stmt.query([entity_id])?;
+
+    let mut captured = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut object = Map::new();
+        for (i, column) in columns.iter().enumerate() {
+            object.insert(column.clone(), to_json(row.get_ref(i)?));
+        }
+        captured.push(Json::Object(object));
+    }
+    Ok(captured)
+}
+
+fn to_json(value: ValueRef<'_>) -> Json {
+    match value {
+        ValueRef::Null => Json::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+        ValueRef::Blob(b) => b.iter().map(|&byte| Json::from(byte)).collect(),
+    }
+}
+
+fn to_sql(value: &Json) -> Value {
+    match value {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::Integer(i64::from(*b)),
+        Json::Number(n) => n
+            .as_i64()
+            .map_or_else(|| Value::Real(n.as_f64().unwrap_or_default()), Value::Integer),
+        Json::String(s) => Value::Text(s.clone()),
+        Json::Array(bytes) => Value::Blob(
+            bytes
+                .iter()
+                .filter_map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect(),
+        ),
+        Json::Object(_) => Value::Text(value.to_string()),
+    }
+}
+
+/// Find an entry by trash ID, by the deleted entity's ID, by an issue's
+/// short ID or by an item's key (the newest entry wins if several match).
+pub(crate) fn find(conn: &Connection, id: &str) -> Result<Option<(TrashEntry, String)>> {
+    Ok(conn
+        .query_row(
+            "SELECT id, entity_type, entity_id, label, project_path, rows, deleted_at, deleted_by
+             FROM trash
+             WHERE id = ?1 OR entity_id = ?1
+                OR (entity_type = 'issue' AND substr(label, 1, length(?1) + 1) = ?1 || ' ')
+                OR (entity_type = 'context_item' AND label = ?1)
+             ORDER BY deleted_at DESC, rowid DESC LIMIT 1",
+            [id],
+            |row| Ok((map_entry(row)?, row.get::<_, String>(5)?)),
+        )
+        .optional()?)
+}
+
+/// List entries, newest first.
+pub(crate) fn list(conn: &Connection, entity_type: Option<&str>, limit: usize) -> Result<Vec<TrashEntry>> {
+    if let Some(entity_type) = entity_type {
+        tables(entity_type)?;
+    }
+    let mut stmt = conn.prepare(
+        "SELECT id, entity_type, entity_id, label, project_path, rows, deleted_at, deleted_by
+         FROM trash WHERE ?1 IS NULL OR entity_type = ?1
+         ORDER BY deleted_at DESC LIMIT ?2",
+    )?;
+    let entries = stmt
+        .query_map(rusqlite::params![entity_type, i64::try_from(limit).unwrap_or(i64::MAX)], map_entry)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+fn map_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<TrashEntry> {
+    let rows: String = row.get(5)?;
+    let rows = serde_json::from_str::<Vec<Json>>(&rows).map_or(0, |tables| {
+        tables
+            .iter()
+            .filter_map(|t| t["rows"].as_array().map(Vec::len))
+            .sum()
+    });
+    Ok(TrashEntry {
+        id: row.get(0)?,
+        entity_type: row.get(1)?,
+        entity_id: row.get(2)?,
+        label: row.get(3)?,
+        project_path: row.get(4)?,
+        rows,
+        deleted_at: row.get(6)?,
+        deleted_by: row.get(7)?,
+    })
+}
+
+/// Insert a trashed entity's rows back and drop the entry.
+///
+/// The entity's own row must go back cleanly; dependent rows that now
+/// conflict, or whose other end is gone, are skipped and counted.
+pub(crate) fn restore(conn: &Connection, entry: TrashEntry, rows: &str) -> Result<TrashRestore> {
+    let captured: Vec<Json> = serde_json::from_str(rows)
+        .map_err(|e| Error::Other(format!("Trash entry {} is corrupt: {e}", entry.id)))?;
+
+    let mut restored_rows = 0;
+    let mut skipped_rows = 0;
+    for (index, table) in captured.iter().enumerate() {
+        let name = table["table"].as_str().unwrap_or_default();
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(Error::Other(format!("Trash entry {} is corrupt", entry.id)));
+        }
+        for row in table["rows"].as_array().into_iter().flatten() {
+            let Some(object) = row.as_object() else { continue };
+            let mut object = object.clone();
+            if name == "context_items" {
+                reset_embedding_state(&mut object);
+            }
+
+            match insert_row(conn, name, &object, TRIGGER_FILLED.contains(&name)) {
+                Ok(()) => restored_rows += 1,
+                Err(rusqlite::Error::SqliteFailure(e, message))
+                    if e.code == rusqlite::ErrorCode::ConstraintViolation =>
+                {
+                    if index == 0 {
+                        return Err(Error::InvalidArgument(format!(
+                            "Cannot restore {} '{}': {}",
+                            entry.entity_type,
+                            entry.label,
+                            restore_conflict(&entry.entity_type, message.as_deref())
+                        )));
+                    }
+                    skipped_rows += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    conn.execute(
+        "DELETE FROM sync_deletions WHERE entity_type = ?1 AND entity_id = ?2",
+        rusqlite::params![entry.entity_type, entry.entity_id],
+    )?;
+    conn.execute("DELETE FROM trash WHERE id = ?1", [&entry.id])?;
+
+    Ok(TrashRestore {
+        entry,
+        restored_rows,
+        skipped_rows,
+    })
+}
+
+fn insert_row(conn: &Connection, table: &str, row: &Map<String, Json>, replace: bool) -> rusqlite::Result<()> {
+    let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+    if columns.iter().any(|c| !c.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')) {
+        return Err(rusqlite::Error::InvalidColumnName(table.to_string()));
+    }
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let verb = if replace { "INSERT OR REPLACE" } else { "INSERT" };
+    let sql = format!("{verb} INTO {table} ({}) VALUES ({placeholders})", columns.join(", "));
+    let values: Vec<Value> = row.values().map(to_sql).collect();
+    conn.execute(&sql, rusqlite::params_from_iter(values))?;
+    Ok(())
+}
+
+/// Embedding chunks are not kept in the trash, so a restored item has to
+/// be embedded again.
+fn reset_embedding_state(row: &mut Map<String, Json>) {
+    for (status, reset_to) in [("embedding_status", "pending"), ("fast_embedding_status", "none")] {
+        if row.get(status).and_then(Json::as_str) == Some("complete") {
+            row.insert(status.to_string(), reset_to.into());
+        }
+    }
+    for column in ["embedded_at", "fast_embedded_at"] {
+        if row.contains_key(column) {
+            row.insert(column.to_string(), Json::Null);
+        }
+    }
+    if row.contains_key("chunk_count") {
+        row.insert("chunk_count".to_string(), 0.into());
+    }
+}
+
+fn restore_conflict(entity_type: &str, message: Option<&str>) -> String {
+    let message = message.unwrap_or_default();
+    match entity_type {
+        "context_item" if message.contains("UNIQUE") => {
+            "the session has a new item with the same key; delete or rename it first".to_string()
+        }
+        "context_item" if message.contains("FOREIGN KEY") => {
+            "its session no longer exists; restore the session first".to_string()
+        }
+        _ if message.contains("UNIQUE") => "it already exists".to_string(),
+        _ => message.to_string(),
+    }
+}
+
+/// Delete entries trashed before `before` (Unix ms), or all of them.
+pub(crate) fn purge(conn: &Connection, before: Option<i64>) -> Result<usize> {
+    Ok(match before {
+        Some(before) => conn.execute("DELETE FROM trash WHERE deleted_at < ?1", [before])?,
+        None => conn.execute("DELETE FROM trash", [])?,
+    })
+}
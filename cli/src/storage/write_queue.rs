@@ -0,0 +1,272 @@
+//! Write coordination between processes sharing a database.
+//!
+//! Only one connection writes a database at a time; the others wait in the
+//! busy handler, which sleeps and polls until `busy_timeout` runs out. With a
+//! dozen agents writing at once the polling is a lottery, and a process
+//! that keeps losing it fails with `SQLITE_BUSY` even though no write
+//! held the database for long.
+//!
+//! So before `BEGIN IMMEDIATE`, [`SqliteStorage`] takes its turn on an
+//! advisory lock on `<db>-write.lock` (`flock` on Unix, `LockFileEx` on
+//! Windows) and holds it until the transaction ends. Writers waiting for
+//! the lock poll it at short, jittered intervals, so they take turns
+//! rather than all retrying at the same moment. The OS drops the lock
+//! when its holder dies, so a crashed process can't block the others, and
+//! a writer that has waited [`QUEUE_TIMEOUT`] goes ahead without it.
+//!
+//! The lock only orders `sc` processes; other writers (the `sqlite3`
+//! shell, older `sc` builds) only meet the database's own locking, so
+//! `BEGIN IMMEDIATE` is still retried with jittered backoff when it
+//! reports busy.
+//!
+//! Writes that had to wait are added up per day in `write_contention`,
+//! which `sc db stats` reports.
+//!
+//! [`SqliteStorage`]: super::SqliteStorage
+
+use crate::error::Result;
+use crate::json_schema::json_schema;
+use fs4::fs_std::FileExt;
+use rusqlite::{Connection, ErrorCode};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::hash::BuildHasher;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Longest a writer waits for its turn before going ahead without it.
+pub const QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest pause between two looks at the lock.
+const MAX_POLL: Duration = Duration::from_millis(50);
+
+/// Retries of `BEGIN IMMEDIATE` after `SQLITE_BUSY`.
+const MAX_BUSY_RETRIES: u32 = 6;
+
+/// Lock files this process holds, by path, with how many transactions
+/// hold each. Connections of one process are ordered by the database alone;
+/// locking the file again from a second handle would wait on ourselves.
+static HELD: Mutex<BTreeMap<PathBuf, (File, usize)>> = Mutex::new(BTreeMap::new());
+
+/// A writer's turn: held from before `BEGIN` until the transaction ends.
+#[derive(Debug)]
+pub(crate) struct WriteTurn {
+    lock: Option<PathBuf>,
+    /// Whether the lock was taken by someone else when we asked.
+    queued: bool,
+    timed_out: bool,
+    waited: Duration,
+    busy_retries: u32,
+}
+
+impl WriteTurn {
+    /// Wait for the write lock of `conn`'s database. In-memory databases
+    /// and databases whose lock file can't be created don't queue.
+    pub(crate) fn wait(conn: &Connection) -> Self {
+        let started = Instant::now();
+        let mut turn = Self { lock: None, queued: false, timed_out: false, waited: Duration::ZERO, busy_retries: 0 };
+        let Some(db_path) = conn.path().filter(|p| !p.is_empty()) else {
+            return turn;
+        };
+        let path = PathBuf::from(format!("{db_path}-write.lock"));
+
+        if let Some((_, count)) = HELD.lock().unwrap_or_else(std::sync::PoisonError::into_inner).get_mut(&path) {
+            *count += 1;
+            turn.lock = Some(path);
+            return turn;
+        }
+        let file = match OpenOptions::new().create(true).truncate(false).write(true).open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::debug!(path = %path.display(), error = %e, "Write lock unavailable, not queueing");
+                return turn;
+            }
+        };
+
+        let mut attempt = 0;
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(true) => break,
+                Ok(false) if started.elapsed() < QUEUE_TIMEOUT => {
+                    turn.queued = true;
+                    std::thread::sleep(jittered(Duration::from_millis(2) * 2u32.pow(attempt.min(5))).min(MAX_POLL));
+                    attempt += 1;
+                }
+                Ok(false) => {
+                    tracing::warn!(path = %path.display(), "Waited {}s for the write lock; going ahead", QUEUE_TIMEOUT.as_secs());
+                    turn.timed_out = true;
+                    turn.waited = started.elapsed();
+                    return turn;
+                }
+                Err(e) => {
+                    tracing::debug!(path = %path.display(), error = %e, "Write lock failed, not queueing");
+                    return turn;
+                }
+            }
+        }
+        HELD.lock().unwrap_or_else(std::sync::PoisonError::into_inner).insert(path.clone(), (file, 1));
+        turn.lock = Some(path);
+        turn.waited = started.elapsed();
+        turn
+    }
+
+    /// Begin the transaction with `begin`, retrying with jittered backoff
+    /// while the database is busy, then count the wait if there was one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `begin`'s error once the retries run out, or at once if it
+    /// isn't `SQLITE_BUSY`.
+    pub(crate) fn begin<T>(&mut self, conn: &Connection, begin: impl Fn() -> rusqlite::Result<T>) -> Result<T> {
+        let started = Instant::now();
+        let result = loop {
+            match begin() {
+                Err(e) if is_busy(&e) && self.busy_retries < MAX_BUSY_RETRIES => {
+                    let delay = jittered(Duration::from_millis(25) * 2u32.pow(self.busy_retries));
+                    tracing::debug!(attempt = self.busy_retries, delay_ms = delay.as_millis(), "Database busy, retrying");
+                    std::thread::sleep(delay);
+                    self.busy_retries += 1;
+                }
+                result => break result?,
+            }
+        };
+        self.waited += started.elapsed();
+        if self.queued || self.timed_out || self.busy_retries > 0 {
+            if let Err(e) = self.record(conn) {
+                tracing::debug!(error = %e, "Could not record write contention");
+            }
+        }
+        Ok(result)
+    }
+
+    fn record(&self, conn: &Connection) -> Result<()> {
+        let day = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let wait_ms = i64::try_from(self.waited.as_millis()).unwrap_or(i64::MAX);
+        conn.execute(
+            "INSERT INTO write_contention (day, waits, wait_ms, max_wait_ms, timeouts, busy_retries)
+             VALUES (?1, ?2, ?3, ?3, ?4, ?5)
+             ON CONFLICT(day) DO UPDATE SET
+                 waits = waits + excluded.waits,
+                 wait_ms = wait_ms + excluded.wait_ms,
+                 max_wait_ms = MAX(max_wait_ms, excluded.max_wait_ms),
+                 timeouts = timeouts + excluded.timeouts,
+                 busy_retries = busy_retries + excluded.busy_retries",
+            rusqlite::params![day, i64::from(self.queued), wait_ms, i64::from(self.timed_out), self.busy_retries],
+        )?;
+        Ok(())
+    }
+}
+
+impl Drop for WriteTurn {
+    fn drop(&mut self) {
+        let Some(path) = self.lock.take() else {
+            return;
+        };
+        let mut held = HELD.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some((_, count)) = held.get_mut(&path) {
+            *count -= 1;
+            if *count == 0 {
+                // Closing the file releases the lock
+                held.remove(&path);
+            }
+        }
+    }
+}
+
+fn is_busy(e: &rusqlite::Error) -> bool {
+    matches!(e.sqlite_error_code(), Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked))
+}
+
+/// A random duration between half of `delay` and all of it.
+fn jittered(delay: Duration) -> Duration {
+    let random = std::collections::hash_map::RandomState::new().hash_one(Instant::now());
+    let half = delay / 2;
+    half + Duration::from_nanos(random % u64::try_from(half.as_nanos()).unwrap_or(u64::MAX).max(1))
+}
+
+/// Write contention over the last few days, for `sc db stats`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WriteContention {
+    /// Days covered, today included.
+    pub days: u32,
+    /// Writes that found another process writing and waited for it.
+    pub waits: i64,
+    /// Time spent waiting, in total and at most once.
+    pub wait_ms: i64,
+    pub max_wait_ms: i64,
+    /// Waits that ran out of patience and went ahead without the lock.
+    pub timeouts: i64,
+    /// `BEGIN IMMEDIATE` retried after `SQLITE_BUSY`.
+    pub busy_retries: i64,
+}
+
+json_schema!(WriteContention { days: u32, waits: i64, wait_ms: i64, max_wait_ms: i64, timeouts: i64, busy_retries: i64 });
+
+/// Contention of the last `days` days.
+pub(crate) fn contention(conn: &Connection, days: u32) -> Result<WriteContention> {
+    let since = (chrono::Utc::now() - chrono::Duration::days(i64::from(days.max(1)) - 1)).format("%Y-%m-%d").to_string();
+    let contention = conn.query_row(
+        "SELECT COALESCE(SUM(waits), 0), COALESCE(SUM(wait_ms), 0), COALESCE(MAX(max_wait_ms), 0),
+                COALESCE(SUM(timeouts), 0), COALESCE(SUM(busy_retries), 0)
+         FROM write_contention WHERE day >= ?1",
+        [since],
+        |row| {
+            Ok(WriteContention {
+                days,
+                waits: row.get(0)?,
+                wait_ms: row.get(1)?,
+                max_wait_ms: row.get(2)?,
+                timeouts: row.get(3)?,
+                busy_retries: row.get(4)?,
+            })
+        },
+    )?;
+    Ok(contention)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SqliteStorage;
+
+    #[test]
+    fn test_writers_take_turns() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let mut storage = SqliteStorage::open(&db_path).unwrap();
+
+        // Another process's turn: a second handle on the lock file
+        let other = OpenOptions::new().create(true).truncate(false).write(true).open(dir.path().join("test.db-write.lock")).unwrap();
+        other.lock_exclusive().unwrap();
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            FileExt::unlock(&other).unwrap();
+        });
+
+        let started = Instant::now();
+        storage.create_session("sess_1", "Work", None, Some("/repo"), None, None, "bot").unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(90));
+        release.join().unwrap();
+
+        // Nested writes of the same process don't wait on themselves
+        storage
+            .with_transaction(|s| (s.create_session("sess_2", "More", None, Some("/repo"), None, None, "bot"), true))
+            .unwrap()
+            .unwrap();
+
+        let contention = contention(storage.conn(), 7).unwrap();
+        assert_eq!((contention.waits, contention.timeouts), (1, 0));
+        assert!(contention.max_wait_ms >= 90 && contention.wait_ms == contention.max_wait_ms);
+        assert!(!HELD.lock().unwrap().contains_key(&dir.path().join("test.db-write.lock")));
+    }
+
+    #[test]
+    fn test_jittered() {
+        for _ in 0..100 {
+            let delay = jittered(Duration::from_millis(40));
+            assert!(delay >= Duration::from_millis(20) && delay < Duration::from_millis(40));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Embedding vectors in the JSONL sync.
+//!
+//! Embeddings are derived data, so the sync leaves them out by default and
+//! every machine re-embeds what it imports. With `--include-embeddings`
+//! the exporter also writes `embeddings.jsonl`, one [`EmbeddingRecord`] per
+//! embedded record, and a teammate's import gets semantic search without
+//! a backfill.
+//!
+//! Raw `f32` vectors are large and compress poorly as JSON, so each
+//! record's vectors are packed as little-endian bytes, zstd-compressed and
+//! base64-encoded into one string.
+//!
+//! Import is defensive: vectors are only stored if they decode to the
+//! stated dimensions, were made with the model this machine embeds with,
+//! and belong to the exact version of the record that exists locally.
+
+use std::io::Read;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::storage::EntityEmbeddings;
+use crate::sync::types::{EmbeddingRecord, EntityType, SyncError, SyncResult};
+
+/// File name of the embeddings sidecar in the export directory.
+pub const EMBEDDINGS_FILE: &str = "embeddings.jsonl";
+
+/// zstd level for packed vectors.
+const COMPRESSION_LEVEL: i32 = 9;
+
+/// Pack vectors into the `vectors` string of an [`EmbeddingRecord`].
+///
+/// # Errors
+///
+/// Returns an error if compression fails.
+pub fn encode_vectors(vectors: &[Vec<f32>]) -> SyncResult<String> {
+    let bytes: Vec<u8> = vectors.iter().flatten().flat_map(|f| f.to_le_bytes()).collect();
+    let compressed = zstd::encode_all(bytes.as_slice(), COMPRESSION_LEVEL)?;
+    Ok(STANDARD.encode(compressed))
+}
+
+/// Unpack `count` vectors of `dimensions` floats.
+///
+/// # Errors
+///
+/// Returns an error if the string isn't valid base64 or zstd, or holds a
+/// different number of floats than `count * dimensions`.
+pub fn decode_vectors(encoded: &str, dimensions: usize, count: usize) -> SyncResult<Vec<Vec<f32>>> {
+    let invalid = |message: String| SyncError::InvalidRecord { line: 0, message };
+    let compressed = STANDARD
+        .decode(encoded)
+        .map_err(|e| invalid(format!("vectors are not base64: {e}")))?;
+    let expected = dimensions * count * 4;
+    let mut bytes = Vec::with_capacity(expected);
+    zstd::Decoder::new(compressed.as_slice())?
+        // One byte over the expected size is enough to detect a mismatch
+        .take(expected as u64 + 1)
+        .read_to_end(&mut bytes)?;
+    if dimensions == 0 || bytes.len() != expected {
+        return Err(invalid(format!(
+            "vectors hold {} bytes, expected {count} x {dimensions} floats",
+            bytes.len()
+        )));
+    }
+    Ok(bytes
+        .chunks_exact(dimensions * 4)
+        .map(|chunk| {
+            chunk
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        })
+        .collect())
+}
+
+impl EmbeddingRecord {
+    /// Build the export line for one record's stored embeddings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity type isn't synced or packing fails.
+    pub fn from_entity(embeddings: &EntityEmbeddings) -> SyncResult<Self> {
+        let entity_type = embeddings
+            .entity_type
+            .parse::<EntityType>()
+            .map_err(SyncError::Database)?;
+        Ok(Self {
+            entity_type,
+            entity_id: embeddings.entity_id.clone(),
+            source_updated_at: embeddings.source_updated_at,
+            provider: embeddings.provider.clone(),
+            model: embeddings.model.clone(),
+            dimensions: embeddings.dimensions,
+            chunks: embeddings.chunk_texts.clone(),
+            vectors: encode_vectors(&embeddings.vectors)?,
+        })
+    }
+
+    /// Decode the record back into storable embeddings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vectors don't match `dimensions` and the
+    /// number of chunks.
+    pub fn to_entity(&self) -> SyncResult<EntityEmbeddings> {
+        Ok(EntityEmbeddings {
+            entity_type: self.entity_type.to_string(),
+            entity_id: self.entity_id.clone(),
+            source_updated_at: self.source_updated_at,
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            dimensions: self.dimensions,
+            chunk_texts: self.chunks.clone(),
+            vectors: decode_vectors(&self.vectors, self.dimensions, self.chunks.len())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_round_trip() {
+        let vectors = vec![vec![0.5, -1.25, 3.0], vec![0.0, 1.0, f32::MIN_POSITIVE]];
+        let encoded = encode_vectors(&vectors).unwrap();
+        assert_eq!(decode_vectors(&encoded, 3, 2).unwrap(), vectors);
+
+        // Dimension and chunk count must match the payload exactly
+        assert!(decode_vectors(&encoded, 4, 1).is_err());
+        assert!(decode_vectors(&encoded, 3, 1).is_err());
+        assert!(decode_vectors(&encoded, 0, 0).is_err());
+        assert!(decode_vectors("not base64!", 3, 2).is_err());
+    }
+}
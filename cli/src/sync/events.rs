@@ -0,0 +1,124 @@
+//! Audit events in the JSONL sync.
+//!
+//! The audit log is local by default. With `"sync_events": true` in the
+//! config, the exporter also keeps `events.jsonl`, one [`EventRecord`] per
+//! event about the project's records, so teammates syncing through git
+//! see the whole history and not just the current state.
+//!
+//! Unlike the snapshot files, `events.jsonl` is append-only: events
+//! already in the file stay there even after this machine prunes them,
+//! and new ones are added at the end. Each record carries an ID hashed
+//! from its content, which lets export skip events the file already holds
+//! and keeps import idempotent.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::storage::events::StoredEvent;
+use crate::sync::file::read_jsonl;
+use crate::sync::hash::content_hash;
+use crate::sync::types::{EventRecord, SyncResult};
+
+/// File name of the events log in the export directory.
+pub const EVENTS_FILE: &str = "events.jsonl";
+
+/// The sync ID of an event: `evt_` and the first 16 hex digits of the
+/// hash of everything but its local row ID.
+#[must_use]
+pub fn event_id(event: &StoredEvent) -> String {
+    let hash = content_hash(&(
+        &event.entity_type,
+        &event.entity_id,
+        &event.event_type,
+        &event.actor,
+        &event.old_value,
+        &event.new_value,
+        &event.comment,
+        event.created_at,
+    ));
+    format!("evt_{}", &hash[..16])
+}
+
+impl EventRecord {
+    /// Build the export line for a stored event.
+    #[must_use]
+    pub fn from_event(event: &StoredEvent) -> Self {
+        Self {
+            id: event_id(event),
+            entity_type: event.entity_type.clone(),
+            entity_id: event.entity_id.clone(),
+            event_type: event.event_type.clone(),
+            actor: event.actor.clone(),
+            old_value: event.old_value.clone(),
+            new_value: event.new_value.clone(),
+            comment: event.comment.clone(),
+            created_at: event.created_at,
+        }
+    }
+
+    /// The event to store locally; the database assigns its row ID.
+    #[must_use]
+    pub fn to_event(&self) -> StoredEvent {
+        StoredEvent {
+            id: 0,
+            entity_type: self.entity_type.clone(),
+            entity_id: self.entity_id.clone(),
+            event_type: self.event_type.clone(),
+            actor: self.actor.clone(),
+            old_value: self.old_value.clone(),
+            new_value: self.new_value.clone(),
+            comment: self.comment.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// Read every record in an events file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or a line isn't a valid
+/// event record.
+pub fn read_events(path: &Path) -> SyncResult<Vec<EventRecord>> {
+    read_jsonl(path)
+}
+
+/// IDs of the events already in an events file, if it exists.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read or parsed.
+pub fn existing_ids(path: &Path) -> SyncResult<HashSet<String>> {
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    Ok(read_events(path)?.into_iter().map(|r| r.id).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_id_is_content_derived() {
+        let event = StoredEvent {
+            id: 7,
+            entity_type: "issue".to_string(),
+            entity_id: "SC-1".to_string(),
+            event_type: "issue_claimed".to_string(),
+            actor: "alice".to_string(),
+            old_value: None,
+            new_value: Some("in_progress".to_string()),
+            comment: None,
+            created_at: 1_700_000_000_000,
+        };
+        let record = EventRecord::from_event(&event);
+        assert!(record.id.starts_with("evt_") && record.id.len() == 20);
+
+        // The local row ID doesn't matter, the content does
+        assert_eq!(event_id(&record.to_event()), record.id);
+        let mut other = event.clone();
+        other.actor = "bob".to_string();
+        assert_ne!(event_id(&other), record.id);
+    }
+}
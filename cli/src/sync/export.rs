@@ -5,7 +5,8 @@
 //! # Snapshot Mode
 //!
 //! Exports use **snapshot mode**: the JSONL file represents the current state
-//! of all records, not a log of changes. Git tracks the history.
+//! of all records, not a log of changes. Git tracks the history. The
+//! optional `events.jsonl` audit log is the exception: it is append-only.
 //!
 //! # Project Scoping
 //!
@@ -25,10 +26,13 @@ use std::path::{Path, PathBuf};
 use chrono::Utc;
 
 use crate::storage::sqlite::SqliteStorage;
-use crate::sync::file::{ensure_gitignore, read_jsonl, write_jsonl};
+use crate::sync::embeddings::EMBEDDINGS_FILE;
+use crate::sync::events::{existing_ids, EVENTS_FILE};
+use crate::sync::file::{ensure_gitignore, read_jsonl, read_text_lines, write_jsonl, write_jsonl_lines};
 use crate::sync::hash::content_hash;
+use crate::sync::status::record_sync_time;
 use crate::sync::types::{
-    CheckpointRecord, ContextItemRecord, DeletionRecord, EntityType, ExportStats, IssueRecord,
+    CheckpointRecord, ContextItemRecord, DeletionRecord, EmbeddingRecord, EventRecord, ExportStats, IssueRecord,
     MemoryRecord, PlanRecord, SessionRecord, SyncError, SyncRecord, SyncResult, TimeEntryRecord,
 };
 
@@ -41,6 +45,8 @@ pub struct Exporter<'a> {
     storage: &'a mut SqliteStorage,
     project_path: String,
     output_dir: PathBuf,
+    include_embeddings: bool,
+    include_events: bool,
 }
 
 impl<'a> Exporter<'a> {
@@ -61,6 +67,8 @@ impl<'a> Exporter<'a> {
             storage,
             project_path,
             output_dir,
+            include_embeddings: false,
+            include_events: false,
         }
     }
 
@@ -77,9 +85,25 @@ impl<'a> Exporter<'a> {
             storage,
             project_path,
             output_dir,
+            include_embeddings: false,
+            include_events: false,
         }
     }
 
+    /// Also write `embeddings.jsonl` with the project's embedding vectors.
+    #[must_use]
+    pub fn with_embeddings(mut self, include: bool) -> Self {
+        self.include_embeddings = include;
+        self
+    }
+
+    /// Also append the project's audit events to `events.jsonl`.
+    #[must_use]
+    pub fn with_events(mut self, include: bool) -> Self {
+        self.include_events = include;
+        self
+    }
+
     /// Get the output directory.
     #[must_use]
     pub fn output_dir(&self) -> &Path {
@@ -119,12 +143,19 @@ impl<'a> Exporter<'a> {
         self.export_checkpoints_snapshot(&mut stats, &now, force)?;
         self.export_plans_snapshot(&mut stats, &now, force)?;
         self.export_time_entries_snapshot(&mut stats, &now, force)?;
+        if self.include_embeddings {
+            self.export_embeddings_snapshot(&mut stats)?;
+        }
+        if self.include_events {
+            self.export_events(&mut stats)?;
+        }
 
         // Export pending deletions (separate file)
         self.export_deletions(&mut stats)?;
 
         // Clear dirty flags after successful export
         self.clear_all_dirty_flags()?;
+        record_sync_time(self.storage, "export", &self.output_dir)?;
 
         if stats.is_empty() {
             return Err(SyncError::NothingToExport);
@@ -225,7 +256,7 @@ impl<'a> Exporter<'a> {
     ) -> SyncResult<()> {
         let items = self
             .storage
-            .get_context_items_by_project(&self.project_path)
+            .get_context_items_for_sync(&self.project_path)
             .map_err(|e| SyncError::Database(e.to_string()))?;
 
         if items.is_empty() {
@@ -424,6 +455,70 @@ impl<'a> Exporter<'a> {
         Ok(())
     }
 
+    /// Export embedding vectors to `embeddings.jsonl`.
+    ///
+    /// Embeddings are derived from the records, so there is no lost-record
+    /// check: the file is always replaced with what is stored now.
+    fn export_embeddings_snapshot(&self, stats: &mut ExportStats) -> SyncResult<()> {
+        let embeddings = self
+            .storage
+            .get_project_embeddings(&self.project_path)
+            .map_err(|e| SyncError::Database(e.to_string()))?;
+
+        if embeddings.is_empty() {
+            return Ok(());
+        }
+
+        let mut content = String::new();
+        for entity in &embeddings {
+            content.push_str(&serde_json::to_string(&EmbeddingRecord::from_entity(entity)?)?);
+            content.push('\n');
+        }
+        write_jsonl_lines(&self.output_dir.join(EMBEDDINGS_FILE), &content)?;
+        stats.embeddings = embeddings.len();
+
+        Ok(())
+    }
+
+    /// Append events not yet in `events.jsonl`.
+    ///
+    /// The file is a log, not a snapshot: lines already there are kept
+    /// as they are, including events this machine has since pruned. Only
+    /// the header is rewritten, as the current one.
+    fn export_events(&self, stats: &mut ExportStats) -> SyncResult<()> {
+        let events = self
+            .storage
+            .get_project_events(&self.project_path)
+            .map_err(|e| SyncError::Database(e.to_string()))?;
+
+        let path = self.output_dir.join(EVENTS_FILE);
+        let mut seen = existing_ids(&path)?;
+        let mut appended = String::new();
+        for event in &events {
+            let record = EventRecord::from_event(event);
+            if seen.insert(record.id.clone()) {
+                appended.push_str(&serde_json::to_string(&record)?);
+                appended.push('\n');
+                stats.events += 1;
+            }
+        }
+        if appended.is_empty() {
+            return Ok(());
+        }
+
+        let mut content = String::new();
+        if path.exists() {
+            for (_, line) in read_text_lines(&path)?.1 {
+                content.push_str(&line);
+                content.push('\n');
+            }
+        }
+        content.push_str(&appended);
+        write_jsonl_lines(&path, &content)?;
+
+        Ok(())
+    }
+
     /// Export deletions to a separate JSONL file.
     ///
     /// Unlike entity exports which use snapshot mode, deletions are **cumulative**:
@@ -446,19 +541,7 @@ impl<'a> Exporter<'a> {
         let path = self.output_dir.join("deletions.jsonl");
 
         // Convert to DeletionRecord format
-        let records: Vec<DeletionRecord> = deletions
-            .iter()
-            .map(|del| DeletionRecord {
-                entity_type: del.entity_type.parse::<EntityType>().unwrap_or(EntityType::Session),
-                entity_id: del.entity_id.clone(),
-                project_path: del.project_path.clone(),
-                // Convert milliseconds to seconds for chrono
-                deleted_at: chrono::DateTime::from_timestamp(del.deleted_at / 1000, 0)
-                    .map(|dt| dt.to_rfc3339())
-                    .unwrap_or_else(|| del.deleted_at.to_string()),
-                deleted_by: del.deleted_by.clone(),
-            })
-            .collect();
+        let records: Vec<DeletionRecord> = deletions.iter().map(DeletionRecord::from_deletion).collect();
 
         // Write as JSONL (one deletion per line)
         let content: String = records
@@ -467,7 +550,7 @@ impl<'a> Exporter<'a> {
             .collect::<Vec<_>>()
             .join("\n");
 
-        crate::sync::file::atomic_write(&path, &format!("{content}\n"))?;
+        write_jsonl_lines(&path, &format!("{content}\n"))?;
 
         // Count pending deletions (those not yet exported)
         let pending_ids: Vec<i64> = self
@@ -496,19 +579,8 @@ impl<'a> Exporter<'a> {
             return Ok(());
         }
 
-        let existing_records = read_jsonl(path)?;
-        let jsonl_ids: HashSet<String> = existing_records
-            .iter()
-            .map(|r| match r {
-                SyncRecord::Session(rec) => rec.data.id.clone(),
-                SyncRecord::Issue(rec) => rec.data.id.clone(),
-                SyncRecord::ContextItem(rec) => rec.data.id.clone(),
-                SyncRecord::Memory(rec) => rec.data.id.clone(),
-                SyncRecord::Checkpoint(rec) => rec.data.id.clone(),
-                SyncRecord::Plan(rec) => rec.data.id.clone(),
-                SyncRecord::TimeEntry(rec) => rec.data.id.clone(),
-            })
-            .collect();
+        let existing_records: Vec<SyncRecord> = read_jsonl(path)?;
+        let jsonl_ids: HashSet<String> = existing_records.iter().map(|r| r.id().to_string()).collect();
 
         let missing: Vec<_> = jsonl_ids.difference(db_ids).collect();
 
@@ -641,7 +713,7 @@ mod tests {
 
         // Create a session for this project
         storage
-            .create_session("sess_1", "Test Session", None, Some(&project_path), None, "test")
+            .create_session("sess_1", "Test Session", None, Some(&project_path), None, None, "test")
             .unwrap();
 
         let mut exporter = Exporter::with_output_dir(
@@ -664,7 +736,7 @@ mod tests {
 
         // Create a session
         storage
-            .create_session("sess_1", "Test Session", None, Some(&project_path), None, "test")
+            .create_session("sess_1", "Test Session", None, Some(&project_path), None, None, "test")
             .unwrap();
 
         // First export
@@ -678,7 +750,7 @@ mod tests {
         // Count lines
         let content = fs::read_to_string(temp_dir.path().join("sessions.jsonl")).unwrap();
         let line_count_1 = content.lines().filter(|l| !l.is_empty()).count();
-        assert_eq!(line_count_1, 1);
+        assert_eq!(line_count_1, 2, "header and one session");
 
         // Second export (should overwrite, not append)
         let mut exporter = Exporter::with_output_dir(
@@ -688,10 +760,10 @@ mod tests {
         );
         exporter.export(true).unwrap(); // force to bypass dirty check
 
-        // Should still be 1 line, not 2
+        // Should still be 1 record, not 2
         let content = fs::read_to_string(temp_dir.path().join("sessions.jsonl")).unwrap();
         let line_count_2 = content.lines().filter(|l| !l.is_empty()).count();
-        assert_eq!(line_count_2, 1, "Export should overwrite, not append");
+        assert_eq!(line_count_2, 2, "Export should overwrite, not append");
     }
 
     #[test]
@@ -715,7 +787,7 @@ mod tests {
 
         // Create session and export
         storage
-            .create_session("sess_1", "Test Session", None, Some(&project_path), None, "test")
+            .create_session("sess_1", "Test Session", None, Some(&project_path), None, None, "test")
             .unwrap();
 
         let mut exporter = Exporter::with_output_dir(
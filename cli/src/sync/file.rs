@@ -3,12 +3,19 @@
 //! This module provides safe file operations that prevent data corruption:
 //! - Atomic writes: write to temp file, sync to disk, then rename
 //! - JSONL appending with fsync for durability
+//!
+//! The JSONL writers put a [`FileHeader`] line first and the readers skip
+//! it, refusing files in a newer format.
 
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 
-use crate::sync::types::{SyncError, SyncRecord, SyncResult};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::sync::meta::{self, FileHeader, LEGACY_VERSION};
+use crate::sync::types::{LineError, SyncError, SyncRecord, SyncResult};
 
 /// Write content to a file atomically.
 ///
@@ -49,7 +56,8 @@ pub fn atomic_write(path: &Path, content: &str) -> SyncResult<()> {
 /// Append a sync record to a JSONL file.
 ///
 /// Each record is serialized as a single JSON line and appended to the file.
-/// The file is synced after each append for durability.
+/// The file is synced after each append for durability. A new file gets
+/// the header first.
 ///
 /// # Errors
 ///
@@ -64,6 +72,9 @@ pub fn append_jsonl(path: &Path, record: &SyncRecord) -> SyncResult<()> {
         .create(true)
         .append(true)
         .open(path)?;
+    if file.metadata()?.len() == 0 {
+        file.write_all(FileHeader::line().as_bytes())?;
+    }
 
     let line = serde_json::to_string(record)?;
     writeln!(file, "{line}")?;
@@ -87,47 +98,150 @@ pub fn write_jsonl(path: &Path, records: &[SyncRecord]) -> SyncResult<()> {
         content.push_str(&line);
         content.push('\n');
     }
-    atomic_write(path, &content)
+    write_jsonl_lines(path, &content)
+}
+
+/// Write already serialized JSONL lines atomically, after the header.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn write_jsonl_lines(path: &Path, lines: &str) -> SyncResult<()> {
+    atomic_write(path, &(FileHeader::line() + lines))
 }
 
 /// Read all sync records from a JSONL file.
 ///
-/// Each line is parsed as a `SyncRecord`. Invalid lines cause an error
-/// with the line number for debugging.
+/// Each line is parsed as `T`, usually a `SyncRecord`. Invalid lines cause
+/// an error with the line number for debugging.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The file cannot be opened
-/// - Any line cannot be parsed as a valid `SyncRecord`
-pub fn read_jsonl(path: &Path) -> SyncResult<Vec<SyncRecord>> {
+/// - The file is in a newer format than this build reads
+/// - Any line cannot be parsed as a valid `T`
+pub fn read_jsonl<T: DeserializeOwned>(path: &Path) -> SyncResult<Vec<T>> {
     if !path.exists() {
         return Err(SyncError::FileNotFound(path.display().to_string()));
     }
 
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut records = Vec::new();
+    let (version, lines) = read_text_lines(path)?;
+    lines
+        .into_iter()
+        .map(|(line, text)| {
+            meta::parse_line(&text, version).map_err(|e| SyncError::InvalidRecord { line, message: e.to_string() })
+        })
+        .collect()
+}
 
-    for (line_num, line_result) in reader.lines().enumerate() {
-        let line = line_result?;
-        if line.trim().is_empty() {
-            continue;
-        }
+/// The format version of a JSONL file and its non-blank lines after the
+/// header, with their 1-indexed line numbers.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is in a newer format
+/// than this build reads.
+pub fn read_text_lines(path: &Path) -> SyncResult<(u32, Vec<(usize, String)>)> {
+    let content = fs::read_to_string(path)?;
+    let mut lines: Vec<(usize, String)> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(i, text)| (i + 1, text.to_string()))
+        .collect();
+    let version = match lines.first() {
+        Some((_, first)) => FileHeader::detect(first, &file_name(path))?,
+        None => None,
+    };
+    if version.is_some() {
+        lines.remove(0);
+    }
+    Ok((version.unwrap_or(LEGACY_VERSION), lines))
+}
 
-        let record: SyncRecord = serde_json::from_str(&line).map_err(|e| {
-            SyncError::InvalidRecord {
-                line: line_num + 1,
-                message: e.to_string(),
+/// The file name of `path`, for messages.
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned())
+}
+
+/// Records parsed from a JSONL file, with their line numbers, and the
+/// lines that didn't parse.
+pub type ParsedLines<T> = (Vec<(usize, T)>, Vec<LineError>);
+
+/// Parse every non-empty line of a JSONL file as `T`, without stopping at
+/// bad ones.
+///
+/// Lines that aren't UTF-8 or JSON, don't match `T`, or hold a string
+/// longer than `max_field_len` bytes are returned as errors alongside the
+/// records that parsed, each with its 1-indexed line number. Lines from
+/// an older format are upconverted first.
+///
+/// # Errors
+///
+/// Returns an error only if the file cannot be read or is in a newer
+/// format than this build reads.
+pub fn read_jsonl_lines<T: DeserializeOwned>(path: &Path, max_field_len: usize) -> SyncResult<ParsedLines<T>> {
+    let file = file_name(path);
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    let mut version = None;
+
+    for (line_num, bytes) in reader.split(b'\n').enumerate() {
+        let line = line_num + 1;
+        let bytes = bytes?;
+        let text = match std::str::from_utf8(&bytes) {
+            Ok(text) if text.trim().is_empty() => continue,
+            Ok(text) => text,
+            Err(e) => {
+                errors.push(LineError { file: file.clone(), line, message: format!("not UTF-8: {e}") });
+                continue;
+            }
+        };
+        if version.is_none() {
+            let header = FileHeader::detect(text, &file)?;
+            version = Some(header.unwrap_or(LEGACY_VERSION));
+            if header.is_some() {
+                continue;
             }
-        })?;
-        records.push(record);
+        }
+        match parse_line(text, max_field_len, version.unwrap_or(LEGACY_VERSION)) {
+            Ok(record) => records.push((line, record)),
+            Err(message) => errors.push(LineError { file: file.clone(), line, message }),
+        }
     }
+    Ok((records, errors))
+}
 
-    Ok(records)
+/// Parse one non-blank line of a file in format `version`.
+fn parse_line<T: DeserializeOwned>(line: &str, max_field_len: usize, version: u32) -> Result<T, String> {
+    let value: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    if let Some((field, len)) = oversized_field(&value, max_field_len, "") {
+        return Err(format!("field `{field}` is {len} bytes (max {max_field_len})"));
+    }
+    meta::parse_value(value, version).map_err(|e| e.to_string())
+}
+
+/// The path and length of the first string (or key) longer than `max`.
+fn oversized_field(value: &Value, max: usize, path: &str) -> Option<(String, usize)> {
+    match value {
+        Value::String(s) if s.len() > max => Some((path.to_string(), s.len())),
+        Value::Array(items) => {
+            items.iter().enumerate().find_map(|(i, item)| oversized_field(item, max, &format!("{path}[{i}]")))
+        }
+        Value::Object(map) => map.iter().find_map(|(key, item)| {
+            let prefix = if path.is_empty() { String::new() } else { format!("{path}.") };
+            if key.len() > max {
+                return Some((format!("{prefix}<key>"), key.len()));
+            }
+            oversized_field(item, max, &format!("{prefix}{key}"))
+        }),
+        _ => None,
+    }
 }
 
-/// Count the number of lines in a JSONL file.
+/// Count the record lines in a JSONL file, leaving out the header.
 ///
 /// This is useful for showing statistics without loading all records into memory.
 ///
@@ -141,8 +255,9 @@ pub fn count_lines(path: &Path) -> SyncResult<usize> {
 
     let file = File::open(path)?;
     let reader = BufReader::new(file);
-    let count = reader.lines().filter(|l| l.is_ok()).count();
-    Ok(count)
+    let mut lines = reader.lines().map_while(std::result::Result::ok).peekable();
+    let header = lines.peek().is_some_and(|first| first.starts_with("{\"type\":\"meta\""));
+    Ok(lines.count() - usize::from(header))
 }
 
 /// Get the size of a file in bytes.
@@ -206,6 +321,7 @@ pub fn ensure_gitignore(export_dir: &Path) -> SyncResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::SessionStatus;
     use crate::storage::sqlite::Session;
     use crate::sync::types::SessionRecord;
     use tempfile::TempDir;
@@ -218,10 +334,11 @@ mod tests {
             branch: None,
             channel: None,
             project_path: Some("/test".to_string()),
-            status: "active".to_string(),
+            status: SessionStatus::Active,
             ended_at: None,
             created_at: 1000,
             updated_at: 1000,
+            worktree: None,
         }
     }
 
@@ -252,7 +369,9 @@ mod tests {
 
         let content = fs::read_to_string(&path).unwrap();
         let lines: Vec<_> = content.lines().filter(|l| !l.is_empty()).collect();
-        assert_eq!(lines.len(), 2);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(FileHeader::detect(lines[0], "sessions.jsonl").unwrap(), Some(meta::SCHEMA_VERSION));
+        assert_eq!(count_lines(&path).unwrap(), 2);
     }
 
     #[test]
@@ -273,8 +392,8 @@ mod tests {
 
         write_jsonl(&path, &[record1, record2]).unwrap();
 
-        let records = read_jsonl(&path).unwrap();
-        assert_eq!(records.len(), 2);
+        let parsed: Vec<SyncRecord> = read_jsonl(&path).unwrap();
+        assert_eq!(parsed.len(), 2);
     }
 
     #[test]
@@ -292,7 +411,7 @@ mod tests {
 
     #[test]
     fn test_file_not_found() {
-        let result = read_jsonl(Path::new("/nonexistent/file.jsonl"));
+        let result = read_jsonl::<SyncRecord>(Path::new("/nonexistent/file.jsonl"));
         assert!(matches!(result, Err(SyncError::FileNotFound(_))));
     }
 
@@ -3,69 +3,218 @@
 //! This module handles importing records from JSONL files with merge support.
 //! It uses content hashing and timestamps to resolve conflicts between
 //! local and external records.
+//!
+//! Files may come from other machines and versions, so lines are checked
+//! one at a time: a line that isn't valid JSON, doesn't match its record
+//! type, or has an oversized field is set aside as a [`LineError`] and the
+//! rest of the file still imports. [`ImportMode::Strict`] checks every file
+//! first and refuses the whole import instead.
 
+use std::collections::HashMap;
 use std::path::Path;
 
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
-use crate::storage::sqlite::SqliteStorage;
-use crate::sync::file::read_jsonl;
+use crate::storage::sqlite::{Issue, SqliteStorage};
+use crate::storage::events::is_protected;
+use crate::sync::embeddings::EMBEDDINGS_FILE;
+use crate::sync::events::{event_id, EVENTS_FILE};
+use crate::sync::file::read_jsonl_lines;
 use crate::sync::hash::content_hash;
+use crate::sync::status::record_sync_time;
 use crate::sync::types::{
-    CheckpointRecord, ContextItemRecord, DeletionRecord, EntityStats, ImportStats, IssueRecord,
-    MemoryRecord, MergeStrategy, PlanRecord, SessionRecord, SyncError, SyncRecord, SyncResult,
-    TimeEntryRecord,
+    CheckpointRecord, ContextItemRecord, DeletionRecord, EmbeddingImportStats, EmbeddingRecord, EntityStats,
+    EntityType, EventImportStats, EventRecord, ImportMode, ImportStats, IssueRecord, LineError,
+    MemoryRecord, MergeStrategies, MergeStrategy, PlanRecord, SessionRecord, SyncError, SyncRecord, SyncResult,
+    TimeEntryRecord, DEFAULT_MAX_FIELD_LEN,
 };
 
+/// Data files in the order they are imported, so records exist before
+/// those that refer to them.
+const DATA_FILES: [&str; 7] = [
+    "sessions.jsonl",
+    "issues.jsonl",
+    "context_items.jsonl",
+    "memories.jsonl",
+    "checkpoints.jsonl",
+    "plans.jsonl",
+    "time_entries.jsonl",
+];
+
+/// Deletions, applied after everything else.
+const DELETIONS_FILE: &str = "deletions.jsonl";
+
 /// Importer for JSONL sync files.
 ///
 /// The importer reads records from JSONL files and merges them into the
 /// local database using the specified merge strategy.
 pub struct Importer<'a> {
     storage: &'a mut SqliteStorage,
-    strategy: MergeStrategy,
+    strategies: MergeStrategies,
+    /// Provider and model this machine embeds with, if known.
+    embedding_model: Option<(String, String)>,
+    /// Events older than this (Unix ms) are left out, as local retention
+    /// would prune them again.
+    events_since: Option<i64>,
+    mode: ImportMode,
+    max_field_len: usize,
+    /// Lines skipped so far.
+    errors: Vec<LineError>,
 }
 
 impl<'a> Importer<'a> {
     /// Create a new importer with the specified merge strategy.
     #[must_use]
     pub fn new(storage: &'a mut SqliteStorage, strategy: MergeStrategy) -> Self {
-        Self { storage, strategy }
+        Self {
+            storage,
+            strategies: MergeStrategies::new(strategy),
+            embedding_model: None,
+            events_since: None,
+            mode: ImportMode::default(),
+            max_field_len: DEFAULT_MAX_FIELD_LEN,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Resolve conflicts with a strategy per entity type.
+    #[must_use]
+    pub fn with_strategies(mut self, strategies: MergeStrategies) -> Self {
+        self.strategies = strategies;
+        self
+    }
+
+    /// Only import embeddings made with this provider and model.
+    ///
+    /// Without it, imported embeddings must match the model of the ones
+    /// already stored, or of the first record in `embeddings.jsonl` when
+    /// nothing is stored yet.
+    #[must_use]
+    pub fn with_embedding_model(mut self, model: Option<(String, String)>) -> Self {
+        self.embedding_model = model;
+        self
+    }
+
+    /// Skip imported events created before `since` (Unix ms).
+    ///
+    /// Creates, deletes, comments and mentions are imported regardless,
+    /// matching what `sc events prune` keeps.
+    #[must_use]
+    pub fn with_events_since(mut self, since: Option<i64>) -> Self {
+        self.events_since = since;
+        self
+    }
+
+    /// Skip bad lines (the default) or refuse the import on the first.
+    #[must_use]
+    pub fn with_mode(mut self, mode: ImportMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Reject lines with a string field longer than `max` bytes
+    /// (default [`DEFAULT_MAX_FIELD_LEN`]).
+    #[must_use]
+    pub fn with_max_field_len(mut self, max: usize) -> Self {
+        self.max_field_len = max;
+        self
+    }
+
+    /// Every line skipped by this importer so far, across files.
+    #[must_use]
+    pub fn errors(&self) -> &[LineError] {
+        &self.errors
+    }
+
+    /// Parse the lines of `path`, setting bad ones aside. In strict mode
+    /// any bad line fails the call instead.
+    fn read_lines<T: serde::de::DeserializeOwned>(&mut self, path: &Path) -> SyncResult<Vec<(usize, T)>> {
+        let (records, errors) = read_jsonl_lines(path, self.max_field_len)?;
+        if self.mode == ImportMode::Strict && !errors.is_empty() {
+            return Err(SyncError::InvalidLines(errors));
+        }
+        self.errors.extend(errors);
+        Ok(records)
+    }
+
+    /// Set aside a line whose record could not be merged; in strict mode,
+    /// fail with its error.
+    fn reject(&mut self, path: &Path, line: usize, error: SyncError) -> SyncResult<()> {
+        if self.mode == ImportMode::Strict {
+            return Err(error);
+        }
+        let file = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        tracing::debug!(file, line, error = %error, "Skipping record that failed to import");
+        // Storage errors already name themselves as database errors
+        let message = match error {
+            SyncError::Database(message) => message,
+            error => error.to_string(),
+        };
+        self.errors.push(LineError { file, line, message });
+        Ok(())
+    }
+
+    /// Every bad line in the sync files of `dir`, without importing
+    /// anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read.
+    pub fn check_all(&self, dir: &Path) -> SyncResult<Vec<LineError>> {
+        fn check<T: serde::de::DeserializeOwned>(path: &Path, max: usize, errors: &mut Vec<LineError>) -> SyncResult<()> {
+            if path.exists() {
+                errors.extend(read_jsonl_lines::<T>(path, max)?.1);
+            }
+            Ok(())
+        }
+
+        let mut errors = Vec::new();
+        for filename in DATA_FILES {
+            check::<SyncRecord>(&dir.join(filename), self.max_field_len, &mut errors)?;
+        }
+        check::<EmbeddingRecord>(&dir.join(EMBEDDINGS_FILE), self.max_field_len, &mut errors)?;
+        check::<EventRecord>(&dir.join(EVENTS_FILE), self.max_field_len, &mut errors)?;
+        check::<DeletionRecord>(&dir.join(DELETIONS_FILE), self.max_field_len, &mut errors)?;
+        Ok(errors)
     }
 
     /// Import records from a JSONL file.
     ///
     /// Each line in the file is parsed and merged into the local database.
-    /// The merge strategy determines how conflicts are resolved.
+    /// The merge strategy determines how conflicts are resolved. Lines that
+    /// can't be parsed or merged are skipped and listed in the stats'
+    /// `errors`, unless the importer is strict.
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or records are invalid.
+    /// Returns an error if the file cannot be read, or in strict mode if a
+    /// line is invalid or fails to import.
     pub fn import(&mut self, path: &Path) -> SyncResult<ImportStats> {
-        let records = read_jsonl(path)?;
+        if !path.exists() {
+            return Err(SyncError::FileNotFound(path.display().to_string()));
+        }
+        let first_error = self.errors.len();
+        let records = self.read_lines::<SyncRecord>(path)?;
         let mut stats = ImportStats::default();
 
-        for record in records {
-            match record {
-                SyncRecord::Session(rec) => self.import_session(rec, &mut stats.sessions)?,
-                SyncRecord::Issue(rec) => self.import_issue(rec, &mut stats.issues)?,
-                SyncRecord::ContextItem(rec) => {
-                    self.import_context_item(rec, &mut stats.context_items)?;
-                }
-                SyncRecord::Memory(rec) => self.import_memory(rec, &mut stats.memories)?,
-                SyncRecord::Checkpoint(rec) => {
-                    self.import_checkpoint(rec, &mut stats.checkpoints)?;
-                }
-                SyncRecord::Plan(rec) => {
-                    self.import_plan(rec, &mut stats.plans)?;
-                }
-                SyncRecord::TimeEntry(rec) => {
-                    self.import_time_entry(rec, &mut stats.time_entries)?;
-                }
+        for (line, record) in records {
+            let result = match record {
+                SyncRecord::Session(rec) => self.import_session(rec, &mut stats.sessions),
+                SyncRecord::Issue(rec) => self.import_issue(rec, &mut stats.issues),
+                SyncRecord::ContextItem(rec) => self.import_context_item(rec, &mut stats.context_items),
+                SyncRecord::Memory(rec) => self.import_memory(rec, &mut stats.memories),
+                SyncRecord::Checkpoint(rec) => self.import_checkpoint(rec, &mut stats.checkpoints),
+                SyncRecord::Plan(rec) => self.import_plan(rec, &mut stats.plans),
+                SyncRecord::TimeEntry(rec) => self.import_time_entry(rec, &mut stats.time_entries),
+            };
+            if let Err(error) = result {
+                self.reject(path, line, error)?;
             }
         }
 
+        self.errors[first_error..].sort_by_key(|e| e.line);
+        stats.errors = self.errors[first_error..].to_vec();
         Ok(stats)
     }
 
@@ -73,26 +222,25 @@ impl<'a> Importer<'a> {
     ///
     /// Imports files in order: sessions, issues, context_items, memories, checkpoints.
     /// Then applies deletions last (to handle records that were created then deleted).
-    /// Files that don't exist are skipped.
+    /// Files that don't exist are skipped. A strict importer checks every
+    /// file before changing anything.
     ///
     /// # Errors
     ///
-    /// Returns an error if any file cannot be read.
+    /// Returns an error if any file cannot be read, or in strict mode if
+    /// any line is invalid or fails to import.
     pub fn import_all(&mut self, dir: &Path) -> SyncResult<ImportStats> {
+        if self.mode == ImportMode::Strict {
+            let errors = self.check_all(dir)?;
+            if !errors.is_empty() {
+                return Err(SyncError::InvalidLines(errors));
+            }
+        }
+        let first_error = self.errors.len();
         let mut total_stats = ImportStats::default();
 
         // Import data records in dependency order
-        let files = [
-            ("sessions.jsonl", "sessions"),
-            ("issues.jsonl", "issues"),
-            ("context_items.jsonl", "context_items"),
-            ("memories.jsonl", "memories"),
-            ("checkpoints.jsonl", "checkpoints"),
-            ("plans.jsonl", "plans"),
-            ("time_entries.jsonl", "time_entries"),
-        ];
-
-        for (filename, _entity) in files {
+        for filename in DATA_FILES {
             let path = dir.join(filename);
             if path.exists() {
                 let stats = self.import(&path)?;
@@ -100,12 +248,27 @@ impl<'a> Importer<'a> {
             }
         }
 
+        // Embeddings need their records in place to check they're current
+        let embeddings_path = dir.join(EMBEDDINGS_FILE);
+        if embeddings_path.exists() {
+            total_stats.embeddings = self.import_embeddings(&embeddings_path)?;
+        }
+
+        let events_path = dir.join(EVENTS_FILE);
+        if events_path.exists() {
+            total_stats.events = self.import_events(&events_path)?;
+        }
+
         // Apply deletions last (after importing any records that might be deleted)
-        let deletions_path = dir.join("deletions.jsonl");
+        let deletions_path = dir.join(DELETIONS_FILE);
         if deletions_path.exists() {
             self.import_deletions(&deletions_path)?;
         }
 
+        if dir.exists() {
+            record_sync_time(self.storage, "import", dir)?;
+        }
+        total_stats.errors = self.errors[first_error..].to_vec();
         Ok(total_stats)
     }
 
@@ -116,38 +279,106 @@ impl<'a> Importer<'a> {
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or deletions cannot be applied.
+    /// Returns an error if the file cannot be read or deletions cannot be
+    /// applied. Bad lines are skipped unless the importer is strict.
     pub fn import_deletions(&mut self, path: &Path) -> SyncResult<usize> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
         let mut deleted_count = 0;
 
-        for (line_num, line) in reader.lines().enumerate() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
+        for (line, deletion) in self.read_lines::<DeletionRecord>(path)? {
+            // Apply the deletion
+            let entity_type = deletion.entity_type.to_string();
+            match self.storage.apply_deletion(&entity_type, &deletion.entity_id) {
+                Ok(true) => deleted_count += 1,
+                Ok(false) => {}
+                Err(e) => self.reject(path, line, SyncError::Database(e.to_string()))?,
             }
+        }
 
-            let deletion: DeletionRecord = serde_json::from_str(&line).map_err(|e| {
-                SyncError::InvalidRecord {
-                    line: line_num + 1,
-                    message: e.to_string(),
-                }
-            })?;
+        Ok(deleted_count)
+    }
 
-            // Apply the deletion
-            let entity_type = deletion.entity_type.to_string();
-            let was_deleted = self
+    /// Import embedding vectors from `embeddings.jsonl`.
+    ///
+    /// Records from another model, with vectors that don't decode to their
+    /// stated dimensions, or whose local record has changed since they were
+    /// embedded are skipped and counted; those records get embedded by the
+    /// next backfill as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the embeddings cannot
+    /// be stored. Bad lines are skipped unless the importer is strict.
+    pub fn import_embeddings(&mut self, path: &Path) -> SyncResult<EmbeddingImportStats> {
+        let mut stats = EmbeddingImportStats::default();
+        // Vectors from different models can't be searched together
+        let mut accepted = match &self.embedding_model {
+            Some(model) => Some(model.clone()),
+            None => self
                 .storage
-                .apply_deletion(&entity_type, &deletion.entity_id)
-                .map_err(|e| SyncError::Database(e.to_string()))?;
+                .embedding_models(false)
+                .map_err(|e| SyncError::Database(e.to_string()))?
+                .into_iter()
+                .next()
+                .map(|m| (m.provider, m.model)),
+        };
 
-            if was_deleted {
-                deleted_count += 1;
+        for (line, record) in self.read_lines::<EmbeddingRecord>(path)? {
+            let model = (record.provider.clone(), record.model.clone());
+            if *accepted.get_or_insert_with(|| model.clone()) != model {
+                stats.other_model += 1;
+                continue;
+            }
+            let embeddings = match record.to_entity() {
+                Ok(embeddings) => embeddings,
+                Err(e) => {
+                    tracing::debug!(line, error = %e, "Skipping undecodable embeddings");
+                    stats.invalid += 1;
+                    continue;
+                }
+            };
+            match self.storage.import_entity_embeddings(&embeddings) {
+                Ok(true) => stats.imported += 1,
+                Ok(false) => stats.stale += 1,
+                Err(e) => self.reject(path, line, SyncError::Database(e.to_string()))?,
             }
         }
 
-        Ok(deleted_count)
+        Ok(stats)
+    }
+
+    /// Import audit events from `events.jsonl`.
+    ///
+    /// Events already recorded locally are skipped, so importing the same
+    /// file twice changes nothing. Records whose ID doesn't match their
+    /// content are counted as invalid and left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the events cannot be
+    /// stored. Bad lines are skipped unless the importer is strict.
+    pub fn import_events(&mut self, path: &Path) -> SyncResult<EventImportStats> {
+        let mut stats = EventImportStats::default();
+        let mut events = Vec::new();
+        for (_, record) in self.read_lines::<EventRecord>(path)? {
+            let event = record.to_event();
+            if event_id(&event) != record.id {
+                stats.invalid += 1;
+            } else if self
+                .events_since
+                .is_some_and(|since| event.created_at < since && !is_protected(&event.event_type))
+            {
+                stats.expired += 1;
+            } else {
+                events.push(event);
+            }
+        }
+
+        stats.imported = self
+            .storage
+            .import_events(&events)
+            .map_err(|e| SyncError::Database(e.to_string()))?;
+        stats.existing = events.len() - stats.imported;
+        Ok(stats)
     }
 
     /// Import a session record with merge.
@@ -168,7 +399,7 @@ impl<'a> Importer<'a> {
                 }
 
                 // Apply merge strategy
-                match self.strategy {
+                match self.strategies.for_entity(EntityType::Session) {
                     MergeStrategy::PreferNewer => {
                         if rec.data.updated_at > local.updated_at {
                             self.storage
@@ -203,50 +434,72 @@ impl<'a> Importer<'a> {
     }
 
     /// Import an issue record with merge.
-    fn import_issue(&mut self, rec: IssueRecord, stats: &mut EntityStats) -> SyncResult<()> {
+    fn import_issue(&mut self, mut rec: IssueRecord, stats: &mut EntityStats) -> SyncResult<()> {
         let existing = self
             .storage
             .get_issue(&rec.data.id, None)
             .map_err(|e| SyncError::Database(e.to_string()))?;
 
-        match existing {
-            Some(local) => {
-                let local_hash = content_hash(&local);
-                if local_hash == rec.content_hash {
-                    stats.skipped += 1;
-                    return Ok(());
-                }
+        let apply = match &existing {
+            Some(local) if content_hash(local) == rec.content_hash => false,
+            Some(local) => match self.strategies.for_entity(EntityType::Issue) {
+                MergeStrategy::PreferNewer => rec.data.updated_at > local.updated_at,
+                MergeStrategy::PreferLocal => false,
+                MergeStrategy::PreferExternal => true,
+            },
+            None => true,
+        };
+        if !apply {
+            stats.skipped += 1;
+            return Ok(());
+        }
 
-                match self.strategy {
-                    MergeStrategy::PreferNewer => {
-                        if rec.data.updated_at > local.updated_at {
-                            self.storage
-                                .upsert_issue(&rec.data)
-                                .map_err(|e| SyncError::Database(e.to_string()))?;
-                            stats.updated += 1;
-                        } else {
-                            stats.skipped += 1;
-                        }
-                    }
-                    MergeStrategy::PreferLocal => {
-                        stats.skipped += 1;
-                    }
-                    MergeStrategy::PreferExternal => {
-                        self.storage
-                            .upsert_issue(&rec.data)
-                            .map_err(|e| SyncError::Database(e.to_string()))?;
-                        stats.updated += 1;
-                    }
-                }
-            }
-            None => {
-                self.storage
-                    .upsert_issue(&rec.data)
-                    .map_err(|e| SyncError::Database(e.to_string()))?;
-                stats.created += 1;
-            }
+        self.claim_short_id(&mut rec.data, existing.is_none(), stats)
+            .map_err(|e| SyncError::Database(e.to_string()))?;
+        self.storage
+            .upsert_issue(&rec.data)
+            .map_err(|e| SyncError::Database(e.to_string()))?;
+        if existing.is_some() {
+            stats.updated += 1;
+        } else {
+            stats.created += 1;
         }
+        Ok(())
+    }
 
+    /// Make sure no other issue holds the short ID of `issue`, which is
+    /// about to be written. When two machines gave out the same short ID,
+    /// the issue with the greater ID takes a new one, so both machines
+    /// renumber the same issue.
+    ///
+    /// A new issue in this database's own shard means another machine
+    /// picked the same shard, so this one moves to a fresh shard.
+    fn claim_short_id(
+        &mut self,
+        issue: &mut Issue,
+        is_new: bool,
+        stats: &mut EntityStats,
+    ) -> crate::error::Result<()> {
+        let Some(short_id) = issue.short_id.clone() else {
+            return Ok(());
+        };
+        if is_new && self.storage.owns_short_id(&short_id)? {
+            self.storage.reroll_machine_shard()?;
+        }
+        let Some(holder) = self.storage.issue_id_by_short_id(&short_id)? else {
+            return Ok(());
+        };
+        if holder == issue.id {
+            return Ok(());
+        }
+        let fresh = self.storage.next_issue_short_id()?;
+        if issue.id > holder {
+            issue.short_id = Some(fresh);
+            issue.updated_at = chrono::Utc::now().timestamp_millis();
+        } else {
+            self.storage.set_issue_short_id(&holder, &fresh)?;
+        }
+        stats.renumbered += 1;
         Ok(())
     }
 
@@ -269,7 +522,7 @@ impl<'a> Importer<'a> {
                     return Ok(());
                 }
 
-                match self.strategy {
+                match self.strategies.for_entity(EntityType::ContextItem) {
                     MergeStrategy::PreferNewer => {
                         if rec.data.updated_at > local.updated_at {
                             self.storage
@@ -304,12 +557,35 @@ impl<'a> Importer<'a> {
 
     /// Import a memory record with merge.
     fn import_memory(&mut self, rec: MemoryRecord, stats: &mut EntityStats) -> SyncResult<()> {
-        // Memory items don't have a get_by_id, so we always upsert
-        // The ON CONFLICT handles deduplication by (project_path, key)
+        // Memory is keyed by (project_path, key); the upsert's ON CONFLICT
+        // merges into the local entry with the same key
+        let existing = self
+            .storage
+            .get_memory(&rec.data.project_path, &rec.data.key)
+            .map_err(|e| SyncError::Database(e.to_string()))?;
+
+        let apply = match &existing {
+            Some(local) if local.value == rec.data.value && local.category == rec.data.category => false,
+            Some(local) => match self.strategies.for_entity(EntityType::Memory) {
+                MergeStrategy::PreferNewer => rec.data.updated_at > local.updated_at,
+                MergeStrategy::PreferLocal => false,
+                MergeStrategy::PreferExternal => true,
+            },
+            None => true,
+        };
+        if !apply {
+            stats.skipped += 1;
+            return Ok(());
+        }
+
         self.storage
             .upsert_memory(&rec.data)
             .map_err(|e| SyncError::Database(e.to_string()))?;
-        stats.created += 1;
+        if existing.is_some() {
+            stats.updated += 1;
+        } else {
+            stats.created += 1;
+        }
         Ok(())
     }
 
@@ -333,7 +609,7 @@ impl<'a> Importer<'a> {
                 }
 
                 // Checkpoints are immutable in nature, but we allow updates
-                match self.strategy {
+                match self.strategies.for_entity(EntityType::Checkpoint) {
                     MergeStrategy::PreferNewer | MergeStrategy::PreferExternal => {
                         self.storage
                             .upsert_checkpoint(&rec.data)
@@ -371,7 +647,7 @@ impl<'a> Importer<'a> {
                     return Ok(());
                 }
 
-                match self.strategy {
+                match self.strategies.for_entity(EntityType::Plan) {
                     MergeStrategy::PreferNewer => {
                         if rec.data.updated_at > local.updated_at {
                             self.storage
@@ -422,7 +698,7 @@ impl<'a> Importer<'a> {
                     return Ok(());
                 }
 
-                match self.strategy {
+                match self.strategies.for_entity(EntityType::TimeEntry) {
                     MergeStrategy::PreferNewer => {
                         if rec.data.updated_at > local.updated_at {
                             self.storage
@@ -456,6 +732,47 @@ impl<'a> Importer<'a> {
     }
 }
 
+/// The project the files in `dir` were exported from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedProject {
+    /// The path most records were exported under.
+    pub project_path: String,
+    /// ID of the project row plans refer to, if there are plans.
+    pub project_id: Option<String>,
+}
+
+/// Find the project the files in `dir` came from, so a checkout at a
+/// different path can tell whose records it holds and register the
+/// project under the ID its plans expect.
+///
+/// # Errors
+///
+/// Returns an error if a file exists but cannot be read.
+pub fn exported_project(dir: &Path) -> SyncResult<Option<ExportedProject>> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut project_id = None;
+    for filename in ["sessions.jsonl", "issues.jsonl", "memories.jsonl", "plans.jsonl"] {
+        let path = dir.join(filename);
+        if !path.exists() {
+            continue;
+        }
+        for line in BufReader::new(File::open(&path)?).lines() {
+            let line = line?;
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            if let Some(project_path) = value.get("project_path").and_then(serde_json::Value::as_str) {
+                *counts.entry(project_path.to_string()).or_default() += 1;
+            }
+            if let Some(id) = value.get("project_id").and_then(serde_json::Value::as_str) {
+                project_id.get_or_insert_with(|| id.to_string());
+            }
+        }
+    }
+    let project_path = counts.into_iter().max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0))).map(|(path, _)| path);
+    Ok(project_path.map(|project_path| ExportedProject { project_path, project_id }))
+}
+
 /// Merge import stats from one operation into accumulated stats.
 fn merge_stats(total: &mut ImportStats, add: &ImportStats) {
     total.sessions.created += add.sessions.created;
@@ -467,6 +784,7 @@ fn merge_stats(total: &mut ImportStats, add: &ImportStats) {
     total.issues.updated += add.issues.updated;
     total.issues.skipped += add.issues.skipped;
     total.issues.conflicts += add.issues.conflicts;
+    total.issues.renumbered += add.issues.renumbered;
 
     total.context_items.created += add.context_items.created;
     total.context_items.updated += add.context_items.updated;
@@ -497,7 +815,9 @@ fn merge_stats(total: &mut ImportStats, add: &ImportStats) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{IssueWorkflow, SessionStatus};
     use crate::storage::sqlite::Session;
+    use crate::sync::events::read_events;
     use crate::sync::file::write_jsonl;
     use tempfile::TempDir;
 
@@ -509,10 +829,11 @@ mod tests {
             branch: None,
             channel: None,
             project_path: Some("/test".to_string()),
-            status: "active".to_string(),
+            status: SessionStatus::Active,
             ended_at: None,
             created_at: 1000,
             updated_at,
+            worktree: None,
         }
     }
 
@@ -552,7 +873,7 @@ mod tests {
 
         // Create local session with older timestamp
         storage
-            .create_session("sess_1", "Local", None, Some("/test"), None, "test")
+            .create_session("sess_1", "Local", None, Some("/test"), None, None, "test")
             .unwrap();
 
         // Create JSONL with newer session
@@ -563,10 +884,11 @@ mod tests {
             branch: None,
             channel: None,
             project_path: Some("/test".to_string()),
-            status: "active".to_string(),
+            status: SessionStatus::Active,
             ended_at: None,
             created_at: 1000,
             updated_at: chrono::Utc::now().timestamp_millis() + 10000, // Future timestamp
+            worktree: None,
         };
         let record = SyncRecord::Session(SessionRecord {
             data: newer_session.clone(),
@@ -587,6 +909,41 @@ mod tests {
         assert_eq!(imported.name, "External");
     }
 
+    #[test]
+    fn test_import_renumbers_colliding_short_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = SqliteStorage::open(&temp_dir.path().join("test.db")).unwrap();
+        storage
+            .create_issue("issue_b", Some("SC-17"), "/test", "Local", None, None, None, None, None, None, "test")
+            .unwrap();
+
+        // Another machine handed out SC-17 too: the smaller ID keeps it.
+        let remote = |id: &str, title: &str| {
+            let mut issue = storage.get_issue("issue_b", None).unwrap().unwrap();
+            issue.id = id.to_string();
+            issue.title = title.to_string();
+            SyncRecord::Issue(IssueRecord {
+                content_hash: content_hash(&issue),
+                data: issue,
+                exported_at: "2025-01-20T00:00:00Z".to_string(),
+            })
+        };
+        let records = [remote("issue_a", "Remote A"), remote("issue_c", "Remote C")];
+        let jsonl_path = temp_dir.path().join("issues.jsonl");
+        write_jsonl(&jsonl_path, &records).unwrap();
+
+        let mut importer = Importer::new(&mut storage, MergeStrategy::PreferNewer);
+        let stats = importer.import(&jsonl_path).unwrap();
+        assert_eq!(stats.issues.created, 2);
+        assert_eq!(stats.issues.renumbered, 2);
+
+        let short_id = |id: &str| storage.get_issue(id, None).unwrap().unwrap().short_id.unwrap();
+        assert_eq!(short_id("issue_a"), "SC-17");
+        let (b, c) = (short_id("issue_b"), short_id("issue_c"));
+        assert!(b != "SC-17" && c != "SC-17" && b != c);
+        assert!(storage.owns_short_id(&b).unwrap());
+    }
+
     #[test]
     fn test_import_prefer_local() {
         let temp_dir = TempDir::new().unwrap();
@@ -595,7 +952,7 @@ mod tests {
 
         // Create local session
         storage
-            .create_session("sess_1", "Local", None, Some("/test"), None, "test")
+            .create_session("sess_1", "Local", None, Some("/test"), None, None, "test")
             .unwrap();
 
         // Create JSONL with different session
@@ -606,10 +963,11 @@ mod tests {
             branch: None,
             channel: None,
             project_path: Some("/test".to_string()),
-            status: "active".to_string(),
+            status: SessionStatus::Active,
             ended_at: None,
             created_at: 1000,
             updated_at: chrono::Utc::now().timestamp_millis() + 10000,
+            worktree: None,
         };
         let record = SyncRecord::Session(SessionRecord {
             data: external_session.clone(),
@@ -629,4 +987,264 @@ mod tests {
         let imported = storage.get_session("sess_1").unwrap().unwrap();
         assert_eq!(imported.name, "Local");
     }
+
+    #[test]
+    fn test_import_strategy_per_entity() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = SqliteStorage::open(&temp_dir.path().join("test.db")).unwrap();
+        storage.create_session("sess_1", "Local", None, Some("/test"), None, None, "test").unwrap();
+        storage.save_memory("mem_1", "/test", "cmd", "local", crate::model::MemoryCategory::Note, None, "test").unwrap();
+
+        let later = chrono::Utc::now().timestamp_millis() + 10000;
+        let mut session = make_session("sess_1", later);
+        session.name = "External".to_string();
+        let mut memory = storage.get_memory("/test", "cmd").unwrap().unwrap();
+        memory.value = "external".to_string();
+        memory.updated_at = later;
+        let records = [
+            SyncRecord::Session(SessionRecord {
+                content_hash: content_hash(&session),
+                data: session,
+                exported_at: "2025-01-20T00:00:00Z".to_string(),
+            }),
+            SyncRecord::Memory(MemoryRecord {
+                content_hash: content_hash(&memory),
+                data: memory,
+                exported_at: "2025-01-20T00:00:00Z".to_string(),
+            }),
+        ];
+        let jsonl_path = temp_dir.path().join("mixed.jsonl");
+        write_jsonl(&jsonl_path, &records).unwrap();
+
+        let mut strategies = MergeStrategies::new(MergeStrategy::PreferNewer);
+        strategies.apply_spec("memories=prefer-local").unwrap();
+        let mut importer = Importer::new(&mut storage, MergeStrategy::PreferNewer).with_strategies(strategies);
+        let stats = importer.import(&jsonl_path).unwrap();
+
+        assert_eq!(stats.sessions.updated, 1);
+        assert_eq!(stats.memories.skipped, 1);
+        assert_eq!(storage.get_session("sess_1").unwrap().unwrap().name, "External");
+        assert_eq!(storage.get_memory("/test", "cmd").unwrap().unwrap().value, "local");
+    }
+
+    #[test]
+    fn test_import_embeddings() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut source = SqliteStorage::open(&temp_dir.path().join("source.db")).unwrap();
+        source
+            .create_issue("i1", None, "/test", "Cache", Some("LRU cache"), None, None, None, None, None, "test")
+            .unwrap();
+        source
+            .store_entity_embedding_chunk("issue", "i1", 0, "Cache: LRU cache", &[0.25, -0.5, 1.0], "ollama", "m")
+            .unwrap();
+        let export_dir = temp_dir.path().join("export");
+        crate::sync::Exporter::with_output_dir(&mut source, "/test".to_string(), export_dir.clone())
+            .with_embeddings(true)
+            .export(true)
+            .unwrap();
+
+        let mut storage = SqliteStorage::open(&temp_dir.path().join("test.db")).unwrap();
+        let mut importer = Importer::new(&mut storage, MergeStrategy::PreferNewer);
+        let stats = importer.import_all(&export_dir).unwrap();
+        assert_eq!(stats.issues.created, 1);
+        assert_eq!(stats.embeddings.imported, 1);
+        let models = storage.embedding_models(false).unwrap();
+        assert_eq!((models[0].model.as_str(), models[0].dimensions), ("m", 3));
+
+        // A machine embedding with another model leaves them out
+        let mut importer = Importer::new(&mut storage, MergeStrategy::PreferNewer)
+            .with_embedding_model(Some(("ollama".to_string(), "other".to_string())));
+        let stats = importer.import_embeddings(&export_dir.join(EMBEDDINGS_FILE)).unwrap();
+        assert_eq!((stats.imported, stats.other_model), (0, 1));
+    }
+
+    #[test]
+    fn test_import_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut source = SqliteStorage::open(&temp_dir.path().join("source.db")).unwrap();
+        source
+            .create_issue("i1", None, "/test", "Cache", None, None, None, None, None, None, "alice")
+            .unwrap();
+        source.claim_issue("i1", &IssueWorkflow::default(), "alice").unwrap();
+        let export_dir = temp_dir.path().join("export");
+        let export = |storage: &mut SqliteStorage| {
+            crate::sync::Exporter::with_output_dir(storage, "/test".to_string(), export_dir.clone())
+                .with_events(true)
+                .export(true)
+                .unwrap()
+        };
+        assert_eq!(export(&mut source).events, 2);
+
+        // Pruned events stay in the file; only new ones are appended
+        let ids: Vec<i64> = source.get_project_events("/test").unwrap().iter().map(|e| e.id).collect();
+        source.delete_events(&ids[1..]).unwrap();
+        source.release_issue("i1", &IssueWorkflow::default(), "alice").unwrap();
+        assert_eq!(export(&mut source).events, 1);
+        let events_path = export_dir.join(EVENTS_FILE);
+        assert_eq!(read_events(&events_path).unwrap().len(), 3);
+
+        let mut storage = SqliteStorage::open(&temp_dir.path().join("test.db")).unwrap();
+        let mut importer = Importer::new(&mut storage, MergeStrategy::PreferNewer);
+        let stats = importer.import_all(&export_dir).unwrap();
+        assert_eq!(stats.issues.created, 1);
+        assert_eq!(stats.events.imported, 3);
+        let stats = importer.import_events(&events_path).unwrap();
+        assert_eq!((stats.imported, stats.existing), (0, 3));
+
+        // Events local retention would prune aren't brought back
+        let mut storage = SqliteStorage::open(&temp_dir.path().join("recent.db")).unwrap();
+        let mut importer = Importer::new(&mut storage, MergeStrategy::PreferNewer).with_events_since(Some(i64::MAX));
+        let stats = importer.import_events(&events_path).unwrap();
+        assert_eq!((stats.imported, stats.expired), (1, 2), "the create is always kept");
+    }
+
+    #[test]
+    fn test_exported_project() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(exported_project(temp_dir.path()).unwrap(), None);
+
+        let records: Vec<SyncRecord> = ["sess_1", "sess_2"]
+            .into_iter()
+            .map(|id| {
+                let session = make_session(id, 1000);
+                SyncRecord::Session(SessionRecord {
+                    content_hash: content_hash(&session),
+                    data: session,
+                    exported_at: "2025-01-20T00:00:00Z".to_string(),
+                })
+            })
+            .collect();
+        write_jsonl(&temp_dir.path().join("sessions.jsonl"), &records).unwrap();
+        std::fs::write(temp_dir.path().join("plans.jsonl"), "{\"project_path\":\"/other\",\"project_id\":\"proj_1\"}\n")
+            .unwrap();
+        let project = exported_project(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(project.project_path, "/test");
+        assert_eq!(project.project_id.as_deref(), Some("proj_1"));
+    }
+
+    fn session_line(id: &str) -> String {
+        let session = make_session(id, 1000);
+        let record = SyncRecord::Session(SessionRecord {
+            content_hash: content_hash(&session),
+            data: session,
+            exported_at: "2025-01-20T00:00:00Z".to_string(),
+        });
+        serde_json::to_string(&record).unwrap()
+    }
+
+    #[test]
+    fn test_import_skips_bad_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let long = session_line("sess_long").replace("\"Test\"", &format!("\"{}\"", "x".repeat(100)));
+        let mut content = [session_line("sess_1"), "not json".to_string(), "{\"type\":\"session\"}".to_string(), long]
+            .join("\n")
+            .into_bytes();
+        content.extend_from_slice(b"\n\xff\xfe\n\n");
+        content.extend_from_slice(session_line("sess_2").as_bytes());
+        std::fs::write(temp_dir.path().join("sessions.jsonl"), content).unwrap();
+
+        let mut storage = SqliteStorage::open_memory().unwrap();
+        let result = Importer::new(&mut storage, MergeStrategy::PreferNewer)
+            .with_mode(ImportMode::Strict)
+            .with_max_field_len(64)
+            .import_all(temp_dir.path());
+        let Err(SyncError::InvalidLines(errors)) = result else { panic!("strict import should fail") };
+        assert_eq!(errors.iter().map(|e| e.line).collect::<Vec<_>>(), [2, 3, 4, 5]);
+        assert!(storage.get_session("sess_1").unwrap().is_none());
+
+        let stats = Importer::new(&mut storage, MergeStrategy::PreferNewer)
+            .with_max_field_len(64)
+            .import_all(temp_dir.path())
+            .unwrap();
+        assert_eq!(stats.sessions.created, 2);
+        assert_eq!(stats.errors.len(), 4);
+        assert_eq!(stats.errors[0].file, "sessions.jsonl");
+        assert!(stats.errors[2].message.contains("field `name` is 100 bytes"), "{}", stats.errors[2].message);
+        assert!(storage.get_session("sess_2").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_import_checks_format_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("sessions.jsonl");
+        let mut storage = SqliteStorage::open_memory().unwrap();
+
+        // Files from before headers existed still import
+        std::fs::write(&path, session_line("sess_1") + "\n").unwrap();
+        let stats = Importer::new(&mut storage, MergeStrategy::PreferNewer).import_all(temp_dir.path()).unwrap();
+        assert_eq!(stats.sessions.created, 1);
+
+        let newer = format!(r#"{{"type":"meta","schema_version":{},"producer":"sc 9.0.0"}}"#, crate::sync::SCHEMA_VERSION + 1);
+        std::fs::write(&path, format!("{newer}\n{}\n", session_line("sess_2"))).unwrap();
+        let result = Importer::new(&mut storage, MergeStrategy::PreferNewer).import_all(temp_dir.path());
+        assert!(matches!(result, Err(SyncError::UnsupportedVersion { version, .. }) if version == crate::sync::SCHEMA_VERSION + 1));
+        assert!(storage.get_session("sess_2").unwrap().is_none());
+    }
+
+    /// How a generated line is damaged.
+    #[derive(Debug, Clone)]
+    enum Damage {
+        None,
+        Truncate(usize),
+        Insert(usize, Vec<u8>),
+        Replace(Vec<u8>),
+    }
+
+    fn damage() -> impl proptest::strategy::Strategy<Value = Damage> {
+        use proptest::prelude::*;
+        prop_oneof![
+            2 => Just(Damage::None),
+            1 => any::<usize>().prop_map(Damage::Truncate),
+            1 => (any::<usize>(), proptest::collection::vec(any::<u8>(), 1..8)).prop_map(|(at, b)| Damage::Insert(at, b)),
+            1 => proptest::collection::vec(any::<u8>(), 0..64).prop_map(Damage::Replace),
+        ]
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(64))]
+
+        /// Whatever the damage, a lenient import accounts for every
+        /// non-blank line as a record or an error, keeps every intact
+        /// record, and a strict import fails exactly when lines were bad.
+        #[test]
+        fn prop_import_survives_damaged_lines(damages in proptest::collection::vec(damage(), 1..12)) {
+            let temp_dir = TempDir::new().unwrap();
+            let mut content = Vec::new();
+            for (i, damage) in damages.iter().enumerate() {
+                let mut line = session_line(&format!("sess_{i}")).into_bytes();
+                match damage {
+                    Damage::None => {}
+                    Damage::Truncate(at) => line.truncate(at % line.len()),
+                    Damage::Insert(at, bytes) => {
+                        let at = at % line.len();
+                        line.splice(at..at, bytes.iter().copied());
+                    }
+                    Damage::Replace(bytes) => line.clone_from(bytes),
+                }
+                content.extend(line.into_iter().filter(|b| *b != b'\n'));
+                content.push(b'\n');
+            }
+            let path = temp_dir.path().join("sessions.jsonl");
+            std::fs::write(&path, &content).unwrap();
+            let lines = content
+                .split(|b| *b == b'\n')
+                .filter(|l| !String::from_utf8_lossy(l).trim().is_empty())
+                .count();
+
+            let mut storage = SqliteStorage::open_memory().unwrap();
+            let strict = Importer::new(&mut storage, MergeStrategy::PreferNewer)
+                .with_mode(ImportMode::Strict)
+                .import_all(temp_dir.path());
+            let stats = Importer::new(&mut storage, MergeStrategy::PreferNewer).import_all(temp_dir.path()).unwrap();
+
+            proptest::prop_assert_eq!(stats.sessions.total() + stats.errors.len(), lines);
+            proptest::prop_assert_eq!(strict.is_err(), !stats.errors.is_empty());
+            for (i, damage) in damages.iter().enumerate() {
+                if matches!(damage, Damage::None) {
+                    let id = format!("sess_{i}");
+                    proptest::prop_assert!(storage.get_session(&id).unwrap().is_some(), "{} missing", id);
+                }
+            }
+        }
+    }
 }
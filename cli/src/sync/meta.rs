@@ -0,0 +1,155 @@
+//! Format headers of the sync files.
+//!
+//! Every JSONL file `sc sync export` writes starts with a header line:
+//!
+//! ```text
+//! {"type":"meta","schema_version":1,"producer":"sc 0.1.31"}
+//! ```
+//!
+//! Readers skip the header. A file from a newer format is refused with an
+//! error asking for a newer `sc`, rather than being misread. Lines from
+//! an older format pass through the [`UPCONVERTERS`] before they are
+//! parsed. Files without a header predate it and are read as
+//! [`LEGACY_VERSION`].
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::sync::types::{SyncError, SyncResult};
+
+/// Format version this build writes, and the newest it reads. Bump it when
+/// a record changes in a way older builds would misread, and add an
+/// upconverter from the previous version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Version of files written before headers existed.
+pub const LEGACY_VERSION: u32 = 1;
+
+/// First line of a sync file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "meta")]
+pub struct FileHeader {
+    pub schema_version: u32,
+    /// `sc` and its version, e.g. `sc 0.1.31`.
+    pub producer: String,
+}
+
+impl FileHeader {
+    /// The header this build writes.
+    #[must_use]
+    pub fn current() -> Self {
+        Self { schema_version: SCHEMA_VERSION, producer: format!("sc {}", env!("CARGO_PKG_VERSION")) }
+    }
+
+    /// The header this build writes, as a JSONL line with its newline.
+    #[must_use]
+    pub fn line() -> String {
+        // A struct of a number and a string always serializes
+        let mut line = serde_json::to_string(&Self::current()).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+
+    /// The format version of a file whose first non-blank line is `line`,
+    /// if that line is a header.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SyncError::UnsupportedVersion`] if `file` is in a newer
+    /// format than this build reads.
+    pub fn detect(line: &str, file: &str) -> SyncResult<Option<u32>> {
+        if !line.contains("\"meta\"") {
+            return Ok(None);
+        }
+        let Ok(header) = serde_json::from_str::<Self>(line) else {
+            return Ok(None);
+        };
+        if header.schema_version > SCHEMA_VERSION {
+            return Err(SyncError::UnsupportedVersion {
+                file: file.to_string(),
+                version: header.schema_version,
+                producer: header.producer,
+            });
+        }
+        Ok(Some(header.schema_version))
+    }
+}
+
+/// Rewrites a line of one format version into the next.
+struct Upconverter {
+    /// Version the line is in before the conversion.
+    from: u32,
+    convert: fn(&mut Value),
+}
+
+/// Conversions between format versions, oldest first. Empty while there
+/// has only been one.
+const UPCONVERTERS: &[Upconverter] = &[];
+
+/// Parse a line of a file in format `version` as `T`, upconverting it
+/// first if the format is older.
+///
+/// # Errors
+///
+/// Returns an error if the line isn't valid JSON or doesn't match `T`.
+pub fn parse_line<T: serde::de::DeserializeOwned>(line: &str, version: u32) -> serde_json::Result<T> {
+    if version >= SCHEMA_VERSION {
+        return serde_json::from_str(line);
+    }
+    let mut value = serde_json::from_str(line)?;
+    upconvert(UPCONVERTERS, &mut value, version);
+    serde_json::from_value(value)
+}
+
+/// Parse an already decoded line; see [`parse_line`].
+///
+/// # Errors
+///
+/// Returns an error if the value doesn't match `T`.
+pub fn parse_value<T: serde::de::DeserializeOwned>(mut value: Value, version: u32) -> serde_json::Result<T> {
+    upconvert(UPCONVERTERS, &mut value, version);
+    serde_json::from_value(value)
+}
+
+fn upconvert(converters: &[Upconverter], value: &mut Value, version: u32) {
+    for converter in converters.iter().filter(|c| c.from >= version) {
+        (converter.convert)(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_header() {
+        assert_eq!(FileHeader::detect(FileHeader::line().trim(), "issues.jsonl").unwrap(), Some(SCHEMA_VERSION));
+        assert_eq!(FileHeader::detect(r#"{"type":"session","id":"meta"}"#, "sessions.jsonl").unwrap(), None);
+
+        let newer = format!(r#"{{"type":"meta","schema_version":{},"producer":"sc 9.0.0"}}"#, SCHEMA_VERSION + 1);
+        let err = FileHeader::detect(&newer, "issues.jsonl").unwrap_err();
+        assert!(err.to_string().contains("sc 9.0.0"), "{err}");
+        assert!(err.to_string().contains("upgrade sc"), "{err}");
+    }
+
+    #[test]
+    fn test_upconvert_applies_later_converters_in_order() {
+        fn rename_title(value: &mut Value) {
+            if let Some(title) = value.as_object_mut().and_then(|o| o.remove("name")) {
+                value["title"] = title;
+            }
+        }
+        fn add_labels(value: &mut Value) {
+            value["labels"] = serde_json::json!([]);
+        }
+        let converters = [Upconverter { from: 1, convert: rename_title }, Upconverter { from: 2, convert: add_labels }];
+
+        let mut value = serde_json::json!({"name": "Old"});
+        upconvert(&converters, &mut value, 1);
+        assert_eq!(value, serde_json::json!({"title": "Old", "labels": []}));
+
+        let mut value = serde_json::json!({"name": "Newer"});
+        upconvert(&converters, &mut value, 2);
+        assert_eq!(value, serde_json::json!({"name": "Newer", "labels": []}));
+    }
+}
@@ -6,6 +6,9 @@
 //! - **Import**: JSONL files → SQLite with merge strategies
 //! - **Hashing**: SHA256 content hashing for change detection
 //! - **Status**: View pending exports and file statistics
+//! - **Embeddings**: Optional `embeddings.jsonl` sidecar of packed vectors
+//! - **Events**: Optional append-only `events.jsonl` audit log
+//! - **Verify**: Compare the export with the database and repair it
 //!
 //! # Architecture
 //!
@@ -16,8 +19,10 @@
 //!
 //! # File Format
 //!
-//! Each JSONL file contains one record per line with a `type` tag:
+//! Each JSONL file starts with a `meta` header giving its format version,
+//! followed by one record per line with a `type` tag:
 //! ```json
+//! {"type":"meta","schema_version":1,"producer":"sc 0.1.31"}
 //! {"type":"session","id":"sess_123","name":"My Session",...,"content_hash":"abc","exported_at":"2025-01-20T10:00:00Z"}
 //! ```
 //!
@@ -38,24 +43,32 @@
 //! let status = status::get_sync_status(&storage, &export_dir)?;
 //! ```
 
+mod embeddings;
+mod events;
 mod export;
 mod file;
 mod hash;
 mod import;
+mod meta;
 mod status;
 mod types;
+mod verify;
 
 // Re-export main types and functions
+pub use embeddings::{decode_vectors, encode_vectors, EMBEDDINGS_FILE};
+pub use events::{event_id, EVENTS_FILE};
 pub use export::{default_export_dir, project_export_dir, Exporter};
 pub use file::{
     append_jsonl, atomic_write, count_lines, ensure_gitignore, file_size, gitignore_content,
-    read_jsonl, write_jsonl,
+    read_jsonl, read_text_lines, write_jsonl, write_jsonl_lines,
 };
 pub use hash::{content_hash, has_changed};
-pub use import::Importer;
-pub use status::{get_sync_status, print_status};
+pub use import::{exported_project, ExportedProject, Importer};
+pub use meta::{FileHeader, LEGACY_VERSION, SCHEMA_VERSION};
+pub use status::{format_size, get_sync_status, print_status};
 pub use types::{
-    CheckpointRecord, ContextItemRecord, DeletionRecord, EntityStats, EntityType, ExportFileInfo,
-    ExportStats, ImportStats, IssueRecord, MemoryRecord, MergeStrategy, PlanRecord, SessionRecord,
-    SyncError, SyncRecord, SyncResult, SyncStatus,
+    CheckpointRecord, ContextItemRecord, DEFAULT_MAX_FIELD_LEN, DeletionRecord, EmbeddingImportStats, EmbeddingRecord,
+    EntityStats, EntityType, EventImportStats, EventRecord, ExportFileInfo, ExportStats, ImportMode, ImportStats, IssueRecord,
+    LineError, MemoryRecord, MergeStrategies, MergeStrategy, PlanRecord, SessionRecord, SyncError, SyncRecord, SyncResult, SyncStatus,
 };
+pub use verify::{repair, verify, Divergence, DivergenceKind, VerifyReport};
@@ -4,8 +4,15 @@
 //! including pending exports and existing export files.
 //!
 //! Status is project-scoped, showing only data for the specified project.
+//!
+//! Besides the counts, status reports how far the export files have drifted
+//! from the database: when they were last exported and imported, how old
+//! the oldest unexported change is, how much newer the newest record is than
+//! the newest file, and a freshness score from 0 to 1 that hooks can
+//! compare against a threshold to decide when to export.
 
 use std::path::Path;
+use std::time::UNIX_EPOCH;
 
 use colored::Colorize;
 
@@ -14,7 +21,7 @@ use crate::sync::file::{count_lines, file_size};
 use crate::sync::types::{ExportFileInfo, SyncError, SyncResult, SyncStatus};
 
 /// Known export file names.
-const EXPORT_FILES: [&str; 7] = [
+const EXPORT_FILES: [&str; 9] = [
     "sessions.jsonl",
     "issues.jsonl",
     "context_items.jsonl",
@@ -22,6 +29,8 @@ const EXPORT_FILES: [&str; 7] = [
     "checkpoints.jsonl",
     "plans.jsonl",
     "deletions.jsonl",
+    "embeddings.jsonl",
+    "events.jsonl",
 ];
 
 /// Get the current sync status for a project.
@@ -56,9 +65,23 @@ pub fn get_sync_status(
         .get_dirty_context_items_by_project(project_path)
         .map_err(|e| SyncError::Database(e.to_string()))?
         .len();
+    let dirty_plans = storage
+        .get_dirty_plans_by_project(project_path)
+        .map_err(|e| SyncError::Database(e.to_string()))?
+        .len();
+    let dirty_time_entries = storage
+        .get_dirty_time_entries_by_project(project_path)
+        .map_err(|e| SyncError::Database(e.to_string()))?
+        .len();
     let pending_deletions = storage
         .count_pending_deletions(project_path)
         .map_err(|e| SyncError::Database(e.to_string()))?;
+    let oldest_unexported_at = storage
+        .oldest_unexported_change(project_path)
+        .map_err(|e| SyncError::Database(e.to_string()))?;
+    let newest_record_at = storage
+        .newest_record_at(project_path)
+        .map_err(|e| SyncError::Database(e.to_string()))?;
 
     // Get total counts for this project (for backfill detection)
     let counts = storage
@@ -67,9 +90,11 @@ pub fn get_sync_status(
 
     // Check export files
     let mut export_files = Vec::new();
+    let mut newest_file_at = None;
     for filename in EXPORT_FILES {
         let path = export_dir.join(filename);
         if path.exists() {
+            newest_file_at = newest_file_at.max(modified_at(&path));
             let size = file_size(&path);
             let line_count = count_lines(&path).unwrap_or(0);
             export_files.push(ExportFileInfo {
@@ -83,13 +108,24 @@ pub fn get_sync_status(
     let has_export_files = !export_files.is_empty();
 
     // Detect if backfill would happen (data exists but nothing dirty)
-    let total_dirty = dirty_sessions + dirty_issues + dirty_context_items;
+    let total_dirty = dirty_sessions + dirty_issues + dirty_context_items + dirty_plans + dirty_time_entries;
     let needs_backfill = total_dirty == 0 && counts.total() > 0 && !has_export_files;
 
+    let skew_ms = newest_record_at.zip(newest_file_at).map(|(record, file)| (record - file).max(0));
+    let freshness = if counts.total() == 0 && total_dirty == 0 {
+        1.0
+    } else if has_export_files {
+        freshness(total_dirty + pending_deletions, counts.total() + pending_deletions, skew_ms.unwrap_or(0))
+    } else {
+        0.0
+    };
+
     Ok(SyncStatus {
         dirty_sessions,
         dirty_issues,
         dirty_context_items,
+        dirty_plans,
+        dirty_time_entries,
         pending_deletions,
         total_sessions: counts.sessions,
         total_issues: counts.issues,
@@ -97,9 +133,53 @@ pub fn get_sync_status(
         needs_backfill,
         has_export_files,
         export_files,
+        last_export_at: last_sync_at(storage, "export", export_dir)?,
+        last_import_at: last_sync_at(storage, "import", export_dir)?,
+        oldest_unexported_at,
+        newest_record_at,
+        newest_file_at,
+        skew_ms,
+        freshness,
     })
 }
 
+/// Share of the project's records that are exported, halved for every day
+/// the newest record is ahead of the newest file, to two decimals.
+#[allow(clippy::cast_precision_loss)]
+fn freshness(pending: usize, total: usize, skew_ms: i64) -> f64 {
+    const DAY_MS: f64 = 86_400_000.0;
+    let exported = 1.0 - pending.min(total) as f64 / total.max(1) as f64;
+    let decay = 0.5_f64.powf(skew_ms as f64 / DAY_MS);
+    (exported * decay * 100.0).round() / 100.0
+}
+
+/// Modification time of a file (Unix ms).
+fn modified_at(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    i64::try_from(modified.duration_since(UNIX_EPOCH).ok()?.as_millis()).ok()
+}
+
+/// `sync_meta` key for when the files in `dir` were last exported or
+/// imported (`kind`).
+fn sync_time_key(kind: &str, dir: &Path) -> String {
+    format!("last_{kind}_at:{}", dir.display())
+}
+
+/// When the files in `dir` were last exported or imported (Unix ms).
+fn last_sync_at(storage: &SqliteStorage, kind: &str, dir: &Path) -> SyncResult<Option<i64>> {
+    let value = storage
+        .sync_meta(&sync_time_key(kind, dir))
+        .map_err(|e| SyncError::Database(e.to_string()))?;
+    Ok(value.and_then(|v| v.parse().ok()))
+}
+
+/// Remember that the files in `dir` were just exported or imported.
+pub(crate) fn record_sync_time(storage: &SqliteStorage, kind: &str, dir: &Path) -> SyncResult<()> {
+    storage
+        .set_sync_meta(&sync_time_key(kind, dir), &chrono::Utc::now().timestamp_millis().to_string())
+        .map_err(|e| SyncError::Database(e.to_string()))
+}
+
 /// Print sync status to stdout in a human-readable format.
 pub fn print_status(status: &SyncStatus) {
     println!("{}", "Sync Status".bold().underline());
@@ -123,8 +203,12 @@ pub fn print_status(status: &SyncStatus) {
     }
 
     // Dirty records section
-    let total_dirty =
-        status.dirty_sessions + status.dirty_issues + status.dirty_context_items + status.pending_deletions;
+    let total_dirty = status.dirty_sessions
+        + status.dirty_issues
+        + status.dirty_context_items
+        + status.dirty_plans
+        + status.dirty_time_entries
+        + status.pending_deletions;
     if total_dirty > 0 {
         println!("{}", "Pending Export:".yellow().bold());
         if status.dirty_sessions > 0 {
@@ -136,9 +220,18 @@ pub fn print_status(status: &SyncStatus) {
         if status.dirty_context_items > 0 {
             println!("  Context Items: {}", status.dirty_context_items);
         }
+        if status.dirty_plans > 0 {
+            println!("  Plans:         {}", status.dirty_plans);
+        }
+        if status.dirty_time_entries > 0 {
+            println!("  Time Entries:  {}", status.dirty_time_entries);
+        }
         if status.pending_deletions > 0 {
             println!("  Deletions:     {}", status.pending_deletions);
         }
+        if let Some(oldest) = status.oldest_unexported_at {
+            println!("  Oldest change: {}", format_age(oldest));
+        }
         println!("  {}: {}", "Total".bold(), total_dirty);
         println!();
         println!(
@@ -186,10 +279,37 @@ pub fn print_status(status: &SyncStatus) {
             );
         }
     }
+
+    println!();
+    println!("{}", "Freshness:".blue().bold());
+    let last = |at: Option<i64>| at.map_or_else(|| "never".to_string(), format_age);
+    println!("  Last export:   {}", last(status.last_export_at));
+    println!("  Last import:   {}", last(status.last_import_at));
+    if let Some(skew) = status.skew_ms.filter(|skew| *skew > 0) {
+        println!("  Records ahead of files by {}", format_span(skew));
+    }
+    println!("  Score:         {:.2}", status.freshness);
+}
+
+/// `5m ago`, `3h ago`, `2d ago`.
+fn format_age(at: i64) -> String {
+    let age = chrono::Utc::now().timestamp_millis() - at;
+    if age < 60_000 { "just now".to_string() } else { format!("{} ago", format_span(age)) }
+}
+
+/// A duration in the largest whole unit: `5m`, `3h`, `2d`.
+fn format_span(ms: i64) -> String {
+    let minutes = ms / 60_000;
+    match minutes {
+        m if m >= 2 * 24 * 60 => format!("{}d", m / (24 * 60)),
+        m if m >= 60 => format!("{}h", m / 60),
+        m => format!("{m}m"),
+    }
 }
 
 /// Format a byte size as a human-readable string.
-fn format_size(bytes: u64) -> String {
+#[must_use]
+pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = 1024 * KB;
 
@@ -245,7 +365,7 @@ mod tests {
 
         // Create a session for this project (triggers dirty tracking)
         storage
-            .create_session("sess_1", "Test Session", None, Some(project_path), None, "test")
+            .create_session("sess_1", "Test Session", None, Some(project_path), None, None, "test")
             .unwrap();
 
         let status = get_sync_status(&storage, temp_dir.path(), project_path).unwrap();
@@ -256,6 +376,41 @@ mod tests {
         assert!(!status.needs_backfill);
     }
 
+    #[test]
+    fn test_sync_status_drift() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = SqliteStorage::open(&temp_dir.path().join("test.db")).unwrap();
+        let project_path = "/test/project";
+        let export_dir = temp_dir.path().join("export");
+        storage
+            .create_session("sess_1", "Test Session", None, Some(project_path), None, None, "test")
+            .unwrap();
+
+        let status = get_sync_status(&storage, &export_dir, project_path).unwrap();
+        assert!(status.oldest_unexported_at.is_some());
+        assert!(status.last_export_at.is_none());
+        assert!(status.skew_ms.is_none());
+        assert!(status.freshness.abs() < f64::EPSILON);
+
+        crate::sync::Exporter::with_output_dir(&mut storage, project_path.to_string(), export_dir.clone())
+            .export(false)
+            .unwrap();
+        let status = get_sync_status(&storage, &export_dir, project_path).unwrap();
+        assert_eq!(status.dirty_sessions, 0);
+        assert!(status.oldest_unexported_at.is_none());
+        assert!(status.last_export_at.is_some());
+        assert_eq!(status.skew_ms, Some(0));
+        assert!((status.freshness - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_freshness() {
+        assert!((freshness(0, 10, 0) - 1.0).abs() < f64::EPSILON);
+        assert!((freshness(5, 10, 0) - 0.5).abs() < f64::EPSILON);
+        assert!((freshness(0, 10, 86_400_000) - 0.5).abs() < f64::EPSILON);
+        assert!(freshness(20, 10, 0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_get_sync_status_with_export_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -284,7 +439,7 @@ mod tests {
 
         // Create a session
         storage
-            .create_session("sess_1", "Test Session", None, Some(project_path), None, "test")
+            .create_session("sess_1", "Test Session", None, Some(project_path), None, None, "test")
             .unwrap();
 
         // Clear dirty flags (simulating old data without dirty tracking)
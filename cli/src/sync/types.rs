@@ -3,10 +3,14 @@
 //! This module defines the record types used in JSONL files for synchronization.
 //! Each record type wraps the underlying data model with sync metadata.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::json_schema::json_schema;
 use crate::model::Plan;
-use crate::storage::sqlite::{Checkpoint, ContextItem, Issue, Memory, Session, TimeEntry};
+use crate::storage::sqlite::{Checkpoint, ContextItem, Issue, Memory, Session, SyncDeletion, TimeEntry};
+use crate::sync::hash::content_hash;
 
 /// Tagged union for JSONL records.
 ///
@@ -32,6 +36,51 @@ pub enum SyncRecord {
     TimeEntry(TimeEntryRecord),
 }
 
+impl SyncRecord {
+    /// ID of the wrapped record.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Session(rec) => &rec.data.id,
+            Self::Issue(rec) => &rec.data.id,
+            Self::ContextItem(rec) => &rec.data.id,
+            Self::Memory(rec) => &rec.data.id,
+            Self::Checkpoint(rec) => &rec.data.id,
+            Self::Plan(rec) => &rec.data.id,
+            Self::TimeEntry(rec) => &rec.data.id,
+        }
+    }
+
+    /// The `content_hash` stored with the record.
+    #[must_use]
+    pub fn stored_hash(&self) -> &str {
+        match self {
+            Self::Session(rec) => &rec.content_hash,
+            Self::Issue(rec) => &rec.content_hash,
+            Self::ContextItem(rec) => &rec.content_hash,
+            Self::Memory(rec) => &rec.content_hash,
+            Self::Checkpoint(rec) => &rec.content_hash,
+            Self::Plan(rec) => &rec.content_hash,
+            Self::TimeEntry(rec) => &rec.content_hash,
+        }
+    }
+
+    /// Hash of the record's data as it is now, which matches
+    /// [`stored_hash`](Self::stored_hash) unless the line was altered.
+    #[must_use]
+    pub fn data_hash(&self) -> String {
+        match self {
+            Self::Session(rec) => content_hash(&rec.data),
+            Self::Issue(rec) => content_hash(&rec.data),
+            Self::ContextItem(rec) => content_hash(&rec.data),
+            Self::Memory(rec) => content_hash(&rec.data),
+            Self::Checkpoint(rec) => content_hash(&rec.data),
+            Self::Plan(rec) => content_hash(&rec.data),
+            Self::TimeEntry(rec) => content_hash(&rec.data),
+        }
+    }
+}
+
 /// Session with sync metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionRecord {
@@ -141,8 +190,79 @@ pub struct DeletionRecord {
     pub deleted_by: String,
 }
 
+impl DeletionRecord {
+    /// The `deletions.jsonl` line for a locally recorded deletion.
+    #[must_use]
+    pub fn from_deletion(del: &SyncDeletion) -> Self {
+        Self {
+            entity_type: del.entity_type.parse::<EntityType>().unwrap_or(EntityType::Session),
+            entity_id: del.entity_id.clone(),
+            project_path: del.project_path.clone(),
+            // Convert milliseconds to seconds for chrono
+            deleted_at: chrono::DateTime::from_timestamp(del.deleted_at / 1000, 0)
+                .map_or_else(|| del.deleted_at.to_string(), |dt| dt.to_rfc3339()),
+            deleted_by: del.deleted_by.clone(),
+        }
+    }
+}
+
+/// Embeddings of one record, a line of `embeddings.jsonl`.
+///
+/// Only written by `sc sync export --include-embeddings`. `vectors` holds
+/// every chunk's vector as little-endian `f32`s, one chunk after another,
+/// zstd-compressed and base64-encoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRecord {
+    /// Type of the embedded record.
+    pub entity_type: EntityType,
+    /// ID of the embedded record.
+    pub entity_id: String,
+    /// `updated_at` of the record version that was embedded.
+    pub source_updated_at: i64,
+    /// Provider that made the vectors.
+    pub provider: String,
+    /// Model that made the vectors.
+    pub model: String,
+    /// Length of each vector.
+    pub dimensions: usize,
+    /// Text of each chunk, in chunk order.
+    pub chunks: Vec<String>,
+    /// Encoded vectors.
+    pub vectors: String,
+}
+
+/// One audit event, a line of `events.jsonl`.
+///
+/// Only written when `sync_events` is on. Local event IDs are plain row
+/// numbers, so `id` is derived from the event's content instead and is
+/// the same on every machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    /// Content-derived event ID (`evt_` and 16 hex digits).
+    pub id: String,
+    /// Type of the record the event is about (`issue`, `project`, ...).
+    pub entity_type: String,
+    /// ID of the record the event is about.
+    pub entity_id: String,
+    /// What happened (`issue_claimed`, `item_updated`, ...).
+    pub event_type: String,
+    /// Actor who caused the event.
+    pub actor: String,
+    /// Value before the change, if recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<String>,
+    /// Value after the change, if recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<String>,
+    /// Free-text comment, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// When the event happened (Unix ms).
+    pub created_at: i64,
+}
+
 /// Entity types for deletion tracking.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EntityType {
     /// A session.
@@ -175,6 +295,29 @@ impl std::fmt::Display for EntityType {
     }
 }
 
+impl EntityType {
+    /// Parse the name of an entity type as users write it: singular or
+    /// plural, with `items` and `time` as short forms.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if the name isn't an entity type.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.replace('-', "_").as_str() {
+            "session" | "sessions" => Ok(Self::Session),
+            "issue" | "issues" => Ok(Self::Issue),
+            "item" | "items" | "context_item" | "context_items" => Ok(Self::ContextItem),
+            "memory" | "memories" => Ok(Self::Memory),
+            "checkpoint" | "checkpoints" => Ok(Self::Checkpoint),
+            "plan" | "plans" => Ok(Self::Plan),
+            "time" | "time_entry" | "time_entries" => Ok(Self::TimeEntry),
+            _ => Err(format!(
+                "Unknown entity type: {name}. Valid values: sessions, issues, items, memories, checkpoints, plans, time_entries"
+            )),
+        }
+    }
+}
+
 impl std::str::FromStr for EntityType {
     type Err = String;
 
@@ -207,6 +350,106 @@ pub enum MergeStrategy {
     PreferExternal,
 }
 
+impl MergeStrategy {
+    pub const ALL: [Self; 3] = [Self::PreferNewer, Self::PreferLocal, Self::PreferExternal];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::PreferNewer => "prefer-newer",
+            Self::PreferLocal => "prefer-local",
+            Self::PreferExternal => "prefer-external",
+        }
+    }
+}
+
+impl std::str::FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|strategy| strategy.as_str() == s.replace('_', "-")).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|strategy| strategy.as_str()).collect();
+            format!("Unknown merge strategy: {s}. Valid values: {}", names.join(", "))
+        })
+    }
+}
+
+/// Merge strategy by entity type, falling back to a default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeStrategies {
+    default: MergeStrategy,
+    by_entity: HashMap<EntityType, MergeStrategy>,
+}
+
+impl MergeStrategies {
+    /// Use `default` for every entity type.
+    #[must_use]
+    pub fn new(default: MergeStrategy) -> Self {
+        Self { default, by_entity: HashMap::new() }
+    }
+
+    /// The strategy for records of `entity`.
+    #[must_use]
+    pub fn for_entity(&self, entity: EntityType) -> MergeStrategy {
+        self.by_entity.get(&entity).copied().unwrap_or(self.default)
+    }
+
+    /// Use `strategy` for records of `entity`.
+    pub fn set(&mut self, entity: EntityType, strategy: MergeStrategy) {
+        self.by_entity.insert(entity, strategy);
+    }
+
+    /// Apply a spec like `issues=prefer-local,items=prefer-newer`. An entry
+    /// without an entity (`prefer-local`) changes the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the entity or strategy that isn't known.
+    pub fn apply_spec(&mut self, spec: &str) -> Result<(), String> {
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once('=') {
+                Some((entity, strategy)) => {
+                    self.set(EntityType::from_name(entity.trim())?, strategy.trim().parse()?);
+                }
+                None => self.default = entry.parse()?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What an import does with lines it cannot use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Skip bad lines, import the rest and report what was skipped.
+    #[default]
+    Lenient,
+    /// Check every line first and import nothing if any is bad.
+    Strict,
+}
+
+/// Largest string field an import accepts by default, in bytes.
+pub const DEFAULT_MAX_FIELD_LEN: usize = 1024 * 1024;
+
+/// A line an import could not use.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineError {
+    /// File name, e.g. `issues.jsonl`.
+    pub file: String,
+    /// Line number (1-indexed).
+    pub line: usize,
+    /// Why the line was rejected.
+    pub message: String,
+}
+
+json_schema!(LineError { file: String, line: usize, message: String });
+
+impl std::fmt::Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.message)
+    }
+}
+
 /// Statistics for an export operation.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct ExportStats {
@@ -226,8 +469,25 @@ pub struct ExportStats {
     pub time_entries: usize,
     /// Number of deletions exported.
     pub deletions: usize,
+    /// Number of records whose embeddings were exported (not counted in totals).
+    pub embeddings: usize,
+    /// Number of events appended to `events.jsonl` (not counted in totals).
+    pub events: usize,
 }
 
+json_schema!(ExportStats {
+    sessions: usize,
+    issues: usize,
+    context_items: usize,
+    memories: usize,
+    checkpoints: usize,
+    plans: usize,
+    time_entries: usize,
+    deletions: usize,
+    embeddings: usize,
+    events: usize,
+});
+
 impl ExportStats {
     /// Total number of data records exported (excludes deletions).
     #[must_use]
@@ -265,8 +525,27 @@ pub struct ImportStats {
     pub plans: EntityStats,
     /// Statistics for time entries.
     pub time_entries: EntityStats,
+    /// Embeddings from `embeddings.jsonl` (not counted in totals).
+    pub embeddings: EmbeddingImportStats,
+    /// Events from `events.jsonl` (not counted in totals).
+    pub events: EventImportStats,
+    /// Lines skipped because they could not be parsed or imported.
+    pub errors: Vec<LineError>,
 }
 
+json_schema!(ImportStats {
+    sessions: EntityStats,
+    issues: EntityStats,
+    context_items: EntityStats,
+    memories: EntityStats,
+    checkpoints: EntityStats,
+    plans: EntityStats,
+    time_entries: EntityStats,
+    embeddings: EmbeddingImportStats,
+    events: EventImportStats,
+    errors: Vec<LineError>,
+});
+
 impl ImportStats {
     /// Total number of records processed.
     #[must_use]
@@ -316,8 +595,14 @@ pub struct EntityStats {
     pub skipped: usize,
     /// Number of conflicts encountered.
     pub conflicts: usize,
+    /// Records given a new short ID because another record had theirs
+    /// (issues only; already counted above).
+    #[serde(skip_serializing_if = "is_zero")]
+    pub renumbered: usize,
 }
 
+json_schema!(EntityStats { created: usize, updated: usize, skipped: usize, conflicts: usize, renumbered: usize = optional });
+
 impl EntityStats {
     /// Total records processed.
     #[must_use]
@@ -326,6 +611,41 @@ impl EntityStats {
     }
 }
 
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes a reference
+const fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Outcome of importing `embeddings.jsonl`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct EmbeddingImportStats {
+    /// Records whose embeddings were stored.
+    pub imported: usize,
+    /// Records missing locally or changed since they were embedded.
+    pub stale: usize,
+    /// Records embedded with a different model than this machine uses.
+    pub other_model: usize,
+    /// Records whose vectors don't decode to the stated dimensions.
+    pub invalid: usize,
+}
+
+json_schema!(EmbeddingImportStats { imported: usize, stale: usize, other_model: usize, invalid: usize });
+
+/// Outcome of importing `events.jsonl`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct EventImportStats {
+    /// Events added to the local audit log.
+    pub imported: usize,
+    /// Events already recorded locally.
+    pub existing: usize,
+    /// Events older than the local retention period.
+    pub expired: usize,
+    /// Events whose ID doesn't match their content.
+    pub invalid: usize,
+}
+
+json_schema!(EventImportStats { imported: usize, existing: usize, expired: usize, invalid: usize });
+
 /// Sync status information.
 #[derive(Debug, Clone, Serialize)]
 pub struct SyncStatus {
@@ -335,6 +655,10 @@ pub struct SyncStatus {
     pub dirty_issues: usize,
     /// Number of dirty context items pending export.
     pub dirty_context_items: usize,
+    /// Number of dirty plans pending export.
+    pub dirty_plans: usize,
+    /// Number of dirty time entries pending export.
+    pub dirty_time_entries: usize,
     /// Number of pending deletions to export.
     pub pending_deletions: usize,
     /// Total sessions for this project.
@@ -349,6 +673,21 @@ pub struct SyncStatus {
     pub has_export_files: bool,
     /// List of export files with their sizes.
     pub export_files: Vec<ExportFileInfo>,
+    /// When these files were last exported from this database (Unix ms).
+    pub last_export_at: Option<i64>,
+    /// When these files were last imported into this database (Unix ms).
+    pub last_import_at: Option<i64>,
+    /// When the oldest change still pending export was made (Unix ms).
+    pub oldest_unexported_at: Option<i64>,
+    /// When the project's most recently changed record changed (Unix ms).
+    pub newest_record_at: Option<i64>,
+    /// Modification time of the newest export file (Unix ms).
+    pub newest_file_at: Option<i64>,
+    /// How far the newest record is ahead of the newest file (0 if not).
+    pub skew_ms: Option<i64>,
+    /// 1 when the files hold everything, toward 0 as unexported changes
+    /// pile up and age.
+    pub freshness: f64,
 }
 
 /// Information about an export file.
@@ -362,6 +701,29 @@ pub struct ExportFileInfo {
     pub line_count: usize,
 }
 
+json_schema!(SyncStatus {
+    dirty_sessions: usize,
+    dirty_issues: usize,
+    dirty_context_items: usize,
+    dirty_plans: usize,
+    dirty_time_entries: usize,
+    pending_deletions: usize,
+    total_sessions: usize,
+    total_issues: usize,
+    total_context_items: usize,
+    needs_backfill: bool,
+    has_export_files: bool,
+    export_files: Vec<ExportFileInfo>,
+    last_export_at: Option<i64>,
+    last_import_at: Option<i64>,
+    oldest_unexported_at: Option<i64>,
+    newest_record_at: Option<i64>,
+    newest_file_at: Option<i64>,
+    skew_ms: Option<i64>,
+    freshness: f64,
+});
+json_schema!(ExportFileInfo { name: String, size: u64, line_count: usize });
+
 /// Sync-specific errors.
 #[derive(Debug, thiserror::Error)]
 pub enum SyncError {
@@ -393,6 +755,24 @@ pub enum SyncError {
         /// Error message.
         message: String,
     },
+
+    /// A file in a newer format than this build reads.
+    #[error(
+        "{file} was written by {producer} in sync format {version}, but this sc reads up to format {}; upgrade sc to read it",
+        crate::sync::meta::SCHEMA_VERSION
+    )]
+    UnsupportedVersion {
+        /// File name.
+        file: String,
+        /// Format version in the file's header.
+        version: u32,
+        /// Producer in the file's header.
+        producer: String,
+    },
+
+    /// Lines a strict import rejected; nothing was imported.
+    #[error("{} invalid line(s), nothing imported; first: {}", .0.len(), .0[0])]
+    InvalidLines(Vec<LineError>),
 }
 
 impl From<rusqlite::Error> for SyncError {
@@ -426,6 +806,7 @@ mod tests {
             updated: 5,
             skipped: 2,
             conflicts: 1,
+            renumbered: 1,
         };
         assert_eq!(stats.total(), 18);
     }
@@ -435,4 +816,18 @@ mod tests {
         let strategy = MergeStrategy::default();
         assert_eq!(strategy, MergeStrategy::PreferNewer);
     }
+
+    #[test]
+    fn test_merge_strategies_spec() {
+        let mut strategies = MergeStrategies::new(MergeStrategy::PreferExternal);
+        strategies.apply_spec("issues=prefer-local, items=prefer_newer").unwrap();
+        assert_eq!(strategies.for_entity(EntityType::Issue), MergeStrategy::PreferLocal);
+        assert_eq!(strategies.for_entity(EntityType::ContextItem), MergeStrategy::PreferNewer);
+        assert_eq!(strategies.for_entity(EntityType::Session), MergeStrategy::PreferExternal);
+
+        strategies.apply_spec("prefer-local").unwrap();
+        assert_eq!(strategies.for_entity(EntityType::Session), MergeStrategy::PreferLocal);
+        assert!(strategies.apply_spec("issue=newest").is_err());
+        assert!(strategies.apply_spec("tickets=prefer-local").is_err());
+    }
 }
@@ -0,0 +1,447 @@
+//! Export round-trip verification.
+//!
+//! A hand-resolved merge conflict or a half-written file can leave the
+//! JSONL export out of step with the database without anything noticing
+//! until an import on another machine goes wrong. [`verify`] re-reads every
+//! entity file, recomputes each line's content hash, and compares the lines
+//! with the project's records and recorded deletions.
+//!
+//! [`repair`] re-exports only the broken subset: altered, stale and
+//! unreadable lines are replaced, missing records appended and locally
+//! deleted ones dropped, while lines the database has never seen are left
+//! alone, since they may be another machine's work waiting to be imported.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::json_schema::{json_schema, string_enum, JsonSchema};
+
+use crate::storage::sqlite::SqliteStorage;
+use crate::sync::file::{read_text_lines, write_jsonl, write_jsonl_lines};
+use crate::sync::meta;
+use crate::sync::hash::content_hash;
+use crate::sync::types::{
+    CheckpointRecord, ContextItemRecord, DeletionRecord, EntityType, IssueRecord, MemoryRecord, PlanRecord,
+    SessionRecord, SyncError, SyncRecord, SyncResult, TimeEntryRecord,
+};
+
+/// Entity files, in export order.
+const ENTITY_FILES: [(&str, EntityType); 7] = [
+    ("sessions.jsonl", EntityType::Session),
+    ("issues.jsonl", EntityType::Issue),
+    ("context_items.jsonl", EntityType::ContextItem),
+    ("memories.jsonl", EntityType::Memory),
+    ("checkpoints.jsonl", EntityType::Checkpoint),
+    ("plans.jsonl", EntityType::Plan),
+    ("time_entries.jsonl", EntityType::TimeEntry),
+];
+
+const DELETIONS_FILE: &str = "deletions.jsonl";
+
+/// How a file differs from the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// The record (or recorded deletion) is in the database but not the file.
+    Missing,
+    /// The line's data no longer matches its `content_hash`.
+    HashMismatch,
+    /// The line is intact but differs from the record in the database.
+    Stale,
+    /// The line is not a valid record.
+    Unreadable,
+    /// The line is for a record deleted locally.
+    Deleted,
+    /// The line is for a record the database has never had.
+    Orphan,
+    /// A `deletions.jsonl` entry whose record still exists locally.
+    OrphanDeletion,
+}
+
+impl DivergenceKind {
+    /// Every kind, in the order checks report them.
+    pub const ALL: [Self; 7] = [
+        Self::Missing,
+        Self::HashMismatch,
+        Self::Stale,
+        Self::Unreadable,
+        Self::Deleted,
+        Self::Orphan,
+        Self::OrphanDeletion,
+    ];
+
+    /// The serialized name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Missing => "missing",
+            Self::HashMismatch => "hash_mismatch",
+            Self::Stale => "stale",
+            Self::Unreadable => "unreadable",
+            Self::Deleted => "deleted",
+            Self::Orphan => "orphan",
+            Self::OrphanDeletion => "orphan_deletion",
+        }
+    }
+
+    /// Whether [`repair`] fixes this kind. Orphans may be another
+    /// machine's changes, so they are left for `sc sync import`.
+    #[must_use]
+    pub const fn is_fixable(self) -> bool {
+        !matches!(self, Self::Orphan | Self::OrphanDeletion)
+    }
+}
+
+/// One difference between an export file and the database.
+#[derive(Debug, Clone, Serialize)]
+pub struct Divergence {
+    /// Export file name.
+    pub file: String,
+    /// What is wrong.
+    pub kind: DivergenceKind,
+    /// ID of the record concerned, if known.
+    pub entity_id: Option<String>,
+    /// Line in the file (1-indexed), if the record is in it.
+    pub line: Option<usize>,
+}
+
+impl JsonSchema for DivergenceKind {
+    fn json_schema() -> Value {
+        string_enum(&Self::ALL.map(Self::as_str))
+    }
+}
+
+json_schema!(Divergence { file: String, kind: DivergenceKind, entity_id: Option<String>, line: Option<usize> });
+
+/// Outcome of [`verify`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    /// Export files that exist and were read.
+    pub files_checked: usize,
+    /// Lines read from them.
+    pub records_checked: usize,
+    /// Every difference found, file by file.
+    pub divergences: Vec<Divergence>,
+}
+
+impl VerifyReport {
+    /// Number of divergences [`repair`] would fix.
+    #[must_use]
+    pub fn fixable(&self) -> usize {
+        self.divergences.iter().filter(|d| d.kind.is_fixable()).count()
+    }
+}
+
+/// Compare the export in `export_dir` with the project's records.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read or a file exists but
+/// cannot be opened.
+pub fn verify(storage: &SqliteStorage, export_dir: &Path, project_path: &str) -> SyncResult<VerifyReport> {
+    let deleted = local_deletions(storage, project_path)?;
+    let mut report = VerifyReport::default();
+    let mut existing: HashSet<(EntityType, String)> = HashSet::new();
+
+    for (file, entity) in ENTITY_FILES {
+        let records = db_records(storage, project_path, entity, "")?;
+        existing.extend(records.keys().map(|id| (entity, id.clone())));
+        let path = export_dir.join(file);
+        let lines = if path.exists() {
+            report.files_checked += 1;
+            read_lines(&path)?
+        } else {
+            Vec::new()
+        };
+        report.records_checked += lines.len();
+        check_entity_file(file, entity, &records, &lines, &deleted, &mut report.divergences);
+    }
+
+    let path = export_dir.join(DELETIONS_FILE);
+    let mut listed = HashSet::new();
+    if path.exists() {
+        report.files_checked += 1;
+        for (line, text) in read_raw_lines(&path)? {
+            report.records_checked += 1;
+            let Ok(record) = serde_json::from_str::<DeletionRecord>(&text) else {
+                report.divergences.push(divergence(DELETIONS_FILE, DivergenceKind::Unreadable, None, Some(line)));
+                continue;
+            };
+            let key = (record.entity_type, record.entity_id);
+            if record.project_path == project_path && existing.contains(&key) {
+                report.divergences.push(divergence(
+                    DELETIONS_FILE,
+                    DivergenceKind::OrphanDeletion,
+                    Some(key.1.clone()),
+                    Some(line),
+                ));
+            }
+            listed.insert(key);
+        }
+    }
+    for key in deleted.difference(&listed) {
+        report.divergences.push(divergence(DELETIONS_FILE, DivergenceKind::Missing, Some(key.1.clone()), None));
+    }
+
+    Ok(report)
+}
+
+/// Re-export the parts of `report` that [`DivergenceKind::is_fixable`],
+/// touching only the files they are in. Returns the number fixed.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be read or a file cannot be
+/// rewritten.
+pub fn repair(
+    storage: &SqliteStorage,
+    export_dir: &Path,
+    project_path: &str,
+    report: &VerifyReport,
+) -> SyncResult<usize> {
+    let broken: HashSet<&str> =
+        report.divergences.iter().filter(|d| d.kind.is_fixable()).map(|d| d.file.as_str()).collect();
+    let now = Utc::now().to_rfc3339();
+    let deleted = local_deletions(storage, project_path)?;
+    fs::create_dir_all(export_dir)?;
+
+    for (file, entity) in ENTITY_FILES {
+        if !broken.contains(file) {
+            continue;
+        }
+        let path = export_dir.join(file);
+        let mut records = db_records(storage, project_path, entity, &now)?;
+        let lines = if path.exists() { read_lines(&path)? } else { Vec::new() };
+
+        let mut kept = Vec::new();
+        for (_, parsed) in lines {
+            let Ok(line) = parsed else { continue };
+            let id = line.id().to_string();
+            if let Some(current) = records.remove(&id) {
+                let intact = current.hash == line.stored_hash() && current.hash == line.data_hash();
+                kept.push(if intact { line } else { current.record });
+            } else if !deleted.contains(&(entity, id)) {
+                kept.push(line);
+            }
+        }
+        let mut missing: Vec<DbRecord> = records.into_values().collect();
+        missing.sort_by_key(|r| r.order);
+        kept.extend(missing.into_iter().map(|r| r.record));
+        write_jsonl(&path, &kept)?;
+    }
+
+    if broken.contains(DELETIONS_FILE) {
+        let path = export_dir.join(DELETIONS_FILE);
+        let mut listed = HashSet::new();
+        let mut content = String::new();
+        if path.exists() {
+            for (_, text) in read_raw_lines(&path)? {
+                if let Ok(record) = serde_json::from_str::<DeletionRecord>(&text) {
+                    listed.insert((record.entity_type, record.entity_id));
+                    content.push_str(&text);
+                    content.push('\n');
+                }
+            }
+        }
+        let deletions = storage.get_all_deletions(project_path).map_err(|e| SyncError::Database(e.to_string()))?;
+        for record in deletions.iter().map(DeletionRecord::from_deletion) {
+            if !listed.contains(&(record.entity_type, record.entity_id.clone())) {
+                content.push_str(&serde_json::to_string(&record)?);
+                content.push('\n');
+            }
+        }
+        write_jsonl_lines(&path, &content)?;
+    }
+
+    Ok(report.fixable())
+}
+
+/// A record as it would be exported now.
+struct DbRecord {
+    /// Position in the export order, so appended records keep it.
+    order: usize,
+    hash: String,
+    record: SyncRecord,
+}
+
+/// Compare one entity file's lines with the database.
+fn check_entity_file(
+    file: &str,
+    entity: EntityType,
+    records: &HashMap<String, DbRecord>,
+    lines: &[(usize, Result<SyncRecord, String>)],
+    deleted: &HashSet<(EntityType, String)>,
+    out: &mut Vec<Divergence>,
+) {
+    let mut seen = HashSet::new();
+    for (line, parsed) in lines {
+        let Ok(record) = parsed else {
+            out.push(divergence(file, DivergenceKind::Unreadable, None, Some(*line)));
+            continue;
+        };
+        let id = record.id().to_string();
+        let data_hash = record.data_hash();
+        let kind = match records.get(&id) {
+            None if deleted.contains(&(entity, id.clone())) => Some(DivergenceKind::Deleted),
+            None => Some(DivergenceKind::Orphan),
+            Some(_) if data_hash != record.stored_hash() => Some(DivergenceKind::HashMismatch),
+            Some(current) if current.hash != data_hash => Some(DivergenceKind::Stale),
+            Some(_) => None,
+        };
+        if let Some(kind) = kind {
+            out.push(divergence(file, kind, Some(id.clone()), Some(*line)));
+        }
+        seen.insert(id);
+    }
+
+    let mut missing: Vec<&DbRecord> = records.values().filter(|r| !seen.contains(r.record.id())).collect();
+    missing.sort_by_key(|r| r.order);
+    for record in missing {
+        out.push(divergence(file, DivergenceKind::Missing, Some(record.record.id().to_string()), None));
+    }
+}
+
+fn divergence(file: &str, kind: DivergenceKind, entity_id: Option<String>, line: Option<usize>) -> Divergence {
+    Divergence { file: file.to_string(), kind, entity_id, line }
+}
+
+/// The project's records of one type, by ID, wrapped as `export` would
+/// write them with `exported_at` set to `now`.
+fn db_records(
+    storage: &SqliteStorage,
+    project_path: &str,
+    entity: EntityType,
+    now: &str,
+) -> SyncResult<HashMap<String, DbRecord>> {
+    let db = |e: crate::error::Error| SyncError::Database(e.to_string());
+    let exported_at = now.to_string();
+    let records: Vec<(String, SyncRecord)> = match entity {
+        EntityType::Session => wrap(storage.get_sessions_by_project(project_path).map_err(db)?, |data, content_hash| {
+            SyncRecord::Session(SessionRecord { data, content_hash, exported_at: exported_at.clone() })
+        }),
+        EntityType::Issue => wrap(storage.get_issues_by_project(project_path).map_err(db)?, |data, content_hash| {
+            SyncRecord::Issue(IssueRecord { data, content_hash, exported_at: exported_at.clone() })
+        }),
+        EntityType::ContextItem => {
+            wrap(storage.get_context_items_for_sync(project_path).map_err(db)?, |data, content_hash| {
+                SyncRecord::ContextItem(ContextItemRecord { data, content_hash, exported_at: exported_at.clone() })
+            })
+        }
+        EntityType::Memory => wrap(storage.get_memory_by_project(project_path).map_err(db)?, |data, content_hash| {
+            SyncRecord::Memory(MemoryRecord { data, content_hash, exported_at: exported_at.clone() })
+        }),
+        EntityType::Checkpoint => {
+            wrap(storage.get_checkpoints_by_project(project_path).map_err(db)?, |data, content_hash| {
+                SyncRecord::Checkpoint(CheckpointRecord { data, content_hash, exported_at: exported_at.clone() })
+            })
+        }
+        EntityType::Plan => wrap(storage.get_plans_by_project(project_path).map_err(db)?, |data, content_hash| {
+            SyncRecord::Plan(PlanRecord { data, content_hash, exported_at: exported_at.clone() })
+        }),
+        EntityType::TimeEntry => {
+            wrap(storage.get_time_entries_by_project(project_path).map_err(db)?, |data, content_hash| {
+                SyncRecord::TimeEntry(TimeEntryRecord { data, content_hash, exported_at: exported_at.clone() })
+            })
+        }
+    };
+    Ok(records
+        .into_iter()
+        .enumerate()
+        .map(|(order, (hash, record))| (record.id().to_string(), DbRecord { order, hash, record }))
+        .collect())
+}
+
+/// Hash each item and wrap it in a record.
+fn wrap<T: Serialize>(items: Vec<T>, make: impl Fn(T, String) -> SyncRecord) -> Vec<(String, SyncRecord)> {
+    items
+        .into_iter()
+        .map(|item| {
+            let hash = content_hash(&item);
+            (hash.clone(), make(item, hash))
+        })
+        .collect()
+}
+
+/// Deletions recorded in this database for the project.
+fn local_deletions(storage: &SqliteStorage, project_path: &str) -> SyncResult<HashSet<(EntityType, String)>> {
+    let deletions = storage.get_all_deletions(project_path).map_err(|e| SyncError::Database(e.to_string()))?;
+    Ok(deletions
+        .into_iter()
+        .filter_map(|d| d.entity_type.parse::<EntityType>().ok().map(|t| (t, d.entity_id)))
+        .collect())
+}
+
+/// Non-blank lines of a file after its header, with their line numbers.
+fn read_raw_lines(path: &Path) -> SyncResult<Vec<(usize, String)>> {
+    Ok(read_text_lines(path)?.1)
+}
+
+/// Parse each line of an entity file, keeping the ones that fail.
+fn read_lines(path: &Path) -> SyncResult<Vec<(usize, Result<SyncRecord, String>)>> {
+    let (version, lines) = read_text_lines(path)?;
+    Ok(lines
+        .into_iter()
+        .map(|(line, text)| (line, meta::parse_line(&text, version).map_err(|e| e.to_string())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::Exporter;
+    use tempfile::TempDir;
+
+    fn kinds(report: &VerifyReport) -> Vec<(DivergenceKind, Option<&str>)> {
+        report.divergences.iter().map(|d| (d.kind, d.entity_id.as_deref())).collect()
+    }
+
+    #[test]
+    fn test_verify_and_repair() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut storage = SqliteStorage::open(&temp_dir.path().join("test.db")).unwrap();
+        let project = "/test/project";
+        let dir = temp_dir.path().join("export");
+        for id in ["sess_1", "sess_2", "sess_3", "sess_4"] {
+            storage.create_session(id, "Session", None, Some(project), None, None, "test").unwrap();
+        }
+        Exporter::with_output_dir(&mut storage, project.to_string(), dir.clone()).export(false).unwrap();
+
+        let report = verify(&storage, &dir, project).unwrap();
+        assert!(report.divergences.is_empty(), "{:?}", report.divergences);
+        assert_eq!(report.records_checked, 4);
+
+        // Tamper with the file and change the database behind it.
+        let path = dir.join("sessions.jsonl");
+        let content = fs::read_to_string(&path).unwrap();
+        let mut lines: Vec<String> = content.lines().map(ToString::to_string).collect();
+        lines.retain(|l| !l.contains("sess_4"));
+        // lines[0] is the header
+        lines[1] = lines[1].replace("\"Session\"", "\"Edited by hand\"");
+        lines.push(r#"{"type":"session","id":"sess_other","name":"Elsewhere","description":null,"branch":null,"channel":null,"project_path":"/test/project","status":"active","ended_at":null,"created_at":1000,"updated_at":1000,"content_hash":"abc","exported_at":"2025-01-01T00:00:00Z"}"#.to_string());
+        lines.push("{not json".to_string());
+        fs::write(&path, lines.join("\n")).unwrap();
+        storage.rename_session("sess_2", "Renamed", "test").unwrap();
+        storage.delete_session("sess_3", "test").unwrap();
+        storage.record_deletion("session", "sess_3", project, "test").unwrap();
+
+        let report = verify(&storage, &dir, project).unwrap();
+        let found = kinds(&report);
+        assert!(found.contains(&(DivergenceKind::HashMismatch, Some("sess_1"))), "{found:?}");
+        assert!(found.contains(&(DivergenceKind::Stale, Some("sess_2"))), "{found:?}");
+        assert!(found.contains(&(DivergenceKind::Deleted, Some("sess_3"))), "{found:?}");
+        assert!(found.contains(&(DivergenceKind::Missing, Some("sess_4"))), "{found:?}");
+        assert!(found.contains(&(DivergenceKind::Orphan, Some("sess_other"))), "{found:?}");
+        assert!(found.contains(&(DivergenceKind::Unreadable, None)), "{found:?}");
+        assert!(found.contains(&(DivergenceKind::Missing, Some("sess_3"))), "deletion not exported: {found:?}");
+
+        repair(&storage, &dir, project, &report).unwrap();
+        let report = verify(&storage, &dir, project).unwrap();
+        // The foreign line is kept for `sc sync import`.
+        assert_eq!(kinds(&report), vec![(DivergenceKind::Orphan, Some("sess_other"))]);
+    }
+}
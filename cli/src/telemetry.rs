@@ -0,0 +1,151 @@
+//! OpenTelemetry trace export (`otel` feature).
+//!
+//! Built with `--features otel`, `sc` can ship its traces to an OTLP
+//! collector (HTTP/protobuf), so platform teams can watch agent tooling
+//! latency next to everything else. Each command is a trace:
+//!
+//! - `sc.command`: the whole command, with `command` (e.g. `issue list`),
+//!   `exit_code` and `ok`; every `sc shell` line is one below the shell's
+//! - `sql`: each statement, with its SQL text (parameters are never
+//!   expanded into it)
+//! - `embedding.generate`: each call to an embedding provider, with
+//!   `provider`, `model` and `texts`
+//!
+//! Nothing is exported unless a collector is named, by the standard
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` / `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`
+//! variables or the `otel` section of `~/.savecontext/config.json`:
+//!
+//! ```json
+//! { "otel": { "endpoint": "http://localhost:4318", "service_name": "sc" } }
+//! ```
+//!
+//! Spans are batched and sent from a background thread; [`shutdown`]
+//! flushes them before the process exits. Without the feature every
+//! function here is a no-op.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing_subscriber::{Layer, Registry};
+
+/// Default `service.name`, unless `OTEL_SERVICE_NAME` or `otel.service_name`
+/// says otherwise.
+pub const DEFAULT_SERVICE_NAME: &str = "savecontext";
+
+/// The `otel` section of `~/.savecontext/config.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OtelConfig {
+    /// Collector base URL; spans go to `<endpoint>/v1/traces`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<String>,
+}
+
+/// A tracing layer that exports spans, if a collector is configured.
+#[cfg(feature = "otel")]
+#[must_use]
+pub fn layer() -> Option<Box<dyn Layer<Registry> + Send + Sync>> {
+    otel::layer()
+}
+
+/// A tracing layer that exports spans; always `None` without the `otel`
+/// feature.
+#[cfg(not(feature = "otel"))]
+#[must_use]
+pub fn layer() -> Option<Box<dyn Layer<Registry> + Send + Sync>> {
+    None
+}
+
+/// Send the spans still waiting in the batch.
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    otel::shutdown();
+}
+
+/// Export a finished SQL statement as a span of the current one.
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub(crate) fn record_query(sql: &str, elapsed: Duration) {
+    #[cfg(feature = "otel")]
+    otel::record_query(sql, elapsed);
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use super::DEFAULT_SERVICE_NAME;
+    use opentelemetry::trace::{Span as _, SpanKind, Tracer as _, TracerProvider as _};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::trace::{SdkTracerProvider, Tracer};
+    use opentelemetry_sdk::Resource;
+    use std::sync::OnceLock;
+    use std::time::{Duration, SystemTime};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::filter::{LevelFilter, Targets};
+    use tracing_subscriber::{Layer, Registry};
+
+    /// The provider and tracer, once a collector is set up.
+    static EXPORT: OnceLock<(SdkTracerProvider, Tracer)> = OnceLock::new();
+
+    pub(super) fn layer() -> Option<Box<dyn Layer<Registry> + Send + Sync>> {
+        let config = crate::config::otel();
+        let from_env = ["OTEL_EXPORTER_OTLP_ENDPOINT", "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT"]
+            .iter()
+            .any(|var| std::env::var(var).is_ok_and(|v| !v.trim().is_empty()));
+        if config.endpoint.is_none() && !from_env {
+            return None;
+        }
+
+        let mut exporter = opentelemetry_otlp::SpanExporter::builder().with_http();
+        if let Some(endpoint) = &config.endpoint {
+            exporter = exporter.with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')));
+        }
+        let exporter = match exporter.build() {
+            Ok(exporter) => exporter,
+            Err(e) => {
+                eprintln!("Warning: OpenTelemetry export is off: {e}");
+                return None;
+            }
+        };
+
+        let mut resource = Resource::builder();
+        if let Some(name) = config.service_name {
+            resource = resource.with_service_name(name);
+        } else if std::env::var_os("OTEL_SERVICE_NAME").is_none() {
+            resource = resource.with_service_name(DEFAULT_SERVICE_NAME);
+        }
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(resource.build())
+            .build();
+        let tracer = provider.tracer("sc");
+        let layer = tracing_opentelemetry::layer()
+            .with_tracer(tracer.clone())
+            .with_filter(Targets::new().with_target("sc", LevelFilter::INFO));
+        let _ = EXPORT.set((provider, tracer));
+        Some(Box::new(layer))
+    }
+
+    pub(super) fn shutdown() {
+        if let Some((provider, _)) = EXPORT.get() {
+            let _ = provider.shutdown();
+        }
+    }
+
+    pub(super) fn record_query(sql: &str, elapsed: Duration) {
+        let Some((_, tracer)) = EXPORT.get() else {
+            return;
+        };
+        let parent = tracing::Span::current().context();
+        let end = SystemTime::now();
+        let mut span = tracer
+            .span_builder("sql")
+            .with_kind(SpanKind::Client)
+            .with_start_time(end.checked_sub(elapsed).unwrap_or(end))
+            .with_attributes([
+                KeyValue::new("db.system.name", "sqlite"),
+                KeyValue::new("db.query.text", crate::storage::explain::collapse_whitespace(sql)),
+            ])
+            .start_with_context(tracer, &parent);
+        span.end_with_timestamp(end);
+    }
+}
@@ -0,0 +1,240 @@
+//! Fixtures for testing code built on `sc`.
+//!
+//! Enabled by the `testing` feature. A [`Fixture`] is an in-memory
+//! database with a registered project, and builder methods that add
+//! sessions, context items, issues, memory and embeddings to it:
+//!
+//! ```ignore
+//! use sc::testing::Fixture;
+//!
+//! let mut fixture = Fixture::new()?;
+//! let item = fixture.item("auth-decision", "Use JWTs with 15 minute expiry")?;
+//! fixture.embed(&item.id)?;
+//! fixture.issue("Rotate refresh tokens")?;
+//! ```
+//!
+//! [`Fixture::demo`] fills the project with the data `sc seed --demo`
+//! creates. Embeddings come from [`fake_embedding`], which needs no
+//! provider and gives texts sharing words a higher similarity, so
+//! semantic search behaves plausibly in tests.
+
+use crate::cli::commands::seed::{populate_demo, DemoCounts};
+use crate::core::{ContextItem, Issue, Memory, NewIssue, NewItem, NewSession, SaveContext, Session};
+use crate::error::{Error, Result};
+use crate::model::{MemoryCategory, Project};
+use crate::storage::SqliteStorage;
+
+/// Project path of [`Fixture::new`].
+pub const PROJECT_PATH: &str = "/fixture/project";
+
+/// Actor of [`Fixture::new`].
+pub const ACTOR: &str = "fixture";
+
+/// Dimensions of [`fake_embedding`] vectors.
+pub const EMBEDDING_DIMENSIONS: usize = 64;
+
+/// Provider name recorded for fixture embeddings.
+const PROVIDER: &str = "fixture";
+
+/// A database with one registered project to build test data in.
+pub struct Fixture {
+    sc: SaveContext<'static>,
+    session: Option<Session>,
+}
+
+impl Fixture {
+    /// An empty in-memory database with [`PROJECT_PATH`] registered, acting
+    /// as [`ACTOR`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be created.
+    pub fn new() -> Result<Self> {
+        Self::with_storage(SqliteStorage::open_memory()?, PROJECT_PATH, ACTOR)
+    }
+
+    /// Build in `storage` instead, registering `project_path` if it isn't
+    /// yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the project cannot be registered.
+    pub fn with_storage(mut storage: SqliteStorage, project_path: &str, actor: &str) -> Result<Self> {
+        if storage.get_project_by_path(project_path)?.is_none() {
+            let name = project_path.rsplit('/').next().unwrap_or(project_path).to_string();
+            storage.create_project(&Project::new(project_path.to_string(), name), actor)?;
+        }
+        Ok(Self { sc: SaveContext::with_storage(storage, project_path, actor), session: None })
+    }
+
+    /// Start a new session, which later items are saved in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn session(&mut self, name: &str) -> Result<Session> {
+        let new = NewSession { name: name.to_string(), force_new: true, ..NewSession::default() };
+        let session = self.sc.start_session(&new)?.session;
+        self.session = Some(session.clone());
+        Ok(session)
+    }
+
+    /// Save a note in the current session, starting one if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a write fails.
+    pub fn item(&mut self, key: &str, value: &str) -> Result<ContextItem> {
+        self.item_with(&NewItem { key: key.to_string(), value: value.to_string(), ..NewItem::default() })
+    }
+
+    /// Save `item` in the current session, starting one if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a write fails.
+    pub fn item_with(&mut self, item: &NewItem) -> Result<ContextItem> {
+        let session_id = match &self.session {
+            Some(session) => session.id.clone(),
+            None => self.session("Fixture session")?.id,
+        };
+        self.sc.save_item(&session_id, item)
+    }
+
+    /// Create an open task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn issue(&mut self, title: &str) -> Result<Issue> {
+        self.issue_with(&NewIssue { title: title.to_string(), ..NewIssue::default() })
+    }
+
+    /// Create an issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn issue_with(&mut self, issue: &NewIssue) -> Result<Issue> {
+        self.sc.create_issue(issue)
+    }
+
+    /// Save a project memory command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the write fails.
+    pub fn memory(&mut self, key: &str, value: &str) -> Result<Memory> {
+        self.sc.save_memory(key, value, MemoryCategory::Command)
+    }
+
+    /// Embed a context item's `key: value` text with [`fake_embedding`],
+    /// making it findable by semantic search.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such item or the write fails.
+    pub fn embed(&mut self, item_id: &str) -> Result<()> {
+        let item = self
+            .sc
+            .storage()
+            .get_context_item(item_id)?
+            .ok_or_else(|| Error::Other(format!("Context item not found: {item_id}")))?;
+        let text = format!("{}: {}", item.key, item.value);
+        let chunk_id = format!("emb_{}_0", item.id);
+        let model = format!("bag-of-words-{EMBEDDING_DIMENSIONS}");
+        self.sc
+            .storage_mut()
+            .store_embedding_chunk(&chunk_id, &item.id, 0, &text, &fake_embedding(&text), PROVIDER, &model)
+    }
+
+    /// Fill the project with the `sc seed --demo` data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a write fails.
+    pub fn demo(&mut self) -> Result<DemoCounts> {
+        populate_demo(&mut self.sc)
+    }
+
+    /// The session items are saved in, if one was started.
+    #[must_use]
+    pub fn current_session(&self) -> Option<&Session> {
+        self.session.as_ref()
+    }
+
+    /// The library handle, for operations the builders don't cover.
+    #[must_use]
+    pub fn context(&self) -> &SaveContext<'static> {
+        &self.sc
+    }
+
+    /// Mutable access to the library handle.
+    pub fn context_mut(&mut self) -> &mut SaveContext<'static> {
+        &mut self.sc
+    }
+
+    /// The underlying storage.
+    #[must_use]
+    pub fn storage(&self) -> &SqliteStorage {
+        self.sc.storage()
+    }
+
+    /// Mutable access to the underlying storage.
+    pub fn storage_mut(&mut self) -> &mut SqliteStorage {
+        self.sc.storage_mut()
+    }
+
+    /// Give up the fixture and keep its library handle.
+    #[must_use]
+    pub fn into_context(self) -> SaveContext<'static> {
+        self.sc
+    }
+}
+
+/// A deterministic, unit-length embedding of `text`: each lowercased word
+/// is hashed into one of [`EMBEDDING_DIMENSIONS`] buckets. Texts sharing
+/// words score higher in cosine similarity; nothing more is modelled.
+#[must_use]
+pub fn fake_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0_f32; EMBEDDING_DIMENSIONS];
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        // FNV-1a, so vectors are the same on every platform and release
+        let hash = word
+            .to_lowercase()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+        vector[usize::try_from(hash % EMBEDDING_DIMENSIONS as u64).unwrap_or(0)] += 1.0;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in &mut vector {
+            *v /= norm;
+        }
+    }
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_builds_searchable_data() {
+        let mut fixture = Fixture::new().unwrap();
+        let auth = fixture.item("auth", "Refresh tokens rotate on every use").unwrap();
+        let db = fixture.item("database", "Postgres connection pool of twenty").unwrap();
+        fixture.embed(&auth.id).unwrap();
+        fixture.embed(&db.id).unwrap();
+        fixture.issue("Rotate refresh tokens").unwrap();
+
+        let session = fixture.current_session().unwrap().id.clone();
+        let results =
+            fixture.storage().semantic_search(&fake_embedding("refresh tokens"), Some(&session), 5, 0.0).unwrap();
+        assert_eq!(results[0].item_id, auth.id);
+
+        let counts = fixture.demo().unwrap();
+        assert_eq!(counts.issues, 7);
+        assert_eq!(fixture.context().list_issues(&crate::core::IssueFilter::default()).unwrap().len(), 7);
+    }
+}
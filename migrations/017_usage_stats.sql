@@ -0,0 +1,24 @@
+-- Migration 017: Local Usage Statistics
+--
+-- Records which `sc` commands run, how long they take, and how large the
+-- database is each day, so `sc stats` can show usage patterns. Only the
+-- command name is stored (never arguments), and nothing leaves the machine.
+-- Set "usage_stats": false in ~/.savecontext/config.json to stop recording.
+
+CREATE TABLE IF NOT EXISTS command_usage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    command TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    success INTEGER NOT NULL DEFAULT 1,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_command_usage_created ON command_usage(created_at);
+CREATE INDEX IF NOT EXISTS idx_command_usage_command ON command_usage(command);
+
+-- One row per day; later samples on the same day overwrite earlier ones.
+CREATE TABLE IF NOT EXISTS db_size_samples (
+    sampled_on TEXT PRIMARY KEY,
+    size_bytes INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+);
@@ -0,0 +1,27 @@
+-- Migration 018: Hot-Path Indexes
+--
+-- Composite indexes for the most frequent predicates:
+-- - context_items by session + category (`sc get --category`, prime buckets)
+-- - issues by project + status, ordered by priority (`sc issue list`, ready queues)
+-- - events by entity, newest first (history lookups)
+--
+-- `sc db indexes` reports how these are used and suggests more.
+--
+-- events is part of the CLI's base schema but not the server's, so a
+-- database the MCP server created may not have it yet.
+
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    comment TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_context_items_session_category ON context_items(session_id, category);
+CREATE INDEX IF NOT EXISTS idx_issues_project_status_priority ON issues(project_path, status, priority DESC);
+CREATE INDEX IF NOT EXISTS idx_events_entity_created ON events(entity_id, created_at DESC);
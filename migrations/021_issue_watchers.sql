@@ -0,0 +1,23 @@
+-- Migration 021: Issue watchers and inbox read state
+--
+-- `sc issue watch` subscribes an actor to an issue; `sc inbox` lists
+-- activity by others on issues the actor watches or is assigned, plus
+-- @mentions of the actor. Mentions are stored as `issue_mentioned` events.
+
+CREATE TABLE IF NOT EXISTS issue_watchers (
+    issue_id TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (issue_id, actor),
+    FOREIGN KEY (issue_id) REFERENCES issues(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_issue_watchers_actor ON issue_watchers(actor);
+
+-- Last time each actor marked their inbox read
+CREATE TABLE IF NOT EXISTS inbox_reads (
+    actor TEXT PRIMARY KEY,
+    read_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_type_value ON events(event_type, new_value);
@@ -0,0 +1,28 @@
+-- Migration 023: Per-session token usage ledger
+--
+-- Hooks call `sc session usage record` with the tokens a model turn
+-- consumed; `sc session usage show` totals them per session and splits
+-- them at compactions (`pre-compact-*` checkpoints). Recording usage
+-- counts as session activity.
+
+CREATE TABLE IF NOT EXISTS session_usage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL,
+    tokens_in INTEGER NOT NULL DEFAULT 0,
+    tokens_out INTEGER NOT NULL DEFAULT 0,
+    model TEXT,
+    source TEXT,
+    recorded_at INTEGER NOT NULL,
+    recorded_by TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_usage_session ON session_usage(session_id, recorded_at);
+
+CREATE TRIGGER IF NOT EXISTS session_activity_usage_insert
+AFTER INSERT ON session_usage
+BEGIN
+    INSERT INTO session_activity (session_id, last_activity_at)
+    VALUES (NEW.session_id, NEW.recorded_at)
+    ON CONFLICT(session_id) DO UPDATE SET last_activity_at = MAX(last_activity_at, excluded.last_activity_at);
+END;
@@ -0,0 +1,19 @@
+-- Migration 024: Embedding cache keyed by content hash
+--
+-- Saving or updating an item with unchanged text used to call the
+-- embedding provider again. Embeddings are now looked up here first by
+-- (provider, model, SHA-256 of the chunk text); `hits` counts lookups
+-- served from the cache, and each row is one miss that was embedded.
+-- Not synced: the cache is rebuilt on demand.
+
+CREATE TABLE IF NOT EXISTS embedding_cache (
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    embedding BLOB NOT NULL,
+    hits INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    last_used_at INTEGER NOT NULL,
+    PRIMARY KEY (provider, model, content_hash)
+);
@@ -0,0 +1,8 @@
+-- Migration 027: Compressed context item values
+--
+-- Agents sometimes save whole diffs or build logs as context items. Values
+-- above `value_compression_threshold` bytes are stored zstd-compressed as
+-- a BLOB in `value`, with this flag set, and decompressed on read. `size`
+-- keeps the uncompressed length.
+
+ALTER TABLE context_items ADD COLUMN value_compressed INTEGER NOT NULL DEFAULT 0;
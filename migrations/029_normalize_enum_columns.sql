@@ -0,0 +1,65 @@
+-- Migration 029: Canonical spellings for enum columns
+--
+-- Session status, context item category and priority, memory category and
+-- issue type and status are now read into typed enums (src/model/enums.rs)
+-- that only accept their canonical lowercase spellings. Nothing checked
+-- these columns before, so older rows can hold `Decision`, `bugfix` or
+-- `done`. Rows are lowercased, known synonyms are mapped, and anything
+-- else falls back to the column's default. Time entries already had a
+-- CHECK constraint.
+--
+-- Issue statuses outside the built-in set are only lowercased: workflow
+-- overrides define statuses of their own.
+
+UPDATE sessions SET status = CASE
+        WHEN status IS NULL THEN 'active'
+        WHEN lower(trim(status)) IN ('active', 'paused', 'completed') THEN lower(trim(status))
+        WHEN lower(trim(status)) IN ('complete', 'done', 'ended', 'closed', 'finished') THEN 'completed'
+        ELSE 'paused'
+    END
+WHERE status IS NULL OR status NOT IN ('active', 'paused', 'completed');
+
+UPDATE context_items SET category = CASE
+        WHEN lower(trim(category)) IN ('reminder', 'decision', 'progress', 'note') THEN lower(trim(category))
+        WHEN lower(trim(category)) IN ('task', 'todo') THEN 'reminder'
+        ELSE 'note'
+    END
+WHERE category IS NULL OR category NOT IN ('reminder', 'decision', 'progress', 'note');
+
+UPDATE context_items SET priority = CASE
+        WHEN lower(trim(priority)) IN ('high', 'normal', 'low') THEN lower(trim(priority))
+        WHEN lower(trim(priority)) IN ('critical', 'urgent', 'important') THEN 'high'
+        WHEN lower(trim(priority)) IN ('minor', 'trivial') THEN 'low'
+        ELSE 'normal'
+    END
+WHERE priority IS NULL OR priority NOT IN ('high', 'normal', 'low');
+
+UPDATE project_memory SET category = CASE
+        WHEN lower(trim(category)) IN ('command', 'config', 'note') THEN lower(trim(category))
+        ELSE 'note'
+    END
+WHERE category IS NULL OR category NOT IN ('command', 'config', 'note');
+
+UPDATE issues SET issue_type = CASE
+        WHEN lower(trim(issue_type)) IN ('task', 'bug', 'feature', 'epic', 'chore') THEN lower(trim(issue_type))
+        WHEN lower(trim(issue_type)) IN ('story', 'enhancement', 'improvement') THEN 'feature'
+        WHEN lower(trim(issue_type)) IN ('issue', 'defect', 'problem') THEN 'bug'
+        WHEN lower(trim(issue_type)) IN ('cleanup', 'refactor', 'maintenance') THEN 'chore'
+        WHEN lower(trim(issue_type)) IN ('parent', 'initiative') THEN 'epic'
+        ELSE 'task'
+    END
+WHERE issue_type IS NULL OR issue_type NOT IN ('task', 'bug', 'feature', 'epic', 'chore');
+
+UPDATE issues SET status = CASE
+        WHEN status IS NULL THEN 'open'
+        WHEN lower(trim(status)) IN ('done', 'complete', 'completed', 'finished', 'resolved', 'wontfix') THEN 'closed'
+        WHEN lower(trim(status)) IN ('wip', 'working', 'active', 'started') THEN 'in_progress'
+        WHEN lower(trim(status)) IN ('new', 'todo', 'pending') THEN 'open'
+        WHEN lower(trim(status)) = 'waiting' THEN 'blocked'
+        WHEN lower(trim(status)) IN ('hold', 'later', 'postponed') THEN 'deferred'
+        ELSE lower(trim(status))
+    END
+WHERE status IS NULL OR status <> lower(trim(status))
+   OR status IN ('done', 'complete', 'completed', 'finished', 'resolved', 'wontfix',
+                 'wip', 'working', 'active', 'started', 'new', 'todo', 'pending',
+                 'waiting', 'hold', 'later', 'postponed');
@@ -0,0 +1,87 @@
+-- Migration 030: Actor registry and aliases
+--
+-- Actor names are free strings, so one person can show up as `alice`,
+-- `Alice L` and `alice@laptop`. `sc actor register` records a canonical
+-- actor ID with a display name and type, and `sc actor alias` maps other
+-- spellings to it. Aliases match case-insensitively; every actor is also
+-- an alias of itself, so `ALICE` resolves to `alice`.
+--
+-- The triggers rewrite aliases to the canonical ID as rows are written:
+-- the actor of audit events and time entries, the actor named by an
+-- @mention, and the creator and assignee of issues. Rows written before an alias existed keep the name
+-- they were recorded with.
+
+CREATE TABLE IF NOT EXISTS actors (
+    id TEXT PRIMARY KEY,
+    display_name TEXT,
+    actor_type TEXT NOT NULL DEFAULT 'human',
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    CHECK (actor_type IN ('human', 'agent', 'model'))
+);
+
+CREATE TABLE IF NOT EXISTS actor_aliases (
+    alias TEXT PRIMARY KEY COLLATE NOCASE,
+    actor_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (actor_id) REFERENCES actors(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_actor_aliases_actor ON actor_aliases(actor_id);
+
+CREATE TRIGGER IF NOT EXISTS normalize_event_actor
+AFTER INSERT ON events
+WHEN EXISTS (SELECT 1 FROM actor_aliases WHERE alias = NEW.actor AND actor_id != NEW.actor)
+BEGIN
+    UPDATE events SET actor = (SELECT actor_id FROM actor_aliases WHERE alias = NEW.actor)
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS normalize_mentioned_actor
+AFTER INSERT ON events
+WHEN NEW.event_type = 'issue_mentioned'
+ AND EXISTS (SELECT 1 FROM actor_aliases WHERE alias = NEW.new_value AND actor_id != NEW.new_value)
+BEGIN
+    UPDATE events SET new_value = (SELECT actor_id FROM actor_aliases WHERE alias = NEW.new_value)
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS normalize_time_entry_actor
+AFTER INSERT ON time_entries
+WHEN EXISTS (SELECT 1 FROM actor_aliases WHERE alias = NEW.actor AND actor_id != NEW.actor)
+BEGIN
+    UPDATE time_entries SET actor = (SELECT actor_id FROM actor_aliases WHERE alias = NEW.actor)
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS normalize_issue_actors_insert
+AFTER INSERT ON issues
+WHEN EXISTS (
+    SELECT 1 FROM actor_aliases
+    WHERE (alias = NEW.created_by_agent AND actor_id != NEW.created_by_agent)
+       OR (alias = NEW.assigned_to_agent AND actor_id != NEW.assigned_to_agent)
+)
+BEGIN
+    UPDATE issues SET
+        created_by_agent = COALESCE(
+            (SELECT actor_id FROM actor_aliases WHERE alias = NEW.created_by_agent), NEW.created_by_agent),
+        assigned_to_agent = COALESCE(
+            (SELECT actor_id FROM actor_aliases WHERE alias = NEW.assigned_to_agent), NEW.assigned_to_agent)
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS normalize_issue_actors_update
+AFTER UPDATE OF created_by_agent, assigned_to_agent ON issues
+WHEN EXISTS (
+    SELECT 1 FROM actor_aliases
+    WHERE (alias = NEW.created_by_agent AND actor_id != NEW.created_by_agent)
+       OR (alias = NEW.assigned_to_agent AND actor_id != NEW.assigned_to_agent)
+)
+BEGIN
+    UPDATE issues SET
+        created_by_agent = COALESCE(
+            (SELECT actor_id FROM actor_aliases WHERE alias = NEW.created_by_agent), NEW.created_by_agent),
+        assigned_to_agent = COALESCE(
+            (SELECT actor_id FROM actor_aliases WHERE alias = NEW.assigned_to_agent), NEW.assigned_to_agent)
+    WHERE id = NEW.id;
+END;
@@ -0,0 +1,9 @@
+-- Migration 034: Worktree of a Session
+--
+-- Linked git worktrees of a repository resolve to the project registered
+-- at its main worktree, so agents working in separate worktrees share the
+-- project's issues. Each session remembers the worktree it was started
+-- in, letting `sc session list --worktree` tell them apart. NULL for
+-- sessions started outside a linked worktree.
+
+ALTER TABLE sessions ADD COLUMN worktree TEXT;
@@ -0,0 +1,18 @@
+-- Migration 035: Monorepo Sub-Projects
+--
+-- A project can register directories of a monorepo as sub-projects
+-- (`sc init --subproject packages/api`), by path relative to the project
+-- root. Issues and memory created inside one are tagged with its path, and
+-- `sc issue list` and `sc prime` show what belongs to the sub-project the
+-- current directory is in, plus everything left untagged. NULL means the
+-- project as a whole.
+
+CREATE TABLE IF NOT EXISTS subprojects (
+    project_path TEXT NOT NULL,
+    path TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (project_path, path)
+);
+
+ALTER TABLE issues ADD COLUMN subproject TEXT;
+ALTER TABLE project_memory ADD COLUMN subproject TEXT;
@@ -0,0 +1,13 @@
+-- Migration 036: Item Read History
+--
+-- Counts how often each context item has been read (returned by `sc get`
+-- or included by `sc prime`) and when it was last, so smart prime, semantic
+-- search and `sc get --sort relevance` can favour the items agents keep
+-- coming back to. Kept apart from context_items so reads don't touch
+-- updated_at or mark items dirty for sync; it is local to this database.
+
+CREATE TABLE IF NOT EXISTS item_access (
+    item_id TEXT PRIMARY KEY REFERENCES context_items(id) ON DELETE CASCADE,
+    access_count INTEGER NOT NULL DEFAULT 0,
+    last_accessed_at INTEGER
+);
@@ -0,0 +1,9 @@
+-- Migration 039: Issue Estimates
+--
+-- Hours of work an issue is expected to take, set with
+-- `sc issue create/update --estimate`. `sc plan critical-path` and
+-- `sc issue critical-path` add them up along dependency chains to find the
+-- issues that decide when a plan or epic can be done. Unestimated issues
+-- keep NULL.
+
+ALTER TABLE issues ADD COLUMN estimate_hours REAL;
@@ -0,0 +1,20 @@
+-- Migration 040: Claim Leases
+--
+-- With `policy.claim_lease_minutes` set, claiming an issue also takes out
+-- a lease on it. Every `sc` command its holder runs in a session renews
+-- the lease; once it runs out (the agent crashed, say), the claim is
+-- released the next time anyone lists or claims ready issues, or by
+-- `sc policy reap`. `assignee` is the name written on the issue, `holder`
+-- the canonical actor whose activity renews it. Leases are local
+-- bookkeeping like presence, so they aren't synced.
+
+CREATE TABLE IF NOT EXISTS claim_leases (
+    issue_id TEXT PRIMARY KEY REFERENCES issues(id) ON DELETE CASCADE,
+    assignee TEXT NOT NULL,
+    holder TEXT NOT NULL,
+    lease_ms INTEGER NOT NULL,
+    renewed_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_claim_leases_holder ON claim_leases(holder);
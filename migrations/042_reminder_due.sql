@@ -0,0 +1,11 @@
+-- Migration 042: Scheduled Reminders
+--
+-- `sc remind "..." --at 2025-07-01` saves a reminder item that comes due
+-- at a point in time. Once due, `sc status`, `sc prime` and the status
+-- line show it until `sc remind done` sets `done_at`. Both stay NULL for
+-- items without a schedule.
+
+ALTER TABLE context_items ADD COLUMN due_at INTEGER;
+ALTER TABLE context_items ADD COLUMN done_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_context_items_due ON context_items(due_at) WHERE due_at IS NOT NULL;
@@ -0,0 +1,12 @@
+-- Migration 043: Memory Read History
+--
+-- Counts how often each project memory entry has been read (`sc memory
+-- get`, or shown by `sc prime`) and when it was last, so `sc memory audit`
+-- can flag entries nobody has looked at in months. Like item_access, reads
+-- stay local to this database and don't touch updated_at or sync.
+
+CREATE TABLE IF NOT EXISTS memory_access (
+    memory_id TEXT PRIMARY KEY REFERENCES project_memory(id) ON DELETE CASCADE,
+    access_count INTEGER NOT NULL DEFAULT 0,
+    last_accessed_at INTEGER
+);
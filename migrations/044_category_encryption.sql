@@ -0,0 +1,9 @@
+-- Migration 044: Category Encryption
+--
+-- `sc category add <name> --encrypt on` marks a category whose item values
+-- are stored encrypted, with a key kept in the OS keychain rather than the
+-- database. `value_encrypted` is set on items stored that way; their
+-- `value` is then an AES-GCM BLOB and their `size` the plaintext length.
+
+ALTER TABLE item_categories ADD COLUMN encrypted INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE context_items ADD COLUMN value_encrypted INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+-- Migration 045: Write Contention
+--
+-- `sc` writers take turns on an advisory lock file next to the database
+-- and retry BEGIN IMMEDIATE with jitter when SQLite still reports busy.
+-- Writes that had to wait are added up here, one row per UTC day, so
+-- `sc db stats` can show how contended the database is. Local only, like
+-- db_stats_snapshots.
+
+CREATE TABLE IF NOT EXISTS write_contention (
+    day TEXT PRIMARY KEY,                       -- YYYY-MM-DD
+    waits INTEGER NOT NULL DEFAULT 0,           -- writes that queued behind another process
+    wait_ms INTEGER NOT NULL DEFAULT 0,         -- total time spent waiting
+    max_wait_ms INTEGER NOT NULL DEFAULT 0,     -- longest single wait
+    timeouts INTEGER NOT NULL DEFAULT 0,        -- waits that gave up on the lock
+    busy_retries INTEGER NOT NULL DEFAULT 0     -- BEGIN IMMEDIATE retried after SQLITE_BUSY
+);
@@ -323,6 +323,13 @@ def main():
         else:
             parts.append(f"\033[95mSaveContext\033[0m  \033[90m|\033[0m  \033[90mNo Active Session\033[0m")
 
+        # Reminders that have come due (written by `sc remind`, `sc status`, `sc prime`)
+        if sc_session:
+            now_ms = int(time.time() * 1000)
+            due = sum(1 for r in sc_session.get('reminders') or [] if r.get('dueAt', now_ms + 1) <= now_ms)
+            if due:
+                parts.append(f"\033[91m{due} reminder{'s' if due != 1 else ''} due\033[0m")
+
         # Context usage
         parts.append(f"\033[90mContext:\033[0m {context_display}")
 
@@ -68,6 +68,7 @@ if command -v jq &> /dev/null; then
   TIMESTAMP=$(jq -r '.timestamp // 0' "$CACHE_FILE" 2>/dev/null)
   SESSION_NAME=$(jq -r '.sessionName // ""' "$CACHE_FILE" 2>/dev/null)
   ITEM_COUNT=$(jq -r '.itemCount // 0' "$CACHE_FILE" 2>/dev/null)
+  DUE_COUNT=$(jq -r --argjson now "$(($(date +%s) * 1000))" '[.reminders[]? | select(.dueAt <= $now)] | length' "$CACHE_FILE" 2>/dev/null)
 else
   # Fallback: simple grep parsing
   TIMESTAMP=$(grep -o '"timestamp":[0-9]*' "$CACHE_FILE" 2>/dev/null | grep -o '[0-9]*' || echo "0")
@@ -86,5 +87,9 @@ fi
 
 # Output status line
 if [ -n "$SESSION_NAME" ]; then
-  echo "[SC: $SESSION_NAME ($ITEM_COUNT)]"
+  if [ "${DUE_COUNT:-0}" -gt 0 ] 2>/dev/null; then
+    echo "[SC: $SESSION_NAME ($ITEM_COUNT) | $DUE_COUNT due]"
+  else
+    echo "[SC: $SESSION_NAME ($ITEM_COUNT)]"
+  fi
 fi
@@ -198,6 +198,8 @@ export interface IssueBatchResult {
     depends_on_short_id: string;
     dependency_type: string;
   }>;
+  /** Short ID created for each input index ("0", "1", ...) */
+  short_ids: Record<string, string>;
   count: number;
   dependency_count: number;
 }
@@ -46,6 +46,7 @@ import {
   Project,
   DatabaseConfig,
 } from '../types/index.js';
+import { ENCRYPTED_PLACEHOLDER, decodeItem } from '../utils/values.js';
 
 /**
  * Safely parse tags JSON string, returning empty array on error
@@ -58,6 +59,15 @@ function safeParseTagsJson(tagsJson: string | null | undefined): string[] {
   }
 }
 
+/**
+ * Refuse to save the placeholder of an encrypted value over the value itself
+ */
+function assertNotPlaceholder(value: unknown): void {
+  if (value === ENCRYPTED_PLACEHOLDER) {
+    throw new DatabaseError('Value is the placeholder of an encrypted item; set it with the sc CLI and the encryption key');
+  }
+}
+
 // Configure custom SQLite library for macOS to enable extension loading
 if (process.platform === 'darwin') {
   const sqlitePaths = [
@@ -216,19 +226,23 @@ export class DatabaseManager {
       // Table doesn't exist yet
     }
 
-    // For NEW databases (no migrations recorded), schema.sql already has everything
-    // Mark all migrations as applied to avoid running ALTER statements on fresh tables
+    // For NEW databases (no migrations recorded), schema.sql already has the
+    // migrations up to its baseline. Mark those as applied to avoid running
+    // ALTER statements on fresh tables; later ones are applied below
     if (applied.size === 0) {
+      const baseline = this.schemaBaseline();
       const now = Date.now();
       const insertStmt = this.db.prepare('INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)');
       for (const file of files) {
         const version = file.replace('.sql', '');
-        insertStmt.run(version, now);
+        if (version <= baseline) {
+          insertStmt.run(version, now);
+          applied.add(version);
+        }
       }
-      return;
     }
 
-    // For EXISTING databases, apply pending migrations
+    // Apply pending migrations
     for (const file of files) {
       const version = file.replace('.sql', '');
 
@@ -249,6 +263,18 @@ export class DatabaseManager {
     }
   }
 
+  /**
+   * Last migration that schema.sql already contains (its `-- Baseline:` line)
+   */
+  private schemaBaseline(): string {
+    const schema = fs.readFileSync(path.join(__dirname, 'schema.sql'), 'utf-8');
+    const match = schema.match(/^-- Baseline: (\S+)/m);
+    if (!match) {
+      throw new DatabaseError('schema.sql has no Baseline line');
+    }
+    return match[1];
+  }
+
   /**
    * Get the underlying database instance
    * Use for custom queries if needed
@@ -705,6 +731,7 @@ export class DatabaseManager {
   // ========================
 
   saveContextItem(item: Omit<ContextItem, 'id' | 'created_at' | 'updated_at'>): ContextItem {
+    assertNotPlaceholder(item.value);
     const now = Date.now();
     const id = this.generateId();
 
@@ -751,7 +778,8 @@ export class DatabaseManager {
       SELECT * FROM context_items
       WHERE session_id = ? AND key = ?
     `);
-    return stmt.get(sessionId, key) as ContextItem | null;
+    const item = stmt.get(sessionId, key) as ContextItem | null;
+    return item ? decodeItem(item) : null;
   }
 
   getContextItems(
@@ -795,7 +823,7 @@ export class DatabaseManager {
     }
 
     const stmt = this.db.prepare(query);
-    return stmt.all(...params) as ContextItem[];
+    return (stmt.all(...params) as ContextItem[]).map(decodeItem);
   }
 
   deleteContextItem(sessionId: string, key: string): boolean {
@@ -832,6 +860,7 @@ export class DatabaseManager {
     const params: SqliteBindValue[] = [];
 
     if (updates.value !== undefined) {
+      assertNotPlaceholder(updates.value);
       fields.push('value = ?');
       params.push(updates.value);
       // Recalculate size if value changed
@@ -2607,6 +2636,9 @@ export class DatabaseManager {
     return stmt.all(sessionId) as Checkpoint[];
   }
 
+  /**
+   * Items of a checkpoint, with values as stored (restore copies them as is)
+   */
   getCheckpointItems(checkpointId: string): ContextItem[] {
     const stmt = this.db.prepare(`
       SELECT ci.*
@@ -3199,7 +3231,7 @@ export class DatabaseManager {
 
     try {
       const stmt = this.db.prepare(query);
-      return stmt.all(...params) as Array<ContextItem & { distance: number }>;
+      return (stmt.all(...params) as Array<ContextItem & { distance: number }>).map(decodeItem);
     } catch (error) {
       // If vec table doesn't exist or other error, return empty
       console.warn('[SaveContext] Semantic search failed:', error);
@@ -3214,11 +3246,12 @@ export class DatabaseManager {
     const stmt = this.db.prepare(`
       SELECT * FROM context_items
       WHERE session_id = ?
+        AND value_encrypted = 0
         AND (embedding_status IS NULL OR embedding_status = 'none' OR embedding_status = 'pending')
       ORDER BY created_at DESC
       LIMIT ?
     `);
-    return stmt.all(sessionId, limit) as ContextItem[];
+    return (stmt.all(sessionId, limit) as ContextItem[]).map(decodeItem);
   }
 
   /**
@@ -3228,11 +3261,12 @@ export class DatabaseManager {
   getAllItemsNeedingEmbeddings(limit: number = 100): ContextItem[] {
     const stmt = this.db.prepare(`
       SELECT * FROM context_items
-      WHERE embedding_status IS NULL OR embedding_status = 'none' OR embedding_status = 'pending' OR embedding_status = 'error'
+      WHERE value_encrypted = 0
+        AND (embedding_status IS NULL OR embedding_status = 'none' OR embedding_status = 'pending' OR embedding_status = 'error')
       ORDER BY created_at DESC
       LIMIT ?
     `);
-    return stmt.all(limit) as ContextItem[];
+    return (stmt.all(limit) as ContextItem[]).map(decodeItem);
   }
 
   /**
@@ -2,6 +2,9 @@
 -- Simple, focused, effective
 -- Learned from Memory Keeper, built for cloud-first architecture
 -- NOTE: PRAGMA statements are executed in TypeScript code
+-- Baseline: 015_add_time_entries
+-- Covers the migrations up to the baseline. runMigrations() applies the
+-- later ones on top, so new tables and columns only go in migrations/.
 
 -- ====================
 -- Schema Version Tracking
@@ -55,6 +58,8 @@ CREATE TABLE IF NOT EXISTS context_items (
   embedding_model TEXT,                 -- Model used for embedding
   chunk_count INTEGER DEFAULT 0,        -- Number of chunks (large items split)
   embedded_at INTEGER,                  -- When embedding was generated
+  fast_embedding_status TEXT DEFAULT 'none', -- Fast tier (Model2Vec), tracked separately
+  fast_embedded_at INTEGER,
 
   -- Cloud sync fields
   synced_at INTEGER,
@@ -322,7 +327,8 @@ CREATE TABLE IF NOT EXISTS issues (
   created_at INTEGER NOT NULL,
   updated_at INTEGER NOT NULL,
   closed_at INTEGER,
-  deferred_at INTEGER
+  deferred_at INTEGER,
+  close_reason TEXT                        -- Why the issue was closed
 );
 
 CREATE INDEX IF NOT EXISTS idx_issues_project ON issues(project_path);
@@ -385,13 +391,37 @@ CREATE TABLE IF NOT EXISTS plans (
   completed_in_session TEXT,
   created_at INTEGER NOT NULL,
   updated_at INTEGER NOT NULL,
-  completed_at INTEGER
+  completed_at INTEGER,
+  session_id TEXT REFERENCES sessions(id) ON DELETE SET NULL,  -- Session the plan is bound to
+  source_path TEXT,                        -- Captured plan file
+  source_hash TEXT                         -- Content hash for capture dedup
 );
 
 CREATE INDEX IF NOT EXISTS idx_plans_project_id ON plans(project_id);
 CREATE INDEX IF NOT EXISTS idx_plans_project_path ON plans(project_path);
 CREATE INDEX IF NOT EXISTS idx_plans_status ON plans(status);
 CREATE INDEX IF NOT EXISTS idx_plans_short_id ON plans(project_id, short_id);
+CREATE INDEX IF NOT EXISTS idx_plans_session ON plans(session_id);
+CREATE INDEX IF NOT EXISTS idx_plans_source_hash ON plans(source_hash);
+
+CREATE TABLE IF NOT EXISTS dirty_plans (
+    plan_id TEXT PRIMARY KEY,
+    marked_at INTEGER NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS mark_plan_dirty_insert
+AFTER INSERT ON plans BEGIN
+    INSERT INTO dirty_plans (plan_id, marked_at)
+    VALUES (NEW.id, strftime('%s', 'now') * 1000)
+    ON CONFLICT(plan_id) DO UPDATE SET marked_at = excluded.marked_at;
+END;
+
+CREATE TRIGGER IF NOT EXISTS mark_plan_dirty_update
+AFTER UPDATE ON plans BEGIN
+    INSERT INTO dirty_plans (plan_id, marked_at)
+    VALUES (NEW.id, strftime('%s', 'now') * 1000)
+    ON CONFLICT(plan_id) DO UPDATE SET marked_at = excluded.marked_at;
+END;
 
 -- ====================
 -- Time Tracking
@@ -452,6 +482,31 @@ CREATE TABLE IF NOT EXISTS embeddings_meta (
   updated_at INTEGER NOT NULL
 );
 
+-- Fast tier embeddings (Model2Vec, 256d), kept apart from the quality tier
+CREATE TABLE IF NOT EXISTS embedding_chunks_fast (
+  id TEXT PRIMARY KEY,
+  item_id TEXT NOT NULL,
+  chunk_index INTEGER NOT NULL DEFAULT 0,
+  chunk_text TEXT NOT NULL,
+  embedding BLOB NOT NULL,
+  dimensions INTEGER NOT NULL DEFAULT 256,
+  provider TEXT NOT NULL DEFAULT 'model2vec',
+  model TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  FOREIGN KEY (item_id) REFERENCES context_items(id) ON DELETE CASCADE,
+  UNIQUE(item_id, chunk_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_embedding_chunks_fast_item ON embedding_chunks_fast(item_id);
+CREATE INDEX IF NOT EXISTS idx_embedding_chunks_fast_provider ON embedding_chunks_fast(provider, model);
+
+INSERT OR IGNORE INTO embeddings_meta (key, value, updated_at)
+VALUES
+  ('tier_fast_dimensions', '256', strftime('%s', 'now') * 1000),
+  ('tier_fast_provider', 'model2vec', strftime('%s', 'now') * 1000),
+  ('tier_fast_model', 'minishlab/potion-base-8M', strftime('%s', 'now') * 1000),
+  ('tiered_embeddings_enabled', 'true', strftime('%s', 'now') * 1000);
+
 -- Vector storage for chunked context item embeddings (sqlite-vec virtual table)
 -- Uses 768 dimensions (nomic-embed-text default)
 -- Large items are split into multiple chunks for full content coverage
@@ -13,4 +13,6 @@ export interface StatusCacheEntry {
   provider?: string;
   itemCount?: number;
   sessionStatus?: 'active' | 'paused' | 'completed';
+  /** Open reminders of the project (written by the CLI's `sc remind`) */
+  reminders?: Array<{ key: string; dueAt: number }>;
 }
@@ -0,0 +1,55 @@
+/**
+ * Context item values as stored by the CLI
+ *
+ * The CLI stores values over its compression threshold zstd-compressed, as
+ * a BLOB in context_items.value (see cli/src/storage/compression.rs). Plain
+ * values stay TEXT, so any BLOB needs decoding before it reaches an agent.
+ *
+ * Values of encrypted categories are BLOBs too (value_encrypted = 1). The key
+ * lives in the CLI user's keychain, so the server never returns the
+ * ciphertext, only a placeholder.
+ */
+
+// zstd frame header
+const ZSTD_MAGIC = [0x28, 0xb5, 0x2f, 0xfd];
+
+// Header of values encrypted by cli/src/storage/encryption.rs ("SCENC\x01")
+const ENCRYPTED_MAGIC = [0x53, 0x43, 0x45, 0x4e, 0x43, 0x01];
+
+export const ENCRYPTED_PLACEHOLDER = '[encrypted: no key for this user]';
+
+const utf8 = new TextDecoder();
+
+function hasPrefix(bytes: Uint8Array, prefix: number[]): boolean {
+  return bytes.length >= prefix.length && prefix.every((b, i) => bytes[i] === b);
+}
+
+/**
+ * Decode a context_items.value column into the text that was saved
+ */
+export function decodeValue(value: string | Uint8Array): string {
+  if (typeof value === 'string') {
+    return value;
+  }
+  if (hasPrefix(value, ENCRYPTED_MAGIC)) {
+    return ENCRYPTED_PLACEHOLDER;
+  }
+  if (hasPrefix(value, ZSTD_MAGIC)) {
+    try {
+      return utf8.decode(Bun.zstdDecompressSync(value));
+    } catch {
+      return '[compressed value could not be read]';
+    }
+  }
+  return utf8.decode(value);
+}
+
+/**
+ * Copy of a context item row with its value decoded
+ */
+export function decodeItem<T extends { value: string; value_encrypted?: number }>(item: T): T {
+  if (item.value_encrypted === 1) {
+    return { ...item, value: ENCRYPTED_PLACEHOLDER };
+  }
+  return { ...item, value: decodeValue(item.value as string | Uint8Array) };
+}
@@ -0,0 +1,31 @@
+import { describe, expect, test } from 'bun:test';
+import { Database } from 'bun:sqlite';
+import { ENCRYPTED_PLACEHOLDER, decodeItem, decodeValue } from '../src/utils/values.js';
+
+describe('decodeValue', () => {
+  test('passes text values through', () => {
+    expect(decodeValue('plain')).toBe('plain');
+  });
+
+  test('decompresses values the CLI stored as zstd BLOBs', () => {
+    const original = 'build log line\n'.repeat(1000);
+    const db = new Database(':memory:');
+    db.run('CREATE TABLE context_items (key TEXT, value TEXT)');
+    db.prepare('INSERT INTO context_items VALUES (?, ?)').run('log', Bun.zstdCompressSync(original));
+
+    const row = db.prepare('SELECT key, value FROM context_items').get() as { key: string; value: string };
+    expect(row.value).toBeInstanceOf(Uint8Array);
+    expect(decodeItem(row).value).toBe(original);
+  });
+
+  test('reports a corrupt compressed value instead of throwing', () => {
+    const corrupt = new Uint8Array([0x28, 0xb5, 0x2f, 0xfd, 0x00]);
+    expect(decodeValue(corrupt)).toBe('[compressed value could not be read]');
+  });
+
+  test('never returns the ciphertext of encrypted values', () => {
+    const stored = new Uint8Array([...new TextEncoder().encode('SCENC\x01'), 1, 2, 3, 4]);
+    expect(decodeValue(stored)).toBe(ENCRYPTED_PLACEHOLDER);
+    expect(decodeItem({ value: stored as unknown as string, value_encrypted: 1 }).value).toBe(ENCRYPTED_PLACEHOLDER);
+  });
+});